@@ -250,6 +250,22 @@ impl CapabilityMatcher {
             .map(|(id, _)| id.clone())
     }
 
+    /// Find the best agent for a set of required capabilities, breaking ties
+    /// between equally good matches by `tie_break` (higher wins), e.g. the
+    /// agents' reputation for the task type
+    pub fn find_best_agent_by(
+        &self,
+        required: &HashSet<Capability>,
+        tie_break: impl Fn(&AgentId) -> f32,
+    ) -> Option<AgentId> {
+        self.agent_capabilities
+            .iter()
+            .filter(|(_, caps)| required.is_subset(caps))
+            .map(|(id, caps)| (id, caps.difference(required).count(), tie_break(id)))
+            .max_by(|a, b| b.1.cmp(&a.1).then_with(|| a.2.total_cmp(&b.2)))
+            .map(|(id, _, _)| id.clone())
+    }
+
     /// Score how well an agent matches required capabilities
     pub fn score_match(&self, agent_id: &AgentId, required: &HashSet<Capability>) -> f32 {
         if let Some(caps) = self.agent_capabilities.get(agent_id) {
@@ -383,4 +399,26 @@ mod tests {
         let score = matcher.score_match(&agent1, &required);
         assert_eq!(score, 1.0);
     }
+
+    #[test]
+    fn test_find_best_agent_tie_break() {
+        let mut matcher = CapabilityMatcher::new();
+        let required: HashSet<_> = [Capability::CodeGeneration].into_iter().collect();
+        let generalist: HashSet<_> = [Capability::CodeGeneration, Capability::Testing].into_iter().collect();
+
+        let exact = AgentId::from_string("exact");
+        let trusted = AgentId::from_string("trusted");
+        let untrusted = AgentId::from_string("untrusted");
+        matcher.register_agent(exact.clone(), required.clone());
+        matcher.register_agent(trusted.clone(), generalist.clone());
+        matcher.register_agent(untrusted.clone(), generalist.clone());
+
+        let reputation = |id: &AgentId| if *id == trusted { 0.9 } else { 0.1 };
+
+        // A closer capability match still wins over reputation
+        assert_eq!(matcher.find_best_agent_by(&required, reputation), Some(exact.clone()));
+
+        matcher.unregister_agent(&exact);
+        assert_eq!(matcher.find_best_agent_by(&required, reputation), Some(trusted));
+    }
 }
@@ -0,0 +1,441 @@
+//! Managed installation of the Claude CLI.
+//!
+//! [`ensure_installed`] downloads the native CLI release matching a version
+//! requirement for the current platform, verifies it against the SHA-256
+//! checksum in the release manifest and installs it under an install
+//! directory, so CI environments can bootstrap without a global install.
+//!
+//! Installs are laid out as `<install_dir>/<version>/claude`, which is where
+//! [`BinarySource::ManagedDir`](super::BinarySource::ManagedDir) looks for them.
+
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+use super::version::{Version, VersionReq};
+use crate::cc::core::BinaryPath;
+use crate::cc::error::{BinaryError, Error};
+use crate::cc::result::Result;
+
+/// Where native CLI releases and their manifests are published.
+pub const DEFAULT_RELEASES_URL: &str = "https://storage.googleapis.com/claude-code-dist-86c565f3-f756-42ad-8dfa-d59b1c096819/claude-code-releases";
+
+/// npm registry document listing every published CLI version.
+pub const DEFAULT_REGISTRY_URL: &str = "https://registry.npmjs.org/@anthropic-ai/claude-code";
+
+/// Default directory for managed installs.
+///
+/// `<local data dir>/axon/claude`, falling back to `~/.axon/claude`.
+pub fn default_install_dir() -> PathBuf {
+    dirs::data_local_dir()
+        .map(|dir| dir.join("axon").join("claude"))
+        .or_else(|| dirs::home_dir().map(|home| home.join(".axon").join("claude")))
+        .unwrap_or_else(|| std::env::temp_dir().join("axon-claude"))
+}
+
+/// File name of the CLI binary on this platform.
+pub fn binary_name() -> &'static str {
+    if cfg!(windows) {
+        "claude.exe"
+    } else {
+        "claude"
+    }
+}
+
+/// Release platform identifier for the current target, e.g. `linux-x64` or
+/// `darwin-arm64`. `None` if no native release is published for it.
+pub fn platform() -> Option<String> {
+    let os = match std::env::consts::OS {
+        "macos" => "darwin",
+        "linux" => "linux",
+        "windows" => "win32",
+        _ => return None,
+    };
+    let arch = match std::env::consts::ARCH {
+        "x86_64" => "x64",
+        "aarch64" => "arm64",
+        _ => return None,
+    };
+    let libc = if os == "linux" && cfg!(target_env = "musl") {
+        "-musl"
+    } else {
+        ""
+    };
+    Some(format!("{}-{}{}", os, arch, libc))
+}
+
+/// Versions installed in `install_dir`, newest first.
+pub fn installed_versions(install_dir: &Path) -> Vec<(Version, BinaryPath)> {
+    let Ok(entries) = std::fs::read_dir(install_dir) else {
+        return Vec::new();
+    };
+
+    let mut installed: Vec<(Version, BinaryPath)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let version = Version::parse(entry.file_name().to_str()?)?;
+            let binary = entry.path().join(binary_name());
+            binary.is_file().then(|| (version, BinaryPath::new(binary)))
+        })
+        .collect();
+    installed.sort_by(|a, b| b.0.cmp(&a.0));
+    installed
+}
+
+/// Download and install the newest CLI release matching `version_req` into
+/// `install_dir`, unless a matching version is already installed there.
+///
+/// Uses the default release and registry locations; see [`ManagedInstaller`]
+/// to use a mirror.
+///
+/// # Examples
+///
+/// ```no_run
+/// use crate::cc::binary::{default_install_dir, ensure_installed, VersionReq};
+///
+/// # #[tokio::main]
+/// # async fn main() -> cc_sdk::Result<()> {
+/// let req = VersionReq::parse(">=1.5, <2.0").unwrap();
+/// let claude = ensure_installed(&req, default_install_dir()).await?;
+/// println!("Using Claude at {}", claude);
+/// # Ok(())
+/// # }
+/// ```
+pub async fn ensure_installed(
+    version_req: &VersionReq,
+    install_dir: impl AsRef<Path>,
+) -> Result<BinaryPath> {
+    ManagedInstaller::new()
+        .ensure_installed(version_req, install_dir.as_ref())
+        .await
+}
+
+/// Downloads CLI releases from a configurable release and registry location.
+#[derive(Debug, Clone)]
+pub struct ManagedInstaller {
+    releases_url: String,
+    registry_url: String,
+    client: reqwest::Client,
+}
+
+impl Default for ManagedInstaller {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ManagedInstaller {
+    pub fn new() -> Self {
+        Self {
+            releases_url: DEFAULT_RELEASES_URL.to_string(),
+            registry_url: DEFAULT_REGISTRY_URL.to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Base URL serving `<version>/manifest.json` and `<version>/<platform>/claude`
+    pub fn with_releases_url(mut self, url: impl Into<String>) -> Self {
+        self.releases_url = url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// URL of an npm registry document whose `versions` lists the releases
+    pub fn with_registry_url(mut self, url: impl Into<String>) -> Self {
+        self.registry_url = url.into();
+        self
+    }
+
+    /// See [`ensure_installed`].
+    pub async fn ensure_installed(
+        &self,
+        version_req: &VersionReq,
+        install_dir: &Path,
+    ) -> Result<BinaryPath> {
+        if let Some((version, path)) = installed_versions(install_dir)
+            .into_iter()
+            .find(|(version, _)| version_req.matches(version))
+        {
+            tracing::debug!("Claude CLI {} already installed at {}", version, path);
+            return Ok(path);
+        }
+
+        let platform = platform().ok_or_else(|| {
+            install_error(
+                version_req,
+                format!(
+                    "no release is published for {}-{}",
+                    std::env::consts::OS,
+                    std::env::consts::ARCH
+                ),
+            )
+        })?;
+        let version = self.resolve_version(version_req).await?;
+        let expected = self.checksum(&version, &platform).await?;
+
+        let url = format!(
+            "{}/{}/{}/{}",
+            self.releases_url,
+            version,
+            platform,
+            binary_name()
+        );
+        tracing::info!("Downloading Claude CLI {} from {}", version, url);
+        let bytes = self
+            .fetch(&url)
+            .await
+            .map_err(|reason| install_error(&version, reason))?
+            .bytes()
+            .await
+            .map_err(|e| install_error(&version, e))?;
+        verify_checksum(&url, &bytes, &expected)?;
+
+        let path = write_binary(&install_dir.join(version.to_string()), &bytes)?;
+        tracing::info!("Installed Claude CLI {} at {}", version, path.display());
+        Ok(BinaryPath::new(path))
+    }
+
+    /// Newest published stable version matching the requirement
+    async fn resolve_version(&self, version_req: &VersionReq) -> Result<Version> {
+        let document: serde_json::Value = self
+            .fetch(&self.registry_url)
+            .await
+            .map_err(|reason| install_error(version_req, reason))?
+            .json()
+            .await
+            .map_err(|e| install_error(version_req, e))?;
+
+        document
+            .get("versions")
+            .and_then(|versions| versions.as_object())
+            .into_iter()
+            .flat_map(|versions| versions.keys())
+            .filter_map(|version| Version::parse(version))
+            .filter(|version| version_req.matches(version))
+            .max()
+            .ok_or_else(|| install_error(version_req, "no published release matches"))
+    }
+
+    /// Published SHA-256 checksum of the release binary for `platform`
+    async fn checksum(&self, version: &Version, platform: &str) -> Result<String> {
+        let url = format!("{}/{}/manifest.json", self.releases_url, version);
+        let manifest: serde_json::Value = self
+            .fetch(&url)
+            .await
+            .map_err(|reason| install_error(version, reason))?
+            .json()
+            .await
+            .map_err(|e| install_error(version, e))?;
+
+        manifest["platforms"][platform]["checksum"]
+            .as_str()
+            .map(str::to_lowercase)
+            .ok_or_else(|| {
+                install_error(
+                    version,
+                    format!("release manifest has no checksum for {}", platform),
+                )
+            })
+    }
+
+    async fn fetch(&self, url: &str) -> std::result::Result<reqwest::Response, String> {
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| format!("request to {} failed: {}", url, e))?;
+        if !response.status().is_success() {
+            return Err(format!("{} returned {}", url, response.status()));
+        }
+        Ok(response)
+    }
+}
+
+fn install_error(version: impl ToString, reason: impl ToString) -> Error {
+    Error::Binary(BinaryError::InstallFailed {
+        version: version.to_string(),
+        reason: reason.to_string(),
+    })
+}
+
+/// Hex-encoded SHA-256 of `bytes`
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn verify_checksum(url: &str, bytes: &[u8], expected: &str) -> Result<()> {
+    let actual = sha256_hex(bytes);
+    if !actual.eq_ignore_ascii_case(expected) {
+        return Err(Error::Binary(BinaryError::ChecksumMismatch {
+            url: url.to_string(),
+            expected: expected.to_string(),
+            actual,
+        }));
+    }
+    Ok(())
+}
+
+/// Write the binary into `dir` and make it executable. The file only
+/// appears under its final name once complete, so a concurrent or
+/// interrupted install never leaves a partial binary behind.
+fn write_binary(dir: &Path, bytes: &[u8]) -> Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(binary_name());
+    let tmp = dir.join(format!(
+        ".{}.{}.download",
+        binary_name(),
+        std::process::id()
+    ));
+    std::fs::write(&tmp, bytes)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&tmp, std::fs::Permissions::from_mode(0o755))?;
+    }
+
+    std::fs::rename(&tmp, &path)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::Router;
+    use axum::routing::get;
+
+    const BINARY: &[u8] = b"#!/bin/sh\necho 1.6.0\n";
+
+    /// Serve a registry document and one release, returning the base URL
+    async fn release_server(checksum: String) -> String {
+        let platform = platform().unwrap();
+        let app = Router::new()
+            .route(
+                "/registry",
+                get(|| async {
+                    axum::Json(serde_json::json!({
+                        "versions": {"1.4.0": {}, "1.6.0": {}, "1.7.0-beta.1": {}, "2.0.0": {}}
+                    }))
+                }),
+            )
+            .route(
+                "/releases/1.6.0/manifest.json",
+                get(move || {
+                    let manifest = serde_json::json!({
+                        "version": "1.6.0",
+                        "platforms": {platform.as_str(): {"checksum": checksum.as_str()}}
+                    });
+                    async move { axum::Json(manifest) }
+                }),
+            )
+            .route(
+                &format!(
+                    "/releases/1.6.0/{}/{}",
+                    super::platform().unwrap(),
+                    binary_name()
+                ),
+                get(|| async { BINARY }),
+            );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        format!("http://{}", addr)
+    }
+
+    fn installer(base: &str) -> ManagedInstaller {
+        ManagedInstaller::new()
+            .with_releases_url(format!("{}/releases", base))
+            .with_registry_url(format!("{}/registry", base))
+    }
+
+    #[test]
+    fn test_sha256_hex() {
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert!(verify_checksum("url", b"abc", &sha256_hex(b"abc").to_uppercase()).is_ok());
+        assert!(matches!(
+            verify_checksum("url", b"abc", "00"),
+            Err(Error::Binary(BinaryError::ChecksumMismatch { .. }))
+        ));
+    }
+
+    #[test]
+    fn test_installed_versions_newest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        for version in ["1.2.0", "1.10.0", "not-a-version"] {
+            write_binary(&dir.path().join(version), b"").unwrap();
+        }
+        // A version directory without a binary is an interrupted install
+        std::fs::create_dir(dir.path().join("3.0.0")).unwrap();
+
+        let versions: Vec<String> = installed_versions(dir.path())
+            .into_iter()
+            .map(|(version, _)| version.to_string())
+            .collect();
+        assert_eq!(versions, vec!["1.10.0", "1.2.0"]);
+        assert!(installed_versions(&dir.path().join("missing")).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_ensure_installed_downloads_matching_release() {
+        if platform().is_none() {
+            return;
+        }
+        let base = release_server(sha256_hex(BINARY)).await;
+        let dir = tempfile::tempdir().unwrap();
+        let req = VersionReq::parse(">=1.5, <2.0").unwrap();
+
+        let path = installer(&base)
+            .ensure_installed(&req, dir.path())
+            .await
+            .unwrap();
+        assert_eq!(
+            *path.as_path(),
+            dir.path().join("1.6.0").join(binary_name())
+        );
+        assert_eq!(std::fs::read(path.as_path()).unwrap(), BINARY);
+        #[cfg(unix)]
+        assert!(path.is_executable());
+
+        // Already installed: no server needed
+        let offline = installer("http://127.0.0.1:9");
+        assert_eq!(
+            offline.ensure_installed(&req, dir.path()).await.unwrap(),
+            path
+        );
+
+        let err = installer(&base)
+            .ensure_installed(&VersionReq::parse(">=3").unwrap(), dir.path())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Binary(BinaryError::InstallFailed { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_checksum_mismatch_discards_download() {
+        if platform().is_none() {
+            return;
+        }
+        let base = release_server(sha256_hex(b"something else")).await;
+        let dir = tempfile::tempdir().unwrap();
+
+        let err = installer(&base)
+            .ensure_installed(&VersionReq::parse("^1.5").unwrap(), dir.path())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Binary(BinaryError::ChecksumMismatch { .. })
+        ));
+        assert!(installed_versions(dir.path()).is_empty());
+    }
+}
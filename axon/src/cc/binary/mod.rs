@@ -32,7 +32,14 @@
 //!
 //! # Discovery Process
 //!
-//! The discovery process checks locations in this order:
+//! [`BinaryResolver`] searches an explicit, overridable list of
+//! [`BinarySource`]s. By default that is:
+//!
+//! 1. Environment variable `CLAUDE_BINARY_PATH`
+//! 2. The managed install directory populated by [`ensure_installed`]
+//! 3. PATH and the standard install locations below
+//!
+//! The standard install locations are checked in this order:
 //!
 //! 1. `which`/`where` command output
 //! 2. NVM directories (`~/.nvm/versions/node/*/bin/claude`)
@@ -93,6 +100,19 @@
 //! assert_eq!(compare_versions("2.0.0", "1.9.9"), Ordering::Greater);
 //! ```
 //!
+//! ## Version Requirements and Managed Installs
+//!
+//! ```no_run
+//! use crate::cc::binary::{ensure_installed, default_install_dir, VersionReq};
+//!
+//! # async fn example() -> crate::cc::Result<()> {
+//! let requirement = VersionReq::parse(">=1.5, <2.0").unwrap();
+//! let claude = ensure_installed(&requirement, default_install_dir()).await?;
+//! println!("Using Claude at: {}", claude);
+//! # Ok(())
+//! # }
+//! ```
+//!
 //! ## Creating Commands
 //!
 //! ```no_run
@@ -157,6 +177,8 @@
 
 mod discovery;
 mod env;
+mod install;
+mod resolve;
 mod version;
 pub mod cache;
 pub mod preferences;
@@ -170,8 +192,13 @@ pub use discovery::{
 };
 pub use env::{create_command_with_env, get_claude_version, setup_environment, reconstruct_path};
 pub use version::{
-    compare_versions, extract_version_from_output, Version,
+    compare_versions, extract_version_from_output, Comparator, Version, VersionOp, VersionReq,
+};
+pub use install::{
+    binary_name, default_install_dir, ensure_installed, installed_versions, platform,
+    ManagedInstaller, DEFAULT_REGISTRY_URL, DEFAULT_RELEASES_URL,
 };
+pub use resolve::{BinaryResolver, BinarySource, BINARY_PATH_ENV};
 pub use cache::{DiscoveryCache, CacheConfig};
 pub use preferences::{PreferenceStore, FilePreferenceStore, default_file_store, default_preference_path};
 pub use validation::{verify_binary, BinaryHealth, is_executable, check_version_compatibility, health_check_all};
@@ -0,0 +1,265 @@
+//! Explicit, overridable order of places to look for the Claude CLI.
+//!
+//! [`BinaryResolver`] walks its [`BinarySource`]s in order and returns the
+//! first binary that satisfies its version requirement, if any. The default
+//! order is the `CLAUDE_BINARY_PATH` environment variable, then the managed
+//! install directory, then PATH and the standard install locations.
+
+use std::path::PathBuf;
+
+use super::discovery::{InstallationType, discover_installations};
+use super::env::get_claude_version;
+use super::install::{default_install_dir, installed_versions};
+use super::version::{Version, VersionReq};
+use crate::cc::core::BinaryPath;
+use crate::cc::error::{BinaryError, Error};
+use crate::cc::result::Result;
+
+/// Environment variable naming a specific Claude binary.
+pub const BINARY_PATH_ENV: &str = "CLAUDE_BINARY_PATH";
+
+/// A place the resolver looks for the Claude CLI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BinarySource {
+    /// The binary named by `CLAUDE_BINARY_PATH`
+    EnvVar,
+    /// Versions installed by [`ensure_installed`](super::ensure_installed)
+    /// in this directory, newest first
+    ManagedDir(PathBuf),
+    /// PATH and the standard install locations (NVM, Homebrew, npm, ...),
+    /// newest version first
+    Path,
+}
+
+impl BinarySource {
+    /// Environment variable, then the default managed directory, then PATH.
+    pub fn default_order() -> Vec<BinarySource> {
+        vec![
+            BinarySource::EnvVar,
+            BinarySource::ManagedDir(default_install_dir()),
+            BinarySource::Path,
+        ]
+    }
+
+    /// Binaries from this source, with their version where it is already known
+    fn candidates(&self) -> Vec<(PathBuf, Option<Version>)> {
+        match self {
+            BinarySource::EnvVar => std::env::var_os(BINARY_PATH_ENV)
+                .map(PathBuf::from)
+                .filter(|path| {
+                    let exists = path.is_file();
+                    if !exists {
+                        tracing::warn!(
+                            "{} does not point to a file: {}",
+                            BINARY_PATH_ENV,
+                            path.display()
+                        );
+                    }
+                    exists
+                })
+                .map(|path| (path, None))
+                .into_iter()
+                .collect(),
+            BinarySource::ManagedDir(dir) => installed_versions(dir)
+                .into_iter()
+                .map(|(version, path)| (path.into_inner(), Some(version)))
+                .collect(),
+            BinarySource::Path => discover_installations()
+                .into_iter()
+                // The environment variable is its own source
+                .filter(|installation| installation.installation_type != InstallationType::Custom)
+                .map(|installation| {
+                    let version = installation.version.as_deref().and_then(Version::parse);
+                    (PathBuf::from(installation.path), version)
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Finds the Claude CLI by walking [`BinarySource`]s in order.
+///
+/// # Examples
+///
+/// ```no_run
+/// use crate::cc::binary::{BinaryResolver, BinarySource, VersionReq};
+///
+/// // Only ever use a CI-provisioned binary
+/// let claude = BinaryResolver::new()
+///     .order(vec![BinarySource::ManagedDir("/opt/ci/claude".into())])
+///     .require(VersionReq::parse(">=1.5, <2.0").unwrap())
+///     .resolve()?;
+/// # Ok::<(), cc_sdk::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct BinaryResolver {
+    order: Vec<BinarySource>,
+    requirement: Option<VersionReq>,
+}
+
+impl Default for BinaryResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BinaryResolver {
+    /// Resolver using [`BinarySource::default_order`] and no version requirement.
+    pub fn new() -> Self {
+        Self {
+            order: BinarySource::default_order(),
+            requirement: None,
+        }
+    }
+
+    /// Replace the sources to search, in order.
+    pub fn order(mut self, order: Vec<BinarySource>) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Skip binaries whose version does not satisfy `requirement`.
+    pub fn require(mut self, requirement: VersionReq) -> Self {
+        self.requirement = Some(requirement);
+        self
+    }
+
+    pub fn sources(&self) -> &[BinarySource] {
+        &self.order
+    }
+
+    pub fn requirement(&self) -> Option<&VersionReq> {
+        self.requirement.as_ref()
+    }
+
+    /// Find the first suitable binary.
+    ///
+    /// Blocking: checking a binary's version runs it with `--version`.
+    ///
+    /// # Errors
+    ///
+    /// - [`BinaryError::IncompatibleVersion`] if binaries were found but none
+    ///   satisfies the requirement, listing every version found
+    /// - [`BinaryError::NotFound`] if no binary was found at all
+    pub fn resolve(&self) -> Result<BinaryPath> {
+        let mut searched = Vec::new();
+        let mut rejected = Vec::new();
+
+        for source in &self.order {
+            for (path, known_version) in source.candidates() {
+                let Some(requirement) = &self.requirement else {
+                    tracing::info!("Using Claude at {} ({:?})", path.display(), source);
+                    return Ok(BinaryPath::new(path));
+                };
+
+                let version = known_version.or_else(|| {
+                    get_claude_version(&path.to_string_lossy())
+                        .ok()
+                        .flatten()
+                        .and_then(|version| Version::parse(&version))
+                });
+                match version {
+                    Some(version) if requirement.matches(&version) => {
+                        tracing::info!(
+                            "Using Claude {} at {} ({:?})",
+                            version,
+                            path.display(),
+                            source
+                        );
+                        return Ok(BinaryPath::new(path));
+                    }
+                    Some(version) => rejected.push(format!("{} at {}", version, path.display())),
+                    None => rejected.push(format!("unknown version at {}", path.display())),
+                }
+                searched.push(path);
+            }
+        }
+
+        match &self.requirement {
+            Some(requirement) if !rejected.is_empty() => {
+                Err(Error::Binary(BinaryError::IncompatibleVersion {
+                    found: rejected.join(", "),
+                    required: requirement.to_string(),
+                }))
+            }
+            _ => Err(Error::Binary(BinaryError::NotFound {
+                searched_paths: searched,
+            })),
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    /// Install a fake CLI reporting `version` the way the managed installer lays it out
+    fn fake_install(dir: &Path, version: &str) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+
+        let version_dir = dir.join(version);
+        std::fs::create_dir_all(&version_dir).unwrap();
+        let path = version_dir.join("claude");
+        std::fs::write(
+            &path,
+            format!("#!/bin/sh\necho '{} (Claude Code)'\n", version),
+        )
+        .unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_managed_dir_honours_requirement() {
+        let dir = tempfile::tempdir().unwrap();
+        fake_install(dir.path(), "1.4.0");
+        let wanted = fake_install(dir.path(), "1.6.2");
+        fake_install(dir.path(), "2.1.0");
+
+        let resolver = BinaryResolver::new()
+            .order(vec![BinarySource::ManagedDir(dir.path().to_path_buf())])
+            .require(VersionReq::parse(">=1.5, <2.0").unwrap());
+        assert_eq!(*resolver.resolve().unwrap().as_path(), wanted);
+
+        // Without a requirement the newest install wins
+        let newest = BinaryResolver::new()
+            .order(vec![BinarySource::ManagedDir(dir.path().to_path_buf())])
+            .resolve()
+            .unwrap();
+        assert!(newest.as_path().ends_with("2.1.0/claude"));
+    }
+
+    #[test]
+    fn test_incompatible_versions_are_listed() {
+        let dir = tempfile::tempdir().unwrap();
+        fake_install(dir.path(), "1.4.0");
+
+        let err = BinaryResolver::new()
+            .order(vec![BinarySource::ManagedDir(dir.path().to_path_buf())])
+            .require(VersionReq::parse(">=1.5").unwrap())
+            .resolve()
+            .unwrap_err();
+        match err {
+            Error::Binary(BinaryError::IncompatibleVersion { found, required }) => {
+                assert!(found.starts_with("1.4.0 at "), "{}", found);
+                assert_eq!(required, ">=1.5");
+            }
+            other => panic!("unexpected error: {}", other),
+        }
+
+        let empty = BinaryResolver::new()
+            .order(vec![BinarySource::ManagedDir(dir.path().join("missing"))])
+            .resolve()
+            .unwrap_err();
+        assert!(matches!(empty, Error::Binary(BinaryError::NotFound { .. })));
+    }
+
+    #[test]
+    fn test_default_order() {
+        let order = BinarySource::default_order();
+        assert_eq!(order.first(), Some(&BinarySource::EnvVar));
+        assert_eq!(order.last(), Some(&BinarySource::Path));
+        assert_eq!(BinaryResolver::new().sources(), order.as_slice());
+    }
+}
@@ -305,6 +305,294 @@ pub fn compare_versions(a: &str, b: &str) -> Ordering {
     }
 }
 
+/// Comparison operator of a single [`VersionReq`] comparator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionOp {
+    /// `=1.5` - any 1.5.x; `=1.5.2` - exactly 1.5.2
+    Exact,
+    /// `>1.5` - anything above every 1.5.x
+    Greater,
+    /// `>=1.5`
+    GreaterEq,
+    /// `<1.5` - anything below 1.5.0
+    Less,
+    /// `<=1.5` - anything up to and including every 1.5.x
+    LessEq,
+    /// `~1.5.2` - patch updates only
+    Tilde,
+    /// `^1.5.2`, or a bare `1.5.2` - updates that don't change the
+    /// left-most non-zero component
+    Caret,
+    /// `*` - any version
+    Wildcard,
+}
+
+impl VersionOp {
+    fn symbol(self) -> &'static str {
+        match self {
+            VersionOp::Exact => "=",
+            VersionOp::Greater => ">",
+            VersionOp::GreaterEq => ">=",
+            VersionOp::Less => "<",
+            VersionOp::LessEq => "<=",
+            VersionOp::Tilde => "~",
+            VersionOp::Caret => "^",
+            VersionOp::Wildcard => "*",
+        }
+    }
+}
+
+/// One comparator of a [`VersionReq`], e.g. `>=1.5`.
+///
+/// Minor and patch may be omitted, in which case they match any value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comparator {
+    pub op: VersionOp,
+    pub major: u32,
+    pub minor: Option<u32>,
+    pub patch: Option<u32>,
+    pub prerelease: Option<String>,
+}
+
+impl Comparator {
+    fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        if text == "*" {
+            return Ok(Self {
+                op: VersionOp::Wildcard,
+                major: 0,
+                minor: None,
+                patch: None,
+                prerelease: None,
+            });
+        }
+
+        let (op, rest) = [
+            (">=", VersionOp::GreaterEq),
+            ("<=", VersionOp::LessEq),
+            (">", VersionOp::Greater),
+            ("<", VersionOp::Less),
+            ("=", VersionOp::Exact),
+            ("~", VersionOp::Tilde),
+            ("^", VersionOp::Caret),
+        ]
+        .into_iter()
+        .find_map(|(symbol, op)| text.strip_prefix(symbol).map(|rest| (op, rest)))
+        .unwrap_or((VersionOp::Caret, text));
+
+        let rest = rest.trim();
+        let (core, prerelease) = match rest.split_once('-') {
+            Some((core, pre)) => (core, Some(pre.to_string())),
+            None => (rest, None),
+        };
+
+        let mut parts = core.split('.');
+        let mut number = |name: &str, required: bool| -> Result<Option<u32>, String> {
+            match parts.next() {
+                None if !required => Ok(None),
+                Some("*" | "x" | "X") if !required => Ok(None),
+                Some(part) => part
+                    .parse()
+                    .map(Some)
+                    .map_err(|_| format!("invalid {} version '{}' in '{}'", name, part, text)),
+                None => Err(format!("missing version in '{}'", text)),
+            }
+        };
+        let major = number("major", true)?.unwrap_or_default();
+        let minor = number("minor", false)?;
+        let patch = number("patch", false)?;
+        if parts.next().is_some() || (minor.is_none() && patch.is_some()) {
+            return Err(format!("invalid version '{}'", text));
+        }
+        if prerelease.is_some() && patch.is_none() {
+            return Err(format!("pre-release '{}' needs a full version", text));
+        }
+
+        Ok(Self {
+            op,
+            major,
+            minor,
+            patch,
+            prerelease,
+        })
+    }
+
+    /// The smallest version with every omitted component set to zero
+    fn floor(&self) -> Version {
+        Version {
+            major: self.major,
+            minor: self.minor.unwrap_or(0),
+            patch: self.patch.unwrap_or(0),
+            prerelease: self.prerelease.clone(),
+            build: None,
+        }
+    }
+
+    /// The first version past every version the given components cover,
+    /// e.g. 1.6.0 for `1.5` and 2.0.0 for `1`
+    fn ceiling(&self) -> Version {
+        let (major, minor, patch) = match (self.minor, self.patch) {
+            (None, _) => (self.major + 1, 0, 0),
+            (Some(minor), None) => (self.major, minor + 1, 0),
+            (Some(minor), Some(patch)) => (self.major, minor, patch + 1),
+        };
+        Version::parse(&format!("{}.{}.{}", major, minor, patch)).expect("valid version")
+    }
+
+    /// Upper bound (exclusive) of a caret requirement
+    fn caret_ceiling(&self) -> Version {
+        let (major, minor, patch) = match (self.major, self.minor, self.patch) {
+            (0, Some(0), Some(patch)) => (0, 0, patch + 1),
+            (0, Some(minor), _) => (0, minor + 1, 0),
+            (major, _, _) => (major + 1, 0, 0),
+        };
+        Version::parse(&format!("{}.{}.{}", major, minor, patch)).expect("valid version")
+    }
+
+    /// Upper bound (exclusive) of a tilde requirement
+    fn tilde_ceiling(&self) -> Version {
+        match self.minor {
+            Some(minor) => Version::parse(&format!("{}.{}.0", self.major, minor + 1)),
+            None => Version::parse(&format!("{}.0.0", self.major + 1)),
+        }
+        .expect("valid version")
+    }
+
+    /// Whether `version` satisfies this comparator.
+    pub fn matches(&self, version: &Version) -> bool {
+        // Build metadata never affects matching
+        let version = &Version {
+            build: None,
+            ..version.clone()
+        };
+
+        match self.op {
+            VersionOp::Wildcard => true,
+            VersionOp::Exact if self.patch.is_some() => *version == self.floor(),
+            VersionOp::Exact => *version >= self.floor() && *version < self.ceiling(),
+            VersionOp::Greater if self.patch.is_some() => *version > self.floor(),
+            VersionOp::Greater => *version >= self.ceiling(),
+            VersionOp::GreaterEq => *version >= self.floor(),
+            VersionOp::Less => *version < self.floor(),
+            VersionOp::LessEq if self.patch.is_some() => *version <= self.floor(),
+            VersionOp::LessEq => *version < self.ceiling(),
+            VersionOp::Tilde => *version >= self.floor() && *version < self.tilde_ceiling(),
+            VersionOp::Caret => *version >= self.floor() && *version < self.caret_ceiling(),
+        }
+    }
+}
+
+impl fmt::Display for Comparator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.op == VersionOp::Wildcard {
+            return write!(f, "*");
+        }
+        write!(f, "{}{}", self.op.symbol(), self.major)?;
+        if let Some(minor) = self.minor {
+            write!(f, ".{}", minor)?;
+        }
+        if let Some(patch) = self.patch {
+            write!(f, ".{}", patch)?;
+        }
+        if let Some(ref pre) = self.prerelease {
+            write!(f, "-{}", pre)?;
+        }
+        Ok(())
+    }
+}
+
+/// A semantic version requirement such as `">=1.5, <2.0"`.
+///
+/// Comma-separated comparators must all match. A comparator without an
+/// operator is a caret requirement, as in Cargo. Pre-release versions only
+/// match if a comparator names a pre-release of the same `major.minor.patch`,
+/// so `>=1.5` never selects `2.0.0-beta.1`.
+///
+/// # Examples
+///
+/// ```
+/// use crate::cc::binary::{Version, VersionReq};
+///
+/// let req = VersionReq::parse(">=1.5, <2.0").unwrap();
+/// assert!(req.matches(&Version::parse("1.7.3").unwrap()));
+/// assert!(!req.matches(&Version::parse("2.0.0").unwrap()));
+/// assert!(!req.matches(&Version::parse("1.4.9").unwrap()));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionReq {
+    comparators: Vec<Comparator>,
+}
+
+impl VersionReq {
+    /// Requirement that matches every version.
+    pub const STAR: VersionReq = VersionReq {
+        comparators: Vec::new(),
+    };
+
+    /// Parse a comma-separated list of comparators.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the first comparator that could not be parsed.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let comparators = text
+            .split(',')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .map(Comparator::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if comparators.is_empty() {
+            return Err("empty version requirement".to_string());
+        }
+        Ok(Self { comparators })
+    }
+
+    pub fn comparators(&self) -> &[Comparator] {
+        &self.comparators
+    }
+
+    /// Whether `version` satisfies every comparator.
+    pub fn matches(&self, version: &Version) -> bool {
+        if version.is_prerelease() {
+            let allowed = self.comparators.iter().any(|c| {
+                c.prerelease.is_some()
+                    && c.major == version.major
+                    && c.minor == Some(version.minor)
+                    && c.patch == Some(version.patch)
+            });
+            if !allowed {
+                return false;
+            }
+        }
+
+        self.comparators.iter().all(|c| c.matches(version))
+    }
+
+    /// Whether the version string parses and satisfies the requirement.
+    pub fn matches_str(&self, version: &str) -> bool {
+        Version::parse(version).is_some_and(|v| self.matches(&v))
+    }
+}
+
+impl std::str::FromStr for VersionReq {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for VersionReq {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.comparators.is_empty() {
+            return write!(f, "*");
+        }
+        let parts: Vec<String> = self.comparators.iter().map(ToString::to_string).collect();
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -417,6 +705,59 @@ mod tests {
     }
 
     // Property-based tests
+    #[test]
+    fn test_version_req_ranges() {
+        let req = VersionReq::parse(">=1.5, <2.0").unwrap();
+        assert_eq!(req.comparators().len(), 2);
+        assert!(req.matches_str("1.5.0"));
+        assert!(req.matches_str("1.99.3"));
+        assert!(!req.matches_str("1.4.9"));
+        assert!(!req.matches_str("2.0.0"));
+        // Pre-releases need an explicit opt-in
+        assert!(!req.matches_str("1.6.0-beta.1"));
+        assert!(VersionReq::parse(">=1.6.0-beta.1").unwrap().matches_str("1.6.0-beta.2"));
+
+        assert!(VersionReq::parse("=1.5").unwrap().matches_str("1.5.7"));
+        assert!(!VersionReq::parse("=1.5.2").unwrap().matches_str("1.5.3"));
+        assert!(VersionReq::parse(">1.5").unwrap().matches_str("1.6.0"));
+        assert!(!VersionReq::parse(">1.5").unwrap().matches_str("1.5.9"));
+        assert!(VersionReq::parse("<=1.5").unwrap().matches_str("1.5.9"));
+        assert!(!VersionReq::parse("<=1.5").unwrap().matches_str("1.6.0"));
+        assert!(VersionReq::parse("*").unwrap().matches_str("0.0.1"));
+        assert!(VersionReq::parse("1.x").unwrap().matches_str("1.9.0"));
+        assert!(VersionReq::parse(">=1.0.0, <2.0.0").unwrap().matches_str("1.0.41+build"));
+    }
+
+    #[test]
+    fn test_version_req_caret_and_tilde() {
+        let caret = VersionReq::parse("^1.5.2").unwrap();
+        assert!(caret.matches_str("1.9.0"));
+        assert!(!caret.matches_str("1.5.1"));
+        assert!(!caret.matches_str("2.0.0"));
+        // Bare versions are caret requirements
+        assert_eq!(VersionReq::parse("1.5.2").unwrap(), caret);
+        assert!(!VersionReq::parse("^0.5.2").unwrap().matches_str("0.6.0"));
+        assert!(!VersionReq::parse("^0.0.3").unwrap().matches_str("0.0.4"));
+
+        let tilde = VersionReq::parse("~1.5.2").unwrap();
+        assert!(tilde.matches_str("1.5.9"));
+        assert!(!tilde.matches_str("1.6.0"));
+        assert!(VersionReq::parse("~1").unwrap().matches_str("1.9.9"));
+    }
+
+    #[test]
+    fn test_version_req_parse_errors_and_display() {
+        assert!(VersionReq::parse("").is_err());
+        assert!(VersionReq::parse(">=abc").is_err());
+        assert!(VersionReq::parse("1.2.3.4").is_err());
+        assert!(VersionReq::parse(">=1.2-beta").is_err());
+
+        let req: VersionReq = " >= 1.5 ,<2.0".parse().unwrap();
+        assert_eq!(req.to_string(), ">=1.5, <2.0");
+        assert_eq!(VersionReq::STAR.to_string(), "*");
+        assert!(VersionReq::STAR.matches_str("3.0.0"));
+    }
+
     #[cfg(test)]
     mod proptests {
         use super::*;
@@ -132,6 +132,7 @@ use crate::cc::messages::Message;
 use crate::cc::options::{ClaudeCodeOptions, McpServerConfig};
 use crate::cc::permissions::PermissionMode;
 use crate::cc::metrics::SessionMetrics;
+use crate::cc::model_recommendation::{ModelRecommendation, TaskDescriptor};
 use crate::cc::streaming::OutputBuffer;
 
 /// Type-safe Claude client with compile-time state verification.
@@ -158,6 +159,7 @@ struct ClientInner {
     message_tx: Option<broadcast::Sender<Message>>,
     metrics: Arc<tokio::sync::Mutex<SessionMetrics>>,
     output_buffer: Arc<OutputBuffer>,
+    resolver: Option<binary::BinaryResolver>,
 }
 
 impl ClientInner {
@@ -170,6 +172,7 @@ impl ClientInner {
             message_tx: None,
             metrics: Arc::new(tokio::sync::Mutex::new(SessionMetrics::new())),
             output_buffer: Arc::new(OutputBuffer::new()),
+            resolver: None,
         }
     }
 }
@@ -208,13 +211,79 @@ pub struct ClaudeClientBuilder<State = NoBinary> {
 
 // NoBinary -> WithBinary transitions
 impl ClaudeClientBuilder<NoBinary> {
+    /// Require the Claude CLI version to satisfy a requirement such as `">=1.5, <2.0"`.
+    ///
+    /// Binaries that do not match are skipped during [`discover_binary`](Self::discover_binary).
+    ///
+    /// # Errors
+    ///
+    /// Returns `BinaryError::InvalidVersionRequirement` if the requirement cannot be parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use super::ClaudeClient;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> cc_sdk::Result<()> {
+    /// let builder = ClaudeClient::builder()
+    ///     .require_version(">=1.5, <2.0")?
+    ///     .discover_binary().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn require_version(mut self, requirement: &str) -> Result<Self> {
+        let parsed = binary::VersionReq::parse(requirement).map_err(|reason| {
+            Error::Binary(BinaryError::InvalidVersionRequirement {
+                requirement: requirement.to_string(),
+                reason,
+            })
+        })?;
+
+        let inner = Arc::get_mut(&mut self.inner)
+            .expect("Builder should have unique access to inner");
+        inner.resolver = Some(inner.resolver.take().unwrap_or_default().require(parsed));
+        Ok(self)
+    }
+
+    /// Replace the order of places searched by [`discover_binary`](Self::discover_binary).
+    ///
+    /// The default is `CLAUDE_BINARY_PATH`, then the managed install
+    /// directory, then PATH and the standard install locations.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use super::ClaudeClient;
+    /// use crate::cc::binary::BinarySource;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> cc_sdk::Result<()> {
+    /// // Never pick up whatever happens to be on PATH
+    /// let builder = ClaudeClient::builder()
+    ///     .discovery_order(vec![BinarySource::EnvVar, BinarySource::ManagedDir("/opt/claude".into())])
+    ///     .discover_binary().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn discovery_order(mut self, order: Vec<binary::BinarySource>) -> Self {
+        let inner = Arc::get_mut(&mut self.inner)
+            .expect("Builder should have unique access to inner");
+        inner.resolver = Some(inner.resolver.take().unwrap_or_default().order(order));
+        self
+    }
+
     /// Discover the Claude binary automatically.
     ///
-    /// Searches for Claude in standard locations (PATH, Homebrew, NVM, etc.).
+    /// Searches the discovery order (`CLAUDE_BINARY_PATH`, the managed install
+    /// directory, then PATH, Homebrew, NVM, etc.) for a binary satisfying any
+    /// [`require_version`](Self::require_version) requirement.
     ///
     /// # Errors
     ///
-    /// Returns `BinaryError::NotFound` if no valid Claude installation is found.
+    /// Returns `BinaryError::NotFound` if no valid Claude installation is found,
+    /// or `BinaryError::IncompatibleVersion` listing the versions found if none
+    /// satisfies the required version.
     ///
     /// # Examples
     ///
@@ -229,25 +298,23 @@ impl ClaudeClientBuilder<NoBinary> {
     /// # }
     /// ```
     pub async fn discover_binary(self) -> Result<ClaudeClientBuilder<WithBinary>> {
+        let resolver = self.inner.resolver.clone().unwrap_or_default();
+
         // Discover binary in blocking thread pool
-        let binary_path = tokio::task::spawn_blocking(|| {
-            binary::find_claude_binary()
-        })
+        let binary_path = tokio::task::spawn_blocking(move || resolver.resolve())
         .await
-        .map_err(|e| Error::Protocol(format!("Discovery task failed: {}", e)))?
-        .map_err(|_e| Error::Binary(BinaryError::NotFound {
-            searched_paths: vec![],  // The error message already contains this info
-        }))?;
+        .map_err(|e| Error::Protocol(format!("Discovery task failed: {}", e)))??;
 
         // Update inner state
         let inner = Arc::new(ClientInner {
-            binary_path: Some(BinaryPath::new(binary_path)),
+            binary_path: Some(binary_path),
             options: None,
             transport: None,
             session_id: None,
             message_tx: None,
             metrics: Arc::new(tokio::sync::Mutex::new(crate::cc::metrics::SessionMetrics::new())),
             output_buffer: Arc::new(crate::cc::streaming::OutputBuffer::new()),
+            resolver: None,
         });
 
         Ok(ClaudeClientBuilder {
@@ -281,6 +348,7 @@ impl ClaudeClientBuilder<NoBinary> {
             message_tx: None,
             metrics: Arc::new(tokio::sync::Mutex::new(crate::cc::metrics::SessionMetrics::new())),
             output_buffer: Arc::new(crate::cc::streaming::OutputBuffer::new()),
+            resolver: None,
         });
 
         ClaudeClientBuilder {
@@ -1130,6 +1198,48 @@ impl ClaudeClientBuilder<WithBinary> {
         self
     }
 
+    /// Use the model recommended for a task, falling back to the runner-up.
+    ///
+    /// The CLI switches to the fallback model when the primary model is
+    /// overloaded. It accepts a single fallback, so callers that retry on their
+    /// own can walk the rest of [`ModelRecommendation::fallback_chain`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if no candidate model satisfies the task.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use super::ClaudeClient;
+    /// use crate::cc::model_recommendation::{ModelRecommendation, TaskDescriptor};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> cc_sdk::Result<()> {
+    /// let recommender = ModelRecommendation::default();
+    /// let builder = ClaudeClient::builder()
+    ///     .discover_binary().await?
+    ///     .recommended_model(&recommender, &TaskDescriptor::new(20_000).max_cost_per_call(0.05))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn recommended_model(
+        self,
+        recommender: &ModelRecommendation,
+        task: &TaskDescriptor,
+    ) -> Result<Self> {
+        let mut chain = recommender.fallback_chain(task).into_iter();
+        let primary = chain
+            .next()
+            .ok_or_else(|| Error::config("No candidate model satisfies the task"))?;
+
+        let builder = self.model(primary);
+        Ok(match chain.next() {
+            Some(fallback) => builder.fallback_model(fallback),
+            None => builder,
+        })
+    }
+
     /// Enable IDE auto-connect
     ///
     /// When enabled, the CLI will automatically connect to supported IDEs.
@@ -1287,6 +1397,7 @@ impl Clone for ClientInner {
             message_tx: self.message_tx.clone(),
             metrics: Arc::clone(&self.metrics),
             output_buffer: Arc::clone(&self.output_buffer),
+            resolver: self.resolver.clone(),
         }
     }
 }
@@ -1356,6 +1467,7 @@ impl ClaudeClientBuilder<Configured> {
             message_tx: Some(message_tx),
             metrics: Arc::new(tokio::sync::Mutex::new(SessionMetrics::new())),
             output_buffer: Arc::new(OutputBuffer::new()),
+            resolver: None,
         });
 
         Ok(ClaudeClientBuilder {
@@ -2311,6 +2423,27 @@ mod tests {
         assert!(builder.inner.binary_path.is_some());
     }
 
+    #[tokio::test]
+    async fn test_require_version_rejects_invalid_requirement() {
+        let err = ClaudeClient::builder().require_version(">=one").err().unwrap();
+        assert!(matches!(
+            err,
+            Error::Binary(BinaryError::InvalidVersionRequirement { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_require_version_and_order_configure_resolver() {
+        let builder = ClaudeClient::builder()
+            .discovery_order(vec![binary::BinarySource::EnvVar])
+            .require_version(">=1.5, <2.0")
+            .unwrap();
+
+        let resolver = builder.inner.resolver.as_ref().unwrap();
+        assert_eq!(resolver.sources(), &[binary::BinarySource::EnvVar]);
+        assert_eq!(resolver.requirement().unwrap().to_string(), ">=1.5, <2.0");
+    }
+
     #[tokio::test]
     async fn test_model_fallback_configuration() {
         let builder = ClaudeClient::builder()
@@ -2543,6 +2676,25 @@ mod tests {
         assert_eq!(options.fallback_model, Some("claude-opus-4".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_recommended_model_configuration() {
+        let recommender = ModelRecommendation::default();
+        let builder = ClaudeClient::builder()
+            .binary("/usr/local/bin/claude")
+            .recommended_model(&recommender, &TaskDescriptor::new(10_000).requires_thinking())
+            .unwrap()
+            .configure();
+
+        let options = builder.inner.options.as_ref().unwrap();
+        assert_eq!(options.model, Some("claude-haiku-4-5".to_string()));
+        assert_eq!(options.fallback_model, Some("claude-sonnet-4-5".to_string()));
+
+        let impossible = ClaudeClient::builder()
+            .binary("/usr/local/bin/claude")
+            .recommended_model(&recommender, &TaskDescriptor::new(5_000_000));
+        assert!(impossible.is_err());
+    }
+
     #[tokio::test]
     async fn test_ide_autoconnect_configuration() {
         let builder = ClaudeClient::builder()
@@ -167,6 +167,44 @@ pub enum BinaryError {
         /// Reason the value is invalid
         reason: String,
     },
+
+    /// Version requirement could not be parsed.
+    ///
+    /// This error occurs when a requirement such as `">=1.5, <2.0"` passed to
+    /// the client builder or installer is malformed.
+    #[error("Invalid Claude CLI version requirement '{requirement}': {reason}")]
+    InvalidVersionRequirement {
+        /// Requirement as given
+        requirement: String,
+        /// Reason it could not be parsed
+        reason: String,
+    },
+
+    /// Managed installation of the CLI failed.
+    ///
+    /// This error occurs when no release matches the requirement, the current
+    /// platform has no release, or the download cannot be completed.
+    #[error("Failed to install Claude CLI {version}: {reason}")]
+    InstallFailed {
+        /// Version (or requirement) being installed
+        version: String,
+        /// Reason for failure
+        reason: String,
+    },
+
+    /// Downloaded binary does not match its published checksum.
+    ///
+    /// The download is discarded; this usually means a corrupted transfer
+    /// or a tampered mirror.
+    #[error("Checksum mismatch for {url}: expected {expected}, got {actual}")]
+    ChecksumMismatch {
+        /// URL the binary was downloaded from
+        url: String,
+        /// Published SHA-256 checksum
+        expected: String,
+        /// SHA-256 checksum of the downloaded bytes
+        actual: String,
+    },
 }
 
 /// Transport-layer errors.
@@ -445,6 +483,16 @@ pub enum SettingsError {
         reason: String,
     },
 
+    /// Settings failed schema validation.
+    ///
+    /// This error occurs when settings are used despite validation errors.
+    /// Every error is listed, not just the first.
+    #[error("Settings failed validation:\n{}", .problems.join("\n"))]
+    ValidationFailed {
+        /// One line per problem, including the file and key path
+        problems: Vec<String>,
+    },
+
     /// Failed to write settings file.
     ///
     /// This error occurs when settings cannot be written to disk.
@@ -570,6 +618,19 @@ pub enum ClientError {
         reason: String,
     },
 
+    /// Request waited too long for a free slot in a query pool.
+    ///
+    /// This error occurs when a [`QueryPool`](crate::cc::pool::QueryPool) is
+    /// saturated and the request is not started within the configured
+    /// maximum queue wait. The request was never sent to the CLI.
+    #[error("Request waited {waited:?} in queue without a free slot (max concurrent: {max_concurrent})")]
+    QueueTimeout {
+        /// Time spent waiting in the queue
+        waited: Duration,
+        /// Concurrency limit of the pool
+        max_concurrent: usize,
+    },
+
     /// Other client error.
     ///
     /// This error is used for miscellaneous client errors that don't fit
@@ -589,6 +650,7 @@ impl Error {
             | Self::Transport(TransportError::StreamEnded)
             | Self::Transport(TransportError::Closed)
             | Self::Transport(TransportError::ProcessExited { .. })
+            | Self::Client(ClientError::QueueTimeout { .. })
             | Self::Session(SessionError::InvalidState { .. }) => true,
             _ => false,
         }
@@ -652,6 +714,19 @@ impl From<crossbeam_channel::RecvError> for Error {
     }
 }
 
+// Local file I/O (process records, managed installs) reuses the transport wrappers
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Self::Transport(TransportError::Io(e))
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Transport(TransportError::Json(e))
+    }
+}
+
 
 
 #[cfg(test)]
@@ -19,6 +19,7 @@
 //! - [`HookInput`] - Strongly-typed hook input (discriminated union)
 //! - [`HookJSONOutput`] - Hook output controlling Claude's behavior
 //! - [`HookMatcher`] - Configuration for matching hook events
+//! - [`replay`] - Recording hook invocations and replaying them in tests
 //!
 //! # Example
 //!
@@ -47,8 +48,11 @@ use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
+pub mod replay;
+
 // Re-export CanUseTool from permissions (it's hook-related)
 pub use crate::cc::permissions::CanUseTool;
+pub use replay::{HookRecorder, RecordingHookWrapper, Redactor, ReplayReport, ReplaySession};
 
 /// Hook context
 #[derive(Debug, Clone)]
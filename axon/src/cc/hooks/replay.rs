@@ -0,0 +1,806 @@
+//! Recording and replay of hook invocations for deterministic tests.
+//!
+//! [`HookRecorder::wrap_hooks`] wraps every callback in a hook configuration with a
+//! [`RecordingHookWrapper`], which appends each input/output pair to a JSONL file
+//! while a real session runs. [`ReplaySession`] later feeds the recorded inputs back
+//! through the same hook configuration and reports every output that changed, so
+//! hook behavior can be pinned in CI without a live Claude session.
+//!
+//! Recordings can contain secrets from tool inputs and outputs. Use a [`Redactor`]
+//! when recording, or on an existing file, before committing it.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use crate::cc::hooks::replay::{HookRecorder, Redactor, ReplaySession};
+//! use crate::cc::hooks::HookMatcher;
+//! use std::collections::HashMap;
+//!
+//! # fn my_hooks() -> HashMap<String, Vec<HookMatcher>> { HashMap::new() }
+//! # async fn example() -> cc_sdk::Result<()> {
+//! // While running against Claude
+//! let recorder = HookRecorder::create("tests/fixtures/hooks.jsonl")?
+//!     .with_redactor(Redactor::new([r"sk-ant-[A-Za-z0-9_-]+"])?);
+//! let hooks = recorder.wrap_hooks(my_hooks());
+//!
+//! // In CI
+//! let report = ReplaySession::new(my_hooks())
+//!     .ignore("timestamp")
+//!     .replay_file("tests/fixtures/hooks.jsonl")
+//!     .await?;
+//! report.assert_ok();
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{HookCallback, HookContext, HookInput, HookJSONOutput, HookMatcher};
+use crate::cc::error::Error;
+use crate::cc::result::Result;
+
+/// Where a callback sits in a hook configuration.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatcherContext {
+    /// Hook event the matcher is registered for, e.g. `PreToolUse`
+    pub event: String,
+    /// The matcher's criteria
+    pub matcher: Option<Value>,
+    /// Position of the matcher among the event's matchers
+    pub matcher_index: usize,
+    /// Position of the callback within the matcher
+    pub hook_index: usize,
+}
+
+/// One recorded hook invocation, stored as one line of a recording.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookRecord {
+    /// When the hook ran
+    pub timestamp: DateTime<Utc>,
+    /// Which callback ran
+    #[serde(flatten)]
+    pub context: MatcherContext,
+    /// Tool use the hook ran for, if any
+    pub tool_use_id: Option<String>,
+    /// Input passed to the hook
+    pub input: HookInput,
+    /// Serialized hook output, absent if the hook failed
+    pub output: Option<Value>,
+    /// Error message, if the hook failed
+    pub error: Option<String>,
+}
+
+/// Replaces regex matches in recorded strings.
+#[derive(Debug, Clone)]
+pub struct Redactor {
+    patterns: Vec<Regex>,
+    replacement: String,
+}
+
+impl Redactor {
+    /// Text substituted for matches unless changed with [`with_replacement`](Self::with_replacement)
+    pub const DEFAULT_REPLACEMENT: &'static str = "[REDACTED]";
+
+    /// Create a redactor from regex patterns.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` naming the first invalid pattern.
+    pub fn new<I, S>(patterns: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let patterns = patterns
+            .into_iter()
+            .map(|pattern| {
+                Regex::new(pattern.as_ref()).map_err(|e| {
+                    Error::config(format!(
+                        "Invalid redaction pattern '{}': {}",
+                        pattern.as_ref(),
+                        e
+                    ))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            patterns,
+            replacement: Self::DEFAULT_REPLACEMENT.to_string(),
+        })
+    }
+
+    /// Use different replacement text
+    pub fn with_replacement(mut self, replacement: impl Into<String>) -> Self {
+        self.replacement = replacement.into();
+        self
+    }
+
+    /// Redact every match in `text`
+    pub fn redact_str(&self, text: &str) -> String {
+        self.patterns
+            .iter()
+            .fold(text.to_string(), |text, pattern| {
+                pattern
+                    .replace_all(&text, self.replacement.as_str())
+                    .into_owned()
+            })
+    }
+
+    /// Redact every string nested in `value`
+    pub fn redact_value(&self, value: &mut Value) {
+        match value {
+            Value::String(text) => *text = self.redact_str(text),
+            Value::Array(items) => items.iter_mut().for_each(|item| self.redact_value(item)),
+            Value::Object(map) => map.values_mut().for_each(|item| self.redact_value(item)),
+            _ => {}
+        }
+    }
+
+    /// Redact an existing recording in place.
+    ///
+    /// Returns the number of records rewritten.
+    pub fn redact_file(&self, path: impl AsRef<Path>) -> Result<usize> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)?;
+
+        let mut redacted = String::with_capacity(content.len());
+        let mut changed = 0;
+        for (index, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let mut value: Value =
+                serde_json::from_str(line).map_err(|e| invalid_line(path, index, e))?;
+            let before = value.clone();
+            self.redact_value(&mut value);
+            if value != before {
+                changed += 1;
+            }
+            redacted.push_str(&serde_json::to_string(&value)?);
+            redacted.push('\n');
+        }
+
+        std::fs::write(path, redacted)?;
+        Ok(changed)
+    }
+}
+
+/// Appends [`HookRecord`]s to a JSONL file.
+///
+/// Cheap to clone; clones share the file.
+#[derive(Clone)]
+pub struct HookRecorder {
+    path: PathBuf,
+    file: Arc<Mutex<File>>,
+    redactor: Option<Redactor>,
+}
+
+impl HookRecorder {
+    /// Start a new recording, replacing any existing file.
+    pub fn create(path: impl Into<PathBuf>) -> Result<Self> {
+        Self::open(path.into(), false)
+    }
+
+    /// Append to an existing recording, creating it if needed.
+    pub fn append(path: impl Into<PathBuf>) -> Result<Self> {
+        Self::open(path.into(), true)
+    }
+
+    fn open(path: PathBuf, append: bool) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(append)
+            .truncate(!append)
+            .open(&path)?;
+
+        Ok(Self {
+            path,
+            file: Arc::new(Mutex::new(file)),
+            redactor: None,
+        })
+    }
+
+    /// Redact every record before it is written
+    pub fn with_redactor(mut self, redactor: Redactor) -> Self {
+        self.redactor = Some(redactor);
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append a record.
+    pub fn record(&self, record: &HookRecord) -> Result<()> {
+        let mut value = serde_json::to_value(record)?;
+        if let Some(redactor) = &self.redactor {
+            redactor.redact_value(&mut value);
+        }
+        let line = serde_json::to_string(&value)?;
+
+        let mut file = self
+            .file
+            .lock()
+            .map_err(|_| Error::protocol("Hook recording lock poisoned"))?;
+        writeln!(file, "{}", line)?;
+        file.flush()?;
+        Ok(())
+    }
+
+    /// Wrap every callback in a hook configuration so its invocations are recorded.
+    pub fn wrap_hooks(
+        &self,
+        hooks: HashMap<String, Vec<HookMatcher>>,
+    ) -> HashMap<String, Vec<HookMatcher>> {
+        hooks
+            .into_iter()
+            .map(|(event, matchers)| {
+                let matchers = matchers
+                    .into_iter()
+                    .enumerate()
+                    .map(|(matcher_index, matcher)| HookMatcher {
+                        hooks: matcher
+                            .hooks
+                            .into_iter()
+                            .enumerate()
+                            .map(|(hook_index, hook)| {
+                                let context = MatcherContext {
+                                    event: event.clone(),
+                                    matcher: matcher.matcher.clone(),
+                                    matcher_index,
+                                    hook_index,
+                                };
+                                Arc::new(RecordingHookWrapper::new(hook, self.clone(), context))
+                                    as Arc<dyn HookCallback>
+                            })
+                            .collect(),
+                        matcher: matcher.matcher,
+                    })
+                    .collect();
+                (event, matchers)
+            })
+            .collect()
+    }
+}
+
+/// A [`HookCallback`] that records every invocation of the callback it wraps.
+///
+/// The wrapped callback's result is passed through unchanged. Failing to
+/// write the recording is logged and never fails the hook.
+pub struct RecordingHookWrapper {
+    inner: Arc<dyn HookCallback>,
+    recorder: HookRecorder,
+    context: MatcherContext,
+}
+
+impl RecordingHookWrapper {
+    pub fn new(
+        inner: Arc<dyn HookCallback>,
+        recorder: HookRecorder,
+        context: MatcherContext,
+    ) -> Self {
+        Self {
+            inner,
+            recorder,
+            context,
+        }
+    }
+}
+
+#[async_trait]
+impl HookCallback for RecordingHookWrapper {
+    async fn execute(
+        &self,
+        input: &HookInput,
+        tool_use_id: Option<&str>,
+        context: &HookContext,
+    ) -> std::result::Result<HookJSONOutput, Error> {
+        let result = self.inner.execute(input, tool_use_id, context).await;
+        let (output, error) = outcome(&result);
+
+        let record = HookRecord {
+            timestamp: Utc::now(),
+            context: self.context.clone(),
+            tool_use_id: tool_use_id.map(str::to_string),
+            input: input.clone(),
+            output,
+            error,
+        };
+        if let Err(e) = self.recorder.record(&record) {
+            tracing::warn!(
+                "Failed to record hook invocation to {}: {}",
+                self.recorder.path.display(),
+                e
+            );
+        }
+
+        result
+    }
+}
+
+/// Read a recording written by [`HookRecorder`].
+pub fn load_recording(path: impl AsRef<Path>) -> Result<Vec<HookRecord>> {
+    let path = path.as_ref();
+    let content = std::fs::read_to_string(path)?;
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| serde_json::from_str(line).map_err(|e| invalid_line(path, index, e)))
+        .collect()
+}
+
+fn invalid_line(path: &Path, index: usize, e: serde_json::Error) -> Error {
+    Error::protocol(format!(
+        "{}:{}: invalid hook record: {}",
+        path.display(),
+        index + 1,
+        e
+    ))
+}
+
+fn outcome(result: &std::result::Result<HookJSONOutput, Error>) -> (Option<Value>, Option<String>) {
+    match result {
+        Ok(output) => (serde_json::to_value(output).ok(), None),
+        Err(e) => (None, Some(e.to_string())),
+    }
+}
+
+/// Feeds recorded hook inputs back through a hook configuration and compares outputs.
+pub struct ReplaySession {
+    hooks: HashMap<String, Vec<HookMatcher>>,
+    ignored: Vec<String>,
+}
+
+impl ReplaySession {
+    /// Replay against the given hook configuration, normally the same one that was recorded
+    pub fn new(hooks: HashMap<String, Vec<HookMatcher>>) -> Self {
+        Self {
+            hooks,
+            ignored: Vec::new(),
+        }
+    }
+
+    /// Leave a field out of the comparison.
+    ///
+    /// A bare name such as `timestamp` is ignored wherever it appears in the
+    /// output; a dotted path such as `hookSpecificOutput.updatedInput.id` only
+    /// at that path.
+    pub fn ignore(mut self, field: impl Into<String>) -> Self {
+        self.ignored.push(field.into());
+        self
+    }
+
+    /// Replay a recording file.
+    pub async fn replay_file(&self, path: impl AsRef<Path>) -> Result<ReplayReport> {
+        let records = load_recording(path)?;
+        Ok(self.replay(&records).await)
+    }
+
+    /// Replay records in order, collecting every mismatch.
+    pub async fn replay(&self, records: &[HookRecord]) -> ReplayReport {
+        let mut report = ReplayReport::default();
+        let context = HookContext { signal: None };
+
+        for (index, record) in records.iter().enumerate() {
+            let mut mismatch = ReplayMismatch {
+                index,
+                context: record.context.clone(),
+                tool_use_id: record.tool_use_id.clone(),
+                differences: Vec::new(),
+            };
+
+            let Some(hook) = self.find(&record.context) else {
+                // Reported as the whole output going missing
+                mismatch.differences.push(Difference {
+                    path: String::new(),
+                    expected: record.output.clone(),
+                    actual: None,
+                });
+                report.mismatches.push(mismatch);
+                continue;
+            };
+
+            let result = hook
+                .execute(&record.input, record.tool_use_id.as_deref(), &context)
+                .await;
+            let (output, error) = outcome(&result);
+            report.replayed += 1;
+
+            if record.error != error {
+                mismatch.differences.push(Difference {
+                    path: "error".to_string(),
+                    expected: record.error.clone().map(Value::String),
+                    actual: error.map(Value::String),
+                });
+            }
+            let expected = record.output.clone().map(|value| self.strip(value));
+            let actual = output.map(|value| self.strip(value));
+            diff_values(
+                "",
+                expected.as_ref(),
+                actual.as_ref(),
+                &mut mismatch.differences,
+            );
+
+            if !mismatch.differences.is_empty() {
+                report.mismatches.push(mismatch);
+            }
+        }
+
+        report
+    }
+
+    fn find(&self, context: &MatcherContext) -> Option<&Arc<dyn HookCallback>> {
+        self.hooks
+            .get(&context.event)?
+            .get(context.matcher_index)?
+            .hooks
+            .get(context.hook_index)
+    }
+
+    fn strip(&self, mut value: Value) -> Value {
+        strip_ignored(&mut value, &self.ignored, "");
+        value
+    }
+}
+
+fn strip_ignored(value: &mut Value, ignored: &[String], path: &str) {
+    match value {
+        Value::Object(map) => {
+            map.retain(|key, _| {
+                let full = join(path, key);
+                !ignored.iter().any(|field| *field == full || field == key)
+            });
+            for (key, item) in map.iter_mut() {
+                strip_ignored(item, ignored, &join(path, key));
+            }
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter_mut().enumerate() {
+                strip_ignored(item, ignored, &format!("{}[{}]", path, index));
+            }
+        }
+        _ => {}
+    }
+}
+
+fn diff_values(
+    path: &str,
+    expected: Option<&Value>,
+    actual: Option<&Value>,
+    out: &mut Vec<Difference>,
+) {
+    match (expected, actual) {
+        (Some(Value::Object(expected)), Some(Value::Object(actual))) => {
+            let keys: BTreeSet<&String> = expected.keys().chain(actual.keys()).collect();
+            for key in keys {
+                diff_values(&join(path, key), expected.get(key), actual.get(key), out);
+            }
+        }
+        (Some(Value::Array(expected)), Some(Value::Array(actual))) => {
+            for index in 0..expected.len().max(actual.len()) {
+                diff_values(
+                    &format!("{}[{}]", path, index),
+                    expected.get(index),
+                    actual.get(index),
+                    out,
+                );
+            }
+        }
+        (expected, actual) if expected != actual => out.push(Difference {
+            path: path.to_string(),
+            expected: expected.cloned(),
+            actual: actual.cloned(),
+        }),
+        _ => {}
+    }
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+/// A value that differs between the recording and the replay.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Difference {
+    /// Path of the value in the hook output, e.g. `hookSpecificOutput.permissionDecision`
+    pub path: String,
+    /// Recorded value, `None` if absent
+    pub expected: Option<Value>,
+    /// Replayed value, `None` if absent
+    pub actual: Option<Value>,
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_empty() {
+            "(output)"
+        } else {
+            &self.path
+        };
+        match &self.expected {
+            Some(value) => writeln!(f, "- {}: {}", path, value)?,
+            None => writeln!(f, "- {}: (absent)", path)?,
+        }
+        match &self.actual {
+            Some(value) => write!(f, "+ {}: {}", path, value),
+            None => write!(f, "+ {}: (absent)", path),
+        }
+    }
+}
+
+/// A recorded invocation whose replay did not match.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayMismatch {
+    /// Position of the record in the recording, starting at 0
+    pub index: usize,
+    /// Which callback the record belongs to
+    pub context: MatcherContext,
+    /// Tool use the hook ran for, if any
+    pub tool_use_id: Option<String>,
+    /// Every differing value
+    pub differences: Vec<Difference>,
+}
+
+impl fmt::Display for ReplayMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "record {} ({} matcher {} hook {}",
+            self.index + 1,
+            self.context.event,
+            self.context.matcher_index,
+            self.context.hook_index
+        )?;
+        if let Some(id) = &self.tool_use_id {
+            write!(f, ", tool use {}", id)?;
+        }
+        writeln!(f, "):")?;
+        for difference in &self.differences {
+            for line in difference.to_string().lines() {
+                writeln!(f, "    {}", line)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of a [`ReplaySession`] run.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReplayReport {
+    /// Records whose hook was found and run
+    pub replayed: usize,
+    /// Records that did not match, in recording order
+    pub mismatches: Vec<ReplayMismatch>,
+}
+
+impl ReplayReport {
+    pub fn is_ok(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+
+    /// Panic with a readable diff of every mismatch.
+    ///
+    /// Intended for tests.
+    #[track_caller]
+    pub fn assert_ok(&self) {
+        if !self.is_ok() {
+            panic!("{}", self);
+        }
+    }
+}
+
+impl fmt::Display for ReplayReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} of {} replayed hook invocations differ from the recording",
+            self.mismatches.len(),
+            self.replayed
+        )?;
+        for mismatch in &self.mismatches {
+            write!(f, "{}", mismatch)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cc::hooks::{
+        BaseHookInput, HookSpecificOutput, PreToolUseHookInput, PreToolUseHookSpecificOutput,
+        SyncHookJSONOutput,
+    };
+
+    /// Denies a fixed tool, allows everything else
+    struct DenyTool(&'static str);
+
+    #[async_trait]
+    impl HookCallback for DenyTool {
+        async fn execute(
+            &self,
+            input: &HookInput,
+            _tool_use_id: Option<&str>,
+            _context: &HookContext,
+        ) -> std::result::Result<HookJSONOutput, Error> {
+            let HookInput::PreToolUse(input) = input else {
+                return Err(Error::protocol("unexpected hook event"));
+            };
+            let decision = if input.tool_name == self.0 {
+                "deny"
+            } else {
+                "allow"
+            };
+            Ok(HookJSONOutput::Sync(SyncHookJSONOutput {
+                reason: Some(format!(
+                    "checked at {}",
+                    Utc::now().timestamp_nanos_opt().unwrap()
+                )),
+                hook_specific_output: Some(HookSpecificOutput::PreToolUse(
+                    PreToolUseHookSpecificOutput {
+                        permission_decision: Some(decision.to_string()),
+                        permission_decision_reason: None,
+                        updated_input: None,
+                    },
+                )),
+                ..Default::default()
+            }))
+        }
+    }
+
+    fn hooks(tool: &'static str) -> HashMap<String, Vec<HookMatcher>> {
+        HashMap::from([(
+            "PreToolUse".to_string(),
+            vec![HookMatcher {
+                matcher: Some(serde_json::json!("*")),
+                hooks: vec![Arc::new(DenyTool(tool))],
+            }],
+        )])
+    }
+
+    fn tool_input(tool: &str, command: &str) -> HookInput {
+        HookInput::PreToolUse(PreToolUseHookInput {
+            base: BaseHookInput {
+                session_id: "session-1".to_string(),
+                transcript_path: "/tmp/transcript.jsonl".to_string(),
+                cwd: "/tmp".to_string(),
+                permission_mode: None,
+            },
+            tool_name: tool.to_string(),
+            tool_input: serde_json::json!({ "command": command }),
+        })
+    }
+
+    async fn record_session(path: &Path, recorder: HookRecorder) {
+        let wrapped = recorder.wrap_hooks(hooks("Bash"));
+        let hook = &wrapped["PreToolUse"][0].hooks[0];
+        let context = HookContext { signal: None };
+        for (id, tool) in [("toolu_1", "Read"), ("toolu_2", "Bash")] {
+            hook.execute(&tool_input(tool, "echo sk-abc123"), Some(id), &context)
+                .await
+                .unwrap();
+        }
+        assert!(path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_record_then_replay_matches() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hooks.jsonl");
+        record_session(&path, HookRecorder::create(&path).unwrap()).await;
+
+        let records = load_recording(&path).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].context.event, "PreToolUse");
+        assert_eq!(records[1].tool_use_id.as_deref(), Some("toolu_2"));
+        assert_eq!(
+            records[1].output.as_ref().unwrap()["hookSpecificOutput"]["permissionDecision"],
+            "deny"
+        );
+
+        // `reason` embeds a timestamp, so it only matches when ignored
+        let strict = ReplaySession::new(hooks("Bash")).replay(&records).await;
+        assert_eq!(strict.mismatches.len(), 2);
+
+        let report = ReplaySession::new(hooks("Bash"))
+            .ignore("reason")
+            .replay_file(&path)
+            .await
+            .unwrap();
+        assert_eq!(report.replayed, 2);
+        report.assert_ok();
+    }
+
+    #[tokio::test]
+    async fn test_changed_behavior_is_reported_with_diff() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hooks.jsonl");
+        record_session(&path, HookRecorder::create(&path).unwrap()).await;
+
+        let report = ReplaySession::new(hooks("Read"))
+            .ignore("reason")
+            .replay_file(&path)
+            .await
+            .unwrap();
+
+        assert_eq!(report.mismatches.len(), 2);
+        let first = &report.mismatches[0];
+        assert_eq!(first.tool_use_id.as_deref(), Some("toolu_1"));
+        assert_eq!(
+            first.differences,
+            vec![Difference {
+                path: "hookSpecificOutput.permissionDecision".to_string(),
+                expected: Some(serde_json::json!("allow")),
+                actual: Some(serde_json::json!("deny")),
+            }]
+        );
+
+        let text = report.to_string();
+        assert!(text.contains("2 of 2 replayed"));
+        assert!(text.contains("- hookSpecificOutput.permissionDecision: \"allow\""));
+        assert!(text.contains("+ hookSpecificOutput.permissionDecision: \"deny\""));
+    }
+
+    #[tokio::test]
+    async fn test_missing_hook_is_a_mismatch() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hooks.jsonl");
+        record_session(&path, HookRecorder::create(&path).unwrap()).await;
+
+        let report = ReplaySession::new(HashMap::new())
+            .replay_file(&path)
+            .await
+            .unwrap();
+        assert_eq!(report.replayed, 0);
+        assert_eq!(report.mismatches.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_redaction_when_recording_and_in_place() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let redacted = dir.path().join("redacted.jsonl");
+        let redactor = Redactor::new([r"sk-[a-z0-9]+"]).unwrap();
+        record_session(
+            &redacted,
+            HookRecorder::create(&redacted)
+                .unwrap()
+                .with_redactor(redactor.clone()),
+        )
+        .await;
+        let content = std::fs::read_to_string(&redacted).unwrap();
+        assert!(!content.contains("sk-abc123"));
+        assert!(content.contains("echo [REDACTED]"));
+
+        let raw = dir.path().join("raw.jsonl");
+        record_session(&raw, HookRecorder::create(&raw).unwrap()).await;
+        assert!(std::fs::read_to_string(&raw).unwrap().contains("sk-abc123"));
+        assert_eq!(redactor.redact_file(&raw).unwrap(), 2);
+        assert!(!std::fs::read_to_string(&raw).unwrap().contains("sk-abc123"));
+        assert_eq!(load_recording(&raw).unwrap().len(), 2);
+
+        assert!(Redactor::new(["("]).is_err());
+    }
+}
@@ -161,6 +161,7 @@ pub fn create_sdk_server_config(
 /// - `McpError::Tool` → `Error::Protocol` (with descriptive message)
 /// - `McpError::Resource` → `Error::Protocol` (with descriptive message)
 /// - `McpError::Middleware` → `Error::Protocol` (with descriptive message)
+/// - `McpError::Prompt` → `Error::Protocol` (with descriptive message)
 /// - `McpError::Config` → `Error::Config`
 /// - `McpError::Protocol` → `Error::Protocol`
 ///
@@ -207,6 +208,10 @@ impl From<McpError> for Error {
                 // Map middleware errors to protocol errors with descriptive messages
                 Error::Protocol(format!("MCP middleware error: {}", middleware_err))
             }
+            McpError::Prompt(prompt_err) => {
+                // Map prompt errors to protocol errors with descriptive messages
+                Error::Protocol(format!("MCP prompt error: {}", prompt_err))
+            }
             McpError::Config(msg) => {
                 // Config errors map directly
                 Error::Config(msg)
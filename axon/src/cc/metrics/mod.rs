@@ -470,6 +470,52 @@ impl Default for SessionMetrics {
     }
 }
 
+/// Counters for orphaned Claude processes found by process discovery.
+///
+/// Maintained by [`ProcessRegistry::discover_orphans`](crate::cc::process::ProcessRegistry::discover_orphans)
+/// and read with [`ProcessRegistry::orphan_metrics`](crate::cc::process::ProcessRegistry::orphan_metrics).
+/// Counts accumulate across discovery runs.
+///
+/// # Examples
+///
+/// ```rust
+/// use crate::cc::metrics::OrphanMetrics;
+///
+/// let mut metrics = OrphanMetrics::default();
+/// metrics.record_discovery(2, 1, 4);
+///
+/// assert_eq!(metrics.discovered, 3);
+/// assert_eq!(metrics.stale_records_removed, 4);
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OrphanMetrics {
+    /// Number of discovery runs
+    pub discovery_runs: u64,
+
+    /// Live orphaned processes found (adopted + reaped)
+    pub discovered: u64,
+
+    /// Orphans re-attached to the registry
+    pub adopted: u64,
+
+    /// Orphans terminated
+    pub reaped: u64,
+
+    /// Records removed for dead, reused or unreadable PIDs
+    pub stale_records_removed: u64,
+}
+
+impl OrphanMetrics {
+    /// Record the outcome of one discovery run.
+    pub fn record_discovery(&mut self, adopted: usize, reaped: usize, stale_removed: usize) {
+        self.discovery_runs += 1;
+        self.discovered += (adopted + reaped) as u64;
+        self.adopted += adopted as u64;
+        self.reaped += reaped as u64;
+        self.stale_records_removed += stale_removed as u64;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
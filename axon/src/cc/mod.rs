@@ -74,6 +74,7 @@
 //! - [`settings`] - Settings loading and saving with scope precedence
 //! - [`mcp`] - Model Context Protocol integration
 //! - [`binary`] - Claude binary discovery and version management
+//! - [`pool`] - Bounded-parallelism request pool for one-shot queries
 //! - [`process`] - Process registry for concurrent session tracking
 //! - [`messages`] - Message and content type definitions
 //! - [`options`] - Configuration and builder types
@@ -114,6 +115,7 @@ pub mod requests;
 pub mod binary;
 pub mod cache;  // Generic caching infrastructure
 pub mod mcp;
+pub mod pool;
 pub mod process;
 pub mod session;
 pub mod settings;
@@ -293,19 +295,28 @@ pub use settings::{ClaudeSettings, HookConfig, SettingsScope};
 // ----------------------------------------------------------------------------
 
 /// Simple query interface for one-shot interactions.
-pub use query::query;
+pub use query::{query, QueryInput};
+
+/// Bounded-parallelism pool for one-shot queries.
+pub use pool::{PoolConfig, PoolRequest, PoolStats, QueryPool, QueryPoolBuilder};
 
 /// Internal query builder (advanced usage).
 pub use internal_query::Query;
 
 /// Model recommendation system.
-pub use model_recommendation::ModelRecommendation;
+pub use model_recommendation::{
+    Calibration, Constraints, ModelObservations, ModelProfile, ModelRecommendation, Priority,
+    Recommendation, TaskDescriptor,
+};
 
 /// Performance utilities for batching and retry logic.
 pub use perf_utils::{MessageBatcher, PerformanceMetrics, RetryConfig};
 
 /// Token usage tracking and budget management.
-pub use token_tracker::{BudgetLimit, BudgetManager, BudgetStatus, TokenUsageTracker};
+pub use token_tracker::{
+    AttributionHook, BudgetLimit, BudgetManager, BudgetStatus, ModelPrice, PriceTable,
+    ReportGrouping, TokenUsageTracker, UsageRecord, UsageReport, UsageReportRow, UsageTotals,
+};
 
 /// Transport implementation.
 pub use transport::SubprocessTransport;
@@ -314,7 +325,7 @@ pub use transport::SubprocessTransport;
 pub use streaming::{JsonlReader, OutputBuffer, extract_session_id, extract_session_id_from_line, parse_jsonl_line};
 
 /// Real-time metrics tracking for sessions.
-pub use metrics::{SessionMetrics, DEFAULT_INPUT_TOKEN_COST, DEFAULT_OUTPUT_TOKEN_COST};
+pub use metrics::{OrphanMetrics, SessionMetrics, DEFAULT_INPUT_TOKEN_COST, DEFAULT_OUTPUT_TOKEN_COST};
 
 // ============================================================================
 // Prelude Module
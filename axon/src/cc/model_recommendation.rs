@@ -2,8 +2,23 @@
 //!
 //! This module provides utilities to help choose the most cost-effective Claude model
 //! based on task complexity and requirements.
+//!
+//! Besides the static task-type mapping, [`ModelRecommendation::recommend`] ranks
+//! candidate models for a [`TaskDescriptor`] using list prices and the cost and
+//! latency actually observed through [`SessionMetrics`] and [`UsageReport`]s.
+//! Observations can be exported as a [`Calibration`] and imported elsewhere, so a
+//! fleet of processes can share what each of them has measured.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
 
-use std::collections::HashMap;
+use crate::cc::core::ModelId;
+use crate::cc::error::Error;
+use crate::cc::metrics::SessionMetrics;
+use crate::cc::result::Result;
+use crate::cc::token_tracker::{PriceTable, ReportGrouping, UsageReport, UsageTotals};
 
 /// Model recommendation helper
 ///
@@ -12,6 +27,9 @@ use std::collections::HashMap;
 #[derive(Debug, Clone)]
 pub struct ModelRecommendation {
     recommendations: HashMap<String, String>,
+    candidates: Vec<ModelProfile>,
+    prices: PriceTable,
+    observations: HashMap<ModelId, ModelObservations>,
 }
 
 impl ModelRecommendation {
@@ -53,7 +71,7 @@ impl ModelRecommendation {
         map.insert("critical".to_string(), "opus".to_string());
         map.insert("advanced".to_string(), "opus".to_string());
 
-        Self { recommendations: map }
+        Self::custom(map)
     }
 
     /// Create with custom recommendations
@@ -71,7 +89,12 @@ impl ModelRecommendation {
     /// let recommender = ModelRecommendation::custom(custom_map);
     /// ```
     pub fn custom(recommendations: HashMap<String, String>) -> Self {
-        Self { recommendations }
+        Self {
+            recommendations,
+            candidates: ModelProfile::defaults(),
+            prices: PriceTable::default(),
+            observations: HashMap::new(),
+        }
     }
 
     /// Get a model suggestion for a given task type
@@ -124,6 +147,325 @@ impl ModelRecommendation {
     pub fn all_recommendations(&self) -> &HashMap<String, String> {
         &self.recommendations
     }
+
+    /// Use `prices` to estimate the cost of a task
+    pub fn with_prices(mut self, prices: PriceTable) -> Self {
+        self.prices = prices;
+        self
+    }
+
+    /// Add a candidate for [`recommend`](Self::recommend), replacing any profile
+    /// for the same model
+    pub fn with_candidate(mut self, profile: ModelProfile) -> Self {
+        self.add_candidate(profile);
+        self
+    }
+
+    /// Add a candidate for [`recommend`](Self::recommend), replacing any profile
+    /// for the same model
+    pub fn add_candidate(&mut self, profile: ModelProfile) {
+        match self
+            .candidates
+            .iter_mut()
+            .find(|c| c.model == profile.model)
+        {
+            Some(existing) => *existing = profile,
+            None => self.candidates.push(profile),
+        }
+    }
+
+    /// Stop considering a model
+    pub fn remove_candidate(&mut self, model: &ModelId) -> Option<ModelProfile> {
+        let index = self.candidates.iter().position(|c| &c.model == model)?;
+        Some(self.candidates.remove(index))
+    }
+
+    /// Models considered by [`recommend`](Self::recommend)
+    pub fn candidates(&self) -> &[ModelProfile] {
+        &self.candidates
+    }
+
+    /// Record one completed call made with `model`
+    ///
+    /// The session duration is taken as the call latency, and the cost and token
+    /// counts calibrate the cost estimate.
+    pub fn observe(&mut self, model: &ModelId, metrics: &SessionMetrics) {
+        let observations = self.observations.entry(model.clone()).or_default();
+        observations.calls += 1;
+        observations.input_tokens += metrics.prompt_tokens.unwrap_or(0);
+        observations.output_tokens += metrics.completion_tokens.unwrap_or(0);
+        observations.cost_usd += metrics.cost_usd.unwrap_or(0.0);
+        if let Some(duration_ms) = metrics.duration_ms {
+            observations.push_latency(duration_ms);
+        }
+    }
+
+    /// Record the latency of one call made with `model`
+    pub fn observe_latency(&mut self, model: &ModelId, latency: Duration) {
+        self.observations
+            .entry(model.clone())
+            .or_default()
+            .push_latency(latency.as_millis() as u64);
+    }
+
+    /// Record the cost and token counts of a report grouped by model
+    ///
+    /// Reports carry no latency. Usage already recorded through
+    /// [`observe`](Self::observe) should not be fed in again, or it is counted twice.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if the report is not grouped by model.
+    pub fn observe_report(&mut self, report: &UsageReport) -> Result<()> {
+        if report.grouping != ReportGrouping::Model {
+            return Err(Error::config(format!(
+                "expected a usage report grouped by model, got one grouped by {:?}",
+                report.grouping
+            )));
+        }
+
+        for row in &report.rows {
+            let observations = self
+                .observations
+                .entry(ModelId::new(row.key.clone()))
+                .or_default();
+            observations.calls += row.requests;
+            // Cached input counts as input, so the calibration reflects cache savings
+            observations.input_tokens +=
+                row.input_tokens + row.cache_creation_tokens + row.cache_read_tokens;
+            observations.output_tokens += row.output_tokens;
+            observations.cost_usd += row.cost_usd;
+        }
+        Ok(())
+    }
+
+    /// Everything observed for a model so far
+    pub fn observations(&self, model: &ModelId) -> Option<&ModelObservations> {
+        self.observations.get(model)
+    }
+
+    /// Rank the candidate models for a task, best first
+    ///
+    /// Candidates that cannot run the task (context too small, no extended
+    /// thinking) or that violate the task's [`Constraints`] are left out. An
+    /// empty result means no candidate qualifies.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use crate::cc::model_recommendation::{ModelRecommendation, TaskDescriptor};
+    ///
+    /// let recommender = ModelRecommendation::default();
+    /// let task = TaskDescriptor::new(20_000)
+    ///     .requires_thinking()
+    ///     .max_cost_per_call(0.05);
+    ///
+    /// for recommendation in recommender.recommend(&task) {
+    ///     println!("{}: {:?}", recommendation.model, recommendation.reasons);
+    /// }
+    /// ```
+    pub fn recommend(&self, task: &TaskDescriptor) -> Vec<Recommendation> {
+        let mut ranked: Vec<Recommendation> = self
+            .candidates
+            .iter()
+            .filter_map(|profile| self.evaluate(profile, task))
+            .collect();
+
+        ranked.sort_by(|a, b| {
+            let by_cost = compare_known(a.expected_cost_usd, b.expected_cost_usd);
+            let by_latency = compare_known(
+                a.p50_latency.map(|d| d.as_secs_f64()),
+                b.p50_latency.map(|d| d.as_secs_f64()),
+            );
+            match task.priority {
+                Priority::Cost => by_cost.then(by_latency),
+                Priority::Latency => by_latency.then(by_cost),
+            }
+            .then_with(|| a.model.as_str().cmp(b.model.as_str()))
+        });
+
+        let ranked_by = match task.priority {
+            Priority::Cost => "expected cost",
+            Priority::Latency => "median latency",
+        };
+        for (index, recommendation) in ranked.iter_mut().enumerate() {
+            recommendation
+                .reasons
+                .insert(0, format!("ranked #{} by {}", index + 1, ranked_by));
+        }
+        ranked
+    }
+
+    /// Models to try for a task, in order
+    ///
+    /// The first model is the recommendation; the rest are used when it is
+    /// overloaded. [`ClaudeClientBuilder::recommended_model`] applies this chain.
+    ///
+    /// [`ClaudeClientBuilder::recommended_model`]: crate::cc::client::ClaudeClientBuilder::recommended_model
+    pub fn fallback_chain(&self, task: &TaskDescriptor) -> Vec<ModelId> {
+        self.recommend(task)
+            .into_iter()
+            .map(|recommendation| recommendation.model)
+            .collect()
+    }
+
+    /// Snapshot of the candidates and everything observed so far
+    pub fn export_calibration(&self) -> Calibration {
+        Calibration {
+            candidates: self.candidates.clone(),
+            observations: self
+                .observations
+                .iter()
+                .map(|(model, observations)| (model.to_string(), observations.clone()))
+                .collect(),
+        }
+    }
+
+    /// Merge a calibration exported by another process
+    ///
+    /// Candidate profiles replace local profiles for the same model, and
+    /// observations are added to the local ones.
+    pub fn import_calibration(&mut self, calibration: Calibration) {
+        for profile in calibration.candidates {
+            self.add_candidate(profile);
+        }
+        for (model, observations) in calibration.observations {
+            self.observations
+                .entry(ModelId::new(model))
+                .or_default()
+                .merge(observations);
+        }
+    }
+
+    fn evaluate(&self, profile: &ModelProfile, task: &TaskDescriptor) -> Option<Recommendation> {
+        let model = &profile.model;
+        let needed = task.prompt_tokens + task.output_tokens;
+        if needed > profile.context_window {
+            tracing::debug!(%model, needed, window = profile.context_window, "task does not fit");
+            return None;
+        }
+        if task.requires_long_context && profile.context_window <= STANDARD_CONTEXT_WINDOW {
+            tracing::debug!(%model, "no long context window");
+            return None;
+        }
+        if task.requires_thinking && !profile.supports_thinking {
+            tracing::debug!(%model, "no extended thinking");
+            return None;
+        }
+
+        let observations = self.observations.get(model);
+        let (expected_cost_usd, cost_reason) = self.expected_cost(model, task, observations);
+        let (p50_latency, p95_latency) = observations.map_or((None, None), |o| {
+            (o.latency_percentile(0.5), o.latency_percentile(0.95))
+        });
+
+        let constraints = &task.constraints;
+        if let Some(min_context) = constraints.min_context_tokens
+            && profile.context_window < min_context
+        {
+            tracing::debug!(%model, min_context, "context window below constraint");
+            return None;
+        }
+        if let Some(max_cost) = constraints.max_cost_per_call
+            && !expected_cost_usd.is_some_and(|cost| cost <= max_cost)
+        {
+            tracing::debug!(%model, max_cost, ?expected_cost_usd, "cost constraint not met");
+            return None;
+        }
+        if let Some(max_p95) = constraints.max_p95_latency
+            && p95_latency.is_none_or(|p95| p95 > max_p95)
+        {
+            tracing::debug!(%model, ?max_p95, ?p95_latency, "latency constraint not met");
+            return None;
+        }
+
+        let mut reasons = vec![cost_reason];
+        reasons.push(match (p50_latency, p95_latency, observations) {
+            (Some(p50), Some(p95), Some(o)) => format!(
+                "p50 latency {:.1}s, p95 {:.1}s over {} calls",
+                p50.as_secs_f64(),
+                p95.as_secs_f64(),
+                o.latencies_ms.len()
+            ),
+            _ => "no latency observations".to_string(),
+        });
+        if task.requires_thinking {
+            reasons.push("supports extended thinking".to_string());
+        }
+        if task.requires_long_context || constraints.min_context_tokens.is_some() {
+            reasons.push(format!("{}-token context window", profile.context_window));
+        }
+
+        Some(Recommendation {
+            model: model.clone(),
+            expected_cost_usd,
+            p50_latency,
+            p95_latency,
+            reasons,
+        })
+    }
+
+    /// Expected cost of one call and how it was derived
+    fn expected_cost(
+        &self,
+        model: &ModelId,
+        task: &TaskDescriptor,
+        observations: Option<&ModelObservations>,
+    ) -> (Option<f64>, String) {
+        let task_totals = UsageTotals {
+            requests: 1,
+            input_tokens: task.prompt_tokens,
+            output_tokens: task.output_tokens,
+            ..Default::default()
+        };
+        let observed = observations.filter(|o| o.cost_usd > 0.0 && o.tokens() > 0);
+
+        match (self.prices.price_for(model), observed) {
+            (Some(price), Some(o)) => {
+                let list_cost = price.cost(&UsageTotals {
+                    requests: o.calls,
+                    input_tokens: o.input_tokens,
+                    output_tokens: o.output_tokens,
+                    ..Default::default()
+                });
+                let factor = if list_cost > 0.0 {
+                    o.cost_usd / list_cost
+                } else {
+                    1.0
+                };
+                let cost = price.cost(&task_totals) * factor;
+                (
+                    Some(cost),
+                    format!(
+                        "expected cost ${:.4} from list price, calibrated x{:.2} by {} observed calls",
+                        cost, factor, o.calls
+                    ),
+                )
+            }
+            (Some(price), None) => {
+                let cost = price.cost(&task_totals);
+                (
+                    Some(cost),
+                    format!("expected cost ${:.4} from list price", cost),
+                )
+            }
+            (None, Some(o)) => {
+                let cost = o.cost_usd / o.tokens() as f64
+                    * (task.prompt_tokens + task.output_tokens) as f64;
+                (
+                    Some(cost),
+                    format!(
+                        "expected cost ${:.4} from observed spend over {} calls",
+                        cost, o.calls
+                    ),
+                )
+            }
+            (None, None) => (
+                None,
+                "cost unknown: no price and no observations".to_string(),
+            ),
+        }
+    }
 }
 
 impl Default for ModelRecommendation {
@@ -184,6 +526,268 @@ pub fn estimate_cost_multiplier(model: &str) -> f64 {
     }
 }
 
+/// Largest context window every current model supports
+///
+/// Tasks that [require long context](TaskDescriptor::requires_long_context) need a
+/// model with a larger window than this.
+pub const STANDARD_CONTEXT_WINDOW: u64 = 200_000;
+
+/// Number of latency samples kept per model for percentile estimates
+pub const LATENCY_WINDOW: usize = 256;
+
+/// What a model can do, as far as selection is concerned
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelProfile {
+    /// Model id passed to the CLI
+    pub model: ModelId,
+    /// Largest usable context window in tokens
+    pub context_window: u64,
+    /// Whether the model supports extended thinking
+    pub supports_thinking: bool,
+}
+
+impl ModelProfile {
+    /// Profile with the standard context window and no extended thinking
+    pub fn new(model: impl Into<ModelId>) -> Self {
+        Self {
+            model: model.into(),
+            context_window: STANDARD_CONTEXT_WINDOW,
+            supports_thinking: false,
+        }
+    }
+
+    /// Set the context window in tokens
+    pub fn with_context_window(mut self, tokens: u64) -> Self {
+        self.context_window = tokens;
+        self
+    }
+
+    /// Mark the model as supporting extended thinking
+    pub fn with_thinking(mut self) -> Self {
+        self.supports_thinking = true;
+        self
+    }
+
+    /// Candidates used unless configured otherwise
+    fn defaults() -> Vec<Self> {
+        vec![
+            Self::new("claude-3-5-haiku-20241022"),
+            Self::new("claude-haiku-4-5").with_thinking(),
+            Self::new("claude-sonnet-4-5")
+                .with_context_window(1_000_000)
+                .with_thinking(),
+            Self::new("claude-opus-4-5").with_thinking(),
+        ]
+    }
+}
+
+/// What is ranked first by [`ModelRecommendation::recommend`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    /// Lowest expected cost, then lowest median latency
+    #[default]
+    Cost,
+    /// Lowest median latency, then lowest expected cost
+    Latency,
+}
+
+/// Hard limits a recommended model must satisfy
+///
+/// A limit on a value that cannot be estimated (no price, no latency samples)
+/// excludes the model, since it cannot be shown to meet the limit.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Constraints {
+    /// Maximum expected cost of one call in USD
+    pub max_cost_per_call: Option<f64>,
+    /// Minimum context window in tokens
+    pub min_context_tokens: Option<u64>,
+    /// Maximum observed 95th percentile latency
+    pub max_p95_latency: Option<Duration>,
+}
+
+/// A task to pick a model for
+///
+/// # Example
+///
+/// ```rust
+/// use crate::cc::model_recommendation::{Priority, TaskDescriptor};
+///
+/// let task = TaskDescriptor::new(150_000)
+///     .with_output_tokens(8_000)
+///     .min_context_tokens(200_000)
+///     .prioritize(Priority::Latency);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskDescriptor {
+    /// Estimated prompt size in tokens
+    pub prompt_tokens: u64,
+    /// Estimated response size in tokens
+    pub output_tokens: u64,
+    /// The task needs extended thinking
+    pub requires_thinking: bool,
+    /// The task needs more than [`STANDARD_CONTEXT_WINDOW`] tokens of context
+    pub requires_long_context: bool,
+    /// What to rank by
+    pub priority: Priority,
+    /// Limits that filter candidates
+    pub constraints: Constraints,
+}
+
+impl TaskDescriptor {
+    /// Task with an estimated prompt size and a 1,000-token response
+    pub fn new(prompt_tokens: u64) -> Self {
+        Self {
+            prompt_tokens,
+            output_tokens: 1_000,
+            requires_thinking: false,
+            requires_long_context: false,
+            priority: Priority::default(),
+            constraints: Constraints::default(),
+        }
+    }
+
+    /// Set the estimated response size in tokens
+    pub fn with_output_tokens(mut self, tokens: u64) -> Self {
+        self.output_tokens = tokens;
+        self
+    }
+
+    /// Only consider models with extended thinking
+    pub fn requires_thinking(mut self) -> Self {
+        self.requires_thinking = true;
+        self
+    }
+
+    /// Only consider models with a larger than standard context window
+    pub fn requires_long_context(mut self) -> Self {
+        self.requires_long_context = true;
+        self
+    }
+
+    /// Set what to rank by
+    pub fn prioritize(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Exclude models expected to cost more than `usd` per call
+    pub fn max_cost_per_call(mut self, usd: f64) -> Self {
+        self.constraints.max_cost_per_call = Some(usd);
+        self
+    }
+
+    /// Exclude models with a context window below `tokens`
+    pub fn min_context_tokens(mut self, tokens: u64) -> Self {
+        self.constraints.min_context_tokens = Some(tokens);
+        self
+    }
+
+    /// Exclude models whose observed p95 latency exceeds `latency`
+    pub fn max_p95_latency(mut self, latency: Duration) -> Self {
+        self.constraints.max_p95_latency = Some(latency);
+        self
+    }
+}
+
+/// One ranked entry returned by [`ModelRecommendation::recommend`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recommendation {
+    /// Recommended model
+    pub model: ModelId,
+    /// Expected cost of one call in USD, if it can be estimated
+    pub expected_cost_usd: Option<f64>,
+    /// Observed median latency
+    pub p50_latency: Option<Duration>,
+    /// Observed 95th percentile latency
+    pub p95_latency: Option<Duration>,
+    /// Why the model is ranked where it is, most important first
+    pub reasons: Vec<String>,
+}
+
+/// Cost and latency observed for one model
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelObservations {
+    /// Number of calls observed
+    pub calls: u64,
+    /// Input tokens across all calls, cached or not
+    pub input_tokens: u64,
+    /// Output tokens across all calls
+    pub output_tokens: u64,
+    /// Total cost in USD
+    pub cost_usd: f64,
+    /// Most recent call latencies in milliseconds, oldest first
+    pub latencies_ms: VecDeque<u64>,
+}
+
+impl ModelObservations {
+    fn tokens(&self) -> u64 {
+        self.input_tokens + self.output_tokens
+    }
+
+    fn push_latency(&mut self, latency_ms: u64) {
+        if self.latencies_ms.len() == LATENCY_WINDOW {
+            self.latencies_ms.pop_front();
+        }
+        self.latencies_ms.push_back(latency_ms);
+    }
+
+    fn merge(&mut self, other: ModelObservations) {
+        self.calls += other.calls;
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cost_usd += other.cost_usd;
+        for latency_ms in other.latencies_ms {
+            self.push_latency(latency_ms);
+        }
+    }
+
+    /// Latency at `quantile` (0.0 to 1.0) by nearest rank, if any were observed
+    pub fn latency_percentile(&self, quantile: f64) -> Option<Duration> {
+        if self.latencies_ms.is_empty() {
+            return None;
+        }
+        let mut sorted: Vec<u64> = self.latencies_ms.iter().copied().collect();
+        sorted.sort_unstable();
+        let rank = (quantile.clamp(0.0, 1.0) * sorted.len() as f64).ceil() as usize;
+        Some(Duration::from_millis(sorted[rank.saturating_sub(1)]))
+    }
+}
+
+/// Candidate profiles and observations shared between processes
+///
+/// Produced by [`ModelRecommendation::export_calibration`] and merged with
+/// [`ModelRecommendation::import_calibration`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Calibration {
+    /// Candidate models
+    pub candidates: Vec<ModelProfile>,
+    /// Observations keyed by model id
+    pub observations: BTreeMap<String, ModelObservations>,
+}
+
+impl Calibration {
+    /// Serialize as pretty-printed JSON
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Parse a calibration produced by [`to_json`](Self::to_json)
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
+
+/// Order known values ascending, with unknown values last
+fn compare_known(a: Option<f64>, b: Option<f64>) -> std::cmp::Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.total_cmp(&b),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -232,4 +836,178 @@ mod tests {
         assert_eq!(balanced_model(), "sonnet");
         assert_eq!(best_model(), "opus");
     }
+
+    fn metrics(duration_ms: u64, prompt: u64, completion: u64, cost: f64) -> SessionMetrics {
+        SessionMetrics {
+            duration_ms: Some(duration_ms),
+            prompt_tokens: Some(prompt),
+            completion_tokens: Some(completion),
+            cost_usd: Some(cost),
+            ..Default::default()
+        }
+    }
+
+    fn models(recommendations: &[Recommendation]) -> Vec<&str> {
+        recommendations.iter().map(|r| r.model.as_str()).collect()
+    }
+
+    #[test]
+    fn test_recommend_filters_by_capability_and_ranks_by_cost() {
+        let recommender = ModelRecommendation::default();
+
+        let ranked = recommender.recommend(&TaskDescriptor::new(10_000));
+        assert_eq!(
+            models(&ranked),
+            vec![
+                "claude-3-5-haiku-20241022",
+                "claude-haiku-4-5",
+                "claude-sonnet-4-5",
+                "claude-opus-4-5"
+            ]
+        );
+        // 10k input and 1k output at $0.80/$4 per million
+        assert!((ranked[0].expected_cost_usd.unwrap() - 0.012).abs() < 1e-9);
+        assert_eq!(ranked[0].reasons[0], "ranked #1 by expected cost");
+
+        let thinking = recommender.recommend(&TaskDescriptor::new(10_000).requires_thinking());
+        assert_eq!(thinking[0].model.as_str(), "claude-haiku-4-5");
+
+        let long = recommender.recommend(&TaskDescriptor::new(500_000).requires_long_context());
+        assert_eq!(models(&long), vec!["claude-sonnet-4-5"]);
+    }
+
+    #[test]
+    fn test_constraints_filter_candidates() {
+        let recommender = ModelRecommendation::default();
+
+        let cheap = recommender.recommend(&TaskDescriptor::new(10_000).max_cost_per_call(0.05));
+        assert!(!models(&cheap).contains(&"claude-opus-4-5"));
+
+        let wide = recommender.recommend(&TaskDescriptor::new(1_000).min_context_tokens(500_000));
+        assert_eq!(models(&wide), vec!["claude-sonnet-4-5"]);
+
+        // No latency has been observed, so no model can be shown to meet the limit
+        let fast = recommender
+            .recommend(&TaskDescriptor::new(1_000).max_p95_latency(Duration::from_secs(5)));
+        assert!(fast.is_empty());
+    }
+
+    #[test]
+    fn test_observed_latency_and_cost_drive_ranking() {
+        let mut recommender = ModelRecommendation::default();
+        let haiku = ModelId::new("claude-haiku-4-5");
+        let sonnet = ModelId::new("claude-sonnet-4-5");
+
+        for latency_ms in 1..=100 {
+            recommender.observe(&haiku, &metrics(latency_ms * 100, 1_000, 1_000, 0.003));
+        }
+        recommender.observe_latency(&sonnet, Duration::from_millis(500));
+
+        let observed = recommender.observations(&haiku).unwrap();
+        assert_eq!(
+            observed.latency_percentile(0.5),
+            Some(Duration::from_millis(5_000))
+        );
+        assert_eq!(
+            observed.latency_percentile(0.95),
+            Some(Duration::from_millis(9_500))
+        );
+
+        let task = TaskDescriptor::new(1_000).requires_thinking();
+        let ranked = recommender.recommend(&task.clone().prioritize(Priority::Latency));
+        assert_eq!(
+            models(&ranked)[..2],
+            ["claude-sonnet-4-5", "claude-haiku-4-5"]
+        );
+
+        // Observed spend is half the list price, so the estimate is halved
+        let ranked = recommender.recommend(&task);
+        let haiku_rec = ranked.iter().find(|r| r.model == haiku).unwrap();
+        assert!((haiku_rec.expected_cost_usd.unwrap() - 0.003).abs() < 1e-9);
+        assert!(haiku_rec.reasons[1].contains("calibrated x0.50 by 100 observed calls"));
+        assert!(haiku_rec.reasons[2].starts_with("p50 latency 5.0s, p95 9.5s"));
+    }
+
+    #[test]
+    fn test_latency_window_is_bounded() {
+        let mut observations = ModelObservations::default();
+        for latency_ms in 0..(LATENCY_WINDOW as u64 + 10) {
+            observations.push_latency(latency_ms);
+        }
+        assert_eq!(observations.latencies_ms.len(), LATENCY_WINDOW);
+        assert_eq!(observations.latencies_ms.front(), Some(&10));
+    }
+
+    #[test]
+    fn test_observe_report_requires_model_grouping() {
+        let mut recommender = ModelRecommendation::default();
+        let mut report = UsageReport {
+            grouping: ReportGrouping::Session,
+            first_day: None,
+            last_day: None,
+            rows: Vec::new(),
+        };
+        assert!(recommender.observe_report(&report).is_err());
+
+        report.grouping = ReportGrouping::Model;
+        report.rows.push(crate::cc::token_tracker::UsageReportRow {
+            key: "claude-opus-4-5".to_string(),
+            requests: 4,
+            input_tokens: 1_000,
+            cache_read_tokens: 9_000,
+            output_tokens: 500,
+            cost_usd: 0.02,
+            ..Default::default()
+        });
+        recommender.observe_report(&report).unwrap();
+
+        let observed = recommender
+            .observations(&ModelId::new("claude-opus-4-5"))
+            .unwrap();
+        assert_eq!(observed.calls, 4);
+        assert_eq!(observed.input_tokens, 10_000);
+        assert!(observed.latencies_ms.is_empty());
+    }
+
+    #[test]
+    fn test_calibration_round_trip_merges() {
+        let mut source = ModelRecommendation::default()
+            .with_candidate(ModelProfile::new("custom-model").with_context_window(50_000));
+        source.observe(
+            &ModelId::new("custom-model"),
+            &metrics(800, 2_000, 500, 0.01),
+        );
+
+        let json = source.export_calibration().to_json().unwrap();
+        let calibration = Calibration::from_json(&json).unwrap();
+
+        let mut fleet = ModelRecommendation::default();
+        fleet.observe(
+            &ModelId::new("custom-model"),
+            &metrics(1_200, 2_000, 500, 0.01),
+        );
+        fleet.import_calibration(calibration);
+
+        assert!(
+            fleet
+                .candidates()
+                .iter()
+                .any(|c| c.model.as_str() == "custom-model")
+        );
+        let observed = fleet.observations(&ModelId::new("custom-model")).unwrap();
+        assert_eq!(observed.calls, 2);
+        assert_eq!(observed.latencies_ms, VecDeque::from(vec![1_200, 800]));
+
+        // Unpriced, so the cost comes from observed spend per token
+        let ranked = fleet.recommend(&TaskDescriptor::new(4_000));
+        let custom = ranked
+            .iter()
+            .find(|r| r.model.as_str() == "custom-model")
+            .unwrap();
+        assert!((custom.expected_cost_usd.unwrap() - 0.02).abs() < 1e-9);
+        assert_eq!(
+            fleet.fallback_chain(&TaskDescriptor::new(4_000)).len(),
+            ranked.len()
+        );
+    }
 }
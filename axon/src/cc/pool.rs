@@ -0,0 +1,418 @@
+//! Bounded-parallelism request pool for one-shot queries
+//!
+//! Every [`query`](crate::cc::query) call spawns its own Claude CLI process, so
+//! firing many of them at once can exhaust the machine. [`QueryPool`] puts a
+//! semaphore in front of `query`: at most `max_concurrent` requests run at a
+//! time and the rest wait in FIFO order.
+//!
+//! Two timeouts apply, measured separately:
+//! - `max_queue_wait` bounds the time a request may wait for a free slot and
+//!   fails with [`ClientError::QueueTimeout`] without ever starting the CLI
+//! - `request_timeout` bounds the request itself and starts when it leaves
+//!   the queue, so queueing never eats into it
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use axon::cc::pool::{PoolRequest, QueryPool};
+//! use std::time::Duration;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let pool = QueryPool::builder()
+//!         .max_concurrent(4)
+//!         .max_queue_wait(Duration::from_secs(60))
+//!         .request_timeout(Duration::from_secs(300))
+//!         .build();
+//!
+//!     let requests = (0..50)
+//!         .map(|i| PoolRequest::new(format!("Summarize chunk {i}")))
+//!         .collect();
+//!
+//!     // Results are returned in the same order as the requests
+//!     for result in pool.query_batch(requests).await {
+//!         println!("{} messages", result.map(|m| m.len()).unwrap_or(0));
+//!     }
+//!     println!("{:?}", pool.stats());
+//! }
+//! ```
+
+use super::{
+    Result,
+    error::{ClientError, Error, TransportError},
+    messages::Message,
+    options::ClaudeCodeOptions,
+    query::{QueryInput, query},
+};
+use futures::TryStreamExt;
+use futures::future::join_all;
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use tokio::time::timeout;
+use tracing::{debug, warn};
+
+/// Default number of requests allowed to run at once
+pub const DEFAULT_MAX_CONCURRENT: usize = 4;
+
+/// Configuration for a [`QueryPool`]
+#[derive(Debug, Clone)]
+pub struct PoolConfig {
+    /// Maximum number of requests running at once
+    pub max_concurrent: usize,
+    /// Maximum time a request may wait for a free slot (None = wait forever)
+    pub max_queue_wait: Option<Duration>,
+    /// Maximum time a request may run once started (None = no limit)
+    pub request_timeout: Option<Duration>,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent: DEFAULT_MAX_CONCURRENT,
+            max_queue_wait: None,
+            request_timeout: None,
+        }
+    }
+}
+
+/// Builder for [`QueryPool`]
+#[derive(Debug, Default)]
+pub struct QueryPoolBuilder {
+    config: PoolConfig,
+}
+
+impl QueryPoolBuilder {
+    /// Set the maximum number of requests running at once (at least 1)
+    pub fn max_concurrent(mut self, max_concurrent: usize) -> Self {
+        self.config.max_concurrent = max_concurrent.max(1);
+        self
+    }
+
+    /// Fail requests that wait longer than `wait` for a free slot
+    pub fn max_queue_wait(mut self, wait: Duration) -> Self {
+        self.config.max_queue_wait = Some(wait);
+        self
+    }
+
+    /// Fail requests that run longer than `timeout` once started
+    pub fn request_timeout(mut self, timeout: Duration) -> Self {
+        self.config.request_timeout = Some(timeout);
+        self
+    }
+
+    /// Build the pool
+    pub fn build(self) -> QueryPool {
+        QueryPool::new(self.config)
+    }
+}
+
+/// Snapshot of a pool's load
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolStats {
+    /// Requests waiting for a free slot
+    pub queued: usize,
+    /// Requests currently running
+    pub in_flight: usize,
+    /// Concurrency limit of the pool
+    pub max_concurrent: usize,
+    /// Requests that ran to completion, successfully or not
+    pub completed: u64,
+    /// Requests rejected after exceeding the maximum queue wait
+    pub queue_timeouts: u64,
+}
+
+/// A single request for [`QueryPool::query_batch`]
+pub struct PoolRequest {
+    /// Prompt to send
+    pub prompt: QueryInput,
+    /// Options for this request (None = defaults)
+    pub options: Option<ClaudeCodeOptions>,
+}
+
+impl PoolRequest {
+    /// Create a request with default options
+    pub fn new(prompt: impl Into<QueryInput>) -> Self {
+        Self {
+            prompt: prompt.into(),
+            options: None,
+        }
+    }
+
+    /// Set the options for this request
+    pub fn with_options(mut self, options: ClaudeCodeOptions) -> Self {
+        self.options = Some(options);
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    queued: AtomicUsize,
+    in_flight: AtomicUsize,
+    completed: AtomicU64,
+    queue_timeouts: AtomicU64,
+}
+
+/// Decrements a gauge when dropped, so cancelled futures keep counts right
+struct GaugeGuard<'a>(&'a AtomicUsize);
+
+impl<'a> GaugeGuard<'a> {
+    fn enter(gauge: &'a AtomicUsize) -> Self {
+        gauge.fetch_add(1, Ordering::Relaxed);
+        Self(gauge)
+    }
+}
+
+impl Drop for GaugeGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Semaphore-bounded pool of one-shot queries
+///
+/// Cloning is cheap and clones share the same slots and statistics.
+#[derive(Debug, Clone)]
+pub struct QueryPool {
+    config: Arc<PoolConfig>,
+    semaphore: Arc<Semaphore>,
+    counters: Arc<Counters>,
+}
+
+impl Default for QueryPool {
+    fn default() -> Self {
+        Self::new(PoolConfig::default())
+    }
+}
+
+impl QueryPool {
+    /// Create a pool from a configuration
+    pub fn new(mut config: PoolConfig) -> Self {
+        config.max_concurrent = config.max_concurrent.max(1);
+        Self {
+            semaphore: Arc::new(Semaphore::new(config.max_concurrent)),
+            config: Arc::new(config),
+            counters: Arc::new(Counters::default()),
+        }
+    }
+
+    /// Create a builder for a pool
+    pub fn builder() -> QueryPoolBuilder {
+        QueryPoolBuilder::default()
+    }
+
+    /// The pool configuration
+    pub fn config(&self) -> &PoolConfig {
+        &self.config
+    }
+
+    /// Current queue depth, in-flight count and totals
+    pub fn stats(&self) -> PoolStats {
+        PoolStats {
+            queued: self.counters.queued.load(Ordering::Relaxed),
+            in_flight: self.counters.in_flight.load(Ordering::Relaxed),
+            max_concurrent: self.config.max_concurrent,
+            completed: self.counters.completed.load(Ordering::Relaxed),
+            queue_timeouts: self.counters.queue_timeouts.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Run a one-shot query once a slot is free and collect all its messages
+    pub async fn query(
+        &self,
+        prompt: impl Into<QueryInput>,
+        options: Option<ClaudeCodeOptions>,
+    ) -> Result<Vec<Message>> {
+        let prompt = prompt.into();
+        self.run(move || async move { query(prompt, options).await?.try_collect().await })
+            .await
+    }
+
+    /// Run many queries with bounded parallelism
+    ///
+    /// The output has one entry per request, in input order, regardless of
+    /// the order in which requests finish.
+    pub async fn query_batch(&self, requests: Vec<PoolRequest>) -> Vec<Result<Vec<Message>>> {
+        let tasks = requests
+            .into_iter()
+            .map(|request| {
+                move || async move {
+                    query(request.prompt, request.options)
+                        .await?
+                        .try_collect()
+                        .await
+                }
+            })
+            .collect();
+        self.run_batch(tasks).await
+    }
+
+    /// Run an arbitrary task under the pool's limits
+    ///
+    /// The task is started only once a slot is free; `request_timeout`
+    /// applies from that point on.
+    pub async fn run<F, Fut, T>(&self, task: F) -> Result<T>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let enqueued = Instant::now();
+        let permit = {
+            let _queued = GaugeGuard::enter(&self.counters.queued);
+            let acquire = self.semaphore.acquire();
+            let acquired = match self.config.max_queue_wait {
+                Some(wait) => match timeout(wait, acquire).await {
+                    Ok(acquired) => acquired,
+                    Err(_) => {
+                        self.counters.queue_timeouts.fetch_add(1, Ordering::Relaxed);
+                        warn!(
+                            "Request rejected after waiting {:?} for a pool slot",
+                            enqueued.elapsed()
+                        );
+                        return Err(Error::Client(ClientError::QueueTimeout {
+                            waited: enqueued.elapsed(),
+                            max_concurrent: self.config.max_concurrent,
+                        }));
+                    }
+                },
+                None => acquire.await,
+            };
+            acquired.map_err(|_| Error::Client(ClientError::Other("query pool closed".into())))?
+        };
+
+        debug!("Pool slot acquired after {:?}", enqueued.elapsed());
+        let result = {
+            let _in_flight = GaugeGuard::enter(&self.counters.in_flight);
+            match self.config.request_timeout {
+                Some(limit) => match timeout(limit, task()).await {
+                    Ok(result) => result,
+                    Err(_) => Err(Error::Transport(TransportError::Timeout {
+                        duration: limit,
+                    })),
+                },
+                None => task().await,
+            }
+        };
+        drop(permit);
+
+        self.counters.completed.fetch_add(1, Ordering::Relaxed);
+        result
+    }
+
+    /// Run many tasks with bounded parallelism, preserving input order
+    pub async fn run_batch<F, Fut, T>(&self, tasks: Vec<F>) -> Vec<Result<T>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        join_all(tasks.into_iter().map(|task| self.run(task))).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::time::sleep;
+
+    fn pool(max_concurrent: usize) -> QueryPool {
+        QueryPool::builder().max_concurrent(max_concurrent).build()
+    }
+
+    #[tokio::test]
+    async fn test_batch_bounds_parallelism_and_preserves_order() {
+        let pool = pool(3);
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let tasks: Vec<_> = (0..12u64)
+            .map(|i| {
+                let pool = pool.clone();
+                let peak = peak.clone();
+                move || async move {
+                    peak.fetch_max(pool.stats().in_flight, Ordering::SeqCst);
+                    // Later requests finish first
+                    sleep(Duration::from_millis(40 - i * 3)).await;
+                    Ok(i)
+                }
+            })
+            .collect();
+
+        let results = pool.run_batch(tasks).await;
+        let values: Vec<u64> = results.into_iter().map(|r| r.unwrap()).collect();
+
+        assert_eq!(values, (0..12).collect::<Vec<_>>());
+        assert!(peak.load(Ordering::SeqCst) <= 3);
+        let stats = pool.stats();
+        assert_eq!(stats.completed, 12);
+        assert_eq!((stats.queued, stats.in_flight), (0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_queue_wait_fails_fast() {
+        let pool = QueryPool::builder()
+            .max_concurrent(1)
+            .max_queue_wait(Duration::from_millis(20))
+            .build();
+
+        let slow = pool.run(|| async {
+            sleep(Duration::from_millis(200)).await;
+            Ok(())
+        });
+        let queued = async {
+            sleep(Duration::from_millis(5)).await;
+            pool.run(|| async { Ok(()) }).await
+        };
+        let (slow, queued) = tokio::join!(slow, queued);
+
+        assert!(slow.is_ok());
+        let err = queued.unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Client(ClientError::QueueTimeout {
+                max_concurrent: 1,
+                ..
+            })
+        ));
+        assert!(err.is_recoverable());
+        assert_eq!(pool.stats().queue_timeouts, 1);
+    }
+
+    #[tokio::test]
+    async fn test_request_timeout_starts_at_send_time() {
+        let pool = QueryPool::builder()
+            .max_concurrent(1)
+            .request_timeout(Duration::from_millis(150))
+            .build();
+
+        // Each task runs for 80ms; the second waits ~80ms in the queue, which
+        // would exceed the timeout if it were measured from enqueue time.
+        let task = || async {
+            sleep(Duration::from_millis(80)).await;
+            Ok(())
+        };
+        let results = pool.run_batch(vec![task, task]).await;
+        assert!(results.iter().all(|r| r.is_ok()));
+
+        let result = pool
+            .run(|| async {
+                sleep(Duration::from_millis(300)).await;
+                Ok(())
+            })
+            .await;
+        assert!(matches!(
+            result,
+            Err(Error::Transport(TransportError::Timeout { .. }))
+        ));
+    }
+
+    #[test]
+    fn test_max_concurrent_is_at_least_one() {
+        assert_eq!(pool(0).stats().max_concurrent, 1);
+        assert_eq!(
+            QueryPool::default().config().max_concurrent,
+            DEFAULT_MAX_CONCURRENT
+        );
+    }
+}
@@ -11,6 +11,8 @@
 //! - **Process Lifecycle Management**: Register, track, and gracefully terminate processes
 //! - **Cross-Platform**: Works on Unix and Windows with platform-specific process control
 //! - **Status Checking**: Check if processes are running and clean up finished processes
+//! - **Orphan Recovery**: Persist process records so a restarted supervisor can adopt or reap
+//!   Claude processes it spawned before
 //!
 //! # Examples
 //!
@@ -50,8 +52,12 @@
 //! }
 //! ```
 
+pub mod orphans;
+pub mod records;
 pub mod registry;
 
+pub use orphans::{OrphanPolicy, OrphanReport};
+pub use records::ProcessRecord;
 pub use registry::{ProcessHandle, ProcessInfo, ProcessRegistry, ProcessType};
 
 #[cfg(test)]
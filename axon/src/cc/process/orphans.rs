@@ -0,0 +1,352 @@
+//! Discovery of Claude processes left behind by a previous supervisor.
+//!
+//! When a supervisor restarts, the Claude CLI processes it spawned keep
+//! running but are no longer tracked. [`ProcessRegistry::discover_orphans`]
+//! scans the persisted [`ProcessRecord`](super::ProcessRecord)s and either
+//! adopts those processes back into the registry or reaps them, depending on
+//! the configured [`OrphanPolicy`].
+
+use super::records::{self, ProcessRecord};
+use super::registry::{ProcessHandle, ProcessInfo, ProcessRegistry, ProcessType};
+use crate::cc::binary::find_claude_binary;
+use crate::cc::core::SessionId;
+use crate::cc::result::Result;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// What to do with orphaned Claude processes found on startup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OrphanPolicy {
+    /// Re-attach orphans to the registry so they can be queried and killed
+    #[default]
+    Adopt,
+    /// Terminate orphans, force killing any still running after `grace_period`
+    Reap {
+        /// How long to wait after the termination signal
+        grace_period: Duration,
+    },
+}
+
+/// Outcome of a [`ProcessRegistry::discover_orphans`] scan.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OrphanReport {
+    /// Sessions adopted into the registry
+    pub adopted: Vec<SessionId>,
+    /// PIDs of orphans that were terminated
+    pub reaped: Vec<u32>,
+    /// Records removed because the process was gone, the PID was reused by
+    /// something other than Claude, or the record was unreadable
+    pub stale_removed: usize,
+}
+
+impl OrphanReport {
+    /// Number of live orphans found (adopted or reaped).
+    pub fn orphan_count(&self) -> usize {
+        self.adopted.len() + self.reaped.len()
+    }
+}
+
+impl ProcessRegistry {
+    /// Find Claude processes spawned by a previous supervisor and adopt or reap them.
+    ///
+    /// Scans the records in the runtime directory. Records whose process is
+    /// gone, or whose PID now belongs to another process, are deleted: a
+    /// process only counts as the recorded one if it started when the record
+    /// says and runs the Claude CLI binary (see
+    /// [`with_claude_binary`](Self::with_claude_binary)). Records owned by
+    /// another running supervisor are left alone. The remaining processes are
+    /// handled according to the registry's [`OrphanPolicy`]; adopted
+    /// processes are tracked by PID from then on.
+    ///
+    /// Does nothing on a registry without a runtime directory.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use crate::cc::process::{OrphanPolicy, ProcessRegistry};
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let registry = ProcessRegistry::with_runtime_dir(ProcessRegistry::default_runtime_dir())?
+    ///     .with_orphan_policy(OrphanPolicy::Reap {
+    ///         grace_period: Duration::from_secs(5),
+    ///     });
+    ///
+    /// let report = registry.discover_orphans().await?;
+    /// println!("Reaped {} orphaned processes", report.reaped.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn discover_orphans(&self) -> Result<OrphanReport> {
+        let mut report = OrphanReport::default();
+        let Some(store) = &self.records else {
+            return Ok(report);
+        };
+
+        let (found, corrupt) = store.load_all()?;
+        for path in corrupt {
+            store.remove_path(&path);
+            report.stale_removed += 1;
+        }
+
+        let binary_name = self.claude_binary_name();
+        let own_pid = std::process::id();
+        let tracked: Vec<u32> = self
+            .list_active()
+            .iter()
+            .filter_map(|session_id| self.get(session_id))
+            .map(|handle| handle.pid)
+            .collect();
+
+        for record in found {
+            if tracked.contains(&record.pid) {
+                continue;
+            }
+
+            if !records::pid_is_alive(record.pid)
+                || !records::is_recorded_claude_process(&record, &binary_name)
+            {
+                store.remove(record.pid);
+                report.stale_removed += 1;
+                continue;
+            }
+
+            // Still owned by another live supervisor - not an orphan
+            if record.owner_pid != own_pid && records::pid_is_alive(record.owner_pid) {
+                continue;
+            }
+
+            match self.orphan_policy {
+                OrphanPolicy::Adopt => {
+                    let handle = self.adopted_handle(&record).await?;
+                    self.insert_handle(record.session_id.clone(), handle)?;
+                    tracing::info!(
+                        "Adopted orphaned Claude process {} ({})",
+                        record.pid,
+                        record.session_id
+                    );
+                    report.adopted.push(record.session_id);
+                }
+                OrphanPolicy::Reap { grace_period } => {
+                    let handle = self.adopted_handle(&record).await?;
+                    if let Err(e) = self.kill_graceful(&handle, grace_period).await {
+                        tracing::warn!(
+                            "Failed to reap orphaned Claude process {}: {}",
+                            record.pid,
+                            e
+                        );
+                        continue;
+                    }
+                    store.remove(record.pid);
+                    tracing::info!(
+                        "Reaped orphaned Claude process {} ({})",
+                        record.pid,
+                        record.session_id
+                    );
+                    report.reaped.push(record.pid);
+                }
+            }
+        }
+
+        if let Ok(mut metrics) = self.orphan_metrics.write() {
+            metrics.record_discovery(
+                report.adopted.len(),
+                report.reaped.len(),
+                report.stale_removed,
+            );
+        }
+
+        Ok(report)
+    }
+
+    /// File name of the Claude CLI binary orphans must be running.
+    fn claude_binary_name(&self) -> String {
+        self.claude_binary
+            .clone()
+            .or_else(|| find_claude_binary().ok().map(PathBuf::from))
+            .and_then(|path| path.file_name().map(|name| name.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| "claude".to_string())
+    }
+
+    async fn adopted_handle(&self, record: &ProcessRecord) -> Result<ProcessHandle> {
+        let info = ProcessInfo {
+            run_id: self.generate_run_id().await?,
+            process_type: ProcessType::ClaudeSession {
+                session_id: record.session_id.to_string(),
+            },
+            pid: record.pid,
+            started_at: record.started_at,
+            project_path: record.cwd.to_string_lossy().into_owned(),
+            task: String::new(),
+            model: String::new(),
+        };
+
+        Ok(ProcessHandle::adopt(
+            record.session_id.clone(),
+            record.pid,
+            info,
+        ))
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use std::time::SystemTime;
+
+    /// Spawn a detached long-running process running a binary named
+    /// `claude`, as a previous supervisor would have left behind.
+    ///
+    /// The shell backgrounds it and exits, so it is not our child and the
+    /// registry has to manage it purely by PID.
+    fn spawn_fake_claude(dir: &Path) -> u32 {
+        let binary = dir.join("claude");
+        std::fs::copy("/bin/sleep", &binary).expect("Failed to copy sleep");
+        let output = std::process::Command::new("sh")
+            .args(["-c", "\"$0\" 30 >/dev/null 2>&1 & echo $!"])
+            .arg(&binary)
+            .output()
+            .expect("Failed to spawn fake claude");
+        String::from_utf8_lossy(&output.stdout)
+            .trim()
+            .parse()
+            .unwrap()
+    }
+
+    /// Persist a record as if a previous supervisor had spawned `pid`.
+    fn write_orphan_record(runtime_dir: &Path, pid: u32, session: &str) {
+        let record = ProcessRecord {
+            pid,
+            session_id: SessionId::new(session),
+            started_at: SystemTime::now(),
+            cwd: "/tmp/project".into(),
+            // Almost certainly not a live process
+            owner_pid: i32::MAX as u32,
+        };
+        std::fs::write(
+            runtime_dir.join(format!("{}.json", pid)),
+            serde_json::to_vec(&record).unwrap(),
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_stale_records_are_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let runtime_dir = dir.path().join("processes");
+        let registry = ProcessRegistry::with_runtime_dir(&runtime_dir)
+            .unwrap()
+            .with_claude_binary(dir.path().join("claude"));
+
+        // Dead pid
+        let mut dead = std::process::Command::new("true").spawn().unwrap();
+        let dead_pid = dead.id();
+        dead.wait().unwrap();
+        write_orphan_record(&runtime_dir, dead_pid, "dead");
+
+        // Live pid that is not Claude (pid reuse)
+        let mut other = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .unwrap();
+        write_orphan_record(&runtime_dir, other.id(), "reused");
+
+        let report = registry.discover_orphans().await.unwrap();
+        assert_eq!(report.stale_removed, 2);
+        assert_eq!(report.orphan_count(), 0);
+        assert!(registry.list_active().is_empty());
+        assert_eq!(std::fs::read_dir(&runtime_dir).unwrap().count(), 0);
+
+        // The unrelated process must not have been touched
+        assert!(other.try_wait().unwrap().is_none());
+        other.kill().unwrap();
+        other.wait().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_adopt_orphan_then_kill_all() {
+        let dir = tempfile::tempdir().unwrap();
+        let runtime_dir = dir.path().join("processes");
+        let registry = ProcessRegistry::with_runtime_dir(&runtime_dir)
+            .unwrap()
+            .with_claude_binary(dir.path().join("claude"));
+        let pid = spawn_fake_claude(dir.path());
+        write_orphan_record(&runtime_dir, pid, "orphan");
+
+        let report = registry.discover_orphans().await.unwrap();
+        assert_eq!(report.adopted, vec![SessionId::new("orphan")]);
+
+        let handle = registry.get(&SessionId::new("orphan")).expect("adopted");
+        assert!(handle.adopted);
+        assert!(handle.is_running().await.unwrap());
+        assert!(
+            registry
+                .get_claude_session_by_id(&SessionId::new("orphan"))
+                .is_some()
+        );
+
+        let metrics = registry.orphan_metrics();
+        assert_eq!(metrics.adopted, 1);
+        assert_eq!(metrics.discovered, 1);
+
+        let stopped = registry.kill_all(Duration::from_secs(2)).await.unwrap();
+        assert_eq!(stopped, 1);
+        assert!(registry.list_active().is_empty());
+        assert_eq!(std::fs::read_dir(&runtime_dir).unwrap().count(), 0);
+        assert!(!records::pid_is_alive(pid));
+    }
+
+    #[tokio::test]
+    async fn test_reap_orphan() {
+        let dir = tempfile::tempdir().unwrap();
+        let runtime_dir = dir.path().join("processes");
+        let registry = ProcessRegistry::with_runtime_dir(&runtime_dir)
+            .unwrap()
+            .with_claude_binary(dir.path().join("claude"))
+            .with_orphan_policy(OrphanPolicy::Reap {
+                grace_period: Duration::from_secs(2),
+            });
+        let pid = spawn_fake_claude(dir.path());
+        write_orphan_record(&runtime_dir, pid, "orphan");
+
+        let report = registry.discover_orphans().await.unwrap();
+
+        assert_eq!(report.reaped, vec![pid]);
+        assert!(registry.list_active().is_empty());
+        assert!(!records::pid_is_alive(pid));
+        assert_eq!(registry.orphan_metrics().reaped, 1);
+        assert_eq!(std::fs::read_dir(&runtime_dir).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_registered_processes_are_persisted() {
+        let dir = tempfile::tempdir().unwrap();
+        let runtime_dir = dir.path().join("processes");
+        let registry = ProcessRegistry::with_runtime_dir(&runtime_dir)
+            .unwrap()
+            .with_claude_binary(dir.path().join("claude"));
+
+        let child = tokio::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .unwrap();
+        let session_id = SessionId::new("persisted");
+        let handle = registry.register(session_id.clone(), child).unwrap();
+
+        let record: ProcessRecord = serde_json::from_slice(
+            &std::fs::read(runtime_dir.join(format!("{}.json", handle.pid))).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(record.session_id, session_id);
+        assert_eq!(record.owner_pid, std::process::id());
+
+        // Our own processes are never treated as orphans
+        let report = registry.discover_orphans().await.unwrap();
+        assert_eq!(report, OrphanReport::default());
+
+        registry.kill(&session_id, false).await.unwrap();
+        assert!(!runtime_dir.join(format!("{}.json", handle.pid)).exists());
+    }
+}
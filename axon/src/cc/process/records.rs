@@ -0,0 +1,429 @@
+//! Persisted process records for surviving supervisor restarts.
+//!
+//! Each process registered with a persistent [`ProcessRegistry`](super::ProcessRegistry)
+//! gets a small JSON record in the runtime directory, named after its PID.
+//! The records outlive the supervisor, which is what lets a restarted instance
+//! find the Claude processes it spawned before.
+
+use super::registry::ProcessHandle;
+use crate::cc::core::SessionId;
+use crate::cc::result::Result;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// On-disk record of a process spawned by this SDK.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcessRecord {
+    /// Operating system process ID
+    pub pid: u32,
+    /// Session the process belongs to
+    pub session_id: SessionId,
+    /// When the process was started
+    pub started_at: SystemTime,
+    /// Working directory the process was started in
+    pub cwd: PathBuf,
+    /// PID of the supervisor that owns the process
+    pub owner_pid: u32,
+}
+
+impl ProcessRecord {
+    /// Build a record for a registered handle, owned by the current process.
+    ///
+    /// `started_at` is the start time the operating system reports for the
+    /// PID when it can be read, so a later scan can tell the process apart
+    /// from one that reused its PID.
+    pub fn from_handle(handle: &ProcessHandle) -> Self {
+        let cwd = match &handle.info {
+            Some(info) if !info.project_path.is_empty() => PathBuf::from(&info.project_path),
+            _ => std::env::current_dir().unwrap_or_default(),
+        };
+
+        Self {
+            pid: handle.pid,
+            session_id: handle.session_id.clone(),
+            started_at: process_start_time(handle.pid).unwrap_or(handle.started_at),
+            cwd,
+            owner_pid: std::process::id(),
+        }
+    }
+}
+
+/// Directory of process records, one JSON file per PID.
+#[derive(Debug, Clone)]
+pub(super) struct RecordStore {
+    dir: PathBuf,
+}
+
+impl RecordStore {
+    /// Open (creating if needed) a record directory.
+    pub(super) fn open(dir: PathBuf) -> Result<Self> {
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    fn path_for(&self, pid: u32) -> PathBuf {
+        self.dir.join(format!("{}.json", pid))
+    }
+
+    /// Write a record, replacing any previous record for the same PID.
+    pub(super) fn write(&self, record: &ProcessRecord) -> Result<()> {
+        let json = serde_json::to_vec_pretty(record)?;
+        // Write then rename so a crash never leaves a half-written record
+        let tmp = self.dir.join(format!(".{}.json.tmp", record.pid));
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, self.path_for(record.pid))?;
+        Ok(())
+    }
+
+    /// Remove the record for a PID, if any.
+    pub(super) fn remove(&self, pid: u32) {
+        let _ = std::fs::remove_file(self.path_for(pid));
+    }
+
+    /// Load every record in the directory.
+    ///
+    /// Returns the parsed records along with the paths of files that could
+    /// not be parsed, so the caller can clean them up.
+    pub(super) fn load_all(&self) -> Result<(Vec<ProcessRecord>, Vec<PathBuf>)> {
+        let mut records = Vec::new();
+        let mut corrupt = Vec::new();
+
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+
+            match std::fs::read(&path)
+                .ok()
+                .and_then(|bytes| serde_json::from_slice::<ProcessRecord>(&bytes).ok())
+            {
+                Some(record) => records.push(record),
+                None => corrupt.push(path),
+            }
+        }
+
+        Ok((records, corrupt))
+    }
+
+    /// Remove a record file by path.
+    pub(super) fn remove_path(&self, path: &Path) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+/// Check whether a process with the given PID is alive.
+pub(super) fn pid_is_alive(pid: u32) -> bool {
+    #[cfg(unix)]
+    {
+        use nix::errno::Errno;
+        use nix::sys::signal::kill;
+        use nix::unistd::Pid;
+
+        // No signal performs the existence check without delivering
+        // anything. EPERM still means the process exists.
+        let exists = matches!(
+            kill(Pid::from_raw(pid as i32), None),
+            Ok(()) | Err(Errno::EPERM)
+        );
+        exists && !is_zombie(pid)
+    }
+
+    #[cfg(windows)]
+    {
+        process_command_line(pid).is_some()
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = pid;
+        false
+    }
+}
+
+/// Whether a process has exited but not been reaped by its parent yet.
+#[cfg(unix)]
+fn is_zombie(pid: u32) -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        // The state is the first field after the parenthesised command name
+        std::fs::read_to_string(format!("/proc/{}/stat", pid))
+            .ok()
+            .and_then(|stat| {
+                let (_, rest) = stat.rsplit_once(')')?;
+                rest.trim_start().chars().next()
+            })
+            .is_some_and(|state| state == 'Z')
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = pid;
+        false
+    }
+}
+
+/// Read the arguments of a running process, starting with the program.
+pub(super) fn process_command_line(pid: u32) -> Option<Vec<String>> {
+    #[cfg(target_os = "linux")]
+    {
+        let raw = std::fs::read(format!("/proc/{}/cmdline", pid)).ok()?;
+        let args = raw
+            .split(|b| *b == 0)
+            .filter(|part| !part.is_empty())
+            .map(|part| String::from_utf8_lossy(part).into_owned())
+            .collect::<Vec<_>>();
+        (!args.is_empty()).then_some(args)
+    }
+
+    #[cfg(all(unix, not(target_os = "linux")))]
+    {
+        let output = std::process::Command::new("ps")
+            .args(["-p", &pid.to_string(), "-o", "command="])
+            .output()
+            .ok()?;
+        let args = String::from_utf8_lossy(&output.stdout)
+            .split_whitespace()
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        (output.status.success() && !args.is_empty()).then_some(args)
+    }
+
+    #[cfg(windows)]
+    {
+        let output = std::process::Command::new("tasklist")
+            .args(["/FI", &format!("PID eq {}", pid), "/FO", "CSV", "/NH"])
+            .output()
+            .ok()?;
+        let line = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        // tasklist prints an informational message instead of a CSV row when
+        // nothing matches; the first column is the image name
+        let image = line.strip_prefix('"')?.split('"').next()?.to_string();
+        Some(vec![image])
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = pid;
+        None
+    }
+}
+
+/// Start time of a running process as reported by the operating system.
+///
+/// Returns `None` where the start time cannot be read; callers must then
+/// treat the process as unverified.
+pub(super) fn process_start_time(pid: u32) -> Option<SystemTime> {
+    #[cfg(target_os = "linux")]
+    {
+        // starttime is field 22 of /proc/<pid>/stat, in clock ticks since
+        // boot; fields are counted from the state, which is field 3
+        let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+        let (_, rest) = stat.rsplit_once(')')?;
+        let ticks: u64 = rest.split_whitespace().nth(22 - 3)?.parse().ok()?;
+
+        let boot_secs: u64 = std::fs::read_to_string("/proc/stat")
+            .ok()?
+            .lines()
+            .find_map(|line| line.strip_prefix("btime "))?
+            .trim()
+            .parse()
+            .ok()?;
+
+        // SAFETY: sysconf has no preconditions
+        let ticks_per_sec = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+        if ticks_per_sec <= 0 {
+            return None;
+        }
+        let ticks_per_sec = ticks_per_sec as u64;
+
+        let since_boot = std::time::Duration::from_secs(ticks / ticks_per_sec)
+            + std::time::Duration::from_nanos((ticks % ticks_per_sec) * 1_000_000_000 / ticks_per_sec);
+        Some(SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(boot_secs) + since_boot)
+    }
+
+    #[cfg(all(unix, not(target_os = "linux")))]
+    {
+        // Elapsed time is printed as [[dd-]hh:]mm:ss
+        let output = std::process::Command::new("ps")
+            .args(["-p", &pid.to_string(), "-o", "etime="])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let etime = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        let (days, clock) = match etime.split_once('-') {
+            Some((days, clock)) => (days.parse::<u64>().ok()?, clock),
+            None => (0, etime.as_str()),
+        };
+        let mut secs = 0u64;
+        for part in clock.split(':') {
+            secs = secs * 60 + part.parse::<u64>().ok()?;
+        }
+        SystemTime::now().checked_sub(std::time::Duration::from_secs(days * 86_400 + secs))
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = pid;
+        None
+    }
+}
+
+/// How far a process start time may drift from its record and still be
+/// considered the same process. Covers the one-second resolution of the
+/// boot time and the gap between spawning and recording a process.
+const START_TIME_TOLERANCE: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Check whether a PID still belongs to the Claude process a record was
+/// written for.
+///
+/// Guards against PID reuse: the process must have been started when the
+/// record says, and its program must be the Claude CLI binary named
+/// `binary_name`. A CLI installed as a script runs under its interpreter,
+/// so the script path is checked as well.
+pub(super) fn is_recorded_claude_process(record: &ProcessRecord, binary_name: &str) -> bool {
+    let Some(started) = process_start_time(record.pid) else {
+        return false;
+    };
+    let drift = started
+        .duration_since(record.started_at)
+        .or_else(|_| record.started_at.duration_since(started))
+        .unwrap_or_default();
+    if drift > START_TIME_TOLERANCE {
+        return false;
+    }
+
+    process_command_line(record.pid).is_some_and(|args| runs_binary(&args, binary_name))
+}
+
+/// Whether a command line runs `binary_name` directly or through a script
+/// interpreter.
+fn runs_binary(args: &[String], binary_name: &str) -> bool {
+    const INTERPRETERS: &[&str] = &["node", "bun", "deno"];
+
+    let basename = |arg: &String| {
+        Path::new(arg)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    };
+    let matches = |arg: &String| {
+        let name = basename(arg);
+        name == binary_name || name.strip_suffix(".exe") == Some(binary_name)
+    };
+
+    match args {
+        [program, ..] if matches(program) => true,
+        [program, script, ..] => {
+            let program = basename(program);
+            INTERPRETERS
+                .iter()
+                .any(|interpreter| program == *interpreter || program.strip_suffix(".exe") == Some(interpreter))
+                && matches(script)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(pid: u32) -> ProcessRecord {
+        ProcessRecord {
+            pid,
+            session_id: SessionId::new(format!("session-{}", pid)),
+            started_at: SystemTime::now(),
+            cwd: PathBuf::from("/tmp/project"),
+            owner_pid: 1,
+        }
+    }
+
+    #[test]
+    fn test_record_store_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = RecordStore::open(dir.path().join("processes")).unwrap();
+
+        store.write(&record(100)).unwrap();
+        store.write(&record(200)).unwrap();
+        std::fs::write(dir.path().join("processes/300.json"), "not json").unwrap();
+
+        let (mut records, corrupt) = store.load_all().unwrap();
+        records.sort_by_key(|r| r.pid);
+        let pids: Vec<_> = records.iter().map(|r| r.pid).collect();
+        assert_eq!(pids, vec![100, 200]);
+        assert_eq!(records[0].session_id, SessionId::new("session-100"));
+        assert_eq!(records[0].cwd, PathBuf::from("/tmp/project"));
+        assert_eq!(corrupt.len(), 1);
+
+        store.remove(100);
+        let (records, _) = store.load_all().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].pid, 200);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_pid_is_alive() {
+        assert!(pid_is_alive(std::process::id()));
+        assert!(!pid_is_alive(u32::MAX / 2));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_is_recorded_claude_process_checks_start_time_and_binary() {
+        let dir = tempfile::tempdir().unwrap();
+        let binary = dir.path().join("claude");
+        std::fs::copy("/bin/sleep", &binary).unwrap();
+        let mut claude = std::process::Command::new(&binary).arg("5").spawn().unwrap();
+        // An unrelated program that merely mentions claude in its arguments
+        let mut other = std::process::Command::new("sh")
+            .args(["-c", "sleep 5", "claude"])
+            .spawn()
+            .unwrap();
+
+        // The command line can read back empty for a moment after spawn
+        for pid in [claude.id(), other.id()] {
+            let read = (0..50).any(|_| {
+                let ready = process_command_line(pid).is_some_and(|args| args.len() > 1);
+                if !ready {
+                    std::thread::sleep(std::time::Duration::from_millis(10));
+                }
+                ready
+            });
+            assert!(read);
+        }
+
+        let mut claude_record = record(claude.id());
+        claude_record.started_at = process_start_time(claude.id()).unwrap();
+        assert!(is_recorded_claude_process(&claude_record, "claude"));
+        assert!(!is_recorded_claude_process(&claude_record, "claude-code"));
+
+        // A record from long before this process started describes a
+        // different process that used the same PID
+        claude_record.started_at -= std::time::Duration::from_secs(3600);
+        assert!(!is_recorded_claude_process(&claude_record, "claude"));
+
+        let mut other_record = record(other.id());
+        other_record.started_at = process_start_time(other.id()).unwrap();
+        assert!(!is_recorded_claude_process(&other_record, "claude"));
+
+        for child in [&mut claude, &mut other] {
+            child.kill().unwrap();
+            child.wait().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_runs_binary() {
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert!(runs_binary(&args(&["/usr/local/bin/claude", "--print"]), "claude"));
+        assert!(runs_binary(&args(&["node", "/home/u/.npm/bin/claude", "-p"]), "claude"));
+        assert!(!runs_binary(&args(&["vim", "/home/u/.claude/settings.json"]), "claude"));
+        assert!(!runs_binary(&args(&["sh", "claude"]), "claude"));
+    }
+}
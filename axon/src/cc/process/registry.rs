@@ -3,9 +3,13 @@
 //! This module provides the core `ProcessRegistry` and `ProcessHandle` types for
 //! tracking and managing multiple concurrent processes with thread-safe access.
 
+use super::orphans::OrphanPolicy;
+use super::records::{self, ProcessRecord, RecordStore};
 use crate::cc::core::SessionId;
+use crate::cc::metrics::OrphanMetrics;
 use crate::cc::result::Result;
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::{Arc, RwLock};
 use std::time::{Duration, SystemTime};
 use tokio::process::Child;
@@ -136,6 +140,12 @@ pub struct ProcessHandle {
 
     /// Rich process information
     pub info: Option<ProcessInfo>,
+
+    /// Whether this process was adopted from a previous supervisor.
+    ///
+    /// Adopted processes have no child handle, so their status is checked
+    /// and signalled by PID.
+    pub adopted: bool,
 }
 
 impl ProcessHandle {
@@ -172,6 +182,7 @@ impl ProcessHandle {
             output_buffer: Arc::new(Mutex::new(String::new())),
             child: Arc::new(Mutex::new(Some(child))),
             info: None,
+            adopted: false,
         }
     }
 
@@ -223,6 +234,7 @@ impl ProcessHandle {
             output_buffer: Arc::new(Mutex::new(String::new())),
             child: Arc::new(Mutex::new(Some(child))),
             info: Some(info),
+            adopted: false,
         }
     }
 
@@ -243,6 +255,18 @@ impl ProcessHandle {
             output_buffer: Arc::new(Mutex::new(String::new())),
             child: Arc::new(Mutex::new(None)),
             info: Some(info),
+            adopted: false,
+        }
+    }
+
+    /// Create a handle for a process adopted from a previous supervisor.
+    ///
+    /// The process was not spawned by this instance, so there is no child
+    /// handle; liveness and termination go through the PID instead.
+    pub fn adopt(session_id: SessionId, pid: u32, info: ProcessInfo) -> Self {
+        Self {
+            adopted: true,
+            ..Self::without_child(session_id, pid, info)
         }
     }
 
@@ -390,6 +414,9 @@ impl ProcessHandle {
                     Ok(false)
                 }
             }
+        } else if self.adopted {
+            // Adopted processes are checked by PID
+            Ok(records::pid_is_alive(self.pid))
         } else {
             // No child handle means process is not running
             Ok(false)
@@ -425,6 +452,9 @@ impl ProcessHandle {
     }
 }
 
+/// How long a graceful kill waits before escalating to a forced kill.
+const GRACEFUL_KILL_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// Thread-safe registry for tracking multiple concurrent processes.
 ///
 /// `ProcessRegistry` provides a centralized location for managing process lifecycle,
@@ -438,6 +468,13 @@ impl ProcessHandle {
 /// - Write operations (register, unregister) take exclusive locks
 /// - All methods are safe to call concurrently
 ///
+/// # Persistence
+///
+/// A registry created with [`ProcessRegistry::with_runtime_dir`] also writes a
+/// [`ProcessRecord`] for every registered process, so a restarted supervisor
+/// can find processes it spawned before and adopt or reap them with
+/// [`ProcessRegistry::discover_orphans`].
+///
 /// # Examples
 ///
 /// ```rust,no_run
@@ -478,6 +515,14 @@ pub struct ProcessRegistry {
     processes: Arc<RwLock<HashMap<SessionId, ProcessHandle>>>,
     /// Auto-incrementing ID for generating unique run IDs
     next_run_id: Arc<Mutex<i64>>,
+    /// Persisted process records, if a runtime directory was configured
+    pub(super) records: Option<RecordStore>,
+    /// What to do with orphans found by `discover_orphans`
+    pub(super) orphan_policy: OrphanPolicy,
+    /// Cumulative orphan discovery counters
+    pub(super) orphan_metrics: Arc<RwLock<OrphanMetrics>>,
+    /// Claude CLI binary orphans must be running; discovered when unset
+    pub(super) claude_binary: Option<PathBuf>,
 }
 
 impl ProcessRegistry {
@@ -494,7 +539,86 @@ impl ProcessRegistry {
         Self {
             processes: Arc::new(RwLock::new(HashMap::new())),
             next_run_id: Arc::new(Mutex::new(1)),
+            records: None,
+            orphan_policy: OrphanPolicy::default(),
+            orphan_metrics: Arc::new(RwLock::new(OrphanMetrics::default())),
+            claude_binary: None,
+        }
+    }
+
+    /// Create a registry that persists process records to a runtime directory.
+    ///
+    /// Every registered process gets a record (pid, session ID, start time,
+    /// working directory) in `dir`, removed again when the process is
+    /// unregistered. Records left behind by a previous supervisor are picked
+    /// up by [`discover_orphans`](Self::discover_orphans).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use crate::cc::process::ProcessRegistry;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let registry = ProcessRegistry::with_runtime_dir(ProcessRegistry::default_runtime_dir())?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_runtime_dir(dir: impl Into<PathBuf>) -> Result<Self> {
+        Ok(Self {
+            records: Some(RecordStore::open(dir.into())?),
+            ..Self::new()
+        })
+    }
+
+    /// Default runtime directory for process records.
+    ///
+    /// Uses the platform runtime directory (e.g. `$XDG_RUNTIME_DIR`) when
+    /// available, falling back to the system temp directory.
+    pub fn default_runtime_dir() -> PathBuf {
+        dirs::runtime_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("axon-cc")
+            .join("processes")
+    }
+
+    /// Set the policy applied to orphans found by `discover_orphans`.
+    pub fn with_orphan_policy(mut self, policy: OrphanPolicy) -> Self {
+        self.orphan_policy = policy;
+        self
+    }
+
+    /// Set the Claude CLI binary that orphans must be running.
+    ///
+    /// Defaults to the binary found by
+    /// [`find_claude_binary`](crate::cc::binary::find_claude_binary).
+    pub fn with_claude_binary(mut self, path: impl Into<PathBuf>) -> Self {
+        self.claude_binary = Some(path.into());
+        self
+    }
+
+    /// Get a snapshot of the orphan discovery counters.
+    pub fn orphan_metrics(&self) -> OrphanMetrics {
+        self.orphan_metrics
+            .read()
+            .map(|metrics| metrics.clone())
+            .unwrap_or_default()
+    }
+
+    /// Insert a handle and persist its record if a runtime directory is configured.
+    pub(super) fn insert_handle(&self, session_id: SessionId, handle: ProcessHandle) -> Result<()> {
+        let mut processes = self.processes
+            .write()
+            .map_err(|e| crate::Error::protocol(format!("Failed to lock registry: {}", e)))?;
+
+        if let Some(records) = &self.records
+            && let Err(e) = records.write(&ProcessRecord::from_handle(&handle))
+        {
+            tracing::warn!("Failed to persist process record for pid {}: {}", handle.pid, e);
         }
+
+        processes.insert(session_id, handle);
+
+        Ok(())
     }
 
     /// Generate a unique run ID for a process.
@@ -539,11 +663,7 @@ impl ProcessRegistry {
 
         let handle = ProcessHandle::new(session_id.clone(), child, pid);
 
-        let mut processes = self.processes
-            .write()
-            .map_err(|e| crate::Error::protocol(format!("Failed to lock registry: {}", e)))?;
-
-        processes.insert(session_id, handle.clone());
+        self.insert_handle(session_id, handle.clone())?;
 
         Ok(handle)
     }
@@ -619,11 +739,7 @@ impl ProcessRegistry {
 
         let handle = ProcessHandle::with_info(session_id.clone(), child, pid, info);
 
-        let mut processes = self.processes
-            .write()
-            .map_err(|e| crate::Error::protocol(format!("Failed to lock registry: {}", e)))?;
-
-        processes.insert(session_id, handle.clone());
+        self.insert_handle(session_id, handle.clone())?;
 
         Ok(handle)
     }
@@ -689,11 +805,7 @@ impl ProcessRegistry {
 
         let handle = ProcessHandle::without_child(session_id.clone(), pid, info);
 
-        let mut processes = self.processes
-            .write()
-            .map_err(|e| crate::Error::protocol(format!("Failed to lock registry: {}", e)))?;
-
-        processes.insert(session_id, handle.clone());
+        self.insert_handle(session_id, handle.clone())?;
 
         Ok(handle)
     }
@@ -752,11 +864,7 @@ impl ProcessRegistry {
 
         let handle = ProcessHandle::without_child(session_id.clone(), pid, info);
 
-        let mut processes = self.processes
-            .write()
-            .map_err(|e| crate::Error::protocol(format!("Failed to lock registry: {}", e)))?;
-
-        processes.insert(session_id, handle.clone());
+        self.insert_handle(session_id, handle.clone())?;
 
         Ok(handle)
     }
@@ -937,7 +1045,13 @@ impl ProcessRegistry {
     /// ```
     pub fn unregister(&self, session_id: &SessionId) -> Option<ProcessHandle> {
         let mut processes = self.processes.write().ok()?;
-        processes.remove(session_id)
+        let handle = processes.remove(session_id)?;
+
+        if let Some(records) = &self.records {
+            records.remove(handle.pid);
+        }
+
+        Some(handle)
     }
 
     /// List all active session IDs.
@@ -1039,7 +1153,7 @@ impl ProcessRegistry {
         })?;
 
         if graceful {
-            self.kill_graceful(&handle).await?;
+            self.kill_graceful(&handle, GRACEFUL_KILL_TIMEOUT).await?;
         } else {
             self.kill_forced(&handle).await?;
         }
@@ -1050,8 +1164,63 @@ impl ProcessRegistry {
         Ok(())
     }
 
-    /// Gracefully kill a process (SIGTERM → SIGKILL escalation).
-    async fn kill_graceful(&self, handle: &ProcessHandle) -> Result<()> {
+    /// Kill every registered process for a clean shutdown.
+    ///
+    /// Sends a termination signal to all processes at once, waits up to
+    /// `grace_period` for them to exit, then force kills the rest. Every
+    /// process is unregistered (and its record removed) even if killing it
+    /// failed. Returns the number of processes that were stopped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use crate::cc::process::ProcessRegistry;
+    /// # use std::time::Duration;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let registry = ProcessRegistry::new();
+    ///
+    /// // ... on shutdown ...
+    /// let stopped = registry.kill_all(Duration::from_secs(10)).await?;
+    /// println!("Stopped {} processes", stopped);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn kill_all(&self, grace_period: Duration) -> Result<usize> {
+        let handles: Vec<ProcessHandle> = self
+            .list_active()
+            .iter()
+            .filter_map(|session_id| self.get(session_id))
+            .collect();
+
+        let results = futures::future::join_all(
+            handles.iter().map(|handle| self.kill_graceful(handle, grace_period)),
+        )
+        .await;
+
+        let mut stopped = 0;
+        let mut failures = Vec::new();
+        for (handle, result) in handles.iter().zip(results) {
+            self.unregister(&handle.session_id);
+            match result {
+                Ok(()) => stopped += 1,
+                Err(e) => failures.push(format!("{} (pid {}): {}", handle.session_id, handle.pid, e)),
+            }
+        }
+
+        if !failures.is_empty() {
+            return Err(crate::Error::protocol(format!(
+                "Failed to kill {} process(es): {}",
+                failures.len(),
+                failures.join("; ")
+            )));
+        }
+
+        Ok(stopped)
+    }
+
+    /// Gracefully kill a process (SIGTERM → SIGKILL escalation after `timeout`).
+    pub(super) async fn kill_graceful(&self, handle: &ProcessHandle, timeout: Duration) -> Result<()> {
         // Try to terminate gracefully first
         {
             let mut child_guard = handle.child.lock().await;
@@ -1062,14 +1231,19 @@ impl ProcessRegistry {
                     // If SIGTERM fails, fall back to start_kill
                     let _ = child.start_kill();
                 }
+            } else if handle.adopted {
+                // Not our child - signal by PID
+                if !records::pid_is_alive(handle.pid) {
+                    return Ok(());
+                }
+                self.send_term_signal(handle.pid)?;
             } else {
                 // Process already gone
                 return Ok(());
             }
         }
 
-        // Wait up to 5 seconds for graceful exit
-        let timeout = Duration::from_secs(5);
+        // Wait for graceful exit
         let start = std::time::Instant::now();
 
         while start.elapsed() < timeout {
@@ -1100,7 +1274,7 @@ impl ProcessRegistry {
                     *child_guard = None;
                     return Ok(());
                 }
-            } else {
+            } else if !handle.adopted || !records::pid_is_alive(handle.pid) {
                 return Ok(());
             }
         }
@@ -0,0 +1,161 @@
+//! Environment variable interpolation in setting values.
+//!
+//! String values may reference the environment as `${NAME}`, or
+//! `${NAME:-default}` to fall back when the variable is unset or empty.
+//! `$${` produces a literal `${`.
+
+use serde_json::Value;
+
+/// Interpolate `text` against the process environment.
+///
+/// Returns the interpolated text and the names of referenced variables that
+/// are unset and have no default. Those references are left in place.
+///
+/// # Examples
+///
+/// ```
+/// use crate::cc::settings::interpolate;
+///
+/// let (text, missing) = interpolate("key=${SURELY_UNSET_VARIABLE:-none}");
+/// assert_eq!(text, "key=none");
+/// assert!(missing.is_empty());
+/// ```
+pub fn interpolate(text: &str) -> (String, Vec<String>) {
+    interpolate_with(text, |name| std::env::var(name).ok())
+}
+
+/// Interpolate `text`, resolving variables with `lookup`.
+pub fn interpolate_with(
+    text: &str,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> (String, Vec<String>) {
+    let mut out = String::with_capacity(text.len());
+    let mut missing = Vec::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find('$') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+
+        if let Some(escaped) = after.strip_prefix("${") {
+            out.push_str("${");
+            rest = escaped;
+            continue;
+        }
+
+        if let Some(body) = after.strip_prefix('{')
+            && let Some(end) = body.find('}')
+        {
+            let expr = &body[..end];
+            let (name, default) = match expr.split_once(":-") {
+                Some((name, default)) => (name, Some(default)),
+                None => (expr, None),
+            };
+
+            if is_variable_name(name) {
+                // `:-` also covers variables that are set but empty
+                let value = lookup(name).filter(|value| default.is_none() || !value.is_empty());
+                match (value, default) {
+                    (Some(value), _) => out.push_str(&value),
+                    (None, Some(default)) => out.push_str(default),
+                    (None, None) => {
+                        missing.push(name.to_string());
+                        out.push_str("${");
+                        out.push_str(expr);
+                        out.push('}');
+                    }
+                }
+                rest = &body[end + 1..];
+                continue;
+            }
+        }
+
+        out.push('$');
+        rest = after;
+    }
+
+    out.push_str(rest);
+    (out, missing)
+}
+
+/// Interpolate every string nested inside a JSON value.
+pub(super) fn interpolate_value(
+    value: &Value,
+    lookup: &impl Fn(&str) -> Option<String>,
+    missing: &mut Vec<String>,
+) -> Value {
+    match value {
+        Value::String(text) => {
+            let (text, unresolved) = interpolate_with(text, lookup);
+            missing.extend(unresolved);
+            Value::String(text)
+        }
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| interpolate_value(item, lookup, missing))
+                .collect(),
+        ),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, item)| (key.clone(), interpolate_value(item, lookup, missing)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+fn is_variable_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "API_KEY" => Some("sk-123".to_string()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_interpolate_variables_and_defaults() {
+        let (text, missing) = interpolate_with("Bearer ${API_KEY}", lookup);
+        assert_eq!(text, "Bearer sk-123");
+        assert!(missing.is_empty());
+
+        let (text, _) = interpolate_with("${UNSET:-fallback}/${EMPTY:-dflt}/${EMPTY}", lookup);
+        assert_eq!(text, "fallback/dflt/");
+    }
+
+    #[test]
+    fn test_interpolate_reports_missing_and_leaves_literals() {
+        let (text, missing) =
+            interpolate_with("${UNSET} costs $5 ${not a var} $${API_KEY}", lookup);
+        assert_eq!(text, "${UNSET} costs $5 ${not a var} ${API_KEY}");
+        assert_eq!(missing, vec!["UNSET".to_string()]);
+    }
+
+    #[test]
+    fn test_interpolate_value_recurses() {
+        let value = serde_json::json!({
+            "env": { "KEY": "${API_KEY}" },
+            "args": ["--key", "${UNSET}"],
+            "count": 3
+        });
+        let mut missing = Vec::new();
+        let resolved = interpolate_value(&value, &lookup, &mut missing);
+
+        assert_eq!(resolved["env"]["KEY"], "sk-123");
+        assert_eq!(resolved["args"][1], "${UNSET}");
+        assert_eq!(resolved["count"], 3);
+        assert_eq!(missing, vec!["UNSET".to_string()]);
+    }
+}
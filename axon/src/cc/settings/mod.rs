@@ -26,10 +26,39 @@
 //! # Ok(())
 //! # }
 //! ```
+//!
+//! # Profiles
+//!
+//! Named profiles layer `settings.<profile>.json` and `settings.local.json`
+//! over `settings.json`. String values may reference the environment as
+//! `${VAR}` or `${VAR:-default}`.
+//!
+//! ```no_run
+//! use crate::cc::settings::Settings;
+//!
+//! # async fn example() -> cc_sdk::Result<()> {
+//! let settings = Settings::load_profile("prod").await?;
+//!
+//! // Every problem at once: unknown keys warn, type mismatches error
+//! print!("{}", settings.validate());
+//!
+//! // Which file a permission rule actually came from
+//! if let Some(explanation) = settings.explain("permissions.deny") {
+//!     println!("{}", explanation);
+//! }
+//! # Ok(())
+//! # }
+//! ```
 
+mod interpolate;
 mod loader;
+mod profile;
 mod types;
+mod validate;
 
 // Re-export public API
+pub use interpolate::{interpolate, interpolate_with};
 pub use loader::{load_settings, save_settings, load_default_settings};
+pub use profile::{Explanation, LayerKind, Settings, SettingsLayer};
 pub use types::{ClaudeSettings, HookConfig, SettingsScope};
+pub use validate::{Severity, SettingsProblem, ValidationReport};
@@ -0,0 +1,669 @@
+//! Named settings profiles layered over a base settings file.
+//!
+//! A profile is selected at runtime and loaded from a settings directory
+//! (`.claude` by default) as three layers, lowest precedence first:
+//!
+//! 1. `settings.json` - the base settings
+//! 2. `settings.<profile>.json` - the profile, e.g. `settings.prod.json`
+//! 3. `settings.local.json` - local overrides, usually not checked in
+//!
+//! Objects are merged key by key across layers; any other value from a
+//! higher layer replaces the one below it. Files may contain `//` and
+//! `/* */` comments.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::{Map, Value};
+
+use crate::cc::error::{Error, SettingsError};
+use crate::cc::result::Result;
+
+use super::interpolate::interpolate_value;
+use super::types::ClaudeSettings;
+use super::validate::{ValidationReport, validate_file};
+
+/// Which layer of a profile a settings file provides.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LayerKind {
+    /// `settings.json`
+    Base,
+    /// `settings.<name>.json`
+    Profile(String),
+    /// `settings.local.json`
+    Local,
+}
+
+impl LayerKind {
+    fn file_name(&self) -> String {
+        match self {
+            LayerKind::Base => "settings.json".to_string(),
+            LayerKind::Profile(name) => format!("settings.{}.json", name),
+            LayerKind::Local => "settings.local.json".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for LayerKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayerKind::Base => write!(f, "base"),
+            LayerKind::Profile(name) => write!(f, "profile '{}'", name),
+            LayerKind::Local => write!(f, "local"),
+        }
+    }
+}
+
+/// One settings file of a loaded profile.
+#[derive(Debug, Clone)]
+pub struct SettingsLayer {
+    kind: LayerKind,
+    path: PathBuf,
+    /// Parsed content, before interpolation. An empty object if the file is missing.
+    value: Value,
+    exists: bool,
+    modified: bool,
+}
+
+impl SettingsLayer {
+    pub fn kind(&self) -> &LayerKind {
+        &self.kind
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The file content as written, with `${VAR}` references unexpanded.
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+
+    /// Whether the file existed when loaded or has been saved since.
+    pub fn exists(&self) -> bool {
+        self.exists
+    }
+}
+
+/// Where the effective value of a setting came from.
+///
+/// Returned by [`Settings::explain`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Explanation {
+    /// The setting that was explained
+    pub key: String,
+    /// Effective value, with `${VAR}` references expanded
+    pub value: Value,
+    /// Value as written in the file that supplied it
+    pub raw: Value,
+    /// Layer that supplied the value
+    pub layer: LayerKind,
+    /// File that supplied the value
+    pub file: PathBuf,
+    /// Lower layers that also set the key and were overridden, highest first.
+    /// For objects these still contribute any keys the winning layer leaves unset.
+    pub overridden: Vec<(LayerKind, PathBuf)>,
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} = {} (from {} layer, {})",
+            self.key,
+            self.value,
+            self.layer,
+            self.file.display()
+        )?;
+        if self.raw != self.value {
+            write!(f, "\n  written as {}", self.raw)?;
+        }
+        for (layer, file) in &self.overridden {
+            write!(f, "\n  overrides {} layer, {}", layer, file.display())?;
+        }
+        Ok(())
+    }
+}
+
+/// Settings for a named profile, keeping track of which file supplied what.
+///
+/// # Examples
+///
+/// ```no_run
+/// use crate::cc::settings::Settings;
+///
+/// # async fn example() -> cc_sdk::Result<()> {
+/// let settings = Settings::load_profile("staging").await?;
+///
+/// let report = settings.validate();
+/// for problem in &report.problems {
+///     eprintln!("{}", problem);
+/// }
+///
+/// if let Some(explanation) = settings.explain("permissions.allow") {
+///     println!("{}", explanation);
+/// }
+///
+/// let effective = settings.to_claude_settings()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Settings {
+    profile: String,
+    /// Lowest precedence first
+    layers: Vec<SettingsLayer>,
+}
+
+impl Settings {
+    /// Directory profiles are loaded from by [`load_profile`](Self::load_profile).
+    pub const DEFAULT_DIR: &'static str = ".claude";
+
+    /// Load a profile from `.claude` in the current directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the profile name is invalid or a settings file
+    /// exists but cannot be read or parsed. Missing files are treated as empty.
+    pub async fn load_profile(name: &str) -> Result<Self> {
+        Self::load_profile_in(Self::DEFAULT_DIR, name).await
+    }
+
+    /// Load a profile from the given settings directory.
+    pub async fn load_profile_in(dir: impl Into<PathBuf>, name: &str) -> Result<Self> {
+        validate_profile_name(name)?;
+
+        let dir = dir.into();
+        let profile = name.to_string();
+        let layers = tokio::task::spawn_blocking(move || -> Result<Vec<SettingsLayer>> {
+            [
+                LayerKind::Base,
+                LayerKind::Profile(profile),
+                LayerKind::Local,
+            ]
+            .into_iter()
+            .map(|kind| load_layer(&dir, kind))
+            .collect()
+        })
+        .await
+        .map_err(|e| Error::Protocol(format!("Task failed: {}", e)))??;
+
+        Ok(Self {
+            profile: name.to_string(),
+            layers,
+        })
+    }
+
+    pub fn profile(&self) -> &str {
+        &self.profile
+    }
+
+    /// Layers from lowest to highest precedence.
+    pub fn layers(&self) -> &[SettingsLayer] {
+        &self.layers
+    }
+
+    /// Effective value of a dotted key such as `permissions.defaultMode`,
+    /// with `${VAR}` references expanded.
+    pub fn get(&self, key: &str) -> Option<Value> {
+        let merged = self.merged();
+        let value = lookup(&merged, key)?;
+        Some(interpolate_value(value, &env_lookup, &mut Vec::new()))
+    }
+
+    /// Report which layer and file supplied the effective value of `key`.
+    ///
+    /// Returns `None` if no layer sets the key.
+    pub fn explain(&self, key: &str) -> Option<Explanation> {
+        let mut defining = self
+            .layers
+            .iter()
+            .rev()
+            .filter(|layer| lookup(&layer.value, key).is_some());
+
+        let winner = defining.next()?;
+        let overridden = defining
+            .map(|layer| (layer.kind.clone(), layer.path.clone()))
+            .collect();
+
+        let raw = lookup(&winner.value, key)?.clone();
+        Some(Explanation {
+            key: key.to_string(),
+            value: self.get(key)?,
+            raw,
+            layer: winner.kind.clone(),
+            file: winner.path.clone(),
+            overridden,
+        })
+    }
+
+    /// Check every layer against the settings schema.
+    ///
+    /// All problems are collected rather than stopping at the first one.
+    /// Unknown keys are warnings; type mismatches and references to unset
+    /// environment variables are errors.
+    pub fn validate(&self) -> ValidationReport {
+        let mut problems = Vec::new();
+        for layer in self.layers.iter().filter(|layer| layer.exists) {
+            validate_file(&layer.path, &layer.value, &env_lookup, &mut problems);
+        }
+        ValidationReport { problems }
+    }
+
+    /// Merge the layers into a [`ClaudeSettings`], expanding `${VAR}` references.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SettingsError::ValidationFailed`] listing every error if
+    /// [`validate`](Self::validate) finds any.
+    pub fn to_claude_settings(&self) -> Result<ClaudeSettings> {
+        let report = self.validate();
+        if !report.is_ok() {
+            return Err(Error::Settings(SettingsError::ValidationFailed {
+                problems: report.errors().map(ToString::to_string).collect(),
+            }));
+        }
+
+        let merged = interpolate_value(&self.merged(), &env_lookup, &mut Vec::new());
+        serde_json::from_value(merged).map_err(|e| {
+            Error::Settings(SettingsError::InvalidValue {
+                key: format!("profile '{}'", self.profile),
+                reason: e.to_string(),
+            })
+        })
+    }
+
+    /// Set a dotted key in one layer, creating intermediate objects as needed.
+    ///
+    /// The change is written by the next [`save`](Self::save).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key is empty or a parent of it is not an object.
+    pub fn set(&mut self, layer: &LayerKind, key: &str, value: Value) -> Result<()> {
+        let invalid = |reason: String| {
+            Error::Settings(SettingsError::InvalidValue {
+                key: key.to_string(),
+                reason,
+            })
+        };
+
+        let target = self
+            .layers
+            .iter_mut()
+            .find(|candidate| candidate.kind == *layer)
+            .ok_or_else(|| invalid(format!("profile '{}' has no {} layer", self.profile, layer)))?;
+
+        let mut parts: Vec<&str> = key.split('.').collect();
+        let last = parts
+            .pop()
+            .filter(|last| !last.is_empty())
+            .ok_or_else(|| invalid("empty key".to_string()))?;
+
+        let mut current = &mut target.value;
+        for part in parts {
+            current = current
+                .as_object_mut()
+                .ok_or_else(|| invalid(format!("parent of `{}` is not an object", part)))?
+                .entry(part)
+                .or_insert_with(|| Value::Object(Map::new()));
+        }
+        current
+            .as_object_mut()
+            .ok_or_else(|| invalid(format!("parent of `{}` is not an object", last)))?
+            .insert(last.to_string(), value);
+
+        target.modified = true;
+        Ok(())
+    }
+
+    /// Write layers changed by [`set`](Self::set).
+    ///
+    /// Files that were not changed are left untouched, so their comments and
+    /// formatting survive. Changed files are rewritten as plain JSON.
+    pub async fn save(&mut self) -> Result<()> {
+        let pending: Vec<(PathBuf, Value)> = self
+            .layers
+            .iter()
+            .filter(|layer| layer.modified)
+            .map(|layer| (layer.path.clone(), layer.value.clone()))
+            .collect();
+
+        tokio::task::spawn_blocking(move || -> Result<()> {
+            for (path, value) in pending {
+                write_layer(&path, &value)?;
+            }
+            Ok(())
+        })
+        .await
+        .map_err(|e| Error::Protocol(format!("Task failed: {}", e)))??;
+
+        for layer in self.layers.iter_mut().filter(|layer| layer.modified) {
+            layer.modified = false;
+            layer.exists = true;
+        }
+        Ok(())
+    }
+
+    /// All layers deep-merged, before interpolation.
+    fn merged(&self) -> Value {
+        let mut merged = Value::Object(Map::new());
+        for layer in &self.layers {
+            merge_into(&mut merged, &layer.value);
+        }
+        merged
+    }
+}
+
+fn env_lookup(name: &str) -> Option<String> {
+    std::env::var(name).ok()
+}
+
+fn validate_profile_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name != "local"
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+    if valid {
+        Ok(())
+    } else {
+        Err(Error::Settings(SettingsError::InvalidValue {
+            key: "profile".to_string(),
+            reason: format!(
+                "'{}' is not a valid profile name (use letters, digits, '-' and '_'; 'local' is reserved)",
+                name
+            ),
+        }))
+    }
+}
+
+fn load_layer(dir: &Path, kind: LayerKind) -> Result<SettingsLayer> {
+    let path = dir.join(kind.file_name());
+    let mut layer = SettingsLayer {
+        kind,
+        path,
+        value: Value::Object(Map::new()),
+        exists: false,
+        modified: false,
+    };
+    if !layer.path.exists() {
+        return Ok(layer);
+    }
+
+    let content = fs::read_to_string(&layer.path).map_err(|e| {
+        Error::Settings(SettingsError::ParseError {
+            path: layer.path.clone(),
+            reason: format!("Failed to read file: {}", e),
+            source: None,
+        })
+    })?;
+
+    layer.value = serde_json::from_str(&strip_comments(&content)).map_err(|e| {
+        Error::Settings(SettingsError::ParseError {
+            path: layer.path.clone(),
+            reason: format!("Invalid JSON: {}", e),
+            source: Some(e),
+        })
+    })?;
+    layer.exists = true;
+    Ok(layer)
+}
+
+fn write_layer(path: &Path, value: &Value) -> Result<()> {
+    let write_error = |reason: String| {
+        Error::Settings(SettingsError::WriteError {
+            path: path.to_path_buf(),
+            reason,
+        })
+    };
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| write_error(format!("Failed to create directory: {}", e)))?;
+    }
+    let content = serde_json::to_string_pretty(value)
+        .map_err(|e| write_error(format!("Failed to serialize: {}", e)))?;
+    fs::write(path, content + "\n").map_err(|e| write_error(format!("Failed to write file: {}", e)))
+}
+
+/// Look up a dotted key such as `permissions.allow`.
+fn lookup<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    key.split('.')
+        .try_fold(value, |current, part| current.get(part))
+}
+
+/// Merge `overlay` into `base`: objects key by key, anything else replaced.
+fn merge_into(base: &mut Value, overlay: &Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(key) {
+                    Some(existing) => merge_into(existing, value),
+                    None => {
+                        base.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay.clone(),
+    }
+}
+
+/// Blank out `//` and `/* */` comments outside strings.
+///
+/// Comments are replaced with spaces (newlines are kept) so that JSON parse
+/// errors still point at the right line and column.
+fn strip_comments(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match (c, chars.peek().copied()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => {
+                out.push(' ');
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        out.push('\n');
+                        break;
+                    }
+                    out.push(' ');
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                out.push_str("  ");
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    out.push(if c == '\n' { '\n' } else { ' ' });
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, content: &str) {
+        fs::write(dir.join(name), content).unwrap();
+    }
+
+    #[test]
+    fn test_strip_comments() {
+        let text = "{\n  // comment\n  \"url\": \"http://x/*y*/\", /* block\n */ \"n\": 1\n}";
+        let value: Value = serde_json::from_str(&strip_comments(text)).unwrap();
+        assert_eq!(value["url"], "http://x/*y*/");
+        assert_eq!(value["n"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_layers_merge_in_order_and_explain() {
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            dir.path(),
+            "settings.json",
+            r#"{"default_model": "base-model", "permissions": {"allow": ["Read"], "defaultMode": "default"}}"#,
+        );
+        write(
+            dir.path(),
+            "settings.prod.json",
+            r#"{"permissions": {"allow": ["Read", "Bash(git:*)"]}}"#,
+        );
+        write(
+            dir.path(),
+            "settings.local.json",
+            r#"{"default_model": "${AXON_TEST_UNSET_MODEL:-local-model}"}"#,
+        );
+
+        let settings = Settings::load_profile_in(dir.path(), "prod").await.unwrap();
+        assert_eq!(settings.profile(), "prod");
+
+        let allow = settings.explain("permissions.allow").unwrap();
+        assert_eq!(allow.layer, LayerKind::Profile("prod".to_string()));
+        assert_eq!(allow.file, dir.path().join("settings.prod.json"));
+        assert_eq!(
+            allow.overridden,
+            vec![(LayerKind::Base, dir.path().join("settings.json"))]
+        );
+        assert_eq!(allow.value, serde_json::json!(["Read", "Bash(git:*)"]));
+
+        // Sibling keys of a merged object keep coming from lower layers
+        assert_eq!(
+            settings.explain("permissions.defaultMode").unwrap().layer,
+            LayerKind::Base
+        );
+
+        let model = settings.explain("default_model").unwrap();
+        assert_eq!(model.layer, LayerKind::Local);
+        assert_eq!(model.value, "local-model");
+        assert_eq!(model.raw, "${AXON_TEST_UNSET_MODEL:-local-model}");
+
+        assert!(settings.explain("permissions.deny").is_none());
+
+        let effective = settings.to_claude_settings().unwrap();
+        assert_eq!(effective.default_model.as_deref(), Some("local-model"));
+        assert_eq!(
+            effective.additional["permissions"]["defaultMode"],
+            "default"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_validate_reports_every_problem() {
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            dir.path(),
+            "settings.json",
+            r#"{"default_model": 4, "colour": "blue", "permissions": {"allow": ["Read", 7], "alow": []}}"#,
+        );
+        write(
+            dir.path(),
+            "settings.dev.json",
+            r#"{"env": {"TOKEN": "${AXON_TEST_UNSET_TOKEN}"}}"#,
+        );
+
+        let settings = Settings::load_profile_in(dir.path(), "dev").await.unwrap();
+        let report = settings.validate();
+        let base = dir.path().join("settings.json");
+
+        let errors: Vec<_> = report
+            .errors()
+            .map(|p| (p.file.clone(), p.path.as_str()))
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                (base.clone(), "default_model"),
+                (base.clone(), "permissions.allow[1]"),
+                (dir.path().join("settings.dev.json"), "env.TOKEN"),
+            ]
+        );
+        let warnings: Vec<_> = report.warnings().map(|p| p.path.as_str()).collect();
+        assert_eq!(warnings, vec!["colour", "permissions.alow"]);
+        assert!(!report.is_ok());
+        assert!(
+            report
+                .to_string()
+                .contains("expected a string, found a number")
+        );
+
+        let err = settings.to_claude_settings().unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Settings(SettingsError::ValidationFailed { ref problems }) if problems.len() == 3
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_save_only_rewrites_modified_layers() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = "{\n  // Shared across profiles\n  \"default_model\": \"base-model\"\n}\n";
+        write(dir.path(), "settings.json", base);
+
+        let mut settings = Settings::load_profile_in(dir.path(), "staging")
+            .await
+            .unwrap();
+        settings
+            .set(
+                &LayerKind::Local,
+                "permissions.allow",
+                serde_json::json!(["Read"]),
+            )
+            .unwrap();
+        settings.save().await.unwrap();
+
+        assert_eq!(
+            fs::read_to_string(dir.path().join("settings.json")).unwrap(),
+            base
+        );
+        assert!(!dir.path().join("settings.staging.json").exists());
+
+        let reloaded = Settings::load_profile_in(dir.path(), "staging")
+            .await
+            .unwrap();
+        assert!(reloaded.layers()[2].exists());
+        assert_eq!(
+            reloaded.explain("permissions.allow").unwrap().layer,
+            LayerKind::Local
+        );
+        assert_eq!(reloaded.get("default_model").unwrap(), "base-model");
+    }
+
+    #[tokio::test]
+    async fn test_invalid_profile_names() {
+        for name in ["", "local", "../prod", "a.b"] {
+            assert!(
+                Settings::load_profile_in("/nonexistent", name)
+                    .await
+                    .is_err(),
+                "{}",
+                name
+            );
+        }
+    }
+}
@@ -0,0 +1,264 @@
+//! Schema validation for settings files.
+//!
+//! Validation never stops at the first problem: every file is checked in
+//! full and all problems are reported together. Unknown keys are warnings,
+//! since newer Claude Code releases add settings this SDK may not know about.
+//! Values of the wrong type and unresolved `${VAR}` references are errors.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use super::interpolate::interpolate_with;
+
+/// How serious a [`SettingsProblem`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The setting is ignored or may not do what was intended
+    Warning,
+    /// The settings cannot be used as written
+    Error,
+}
+
+/// A single problem found while validating settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SettingsProblem {
+    /// How serious the problem is
+    pub severity: Severity,
+    /// Settings file containing the problem
+    pub file: PathBuf,
+    /// Path of the offending value within the file, e.g. `permissions.allow[2]`
+    pub path: String,
+    /// What is wrong
+    pub message: String,
+}
+
+impl fmt::Display for SettingsProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        let path = if self.path.is_empty() {
+            "(root)"
+        } else {
+            &self.path
+        };
+        write!(
+            f,
+            "{}: {}: {}: {}",
+            severity,
+            self.file.display(),
+            path,
+            self.message
+        )
+    }
+}
+
+/// All problems found by [`Settings::validate`](super::Settings::validate).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// Problems in layer order, then file order
+    pub problems: Vec<SettingsProblem>,
+}
+
+impl ValidationReport {
+    /// True when there are no errors. Warnings are allowed.
+    pub fn is_ok(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &SettingsProblem> {
+        self.problems
+            .iter()
+            .filter(|problem| problem.severity == Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &SettingsProblem> {
+        self.problems
+            .iter()
+            .filter(|problem| problem.severity == Severity::Warning)
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for problem in &self.problems {
+            writeln!(f, "{}", problem)?;
+        }
+        Ok(())
+    }
+}
+
+/// Expected shape of a setting value.
+enum Shape {
+    String,
+    Bool,
+    Number,
+    /// Any JSON object, checked further when deserialized
+    Object,
+    StringArray,
+    /// Object whose values are all strings
+    StringMap,
+    /// Object with a fixed set of known keys
+    Fields(&'static [(&'static str, Shape)]),
+}
+
+const PERMISSIONS: &[(&str, Shape)] = &[
+    ("allow", Shape::StringArray),
+    ("deny", Shape::StringArray),
+    ("ask", Shape::StringArray),
+    ("defaultMode", Shape::String),
+    ("additionalDirectories", Shape::StringArray),
+    ("disableBypassPermissionsMode", Shape::String),
+];
+
+const SCHEMA: &[(&str, Shape)] = &[
+    ("$schema", Shape::String),
+    // Fields of `ClaudeSettings`
+    ("hooks", Shape::Object),
+    ("mcp_servers", Shape::Object),
+    ("default_model", Shape::String),
+    ("permission_mode", Shape::String),
+    ("prompts", Shape::StringMap),
+    ("env", Shape::StringMap),
+    // Claude Code settings kept in `ClaudeSettings::additional`
+    ("permissions", Shape::Fields(PERMISSIONS)),
+    ("model", Shape::String),
+    ("apiKeyHelper", Shape::String),
+    ("cleanupPeriodDays", Shape::Number),
+    ("includeCoAuthoredBy", Shape::Bool),
+    ("statusLine", Shape::Object),
+];
+
+/// Validate one settings file, appending every problem found.
+pub(super) fn validate_file(
+    file: &Path,
+    value: &Value,
+    lookup: &impl Fn(&str) -> Option<String>,
+    problems: &mut Vec<SettingsProblem>,
+) {
+    let mut checker = Checker { file, problems };
+    checker.check(value, &Shape::Fields(SCHEMA), String::new());
+    checker.check_references(value, lookup, String::new());
+}
+
+struct Checker<'a> {
+    file: &'a Path,
+    problems: &'a mut Vec<SettingsProblem>,
+}
+
+impl Checker<'_> {
+    fn report(&mut self, severity: Severity, path: &str, message: String) {
+        self.problems.push(SettingsProblem {
+            severity,
+            file: self.file.to_path_buf(),
+            path: path.to_string(),
+            message,
+        });
+    }
+
+    fn mismatch(&mut self, path: &str, expected: &str, found: &Value) {
+        self.report(
+            Severity::Error,
+            path,
+            format!("expected {}, found {}", expected, kind(found)),
+        );
+    }
+
+    fn check(&mut self, value: &Value, shape: &Shape, path: String) {
+        match shape {
+            Shape::String if !value.is_string() => self.mismatch(&path, "a string", value),
+            Shape::Bool if !value.is_boolean() => self.mismatch(&path, "a boolean", value),
+            Shape::Number if !value.is_number() => self.mismatch(&path, "a number", value),
+            Shape::Object if !value.is_object() => self.mismatch(&path, "an object", value),
+            Shape::StringArray => match value.as_array() {
+                Some(items) => {
+                    for (index, item) in items.iter().enumerate() {
+                        if !item.is_string() {
+                            self.mismatch(&format!("{}[{}]", path, index), "a string", item);
+                        }
+                    }
+                }
+                None => self.mismatch(&path, "an array of strings", value),
+            },
+            Shape::StringMap => match value.as_object() {
+                Some(map) => {
+                    for (key, item) in map {
+                        if !item.is_string() {
+                            self.mismatch(&join(&path, key), "a string", item);
+                        }
+                    }
+                }
+                None => self.mismatch(&path, "an object", value),
+            },
+            Shape::Fields(fields) => match value.as_object() {
+                Some(map) => {
+                    for (key, item) in map {
+                        match fields.iter().find(|(name, _)| name == key) {
+                            Some((_, shape)) => self.check(item, shape, join(&path, key)),
+                            None => self.report(
+                                Severity::Warning,
+                                &join(&path, key),
+                                format!("unknown key `{}`", key),
+                            ),
+                        }
+                    }
+                }
+                None => self.mismatch(&path, "an object", value),
+            },
+            _ => {}
+        }
+    }
+
+    /// Report `${VAR}` references to unset variables.
+    fn check_references(
+        &mut self,
+        value: &Value,
+        lookup: &impl Fn(&str) -> Option<String>,
+        path: String,
+    ) {
+        match value {
+            Value::String(text) => {
+                for name in interpolate_with(text, lookup).1 {
+                    self.report(
+                        Severity::Error,
+                        &path,
+                        format!("environment variable `{}` is not set", name),
+                    );
+                }
+            }
+            Value::Array(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    self.check_references(item, lookup, format!("{}[{}]", path, index));
+                }
+            }
+            Value::Object(map) => {
+                for (key, item) in map {
+                    self.check_references(item, lookup, join(&path, key));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
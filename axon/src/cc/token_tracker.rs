@@ -2,11 +2,27 @@
 //!
 //! This module provides utilities for monitoring token consumption and managing budgets
 //! to help control costs when using Claude Code.
+//!
+//! Usage recorded as [`UsageRecord`]s is also attributed to a session, a model and an
+//! optional label (set by [`AttributionHook`] when a tool runs), and can be broken down
+//! with [`TokenUsageTracker::report_by_session`], [`TokenUsageTracker::report_by_model`]
+//! and [`TokenUsageTracker::report_by_label`]. Attributed usage is kept as rolling daily
+//! aggregates, so long-running processes do not hold every record in memory.
 
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::ops::RangeBounds;
 use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 use tracing::warn;
 
+use crate::cc::core::{ModelId, SessionId};
+use crate::cc::hooks::{HookCallback, HookContext, HookInput, HookJSONOutput};
+
 /// Token usage statistics tracker
 #[derive(Debug, Clone, Default)]
 pub struct TokenUsageTracker {
@@ -18,6 +34,14 @@ pub struct TokenUsageTracker {
     pub total_cost_usd: f64,
     /// Number of sessions/queries completed
     pub session_count: usize,
+    /// Prices used to cost [`UsageRecord`]s and reports
+    pub prices: PriceTable,
+    /// Days of attributed usage to keep (None = keep everything)
+    pub retention_days: Option<u32>,
+    /// Attributed usage per day
+    daily: BTreeMap<NaiveDate, HashMap<Attribution, UsageTotals>>,
+    /// Label applied to a session's records that do not carry their own
+    active_labels: HashMap<SessionId, String>,
 }
 
 impl TokenUsageTracker {
@@ -63,6 +87,435 @@ impl TokenUsageTracker {
         self.total_output_tokens = 0;
         self.total_cost_usd = 0.0;
         self.session_count = 0;
+        self.daily.clear();
+    }
+
+    /// Use a custom price table
+    pub fn with_prices(mut self, prices: PriceTable) -> Self {
+        self.prices = prices;
+        self
+    }
+
+    /// Only keep attributed usage for the last `days` days
+    pub fn with_retention_days(mut self, days: u32) -> Self {
+        self.retention_days = Some(days);
+        self
+    }
+
+    /// Record attributed usage
+    ///
+    /// Updates the totals, costing the record with the price table, and adds it to
+    /// the daily aggregate. Records without a label take the session's active label.
+    pub fn record(&mut self, record: UsageRecord) {
+        let label = record
+            .label
+            .clone()
+            .or_else(|| self.active_labels.get(&record.session_id).cloned());
+        let totals = UsageTotals::from(&record);
+        let cost_usd = self.prices.cost(&record.model, &totals);
+        self.update(record.input_tokens, record.output_tokens, cost_usd);
+
+        let day = record.timestamp.date_naive();
+        let attribution = Attribution {
+            session_id: record.session_id,
+            model: record.model,
+            label,
+        };
+        self.daily
+            .entry(day)
+            .or_default()
+            .entry(attribution)
+            .or_default()
+            .add(&totals);
+
+        if let Some(days) = self.retention_days {
+            let cutoff = day - Duration::days(i64::from(days));
+            self.daily = self.daily.split_off(&cutoff);
+        }
+    }
+
+    /// Attribute the session's subsequent records to `label`
+    pub fn set_label(&mut self, session_id: SessionId, label: impl Into<String>) {
+        self.active_labels.insert(session_id, label.into());
+    }
+
+    /// Stop attributing the session's records to a label
+    pub fn clear_label(&mut self, session_id: &SessionId) {
+        self.active_labels.remove(session_id);
+    }
+
+    /// The label currently applied to a session's records
+    pub fn active_label(&self, session_id: &SessionId) -> Option<&str> {
+        self.active_labels.get(session_id).map(String::as_str)
+    }
+
+    /// Cost and token usage per session for the days in `range`
+    pub fn report_by_session(&self, range: impl RangeBounds<NaiveDate>) -> UsageReport {
+        self.report(range, ReportGrouping::Session)
+    }
+
+    /// Cost and token usage per model for the days in `range`
+    pub fn report_by_model(&self, range: impl RangeBounds<NaiveDate>) -> UsageReport {
+        self.report(range, ReportGrouping::Model)
+    }
+
+    /// Cost and token usage per attribution label for the days in `range`
+    ///
+    /// Usage without a label is reported under [`UNATTRIBUTED`].
+    pub fn report_by_label(&self, range: impl RangeBounds<NaiveDate>) -> UsageReport {
+        self.report(range, ReportGrouping::Label)
+    }
+
+    fn report(&self, range: impl RangeBounds<NaiveDate>, grouping: ReportGrouping) -> UsageReport {
+        let mut rows: BTreeMap<String, UsageReportRow> = BTreeMap::new();
+        let mut days = Vec::new();
+
+        for (day, buckets) in self.daily.range(range) {
+            days.push(*day);
+            for (attribution, totals) in buckets {
+                let key = match grouping {
+                    ReportGrouping::Session => attribution.session_id.to_string(),
+                    ReportGrouping::Model => attribution.model.to_string(),
+                    ReportGrouping::Label => attribution
+                        .label
+                        .clone()
+                        .unwrap_or_else(|| UNATTRIBUTED.to_string()),
+                };
+                let row = rows.entry(key.clone()).or_insert_with(|| UsageReportRow {
+                    key,
+                    ..Default::default()
+                });
+                row.requests += totals.requests;
+                row.input_tokens += totals.input_tokens;
+                row.output_tokens += totals.output_tokens;
+                row.cache_creation_tokens += totals.cache_creation_tokens;
+                row.cache_read_tokens += totals.cache_read_tokens;
+                row.cost_usd += self.prices.cost(&attribution.model, totals);
+            }
+        }
+
+        let mut rows: Vec<UsageReportRow> = rows.into_values().collect();
+        rows.sort_by(|a, b| {
+            b.cost_usd
+                .total_cmp(&a.cost_usd)
+                .then_with(|| a.key.cmp(&b.key))
+        });
+
+        UsageReport {
+            grouping,
+            first_day: days.first().copied(),
+            last_day: days.last().copied(),
+            rows,
+        }
+    }
+}
+
+/// Report key used for usage without an attribution label
+pub const UNATTRIBUTED: &str = "(unattributed)";
+
+/// A single model response's token usage, attributed to a session, model and label
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsageRecord {
+    /// When the usage happened
+    pub timestamp: DateTime<Utc>,
+    /// Session that generated the usage
+    pub session_id: SessionId,
+    /// Model that generated the usage
+    pub model: ModelId,
+    /// What triggered the generation, e.g. the tool whose result was being processed
+    pub label: Option<String>,
+    /// Uncached input tokens
+    pub input_tokens: u64,
+    /// Output tokens
+    pub output_tokens: u64,
+    /// Input tokens written to the prompt cache
+    pub cache_creation_tokens: u64,
+    /// Input tokens read from the prompt cache
+    pub cache_read_tokens: u64,
+}
+
+impl UsageRecord {
+    /// Create a record timestamped now
+    pub fn new(
+        session_id: SessionId,
+        model: ModelId,
+        input_tokens: u64,
+        output_tokens: u64,
+    ) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            session_id,
+            model,
+            label: None,
+            input_tokens,
+            output_tokens,
+            cache_creation_tokens: 0,
+            cache_read_tokens: 0,
+        }
+    }
+
+    /// Set prompt cache write and read token counts
+    pub fn with_cache(mut self, creation_tokens: u64, read_tokens: u64) -> Self {
+        self.cache_creation_tokens = creation_tokens;
+        self.cache_read_tokens = read_tokens;
+        self
+    }
+
+    /// Attribute the record to a label
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Set when the usage happened
+    pub fn at(mut self, timestamp: DateTime<Utc>) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+}
+
+/// Summed token counts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsageTotals {
+    /// Number of records summed
+    pub requests: u64,
+    /// Uncached input tokens
+    pub input_tokens: u64,
+    /// Output tokens
+    pub output_tokens: u64,
+    /// Input tokens written to the prompt cache
+    pub cache_creation_tokens: u64,
+    /// Input tokens read from the prompt cache
+    pub cache_read_tokens: u64,
+}
+
+impl UsageTotals {
+    fn add(&mut self, other: &UsageTotals) {
+        self.requests += other.requests;
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_creation_tokens += other.cache_creation_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+    }
+}
+
+impl From<&UsageRecord> for UsageTotals {
+    fn from(record: &UsageRecord) -> Self {
+        Self {
+            requests: 1,
+            input_tokens: record.input_tokens,
+            output_tokens: record.output_tokens,
+            cache_creation_tokens: record.cache_creation_tokens,
+            cache_read_tokens: record.cache_read_tokens,
+        }
+    }
+}
+
+/// Key of a daily aggregate bucket
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Attribution {
+    session_id: SessionId,
+    model: ModelId,
+    label: Option<String>,
+}
+
+/// Price of a model in USD per million tokens
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelPrice {
+    /// Uncached input tokens
+    pub input_per_mtok: f64,
+    /// Output tokens
+    pub output_per_mtok: f64,
+    /// Prompt cache writes
+    pub cache_write_per_mtok: f64,
+    /// Prompt cache reads
+    pub cache_read_per_mtok: f64,
+}
+
+impl ModelPrice {
+    /// Price with the usual cache multipliers (writes 1.25x, reads 0.1x input)
+    pub fn new(input_per_mtok: f64, output_per_mtok: f64) -> Self {
+        Self {
+            input_per_mtok,
+            output_per_mtok,
+            cache_write_per_mtok: input_per_mtok * 1.25,
+            cache_read_per_mtok: input_per_mtok * 0.1,
+        }
+    }
+
+    /// Cost of the given usage in USD
+    pub fn cost(&self, totals: &UsageTotals) -> f64 {
+        (totals.input_tokens as f64 * self.input_per_mtok
+            + totals.output_tokens as f64 * self.output_per_mtok
+            + totals.cache_creation_tokens as f64 * self.cache_write_per_mtok
+            + totals.cache_read_tokens as f64 * self.cache_read_per_mtok)
+            / 1_000_000.0
+    }
+}
+
+/// Model prices, matched by model id prefix
+///
+/// The defaults reflect published list prices at the time of writing and will go
+/// stale; override them with [`PriceTable::with_price`]. The most specific (longest)
+/// matching prefix wins, so `claude-opus-4-5` can be priced apart from `claude-opus-4`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriceTable {
+    prices: BTreeMap<String, ModelPrice>,
+    fallback: Option<ModelPrice>,
+}
+
+impl Default for PriceTable {
+    fn default() -> Self {
+        Self::empty()
+            .with_price("claude-opus-4", ModelPrice::new(15.0, 75.0))
+            .with_price("claude-opus-4-5", ModelPrice::new(5.0, 25.0))
+            .with_price("claude-sonnet-4", ModelPrice::new(3.0, 15.0))
+            .with_price("claude-3-7-sonnet", ModelPrice::new(3.0, 15.0))
+            .with_price("claude-3-5-sonnet", ModelPrice::new(3.0, 15.0))
+            .with_price("claude-haiku-4-5", ModelPrice::new(1.0, 5.0))
+            .with_price("claude-3-5-haiku", ModelPrice::new(0.8, 4.0))
+    }
+}
+
+impl PriceTable {
+    /// A table with no prices; unknown models cost nothing
+    pub fn empty() -> Self {
+        Self {
+            prices: BTreeMap::new(),
+            fallback: None,
+        }
+    }
+
+    /// Set the price for model ids starting with `model_prefix`
+    pub fn with_price(mut self, model_prefix: impl Into<String>, price: ModelPrice) -> Self {
+        self.set_price(model_prefix, price);
+        self
+    }
+
+    /// Set the price for model ids starting with `model_prefix`
+    pub fn set_price(&mut self, model_prefix: impl Into<String>, price: ModelPrice) {
+        self.prices.insert(model_prefix.into(), price);
+    }
+
+    /// Price used for models no prefix matches
+    pub fn with_fallback(mut self, price: ModelPrice) -> Self {
+        self.fallback = Some(price);
+        self
+    }
+
+    /// Price for a model, if known
+    pub fn price_for(&self, model: &ModelId) -> Option<&ModelPrice> {
+        self.prices
+            .iter()
+            .filter(|(prefix, _)| model.as_str().starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, price)| price)
+            .or(self.fallback.as_ref())
+    }
+
+    /// Cost of usage by a model in USD, zero if the model has no price
+    pub fn cost(&self, model: &ModelId, totals: &UsageTotals) -> f64 {
+        self.price_for(model)
+            .map_or(0.0, |price| price.cost(totals))
+    }
+}
+
+/// What a [`UsageReport`] is grouped by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportGrouping {
+    /// One row per session
+    Session,
+    /// One row per model
+    Model,
+    /// One row per attribution label
+    Label,
+}
+
+impl ReportGrouping {
+    fn column(&self) -> &'static str {
+        match self {
+            ReportGrouping::Session => "session",
+            ReportGrouping::Model => "model",
+            ReportGrouping::Label => "label",
+        }
+    }
+}
+
+/// One row of a [`UsageReport`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageReportRow {
+    /// Session id, model id or label, depending on the grouping
+    pub key: String,
+    /// Number of model responses
+    pub requests: u64,
+    /// Uncached input tokens
+    pub input_tokens: u64,
+    /// Output tokens
+    pub output_tokens: u64,
+    /// Input tokens written to the prompt cache
+    pub cache_creation_tokens: u64,
+    /// Input tokens read from the prompt cache
+    pub cache_read_tokens: u64,
+    /// Cost in USD according to the price table
+    pub cost_usd: f64,
+}
+
+/// Token usage and cost broken down by session, model or label
+///
+/// Rows are ordered by cost, most expensive first.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsageReport {
+    /// What the rows are grouped by
+    pub grouping: ReportGrouping,
+    /// First day with usage in the report
+    pub first_day: Option<NaiveDate>,
+    /// Last day with usage in the report
+    pub last_day: Option<NaiveDate>,
+    /// One row per group
+    pub rows: Vec<UsageReportRow>,
+}
+
+impl UsageReport {
+    /// Total cost of all rows in USD
+    pub fn total_cost_usd(&self) -> f64 {
+        self.rows.iter().map(|row| row.cost_usd).sum()
+    }
+
+    /// Serialize as pretty-printed JSON
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Serialize as CSV with a header row
+    pub fn to_csv(&self) -> String {
+        let mut csv = format!(
+            "{},requests,input_tokens,output_tokens,cache_creation_tokens,cache_read_tokens,cost_usd\n",
+            self.grouping.column()
+        );
+        for row in &self.rows {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{},{:.6}",
+                csv_field(&row.key),
+                row.requests,
+                row.input_tokens,
+                row.output_tokens,
+                row.cache_creation_tokens,
+                row.cache_read_tokens,
+                row.cost_usd
+            );
+        }
+        csv
+    }
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
     }
 }
 
@@ -233,8 +686,26 @@ impl BudgetManager {
     pub async fn update_usage(&self, input_tokens: u64, output_tokens: u64, cost_usd: f64) {
         // Update tracker
         self.tracker.write().await.update(input_tokens, output_tokens, cost_usd);
+        self.check_limits().await;
+    }
 
-        // Check limits
+    /// Record attributed usage and check limits
+    pub async fn record_usage(&self, record: UsageRecord) {
+        self.tracker.write().await.record(record);
+        self.check_limits().await;
+    }
+
+    /// Attribute the session's subsequent usage to `label`
+    pub async fn set_label(&self, session_id: SessionId, label: impl Into<String>) {
+        self.tracker.write().await.set_label(session_id, label);
+    }
+
+    /// Stop attributing the session's usage to a label
+    pub async fn clear_label(&self, session_id: &SessionId) {
+        self.tracker.write().await.clear_label(session_id);
+    }
+
+    async fn check_limits(&self) {
         if let Some(limit) = self.limit.read().await.as_ref() {
             let usage = self.tracker.read().await.clone();
             let status = limit.check_limits(&usage);
@@ -290,6 +761,62 @@ impl Default for BudgetManager {
     }
 }
 
+/// Hook that attributes follow-up generations to the tool that triggered them
+///
+/// Register it for `PostToolUse` and `UserPromptSubmit`. After a tool runs, the
+/// session's usage is labelled with the tool name until the next user prompt,
+/// so [`TokenUsageTracker::report_by_label`] shows what each tool's results cost
+/// to process.
+///
+/// # Examples
+///
+/// ```
+/// use crate::cc::hooks::HookMatcher;
+/// use crate::cc::token_tracker::{AttributionHook, BudgetManager};
+/// use std::sync::Arc;
+///
+/// let budget = BudgetManager::new();
+/// let matcher = HookMatcher {
+///     matcher: None,
+///     hooks: vec![Arc::new(AttributionHook::new(budget.clone()))],
+/// };
+/// ```
+pub struct AttributionHook {
+    manager: BudgetManager,
+}
+
+impl AttributionHook {
+    /// Create a hook labelling usage in `manager`'s tracker
+    pub fn new(manager: BudgetManager) -> Self {
+        Self { manager }
+    }
+}
+
+#[async_trait]
+impl HookCallback for AttributionHook {
+    async fn execute(
+        &self,
+        input: &HookInput,
+        _tool_use_id: Option<&str>,
+        _context: &HookContext,
+    ) -> Result<HookJSONOutput, crate::cc::error::Error> {
+        match input {
+            HookInput::PostToolUse(input) => {
+                let session_id = SessionId::new(input.base.session_id.clone());
+                self.manager
+                    .set_label(session_id, input.tool_name.clone())
+                    .await;
+            }
+            HookInput::UserPromptSubmit(input) => {
+                let session_id = SessionId::new(input.base.session_id.clone());
+                self.manager.clear_label(&session_id).await;
+            }
+            _ => {}
+        }
+        Ok(HookJSONOutput::Sync(Default::default()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -343,4 +870,156 @@ mod tests {
         manager.update_usage(300, 300, 0.05).await;
         assert!(manager.is_exceeded().await);
     }
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, d).unwrap()
+    }
+
+    fn record(session: &str, model: &str, d: u32, input: u64, output: u64) -> UsageRecord {
+        UsageRecord::new(SessionId::new(session), ModelId::new(model), input, output)
+            .at(day(d).and_hms_opt(12, 0, 0).unwrap().and_utc())
+    }
+
+    #[test]
+    fn test_price_table_prefers_longest_prefix_and_overrides() {
+        let table = PriceTable::default();
+        let opus = table
+            .price_for(&ModelId::new("claude-opus-4-1-20250805"))
+            .unwrap();
+        assert_eq!(opus.input_per_mtok, 15.0);
+        let opus_45 = table
+            .price_for(&ModelId::new("claude-opus-4-5-20251101"))
+            .unwrap();
+        assert_eq!(opus_45.input_per_mtok, 5.0);
+        assert!(table.price_for(&ModelId::new("gpt-4")).is_none());
+
+        let table = table.with_price("claude-opus-4", ModelPrice::new(1.0, 2.0));
+        let million = UsageTotals {
+            input_tokens: 1_000_000,
+            output_tokens: 1_000_000,
+            cache_read_tokens: 1_000_000,
+            ..Default::default()
+        };
+        assert!((table.cost(&ModelId::new("claude-opus-4-1"), &million) - 3.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_reports_by_session_model_and_label() {
+        let prices = PriceTable::empty()
+            .with_price("sonnet", ModelPrice::new(3.0, 15.0))
+            .with_price("haiku", ModelPrice::new(1.0, 5.0));
+        let mut tracker = TokenUsageTracker::new().with_prices(prices);
+
+        tracker.record(record("s1", "sonnet", 1, 1_000_000, 0));
+        tracker.set_label(SessionId::new("s1"), "Bash");
+        tracker.record(record("s1", "sonnet", 2, 0, 100_000).with_cache(0, 1_000_000));
+        tracker.record(record("s2", "haiku", 2, 1_000_000, 0).with_label("Read"));
+        tracker.record(record("s2", "haiku", 5, 1_000_000, 0));
+
+        assert_eq!(tracker.session_count, 4);
+        assert!((tracker.total_cost_usd - 6.8).abs() < 1e-9);
+
+        let by_session = tracker.report_by_session(day(1)..=day(2));
+        assert_eq!(by_session.first_day, Some(day(1)));
+        assert_eq!(by_session.last_day, Some(day(2)));
+        let keys: Vec<_> = by_session.rows.iter().map(|r| r.key.as_str()).collect();
+        assert_eq!(keys, vec!["s1", "s2"]);
+        assert_eq!(by_session.rows[0].requests, 2);
+        assert_eq!(by_session.rows[0].cache_read_tokens, 1_000_000);
+        assert!((by_session.rows[0].cost_usd - 4.8).abs() < 1e-9);
+
+        let by_model = tracker.report_by_model(..);
+        assert_eq!(by_model.rows[0].key, "sonnet");
+        assert_eq!(by_model.rows[1].input_tokens, 2_000_000);
+        assert!((by_model.total_cost_usd() - 6.8).abs() < 1e-9);
+
+        let by_label = tracker.report_by_label(day(2)..);
+        let labels: Vec<_> = by_label.rows.iter().map(|r| r.key.as_str()).collect();
+        // Equal costs are ordered by key
+        assert_eq!(labels, vec!["Bash", UNATTRIBUTED, "Read"]);
+    }
+
+    #[test]
+    fn test_report_serialization() {
+        let mut tracker = TokenUsageTracker::new().with_prices(PriceTable::empty());
+        tracker.record(record("a,\"b\"", "m", 1, 10, 20));
+
+        let report = tracker.report_by_session(..);
+        let csv = report.to_csv();
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "session,requests,input_tokens,output_tokens,cache_creation_tokens,cache_read_tokens,cost_usd"
+        );
+        assert_eq!(lines[1], "\"a,\"\"b\"\"\",1,10,20,0,0,0.000000");
+
+        let json = report.to_json().unwrap();
+        let parsed: UsageReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, report);
+        assert!(json.contains("\"grouping\": \"session\""));
+    }
+
+    #[test]
+    fn test_daily_aggregates_roll_and_expire() {
+        let mut tracker = TokenUsageTracker::new()
+            .with_prices(PriceTable::empty())
+            .with_retention_days(2);
+        for _ in 0..100 {
+            tracker.record(record("s", "m", 1, 1, 1));
+        }
+        assert_eq!(tracker.daily.len(), 1);
+        assert_eq!(tracker.daily[&day(1)].len(), 1);
+        assert_eq!(tracker.report_by_session(..).rows[0].requests, 100);
+
+        tracker.record(record("s", "m", 4, 1, 1));
+        assert_eq!(
+            tracker.daily.keys().copied().collect::<Vec<_>>(),
+            vec![day(4)]
+        );
+        // Totals are unaffected by expiry
+        assert_eq!(tracker.session_count, 101);
+    }
+
+    #[tokio::test]
+    async fn test_attribution_hook_labels_follow_up_usage() {
+        use crate::cc::hooks::{BaseHookInput, PostToolUseHookInput, UserPromptSubmitHookInput};
+
+        let manager = BudgetManager::new();
+        let hook = AttributionHook::new(manager.clone());
+        let context = HookContext { signal: None };
+        let base = BaseHookInput {
+            session_id: "s1".to_string(),
+            transcript_path: String::new(),
+            cwd: String::new(),
+            permission_mode: None,
+        };
+
+        let tool_ran = HookInput::PostToolUse(PostToolUseHookInput {
+            base: base.clone(),
+            tool_name: "Grep".to_string(),
+            tool_input: serde_json::json!({}),
+            tool_response: serde_json::json!({}),
+        });
+        hook.execute(&tool_ran, None, &context).await.unwrap();
+        manager
+            .record_usage(record("s1", "claude-sonnet-4-5", 1, 10, 10))
+            .await;
+
+        let prompt = HookInput::UserPromptSubmit(UserPromptSubmitHookInput {
+            base,
+            prompt: "next".to_string(),
+        });
+        hook.execute(&prompt, None, &context).await.unwrap();
+        manager
+            .record_usage(record("s1", "claude-sonnet-4-5", 1, 10, 10))
+            .await;
+
+        let report = manager.get_usage().await.report_by_label(..);
+        let labels: Vec<_> = report
+            .rows
+            .iter()
+            .map(|r| (r.key.as_str(), r.requests))
+            .collect();
+        assert_eq!(labels, vec![(UNATTRIBUTED, 1), ("Grep", 1)]);
+    }
 }
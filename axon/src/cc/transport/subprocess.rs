@@ -143,6 +143,23 @@ impl SubprocessTransport {
         self.sdk_control_rx.take()
     }
 
+    /// Take the control response receiver (can only be called once)
+    ///
+    /// Control responses are also delivered here for callers that don't run
+    /// the control protocol; callers that do should take and drain it so the
+    /// stdout handler never blocks on a full channel.
+    pub fn take_control_receiver(&mut self) -> Option<mpsc::Receiver<ControlResponse>> {
+        self.control_rx.take()
+    }
+
+    /// Whether the CLI process has exited or was never started
+    pub fn has_exited(&mut self) -> bool {
+        match self.child.as_mut() {
+            Some(child) => !matches!(child.try_wait(), Ok(None)),
+            None => true,
+        }
+    }
+
     /// Create with a specific CLI path
     pub fn with_cli_path(options: ClaudeCodeOptions, cli_path: impl Into<PathBuf>) -> Self {
         Self {
@@ -0,0 +1,661 @@
+//! Agent liveness supervision
+//!
+//! Agents started through the [`RuntimeManager`](super::runtime_manager_impl::RuntimeManager)
+//! get an [`AgentHandle`] from the configured [`AgentLauncher`]. A background
+//! loop probes every handle each interval: the process must still be alive and
+//! answer a heartbeat over its control channel within the timeout. An agent
+//! failing either check is marked `crashed`, its in-flight task assignments
+//! go back to the pending queue, and if it has a [`RestartPolicy`] it is
+//! relaunched after an exponential backoff, as long as it has not used up its
+//! restarts for the current window.
+//!
+//! Agents registered without a launcher have no handle and are never probed.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, RwLock};
+use tracing::{debug, info, warn};
+
+use super::runtime_manager_impl::AgentInfo;
+use crate::orchestration::RouteRequest;
+
+/// When and how often a crashed agent is restarted
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RestartPolicy {
+    /// Restarts allowed within `window`
+    pub max_restarts: u32,
+    /// Sliding window restarts are counted over
+    pub window: Duration,
+    /// Delay before the first restart in a window, doubled for each further one
+    pub backoff: Duration,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            max_restarts: 3,
+            window: Duration::from_secs(300),
+            backoff: Duration::from_secs(1),
+        }
+    }
+}
+
+impl RestartPolicy {
+    /// Delay before the next restart given the restarts already made in the
+    /// window, or `None` once the limit is reached
+    pub fn next_delay(&self, recent_restarts: u32) -> Option<Duration> {
+        if recent_restarts >= self.max_restarts {
+            return None;
+        }
+        Some(self.backoff.saturating_mul(1 << recent_restarts.min(16)))
+    }
+}
+
+/// A restart made by the supervisor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestartEvent {
+    pub at: String,
+    /// Why the previous instance was considered dead
+    pub reason: String,
+    /// Restart number within the policy window, starting at 1
+    pub attempt: u32,
+}
+
+/// Work handed to an agent that must not be lost if the agent dies
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskAssignment {
+    pub task_id: String,
+    pub payload: serde_json::Value,
+    /// Capabilities an agent needs to take the task
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub required_capabilities: Vec<String>,
+    /// Capabilities that make an agent a better fit
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub preferred_capabilities: Vec<String>,
+    /// Kind of work, used to prefer agents with a good record on it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task_type: Option<String>,
+}
+
+impl From<TaskAssignment> for RouteRequest {
+    fn from(task: TaskAssignment) -> Self {
+        Self {
+            task_id: task.task_id,
+            required_capabilities: task.required_capabilities,
+            preferred_capabilities: task.preferred_capabilities,
+            task_type: task.task_type,
+            payload: task.payload,
+        }
+    }
+}
+
+impl From<RouteRequest> for TaskAssignment {
+    fn from(request: RouteRequest) -> Self {
+        Self {
+            task_id: request.task_id,
+            payload: request.payload,
+            required_capabilities: request.required_capabilities,
+            preferred_capabilities: request.preferred_capabilities,
+            task_type: request.task_type,
+        }
+    }
+}
+
+/// Live connection to a running agent
+#[async_trait]
+pub trait AgentHandle: Send + Sync {
+    /// Whether the underlying process is still running
+    fn is_alive(&self) -> bool;
+
+    /// Round trip over the control channel
+    async fn heartbeat(&self) -> Result<()>;
+
+    /// Stop the agent; called when it is stopped or declared dead
+    async fn shutdown(&self) {}
+}
+
+/// Starts the process behind an agent
+#[async_trait]
+pub trait AgentLauncher: Send + Sync {
+    async fn launch(&self, agent: &AgentInfo) -> Result<Arc<dyn AgentHandle>>;
+}
+
+/// Supervisor settings
+#[derive(Debug, Clone)]
+pub struct SupervisorConfig {
+    /// Time between health checks
+    pub interval: Duration,
+    /// How long a heartbeat may take before the agent counts as unresponsive
+    pub heartbeat_timeout: Duration,
+    /// Supervisor log lines kept per agent
+    pub log_buffer_lines: usize,
+}
+
+impl Default for SupervisorConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(5),
+            heartbeat_timeout: Duration::from_secs(5),
+            log_buffer_lines: 200,
+        }
+    }
+}
+
+#[derive(Default)]
+struct SupervisedAgent {
+    handle: Option<Arc<dyn AgentHandle>>,
+    policy: Option<RestartPolicy>,
+    /// Restart times within the current policy window
+    restarts: VecDeque<Instant>,
+    restart_at: Option<Instant>,
+    last_crash: Option<String>,
+    in_flight: Vec<TaskAssignment>,
+    log: VecDeque<String>,
+}
+
+/// Health-checks agents and restarts the ones that die
+pub struct AgentSupervisor {
+    config: SupervisorConfig,
+    launcher: Option<Arc<dyn AgentLauncher>>,
+    agents: Arc<RwLock<HashMap<String, AgentInfo>>>,
+    supervised: RwLock<HashMap<String, SupervisedAgent>>,
+    pending: Mutex<VecDeque<TaskAssignment>>,
+    error_counts: RwLock<HashMap<String, u64>>,
+    started: AtomicBool,
+}
+
+impl AgentSupervisor {
+    pub fn new(
+        config: SupervisorConfig,
+        launcher: Option<Arc<dyn AgentLauncher>>,
+        agents: Arc<RwLock<HashMap<String, AgentInfo>>>,
+    ) -> Self {
+        Self {
+            config,
+            launcher,
+            agents,
+            supervised: RwLock::new(HashMap::new()),
+            pending: Mutex::new(VecDeque::new()),
+            error_counts: RwLock::new(HashMap::new()),
+            started: AtomicBool::new(false),
+        }
+    }
+
+    /// Launch the process for a newly started agent and begin supervising it
+    pub async fn register(&self, agent: &AgentInfo, policy: Option<RestartPolicy>) -> Result<()> {
+        let handle = match &self.launcher {
+            Some(launcher) => Some(launcher.launch(agent).await?),
+            None => None,
+        };
+
+        let mut supervised = SupervisedAgent {
+            handle,
+            policy,
+            ..Default::default()
+        };
+        push_log(&mut supervised.log, self.config.log_buffer_lines, "started");
+        self.supervised.write().await.insert(agent.id.clone(), supervised);
+        Ok(())
+    }
+
+    /// Stop supervising an agent, shutting its process down and returning
+    /// its in-flight tasks to the pending queue
+    pub async fn unregister(&self, agent_id: &str) {
+        let Some(supervised) = self.supervised.write().await.remove(agent_id) else {
+            return;
+        };
+        if let Some(handle) = supervised.handle {
+            handle.shutdown().await;
+        }
+        self.pending.lock().await.extend(supervised.in_flight);
+    }
+
+    /// Start the periodic health check unless it is already running. The
+    /// loop ends once the supervisor is dropped.
+    pub fn ensure_running(self: &Arc<Self>) {
+        if self.started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        let supervisor = Arc::downgrade(self);
+        let interval = self.config.interval;
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let Some(supervisor) = Weak::upgrade(&supervisor) else {
+                    break;
+                };
+                supervisor.check_agents().await;
+            }
+        });
+    }
+
+    /// Run one health check over every supervised agent, restarting those
+    /// whose backoff has elapsed
+    pub async fn check_agents(&self) {
+        let now = Instant::now();
+        let mut to_probe = Vec::new();
+        let mut to_restart = Vec::new();
+        {
+            let supervised = self.supervised.read().await;
+            for (id, agent) in supervised.iter() {
+                if agent.restart_at.is_some_and(|at| at <= now) {
+                    to_restart.push(id.clone());
+                } else if let Some(handle) = &agent.handle {
+                    to_probe.push((id.clone(), handle.clone()));
+                }
+            }
+        }
+
+        for (id, handle) in to_probe {
+            if let Err(reason) = self.probe(handle.as_ref()).await {
+                self.mark_crashed(&id, &reason).await;
+            }
+        }
+
+        for id in to_restart {
+            self.restart(&id).await;
+        }
+    }
+
+    /// Record a task as running on an agent
+    pub async fn assign(&self, agent_id: &str, task: TaskAssignment) -> Result<()> {
+        let mut supervised = self.supervised.write().await;
+        let agent = supervised
+            .get_mut(agent_id)
+            .ok_or_else(|| anyhow::anyhow!("Agent not found: {}", agent_id))?;
+        if agent.last_crash.is_some() {
+            return Err(anyhow::anyhow!("Agent {} has crashed and cannot take tasks", agent_id));
+        }
+        agent.in_flight.push(task);
+        Ok(())
+    }
+
+    /// Forget a finished task. Returns whether it was assigned to the agent.
+    pub async fn complete(&self, agent_id: &str, task_id: &str) -> bool {
+        let mut supervised = self.supervised.write().await;
+        let Some(agent) = supervised.get_mut(agent_id) else {
+            return false;
+        };
+        let before = agent.in_flight.len();
+        agent.in_flight.retain(|task| task.task_id != task_id);
+        agent.in_flight.len() != before
+    }
+
+    /// Drain tasks returned by crashed or stopped agents, oldest first
+    pub async fn take_pending(&self) -> Vec<TaskAssignment> {
+        self.pending.lock().await.drain(..).collect()
+    }
+
+    /// Supervisor log lines for an agent, oldest first
+    pub async fn log_lines(&self, agent_id: &str) -> Vec<String> {
+        self.supervised
+            .read()
+            .await
+            .get(agent_id)
+            .map(|agent| agent.log.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Most frequent supervisor errors, most frequent first
+    pub async fn top_errors(&self, limit: usize) -> Vec<(String, u64)> {
+        let mut errors: Vec<(String, u64)> = self
+            .error_counts
+            .read()
+            .await
+            .iter()
+            .map(|(error, count)| (error.clone(), *count))
+            .collect();
+        errors.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        errors.truncate(limit);
+        errors
+    }
+
+    async fn probe(&self, handle: &dyn AgentHandle) -> std::result::Result<(), String> {
+        if !handle.is_alive() {
+            return Err("process exited".to_string());
+        }
+        match tokio::time::timeout(self.config.heartbeat_timeout, handle.heartbeat()).await {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(e)) => Err(format!("heartbeat failed: {}", e)),
+            Err(_) => Err(format!("no heartbeat within {:?}", self.config.heartbeat_timeout)),
+        }
+    }
+
+    async fn mark_crashed(&self, agent_id: &str, reason: &str) {
+        warn!("Agent {} crashed: {}", agent_id, reason);
+
+        let (handle, returned) = {
+            let mut supervised = self.supervised.write().await;
+            let Some(agent) = supervised.get_mut(agent_id) else {
+                return;
+            };
+            let limit = self.config.log_buffer_lines;
+            push_log(&mut agent.log, limit, &format!("crashed: {}", reason));
+
+            let returned = std::mem::take(&mut agent.in_flight);
+            if !returned.is_empty() {
+                push_log(
+                    &mut agent.log,
+                    limit,
+                    &format!("returned {} in-flight task(s) to the queue", returned.len()),
+                );
+            }
+
+            agent.last_crash = Some(reason.to_string());
+            self.schedule_restart(agent);
+            (agent.handle.take(), returned)
+        };
+
+        self.pending.lock().await.extend(returned);
+        *self
+            .error_counts
+            .write()
+            .await
+            .entry(format!("Agent crashed: {}", crash_kind(reason)))
+            .or_default() += 1;
+        self.set_status(agent_id, "crashed").await;
+
+        if let Some(handle) = handle {
+            handle.shutdown().await;
+        }
+    }
+
+    /// Set `restart_at` from the agent's policy, or leave it unset when the
+    /// agent has no policy or no restarts left
+    fn schedule_restart(&self, agent: &mut SupervisedAgent) {
+        let Some(policy) = &agent.policy else {
+            return;
+        };
+
+        let now = Instant::now();
+        while agent
+            .restarts
+            .front()
+            .is_some_and(|at| now.duration_since(*at) > policy.window)
+        {
+            agent.restarts.pop_front();
+        }
+
+        let limit = self.config.log_buffer_lines;
+        match policy.next_delay(agent.restarts.len() as u32) {
+            Some(delay) => {
+                agent.restart_at = Some(now + delay);
+                push_log(&mut agent.log, limit, &format!("restart scheduled in {:?}", delay));
+            }
+            None => {
+                agent.restart_at = None;
+                push_log(
+                    &mut agent.log,
+                    limit,
+                    &format!("restart limit reached ({} in {:?})", policy.max_restarts, policy.window),
+                );
+            }
+        }
+    }
+
+    async fn restart(&self, agent_id: &str) {
+        let Some(launcher) = self.launcher.clone() else {
+            return;
+        };
+        let Some(info) = self.agents.read().await.get(agent_id).cloned() else {
+            self.supervised.write().await.remove(agent_id);
+            return;
+        };
+
+        let (reason, attempt) = {
+            let mut supervised = self.supervised.write().await;
+            let Some(agent) = supervised.get_mut(agent_id) else {
+                return;
+            };
+            agent.restart_at = None;
+            agent.restarts.push_back(Instant::now());
+            (agent.last_crash.clone().unwrap_or_default(), agent.restarts.len() as u32)
+        };
+
+        info!("Restarting agent {} (attempt {})", agent_id, attempt);
+        self.set_status(agent_id, "restarting").await;
+
+        match launcher.launch(&info).await {
+            Ok(handle) => {
+                {
+                    let mut supervised = self.supervised.write().await;
+                    let Some(agent) = supervised.get_mut(agent_id) else {
+                        return;
+                    };
+                    agent.handle = Some(handle);
+                    agent.last_crash = None;
+                    push_log(
+                        &mut agent.log,
+                        self.config.log_buffer_lines,
+                        &format!("restarted (attempt {})", attempt),
+                    );
+                }
+
+                let mut agents = self.agents.write().await;
+                if let Some(info) = agents.get_mut(agent_id) {
+                    info.status = "running".to_string();
+                    info.restarts.push(RestartEvent {
+                        at: Utc::now().to_rfc3339(),
+                        reason,
+                        attempt,
+                    });
+                }
+            }
+            Err(e) => {
+                debug!("Relaunching agent {} failed: {}", agent_id, e);
+                self.mark_crashed(agent_id, &format!("restart failed: {}", e)).await;
+            }
+        }
+    }
+
+    async fn set_status(&self, agent_id: &str, status: &str) {
+        if let Some(info) = self.agents.write().await.get_mut(agent_id) {
+            info.status = status.to_string();
+        }
+    }
+}
+
+/// Crash reason without instance-specific detail, for error counting
+fn crash_kind(reason: &str) -> &str {
+    reason.split(':').next().unwrap_or(reason)
+}
+
+fn push_log(log: &mut VecDeque<String>, limit: usize, message: &str) {
+    log.push_back(format!("[{}] supervisor: {}", Utc::now().to_rfc3339(), message));
+    while log.len() > limit {
+        log.pop_front();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::AgentType;
+    use crate::commands::runtime_manager_impl::RuntimeManager;
+    use crate::orchestration::RouteDecision;
+    use std::sync::atomic::AtomicU32;
+
+    struct FakeAgent {
+        alive: AtomicBool,
+    }
+
+    #[async_trait]
+    impl AgentHandle for FakeAgent {
+        fn is_alive(&self) -> bool {
+            self.alive.load(Ordering::SeqCst)
+        }
+
+        async fn heartbeat(&self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct FakeLauncher {
+        launches: AtomicU32,
+        instances: std::sync::Mutex<Vec<Arc<FakeAgent>>>,
+    }
+
+    impl FakeLauncher {
+        fn kill_latest(&self) {
+            let instances = self.instances.lock().unwrap();
+            instances.last().unwrap().alive.store(false, Ordering::SeqCst);
+        }
+    }
+
+    #[async_trait]
+    impl AgentLauncher for FakeLauncher {
+        async fn launch(&self, _agent: &AgentInfo) -> Result<Arc<dyn AgentHandle>> {
+            self.launches.fetch_add(1, Ordering::SeqCst);
+            let agent = Arc::new(FakeAgent {
+                alive: AtomicBool::new(true),
+            });
+            self.instances.lock().unwrap().push(agent.clone());
+            Ok(agent)
+        }
+    }
+
+    fn manager(launcher: Arc<FakeLauncher>) -> RuntimeManager {
+        let config = SupervisorConfig {
+            interval: Duration::from_secs(3600),
+            ..Default::default()
+        };
+        RuntimeManager::with_launcher(launcher, config)
+    }
+
+    async fn start(manager: &RuntimeManager, policy: Option<RestartPolicy>) -> String {
+        manager
+            .start_agent("worker".to_string(), AgentType::Developer, Vec::new(), None, 1, policy)
+            .await
+            .unwrap()
+    }
+
+    fn task(id: &str) -> TaskAssignment {
+        TaskAssignment {
+            task_id: id.to_string(),
+            payload: serde_json::json!({}),
+            required_capabilities: Vec::new(),
+            preferred_capabilities: Vec::new(),
+            task_type: None,
+        }
+    }
+
+    #[test]
+    fn test_next_delay_doubles_until_limit() {
+        let policy = RestartPolicy {
+            max_restarts: 3,
+            window: Duration::from_secs(60),
+            backoff: Duration::from_millis(100),
+        };
+        assert_eq!(policy.next_delay(0), Some(Duration::from_millis(100)));
+        assert_eq!(policy.next_delay(2), Some(Duration::from_millis(400)));
+        assert_eq!(policy.next_delay(3), None);
+    }
+
+    #[tokio::test]
+    async fn test_crash_requeues_tasks_and_restarts() {
+        let launcher = Arc::new(FakeLauncher::default());
+        let manager = manager(launcher.clone());
+        let policy = RestartPolicy {
+            max_restarts: 1,
+            window: Duration::from_secs(60),
+            backoff: Duration::ZERO,
+        };
+        let id = start(&manager, Some(policy)).await;
+
+        manager.assign_task(&id, task("a")).await.unwrap();
+        manager.assign_task(&id, task("b")).await.unwrap();
+        assert!(manager.complete_task(&id, "a").await);
+
+        launcher.kill_latest();
+        manager.supervisor().check_agents().await;
+
+        assert_eq!(manager.get_agent_info(&id).await.unwrap().status, "crashed");
+        assert_eq!(manager.take_pending_tasks().await, vec![task("b")]);
+        assert!(manager.assign_task(&id, task("c")).await.is_err());
+
+        // Backoff is zero, so the next check restarts it
+        manager.supervisor().check_agents().await;
+        let info = manager.get_agent_info(&id).await.unwrap();
+        assert_eq!(info.status, "running");
+        assert_eq!(info.restarts.len(), 1);
+        assert_eq!(info.restarts[0].reason, "process exited");
+        assert_eq!(launcher.launches.load(Ordering::SeqCst), 2);
+
+        // The single restart in the window is used up
+        launcher.kill_latest();
+        manager.supervisor().check_agents().await;
+        manager.supervisor().check_agents().await;
+        assert_eq!(manager.get_agent_info(&id).await.unwrap().status, "crashed");
+        assert_eq!(launcher.launches.load(Ordering::SeqCst), 2);
+
+        let log = manager.supervisor().log_lines(&id).await;
+        assert!(log.iter().any(|line| line.contains("restarted (attempt 1)")));
+        assert!(log.last().unwrap().contains("restart limit reached"));
+
+        let telemetry = manager.get_telemetry(60).await.unwrap();
+        assert_eq!(telemetry.top_errors, vec![("Agent crashed: process exited".to_string(), 2)]);
+    }
+
+    #[tokio::test]
+    async fn test_no_policy_stays_crashed() {
+        let launcher = Arc::new(FakeLauncher::default());
+        let manager = manager(launcher.clone());
+        let id = start(&manager, None).await;
+
+        launcher.kill_latest();
+        manager.supervisor().check_agents().await;
+        manager.supervisor().check_agents().await;
+
+        assert_eq!(manager.get_agent_info(&id).await.unwrap().status, "crashed");
+        assert_eq!(launcher.launches.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_stop_returns_in_flight_tasks() {
+        let manager = manager(Arc::new(FakeLauncher::default()));
+        let id = start(&manager, None).await;
+
+        manager.assign_task(&id, task("a")).await.unwrap();
+        manager.stop_agent(&id).await.unwrap();
+
+        assert_eq!(manager.take_pending_tasks().await, vec![task("a")]);
+        assert!(manager.take_pending_tasks().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_routed_task_waits_for_capable_agent() {
+        let manager = manager(Arc::new(FakeLauncher::default()));
+        start(&manager, None).await;
+
+        let mut routed = task("a");
+        routed.required_capabilities = vec!["rust".to_string(), "testing".to_string()];
+        assert_eq!(manager.route_task(routed.clone()).await.unwrap(), RouteDecision::Waiting);
+        assert_eq!(manager.get_telemetry(60).await.unwrap().waiting_tasks.len(), 1);
+
+        let capable = manager
+            .start_agent(
+                "qa".to_string(),
+                AgentType::Tester,
+                vec!["rust".to_string(), "testing".to_string()],
+                None,
+                1,
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(manager.waiting_tasks().await.is_empty());
+
+        // Placed tasks are supervised like directly assigned ones
+        manager.stop_agent(&capable).await.unwrap();
+        assert_eq!(manager.take_pending_tasks().await, vec![routed]);
+    }
+}
@@ -34,6 +34,19 @@ pub async fn logging(
     Ok(response)
 }
 
+/// Records the latency and outcome of every request for the telemetry
+/// history; server errors count as failed requests
+pub async fn record_telemetry(
+    recorder: Arc<crate::monitoring::TelemetryRecorder>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let started = Instant::now();
+    let response = next.run(req).await;
+    recorder.record(started.elapsed(), !response.status().is_server_error());
+    response
+}
+
 /// API key for authentication
 #[derive(Debug, Clone)]
 pub struct ApiKey(pub String);
@@ -81,6 +81,7 @@ pub fn create_routes(state: AppState) -> Router {
         .route("/metrics/export", post(export_metrics))
         .route("/telemetry", get(get_telemetry))
         .route("/telemetry/summary", get(telemetry_summary))
+        .route("/telemetry/history", get(telemetry_history))
 
         // Configuration
         .route("/config", get(get_config).put(update_config))
@@ -323,6 +324,31 @@ async fn get_telemetry(
     Ok(Json(telemetry))
 }
 
+/// Get telemetry history
+#[derive(Debug, Deserialize)]
+struct TelemetryHistoryQuery {
+    /// Minutes before `to`, used when `from` is not given
+    range: Option<u64>,
+    from: Option<chrono::DateTime<chrono::Utc>>,
+    to: Option<chrono::DateTime<chrono::Utc>>,
+    /// Bucket width in seconds; raw samples and rollups if not given
+    step: Option<u64>,
+}
+
+async fn telemetry_history(
+    State(state): State<AppState>,
+    Query(params): Query<TelemetryHistoryQuery>,
+) -> Result<Json<Vec<crate::monitoring::TelemetrySample>>, ApiError> {
+    let runtime = state.runtime.read().await;
+    let to = params.to.unwrap_or_else(chrono::Utc::now);
+    let from = params
+        .from
+        .unwrap_or_else(|| to - chrono::Duration::minutes(params.range.unwrap_or(60) as i64));
+    let step = params.step.filter(|s| *s > 0).map(std::time::Duration::from_secs);
+    let samples = runtime.telemetry_history(from, to, step).await?;
+    Ok(Json(samples))
+}
+
 /// System status
 async fn system_status(
     State(state): State<AppState>,
@@ -473,6 +499,9 @@ async fn resume_workflow(
 struct ExportMetricsRequest {
     format: Option<String>,
     output_path: Option<String>,
+    /// Export the telemetry history of the last this many minutes instead
+    /// of the current snapshot
+    range_minutes: Option<u64>,
 }
 
 #[derive(Debug, Serialize)]
@@ -493,7 +522,7 @@ async fn export_metrics(
     });
 
     runtime
-        .export_metrics(&std::path::PathBuf::from(&output_path), &format)
+        .export_metrics(&std::path::PathBuf::from(&output_path), &format, req.range_minutes)
         .await?;
 
     Ok(Json(ExportMetricsResponse {
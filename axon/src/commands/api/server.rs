@@ -22,6 +22,10 @@ use tracing::{debug, info, warn, Level};
 
 use super::{auth_proxy, middleware as api_middleware, routes, websocket};
 use crate::commands::{config::AxonConfig, runtime_manager::AgentRuntimeManager};
+use crate::monitoring::{AgentTaskCounts, SAMPLE_INTERVAL, TelemetryRecorder, TelemetryStore};
+
+/// How often the telemetry history is compacted
+const COMPACTION_INTERVAL: Duration = Duration::from_secs(60 * 60);
 
 /// SPA fallback handler - serves index.html for all non-API routes
 async fn spa_fallback_handler(
@@ -97,6 +101,59 @@ async fn spa_fallback_handler(
     }
 }
 
+/// Takes a telemetry sample every [`SAMPLE_INTERVAL`] and compacts the
+/// history every [`COMPACTION_INTERVAL`]
+async fn sample_telemetry(
+    store: Arc<TelemetryStore>,
+    recorder: Arc<TelemetryRecorder>,
+    runtime: Arc<RwLock<AgentRuntimeManager>>,
+) {
+    let mut interval = tokio::time::interval(SAMPLE_INTERVAL);
+    // The first tick completes immediately
+    interval.tick().await;
+    let mut last_compaction: Option<std::time::Instant> = None;
+
+    loop {
+        interval.tick().await;
+
+        let now = chrono::Utc::now();
+        let mut sample = recorder.take_sample(now);
+        {
+            let runtime = runtime.read().await;
+            if let Ok(status) = runtime.get_system_status().await {
+                sample.active_agents = status.active_agents;
+                sample.active_workflows = status.running_workflows;
+            }
+            if let Ok(metrics) = runtime.get_metrics(None).await {
+                sample.agent_tasks = metrics
+                    .into_iter()
+                    .map(|(id, m)| {
+                        (
+                            id,
+                            AgentTaskCounts {
+                                completed: m.tasks_completed,
+                                failed: m.tasks_failed,
+                            },
+                        )
+                    })
+                    .collect();
+            }
+        }
+
+        if let Err(e) = store.record(&sample).await {
+            warn!("Failed to record telemetry sample: {}", e);
+        }
+
+        if last_compaction.is_none_or(|at| at.elapsed() >= COMPACTION_INTERVAL) {
+            match store.compact(now).await {
+                Ok(stats) => debug!("Compacted telemetry history: {:?}", stats),
+                Err(e) => warn!("Failed to compact telemetry history: {}", e),
+            }
+            last_compaction = Some(std::time::Instant::now());
+        }
+    }
+}
+
 /// Run the REST API server (blocking)
 pub async fn start_server(host: String, port: u16, workers: Option<usize>) -> Result<()> {
     info!("Starting Axon REST API Server");
@@ -110,8 +167,22 @@ pub async fn start_server(host: String, port: u16, workers: Option<usize>) -> Re
     let config = AxonConfig::load()?;
     let global_config = GlobalConfig::load_or_create_default().await?;
 
+    // Open the telemetry history; the server still runs without one
+    let telemetry_store = match TelemetryStore::open_default().await {
+        Ok(store) => Some(Arc::new(store)),
+        Err(e) => {
+            warn!("Telemetry history unavailable: {}", e);
+            None
+        }
+    };
+
     // Create runtime manager
-    let runtime = Arc::new(RwLock::new(AgentRuntimeManager::new(config.clone())?));
+    let mut runtime_manager = AgentRuntimeManager::new(config.clone())?;
+    if let Some(store) = &telemetry_store {
+        runtime_manager = runtime_manager.with_telemetry_store(store.clone());
+    }
+    let runtime = Arc::new(RwLock::new(runtime_manager));
+    let telemetry_recorder = Arc::new(TelemetryRecorder::new());
 
     // Create WebSocket manager
     let ws_manager = websocket::WsManager::new();
@@ -130,6 +201,11 @@ pub async fn start_server(host: String, port: u16, workers: Option<usize>) -> Re
         }
     });
 
+    // Spawn background task that samples telemetry into the history
+    if let Some(store) = telemetry_store {
+        tokio::spawn(sample_telemetry(store, telemetry_recorder.clone(), runtime.clone()));
+    }
+
     // Create application state
     let app_state = routes::AppState {
         runtime: runtime.clone(),
@@ -153,6 +229,13 @@ pub async fn start_server(host: String, port: u16, workers: Option<usize>) -> Re
             }
         }))
         .layer(middleware::from_fn(api_middleware::logging))
+        .layer(middleware::from_fn({
+            let recorder = telemetry_recorder.clone();
+            move |req, next| {
+                let recorder = recorder.clone();
+                api_middleware::record_telemetry(recorder, req, next)
+            }
+        }))
         .layer(
             TraceLayer::new_for_http()
                 .make_span_with(DefaultMakeSpan::new().level(Level::INFO))
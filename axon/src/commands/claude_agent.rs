@@ -0,0 +1,215 @@
+//! Claude CLI agent processes
+//!
+//! [`ClaudeLauncher`] backs every agent started through the
+//! [`RuntimeManager`](super::runtime_manager_impl::RuntimeManager) with a
+//! Claude CLI process speaking stream-JSON over stdin/stdout. The supervisor
+//! probes it through [`ClaudeAgentHandle`]: the process is alive until it
+//! exits, and a heartbeat is a control-protocol round trip that re-asserts
+//! the permission mode the agent was launched with, so it changes nothing
+//! but only succeeds while the CLI's control loop is responsive.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::{debug, info, warn};
+
+use super::agent_supervisor::{AgentHandle, AgentLauncher};
+use super::runtime_manager_impl::AgentInfo;
+use crate::cc::Query;
+use crate::cc::binary::find_claude_binary;
+use crate::cc::options::{ClaudeCodeOptions, InputFormat, OutputFormat};
+use crate::cc::permissions::PermissionMode;
+use crate::cc::transport::{SubprocessTransport, Transport};
+
+/// Permission mode agents are launched with, as the control protocol names it
+const PERMISSION_MODE: &str = "default";
+
+/// Launches agents as Claude CLI processes
+#[derive(Debug, Default)]
+pub struct ClaudeLauncher {
+    /// CLI to run; discovered on first launch when unset
+    cli_path: Option<PathBuf>,
+    /// Working directory of the processes; the current one when unset
+    working_dir: Option<PathBuf>,
+}
+
+impl ClaudeLauncher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run `path` instead of the discovered Claude CLI
+    pub fn with_cli_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.cli_path = Some(path.into());
+        self
+    }
+
+    /// Start agent processes in `dir`
+    pub fn with_working_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.working_dir = Some(dir.into());
+        self
+    }
+
+    fn cli_path(&self) -> Result<PathBuf> {
+        match &self.cli_path {
+            Some(path) => Ok(path.clone()),
+            None => find_claude_binary()
+                .map(PathBuf::from)
+                .map_err(|e| anyhow::anyhow!("Claude CLI not found: {}", e)),
+        }
+    }
+
+    fn options(&self, agent: &AgentInfo) -> ClaudeCodeOptions {
+        let mut builder = ClaudeCodeOptions::builder()
+            .permission_mode(PermissionMode::Default)
+            .input_format(InputFormat::StreamJson)
+            .output_format(OutputFormat::StreamJson);
+        if let Some(model) = &agent.model {
+            builder = builder.model(model.clone());
+        }
+        if let Some(dir) = &self.working_dir {
+            builder = builder.cwd(dir.clone());
+        }
+        builder.build()
+    }
+}
+
+#[async_trait]
+impl AgentLauncher for ClaudeLauncher {
+    async fn launch(&self, agent: &AgentInfo) -> Result<Arc<dyn AgentHandle>> {
+        let cli_path = self.cli_path()?;
+        let mut transport = SubprocessTransport::with_cli_path(self.options(agent), &cli_path);
+        transport
+            .connect()
+            .await
+            .with_context(|| format!("Failed to start {} for agent {}", cli_path.display(), agent.id))?;
+
+        // The query handler reads control responses from the SDK channel;
+        // drain the plain one so the CLI's stdout is never blocked on it
+        if let Some(mut responses) = transport.take_control_receiver() {
+            tokio::spawn(async move { while responses.recv().await.is_some() {} });
+        }
+
+        let transport: Arc<Mutex<Box<dyn Transport + Send>>> = Arc::new(Mutex::new(Box::new(transport)));
+        let mut query = Query::new(transport.clone(), true, None, None, HashMap::new());
+        let handle = ClaudeAgentHandle {
+            transport,
+            query: Mutex::new(None),
+        };
+
+        let started = async {
+            query.start().await?;
+            query.initialize().await
+        };
+        if let Err(e) = started.await {
+            handle.shutdown().await;
+            return Err(anyhow::anyhow!("Agent {} did not complete the control handshake: {}", agent.id, e));
+        }
+        *handle.query.lock().await = Some(query);
+
+        info!("Launched Claude CLI for agent {} ({})", agent.id, agent.name);
+        Ok(Arc::new(handle))
+    }
+}
+
+/// A running Claude CLI process
+pub struct ClaudeAgentHandle {
+    transport: Arc<Mutex<Box<dyn Transport + Send>>>,
+    /// Control protocol over the process's stdin/stdout; `None` until the
+    /// handshake completes
+    query: Mutex<Option<Query>>,
+}
+
+#[async_trait]
+impl AgentHandle for ClaudeAgentHandle {
+    fn is_alive(&self) -> bool {
+        // The transport is only locked briefly to send; if it is busy right
+        // now the process was alive a moment ago and the heartbeat decides
+        let Ok(mut transport) = self.transport.try_lock() else {
+            return true;
+        };
+        transport
+            .as_any_mut()
+            .downcast_mut::<SubprocessTransport>()
+            .is_some_and(|process| !process.has_exited())
+    }
+
+    async fn heartbeat(&self) -> Result<()> {
+        let mut query = self.query.lock().await;
+        let query = query
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("control protocol not initialized"))?;
+        query.set_permission_mode(PERMISSION_MODE).await?;
+        debug!("Claude CLI answered heartbeat");
+        Ok(())
+    }
+
+    async fn shutdown(&self) {
+        if let Err(e) = self.transport.lock().await.disconnect().await {
+            warn!("Failed to stop Claude CLI: {}", e);
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::agents::AgentType;
+    use std::os::unix::fs::PermissionsExt;
+
+    /// Stand-in CLI that acknowledges every control request
+    const FAKE_CLI: &str = r#"#!/bin/sh
+if [ "$1" = "--version" ]; then
+    echo "2.0.0 (Claude Code)"
+    exit 0
+fi
+while read -r line; do
+    id=$(printf '%s' "$line" | sed -n 's/.*"request_id":"\([^"]*\)".*/\1/p')
+    if [ -n "$id" ]; then
+        printf '{"type":"control_response","response":{"subtype":"success","request_id":"%s","response":{}}}\n' "$id"
+    fi
+done
+"#;
+
+    fn agent() -> AgentInfo {
+        AgentInfo {
+            id: "agent-1".to_string(),
+            name: "worker".to_string(),
+            agent_type: AgentType::Developer,
+            status: "running".to_string(),
+            model: None,
+            capabilities: Vec::new(),
+            started_at: chrono::Utc::now().to_rfc3339(),
+            metrics: None,
+            restart_policy: None,
+            restarts: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_launch_heartbeat_and_shutdown() {
+        let dir = tempfile::tempdir().unwrap();
+        let cli = dir.path().join("claude");
+        std::fs::write(&cli, FAKE_CLI).unwrap();
+        std::fs::set_permissions(&cli, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let launcher = ClaudeLauncher::new().with_cli_path(&cli).with_working_dir(dir.path());
+        let handle = launcher.launch(&agent()).await.unwrap();
+        assert!(handle.is_alive());
+        for _ in 0..3 {
+            handle.heartbeat().await.unwrap();
+        }
+
+        handle.shutdown().await;
+        assert!(!handle.is_alive());
+    }
+
+    #[tokio::test]
+    async fn test_launch_fails_without_cli() {
+        let launcher = ClaudeLauncher::new().with_cli_path("/nonexistent/claude");
+        assert!(launcher.launch(&agent()).await.is_err());
+    }
+}
@@ -1,5 +1,7 @@
 //! Command modules for Axon CLI
 
+pub mod agent_supervisor;
+pub mod claude_agent;
 pub mod config;
 pub mod output;
 pub mod runtime_manager;
@@ -17,13 +19,19 @@ use tracing::info;
 use serde_json::json;
 
 use crate::agents::AgentType;
+use crate::commands::agent_supervisor::SupervisorConfig;
+use crate::commands::claude_agent::ClaudeLauncher;
 use crate::commands::output::OutputFormatArg;
 use crate::commands::runtime_manager_impl::RuntimeManager;
 use crate::commands::server_manager::ServerManager;
+use crate::monitoring::{TelemetryStore, render_samples};
 
 // Global runtime manager
 lazy_static::lazy_static! {
-    static ref RUNTIME_MANAGER: Arc<RuntimeManager> = Arc::new(RuntimeManager::new());
+    static ref RUNTIME_MANAGER: Arc<RuntimeManager> = Arc::new(RuntimeManager::with_launcher(
+        Arc::new(ClaudeLauncher::new()),
+        SupervisorConfig::default(),
+    ));
     static ref SERVER_MANAGER: Arc<ServerManager> = Arc::new(ServerManager::new());
 }
 
@@ -102,6 +110,7 @@ pub async fn agent_start(
     capabilities: Option<String>,
     model: Option<String>,
     max_tasks: usize,
+    restart_policy: Option<agent_supervisor::RestartPolicy>,
 ) -> Result<()> {
     info!("Starting agent: {} (type: {:?})", name, agent_type);
 
@@ -115,6 +124,7 @@ pub async fn agent_start(
         caps,
         model,
         max_tasks,
+        restart_policy,
     ).await?;
 
     println!("✓ Agent '{}' started successfully", name);
@@ -172,6 +182,20 @@ pub async fn agent_list(
                         println!("  Model: {}", agent.model.as_deref().unwrap_or("default"));
                         println!("  Capabilities: {}", agent.capabilities.join(", "));
                         println!("  Started: {}", agent.started_at);
+                        if let Some(policy) = &agent.restart_policy {
+                            println!(
+                                "  Restart policy: max {} in {:?}, backoff {:?}",
+                                policy.max_restarts, policy.window, policy.backoff
+                            );
+                        }
+                        if let Some(last) = agent.restarts.last() {
+                            println!(
+                                "  Restarts: {} (last at {}: {})",
+                                agent.restarts.len(),
+                                last.at,
+                                last.reason
+                            );
+                        }
                         if let Some(metrics) = agent.metrics {
                             println!("  Tasks: {}", metrics.tasks_completed);
                             println!("  Errors: {}", metrics.errors);
@@ -340,6 +364,20 @@ pub async fn workflow_status(workflow_id: String, format: OutputFormatArg) -> Re
             }
             println!("Progress: {}/{} tasks", status.tasks_completed, status.total_tasks);
 
+            if !status.tasks.is_empty() {
+                println!("\nTasks:");
+                for task in &status.tasks {
+                    print_task_progress(task, "  ");
+                    if !task.items.is_empty() {
+                        let succeeded = task.items.iter().filter(|item| item.status == "success").count();
+                        println!("    items {}/{} succeeded", succeeded, task.items.len());
+                        for item in &task.items {
+                            print_task_progress(item, "    ");
+                        }
+                    }
+                }
+            }
+
             if !status.current_tasks.is_empty() {
                 println!("\nCurrent Tasks:");
                 for task in &status.current_tasks {
@@ -356,6 +394,18 @@ pub async fn workflow_status(workflow_id: String, format: OutputFormatArg) -> Re
     Ok(())
 }
 
+fn print_task_progress(task: &runtime_manager_impl::TaskProgress, indent: &str) {
+    let detail = match (&task.error, task.status.as_str()) {
+        (Some(reason), "skipped") => format!(" ({})", reason),
+        (Some(error), _) => format!(" - {}", error),
+        (None, _) => String::new(),
+    };
+    println!(
+        "{}{:<24} {:<8} attempts: {}{}",
+        indent, task.id, task.status, task.attempts, detail
+    );
+}
+
 pub async fn workflow_cancel(workflow_id: String) -> Result<()> {
     RUNTIME_MANAGER.cancel_workflow(&workflow_id).await?;
     println!("✓ Workflow '{}' cancelled", workflow_id);
@@ -376,8 +426,8 @@ pub async fn workflow_validate(workflow: PathBuf) -> Result<()> {
     Ok(())
 }
 
-/// Validate workflow content structure
-fn validate_workflow_content(content: &str) -> Result<()> {
+/// Validate workflow content structure, returning the parsed workflow
+fn validate_workflow_content(content: &str) -> Result<crate::orchestration::workflow::Workflow> {
     // Try to parse as JSON first
     let workflow: crate::orchestration::workflow::Workflow =
         if let Ok(wf) = serde_json::from_str(content) {
@@ -414,6 +464,14 @@ fn validate_workflow_content(content: &str) -> Result<()> {
             return Err(anyhow::anyhow!("Dependency references non-existent task: {}", task_id));
         }
         for dep in deps {
+            if let Some((parent, _)) = crate::orchestration::ForEach::parse_child_id(dep)
+                && workflow.tasks.iter().any(|t| t.id == parent && t.for_each.is_some())
+            {
+                return Err(anyhow::anyhow!(
+                    "Task {} depends on {}, an instance of map task {}; depend on {} instead",
+                    task_id, dep, parent, parent
+                ));
+            }
             if !task_ids.contains(dep.as_str()) {
                 return Err(anyhow::anyhow!("Task {} depends on non-existent task: {}", task_id, dep));
             }
@@ -425,7 +483,10 @@ fn validate_workflow_content(content: &str) -> Result<()> {
         return Err(anyhow::anyhow!("Workflow contains circular dependencies"));
     }
 
-    Ok(())
+    // Validate `when` conditions, `for_each` settings and retry policies
+    crate::orchestration::DagValidator::new().validate(&workflow)?;
+
+    Ok(workflow)
 }
 
 /// Check for circular dependencies using depth-first search
@@ -545,7 +606,7 @@ pub async fn show_status(detailed: bool, format: OutputFormatArg) -> Result<()>
     let workflows = RUNTIME_MANAGER.list_workflows(None).await?;
     let server_running = SERVER_MANAGER.get_server_pid().await.is_some();
 
-    let status = json!({
+    let mut status = json!({
         "agents": {
             "total": agents.len(),
             "running": agents.iter().filter(|a| a.status == "running").count(),
@@ -563,6 +624,10 @@ pub async fn show_status(detailed: bool, format: OutputFormatArg) -> Result<()>
         }
     });
 
+    if detailed {
+        status["cortex"] = cortex_status().await;
+    }
+
     match format {
         OutputFormatArg::Json => {
             println!("{}", serde_json::to_string_pretty(&status)?);
@@ -606,6 +671,33 @@ pub async fn show_status(detailed: bool, format: OutputFormatArg) -> Result<()>
                         println!("  - {} ({})", wf.name, wf.id);
                     }
                 }
+
+                let cortex = &status["cortex"];
+                println!();
+                println!("Cortex:");
+                println!("  URL: {}", cortex["url"].as_str().unwrap_or("unknown"));
+                if let Some(error) = cortex["error"].as_str() {
+                    println!("  Status: Unavailable ({})", error);
+                } else {
+                    let cache = &cortex["cache"];
+                    let online = cortex["online"].as_bool().unwrap_or(false);
+                    println!("  Status: {}", if online { "Online" } else { "Offline (serving cache)" });
+                    println!(
+                        "  Cache: {} entries, {:.1}/{:.1} MB, TTL {}s",
+                        cache["entries"],
+                        cache["size_bytes"].as_f64().unwrap_or(0.0) / (1024.0 * 1024.0),
+                        cache["capacity_bytes"].as_f64().unwrap_or(0.0) / (1024.0 * 1024.0),
+                        cache["ttl_seconds"]
+                    );
+                    println!(
+                        "  Cache hits: {} fresh, {} stale, {} misses ({:.0}% hit rate), {} evictions",
+                        cache["hits"],
+                        cache["stale_hits"],
+                        cache["misses"],
+                        cortex["hit_rate"].as_f64().unwrap_or(0.0) * 100.0,
+                        cache["evictions"]
+                    );
+                }
             }
         }
     }
@@ -613,6 +705,30 @@ pub async fn show_status(detailed: bool, format: OutputFormatArg) -> Result<()>
     Ok(())
 }
 
+/// Cortex connectivity and response cache statistics for `status --detailed`
+async fn cortex_status() -> serde_json::Value {
+    let config = crate::cortex_bridge::CortexConfig::from_global_config()
+        .await
+        .unwrap_or_default();
+    let url = config.base_url.clone();
+
+    match crate::cortex_bridge::CortexBridge::connect_or_offline(config).await {
+        Ok(bridge) => {
+            let stats = bridge.cache_stats();
+            json!({
+                "url": url,
+                "online": !bridge.is_offline(),
+                "hit_rate": stats.hit_rate(),
+                "cache": stats,
+            })
+        }
+        Err(e) => json!({
+            "url": url,
+            "error": e.to_string(),
+        }),
+    }
+}
+
 // Config commands
 pub async fn config_get(key: String) -> Result<()> {
     let config = config::ConfigManager::load().await?;
@@ -723,6 +839,11 @@ pub async fn monitor_metrics(agent_id: Option<String>, format: OutputFormatArg)
 }
 
 pub async fn monitor_telemetry(range: u64, format: OutputFormatArg) -> Result<()> {
+    // The history is written by the API server; without it only live state is shown
+    match TelemetryStore::open_default().await {
+        Ok(store) => RUNTIME_MANAGER.set_telemetry_store(Arc::new(store)),
+        Err(e) => info!("Telemetry history unavailable: {}", e),
+    }
     let telemetry = RUNTIME_MANAGER.get_telemetry(range).await?;
 
     match format {
@@ -737,6 +858,7 @@ pub async fn monitor_telemetry(range: u64, format: OutputFormatArg) -> Result<()
             println!("Request Rate: {:.1} req/min", telemetry.request_rate);
             println!("Error Rate: {:.1}%", telemetry.error_rate);
             println!("Avg Latency: {:.2}ms", telemetry.avg_latency_ms);
+            println!("p95 / p99 Latency: {:.2}ms / {:.2}ms", telemetry.latency_p95_ms, telemetry.latency_p99_ms);
             println!("Active Agents: {}", telemetry.active_agents);
             println!("Active Workflows: {}", telemetry.active_workflows);
             println!();
@@ -747,6 +869,48 @@ pub async fn monitor_telemetry(range: u64, format: OutputFormatArg) -> Result<()
                     println!("  {} ({}x)", error, count);
                 }
             }
+
+            if !telemetry.history.is_empty() {
+                println!("History:");
+                for sample in &telemetry.history {
+                    println!(
+                        "  {}  {:>8.1} req/min  {:>5.1}% errors  p95 {:.0}ms",
+                        sample.timestamp.with_timezone(&chrono::Local).format("%m-%d %H:%M"),
+                        sample.request_rate(),
+                        sample.error_rate(),
+                        sample.latency_p95_ms
+                    );
+                }
+                println!();
+            }
+
+            if !telemetry.waiting_tasks.is_empty() {
+                println!("Waiting Tasks:");
+                for waiting in &telemetry.waiting_tasks {
+                    println!(
+                        "  {} - {} ({}) since {}",
+                        waiting.request.task_id,
+                        waiting.state(),
+                        waiting.request.required_capabilities.join(", "),
+                        waiting.since.format("%Y-%m-%d %H:%M:%S")
+                    );
+                }
+            }
+
+            if !telemetry.reputation.is_empty() {
+                println!("Agent Reputation:");
+                for entry in &telemetry.reputation {
+                    println!(
+                        "  {:<12} {}  score {:.2}  success {:.0}%  validation {:.0}%  {} tasks",
+                        entry.task_type,
+                        entry.agent_id,
+                        entry.score,
+                        entry.stats.success_rate * 100.0,
+                        entry.stats.validation_pass_rate * 100.0,
+                        entry.stats.samples
+                    );
+                }
+            }
         }
     }
 
@@ -868,7 +1032,18 @@ pub async fn doctor_health() -> Result<()> {
 }
 
 // Export commands
-pub async fn export_metrics(output: PathBuf, format: String) -> Result<()> {
+/// Exports the current per-agent metrics, or with `range` the telemetry
+/// history of the last `range` minutes
+pub async fn export_metrics(output: PathBuf, format: String, range: Option<u64>) -> Result<()> {
+    if let Some(range) = range {
+        let store = TelemetryStore::open_default().await?;
+        let to = chrono::Utc::now();
+        let samples = store.query(to - chrono::Duration::minutes(range as i64), to).await?;
+        fs::write(&output, render_samples(&samples, &format)?).await?;
+        println!("✓ {} telemetry samples exported to: {}", samples.len(), output.display());
+        return Ok(());
+    }
+
     let metrics = RUNTIME_MANAGER.get_all_metrics().await?;
 
     let content = match format.as_str() {
@@ -1050,9 +1225,15 @@ pub async fn mcp_stdio() -> Result<()> {
         ..Default::default()
     };
 
-    let cortex = match crate::cortex_bridge::CortexBridge::new(cortex_config).await {
+    // Start even if Cortex went away again, answering from the cache until
+    // it is back
+    let cortex = match crate::cortex_bridge::CortexBridge::connect_or_offline(cortex_config).await {
         Ok(bridge) => {
-            tracing::info!("Successfully connected to Cortex");
+            if bridge.is_offline() {
+                tracing::warn!("Cortex is unreachable, serving cached queries only");
+            } else {
+                tracing::info!("Successfully connected to Cortex");
+            }
             Arc::new(bridge)
         }
         Err(e) => {
@@ -1123,7 +1304,7 @@ pub async fn mcp_http(address: String, port: u16) -> Result<()> {
         base_url: cortex_url,
         ..Default::default()
     };
-    let cortex = Arc::new(crate::cortex_bridge::CortexBridge::new(cortex_config).await?);
+    let cortex = Arc::new(crate::cortex_bridge::CortexBridge::connect_or_offline(cortex_config).await?);
 
     // Create MCP server
     let server = crate::mcp_server::AxonMcpServer::new(config, cortex);
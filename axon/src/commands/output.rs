@@ -252,6 +252,7 @@ pub fn print_telemetry(telemetry: &TelemetryData) {
     println!("  Successful:       {}", telemetry.successful_requests);
     println!("  Failed:           {}", telemetry.failed_requests);
     println!("  Avg Response:     {}ms", telemetry.avg_response_time_ms);
+    println!("  p95 / p99:        {}ms / {}ms", telemetry.p95_response_time_ms, telemetry.p99_response_time_ms);
     println!();
 }
 
@@ -326,4 +327,8 @@ pub struct TelemetryData {
     pub successful_requests: u64,
     pub failed_requests: u64,
     pub avg_response_time_ms: u64,
+    #[serde(default)]
+    pub p95_response_time_ms: u64,
+    #[serde(default)]
+    pub p99_response_time_ms: u64,
 }
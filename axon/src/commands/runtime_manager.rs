@@ -8,11 +8,13 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use super::config::AxonConfig;
 use super::output::*;
+use crate::monitoring::{TelemetrySample, TelemetryStore, render_samples};
 
 /// Agent runtime manager
 pub struct AgentRuntimeManager {
     config: AxonConfig,
     agents: Arc<RwLock<HashMap<String, RunningAgent>>>,
+    telemetry: Option<Arc<TelemetryStore>>,
 }
 
 struct RunningAgent {
@@ -31,9 +33,16 @@ impl AgentRuntimeManager {
         Ok(Self {
             config,
             agents: Arc::new(RwLock::new(HashMap::new())),
+            telemetry: None,
         })
     }
 
+    /// Answer telemetry queries and exports from a persisted history
+    pub fn with_telemetry_store(mut self, store: Arc<TelemetryStore>) -> Self {
+        self.telemetry = Some(store);
+        self
+    }
+
     /// Start a new agent
     pub async fn start_agent(&mut self, config: AgentConfig) -> Result<AgentId> {
         let agent_id = AgentId::new();
@@ -232,20 +241,77 @@ impl AgentRuntimeManager {
         Ok(HashMap::new())
     }
 
-    /// Get telemetry
+    /// Get telemetry for the last `range` minutes
     pub async fn get_telemetry(&self, range: u64) -> Result<TelemetryData> {
+        let to = chrono::Utc::now();
+        let from = to - chrono::Duration::minutes(range as i64);
+        let summary = match &self.telemetry {
+            Some(store) => store.summary(from, to).await?,
+            None => None,
+        };
+        let summary = summary.unwrap_or_else(|| TelemetrySample::new(from, 0));
+
         Ok(TelemetryData {
             range_minutes: range,
-            total_requests: 0,
-            successful_requests: 0,
-            failed_requests: 0,
-            avg_response_time_ms: 0,
+            total_requests: summary.requests,
+            successful_requests: summary.requests - summary.errors,
+            failed_requests: summary.errors,
+            avg_response_time_ms: summary.avg_latency_ms.round() as u64,
+            p95_response_time_ms: summary.latency_p95_ms.round() as u64,
+            p99_response_time_ms: summary.latency_p99_ms.round() as u64,
+        })
+    }
+
+    /// Telemetry samples between `from` and `to`, combined into buckets of
+    /// `step` if given
+    pub async fn telemetry_history(
+        &self,
+        from: chrono::DateTime<chrono::Utc>,
+        to: chrono::DateTime<chrono::Utc>,
+        step: Option<std::time::Duration>,
+    ) -> Result<Vec<TelemetrySample>> {
+        let Some(store) = &self.telemetry else {
+            return Ok(Vec::new());
+        };
+        Ok(match step {
+            Some(step) => store.query_downsampled(from, to, step).await?,
+            None => store.query(from, to).await?,
         })
     }
 
-    /// Export metrics
-    pub async fn export_metrics(&self, output: &PathBuf, format: &str) -> Result<()> {
+    /// Export metrics; with a `range` in minutes the telemetry history of
+    /// that range is exported instead of the current snapshot
+    pub async fn export_metrics(&self, output: &PathBuf, format: &str, range: Option<u64>) -> Result<()> {
         tracing::info!("Exporting metrics to {} (format: {})", output.display(), format);
+
+        let content = match range {
+            Some(range) => {
+                let to = chrono::Utc::now();
+                let from = to - chrono::Duration::minutes(range as i64);
+                render_samples(&self.telemetry_history(from, to, None).await?, format)?
+            }
+            None => {
+                let metrics = self.get_metrics(None).await?;
+                match format {
+                    "json" => serde_json::to_string_pretty(&metrics)?,
+                    "yaml" => serde_yaml::to_string(&metrics)?,
+                    "csv" => {
+                        let mut csv = String::from("agent_id,tasks_completed,tasks_failed,success_rate,tokens_used,total_cost_cents\n");
+                        for (id, m) in metrics {
+                            csv.push_str(&format!(
+                                "{},{},{},{},{},{}\n",
+                                id, m.tasks_completed, m.tasks_failed,
+                                m.success_rate, m.tokens_used, m.total_cost_cents
+                            ));
+                        }
+                        csv
+                    }
+                    _ => return Err(anyhow!("Unsupported format: {}", format)),
+                }
+            }
+        };
+
+        tokio::fs::write(output, content).await?;
         Ok(())
     }
 
@@ -5,16 +5,30 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use tokio::sync::{RwLock, mpsc};
 use chrono::Utc;
 
-use crate::agents::AgentType;
+use super::agent_supervisor::{
+    AgentLauncher, AgentSupervisor, RestartEvent, RestartPolicy, SupervisorConfig, TaskAssignment,
+};
+use crate::agents::{AgentId, AgentType};
+use crate::intelligence::{AgentReputation, ReputationEntry};
+use crate::monitoring::{TelemetrySample, TelemetryStore};
+use crate::quality::{PipelineReport, QualityConfig};
+use crate::orchestration::{
+    ForEach, Orchestrator, RouteDecision, TaskResult, TaskRouter, TaskScheduler, WaitingTask, WorkflowExecutor,
+};
 
 /// Runtime Manager for CLI commands
 pub struct RuntimeManager {
     agents: Arc<RwLock<HashMap<String, AgentInfo>>>,
     workflows: Arc<RwLock<HashMap<String, WorkflowInfo>>>,
+    supervisor: Arc<AgentSupervisor>,
+    router: Arc<TaskRouter>,
+    telemetry: OnceLock<Arc<TelemetryStore>>,
+    /// Shared by the router and every workflow executor
+    reputation: Arc<AgentReputation>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,6 +41,11 @@ pub struct AgentInfo {
     pub capabilities: Vec<String>,
     pub started_at: String,
     pub metrics: Option<AgentMetrics>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restart_policy: Option<RestartPolicy>,
+    /// Restarts made by the supervisor after crashes, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub restarts: Vec<RestartEvent>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,6 +65,13 @@ pub struct WorkflowInfo {
     pub status: String,
     pub started_at: String,
     pub completed_at: Option<String>,
+    #[serde(default)]
+    pub total_tasks: usize,
+    /// Per-task outcomes, filled in when the run finishes
+    #[serde(default)]
+    pub tasks: Vec<TaskProgress>,
+    #[serde(default)]
+    pub error: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -58,9 +84,53 @@ pub struct WorkflowStatus {
     pub tasks_completed: usize,
     pub total_tasks: usize,
     pub current_tasks: Vec<TaskInfo>,
+    #[serde(default)]
+    pub tasks: Vec<TaskProgress>,
     pub error: Option<String>,
 }
 
+/// Outcome of one workflow task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskProgress {
+    pub id: String,
+    /// `success`, `failed` or `skipped`
+    pub status: String,
+    pub attempts: u32,
+    /// Failure message, or why the task was skipped
+    pub error: Option<String>,
+    /// Child instances of a `for_each` task, in item order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub items: Vec<TaskProgress>,
+    /// Post-task validation rounds, if the workflow has quality checks
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub validation: Vec<PipelineReport>,
+}
+
+impl From<&TaskResult> for TaskProgress {
+    fn from(result: &TaskResult) -> Self {
+        Self {
+            id: result.task_id.clone(),
+            status: result.outcome().to_string(),
+            attempts: result.attempts,
+            error: result.error.clone(),
+            items: Vec::new(),
+            validation: result.validation.clone(),
+        }
+    }
+}
+
+impl TaskProgress {
+    /// Progress for a task and, for map tasks, each of its child instances
+    fn collect(task_id: &str, results: &HashMap<String, TaskResult>) -> Option<Self> {
+        let mut progress = Self::from(results.get(task_id)?);
+        progress.items = (0..)
+            .map_while(|index| results.get(&ForEach::child_id(task_id, index)))
+            .map(Self::from)
+            .collect();
+        Some(progress)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TaskInfo {
     pub agent: String,
@@ -75,16 +145,68 @@ pub struct TelemetryData {
     pub active_agents: usize,
     pub active_workflows: usize,
     pub top_errors: Vec<(String, u64)>,
+    /// Tasks queued until an agent with the required capabilities starts
+    #[serde(default)]
+    pub waiting_tasks: Vec<WaitingTask>,
+    #[serde(default)]
+    pub latency_p95_ms: f64,
+    #[serde(default)]
+    pub latency_p99_ms: f64,
+    /// Persisted samples over the range, combined into at most
+    /// [`HISTORY_POINTS`] buckets
+    #[serde(default)]
+    pub history: Vec<TelemetrySample>,
+    /// Agent scores per task type, best first
+    #[serde(default)]
+    pub reputation: Vec<ReputationEntry>,
 }
 
+/// Buckets the telemetry history is combined into for display
+pub const HISTORY_POINTS: u64 = 12;
+
 impl RuntimeManager {
     pub fn new() -> Self {
+        let agents = Arc::new(RwLock::new(HashMap::new()));
+        let reputation = Arc::new(AgentReputation::new());
+        Self {
+            supervisor: Arc::new(AgentSupervisor::new(SupervisorConfig::default(), None, agents.clone())),
+            agents,
+            workflows: Arc::new(RwLock::new(HashMap::new())),
+            router: Arc::new(TaskRouter::default().with_reputation(reputation.clone())),
+            telemetry: OnceLock::new(),
+            reputation,
+        }
+    }
+
+    /// Runtime manager that launches a process for every agent and
+    /// supervises it
+    pub fn with_launcher(launcher: Arc<dyn AgentLauncher>, config: SupervisorConfig) -> Self {
+        let agents = Arc::new(RwLock::new(HashMap::new()));
+        let reputation = Arc::new(AgentReputation::new());
         Self {
-            agents: Arc::new(RwLock::new(HashMap::new())),
+            supervisor: Arc::new(AgentSupervisor::new(config, Some(launcher), agents.clone())),
+            agents,
             workflows: Arc::new(RwLock::new(HashMap::new())),
+            router: Arc::new(TaskRouter::default().with_reputation(reputation.clone())),
+            telemetry: OnceLock::new(),
+            reputation,
         }
     }
 
+    /// Route tasks with `router` instead of the default least-loaded one
+    pub fn with_router(mut self, router: Arc<TaskRouter>) -> Self {
+        self.router = router;
+        self
+    }
+
+    pub fn supervisor(&self) -> &Arc<AgentSupervisor> {
+        &self.supervisor
+    }
+
+    pub fn reputation(&self) -> &Arc<AgentReputation> {
+        &self.reputation
+    }
+
     pub async fn start_agent(
         &self,
         name: String,
@@ -92,6 +214,7 @@ impl RuntimeManager {
         capabilities: Vec<String>,
         model: Option<String>,
         _max_tasks: usize,
+        restart_policy: Option<RestartPolicy>,
     ) -> Result<String> {
         let id = uuid::Uuid::new_v4().to_string();
 
@@ -101,7 +224,7 @@ impl RuntimeManager {
             agent_type,
             status: "running".to_string(),
             model,
-            capabilities,
+            capabilities: capabilities.clone(),
             started_at: Utc::now().to_rfc3339(),
             metrics: Some(AgentMetrics {
                 tasks_completed: 0,
@@ -111,10 +234,18 @@ impl RuntimeManager {
                 memory_usage_mb: 0,
                 cpu_usage_percent: 0.0,
             }),
+            restart_policy: restart_policy.clone(),
+            restarts: Vec::new(),
         };
 
-        let mut agents = self.agents.write().await;
-        agents.insert(id.clone(), agent_info);
+        self.supervisor.register(&agent_info, restart_policy).await?;
+        self.agents.write().await.insert(id.clone(), agent_info);
+        self.supervisor.ensure_running();
+
+        // The new agent may be the first able to take queued tasks
+        for (agent_id, request) in self.router.register_agent(AgentId::from_string(&id), &capabilities).await {
+            self.supervisor.assign(&agent_id.to_string(), request.into()).await?;
+        }
 
         Ok(id)
     }
@@ -123,9 +254,46 @@ impl RuntimeManager {
         let mut agents = self.agents.write().await;
         agents.remove(agent_id)
             .ok_or_else(|| anyhow::anyhow!("Agent not found: {}", agent_id))?;
+        drop(agents);
+
+        self.supervisor.unregister(agent_id).await;
+        self.router.unregister_agent(&AgentId::from_string(agent_id)).await;
         Ok(())
     }
 
+    /// Record a task as running on an agent, so it is requeued if the
+    /// agent crashes
+    pub async fn assign_task(&self, agent_id: &str, task: TaskAssignment) -> Result<()> {
+        self.supervisor.assign(agent_id, task).await
+    }
+
+    /// Place a task on the best agent with its required capabilities, or
+    /// queue it until such an agent starts
+    pub async fn route_task(&self, task: TaskAssignment) -> Result<RouteDecision> {
+        let decision = self.router.route(task.clone().into()).await;
+        if let RouteDecision::Assigned(agent_id) = &decision {
+            self.supervisor.assign(&agent_id.to_string(), task).await?;
+        }
+        Ok(decision)
+    }
+
+    /// Tasks waiting for a capable agent, oldest first
+    pub async fn waiting_tasks(&self) -> Vec<WaitingTask> {
+        self.router.waiting().await
+    }
+
+    /// Mark an assigned task as finished. Returns whether it was assigned.
+    pub async fn complete_task(&self, agent_id: &str, task_id: &str) -> bool {
+        self.router.complete(&AgentId::from_string(agent_id), task_id).await;
+        self.supervisor.complete(agent_id, task_id).await
+    }
+
+    /// Tasks returned by crashed or stopped agents, for the orchestrator to
+    /// reassign
+    pub async fn take_pending_tasks(&self) -> Vec<TaskAssignment> {
+        self.supervisor.take_pending().await
+    }
+
     pub async fn force_stop_agent(&self, agent_id: &str) -> Result<()> {
         self.stop_agent(agent_id).await
     }
@@ -181,17 +349,23 @@ impl RuntimeManager {
             .map_err(|e| anyhow::anyhow!("Failed to load config: {}", e))?;
         let log_file = config.logs_dir().join(format!("{}.log", agent_id));
 
-        // Check if log file exists
-        if !log_file.exists() {
+        // Read the log file, followed by supervisor events (crashes, restarts)
+        let mut log_lines: Vec<String> = if log_file.exists() {
+            std::fs::read_to_string(&log_file)
+                .map_err(|e| anyhow::anyhow!("Failed to read log file: {}", e))?
+                .lines()
+                .map(|s| s.to_string())
+                .collect()
+        } else {
+            Vec::new()
+        };
+        log_lines.extend(self.supervisor.log_lines(agent_id).await);
+
+        if log_lines.is_empty() {
             return Ok(vec!["No logs available yet".to_string()]);
         }
 
-        // Read the log file
-        let content = std::fs::read_to_string(&log_file)
-            .map_err(|e| anyhow::anyhow!("Failed to read log file: {}", e))?;
-
         // Get the last N lines
-        let log_lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
         let start = log_lines.len().saturating_sub(lines);
         Ok(log_lines[start..].to_vec())
     }
@@ -275,21 +449,78 @@ impl RuntimeManager {
 
     pub async fn run_workflow(
         &self,
-        _workflow_content: String,
-        _input_data: serde_json::Value,
+        workflow_content: String,
+        input_data: serde_json::Value,
     ) -> Result<String> {
+        let workflow = super::validate_workflow_content(&workflow_content)?;
         let id = uuid::Uuid::new_v4().to_string();
+        let task_order: Vec<String> = workflow.tasks.iter().map(|t| t.id.clone()).collect();
 
         let workflow_info = WorkflowInfo {
             id: id.clone(),
-            name: "Workflow".to_string(),
+            name: workflow.name.clone(),
             status: "running".to_string(),
             started_at: Utc::now().to_rfc3339(),
             completed_at: None,
+            total_tasks: task_order.len(),
+            tasks: Vec::new(),
+            error: None,
         };
 
-        let mut workflows = self.workflows.write().await;
-        workflows.insert(id.clone(), workflow_info);
+        self.workflows.write().await.insert(id.clone(), workflow_info);
+
+        // Default quality checks for workflows that don't define their own
+        let mut executor = WorkflowExecutor::new().with_reputation(self.reputation.clone());
+        let axon_json = std::path::Path::new("axon.json");
+        if axon_json.exists() {
+            match QualityConfig::from_axon_json(axon_json) {
+                Ok(Some(quality)) => executor = executor.with_quality(quality),
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Ignoring quality config in axon.json: {}", e),
+            }
+        }
+
+        let workflows = self.workflows.clone();
+        let workflow_id = id.clone();
+        tokio::spawn(async move {
+            let orchestrator = Orchestrator::new(
+                Arc::new(TaskScheduler::new()),
+                Arc::new(executor),
+            );
+            let outcome = orchestrator.execute_workflow_with_input(workflow, input_data).await;
+
+            let mut workflows = workflows.write().await;
+            let Some(info) = workflows.get_mut(&workflow_id) else {
+                return;
+            };
+            if info.status == "cancelled" {
+                return;
+            }
+
+            info.completed_at = Some(Utc::now().to_rfc3339());
+            match outcome {
+                Ok(result) => {
+                    info.tasks = task_order
+                        .iter()
+                        .filter_map(|task_id| TaskProgress::collect(task_id, &result.task_results))
+                        .collect();
+                    info.status = if result.success { "completed" } else { "failed" }.to_string();
+                    info.error = (!result.success).then(|| {
+                        let failed: Vec<&str> = info
+                            .tasks
+                            .iter()
+                            .filter(|t| t.status == "failed")
+                            .map(|t| t.id.as_str())
+                            .collect();
+                        format!("Failed tasks: {}", failed.join(", "))
+                    });
+                }
+                Err(e) => {
+                    info.status = "failed".to_string();
+                    info.error = Some(e.to_string());
+                }
+            }
+        });
 
         Ok(id)
     }
@@ -316,10 +547,11 @@ impl RuntimeManager {
             status: workflow.status.clone(),
             started_at: workflow.started_at.clone(),
             completed_at: workflow.completed_at.clone(),
-            tasks_completed: 0,
-            total_tasks: 1,
+            tasks_completed: workflow.tasks.iter().filter(|t| t.status != "failed").count(),
+            total_tasks: workflow.total_tasks,
             current_tasks: Vec::new(),
-            error: None,
+            tasks: workflow.tasks.clone(),
+            error: workflow.error.clone(),
         })
     }
 
@@ -360,17 +592,39 @@ impl RuntimeManager {
         Ok(result)
     }
 
-    pub async fn get_telemetry(&self, _range: u64) -> Result<TelemetryData> {
+    /// Answer telemetry queries from a persisted history; only the first
+    /// store set is used
+    pub fn set_telemetry_store(&self, store: Arc<TelemetryStore>) {
+        let _ = self.telemetry.set(store);
+    }
+
+    /// Live state plus request figures for the last `range` minutes from the
+    /// telemetry history, if one is set
+    pub async fn get_telemetry(&self, range: u64) -> Result<TelemetryData> {
+        let to = Utc::now();
+        let from = to - chrono::Duration::minutes(range as i64);
+        let samples = match self.telemetry.get() {
+            Some(store) => store.query(from, to).await?,
+            None => Vec::new(),
+        };
+        let summary = TelemetrySample::combine(&samples).unwrap_or_else(|| TelemetrySample::new(from, 0));
+        let step = std::time::Duration::from_secs((range * 60 / HISTORY_POINTS).max(60));
+
         let agents = self.agents.read().await;
         let workflows = self.workflows.read().await;
 
         Ok(TelemetryData {
-            request_rate: 0.0,
-            error_rate: 0.0,
-            avg_latency_ms: 0.0,
+            request_rate: summary.request_rate(),
+            error_rate: summary.error_rate(),
+            avg_latency_ms: summary.avg_latency_ms,
             active_agents: agents.len(),
             active_workflows: workflows.len(),
-            top_errors: Vec::new(),
+            top_errors: self.supervisor.top_errors(10).await,
+            waiting_tasks: self.router.waiting().await,
+            latency_p95_ms: summary.latency_p95_ms,
+            latency_p99_ms: summary.latency_p99_ms,
+            history: crate::monitoring::downsample(&samples, step),
+            reputation: self.reputation.report().entries,
         })
     }
 }
@@ -5,6 +5,7 @@
 //! - Simple majority voting
 //! - Weighted voting
 //! - Byzantine fault tolerance
+//! - Quorum voting over the message bus
 //! - Conflict resolution
 
 use std::collections::HashMap;
@@ -18,10 +19,12 @@ use futures::future::join_all;
 pub mod voting;
 pub mod sangha;
 pub mod conflict;
+pub mod quorum;
 
 pub use voting::*;
 pub use sangha::*;
 pub use conflict::*;
+pub use quorum::*;
 
 use crate::agents::AgentId;
 
@@ -469,6 +472,12 @@ pub enum ConsensusError {
     #[error("Insufficient quorum: required {required}, available {available}")]
     InsufficientQuorum { required: f32, available: usize },
 
+    #[error("Invalid proposal: {0}")]
+    InvalidProposal(String),
+
+    #[error("Communication error: {0}")]
+    Communication(String),
+
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
@@ -0,0 +1,819 @@
+//! Quorum voting over the message bus
+//!
+//! The proposer sends a [`DecisionProposal`] to every registered voter as a
+//! `consensus_proposal` message. Voters answer with a `consensus_vote`
+//! message (see [`DecisionProposal::reply`]) naming one of the options, or
+//! none to abstain. Voters that have not answered by the deadline, cannot be
+//! reached or name an unknown option count as abstaining.
+//!
+//! Abstentions stay in the electorate, so the winning option needs its share
+//! of *all* voters' weight to meet the [`Quorum`]. When it does not and the
+//! leading options are tied, the [`TieBreak`] decides. Every decision
+//! produces a [`ConsensusRecord`], stored as a Cortex episode when a bridge
+//! is configured.
+
+use super::*;
+use crate::coordination::{Message, MessageEnvelope, UnifiedMessageBus};
+use crate::cortex_bridge::{
+    CortexBridge, Episode, EpisodeOutcome, EpisodeType, SessionId, TokenUsage, WorkspaceId,
+};
+use crate::intelligence::ReputationTracker;
+use std::collections::{BTreeMap, HashSet};
+use tracing::{debug, info, warn};
+
+/// `message_type` of proposals sent to voters
+pub const PROPOSAL_MESSAGE: &str = "consensus_proposal";
+
+/// `message_type` of votes sent back to the proposer
+pub const VOTE_MESSAGE: &str = "consensus_vote";
+
+/// Share of the electorate an option needs to win
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Quorum {
+    /// More than half of the voters
+    SimpleMajority,
+    /// At least two thirds of the voters
+    TwoThirds,
+    /// More than half of the voters' combined reputation
+    WeightedByReputation,
+}
+
+impl Quorum {
+    fn is_met(&self, share: f64) -> bool {
+        match self {
+            Quorum::SimpleMajority | Quorum::WeightedByReputation => share > 0.5,
+            Quorum::TwoThirds => share >= 2.0 / 3.0 - 1e-9,
+        }
+    }
+}
+
+/// What to do when the leading options are tied and none reached quorum
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TieBreak {
+    /// No decision
+    Fail,
+    /// Take the proposer's preferred option if it is among the tied ones
+    ProposerDecides,
+    /// Pick one of the tied options at random
+    Random,
+}
+
+/// A decision put to the voters
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DecisionProposal {
+    pub id: String,
+    pub proposer: AgentId,
+    pub topic: String,
+    /// Whatever the voters need to make up their minds
+    pub payload: serde_json::Value,
+    pub options: Vec<String>,
+    /// Option the proposer picks under [`TieBreak::ProposerDecides`]
+    #[serde(default)]
+    pub proposer_choice: Option<String>,
+    /// Votes arriving after this are ignored
+    pub deadline: DateTime<Utc>,
+}
+
+impl DecisionProposal {
+    pub fn new(
+        proposer: AgentId,
+        topic: impl Into<String>,
+        payload: serde_json::Value,
+        options: Vec<String>,
+    ) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            proposer,
+            topic: topic.into(),
+            payload,
+            options,
+            proposer_choice: None,
+            deadline: Utc::now(),
+        }
+    }
+
+    pub fn with_proposer_choice(mut self, option: impl Into<String>) -> Self {
+        self.proposer_choice = Some(option.into());
+        self
+    }
+
+    /// Read a proposal out of a message received by a voter
+    pub fn from_envelope(envelope: &MessageEnvelope) -> Option<Self> {
+        match &envelope.payload {
+            Message::Custom { message_type, data } if message_type == PROPOSAL_MESSAGE => {
+                serde_json::from_value(data.clone()).ok()
+            }
+            _ => None,
+        }
+    }
+
+    /// Vote message answering `envelope`, the proposal message a voter
+    /// received. `option` of `None` abstains.
+    pub fn reply(
+        envelope: &MessageEnvelope,
+        voter: AgentId,
+        option: Option<String>,
+        rationale: Option<String>,
+    ) -> Option<MessageEnvelope> {
+        let proposal = Self::from_envelope(envelope)?;
+        let vote = VoteReply {
+            proposal_id: proposal.id,
+            option,
+            rationale,
+        };
+
+        Some(MessageEnvelope {
+            message_id: uuid::Uuid::new_v4().to_string(),
+            correlation_id: envelope.correlation_id.clone(),
+            causation_id: Some(envelope.message_id.clone()),
+            from: voter,
+            to: Some(envelope.from.clone()),
+            topic: None,
+            payload: Message::Custom {
+                message_type: VOTE_MESSAGE.to_string(),
+                data: serde_json::to_value(vote).ok()?,
+            },
+            timestamp: Utc::now(),
+            expires_at: envelope.expires_at,
+            attempt_count: 0,
+            metadata: HashMap::new(),
+            ..envelope.clone()
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct VoteReply {
+    proposal_id: String,
+    option: Option<String>,
+    rationale: Option<String>,
+}
+
+/// One voter's recorded ballot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Ballot {
+    pub voter: AgentId,
+    /// Chosen option, `None` for an abstention
+    pub option: Option<String>,
+    pub weight: f64,
+    /// The voter's rationale, or why the ballot counts as an abstention
+    pub rationale: Option<String>,
+}
+
+/// How a decision ended
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "result", rename_all = "snake_case")]
+pub enum ConsensusOutcome {
+    Decided {
+        option: String,
+        share: f64,
+        /// Set when the option won a tie rather than reaching quorum
+        tie_break: Option<TieBreak>,
+    },
+    /// The leading option fell short of the quorum
+    NoQuorum { leader: Option<String>, share: f64 },
+    /// The leading options were tied and the tie-break did not settle it
+    Tied { options: Vec<String> },
+}
+
+impl ConsensusOutcome {
+    pub fn decided_option(&self) -> Option<&str> {
+        match self {
+            ConsensusOutcome::Decided { option, .. } => Some(option),
+            _ => None,
+        }
+    }
+}
+
+/// Audit record of one decision
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsensusRecord {
+    pub proposal: DecisionProposal,
+    pub quorum: Quorum,
+    pub tie_break: TieBreak,
+    /// One ballot per voter, abstentions included
+    pub ballots: Vec<Ballot>,
+    /// Weight behind each option
+    pub tally: BTreeMap<String, f64>,
+    pub total_weight: f64,
+    pub outcome: ConsensusOutcome,
+    pub started_at: DateTime<Utc>,
+    pub decided_at: DateTime<Utc>,
+    /// Cortex episode the record was stored as
+    pub episode_id: Option<String>,
+}
+
+/// Settings for [`QuorumVoting`]
+#[derive(Debug, Clone)]
+pub struct QuorumConfig {
+    pub quorum: Quorum,
+    pub tie_break: TieBreak,
+    /// How long voters have to answer
+    pub vote_timeout: Duration,
+    pub session_id: SessionId,
+    pub workspace_id: WorkspaceId,
+}
+
+impl Default for QuorumConfig {
+    fn default() -> Self {
+        Self {
+            quorum: Quorum::SimpleMajority,
+            tie_break: TieBreak::Fail,
+            vote_timeout: Duration::from_secs(30),
+            session_id: SessionId::from("consensus".to_string()),
+            workspace_id: WorkspaceId::from("default".to_string()),
+        }
+    }
+}
+
+/// Quorum voting among registered voter agents
+pub struct QuorumVoting {
+    bus: Arc<UnifiedMessageBus>,
+    config: QuorumConfig,
+    voters: RwLock<Vec<AgentId>>,
+    reputation: Option<Arc<ReputationTracker>>,
+    cortex: Option<Arc<CortexBridge>>,
+}
+
+impl QuorumVoting {
+    pub fn new(bus: Arc<UnifiedMessageBus>, config: QuorumConfig) -> Self {
+        Self {
+            bus,
+            config,
+            voters: RwLock::new(Vec::new()),
+            reputation: None,
+            cortex: None,
+        }
+    }
+
+    /// Source of weights for [`Quorum::WeightedByReputation`]
+    pub fn with_reputation(mut self, reputation: Arc<ReputationTracker>) -> Self {
+        self.reputation = Some(reputation);
+        self
+    }
+
+    /// Store every [`ConsensusRecord`] as a Cortex episode
+    pub fn with_cortex(mut self, cortex: Arc<CortexBridge>) -> Self {
+        self.cortex = Some(cortex);
+        self
+    }
+
+    pub async fn register_voter(&self, agent_id: AgentId) {
+        let mut voters = self.voters.write().await;
+        if !voters.contains(&agent_id) {
+            voters.push(agent_id);
+        }
+    }
+
+    pub async fn unregister_voter(&self, agent_id: &AgentId) {
+        self.voters.write().await.retain(|voter| voter != agent_id);
+    }
+
+    pub async fn voters(&self) -> Vec<AgentId> {
+        self.voters.read().await.clone()
+    }
+
+    /// Put a proposal to the voters and resolve it once everyone has voted
+    /// or the vote timeout passes
+    pub async fn decide(&self, mut proposal: DecisionProposal) -> Result<ConsensusRecord> {
+        let voters = self.voters().await;
+        validate(&proposal, &voters)?;
+
+        let started_at = Utc::now();
+        proposal.deadline = started_at
+            + chrono::Duration::from_std(self.config.vote_timeout).map_err(|e| {
+                ConsensusError::InvalidProposal(format!("invalid vote timeout: {}", e))
+            })?;
+        info!(
+            "Putting proposal {} ({}) to {} voters",
+            proposal.id,
+            proposal.topic,
+            voters.len()
+        );
+
+        let replies = self.collect_votes(&proposal, &voters).await?;
+        let ballots = self.ballots(&proposal, &voters, replies).await;
+
+        let total_weight: f64 = ballots.iter().map(|b| b.weight).sum();
+        let mut tally: BTreeMap<String, f64> =
+            proposal.options.iter().map(|o| (o.clone(), 0.0)).collect();
+        for ballot in &ballots {
+            if let Some(option) = &ballot.option {
+                *tally.entry(option.clone()).or_default() += ballot.weight;
+            }
+        }
+
+        let outcome = resolve(
+            &proposal,
+            &tally,
+            total_weight,
+            self.config.quorum,
+            self.config.tie_break,
+        );
+        debug!("Proposal {} resolved: {:?}", proposal.id, outcome);
+
+        let mut record = ConsensusRecord {
+            proposal,
+            quorum: self.config.quorum,
+            tie_break: self.config.tie_break,
+            ballots,
+            tally,
+            total_weight,
+            outcome,
+            started_at,
+            decided_at: Utc::now(),
+            episode_id: None,
+        };
+
+        if let Some(cortex) = &self.cortex {
+            match cortex.store_episode(self.episode(&record)).await {
+                Ok(id) => record.episode_id = Some(id.to_string()),
+                Err(e) => warn!(
+                    "Failed to store consensus record {}: {}",
+                    record.proposal.id, e
+                ),
+            }
+        }
+
+        Ok(record)
+    }
+
+    /// Send the proposal to every voter and gather replies until all have
+    /// answered or the deadline passes. Voters that could not be reached
+    /// map to the delivery error.
+    async fn collect_votes(
+        &self,
+        proposal: &DecisionProposal,
+        voters: &[AgentId],
+    ) -> Result<HashMap<AgentId, std::result::Result<VoteReply, String>>> {
+        // Votes come back to a mailbox dedicated to this proposal
+        let mailbox = AgentId::from_string(format!("consensus-{}", proposal.id));
+        let mut inbox = self
+            .bus
+            .register_agent(mailbox.clone(), self.config.session_id.clone())
+            .await
+            .map_err(|e| ConsensusError::Communication(e.to_string()))?;
+
+        let data = serde_json::to_value(proposal).map_err(anyhow::Error::from)?;
+        let mut replies = HashMap::new();
+        for voter in voters {
+            let envelope = MessageEnvelope {
+                message_id: uuid::Uuid::new_v4().to_string(),
+                correlation_id: Some(proposal.id.clone()),
+                causation_id: None,
+                from: mailbox.clone(),
+                to: Some(voter.clone()),
+                topic: None,
+                session_id: self.config.session_id.clone(),
+                workspace_id: self.config.workspace_id.clone(),
+                payload: Message::Custom {
+                    message_type: PROPOSAL_MESSAGE.to_string(),
+                    data: data.clone(),
+                },
+                timestamp: Utc::now(),
+                expires_at: Some(proposal.deadline),
+                priority: 8,
+                attempt_count: 0,
+                max_attempts: 1,
+                metadata: HashMap::new(),
+            };
+            if let Err(e) = self.bus.send(envelope).await {
+                warn!(
+                    "Could not deliver proposal {} to {}: {}",
+                    proposal.id, voter, e
+                );
+                replies.insert(voter.clone(), Err(format!("unreachable: {}", e)));
+            }
+        }
+
+        let deadline = tokio::time::Instant::now() + self.config.vote_timeout;
+        while replies.len() < voters.len() {
+            let envelope = match tokio::time::timeout_at(deadline, inbox.recv()).await {
+                Ok(Some(envelope)) => envelope,
+                Ok(None) | Err(_) => break,
+            };
+            let Message::Custom { message_type, data } = &envelope.payload else {
+                continue;
+            };
+            if message_type != VOTE_MESSAGE
+                || !voters.contains(&envelope.from)
+                || replies.contains_key(&envelope.from)
+            {
+                continue;
+            }
+            match serde_json::from_value::<VoteReply>(data.clone()) {
+                Ok(reply) if reply.proposal_id == proposal.id => {
+                    replies.insert(envelope.from.clone(), Ok(reply));
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    replies.insert(envelope.from.clone(), Err(format!("malformed vote: {}", e)));
+                }
+            }
+        }
+
+        if let Err(e) = self.bus.unregister_agent(&mailbox).await {
+            debug!(
+                "Failed to remove mailbox for proposal {}: {}",
+                proposal.id, e
+            );
+        }
+        Ok(replies)
+    }
+
+    /// One ballot per voter, turning missing and invalid votes into
+    /// abstentions
+    async fn ballots(
+        &self,
+        proposal: &DecisionProposal,
+        voters: &[AgentId],
+        mut replies: HashMap<AgentId, std::result::Result<VoteReply, String>>,
+    ) -> Vec<Ballot> {
+        let mut ballots = Vec::with_capacity(voters.len());
+        for voter in voters {
+            let weight = match (self.config.quorum, &self.reputation) {
+                (Quorum::WeightedByReputation, Some(reputation)) => {
+                    reputation.score(voter).await as f64
+                }
+                _ => 1.0,
+            };
+            let (option, rationale) = match replies.remove(voter) {
+                Some(Ok(reply)) => match reply.option {
+                    Some(option) if !proposal.options.contains(&option) => {
+                        (None, Some(format!("unknown option '{}'", option)))
+                    }
+                    option => (option, reply.rationale),
+                },
+                Some(Err(reason)) => (None, Some(reason)),
+                None => (None, Some("no response before the deadline".to_string())),
+            };
+            ballots.push(Ballot {
+                voter: voter.clone(),
+                option,
+                weight,
+                rationale,
+            });
+        }
+        ballots
+    }
+
+    fn episode(&self, record: &ConsensusRecord) -> Episode {
+        let decided = record.outcome.decided_option().is_some();
+        Episode {
+            id: uuid::Uuid::new_v4().to_string(),
+            episode_type: EpisodeType::Task,
+            task_description: format!("Consensus: {}", record.proposal.topic),
+            agent_id: record.proposal.proposer.to_string(),
+            session_id: Some(self.config.session_id.to_string()),
+            workspace_id: self.config.workspace_id.to_string(),
+            entities_created: vec![],
+            entities_modified: vec![],
+            entities_deleted: vec![],
+            files_touched: vec![],
+            queries_made: vec![],
+            tools_used: vec![],
+            solution_summary: match &record.outcome {
+                ConsensusOutcome::Decided { option, share, .. } => {
+                    format!("Decided '{}' with {:.0}% support", option, share * 100.0)
+                }
+                ConsensusOutcome::NoQuorum { share, .. } => {
+                    format!("No option reached quorum (best {:.0}%)", share * 100.0)
+                }
+                ConsensusOutcome::Tied { options } => {
+                    format!("Tied between {}", options.join(", "))
+                }
+            },
+            outcome: if decided {
+                EpisodeOutcome::Success
+            } else {
+                EpisodeOutcome::Failure
+            },
+            success_metrics: serde_json::to_value(record).unwrap_or_default(),
+            errors_encountered: vec![],
+            lessons_learned: vec![],
+            duration_seconds: (record.decided_at - record.started_at).num_seconds() as i32,
+            tokens_used: TokenUsage::default(),
+            embedding: vec![],
+            created_at: record.started_at,
+            completed_at: Some(record.decided_at),
+        }
+    }
+}
+
+fn validate(proposal: &DecisionProposal, voters: &[AgentId]) -> Result<()> {
+    if voters.is_empty() {
+        return Err(ConsensusError::InsufficientQuorum {
+            required: 1.0,
+            available: 0,
+        });
+    }
+    if proposal.options.is_empty() {
+        return Err(ConsensusError::InvalidProposal(
+            "a proposal needs at least one option".to_string(),
+        ));
+    }
+    let unique: HashSet<&String> = proposal.options.iter().collect();
+    if unique.len() != proposal.options.len() {
+        return Err(ConsensusError::InvalidProposal(
+            "options must be unique".to_string(),
+        ));
+    }
+    if let Some(choice) = &proposal.proposer_choice
+        && !proposal.options.contains(choice)
+    {
+        return Err(ConsensusError::InvalidProposal(format!(
+            "proposer choice '{}' is not one of the options",
+            choice
+        )));
+    }
+    Ok(())
+}
+
+fn resolve(
+    proposal: &DecisionProposal,
+    tally: &BTreeMap<String, f64>,
+    total_weight: f64,
+    quorum: Quorum,
+    tie_break: TieBreak,
+) -> ConsensusOutcome {
+    let share = |weight: f64| {
+        if total_weight > 0.0 {
+            weight / total_weight
+        } else {
+            0.0
+        }
+    };
+    let best = tally.values().copied().fold(0.0, f64::max);
+    let leaders: Vec<&String> = tally
+        .iter()
+        .filter(|(_, weight)| best > 0.0 && (**weight - best).abs() < 1e-9)
+        .map(|(option, _)| option)
+        .collect();
+
+    if let [leader] = leaders.as_slice() {
+        return if quorum.is_met(share(best)) {
+            ConsensusOutcome::Decided {
+                option: leader.to_string(),
+                share: share(best),
+                tie_break: None,
+            }
+        } else {
+            ConsensusOutcome::NoQuorum {
+                leader: Some(leader.to_string()),
+                share: share(best),
+            }
+        };
+    }
+    if leaders.is_empty() {
+        return ConsensusOutcome::NoQuorum {
+            leader: None,
+            share: 0.0,
+        };
+    }
+
+    let winner = match tie_break {
+        TieBreak::Fail => None,
+        TieBreak::ProposerDecides => proposal
+            .proposer_choice
+            .as_ref()
+            .filter(|choice| leaders.contains(choice))
+            .cloned(),
+        TieBreak::Random => {
+            use rand::seq::IndexedRandom;
+            leaders
+                .choose(&mut rand::rng())
+                .map(|option| option.to_string())
+        }
+    };
+
+    match winner {
+        Some(option) => ConsensusOutcome::Decided {
+            option,
+            share: share(best),
+            tie_break: Some(tie_break),
+        },
+        None => ConsensusOutcome::Tied {
+            options: leaders.into_iter().cloned().collect(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Voter that answers every proposal with `choice`, or never answers
+    async fn spawn_voter(
+        bus: &Arc<UnifiedMessageBus>,
+        name: &str,
+        choice: Option<&'static str>,
+    ) -> AgentId {
+        let id = AgentId::from_string(name);
+        let mut inbox = bus
+            .register_agent(id.clone(), SessionId::from("consensus".to_string()))
+            .await
+            .unwrap();
+        let (bus, voter) = (bus.clone(), id.clone());
+        tokio::spawn(async move {
+            while let Some(envelope) = inbox.recv().await {
+                let Some(choice) = choice else { continue };
+                let reply = DecisionProposal::reply(
+                    &envelope,
+                    voter.clone(),
+                    Some(choice.to_string()),
+                    None,
+                )
+                .unwrap();
+                bus.send(reply).await.unwrap();
+            }
+        });
+        id
+    }
+
+    async fn voting(
+        bus: &Arc<UnifiedMessageBus>,
+        quorum: Quorum,
+        tie_break: TieBreak,
+        voters: &[(&str, Option<&'static str>)],
+    ) -> QuorumVoting {
+        let voting = QuorumVoting::new(
+            bus.clone(),
+            QuorumConfig {
+                quorum,
+                tie_break,
+                vote_timeout: Duration::from_millis(300),
+                ..Default::default()
+            },
+        );
+        for (name, choice) in voters {
+            voting
+                .register_voter(spawn_voter(bus, name, *choice).await)
+                .await;
+        }
+        voting
+    }
+
+    fn proposal() -> DecisionProposal {
+        DecisionProposal::new(
+            AgentId::from_string("lead"),
+            "storage engine",
+            serde_json::json!({"context": "pick one"}),
+            vec!["rocksdb".to_string(), "sqlite".to_string()],
+        )
+    }
+
+    #[tokio::test]
+    async fn test_majority_with_silent_voter() {
+        let bus = Arc::new(UnifiedMessageBus::new());
+        let voting = voting(
+            &bus,
+            Quorum::SimpleMajority,
+            TieBreak::Fail,
+            &[
+                ("a", Some("sqlite")),
+                ("b", Some("sqlite")),
+                ("c", Some("rocksdb")),
+                ("d", None),
+            ],
+        )
+        .await;
+
+        // 2 of 4 is not a majority once the silent voter abstains
+        let record = voting.decide(proposal()).await.unwrap();
+        assert_eq!(
+            record.outcome,
+            ConsensusOutcome::NoQuorum {
+                leader: Some("sqlite".to_string()),
+                share: 0.5
+            }
+        );
+        let silent = record
+            .ballots
+            .iter()
+            .find(|b| b.voter.to_string() == "d")
+            .unwrap();
+        assert_eq!(silent.option, None);
+        assert_eq!(
+            silent.rationale.as_deref(),
+            Some("no response before the deadline")
+        );
+
+        voting
+            .register_voter(spawn_voter(&bus, "e", Some("sqlite")).await)
+            .await;
+        let record = voting.decide(proposal()).await.unwrap();
+        assert_eq!(record.outcome.decided_option(), Some("sqlite"));
+        assert_eq!(record.tally["sqlite"], 3.0);
+        assert_eq!(record.total_weight, 5.0);
+    }
+
+    #[tokio::test]
+    async fn test_two_thirds_and_tie_breaks() {
+        let bus = Arc::new(UnifiedMessageBus::new());
+        let voters = [
+            ("a", Some("sqlite")),
+            ("b", Some("sqlite")),
+            ("c", Some("rocksdb")),
+        ];
+
+        let record = voting(&bus, Quorum::TwoThirds, TieBreak::Fail, &voters)
+            .await
+            .decide(proposal())
+            .await
+            .unwrap();
+        assert_eq!(record.outcome.decided_option(), Some("sqlite"));
+
+        let tied = [("d", Some("sqlite")), ("e", Some("rocksdb"))];
+        let record = voting(&bus, Quorum::SimpleMajority, TieBreak::Fail, &tied)
+            .await
+            .decide(proposal())
+            .await
+            .unwrap();
+        assert!(
+            matches!(record.outcome, ConsensusOutcome::Tied { ref options } if options.len() == 2)
+        );
+
+        let tied = [("f", Some("sqlite")), ("g", Some("rocksdb"))];
+        let record = voting(
+            &bus,
+            Quorum::SimpleMajority,
+            TieBreak::ProposerDecides,
+            &tied,
+        )
+        .await
+        .decide(proposal().with_proposer_choice("rocksdb"))
+        .await
+        .unwrap();
+        assert_eq!(
+            record.outcome,
+            ConsensusOutcome::Decided {
+                option: "rocksdb".to_string(),
+                share: 0.5,
+                tie_break: Some(TieBreak::ProposerDecides)
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_weighted_by_reputation() {
+        let bus = Arc::new(UnifiedMessageBus::new());
+        let reputation = Arc::new(ReputationTracker::new());
+        reputation
+            .set_score(AgentId::from_string("senior"), 0.9)
+            .await;
+        reputation
+            .set_score(AgentId::from_string("junior-1"), 0.2)
+            .await;
+        reputation
+            .set_score(AgentId::from_string("junior-2"), 0.2)
+            .await;
+
+        let voting = voting(
+            &bus,
+            Quorum::WeightedByReputation,
+            TieBreak::Fail,
+            &[
+                ("senior", Some("rocksdb")),
+                ("junior-1", Some("sqlite")),
+                ("junior-2", Some("sqlite")),
+            ],
+        )
+        .await
+        .with_reputation(reputation);
+
+        let record = voting.decide(proposal()).await.unwrap();
+        assert_eq!(record.outcome.decided_option(), Some("rocksdb"));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_proposals() {
+        let bus = Arc::new(UnifiedMessageBus::new());
+        let empty = QuorumVoting::new(bus.clone(), QuorumConfig::default());
+        assert!(matches!(
+            empty.decide(proposal()).await,
+            Err(ConsensusError::InsufficientQuorum { .. })
+        ));
+
+        let voting = voting(
+            &bus,
+            Quorum::SimpleMajority,
+            TieBreak::Fail,
+            &[("a", Some("sqlite"))],
+        )
+        .await;
+        let mut duplicate = proposal();
+        duplicate.options.push("sqlite".to_string());
+        assert!(matches!(
+            voting.decide(duplicate).await,
+            Err(ConsensusError::InvalidProposal(_))
+        ));
+        assert!(
+            voting
+                .decide(proposal().with_proposer_choice("postgres"))
+                .await
+                .is_err()
+        );
+    }
+}
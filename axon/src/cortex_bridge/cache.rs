@@ -0,0 +1,287 @@
+//! Local response cache for read-only Cortex endpoints
+//!
+//! Responses are kept in a byte-bounded LRU keyed by endpoint and normalized
+//! request body. Entries older than the TTL are refetched while Cortex is
+//! reachable, but are kept until evicted so they can still be served, marked
+//! stale, while it is offline.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// A response along with where it came from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cached<T> {
+    pub data: T,
+    pub meta: ResponseMeta,
+}
+
+impl<T> Cached<T> {
+    pub fn into_inner(self) -> T {
+        self.data
+    }
+
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Cached<U> {
+        Cached {
+            data: f(self.data),
+            meta: self.meta,
+        }
+    }
+}
+
+/// Provenance of a response
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResponseMeta {
+    /// Served from the local cache rather than fetched
+    pub cached: bool,
+    /// Served from the cache because Cortex is offline; it may be outdated
+    pub stale: bool,
+}
+
+/// Cache counters, as shown by `axon status --detailed`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CacheStats {
+    pub entries: usize,
+    pub size_bytes: usize,
+    pub capacity_bytes: usize,
+    pub ttl_seconds: u64,
+    /// Fresh entries served while online
+    pub hits: u64,
+    /// Lookups that had to go to Cortex
+    pub misses: u64,
+    /// Entries served while offline
+    pub stale_hits: u64,
+    pub evictions: u64,
+}
+
+impl CacheStats {
+    /// Share of lookups answered from the cache
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.stale_hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            (self.hits + self.stale_hits) as f64 / lookups as f64
+        }
+    }
+}
+
+struct CacheEntry {
+    value: Value,
+    size: usize,
+    stored_at: Instant,
+    /// Position in the recency order
+    tick: u64,
+}
+
+#[derive(Default)]
+struct CacheState {
+    entries: HashMap<String, CacheEntry>,
+    /// Keys by last use, oldest first
+    recency: BTreeMap<u64, String>,
+    tick: u64,
+    stats: CacheStats,
+}
+
+impl CacheState {
+    fn touch(&mut self, key: &str) {
+        self.tick += 1;
+        let tick = self.tick;
+        if let Some(entry) = self.entries.get_mut(key) {
+            self.recency.remove(&entry.tick);
+            entry.tick = tick;
+            self.recency.insert(tick, key.to_string());
+        }
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.recency.remove(&entry.tick);
+            self.stats.size_bytes -= entry.size;
+        }
+    }
+}
+
+/// Byte-bounded LRU of response bodies
+pub struct ResponseCache {
+    state: Mutex<CacheState>,
+    capacity_bytes: usize,
+    ttl: Duration,
+}
+
+impl ResponseCache {
+    /// A capacity of zero disables caching
+    pub fn new(capacity_bytes: usize, ttl: Duration) -> Self {
+        Self {
+            state: Mutex::new(CacheState {
+                stats: CacheStats {
+                    capacity_bytes,
+                    ttl_seconds: ttl.as_secs(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            capacity_bytes,
+            ttl,
+        }
+    }
+
+    /// Cache key for a request: the endpoint plus the body with object keys
+    /// sorted, so equivalent requests share an entry
+    pub fn key(endpoint: &str, body: Option<&Value>) -> String {
+        match body {
+            Some(body) => format!("{} {}", endpoint, normalize(body)),
+            None => endpoint.to_string(),
+        }
+    }
+
+    /// An entry younger than the TTL
+    pub fn get_fresh(&self, key: &str) -> Option<Value> {
+        let mut state = self.state.lock().unwrap();
+        let fresh = state
+            .entries
+            .get(key)
+            .filter(|entry| entry.stored_at.elapsed() < self.ttl)
+            .map(|entry| entry.value.clone());
+        match &fresh {
+            Some(_) => {
+                state.stats.hits += 1;
+                state.touch(key);
+            }
+            None => state.stats.misses += 1,
+        }
+        fresh
+    }
+
+    /// An entry of any age, for use while Cortex is offline
+    pub fn get_stale(&self, key: &str) -> Option<Value> {
+        let mut state = self.state.lock().unwrap();
+        let value = state.entries.get(key).map(|entry| entry.value.clone());
+        if value.is_some() {
+            state.stats.stale_hits += 1;
+            state.touch(key);
+        }
+        value
+    }
+
+    pub fn insert(&self, key: String, value: Value) {
+        let size = key.len() + value.to_string().len();
+        if size > self.capacity_bytes {
+            return;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        state.remove(&key);
+        while state.stats.size_bytes + size > self.capacity_bytes {
+            let Some((_, oldest)) = state.recency.pop_first() else {
+                break;
+            };
+            if let Some(entry) = state.entries.remove(&oldest) {
+                state.stats.size_bytes -= entry.size;
+                state.stats.evictions += 1;
+            }
+        }
+
+        state.tick += 1;
+        let tick = state.tick;
+        state.recency.insert(tick, key.clone());
+        state.entries.insert(
+            key,
+            CacheEntry {
+                value,
+                size,
+                stored_at: Instant::now(),
+                tick,
+            },
+        );
+        state.stats.size_bytes += size;
+    }
+
+    pub fn clear(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.entries.clear();
+        state.recency.clear();
+        state.stats.size_bytes = 0;
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let state = self.state.lock().unwrap();
+        CacheStats {
+            entries: state.entries.len(),
+            ..state.stats.clone()
+        }
+    }
+}
+
+/// Compact JSON with object keys in sorted order
+fn normalize(value: &Value) -> String {
+    match value {
+        Value::Object(map) => {
+            let mut fields: Vec<(&String, &Value)> = map.iter().collect();
+            fields.sort_by(|a, b| a.0.cmp(b.0));
+            let fields: Vec<String> = fields
+                .into_iter()
+                .map(|(key, value)| format!("{}:{}", Value::String(key.clone()), normalize(value)))
+                .collect();
+            format!("{{{}}}", fields.join(","))
+        }
+        Value::Array(values) => {
+            let values: Vec<String> = values.iter().map(normalize).collect();
+            format!("[{}]", values.join(","))
+        }
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_key_ignores_field_order() {
+        let a = json!({"query": "auth", "filters": {"types": ["fn"], "limit": 5}});
+        let b = json!({"filters": {"limit": 5, "types": ["fn"]}, "query": "auth"});
+        assert_eq!(
+            ResponseCache::key("/search/semantic", Some(&a)),
+            ResponseCache::key("/search/semantic", Some(&b))
+        );
+        assert_ne!(
+            ResponseCache::key("/search/semantic", Some(&a)),
+            ResponseCache::key("/search/semantic", Some(&json!({"query": "auth"})))
+        );
+    }
+
+    #[test]
+    fn test_lru_eviction_by_size() {
+        let cache = ResponseCache::new(40, Duration::from_secs(60));
+        cache.insert("a".to_string(), json!("0123456789"));
+        cache.insert("b".to_string(), json!("0123456789"));
+        cache.insert("c".to_string(), json!("0123456789"));
+
+        // Using "a" makes "b" the least recently used
+        assert!(cache.get_fresh("a").is_some());
+        cache.insert("d".to_string(), json!("0123456789"));
+
+        assert!(cache.get_fresh("b").is_none());
+        assert!(cache.get_fresh("a").is_some());
+        let stats = cache.stats();
+        assert_eq!(stats.entries, 3);
+        assert_eq!(stats.evictions, 1);
+        assert!(stats.size_bytes <= 40);
+    }
+
+    #[test]
+    fn test_expired_entries_only_served_stale() {
+        let cache = ResponseCache::new(1024, Duration::ZERO);
+        cache.insert("units".to_string(), json!({"units": []}));
+
+        assert!(cache.get_fresh("units").is_none());
+        assert_eq!(cache.get_stale("units"), Some(json!({"units": []})));
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.stale_hits), (0, 1, 1));
+    }
+}
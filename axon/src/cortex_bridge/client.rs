@@ -2,12 +2,20 @@
 //!
 //! This module provides a robust HTTP client with retry logic, error handling,
 //! and response unwrapping for the Cortex API.
+//!
+//! A failed health check or connection puts the client in offline mode:
+//! requests fail fast with [`CortexError::CortexUnavailable`], except cached
+//! reads, which are served from the [`ResponseCache`] marked stale. Cortex is
+//! probed again at most every `offline_recheck_secs`.
 
+use super::cache::{CacheStats, Cached, ResponseCache, ResponseMeta};
 use super::models::*;
 use cortex_core::config::GlobalConfig;
-use reqwest::{Client as HttpClient, Response};
+use reqwest::{Client as HttpClient, RequestBuilder, Response};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
-use std::time::Duration;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use thiserror::Error;
 use tracing::{debug, error, info, warn};
 
@@ -97,6 +105,8 @@ pub struct CortexConfig {
     pub enable_websocket: bool,
     /// Reconnect WebSocket on disconnect
     pub reconnect_websocket: bool,
+    /// Minimum time between health checks while Cortex is offline
+    pub offline_recheck_secs: u64,
 }
 
 impl Default for CortexConfig {
@@ -118,6 +128,7 @@ impl Default for CortexConfig {
             retry_delay_ms: 1000,
             enable_websocket: true,
             reconnect_websocket: true,
+            offline_recheck_secs: 30,
         }
     }
 }
@@ -148,16 +159,26 @@ impl CortexConfig {
             retry_delay_ms: 1000,
             enable_websocket: true,
             reconnect_websocket: true,
+            offline_recheck_secs: 30,
         })
     }
 }
 
+/// Whether Cortex is believed reachable, shared by all client clones
+#[derive(Default)]
+struct Connectivity {
+    offline: AtomicBool,
+    last_check: Mutex<Option<Instant>>,
+}
+
 /// Internal Cortex HTTP client
 #[derive(Clone)]
 pub(crate) struct CortexClient {
     client: HttpClient,
     base_url: String,
     config: CortexConfig,
+    cache: Arc<ResponseCache>,
+    connectivity: Arc<Connectivity>,
 }
 
 impl CortexClient {
@@ -169,11 +190,17 @@ impl CortexClient {
             .build()?;
 
         let base_url = format!("{}/{}", config.base_url, config.api_version);
+        let cache = ResponseCache::new(
+            config.cache_size_mb * 1024 * 1024,
+            Duration::from_secs(config.cache_ttl_seconds),
+        );
 
         Ok(Self {
             client,
             base_url,
             config,
+            cache: Arc::new(cache),
+            connectivity: Arc::new(Connectivity::default()),
         })
     }
 
@@ -192,8 +219,16 @@ impl CortexClient {
         &self.config
     }
 
-    /// Health check
+    /// Health check. Switches the client to offline mode on failure and
+    /// back online on success.
     pub async fn health_check(&self) -> Result<HealthStatus> {
+        *self.connectivity.last_check.lock().unwrap() = Some(Instant::now());
+        let result = self.probe_health().await;
+        self.set_offline(result.is_err());
+        result
+    }
+
+    async fn probe_health(&self) -> Result<HealthStatus> {
         let response = self
             .client
             .get(format!("{}/health", self.base_url))
@@ -213,6 +248,116 @@ impl CortexClient {
         Ok(health)
     }
 
+    /// Whether the client is in offline mode
+    pub fn is_offline(&self) -> bool {
+        self.connectivity.offline.load(Ordering::SeqCst)
+    }
+
+    fn set_offline(&self, offline: bool) {
+        let was_offline = self.connectivity.offline.swap(offline, Ordering::SeqCst);
+        match (was_offline, offline) {
+            (false, true) => warn!("Cortex is unreachable, switching to offline mode"),
+            (true, false) => info!("Cortex is reachable again, leaving offline mode"),
+            _ => {}
+        }
+    }
+
+    /// Fail fast while offline, unless a new health check is due and passes
+    async fn ensure_online(&self) -> Result<()> {
+        if !self.is_offline() {
+            return Ok(());
+        }
+
+        let recheck = Duration::from_secs(self.config.offline_recheck_secs);
+        let due = self
+            .connectivity
+            .last_check
+            .lock()
+            .unwrap()
+            .is_none_or(|checked| checked.elapsed() >= recheck);
+        if due && self.health_check().await.is_ok() {
+            return Ok(());
+        }
+
+        Err(CortexError::CortexUnavailable(
+            "Cortex is offline".to_string(),
+        ))
+    }
+
+    /// Send a request, switching to offline mode if Cortex cannot be reached
+    async fn send(&self, request: RequestBuilder) -> Result<Response> {
+        self.ensure_online().await?;
+        request.send().await.map_err(|e| {
+            let error = CortexError::from(e);
+            if matches!(error, CortexError::CortexUnavailable(_) | CortexError::Timeout(_)) {
+                self.set_offline(true);
+            }
+            error
+        })
+    }
+
+    /// Response cache statistics
+    pub fn cache_stats(&self) -> CacheStats {
+        self.cache.stats()
+    }
+
+    /// Serve a read-only request from the cache when fresh, otherwise fetch
+    /// and cache it. While offline, any cached response is served marked
+    /// stale.
+    async fn cached<T, Fut>(&self, key: String, fetch: Fut) -> Result<Cached<T>>
+    where
+        T: DeserializeOwned,
+        Fut: std::future::Future<Output = Result<serde_json::Value>>,
+    {
+        let fresh = if self.is_offline() {
+            None
+        } else {
+            self.cache.get_fresh(&key)
+        };
+        let (value, meta) = match fresh {
+            Some(value) => (value, ResponseMeta { cached: true, stale: false }),
+            None => match fetch.await {
+                Ok(value) => {
+                    self.cache.insert(key, value.clone());
+                    (value, ResponseMeta::default())
+                }
+                Err(e) if self.is_offline() => {
+                    let value = self.cache.get_stale(&key).ok_or_else(|| {
+                        CortexError::CortexUnavailable(format!(
+                            "Cortex is offline and no cached response exists for {}",
+                            key
+                        ))
+                    })?;
+                    debug!("Serving stale response for {} after: {}", key, e);
+                    (value, ResponseMeta { cached: true, stale: true })
+                }
+                Err(e) => return Err(e),
+            },
+        };
+
+        Ok(Cached {
+            data: serde_json::from_value(value)?,
+            meta,
+        })
+    }
+
+    /// GET a read-only endpoint through the response cache
+    pub async fn get_cached<T: DeserializeOwned>(&self, path: &str) -> Result<Cached<T>> {
+        self.cached(ResponseCache::key(path, None), self.get(path)).await
+    }
+
+    /// POST to a read-only endpoint (such as a search) through the response
+    /// cache
+    pub async fn post_cached<B: Serialize, T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<Cached<T>> {
+        let body_value = serde_json::to_value(body)?;
+        let key = ResponseCache::key(path, Some(&body_value));
+        self.cached(key, self.post(path, &body_value)).await
+    }
+
     /// Unwrap Cortex API response envelope
     pub async fn unwrap_response<T: DeserializeOwned>(response: Response) -> Result<T> {
         #[derive(Deserialize)]
@@ -312,7 +457,7 @@ impl CortexClient {
         let url = format!("{}{}", self.base_url, path);
         debug!("GET {}", url);
 
-        let response = self.client.get(&url).send().await?;
+        let response = self.send(self.client.get(&url)).await?;
         Self::unwrap_response(response).await
     }
 
@@ -325,7 +470,7 @@ impl CortexClient {
         let url = format!("{}{}", self.base_url, path);
         debug!("POST {}", url);
 
-        let response = self.client.post(&url).json(body).send().await?;
+        let response = self.send(self.client.post(&url).json(body)).await?;
         Self::unwrap_response(response).await
     }
 
@@ -338,7 +483,7 @@ impl CortexClient {
         let url = format!("{}{}", self.base_url, path);
         debug!("PUT {}", url);
 
-        let response = self.client.put(&url).json(body).send().await?;
+        let response = self.send(self.client.put(&url).json(body)).await?;
         Self::unwrap_response(response).await
     }
 
@@ -347,7 +492,7 @@ impl CortexClient {
         let url = format!("{}{}", self.base_url, path);
         debug!("DELETE {}", url);
 
-        let response = self.client.delete(&url).send().await?;
+        let response = self.send(self.client.delete(&url)).await?;
         Self::unwrap_response(response).await
     }
 }
@@ -365,6 +510,33 @@ mod tests {
         assert_eq!(config.max_retries, 3);
     }
 
+    #[tokio::test]
+    async fn test_offline_fails_fast() {
+        let client = CortexClient::new(CortexConfig {
+            // Nothing listens on the discard port
+            base_url: "http://127.0.0.1:9".to_string(),
+            offline_recheck_secs: 3600,
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert!(client.health_check().await.is_err());
+        assert!(client.is_offline());
+
+        let write: Result<serde_json::Value> = client.post("/memory/episodes", &serde_json::json!({})).await;
+        assert!(matches!(write, Err(CortexError::CortexUnavailable(_))));
+
+        // Cached reads survive going offline, marked stale
+        let key = ResponseCache::key("/workspaces/ws/units", None);
+        client.cache.insert(key, serde_json::json!({"units": []}));
+        let read: Cached<serde_json::Value> = client.get_cached("/workspaces/ws/units").await.unwrap();
+        assert_eq!(read.meta, ResponseMeta { cached: true, stale: true });
+
+        let miss: Result<Cached<serde_json::Value>> = client.get_cached("/workspaces/ws/units/x").await;
+        assert!(matches!(miss, Err(CortexError::CortexUnavailable(_))));
+        assert_eq!(client.cache_stats().stale_hits, 1);
+    }
+
     #[test]
     fn test_is_retryable() {
         assert!(CortexClient::is_retryable(&CortexError::NetworkError(
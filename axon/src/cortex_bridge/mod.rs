@@ -11,6 +11,8 @@
 //! - **Episodic Memory**: Shared learning across all agents
 //! - **Semantic Search**: Context-aware code discovery
 //! - **Distributed Locks**: Safe coordination between agents
+//! - **Offline Degradation**: Cached read-only queries keep working while
+//!   Cortex is down; writes fail fast
 //!
 //! # Example
 //!
@@ -36,6 +38,7 @@ use tokio::sync::RwLock;
 use tracing::{info, warn};
 
 // Module declarations
+pub mod cache;
 pub mod client;
 pub mod locks;
 pub mod memory;
@@ -46,6 +49,7 @@ pub mod working_memory;
 pub mod consolidation;
 
 // Re-export key types
+pub use cache::{CacheStats, Cached, ResponseMeta};
 pub use client::{CortexConfig, CortexError, Result};
 pub use locks::{LockGuard, LockManager};
 pub use memory::MemoryManager;
@@ -120,6 +124,26 @@ impl CortexBridge {
         client.health_check().await?;
         info!("Cortex health check passed");
 
+        Ok(Self::with_client(client, config))
+    }
+
+    /// Create a CortexBridge even if Cortex is unreachable
+    ///
+    /// A failed health check starts the bridge in offline mode instead of
+    /// failing, so long-running servers keep answering from the cache and
+    /// reconnect once Cortex comes back.
+    pub async fn connect_or_offline(config: CortexConfig) -> Result<Self> {
+        info!("Initializing CortexBridge with base_url: {}", config.base_url);
+
+        let client = Arc::new(CortexClient::new(config.clone())?);
+        if let Err(e) = client.health_check().await {
+            warn!("Cortex health check failed, starting in offline mode: {}", e);
+        }
+
+        Ok(Self::with_client(client, config))
+    }
+
+    fn with_client(client: Arc<CortexClient>, config: CortexConfig) -> Self {
         // Create managers
         let session_manager = SessionManager::new(client.as_ref().clone());
         let memory_manager = MemoryManager::new(client.as_ref().clone());
@@ -128,7 +152,7 @@ impl CortexBridge {
         let working_memory_manager = WorkingMemoryManager::new(client.as_ref().clone());
         let consolidation_manager = ConsolidationManager::new(client.as_ref().clone());
 
-        Ok(Self {
+        Self {
             client,
             session_manager,
             memory_manager,
@@ -138,7 +162,7 @@ impl CortexBridge {
             consolidation_manager,
             active_sessions: Arc::new(RwLock::new(HashMap::new())),
             config,
-        })
+        }
     }
 
     /// Get the configuration
@@ -155,6 +179,16 @@ impl CortexBridge {
         self.client.health_check().await
     }
 
+    /// Whether Cortex is unreachable and the bridge is serving from cache
+    pub fn is_offline(&self) -> bool {
+        self.client.is_offline()
+    }
+
+    /// Statistics of the read-only response cache
+    pub fn cache_stats(&self) -> CacheStats {
+        self.client.cache_stats()
+    }
+
     // ========================================================================
     // Session Management
     // ========================================================================
@@ -310,6 +344,19 @@ impl CortexBridge {
         workspace_id: &WorkspaceId,
         filters: SearchFilters,
     ) -> Result<Vec<CodeSearchResult>> {
+        self.semantic_search_with_meta(query, workspace_id, filters)
+            .await
+            .map(Cached::into_inner)
+    }
+
+    /// Perform semantic code search, reporting whether the results came from
+    /// the cache and whether they are stale
+    pub async fn semantic_search_with_meta(
+        &self,
+        query: &str,
+        workspace_id: &WorkspaceId,
+        filters: SearchFilters,
+    ) -> Result<Cached<Vec<CodeSearchResult>>> {
         self.search_manager
             .semantic_search(query, workspace_id, filters)
             .await
@@ -324,6 +371,7 @@ impl CortexBridge {
         self.search_manager
             .get_code_units(workspace_id, filters)
             .await
+            .map(Cached::into_inner)
     }
 
     /// Get a specific code unit
@@ -335,6 +383,7 @@ impl CortexBridge {
         self.search_manager
             .get_code_unit(workspace_id, unit_id)
             .await
+            .map(Cached::into_inner)
     }
 
     /// Find references to a code unit
@@ -346,6 +395,7 @@ impl CortexBridge {
         self.search_manager
             .find_references(workspace_id, unit_id)
             .await
+            .map(Cached::into_inner)
     }
 
     /// Query the knowledge graph
@@ -354,7 +404,10 @@ impl CortexBridge {
         query: &str,
         parameters: serde_json::Value,
     ) -> Result<search::GraphQueryResponse> {
-        self.search_manager.query_graph(query, parameters).await
+        self.search_manager
+            .query_graph(query, parameters)
+            .await
+            .map(Cached::into_inner)
     }
 
     // ========================================================================
@@ -637,10 +690,13 @@ impl CortexBridge {
 
 impl Drop for CortexBridge {
     fn drop(&mut self) {
-        if !self.active_sessions.blocking_read().is_empty() {
+        // `blocking_read` would panic when dropped inside the runtime
+        if let Ok(sessions) = self.active_sessions.try_read()
+            && !sessions.is_empty()
+        {
             warn!(
                 "CortexBridge dropped with {} active sessions. Call shutdown() for clean closure.",
-                self.active_sessions.blocking_read().len()
+                sessions.len()
             );
         }
     }
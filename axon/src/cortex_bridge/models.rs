@@ -416,7 +416,7 @@ impl Default for SearchFilters {
 }
 
 /// Code search result
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CodeSearchResult {
     /// Code unit ID
     pub unit_id: String,
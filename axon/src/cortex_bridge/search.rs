@@ -1,7 +1,12 @@
 //! Semantic search functionality for Cortex integration
 //!
 //! This module provides semantic code search and code unit discovery.
+//!
+//! Searches and code navigation are read-only, so they go through the
+//! client's response cache and report in [`Cached::meta`] whether the result
+//! came from the cache and whether it is stale.
 
+use super::cache::Cached;
 use super::client::{CortexClient, Result};
 use super::models::*;
 use serde::{Deserialize, Serialize};
@@ -86,7 +91,7 @@ impl SearchManager {
         query: &str,
         workspace_id: &WorkspaceId,
         filters: SearchFilters,
-    ) -> Result<Vec<CodeSearchResult>> {
+    ) -> Result<Cached<Vec<CodeSearchResult>>> {
         let request = SemanticSearchRequest {
             query: query.to_string(),
             workspace_id: Some(workspace_id.0.clone()),
@@ -99,18 +104,18 @@ impl SearchManager {
             limit: 20,
         };
 
-        let response: SemanticSearchResponse = self
+        let response: Cached<SemanticSearchResponse> = self
             .client
-            .post("/search/semantic", &request)
+            .post_cached("/search/semantic", &request)
             .await?;
 
         info!(
             "Semantic search returned {} results for query: {}",
-            response.results.len(),
+            response.data.results.len(),
             query
         );
 
-        Ok(response.results)
+        Ok(response.map(|r| r.results))
     }
 
     /// Get code units from workspace
@@ -118,7 +123,7 @@ impl SearchManager {
         &self,
         workspace_id: &WorkspaceId,
         filters: UnitFilters,
-    ) -> Result<Vec<CodeUnit>> {
+    ) -> Result<Cached<Vec<CodeUnit>>> {
         let mut query_params = vec![];
 
         if let Some(unit_type) = &filters.unit_type {
@@ -138,15 +143,15 @@ impl SearchManager {
         };
 
         let path = format!("/workspaces/{}/units{}", workspace_id, query_string);
-        let response: UnitsResponse = self.client.get(&path).await?;
+        let response: Cached<UnitsResponse> = self.client.get_cached(&path).await?;
 
         info!(
             "Retrieved {} code units from workspace {}",
-            response.units.len(),
+            response.data.units.len(),
             workspace_id
         );
 
-        Ok(response.units)
+        Ok(response.map(|r| r.units))
     }
 
     /// Get a specific code unit by ID
@@ -154,11 +159,9 @@ impl SearchManager {
         &self,
         workspace_id: &WorkspaceId,
         unit_id: &str,
-    ) -> Result<CodeUnit> {
+    ) -> Result<Cached<CodeUnit>> {
         let path = format!("/workspaces/{}/units/{}", workspace_id, unit_id);
-        let unit: CodeUnit = self.client.get(&path).await?;
-
-        Ok(unit)
+        self.client.get_cached(&path).await
     }
 
     /// Search for code units by name
@@ -166,11 +169,11 @@ impl SearchManager {
         &self,
         workspace_id: &WorkspaceId,
         name: &str,
-    ) -> Result<Vec<CodeUnit>> {
+    ) -> Result<Cached<Vec<CodeUnit>>> {
         let path = format!("/workspaces/{}/units?name={}", workspace_id, urlencoding::encode(name));
-        let response: UnitsResponse = self.client.get(&path).await?;
+        let response: Cached<UnitsResponse> = self.client.get_cached(&path).await?;
 
-        Ok(response.units)
+        Ok(response.map(|r| r.units))
     }
 
     /// Get dependencies for a code unit
@@ -178,11 +181,11 @@ impl SearchManager {
         &self,
         workspace_id: &WorkspaceId,
         unit_id: &str,
-    ) -> Result<Vec<CodeUnit>> {
+    ) -> Result<Cached<Vec<CodeUnit>>> {
         let path = format!("/workspaces/{}/units/{}/dependencies", workspace_id, unit_id);
-        let response: UnitsResponse = self.client.get(&path).await?;
+        let response: Cached<UnitsResponse> = self.client.get_cached(&path).await?;
 
-        Ok(response.units)
+        Ok(response.map(|r| r.units))
     }
 
     /// Get dependents of a code unit
@@ -190,11 +193,11 @@ impl SearchManager {
         &self,
         workspace_id: &WorkspaceId,
         unit_id: &str,
-    ) -> Result<Vec<CodeUnit>> {
+    ) -> Result<Cached<Vec<CodeUnit>>> {
         let path = format!("/workspaces/{}/units/{}/dependents", workspace_id, unit_id);
-        let response: UnitsResponse = self.client.get(&path).await?;
+        let response: Cached<UnitsResponse> = self.client.get_cached(&path).await?;
 
-        Ok(response.units)
+        Ok(response.map(|r| r.units))
     }
 
     /// Query the knowledge graph
@@ -202,21 +205,21 @@ impl SearchManager {
         &self,
         query: &str,
         parameters: serde_json::Value,
-    ) -> Result<GraphQueryResponse> {
+    ) -> Result<Cached<GraphQueryResponse>> {
         let request = GraphQueryRequest {
             query: query.to_string(),
             parameters,
         };
 
-        let response: GraphQueryResponse = self
+        let response: Cached<GraphQueryResponse> = self
             .client
-            .post("/graph/query", &request)
+            .post_cached("/graph/query", &request)
             .await?;
 
         info!(
             "Graph query returned {} nodes and {} edges",
-            response.nodes.len(),
-            response.edges.len()
+            response.data.nodes.len(),
+            response.data.edges.len()
         );
 
         Ok(response)
@@ -227,7 +230,7 @@ impl SearchManager {
         &self,
         workspace_id: &WorkspaceId,
         unit_id: &str,
-    ) -> Result<Vec<CodeSearchResult>> {
+    ) -> Result<Cached<Vec<CodeSearchResult>>> {
         let path = format!("/workspaces/{}/units/{}/references", workspace_id, unit_id);
 
         #[derive(Deserialize)]
@@ -235,10 +238,10 @@ impl SearchManager {
             references: Vec<CodeSearchResult>,
         }
 
-        let response: ReferencesResponse = self.client.get(&path).await?;
-        info!("Found {} references to unit {}", response.references.len(), unit_id);
+        let response: Cached<ReferencesResponse> = self.client.get_cached(&path).await?;
+        info!("Found {} references to unit {}", response.data.references.len(), unit_id);
 
-        Ok(response.references)
+        Ok(response.map(|r| r.references))
     }
 
     /// Get call graph for a function
@@ -247,14 +250,13 @@ impl SearchManager {
         workspace_id: &WorkspaceId,
         unit_id: &str,
         depth: u32,
-    ) -> Result<GraphQueryResponse> {
+    ) -> Result<Cached<GraphQueryResponse>> {
         let path = format!(
             "/workspaces/{}/units/{}/callgraph?depth={}",
             workspace_id, unit_id, depth
         );
 
-        let response: GraphQueryResponse = self.client.get(&path).await?;
-        Ok(response)
+        self.client.get_cached(&path).await
     }
 
     /// Analyze and index code for semantic search
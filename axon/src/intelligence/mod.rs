@@ -9,6 +9,8 @@
 //! - Model Router: Selects optimal LLM provider based on task requirements
 //! - Context Optimizer: Optimizes token usage through Cortex Context 3.0
 //! - Pattern Analyzer: Extracts and applies patterns from Cortex
+//! - Reputation Tracker: Scores agents by how reliably they complete work,
+//!   per task type, to break ties when routing tasks
 
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -18,10 +20,12 @@ use tokio::sync::RwLock;
 pub mod router;
 pub mod optimizer;
 pub mod patterns;
+pub mod reputation;
 
 pub use router::*;
 pub use optimizer::*;
 pub use patterns::*;
+pub use reputation::*;
 
 /// Result type for intelligence operations
 pub type Result<T> = std::result::Result<T, IntelligenceError>;
@@ -0,0 +1,625 @@
+//! Agent reputation scores
+//!
+//! A reputation is a score in `[0.0, 1.0]` tracking how reliably an agent has
+//! completed its work, as an exponential moving average of task outcomes.
+//! Agents start at [`DEFAULT_REPUTATION`], the same neutral value agent
+//! metadata uses for `performance_score`.
+//!
+//! [`ReputationTracker`] keeps a single score per agent, which is what
+//! consensus voting weighs by. [`AgentReputation`] is finer grained: it keeps
+//! success, validation, review and latency statistics per agent and task
+//! type, and its scores break ties when routing tasks to agents.
+
+use super::*;
+use crate::agents::AgentId;
+use crate::cortex_bridge::{CortexBridge, Episode, EpisodeOutcome, EpisodeType, TokenUsage};
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+
+/// Score for agents without recorded outcomes
+pub const DEFAULT_REPUTATION: f32 = 0.5;
+
+/// Per-agent reputation scores
+pub struct ReputationTracker {
+    scores: RwLock<HashMap<AgentId, f32>>,
+    /// Weight of the latest outcome in the moving average
+    learning_rate: f32,
+}
+
+impl Default for ReputationTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ReputationTracker {
+    pub fn new() -> Self {
+        Self {
+            scores: RwLock::new(HashMap::new()),
+            learning_rate: 0.1,
+        }
+    }
+
+    pub fn with_learning_rate(mut self, learning_rate: f32) -> Self {
+        self.learning_rate = learning_rate.clamp(0.0, 1.0);
+        self
+    }
+
+    pub async fn score(&self, agent_id: &AgentId) -> f32 {
+        self.scores
+            .read()
+            .await
+            .get(agent_id)
+            .copied()
+            .unwrap_or(DEFAULT_REPUTATION)
+    }
+
+    /// Override an agent's score, e.g. when restoring it from storage
+    pub async fn set_score(&self, agent_id: AgentId, score: f32) {
+        self.scores
+            .write()
+            .await
+            .insert(agent_id, score.clamp(0.0, 1.0));
+    }
+
+    /// Move an agent's score towards 1.0 on success or 0.0 on failure.
+    /// Returns the new score.
+    pub async fn record_outcome(&self, agent_id: &AgentId, success: bool) -> f32 {
+        let mut scores = self.scores.write().await;
+        let score = scores.entry(agent_id.clone()).or_insert(DEFAULT_REPUTATION);
+        let target = if success { 1.0 } else { 0.0 };
+        *score += (target - *score) * self.learning_rate;
+        *score
+    }
+
+    /// Snapshot of every recorded score
+    pub async fn scores(&self) -> HashMap<AgentId, f32> {
+        self.scores.read().await.clone()
+    }
+}
+
+/// Agent ID under which reputation snapshots are stored in Cortex
+const SNAPSHOT_AGENT: &str = "axon-reputation";
+
+/// Task description of reputation snapshot episodes, also the search query
+/// used to find them again
+const SNAPSHOT_DESCRIPTION: &str = "Agent reputation snapshot";
+
+/// A finished task, as reported to [`AgentReputation::record`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskOutcome {
+    pub task_type: String,
+    pub duration: Duration,
+    pub success: bool,
+    /// Whether the first validation round passed, if the task was validated
+    pub validation_passed: Option<bool>,
+    /// Reviewer feedback in `[0.0, 1.0]`, if the work was reviewed
+    pub review_score: Option<f32>,
+}
+
+impl TaskOutcome {
+    pub fn new(task_type: impl Into<String>, duration: Duration, success: bool) -> Self {
+        Self {
+            task_type: task_type.into(),
+            duration,
+            success,
+            validation_passed: None,
+            review_score: None,
+        }
+    }
+
+    pub fn with_validation(mut self, passed: bool) -> Self {
+        self.validation_passed = Some(passed);
+        self
+    }
+
+    pub fn with_review(mut self, score: f32) -> Self {
+        self.review_score = Some(score.clamp(0.0, 1.0));
+        self
+    }
+}
+
+/// Rates assumed for an agent with no history on a task type.
+///
+/// The defaults are optimistic so new agents still win ties against agents
+/// with a mediocre record, and get the chance to build one of their own.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ReputationPrior {
+    pub success_rate: f32,
+    pub validation_pass_rate: f32,
+    pub review_score: f32,
+}
+
+impl Default for ReputationPrior {
+    fn default() -> Self {
+        Self {
+            success_rate: 0.9,
+            validation_pass_rate: 0.9,
+            review_score: 0.9,
+        }
+    }
+}
+
+/// Moving averages for one agent on one task type
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReputationStats {
+    /// Number of outcomes recorded
+    pub samples: u64,
+    pub success_rate: f32,
+    pub validation_pass_rate: f32,
+    pub review_score: f32,
+    /// Average task duration, `None` until the first outcome
+    pub latency_ms: Option<f64>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ReputationStats {
+    fn from_prior(prior: &ReputationPrior, now: DateTime<Utc>) -> Self {
+        Self {
+            samples: 0,
+            success_rate: prior.success_rate,
+            validation_pass_rate: prior.validation_pass_rate,
+            review_score: prior.review_score,
+            latency_ms: None,
+            updated_at: now,
+        }
+    }
+
+    /// Pull the rates back towards the prior, halving their distance from it
+    /// every `half_life` since the last update. Old evidence counts for less,
+    /// so an agent that improved (or regressed) is not stuck with its past.
+    fn decayed(&self, prior: &ReputationPrior, half_life: Duration, now: DateTime<Utc>) -> Self {
+        let age = (now - self.updated_at).to_std().unwrap_or_default();
+        if half_life.is_zero() || age.is_zero() {
+            return self.clone();
+        }
+
+        let keep = 0.5f64.powf(age.as_secs_f64() / half_life.as_secs_f64()) as f32;
+        let pull = |value: f32, towards: f32| towards + (value - towards) * keep;
+        Self {
+            success_rate: pull(self.success_rate, prior.success_rate),
+            validation_pass_rate: pull(self.validation_pass_rate, prior.validation_pass_rate),
+            review_score: pull(self.review_score, prior.review_score),
+            ..self.clone()
+        }
+    }
+}
+
+/// Turns an agent's statistics into a single score in `[0.0, 1.0]`
+pub trait ReputationScorer: Send + Sync {
+    fn score(&self, stats: &ReputationStats) -> f32;
+}
+
+/// Default scorer: a weighted blend of correctness, reviewer feedback and
+/// speed. Teams that care more about one than the others adjust the weights.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeightedScorer {
+    /// Weight of the task success and validation pass rates
+    pub correctness: f32,
+    /// Weight of reviewer feedback
+    pub review: f32,
+    /// Weight of task latency
+    pub speed: f32,
+    /// Latency that scores 0.5 on speed; faster scores higher
+    pub reference_latency: Duration,
+}
+
+impl Default for WeightedScorer {
+    fn default() -> Self {
+        Self {
+            correctness: 0.6,
+            review: 0.2,
+            speed: 0.2,
+            reference_latency: Duration::from_secs(60),
+        }
+    }
+}
+
+impl ReputationScorer for WeightedScorer {
+    fn score(&self, stats: &ReputationStats) -> f32 {
+        let total = self.correctness + self.review + self.speed;
+        if total <= 0.0 {
+            return DEFAULT_REPUTATION;
+        }
+
+        let correctness = (stats.success_rate + stats.validation_pass_rate) / 2.0;
+        let reference = self.reference_latency.as_millis() as f64;
+        // Unknown latency is treated optimistically, like the other priors
+        let speed = match stats.latency_ms {
+            Some(latency) if reference + latency > 0.0 => (reference / (reference + latency)) as f32,
+            _ => 1.0,
+        };
+
+        let weighted = correctness * self.correctness + stats.review_score * self.review + speed * self.speed;
+        (weighted / total).clamp(0.0, 1.0)
+    }
+}
+
+/// One row of a [`ReputationReport`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReputationEntry {
+    pub agent_id: AgentId,
+    pub task_type: String,
+    pub score: f32,
+    pub stats: ReputationStats,
+}
+
+/// Reputation of every agent on every task type it has worked on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReputationReport {
+    pub generated_at: DateTime<Utc>,
+    /// Sorted by task type, then best score first
+    pub entries: Vec<ReputationEntry>,
+}
+
+/// Per-(agent, task type) performance tracking.
+///
+/// Every finished task updates exponential moving averages of the agent's
+/// success rate, validation pass rate, reviewer feedback and latency for
+/// that task type. Statistics decay back towards the prior as they age.
+/// The capability router uses [`AgentReputation::score`] to pick between
+/// equally capable agents.
+pub struct AgentReputation {
+    stats: std::sync::RwLock<HashMap<(AgentId, String), ReputationStats>>,
+    scorer: Arc<dyn ReputationScorer>,
+    prior: ReputationPrior,
+    /// Weight of the latest outcome in the moving averages
+    learning_rate: f32,
+    half_life: Duration,
+}
+
+impl Default for AgentReputation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AgentReputation {
+    pub fn new() -> Self {
+        Self {
+            stats: std::sync::RwLock::new(HashMap::new()),
+            scorer: Arc::new(WeightedScorer::default()),
+            prior: ReputationPrior::default(),
+            learning_rate: 0.2,
+            half_life: Duration::from_secs(7 * 24 * 3600),
+        }
+    }
+
+    pub fn with_scorer(mut self, scorer: impl ReputationScorer + 'static) -> Self {
+        self.scorer = Arc::new(scorer);
+        self
+    }
+
+    pub fn with_prior(mut self, prior: ReputationPrior) -> Self {
+        self.prior = prior;
+        self
+    }
+
+    pub fn with_learning_rate(mut self, learning_rate: f32) -> Self {
+        self.learning_rate = learning_rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// How long it takes for half of an agent's record to be forgotten.
+    /// Zero disables decay.
+    pub fn with_half_life(mut self, half_life: Duration) -> Self {
+        self.half_life = half_life;
+        self
+    }
+
+    /// Record a finished task. Returns the agent's new score for the task type.
+    pub fn record(&self, agent_id: &AgentId, outcome: &TaskOutcome) -> f32 {
+        self.record_at(agent_id, outcome, Utc::now())
+    }
+
+    fn record_at(&self, agent_id: &AgentId, outcome: &TaskOutcome, now: DateTime<Utc>) -> f32 {
+        let mut all = self.stats.write().unwrap_or_else(|e| e.into_inner());
+        let key = (agent_id.clone(), outcome.task_type.clone());
+        let current = all
+            .get(&key)
+            .map(|stats| stats.decayed(&self.prior, self.half_life, now))
+            .unwrap_or_else(|| ReputationStats::from_prior(&self.prior, now));
+
+        let rate = self.learning_rate;
+        let ewma = |value: f32, target: f32| value + (target - value) * rate;
+        let as_target = |passed: bool| -> f32 { if passed { 1.0 } else { 0.0 } };
+        let latency = outcome.duration.as_secs_f64() * 1000.0;
+
+        let updated = ReputationStats {
+            samples: current.samples + 1,
+            success_rate: ewma(current.success_rate, as_target(outcome.success)),
+            validation_pass_rate: outcome
+                .validation_passed
+                .map_or(current.validation_pass_rate, |passed| {
+                    ewma(current.validation_pass_rate, as_target(passed))
+                }),
+            review_score: outcome
+                .review_score
+                .map_or(current.review_score, |score| ewma(current.review_score, score)),
+            latency_ms: Some(match current.latency_ms {
+                Some(average) => average + (latency - average) * rate as f64,
+                None => latency,
+            }),
+            updated_at: now,
+        };
+
+        let score = self.scorer.score(&updated);
+        all.insert(key, updated);
+        score
+    }
+
+    /// Record reviewer feedback that arrives after the task itself was recorded
+    pub fn record_review(&self, agent_id: &AgentId, task_type: &str, score: f32) -> f32 {
+        let now = Utc::now();
+        let mut all = self.stats.write().unwrap_or_else(|e| e.into_inner());
+        let stats = all
+            .entry((agent_id.clone(), task_type.to_string()))
+            .or_insert_with(|| ReputationStats::from_prior(&self.prior, now));
+        let mut updated = stats.decayed(&self.prior, self.half_life, now);
+        updated.review_score += (score.clamp(0.0, 1.0) - updated.review_score) * self.learning_rate;
+        updated.updated_at = now;
+        *stats = updated;
+        self.scorer.score(stats)
+    }
+
+    /// Current statistics, or the prior if the agent has no history on the task type
+    pub fn stats(&self, agent_id: &AgentId, task_type: &str) -> ReputationStats {
+        let now = Utc::now();
+        self.stats
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&(agent_id.clone(), task_type.to_string()))
+            .map(|stats| stats.decayed(&self.prior, self.half_life, now))
+            .unwrap_or_else(|| ReputationStats::from_prior(&self.prior, now))
+    }
+
+    pub fn score(&self, agent_id: &AgentId, task_type: &str) -> f32 {
+        self.scorer.score(&self.stats(agent_id, task_type))
+    }
+
+    /// Agents with a history on `task_type`, best first
+    pub fn rank_agents(&self, task_type: &str) -> Vec<(AgentId, f32)> {
+        let mut ranked: Vec<_> = self
+            .report()
+            .entries
+            .into_iter()
+            .filter(|entry| entry.task_type == task_type)
+            .map(|entry| (entry.agent_id, entry.score))
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.to_string().cmp(&b.0.to_string())));
+        ranked
+    }
+
+    /// Scores and statistics of every tracked agent, for the monitoring dashboard
+    pub fn report(&self) -> ReputationReport {
+        let now = Utc::now();
+        let mut entries: Vec<_> = self
+            .stats
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|((agent_id, task_type), stats)| {
+                let stats = stats.decayed(&self.prior, self.half_life, now);
+                ReputationEntry {
+                    agent_id: agent_id.clone(),
+                    task_type: task_type.clone(),
+                    score: self.scorer.score(&stats),
+                    stats,
+                }
+            })
+            .collect();
+        entries.sort_by(|a, b| {
+            a.task_type
+                .cmp(&b.task_type)
+                .then_with(|| b.score.total_cmp(&a.score))
+                .then_with(|| a.agent_id.to_string().cmp(&b.agent_id.to_string()))
+        });
+
+        ReputationReport {
+            generated_at: now,
+            entries,
+        }
+    }
+
+    /// Raw statistics, without decay applied, for persistence
+    fn snapshot(&self) -> Vec<ReputationEntry> {
+        self.stats
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|((agent_id, task_type), stats)| ReputationEntry {
+                agent_id: agent_id.clone(),
+                task_type: task_type.clone(),
+                score: self.scorer.score(stats),
+                stats: stats.clone(),
+            })
+            .collect()
+    }
+
+    /// Replace all statistics with a previously saved snapshot
+    fn restore(&self, entries: Vec<ReputationEntry>) {
+        let mut all = self.stats.write().unwrap_or_else(|e| e.into_inner());
+        all.clear();
+        all.extend(
+            entries
+                .into_iter()
+                .map(|entry| ((entry.agent_id, entry.task_type), entry.stats)),
+        );
+    }
+
+    /// Store a snapshot of the statistics in Cortex episodic memory
+    pub async fn save(&self, cortex: &CortexBridge) -> Result<()> {
+        let now = Utc::now();
+        let entries = self.snapshot();
+        let episode = Episode {
+            id: uuid::Uuid::new_v4().to_string(),
+            episode_type: EpisodeType::Task,
+            task_description: SNAPSHOT_DESCRIPTION.to_string(),
+            agent_id: SNAPSHOT_AGENT.to_string(),
+            session_id: None,
+            workspace_id: "default".to_string(),
+            entities_created: vec![],
+            entities_modified: vec![],
+            entities_deleted: vec![],
+            files_touched: vec![],
+            queries_made: vec![],
+            tools_used: vec![],
+            solution_summary: format!("Reputation of {} agent/task type pairs", entries.len()),
+            outcome: EpisodeOutcome::Success,
+            success_metrics: serde_json::json!({ "entries": entries }),
+            errors_encountered: vec![],
+            lessons_learned: vec![],
+            duration_seconds: 0,
+            tokens_used: TokenUsage::default(),
+            embedding: vec![],
+            created_at: now,
+            completed_at: Some(now),
+        };
+
+        cortex
+            .store_episode(episode)
+            .await
+            .map_err(anyhow::Error::from)?;
+        Ok(())
+    }
+
+    /// Restore the most recent snapshot stored by [`save`](Self::save).
+    /// Returns `false` if Cortex has none.
+    pub async fn load(&self, cortex: &CortexBridge) -> Result<bool> {
+        let episodes = cortex
+            .search_episodes(SNAPSHOT_DESCRIPTION, 20)
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        let Some(latest) = episodes
+            .into_iter()
+            .filter(|episode| episode.agent_id == SNAPSHOT_AGENT)
+            .max_by_key(|episode| episode.created_at)
+        else {
+            return Ok(false);
+        };
+
+        let entries: Vec<ReputationEntry> =
+            serde_json::from_value(latest.success_metrics["entries"].clone()).map_err(anyhow::Error::from)?;
+        self.restore(entries);
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_outcomes_move_score() {
+        let tracker = ReputationTracker::new().with_learning_rate(0.5);
+        let agent = AgentId::from_string("agent-1");
+
+        assert_eq!(tracker.score(&agent).await, DEFAULT_REPUTATION);
+        assert_eq!(tracker.record_outcome(&agent, true).await, 0.75);
+        assert_eq!(tracker.record_outcome(&agent, false).await, 0.375);
+
+        tracker.set_score(agent.clone(), 3.0).await;
+        assert_eq!(tracker.score(&agent).await, 1.0);
+    }
+    fn outcome(success: bool, seconds: u64) -> TaskOutcome {
+        TaskOutcome::new("development", Duration::from_secs(seconds), success)
+    }
+
+    #[test]
+    fn test_new_agents_start_optimistic() {
+        let reputation = AgentReputation::new();
+        let veteran = AgentId::from_string("veteran");
+        let newcomer = AgentId::from_string("newcomer");
+
+        // A veteran with a mixed record
+        for success in [true, false, true, false] {
+            reputation.record(&veteran, &outcome(success, 60).with_validation(success));
+        }
+
+        assert_eq!(reputation.stats(&newcomer, "development").samples, 0);
+        assert!(reputation.score(&newcomer, "development") > reputation.score(&veteran, "development"));
+        // Only agents with a history are ranked
+        assert_eq!(reputation.rank_agents("development").len(), 1);
+        assert!(reputation.rank_agents("review").is_empty());
+    }
+
+    #[test]
+    fn test_rank_agents_per_task_type() {
+        let reputation = AgentReputation::new();
+        let fast = AgentId::from_string("fast");
+        let flaky = AgentId::from_string("flaky");
+
+        for _ in 0..5 {
+            reputation.record(&fast, &outcome(true, 5).with_validation(true));
+            reputation.record(&flaky, &outcome(false, 5).with_validation(false));
+            reputation.record(&flaky, &TaskOutcome::new("review", Duration::from_secs(5), true));
+        }
+
+        let ranked = reputation.rank_agents("development");
+        assert_eq!(ranked[0].0, fast);
+        assert_eq!(ranked[1].0, flaky);
+        assert!(ranked[0].1 > ranked[1].1);
+        assert_eq!(reputation.rank_agents("review")[0].0, flaky);
+
+        let stats = reputation.stats(&fast, "development");
+        assert_eq!(stats.samples, 5);
+        assert!((stats.latency_ms.unwrap() - 5000.0).abs() < 1e-6);
+
+        let report = reputation.report();
+        assert_eq!(report.entries.len(), 3);
+        assert_eq!(report.entries[0].task_type, "development");
+        assert_eq!(report.entries[0].agent_id, fast);
+    }
+
+    #[test]
+    fn test_scorer_is_pluggable() {
+        struct SpeedOnly;
+        impl ReputationScorer for SpeedOnly {
+            fn score(&self, stats: &ReputationStats) -> f32 {
+                stats.latency_ms.map_or(1.0, |ms| (1000.0 / (1000.0 + ms)) as f32)
+            }
+        }
+
+        let reputation = AgentReputation::new().with_scorer(SpeedOnly);
+        let careful = AgentId::from_string("careful");
+        let hasty = AgentId::from_string("hasty");
+        reputation.record(&careful, &outcome(true, 60));
+        reputation.record(&hasty, &outcome(false, 1));
+
+        assert_eq!(reputation.rank_agents("development")[0].0, hasty);
+    }
+
+    #[test]
+    fn test_stats_decay_towards_prior() {
+        let reputation = AgentReputation::new()
+            .with_learning_rate(1.0)
+            .with_half_life(Duration::from_secs(3600));
+        let agent = AgentId::from_string("agent");
+        let then = Utc::now() - chrono::Duration::hours(1);
+
+        reputation.record_at(&agent, &outcome(false, 10), then);
+        let prior = ReputationPrior::default().success_rate;
+        let decayed = reputation.stats(&agent, "development").success_rate;
+        assert!((decayed - prior / 2.0).abs() < 0.01, "{}", decayed);
+
+        // Reviews arriving later still count
+        let before = reputation.score(&agent, "development");
+        assert!(reputation.record_review(&agent, "development", 0.0) < before);
+    }
+
+    #[test]
+    fn test_snapshot_restore() {
+        let reputation = AgentReputation::new();
+        let agent = AgentId::from_string("agent");
+        reputation.record(&agent, &outcome(true, 3).with_review(0.5));
+
+        let restored = AgentReputation::new();
+        restored.restore(serde_json::from_value(serde_json::json!(reputation.snapshot())).unwrap());
+        assert_eq!(restored.stats(&agent, "development").samples, 1);
+        assert_eq!(
+            restored.stats(&agent, "development").review_score,
+            reputation.stats(&agent, "development").review_score
+        );
+    }
+}
@@ -179,6 +179,18 @@ enum AgentCommands {
         /// Maximum concurrent tasks
         #[arg(long, default_value = "1")]
         max_tasks: usize,
+
+        /// Restart the agent up to this many times per window if it crashes
+        #[arg(long, default_value = "0")]
+        max_restarts: u32,
+
+        /// Window (seconds) restarts are counted over
+        #[arg(long, default_value = "300")]
+        restart_window: u64,
+
+        /// Delay (seconds) before the first restart, doubled for each further one
+        #[arg(long, default_value = "1")]
+        restart_backoff: u64,
     },
 
     /// Stop an agent
@@ -374,6 +386,11 @@ enum ExportCommands {
         /// Export format (json, csv, yaml)
         #[arg(short, long, default_value = "json")]
         format: String,
+
+        /// Export the telemetry history of the last this many minutes
+        /// instead of the current metrics
+        #[arg(short, long)]
+        range: Option<u64>,
     },
 
     /// Export workflow results
@@ -456,13 +473,22 @@ async fn run() -> Result<()> {
                 capabilities,
                 model,
                 max_tasks,
+                max_restarts,
+                restart_window,
+                restart_backoff,
             } => {
+                let restart_policy = (max_restarts > 0).then(|| agent_supervisor::RestartPolicy {
+                    max_restarts,
+                    window: std::time::Duration::from_secs(restart_window),
+                    backoff: std::time::Duration::from_secs(restart_backoff),
+                });
                 agent_start(
                     agent_type.into(),
                     name,
                     capabilities,
                     model,
                     max_tasks,
+                    restart_policy,
                 ).await?;
             }
             AgentCommands::Stop { agent_id, force } => {
@@ -553,8 +579,8 @@ async fn run() -> Result<()> {
         },
 
         Commands::Export(export_cmd) => match export_cmd {
-            ExportCommands::Metrics { output, format } => {
-                export_metrics(output, format).await?;
+            ExportCommands::Metrics { output, format, range } => {
+                export_metrics(output, format, range).await?;
             }
             ExportCommands::Workflows { output, format } => {
                 export_workflows(output, format).await?;
@@ -66,7 +66,7 @@ impl AxonMcpServer {
         let agent_status = AgentStatusTool::new(self.registry.clone());
         let agent_stop = AgentStopTool::new(self.registry.clone());
         let orchestrate = OrchestrateTool;
-        let cortex_query = CortexQueryTool;
+        let cortex_query = CortexQueryTool::new(self.cortex.clone());
         let session_create = SessionCreateTool;
         let session_merge = SessionMergeTool;
 
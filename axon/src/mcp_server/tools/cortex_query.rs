@@ -1,24 +1,49 @@
 //! Cortex Query Tool
 
+use crate::cortex_bridge::{CortexBridge, ResponseMeta, SearchFilters, WorkspaceId};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct CortexQueryInput {
     pub query: String,
+    /// Workspace to search, `default` if omitted
+    #[serde(default)]
+    pub workspace_id: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct CortexQueryOutput {
     pub results: Vec<serde_json::Value>,
+    /// Whether the results came from the local cache and whether they are
+    /// stale because Cortex is offline
+    pub metadata: ResponseMeta,
 }
 
-pub struct CortexQueryTool;
+pub struct CortexQueryTool {
+    cortex: Arc<CortexBridge>,
+}
 
 impl CortexQueryTool {
-    pub async fn query(&self, _input: CortexQueryInput) -> Result<CortexQueryOutput> {
+    pub fn new(cortex: Arc<CortexBridge>) -> Self {
+        Self { cortex }
+    }
+
+    pub async fn query(&self, input: CortexQueryInput) -> Result<CortexQueryOutput> {
+        let workspace_id = WorkspaceId::from(input.workspace_id.unwrap_or_else(|| "default".to_string()));
+        let response = self
+            .cortex
+            .semantic_search_with_meta(&input.query, &workspace_id, SearchFilters::default())
+            .await?;
+
         Ok(CortexQueryOutput {
-            results: vec![],
+            results: response
+                .data
+                .iter()
+                .map(serde_json::to_value)
+                .collect::<std::result::Result<_, _>>()?,
+            metadata: response.meta,
         })
     }
 }
@@ -1,20 +1,31 @@
 //! Dashboard and visualization support
 
 use super::*;
+use crate::intelligence::AgentReputation;
 
 pub struct Dashboard {
     metrics_collector: Arc<MetricsCollector>,
+    reputation: Option<Arc<AgentReputation>>,
 }
 
 impl Dashboard {
     pub fn new(metrics_collector: Arc<MetricsCollector>) -> Self {
-        Self { metrics_collector }
+        Self {
+            metrics_collector,
+            reputation: None,
+        }
+    }
+
+    /// Include per-task-type agent scores in the report
+    pub fn with_reputation(mut self, reputation: Arc<AgentReputation>) -> Self {
+        self.reputation = Some(reputation);
+        self
     }
 
     pub fn generate_report(&self) -> String {
         let snapshot = self.metrics_collector.snapshot();
 
-        format!(
+        let mut report = format!(
             "=== Axon Multi-Agent System Dashboard ===\n\
              Total Tasks: {}\n\
              Successful: {}\n\
@@ -32,6 +43,21 @@ impl Dashboard {
             snapshot.total_tokens,
             snapshot.total_cost_dollars,
             snapshot.timestamp
-        )
+        );
+
+        if let Some(reputation) = &self.reputation {
+            let entries = reputation.report().entries;
+            if !entries.is_empty() {
+                report.push_str("Agent Reputation:\n");
+                for entry in entries {
+                    report.push_str(&format!(
+                        "  {} / {}: {:.2} ({} tasks)\n",
+                        entry.task_type, entry.agent_id, entry.score, entry.stats.samples
+                    ));
+                }
+            }
+        }
+
+        report
     }
 }
@@ -0,0 +1,632 @@
+//! Persistent telemetry history
+//!
+//! The API server takes a sample of request throughput, errors, latency
+//! percentiles and per-agent task counts every minute and stores it in a local
+//! SQLite database. Raw samples are kept for a day and are then folded into
+//! five-minute rollups, which are kept for thirty days.
+
+use super::*;
+use cortex_core::config::GlobalConfig;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions, SqliteRow};
+use sqlx::{Row, SqlitePool};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// How often the API server takes a sample
+pub const SAMPLE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// File name of the history database under the Axon directory
+pub const TELEMETRY_DB_FILE: &str = "telemetry.db";
+
+/// Task counters of one agent at the end of a sample
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AgentTaskCounts {
+    pub completed: u64,
+    pub failed: u64,
+}
+
+/// Telemetry over one sampling window, or a rollup of several
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TelemetrySample {
+    /// Start of the window
+    pub timestamp: DateTime<Utc>,
+    /// Seconds covered by the sample
+    pub window_secs: u64,
+    pub requests: u64,
+    pub errors: u64,
+    pub avg_latency_ms: f64,
+    pub latency_p50_ms: f64,
+    pub latency_p95_ms: f64,
+    pub latency_p99_ms: f64,
+    pub active_agents: usize,
+    pub active_workflows: usize,
+    /// Cumulative task counts per agent at the end of the window
+    #[serde(default)]
+    pub agent_tasks: BTreeMap<String, AgentTaskCounts>,
+}
+
+impl TelemetrySample {
+    /// An empty sample for the window starting at `timestamp`
+    pub fn new(timestamp: DateTime<Utc>, window_secs: u64) -> Self {
+        Self {
+            timestamp,
+            window_secs,
+            requests: 0,
+            errors: 0,
+            avg_latency_ms: 0.0,
+            latency_p50_ms: 0.0,
+            latency_p95_ms: 0.0,
+            latency_p99_ms: 0.0,
+            active_agents: 0,
+            active_workflows: 0,
+            agent_tasks: BTreeMap::new(),
+        }
+    }
+
+    /// Requests per minute
+    pub fn request_rate(&self) -> f64 {
+        if self.window_secs == 0 {
+            0.0
+        } else {
+            self.requests as f64 * 60.0 / self.window_secs as f64
+        }
+    }
+
+    /// Share of failed requests in percent
+    pub fn error_rate(&self) -> f64 {
+        if self.requests == 0 {
+            0.0
+        } else {
+            self.errors as f64 * 100.0 / self.requests as f64
+        }
+    }
+
+    /// Folds consecutive samples into one starting at the first of them.
+    ///
+    /// Counts are summed, the mean and median latency are weighted by
+    /// request count, the tail percentiles and gauges keep their maximum and
+    /// agent task counts are taken from the last sample.
+    pub fn combine(samples: &[TelemetrySample]) -> Option<TelemetrySample> {
+        let first = samples.first()?;
+        let last = samples.last()?;
+        let mut combined = TelemetrySample::new(first.timestamp, 0);
+
+        for sample in samples {
+            combined.window_secs += sample.window_secs;
+            combined.requests += sample.requests;
+            combined.errors += sample.errors;
+            combined.avg_latency_ms += sample.avg_latency_ms * sample.requests as f64;
+            combined.latency_p50_ms += sample.latency_p50_ms * sample.requests as f64;
+            combined.latency_p95_ms = combined.latency_p95_ms.max(sample.latency_p95_ms);
+            combined.latency_p99_ms = combined.latency_p99_ms.max(sample.latency_p99_ms);
+            combined.active_agents = combined.active_agents.max(sample.active_agents);
+            combined.active_workflows = combined.active_workflows.max(sample.active_workflows);
+        }
+
+        if combined.requests > 0 {
+            combined.avg_latency_ms /= combined.requests as f64;
+            combined.latency_p50_ms /= combined.requests as f64;
+        }
+        combined.agent_tasks = last.agent_tasks.clone();
+
+        Some(combined)
+    }
+}
+
+/// Groups time-ordered samples into buckets of `step` aligned to the epoch
+/// and combines each bucket
+pub fn downsample(samples: &[TelemetrySample], step: Duration) -> Vec<TelemetrySample> {
+    let step_ms = (step.as_millis() as i64).max(1);
+    let mut buckets: Vec<(i64, Vec<TelemetrySample>)> = Vec::new();
+
+    for sample in samples {
+        let bucket = sample.timestamp.timestamp_millis().div_euclid(step_ms) * step_ms;
+        match buckets.last_mut() {
+            Some((start, members)) if *start == bucket => members.push(sample.clone()),
+            _ => buckets.push((bucket, vec![sample.clone()])),
+        }
+    }
+
+    buckets
+        .into_iter()
+        .filter_map(|(start, members)| {
+            let mut combined = TelemetrySample::combine(&members)?;
+            combined.timestamp =
+                DateTime::from_timestamp_millis(start).unwrap_or(combined.timestamp);
+            Some(combined)
+        })
+        .collect()
+}
+
+/// How long samples are kept
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    /// Age after which raw samples are folded into rollups
+    pub raw: Duration,
+    /// Width of a rollup
+    pub rollup_interval: Duration,
+    /// Age after which rollups are deleted
+    pub rollup: Duration,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            raw: Duration::from_secs(24 * 60 * 60),
+            rollup_interval: Duration::from_secs(5 * 60),
+            rollup: Duration::from_secs(30 * 24 * 60 * 60),
+        }
+    }
+}
+
+/// Outcome of a compaction pass
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactionStats {
+    /// Raw samples folded into rollups
+    pub samples_rolled_up: usize,
+    pub rollups_created: usize,
+    /// Rollups deleted for being older than the retention
+    pub rollups_expired: usize,
+}
+
+/// SQLite-backed telemetry history
+pub struct TelemetryStore {
+    pool: SqlitePool,
+    retention: RetentionPolicy,
+}
+
+impl TelemetryStore {
+    /// Location of the history database, `~/.ryht/axon/telemetry.db`
+    pub fn default_path() -> Result<PathBuf> {
+        let dir = GlobalConfig::axon_dir()
+            .map_err(|e| MonitoringError::Other(anyhow::anyhow!("{}", e)))?;
+        Ok(dir.join(TELEMETRY_DB_FILE))
+    }
+
+    /// Opens the history database at the default path, creating it if needed
+    pub async fn open_default() -> Result<Self> {
+        Self::open(&Self::default_path()?).await
+    }
+
+    /// Opens the history database at `path`, creating it if needed
+    pub async fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| MonitoringError::Other(e.into()))?;
+        }
+        let options = SqliteConnectOptions::new()
+            .filename(path)
+            .create_if_missing(true);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(4)
+            .connect_with(options)
+            .await?;
+        Self::init(pool).await
+    }
+
+    /// A store that lives only as long as the process, for tests
+    pub async fn in_memory() -> Result<Self> {
+        // Every connection to `:memory:` is its own database, so keep exactly one
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .min_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .connect_with(SqliteConnectOptions::new().in_memory(true))
+            .await?;
+        Self::init(pool).await
+    }
+
+    async fn init(pool: SqlitePool) -> Result<Self> {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS telemetry_samples (
+                timestamp_ms INTEGER NOT NULL,
+                rollup INTEGER NOT NULL,
+                window_secs INTEGER NOT NULL,
+                requests INTEGER NOT NULL,
+                errors INTEGER NOT NULL,
+                avg_latency_ms REAL NOT NULL,
+                latency_p50_ms REAL NOT NULL,
+                latency_p95_ms REAL NOT NULL,
+                latency_p99_ms REAL NOT NULL,
+                active_agents INTEGER NOT NULL,
+                active_workflows INTEGER NOT NULL,
+                agent_tasks TEXT NOT NULL
+            )",
+        )
+        .execute(&pool)
+        .await?;
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS telemetry_samples_by_time
+                ON telemetry_samples (rollup, timestamp_ms)",
+        )
+        .execute(&pool)
+        .await?;
+
+        Ok(Self {
+            pool,
+            retention: RetentionPolicy::default(),
+        })
+    }
+
+    pub fn with_retention(mut self, retention: RetentionPolicy) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    pub fn retention(&self) -> RetentionPolicy {
+        self.retention
+    }
+
+    /// Stores a raw sample
+    pub async fn record(&self, sample: &TelemetrySample) -> Result<()> {
+        insert(&self.pool, sample, false).await
+    }
+
+    /// Raw samples and rollups starting in `[from, to)`, oldest first
+    pub async fn query(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<TelemetrySample>> {
+        let rows = sqlx::query(
+            "SELECT * FROM telemetry_samples
+                WHERE timestamp_ms >= ? AND timestamp_ms < ?
+                ORDER BY timestamp_ms",
+        )
+        .bind(from.timestamp_millis())
+        .bind(to.timestamp_millis())
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(decode).collect()
+    }
+
+    /// Like [`query`](Self::query), with samples combined into buckets of `step`
+    pub async fn query_downsampled(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        step: Duration,
+    ) -> Result<Vec<TelemetrySample>> {
+        Ok(downsample(&self.query(from, to).await?, step))
+    }
+
+    /// All samples in `[from, to)` combined into one, if there are any
+    pub async fn summary(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Option<TelemetrySample>> {
+        Ok(TelemetrySample::combine(&self.query(from, to).await?))
+    }
+
+    /// Folds raw samples older than the raw retention into rollups and
+    /// deletes rollups older than the rollup retention
+    pub async fn compact(&self, now: DateTime<Utc>) -> Result<CompactionStats> {
+        let interval_ms = (self.retention.rollup_interval.as_millis() as i64).max(1);
+        // Align to a rollup boundary so no bucket is split across passes
+        let raw_cutoff = (now.timestamp_millis() - self.retention.raw.as_millis() as i64)
+            .div_euclid(interval_ms)
+            * interval_ms;
+        let rollup_cutoff = now.timestamp_millis() - self.retention.rollup.as_millis() as i64;
+
+        let mut tx = self.pool.begin().await?;
+
+        let rows = sqlx::query(
+            "SELECT * FROM telemetry_samples
+                WHERE rollup = 0 AND timestamp_ms < ?
+                ORDER BY timestamp_ms",
+        )
+        .bind(raw_cutoff)
+        .fetch_all(&mut *tx)
+        .await?;
+        let raw = rows.iter().map(decode).collect::<Result<Vec<_>>>()?;
+        let rollups = downsample(&raw, self.retention.rollup_interval);
+
+        for rollup in &rollups {
+            insert(&mut *tx, rollup, true).await?;
+        }
+        sqlx::query("DELETE FROM telemetry_samples WHERE rollup = 0 AND timestamp_ms < ?")
+            .bind(raw_cutoff)
+            .execute(&mut *tx)
+            .await?;
+        let expired =
+            sqlx::query("DELETE FROM telemetry_samples WHERE rollup = 1 AND timestamp_ms < ?")
+                .bind(rollup_cutoff)
+                .execute(&mut *tx)
+                .await?;
+
+        tx.commit().await?;
+
+        Ok(CompactionStats {
+            samples_rolled_up: raw.len(),
+            rollups_created: rollups.len(),
+            rollups_expired: expired.rows_affected() as usize,
+        })
+    }
+}
+
+async fn insert<'e, E>(executor: E, sample: &TelemetrySample, rollup: bool) -> Result<()>
+where
+    E: sqlx::Executor<'e, Database = sqlx::Sqlite>,
+{
+    let agent_tasks =
+        serde_json::to_string(&sample.agent_tasks).map_err(|e| MonitoringError::Other(e.into()))?;
+    sqlx::query(
+        "INSERT INTO telemetry_samples (
+            timestamp_ms, rollup, window_secs, requests, errors,
+            avg_latency_ms, latency_p50_ms, latency_p95_ms, latency_p99_ms,
+            active_agents, active_workflows, agent_tasks
+        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(sample.timestamp.timestamp_millis())
+    .bind(rollup)
+    .bind(sample.window_secs as i64)
+    .bind(sample.requests as i64)
+    .bind(sample.errors as i64)
+    .bind(sample.avg_latency_ms)
+    .bind(sample.latency_p50_ms)
+    .bind(sample.latency_p95_ms)
+    .bind(sample.latency_p99_ms)
+    .bind(sample.active_agents as i64)
+    .bind(sample.active_workflows as i64)
+    .bind(agent_tasks)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+fn decode(row: &SqliteRow) -> Result<TelemetrySample> {
+    let timestamp_ms: i64 = row.try_get("timestamp_ms")?;
+    let agent_tasks: String = row.try_get("agent_tasks")?;
+
+    Ok(TelemetrySample {
+        timestamp: DateTime::from_timestamp_millis(timestamp_ms).ok_or_else(|| {
+            MonitoringError::CollectionFailed(format!("invalid sample timestamp {}", timestamp_ms))
+        })?,
+        window_secs: row.try_get::<i64, _>("window_secs")? as u64,
+        requests: row.try_get::<i64, _>("requests")? as u64,
+        errors: row.try_get::<i64, _>("errors")? as u64,
+        avg_latency_ms: row.try_get("avg_latency_ms")?,
+        latency_p50_ms: row.try_get("latency_p50_ms")?,
+        latency_p95_ms: row.try_get("latency_p95_ms")?,
+        latency_p99_ms: row.try_get("latency_p99_ms")?,
+        active_agents: row.try_get::<i64, _>("active_agents")? as usize,
+        active_workflows: row.try_get::<i64, _>("active_workflows")? as usize,
+        agent_tasks: serde_json::from_str(&agent_tasks)
+            .map_err(|e| MonitoringError::Other(e.into()))?,
+    })
+}
+
+struct RecorderWindow {
+    started: DateTime<Utc>,
+    latencies_ms: Vec<f64>,
+    errors: u64,
+}
+
+/// Collects request outcomes between two samples
+pub struct TelemetryRecorder {
+    window: Mutex<RecorderWindow>,
+}
+
+impl TelemetryRecorder {
+    pub fn new() -> Self {
+        Self {
+            window: Mutex::new(RecorderWindow {
+                started: Utc::now(),
+                latencies_ms: Vec::new(),
+                errors: 0,
+            }),
+        }
+    }
+
+    pub fn record(&self, latency: Duration, success: bool) {
+        let mut window = self.window.lock().unwrap();
+        window.latencies_ms.push(latency.as_secs_f64() * 1000.0);
+        if !success {
+            window.errors += 1;
+        }
+    }
+
+    /// Closes the current window at `now` and returns its sample; agent and
+    /// workflow figures are left for the caller to fill in
+    pub fn take_sample(&self, now: DateTime<Utc>) -> TelemetrySample {
+        let mut window = self.window.lock().unwrap();
+        let window_secs = (now - window.started).num_seconds().max(0) as u64;
+        let mut sample = TelemetrySample::new(window.started, window_secs);
+
+        let mut latencies = std::mem::take(&mut window.latencies_ms);
+        latencies.sort_by(|a, b| a.total_cmp(b));
+        sample.requests = latencies.len() as u64;
+        sample.errors = std::mem::take(&mut window.errors);
+        if !latencies.is_empty() {
+            sample.avg_latency_ms = latencies.iter().sum::<f64>() / latencies.len() as f64;
+            sample.latency_p50_ms = percentile(&latencies, 0.50);
+            sample.latency_p95_ms = percentile(&latencies, 0.95);
+            sample.latency_p99_ms = percentile(&latencies, 0.99);
+        }
+        window.started = now;
+
+        sample
+    }
+}
+
+impl Default for TelemetryRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Nearest-rank percentile of sorted values
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = (p * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Renders samples as json, yaml or csv for export
+pub fn render_samples(samples: &[TelemetrySample], format: &str) -> Result<String> {
+    match format {
+        "json" => serde_json::to_string_pretty(samples)
+            .map_err(|e| MonitoringError::ExportFailed(e.to_string())),
+        "yaml" => {
+            serde_yaml::to_string(samples).map_err(|e| MonitoringError::ExportFailed(e.to_string()))
+        }
+        "csv" => {
+            let mut csv = String::from(
+                "timestamp,window_secs,requests,errors,request_rate,error_rate,avg_latency_ms,latency_p50_ms,latency_p95_ms,latency_p99_ms,active_agents,active_workflows\n",
+            );
+            for sample in samples {
+                csv.push_str(&format!(
+                    "{},{},{},{},{:.2},{:.2},{:.2},{:.2},{:.2},{:.2},{},{}\n",
+                    sample.timestamp.to_rfc3339(),
+                    sample.window_secs,
+                    sample.requests,
+                    sample.errors,
+                    sample.request_rate(),
+                    sample.error_rate(),
+                    sample.avg_latency_ms,
+                    sample.latency_p50_ms,
+                    sample.latency_p95_ms,
+                    sample.latency_p99_ms,
+                    sample.active_agents,
+                    sample.active_workflows
+                ));
+            }
+            Ok(csv)
+        }
+        _ => Err(MonitoringError::ExportFailed(format!(
+            "Unsupported format: {}",
+            format
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn sample(timestamp: DateTime<Utc>, requests: u64, errors: u64, p95: f64) -> TelemetrySample {
+        let mut sample = TelemetrySample::new(timestamp, 60);
+        sample.requests = requests;
+        sample.errors = errors;
+        sample.avg_latency_ms = 10.0;
+        sample.latency_p50_ms = 8.0;
+        sample.latency_p95_ms = p95;
+        sample.latency_p99_ms = p95 * 2.0;
+        sample
+    }
+
+    #[test]
+    fn test_recorder_percentiles() {
+        let recorder = TelemetryRecorder::new();
+        for ms in 1..=100 {
+            recorder.record(Duration::from_millis(ms), ms % 10 != 0);
+        }
+
+        let now = Utc::now() + chrono::Duration::seconds(60);
+        let sample = recorder.take_sample(now);
+        assert_eq!(sample.requests, 100);
+        assert_eq!(sample.errors, 10);
+        assert!((sample.latency_p50_ms - 50.0).abs() < 1e-6);
+        assert!((sample.latency_p95_ms - 95.0).abs() < 1e-6);
+        assert!((sample.latency_p99_ms - 99.0).abs() < 1e-6);
+        assert!((sample.error_rate() - 10.0).abs() < 1e-6);
+
+        // The next window starts empty
+        let next = recorder.take_sample(now);
+        assert_eq!((next.requests, next.errors), (0, 0));
+        assert_eq!(next.timestamp, now);
+    }
+
+    #[tokio::test]
+    async fn test_query_time_range() {
+        let store = TelemetryStore::in_memory().await.unwrap();
+        let start = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        for minute in 0..10 {
+            store
+                .record(&sample(
+                    start + chrono::Duration::minutes(minute),
+                    60,
+                    0,
+                    20.0,
+                ))
+                .await
+                .unwrap();
+        }
+
+        let samples = store
+            .query(
+                start + chrono::Duration::minutes(2),
+                start + chrono::Duration::minutes(5),
+            )
+            .await
+            .unwrap();
+        assert_eq!(samples.len(), 3);
+        assert_eq!(samples[0].timestamp, start + chrono::Duration::minutes(2));
+
+        let buckets = store
+            .query_downsampled(
+                start,
+                start + chrono::Duration::minutes(10),
+                Duration::from_secs(300),
+            )
+            .await
+            .unwrap();
+        assert_eq!(buckets.len(), 2);
+        assert_eq!(buckets[0].requests, 300);
+        assert!((buckets[0].request_rate() - 60.0).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn test_compaction_rolls_up_and_expires() {
+        let store = TelemetryStore::in_memory().await.unwrap();
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap();
+        let old = now - chrono::Duration::hours(30);
+
+        // Ten minutes of raw samples older than a day, with one slow minute
+        for minute in 0..10 {
+            let p95 = if minute == 3 { 500.0 } else { 20.0 };
+            store
+                .record(&sample(old + chrono::Duration::minutes(minute), 10, 1, p95))
+                .await
+                .unwrap();
+        }
+        // A recent sample that stays raw
+        store
+            .record(&sample(now - chrono::Duration::minutes(1), 10, 0, 20.0))
+            .await
+            .unwrap();
+        // A rollup past the retention
+        insert(
+            &store.pool,
+            &sample(now - chrono::Duration::days(31), 10, 0, 20.0),
+            true,
+        )
+        .await
+        .unwrap();
+
+        let stats = store.compact(now).await.unwrap();
+        assert_eq!(
+            stats,
+            CompactionStats {
+                samples_rolled_up: 10,
+                rollups_created: 2,
+                rollups_expired: 1,
+            }
+        );
+
+        let samples = store
+            .query(now - chrono::Duration::days(60), now)
+            .await
+            .unwrap();
+        assert_eq!(samples.len(), 3);
+        assert_eq!(samples[0].window_secs, 300);
+        assert_eq!((samples[0].requests, samples[0].errors), (50, 5));
+        assert_eq!(samples[0].latency_p95_ms, 500.0);
+        assert_eq!(samples[1].latency_p95_ms, 20.0);
+        assert_eq!(samples[2].window_secs, 60);
+    }
+}
@@ -1,6 +1,7 @@
 //! Performance Monitoring and Metrics
 //!
 //! Comprehensive monitoring for agents, workflows, and system performance.
+//! Telemetry history is persisted locally, see [`history`].
 
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
@@ -10,10 +11,12 @@ use chrono::{DateTime, Utc};
 pub mod metrics;
 pub mod telemetry;
 pub mod dashboard;
+pub mod history;
 
 pub use metrics::*;
 pub use telemetry::*;
 pub use dashboard::*;
+pub use history::*;
 
 /// Main monitoring coordinator
 pub struct MonitoringCoordinator {
@@ -56,6 +59,9 @@ pub enum MonitoringError {
     #[error("Export failed: {0}")]
     ExportFailed(String),
 
+    #[error("Telemetry store error: {0}")]
+    Storage(#[from] sqlx::Error),
+
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
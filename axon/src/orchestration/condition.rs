@@ -0,0 +1,705 @@
+//! `when` conditions and `for_each` item expressions for workflow tasks
+//!
+//! A condition is a small boolean expression over the workflow input and the
+//! results of tasks that have already run:
+//!
+//! ```text
+//! tasks.build.status == 'success'
+//! tasks.test.output.coverage >= 80 && tasks.lint.status != 'failed'
+//! !(tasks.review.output.approved) || tasks.review.attempts > 1
+//! ```
+//!
+//! References have the form `tasks.<id>.<field>` where the field is
+//! `status` (`'success'`, `'failed'` or `'skipped'`), `error`, `attempts` or
+//! `output`. Output references may continue into the task's JSON output with
+//! `.key` and `[index]` segments; missing values evaluate to `null`. The
+//! workflow input is read the same way through `inputs`, e.g.
+//! `inputs.changed_files[0]`. Operands compare with `==`, `!=`, `<`, `<=`, `>`, `>=`, combine with
+//! `&&`, `||`, `!` and parentheses, and a bare operand tests truthiness.
+
+use super::TaskResult;
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+/// Values a `tasks.<id>.status` reference can take
+pub const TASK_OUTCOMES: [&str; 3] = ["success", "failed", "skipped"];
+
+/// What expressions can read: the workflow input and the results so far
+#[derive(Debug, Clone, Copy)]
+pub struct EvalContext<'a> {
+    pub inputs: &'a Value,
+    pub results: &'a HashMap<String, TaskResult>,
+}
+
+/// A parsed `when` condition
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskCondition {
+    source: String,
+    expr: Expr,
+}
+
+/// A single reference such as `tasks.diff.output.files`, used where a value
+/// rather than a boolean is needed
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValueExpr {
+    source: String,
+    reference: Reference,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Or(Vec<Expr>),
+    And(Vec<Expr>),
+    Not(Box<Expr>),
+    Compare(Operand, CompareOp, Operand),
+    Truthy(Operand),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    Literal(Value),
+    Ref(Reference),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Reference {
+    Task(TaskRef),
+    Input(Vec<PathSegment>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct TaskRef {
+    task_id: String,
+    field: TaskField,
+    path: Vec<PathSegment>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TaskField {
+    Status,
+    Output,
+    Error,
+    Attempts,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl TaskCondition {
+    /// Parse a condition, reporting the first syntax error
+    pub fn parse(source: &str) -> std::result::Result<Self, String> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.parse_or()?;
+        if let Some(token) = parser.peek() {
+            return Err(format!("unexpected {} after end of expression", token));
+        }
+        Ok(Self {
+            source: source.to_string(),
+            expr,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// IDs of the tasks whose results the condition reads
+    pub fn referenced_tasks(&self) -> BTreeSet<&str> {
+        let mut tasks = BTreeSet::new();
+        self.expr.collect_refs(&mut tasks);
+        tasks
+    }
+
+    /// Evaluate against the results recorded so far. Tasks without a result
+    /// read as `null` fields.
+    pub fn evaluate(&self, ctx: &EvalContext<'_>) -> bool {
+        self.expr.evaluate(ctx)
+    }
+}
+
+impl ValueExpr {
+    /// Parse a bare `inputs...` or `tasks...` reference
+    pub fn parse(source: &str) -> std::result::Result<Self, String> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let operand = parser.parse_operand()?;
+        if let Some(token) = parser.peek() {
+            return Err(format!("unexpected {} after reference", token));
+        }
+        match operand {
+            Operand::Ref(reference) => Ok(Self {
+                source: source.to_string(),
+                reference,
+            }),
+            Operand::Literal(_) => Err("expected a reference to inputs or a task".to_string()),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// ID of the task the expression reads, if it reads one
+    pub fn referenced_task(&self) -> Option<&str> {
+        match &self.reference {
+            Reference::Task(task_ref) => Some(&task_ref.task_id),
+            Reference::Input(_) => None,
+        }
+    }
+
+    pub fn resolve(&self, ctx: &EvalContext<'_>) -> Value {
+        self.reference.resolve(ctx)
+    }
+}
+
+impl fmt::Display for ValueExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl fmt::Display for TaskCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl Expr {
+    fn collect_refs<'a>(&'a self, tasks: &mut BTreeSet<&'a str>) {
+        match self {
+            Expr::Or(exprs) | Expr::And(exprs) => exprs.iter().for_each(|e| e.collect_refs(tasks)),
+            Expr::Not(expr) => expr.collect_refs(tasks),
+            Expr::Compare(left, _, right) => {
+                left.collect_ref(tasks);
+                right.collect_ref(tasks);
+            }
+            Expr::Truthy(operand) => operand.collect_ref(tasks),
+        }
+    }
+
+    fn evaluate(&self, ctx: &EvalContext<'_>) -> bool {
+        match self {
+            Expr::Or(exprs) => exprs.iter().any(|e| e.evaluate(ctx)),
+            Expr::And(exprs) => exprs.iter().all(|e| e.evaluate(ctx)),
+            Expr::Not(expr) => !expr.evaluate(ctx),
+            Expr::Compare(left, op, right) => op.apply(&left.resolve(ctx), &right.resolve(ctx)),
+            Expr::Truthy(operand) => is_truthy(&operand.resolve(ctx)),
+        }
+    }
+}
+
+impl Operand {
+    fn collect_ref<'a>(&'a self, tasks: &mut BTreeSet<&'a str>) {
+        if let Operand::Ref(Reference::Task(task_ref)) = self {
+            tasks.insert(&task_ref.task_id);
+        }
+    }
+
+    fn resolve(&self, ctx: &EvalContext<'_>) -> Value {
+        match self {
+            Operand::Literal(value) => value.clone(),
+            Operand::Ref(reference) => reference.resolve(ctx),
+        }
+    }
+}
+
+impl Reference {
+    fn resolve(&self, ctx: &EvalContext<'_>) -> Value {
+        let task_ref = match self {
+            Reference::Input(path) => return follow_path(ctx.inputs, path),
+            Reference::Task(task_ref) => task_ref,
+        };
+        let Some(result) = ctx.results.get(&task_ref.task_id) else {
+            return Value::Null;
+        };
+
+        match task_ref.field {
+            TaskField::Status => Value::from(result.outcome()),
+            TaskField::Error => result.error.clone().map(Value::from).unwrap_or(Value::Null),
+            TaskField::Attempts => Value::from(result.attempts),
+            TaskField::Output => follow_path(result.output.as_ref().unwrap_or(&Value::Null), &task_ref.path),
+        }
+    }
+}
+
+fn follow_path(root: &Value, path: &[PathSegment]) -> Value {
+    let mut current = root;
+    for segment in path {
+        let next = match segment {
+            PathSegment::Key(key) => current.get(key),
+            PathSegment::Index(index) => current.get(index),
+        };
+        match next {
+            Some(value) => current = value,
+            None => return Value::Null,
+        }
+    }
+    current.clone()
+}
+
+impl CompareOp {
+    fn apply(self, left: &Value, right: &Value) -> bool {
+        let ordering = match (left, right) {
+            (Value::Number(a), Value::Number(b)) => a.as_f64().zip(b.as_f64()).and_then(|(a, b)| a.partial_cmp(&b)),
+            (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+            _ => None,
+        };
+
+        match self {
+            CompareOp::Eq => ordering.map_or(left == right, |o| o == Ordering::Equal),
+            CompareOp::Ne => ordering.map_or(left != right, |o| o != Ordering::Equal),
+            CompareOp::Lt => ordering == Some(Ordering::Less),
+            CompareOp::Le => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+            CompareOp::Gt => ordering == Some(Ordering::Greater),
+            CompareOp::Ge => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+        }
+    }
+}
+
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().is_some_and(|n| n != 0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(_) => true,
+    }
+}
+
+// ============================================================================
+// Tokenizer
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Num(f64),
+    Dot,
+    LBracket,
+    RBracket,
+    LParen,
+    RParen,
+    Op(CompareOp),
+    And,
+    Or,
+    Not,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Ident(name) => write!(f, "'{}'", name),
+            Token::Str(s) => write!(f, "string '{}'", s),
+            Token::Num(n) => write!(f, "number {}", n),
+            Token::Dot => f.write_str("'.'"),
+            Token::LBracket => f.write_str("'['"),
+            Token::RBracket => f.write_str("']'"),
+            Token::LParen => f.write_str("'('"),
+            Token::RParen => f.write_str("')'"),
+            Token::Op(_) => f.write_str("comparison operator"),
+            Token::And => f.write_str("'&&'"),
+            Token::Or => f.write_str("'||'"),
+            Token::Not => f.write_str("'!'"),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> std::result::Result<Vec<Token>, String> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let after_dot = tokens.last() == Some(&Token::Dot);
+
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '.' => {
+                tokens.push(Token::Dot);
+                i += 1;
+            }
+            '[' => {
+                tokens.push(Token::LBracket);
+                i += 1;
+            }
+            ']' => {
+                tokens.push(Token::RBracket);
+                i += 1;
+            }
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            '&' if next == Some('&') => {
+                tokens.push(Token::And);
+                i += 2;
+            }
+            '|' if next == Some('|') => {
+                tokens.push(Token::Or);
+                i += 2;
+            }
+            '=' if next == Some('=') => {
+                tokens.push(Token::Op(CompareOp::Eq));
+                i += 2;
+            }
+            '!' if next == Some('=') => {
+                tokens.push(Token::Op(CompareOp::Ne));
+                i += 2;
+            }
+            '!' => {
+                tokens.push(Token::Not);
+                i += 1;
+            }
+            '<' | '>' => {
+                let or_equal = next == Some('=');
+                tokens.push(Token::Op(match (c, or_equal) {
+                    ('<', false) => CompareOp::Lt,
+                    ('<', true) => CompareOp::Le,
+                    ('>', false) => CompareOp::Gt,
+                    _ => CompareOp::Ge,
+                }));
+                i += if or_equal { 2 } else { 1 };
+            }
+            '\'' | '"' => {
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|&ch| ch == c)
+                    .ok_or_else(|| format!("unterminated string starting at column {}", i + 1))?;
+                tokens.push(Token::Str(chars[i + 1..i + 1 + end].iter().collect()));
+                i += end + 2;
+            }
+            // Path segments may be task IDs such as `build-2`
+            c if after_dot && (c.is_alphanumeric() || c == '_') => {
+                let len = chars[i..]
+                    .iter()
+                    .take_while(|ch| ch.is_alphanumeric() || **ch == '_' || **ch == '-')
+                    .count();
+                tokens.push(Token::Ident(chars[i..i + len].iter().collect()));
+                i += len;
+            }
+            c if c.is_ascii_digit() || (c == '-' && next.is_some_and(|n| n.is_ascii_digit())) => {
+                let len = 1 + chars[i + 1..]
+                    .iter()
+                    .take_while(|ch| ch.is_ascii_digit() || **ch == '.')
+                    .count();
+                let text: String = chars[i..i + len].iter().collect();
+                let number = text.parse().map_err(|_| format!("invalid number '{}'", text))?;
+                tokens.push(Token::Num(number));
+                i += len;
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let len = chars[i..]
+                    .iter()
+                    .take_while(|ch| ch.is_alphanumeric() || **ch == '_')
+                    .count();
+                tokens.push(Token::Ident(chars[i..i + len].iter().collect()));
+                i += len;
+            }
+            other => return Err(format!("unexpected character '{}' at column {}", other, i + 1)),
+        }
+    }
+
+    Ok(tokens)
+}
+
+// ============================================================================
+// Parser
+// ============================================================================
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> std::result::Result<(), String> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            Some(token) => Err(format!("expected {}, found {}", expected, token)),
+            None => Err(format!("expected {}, found end of expression", expected)),
+        }
+    }
+
+    fn parse_or(&mut self) -> std::result::Result<Expr, String> {
+        let mut exprs = vec![self.parse_and()?];
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            exprs.push(self.parse_and()?);
+        }
+        Ok(if exprs.len() == 1 { exprs.remove(0) } else { Expr::Or(exprs) })
+    }
+
+    fn parse_and(&mut self) -> std::result::Result<Expr, String> {
+        let mut exprs = vec![self.parse_unary()?];
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            exprs.push(self.parse_unary()?);
+        }
+        Ok(if exprs.len() == 1 { exprs.remove(0) } else { Expr::And(exprs) })
+    }
+
+    fn parse_unary(&mut self) -> std::result::Result<Expr, String> {
+        match self.peek() {
+            Some(Token::Not) => {
+                self.pos += 1;
+                Ok(Expr::Not(Box::new(self.parse_unary()?)))
+            }
+            Some(Token::LParen) => {
+                self.pos += 1;
+                let expr = self.parse_or()?;
+                self.expect(Token::RParen)?;
+                Ok(expr)
+            }
+            _ => self.parse_comparison(),
+        }
+    }
+
+    fn parse_comparison(&mut self) -> std::result::Result<Expr, String> {
+        let left = self.parse_operand()?;
+        let Some(Token::Op(op)) = self.peek().cloned() else {
+            return Ok(Expr::Truthy(left));
+        };
+        self.pos += 1;
+        let right = self.parse_operand()?;
+
+        for (reference, other) in [(&left, &right), (&right, &left)] {
+            if let (
+                Operand::Ref(Reference::Task(TaskRef { field: TaskField::Status, .. })),
+                Operand::Literal(Value::String(status)),
+            ) = (reference, other)
+                && !TASK_OUTCOMES.contains(&status.as_str())
+            {
+                return Err(format!(
+                    "unknown task status '{}' (expected one of {})",
+                    status,
+                    TASK_OUTCOMES.join(", ")
+                ));
+            }
+        }
+
+        Ok(Expr::Compare(left, op, right))
+    }
+
+    fn parse_operand(&mut self) -> std::result::Result<Operand, String> {
+        match self.next() {
+            Some(Token::Str(s)) => Ok(Operand::Literal(Value::from(s))),
+            Some(Token::Num(n)) => Ok(Operand::Literal(
+                serde_json::Number::from_f64(n).map(Value::Number).unwrap_or(Value::Null),
+            )),
+            Some(Token::Ident(name)) => match name.as_str() {
+                "true" => Ok(Operand::Literal(Value::Bool(true))),
+                "false" => Ok(Operand::Literal(Value::Bool(false))),
+                "null" => Ok(Operand::Literal(Value::Null)),
+                "tasks" => self.parse_task_ref().map(|r| Operand::Ref(Reference::Task(r))),
+                "inputs" => self.parse_path().map(|path| Operand::Ref(Reference::Input(path))),
+                other => Err(format!(
+                    "unknown identifier '{}' (references start with 'tasks.' or 'inputs')",
+                    other
+                )),
+            },
+            Some(token) => Err(format!("expected a value, found {}", token)),
+            None => Err("expected a value, found end of expression".to_string()),
+        }
+    }
+
+    /// Parse the part of a reference after `tasks`
+    fn parse_task_ref(&mut self) -> std::result::Result<TaskRef, String> {
+        self.expect(Token::Dot)?;
+        let task_id = self.ident("a task ID")?;
+        self.expect(Token::Dot)?;
+
+        let field = match self.ident("a task field")?.as_str() {
+            "status" => TaskField::Status,
+            "output" => TaskField::Output,
+            "error" => TaskField::Error,
+            "attempts" => TaskField::Attempts,
+            other => {
+                return Err(format!(
+                    "unknown field '{}' on task '{}' (expected status, output, error or attempts)",
+                    other, task_id
+                ));
+            }
+        };
+
+        let path = self.parse_path()?;
+        if !path.is_empty() && field != TaskField::Output {
+            return Err(format!("only tasks.{}.output can be indexed", task_id));
+        }
+
+        Ok(TaskRef { task_id, field, path })
+    }
+
+    /// `.key` and `[index]` segments following a reference
+    fn parse_path(&mut self) -> std::result::Result<Vec<PathSegment>, String> {
+        let mut path = Vec::new();
+        loop {
+            match self.peek() {
+                Some(Token::Dot) => {
+                    self.pos += 1;
+                    path.push(PathSegment::Key(self.ident("an output key")?));
+                }
+                Some(Token::LBracket) => {
+                    self.pos += 1;
+                    match self.next() {
+                        Some(Token::Num(n)) if n >= 0.0 && n.fract() == 0.0 => {
+                            path.push(PathSegment::Index(n as usize))
+                        }
+                        Some(Token::Str(key)) => path.push(PathSegment::Key(key)),
+                        _ => return Err("expected an array index or quoted key inside '[ ]'".to_string()),
+                    }
+                    self.expect(Token::RBracket)?;
+                }
+                _ => break,
+            }
+        }
+        Ok(path)
+    }
+
+    fn ident(&mut self, what: &str) -> std::result::Result<String, String> {
+        match self.next() {
+            Some(Token::Ident(name)) => Ok(name),
+            Some(token) => Err(format!("expected {}, found {}", what, token)),
+            None => Err(format!("expected {}, found end of expression", what)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn result(task_id: &str, success: bool, output: Option<Value>) -> (String, TaskResult) {
+        (
+            task_id.to_string(),
+            TaskResult {
+                task_id: task_id.to_string(),
+                success,
+                output,
+                error: (!success).then(|| "compile error".to_string()),
+                attempts: 2,
+                skipped: false,
+                validation: Vec::new(),
+            },
+        )
+    }
+
+    fn results() -> HashMap<String, TaskResult> {
+        HashMap::from([
+            result("build", true, Some(json!({"artifacts": [{"name": "app"}], "warnings": 3}))),
+            result("lint-2", false, None),
+        ])
+    }
+
+    fn eval(source: &str) -> bool {
+        let inputs = json!({"files": ["a.rs", "b.rs"], "dry_run": false});
+        let results = results();
+        let ctx = EvalContext {
+            inputs: &inputs,
+            results: &results,
+        };
+        TaskCondition::parse(source).unwrap().evaluate(&ctx)
+    }
+
+    #[test]
+    fn test_status_and_output_comparisons() {
+        assert!(eval("tasks.build.status == 'success'"));
+        assert!(eval("tasks.lint-2.status == \"failed\""));
+        assert!(eval("tasks.build.output.warnings < 5"));
+        assert!(eval("tasks.build.output.artifacts[0].name == 'app'"));
+        assert!(eval("tasks.build.output.missing.deeper == null"));
+        assert!(eval("tasks.lint-2.attempts >= 2"));
+        assert!(!eval("tasks.lint-2.error != 'compile error'"));
+        // Tasks that have not run read as null
+        assert!(eval("tasks.deploy.status == null"));
+        assert!(eval("inputs.files[1] == 'b.rs' && !inputs.dry_run"));
+        assert!(eval("inputs.missing == null"));
+    }
+
+    #[test]
+    fn test_boolean_operators() {
+        assert!(eval("tasks.build.status == 'success' && tasks.lint-2.status == 'failed'"));
+        assert!(eval("tasks.build.status == 'failed' || tasks.build.output.warnings == 3"));
+        assert!(eval("!(tasks.build.status == 'failed')"));
+        assert!(eval("tasks.build.output.artifacts && !tasks.lint-2.output"));
+    }
+
+    #[test]
+    fn test_referenced_tasks() {
+        let condition = TaskCondition::parse("tasks.a.status == 'success' || tasks.b.output.x > tasks.a.attempts").unwrap();
+        assert_eq!(condition.referenced_tasks().into_iter().collect::<Vec<_>>(), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_value_expr() {
+        let inputs = json!({"files": ["a.rs", "b.rs"]});
+        let results = results();
+        let ctx = EvalContext {
+            inputs: &inputs,
+            results: &results,
+        };
+
+        let files = ValueExpr::parse("inputs.files").unwrap();
+        assert_eq!(files.referenced_task(), None);
+        assert_eq!(files.resolve(&ctx), json!(["a.rs", "b.rs"]));
+
+        let artifacts = ValueExpr::parse("tasks.build.output.artifacts").unwrap();
+        assert_eq!(artifacts.referenced_task(), Some("build"));
+        assert_eq!(artifacts.resolve(&ctx), json!([{"name": "app"}]));
+
+        for source in ["'literal'", "inputs.files == 1", "inputs.files &&", "files"] {
+            assert!(ValueExpr::parse(source).is_err(), "should reject {:?}", source);
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for source in [
+            "",
+            "tasks.build",
+            "tasks.build.result == 1",
+            "tasks.build.status == 'done'",
+            "tasks.build.status[0] == 1",
+            "build.status == 'success'",
+            "tasks.build.status == 'success' &&",
+            "(tasks.build.status == 'success'",
+            "tasks.build.output.name == 'unterminated",
+        ] {
+            assert!(TaskCondition::parse(source).is_err(), "should reject {:?}", source);
+        }
+    }
+}
@@ -18,6 +18,7 @@ impl DagValidator {
     pub fn validate(&self, workflow: &Workflow) -> Result<()> {
         self.check_cycles(&workflow.dependencies)?;
         self.check_dependencies_exist(workflow)?;
+        self.check_task_controls(workflow)?;
         Ok(())
     }
 
@@ -74,6 +75,19 @@ impl DagValidator {
             }
 
             for dep_id in deps {
+                // Child instances of map tasks only exist at run time
+                if let Some((parent, _)) = ForEach::parse_child_id(dep_id)
+                    && workflow.tasks.iter().any(|t| t.id == parent && t.for_each.is_some())
+                {
+                    return Err(OrchestrationError::InvalidTask {
+                        task_id: task_id.clone(),
+                        reason: format!(
+                            "cannot depend on '{}', an instance of map task '{}'; depend on '{}' instead",
+                            dep_id, parent, parent
+                        ),
+                    });
+                }
+
                 if !task_ids.contains(dep_id.as_str()) {
                     return Err(OrchestrationError::DependencyNotFound {
                         task_id: task_id.clone(),
@@ -85,4 +99,82 @@ impl DagValidator {
 
         Ok(())
     }
+
+    /// Validate `when` conditions, `for_each` settings and retry policies.
+    /// Conditions and item expressions may only read tasks that are
+    /// guaranteed to have finished, i.e. upstream dependencies.
+    fn check_task_controls(&self, workflow: &Workflow) -> Result<()> {
+        let task_ids: HashSet<_> = workflow.tasks.iter().map(|t| t.id.as_str()).collect();
+
+        for task in &workflow.tasks {
+            let invalid = |reason: String| OrchestrationError::InvalidTask {
+                task_id: task.id.clone(),
+                reason,
+            };
+
+            if let Some(when) = &task.when {
+                let condition = TaskCondition::parse(when)
+                    .map_err(|e| invalid(format!("invalid when condition '{}': {}", when, e)))?;
+
+                let upstream = upstream_tasks(&task.id, &workflow.dependencies);
+                for referenced in condition.referenced_tasks() {
+                    if !task_ids.contains(referenced) {
+                        return Err(invalid(format!("when condition references unknown task '{}'", referenced)));
+                    }
+                    if !upstream.contains(referenced) {
+                        return Err(invalid(format!(
+                            "when condition reads task '{}', which is not one of its dependencies",
+                            referenced
+                        )));
+                    }
+                }
+            }
+
+            if let Some(for_each) = &task.for_each {
+                let items = ValueExpr::parse(&for_each.items)
+                    .map_err(|e| invalid(format!("invalid for_each items '{}': {}", for_each.items, e)))?;
+
+                if let Some(referenced) = items.referenced_task() {
+                    if !task_ids.contains(referenced) {
+                        return Err(invalid(format!("for_each items reference unknown task '{}'", referenced)));
+                    }
+                    if !upstream_tasks(&task.id, &workflow.dependencies).contains(referenced) {
+                        return Err(invalid(format!(
+                            "for_each items read task '{}', which is not one of its dependencies",
+                            referenced
+                        )));
+                    }
+                }
+                if for_each.max_parallel == 0 {
+                    return Err(invalid("for_each.max_parallel must be at least 1".to_string()));
+                }
+            }
+
+            if let Some(retry) = &task.retry {
+                if retry.max_attempts == 0 {
+                    return Err(invalid("retry.max_attempts must be at least 1".to_string()));
+                }
+                for pattern in &retry.retry_on {
+                    regex::Regex::new(pattern)
+                        .map_err(|e| invalid(format!("invalid retry_on pattern '{}': {}", pattern, e)))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Every task `task_id` depends on, directly or transitively
+fn upstream_tasks<'a>(task_id: &str, deps: &'a HashMap<String, Vec<String>>) -> HashSet<&'a str> {
+    let mut upstream = HashSet::new();
+    let mut stack: Vec<&str> = deps.get(task_id).into_iter().flatten().map(String::as_str).collect();
+
+    while let Some(dep) = stack.pop() {
+        if upstream.insert(dep) {
+            stack.extend(deps.get(dep).into_iter().flatten().map(String::as_str));
+        }
+    }
+
+    upstream
 }
@@ -1,19 +1,38 @@
 //! Workflow execution engine
 
 use super::*;
+use super::task_router::{RouteRequest, TaskRouter};
+use crate::intelligence::{AgentReputation, TaskOutcome};
+use crate::quality::{CheckContext, PipelineReport, QualityConfig, ValidationPipeline};
 use crate::agents::{
-    Agent, AgentType, AgentId, Capability, CapabilityMatcher,
+    Agent, AgentType, AgentId, Capability,
     developer::DeveloperAgent,
     reviewer::ReviewerAgent,
     tester::TesterAgent,
     orchestrator::OrchestratorAgent,
 };
+use futures::StreamExt;
+use serde_json::Value;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
 use tokio::time::{timeout, Duration as TokioDuration};
+use tracing::{debug, warn};
 
 pub struct WorkflowExecutor {
     agent_pool: Arc<RwLock<AgentPool>>,
-    capability_matcher: Arc<RwLock<CapabilityMatcher>>,
+    /// Places tasks on pool agents by the capabilities they require
+    router: TaskRouter,
+    /// Agent that last ran each task, so validation follow-ups go back to it
+    assignments: Arc<RwLock<HashMap<String, AgentId>>>,
+    /// Validation for workflows without their own `quality` section
+    quality: Option<QualityConfig>,
+    /// Where checks run unless a task names its own `workspace_path`
+    workspace: PathBuf,
+    /// Per-task-type agent scores, updated after each task and used to
+    /// choose between equally capable agents
+    reputation: Option<Arc<AgentReputation>>,
 }
 
 impl Default for WorkflowExecutor {
@@ -25,64 +44,90 @@ impl Default for WorkflowExecutor {
 impl WorkflowExecutor {
     pub fn new() -> Self {
         let mut agent_pool = AgentPool::new();
-        let mut capability_matcher = CapabilityMatcher::new();
+        let mut router = TaskRouter::default();
 
         // Initialize default agents
-        agent_pool.initialize_default_agents(&mut capability_matcher);
+        for (agent_id, capabilities) in agent_pool.initialize_default_agents() {
+            router = router.with_agent(agent_id, &capability_names(&capabilities));
+        }
 
         Self {
             agent_pool: Arc::new(RwLock::new(agent_pool)),
-            capability_matcher: Arc::new(RwLock::new(capability_matcher)),
+            router,
+            assignments: Arc::new(RwLock::new(HashMap::new())),
+            quality: None,
+            workspace: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+            reputation: None,
         }
     }
 
+    /// Validate completed tasks with `config` (typically axon.json's
+    /// `quality` section) unless the workflow defines its own
+    pub fn with_quality(mut self, config: QualityConfig) -> Self {
+        self.quality = Some(config);
+        self
+    }
+
+    /// Workspace that quality checks run in
+    pub fn with_workspace(mut self, workspace: impl Into<PathBuf>) -> Self {
+        self.workspace = workspace.into();
+        self
+    }
+
+    /// Record task outcomes in `reputation` and route by it
+    pub fn with_reputation(mut self, reputation: Arc<AgentReputation>) -> Self {
+        self.router = std::mem::take(&mut self.router).with_reputation(reputation.clone());
+        self.reputation = Some(reputation);
+        self
+    }
+
     pub async fn execute(
         &self,
         workflow: Workflow,
         schedule: ExecutionSchedule,
+    ) -> Result<WorkflowResult> {
+        self.execute_with_input(workflow, schedule, Value::Null).await
+    }
+
+    /// Execute with `input` visible to `inputs.` references
+    pub async fn execute_with_input(
+        &self,
+        workflow: Workflow,
+        schedule: ExecutionSchedule,
+        input: Value,
     ) -> Result<WorkflowResult> {
         let start = std::time::Instant::now();
         let mut task_results = HashMap::new();
+        let pipeline = workflow
+            .quality
+            .as_ref()
+            .or(self.quality.as_ref())
+            .map(ValidationPipeline::from_config)
+            .filter(|pipeline| !pipeline.is_empty());
 
         // Execute tasks according to schedule
         for task_id in &schedule.sorted_tasks {
             if let Some(task) = workflow.tasks.iter().find(|t| t.id == *task_id) {
-                // Check dependencies are complete
-                if !self.check_dependencies(&task.id, &workflow.dependencies, &task_results).await {
-                    task_results.insert(task_id.clone(), TaskResult {
-                        task_id: task_id.clone(),
-                        success: false,
-                        output: None,
-                        error: Some("Dependencies not met".to_string()),
-                    });
-                    continue;
-                }
-
-                // Execute with timeout
-                let task_timeout = TokioDuration::from_secs(300); // 5 minutes default
-                let result = timeout(task_timeout, self.execute_task(task)).await;
-
-                let task_result = match result {
-                    Ok(Ok(res)) => res,
-                    Ok(Err(e)) => TaskResult {
-                        task_id: task.id.clone(),
-                        success: false,
-                        output: None,
-                        error: Some(e.to_string()),
-                    },
-                    Err(_) => TaskResult {
-                        task_id: task.id.clone(),
-                        success: false,
-                        output: None,
-                        error: Some("Task execution timeout".to_string()),
+                let task_result = match self.check_schedulable(task, &workflow, &input, &task_results) {
+                    Ok(()) => match &task.for_each {
+                        Some(for_each) => {
+                            self.execute_map_task(task, for_each, &input, pipeline.as_ref(), &mut task_results)
+                                .await
+                        }
+                        None => self.execute_validated(task, pipeline.as_ref()).await,
                     },
+                    Err(blocked) => blocked,
                 };
 
                 task_results.insert(task_id.clone(), task_result);
             }
         }
 
-        let success = task_results.values().all(|r| r.success);
+        let success = workflow.tasks.iter().all(|task| {
+            task_results
+                .get(&task.id)
+                .is_none_or(|r| r.success || r.skipped || task.continue_on_error)
+        });
 
         Ok(WorkflowResult {
             workflow_id: workflow.id,
@@ -92,22 +137,278 @@ impl WorkflowExecutor {
         })
     }
 
-    async fn execute_task(&self, task: &Task) -> Result<TaskResult> {
+    /// Decide at scheduling time whether a task runs. Tasks with a `when`
+    /// condition run whenever it holds, so they can react to failures;
+    /// other tasks need every dependency to have succeeded (or failed with
+    /// `continue_on_error`) and are skipped along with skipped dependencies.
+    fn check_schedulable(
+        &self,
+        task: &Task,
+        workflow: &Workflow,
+        input: &Value,
+        completed_tasks: &HashMap<String, TaskResult>,
+    ) -> std::result::Result<(), TaskResult> {
+        if let Some(when) = &task.when {
+            let condition = TaskCondition::parse(when)
+                .map_err(|e| TaskResult::failed(&task.id, format!("Invalid when condition: {}", e)))?;
+
+            let ctx = EvalContext {
+                inputs: input,
+                results: completed_tasks,
+            };
+            return if condition.evaluate(&ctx) {
+                Ok(())
+            } else {
+                debug!("Skipping task {}: condition '{}' is false", task.id, condition);
+                Err(TaskResult::skipped(&task.id, format!("Condition not met: {}", condition)))
+            };
+        }
+
+        for dep in workflow.dependencies.get(&task.id).into_iter().flatten() {
+            let Some(result) = completed_tasks.get(dep) else {
+                continue;
+            };
+            if result.skipped {
+                return Err(TaskResult::skipped(&task.id, format!("Dependency {} was skipped", dep)));
+            }
+
+            let tolerated = workflow.tasks.iter().any(|t| t.id == *dep && t.continue_on_error);
+            if !result.success && !tolerated {
+                return Err(TaskResult::failed(&task.id, "Dependencies not met"));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Fan a map task out over its items, at most `max_parallel` at a time.
+    /// Child results are recorded under their instance IDs; the returned
+    /// parent result carries the child outputs in item order and succeeds
+    /// only if every child did.
+    async fn execute_map_task(
+        &self,
+        task: &Task,
+        for_each: &ForEach,
+        input: &Value,
+        pipeline: Option<&ValidationPipeline>,
+        task_results: &mut HashMap<String, TaskResult>,
+    ) -> TaskResult {
+        let items = match ValueExpr::parse(&for_each.items) {
+            Ok(expr) => expr.resolve(&EvalContext {
+                inputs: input,
+                results: &*task_results,
+            }),
+            Err(e) => return TaskResult::failed(&task.id, format!("Invalid for_each items: {}", e)),
+        };
+        let Value::Array(items) = items else {
+            return TaskResult::failed(
+                &task.id,
+                format!("for_each items '{}' did not resolve to an array (got {})", for_each.items, items),
+            );
+        };
+
+        let children: Vec<Task> = items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| Task {
+                id: ForEach::child_id(&task.id, index),
+                name: format!("{} [{}]", task.name, index),
+                input: ForEach::child_input(&task.input, item, index),
+                when: None,
+                for_each: None,
+                ..task.clone()
+            })
+            .collect();
+        debug!(
+            "Fanning out task {} over {} items (max_parallel: {})",
+            task.id,
+            children.len(),
+            for_each.max_parallel
+        );
+
+        let failed = AtomicBool::new(false);
+        // Owned children keep the per-item futures free of borrowed `&Task`
+        // arguments, so the workflow future stays `Send` for `tokio::spawn`
+        let child_results: Vec<TaskResult> = futures::stream::iter(children)
+            .map(|child| {
+                let failed = &failed;
+                async move {
+                    if for_each.fail_fast && failed.load(Ordering::SeqCst) {
+                        return TaskResult::skipped(&child.id, "An earlier item failed");
+                    }
+                    let result = self.execute_validated(&child, pipeline).await;
+                    if !result.success {
+                        failed.store(true, Ordering::SeqCst);
+                    }
+                    result
+                }
+            })
+            .buffered(for_each.max_parallel.max(1))
+            .collect()
+            .await;
+
+        let total = child_results.len();
+        let succeeded = child_results.iter().filter(|r| r.success).count();
+        let first_failure = child_results
+            .iter()
+            .find(|r| !r.success && !r.skipped)
+            .map(|r| format!("{}: {}", r.task_id, r.error.as_deref().unwrap_or("failed")));
+        let outputs = child_results
+            .iter()
+            .map(|r| r.output.clone().unwrap_or(Value::Null))
+            .collect();
+
+        for child in child_results {
+            task_results.insert(child.task_id.clone(), child);
+        }
+
+        TaskResult {
+            task_id: task.id.clone(),
+            success: succeeded == total,
+            output: Some(Value::Array(outputs)),
+            error: first_failure.map(|first| format!("{}/{} items succeeded; first failure: {}", succeeded, total, first)),
+            attempts: 1,
+            skipped: false,
+            validation: Vec::new(),
+        }
+    }
+
+    /// Run a task, then validate its output. Failed validations go back to
+    /// the agent that produced the output as a follow-up task carrying the
+    /// failures, until the checks pass or the pipeline's retry budget runs
+    /// out. Every validation round is recorded on the result, and the final
+    /// outcome is credited to the agent's reputation.
+    async fn execute_validated(&self, task: &Task, pipeline: Option<&ValidationPipeline>) -> TaskResult {
+        let started = Instant::now();
+        let mut result = self.execute_with_retries(task, None).await;
+        if let Some(pipeline) = pipeline.filter(|p| p.applies_to(task.task_type.as_str())) {
+            result = self.validate_with_follow_ups(task, pipeline, result).await;
+        }
+        self.record_reputation(task, &result, started.elapsed()).await;
+        result
+    }
+
+    async fn validate_with_follow_ups(
+        &self,
+        task: &Task,
+        pipeline: &ValidationPipeline,
+        mut result: TaskResult,
+    ) -> TaskResult {
+        let workspace = task
+            .input
+            .get("workspace_path")
+            .and_then(Value::as_str)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| self.workspace.clone());
+        let mut reports = Vec::new();
+        let mut follow_ups = 0;
+
+        while result.success {
+            let ctx = CheckContext::new(
+                &task.id,
+                task.task_type.as_str(),
+                &workspace,
+                result.output.clone().unwrap_or(Value::Null),
+            );
+            let report = pipeline.validate(&ctx).await;
+            let passed = report.passed;
+            let summary = report.failure_summary();
+            reports.push(report);
+
+            if passed {
+                break;
+            }
+            if follow_ups >= pipeline.max_retries() {
+                result.success = false;
+                result.error = Some(format!("Validation failed after {} follow-up(s): {}", follow_ups, summary));
+                break;
+            }
+
+            follow_ups += 1;
+            warn!(
+                "Task {} failed validation ({}); sending follow-up {}/{} to its agent",
+                task.id,
+                summary,
+                follow_ups,
+                pipeline.max_retries()
+            );
+            let producer = self.assignments.read().await.get(&task.id).cloned();
+            let follow_up = follow_up_task(task, reports.last().unwrap(), follow_ups);
+            result = self.execute_with_retries(&follow_up, producer.as_ref()).await;
+        }
+
+        result.validation = reports;
+        result
+    }
+
+    /// Credit the agent that produced the task's output with the outcome
+    async fn record_reputation(&self, task: &Task, result: &TaskResult, elapsed: std::time::Duration) {
+        let Some(reputation) = &self.reputation else {
+            return;
+        };
+        let Some(agent_id) = self.assignments.read().await.get(&task.id).cloned() else {
+            return;
+        };
+
+        let mut outcome = TaskOutcome::new(task.task_type.as_str(), elapsed, result.success);
+        if let Some(first) = result.validation.first() {
+            outcome = outcome.with_validation(first.passed);
+        }
+        let score = reputation.record(&agent_id, &outcome);
+        debug!("Agent {} now scores {:.2} on {} tasks", agent_id, score, outcome.task_type);
+    }
+
+    /// Run a task under its retry policy with the default per-attempt
+    /// timeout, on `agent` if given or the best matching agent otherwise
+    async fn execute_with_retries(&self, task: &Task, agent: Option<&AgentId>) -> TaskResult {
+        let policy = task.retry.clone().unwrap_or_default();
+
+        run_with_retries(&task.id, &policy, || async {
+            // Execute with timeout
+            let task_timeout = TokioDuration::from_secs(300); // 5 minutes default
+            match timeout(task_timeout, self.execute_task(task, agent)).await {
+                Ok(Ok(res)) => res,
+                Ok(Err(e)) => TaskResult::failed(&task.id, e.to_string()),
+                Err(_) => {
+                    // The attempt was dropped before it could release its agent
+                    if let Some(agent_id) = self.assignments.read().await.get(&task.id) {
+                        self.router.complete(agent_id, &task.id).await;
+                    }
+                    TaskResult::failed(&task.id, "Task execution timeout")
+                }
+            }
+        })
+        .await
+    }
+
+    async fn execute_task(&self, task: &Task, agent: Option<&AgentId>) -> Result<TaskResult> {
         // Determine required capabilities based on task type
         let required_capabilities = self.get_required_capabilities(&task.task_type);
 
         // Find suitable agent
-        let agent_id = {
-            let matcher = self.capability_matcher.read().await;
-            matcher.find_best_agent(&required_capabilities)
-                .ok_or_else(|| OrchestrationError::NoSuitableAgent {
-                    task_id: task.id.clone()
-                })?
+        let agent_id = match agent {
+            Some(agent_id) => agent_id.clone(),
+            None => {
+                let request = RouteRequest {
+                    task_id: task.id.clone(),
+                    required_capabilities: capability_names(&required_capabilities),
+                    preferred_capabilities: Vec::new(),
+                    task_type: Some(task.task_type.as_str().to_string()),
+                    payload: Value::Null,
+                };
+                self.router
+                    .try_route(&request)
+                    .await
+                    .ok_or_else(|| OrchestrationError::NoSuitableAgent {
+                        task_id: task.id.clone()
+                    })?
+            }
         };
+        self.assignments.write().await.insert(task.id.clone(), agent_id.clone());
 
         // Get agent from pool and execute
-        let mut pool = self.agent_pool.write().await;
-        let execution_result = pool.execute_with_agent(&agent_id, task).await;
+        let execution_result = self.agent_pool.write().await.execute_with_agent(&agent_id, task).await;
+        self.router.complete(&agent_id, &task.id).await;
 
         match execution_result {
             Ok(output) => Ok(TaskResult {
@@ -115,30 +416,12 @@ impl WorkflowExecutor {
                 success: true,
                 output: Some(output),
                 error: None,
+                attempts: 1,
+                skipped: false,
+                validation: Vec::new(),
             }),
-            Err(e) => Ok(TaskResult {
-                task_id: task.id.clone(),
-                success: false,
-                output: None,
-                error: Some(e.to_string()),
-            })
-        }
-    }
-
-    async fn check_dependencies(
-        &self,
-        task_id: &str,
-        dependencies: &HashMap<String, Vec<String>>,
-        completed_tasks: &HashMap<String, TaskResult>,
-    ) -> bool {
-        if let Some(deps) = dependencies.get(task_id) {
-            for dep in deps {
-                if !completed_tasks.get(dep).is_some_and(|r| r.success) {
-                    return false;
-                }
-            }
+            Err(e) => Ok(TaskResult::failed(&task.id, e))
         }
-        true
     }
 
     fn get_required_capabilities(&self, task_type: &TaskType) -> HashSet<Capability> {
@@ -188,6 +471,76 @@ impl WorkflowExecutor {
     }
 }
 
+/// Capabilities as the router names them
+fn capability_names(capabilities: &HashSet<Capability>) -> Vec<String> {
+    capabilities.iter().map(|capability| format!("{:?}", capability)).collect()
+}
+
+/// Follow-up for a task whose output failed validation: the original task
+/// with the failed checks and their evidence added to its input
+fn follow_up_task(task: &Task, report: &PipelineReport, round: u32) -> Task {
+    let feedback = serde_json::json!({
+        "round": round,
+        "failures": report.failures().map(|r| serde_json::json!({
+            "check": r.check,
+            "evidence": r.evidence,
+        })).collect::<Vec<_>>(),
+    });
+
+    let input = match &task.input {
+        Value::Object(map) => {
+            let mut map = map.clone();
+            map.insert("validation_feedback".to_string(), feedback);
+            Value::Object(map)
+        }
+        other => serde_json::json!({"original_input": other, "validation_feedback": feedback}),
+    };
+
+    Task {
+        name: format!("{} (validation follow-up {})", task.name, round),
+        input,
+        ..task.clone()
+    }
+}
+
+/// Run `attempt` until it succeeds, the policy's attempts are used up or the
+/// error is not retryable, backing off between attempts. The returned result
+/// records how many attempts were made.
+async fn run_with_retries<F, Fut>(task_id: &str, policy: &RetryPolicy, mut attempt: F) -> TaskResult
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = TaskResult>,
+{
+    let max_attempts = policy.max_attempts.max(1);
+    let mut number = 1;
+
+    loop {
+        let mut result = attempt().await;
+        result.attempts = number;
+        if result.success || number >= max_attempts {
+            return result;
+        }
+
+        let error = result.error.clone().unwrap_or_default();
+        if !policy.should_retry(&error) {
+            debug!("Task {} failed with non-retryable error: {}", task_id, error);
+            return result;
+        }
+
+        number += 1;
+        let delay = policy.backoff(number);
+        warn!(
+            "Task {} failed (attempt {}/{}): {}; retrying in {:?}",
+            task_id,
+            number - 1,
+            max_attempts,
+            error,
+            delay
+        );
+        tokio::time::sleep(delay).await;
+    }
+}
+
 /// Agent pool for managing available agents
 struct AgentPool {
     agents: HashMap<AgentId, Box<dyn Agent>>,
@@ -208,38 +561,26 @@ impl AgentPool {
         }
     }
 
-    fn initialize_default_agents(&mut self, matcher: &mut CapabilityMatcher) {
-        // Create default developer agent
-        let dev_agent = Box::new(DeveloperAgent::new("Developer-1".to_string()));
-        let dev_id = dev_agent.id().clone();
-        let dev_caps = dev_agent.capabilities().clone();
-        self.agents.insert(dev_id.clone(), dev_agent);
-        self.agent_states.insert(dev_id.clone(), AgentPoolState::Idle);
-        matcher.register_agent(dev_id, dev_caps);
-
-        // Create default reviewer agent
-        let review_agent = Box::new(ReviewerAgent::new("Reviewer-1".to_string()));
-        let review_id = review_agent.id().clone();
-        let review_caps = review_agent.capabilities().clone();
-        self.agents.insert(review_id.clone(), review_agent);
-        self.agent_states.insert(review_id.clone(), AgentPoolState::Idle);
-        matcher.register_agent(review_id, review_caps);
-
-        // Create default tester agent
-        let test_agent = Box::new(TesterAgent::new("Tester-1".to_string()));
-        let test_id = test_agent.id().clone();
-        let test_caps = test_agent.capabilities().clone();
-        self.agents.insert(test_id.clone(), test_agent);
-        self.agent_states.insert(test_id.clone(), AgentPoolState::Idle);
-        matcher.register_agent(test_id, test_caps);
-
-        // Create orchestrator agent
-        let orch_agent = Box::new(OrchestratorAgent::new("Orchestrator-1".to_string()));
-        let orch_id = orch_agent.id().clone();
-        let orch_caps = orch_agent.capabilities().clone();
-        self.agents.insert(orch_id.clone(), orch_agent);
-        self.agent_states.insert(orch_id.clone(), AgentPoolState::Idle);
-        matcher.register_agent(orch_id, orch_caps);
+    /// Create the default agents. Returns each agent's ID with its
+    /// capabilities.
+    fn initialize_default_agents(&mut self) -> Vec<(AgentId, HashSet<Capability>)> {
+        let agents: Vec<Box<dyn Agent>> = vec![
+            Box::new(DeveloperAgent::new("Developer-1".to_string())),
+            Box::new(ReviewerAgent::new("Reviewer-1".to_string())),
+            Box::new(TesterAgent::new("Tester-1".to_string())),
+            Box::new(OrchestratorAgent::new("Orchestrator-1".to_string())),
+        ];
+
+        agents
+            .into_iter()
+            .map(|agent| {
+                let agent_id = agent.id().clone();
+                let capabilities = agent.capabilities().clone();
+                self.agents.insert(agent_id.clone(), agent);
+                self.agent_states.insert(agent_id.clone(), AgentPoolState::Idle);
+                (agent_id, capabilities)
+            })
+            .collect()
     }
 
     async fn execute_with_agent(
@@ -351,3 +692,333 @@ impl AgentPool {
         }))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use crate::intelligence::ReputationPrior;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn task(id: &str, when: Option<&str>) -> Task {
+        Task {
+            id: id.to_string(),
+            name: id.to_string(),
+            task_type: TaskType::Development,
+            input: serde_json::json!({}),
+            status: TaskStatus::Pending,
+            when: when.map(str::to_string),
+            retry: None,
+            continue_on_error: false,
+            for_each: None,
+        }
+    }
+
+    fn workflow(tasks: Vec<Task>, dependencies: &[(&str, &[&str])]) -> Workflow {
+        Workflow {
+            id: "wf".to_string(),
+            name: "wf".to_string(),
+            description: String::new(),
+            tasks,
+            dependencies: dependencies
+                .iter()
+                .map(|(task, deps)| (task.to_string(), deps.iter().map(|d| d.to_string()).collect()))
+                .collect(),
+            metadata: WorkflowMetadata {
+                created_at: Utc::now(),
+                priority: 1,
+                timeout: Duration::from_secs(60),
+                max_retries: 0,
+            },
+            quality: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_conditions_skip_branches() {
+        let workflow = workflow(
+            vec![
+                task("notify", None),
+                task("rollback", Some("tasks.build.status == 'failed'")),
+                task("report", Some("tasks.build.output.status == 'completed'")),
+                task("build", None),
+            ],
+            &[("rollback", &["build"]), ("report", &["build"]), ("notify", &["rollback"])],
+        );
+        DagValidator::new().validate(&workflow).unwrap();
+
+        let schedule = TaskScheduler::new().create_schedule(&workflow).await.unwrap();
+        assert_eq!(schedule.sorted_tasks.first().map(String::as_str), Some("build"));
+        assert_eq!(schedule.sorted_tasks.last().map(String::as_str), Some("notify"));
+
+        let result = WorkflowExecutor::new().execute(workflow, schedule).await.unwrap();
+
+        assert!(result.success);
+        assert_eq!(result.task_results["build"].outcome(), "success");
+        assert_eq!(result.task_results["build"].attempts, 1);
+        assert_eq!(result.task_results["rollback"].outcome(), "skipped");
+        assert_eq!(result.task_results["rollback"].attempts, 0);
+        assert_eq!(result.task_results["notify"].outcome(), "skipped");
+        assert_eq!(result.task_results["report"].outcome(), "success");
+    }
+
+    #[tokio::test]
+    async fn test_retries_until_success() {
+        let calls = AtomicU32::new(0);
+        let policy = RetryPolicy {
+            max_attempts: 5,
+            backoff_seconds: 0,
+            retry_on: vec!["timeout|unavailable".to_string()],
+        };
+
+        let result = run_with_retries("t", &policy, || async {
+            match calls.fetch_add(1, Ordering::SeqCst) {
+                0 | 1 => TaskResult::failed("t", "service unavailable"),
+                _ => TaskResult {
+                    task_id: "t".to_string(),
+                    success: true,
+                    output: None,
+                    error: None,
+                    attempts: 1,
+                    skipped: false,
+                    validation: Vec::new(),
+                },
+            }
+        })
+        .await;
+
+        assert!(result.success);
+        assert_eq!(result.attempts, 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_stops_on_unmatched_error_or_exhaustion() {
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            backoff_seconds: 0,
+            retry_on: vec!["timeout".to_string()],
+        };
+
+        let result = run_with_retries("t", &policy, || async { TaskResult::failed("t", "syntax error") }).await;
+        assert_eq!(result.attempts, 1);
+
+        let result = run_with_retries("t", &policy, || async { TaskResult::failed("t", "Task execution timeout") }).await;
+        assert_eq!(result.attempts, 3);
+        assert!(!result.success);
+    }
+
+    #[test]
+    fn test_backoff_doubles() {
+        let policy = RetryPolicy {
+            max_attempts: 4,
+            backoff_seconds: 2,
+            retry_on: Vec::new(),
+        };
+        assert_eq!(policy.backoff(2), Duration::from_secs(2));
+        assert_eq!(policy.backoff(3), Duration::from_secs(4));
+        assert_eq!(policy.backoff(4), Duration::from_secs(8));
+    }
+
+    fn map_task(id: &str, items: &str, input: Value) -> Task {
+        Task {
+            input,
+            for_each: Some(ForEach {
+                items: items.to_string(),
+                max_parallel: 2,
+                fail_fast: true,
+            }),
+            ..task(id, None)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_map_task_fans_out_over_inputs() {
+        let workflow = workflow(
+            vec![
+                map_task(
+                    "lint",
+                    "inputs.files",
+                    serde_json::json!({"description": "Lint {{item.path}} (#{{index}})"}),
+                ),
+                task("report", Some("tasks.lint.output[2].description == 'Lint c.rs (#2)'")),
+            ],
+            &[("report", &["lint"])],
+        );
+        DagValidator::new().validate(&workflow).unwrap();
+
+        let input = serde_json::json!({"files": [{"path": "a.rs"}, {"path": "b.rs"}, {"path": "c.rs"}]});
+        let schedule = TaskScheduler::new().create_schedule(&workflow).await.unwrap();
+        let result = WorkflowExecutor::new()
+            .execute_with_input(workflow, schedule, input)
+            .await
+            .unwrap();
+
+        assert!(result.success);
+        let lint = &result.task_results["lint"];
+        let outputs = lint.output.as_ref().unwrap().as_array().unwrap();
+        assert_eq!(outputs.len(), 3);
+        assert_eq!(outputs[1]["task_id"], "lint[1]");
+        assert_eq!(outputs[1]["description"], "Lint b.rs (#1)");
+        assert_eq!(result.task_results["lint[0]"].outcome(), "success");
+        assert_eq!(result.task_results["report"].outcome(), "success");
+    }
+
+    #[tokio::test]
+    async fn test_map_task_requires_an_array() {
+        let workflow = workflow(
+            vec![
+                map_task("each", "tasks.build.output.lines_added", serde_json::json!({})),
+                task("build", None),
+            ],
+            &[("each", &["build"])],
+        );
+        DagValidator::new().validate(&workflow).unwrap();
+
+        let schedule = TaskScheduler::new().create_schedule(&workflow).await.unwrap();
+        let result = WorkflowExecutor::new().execute(workflow, schedule).await.unwrap();
+
+        assert!(!result.success);
+        assert!(result.task_results["each"].error.as_deref().unwrap().contains("did not resolve to an array"));
+    }
+
+    #[test]
+    fn test_child_ids_and_templates() {
+        assert_eq!(ForEach::child_id("lint", 3), "lint[3]");
+        assert_eq!(ForEach::parse_child_id("lint[3]"), Some(("lint", 3)));
+        assert_eq!(ForEach::parse_child_id("lint"), None);
+        assert_eq!(ForEach::parse_child_id("lint[x]"), None);
+
+        let item = serde_json::json!({"name": "core", "deps": ["a", "b"]});
+        let input = serde_json::json!({
+            "deps": "{{item.deps}}",
+            "first": "{{ item.deps.0 }}",
+            "text": "{{index}}-{{item.name}}-{{unknown}}",
+            "n": 5
+        });
+        assert_eq!(
+            ForEach::child_input(&input, &item, 7),
+            serde_json::json!({"deps": ["a", "b"], "first": "a", "text": "7-core-{{unknown}}", "n": 5})
+        );
+    }
+
+    #[test]
+    fn test_validation_rejects_child_dependencies() {
+        let depends_on_child = workflow(
+            vec![map_task("lint", "inputs.files", serde_json::json!({})), task("report", None)],
+            &[("report", &["lint[0]"])],
+        );
+        assert!(matches!(
+            DagValidator::new().validate(&depends_on_child),
+            Err(OrchestrationError::InvalidTask { reason, .. }) if reason.contains("depend on 'lint' instead")
+        ));
+
+        let mut serial = map_task("lint", "inputs.files", serde_json::json!({}));
+        serial.for_each.as_mut().unwrap().max_parallel = 0;
+        assert!(DagValidator::new().validate(&workflow(vec![serial], &[])).is_err());
+
+        let not_upstream = workflow(
+            vec![map_task("lint", "tasks.scan.output.files", serde_json::json!({})), task("scan", None)],
+            &[],
+        );
+        assert!(DagValidator::new().validate(&not_upstream).is_err());
+    }
+
+    #[test]
+    fn test_validation_rejects_unknown_task_outputs() {
+        let unknown = workflow(vec![task("a", Some("tasks.ghost.status == 'success'"))], &[]);
+        assert!(matches!(
+            DagValidator::new().validate(&unknown),
+            Err(OrchestrationError::InvalidTask { .. })
+        ));
+
+        // `b` exists but is not upstream of `a`, so it may not have run yet
+        let not_upstream = workflow(vec![task("a", Some("tasks.b.output.x == 1")), task("b", None)], &[]);
+        assert!(DagValidator::new().validate(&not_upstream).is_err());
+
+        let mut bad_retry = task("a", None);
+        bad_retry.retry = Some(RetryPolicy {
+            retry_on: vec!["(".to_string()],
+            ..Default::default()
+        });
+        assert!(DagValidator::new().validate(&workflow(vec![bad_retry], &[])).is_err());
+    }
+
+    fn diff_limited(max_lines_changed: u64) -> QualityConfig {
+        QualityConfig {
+            max_retries: 1,
+            checks: vec![crate::quality::CheckSpec {
+                max_lines_changed: Some(max_lines_changed),
+                ..crate::quality::CheckSpec::new(crate::quality::CheckKind::DiffSize)
+            }],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_validation_is_followed_up_then_fails() {
+        // The developer agent reports 52 changed lines
+        let mut workflow = workflow(vec![task("build", None)], &[]);
+        workflow.quality = Some(diff_limited(10));
+
+        let schedule = TaskScheduler::new().create_schedule(&workflow).await.unwrap();
+        let result = WorkflowExecutor::new().execute(workflow, schedule).await.unwrap();
+
+        assert!(!result.success);
+        let build = &result.task_results["build"];
+        assert_eq!(build.validation.len(), 2);
+        assert!(build.validation.iter().all(|report| !report.passed));
+        assert!(build.error.as_deref().unwrap().contains("Validation failed after 1 follow-up(s)"));
+        assert!(build.error.as_deref().unwrap().contains("diff_size"));
+    }
+
+    #[tokio::test]
+    async fn test_passing_validation_is_recorded() {
+        let workflow = workflow(vec![task("build", None), task("review", None)], &[("review", &["build"])]);
+        let schedule = TaskScheduler::new().create_schedule(&workflow).await.unwrap();
+        let result = WorkflowExecutor::new()
+            .with_quality(diff_limited(100))
+            .execute(workflow, schedule)
+            .await
+            .unwrap();
+
+        assert!(result.success);
+        assert_eq!(result.task_results["build"].validation.len(), 1);
+        assert!(result.task_results["build"].validation[0].passed);
+    }
+
+    #[tokio::test]
+    async fn test_outcomes_feed_reputation() {
+        let reputation = Arc::new(AgentReputation::new());
+        let mut workflow = workflow(vec![task("build", None)], &[]);
+        workflow.quality = Some(diff_limited(10));
+
+        let schedule = TaskScheduler::new().create_schedule(&workflow).await.unwrap();
+        let executor = WorkflowExecutor::new().with_reputation(reputation.clone());
+        executor.execute(workflow, schedule).await.unwrap();
+
+        let ranked = reputation.rank_agents("development");
+        assert_eq!(ranked.len(), 1);
+        let stats = reputation.stats(&ranked[0].0, "development");
+        assert_eq!(stats.samples, 1);
+        assert!(stats.success_rate < ReputationPrior::default().success_rate);
+        assert!(stats.validation_pass_rate < ReputationPrior::default().validation_pass_rate);
+    }
+
+    #[test]
+    fn test_follow_up_task_carries_failures() {
+        let report = PipelineReport {
+            task_id: "build".to_string(),
+            passed: false,
+            results: vec![crate::quality::pipeline::CheckResult::fail("lint", "unused variable `x`")],
+        };
+
+        let follow_up = follow_up_task(&task("build", None), &report, 1);
+        assert_eq!(follow_up.id, "build");
+        assert_eq!(follow_up.input["validation_feedback"]["round"], 1);
+        assert_eq!(follow_up.input["validation_feedback"]["failures"][0]["check"], "lint");
+
+        let mut scalar = task("build", None);
+        scalar.input = serde_json::json!("write the parser");
+        let follow_up = follow_up_task(&scalar, &report, 2);
+        assert_eq!(follow_up.input["original_input"], "write the parser");
+    }
+}
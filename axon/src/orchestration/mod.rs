@@ -30,6 +30,7 @@
 //! - 90% time reduction for complex queries through parallelization
 //! - Intelligent resource allocation based on query complexity
 //! - Worker pool management with capability matching
+//! - Capability-aware task routing with pluggable load balancing
 //!
 //! ## Usage Example
 //!
@@ -53,6 +54,7 @@ use serde::{Deserialize, Serialize};
 
 // DAG-based workflow modules
 pub mod workflow;
+pub mod condition;
 pub mod scheduler;
 pub mod executor;
 pub mod dag;
@@ -66,9 +68,11 @@ pub mod result_synthesizer;
 pub mod execution_plan;
 pub mod runtime_integration;
 pub mod parallel_tool_executor;
+pub mod task_router;
 
 // Re-export DAG-based types
 pub use workflow::*;
+pub use condition::*;
 pub use scheduler::*;
 pub use executor::*;
 pub use dag::*;
@@ -82,6 +86,10 @@ pub use result_synthesizer::{ResultSynthesizer, SynthesizedResult, Finding, Reco
 pub use execution_plan::{ExecutionPlan, ResourceAllocation, ExecutionProgress};
 pub use runtime_integration::{RuntimeIntegration, LeadAgentWithRuntime};
 pub use parallel_tool_executor::{ParallelToolExecutor, ToolCall, ToolResult, ExecutionStats};
+pub use task_router::{
+    TaskRouter, RouteRequest, RouteDecision, WaitingTask, RoutingStrategy, AgentLoad, LeastLoaded,
+    FastestResponse, RoundRobin, WAITING_STATE,
+};
 
 /// Main orchestrator for coordinating agent workflows
 pub struct Orchestrator {
@@ -111,6 +119,16 @@ impl Orchestrator {
 
     /// Execute a workflow
     pub async fn execute_workflow(&self, workflow: Workflow) -> Result<WorkflowResult> {
+        self.execute_workflow_with_input(workflow, serde_json::Value::Null).await
+    }
+
+    /// Execute a workflow, exposing `input` to `inputs.` references in
+    /// conditions and `for_each` items
+    pub async fn execute_workflow_with_input(
+        &self,
+        workflow: Workflow,
+        input: serde_json::Value,
+    ) -> Result<WorkflowResult> {
         // Validate workflow DAG
         self.validator.validate(&workflow)?;
 
@@ -124,7 +142,7 @@ impl Orchestrator {
             .insert(workflow.id.clone(), WorkflowStatus::Running);
 
         // Execute workflow
-        let result = self.executor.execute(workflow, schedule).await?;
+        let result = self.executor.execute_with_input(workflow, schedule, input).await?;
 
         // Update status
         let status = if result.success {
@@ -178,6 +196,9 @@ pub enum OrchestrationError {
     #[error("Invalid DAG: {reason}")]
     InvalidDag { reason: String },
 
+    #[error("Invalid task {task_id}: {reason}")]
+    InvalidTask { task_id: String, reason: String },
+
     #[error("No suitable agent for task {task_id}")]
     NoSuitableAgent { task_id: String },
 
@@ -25,36 +25,42 @@ impl TaskScheduler {
         })
     }
 
+    /// Order tasks so every task comes after the tasks it depends on
     fn topological_sort(&self, workflow: &Workflow) -> Result<Vec<String>> {
         let mut result = Vec::new();
-        let mut in_degree: HashMap<String, usize> = HashMap::new();
+        let mut in_degree: HashMap<&str, usize> = HashMap::new();
+        let mut dependents: HashMap<&str, Vec<&str>> = HashMap::new();
 
         for task in &workflow.tasks {
-            in_degree.insert(task.id.clone(), 0);
+            in_degree.insert(task.id.as_str(), 0);
         }
 
-        for deps in workflow.dependencies.values() {
+        for (task_id, deps) in &workflow.dependencies {
             for dep in deps {
-                *in_degree.get_mut(dep).unwrap() += 1;
+                let degree = in_degree.get_mut(task_id.as_str()).ok_or_else(|| OrchestrationError::TaskNotFound {
+                    task_id: task_id.clone(),
+                })?;
+                *degree += 1;
+                dependents.entry(dep.as_str()).or_default().push(task_id.as_str());
             }
         }
 
-        let mut queue: VecDeque<String> = in_degree
+        // Declaration order among ready tasks keeps schedules deterministic
+        let mut queue: VecDeque<&str> = workflow
+            .tasks
             .iter()
-            .filter(|&(_, &degree)| degree == 0)
-            .map(|(id, _)| id.clone())
+            .map(|t| t.id.as_str())
+            .filter(|id| in_degree[id] == 0)
             .collect();
 
         while let Some(task_id) = queue.pop_front() {
-            result.push(task_id.clone());
+            result.push(task_id.to_string());
 
-            if let Some(deps) = workflow.dependencies.get(&task_id) {
-                for dep in deps {
-                    let degree = in_degree.get_mut(dep).unwrap();
-                    *degree -= 1;
-                    if *degree == 0 {
-                        queue.push_back(dep.clone());
-                    }
+            for dependent in dependents.get(task_id).into_iter().flatten() {
+                let degree = in_degree.get_mut(dependent).unwrap();
+                *degree -= 1;
+                if *degree == 0 {
+                    queue.push_back(dependent);
                 }
             }
         }
@@ -0,0 +1,613 @@
+//! Task Router - Capability-Aware Task Placement
+//!
+//! Places tasks on agents by the free-form capabilities agents declare when
+//! they start. A task names capabilities it requires and, optionally, ones
+//! it prefers:
+//!
+//! 1. Agents lacking any required capability are ruled out
+//! 2. Of the rest, those matching the most preferred capabilities are kept
+//! 3. A [`RoutingStrategy`] picks one of them; among agents it considers
+//!    equal, the one with the best [`AgentReputation`] for the task type wins
+//!
+//! Tasks no agent can take wait in a queue and are placed, in arrival order,
+//! as soon as a capable agent registers. Capabilities compare
+//! case-insensitively.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use tracing::{debug, info};
+
+use crate::agents::{AgentId, AgentMetrics};
+use crate::intelligence::AgentReputation;
+
+/// State shown for tasks queued in the router
+pub const WAITING_STATE: &str = "waiting for capable agent";
+
+// ============================================================================
+// Requests and Decisions
+// ============================================================================
+
+/// A task to place on an agent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RouteRequest {
+    pub task_id: String,
+    /// Capabilities the agent must have, all of them
+    #[serde(default)]
+    pub required_capabilities: Vec<String>,
+    /// Capabilities that make an agent a better fit
+    #[serde(default)]
+    pub preferred_capabilities: Vec<String>,
+    /// Kind of work, used to look up agents' reputation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task_type: Option<String>,
+    #[serde(default)]
+    pub payload: serde_json::Value,
+}
+
+/// Where a task went
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RouteDecision {
+    Assigned(AgentId),
+    /// No registered agent has the required capabilities; the task is queued
+    Waiting,
+}
+
+/// A task queued until a capable agent registers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaitingTask {
+    pub request: RouteRequest,
+    pub since: DateTime<Utc>,
+}
+
+impl WaitingTask {
+    pub fn state(&self) -> &'static str {
+        WAITING_STATE
+    }
+}
+
+// ============================================================================
+// Routing Strategies
+// ============================================================================
+
+/// A capable agent as seen by a [`RoutingStrategy`]
+#[derive(Debug, Clone)]
+pub struct AgentLoad {
+    pub agent_id: AgentId,
+    /// Tasks routed to the agent and not yet completed
+    pub in_flight: usize,
+    /// Average time from routing to completion, if any task has completed
+    pub avg_response_ms: Option<f64>,
+}
+
+/// Picks the agent for a task among the capable ones
+pub trait RoutingStrategy: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Index of the chosen agent. `candidates` is never empty and is sorted
+    /// best reputation first, then by agent ID, so strategies that keep the
+    /// first of equally good agents break ties by reputation.
+    fn select(&self, candidates: &[AgentLoad]) -> usize;
+}
+
+/// Agent with the fewest in-flight tasks
+#[derive(Debug, Default)]
+pub struct LeastLoaded;
+
+impl RoutingStrategy for LeastLoaded {
+    fn name(&self) -> &str {
+        "least_loaded"
+    }
+
+    fn select(&self, candidates: &[AgentLoad]) -> usize {
+        candidates
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, agent)| agent.in_flight)
+            .map(|(index, _)| index)
+            .unwrap_or(0)
+    }
+}
+
+/// Agent with the lowest average response time. Agents without
+/// measurements go first so they get measured; ties go to the less loaded.
+#[derive(Debug, Default)]
+pub struct FastestResponse;
+
+impl RoutingStrategy for FastestResponse {
+    fn name(&self) -> &str {
+        "fastest_response"
+    }
+
+    fn select(&self, candidates: &[AgentLoad]) -> usize {
+        candidates
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| {
+                let a_ms = a.avg_response_ms.unwrap_or(0.0);
+                let b_ms = b.avg_response_ms.unwrap_or(0.0);
+                a_ms.total_cmp(&b_ms).then(a.in_flight.cmp(&b.in_flight))
+            })
+            .map(|(index, _)| index)
+            .unwrap_or(0)
+    }
+}
+
+/// Capable agents in turn
+#[derive(Debug, Default)]
+pub struct RoundRobin {
+    next: AtomicUsize,
+}
+
+impl RoutingStrategy for RoundRobin {
+    fn name(&self) -> &str {
+        "round_robin"
+    }
+
+    fn select(&self, candidates: &[AgentLoad]) -> usize {
+        self.next.fetch_add(1, Ordering::Relaxed) % candidates.len()
+    }
+}
+
+// ============================================================================
+// Task Router
+// ============================================================================
+
+struct RoutedAgent {
+    capabilities: HashSet<String>,
+    /// Routed task IDs and when they were routed
+    in_flight: HashMap<String, Instant>,
+    avg_response_ms: Option<f64>,
+    completed: u64,
+}
+
+impl RoutedAgent {
+    fn new(capabilities: &[String]) -> Self {
+        Self {
+            capabilities: normalize(capabilities),
+            in_flight: HashMap::new(),
+            avg_response_ms: None,
+            completed: 0,
+        }
+    }
+}
+
+#[derive(Default)]
+struct RouterState {
+    agents: HashMap<AgentId, RoutedAgent>,
+    waiting: VecDeque<WaitingTask>,
+}
+
+impl RouterState {
+    /// Pick an agent for `request` and record the task as in flight on it
+    fn place(
+        &mut self,
+        request: &RouteRequest,
+        strategy: &dyn RoutingStrategy,
+        reputation: Option<&AgentReputation>,
+    ) -> Option<AgentId> {
+        let required = normalize(&request.required_capabilities);
+        let preferred = normalize(&request.preferred_capabilities);
+
+        let capable: Vec<(&AgentId, &RoutedAgent)> = self
+            .agents
+            .iter()
+            .filter(|(_, agent)| required.is_subset(&agent.capabilities))
+            .collect();
+        let best_fit = capable
+            .iter()
+            .map(|(_, agent)| preferred.intersection(&agent.capabilities).count())
+            .max()?;
+
+        let mut candidates: Vec<AgentLoad> = capable
+            .into_iter()
+            .filter(|(_, agent)| preferred.intersection(&agent.capabilities).count() == best_fit)
+            .map(|(agent_id, agent)| AgentLoad {
+                agent_id: agent_id.clone(),
+                in_flight: agent.in_flight.len(),
+                avg_response_ms: agent.avg_response_ms,
+            })
+            .collect();
+        let score = |agent_id: &AgentId| match (reputation, &request.task_type) {
+            (Some(reputation), Some(task_type)) => reputation.score(agent_id, task_type),
+            _ => 0.0,
+        };
+        candidates.sort_by(|a, b| {
+            score(&b.agent_id)
+                .total_cmp(&score(&a.agent_id))
+                .then_with(|| a.agent_id.to_string().cmp(&b.agent_id.to_string()))
+        });
+
+        let chosen = candidates
+            .get(strategy.select(&candidates))
+            .unwrap_or(&candidates[0])
+            .agent_id
+            .clone();
+        if let Some(agent) = self.agents.get_mut(&chosen) {
+            agent
+                .in_flight
+                .insert(request.task_id.clone(), Instant::now());
+        }
+        Some(chosen)
+    }
+}
+
+/// Routes tasks to agents by capability
+pub struct TaskRouter {
+    strategy: Box<dyn RoutingStrategy>,
+    state: RwLock<RouterState>,
+    reputation: Option<Arc<AgentReputation>>,
+}
+
+impl Default for TaskRouter {
+    fn default() -> Self {
+        Self::new(LeastLoaded)
+    }
+}
+
+impl TaskRouter {
+    pub fn new(strategy: impl RoutingStrategy + 'static) -> Self {
+        Self {
+            strategy: Box::new(strategy),
+            state: RwLock::new(RouterState::default()),
+            reputation: None,
+        }
+    }
+
+    /// Break ties between equally suitable agents by their reputation for
+    /// the request's task type
+    pub fn with_reputation(mut self, reputation: Arc<AgentReputation>) -> Self {
+        self.reputation = Some(reputation);
+        self
+    }
+
+    /// Register an agent before any task is routed
+    pub fn with_agent(mut self, agent_id: AgentId, capabilities: &[String]) -> Self {
+        self.state.get_mut().agents.insert(agent_id, RoutedAgent::new(capabilities));
+        self
+    }
+
+    pub fn strategy_name(&self) -> &str {
+        self.strategy.name()
+    }
+
+    /// Make an agent available for routing. Returns the waiting tasks it
+    /// unblocked, each with the agent it was placed on.
+    pub async fn register_agent(
+        &self,
+        agent_id: AgentId,
+        capabilities: &[String],
+    ) -> Vec<(AgentId, RouteRequest)> {
+        info!(
+            "Registering agent {} for routing with capabilities {:?}",
+            agent_id, capabilities
+        );
+        let mut state = self.state.write().await;
+        state.agents.insert(agent_id, RoutedAgent::new(capabilities));
+
+        let mut placed = Vec::new();
+        let mut still_waiting = VecDeque::new();
+        while let Some(waiting) = state.waiting.pop_front() {
+            match state.place(&waiting.request, self.strategy.as_ref(), self.reputation.as_deref()) {
+                Some(agent_id) => {
+                    debug!(
+                        "Waiting task {} placed on agent {}",
+                        waiting.request.task_id, agent_id
+                    );
+                    placed.push((agent_id, waiting.request));
+                }
+                None => still_waiting.push_back(waiting),
+            }
+        }
+        state.waiting = still_waiting;
+        placed
+    }
+
+    /// Stop routing to an agent. Returns the IDs of tasks still in flight
+    /// on it.
+    pub async fn unregister_agent(&self, agent_id: &AgentId) -> Vec<String> {
+        self.state
+            .write()
+            .await
+            .agents
+            .remove(agent_id)
+            .map(|agent| agent.in_flight.into_keys().collect())
+            .unwrap_or_default()
+    }
+
+    /// Place a task, or queue it if no registered agent has its required
+    /// capabilities
+    pub async fn route(&self, request: RouteRequest) -> RouteDecision {
+        let mut state = self.state.write().await;
+        match state.place(&request, self.strategy.as_ref(), self.reputation.as_deref()) {
+            Some(agent_id) => {
+                debug!(
+                    "Routed task {} to agent {} ({})",
+                    request.task_id,
+                    agent_id,
+                    self.strategy.name()
+                );
+                RouteDecision::Assigned(agent_id)
+            }
+            None => {
+                info!(
+                    "Task {} is {} (requires {:?})",
+                    request.task_id, WAITING_STATE, request.required_capabilities
+                );
+                state.waiting.push_back(WaitingTask {
+                    request,
+                    since: Utc::now(),
+                });
+                RouteDecision::Waiting
+            }
+        }
+    }
+
+    /// Place a task without queueing it. Returns `None` if no registered
+    /// agent has its required capabilities.
+    pub async fn try_route(&self, request: &RouteRequest) -> Option<AgentId> {
+        let agent_id = self
+            .state
+            .write()
+            .await
+            .place(request, self.strategy.as_ref(), self.reputation.as_deref())?;
+        debug!(
+            "Routed task {} to agent {} ({})",
+            request.task_id,
+            agent_id,
+            self.strategy.name()
+        );
+        Some(agent_id)
+    }
+
+    /// Mark a routed task as done, folding its duration into the agent's
+    /// average response time. Returns whether the task was in flight on the
+    /// agent.
+    pub async fn complete(&self, agent_id: &AgentId, task_id: &str) -> bool {
+        let mut state = self.state.write().await;
+        let Some(agent) = state.agents.get_mut(agent_id) else {
+            return false;
+        };
+        let Some(routed_at) = agent.in_flight.remove(task_id) else {
+            return false;
+        };
+
+        let elapsed_ms = routed_at.elapsed().as_secs_f64() * 1000.0;
+        let previous = agent.avg_response_ms.unwrap_or(0.0) * agent.completed as f64;
+        agent.completed += 1;
+        agent.avg_response_ms = Some((previous + elapsed_ms) / agent.completed as f64);
+        true
+    }
+
+    /// Seed an agent's average response time from its metrics
+    pub async fn update_metrics(&self, agent_id: &AgentId, metrics: &AgentMetrics) {
+        let completed = metrics.tasks_completed.load(Ordering::Relaxed);
+        if completed == 0 {
+            return;
+        }
+        if let Some(agent) = self.state.write().await.agents.get_mut(agent_id) {
+            agent.avg_response_ms =
+                Some(metrics.avg_task_duration_ms.load(Ordering::Relaxed) as f64);
+            agent.completed = completed;
+        }
+    }
+
+    /// Tasks currently routed to an agent
+    pub async fn in_flight(&self, agent_id: &AgentId) -> usize {
+        self.state
+            .read()
+            .await
+            .agents
+            .get(agent_id)
+            .map(|agent| agent.in_flight.len())
+            .unwrap_or(0)
+    }
+
+    /// Queued tasks, oldest first
+    pub async fn waiting(&self) -> Vec<WaitingTask> {
+        self.state.read().await.waiting.iter().cloned().collect()
+    }
+}
+
+fn normalize(capabilities: &[String]) -> HashSet<String> {
+    capabilities
+        .iter()
+        .map(|capability| capability.trim().to_lowercase())
+        .filter(|capability| !capability.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn caps(list: &str) -> Vec<String> {
+        list.split(',').map(String::from).collect()
+    }
+
+    fn request(task_id: &str, required: &str, preferred: &str) -> RouteRequest {
+        RouteRequest {
+            task_id: task_id.to_string(),
+            required_capabilities: caps(required),
+            preferred_capabilities: caps(preferred),
+            task_type: None,
+            payload: serde_json::Value::Null,
+        }
+    }
+
+    /// Two agents can take `rust,testing` work, the others cannot
+    async fn mixed_fleet(router: &TaskRouter) {
+        router
+            .register_agent(AgentId::from_string("py-tester"), &caps("python,testing"))
+            .await;
+        router
+            .register_agent(AgentId::from_string("rust-dev"), &caps("rust,codegen"))
+            .await;
+        router
+            .register_agent(AgentId::from_string("rust-qa-1"), &caps("Rust,Testing"))
+            .await;
+        router
+            .register_agent(
+                AgentId::from_string("rust-qa-2"),
+                &caps("rust,testing,fuzzing"),
+            )
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_required_capabilities_least_loaded() {
+        let router = TaskRouter::default();
+        mixed_fleet(&router).await;
+
+        let mut placed = Vec::new();
+        for i in 0..4 {
+            match router
+                .route(request(&format!("t{}", i), "rust,testing", ""))
+                .await
+            {
+                RouteDecision::Assigned(agent_id) => placed.push(agent_id.to_string()),
+                RouteDecision::Waiting => panic!("task t{} should have been placed", i),
+            }
+        }
+        assert_eq!(
+            placed,
+            vec!["rust-qa-1", "rust-qa-2", "rust-qa-1", "rust-qa-2"]
+        );
+        assert_eq!(router.in_flight(&AgentId::from_string("rust-dev")).await, 0);
+
+        // Preferred capabilities narrow the choice without being required
+        assert_eq!(
+            router
+                .route(request("fuzz", "rust,testing", "fuzzing"))
+                .await,
+            RouteDecision::Assigned(AgentId::from_string("rust-qa-2"))
+        );
+        assert_eq!(
+            router.route(request("any", "rust,testing", "gpu")).await,
+            RouteDecision::Assigned(AgentId::from_string("rust-qa-1"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fastest_response_and_round_robin() {
+        let router = TaskRouter::new(FastestResponse);
+        mixed_fleet(&router).await;
+        let slow = AgentId::from_string("rust-qa-1");
+        let metrics = AgentMetrics::new();
+        metrics.record_success(5_000, 0, 0);
+        router.update_metrics(&slow, &metrics).await;
+
+        assert_eq!(
+            router.route(request("t0", "rust,testing", "")).await,
+            RouteDecision::Assigned(AgentId::from_string("rust-qa-2"))
+        );
+
+        let router = TaskRouter::new(RoundRobin::default());
+        mixed_fleet(&router).await;
+        let mut placed = Vec::new();
+        for i in 0..3 {
+            if let RouteDecision::Assigned(agent_id) = router
+                .route(request(&format!("t{}", i), "testing", ""))
+                .await
+            {
+                placed.push(agent_id.to_string());
+            }
+        }
+        assert_eq!(placed, vec!["py-tester", "rust-qa-1", "rust-qa-2"]);
+    }
+
+    #[tokio::test]
+    async fn test_waiting_task_placed_when_capable_agent_starts() {
+        let router = TaskRouter::default();
+        router
+            .register_agent(AgentId::from_string("rust-dev"), &caps("rust"))
+            .await;
+
+        assert_eq!(
+            router.route(request("t0", "rust,testing", "")).await,
+            RouteDecision::Waiting
+        );
+        let waiting = router.waiting().await;
+        assert_eq!(waiting.len(), 1);
+        assert_eq!(waiting[0].state(), "waiting for capable agent");
+
+        assert!(
+            router
+                .register_agent(AgentId::from_string("py-tester"), &caps("python,testing"))
+                .await
+                .is_empty()
+        );
+
+        let qa = AgentId::from_string("rust-qa");
+        let placed = router
+            .register_agent(qa.clone(), &caps("rust,testing"))
+            .await;
+        assert_eq!(placed.len(), 1);
+        assert_eq!(placed[0].0, qa);
+        assert_eq!(placed[0].1.task_id, "t0");
+        assert!(router.waiting().await.is_empty());
+
+        assert!(router.complete(&qa, "t0").await);
+        assert!(!router.complete(&qa, "t0").await);
+        assert_eq!(router.in_flight(&qa).await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_try_route_does_not_queue() {
+        let qa = AgentId::from_string("rust-qa");
+        let router = TaskRouter::default().with_agent(qa.clone(), &caps("rust,testing"));
+
+        assert_eq!(router.try_route(&request("t0", "rust", "")).await, Some(qa.clone()));
+        assert_eq!(router.in_flight(&qa).await, 1);
+
+        assert_eq!(router.try_route(&request("t1", "python", "")).await, None);
+        assert!(router.waiting().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_reputation_breaks_ties() {
+        let reputation = Arc::new(AgentReputation::new());
+        let unreliable = AgentId::from_string("rust-qa-1");
+        for _ in 0..3 {
+            reputation.record(
+                &unreliable,
+                &crate::intelligence::TaskOutcome::new(
+                    "testing",
+                    std::time::Duration::from_secs(1),
+                    false,
+                ),
+            );
+        }
+
+        let router = TaskRouter::default().with_reputation(reputation);
+        mixed_fleet(&router).await;
+
+        let mut testing = request("t0", "rust,testing", "");
+        testing.task_type = Some("testing".to_string());
+        assert_eq!(
+            router.route(testing).await,
+            RouteDecision::Assigned(AgentId::from_string("rust-qa-2"))
+        );
+
+        // Load still comes first: rust-qa-2 is now busier
+        let mut testing = request("t1", "rust,testing", "");
+        testing.task_type = Some("testing".to_string());
+        assert_eq!(
+            router.route(testing).await,
+            RouteDecision::Assigned(unreliable)
+        );
+
+        // Without a task type there is nothing to look up
+        let reputation_router = TaskRouter::default().with_reputation(Arc::new(AgentReputation::new()));
+        mixed_fleet(&reputation_router).await;
+        assert_eq!(
+            reputation_router.route(request("t2", "rust,testing", "")).await,
+            RouteDecision::Assigned(AgentId::from_string("rust-qa-1"))
+        );
+    }
+}
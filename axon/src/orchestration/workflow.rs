@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use chrono::{DateTime, Utc};
 use std::time::Duration;
+use crate::quality::{PipelineReport, QualityConfig};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Workflow {
@@ -13,6 +14,9 @@ pub struct Workflow {
     pub tasks: Vec<Task>,
     pub dependencies: HashMap<String, Vec<String>>,
     pub metadata: WorkflowMetadata,
+    /// Post-task validation; overrides the executor's default from axon.json
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quality: Option<QualityConfig>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,6 +34,176 @@ pub struct Task {
     pub task_type: TaskType,
     pub input: serde_json::Value,
     pub status: TaskStatus,
+    /// Condition over earlier task results; the task is skipped when false
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub when: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry: Option<RetryPolicy>,
+    /// A failure neither fails the workflow nor blocks dependent tasks
+    #[serde(default)]
+    pub continue_on_error: bool,
+    /// Run one child instance of the task per item of a collection
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub for_each: Option<ForEach>,
+}
+
+/// Fan-out settings for a map task.
+///
+/// Each item gets a child instance with ID `<task>[<index>]` whose input is
+/// the task input with `{{item}}`, `{{item.<path>}}` and `{{index}}`
+/// substituted. The parent's output is the array of child outputs, in item
+/// order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForEach {
+    /// Reference resolving to an array, e.g. `inputs.files` or
+    /// `tasks.scan.output.modules`
+    pub items: String,
+    #[serde(default = "default_max_parallel")]
+    pub max_parallel: usize,
+    /// Stop starting children after the first failure
+    #[serde(default = "default_fail_fast")]
+    pub fail_fast: bool,
+}
+
+fn default_max_parallel() -> usize {
+    4
+}
+
+fn default_fail_fast() -> bool {
+    true
+}
+
+impl ForEach {
+    /// ID of the child instance for item `index`
+    pub fn child_id(task_id: &str, index: usize) -> String {
+        format!("{}[{}]", task_id, index)
+    }
+
+    /// Split a child instance ID into its parent task ID and item index
+    pub fn parse_child_id(id: &str) -> Option<(&str, usize)> {
+        let (parent, rest) = id.strip_suffix(']')?.rsplit_once('[')?;
+        Some((parent, rest.parse().ok()?))
+    }
+
+    /// Input for one child: substitute the item into every string of the
+    /// task input. A string that is exactly `{{item}}` (or `{{item.<path>}}`)
+    /// takes the item's value as-is, so arrays and objects pass through.
+    pub fn child_input(input: &serde_json::Value, item: &serde_json::Value, index: usize) -> serde_json::Value {
+        use serde_json::Value;
+
+        match input {
+            Value::String(template) => {
+                if let Some(value) = template
+                    .trim()
+                    .strip_prefix("{{")
+                    .and_then(|rest| rest.strip_suffix("}}"))
+                    .filter(|inner| !inner.contains("{{") && !inner.contains("}}"))
+                    .and_then(|inner| template_value(inner.trim(), item, index))
+                {
+                    return value;
+                }
+                Value::String(render_template(template, item, index))
+            }
+            Value::Array(values) => Value::Array(values.iter().map(|v| Self::child_input(v, item, index)).collect()),
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(k, v)| (k.clone(), Self::child_input(v, item, index)))
+                    .collect(),
+            ),
+            other => other.clone(),
+        }
+    }
+}
+
+/// Value of a `{{...}}` placeholder, or `None` if it is not one of ours
+fn template_value(name: &str, item: &serde_json::Value, index: usize) -> Option<serde_json::Value> {
+    if name == "index" {
+        return Some(serde_json::Value::from(index));
+    }
+    let path = match name.strip_prefix("item") {
+        Some("") => return Some(item.clone()),
+        Some(rest) => rest.strip_prefix('.')?,
+        None => return None,
+    };
+
+    let mut current = item;
+    for key in path.split('.') {
+        current = match key.parse::<usize>() {
+            Ok(i) if current.is_array() => current.get(i),
+            _ => current.get(key),
+        }
+        .unwrap_or(&serde_json::Value::Null);
+    }
+    Some(current.clone())
+}
+
+/// Replace every known placeholder in `template`, leaving unknown ones intact
+fn render_template(template: &str, item: &serde_json::Value, index: usize) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start..].find("}}") else {
+            break;
+        };
+        let placeholder = &rest[start..start + len + 2];
+        rendered.push_str(&rest[..start]);
+        match template_value(placeholder[2..placeholder.len() - 2].trim(), item, index) {
+            Some(serde_json::Value::String(s)) => rendered.push_str(&s),
+            Some(value) => rendered.push_str(&value.to_string()),
+            None => rendered.push_str(placeholder),
+        }
+        rest = &rest[start + len + 2..];
+    }
+
+    rendered.push_str(rest);
+    rendered
+}
+
+/// Per-task retry policy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// Total attempts including the first
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled after each further attempt
+    #[serde(default)]
+    pub backoff_seconds: u64,
+    /// Regexes matched against the error; empty retries every failure
+    #[serde(default)]
+    pub retry_on: Vec<String>,
+}
+
+fn default_max_attempts() -> u32 {
+    1
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_max_attempts(),
+            backoff_seconds: 0,
+            retry_on: Vec::new(),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before attempt number `attempt` (2 for the first retry)
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let doublings = attempt.saturating_sub(2).min(16);
+        Duration::from_secs(self.backoff_seconds.saturating_mul(1 << doublings))
+    }
+
+    /// Whether an error may be retried. Invalid patterns never match; the
+    /// DAG validator rejects them up front.
+    pub fn should_retry(&self, error: &str) -> bool {
+        self.retry_on.is_empty()
+            || self
+                .retry_on
+                .iter()
+                .any(|pattern| regex::Regex::new(pattern).is_ok_and(|re| re.is_match(error)))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,6 +215,19 @@ pub enum TaskType {
     Custom(String),
 }
 
+impl TaskType {
+    /// Name used to select quality checks, e.g. `development`
+    pub fn as_str(&self) -> &str {
+        match self {
+            TaskType::Development => "development",
+            TaskType::Review => "review",
+            TaskType::Testing => "testing",
+            TaskType::Documentation => "documentation",
+            TaskType::Custom(name) => name,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TaskStatus {
     Pending,
@@ -63,4 +250,52 @@ pub struct TaskResult {
     pub success: bool,
     pub output: Option<serde_json::Value>,
     pub error: Option<String>,
+    /// Attempts made; zero when the task never ran
+    #[serde(default)]
+    pub attempts: u32,
+    /// Skipped because its `when` condition was false or a dependency was skipped
+    #[serde(default)]
+    pub skipped: bool,
+    /// Validation pipeline reports, one per round; follow-ups add rounds
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub validation: Vec<PipelineReport>,
+}
+
+impl TaskResult {
+    /// Result for a failed attempt or a task that could not start
+    pub fn failed(task_id: impl Into<String>, error: impl Into<String>) -> Self {
+        Self {
+            task_id: task_id.into(),
+            success: false,
+            output: None,
+            error: Some(error.into()),
+            attempts: 0,
+            skipped: false,
+            validation: Vec::new(),
+        }
+    }
+
+    /// Result for a task that did not run
+    pub fn skipped(task_id: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self {
+            task_id: task_id.into(),
+            success: false,
+            output: None,
+            error: Some(reason.into()),
+            attempts: 0,
+            skipped: true,
+            validation: Vec::new(),
+        }
+    }
+
+    /// `"success"`, `"failed"` or `"skipped"`, as seen by `when` conditions
+    pub fn outcome(&self) -> &'static str {
+        if self.skipped {
+            "skipped"
+        } else if self.success {
+            "success"
+        } else {
+            "failed"
+        }
+    }
 }
@@ -1,6 +1,7 @@
 //! Quality Assurance
 //!
-//! Validation, verification, and quality checks for multi-agent workflows.
+//! Validation, verification, and quality checks for multi-agent workflows,
+//! including the post-task [`ValidationPipeline`] run by the workflow executor.
 
 use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
@@ -8,10 +9,16 @@ use serde::{Deserialize, Serialize};
 pub mod validation;
 pub mod verification;
 pub mod testing;
+pub mod pipeline;
 
 pub use validation::*;
 pub use verification::*;
 pub use testing::*;
+// `pipeline::CheckResult` stays qualified; `verification::CheckResult` owns the short name
+pub use pipeline::{
+    AffectedCratesTestCheck, CheckContext, CheckKind, CheckSpec, CommandCheck, CompileCheck, DiffSizeCheck,
+    PipelineReport, QualityCheck, QualityConfig, ValidationPipeline,
+};
 
 /// Quality coordinator
 pub struct QualityCoordinator {
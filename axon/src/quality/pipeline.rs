@@ -0,0 +1,701 @@
+//! Post-task validation pipeline
+//!
+//! After an agent completes a task, the pipeline runs the quality checks
+//! configured for its task type (compile, tests, lint, diff size, or any
+//! custom [`QualityCheck`]) in the task's workspace, each under its own
+//! timeout. The resulting [`PipelineReport`] tells the orchestrator whether
+//! to accept the output or send the failures back to the producing agent.
+
+use super::*;
+use async_trait::async_trait;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::process::Command;
+
+/// Lines of command output kept as evidence
+const EVIDENCE_TAIL_LINES: usize = 40;
+
+/// What a check gets to look at
+#[derive(Debug, Clone)]
+pub struct CheckContext {
+    pub task_id: String,
+    pub task_type: String,
+    /// Directory the task worked in; commands run here
+    pub workspace: PathBuf,
+    /// Output the agent produced for the task
+    pub output: Value,
+}
+
+impl CheckContext {
+    pub fn new(
+        task_id: impl Into<String>,
+        task_type: impl Into<String>,
+        workspace: impl Into<PathBuf>,
+        output: Value,
+    ) -> Self {
+        Self {
+            task_id: task_id.into(),
+            task_type: task_type.into(),
+            workspace: workspace.into(),
+            output,
+        }
+    }
+
+    /// Files the agent reports as changed (`files_modified` in its output)
+    pub fn changed_files(&self) -> Vec<String> {
+        self.output
+            .get("files_modified")
+            .and_then(Value::as_array)
+            .map(|files| {
+                files
+                    .iter()
+                    .filter_map(|f| f.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Outcome of a single check
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheckResult {
+    pub check: String,
+    pub passed: bool,
+    /// Why the check passed or failed: command output, measured sizes, ...
+    pub evidence: String,
+    pub duration_ms: u64,
+}
+
+impl CheckResult {
+    pub fn pass(check: impl Into<String>, evidence: impl Into<String>) -> Self {
+        Self {
+            check: check.into(),
+            passed: true,
+            evidence: evidence.into(),
+            duration_ms: 0,
+        }
+    }
+
+    pub fn fail(check: impl Into<String>, evidence: impl Into<String>) -> Self {
+        Self {
+            check: check.into(),
+            passed: false,
+            evidence: evidence.into(),
+            duration_ms: 0,
+        }
+    }
+}
+
+/// A post-task quality check
+#[async_trait]
+pub trait QualityCheck: Send + Sync {
+    fn name(&self) -> &str;
+
+    async fn run(&self, ctx: &CheckContext) -> CheckResult;
+}
+
+/// Outcome of one pipeline run over a task's output
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PipelineReport {
+    pub task_id: String,
+    pub passed: bool,
+    pub results: Vec<CheckResult>,
+}
+
+impl PipelineReport {
+    pub fn failures(&self) -> impl Iterator<Item = &CheckResult> {
+        self.results.iter().filter(|r| !r.passed)
+    }
+
+    /// One line per failed check, for error messages and follow-up tasks
+    pub fn failure_summary(&self) -> String {
+        self.failures()
+            .map(|r| {
+                format!(
+                    "{}: {}",
+                    r.check,
+                    r.evidence.lines().last().unwrap_or("failed")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+struct RegisteredCheck {
+    check: Arc<dyn QualityCheck>,
+    /// Task types the check applies to; empty means all
+    task_types: Vec<String>,
+    timeout: Duration,
+}
+
+/// Runs the applicable checks for a completed task
+pub struct ValidationPipeline {
+    checks: Vec<RegisteredCheck>,
+    max_retries: u32,
+}
+
+impl ValidationPipeline {
+    pub fn new() -> Self {
+        Self {
+            checks: Vec::new(),
+            max_retries: default_max_retries(),
+        }
+    }
+
+    /// Build the pipeline described by a workflow's or axon.json's `quality` section
+    pub fn from_config(config: &QualityConfig) -> Self {
+        let mut pipeline = Self::new().with_max_retries(config.max_retries);
+        for spec in &config.checks {
+            pipeline.add_check(
+                spec.build(),
+                spec.task_types.clone(),
+                Duration::from_secs(spec.timeout_seconds),
+            );
+        }
+        pipeline
+    }
+
+    /// Follow-up tasks allowed after a failed validation
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    pub fn max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    pub fn add_check(
+        &mut self,
+        check: Arc<dyn QualityCheck>,
+        task_types: Vec<String>,
+        timeout: Duration,
+    ) -> &mut Self {
+        self.checks.push(RegisteredCheck {
+            check,
+            task_types,
+            timeout,
+        });
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.checks.is_empty()
+    }
+
+    /// Whether any check applies to `task_type`
+    pub fn applies_to(&self, task_type: &str) -> bool {
+        self.checks.iter().any(|c| c.applies_to(task_type))
+    }
+
+    /// Run every applicable check in registration order. A check that
+    /// exceeds its timeout fails; the rest still run so the agent gets all
+    /// failures at once.
+    pub async fn validate(&self, ctx: &CheckContext) -> PipelineReport {
+        let mut results = Vec::new();
+
+        for registered in self.checks.iter().filter(|c| c.applies_to(&ctx.task_type)) {
+            let name = registered.check.name().to_string();
+            let start = Instant::now();
+            let mut result =
+                match tokio::time::timeout(registered.timeout, registered.check.run(ctx)).await {
+                    Ok(result) => result,
+                    Err(_) => CheckResult::fail(
+                        &name,
+                        format!("Timed out after {:?}", registered.timeout),
+                    ),
+                };
+            result.duration_ms = start.elapsed().as_millis() as u64;
+            tracing::debug!(
+                "Quality check {} for task {}: passed={}",
+                name,
+                ctx.task_id,
+                result.passed
+            );
+            results.push(result);
+        }
+
+        PipelineReport {
+            task_id: ctx.task_id.clone(),
+            passed: results.iter().all(|r| r.passed),
+            results,
+        }
+    }
+}
+
+impl Default for ValidationPipeline {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegisteredCheck {
+    fn applies_to(&self, task_type: &str) -> bool {
+        self.task_types.is_empty()
+            || self
+                .task_types
+                .iter()
+                .any(|t| t.eq_ignore_ascii_case(task_type))
+    }
+}
+
+// ============================================================================
+// Configuration
+// ============================================================================
+
+/// `quality` section of a workflow definition or axon.json
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QualityConfig {
+    /// Follow-up tasks allowed after a failed validation
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    #[serde(default)]
+    pub checks: Vec<CheckSpec>,
+}
+
+fn default_max_retries() -> u32 {
+    2
+}
+
+impl Default for QualityConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: default_max_retries(),
+            checks: Vec::new(),
+        }
+    }
+}
+
+impl QualityConfig {
+    /// Read the `quality` section of an axon.json file, if it has one
+    pub fn from_axon_json(path: &Path) -> Result<Option<Self>> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+        let mut json: Value = serde_json::from_str(&content)
+            .map_err(|e| anyhow::anyhow!("Invalid JSON in {}: {}", path.display(), e))?;
+
+        match json.get_mut("quality").map(Value::take) {
+            Some(section) => {
+                Ok(Some(serde_json::from_value(section).map_err(|e| {
+                    anyhow::anyhow!("Invalid quality config: {}", e)
+                })?))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+/// Kind of built-in check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckKind {
+    /// `cargo check` or `tsc --noEmit`, depending on the workspace
+    Compile,
+    /// `cargo test` for the crates containing changed files
+    Test,
+    /// `cargo clippy -- -D warnings`
+    Lint,
+    /// Limits on the lines and files a task may change
+    DiffSize,
+}
+
+/// One configured check
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheckSpec {
+    pub kind: CheckKind,
+    /// Task types the check applies to (e.g. `development`); empty means all
+    #[serde(default)]
+    pub task_types: Vec<String>,
+    #[serde(default = "default_check_timeout_seconds")]
+    pub timeout_seconds: u64,
+    /// Replace the default command (program followed by arguments)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_lines_changed: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_files_changed: Option<usize>,
+}
+
+fn default_check_timeout_seconds() -> u64 {
+    300
+}
+
+impl CheckSpec {
+    pub fn new(kind: CheckKind) -> Self {
+        Self {
+            kind,
+            task_types: Vec::new(),
+            timeout_seconds: default_check_timeout_seconds(),
+            command: None,
+            max_lines_changed: None,
+            max_files_changed: None,
+        }
+    }
+
+    fn build(&self) -> Arc<dyn QualityCheck> {
+        let name = match self.kind {
+            CheckKind::Compile => "compile",
+            CheckKind::Test => "test",
+            CheckKind::Lint => "lint",
+            CheckKind::DiffSize => "diff_size",
+        };
+
+        if let Some((program, args)) = self.command.as_ref().and_then(|c| c.split_first()) {
+            return Arc::new(CommandCheck::new(name, program, args.to_vec()));
+        }
+
+        match self.kind {
+            CheckKind::Compile => Arc::new(CompileCheck),
+            CheckKind::Test => Arc::new(AffectedCratesTestCheck),
+            CheckKind::Lint => Arc::new(CommandCheck::new(
+                name,
+                "cargo",
+                ["clippy", "--all-targets", "--", "-D", "warnings"]
+                    .map(String::from)
+                    .to_vec(),
+            )),
+            CheckKind::DiffSize => Arc::new(DiffSizeCheck {
+                max_lines_changed: self.max_lines_changed,
+                max_files_changed: self.max_files_changed,
+            }),
+        }
+    }
+}
+
+// ============================================================================
+// Built-in checks
+// ============================================================================
+
+/// Runs a command in the task workspace; passes on exit status 0
+pub struct CommandCheck {
+    name: String,
+    program: String,
+    args: Vec<String>,
+}
+
+impl CommandCheck {
+    pub fn new(name: impl Into<String>, program: impl Into<String>, args: Vec<String>) -> Self {
+        Self {
+            name: name.into(),
+            program: program.into(),
+            args,
+        }
+    }
+}
+
+#[async_trait]
+impl QualityCheck for CommandCheck {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn run(&self, ctx: &CheckContext) -> CheckResult {
+        run_command(&self.name, &self.program, &self.args, &ctx.workspace).await
+    }
+}
+
+/// Run a command to completion. The child is killed if the future is
+/// dropped, which is how pipeline timeouts stop runaway checks.
+async fn run_command(name: &str, program: &str, args: &[String], workspace: &Path) -> CheckResult {
+    let command_line = std::iter::once(program)
+        .chain(args.iter().map(String::as_str))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let output = Command::new(program)
+        .args(args)
+        .current_dir(workspace)
+        .kill_on_drop(true)
+        .output()
+        .await;
+
+    match output {
+        Ok(output) => {
+            let mut combined = String::from_utf8_lossy(&output.stdout).into_owned();
+            combined.push_str(&String::from_utf8_lossy(&output.stderr));
+            let evidence = format!(
+                "$ {}\n{}\n{}",
+                command_line,
+                tail(&combined, EVIDENCE_TAIL_LINES),
+                output.status
+            );
+
+            if output.status.success() {
+                CheckResult::pass(name, evidence)
+            } else {
+                CheckResult::fail(name, evidence)
+            }
+        }
+        Err(e) => CheckResult::fail(name, format!("Failed to run `{}`: {}", command_line, e)),
+    }
+}
+
+fn tail(text: &str, lines: usize) -> String {
+    let all: Vec<&str> = text.trim_end().lines().collect();
+    all[all.len().saturating_sub(lines)..].join("\n")
+}
+
+/// `cargo check` for Rust workspaces, `tsc --noEmit` for TypeScript ones
+pub struct CompileCheck;
+
+#[async_trait]
+impl QualityCheck for CompileCheck {
+    fn name(&self) -> &str {
+        "compile"
+    }
+
+    async fn run(&self, ctx: &CheckContext) -> CheckResult {
+        if ctx.workspace.join("Cargo.toml").exists() {
+            let args = ["check", "--all-targets", "--message-format", "short"].map(String::from);
+            run_command(self.name(), "cargo", &args, &ctx.workspace).await
+        } else if ctx.workspace.join("tsconfig.json").exists() {
+            let args = ["tsc", "--noEmit"].map(String::from);
+            run_command(self.name(), "npx", &args, &ctx.workspace).await
+        } else {
+            CheckResult::pass(
+                self.name(),
+                "No Cargo.toml or tsconfig.json in workspace; nothing to compile",
+            )
+        }
+    }
+}
+
+/// `cargo test` restricted to the crates that contain changed files
+pub struct AffectedCratesTestCheck;
+
+impl AffectedCratesTestCheck {
+    /// Package names of the crates owning `files`, found by walking up to
+    /// the nearest Cargo.toml with a `[package]` section
+    pub fn affected_crates(workspace: &Path, files: &[String]) -> Vec<String> {
+        let mut crates = Vec::new();
+
+        for file in files {
+            let mut dir = workspace.join(file).parent().map(Path::to_path_buf);
+            while let Some(current) = dir {
+                if !current.starts_with(workspace) {
+                    break;
+                }
+                if let Some(name) = package_name(&current.join("Cargo.toml")) {
+                    if !crates.contains(&name) {
+                        crates.push(name);
+                    }
+                    break;
+                }
+                dir = current.parent().map(Path::to_path_buf);
+            }
+        }
+
+        crates
+    }
+}
+
+fn package_name(manifest: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(manifest).ok()?;
+    let manifest: toml::Value = toml::from_str(&content).ok()?;
+    manifest
+        .get("package")?
+        .get("name")?
+        .as_str()
+        .map(str::to_string)
+}
+
+#[async_trait]
+impl QualityCheck for AffectedCratesTestCheck {
+    fn name(&self) -> &str {
+        "test"
+    }
+
+    async fn run(&self, ctx: &CheckContext) -> CheckResult {
+        let crates = Self::affected_crates(&ctx.workspace, &ctx.changed_files());
+        if crates.is_empty() {
+            return CheckResult::pass(self.name(), "No Rust crates affected by the change");
+        }
+
+        let mut args = vec!["test".to_string()];
+        for name in &crates {
+            args.push("-p".to_string());
+            args.push(name.clone());
+        }
+        run_command(self.name(), "cargo", &args, &ctx.workspace).await
+    }
+}
+
+/// Sanity limits on how much a single task may change, based on the
+/// `lines_added`, `lines_removed` and `files_modified` the agent reports
+pub struct DiffSizeCheck {
+    pub max_lines_changed: Option<u64>,
+    pub max_files_changed: Option<usize>,
+}
+
+#[async_trait]
+impl QualityCheck for DiffSizeCheck {
+    fn name(&self) -> &str {
+        "diff_size"
+    }
+
+    async fn run(&self, ctx: &CheckContext) -> CheckResult {
+        let count = |key: &str| ctx.output.get(key).and_then(Value::as_u64).unwrap_or(0);
+        let lines = count("lines_added") + count("lines_removed");
+        let files = ctx.changed_files().len();
+        let evidence = format!("{} lines changed across {} files", lines, files);
+
+        if let Some(max) = self.max_lines_changed
+            && lines > max
+        {
+            return CheckResult::fail(self.name(), format!("{} (limit {} lines)", evidence, max));
+        }
+        if let Some(max) = self.max_files_changed
+            && files > max
+        {
+            return CheckResult::fail(self.name(), format!("{} (limit {} files)", evidence, max));
+        }
+
+        CheckResult::pass(self.name(), evidence)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Fixed(&'static str, bool);
+
+    #[async_trait]
+    impl QualityCheck for Fixed {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        async fn run(&self, _ctx: &CheckContext) -> CheckResult {
+            if self.1 {
+                CheckResult::pass(self.0, "ok")
+            } else {
+                CheckResult::fail(self.0, "broken")
+            }
+        }
+    }
+
+    const TIMEOUT: Duration = Duration::from_secs(300);
+
+    fn ctx(task_type: &str, output: Value) -> CheckContext {
+        CheckContext::new("t1", task_type, std::env::temp_dir(), output)
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_runs_applicable_checks() {
+        let mut pipeline = ValidationPipeline::new();
+        pipeline
+            .add_check(Arc::new(Fixed("always", true)), vec![], TIMEOUT)
+            .add_check(
+                Arc::new(Fixed("dev_only", false)),
+                vec!["development".to_string()],
+                TIMEOUT,
+            );
+
+        let report = pipeline.validate(&ctx("review", Value::Null)).await;
+        assert!(report.passed);
+        assert_eq!(report.results.len(), 1);
+
+        let report = pipeline.validate(&ctx("Development", Value::Null)).await;
+        assert!(!report.passed);
+        assert_eq!(report.results.len(), 2);
+        assert_eq!(report.failure_summary(), "dev_only: broken");
+    }
+
+    #[tokio::test]
+    async fn test_diff_size_check() {
+        let check = DiffSizeCheck {
+            max_lines_changed: Some(50),
+            max_files_changed: Some(1),
+        };
+        let output =
+            serde_json::json!({"lines_added": 42, "lines_removed": 10, "files_modified": ["a.rs"]});
+        let result = check.run(&ctx("development", output)).await;
+        assert!(!result.passed);
+        assert!(result.evidence.contains("52 lines changed across 1 files"));
+
+        let output = serde_json::json!({"lines_added": 5, "files_modified": ["a.rs"]});
+        assert!(check.run(&ctx("development", output)).await.passed);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_command_check_and_timeout() {
+        let failing = CommandCheck::new(
+            "fails",
+            "sh",
+            vec!["-c".to_string(), "echo boom >&2; exit 3".to_string()],
+        );
+        let result = failing.run(&ctx("development", Value::Null)).await;
+        assert!(!result.passed);
+        assert!(result.evidence.contains("boom"));
+
+        let mut pipeline = ValidationPipeline::new();
+        pipeline.add_check(
+            Arc::new(CommandCheck::new("slow", "sleep", vec!["10".to_string()])),
+            vec![],
+            Duration::from_millis(100),
+        );
+        let report = pipeline.validate(&ctx("development", Value::Null)).await;
+        assert!(!report.passed);
+        assert!(report.results[0].evidence.starts_with("Timed out"));
+    }
+
+    #[test]
+    fn test_affected_crates() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"core\"]\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(root.join("core/src")).unwrap();
+        std::fs::write(
+            root.join("core/Cargo.toml"),
+            "[package]\nname = \"my-core\"\n",
+        )
+        .unwrap();
+
+        let files = vec![
+            "core/src/lib.rs".to_string(),
+            "core/src/x.rs".to_string(),
+            "README.md".to_string(),
+        ];
+        assert_eq!(
+            AffectedCratesTestCheck::affected_crates(root, &files),
+            vec!["my-core".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_config_from_axon_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("axon.json");
+        std::fs::write(
+            &path,
+            r#"{"runtime": {}, "quality": {"max_retries": 1, "checks": [
+                {"kind": "compile", "task_types": ["development"]},
+                {"kind": "diff_size", "max_lines_changed": 500, "timeout_seconds": 5}
+            ]}}"#,
+        )
+        .unwrap();
+
+        let config = QualityConfig::from_axon_json(&path).unwrap().unwrap();
+        assert_eq!(config.max_retries, 1);
+        assert_eq!(config.checks[0].kind, CheckKind::Compile);
+        assert_eq!(config.checks[0].timeout_seconds, 300);
+        assert_eq!(config.checks[1].max_lines_changed, Some(500));
+
+        let pipeline = ValidationPipeline::from_config(&config);
+        assert!(pipeline.applies_to("development"));
+        assert!(pipeline.applies_to("review"));
+
+        std::fs::write(&path, "{}").unwrap();
+        assert!(QualityConfig::from_axon_json(&path).unwrap().is_none());
+    }
+}
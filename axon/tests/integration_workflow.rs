@@ -238,6 +238,10 @@ fn create_dev_review_workflow() -> Workflow {
                 task_type: TaskType::Development,
                 input: serde_json::json!({"feature": "login"}),
                 status: TaskStatus::Pending,
+                when: None,
+                retry: None,
+                continue_on_error: false,
+                for_each: None,
             },
             Task {
                 id: "review".to_string(),
@@ -245,6 +249,10 @@ fn create_dev_review_workflow() -> Workflow {
                 task_type: TaskType::Review,
                 input: serde_json::json!({}),
                 status: TaskStatus::Pending,
+                when: None,
+                retry: None,
+                continue_on_error: false,
+                for_each: None,
             },
         ],
         dependencies,
@@ -254,6 +262,7 @@ fn create_dev_review_workflow() -> Workflow {
             timeout: Duration::from_secs(600),
             max_retries: 3,
         },
+        quality: None,
     }
 }
 
@@ -269,6 +278,10 @@ fn create_parallel_workflow() -> Workflow {
                 task_type: TaskType::Development,
                 input: serde_json::json!({}),
                 status: TaskStatus::Pending,
+                when: None,
+                retry: None,
+                continue_on_error: false,
+                for_each: None,
             },
             Task {
                 id: "task2".to_string(),
@@ -276,6 +289,10 @@ fn create_parallel_workflow() -> Workflow {
                 task_type: TaskType::Development,
                 input: serde_json::json!({}),
                 status: TaskStatus::Pending,
+                when: None,
+                retry: None,
+                continue_on_error: false,
+                for_each: None,
             },
             Task {
                 id: "task3".to_string(),
@@ -283,6 +300,10 @@ fn create_parallel_workflow() -> Workflow {
                 task_type: TaskType::Development,
                 input: serde_json::json!({}),
                 status: TaskStatus::Pending,
+                when: None,
+                retry: None,
+                continue_on_error: false,
+                for_each: None,
             },
         ],
         dependencies: HashMap::new(),
@@ -292,6 +313,7 @@ fn create_parallel_workflow() -> Workflow {
             timeout: Duration::from_secs(300),
             max_retries: 2,
         },
+        quality: None,
     }
 }
 
@@ -313,6 +335,10 @@ fn create_complex_workflow() -> Workflow {
                 task_type: TaskType::Development,
                 input: serde_json::json!({}),
                 status: TaskStatus::Pending,
+                when: None,
+                retry: None,
+                continue_on_error: false,
+                for_each: None,
             },
             Task {
                 id: "review".to_string(),
@@ -320,6 +346,10 @@ fn create_complex_workflow() -> Workflow {
                 task_type: TaskType::Review,
                 input: serde_json::json!({}),
                 status: TaskStatus::Pending,
+                when: None,
+                retry: None,
+                continue_on_error: false,
+                for_each: None,
             },
             Task {
                 id: "test".to_string(),
@@ -327,6 +357,10 @@ fn create_complex_workflow() -> Workflow {
                 task_type: TaskType::Testing,
                 input: serde_json::json!({}),
                 status: TaskStatus::Pending,
+                when: None,
+                retry: None,
+                continue_on_error: false,
+                for_each: None,
             },
             Task {
                 id: "document".to_string(),
@@ -334,6 +368,10 @@ fn create_complex_workflow() -> Workflow {
                 task_type: TaskType::Documentation,
                 input: serde_json::json!({}),
                 status: TaskStatus::Pending,
+                when: None,
+                retry: None,
+                continue_on_error: false,
+                for_each: None,
             },
             Task {
                 id: "deploy".to_string(),
@@ -341,6 +379,10 @@ fn create_complex_workflow() -> Workflow {
                 task_type: TaskType::Custom("deployment".to_string()),
                 input: serde_json::json!({}),
                 status: TaskStatus::Pending,
+                when: None,
+                retry: None,
+                continue_on_error: false,
+                for_each: None,
             },
         ],
         dependencies,
@@ -350,6 +392,7 @@ fn create_complex_workflow() -> Workflow {
             timeout: Duration::from_secs(1800),
             max_retries: 5,
         },
+        quality: None,
     }
 }
 
@@ -370,6 +413,10 @@ fn create_full_pipeline_workflow() -> Workflow {
                 task_type: TaskType::Development,
                 input: serde_json::json!({}),
                 status: TaskStatus::Pending,
+                when: None,
+                retry: None,
+                continue_on_error: false,
+                for_each: None,
             },
             Task {
                 id: "review".to_string(),
@@ -377,6 +424,10 @@ fn create_full_pipeline_workflow() -> Workflow {
                 task_type: TaskType::Review,
                 input: serde_json::json!({}),
                 status: TaskStatus::Pending,
+                when: None,
+                retry: None,
+                continue_on_error: false,
+                for_each: None,
             },
             Task {
                 id: "test".to_string(),
@@ -384,6 +435,10 @@ fn create_full_pipeline_workflow() -> Workflow {
                 task_type: TaskType::Testing,
                 input: serde_json::json!({}),
                 status: TaskStatus::Pending,
+                when: None,
+                retry: None,
+                continue_on_error: false,
+                for_each: None,
             },
             Task {
                 id: "document".to_string(),
@@ -391,6 +446,10 @@ fn create_full_pipeline_workflow() -> Workflow {
                 task_type: TaskType::Documentation,
                 input: serde_json::json!({}),
                 status: TaskStatus::Pending,
+                when: None,
+                retry: None,
+                continue_on_error: false,
+                for_each: None,
             },
         ],
         dependencies,
@@ -400,5 +459,6 @@ fn create_full_pipeline_workflow() -> Workflow {
             timeout: Duration::from_secs(1200),
             max_retries: 3,
         },
+        quality: None,
     }
 }
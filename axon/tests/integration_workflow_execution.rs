@@ -36,6 +36,10 @@ async fn test_simple_workflow_execution() {
                 task_type: TaskType::Development,
                 input: serde_json::json!({"code": "print('hello')"}),
                 status: TaskStatus::Pending,
+                when: None,
+                retry: None,
+                continue_on_error: false,
+                for_each: None,
             },
             Task {
                 id: "task-2".to_string(),
@@ -43,6 +47,10 @@ async fn test_simple_workflow_execution() {
                 task_type: TaskType::Testing,
                 input: serde_json::json!({"test": "verify"}),
                 status: TaskStatus::Pending,
+                when: None,
+                retry: None,
+                continue_on_error: false,
+                for_each: None,
             },
         ],
         dependencies: {
@@ -56,6 +64,7 @@ async fn test_simple_workflow_execution() {
             timeout: Duration::from_secs(300),
             max_retries: 3,
         },
+        quality: None,
     };
 
     // Validate workflow
@@ -79,6 +88,10 @@ async fn test_parallel_workflow_execution() {
                 task_type: TaskType::Development,
                 input: serde_json::json!({"code": "task1"}),
                 status: TaskStatus::Pending,
+                when: None,
+                retry: None,
+                continue_on_error: false,
+                for_each: None,
             },
             Task {
                 id: "task-2".to_string(),
@@ -86,6 +99,10 @@ async fn test_parallel_workflow_execution() {
                 task_type: TaskType::Development,
                 input: serde_json::json!({"code": "task2"}),
                 status: TaskStatus::Pending,
+                when: None,
+                retry: None,
+                continue_on_error: false,
+                for_each: None,
             },
             Task {
                 id: "task-3".to_string(),
@@ -93,6 +110,10 @@ async fn test_parallel_workflow_execution() {
                 task_type: TaskType::Development,
                 input: serde_json::json!({"code": "task3"}),
                 status: TaskStatus::Pending,
+                when: None,
+                retry: None,
+                continue_on_error: false,
+                for_each: None,
             },
         ],
         dependencies: HashMap::new(), // No dependencies = all parallel
@@ -102,6 +123,7 @@ async fn test_parallel_workflow_execution() {
             timeout: Duration::from_secs(300),
             max_retries: 2,
         },
+        quality: None,
     };
 
     let validator = DagValidator::new();
@@ -130,6 +152,10 @@ async fn test_complex_dag_workflow() {
                 task_type: TaskType::Development,
                 input: serde_json::json!({}),
                 status: TaskStatus::Pending,
+                when: None,
+                retry: None,
+                continue_on_error: false,
+                for_each: None,
             },
             Task {
                 id: "task-2".to_string(),
@@ -137,6 +163,10 @@ async fn test_complex_dag_workflow() {
                 task_type: TaskType::Review,
                 input: serde_json::json!({}),
                 status: TaskStatus::Pending,
+                when: None,
+                retry: None,
+                continue_on_error: false,
+                for_each: None,
             },
             Task {
                 id: "task-3".to_string(),
@@ -144,6 +174,10 @@ async fn test_complex_dag_workflow() {
                 task_type: TaskType::Testing,
                 input: serde_json::json!({}),
                 status: TaskStatus::Pending,
+                when: None,
+                retry: None,
+                continue_on_error: false,
+                for_each: None,
             },
             Task {
                 id: "task-4".to_string(),
@@ -151,6 +185,10 @@ async fn test_complex_dag_workflow() {
                 task_type: TaskType::Documentation,
                 input: serde_json::json!({}),
                 status: TaskStatus::Pending,
+                when: None,
+                retry: None,
+                continue_on_error: false,
+                for_each: None,
             },
         ],
         dependencies: {
@@ -166,6 +204,7 @@ async fn test_complex_dag_workflow() {
             timeout: Duration::from_secs(600),
             max_retries: 3,
         },
+        quality: None,
     };
 
     let validator = DagValidator::new();
@@ -192,6 +231,10 @@ async fn test_cycle_detection() {
                 task_type: TaskType::Development,
                 input: serde_json::json!({}),
                 status: TaskStatus::Pending,
+                when: None,
+                retry: None,
+                continue_on_error: false,
+                for_each: None,
             },
             Task {
                 id: "task-2".to_string(),
@@ -199,6 +242,10 @@ async fn test_cycle_detection() {
                 task_type: TaskType::Testing,
                 input: serde_json::json!({}),
                 status: TaskStatus::Pending,
+                when: None,
+                retry: None,
+                continue_on_error: false,
+                for_each: None,
             },
         ],
         dependencies: {
@@ -213,6 +260,7 @@ async fn test_cycle_detection() {
             timeout: Duration::from_secs(300),
             max_retries: 3,
         },
+        quality: None,
     };
 
     let validator = DagValidator::new();
@@ -235,6 +283,10 @@ async fn test_missing_dependency_detection() {
                 task_type: TaskType::Development,
                 input: serde_json::json!({}),
                 status: TaskStatus::Pending,
+                when: None,
+                retry: None,
+                continue_on_error: false,
+                for_each: None,
             },
         ],
         dependencies: {
@@ -248,6 +300,7 @@ async fn test_missing_dependency_detection() {
             timeout: Duration::from_secs(300),
             max_retries: 3,
         },
+        quality: None,
     };
 
     let validator = DagValidator::new();
@@ -375,6 +428,7 @@ fn test_workflow_creation() {
             timeout: Duration::from_secs(300),
             max_retries: 3,
         },
+        quality: None,
     };
 
     assert_eq!(workflow.id, "builder-test");
@@ -390,6 +444,10 @@ fn test_task_creation() {
         task_type: TaskType::Development,
         input: serde_json::json!({"key": "value"}),
         status: TaskStatus::Pending,
+        when: None,
+        retry: None,
+        continue_on_error: false,
+        for_each: None,
     };
 
     assert_eq!(task.id, "test-task");
@@ -414,6 +472,10 @@ async fn test_workflow_timeout() {
                 task_type: TaskType::Development,
                 input: serde_json::json!({}),
                 status: TaskStatus::Pending,
+                when: None,
+                retry: None,
+                continue_on_error: false,
+                for_each: None,
             },
         ],
         dependencies: HashMap::new(),
@@ -423,6 +485,7 @@ async fn test_workflow_timeout() {
             timeout: Duration::from_millis(100), // Very short timeout
             max_retries: 0,
         },
+        quality: None,
     };
 
     // Workflow should timeout if tasks take too long
@@ -442,6 +505,10 @@ async fn test_workflow_retry_logic() {
                 task_type: TaskType::Testing,
                 input: serde_json::json!({}),
                 status: TaskStatus::Pending,
+                when: None,
+                retry: None,
+                continue_on_error: false,
+                for_each: None,
             },
         ],
         dependencies: HashMap::new(),
@@ -451,6 +518,7 @@ async fn test_workflow_retry_logic() {
             timeout: Duration::from_secs(300),
             max_retries: 3, // Allow 3 retries
         },
+        quality: None,
     };
 
     assert_eq!(workflow.metadata.max_retries, 3);
@@ -473,6 +541,10 @@ fn test_empty_dependency_graph() {
                 task_type: TaskType::Development,
                 input: serde_json::json!({}),
                 status: TaskStatus::Pending,
+                when: None,
+                retry: None,
+                continue_on_error: false,
+                for_each: None,
             },
         ],
         dependencies: HashMap::new(),
@@ -482,6 +554,7 @@ fn test_empty_dependency_graph() {
             timeout: Duration::from_secs(300),
             max_retries: 3,
         },
+        quality: None,
     };
 
     assert!(workflow.dependencies.is_empty());
@@ -535,6 +608,9 @@ fn test_workflow_result_structure() {
                     success: true,
                     output: Some(serde_json::json!({"result": "success"})),
                     error: None,
+                    attempts: 1,
+                    skipped: false,
+                    validation: Vec::new(),
                 },
             );
             results
@@ -555,6 +631,9 @@ fn test_task_result_success() {
         success: true,
         output: Some(serde_json::json!({"data": "output"})),
         error: None,
+        attempts: 1,
+        skipped: false,
+        validation: Vec::new(),
     };
 
     assert!(result.success);
@@ -571,6 +650,9 @@ fn test_task_result_failure() {
         success: false,
         output: None,
         error: Some("Task failed due to error".to_string()),
+        attempts: 1,
+        skipped: false,
+        validation: Vec::new(),
     };
 
     assert!(!result.success);
@@ -57,6 +57,10 @@ fn test_task_creation() {
         task_type: TaskType::Development,
         input: serde_json::json!({"key": "value"}),
         status: TaskStatus::Pending,
+        when: None,
+        retry: None,
+        continue_on_error: false,
+        for_each: None,
     };
 
     assert_eq!(task.id, "task-1");
@@ -79,6 +83,10 @@ fn test_task_status_transitions() {
             task_type: TaskType::Development,
             input: serde_json::json!({}),
             status,
+            when: None,
+            retry: None,
+            continue_on_error: false,
+            for_each: None,
         };
 
         assert_eq!(task.status, status);
@@ -102,6 +110,10 @@ fn test_task_types() {
             task_type: task_type.clone(),
             input: serde_json::json!({}),
             status: TaskStatus::Pending,
+            when: None,
+            retry: None,
+            continue_on_error: false,
+            for_each: None,
         };
 
         // Verify task type is set correctly
@@ -239,6 +251,9 @@ fn test_workflow_result_with_task_results() {
             success: true,
             output: Some(serde_json::json!({"result": "ok"})),
             error: None,
+            attempts: 1,
+            skipped: false,
+            validation: Vec::new(),
         },
     );
 
@@ -260,6 +275,9 @@ fn test_task_result_success() {
         success: true,
         output: Some(serde_json::json!({"data": "value"})),
         error: None,
+        attempts: 1,
+        skipped: false,
+        validation: Vec::new(),
     };
 
     assert!(result.success);
@@ -274,6 +292,9 @@ fn test_task_result_failure() {
         success: false,
         output: None,
         error: Some("Task execution failed".to_string()),
+        attempts: 1,
+        skipped: false,
+        validation: Vec::new(),
     };
 
     assert!(!result.success);
@@ -374,6 +395,7 @@ fn test_empty_workflow() {
             timeout: Duration::from_secs(300),
             max_retries: 3,
         },
+        quality: None,
     };
 
     let validator = DagValidator::new();
@@ -403,6 +425,10 @@ fn test_workflow_with_many_tasks() {
             task_type: TaskType::Development,
             input: serde_json::json!({}),
             status: TaskStatus::Pending,
+            when: None,
+            retry: None,
+            continue_on_error: false,
+            for_each: None,
         });
     }
 
@@ -423,6 +449,10 @@ fn test_linear_dependency_chain() {
             task_type: TaskType::Development,
             input: serde_json::json!({}),
             status: TaskStatus::Pending,
+            when: None,
+            retry: None,
+            continue_on_error: false,
+            for_each: None,
         });
 
         // Each task depends on the previous one
@@ -453,6 +483,10 @@ fn create_simple_workflow() -> Workflow {
                 task_type: TaskType::Development,
                 input: serde_json::json!({}),
                 status: TaskStatus::Pending,
+                when: None,
+                retry: None,
+                continue_on_error: false,
+                for_each: None,
             },
             Task {
                 id: "task2".to_string(),
@@ -460,6 +494,10 @@ fn create_simple_workflow() -> Workflow {
                 task_type: TaskType::Review,
                 input: serde_json::json!({}),
                 status: TaskStatus::Pending,
+                when: None,
+                retry: None,
+                continue_on_error: false,
+                for_each: None,
             },
         ],
         dependencies: HashMap::new(),
@@ -469,6 +507,7 @@ fn create_simple_workflow() -> Workflow {
             timeout: Duration::from_secs(300),
             max_retries: 3,
         },
+        quality: None,
     }
 }
 
@@ -494,6 +533,10 @@ fn create_complex_workflow() -> Workflow {
                 task_type: TaskType::Development,
                 input: serde_json::json!({}),
                 status: TaskStatus::Pending,
+                when: None,
+                retry: None,
+                continue_on_error: false,
+                for_each: None,
             },
             Task {
                 id: "task2".to_string(),
@@ -501,6 +544,10 @@ fn create_complex_workflow() -> Workflow {
                 task_type: TaskType::Development,
                 input: serde_json::json!({}),
                 status: TaskStatus::Pending,
+                when: None,
+                retry: None,
+                continue_on_error: false,
+                for_each: None,
             },
             Task {
                 id: "task3".to_string(),
@@ -508,6 +555,10 @@ fn create_complex_workflow() -> Workflow {
                 task_type: TaskType::Review,
                 input: serde_json::json!({}),
                 status: TaskStatus::Pending,
+                when: None,
+                retry: None,
+                continue_on_error: false,
+                for_each: None,
             },
             Task {
                 id: "task4".to_string(),
@@ -515,6 +566,10 @@ fn create_complex_workflow() -> Workflow {
                 task_type: TaskType::Testing,
                 input: serde_json::json!({}),
                 status: TaskStatus::Pending,
+                when: None,
+                retry: None,
+                continue_on_error: false,
+                for_each: None,
             },
         ],
         dependencies: HashMap::new(),
@@ -524,6 +579,7 @@ fn create_complex_workflow() -> Workflow {
             timeout: Duration::from_secs(600),
             max_retries: 5,
         },
+        quality: None,
     };
 
     // task3 depends on task1 and task2
@@ -439,6 +439,42 @@ async fn test_telemetry_summary_endpoint() {
     assert_eq!(status, StatusCode::OK);
 }
 
+#[tokio::test]
+async fn test_telemetry_history_endpoint() {
+    use axon::commands::api::routes;
+    use axon::monitoring::{TelemetrySample, TelemetryStore};
+
+    let store = Arc::new(TelemetryStore::in_memory().await.unwrap());
+    let now = chrono::Utc::now();
+    for minute in 1..=3 {
+        let mut sample = TelemetrySample::new(now - chrono::Duration::minutes(minute), 60);
+        sample.requests = 30;
+        sample.errors = 3;
+        store.record(&sample).await.unwrap();
+    }
+
+    let runtime = AgentRuntimeManager::new(AxonConfig::default())
+        .unwrap()
+        .with_telemetry_store(store);
+    let state = AppState {
+        runtime: Arc::new(RwLock::new(runtime)),
+        ws_manager: WsManager::new(),
+    };
+
+    let app = routes::create_routes(state.clone());
+    let (status, body) = send_request(app, "GET", "/telemetry/history?range=10", None).await;
+    assert_eq!(status, StatusCode::OK);
+    let samples: Vec<TelemetrySample> = serde_json::from_str(&body).unwrap();
+    assert_eq!(samples.len(), 3);
+
+    let app = routes::create_routes(state);
+    let (status, body) = send_request(app, "GET", "/telemetry?range=10", None).await;
+    assert_eq!(status, StatusCode::OK);
+    let telemetry: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(telemetry["total_requests"], 90);
+    assert_eq!(telemetry["failed_requests"], 9);
+}
+
 // ============================================================================
 // Configuration Endpoint Tests
 // ============================================================================
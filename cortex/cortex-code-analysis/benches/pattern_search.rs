@@ -0,0 +1,181 @@
+//! Structural Pattern Search Benchmarks
+//!
+//! Measures `AstFinder` pattern queries over a synthetic 100K LOC repository
+//! (100 files of 1K LOC each), parsed once up front:
+//! - Plain pattern (`$X.unwrap()`)
+//! - Pattern with an `inside` constraint
+//! - Pattern with a `has` constraint
+//! - Pattern compilation alone
+//!
+//! Target: a full pass over 100K LOC under 500ms per query.
+
+use cortex_code_analysis::analysis::find::{AstFinder, FindConfig};
+use cortex_code_analysis::analysis::pattern::PatternRule;
+use cortex_code_analysis::traits::ParserTrait;
+use cortex_code_analysis::{Lang, Parser, RustLanguage, TypeScriptLanguage};
+use criterion::{BenchmarkId, Criterion, Throughput, black_box, criterion_group, criterion_main};
+use std::path::Path;
+use std::time::Duration;
+
+const FILES: usize = 100;
+const LINES_PER_FILE: usize = 1_000;
+
+// ==============================================================================
+// Test Code Generation Helpers
+// ==============================================================================
+
+fn generate_rust_file(file: usize, lines: usize) -> String {
+    let mut code = String::new();
+    code.push_str("use std::collections::HashMap;\n\n");
+
+    // Each function is 10 lines
+    for i in 0..lines / 10 {
+        code.push_str(&format!(
+            "pub fn load_{file}_{i}(map: &HashMap<String, u32>) -> Result<u32, String> {{\n\
+             \tlet key = format!(\"key{i}\");\n\
+             \tlet value = map.get(&key).copied().unwrap();\n\
+             \tif value > {i} {{\n\
+             \t\treturn Ok(value);\n\
+             \t}}\n\
+             \tlet other = map.get(\"fallback\").ok_or(\"missing\")?;\n\
+             \tOk(*other + value)\n\
+             }}\n\n"
+        ));
+    }
+
+    code
+}
+
+fn generate_typescript_file(file: usize, lines: usize) -> String {
+    let mut code = String::new();
+    code.push_str("import { lookup } from './lookup';\n\n");
+
+    // Each function is 10 lines
+    for i in 0..lines / 10 {
+        code.push_str(&format!(
+            "export function load{file}_{i}(map: Map<string, number>): number {{\n\
+             \tconst key = `key{i}`;\n\
+             \tconst value = map.get(key) ?? 0;\n\
+             \tif (value > {i}) {{\n\
+             \t\tconsole.log(key, value);\n\
+             \t\treturn value;\n\
+             \t}}\n\
+             \treturn lookup(map, key) + value;\n\
+             }}\n\n"
+        ));
+    }
+
+    code
+}
+
+fn rust_repo() -> Vec<Parser<RustLanguage>> {
+    (0..FILES)
+        .map(|file| {
+            let code = generate_rust_file(file, LINES_PER_FILE);
+            Parser::<RustLanguage>::new(code.into_bytes(), Path::new("bench.rs")).unwrap()
+        })
+        .collect()
+}
+
+fn typescript_repo() -> Vec<Parser<TypeScriptLanguage>> {
+    (0..FILES)
+        .map(|file| {
+            let code = generate_typescript_file(file, LINES_PER_FILE);
+            Parser::<TypeScriptLanguage>::new(code.into_bytes(), Path::new("bench.ts")).unwrap()
+        })
+        .collect()
+}
+
+fn search_repo<T: ParserTrait>(repo: &[T], config: &FindConfig) -> usize {
+    repo.iter()
+        .map(|parser| AstFinder::new(parser).find(config).unwrap().nodes_matched)
+        .sum()
+}
+
+// ==============================================================================
+// Pattern Search Benchmarks
+// ==============================================================================
+
+fn bench_rust_patterns(c: &mut Criterion) {
+    let repo = rust_repo();
+    let mut group = c.benchmark_group("pattern_search_rust_100k");
+    group.throughput(Throughput::Elements((FILES * LINES_PER_FILE) as u64));
+
+    let queries = [
+        ("unwrap", PatternRule::new("$X.unwrap()")),
+        (
+            "unwrap_inside_result_fn",
+            PatternRule::new("$X.unwrap()").inside("pub fn $F($$$) -> Result<$$$> { $$$ }"),
+        ),
+        (
+            "if_has_return",
+            PatternRule::new("if $COND { $$$ }").has("return $VALUE"),
+        ),
+    ];
+
+    for (name, rule) in queries {
+        let config = FindConfig::builder().pattern(rule).build();
+        group.bench_with_input(BenchmarkId::from_parameter(name), &config, |b, config| {
+            b.iter(|| black_box(search_repo(&repo, config)));
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_typescript_patterns(c: &mut Criterion) {
+    let repo = typescript_repo();
+    let mut group = c.benchmark_group("pattern_search_typescript_100k");
+    group.throughput(Throughput::Elements((FILES * LINES_PER_FILE) as u64));
+
+    let queries = [
+        ("console_log", PatternRule::new("console.log($$$ARGS)")),
+        (
+            "console_log_inside_if",
+            PatternRule::new("console.log($$$ARGS)").inside("if ($COND) { $$$ }"),
+        ),
+    ];
+
+    for (name, rule) in queries {
+        let config = FindConfig::builder().pattern(rule).build();
+        group.bench_with_input(BenchmarkId::from_parameter(name), &config, |b, config| {
+            b.iter(|| black_box(search_repo(&repo, config)));
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_pattern_compilation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("pattern_compilation");
+
+    let rule = PatternRule::new("$X.unwrap()").inside("fn $F($$$) -> Result<$$$> { $$$ }");
+    group.bench_function("rust", |b| {
+        b.iter(|| black_box(rule.compile(Lang::Rust).unwrap()));
+    });
+
+    let rule = PatternRule::new("console.log($$$ARGS)").inside("if ($COND) { $$$ }");
+    group.bench_function("typescript", |b| {
+        b.iter(|| black_box(rule.compile(Lang::TypeScript).unwrap()));
+    });
+
+    group.finish();
+}
+
+// ==============================================================================
+// Main Benchmark Configuration
+// ==============================================================================
+
+criterion_group!(
+    name = benches;
+    config = Criterion::default()
+        .sample_size(20)
+        .measurement_time(Duration::from_secs(10))
+        .warm_up_time(Duration::from_secs(3));
+    targets =
+        bench_rust_patterns,
+        bench_typescript_patterns,
+        bench_pattern_compilation,
+);
+
+criterion_main!(benches);
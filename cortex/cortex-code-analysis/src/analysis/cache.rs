@@ -8,6 +8,11 @@
 //!
 //! Uses LRU (Least Recently Used) eviction policy with configurable size limits.
 //!
+//! [`CacheManager`] can be backed by a [`SharedCache`] persisted on disk, which
+//! lets several processes reuse each other's metrics and extracted symbols.
+//! Reads go through the process-local cache first, then the shared one; writes
+//! go to both.
+//!
 //! # Examples
 //!
 //! ```
@@ -21,11 +26,17 @@
 //! ```
 
 use lru::LruCache;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 
+use super::shared_cache::{SharedCache, SharedNamespace};
+
 /// Compute a hash for a given value
 fn compute_hash<T: Hash>(value: &T) -> u64 {
     let mut hasher = DefaultHasher::new();
@@ -105,6 +116,14 @@ impl<K: Hash + Eq, V: Clone> Cache<K, V> {
     }
 }
 
+/// Compute a hash of `content` that is stable across processes and builds
+fn compute_content_hash(content: &[u8]) -> u64 {
+    let hash = blake3::hash(content);
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&hash.as_bytes()[..8]);
+    u64::from_le_bytes(bytes)
+}
+
 /// Cache key for source code
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
 pub struct SourceKey {
@@ -112,16 +131,38 @@ pub struct SourceKey {
     pub content_hash: u64,
     /// Language of the source
     pub language: String,
+    /// Path of the source file, if known
+    pub path: Option<String>,
 }
 
 impl SourceKey {
     /// Create a new source key
     pub fn new(content: &[u8], language: &str) -> Self {
         Self {
-            content_hash: compute_hash(&content),
+            content_hash: compute_content_hash(content),
             language: language.to_string(),
+            path: None,
         }
     }
+
+    /// Create a source key for the file at `path`
+    pub fn for_path(path: impl Into<String>, content: &[u8], language: &str) -> Self {
+        Self {
+            path: Some(path.into()),
+            ..Self::new(content, language)
+        }
+    }
+
+    /// Key of the entry in a [`SharedCache`]
+    fn shared_key(&self) -> Vec<u8> {
+        format!(
+            "{}\0{}\0{:016x}",
+            self.language,
+            self.path.as_deref().unwrap_or_default(),
+            self.content_hash
+        )
+        .into_bytes()
+    }
 }
 
 /// Cached parsed AST result
@@ -148,6 +189,18 @@ pub struct CachedMetrics {
 /// Cache specifically for computed metrics
 pub type MetricsCache = Cache<SourceKey, CachedMetrics>;
 
+/// Cached extracted symbols
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CachedSymbols {
+    /// JSON-serialized symbols
+    pub symbols_json: String,
+    /// Timestamp when cached
+    pub timestamp: std::time::SystemTime,
+}
+
+/// Cache specifically for extracted symbols
+pub type SymbolsCache = Cache<SourceKey, CachedSymbols>;
+
 /// Cache key for search operations
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
 pub struct SearchKey {
@@ -179,6 +232,26 @@ pub struct CachedSearch {
 /// Cache specifically for search results
 pub type SearchCache = Cache<SearchKey, CachedSearch>;
 
+/// Backend of the metrics and symbols caches of a [`CacheManager`]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum CacheBackend {
+    /// Process-local caches only
+    #[default]
+    InMemory,
+    /// Process-local caches backed by a database shared between processes
+    Persistent(PathBuf),
+}
+
+/// Default size budget of the shared cache
+pub const DEFAULT_SHARED_MAX_BYTES: u64 = 256 * 1024 * 1024;
+
+#[derive(Debug, Default)]
+struct HitCounters {
+    local_hits: AtomicU64,
+    shared_hits: AtomicU64,
+    misses: AtomicU64,
+}
+
 /// Multi-level cache manager
 ///
 /// Manages multiple caches for different operation types.
@@ -188,33 +261,120 @@ pub struct CacheManager {
     pub ast_cache: AstCache,
     /// Cache for computed metrics
     pub metrics_cache: MetricsCache,
+    /// Cache for extracted symbols
+    pub symbols_cache: SymbolsCache,
     /// Cache for search results
     pub search_cache: SearchCache,
+    shared: Option<SharedCache>,
+    counters: Arc<HitCounters>,
 }
 
 impl CacheManager {
     /// Create a new cache manager with default capacities
     pub fn new() -> Self {
-        Self {
-            ast_cache: AstCache::new(50),
-            metrics_cache: MetricsCache::new(100),
-            search_cache: SearchCache::new(200),
-        }
+        CacheBuilder::new().build()
     }
 
     /// Create a cache manager with custom capacities
     pub fn with_capacities(ast_cap: usize, metrics_cap: usize, search_cap: usize) -> Self {
-        Self {
-            ast_cache: AstCache::new(ast_cap),
-            metrics_cache: MetricsCache::new(metrics_cap),
-            search_cache: SearchCache::new(search_cap),
+        CacheBuilder::new()
+            .ast_capacity(ast_cap)
+            .metrics_capacity(metrics_cap)
+            .search_capacity(search_cap)
+            .build()
+    }
+
+    /// The shared cache backing this manager, if any
+    pub fn shared(&self) -> Option<&SharedCache> {
+        self.shared.as_ref()
+    }
+
+    /// Get metrics from the local cache, then from the shared cache
+    pub fn get_metrics(&self, key: &SourceKey) -> Option<CachedMetrics> {
+        self.read_through(&self.metrics_cache, SharedNamespace::Metrics, key)
+    }
+
+    /// Store metrics in the local cache and the shared cache
+    pub fn put_metrics(&self, key: SourceKey, metrics: CachedMetrics) {
+        self.write_through(&self.metrics_cache, SharedNamespace::Metrics, key, metrics);
+    }
+
+    /// Get extracted symbols from the local cache, then from the shared cache
+    pub fn get_symbols(&self, key: &SourceKey) -> Option<CachedSymbols> {
+        self.read_through(&self.symbols_cache, SharedNamespace::Symbols, key)
+    }
+
+    /// Store extracted symbols in the local cache and the shared cache
+    pub fn put_symbols(&self, key: SourceKey, symbols: CachedSymbols) {
+        self.write_through(&self.symbols_cache, SharedNamespace::Symbols, key, symbols);
+    }
+
+    fn read_through<V>(
+        &self,
+        local: &Cache<SourceKey, V>,
+        namespace: SharedNamespace,
+        key: &SourceKey,
+    ) -> Option<V>
+    where
+        V: Clone + DeserializeOwned,
+    {
+        if let Some(value) = local.get(key) {
+            self.counters.local_hits.fetch_add(1, Ordering::Relaxed);
+            return Some(value);
+        }
+
+        let shared = self.shared.as_ref().and_then(|shared| {
+            match shared.get(namespace, &key.shared_key()) {
+                Ok(Some(bytes)) => serde_json::from_slice::<V>(&bytes).ok(),
+                Ok(None) => None,
+                Err(e) => {
+                    tracing::warn!("Shared cache read failed: {}", e);
+                    None
+                }
+            }
+        });
+
+        match shared {
+            Some(value) => {
+                self.counters.shared_hits.fetch_add(1, Ordering::Relaxed);
+                local.put(key.clone(), value.clone());
+                Some(value)
+            }
+            None => {
+                self.counters.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
+    fn write_through<V>(
+        &self,
+        local: &Cache<SourceKey, V>,
+        namespace: SharedNamespace,
+        key: SourceKey,
+        value: V,
+    ) where
+        V: Clone + Serialize,
+    {
+        if let Some(shared) = &self.shared {
+            let result = serde_json::to_vec(&value)
+                .map_err(anyhow::Error::from)
+                .and_then(|bytes| shared.put(namespace, &key.shared_key(), &bytes));
+            if let Err(e) = result {
+                tracing::warn!("Shared cache write failed: {}", e);
+            }
         }
+        local.put(key, value);
     }
 
-    /// Clear all caches
+    /// Clear all process-local caches
+    ///
+    /// The shared cache is left alone since other processes rely on it; use
+    /// [`SharedCache::clear`] through [`shared`](Self::shared) to empty it.
     pub fn clear_all(&self) {
         self.ast_cache.clear();
         self.metrics_cache.clear();
+        self.symbols_cache.clear();
         self.search_cache.clear();
     }
 
@@ -223,7 +383,11 @@ impl CacheManager {
         CacheStats {
             ast_entries: self.ast_cache.len(),
             metrics_entries: self.metrics_cache.len(),
+            symbols_entries: self.symbols_cache.len(),
             search_entries: self.search_cache.len(),
+            local_hits: self.counters.local_hits.load(Ordering::Relaxed),
+            shared_hits: self.counters.shared_hits.load(Ordering::Relaxed),
+            misses: self.counters.misses.load(Ordering::Relaxed),
         }
     }
 }
@@ -241,14 +405,37 @@ pub struct CacheStats {
     pub ast_entries: usize,
     /// Number of metrics entries cached
     pub metrics_entries: usize,
+    /// Number of symbols entries cached
+    #[serde(default)]
+    pub symbols_entries: usize,
     /// Number of search entries cached
     pub search_entries: usize,
+    /// Metrics and symbols lookups served by the process-local cache
+    #[serde(default)]
+    pub local_hits: u64,
+    /// Metrics and symbols lookups served by the shared cache
+    #[serde(default)]
+    pub shared_hits: u64,
+    /// Metrics and symbols lookups served by neither
+    #[serde(default)]
+    pub misses: u64,
 }
 
 impl CacheStats {
     /// Get total number of cached entries
     pub fn total_entries(&self) -> usize {
-        self.ast_entries + self.metrics_entries + self.search_entries
+        self.ast_entries + self.metrics_entries + self.symbols_entries + self.search_entries
+    }
+
+    /// Share of metrics and symbols lookups served by either cache
+    pub fn hit_rate(&self) -> f64 {
+        let hits = self.local_hits + self.shared_hits;
+        let total = hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            hits as f64 / total as f64
+        }
     }
 }
 
@@ -257,7 +444,10 @@ impl CacheStats {
 pub struct CacheBuilder {
     ast_capacity: usize,
     metrics_capacity: usize,
+    symbols_capacity: usize,
     search_capacity: usize,
+    backend: CacheBackend,
+    shared_max_bytes: u64,
 }
 
 impl Default for CacheBuilder {
@@ -265,7 +455,10 @@ impl Default for CacheBuilder {
         Self {
             ast_capacity: 50,
             metrics_capacity: 100,
+            symbols_capacity: 100,
             search_capacity: 200,
+            backend: CacheBackend::InMemory,
+            shared_max_bytes: DEFAULT_SHARED_MAX_BYTES,
         }
     }
 }
@@ -288,19 +481,59 @@ impl CacheBuilder {
         self
     }
 
+    /// Set symbols cache capacity
+    pub fn symbols_capacity(mut self, capacity: usize) -> Self {
+        self.symbols_capacity = capacity;
+        self
+    }
+
     /// Set search cache capacity
     pub fn search_capacity(mut self, capacity: usize) -> Self {
         self.search_capacity = capacity;
         self
     }
 
+    /// Set the backend of the metrics and symbols caches
+    pub fn backend(mut self, backend: CacheBackend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Set the size budget of the shared cache, in bytes
+    pub fn shared_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.shared_max_bytes = max_bytes;
+        self
+    }
+
     /// Build the cache manager
+    ///
+    /// If the persistent backend cannot be opened, the manager falls back to
+    /// process-local caches; use [`try_build`](Self::try_build) to get the error.
     pub fn build(self) -> CacheManager {
-        CacheManager::with_capacities(
-            self.ast_capacity,
-            self.metrics_capacity,
-            self.search_capacity,
-        )
+        let fallback = self.clone().backend(CacheBackend::InMemory);
+        self.try_build().unwrap_or_else(|e| {
+            tracing::warn!("Shared cache unavailable, using in-memory caches: {}", e);
+            fallback.try_build().expect("in-memory caches cannot fail")
+        })
+    }
+
+    /// Build the cache manager, failing if the persistent backend cannot be opened
+    pub fn try_build(self) -> anyhow::Result<CacheManager> {
+        let shared = match &self.backend {
+            CacheBackend::InMemory => None,
+            CacheBackend::Persistent(path) => {
+                Some(SharedCache::open(path.clone(), self.shared_max_bytes)?)
+            }
+        };
+
+        Ok(CacheManager {
+            ast_cache: AstCache::new(self.ast_capacity),
+            metrics_cache: MetricsCache::new(self.metrics_capacity),
+            symbols_cache: SymbolsCache::new(self.symbols_capacity),
+            search_cache: SearchCache::new(self.search_capacity),
+            shared,
+            counters: Arc::new(HitCounters::default()),
+        })
     }
 }
 
@@ -401,6 +634,50 @@ mod tests {
         assert_eq!(manager.search_cache.len(), 0);
     }
 
+    #[test]
+    fn test_cache_manager_read_through_shared_cache() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let backend = CacheBackend::Persistent(dir.path().join("cache.redb"));
+        // Two managers on the same database stand in for two processes
+        let cli = CacheBuilder::new().backend(backend.clone()).try_build()?;
+        let server = CacheBuilder::new().backend(backend).try_build()?;
+
+        let key = SourceKey::for_path("src/lib.rs", b"fn main() {}", "rust");
+        assert!(server.get_metrics(&key).is_none());
+
+        cli.put_metrics(
+            key.clone(),
+            CachedMetrics {
+                metrics_json: "{\"nom\":1}".to_string(),
+                timestamp: std::time::SystemTime::now(),
+            },
+        );
+        assert_eq!(cli.get_metrics(&key).unwrap().metrics_json, "{\"nom\":1}");
+
+        // First lookup comes from the shared cache, the next from the local one
+        assert_eq!(server.get_metrics(&key).unwrap().metrics_json, "{\"nom\":1}");
+        assert!(server.get_metrics(&key).is_some());
+        let stats = server.stats();
+        assert_eq!((stats.misses, stats.shared_hits, stats.local_hits), (1, 1, 1));
+        assert_eq!(cli.stats().local_hits, 1);
+
+        // Same content at another path is a different entry
+        let moved = SourceKey::for_path("src/main.rs", b"fn main() {}", "rust");
+        assert!(server.get_symbols(&moved).is_none());
+        assert!(server.get_metrics(&moved).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_cache_builder_falls_back_to_memory() {
+        let dir = tempfile::tempdir().unwrap();
+        // A directory cannot be opened as a database
+        let manager = CacheBuilder::new()
+            .backend(CacheBackend::Persistent(dir.path().to_path_buf()))
+            .build();
+        assert!(manager.shared().is_none());
+    }
+
     #[test]
     fn test_search_key() {
         let content = b"fn main() {}";
@@ -6,11 +6,13 @@
 //! - Performance optimizations for large ASTs
 //! - Type-safe node kind filtering
 //! - Range-based filtering (line and column)
+//! - Structural pattern queries with metavariable bindings (see [`super::pattern`])
 //!
 //! # Examples
 //!
 //! ```no_run
 //! use cortex_code_analysis::analysis::find::{AstFinder, FindConfig, NodeFilter};
+//! use cortex_code_analysis::analysis::pattern::PatternRule;
 //! use cortex_code_analysis::{Parser, RustLanguage};
 //! use cortex_code_analysis::traits::ParserTrait;
 //! use std::path::Path;
@@ -25,12 +27,22 @@
 //!
 //! let finder = AstFinder::new(&parser);
 //! let results = finder.find(&config)?;
+//!
+//! // Find every `.unwrap()` inside a function returning `Result`
+//! let config = FindConfig::builder()
+//!     .pattern(PatternRule::new("$X.unwrap()").inside("fn $F($$$) -> Result<$$$> { $$$ }"))
+//!     .build();
+//!
+//! for m in finder.find(&config)?.matches {
+//!     println!("{} unwraps {:?}", m.text("F").unwrap_or("?"), m.text("X"));
+//! }
 //! # Ok::<(), anyhow::Error>(())
 //! ```
 
+use super::pattern::{CompiledRule, PatternMatch, PatternRule};
 use crate::node::Node;
 use crate::traits::ParserTrait;
-use anyhow::Result;
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::path::PathBuf;
@@ -93,9 +105,13 @@ pub struct FindConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub path: Option<PathBuf>,
 
-    /// Filters to apply (node matches if ANY filter matches)
+    /// Filters to apply (node matches if ANY filter or pattern matches)
     pub filters: Vec<NodeFilter>,
 
+    /// Structural patterns, compiled for the parser's language at search time
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub patterns: Vec<PatternRule>,
+
     /// Maximum number of results to return (None = unlimited)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub limit: Option<usize>,
@@ -112,6 +128,7 @@ impl Default for FindConfig {
         Self {
             path: None,
             filters: Vec::new(),
+            patterns: Vec::new(),
             limit: None,
             include_descendants: true,
             deduplicate: false,
@@ -131,6 +148,7 @@ impl FindConfig {
 pub struct FindConfigBuilder {
     path: Option<PathBuf>,
     filters: Vec<NodeFilter>,
+    patterns: Vec<PatternRule>,
     limit: Option<usize>,
     include_descendants: bool,
     deduplicate: bool,
@@ -155,6 +173,13 @@ impl FindConfigBuilder {
         self
     }
 
+    /// Add a structural pattern, e.g. `"$X.unwrap()"` or a [`PatternRule`]
+    /// carrying `inside`/`has` constraints
+    pub fn pattern(mut self, rule: impl Into<PatternRule>) -> Self {
+        self.patterns.push(rule.into());
+        self
+    }
+
     /// Set the result limit
     pub fn limit(mut self, limit: usize) -> Self {
         self.limit = Some(limit);
@@ -178,6 +203,7 @@ impl FindConfigBuilder {
         FindConfig {
             path: self.path,
             filters: self.filters,
+            patterns: self.patterns,
             limit: self.limit,
             include_descendants: self.include_descendants,
             deduplicate: self.deduplicate,
@@ -200,6 +226,10 @@ pub struct FindResult<'a> {
 
     /// Whether the search was limited
     pub limited: bool,
+
+    /// Metavariable bindings of nodes matched by a pattern, in match order
+    #[serde(default)]
+    pub matches: Vec<PatternMatch>,
 }
 
 impl<'a> FindResult<'a> {
@@ -211,8 +241,15 @@ impl<'a> FindResult<'a> {
             nodes_visited,
             nodes_matched,
             limited,
+            matches: Vec::new(),
         }
     }
+
+    /// Attach the pattern matches found during the search
+    pub fn with_matches(mut self, matches: Vec<PatternMatch>) -> Self {
+        self.matches = matches;
+        self
+    }
 }
 
 /// High-performance AST finder with optimized traversal
@@ -241,10 +278,22 @@ impl<'a, T: ParserTrait> AstFinder<'a, T> {
     /// Uses an iterative stack-based approach for efficient traversal,
     /// avoiding recursion overhead and stack overflow issues.
     pub fn find(&self, config: &FindConfig) -> Result<FindResult<'a>> {
-        if config.filters.is_empty() {
+        if config.filters.is_empty() && config.patterns.is_empty() {
             return Ok(FindResult::new(Vec::new(), 0, false));
         }
 
+        let lang = self.parser.get_language();
+        let rules = config
+            .patterns
+            .iter()
+            .map(|rule| {
+                rule.compile(lang)
+                    .with_context(|| format!("invalid pattern `{}`", rule.pattern))
+            })
+            .collect::<Result<Vec<CompiledRule>>>()?;
+        let code = self.parser.get_code();
+        let mut pattern_matches = Vec::new();
+
         let root = self.parser.get_root();
         let mut cursor = root.cursor();
         let mut stack = Vec::with_capacity(1024); // Pre-allocate for performance
@@ -273,8 +322,22 @@ impl<'a, T: ParserTrait> AstFinder<'a, T> {
                 }
             }
 
-            // Apply filters
-            let matches = config.filters.iter().any(|filter| filter.matches(&node, depth));
+            // Apply filters, then patterns (cheap kind check first)
+            let mut matches = config.filters.iter().any(|filter| filter.matches(&node, depth));
+            if !matches {
+                let pattern_match = rules
+                    .iter()
+                    .filter(|rule| {
+                        rule.pattern()
+                            .root_kind()
+                            .is_none_or(|kind| kind == node.kind())
+                    })
+                    .find_map(|rule| rule.matches(&node, code));
+                if let Some(pattern_match) = pattern_match {
+                    pattern_matches.push(pattern_match);
+                    matches = true;
+                }
+            }
 
             if matches {
                 matched.push(node);
@@ -282,7 +345,8 @@ impl<'a, T: ParserTrait> AstFinder<'a, T> {
                 // Check limit
                 if let Some(limit) = config.limit {
                     if matched.len() >= limit {
-                        return Ok(FindResult::new(matched, visited, true));
+                        return Ok(FindResult::new(matched, visited, true)
+                            .with_matches(pattern_matches));
                     }
                 }
 
@@ -311,7 +375,7 @@ impl<'a, T: ParserTrait> AstFinder<'a, T> {
             }
         }
 
-        Ok(FindResult::new(matched, visited, false))
+        Ok(FindResult::new(matched, visited, false).with_matches(pattern_matches))
     }
 
     /// Find the first node matching the configuration
@@ -349,6 +413,12 @@ impl<'a, T: ParserTrait> AstFinder<'a, T> {
         self.find(&config)
     }
 
+    /// Find all nodes matching a structural pattern
+    pub fn find_pattern(&self, pattern: &str) -> Result<FindResult<'a>> {
+        let config = FindConfig::builder().pattern(pattern).build();
+        self.find(&config)
+    }
+
     /// Count nodes matching the configuration
     pub fn count(&self, config: &FindConfig) -> Result<usize> {
         let result = self.find(config)?;
@@ -449,4 +519,37 @@ mod tests {
         assert!(!config.include_descendants);
         assert!(config.deduplicate);
     }
+
+    #[test]
+    fn test_find_pattern_with_bindings() {
+        let source = "fn load() -> Result<Config, Error> {\n    let raw = read(path).unwrap();\n    parse(raw)\n}\nfn main() { load().unwrap(); }";
+        let parser = Parser::<RustLanguage>::new(source.as_bytes().to_vec(), Path::new("test.rs")).unwrap();
+
+        let finder = AstFinder::new(&parser);
+        let result = finder.find_pattern("$X.unwrap()").unwrap();
+        assert_eq!(result.nodes.len(), 2);
+        assert_eq!(result.matches.len(), 2);
+
+        let config = FindConfig::builder()
+            .pattern(PatternRule::new("$X.unwrap()").inside("fn $F($$$) -> Result<$$$> { $$$ }"))
+            .build();
+        let result = finder.find(&config).unwrap();
+        assert_eq!(result.nodes.len(), 1);
+
+        let m = &result.matches[0];
+        assert_eq!(m.text("X"), Some("read(path)"));
+        assert_eq!(m.text("F"), Some("load"));
+        assert_eq!(m.get("X").unwrap().start_position, (1, 14));
+        assert_eq!(m.start_byte, result.nodes[0].start_byte());
+    }
+
+    #[test]
+    fn test_find_pattern_error() {
+        let source = "fn main() {}";
+        let parser = Parser::<RustLanguage>::new(source.as_bytes().to_vec(), Path::new("test.rs")).unwrap();
+
+        let config = FindConfig::builder().pattern("let = ;").build();
+        let err = AstFinder::new(&parser).find(&config).unwrap_err();
+        assert!(format!("{err:#}").contains("does not parse as Rust code"), "{err:#}");
+    }
 }
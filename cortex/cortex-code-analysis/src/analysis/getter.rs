@@ -25,7 +25,8 @@
 //! ## JavaScript/TypeScript/TSX
 //! - Extracts names from pair assignments and variable declarations
 //! - Handles anonymous functions
-//! - Full operator/operand classification
+//! - Operator/operand classification covering JSX, optional chaining and
+//!   template literals, aligned with the Rust counting rules
 //!
 //! ## C++
 //! - Complex function name extraction from declarators
@@ -302,19 +303,83 @@ fn get_typescript_space_kind(node: &Node) -> SpaceKind {
     }
 }
 
+/// Classifies TypeScript/JavaScript nodes for Halstead metrics.
+///
+/// The counting follows the Rust classifier so that the figures of both
+/// languages can be compared:
+/// - Only leaves are counted. Composite nodes such as `member_expression` or
+///   `nested_identifier` are left to their children, so `a.b` yields the
+///   operands `a`, `b` and the operator `.`.
+/// - Bracket pairs count once, through their opening token (`(`, `[`, `{`).
+/// - Literals count as a single operand; the quotes, fragments and flags of
+///   `string` and `regex` nodes are not counted on their own.
+/// - Type keywords (`predefined_type`) are operators, like `primitive_type`
+///   in Rust, while user-defined type names are operands.
+///
+/// Where the grammar has no Rust counterpart the divergences are:
+/// - Template literals: every text fragment between interpolations is an
+///   operand, each `${` is an operator and the interpolated expressions are
+///   classified as ordinary code. Backticks, the closing `}` and escape
+///   sequences are not counted, so `` `a${b}c` `` yields the operands `a`,
+///   `b`, `c` and the operator `${`.
+/// - Optional chaining: `?.` is an operator of its own, distinct from `.`.
+/// - JSX: the `<` opening a tag is the operator of the element, as the
+///   closing tag and the `/` and `>` delimiters only end it. Tag names and
+///   attribute names are operands, `{` of an embedded expression is an
+///   operator and each `jsx_text` is an operand.
 fn get_typescript_op_type(node: &Node) -> HalsteadType {
-    match node.kind() {
-        "export" | "import" | "extends" | "." | "from" | "(" | "," | "as" | "*" | ">>" | ">>>"
-        | ":" | "return" | "delete" | "throw" | "break" | "continue" | "if" | "else" | "switch"
-        | "case" | "default" | "async" | "for" | "in" | "of" | "while" | "try" | "catch"
-        | "finally" | "with" | "=" | "@" | "&&" | "||" | "+" | "-" | "--" | "++" | "/" | "%"
-        | "**" | "|" | "&" | "<<" | "~" | "<" | "<=" | "==" | "!=" | ">=" | ">" | "+=" | "!"
-        | "!==" | "===" | "-=" | "*=" | "/=" | "%=" | "**=" | ">>=" | ">>>=" | "<<=" | "&="
-        | "^" | "^=" | "|=" | "yield" | "[" | "{" | "await" | "??" | "?" | "new" | "let"
-        | "var" | "const" | "function" | ";" => HalsteadType::Operator,
-        "identifier" | "nested_identifier" | "member_expression" | "property_identifier"
-        | "string" | "number" | "true" | "false" | "null" | "void" | "this" | "super"
-        | "undefined" | "set" | "get" | "typeof" | "instanceof" => HalsteadType::Operand,
+    let kind = node.kind();
+
+    if let Some(parent) = node.parent() {
+        match parent.kind() {
+            "string" | "regex" | "predefined_type" | "optional_chain" => {
+                return HalsteadType::Unknown;
+            }
+            "template_string" => {
+                return if kind == "string_fragment" {
+                    HalsteadType::Operand
+                } else {
+                    HalsteadType::Unknown
+                };
+            }
+            "jsx_opening_element" | "jsx_self_closing_element" if !node.is_named() => {
+                return if kind == "<" {
+                    HalsteadType::Operator
+                } else {
+                    HalsteadType::Unknown
+                };
+            }
+            "jsx_closing_element" if !node.is_named() => return HalsteadType::Unknown,
+            _ => {}
+        }
+    }
+
+    // Literal kinds clash with the type keywords of the same name, which are
+    // anonymous tokens
+    if node.is_named() {
+        match kind {
+            "identifier" | "property_identifier" | "shorthand_property_identifier"
+            | "shorthand_property_identifier_pattern" | "private_property_identifier"
+            | "type_identifier" | "statement_identifier" | "string" | "number" | "regex"
+            | "true" | "false" | "null" | "undefined" | "this" | "super" | "jsx_text" => return HalsteadType::Operand,
+            "predefined_type" | "optional_chain" => return HalsteadType::Operator,
+            _ => {}
+        }
+    }
+
+    match kind {
+        "export" | "import" | "extends" | "implements" | "." | "?." | "from" | "(" | ","
+        | "as" | "satisfies" | "*" | ">>" | ">>>" | ":" | "return" | "delete" | "throw"
+        | "break" | "continue" | "if" | "else" | "switch" | "case" | "default" | "async"
+        | "for" | "in" | "of" | "while" | "do" | "try" | "catch" | "finally" | "with" | "="
+        | "@" | "&&" | "||" | "??" | "+" | "-" | "--" | "++" | "/" | "%" | "**" | "|" | "&"
+        | "<<" | "~" | "<" | "<=" | "==" | "!=" | ">=" | ">" | "+=" | "!" | "!==" | "==="
+        | "-=" | "*=" | "/=" | "%=" | "**=" | ">>=" | ">>>=" | "<<=" | "&=" | "^" | "^="
+        | "|=" | "&&=" | "||=" | "??=" | "yield" | "[" | "{" | "${" | "await" | "?" | "new"
+        | "let" | "var" | "const" | "function" | "class" | "interface" | "type" | "enum"
+        | "namespace" | "=>" | "..." | "typeof" | "instanceof" | "void" | "keyof" | "get"
+        | "set" | "static" | "readonly" | "public" | "private" | "protected" | "abstract"
+        | "declare" | "override" | "debugger" | ";" => HalsteadType::Operator,
         _ => HalsteadType::Unknown,
     }
 }
@@ -8,10 +8,12 @@
 //!
 //! ## Advanced Features
 //! - [`find`]: High-performance AST search and navigation
+//! - [`pattern`]: Structural (ast-grep style) pattern queries
 //! - [`count`]: Efficient node counting with statistics
 //! - [`alterator`]: AST transformation and mutation
 //! - [`tools`]: Utility functions for file I/O and language detection
 //! - [`cache`]: LRU caching for parsed ASTs and computed metrics
+//! - [`shared_cache`]: Persistent cache shared between processes
 //!
 //! # Examples
 //!
@@ -72,6 +74,8 @@ pub mod comment;
 pub mod count;
 pub mod find;
 pub mod getter;
+pub mod pattern;
+pub mod shared_cache;
 pub mod tools;
 pub mod types;
 
@@ -84,9 +88,11 @@ pub use alterator::{
     AstDiff, DiffConfig, Rewrite, AstPattern, visit_ast, diff_ast, apply_rewrites,
 };
 pub use cache::{
-    AstCache, Cache, CacheBuilder, CacheManager, CachedAst, CachedMetrics, CachedSearch,
-    MetricsCache, SearchCache, SearchKey, SourceKey,
+    AstCache, Cache, CacheBackend, CacheBuilder, CacheManager, CacheStats, CachedAst,
+    CachedMetrics, CachedSearch, CachedSymbols, MetricsCache, SearchCache, SearchKey, SourceKey,
+    SymbolsCache,
 };
+pub use shared_cache::{SharedCache, SharedNamespace};
 pub use checker::{
     DefaultNodeChecker, NodeChecker, LintRule, LintChecker, LintViolation, Severity,
     AntiPattern, AntiPatternDetector, FunctionTooLongRule, DeepNestingRule,
@@ -99,6 +105,7 @@ pub use find::{
     AstFinder, FindConfig, FindConfigBuilder, FindResult, NodeFilter,
 };
 pub use getter::{DefaultNodeGetter, NodeGetter};
+pub use pattern::{CompiledRule, MetaBinding, PatternMatch, PatternRule, StructuralPattern};
 pub use types::{HalsteadType, SpaceKind};
 pub use comment::{
     Comment, CommentAnalyzer, CommentMetrics, CommentType, analyze_comments,
@@ -0,0 +1,805 @@
+//! Structural pattern queries over the AST
+//!
+//! Patterns are snippets of code in the target language, in the style of
+//! ast-grep, with metavariables standing in for arbitrary subtrees:
+//!
+//! - `$NAME` matches exactly one node and binds it to `NAME`
+//! - `$$$NAME` matches zero or more sibling nodes and binds them to `NAME`
+//! - `$_` and `$$$` match without binding anything
+//!
+//! A pattern is compiled by parsing it with the tree-sitter grammar of the
+//! target language, so `$X.unwrap()` or `if $COND { $$$ }` are matched
+//! against the same node kinds the analysed code produces. Using the same
+//! metavariable twice requires both occurrences to bind identical text.
+//!
+//! [`PatternRule`] adds contextual constraints on top of a pattern:
+//! `inside(rule)` requires an ancestor to match `rule`, and `has(rule)`
+//! requires a descendant to match it.
+//!
+//! Only Rust and TypeScript (including TSX) are supported for now.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use cortex_code_analysis::analysis::pattern::PatternRule;
+//! use cortex_code_analysis::Lang;
+//!
+//! let rule = PatternRule::new("$X.unwrap()").inside("fn $F($$$) -> $R { $$$ }");
+//! let compiled = rule.compile(Lang::Rust)?;
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use crate::lang::Lang;
+use crate::node::Node;
+use crate::tree_sitter_wrapper::TreeSitterWrapper;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Prefix of the identifier a single-node metavariable is rewritten to
+const SINGLE_PREFIX: &str = "__cortex_mv_";
+
+/// Prefix of the identifier a multi-node metavariable is rewritten to
+const MULTI_PREFIX: &str = "__cortex_mvs_";
+
+/// A node of a compiled pattern
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PatternNode {
+    /// `$NAME`: any single node (anonymous when the name is empty)
+    Single(String),
+    /// `$$$NAME`: any run of sibling nodes (anonymous when the name is empty)
+    Multi(String),
+    /// A concrete node that must match by kind and, for leaves, by text
+    Node {
+        kind: &'static str,
+        named: bool,
+        text: Option<String>,
+        children: Vec<PatternNode>,
+    },
+}
+
+/// A pattern compiled against the grammar of a language
+#[derive(Debug, Clone)]
+pub struct StructuralPattern {
+    source: String,
+    lang: Lang,
+    root: PatternNode,
+}
+
+impl StructuralPattern {
+    /// Compile `pattern` for `lang`
+    ///
+    /// Fails when the language is not supported or when the pattern is not a
+    /// single, syntactically valid node in the language's grammar.
+    pub fn compile(pattern: &str, lang: Lang) -> Result<Self> {
+        if !matches!(lang, Lang::Rust | Lang::TypeScript | Lang::Tsx) {
+            bail!(
+                "structural patterns are not supported for {} yet (supported: Rust, TypeScript)",
+                lang.display_name()
+            );
+        }
+
+        let trimmed = pattern.trim();
+        if trimmed.is_empty() {
+            bail!("pattern is empty");
+        }
+
+        let rewritten = rewrite_metavariables(trimmed);
+        let mut parser = TreeSitterWrapper::new(lang.get_ts_language())?;
+        let mut first_error = None;
+
+        for (prefix, suffix) in wrappers(lang) {
+            let source = format!("{prefix}{rewritten}{suffix}");
+            let tree = parser.parse(&source)?;
+            let root = tree.root_node();
+            if root.has_error() {
+                if first_error.is_none() {
+                    first_error = first_error_position(root);
+                }
+                continue;
+            }
+
+            let target = prefix.len()..prefix.len() + rewritten.len();
+            let Some(node) = pattern_root(root, target) else {
+                bail!(
+                    "pattern `{}` must be a single {} node",
+                    trimmed,
+                    lang.display_name()
+                );
+            };
+
+            return Ok(Self {
+                source: trimmed.to_string(),
+                lang,
+                root: convert(node, source.as_bytes()),
+            });
+        }
+
+        match first_error {
+            Some((row, col)) => bail!(
+                "pattern `{}` does not parse as {} code (syntax error at line {}, column {})",
+                trimmed,
+                lang.display_name(),
+                row + 1,
+                col + 1
+            ),
+            None => bail!(
+                "pattern `{}` does not parse as {} code",
+                trimmed,
+                lang.display_name()
+            ),
+        }
+    }
+
+    /// The pattern source as written
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// The language the pattern was compiled for
+    pub fn lang(&self) -> Lang {
+        self.lang
+    }
+
+    /// The node kind a match must have, or `None` if the pattern is a bare
+    /// metavariable matching any node
+    pub fn root_kind(&self) -> Option<&'static str> {
+        match &self.root {
+            PatternNode::Node { kind, .. } => Some(*kind),
+            _ => None,
+        }
+    }
+
+    /// Match the pattern against `node`, returning its bindings on success
+    pub fn matches<'t>(&self, node: &Node<'t>, code: &'t [u8]) -> Option<PatternMatch> {
+        let mut env = Vec::new();
+        if match_node(&self.root, node, code, &mut env) {
+            Some(PatternMatch::new(node, &env, code))
+        } else {
+            None
+        }
+    }
+}
+
+/// A pattern together with contextual constraints
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PatternRule {
+    /// The pattern the node itself must match
+    pub pattern: String,
+
+    /// Rules an ancestor must match (all of them, possibly different ancestors)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inside: Vec<PatternRule>,
+
+    /// Rules a descendant must match (all of them, possibly different descendants)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub has: Vec<PatternRule>,
+}
+
+impl PatternRule {
+    /// Create a rule from a pattern without constraints
+    pub fn new(pattern: impl Into<String>) -> Self {
+        Self {
+            pattern: pattern.into(),
+            inside: Vec::new(),
+            has: Vec::new(),
+        }
+    }
+
+    /// Require an ancestor of the match to satisfy `rule`
+    pub fn inside(mut self, rule: impl Into<PatternRule>) -> Self {
+        self.inside.push(rule.into());
+        self
+    }
+
+    /// Require a descendant of the match to satisfy `rule`
+    pub fn has(mut self, rule: impl Into<PatternRule>) -> Self {
+        self.has.push(rule.into());
+        self
+    }
+
+    /// Compile the rule and all of its constraints for `lang`
+    pub fn compile(&self, lang: Lang) -> Result<CompiledRule> {
+        let pattern = StructuralPattern::compile(&self.pattern, lang)?;
+        let inside = self
+            .inside
+            .iter()
+            .map(|rule| rule.compile(lang))
+            .collect::<Result<Vec<_>>>()
+            .with_context(|| format!("invalid `inside` constraint of `{}`", self.pattern))?;
+        let has = self
+            .has
+            .iter()
+            .map(|rule| rule.compile(lang))
+            .collect::<Result<Vec<_>>>()
+            .with_context(|| format!("invalid `has` constraint of `{}`", self.pattern))?;
+
+        Ok(CompiledRule {
+            pattern,
+            inside,
+            has,
+        })
+    }
+}
+
+impl From<&str> for PatternRule {
+    fn from(pattern: &str) -> Self {
+        Self::new(pattern)
+    }
+}
+
+impl From<String> for PatternRule {
+    fn from(pattern: String) -> Self {
+        Self::new(pattern)
+    }
+}
+
+/// A [`PatternRule`] compiled for a specific language
+#[derive(Debug, Clone)]
+pub struct CompiledRule {
+    pattern: StructuralPattern,
+    inside: Vec<CompiledRule>,
+    has: Vec<CompiledRule>,
+}
+
+impl CompiledRule {
+    /// The compiled main pattern
+    pub fn pattern(&self) -> &StructuralPattern {
+        &self.pattern
+    }
+
+    /// Match the rule against `node`
+    ///
+    /// Metavariables bound by `inside` and `has` constraints are reported
+    /// alongside those of the main pattern, and must agree with them when a
+    /// name is reused.
+    pub fn matches<'t>(&self, node: &Node<'t>, code: &'t [u8]) -> Option<PatternMatch> {
+        let mut env = Vec::new();
+        if self.matches_with(node, code, &mut env) {
+            Some(PatternMatch::new(node, &env, code))
+        } else {
+            None
+        }
+    }
+
+    fn matches_with<'p, 't>(
+        &'p self,
+        node: &Node<'t>,
+        code: &'t [u8],
+        env: &mut Env<'p, 't>,
+    ) -> bool {
+        let mark = env.len();
+        let matched = match_node(&self.pattern.root, node, code, env)
+            && self
+                .inside
+                .iter()
+                .all(|rule| rule.matches_ancestor(node, code, env))
+            && self
+                .has
+                .iter()
+                .all(|rule| rule.matches_descendant(node, code, env));
+        if !matched {
+            env.truncate(mark);
+        }
+        matched
+    }
+
+    fn matches_ancestor<'p, 't>(
+        &'p self,
+        node: &Node<'t>,
+        code: &'t [u8],
+        env: &mut Env<'p, 't>,
+    ) -> bool {
+        let mut current = node.parent();
+        while let Some(ancestor) = current {
+            if self.matches_with(&ancestor, code, env) {
+                return true;
+            }
+            current = ancestor.parent();
+        }
+        false
+    }
+
+    fn matches_descendant<'p, 't>(
+        &'p self,
+        node: &Node<'t>,
+        code: &'t [u8],
+        env: &mut Env<'p, 't>,
+    ) -> bool {
+        let mut stack: Vec<Node<'t>> = node.children().collect();
+        while let Some(descendant) = stack.pop() {
+            if self.matches_with(&descendant, code, env) {
+                return true;
+            }
+            stack.extend(descendant.children());
+        }
+        false
+    }
+}
+
+/// The source span and text bound to a metavariable
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetaBinding {
+    /// The matched source text
+    pub text: String,
+    /// Start byte offset of the first bound node
+    pub start_byte: usize,
+    /// End byte offset of the last bound node
+    pub end_byte: usize,
+    /// Start position (row, column) of the first bound node
+    pub start_position: (usize, usize),
+    /// End position (row, column) of the last bound node
+    pub end_position: (usize, usize),
+    /// Number of nodes bound (always 1 for `$NAME`)
+    pub node_count: usize,
+}
+
+impl MetaBinding {
+    fn from_nodes(nodes: &[Node<'_>], code: &[u8]) -> Option<Self> {
+        let (first, last) = (nodes.first()?, nodes.last()?);
+        let (start_byte, end_byte) = (first.start_byte(), last.end_byte());
+        Some(Self {
+            text: String::from_utf8_lossy(&code[start_byte..end_byte]).into_owned(),
+            start_byte,
+            end_byte,
+            start_position: first.start_position(),
+            end_position: last.end_position(),
+            node_count: nodes.len(),
+        })
+    }
+}
+
+/// A successful pattern match
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PatternMatch {
+    /// Start byte offset of the matched node
+    pub start_byte: usize,
+    /// End byte offset of the matched node
+    pub end_byte: usize,
+    /// Start position (row, column) of the matched node
+    pub start_position: (usize, usize),
+    /// End position (row, column) of the matched node
+    pub end_position: (usize, usize),
+    /// Metavariable bindings, keyed by name without the leading `$`
+    ///
+    /// `$$$NAME` metavariables that matched no nodes have no entry.
+    pub bindings: BTreeMap<String, MetaBinding>,
+}
+
+impl PatternMatch {
+    fn new(node: &Node<'_>, env: &Env<'_, '_>, code: &[u8]) -> Self {
+        let bindings = env
+            .iter()
+            .filter_map(|(name, nodes)| {
+                MetaBinding::from_nodes(nodes, code).map(|binding| (name.to_string(), binding))
+            })
+            .collect();
+        Self {
+            start_byte: node.start_byte(),
+            end_byte: node.end_byte(),
+            start_position: node.start_position(),
+            end_position: node.end_position(),
+            bindings,
+        }
+    }
+
+    /// Get the binding of a metavariable
+    pub fn get(&self, name: &str) -> Option<&MetaBinding> {
+        self.bindings.get(name.trim_start_matches('$'))
+    }
+
+    /// Get the text bound to a metavariable
+    pub fn text(&self, name: &str) -> Option<&str> {
+        self.get(name).map(|binding| binding.text.as_str())
+    }
+}
+
+/// Metavariable bindings accumulated during matching
+///
+/// A vector rather than a map so that backtracking is a simple truncate.
+type Env<'p, 't> = Vec<(&'p str, Vec<Node<'t>>)>;
+
+/// Candidate wrappers tried, in order, to turn a fragment into a valid program
+fn wrappers(lang: Lang) -> &'static [(&'static str, &'static str)] {
+    match lang {
+        Lang::Rust => &[("", ""), ("fn __cortex_pattern() {\n", "\n}")],
+        _ => &[("", ""), ("function __cortex_pattern() {\n", "\n}")],
+    }
+}
+
+/// Replace `$NAME` and `$$$NAME` with identifiers valid in every grammar
+fn rewrite_metavariables(pattern: &str) -> String {
+    let bytes = pattern.as_bytes();
+    let mut out = String::with_capacity(pattern.len() + 16);
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'$' {
+            let multi = bytes[i..].starts_with(b"$$$");
+            let name_start = if multi { i + 3 } else { i + 1 };
+            let name_end = bytes[name_start..]
+                .iter()
+                .position(|b| !is_metavariable_char(*b))
+                .map_or(bytes.len(), |len| name_start + len);
+            let name = &pattern[name_start..name_end];
+            let starts_well = name
+                .bytes()
+                .next()
+                .is_some_and(|b| b.is_ascii_uppercase() || b == b'_');
+
+            if multi && (name.is_empty() || starts_well) {
+                out.push_str(MULTI_PREFIX);
+                out.push_str(name);
+                i = name_end;
+                continue;
+            }
+            if !multi && starts_well {
+                out.push_str(SINGLE_PREFIX);
+                out.push_str(name);
+                i = name_end;
+                continue;
+            }
+        }
+        let ch = pattern[i..].chars().next().unwrap_or_default();
+        out.push(ch);
+        i += ch.len_utf8();
+    }
+    out
+}
+
+fn is_metavariable_char(b: u8) -> bool {
+    b.is_ascii_uppercase() || b.is_ascii_digit() || b == b'_'
+}
+
+fn first_error_position(root: tree_sitter::Node<'_>) -> Option<(usize, usize)> {
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if node.is_error() || node.is_missing() {
+            let pos = node.start_position();
+            return Some((pos.row, pos.column));
+        }
+        if node.has_error() {
+            let mut cursor = node.walk();
+            let children: Vec<_> = node.children(&mut cursor).collect();
+            stack.extend(children.into_iter().rev());
+        }
+    }
+    None
+}
+
+/// Find the node spanning exactly `target`, unwrapping single-child wrappers
+/// such as `expression_statement`
+fn pattern_root<'t>(
+    root: tree_sitter::Node<'t>,
+    target: std::ops::Range<usize>,
+) -> Option<tree_sitter::Node<'t>> {
+    let mut node = root;
+    while node.byte_range() != target {
+        let mut cursor = node.walk();
+        let next = node
+            .children(&mut cursor)
+            .find(|child| child.start_byte() <= target.start && child.end_byte() >= target.end)?;
+        node = next;
+    }
+
+    while node.named_child_count() == 1 {
+        match node.named_child(0) {
+            Some(child) if child.byte_range() == target => node = child,
+            _ => break,
+        }
+    }
+
+    // A pattern spanning several top-level nodes only matches the whole file
+    if node.id() == root.id() {
+        return None;
+    }
+    Some(node)
+}
+
+/// Recognise a metavariable placeholder, possibly wrapped in nodes that only
+/// add punctuation (e.g. `expression_statement` adding `;`)
+fn placeholder(node: tree_sitter::Node<'_>, code: &[u8]) -> Option<PatternNode> {
+    if node.named_child_count() == 0 {
+        let text = node.utf8_text(code).ok()?;
+        if let Some(name) = text.strip_prefix(MULTI_PREFIX) {
+            return Some(PatternNode::Multi(anonymous_if_underscore(name)));
+        }
+        if let Some(name) = text.strip_prefix(SINGLE_PREFIX) {
+            return Some(PatternNode::Single(anonymous_if_underscore(name)));
+        }
+        return None;
+    }
+    if node.named_child_count() == 1 && node.is_named() && only_adds_terminator(node) {
+        return placeholder(node.named_child(0)?, code);
+    }
+    None
+}
+
+/// Whether `node` wraps its single named child with nothing but an optional
+/// trailing `;`, unlike blocks or argument lists whose delimiters must match
+fn only_adds_terminator(node: tree_sitter::Node<'_>) -> bool {
+    let mut cursor = node.walk();
+    let anonymous: Vec<_> = node
+        .children(&mut cursor)
+        .filter(|child| !child.is_named() && !child.is_extra())
+        .collect();
+    match anonymous.as_slice() {
+        [] => true,
+        [last] => last.kind() == ";" && last.end_byte() == node.end_byte(),
+        _ => false,
+    }
+}
+
+fn anonymous_if_underscore(name: &str) -> String {
+    if name == "_" {
+        String::new()
+    } else {
+        name.to_string()
+    }
+}
+
+fn convert(node: tree_sitter::Node<'_>, code: &[u8]) -> PatternNode {
+    if let Some(meta) = placeholder(node, code) {
+        return meta;
+    }
+
+    let mut cursor = node.walk();
+    let children: Vec<_> = node
+        .children(&mut cursor)
+        .filter(|child| !child.is_extra())
+        .map(|child| convert(child, code))
+        .collect();
+    let text = if children.is_empty() {
+        node.utf8_text(code).ok().map(str::to_string)
+    } else {
+        None
+    };
+
+    PatternNode::Node {
+        kind: node.kind(),
+        named: node.is_named(),
+        text,
+        children,
+    }
+}
+
+fn bind<'p, 't>(name: &'p str, nodes: &[Node<'t>], code: &'t [u8], env: &mut Env<'p, 't>) -> bool {
+    if name.is_empty() {
+        return true;
+    }
+    if let Some((_, bound)) = env.iter().find(|(bound_name, _)| *bound_name == name) {
+        return span_text(bound, code) == span_text(nodes, code);
+    }
+    env.push((name, nodes.to_vec()));
+    true
+}
+
+fn span_text<'t>(nodes: &[Node<'t>], code: &'t [u8]) -> &'t [u8] {
+    match (nodes.first(), nodes.last()) {
+        (Some(first), Some(last)) => &code[first.start_byte()..last.end_byte()],
+        _ => &[],
+    }
+}
+
+fn significant_children<'t>(node: &Node<'t>) -> Vec<Node<'t>> {
+    node.children()
+        .filter(|child| !child.inner().is_extra())
+        .collect()
+}
+
+fn match_node<'p, 't>(
+    pattern: &'p PatternNode,
+    node: &Node<'t>,
+    code: &'t [u8],
+    env: &mut Env<'p, 't>,
+) -> bool {
+    match pattern {
+        PatternNode::Single(name) => bind(name, std::slice::from_ref(node), code, env),
+        PatternNode::Multi(name) => bind(name, std::slice::from_ref(node), code, env),
+        PatternNode::Node {
+            kind,
+            named,
+            text,
+            children,
+        } => {
+            if node.kind() != *kind || node.is_named() != *named {
+                return false;
+            }
+            if children.is_empty() {
+                let is_leaf = node.child_count() == 0 || significant_children(node).is_empty();
+                return is_leaf && (!*named || text.as_deref() == node.utf8_text(code));
+            }
+            let mark = env.len();
+            let matched = match_seq(children, &significant_children(node), code, env);
+            if !matched {
+                env.truncate(mark);
+            }
+            matched
+        }
+    }
+}
+
+fn match_seq<'p, 't>(
+    patterns: &'p [PatternNode],
+    nodes: &[Node<'t>],
+    code: &'t [u8],
+    env: &mut Env<'p, 't>,
+) -> bool {
+    let Some((first, rest)) = patterns.split_first() else {
+        return nodes.is_empty();
+    };
+
+    if let PatternNode::Multi(name) = first {
+        for take in 0..=nodes.len() {
+            let mark = env.len();
+            if bind(name, &nodes[..take], code, env) && match_seq(rest, &nodes[take..], code, env) {
+                return true;
+            }
+            env.truncate(mark);
+        }
+        return false;
+    }
+
+    let Some((node, others)) = nodes.split_first() else {
+        return false;
+    };
+    let mark = env.len();
+    if match_node(first, node, code, env) && match_seq(rest, others, code, env) {
+        return true;
+    }
+    env.truncate(mark);
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::ParserTrait;
+    use crate::{Parser, RustLanguage, TypeScriptLanguage};
+    use std::path::Path;
+
+    fn all_matches<T: ParserTrait>(parser: &T, rule: &CompiledRule) -> Vec<PatternMatch> {
+        let code = parser.get_code();
+        let mut out = Vec::new();
+        let mut stack = vec![parser.get_root()];
+        while let Some(node) = stack.pop() {
+            if let Some(m) = rule.matches(&node, code) {
+                out.push(m);
+            }
+            let mut children: Vec<_> = node.children().collect();
+            children.reverse();
+            stack.extend(children);
+        }
+        out
+    }
+
+    fn rust(code: &str) -> Parser<RustLanguage> {
+        Parser::<RustLanguage>::new(code.as_bytes().to_vec(), Path::new("test.rs")).unwrap()
+    }
+
+    #[test]
+    fn test_rewrite_metavariables() {
+        assert_eq!(
+            rewrite_metavariables("$X.unwrap()"),
+            "__cortex_mv_X.unwrap()"
+        );
+        assert_eq!(
+            rewrite_metavariables("f($$$ARGS, $$$)"),
+            "f(__cortex_mvs_ARGS, __cortex_mvs_)"
+        );
+        // Lowercase `$` identifiers and template substitutions are left alone
+        assert_eq!(rewrite_metavariables("`${a}` + $el"), "`${a}` + $el");
+    }
+
+    #[test]
+    fn test_unwrap_binding() {
+        let parser = rust("fn main() { let v = config.get(\"k\").unwrap(); other.unwrap(); }");
+        let rule = PatternRule::new("$X.unwrap()").compile(Lang::Rust).unwrap();
+
+        let found = all_matches(&parser, &rule);
+        let texts: Vec<_> = found.iter().map(|m| m.text("X").unwrap()).collect();
+        assert_eq!(texts, vec!["config.get(\"k\")", "other"]);
+
+        let binding = found[1].get("$X").unwrap();
+        assert_eq!(binding.node_count, 1);
+        assert_eq!(binding.start_position.0, 0);
+        assert_eq!(binding.end_byte - binding.start_byte, "other".len());
+    }
+
+    #[test]
+    fn test_if_with_multi_body() {
+        let parser = rust("fn f(a: bool) { if a { g(); h(); } if a {} }");
+        let rule = PatternRule::new("if $COND { $$$BODY }")
+            .compile(Lang::Rust)
+            .unwrap();
+
+        let found = all_matches(&parser, &rule);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].text("COND"), Some("a"));
+        assert_eq!(found[0].text("BODY"), Some("g(); h();"));
+        assert_eq!(found[0].get("BODY").unwrap().node_count, 2);
+        // An empty `$$$` run matches but binds nothing
+        assert!(found[1].get("BODY").is_none());
+    }
+
+    #[test]
+    fn test_repeated_metavariable_must_agree() {
+        let parser = rust("fn f() { a == a; a == b; }");
+        let rule = PatternRule::new("$X == $X").compile(Lang::Rust).unwrap();
+
+        let found = all_matches(&parser, &rule);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].text("X"), Some("a"));
+    }
+
+    #[test]
+    fn test_inside_and_has() {
+        let code = "fn a() -> Result<(), E> { x.unwrap(); Ok(()) }\nfn b() { y.unwrap(); }";
+        let parser = rust(code);
+
+        let inside = PatternRule::new("$X.unwrap()")
+            .inside("fn $F($$$) -> Result<$$$> { $$$ }")
+            .compile(Lang::Rust)
+            .unwrap();
+        let found = all_matches(&parser, &inside);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].text("X"), Some("x"));
+        assert_eq!(found[0].text("F"), Some("a"));
+
+        let has = PatternRule::new("fn $F($$$) { $$$ }")
+            .has("$X.unwrap()")
+            .compile(Lang::Rust)
+            .unwrap();
+        let found = all_matches(&parser, &has);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].text("F"), Some("b"));
+    }
+
+    #[test]
+    fn test_typescript_patterns() {
+        let code = "function f(x: number) { if (x > 1) { log(x); } console.log(x, 2); }";
+        let parser =
+            Parser::<TypeScriptLanguage>::new(code.as_bytes().to_vec(), Path::new("test.ts"))
+                .unwrap();
+
+        let rule = PatternRule::new("console.log($$$ARGS)")
+            .compile(Lang::TypeScript)
+            .unwrap();
+        let found = all_matches(&parser, &rule);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].text("ARGS"), Some("x, 2"));
+
+        let rule = PatternRule::new("if ($COND) { $$$ }")
+            .compile(Lang::TypeScript)
+            .unwrap();
+        let found = all_matches(&parser, &rule);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].text("COND"), Some("x > 1"));
+    }
+
+    #[test]
+    fn test_invalid_patterns() {
+        let err = StructuralPattern::compile("fn (", Lang::Rust).unwrap_err();
+        assert!(
+            err.to_string().contains("does not parse as Rust code"),
+            "{err}"
+        );
+
+        let err = StructuralPattern::compile("a; b;", Lang::TypeScript).unwrap_err();
+        assert!(err.to_string().contains("single TypeScript node"), "{err}");
+
+        let err = StructuralPattern::compile("$X", Lang::Python).unwrap_err();
+        assert!(
+            err.to_string().contains("not supported for Python"),
+            "{err}"
+        );
+
+        let err = PatternRule::new("$X.unwrap()")
+            .inside("fn {")
+            .compile(Lang::Rust)
+            .unwrap_err();
+        assert!(
+            format!("{err:#}").contains("invalid `inside` constraint"),
+            "{err:#}"
+        );
+    }
+}
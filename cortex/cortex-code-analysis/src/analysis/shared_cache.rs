@@ -0,0 +1,399 @@
+//! Persistent cache shared between processes.
+//!
+//! [`SharedCache`] keeps serialized metrics and extracted symbols in a redb
+//! database, so that several processes working on the same repository (the
+//! CLI, the MCP server, a fleet of agents) analyse each file once. ASTs stay
+//! in the per-process caches of [`CacheManager`](super::CacheManager).
+//!
+//! redb locks the database file for as long as it is open, so the cache only
+//! opens it for the duration of one operation and retries with a short
+//! backoff while another process holds it. Entries are evicted by last
+//! access once their total size exceeds the configured budget.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use cortex_code_analysis::analysis::shared_cache::{SharedCache, SharedNamespace};
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let cache = SharedCache::open("/tmp/cortex-cache.redb", 64 * 1024 * 1024)?;
+//! cache.put(SharedNamespace::Metrics, b"key", b"{}")?;
+//! assert_eq!(cache.get(SharedNamespace::Metrics, b"key")?, Some(b"{}".to_vec()));
+//! # Ok(())
+//! # }
+//! ```
+
+use anyhow::{bail, Result};
+use redb::{Database, DatabaseError, ReadableTable, ReadableTableMetadata, Table, TableDefinition};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Serialized entries, keyed by namespace and source key
+const ENTRIES: TableDefinition<&[u8], &[u8]> = TableDefinition::new("entries");
+/// Last access of each entry, in milliseconds since the Unix epoch
+const ACCESS: TableDefinition<&[u8], u64> = TableDefinition::new("access");
+/// Bookkeeping values
+const META: TableDefinition<&str, u64> = TableDefinition::new("meta");
+/// Total size of the entries, keys included
+const META_BYTES: &str = "bytes";
+
+/// Attempts to acquire the database before giving up
+const OPEN_ATTEMPTS: u32 = 500;
+/// Upper bound of the backoff between two attempts
+const MAX_BACKOFF: Duration = Duration::from_millis(20);
+/// Hits closer than this to the recorded last access leave it untouched, so
+/// that reads rarely need a write transaction
+const ACCESS_GRANULARITY_MS: u64 = 60_000;
+/// Eviction frees space down to this share of the budget, in percent
+const EVICTION_WATERMARK: u64 = 90;
+
+/// Kind of data stored in a [`SharedCache`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SharedNamespace {
+    /// Serialized metrics
+    Metrics,
+    /// Serialized extracted symbols
+    Symbols,
+}
+
+impl SharedNamespace {
+    fn prefix(self) -> u8 {
+        match self {
+            Self::Metrics => b'm',
+            Self::Symbols => b's',
+        }
+    }
+}
+
+/// Cache persisted in a redb database and safe to share between processes.
+#[derive(Debug, Clone)]
+pub struct SharedCache {
+    path: PathBuf,
+    max_bytes: u64,
+    /// Serializes the accesses of this process, which redb would otherwise
+    /// reject while the database is open
+    lock: Arc<Mutex<()>>,
+}
+
+impl SharedCache {
+    /// Open or create the cache database at `path`, holding at most `max_bytes`
+    pub fn open(path: impl Into<PathBuf>, max_bytes: u64) -> Result<Self> {
+        let path = path.into();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let cache = Self {
+            path,
+            max_bytes,
+            lock: Arc::new(Mutex::new(())),
+        };
+
+        // Create the tables up front so that readers always find them
+        cache.with_db(|db| {
+            let txn = db.begin_write()?;
+            txn.open_table(ENTRIES)?;
+            txn.open_table(ACCESS)?;
+            txn.open_table(META)?;
+            txn.commit()?;
+            Ok(())
+        })?;
+
+        Ok(cache)
+    }
+
+    /// Path of the database file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Size budget of the entries
+    pub fn max_bytes(&self) -> u64 {
+        self.max_bytes
+    }
+
+    /// Get an entry, recording the access
+    pub fn get(&self, namespace: SharedNamespace, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        let key = entry_key(namespace, key);
+        self.with_db(|db| {
+            let (value, last_access) = {
+                let txn = db.begin_read()?;
+                let entries = txn.open_table(ENTRIES)?;
+                let Some(guard) = entries.get(key.as_slice())? else {
+                    return Ok(None);
+                };
+                let value = guard.value().to_vec();
+                let access = txn.open_table(ACCESS)?;
+                let last_access = access.get(key.as_slice())?.map_or(0, |guard| guard.value());
+                (value, last_access)
+            };
+
+            let now = now_millis();
+            if now.saturating_sub(last_access) >= ACCESS_GRANULARITY_MS {
+                let txn = db.begin_write()?;
+                {
+                    // The entry may have been evicted by another process meanwhile
+                    let entries = txn.open_table(ENTRIES)?;
+                    if entries.get(key.as_slice())?.is_some() {
+                        let mut access = txn.open_table(ACCESS)?;
+                        access.insert(key.as_slice(), now)?;
+                    }
+                }
+                txn.commit()?;
+            }
+
+            Ok(Some(value))
+        })
+    }
+
+    /// Insert or replace an entry, evicting the least recently used entries
+    /// when the budget is exceeded
+    ///
+    /// Entries larger than the whole budget are not stored.
+    pub fn put(&self, namespace: SharedNamespace, key: &[u8], value: &[u8]) -> Result<()> {
+        let key = entry_key(namespace, key);
+        let size = entry_size(&key, value.len());
+        if size > self.max_bytes {
+            return Ok(());
+        }
+
+        self.with_db(|db| {
+            let txn = db.begin_write()?;
+            {
+                let mut entries = txn.open_table(ENTRIES)?;
+                let mut access = txn.open_table(ACCESS)?;
+                let mut meta = txn.open_table(META)?;
+
+                let replaced = entries
+                    .insert(key.as_slice(), value)?
+                    .map_or(0, |old| entry_size(&key, old.value().len()));
+                access.insert(key.as_slice(), now_millis())?;
+
+                let mut total = meta.get(META_BYTES)?.map_or(0, |guard| guard.value());
+                total = total.saturating_sub(replaced) + size;
+                if total > self.max_bytes {
+                    let target = self.max_bytes.saturating_mul(EVICTION_WATERMARK) / 100;
+                    total = evict(&mut entries, &mut access, total, target, &key)?;
+                }
+                meta.insert(META_BYTES, total)?;
+            }
+            txn.commit()?;
+            Ok(())
+        })
+    }
+
+    /// Remove an entry
+    pub fn remove(&self, namespace: SharedNamespace, key: &[u8]) -> Result<bool> {
+        let key = entry_key(namespace, key);
+        self.with_db(|db| {
+            let txn = db.begin_write()?;
+            let removed = {
+                let mut entries = txn.open_table(ENTRIES)?;
+                let mut access = txn.open_table(ACCESS)?;
+                let mut meta = txn.open_table(META)?;
+
+                let removed = entries
+                    .remove(key.as_slice())?
+                    .map(|old| entry_size(&key, old.value().len()));
+                access.remove(key.as_slice())?;
+                if let Some(size) = removed {
+                    let total = meta.get(META_BYTES)?.map_or(0, |guard| guard.value());
+                    meta.insert(META_BYTES, total.saturating_sub(size))?;
+                }
+                removed.is_some()
+            };
+            txn.commit()?;
+            Ok(removed)
+        })
+    }
+
+    /// Remove every entry, for all the processes sharing the cache
+    pub fn clear(&self) -> Result<()> {
+        self.with_db(|db| {
+            let txn = db.begin_write()?;
+            txn.delete_table(ENTRIES)?;
+            txn.delete_table(ACCESS)?;
+            txn.delete_table(META)?;
+            txn.open_table(ENTRIES)?;
+            txn.open_table(ACCESS)?;
+            txn.open_table(META)?;
+            txn.commit()?;
+            Ok(())
+        })
+    }
+
+    /// Number of entries
+    pub fn len(&self) -> Result<u64> {
+        self.with_db(|db| {
+            let txn = db.begin_read()?;
+            let entries = txn.open_table(ENTRIES)?;
+            let len = entries.len()?;
+            Ok(len)
+        })
+    }
+
+    /// Check if the cache is empty
+    pub fn is_empty(&self) -> Result<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    /// Total size of the entries, keys included
+    pub fn size_bytes(&self) -> Result<u64> {
+        self.with_db(|db| {
+            let txn = db.begin_read()?;
+            let meta = txn.open_table(META)?;
+            let bytes = meta.get(META_BYTES)?.map_or(0, |guard| guard.value());
+            Ok(bytes)
+        })
+    }
+
+    fn with_db<T>(&self, f: impl FnOnce(&Database) -> Result<T>) -> Result<T> {
+        let _guard = self.lock.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let db = self.open_db()?;
+        f(&db)
+    }
+
+    fn open_db(&self) -> Result<Database> {
+        let mut backoff = Duration::from_millis(1);
+        for _ in 0..OPEN_ATTEMPTS {
+            match Database::create(&self.path) {
+                Ok(db) => return Ok(db),
+                Err(DatabaseError::DatabaseAlreadyOpen) => {
+                    thread::sleep(backoff);
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        bail!("shared cache {} is locked by another process", self.path.display())
+    }
+}
+
+/// Removes the least recently accessed entries, except `keep`, until `total`
+/// drops to `target`, and returns the new total
+fn evict(
+    entries: &mut Table<'_, &'static [u8], &'static [u8]>,
+    access: &mut Table<'_, &'static [u8], u64>,
+    mut total: u64,
+    target: u64,
+    keep: &[u8],
+) -> Result<u64> {
+    let mut candidates = Vec::new();
+    for item in access.iter()? {
+        let (key, last_access) = item?;
+        if key.value() != keep {
+            candidates.push((last_access.value(), key.value().to_vec()));
+        }
+    }
+    candidates.sort_unstable();
+
+    for (_, key) in candidates {
+        if total <= target {
+            break;
+        }
+        if let Some(old) = entries.remove(key.as_slice())? {
+            total = total.saturating_sub(entry_size(&key, old.value().len()));
+        }
+        access.remove(key.as_slice())?;
+    }
+
+    Ok(total)
+}
+
+fn entry_key(namespace: SharedNamespace, key: &[u8]) -> Vec<u8> {
+    let mut entry_key = Vec::with_capacity(key.len() + 1);
+    entry_key.push(namespace.prefix());
+    entry_key.extend_from_slice(key);
+    entry_key
+}
+
+fn entry_size(key: &[u8], value_len: usize) -> u64 {
+    (key.len() + value_len) as u64
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_cache_roundtrip() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let cache = SharedCache::open(dir.path().join("cache.redb"), 1024 * 1024)?;
+
+        cache.put(SharedNamespace::Metrics, b"a", b"metrics")?;
+        cache.put(SharedNamespace::Symbols, b"a", b"symbols")?;
+        assert_eq!(cache.get(SharedNamespace::Metrics, b"a")?, Some(b"metrics".to_vec()));
+        assert_eq!(cache.get(SharedNamespace::Symbols, b"a")?, Some(b"symbols".to_vec()));
+        assert_eq!(cache.get(SharedNamespace::Metrics, b"b")?, None);
+        assert_eq!(cache.len()?, 2);
+        assert_eq!(cache.size_bytes()?, 2 * (2 + 7));
+
+        assert!(cache.remove(SharedNamespace::Metrics, b"a")?);
+        assert_eq!(cache.size_bytes()?, 2 + 7);
+
+        cache.clear()?;
+        assert!(cache.is_empty()?);
+        assert_eq!(cache.size_bytes()?, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_shared_cache_evicts_least_recently_used() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        // Room for two entries of 2 + 100 bytes
+        let cache = SharedCache::open(dir.path().join("cache.redb"), 250)?;
+        let value = [0u8; 100];
+
+        for key in [b"a", b"b", b"c"] {
+            cache.put(SharedNamespace::Metrics, key, &value)?;
+            thread::sleep(Duration::from_millis(5));
+        }
+
+        assert_eq!(cache.get(SharedNamespace::Metrics, b"a")?, None);
+        assert!(cache.get(SharedNamespace::Metrics, b"b")?.is_some());
+        assert!(cache.get(SharedNamespace::Metrics, b"c")?.is_some());
+        assert!(cache.size_bytes()? <= 250);
+
+        // Entries larger than the budget are skipped
+        cache.put(SharedNamespace::Metrics, b"d", &[0u8; 300])?;
+        assert_eq!(cache.get(SharedNamespace::Metrics, b"d")?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_shared_cache_concurrent_handles() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("cache.redb");
+        SharedCache::open(&path, 1024 * 1024)?;
+
+        // Independent handles contend on the file lock like separate processes
+        let handles: Vec<_> = (0..4u8)
+            .map(|worker| {
+                let path = path.clone();
+                thread::spawn(move || -> Result<()> {
+                    let cache = SharedCache::open(path, 1024 * 1024)?;
+                    for i in 0..10u8 {
+                        cache.put(SharedNamespace::Symbols, &[worker, i], &[i; 16])?;
+                    }
+                    Ok(())
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap()?;
+        }
+
+        let cache = SharedCache::open(&path, 1024 * 1024)?;
+        assert_eq!(cache.len()?, 40);
+        assert_eq!(cache.get(SharedNamespace::Symbols, &[3, 9])?, Some(vec![9; 16]));
+        Ok(())
+    }
+}
@@ -183,6 +183,12 @@ impl AstEditor {
         }
     }
 
+    /// Find every occurrence of a symbol in the current file
+    pub fn symbol_occurrences(&self, name: &str) -> Vec<Range> {
+        let mut occurrences = Vec::new();
+        self.find_identifiers_recursive(self.root_node(), name, name, &mut occurrences);
+        occurrences.into_iter().map(|edit| edit.range).collect()
+    }
 
     /// Apply all pending edits and update the AST
     pub fn apply_edits(&mut self) -> Result<()> {
@@ -408,6 +414,114 @@ impl AstEditor {
         })
     }
 
+    /// Optimize imports (remove duplicates, sort) in TypeScript
+    pub fn optimize_imports_typescript(&mut self) -> Result<OptimizeImportsResult> {
+        let imports = self.query("(import_statement) @import")?;
+
+        let import_data: Vec<(Range, String)> = imports
+            .iter()
+            .filter(|node| node.parent().map(|p| p.kind()) == Some("program"))
+            .map(|node| (Range::from_node(node), self.node_text(node).to_string()))
+            .collect();
+
+        let mut import_texts: Vec<String> = import_data.iter().map(|(_, text)| text.clone()).collect();
+        let original_count = import_texts.len();
+
+        let mut seen = HashSet::new();
+        import_texts.retain(|import| seen.insert(import.clone()));
+        let removed_count = original_count - import_texts.len();
+
+        import_texts.sort();
+
+        for (range, _) in import_data.iter().rev() {
+            self.edits.push(Edit::delete(*range));
+        }
+
+        if !import_texts.is_empty() {
+            let sorted_imports = import_texts.join("\n") + "\n";
+            self.insert_at(0, 0, &sorted_imports)?;
+        }
+
+        Ok(OptimizeImportsResult {
+            removed: removed_count,
+            sorted: true,
+            grouped: false,
+        })
+    }
+
+    /// Check that lines `start_line..=end_line` can be extracted as a unit.
+    ///
+    /// The selection must cover whole statements of a single block. Returns a
+    /// description of the problem if it does not.
+    pub fn selection_conflict(&self, start_line: usize, end_line: usize) -> Option<String> {
+        let lines: Vec<&str> = self.source.lines().collect();
+
+        if start_line > end_line || end_line >= lines.len() {
+            return Some(format!("Invalid line range: {}-{}", start_line, end_line));
+        }
+
+        // Ignore leading and trailing whitespace of the selected lines
+        let start_col = indentation(lines[start_line]).len();
+        let end_col = lines[end_line].trim_end().len();
+        let start = self.position_to_byte(Position::new(start_line, start_col)).ok()?;
+        let end = self.position_to_byte(Position::new(end_line, end_col)).ok()?;
+        if start >= end {
+            return Some("Selection is empty".to_string());
+        }
+
+        let mut block = self.root_node().descendant_for_byte_range(start, end)?;
+        while !matches!(block.kind(), "block" | "statement_block") {
+            block = match block.parent() {
+                Some(parent) => parent,
+                None => return Some("Selection is not inside a function body".to_string()),
+            };
+        }
+
+        let mut cursor = block.walk();
+        for child in block.named_children(&mut cursor) {
+            let overlaps = child.start_byte() < end && child.end_byte() > start;
+            if overlaps && (child.start_byte() < start || child.end_byte() > end) {
+                return Some(format!(
+                    "Selection is not contiguous: it covers part of the {} at lines {}-{}",
+                    child.kind(),
+                    child.start_position().row,
+                    child.end_position().row
+                ));
+            }
+        }
+
+        None
+    }
+
+    /// Extract a block of code into a new function.
+    ///
+    /// Dispatches to the Rust or TypeScript implementation depending on the
+    /// editor's language; other languages are rejected. The selection is not
+    /// checked here, see [`Self::selection_conflict`].
+    pub fn extract_function(
+        &mut self,
+        start_line: usize,
+        end_line: usize,
+        function_name: &str,
+    ) -> Result<ExtractFunctionResult> {
+        if self.language == Language::from(tree_sitter_rust::LANGUAGE) {
+            let (parameters, return_type, function_code) =
+                self.extract_function_rust(start_line, end_line, function_name)?;
+            Ok(ExtractFunctionResult {
+                function_name: function_name.to_string(),
+                parameters,
+                return_type,
+                function_code,
+            })
+        } else if self.language == Language::from(tree_sitter_typescript::LANGUAGE_TYPESCRIPT)
+            || self.language == Language::from(tree_sitter_typescript::LANGUAGE_TSX)
+        {
+            self.extract_function_typescript(start_line, end_line, function_name)
+        } else {
+            Err(anyhow!("Extract function is only supported for Rust and TypeScript"))
+        }
+    }
+
     /// Extract a block of code into a new function (Rust-specific).
     ///
     /// Analyzes the code block between start_line and end_line, extracts variables
@@ -497,7 +611,9 @@ impl AstEditor {
             .map(|(name, _)| name.as_str())
             .collect::<Vec<_>>()
             .join(", ");
-        let function_call = format!("{}({})", function_name, call_args);
+        let indent = indentation(lines[start_line]);
+        let terminator = if return_type.is_some() { "" } else { ";" };
+        let function_call = format!("{}{}({}){}", indent, function_name, call_args, terminator);
 
         // Create edit to replace extracted code with function call
         let start_pos = Position::new(start_line, 0);
@@ -513,6 +629,142 @@ impl AstEditor {
         Ok((params, return_type, function_code))
     }
 
+    /// Extract a block of code into a new function (TypeScript-specific).
+    ///
+    /// Works like [`Self::extract_function_rust`]. Parameters are typed `any`
+    /// and the return type is left to TypeScript's inference.
+    ///
+    /// # Limitations
+    ///
+    /// - Basic variable analysis (may not catch all dependencies)
+    /// - Does not handle complex control flow (break, continue, return)
+    /// - Does not handle variables reassigned in the extracted code
+    pub fn extract_function_typescript(
+        &mut self,
+        start_line: usize,
+        end_line: usize,
+        function_name: &str,
+    ) -> Result<ExtractFunctionResult> {
+        let lines: Vec<&str> = self.source.lines().collect();
+
+        if start_line >= lines.len() || end_line >= lines.len() || start_line > end_line {
+            return Err(anyhow!("Invalid line range: {}-{}", start_line, end_line));
+        }
+
+        let extracted_code = lines[start_line..=end_line].join("\n");
+
+        let mut used_vars = HashSet::new();
+        let mut defined_vars = HashSet::new();
+
+        let wrapped_code = format!("function _temp() {{\n{}\n}}", extracted_code);
+        let mut temp_parser = Parser::new();
+        temp_parser.set_language(&self.language)?;
+
+        if let Some(temp_tree) = temp_parser.parse(&wrapped_code, None) {
+            Self::analyze_variables_typescript(
+                temp_tree.root_node(),
+                &wrapped_code,
+                &mut used_vars,
+                &mut defined_vars,
+            );
+        }
+
+        // Only variables declared before the selection are passed in; other
+        // identifiers are globals or functions in scope
+        let mut declared = HashSet::new();
+        self.declarations_before_typescript(self.root_node(), start_line, &mut declared);
+
+        let mut parameters: Vec<(String, String)> = used_vars
+            .difference(&defined_vars)
+            .filter(|v| declared.contains(*v))
+            .map(|v| (v.to_string(), "any".to_string()))
+            .collect();
+        parameters.sort();
+
+        let param_list = parameters
+            .iter()
+            .map(|(name, ty)| format!("{}: {}", name, ty))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let function_code = format!(
+            "function {}({}) {{\n{}\n}}",
+            function_name, param_list, extracted_code
+        );
+
+        let call_args = parameters
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let indent = indentation(lines[start_line]);
+        let function_call = format!("{}{}({});", indent, function_name, call_args);
+
+        let range = Range::new(
+            Position::new(start_line, 0),
+            Position::new(end_line, lines[end_line].len()),
+        );
+        self.edits.push(Edit::replace(range, function_call));
+
+        let insert_pos = Position::new(lines.len(), 0);
+        self.edits.push(Edit::insert(insert_pos, format!("\n\n{}", function_code)));
+
+        Ok(ExtractFunctionResult {
+            function_name: function_name.to_string(),
+            parameters,
+            return_type: None,
+            function_code,
+        })
+    }
+
+    /// Collect identifiers used and declared in TypeScript code
+    fn analyze_variables_typescript(
+        node: Node,
+        source: &str,
+        used_vars: &mut HashSet<String>,
+        defined_vars: &mut HashSet<String>,
+    ) {
+        if node.kind() == "identifier" {
+            let text = source[node.byte_range()].to_string();
+            let declared = node.parent().and_then(Self::declaration_name);
+            if declared.is_some_and(|name| name.id() == node.id()) {
+                defined_vars.insert(text);
+            } else {
+                used_vars.insert(text);
+            }
+            return;
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            Self::analyze_variables_typescript(child, source, used_vars, defined_vars);
+        }
+    }
+
+    /// Collect the TypeScript variables and parameters declared before `line`
+    fn declarations_before_typescript(&self, node: Node, line: usize, names: &mut HashSet<String>) {
+        if node.start_position().row >= line {
+            return;
+        }
+        if let Some(name) = Self::declaration_name(node) {
+            names.insert(self.node_text(&name).to_string());
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.declarations_before_typescript(child, line, names);
+        }
+    }
+
+    /// The identifier a TypeScript variable declarator or parameter declares
+    fn declaration_name(node: Node) -> Option<Node> {
+        let name = match node.kind() {
+            "variable_declarator" => node.child_by_field_name("name"),
+            "required_parameter" | "optional_parameter" => node.child_by_field_name("pattern"),
+            _ => None,
+        }?;
+        (name.kind() == "identifier").then_some(name)
+    }
+
     /// Analyze variables in a node recursively
     fn analyze_variables_in_node(
         &self,
@@ -675,6 +927,21 @@ pub struct OptimizeImportsResult {
     pub grouped: bool,
 }
 
+/// Leading whitespace of a line
+fn indentation(line: &str) -> &str {
+    &line[..line.len() - line.trim_start().len()]
+}
+
+/// Result of extracting code into a new function
+#[derive(Debug, Clone)]
+pub struct ExtractFunctionResult {
+    pub function_name: String,
+    /// Inferred parameters as (name, type) pairs
+    pub parameters: Vec<(String, String)>,
+    pub return_type: Option<String>,
+    pub function_code: String,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -728,4 +995,48 @@ fn bar() {}
         assert!(functions.is_ok());
         assert_eq!(functions.unwrap().len(), 2);
     }
+
+    #[test]
+    fn test_selection_conflict() {
+        let source = r#"fn main() {
+    let x = 1;
+    if x > 0 {
+        println!("{}", x);
+    }
+}"#
+        .to_string();
+        let editor = AstEditor::new(source, tree_sitter_rust::LANGUAGE.into()).unwrap();
+
+        assert!(editor.selection_conflict(1, 4).is_none());
+        assert!(editor.selection_conflict(2, 4).is_none());
+        assert!(editor.selection_conflict(1, 2).is_some());
+        assert!(editor.selection_conflict(0, 5).is_some());
+    }
+
+    #[test]
+    fn test_extract_function_typescript() {
+        let source = r#"function main(items: number[]) {
+    const total = items.length;
+    console.log(total);
+}"#
+        .to_string();
+        let mut editor =
+            AstEditor::new(source, tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into()).unwrap();
+
+        let result = editor.extract_function(1, 2, "report").unwrap();
+        assert_eq!(result.parameters, vec![("items".to_string(), "any".to_string())]);
+        assert!(result.return_type.is_none());
+
+        editor.apply_edits().unwrap();
+        assert!(editor.get_source().contains("    report(items);"));
+        assert!(editor.get_source().contains("function report(items: any) {"));
+    }
+
+    #[test]
+    fn test_symbol_occurrences() {
+        let source = "fn foo() {}\nfn bar() { foo(); }".to_string();
+        let editor = AstEditor::new(source, tree_sitter_rust::LANGUAGE.into()).unwrap();
+        assert_eq!(editor.symbol_occurrences("foo").len(), 2);
+        assert!(editor.symbol_occurrences("baz").is_empty());
+    }
 }
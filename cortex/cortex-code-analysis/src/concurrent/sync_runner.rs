@@ -558,4 +558,40 @@ mod tests {
         let results = runner.run((), files).unwrap();
         assert_eq!(results.get("rs").map(|v| v.len()), Some(2));
     }
+
+    #[test]
+    fn test_parse_python_files() {
+        let temp = TempDir::new().unwrap();
+        let file1 = temp.path().join("models.py");
+        let file2 = temp.path().join("views.py");
+
+        fs::write(&file1, "class User:\n    def save(self):\n        pass\n").unwrap();
+        fs::write(&file2, "import models\n\ndef index():\n    pass\n").unwrap();
+
+        let parsed = Arc::new(Mutex::new(Vec::new()));
+        let parsed_clone = parsed.clone();
+
+        let runner = ConcurrentRunner::new(2, move |path: PathBuf, _: &()| {
+            let source = fs::read_to_string(&path)?;
+            let mut parser = crate::CodeParser::new()?;
+            let file = parser.parse_file_auto(&path.to_string_lossy(), &source)?;
+            parsed_clone.lock().unwrap().push(file);
+            Ok(())
+        });
+
+        let files = FilesData {
+            paths: vec![file1, file2],
+            include: GlobSet::empty(),
+            exclude: GlobSet::empty(),
+        };
+
+        runner.run((), files).unwrap();
+
+        let parsed = parsed.lock().unwrap();
+        let functions: usize = parsed.iter().map(|file| file.functions.len()).sum();
+        let imports: usize = parsed.iter().map(|file| file.imports.len()).sum();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(functions, 2);
+        assert_eq!(imports, 1);
+    }
 }
@@ -4,9 +4,12 @@
 //! - Function calls (CALLS relationship)
 //! - Type usage (USES_TYPE relationship)
 //! - Inheritance (INHERITS relationship)
-//! - Trait implementations (IMPLEMENTS relationship)
+//! - Trait implementations (IMPLEMENTS relationship), including derived traits
 //! - Import statements (IMPORTS relationship)
 //!
+//! Function bodies are re-parsed with the Rust grammar, so for Python files
+//! only import and class inheritance dependencies are extracted.
+//!
 //! # Example
 //!
 //! ```no_run
@@ -38,10 +41,12 @@
 //! # }
 //! ```
 
+use crate::Lang;
 use crate::types::*;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use std::path::Path;
 use tree_sitter::{Node, Parser};
 
 /// Location information for a dependency.
@@ -170,6 +175,13 @@ impl DependencyExtractor {
         // Extract import dependencies
         dependencies.extend(self.extract_import_dependencies(parsed, source)?);
 
+        if Lang::from_path(Path::new(&parsed.path)) == Some(Lang::Python) {
+            for class in &parsed.structs {
+                dependencies.extend(self.extract_base_classes(class));
+            }
+            return Ok(dependencies);
+        }
+
         // Extract function dependencies
         for func in &parsed.functions {
             dependencies.extend(self.extract_from_function(func, source)?);
@@ -291,7 +303,8 @@ impl DependencyExtractor {
 
     /// Extract dependencies from struct.
     fn extract_from_struct(&self, struct_info: &StructInfo) -> Result<Vec<Dependency>> {
-        let mut dependencies = Vec::new();
+        let mut dependencies =
+            self.extract_derives(&struct_info.qualified_name, &struct_info.derives, struct_info.start_line);
 
         // Extract type usage from fields
         for field in &struct_info.fields {
@@ -317,7 +330,8 @@ impl DependencyExtractor {
 
     /// Extract dependencies from enum.
     fn extract_from_enum(&self, enum_info: &EnumInfo) -> Result<Vec<Dependency>> {
-        let mut dependencies = Vec::new();
+        let mut dependencies =
+            self.extract_derives(&enum_info.qualified_name, &enum_info.derives, enum_info.start_line);
 
         // Extract type usage from variant fields
         for variant in &enum_info.variants {
@@ -362,6 +376,50 @@ impl DependencyExtractor {
         Ok(dependencies)
     }
 
+    /// Extract INHERITS dependencies for the base classes of a class.
+    fn extract_base_classes(&self, class: &StructInfo) -> Vec<Dependency> {
+        class
+            .base_classes
+            .iter()
+            .map(|base| {
+                Dependency::new(
+                    class.qualified_name.clone(),
+                    base.clone(),
+                    DependencyType::Inherits,
+                    Location {
+                        file: "".to_string(),
+                        start_line: class.start_line,
+                        end_line: class.start_line,
+                        start_column: 0,
+                        end_column: 0,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    /// Extract IMPLEMENTS dependencies for traits named in `#[derive(...)]`.
+    fn extract_derives(&self, type_name: &str, derives: &[String], line: usize) -> Vec<Dependency> {
+        derives
+            .iter()
+            .map(|trait_name| {
+                Dependency::new(
+                    type_name.to_string(),
+                    trait_name.clone(),
+                    DependencyType::Implements,
+                    Location {
+                        file: "".to_string(),
+                        start_line: line,
+                        end_line: line,
+                        start_column: 0,
+                        end_column: 0,
+                    },
+                )
+                .with_metadata("via".to_string(), "derive".to_string())
+            })
+            .collect()
+    }
+
     /// Extract dependencies from trait.
     fn extract_from_trait(
         &mut self,
@@ -427,9 +485,7 @@ impl DependencyExtractor {
         let mut imports = Vec::new();
 
         for import_str in &parsed.imports {
-            if let Some(import) = self.parse_import_statement(import_str) {
-                imports.push(import);
-            }
+            imports.extend(self.parse_imports(import_str));
         }
 
         Ok(imports)
@@ -445,7 +501,8 @@ impl DependencyExtractor {
         let file_path = &parsed.path;
 
         for import_str in &parsed.imports {
-            if let Some(import) = self.parse_import_statement(import_str) {
+            let separator = if is_python_import(import_str) { "." } else { "::" };
+            for import in self.parse_imports(import_str) {
                 // Create dependency for each imported item
                 if import.items.is_empty() || import.is_glob {
                     // Whole module import
@@ -460,8 +517,11 @@ impl DependencyExtractor {
                     for item in &import.items {
                         let full_path = if import.module.is_empty() {
                             item.clone()
+                        } else if import.module.ends_with('.') {
+                            // Relative import: `from . import item`
+                            format!("{}{}", import.module, item)
                         } else {
-                            format!("{}::{}", import.module, item)
+                            format!("{}{}{}", import.module, separator, item)
                         };
                         dependencies.push(Dependency::new(
                             file_path.clone(),
@@ -477,6 +537,72 @@ impl DependencyExtractor {
         Ok(dependencies)
     }
 
+    /// Parse a Rust use statement or a Python import statement.
+    fn parse_imports(&self, import_str: &str) -> Vec<Import> {
+        if is_python_import(import_str) {
+            self.parse_python_import(import_str)
+        } else {
+            self.parse_import_statement(import_str).into_iter().collect()
+        }
+    }
+
+    /// Parse a Python import statement into one Import per imported module.
+    ///
+    /// `import a.b as c, d` imports the modules `a.b` and `d`;
+    /// `from a import (b as c, d)` imports the items `b` and `d` of `a`.
+    /// Aliases are dropped, as dependencies point at the original names.
+    fn parse_python_import(&self, import_str: &str) -> Vec<Import> {
+        let location = Location {
+            file: "".to_string(),
+            start_line: 0,
+            end_line: 0,
+            start_column: 0,
+            end_column: 0,
+        };
+        let unaliased = |name: &str| name.split(" as ").next().unwrap_or(name).trim().to_string();
+        let statement = import_str
+            .trim()
+            .replace(['(', ')', '\\'], " ")
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        if let Some(modules) = statement.strip_prefix("import ") {
+            return modules
+                .split(',')
+                .map(unaliased)
+                .filter(|module| !module.is_empty())
+                .map(|module| Import {
+                    module,
+                    items: Vec::new(),
+                    is_glob: false,
+                    location: location.clone(),
+                })
+                .collect();
+        }
+
+        let Some((module, items)) = statement
+            .strip_prefix("from ")
+            .and_then(|rest| rest.split_once(" import "))
+        else {
+            return Vec::new();
+        };
+
+        let items: Vec<String> = items
+            .split(',')
+            .map(unaliased)
+            .filter(|item| !item.is_empty())
+            .collect();
+        let is_glob = items.iter().any(|item| item == "*");
+
+        vec![Import {
+            module: module.trim().to_string(),
+            items: if is_glob { Vec::new() } else { items },
+            is_glob,
+            location,
+        }]
+    }
+
     /// Parse a use statement into an Import.
     fn parse_import_statement(&self, import_str: &str) -> Option<Import> {
         let trimmed = import_str.trim();
@@ -709,6 +835,12 @@ impl DependencyExtractor {
     }
 }
 
+/// Whether an import string is a Python import rather than a Rust use statement.
+fn is_python_import(import_str: &str) -> bool {
+    let trimmed = import_str.trim_start();
+    trimmed.starts_with("import ") || trimmed.starts_with("from ")
+}
+
 /// Helper function to extract function name from call expression.
 fn extract_function_name(node: Node, source: &str) -> String {
     match node.kind() {
@@ -949,6 +1081,96 @@ impl MyTrait for MyStruct {
         Ok(())
     }
 
+    #[test]
+    fn test_extract_derived_trait_implementations() -> Result<()> {
+        let source = r#"
+#[derive(Debug, Clone)]
+#[derive(Serialize, Deserialize)]
+struct Config {
+    name: String,
+}
+
+#[derive(PartialEq)]
+enum Mode {
+    Fast,
+}
+"#;
+
+        let mut parser = RustParser::new()?;
+        let parsed = parser.parse_file("test.rs", source)?;
+
+        let mut extractor = DependencyExtractor::new()?;
+        let deps = extractor.extract_all(&parsed, source)?;
+
+        let implemented: Vec<_> = deps
+            .iter()
+            .filter(|d| d.dep_type == DependencyType::Implements)
+            .map(|d| (d.from_unit.as_str(), d.to_unit.as_str()))
+            .collect();
+        assert_eq!(
+            implemented,
+            vec![
+                ("Config", "Debug"),
+                ("Config", "Clone"),
+                ("Config", "Serialize"),
+                ("Config", "Deserialize"),
+                ("Mode", "PartialEq"),
+            ]
+        );
+        assert!(deps
+            .iter()
+            .filter(|d| d.dep_type == DependencyType::Implements)
+            .all(|d| d.metadata.get("via").map(String::as_str) == Some("derive")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_python_dependencies() -> Result<()> {
+        let source = r#"
+import os.path as osp, sys
+from typing import (
+    List as L,
+    Optional,
+)
+from . import sibling
+from pkg.mod import *
+
+class Base:
+    pass
+
+class Child(Base, Mixin):
+    def run(self):
+        return sys.argv
+"#;
+
+        let mut parser = crate::PythonParser::new()?;
+        let parsed = parser.parse_file("app.py", source)?;
+
+        let mut extractor = DependencyExtractor::new()?;
+        let deps = extractor.extract_all(&parsed, source)?;
+
+        let imports: Vec<_> = deps
+            .iter()
+            .filter(|d| d.dep_type == DependencyType::Imports)
+            .map(|d| d.to_unit.as_str())
+            .collect();
+        assert_eq!(
+            imports,
+            vec!["os.path", "sys", "typing.List", "typing.Optional", ".sibling", "pkg.mod"]
+        );
+
+        let inherits: Vec<_> = deps
+            .iter()
+            .filter(|d| d.dep_type == DependencyType::Inherits)
+            .map(|d| (d.from_unit.as_str(), d.to_unit.as_str()))
+            .collect();
+        assert_eq!(inherits, vec![("Child", "Base"), ("Child", "Mixin")]);
+        assert_eq!(deps.len(), imports.len() + inherits.len());
+
+        Ok(())
+    }
+
     #[test]
     fn test_dependency_graph() -> Result<()> {
         let source = r#"
@@ -0,0 +1,536 @@
+//! Extraction of code embedded in non-code host files.
+//!
+//! Markdown documents carry code in fenced blocks, and single-file components
+//! (Vue, Svelte) carry it in `<script>` sections. This module finds those
+//! regions and maps them to a parseable [`Lang`], recording where each one
+//! sits in the host file so that symbols extracted from it can be attributed
+//! back to the right line.
+//!
+//! # Example
+//!
+//! ```
+//! use cortex_code_analysis::embedded::{extract_embedded, HostFormat};
+//! use cortex_code_analysis::Lang;
+//!
+//! let doc = "# Usage\n\n```rust\nfn main() {}\n```\n";
+//! let extraction = extract_embedded(HostFormat::Markdown, doc);
+//!
+//! assert_eq!(extraction.blocks.len(), 1);
+//! assert_eq!(extraction.blocks[0].lang, Lang::Rust);
+//! assert_eq!(extraction.blocks[0].start_line, 4);
+//! ```
+
+use crate::lang::Lang;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// File formats that may host embedded code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HostFormat {
+    /// Markdown document with fenced code blocks
+    Markdown,
+    /// Vue single-file component
+    Vue,
+    /// Svelte component
+    Svelte,
+}
+
+impl HostFormat {
+    /// Detect the host format from a file extension.
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext.to_ascii_lowercase().as_str() {
+            "md" | "markdown" | "mdx" => Some(Self::Markdown),
+            "vue" => Some(Self::Vue),
+            "svelte" => Some(Self::Svelte),
+            _ => None,
+        }
+    }
+
+    /// Detect the host format from a file path.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(Self::from_extension)
+    }
+
+    /// Kind of region this format embeds code in.
+    pub fn region_kind(&self) -> &'static str {
+        match self {
+            Self::Markdown => "fenced_code_block",
+            Self::Vue | Self::Svelte => "script_block",
+        }
+    }
+}
+
+/// A region of embedded code, located within its host file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmbeddedBlock {
+    /// Language the code is parsed as
+    pub lang: Lang,
+
+    /// Language tag as written in the host (fence info string or `lang` attribute)
+    pub tag: String,
+
+    /// Extracted source code
+    pub code: String,
+
+    /// Host line (1-indexed) of the first line of `code`
+    pub start_line: usize,
+
+    /// Host line (1-indexed) of the last line of `code`
+    pub end_line: usize,
+
+    /// Whether this is a Vue `<script setup>` block
+    pub setup: bool,
+}
+
+impl EmbeddedBlock {
+    /// Number of lines to add to a block-relative line to get the host line.
+    pub fn line_offset(&self) -> usize {
+        self.start_line - 1
+    }
+}
+
+/// Why an embedded region was not extracted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    /// The region has no language tag
+    MissingTag,
+    /// The tag does not map to a supported parser
+    UnknownTag,
+}
+
+/// An embedded region that was skipped.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkippedBlock {
+    /// Language tag, if any
+    pub tag: Option<String>,
+
+    /// Host line (1-indexed) where the region opens
+    pub start_line: usize,
+
+    /// Reason the region was skipped
+    pub reason: SkipReason,
+}
+
+/// Embedded regions found in a host file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EmbeddedExtraction {
+    /// Regions with a supported language
+    pub blocks: Vec<EmbeddedBlock>,
+
+    /// Regions with a missing or unknown language tag
+    pub skipped: Vec<SkippedBlock>,
+}
+
+/// Map a fence info string or script `lang` attribute to a parseable language.
+///
+/// Only languages that [`CodeParser`](crate::CodeParser) can extract symbols
+/// from are recognized.
+pub fn lang_from_tag(tag: &str) -> Option<Lang> {
+    match tag.to_ascii_lowercase().as_str() {
+        "rust" | "rs" => Some(Lang::Rust),
+        "typescript" | "ts" | "mts" | "cts" => Some(Lang::TypeScript),
+        "tsx" => Some(Lang::Tsx),
+        "javascript" | "js" | "mjs" | "cjs" | "node" => Some(Lang::JavaScript),
+        "jsx" => Some(Lang::Jsx),
+        "python" | "py" | "python3" => Some(Lang::Python),
+        _ => None,
+    }
+}
+
+/// Extract embedded code regions from a host file.
+pub fn extract_embedded(format: HostFormat, content: &str) -> EmbeddedExtraction {
+    match format {
+        HostFormat::Markdown => extract_fenced_blocks(content),
+        HostFormat::Vue | HostFormat::Svelte => extract_script_blocks(content),
+    }
+}
+
+// ============================================================================
+// Markdown
+// ============================================================================
+
+struct Fence {
+    marker: char,
+    len: usize,
+    indent: usize,
+    info: String,
+    line: usize,
+}
+
+/// Parse a CommonMark opening code fence.
+fn opening_fence(line: &str, line_no: usize) -> Option<Fence> {
+    let trimmed = line.trim_start_matches(' ');
+    let indent = line.len() - trimmed.len();
+    if indent > 3 {
+        return None;
+    }
+
+    let marker = trimmed.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let len = trimmed.chars().take_while(|c| *c == marker).count();
+    if len < 3 {
+        return None;
+    }
+
+    let info = trimmed[len..].trim();
+    // Backtick fences may not have backticks in their info string
+    if marker == '`' && info.contains('`') {
+        return None;
+    }
+
+    Some(Fence {
+        marker,
+        len,
+        indent,
+        info: info.to_string(),
+        line: line_no,
+    })
+}
+
+fn closes_fence(line: &str, fence: &Fence) -> bool {
+    let trimmed = line.trim_start_matches(' ');
+    if line.len() - trimmed.len() > 3 {
+        return false;
+    }
+    let len = trimmed.chars().take_while(|c| *c == fence.marker).count();
+    len >= fence.len && trimmed[len..].trim().is_empty()
+}
+
+/// First word of a fence info string, without attribute syntax such as
+/// `rust,ignore` or `{.python}`.
+fn fence_tag(info: &str) -> Option<&str> {
+    let word = info.split_whitespace().next()?;
+    let word = word.trim_start_matches('{').trim_start_matches('.');
+    let tag = word
+        .split([',', '{', '}'])
+        .next()
+        .unwrap_or_default()
+        .trim();
+    (!tag.is_empty()).then_some(tag)
+}
+
+fn extract_fenced_blocks(content: &str) -> EmbeddedExtraction {
+    let mut extraction = EmbeddedExtraction::default();
+    let mut open: Option<(Fence, Vec<&str>)> = None;
+
+    for (idx, line) in content.lines().enumerate() {
+        let line_no = idx + 1;
+        match open.as_mut() {
+            Some((fence, body)) => {
+                if closes_fence(line, fence) {
+                    let (fence, body) = open.take().unwrap();
+                    push_fenced_block(&mut extraction, fence, body);
+                } else {
+                    // Content lines lose up to the fence's own indentation
+                    let strip = line.len() - line.trim_start_matches(' ').len();
+                    body.push(&line[strip.min(fence.indent)..]);
+                }
+            }
+            None => {
+                if let Some(fence) = opening_fence(line, line_no) {
+                    open = Some((fence, Vec::new()));
+                }
+            }
+        }
+    }
+
+    // An unclosed fence runs to the end of the document
+    if let Some((fence, body)) = open {
+        push_fenced_block(&mut extraction, fence, body);
+    }
+
+    extraction
+}
+
+fn push_fenced_block(extraction: &mut EmbeddedExtraction, fence: Fence, body: Vec<&str>) {
+    let Some(tag) = fence_tag(&fence.info) else {
+        extraction.skipped.push(SkippedBlock {
+            tag: None,
+            start_line: fence.line,
+            reason: SkipReason::MissingTag,
+        });
+        return;
+    };
+
+    let Some(lang) = lang_from_tag(tag) else {
+        extraction.skipped.push(SkippedBlock {
+            tag: Some(tag.to_string()),
+            start_line: fence.line,
+            reason: SkipReason::UnknownTag,
+        });
+        return;
+    };
+
+    let start_line = fence.line + 1;
+    let end_line = start_line + body.len().saturating_sub(1);
+    let mut code = body.join("\n");
+    if !body.is_empty() {
+        code.push('\n');
+    }
+
+    extraction.blocks.push(EmbeddedBlock {
+        lang,
+        tag: tag.to_string(),
+        code,
+        start_line,
+        end_line,
+        setup: false,
+    });
+}
+
+// ============================================================================
+// Single-file components
+// ============================================================================
+
+/// Byte offset of the next `<script` open tag at or after `from`.
+fn find_script_open(content: &str, from: usize) -> Option<usize> {
+    let mut pos = from;
+    while let Some(found) = content[pos..].find("<script") {
+        let start = pos + found;
+        let after = start + "<script".len();
+        match content[after..].chars().next() {
+            Some(c) if c == '>' || c == '/' || c.is_whitespace() => return Some(start),
+            _ => pos = after,
+        }
+    }
+    None
+}
+
+/// Parse the attributes of a `<script ...>` tag into `(name, value)` pairs.
+fn parse_attributes(attrs: &str) -> Vec<(String, Option<String>)> {
+    let mut result = Vec::new();
+    let mut rest = attrs.trim();
+
+    while !rest.is_empty() {
+        let name_end = rest
+            .find(|c: char| c.is_whitespace() || c == '=')
+            .unwrap_or(rest.len());
+        let name = rest[..name_end].to_ascii_lowercase();
+        rest = rest[name_end..].trim_start();
+
+        let value = if let Some(after_eq) = rest.strip_prefix('=') {
+            let after_eq = after_eq.trim_start();
+            let (value, remaining) = match after_eq.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let inner = &after_eq[1..];
+                    let end = inner.find(quote).unwrap_or(inner.len());
+                    (&inner[..end], inner.get(end + 1..).unwrap_or_default())
+                }
+                _ => {
+                    let end = after_eq.find(char::is_whitespace).unwrap_or(after_eq.len());
+                    (&after_eq[..end], &after_eq[end..])
+                }
+            };
+            rest = remaining.trim_start();
+            Some(value.to_string())
+        } else {
+            None
+        };
+
+        if !name.is_empty() {
+            result.push((name, value));
+        }
+    }
+
+    result
+}
+
+fn line_at(content: &str, byte: usize) -> usize {
+    content[..byte].bytes().filter(|b| *b == b'\n').count() + 1
+}
+
+fn extract_script_blocks(content: &str) -> EmbeddedExtraction {
+    let mut extraction = EmbeddedExtraction::default();
+    let mut pos = 0;
+
+    while let Some(open) = find_script_open(content, pos) {
+        let Some(tag_end) = content[open..].find('>').map(|i| open + i) else {
+            break;
+        };
+        let tag_body = &content[open + "<script".len()..tag_end];
+
+        // Self-closing `<script src="..." />` has no inline code
+        if tag_body.trim_end().ends_with('/') {
+            pos = tag_end + 1;
+            continue;
+        }
+
+        let code_start = tag_end + 1;
+        let code_end = content[code_start..]
+            .find("</script")
+            .map(|i| code_start + i)
+            .unwrap_or(content.len());
+        pos = content[code_end..]
+            .find('>')
+            .map(|i| code_end + i + 1)
+            .unwrap_or(content.len());
+
+        let attrs = parse_attributes(tag_body.trim_end_matches('/'));
+        let attr = |name: &str| attrs.iter().find(|(n, _)| n == name);
+        let setup = attr("setup").is_some();
+        let lang_attr = attr("lang").and_then(|(_, v)| v.clone());
+        let start_line = line_at(content, code_start);
+
+        // Scripts without a `lang` attribute are plain JavaScript
+        let (tag, lang) = match &lang_attr {
+            Some(tag) => (tag.clone(), lang_from_tag(tag)),
+            None => ("js".to_string(), Some(Lang::JavaScript)),
+        };
+        let Some(lang) = lang else {
+            extraction.skipped.push(SkippedBlock {
+                tag: Some(tag),
+                start_line: line_at(content, open),
+                reason: SkipReason::UnknownTag,
+            });
+            continue;
+        };
+
+        let code = &content[code_start..code_end];
+        extraction.blocks.push(EmbeddedBlock {
+            lang,
+            tag,
+            code: code.to_string(),
+            start_line,
+            end_line: start_line + code.trim_end().matches('\n').count(),
+            setup,
+        });
+    }
+
+    extraction
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_host_format_detection() {
+        assert_eq!(
+            HostFormat::from_path(Path::new("README.md")),
+            Some(HostFormat::Markdown)
+        );
+        assert_eq!(
+            HostFormat::from_path(Path::new("App.vue")),
+            Some(HostFormat::Vue)
+        );
+        assert_eq!(
+            HostFormat::from_path(Path::new("Nav.svelte")),
+            Some(HostFormat::Svelte)
+        );
+        assert_eq!(HostFormat::from_path(Path::new("main.rs")), None);
+    }
+
+    #[test]
+    fn test_markdown_fences_with_line_offsets() {
+        let doc = "# Title\n\
+                   \n\
+                   ```rust,ignore\n\
+                   fn first() {}\n\
+                   ```\n\
+                   \n\
+                   ~~~ python\n\
+                   def second():\n\
+                   \x20   pass\n\
+                   ~~~\n";
+        let extraction = extract_embedded(HostFormat::Markdown, doc);
+
+        assert_eq!(extraction.blocks.len(), 2);
+        assert!(extraction.skipped.is_empty());
+
+        let rust = &extraction.blocks[0];
+        assert_eq!(rust.lang, Lang::Rust);
+        assert_eq!(rust.tag, "rust");
+        assert_eq!(rust.code, "fn first() {}\n");
+        assert_eq!((rust.start_line, rust.end_line), (4, 4));
+
+        let python = &extraction.blocks[1];
+        assert_eq!(python.lang, Lang::Python);
+        assert_eq!((python.start_line, python.end_line), (8, 9));
+        assert_eq!(python.line_offset(), 7);
+    }
+
+    #[test]
+    fn test_markdown_skips_untagged_and_unknown_blocks() {
+        let doc =
+            "```\nplain text\n```\n\n```haskell\nmain = pure ()\n```\n\n```ts\nlet x = 1;\n```\n";
+        let extraction = extract_embedded(HostFormat::Markdown, doc);
+
+        assert_eq!(extraction.blocks.len(), 1);
+        assert_eq!(extraction.blocks[0].lang, Lang::TypeScript);
+        assert_eq!(
+            extraction.skipped,
+            vec![
+                SkippedBlock {
+                    tag: None,
+                    start_line: 1,
+                    reason: SkipReason::MissingTag
+                },
+                SkippedBlock {
+                    tag: Some("haskell".to_string()),
+                    start_line: 5,
+                    reason: SkipReason::UnknownTag,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_markdown_longer_closing_fence_and_nested_backticks() {
+        let doc = "````md\n```rust\nfn inner() {}\n```\n````\n";
+        let extraction = extract_embedded(HostFormat::Markdown, doc);
+
+        // The outer block is markdown, which is not parsed; the inner fence is its content
+        assert!(extraction.blocks.is_empty());
+        assert_eq!(extraction.skipped.len(), 1);
+        assert_eq!(extraction.skipped[0].tag.as_deref(), Some("md"));
+    }
+
+    #[test]
+    fn test_vue_script_blocks() {
+        let sfc = "<template>\n  <div>{{ msg }}</div>\n</template>\n\n\
+                   <script lang=\"ts\">\nexport default { name: 'App' }\n</script>\n\n\
+                   <script setup lang='ts'>\nconst msg = 'hi'\nfunction greet() {}\n</script>\n\n\
+                   <style scoped>\ndiv { color: red; }\n</style>\n";
+        let extraction = extract_embedded(HostFormat::Vue, sfc);
+
+        assert_eq!(extraction.blocks.len(), 2);
+        let plain = &extraction.blocks[0];
+        assert_eq!(plain.lang, Lang::TypeScript);
+        assert!(!plain.setup);
+        assert_eq!(plain.start_line, 5);
+
+        let setup = &extraction.blocks[1];
+        assert!(setup.setup);
+        assert_eq!(setup.lang, Lang::TypeScript);
+        // Line 1 of the block is the remainder of the `<script setup>` line
+        assert_eq!(setup.start_line, 9);
+        assert_eq!(setup.end_line, 11);
+        assert_eq!(setup.code.lines().nth(2), Some("function greet() {}"));
+    }
+
+    #[test]
+    fn test_svelte_default_javascript_and_unknown_lang() {
+        let component = "<script>\n  let count = 0;\n</script>\n\
+                         <script lang=\"coffee\">\nx = 1\n</script>\n\
+                         <script src=\"./external.js\" />\n\
+                         <button on:click={() => count++}>{count}</button>\n";
+        let extraction = extract_embedded(HostFormat::Svelte, component);
+
+        assert_eq!(extraction.blocks.len(), 1);
+        assert_eq!(extraction.blocks[0].lang, Lang::JavaScript);
+        assert_eq!(extraction.blocks[0].tag, "js");
+        assert_eq!(extraction.skipped.len(), 1);
+        assert_eq!(extraction.skipped[0].start_line, 4);
+    }
+
+    #[test]
+    fn test_lang_from_tag() {
+        assert_eq!(lang_from_tag("RS"), Some(Lang::Rust));
+        assert_eq!(lang_from_tag("tsx"), Some(Lang::Tsx));
+        assert_eq!(lang_from_tag("py"), Some(Lang::Python));
+        assert_eq!(lang_from_tag("bash"), None);
+    }
+}
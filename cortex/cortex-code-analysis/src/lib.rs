@@ -87,8 +87,10 @@ pub mod languages;
 pub mod ast_builder;
 pub mod ast_editor;
 pub mod comment_removal;
+pub mod embedded;
 pub mod extractor;
 pub mod function;
+pub mod python_parser;
 pub mod rust_parser;
 pub mod tree_sitter_wrapper;
 pub mod types;
@@ -133,8 +135,13 @@ pub use languages::{
 
 // Re-export main types
 pub use ast_builder::{build_ast, build_ast_with_config, AstConfig, AstNode, Span};
-pub use ast_editor::{AstEditor, Edit, OptimizeImportsResult, Position, Range};
+pub use ast_editor::{AstEditor, Edit, ExtractFunctionResult, OptimizeImportsResult, Position, Range};
 pub use comment_removal::{extract_comments, remove_comments, CommentSpan};
+pub use embedded::{
+    extract_embedded, lang_from_tag, EmbeddedBlock, EmbeddedExtraction, HostFormat, SkipReason,
+    SkippedBlock,
+};
+pub use python_parser::PythonParser;
 pub use rust_parser::RustParser;
 pub use tree_sitter_wrapper::TreeSitterWrapper;
 pub use types::*;
@@ -156,7 +163,8 @@ pub use spaces::{compute_spaces, FuncSpace, SpaceMetrics};
 // Re-export output module types
 pub use output::{
     dump_node, dump_metrics, dump_ops, export_ast, export_metrics, export_ops,
-    DumpConfig, ExportConfig, ExportMetadata, OutputFormat, Serializable,
+    DumpConfig, ExportConfig, ExportMetadata, OutputFormat, SarifConfig, SarifLevel, SarifLog,
+    Serializable,
 };
 
 // Re-export utility functions
@@ -171,6 +179,7 @@ pub use utils::{
 pub use analysis::{
     // Search and navigation
     AstFinder, FindConfig, FindConfigBuilder, FindResult, NodeFilter,
+    PatternRule, PatternMatch, MetaBinding, StructuralPattern, CompiledRule,
     // Counting and statistics
     AstCounter, ConcurrentCounter, CountConfig, CountFilter, CountStats,
     // AST transformation
@@ -180,8 +189,9 @@ pub use analysis::{
     Rewrite, apply_rewrites,
     AstPattern,
     // Caching
-    Cache, CacheManager, CacheBuilder, AstCache, MetricsCache, SearchCache,
-    CachedAst, CachedMetrics, CachedSearch, SourceKey, SearchKey,
+    Cache, CacheManager, CacheBuilder, CacheBackend, AstCache, MetricsCache, SearchCache,
+    SymbolsCache, CachedAst, CachedMetrics, CachedSearch, CachedSymbols, SourceKey, SearchKey,
+    SharedCache, SharedNamespace,
     // Node analysis
     NodeChecker, DefaultNodeChecker, NodeGetter, DefaultNodeGetter,
     HalsteadType,
@@ -196,7 +206,7 @@ pub use analysis::{
 // Re-export metrics strategy types
 pub use metrics::{
     CodeMetrics,
-    MetricsStrategy, MetricsCalculatorType, MetricsBuilder, MetricsAggregator,
+    MetricsStrategy, MetricsCalculatorType, MetricsBuilder, MetricsAggregator, LanguageRollup,
 };
 
 // Re-export concurrent types
@@ -219,6 +229,7 @@ pub struct CodeParser {
     rust_parser: Option<RustParser>,
     typescript_parser: Option<TypeScriptParser>,
     javascript_parser: Option<TypeScriptParser>,
+    python_parser: Option<PythonParser>,
 }
 
 impl CodeParser {
@@ -228,6 +239,7 @@ impl CodeParser {
             rust_parser: Some(RustParser::new()?),
             typescript_parser: Some(TypeScriptParser::new()?),
             javascript_parser: Some(TypeScriptParser::new_javascript()?),
+            python_parser: Some(PythonParser::new()?),
         })
     }
 
@@ -237,6 +249,7 @@ impl CodeParser {
             rust_parser: None,
             typescript_parser: None,
             javascript_parser: None,
+            python_parser: None,
         };
 
         match language {
@@ -249,6 +262,9 @@ impl CodeParser {
             Lang::JavaScript | Lang::Jsx => {
                 parser.javascript_parser = Some(TypeScriptParser::new_javascript()?);
             }
+            Lang::Python => {
+                parser.python_parser = Some(PythonParser::new()?);
+            }
             _ => {
                 anyhow::bail!("Language {:?} not yet fully supported in CodeParser", language);
             }
@@ -281,6 +297,13 @@ impl CodeParser {
                     .context("JavaScript parser not initialized")?;
                 parser.parse_file(path, source)
             }
+            Lang::Python => {
+                let parser = self
+                    .python_parser
+                    .as_mut()
+                    .context("Python parser not initialized")?;
+                parser.parse_file(path, source)
+            }
             _ => {
                 anyhow::bail!("Language {:?} not yet fully supported in CodeParser", language);
             }
@@ -310,6 +333,20 @@ impl CodeParser {
     pub fn parse_javascript(&mut self, path: &str, source: &str) -> Result<ParsedFile> {
         self.parse_file(path, source, Lang::JavaScript)
     }
+
+    /// Parse a Python file specifically.
+    pub fn parse_python(&mut self, path: &str, source: &str) -> Result<ParsedFile> {
+        self.parse_file(path, source, Lang::Python)
+    }
+
+    /// Parse a block of code embedded in a host file.
+    ///
+    /// Line numbers in the result refer to the host file at `path`.
+    pub fn parse_embedded(&mut self, path: &str, block: &EmbeddedBlock) -> Result<ParsedFile> {
+        let mut parsed = self.parse_file(path, &block.code, block.lang)?;
+        parsed.shift_lines(block.line_offset());
+        Ok(parsed)
+    }
 }
 
 impl Default for CodeParser {
@@ -361,6 +398,32 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_parse_python_auto() {
+        let mut parser = CodeParser::new().unwrap();
+        let source = "import os\n\nclass Greeter:\n    def greet(self):\n        pass\n";
+        let result = parser.parse_file_auto("test.py", source).unwrap();
+        assert_eq!(result.structs.len(), 1);
+        assert_eq!(result.functions[0].qualified_name, "Greeter.greet");
+        assert_eq!(result.imports, vec!["import os"]);
+
+        let mut parser = CodeParser::for_language(Lang::Python).unwrap();
+        assert!(parser.parse_python("test.py", source).is_ok());
+    }
+
+    #[test]
+    fn test_parse_embedded_uses_host_lines() {
+        let mut parser = CodeParser::new().unwrap();
+        let doc = "# Guide\n\n```rust\n/// Adds.\nfn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n```\n";
+        let extraction = extract_embedded(HostFormat::Markdown, doc);
+        let result = parser.parse_embedded("docs/guide.md", &extraction.blocks[0]).unwrap();
+
+        assert_eq!(result.path, "docs/guide.md");
+        assert_eq!(result.functions[0].name, "add");
+        assert_eq!(result.functions[0].start_line, 5);
+        assert_eq!(result.functions[0].end_line, 7);
+    }
+
     #[test]
     fn test_language_specific_parser() {
         let mut parser = CodeParser::for_language(Lang::Rust).unwrap();
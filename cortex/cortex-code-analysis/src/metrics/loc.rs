@@ -69,6 +69,19 @@ impl LocStats {
         self.blank.blank()
     }
 
+    /// Sets the rows spanned by the measured code.
+    ///
+    /// With `exclusive_end` the `end` row is not counted, which is the case
+    /// for a file ending with a newline.
+    pub fn set_span(&mut self, start: usize, end: usize, exclusive_end: bool) {
+        self.sloc = Sloc::new(start, end, exclusive_end);
+    }
+
+    /// Records a row holding code
+    pub fn add_code_line(&mut self, line: usize) {
+        self.ploc.add_line(line);
+    }
+
     /// Merges another LocStats into this one
     pub fn merge(&mut self, other: &LocStats) {
         self.sloc.merge(&other.sloc);
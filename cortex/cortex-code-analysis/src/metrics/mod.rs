@@ -65,7 +65,7 @@ pub use nom::NomStats;
 pub use npa::NpaStats;
 pub use npm::NpmStats;
 pub use strategy::{
-    MetricsStrategy, MetricsCalculatorType, MetricsBuilder, MetricsAggregator,
+    MetricsStrategy, MetricsCalculatorType, MetricsBuilder, MetricsAggregator, LanguageRollup,
 };
 pub use wmc::WmcStats;
 
@@ -5,7 +5,7 @@
 //! - Configurable calculation strategies
 //! - Parallel metrics computation
 //! - Incremental metrics updates
-//! - Custom metric aggregation
+//! - Custom metric aggregation, with per-language rollups
 //!
 //! # Examples
 //!
@@ -23,9 +23,13 @@
 //! # Ok::<(), anyhow::Error>(())
 //! ```
 
+use crate::lang::Lang;
 use crate::metrics::*;
+use crate::spaces::compute_spaces;
 use crate::traits::ParserTrait;
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Enum representing different metrics calculator types
 #[derive(Clone)]
@@ -55,87 +59,24 @@ impl MetricsCalculatorType {
     }
 
     fn calculate_default<P: ParserTrait>(parser: &P, _code: &[u8]) -> Result<CodeMetrics> {
-        let _root = parser.get_root();
-        let mut metrics = CodeMetrics::new();
-
-        // Calculate all metrics
-        metrics.cyclomatic = CyclomaticStats::default();
-        metrics.loc = LocStats::default();
-
-        // Calculate Halstead metrics (use default for now)
-        metrics.halstead = HalsteadStats::default();
-
-        metrics.abc = AbcStats::default();
-        metrics.cognitive = CognitiveStats::default();
-        metrics.exit = ExitStats::default();
-        metrics.nom = NomStats::default();
-        metrics.nargs = NargsStats::default();
-        metrics.npm = NpmStats::default();
-        metrics.npa = NpaStats::default();
-
-        // Compute derived metrics
-        metrics.compute_derived();
-
-        Ok(metrics)
+        let space = compute_spaces(parser.get_root(), parser.get_code(), parser.get_language(), "")?;
+        Ok(space.metrics.into())
     }
 
-    fn calculate_parallel<P: ParserTrait>(parser: &P, _code: &[u8]) -> Result<CodeMetrics> {
-        let _root = parser.get_root();
-        let _lang = parser.get_language();
-
-        // This would need actual parallel execution - simplified for now
-        let mut metrics = CodeMetrics::new();
-        metrics.cyclomatic = CyclomaticStats::default();
-        metrics.loc = LocStats::default();
-
-        // Calculate Halstead metrics (use default for now)
-        metrics.halstead = HalsteadStats::default();
-
-        metrics.abc = AbcStats::default();
-        metrics.cognitive = CognitiveStats::default();
-        metrics.exit = ExitStats::default();
-        metrics.nom = NomStats::default();
-        metrics.nargs = NargsStats::default();
-        metrics.npm = NpmStats::default();
-        metrics.npa = NpaStats::default();
-
-        metrics.compute_derived();
-
-        Ok(metrics)
+    fn calculate_parallel<P: ParserTrait>(parser: &P, code: &[u8]) -> Result<CodeMetrics> {
+        // Spaces are computed in a single traversal, there is nothing to
+        // split within one file
+        Self::calculate_default(parser, code)
     }
 
     fn calculate_incremental<P: ParserTrait>(
         parser: &P,
-        _code: &[u8],
-        base_metrics: &Option<CodeMetrics>,
+        code: &[u8],
+        _base_metrics: &Option<CodeMetrics>,
     ) -> Result<CodeMetrics> {
-        let mut metrics = if let Some(base) = base_metrics {
-            base.clone()
-        } else {
-            CodeMetrics::new()
-        };
-
-        let _root = parser.get_root();
-        let _lang = parser.get_language();
-
-        // Update metrics
-        metrics.cyclomatic = CyclomaticStats::default();
-        metrics.loc = LocStats::default();
-
-        // Calculate Halstead metrics (use default for now)
-        metrics.halstead = HalsteadStats::default();
-
-        metrics.abc = AbcStats::default();
-        metrics.cognitive = CognitiveStats::default();
-        metrics.exit = ExitStats::default();
-        metrics.nom = NomStats::default();
-        metrics.nargs = NargsStats::default();
-        metrics.npm = NpmStats::default();
-        metrics.npa = NpaStats::default();
-
-        metrics.compute_derived();
-
-        Ok(metrics)
+        // Every metric depends on the whole tree, so the file is measured
+        // again rather than patched from the base metrics
+        Self::calculate_default(parser, code)
     }
 }
 
@@ -237,9 +178,70 @@ impl Default for MetricsBuilder {
     }
 }
 
+/// Totals of the metrics of the files of one language
+///
+/// Halstead and maintainability figures cannot be merged across files, so
+/// they are summed from the per-file values. The `*_average` accessors divide
+/// by the number of files, which keeps rollups of languages with different
+/// file counts comparable.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LanguageRollup {
+    /// Number of files
+    pub files: usize,
+    /// Total source lines of code
+    pub sloc: f64,
+    /// Total physical lines of code
+    pub ploc: f64,
+    /// Total cyclomatic complexity
+    pub cyclomatic: f64,
+    /// Total Halstead volume
+    pub halstead_volume: f64,
+    /// Total Halstead effort
+    pub halstead_effort: f64,
+    /// Total maintainability index, Visual Studio variant
+    pub mi_visual_studio: f64,
+}
+
+impl LanguageRollup {
+    /// Adds the metrics of one file
+    pub fn add(&mut self, metrics: &CodeMetrics) {
+        self.files += 1;
+        self.sloc += metrics.loc.sloc();
+        self.ploc += metrics.loc.ploc();
+        self.cyclomatic += metrics.cyclomatic.cyclomatic_sum();
+        self.halstead_volume += metrics.halstead.volume();
+        self.halstead_effort += metrics.halstead.effort();
+        self.mi_visual_studio += metrics.maintainability_index.mi_visual_studio();
+    }
+
+    /// Returns the average cyclomatic complexity per file
+    pub fn cyclomatic_average(&self) -> f64 {
+        self.average(self.cyclomatic)
+    }
+
+    /// Returns the average Halstead volume per file
+    pub fn halstead_volume_average(&self) -> f64 {
+        self.average(self.halstead_volume)
+    }
+
+    /// Returns the average maintainability index per file
+    pub fn mi_average(&self) -> f64 {
+        self.average(self.mi_visual_studio)
+    }
+
+    fn average(&self, total: f64) -> f64 {
+        if self.files == 0 {
+            0.0
+        } else {
+            total / self.files as f64
+        }
+    }
+}
+
 /// Metrics aggregator for combining metrics from multiple sources
 pub struct MetricsAggregator {
     metrics: Vec<CodeMetrics>,
+    by_language: HashMap<Lang, LanguageRollup>,
 }
 
 impl MetricsAggregator {
@@ -247,6 +249,7 @@ impl MetricsAggregator {
     pub fn new() -> Self {
         Self {
             metrics: Vec::new(),
+            by_language: HashMap::new(),
         }
     }
 
@@ -255,6 +258,15 @@ impl MetricsAggregator {
         self.metrics.push(metrics);
     }
 
+    /// Add the metrics of a file written in `lang`
+    ///
+    /// The metrics are aggregated like [`add`](Self::add) and also counted
+    /// in the rollup of their language.
+    pub fn add_for_language(&mut self, lang: Lang, metrics: CodeMetrics) {
+        self.by_language.entry(lang).or_default().add(&metrics);
+        self.metrics.push(metrics);
+    }
+
     /// Aggregate all metrics
     pub fn aggregate(&self) -> CodeMetrics {
         if self.metrics.is_empty() {
@@ -268,9 +280,15 @@ impl MetricsAggregator {
         result
     }
 
+    /// Rollups of the metrics added with [`add_for_language`](Self::add_for_language)
+    pub fn rollup_by_language(&self) -> &HashMap<Lang, LanguageRollup> {
+        &self.by_language
+    }
+
     /// Clear all metrics
     pub fn clear(&mut self) {
         self.metrics.clear();
+        self.by_language.clear();
     }
 }
 
@@ -358,4 +376,34 @@ mod tests {
         assert!(metrics.loc.sloc() > 0.0);
         assert!(metrics.cyclomatic.cyclomatic() > 0.0);
     }
+
+    #[test]
+    fn test_rollup_by_language() {
+        use crate::TypeScriptLanguage;
+
+        let rust = "fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n";
+        let ts = "function add(a: number, b: number): number {\n    return a + b;\n}\n";
+        let rust_parser = Parser::<RustLanguage>::new(rust.as_bytes().to_vec(), Path::new("a.rs")).unwrap();
+        let ts_parser = Parser::<TypeScriptLanguage>::new(ts.as_bytes().to_vec(), Path::new("a.ts")).unwrap();
+
+        let strategy = MetricsStrategy::default();
+        let mut aggregator = MetricsAggregator::new();
+        for _ in 0..2 {
+            aggregator.add_for_language(Lang::Rust, strategy.calculate(&rust_parser, rust.as_bytes()).unwrap());
+        }
+        aggregator.add_for_language(Lang::TypeScript, strategy.calculate(&ts_parser, ts.as_bytes()).unwrap());
+
+        let rollups = aggregator.rollup_by_language();
+        let rust_rollup = &rollups[&Lang::Rust];
+        let ts_rollup = &rollups[&Lang::TypeScript];
+        assert_eq!(rust_rollup.files, 2);
+        assert_eq!(ts_rollup.files, 1);
+        assert_eq!(rust_rollup.sloc, 2.0 * ts_rollup.sloc);
+        assert!(rust_rollup.halstead_volume_average() > 0.0);
+        assert!(ts_rollup.halstead_volume_average() > rust_rollup.halstead_volume_average());
+        assert!(ts_rollup.mi_average() > 0.0);
+
+        aggregator.clear();
+        assert!(aggregator.rollup_by_language().is_empty());
+    }
 }
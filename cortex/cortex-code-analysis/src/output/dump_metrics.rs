@@ -13,6 +13,7 @@ use std::io::{self, Write};
 
 use crate::spaces::{FuncSpace, SpaceMetrics};
 use super::{ExportConfig, ExportMetadata, OutputFormat, serialize_to_format};
+use super::sarif::export_metrics_sarif;
 
 /// Serializable representation of a function space with metrics.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -388,6 +389,9 @@ fn dump_value_inline<W: Write>(
 
 /// Export metrics in the specified format.
 ///
+/// With [`OutputFormat::Sarif`] only the functions exceeding the thresholds
+/// of [`ExportConfig::sarif`] are reported.
+///
 /// # Examples
 ///
 /// ```
@@ -415,6 +419,7 @@ fn dump_value_inline<W: Write>(
 pub fn export_metrics(space: &FuncSpace, config: &ExportConfig) -> Result<String> {
     match config.format {
         OutputFormat::Csv => export_metrics_csv(space),
+        OutputFormat::Sarif => export_metrics_sarif(space, config),
         _ => export_metrics_structured(space, config),
     }
 }
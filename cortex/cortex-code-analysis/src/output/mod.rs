@@ -3,6 +3,7 @@
 //! This module provides comprehensive output capabilities for code analysis results:
 //! - AST dumping with pretty-printing
 //! - Metrics serialization to JSON, YAML, TOML, and CSV
+//! - SARIF 2.1.0 reports of metric threshold violations for CI annotations
 //! - Operations (operands/operators) export
 //! - Streaming output for large files
 //! - Compression support (gzip)
@@ -56,10 +57,12 @@
 pub(crate) mod dump;
 pub(crate) mod dump_metrics;
 pub(crate) mod dump_ops;
+pub(crate) mod sarif;
 
 pub use dump::*;
 pub use dump_metrics::*;
 pub use dump_ops::*;
+pub use sarif::*;
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
@@ -79,6 +82,8 @@ pub enum OutputFormat {
     Csv,
     /// Plain text format (human-readable)
     Text,
+    /// SARIF 2.1.0 (metric violations only)
+    Sarif,
 }
 
 impl Default for OutputFormat {
@@ -96,6 +101,7 @@ impl OutputFormat {
             Self::Toml => "toml",
             Self::Csv => "csv",
             Self::Text => "txt",
+            Self::Sarif => "sarif",
         }
     }
 
@@ -107,6 +113,7 @@ impl OutputFormat {
             Self::Toml => "application/toml",
             Self::Csv => "text/csv",
             Self::Text => "text/plain",
+            Self::Sarif => "application/sarif+json",
         }
     }
 }
@@ -128,6 +135,9 @@ pub struct ExportConfig {
     pub filter_functions: Option<Vec<String>>,
     /// Exclude empty metrics
     pub exclude_empty: bool,
+    /// Thresholds and repository root of the SARIF export
+    #[serde(default)]
+    pub sarif: SarifConfig,
 }
 
 impl Default for ExportConfig {
@@ -140,6 +150,7 @@ impl Default for ExportConfig {
             include_metadata: true,
             filter_functions: None,
             exclude_empty: false,
+            sarif: SarifConfig::default(),
         }
     }
 }
@@ -201,6 +212,9 @@ pub fn serialize_to_format<T: Serialize>(value: &T, config: &ExportConfig) -> Re
         OutputFormat::Text => {
             anyhow::bail!("Text format requires specific handling for each type")
         }
+        OutputFormat::Sarif => {
+            anyhow::bail!("SARIF format is only available for metrics")
+        }
     };
 
     Ok(result)
@@ -234,6 +248,9 @@ pub fn serialize_to_writer<T: Serialize, W: Write>(
         OutputFormat::Text => {
             anyhow::bail!("Text format requires specific handling for each type")
         }
+        OutputFormat::Sarif => {
+            anyhow::bail!("SARIF format is only available for metrics")
+        }
     }
 
     Ok(())
@@ -293,6 +310,7 @@ mod tests {
         assert_eq!(OutputFormat::Toml.extension(), "toml");
         assert_eq!(OutputFormat::Csv.extension(), "csv");
         assert_eq!(OutputFormat::Text.extension(), "txt");
+        assert_eq!(OutputFormat::Sarif.extension(), "sarif");
     }
 
     #[test]
@@ -302,6 +320,7 @@ mod tests {
         assert_eq!(OutputFormat::Toml.mime_type(), "application/toml");
         assert_eq!(OutputFormat::Csv.mime_type(), "text/csv");
         assert_eq!(OutputFormat::Text.mime_type(), "text/plain");
+        assert_eq!(OutputFormat::Sarif.mime_type(), "application/sarif+json");
     }
 
     #[test]
@@ -0,0 +1,563 @@
+//! SARIF 2.1.0 export of metric violations.
+//!
+//! Every function whose cyclomatic complexity, cognitive complexity or
+//! maintainability index crosses the thresholds of [`SarifConfig`] becomes a
+//! SARIF result, so CI systems such as GitHub code scanning can show it as an
+//! annotation on the offending lines.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::ExportConfig;
+use crate::analysis::SpaceKind;
+use crate::spaces::FuncSpace;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+const SRCROOT: &str = "%SRCROOT%";
+
+/// Thresholds and path settings of the SARIF export.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SarifConfig {
+    /// Highest cyclomatic complexity a function may have
+    pub max_cyclomatic: f64,
+    /// Highest cognitive complexity a function may have
+    pub max_cognitive: f64,
+    /// Lowest maintainability index (Visual Studio variant, 0-100) a function may have
+    pub min_maintainability_index: f64,
+    /// Root the file URIs are made relative to
+    ///
+    /// Paths outside of it, or all paths when unset, are emitted as given.
+    pub repo_root: Option<PathBuf>,
+}
+
+impl Default for SarifConfig {
+    fn default() -> Self {
+        Self {
+            max_cyclomatic: 10.0,
+            max_cognitive: 15.0,
+            min_maintainability_index: 20.0,
+            repo_root: None,
+        }
+    }
+}
+
+/// Severity of a SARIF result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SarifLevel {
+    /// Up to 25% past the threshold
+    Note,
+    /// Up to twice the threshold
+    Warning,
+    /// More than twice the threshold
+    Error,
+}
+
+impl SarifLevel {
+    /// Derives the level from how far a value is past its threshold.
+    ///
+    /// `ratio` is the value divided by the threshold for upper bounds, and the
+    /// threshold divided by the value for lower bounds.
+    pub fn from_ratio(ratio: f64) -> Self {
+        if ratio > 2.0 {
+            Self::Error
+        } else if ratio > 1.25 {
+            Self::Warning
+        } else {
+            Self::Note
+        }
+    }
+}
+
+/// Metric checked by the SARIF export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MetricRule {
+    Cyclomatic,
+    Cognitive,
+    MaintainabilityIndex,
+}
+
+impl MetricRule {
+    const ALL: [MetricRule; 3] = [
+        MetricRule::Cyclomatic,
+        MetricRule::Cognitive,
+        MetricRule::MaintainabilityIndex,
+    ];
+
+    fn id(self) -> &'static str {
+        match self {
+            Self::Cyclomatic => "CCA001",
+            Self::Cognitive => "CCA002",
+            Self::MaintainabilityIndex => "CCA003",
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Cyclomatic => "HighCyclomaticComplexity",
+            Self::Cognitive => "HighCognitiveComplexity",
+            Self::MaintainabilityIndex => "LowMaintainabilityIndex",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Cyclomatic => "cyclomatic complexity",
+            Self::Cognitive => "cognitive complexity",
+            Self::MaintainabilityIndex => "maintainability index",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Self::Cyclomatic => "Function has too many independent paths through its code.",
+            Self::Cognitive => {
+                "Function is hard to follow because of nested or interrupted control flow."
+            }
+            Self::MaintainabilityIndex => {
+                "Function combines size and complexity in a way that is hard to maintain."
+            }
+        }
+    }
+
+    fn threshold(self, config: &SarifConfig) -> f64 {
+        match self {
+            Self::Cyclomatic => config.max_cyclomatic,
+            Self::Cognitive => config.max_cognitive,
+            Self::MaintainabilityIndex => config.min_maintainability_index,
+        }
+    }
+
+    fn value(self, space: &FuncSpace) -> f64 {
+        let metrics = &space.metrics;
+        match self {
+            Self::Cyclomatic => metrics.cyclomatic.cyclomatic(),
+            Self::Cognitive => metrics.cognitive.cognitive(),
+            Self::MaintainabilityIndex => metrics.mi.mi_visual_studio(),
+        }
+    }
+
+    /// Returns how far `value` is past `threshold`, or `None` if it is within it
+    fn violation_ratio(self, value: f64, threshold: f64) -> Option<f64> {
+        match self {
+            Self::Cyclomatic | Self::Cognitive => (value > threshold).then(|| {
+                if threshold > 0.0 {
+                    value / threshold
+                } else {
+                    f64::INFINITY
+                }
+            }),
+            Self::MaintainabilityIndex => (value < threshold).then(|| {
+                if value > 0.0 {
+                    threshold / value
+                } else {
+                    f64::INFINITY
+                }
+            }),
+        }
+    }
+}
+
+/// Top-level SARIF log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SarifLog {
+    #[serde(rename = "$schema")]
+    pub schema: String,
+    pub version: String,
+    pub runs: Vec<SarifRun>,
+}
+
+/// A single run of the analysis.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifRun {
+    pub tool: SarifTool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original_uri_base_ids: Option<serde_json::Map<String, serde_json::Value>>,
+    pub results: Vec<SarifResult>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SarifTool {
+    pub driver: SarifDriver,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifDriver {
+    pub name: String,
+    pub version: String,
+    pub rules: Vec<SarifRule>,
+}
+
+/// Metadata of a rule, referenced by its results.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifRule {
+    pub id: String,
+    pub name: String,
+    pub short_description: SarifMessage,
+    pub full_description: SarifMessage,
+    pub default_configuration: SarifRuleConfiguration,
+    pub properties: SarifRuleProperties,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SarifRuleConfiguration {
+    pub level: SarifLevel,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SarifRuleProperties {
+    pub threshold: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SarifMessage {
+    pub text: String,
+}
+
+/// A metric violation of one function.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifResult {
+    pub rule_id: String,
+    pub rule_index: usize,
+    pub level: SarifLevel,
+    pub message: SarifMessage,
+    pub locations: Vec<SarifLocation>,
+    pub properties: SarifResultProperties,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SarifResultProperties {
+    pub value: f64,
+    pub threshold: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifLocation {
+    pub physical_location: SarifPhysicalLocation,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifPhysicalLocation {
+    pub artifact_location: SarifArtifactLocation,
+    pub region: SarifRegion,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifArtifactLocation {
+    pub uri: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uri_base_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifRegion {
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+impl SarifLog {
+    /// Builds the log of the metric violations of the functions in `space`.
+    ///
+    /// `space` is the root returned by [`compute_spaces`](crate::spaces::compute_spaces),
+    /// whose name is the path of the analysed file.
+    pub fn from_space(space: &FuncSpace, config: &SarifConfig) -> Self {
+        let path = space.name.as_deref().unwrap_or_default();
+        let artifact = artifact_location(Path::new(path), config.repo_root.as_deref());
+
+        let mut results = Vec::new();
+        collect_results(space, config, &artifact, &mut results);
+
+        let original_uri_base_ids = config.repo_root.as_ref().map(|root| {
+            let mut ids = serde_json::Map::new();
+            ids.insert(
+                SRCROOT.to_string(),
+                serde_json::json!({ "uri": directory_uri(root) }),
+            );
+            ids
+        });
+
+        Self {
+            schema: SARIF_SCHEMA.to_string(),
+            version: SARIF_VERSION.to_string(),
+            runs: vec![SarifRun {
+                tool: SarifTool {
+                    driver: SarifDriver {
+                        name: env!("CARGO_PKG_NAME").to_string(),
+                        version: env!("CARGO_PKG_VERSION").to_string(),
+                        rules: MetricRule::ALL
+                            .iter()
+                            .map(|rule| sarif_rule(*rule, config))
+                            .collect(),
+                    },
+                },
+                original_uri_base_ids,
+                results,
+            }],
+        }
+    }
+}
+
+fn sarif_rule(rule: MetricRule, config: &SarifConfig) -> SarifRule {
+    let bound = match rule {
+        MetricRule::MaintainabilityIndex => "below",
+        _ => "above",
+    };
+    SarifRule {
+        id: rule.id().to_string(),
+        name: rule.name().to_string(),
+        short_description: SarifMessage {
+            text: format!("Function {} {} {}", rule.label(), bound, rule.threshold(config)),
+        },
+        full_description: SarifMessage {
+            text: rule.description().to_string(),
+        },
+        default_configuration: SarifRuleConfiguration {
+            level: SarifLevel::Warning,
+        },
+        properties: SarifRuleProperties {
+            threshold: rule.threshold(config),
+        },
+    }
+}
+
+fn collect_results(
+    space: &FuncSpace,
+    config: &SarifConfig,
+    artifact: &SarifArtifactLocation,
+    results: &mut Vec<SarifResult>,
+) {
+    if space.kind == SpaceKind::Function {
+        let name = space.name.as_deref().unwrap_or("<anonymous>");
+        for (rule_index, rule) in MetricRule::ALL.iter().enumerate() {
+            let value = rule.value(space);
+            let threshold = rule.threshold(config);
+            let Some(ratio) = rule.violation_ratio(value, threshold) else {
+                continue;
+            };
+
+            results.push(SarifResult {
+                rule_id: rule.id().to_string(),
+                rule_index,
+                level: SarifLevel::from_ratio(ratio),
+                message: SarifMessage {
+                    text: format!(
+                        "Function `{}` has a {} of {:.2} (threshold {})",
+                        name,
+                        rule.label(),
+                        value,
+                        threshold
+                    ),
+                },
+                locations: vec![SarifLocation {
+                    physical_location: SarifPhysicalLocation {
+                        artifact_location: artifact.clone(),
+                        region: SarifRegion {
+                            start_line: space.start_line.max(1),
+                            end_line: space.end_line.max(space.start_line).max(1),
+                        },
+                    },
+                }],
+                properties: SarifResultProperties { value, threshold },
+            });
+        }
+    }
+
+    for child in &space.spaces {
+        collect_results(child, config, artifact, results);
+    }
+}
+
+/// Makes `path` relative to `repo_root` when it lies inside it.
+fn artifact_location(path: &Path, repo_root: Option<&Path>) -> SarifArtifactLocation {
+    if let Some(root) = repo_root
+        && let Ok(relative) = path.strip_prefix(root)
+    {
+        return SarifArtifactLocation {
+            uri: uri_path(relative),
+            uri_base_id: Some(SRCROOT.to_string()),
+        };
+    }
+
+    SarifArtifactLocation {
+        uri: uri_path(path),
+        uri_base_id: None,
+    }
+}
+
+/// Returns `path` with `/` separators, as URIs require on every platform.
+fn uri_path(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// Returns the `file://` URI of a directory, with the trailing slash SARIF
+/// requires for base URIs.
+fn directory_uri(root: &Path) -> String {
+    let path = uri_path(root);
+    let path = path.trim_end_matches('/');
+    if path.starts_with('/') {
+        format!("file://{}/", path)
+    } else {
+        format!("file:///{}/", path)
+    }
+}
+
+/// Export the metric violations of `space` as a SARIF 2.1.0 log.
+pub(crate) fn export_metrics_sarif(space: &FuncSpace, config: &ExportConfig) -> Result<String> {
+    let log = SarifLog::from_space(space, &config.sarif);
+    let sarif = if config.pretty {
+        serde_json::to_string_pretty(&log)?
+    } else {
+        serde_json::to_string(&log)?
+    };
+    Ok(sarif)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::{export_metrics, OutputFormat};
+    use crate::spaces::compute_spaces;
+    use crate::{Lang, Parser, ParserTrait, RustLanguage};
+    use serde_json::Value;
+
+    const CODE: &str = r#"
+fn simple(a: i32) -> i32 {
+    a + 1
+}
+
+fn branchy(x: i32) -> i32 {
+    if x > 0 {
+        if x > 10 {
+            return 1;
+        }
+        return 2;
+    } else if x < -10 {
+        return 3;
+    }
+    match x {
+        0 => 4,
+        1 => 5,
+        _ => 6,
+    }
+}
+"#;
+
+    fn export(path: &str, sarif: SarifConfig) -> Result<Value> {
+        let parser = Parser::<RustLanguage>::new(CODE.as_bytes().to_vec(), Path::new(path))?;
+        let spaces = compute_spaces(parser.get_root(), parser.get_code(), Lang::Rust, path)?;
+        let config = ExportConfig {
+            format: OutputFormat::Sarif,
+            sarif,
+            ..Default::default()
+        };
+        Ok(serde_json::from_str(&export_metrics(&spaces, &config)?)?)
+    }
+
+    /// Checks the constraints of the SARIF 2.1.0 schema the export relies on
+    fn assert_valid_sarif(log: &Value) {
+        assert_eq!(log["version"], "2.1.0");
+        assert!(log["$schema"].as_str().unwrap().starts_with("https://"));
+        let runs = log["runs"].as_array().unwrap();
+        assert!(!runs.is_empty());
+
+        for run in runs {
+            let driver = &run["tool"]["driver"];
+            assert!(driver["name"].is_string());
+            let rules = driver["rules"].as_array().unwrap();
+            for rule in rules {
+                assert!(rule["id"].is_string());
+                assert!(rule["shortDescription"]["text"].is_string());
+            }
+
+            if let Some(bases) = run.get("originalUriBaseIds") {
+                for base in bases.as_object().unwrap().values() {
+                    assert!(base["uri"].as_str().unwrap().ends_with('/'));
+                }
+            }
+
+            for result in run["results"].as_array().unwrap() {
+                assert!(result["message"]["text"].is_string());
+                let index = result["ruleIndex"].as_u64().unwrap() as usize;
+                assert_eq!(rules[index]["id"], result["ruleId"]);
+                assert!(matches!(
+                    result["level"].as_str().unwrap(),
+                    "none" | "note" | "warning" | "error"
+                ));
+
+                for location in result["locations"].as_array().unwrap() {
+                    let physical = &location["physicalLocation"];
+                    assert!(physical["artifactLocation"]["uri"].is_string());
+                    let start = physical["region"]["startLine"].as_u64().unwrap();
+                    let end = physical["region"]["endLine"].as_u64().unwrap();
+                    assert!(start >= 1 && end >= start);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_export_sarif_reports_functions_over_threshold() -> Result<()> {
+        let log = export(
+            "src/lib.rs",
+            SarifConfig {
+                max_cyclomatic: 3.0,
+                max_cognitive: 100.0,
+                min_maintainability_index: 0.0,
+                repo_root: None,
+            },
+        )?;
+        assert_valid_sarif(&log);
+
+        let results = log["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 1);
+        let result = &results[0];
+        assert_eq!(result["ruleId"], "CCA001");
+        assert!(result["message"]["text"].as_str().unwrap().contains("branchy"));
+        assert_eq!(result["locations"][0]["physicalLocation"]["region"]["startLine"], 6);
+        assert_eq!(result["locations"][0]["physicalLocation"]["artifactLocation"]["uri"], "src/lib.rs");
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_sarif_paths_relative_to_repo_root() -> Result<()> {
+        let log = export(
+            "/work/repo/crates/core/src/lib.rs",
+            SarifConfig {
+                max_cyclomatic: 1.0,
+                repo_root: Some(PathBuf::from("/work/repo")),
+                ..Default::default()
+            },
+        )?;
+        assert_valid_sarif(&log);
+
+        let run = &log["runs"][0];
+        assert_eq!(run["originalUriBaseIds"]["%SRCROOT%"]["uri"], "file:///work/repo/");
+        let artifact = &run["results"][0]["locations"][0]["physicalLocation"]["artifactLocation"];
+        assert_eq!(artifact["uri"], "crates/core/src/lib.rs");
+        assert_eq!(artifact["uriBaseId"], "%SRCROOT%");
+        Ok(())
+    }
+
+    #[test]
+    fn test_sarif_level_from_ratio() {
+        assert_eq!(SarifLevel::from_ratio(1.1), SarifLevel::Note);
+        assert_eq!(SarifLevel::from_ratio(1.5), SarifLevel::Warning);
+        assert_eq!(SarifLevel::from_ratio(2.5), SarifLevel::Error);
+        assert_eq!(
+            MetricRule::MaintainabilityIndex.violation_ratio(5.0, 20.0),
+            Some(4.0)
+        );
+        assert_eq!(MetricRule::Cyclomatic.violation_ratio(5.0, 10.0), None);
+    }
+}
@@ -0,0 +1,711 @@
+//! Python-specific parsing using tree-sitter.
+//!
+//! Classes are represented as structs and their methods are also listed
+//! as functions, like the TypeScript parser does. Qualified names use the
+//! Python dotted form (e.g., "Outer.Inner.method"), nested functions are
+//! qualified by their enclosing function, and `@property` methods are also
+//! listed as fields of their class.
+
+use crate::extractor::NodeExtractor;
+use crate::tree_sitter_wrapper::TreeSitterWrapper;
+use crate::types::*;
+use anyhow::{Context, Result};
+use tree_sitter::Node;
+
+/// Decorators that turn a method into a property.
+const PROPERTY_DECORATORS: &[&str] = &["property", "functools.cached_property", "cached_property"];
+
+/// Python parser using tree-sitter.
+pub struct PythonParser {
+    wrapper: TreeSitterWrapper,
+}
+
+impl PythonParser {
+    /// Create a new Python parser.
+    pub fn new() -> Result<Self> {
+        let wrapper = TreeSitterWrapper::new(tree_sitter_python::LANGUAGE.into())?;
+        Ok(Self { wrapper })
+    }
+
+    /// Parse a Python source file.
+    pub fn parse_file(&mut self, path: &str, source: &str) -> Result<ParsedFile> {
+        let tree = self.wrapper.parse(source)?;
+        let root = tree.root_node();
+
+        let mut parsed = ParsedFile::new(path.to_string());
+        self.process_block(root, source, &mut parsed, &[])?;
+
+        Ok(parsed)
+    }
+
+    /// Process the statements of a module, function or compound statement body.
+    fn process_block(
+        &self,
+        block: Node,
+        source: &str,
+        parsed: &mut ParsedFile,
+        scope: &[String],
+    ) -> Result<()> {
+        let mut cursor = block.walk();
+        for child in block.children(&mut cursor) {
+            self.process_statement(child, source, parsed, scope)?;
+        }
+        Ok(())
+    }
+
+    /// Process a single statement.
+    fn process_statement(
+        &self,
+        node: Node,
+        source: &str,
+        parsed: &mut ParsedFile,
+        scope: &[String],
+    ) -> Result<()> {
+        match node.kind() {
+            "function_definition" | "class_definition" => {
+                self.process_definition(node, &[], source, parsed, scope)?;
+            }
+            "decorated_definition" => {
+                if let Some(definition) = node.child_by_field_name("definition") {
+                    let decorators = extract_decorators(node, source);
+                    self.process_definition(definition, &decorators, source, parsed, scope)?;
+                }
+            }
+            "import_statement" | "import_from_statement" | "future_import_statement"
+                if scope.is_empty() =>
+            {
+                parsed.imports.push(node.text(source).to_string());
+            }
+            // Definitions and imports guarded by `if TYPE_CHECKING:`,
+            // `try: ... except ImportError:` and the like
+            "if_statement" | "elif_clause" | "else_clause" | "try_statement" | "except_clause"
+            | "finally_clause" | "with_statement" | "for_statement" | "while_statement"
+            | "block" => {
+                self.process_block(node, source, parsed, scope)?;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Process a function or class definition.
+    fn process_definition(
+        &self,
+        node: Node,
+        decorators: &[String],
+        source: &str,
+        parsed: &mut ParsedFile,
+        scope: &[String],
+    ) -> Result<()> {
+        match node.kind() {
+            "function_definition" => {
+                let func = self.extract_function(node, decorators, source, scope, false)?;
+                let inner_scope = child_scope(scope, &func.name);
+                parsed.functions.push(func);
+
+                // Nested functions and classes
+                if let Some(body) = node.child_by_field_name("body") {
+                    self.process_block(body, source, parsed, &inner_scope)?;
+                }
+            }
+            "class_definition" => {
+                self.extract_class(node, decorators, source, parsed, scope)?;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Extract function information.
+    fn extract_function(
+        &self,
+        node: Node,
+        decorators: &[String],
+        source: &str,
+        scope: &[String],
+        is_method: bool,
+    ) -> Result<FunctionInfo> {
+        let name = node
+            .child_by_field_name("name")
+            .map(|n| n.text(source).to_string())
+            .context("Function missing name")?;
+
+        let is_static = decorators.iter().any(|d| d == "@staticmethod");
+        let parameters = self.extract_parameters(node, source, is_method && !is_static);
+
+        let return_type = node
+            .child_by_field_name("return_type")
+            .map(|rt| rt.text(source).to_string());
+
+        let body_node = node.child_by_field_name("body");
+        let body = body_node
+            .map(|b| b.text(source).to_string())
+            .unwrap_or_default();
+
+        let is_async = node
+            .children(&mut node.walk())
+            .any(|c| c.kind() == "async");
+
+        let generics = node
+            .child_by_field_name("type_parameters")
+            .map(|params| type_parameters(params, source))
+            .unwrap_or_default();
+
+        Ok(FunctionInfo {
+            qualified_name: child_scope(scope, &name).join("."),
+            visibility: python_visibility(&name),
+            name,
+            parameters,
+            return_type,
+            attributes: decorators.to_vec(),
+            parsed_attributes: decorators.iter().filter_map(|d| parse_decorator(d)).collect(),
+            body,
+            start_line: node.start_line(),
+            end_line: node.end_line(),
+            docstring: body_node.and_then(|b| extract_docstring(b, source)),
+            is_async,
+            is_const: false,
+            is_unsafe: false,
+            generics,
+            where_clause: None,
+            complexity: body_node.map(calculate_complexity),
+        })
+    }
+
+    /// Extract function parameters.
+    ///
+    /// With `bound`, the first parameter is the instance or class (`self`,
+    /// `cls`) and is marked as such.
+    fn extract_parameters(&self, node: Node, source: &str, bound: bool) -> Vec<Parameter> {
+        let mut params = Vec::new();
+
+        if let Some(params_node) = node.child_by_field_name("parameters") {
+            let mut cursor = params_node.walk();
+            for child in params_node.children(&mut cursor) {
+                if let Some(mut param) = self.extract_parameter(child, source) {
+                    param.is_self = bound && params.is_empty();
+                    params.push(param);
+                }
+            }
+        }
+
+        params
+    }
+
+    /// Extract a single parameter, None for separators.
+    fn extract_parameter(&self, node: Node, source: &str) -> Option<Parameter> {
+        let field_text = |field: &str| {
+            node.child_by_field_name(field)
+                .map(|n| n.text(source).to_string())
+        };
+
+        let (name, param_type, default_value) = match node.kind() {
+            // `x`, `*args`, `**kwargs`
+            "identifier" | "list_splat_pattern" | "dictionary_splat_pattern" => {
+                (node.text(source).to_string(), None, None)
+            }
+            // `x: int`, `*args: str`
+            "typed_parameter" => (
+                node.named_child(0)?.text(source).to_string(),
+                field_text("type"),
+                None,
+            ),
+            // `x=1`
+            "default_parameter" => (field_text("name")?, None, field_text("value")),
+            // `x: int = 1`
+            "typed_default_parameter" => {
+                (field_text("name")?, field_text("type"), field_text("value"))
+            }
+            _ => return None,
+        };
+
+        Some(Parameter {
+            name,
+            param_type: param_type.unwrap_or_default(),
+            default_value,
+            is_self: false,
+            is_mut: false,
+            is_reference: false,
+        })
+    }
+
+    /// Extract a class as a struct, with its methods listed as functions.
+    fn extract_class(
+        &self,
+        node: Node,
+        decorators: &[String],
+        source: &str,
+        parsed: &mut ParsedFile,
+        scope: &[String],
+    ) -> Result<()> {
+        let name = node
+            .child_by_field_name("name")
+            .map(|n| n.text(source).to_string())
+            .context("Class missing name")?;
+        let class_scope = child_scope(scope, &name);
+
+        // `class Foo(Base, metaclass=Meta)` -> ["Base"]
+        let base_classes = node
+            .child_by_field_name("superclasses")
+            .map(|args| {
+                let mut cursor = args.walk();
+                args.named_children(&mut cursor)
+                    .filter(|arg| !matches!(arg.kind(), "keyword_argument" | "comment"))
+                    .map(|arg| arg.text(source).to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut fields = Vec::new();
+        let body = node.child_by_field_name("body");
+
+        if let Some(body) = body {
+            let mut cursor = body.walk();
+            for child in body.children(&mut cursor) {
+                let (definition, member_decorators) = match child.kind() {
+                    "decorated_definition" => match child.child_by_field_name("definition") {
+                        Some(definition) => (definition, extract_decorators(child, source)),
+                        None => continue,
+                    },
+                    "expression_statement" => {
+                        if let Some(field) = class_attribute(child, source) {
+                            push_field(&mut fields, field);
+                        }
+                        continue;
+                    }
+                    _ => (child, Vec::new()),
+                };
+
+                match definition.kind() {
+                    "function_definition" => {
+                        let method = self.extract_function(
+                            definition,
+                            &member_decorators,
+                            source,
+                            &class_scope,
+                            true,
+                        )?;
+
+                        if member_decorators
+                            .iter()
+                            .any(|d| PROPERTY_DECORATORS.contains(&d.trim_start_matches('@')))
+                        {
+                            push_field(
+                                &mut fields,
+                                Field {
+                                    name: method.name.clone(),
+                                    field_type: method.return_type.clone().unwrap_or_default(),
+                                    visibility: method.visibility,
+                                    attributes: member_decorators.clone(),
+                                    docstring: method.docstring.clone(),
+                                },
+                            );
+                        }
+
+                        if let Some(method_body) = definition.child_by_field_name("body") {
+                            if method.name == "__init__" {
+                                let receiver = method
+                                    .parameters
+                                    .first()
+                                    .filter(|p| p.is_self)
+                                    .map(|p| p.name.clone());
+                                if let Some(receiver) = receiver {
+                                    for field in instance_attributes(method_body, source, &receiver)
+                                    {
+                                        push_field(&mut fields, field);
+                                    }
+                                }
+                            }
+
+                            let method_scope = child_scope(&class_scope, &method.name);
+                            parsed.functions.push(method);
+                            self.process_block(method_body, source, parsed, &method_scope)?;
+                        } else {
+                            parsed.functions.push(method);
+                        }
+                    }
+                    "class_definition" => {
+                        self.extract_class(definition, &member_decorators, source, parsed, &class_scope)?;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        parsed.structs.push(StructInfo {
+            qualified_name: class_scope.join("."),
+            visibility: python_visibility(&name),
+            name,
+            fields,
+            attributes: decorators.to_vec(),
+            derives: Vec::new(),
+            base_classes,
+            parsed_attributes: decorators.iter().filter_map(|d| parse_decorator(d)).collect(),
+            start_line: node.start_line(),
+            end_line: node.end_line(),
+            docstring: body.and_then(|b| extract_docstring(b, source)),
+            generics: node
+                .child_by_field_name("type_parameters")
+                .map(|params| type_parameters(params, source))
+                .unwrap_or_default(),
+            where_clause: None,
+            is_tuple_struct: false,
+            is_unit_struct: false,
+        });
+
+        Ok(())
+    }
+}
+
+impl Default for PythonParser {
+    fn default() -> Self {
+        Self::new().expect("Failed to create PythonParser")
+    }
+}
+
+/// `scope` extended by `name`.
+fn child_scope(scope: &[String], name: &str) -> Vec<String> {
+    let mut path = scope.to_vec();
+    path.push(name.to_string());
+    path
+}
+
+/// Names with a leading underscore are private by convention; dunder
+/// names (`__init__`) are not.
+fn python_visibility(name: &str) -> Visibility {
+    let dunder = name.len() > 4 && name.starts_with("__") && name.ends_with("__");
+    if name.starts_with('_') && !dunder {
+        Visibility::Private
+    } else {
+        Visibility::Public
+    }
+}
+
+/// Decorators of a decorated definition, as written (e.g., "@app.route('/')").
+fn extract_decorators(node: Node, source: &str) -> Vec<String> {
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .filter(|child| child.kind() == "decorator")
+        .map(|decorator| decorator.text(source).trim().to_string())
+        .collect()
+}
+
+/// Parse decorator text such as `@lru_cache(maxsize=None)`.
+fn parse_decorator(text: &str) -> Option<AttributeInfo> {
+    let inner = text.trim().strip_prefix('@')?.trim();
+    let (name, arguments) = match inner.find('(') {
+        Some(i) => (
+            inner[..i].trim(),
+            inner[i + 1..]
+                .trim_end()
+                .strip_suffix(')')
+                .map(|args| args.trim().to_string()),
+        ),
+        None => (inner, None),
+    };
+    if name.is_empty() {
+        return None;
+    }
+
+    Some(AttributeInfo {
+        name: name.to_string(),
+        arguments,
+        kind: AttributeKind::Custom,
+    })
+}
+
+/// Type parameters of a PEP 695 generic (`def f[T](x: T)`).
+fn type_parameters(node: Node, source: &str) -> Vec<String> {
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .map(|param| param.text(source).to_string())
+        .collect()
+}
+
+/// Docstring of a module, class or function body: a string literal as the
+/// first statement.
+fn extract_docstring(body: Node, source: &str) -> Option<String> {
+    let mut cursor = body.walk();
+    let first = body
+        .named_children(&mut cursor)
+        .find(|child| child.kind() != "comment")?;
+    if first.kind() != "expression_statement" {
+        return None;
+    }
+
+    let string = first.named_child(0)?;
+    if string.kind() != "string" {
+        return None;
+    }
+
+    let docstring = clean_docstring(string.text(source));
+    (!docstring.is_empty()).then_some(docstring)
+}
+
+/// Strip the quotes and the common indentation of a docstring literal.
+fn clean_docstring(literal: &str) -> String {
+    let unprefixed = literal.trim_start_matches(|c: char| "rRuU".contains(c));
+    let content = ["\"\"\"", "'''", "\"", "'"]
+        .iter()
+        .find_map(|quote| unprefixed.strip_prefix(quote)?.strip_suffix(quote))
+        .unwrap_or(unprefixed);
+
+    let lines: Vec<&str> = content.lines().collect();
+    let indent = lines
+        .iter()
+        .skip(1)
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            if i == 0 {
+                line.trim()
+            } else {
+                line.get(indent..).unwrap_or("").trim_end()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// Field for a class-level assignment such as `count: int = 0`.
+fn class_attribute(statement: Node, source: &str) -> Option<Field> {
+    let assignment = statement.named_child(0)?;
+    if assignment.kind() != "assignment" {
+        return None;
+    }
+
+    let target = assignment.child_by_field_name("left")?;
+    if target.kind() != "identifier" {
+        return None;
+    }
+
+    Some(assignment_field(assignment, target.text(source), source))
+}
+
+/// Fields for `self.x = ...` assignments in an `__init__` body, not
+/// descending into nested functions, classes or lambdas.
+fn instance_attributes(body: Node, source: &str, receiver: &str) -> Vec<Field> {
+    let mut fields = Vec::new();
+    let mut stack = vec![body];
+
+    while let Some(node) = stack.pop() {
+        match node.kind() {
+            "function_definition" | "class_definition" | "lambda" => continue,
+            "assignment" => {
+                if let Some(target) = node.child_by_field_name("left") {
+                    let object = target.child_by_field_name("object");
+                    let attribute = target.child_by_field_name("attribute");
+                    if let (Some(object), Some(attribute)) = (object, attribute)
+                        && target.kind() == "attribute"
+                        && object.text(source) == receiver
+                    {
+                        fields.push(assignment_field(node, attribute.text(source), source));
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        let mut cursor = node.walk();
+        let children: Vec<_> = node.children(&mut cursor).collect();
+        stack.extend(children.into_iter().rev());
+    }
+
+    fields
+}
+
+/// Field named `name` for an assignment, typed by its annotation if any.
+fn assignment_field(assignment: Node, name: &str, source: &str) -> Field {
+    Field {
+        name: name.to_string(),
+        field_type: assignment
+            .child_by_field_name("type")
+            .map(|t| t.text(source).to_string())
+            .unwrap_or_default(),
+        visibility: python_visibility(name),
+        attributes: Vec::new(),
+        docstring: None,
+    }
+}
+
+/// Add `field` unless a field with the same name is already listed.
+fn push_field(fields: &mut Vec<Field>, field: Field) {
+    if !fields.iter().any(|f| f.name == field.name) {
+        fields.push(field);
+    }
+}
+
+/// Cyclomatic complexity of a Python function body.
+fn calculate_complexity(node: Node) -> u32 {
+    let mut complexity = 1;
+    let mut stack = vec![node];
+
+    while let Some(current) = stack.pop() {
+        match current.kind() {
+            "if_statement" | "elif_clause" | "for_statement" | "while_statement"
+            | "except_clause" | "conditional_expression" | "case_clause" | "if_clause"
+            | "boolean_operator" => {
+                complexity += 1;
+            }
+            _ => {}
+        }
+
+        let mut cursor = current.walk();
+        stack.extend(current.children(&mut cursor));
+    }
+
+    complexity
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_functions() {
+        let source = r#"
+import os
+from typing import List as L, Optional
+
+@lru_cache(maxsize=None)
+def greet(name: str, greeting="Hello", *args, times: int = 1, **kwargs) -> str:
+    """Greet someone.
+
+    Repeats the greeting.
+    """
+    if times > 1 and name:
+        return greeting * times
+    return greeting
+
+async def fetch(url):
+    def parse(body):
+        return body
+    return parse(url)
+"#;
+        let mut parser = PythonParser::new().unwrap();
+        let result = parser.parse_file("test.py", source).unwrap();
+
+        assert_eq!(
+            result.imports,
+            vec!["import os", "from typing import List as L, Optional"]
+        );
+
+        let greet = &result.functions[0];
+        assert_eq!(greet.name, "greet");
+        assert_eq!(greet.return_type.as_deref(), Some("str"));
+        assert_eq!(greet.attributes, vec!["@lru_cache(maxsize=None)"]);
+        assert_eq!(greet.parsed_attributes[0].name, "lru_cache");
+        assert_eq!(
+            greet.parsed_attributes[0].arguments.as_deref(),
+            Some("maxsize=None")
+        );
+        assert_eq!(
+            greet.docstring.as_deref(),
+            Some("Greet someone.\n\nRepeats the greeting.")
+        );
+        assert_eq!(greet.complexity, Some(3));
+
+        let params: Vec<_> = greet
+            .parameters
+            .iter()
+            .map(|p| (p.name.as_str(), p.param_type.as_str(), p.default_value.as_deref()))
+            .collect();
+        assert_eq!(
+            params,
+            vec![
+                ("name", "str", None),
+                ("greeting", "", Some("\"Hello\"")),
+                ("*args", "", None),
+                ("times", "int", Some("1")),
+                ("**kwargs", "", None),
+            ]
+        );
+
+        let fetch = &result.functions[1];
+        assert!(fetch.is_async);
+        assert!(!greet.is_async);
+
+        let parse = &result.functions[2];
+        assert_eq!(parse.qualified_name, "fetch.parse");
+    }
+
+    #[test]
+    fn test_parse_classes() {
+        let source = r#"
+class Base:
+    pass
+
+class Account(Base, metaclass=Meta):
+    """A bank account."""
+
+    currency: str = "EUR"
+
+    def __init__(self, owner, balance: int = 0):
+        self.owner = owner
+        self._balance: int = balance
+
+    @property
+    def balance(self) -> int:
+        return self._balance
+
+    @staticmethod
+    def create(owner):
+        return Account(owner)
+
+    class Meta:
+        ordering = "owner"
+"#;
+        let mut parser = PythonParser::new().unwrap();
+        let result = parser.parse_file("test.py", source).unwrap();
+
+        let account = result.structs.iter().find(|s| s.name == "Account").unwrap();
+        assert_eq!(account.base_classes, vec!["Base"]);
+        assert_eq!(account.docstring.as_deref(), Some("A bank account."));
+
+        let fields: Vec<_> = account
+            .fields
+            .iter()
+            .map(|f| (f.name.as_str(), f.field_type.as_str(), f.visibility))
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("currency", "str", Visibility::Public),
+                ("owner", "", Visibility::Public),
+                ("_balance", "int", Visibility::Private),
+                ("balance", "int", Visibility::Public),
+            ]
+        );
+
+        let methods: Vec<_> = result
+            .functions
+            .iter()
+            .map(|f| f.qualified_name.as_str())
+            .collect();
+        assert_eq!(
+            methods,
+            vec!["Account.__init__", "Account.balance", "Account.create"]
+        );
+
+        let init = &result.functions[0];
+        assert_eq!(init.visibility, Visibility::Public);
+        assert!(init.parameters[0].is_self);
+        assert!(!init.parameters[1].is_self);
+
+        let create = &result.functions[2];
+        assert!(!create.parameters[0].is_self);
+
+        let meta = result.structs.iter().find(|s| s.name == "Meta").unwrap();
+        assert_eq!(meta.qualified_name, "Account.Meta");
+    }
+}
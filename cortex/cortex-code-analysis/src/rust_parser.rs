@@ -34,6 +34,8 @@ impl RustParser {
             self.process_item(child, source, &mut parsed, vec![])?;
         }
 
+        collect_macro_invocations(root, source, &mut Vec::new(), None, &mut parsed.macro_invocations);
+
         Ok(parsed)
     }
 
@@ -105,6 +107,7 @@ impl RustParser {
         let return_type = self.extract_return_type(node, source);
         let visibility = self.extract_visibility(node, source);
         let attributes = extract_attributes(node, source);
+        let parsed_attributes = attributes.iter().filter_map(|a| parse_attribute(a)).collect();
         let docstring = extract_docstring(node, source);
 
         let body = node
@@ -139,6 +142,7 @@ impl RustParser {
             return_type,
             visibility,
             attributes,
+            parsed_attributes,
             body,
             start_line,
             end_line,
@@ -271,12 +275,18 @@ impl RustParser {
             Vec::new()
         };
 
+        let attributes = extract_attributes(node, source);
+        let (derives, parsed_attributes) = split_attributes(&attributes);
+
         Ok(StructInfo {
             name,
             qualified_name,
             fields,
             visibility: self.extract_visibility(node, source),
-            attributes: extract_attributes(node, source),
+            attributes,
+            derives,
+            base_classes: Vec::new(),
+            parsed_attributes,
             start_line: node.start_line(),
             end_line: node.end_line(),
             docstring: extract_docstring(node, source),
@@ -341,12 +351,17 @@ impl RustParser {
             Vec::new()
         };
 
+        let attributes = extract_attributes(node, source);
+        let (derives, parsed_attributes) = split_attributes(&attributes);
+
         Ok(EnumInfo {
             name,
             qualified_name,
             variants,
             visibility: self.extract_visibility(node, source),
-            attributes: extract_attributes(node, source),
+            attributes,
+            derives,
+            parsed_attributes,
             start_line: node.start_line(),
             end_line: node.end_line(),
             docstring: extract_docstring(node, source),
@@ -580,6 +595,136 @@ impl RustParser {
     }
 }
 
+/// Split raw attributes into the traits they derive and the other attributes.
+fn split_attributes(attributes: &[String]) -> (Vec<String>, Vec<AttributeInfo>) {
+    let mut derives = Vec::new();
+    let mut others = Vec::new();
+
+    for attribute in attributes.iter().filter_map(|a| parse_attribute(a)) {
+        if attribute.name == "derive" {
+            // `#[derive(Serialize, Deserialize)]` -> ["Serialize", "Deserialize"]
+            derives.extend(
+                attribute
+                    .arguments
+                    .as_deref()
+                    .unwrap_or_default()
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty())
+                    .map(String::from),
+            );
+        } else {
+            others.push(attribute);
+        }
+    }
+
+    (derives, others)
+}
+
+/// Parse attribute text such as `#[cfg(test)]` or `#![allow(dead_code)]`.
+fn parse_attribute(text: &str) -> Option<AttributeInfo> {
+    let text = text.trim();
+    let inner = text
+        .strip_prefix("#!")
+        .or_else(|| text.strip_prefix('#'))?
+        .trim_start()
+        .strip_prefix('[')?
+        .strip_suffix(']')?
+        .trim();
+
+    let (name, arguments) = match inner.find(['(', '=']) {
+        Some(i) if inner[i..].starts_with('(') => (
+            inner[..i].trim(),
+            inner[i + 1..]
+                .trim_end()
+                .strip_suffix(')')
+                .map(|args| args.trim().to_string()),
+        ),
+        Some(i) => (inner[..i].trim(), Some(inner[i + 1..].trim().to_string())),
+        None => (inner, None),
+    };
+    if name.is_empty() {
+        return None;
+    }
+
+    let kind = match name {
+        "cfg" | "cfg_attr" => AttributeKind::Cfg,
+        "allow" | "warn" | "deny" | "forbid" | "expect" => AttributeKind::Lint,
+        _ => AttributeKind::Custom,
+    };
+
+    Some(AttributeInfo {
+        name: name.to_string(),
+        arguments,
+        kind,
+    })
+}
+
+/// Collect macro invocations under `node`, tracking the innermost named item.
+///
+/// `scope` holds the path used for qualified names, matching the names given
+/// to extracted items (impl methods are qualified by the impl type).
+fn collect_macro_invocations(
+    node: Node,
+    source: &str,
+    scope: &mut Vec<String>,
+    enclosing: Option<&str>,
+    invocations: &mut Vec<MacroInvocation>,
+) {
+    let name = || {
+        node.child_by_field_name("name")
+            .map(|n| n.text(source).to_string())
+    };
+
+    let mut pushed = false;
+    let mut item = None;
+    match node.kind() {
+        "function_item" | "struct_item" | "enum_item" | "union_item" | "const_item"
+        | "static_item" | "mod_item" => {
+            if let Some(name) = name() {
+                scope.push(name);
+                pushed = true;
+                item = Some(scope.join("::"));
+            }
+        }
+        "trait_item" => {
+            // Trait methods are not qualified by the trait name
+            if let Some(name) = name() {
+                let mut path = scope.clone();
+                path.push(name);
+                item = Some(path.join("::"));
+            }
+        }
+        "impl_item" => {
+            if let Some(type_node) = node.child_by_field_name("type") {
+                scope.push(type_node.text(source).to_string());
+                pushed = true;
+            }
+        }
+        "macro_invocation" => {
+            if let Some(macro_node) = node.child_by_field_name("macro") {
+                invocations.push(MacroInvocation {
+                    name: macro_node.text(source).to_string(),
+                    start_line: node.start_line(),
+                    end_line: node.end_line(),
+                    enclosing_item: enclosing.map(String::from),
+                });
+            }
+        }
+        _ => {}
+    }
+
+    let enclosing = item.as_deref().or(enclosing);
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_macro_invocations(child, source, scope, enclosing, invocations);
+    }
+
+    if pushed {
+        scope.pop();
+    }
+}
+
 impl Default for RustParser {
     fn default() -> Self {
         Self::new().expect("Failed to create RustParser")
@@ -699,4 +844,89 @@ trait MultiExtended: Base + Clone {
         assert!(multi.supertraits.contains(&"Base".to_string()));
         assert!(multi.supertraits.contains(&"Clone".to_string()));
     }
+
+    #[test]
+    fn test_parse_derives_and_attributes() {
+        let source = r#"
+#[derive(Debug, Clone)]
+#[derive(Serialize, Deserialize)]
+#[cfg(feature = "serde")]
+#[serde(rename_all = "camelCase")]
+struct Config {
+    name: String,
+}
+
+#[allow(dead_code)]
+#[derive(PartialEq)]
+enum Mode {
+    Fast,
+}
+
+#[tokio::test]
+async fn runs() {}
+"#;
+        let mut parser = RustParser::new().unwrap();
+        let result = parser.parse_file("test.rs", source).unwrap();
+
+        let config = &result.structs[0];
+        assert_eq!(config.derives, vec!["Debug", "Clone", "Serialize", "Deserialize"]);
+        assert_eq!(config.parsed_attributes.len(), 2);
+        assert_eq!(config.parsed_attributes[0].name, "cfg");
+        assert_eq!(config.parsed_attributes[0].kind, AttributeKind::Cfg);
+        assert_eq!(
+            config.parsed_attributes[0].arguments.as_deref(),
+            Some("feature = \"serde\"")
+        );
+        assert_eq!(config.parsed_attributes[1].name, "serde");
+        assert_eq!(config.parsed_attributes[1].kind, AttributeKind::Custom);
+
+        let mode = &result.enums[0];
+        assert_eq!(mode.derives, vec!["PartialEq"]);
+        assert_eq!(mode.parsed_attributes[0].kind, AttributeKind::Lint);
+        assert_eq!(mode.parsed_attributes[0].arguments.as_deref(), Some("dead_code"));
+
+        let runs = &result.functions[0];
+        assert_eq!(runs.parsed_attributes[0].name, "tokio::test");
+        assert_eq!(runs.parsed_attributes[0].arguments, None);
+    }
+
+    #[test]
+    fn test_parse_macro_invocations() {
+        let source = r#"
+lazy_static! {
+    static ref CACHE: u32 = 0;
+}
+
+struct Counter;
+
+impl Counter {
+    fn bump(&self) {
+        tracing::info!("bump");
+        let v = vec![1, 2];
+    }
+}
+
+fn main() {
+    println!("hi");
+}
+"#;
+        let mut parser = RustParser::new().unwrap();
+        let result = parser.parse_file("test.rs", source).unwrap();
+
+        let invocations: Vec<_> = result
+            .macro_invocations
+            .iter()
+            .map(|m| (m.name.as_str(), m.enclosing_item.as_deref(), m.start_line))
+            .collect();
+        assert_eq!(
+            invocations,
+            vec![
+                ("lazy_static", None, 2),
+                ("tracing::info", Some("Counter::bump"), 10),
+                ("vec", Some("Counter::bump"), 11),
+                ("println", Some("main"), 16),
+            ]
+        );
+        assert_eq!(result.macro_invocations[0].end_line, 4);
+    }
 }
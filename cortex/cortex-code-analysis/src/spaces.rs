@@ -45,7 +45,7 @@ use std::fmt;
 use crate::analysis::{DefaultNodeChecker, DefaultNodeGetter, NodeChecker, NodeGetter, SpaceKind};
 use crate::lang::Lang;
 use crate::metrics::{
-    AbcStats, CognitiveStats, CyclomaticStats, ExitStats, HalsteadCollector, HalsteadStats,
+    AbcStats, CodeMetrics, CognitiveStats, CyclomaticStats, ExitStats, HalsteadCollector, HalsteadStats,
     LocStats, MaintainabilityIndexStats, NargsStats, NomStats, NpaStats, NpmStats, WmcStats,
 };
 use crate::node::Node;
@@ -150,6 +150,25 @@ impl SpaceMetrics {
     }
 }
 
+impl From<SpaceMetrics> for CodeMetrics {
+    fn from(metrics: SpaceMetrics) -> Self {
+        Self {
+            cyclomatic: metrics.cyclomatic,
+            loc: metrics.loc,
+            halstead: metrics.halstead,
+            abc: metrics.abc,
+            cognitive: metrics.cognitive,
+            maintainability_index: metrics.mi,
+            exit: metrics.exit,
+            nom: metrics.nom,
+            nargs: metrics.nargs,
+            npm: metrics.npm,
+            npa: metrics.npa,
+            wmc: metrics.wmc,
+        }
+    }
+}
+
 /// A code space representing a function, class, module, or other code unit.
 ///
 /// Code spaces form a hierarchical tree structure where:
@@ -366,8 +385,16 @@ pub fn compute_spaces<'a>(root: Node<'a>, code: &'a [u8], lang: Lang, path: &str
                 (node.start_row() + 1, node.end_row() + 1)
             };
 
+            let mut space = FuncSpace::new(name, start_line, end_line, kind);
+            // A unit ending with a newline ends on the row after its last line
+            let exclusive_end = is_unit && node.end_position().1 == 0;
+            space
+                .metrics
+                .loc
+                .set_span(node.start_row(), node.end_row(), exclusive_end);
+
             let state = State {
-                space: FuncSpace::new(name, start_line, end_line, kind),
+                space,
                 halstead_collector: HalsteadCollector::new(),
             };
             state_stack.push(state);
@@ -379,7 +406,7 @@ pub fn compute_spaces<'a>(root: Node<'a>, code: &'a [u8], lang: Lang, path: &str
 
         // Compute metrics for the current node within the current state
         if let Some(state) = state_stack.last_mut() {
-            compute_node_metrics(&node, code, state, &mut nesting_map, lang, is_func, is_unit);
+            compute_node_metrics(&node, code, state, &mut nesting_map, lang);
         }
 
         // Traverse children
@@ -415,8 +442,6 @@ fn compute_node_metrics<'a>(
     state: &mut State<'a>,
     nesting_map: &mut HashMap<usize, (usize, usize, usize)>,
     lang: Lang,
-    is_func: bool,
-    is_unit: bool,
 ) {
     let metrics = &mut state.space.metrics;
 
@@ -430,7 +455,7 @@ fn compute_node_metrics<'a>(
     compute_halstead_metrics(node, code, &mut state.halstead_collector, lang);
 
     // Lines of code
-    compute_loc_metrics(node, metrics, is_func, is_unit);
+    compute_loc_metrics(node, metrics, lang);
 
     // Number of methods
     compute_nom_metrics(node, metrics, lang);
@@ -815,7 +840,14 @@ fn compute_halstead_metrics<'a>(
 
     match op_type {
         HalsteadType::Operator => {
-            let op_str = DefaultNodeGetter::get_operator_id_as_str(node.kind_id(), lang);
+            // Anonymous tokens are keyed by their kind, which is their text.
+            // Named operators such as primitive types share a kind across
+            // distinct spellings, so they are keyed by their source text.
+            let op_str = if node.is_named() {
+                node.utf8_text(code).unwrap_or_else(|| node.kind())
+            } else {
+                node.kind()
+            };
             collector.add_operator(op_str);
         }
         HalsteadType::Operand => {
@@ -832,12 +864,15 @@ fn compute_halstead_metrics<'a>(
 }
 
 /// Computes lines of code metrics for a node
-fn compute_loc_metrics(node: &Node, _metrics: &mut SpaceMetrics, _is_func: bool, _is_unit: bool) {
-    // LOC metrics are computed differently in cortex
-    // The line counting is handled at finalization
-    // This is intentionally simplified as LOC computation in the original
-    // rust-code-analysis is complex and requires tracking individual lines
-    let _ = node; // Suppress unused warning
+///
+/// Every line holding a token counts as a physical line of code; the line
+/// span of the space itself is recorded when the space is created.
+fn compute_loc_metrics(node: &Node, metrics: &mut SpaceMetrics, lang: Lang) {
+    if node.child_count() == 0 && !DefaultNodeChecker::is_comment(node, lang) {
+        for line in node.start_row()..=node.end_row() {
+            metrics.loc.add_code_line(line);
+        }
+    }
 }
 
 /// Computes number of methods for a node
@@ -947,6 +982,10 @@ fn compute_npm_metrics(node: &Node, metrics: &mut SpaceMetrics, code: &[u8], lan
                 let text = node.utf8_text(code).unwrap_or("");
                 !text.contains("private") && !text.contains("protected")
             }
+            Lang::Python => node
+                .child_by_field_name("name")
+                .and_then(|name| name.utf8_text(code))
+                .is_some_and(is_python_public_name),
             _ => false,
         };
 
@@ -965,6 +1004,15 @@ fn compute_npa_metrics(node: &Node, metrics: &mut SpaceMetrics, code: &[u8], lan
         Lang::TypeScript | Lang::Tsx | Lang::JavaScript | Lang::Jsx => {
             kind == "field_definition" || kind == "public_field_definition"
         }
+        // Class-level assignments: class_definition > block > expression_statement > assignment
+        Lang::Python => {
+            kind == "assignment"
+                && node
+                    .parent()
+                    .and_then(|statement| statement.parent())
+                    .and_then(|block| block.parent())
+                    .is_some_and(|class| class.kind() == "class_definition")
+        }
         _ => false,
     };
 
@@ -979,6 +1027,11 @@ fn compute_npa_metrics(node: &Node, metrics: &mut SpaceMetrics, code: &[u8], lan
                 let text = node.utf8_text(code).unwrap_or("");
                 kind == "public_field_definition" || !text.contains("private")
             }
+            Lang::Python => node
+                .child_by_field_name("left")
+                .filter(|target| target.kind() == "identifier")
+                .and_then(|target| target.utf8_text(code))
+                .is_some_and(is_python_public_name),
             _ => false,
         };
 
@@ -988,6 +1041,12 @@ fn compute_npa_metrics(node: &Node, metrics: &mut SpaceMetrics, code: &[u8], lan
     }
 }
 
+/// Python names are public unless they start with an underscore; dunder
+/// names (`__init__`) are public
+fn is_python_public_name(name: &str) -> bool {
+    !name.starts_with('_') || (name.len() > 4 && name.starts_with("__") && name.ends_with("__"))
+}
+
 /// Finalizes completed states by computing derived metrics and merging into parent
 fn finalize_states<'a>(state_stack: &mut Vec<State<'a>>, diff_level: usize, lang: Lang) -> Result<()> {
     if state_stack.is_empty() {
@@ -1194,6 +1253,44 @@ fn main() {
         Ok(())
     }
 
+    #[test]
+    fn test_compute_spaces_python_class() -> Result<()> {
+        use crate::{ParserTrait, PythonLanguage};
+        use std::path::Path;
+
+        let code = r#"
+class Account:
+    currency = "EUR"
+    _ledger = None
+
+    def __init__(self, owner):
+        self.owner = owner
+
+    def deposit(self, amount):
+        if amount > 0:
+            return amount
+        return 0
+
+    def _audit(self):
+        pass
+"#;
+        let parser = Parser::<PythonLanguage>::new(code.as_bytes().to_vec(), Path::new("test.py"))?;
+        let root = parser.get_root();
+        let spaces = compute_spaces(root, parser.get_code(), Lang::Python, "test.py")?;
+
+        let class = &spaces.spaces[0];
+        assert_eq!(class.name, Some("Account".to_string()));
+        assert_eq!(class.spaces.len(), 3);
+        assert_eq!(spaces.find_all_functions().len(), 3);
+
+        // __init__ and deposit are public, _audit is not
+        assert_eq!(spaces.metrics.npm.npm_sum(), 2.0);
+        // currency is public, _ledger is not
+        assert_eq!(spaces.metrics.npa.npa_sum(), 1.0);
+
+        Ok(())
+    }
+
     #[test]
     fn test_space_metrics_merge() {
         let mut metrics1 = SpaceMetrics::default();
@@ -1325,4 +1422,90 @@ fn boolean_test(a: bool, b: bool, c: bool, d: bool) -> bool {
 
         Ok(())
     }
+
+    /// Returns (unique operators, operators, unique operands, operands)
+    fn halstead_tallies(space: &FuncSpace) -> (f64, f64, f64, f64) {
+        let halstead = &space.metrics.halstead;
+        (
+            halstead.u_operators(),
+            halstead.operators(),
+            halstead.u_operands(),
+            halstead.operands(),
+        )
+    }
+
+    #[test]
+    fn test_halstead_rust_function() -> Result<()> {
+        use crate::{ParserTrait, RustLanguage};
+        use std::path::Path;
+
+        let code = "fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n";
+        let parser = Parser::<RustLanguage>::new(code.as_bytes().to_vec(), Path::new("add.rs"))?;
+        let spaces = compute_spaces(parser.get_root(), parser.get_code(), Lang::Rust, "add.rs")?;
+        let func = &spaces.spaces[0];
+
+        // Operators: fn ( i32 , i32 -> i32 { +
+        // Operands: add a b a b
+        assert_eq!(halstead_tallies(func), (7.0, 9.0, 3.0, 5.0));
+        assert_eq!(func.metrics.loc.sloc(), 3.0);
+        assert!(func.metrics.mi.mi_original() > 0.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_halstead_typescript_function() -> Result<()> {
+        use crate::{ParserTrait, TypeScriptLanguage};
+        use std::path::Path;
+
+        let code = "function add(a: number, b: number): number {\n    return a + b;\n}\n";
+        let parser = Parser::<TypeScriptLanguage>::new(code.as_bytes().to_vec(), Path::new("add.ts"))?;
+        let spaces = compute_spaces(parser.get_root(), parser.get_code(), Lang::TypeScript, "add.ts")?;
+        let func = &spaces.spaces[0];
+
+        // Operators: function ( : number , : number : number { return + ;
+        // Operands: add a b a b
+        assert_eq!(halstead_tallies(func), (9.0, 13.0, 3.0, 5.0));
+        assert_eq!(func.metrics.loc.sloc(), 3.0);
+        assert_eq!(func.metrics.loc.ploc(), 3.0);
+        assert!(func.metrics.mi.mi_original() > 0.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_halstead_javascript_template_and_optional_chain() -> Result<()> {
+        use crate::{JavaScriptLanguage, ParserTrait};
+        use std::path::Path;
+
+        let code = "function greet(user) {\n    return `Hi ${user?.name}!`;\n}\n";
+        let parser = Parser::<JavaScriptLanguage>::new(code.as_bytes().to_vec(), Path::new("greet.js"))?;
+        let spaces = compute_spaces(parser.get_root(), parser.get_code(), Lang::JavaScript, "greet.js")?;
+        let func = &spaces.spaces[0];
+
+        // Operators: function ( { return ${ ?. ;
+        // Operands: greet user "Hi " user name "!"
+        assert_eq!(halstead_tallies(func), (7.0, 7.0, 5.0, 6.0));
+        assert!(func.metrics.mi.mi_original() > 0.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_halstead_jsx_element() -> Result<()> {
+        use crate::{JavaScriptLanguage, ParserTrait};
+        use std::path::Path;
+
+        let code = "function App() {\n    return <div className=\"x\">{count}</div>;\n}\n";
+        let parser = Parser::<JavaScriptLanguage>::new(code.as_bytes().to_vec(), Path::new("app.jsx"))?;
+        let spaces = compute_spaces(parser.get_root(), parser.get_code(), Lang::JavaScript, "app.jsx")?;
+        let func = &spaces.spaces[0];
+
+        // Operators: function ( { return < = { ;
+        // Operands: App div className "x" count div
+        assert_eq!(halstead_tallies(func), (7.0, 8.0, 5.0, 6.0));
+        assert!(func.metrics.mi.mi_original() > 0.0);
+
+        Ok(())
+    }
 }
@@ -24,6 +24,10 @@ pub struct FunctionInfo {
     /// Attributes/annotations (e.g., #[test], #[async])
     pub attributes: Vec<String>,
 
+    /// Attributes split into name and arguments
+    #[serde(default)]
+    pub parsed_attributes: Vec<AttributeInfo>,
+
     /// Function body as text
     pub body: String,
 
@@ -55,6 +59,47 @@ pub struct FunctionInfo {
     pub complexity: Option<u32>,
 }
 
+/// Kind of a parsed attribute.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum AttributeKind {
+    /// Conditional compilation (`#[cfg(...)]`, `#[cfg_attr(...)]`)
+    Cfg,
+    /// Lint level (`#[allow(...)]`, `#[warn(...)]`, `#[deny(...)]`, ...)
+    Lint,
+    /// Any other attribute (e.g., `#[test]`, `#[serde(...)]`)
+    Custom,
+}
+
+/// Represents a parsed attribute such as `#[cfg(test)]`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AttributeInfo {
+    /// Attribute path (e.g., "cfg", "tokio::test")
+    pub name: String,
+
+    /// Text between the parentheses, or after `=` (e.g., "test")
+    pub arguments: Option<String>,
+
+    /// Kind of attribute
+    pub kind: AttributeKind,
+}
+
+/// Represents a macro invocation such as `println!(...)`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MacroInvocation {
+    /// Macro name without the `!` (e.g., "println", "tracing::info")
+    pub name: String,
+
+    /// Starting line number (1-indexed)
+    pub start_line: usize,
+
+    /// Ending line number (1-indexed)
+    pub end_line: usize,
+
+    /// Qualified name of the item containing the invocation, None at file level
+    pub enclosing_item: Option<String>,
+}
+
 /// Represents a function parameter.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Parameter {
@@ -118,6 +163,18 @@ pub struct StructInfo {
     /// Attributes
     pub attributes: Vec<String>,
 
+    /// Traits named in `#[derive(...)]` attributes, one entry per trait
+    #[serde(default)]
+    pub derives: Vec<String>,
+
+    /// Base classes, for languages with class inheritance
+    #[serde(default)]
+    pub base_classes: Vec<String>,
+
+    /// Attributes other than derives, split into name and arguments
+    #[serde(default)]
+    pub parsed_attributes: Vec<AttributeInfo>,
+
     /// Starting line number
     pub start_line: usize,
 
@@ -177,6 +234,14 @@ pub struct EnumInfo {
     /// Attributes
     pub attributes: Vec<String>,
 
+    /// Traits named in `#[derive(...)]` attributes, one entry per trait
+    #[serde(default)]
+    pub derives: Vec<String>,
+
+    /// Attributes other than derives, split into name and arguments
+    #[serde(default)]
+    pub parsed_attributes: Vec<AttributeInfo>,
+
     /// Starting line number
     pub start_line: usize,
 
@@ -347,6 +412,10 @@ pub struct ParsedFile {
     /// Use statements/imports
     pub imports: Vec<String>,
 
+    /// Macro invocations, in source order
+    #[serde(default)]
+    pub macro_invocations: Vec<MacroInvocation>,
+
     /// Additional metadata
     pub metadata: HashMap<String, String>,
 }
@@ -362,7 +431,53 @@ impl ParsedFile {
             impls: Vec::new(),
             modules: Vec::new(),
             imports: Vec::new(),
+            macro_invocations: Vec::new(),
             metadata: HashMap::new(),
         }
     }
+
+    /// Shift every line number by `offset`.
+    ///
+    /// Used when the parsed source is a region of a larger host file, such as
+    /// a fenced block in a markdown document.
+    pub fn shift_lines(&mut self, offset: usize) {
+        fn shift_function(func: &mut FunctionInfo, offset: usize) {
+            func.start_line += offset;
+            func.end_line += offset;
+        }
+
+        for func in &mut self.functions {
+            shift_function(func, offset);
+        }
+        for struct_info in &mut self.structs {
+            struct_info.start_line += offset;
+            struct_info.end_line += offset;
+        }
+        for enum_info in &mut self.enums {
+            enum_info.start_line += offset;
+            enum_info.end_line += offset;
+        }
+        for trait_info in &mut self.traits {
+            trait_info.start_line += offset;
+            trait_info.end_line += offset;
+            for method in &mut trait_info.methods {
+                shift_function(method, offset);
+            }
+        }
+        for impl_info in &mut self.impls {
+            impl_info.start_line += offset;
+            impl_info.end_line += offset;
+            for method in &mut impl_info.methods {
+                shift_function(method, offset);
+            }
+        }
+        for module in &mut self.modules {
+            module.start_line += offset;
+            module.end_line += offset;
+        }
+        for invocation in &mut self.macro_invocations {
+            invocation.start_line += offset;
+            invocation.end_line += offset;
+        }
+    }
 }
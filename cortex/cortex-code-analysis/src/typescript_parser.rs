@@ -52,12 +52,29 @@ impl TypeScriptParser {
                 if let Ok(class) = self.extract_class(node, source) {
                     parsed.structs.push(class);
                 }
+                // Also add the methods to the functions list for easier access
+                if let Some(body) = node.child_by_field_name("body") {
+                    let mut cursor = body.walk();
+                    for member in body
+                        .children(&mut cursor)
+                        .filter(|member| member.kind() == "method_definition")
+                    {
+                        if let Ok(method) = self.extract_function(member, source) {
+                            parsed.functions.push(method);
+                        }
+                    }
+                }
             }
             "interface_declaration" => {
                 if let Ok(interface) = self.extract_interface(node, source) {
                     parsed.traits.push(interface);
                 }
             }
+            "export_statement" => {
+                if let Some(declaration) = node.child_by_field_name("declaration") {
+                    self.process_item(declaration, source, parsed)?;
+                }
+            }
             "import_statement" => {
                 parsed.imports.push(node.text(source).to_string());
             }
@@ -93,6 +110,7 @@ impl TypeScriptParser {
             return_type,
             visibility: Visibility::Public, // TypeScript doesn't have same visibility
             attributes: Vec::new(),
+            parsed_attributes: Vec::new(),
             body,
             start_line: node.start_line(),
             end_line: node.end_line(),
@@ -165,6 +183,9 @@ impl TypeScriptParser {
             fields,
             visibility: Visibility::Public,
             attributes: Vec::new(),
+            derives: Vec::new(),
+            base_classes: Vec::new(),
+            parsed_attributes: Vec::new(),
             start_line: node.start_line(),
             end_line: node.end_line(),
             docstring: None,
@@ -282,4 +303,28 @@ class Person {
         assert_eq!(result.functions.len(), 1);
         assert!(result.functions[0].is_async);
     }
+
+    #[test]
+    fn test_parse_exported_items_and_class_methods() {
+        let source = r#"
+export function greet(name: string): string {
+    return `hello ${name}`;
+}
+
+export class Counter {
+    count: number = 0;
+
+    increment(): void {
+        this.count += 1;
+    }
+}
+"#;
+        let mut parser = TypeScriptParser::new().unwrap();
+        let result = parser.parse_file("test.ts", source).unwrap();
+
+        let names: Vec<&str> = result.functions.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["greet", "increment"]);
+        assert_eq!(result.structs.len(), 1);
+        assert_eq!(result.structs[0].name, "Counter");
+    }
 }
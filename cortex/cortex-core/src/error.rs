@@ -62,10 +62,22 @@ pub enum CortexError {
     #[error("Deadlock detected: {0}")]
     Deadlock(String),
 
+    /// A lock acquisition was aborted to break a wait-for cycle
+    #[error("Deadlock detected: session {victim} aborted to break cycle {cycle:?}")]
+    DeadlockDetected { victim: String, cycle: Vec<String> },
+
+    /// A write targeted a read-only (frozen) workspace
+    #[error("Workspace is read-only: {0}")]
+    WorkspaceReadOnly(String),
+
     /// Semantic search errors
     #[error("Semantic error: {0}")]
     Semantic(String),
 
+    /// The database schema is not compatible with this binary
+    #[error("Incompatible schema: {0}")]
+    IncompatibleSchema(String),
+
     /// Generic internal errors
     #[error("Internal error: {0}")]
     Internal(String),
@@ -139,6 +151,19 @@ impl CortexError {
         Self::Deadlock(msg.into())
     }
 
+    /// Create a deadlock error for a victim chosen to break `cycle`
+    pub fn deadlock_detected(victim: impl Into<String>, cycle: Vec<String>) -> Self {
+        Self::DeadlockDetected {
+            victim: victim.into(),
+            cycle,
+        }
+    }
+
+    /// Create an error for a write to the read-only workspace `workspace_id`
+    pub fn workspace_read_only(workspace_id: impl ToString) -> Self {
+        Self::WorkspaceReadOnly(workspace_id.to_string())
+    }
+
     /// Create a new semantic error
     pub fn semantic(msg: impl Into<String>) -> Self {
         Self::Semantic(msg.into())
@@ -164,6 +189,11 @@ impl CortexError {
         Self::Internal(format!("Migration error: {}", msg.into()))
     }
 
+    /// Create a new schema incompatibility error
+    pub fn incompatible_schema(msg: impl Into<String>) -> Self {
+        Self::IncompatibleSchema(msg.into())
+    }
+
     /// Check if this is a not found error
     pub fn is_not_found(&self) -> bool {
         matches!(self, Self::NotFound { .. })
@@ -178,4 +208,19 @@ impl CortexError {
     pub fn is_database(&self) -> bool {
         matches!(self, Self::Database(_))
     }
+
+    /// Check if this is a write to a read-only workspace
+    pub fn is_workspace_read_only(&self) -> bool {
+        matches!(self, Self::WorkspaceReadOnly(_))
+    }
+
+    /// Check if the database schema is incompatible with this binary
+    pub fn is_incompatible_schema(&self) -> bool {
+        matches!(self, Self::IncompatibleSchema(_))
+    }
+
+    /// Check if this is a deadlock error of either kind
+    pub fn is_deadlock(&self) -> bool {
+        matches!(self, Self::Deadlock(_) | Self::DeadlockDetected { .. })
+    }
 }
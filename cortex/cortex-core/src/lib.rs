@@ -9,12 +9,17 @@ pub mod traits;
 pub mod id;
 pub mod metadata;
 pub mod config;
+pub mod versioning;
 
 pub use error::{CortexError, Result};
 pub use types::*;
 pub use traits::*;
 pub use id::CortexId;
 pub use config::{GlobalConfig, ConfigManager, ConfigProfile, ConfigMetadata};
+pub use versioning::{
+    AppliedMigration, Compatibility, CompatibilityChecker, CompatibilityReport, ComponentStatus,
+    ComponentVersion, ComponentVersionStore, Migration, MigrationRunner,
+};
 
 /// Re-export commonly used types
 pub mod prelude {
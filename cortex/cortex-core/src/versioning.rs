@@ -0,0 +1,542 @@
+//! Schema version compatibility between Cortex components.
+//!
+//! Each component that persists data (storage, VFS, memory) declares a
+//! [`ComponentVersion`] that it bumps whenever its on-disk format changes.
+//! The storage layer records the versions a database has been migrated to in
+//! its `component_versions` table. At startup, [`CompatibilityChecker::verify`]
+//! compares those against the versions the running binary expects, and
+//! [`MigrationRunner`] brings older databases forward by applying registered
+//! [`Migration`]s in component dependency order.
+//!
+//! This module only holds the comparison and ordering logic; reading and
+//! writing stored versions goes through [`ComponentVersionStore`], which the
+//! storage crate implements for its connection manager.
+
+use crate::error::{CortexError, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Schema version a component expects, and the components it builds on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComponentVersion {
+    /// Component name, as recorded in `component_versions`
+    pub name: &'static str,
+
+    /// Current schema version
+    pub version: u32,
+
+    /// Components whose migrations must run before this one's
+    pub depends_on: &'static [&'static str],
+}
+
+impl ComponentVersion {
+    pub const fn new(
+        name: &'static str,
+        version: u32,
+        depends_on: &'static [&'static str],
+    ) -> Self {
+        Self {
+            name,
+            version,
+            depends_on,
+        }
+    }
+}
+
+/// How a component's stored version relates to the expected one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum Compatibility {
+    /// Stored and expected versions match
+    Compatible,
+
+    /// The database is older; `path` lists the versions to step through
+    NeedsMigration {
+        from: Option<u32>,
+        to: u32,
+        path: Vec<u32>,
+    },
+
+    /// The database was written by a newer release
+    TooNew { stored: u32, supported: u32 },
+}
+
+impl Compatibility {
+    /// Compare a stored version (`None` if never recorded) with the expected one.
+    pub fn between(stored: Option<u32>, expected: u32) -> Self {
+        match stored {
+            Some(stored) if stored == expected => Compatibility::Compatible,
+            Some(stored) if stored > expected => Compatibility::TooNew {
+                stored,
+                supported: expected,
+            },
+            from => Compatibility::NeedsMigration {
+                from,
+                to: expected,
+                path: (from.unwrap_or(0) + 1..=expected).collect(),
+            },
+        }
+    }
+}
+
+/// Compatibility of one component.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComponentStatus {
+    pub component: String,
+    pub expected: u32,
+    pub stored: Option<u32>,
+    pub compatibility: Compatibility,
+}
+
+impl fmt::Display for ComponentStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.compatibility {
+            Compatibility::Compatible => {
+                write!(f, "{} is at version {}", self.component, self.expected)
+            }
+            Compatibility::NeedsMigration { from: None, to, .. } => {
+                write!(
+                    f,
+                    "{} has no recorded version (expected {})",
+                    self.component, to
+                )
+            }
+            Compatibility::NeedsMigration {
+                from: Some(from),
+                to,
+                ..
+            } => write!(
+                f,
+                "{} needs migration from version {} to {}",
+                self.component, from, to
+            ),
+            Compatibility::TooNew { stored, supported } => write!(
+                f,
+                "{} is at version {}, newer than this binary supports ({})",
+                self.component, stored, supported
+            ),
+        }
+    }
+}
+
+/// Compatibility of every component, in dependency order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompatibilityReport {
+    pub components: Vec<ComponentStatus>,
+}
+
+impl CompatibilityReport {
+    /// Whether every component matches its expected version.
+    pub fn is_compatible(&self) -> bool {
+        self.components
+            .iter()
+            .all(|status| status.compatibility == Compatibility::Compatible)
+    }
+
+    /// Components whose stored version is older than expected.
+    pub fn needs_migration(&self) -> Vec<&ComponentStatus> {
+        self.components
+            .iter()
+            .filter(|status| matches!(status.compatibility, Compatibility::NeedsMigration { .. }))
+            .collect()
+    }
+
+    /// Components written by a newer release; these cannot be migrated.
+    pub fn too_new(&self) -> Vec<&ComponentStatus> {
+        self.components
+            .iter()
+            .filter(|status| matches!(status.compatibility, Compatibility::TooNew { .. }))
+            .collect()
+    }
+
+    /// One line per component that is not compatible.
+    pub fn summary(&self) -> String {
+        self.components
+            .iter()
+            .filter(|status| status.compatibility != Compatibility::Compatible)
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+/// Where component versions are persisted.
+#[async_trait]
+pub trait ComponentVersionStore: Send + Sync {
+    /// Stored version of each component that has one.
+    async fn load_component_versions(&self) -> Result<HashMap<String, u32>>;
+
+    /// Record that `component` is now at `version`.
+    async fn record_component_version(&self, component: &str, version: u32) -> Result<()>;
+}
+
+/// Compares stored component versions against the versions this binary expects.
+#[derive(Debug, Clone)]
+pub struct CompatibilityChecker {
+    /// Components in dependency order
+    components: Vec<ComponentVersion>,
+}
+
+impl CompatibilityChecker {
+    /// Create a checker for `components`.
+    ///
+    /// Fails if a name is declared twice, a dependency is not among the
+    /// components, or the dependencies form a cycle.
+    pub fn new(components: impl IntoIterator<Item = ComponentVersion>) -> Result<Self> {
+        let components: Vec<_> = components.into_iter().collect();
+
+        for (i, component) in components.iter().enumerate() {
+            if components[..i].iter().any(|c| c.name == component.name) {
+                return Err(CortexError::config(format!(
+                    "component {} is declared twice",
+                    component.name
+                )));
+            }
+            if let Some(missing) = component
+                .depends_on
+                .iter()
+                .find(|dep| !components.iter().any(|c| c.name == **dep))
+            {
+                return Err(CortexError::config(format!(
+                    "component {} depends on undeclared component {}",
+                    component.name, missing
+                )));
+            }
+        }
+
+        // Kahn's algorithm, keeping declaration order among ready components
+        let mut ordered: Vec<ComponentVersion> = Vec::with_capacity(components.len());
+        let mut remaining = components;
+        while !remaining.is_empty() {
+            let Some(index) = remaining.iter().position(|component| {
+                component
+                    .depends_on
+                    .iter()
+                    .all(|dep| ordered.iter().any(|c| c.name == *dep))
+            }) else {
+                let names: Vec<_> = remaining.iter().map(|c| c.name).collect();
+                return Err(CortexError::config(format!(
+                    "component dependencies form a cycle among {}",
+                    names.join(", ")
+                )));
+            };
+            ordered.push(remaining.remove(index));
+        }
+
+        Ok(Self {
+            components: ordered,
+        })
+    }
+
+    /// Declared components, dependencies first.
+    pub fn components(&self) -> &[ComponentVersion] {
+        &self.components
+    }
+
+    /// Build a report from already-loaded stored versions.
+    pub fn evaluate(&self, stored: &HashMap<String, u32>) -> CompatibilityReport {
+        let components = self
+            .components
+            .iter()
+            .map(|component| {
+                let stored = stored.get(component.name).copied();
+                ComponentStatus {
+                    component: component.name.to_string(),
+                    expected: component.version,
+                    stored,
+                    compatibility: Compatibility::between(stored, component.version),
+                }
+            })
+            .collect();
+
+        CompatibilityReport { components }
+    }
+
+    /// Load stored versions from `store` and compare them with the expected ones.
+    pub async fn verify<S>(&self, store: &S) -> Result<CompatibilityReport>
+    where
+        S: ComponentVersionStore + ?Sized,
+    {
+        let stored = store.load_component_versions().await?;
+        Ok(self.evaluate(&stored))
+    }
+}
+
+/// A forward migration of one component's schema.
+///
+/// `C` is whatever the migration needs to act on, typically the storage
+/// connection manager.
+#[async_trait]
+pub trait Migration<C: ?Sized + Sync>: Send + Sync {
+    /// Component this migration belongs to.
+    fn component(&self) -> &str;
+
+    /// Version the component is at once this migration has run.
+    fn target_version(&self) -> u32;
+
+    /// Short human-readable description for logs and reports.
+    fn description(&self) -> String {
+        format!(
+            "migrate {} to version {}",
+            self.component(),
+            self.target_version()
+        )
+    }
+
+    /// Apply the migration. Must be safe to re-run if recording the new
+    /// version afterwards fails.
+    async fn apply(&self, ctx: &C) -> Result<()>;
+}
+
+/// A migration step that was applied.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppliedMigration {
+    pub component: String,
+    pub from: Option<u32>,
+    pub to: u32,
+    pub description: String,
+}
+
+/// Applies registered migrations to bring every component to its expected version.
+pub struct MigrationRunner<C: ?Sized + Sync> {
+    checker: CompatibilityChecker,
+    migrations: Vec<Box<dyn Migration<C>>>,
+}
+
+impl<C: ?Sized + Sync> MigrationRunner<C> {
+    pub fn new(checker: CompatibilityChecker) -> Self {
+        Self {
+            checker,
+            migrations: Vec::new(),
+        }
+    }
+
+    /// Register a forward migration.
+    pub fn register(&mut self, migration: impl Migration<C> + 'static) -> &mut Self {
+        self.migrations.push(Box::new(migration));
+        self
+    }
+
+    /// Migrate every component that is behind, dependencies first.
+    ///
+    /// Steps through each version on the migration path, applying the
+    /// migrations registered for it (versions without one only have their
+    /// number recorded) and recording progress after every step so an
+    /// interrupted run resumes where it stopped. Fails without changing
+    /// anything if any component is newer than expected.
+    pub async fn run<S>(&self, ctx: &C, store: &S) -> Result<Vec<AppliedMigration>>
+    where
+        S: ComponentVersionStore + ?Sized,
+    {
+        let report = self.checker.verify(store).await?;
+
+        let too_new = report.too_new();
+        if !too_new.is_empty() {
+            let reasons: Vec<_> = too_new.iter().map(ToString::to_string).collect();
+            return Err(CortexError::incompatible_schema(reasons.join("; ")));
+        }
+
+        let mut applied = Vec::new();
+        for status in &report.components {
+            let Compatibility::NeedsMigration { from, path, .. } = &status.compatibility else {
+                continue;
+            };
+
+            let mut current = *from;
+            for &version in path {
+                for migration in self
+                    .migrations
+                    .iter()
+                    .filter(|m| m.component() == status.component && m.target_version() == version)
+                {
+                    let description = migration.description();
+                    tracing::info!("Applying migration: {}", description);
+                    migration.apply(ctx).await.map_err(|e| {
+                        CortexError::migration(format!("{} failed: {}", description, e))
+                    })?;
+                    applied.push(AppliedMigration {
+                        component: status.component.clone(),
+                        from: current,
+                        to: version,
+                        description,
+                    });
+                }
+
+                store
+                    .record_component_version(&status.component, version)
+                    .await?;
+                current = Some(version);
+            }
+        }
+
+        Ok(applied)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    const STORAGE: ComponentVersion = ComponentVersion::new("storage", 3, &[]);
+    const VFS: ComponentVersion = ComponentVersion::new("vfs", 2, &["storage", "memory"]);
+    const MEMORY: ComponentVersion = ComponentVersion::new("memory", 1, &["storage"]);
+
+    #[derive(Default)]
+    struct MemoryStore {
+        versions: Mutex<HashMap<String, u32>>,
+    }
+
+    impl MemoryStore {
+        fn with(versions: &[(&str, u32)]) -> Self {
+            let versions = versions.iter().map(|(c, v)| (c.to_string(), *v)).collect();
+            Self {
+                versions: Mutex::new(versions),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl ComponentVersionStore for MemoryStore {
+        async fn load_component_versions(&self) -> Result<HashMap<String, u32>> {
+            Ok(self.versions.lock().unwrap().clone())
+        }
+
+        async fn record_component_version(&self, component: &str, version: u32) -> Result<()> {
+            self.versions
+                .lock()
+                .unwrap()
+                .insert(component.to_string(), version);
+            Ok(())
+        }
+    }
+
+    /// Appends "component@version" to the shared log when applied.
+    struct Step(&'static str, u32);
+
+    #[async_trait]
+    impl Migration<Mutex<Vec<String>>> for Step {
+        fn component(&self) -> &str {
+            self.0
+        }
+
+        fn target_version(&self) -> u32 {
+            self.1
+        }
+
+        async fn apply(&self, log: &Mutex<Vec<String>>) -> Result<()> {
+            log.lock().unwrap().push(format!("{}@{}", self.0, self.1));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_components_sorted_by_dependency() {
+        let checker = CompatibilityChecker::new([VFS, MEMORY, STORAGE]).unwrap();
+        let names: Vec<_> = checker.components().iter().map(|c| c.name).collect();
+        assert_eq!(names, ["storage", "memory", "vfs"]);
+    }
+
+    #[test]
+    fn test_invalid_component_graphs() {
+        assert!(CompatibilityChecker::new([VFS, STORAGE]).is_err());
+        assert!(CompatibilityChecker::new([STORAGE, STORAGE]).is_err());
+
+        const A: ComponentVersion = ComponentVersion::new("a", 1, &["b"]);
+        const B: ComponentVersion = ComponentVersion::new("b", 1, &["a"]);
+        let err = CompatibilityChecker::new([A, B]).unwrap_err();
+        assert!(err.to_string().contains("cycle"));
+    }
+
+    #[test]
+    fn test_compatibility_between() {
+        assert_eq!(
+            Compatibility::between(Some(3), 3),
+            Compatibility::Compatible
+        );
+        assert_eq!(
+            Compatibility::between(Some(4), 3),
+            Compatibility::TooNew {
+                stored: 4,
+                supported: 3
+            }
+        );
+        assert_eq!(
+            Compatibility::between(Some(1), 3),
+            Compatibility::NeedsMigration {
+                from: Some(1),
+                to: 3,
+                path: vec![2, 3]
+            }
+        );
+        assert_eq!(
+            Compatibility::between(None, 2),
+            Compatibility::NeedsMigration {
+                from: None,
+                to: 2,
+                path: vec![1, 2]
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verify_report() {
+        let checker = CompatibilityChecker::new([STORAGE, MEMORY, VFS]).unwrap();
+        let store = MemoryStore::with(&[("storage", 3), ("memory", 2), ("vfs", 1)]);
+
+        let report = checker.verify(&store).await.unwrap();
+        assert!(!report.is_compatible());
+        assert_eq!(report.too_new().len(), 1);
+        assert_eq!(report.too_new()[0].component, "memory");
+        assert_eq!(report.needs_migration()[0].component, "vfs");
+        assert_eq!(
+            report.summary(),
+            "memory is at version 2, newer than this binary supports (1); \
+             vfs needs migration from version 1 to 2"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_runner_applies_in_dependency_order() {
+        let checker = CompatibilityChecker::new([VFS, MEMORY, STORAGE]).unwrap();
+        let mut runner = MigrationRunner::new(checker.clone());
+        runner
+            .register(Step("vfs", 2))
+            .register(Step("storage", 3))
+            .register(Step("storage", 2))
+            .register(Step("memory", 1));
+
+        let store = MemoryStore::with(&[("storage", 1), ("vfs", 1)]);
+        let log = Mutex::new(Vec::new());
+        let applied = runner.run(&log, &store).await.unwrap();
+
+        assert_eq!(
+            *log.lock().unwrap(),
+            ["storage@2", "storage@3", "memory@1", "vfs@2"]
+        );
+        assert_eq!(applied.len(), 4);
+        assert_eq!(applied[0].from, Some(1));
+        assert_eq!(applied[2].from, None);
+        assert!(checker.verify(&store).await.unwrap().is_compatible());
+    }
+
+    #[tokio::test]
+    async fn test_runner_refuses_newer_database() {
+        let checker = CompatibilityChecker::new([STORAGE, MEMORY]).unwrap();
+        let mut runner = MigrationRunner::new(checker);
+        runner.register(Step("memory", 1));
+
+        let store = MemoryStore::with(&[("storage", 4)]);
+        let log = Mutex::new(Vec::new());
+        let err = runner.run(&log, &store).await.unwrap_err();
+
+        assert!(err.is_incompatible_schema());
+        assert!(log.lock().unwrap().is_empty());
+        assert_eq!(
+            store.load_component_versions().await.unwrap().get("memory"),
+            None
+        );
+    }
+}
@@ -7,8 +7,11 @@
 //! - Size-based chunking with overlap
 //! - Token-based chunking (max 512 tokens per chunk)
 
+use crate::processors::{ChunkType, ContentChunk};
+use cortex_code_analysis::{CodeParser, Lang, ParsedFile};
 use cortex_core::traits::Chunker as ChunkerTrait;
 use regex::Regex;
+use std::path::Path;
 
 /// Approximate token count (rough estimate: 1 token ~= 4 characters)
 pub fn estimate_tokens(text: &str) -> usize {
@@ -527,6 +530,442 @@ impl ChunkerTrait for CodeChunker {
     }
 }
 
+/// Lines of the previous piece repeated at the start of the next one when a
+/// function is too large for a single chunk
+const CODE_CONTEXT_LINES: usize = 3;
+
+/// A symbol of a parsed source file, by 1-indexed inclusive line range
+#[derive(Debug, Clone)]
+struct SymbolSpan {
+    name: String,
+    kind: &'static str,
+    start_line: usize,
+    end_line: usize,
+}
+
+impl SymbolSpan {
+    /// Whether `other` lies strictly inside this symbol
+    fn contains(&self, other: &SymbolSpan) -> bool {
+        other.start_line >= self.start_line
+            && other.end_line <= self.end_line
+            && (other.start_line, other.end_line) != (self.start_line, self.end_line)
+    }
+
+    fn is_container(&self) -> bool {
+        matches!(self.kind, "impl" | "trait" | "class" | "interface")
+    }
+}
+
+/// A line range of a source file that becomes one chunk
+#[derive(Debug, Clone)]
+struct CodePiece {
+    start_line: usize,
+    end_line: usize,
+    symbol: Option<String>,
+    kind: &'static str,
+    /// Index of the piece when a function was split
+    part: Option<usize>,
+}
+
+/// Line index of a source file
+struct SourceLines<'a> {
+    content: &'a str,
+    /// Byte offset where each line starts
+    starts: Vec<usize>,
+}
+
+impl<'a> SourceLines<'a> {
+    fn new(content: &'a str) -> Self {
+        let mut starts = vec![0];
+        for (i, byte) in content.bytes().enumerate() {
+            if byte == b'\n' && i + 1 < content.len() {
+                starts.push(i + 1);
+            }
+        }
+        Self { content, starts }
+    }
+
+    fn count(&self) -> usize {
+        if self.content.is_empty() {
+            0
+        } else {
+            self.starts.len()
+        }
+    }
+
+    /// Byte range of lines `from..=to`, without the final line break
+    fn byte_range(&self, from: usize, to: usize) -> (usize, usize) {
+        let start = self.starts[from - 1];
+        let end = match self.starts.get(to) {
+            Some(&next) => next - 1,
+            None => self.content.len(),
+        };
+        let text = self.content[start..end].trim_end_matches(['\n', '\r']);
+        (start, start + text.len())
+    }
+
+    fn text(&self, from: usize, to: usize) -> &'a str {
+        let (start, end) = self.byte_range(from, to);
+        &self.content[start..end]
+    }
+
+    fn line(&self, line: usize) -> &'a str {
+        self.text(line, line)
+    }
+
+    fn is_blank(&self, line: usize) -> bool {
+        self.line(line).trim().is_empty()
+    }
+
+    fn size(&self, from: usize, to: usize) -> usize {
+        self.text(from, to).chars().count()
+    }
+}
+
+impl CodeChunker {
+    /// Chunk a source file along the boundaries of its functions, types and
+    /// impl blocks, attaching the enclosing symbol to each chunk.
+    ///
+    /// Each chunk holds one top-level item along with the doc comments and
+    /// attributes right above it. Items larger than the chunk size are split
+    /// into their methods, and functions larger than the chunk size are split
+    /// at statement boundaries, each piece repeating the last lines of the
+    /// previous one. Languages without a parser in `cortex-code-analysis`, and
+    /// files that fail to parse, fall back to block-based chunking.
+    ///
+    /// Chunk metadata: `language`, `symbol` (absent at module level),
+    /// `symbol_kind`, `start_line` and `end_line` (1-indexed, inclusive), and
+    /// `part` for pieces of a split function.
+    pub fn chunk_source(&self, path: &Path, content: &str) -> Vec<ContentChunk> {
+        let language = crate::extractor::detect_programming_language(path)
+            .map(|language| language.to_lowercase());
+
+        let parsed = Lang::from_path(path).and_then(|lang| {
+            let mut parser = CodeParser::for_language(lang).ok()?;
+            match parser.parse_file(&path.to_string_lossy(), content, lang) {
+                Ok(parsed) => Some((parsed, lang)),
+                Err(e) => {
+                    tracing::debug!(
+                        "Failed to parse {}, chunking by blocks: {}",
+                        path.display(),
+                        e
+                    );
+                    None
+                }
+            }
+        });
+
+        let Some((parsed, lang)) = parsed else {
+            return self
+                .chunk_code(content)
+                .into_iter()
+                .filter(|chunk| !chunk.trim().is_empty())
+                .map(|chunk| {
+                    let chunk = ContentChunk::new(chunk, ChunkType::CodeBlock);
+                    match &language {
+                        Some(language) => {
+                            chunk.with_metadata("language".to_string(), serde_json::json!(language))
+                        }
+                        None => chunk,
+                    }
+                })
+                .collect();
+        };
+
+        let lines = SourceLines::new(content);
+        if lines.count() == 0 {
+            return Vec::new();
+        }
+
+        let spans = Self::symbol_spans(&parsed, lang);
+        let mut pieces = Vec::new();
+        self.chunk_region(&lines, 1, lines.count(), &spans, None, &mut pieces);
+
+        pieces
+            .into_iter()
+            .map(|piece| {
+                let (start, end) = lines.byte_range(piece.start_line, piece.end_line);
+                let mut chunk = ContentChunk::with_offsets(
+                    content[start..end].to_string(),
+                    ChunkType::CodeBlock,
+                    start,
+                    end,
+                )
+                .with_metadata("symbol_kind".to_string(), serde_json::json!(piece.kind))
+                .with_metadata(
+                    "start_line".to_string(),
+                    serde_json::json!(piece.start_line),
+                )
+                .with_metadata("end_line".to_string(), serde_json::json!(piece.end_line));
+                if let Some(language) = &language {
+                    chunk =
+                        chunk.with_metadata("language".to_string(), serde_json::json!(language));
+                }
+                if let Some(symbol) = piece.symbol {
+                    chunk = chunk.with_metadata("symbol".to_string(), serde_json::json!(symbol));
+                }
+                if let Some(part) = piece.part {
+                    chunk = chunk.with_metadata("part".to_string(), serde_json::json!(part));
+                }
+                chunk
+            })
+            .collect()
+    }
+
+    /// All symbols of a parsed file; impl methods and class methods are also
+    /// listed as functions, nested in their container
+    fn symbol_spans(parsed: &ParsedFile, lang: Lang) -> Vec<SymbolSpan> {
+        let is_rust = lang == Lang::Rust;
+        let span = |name: &str, kind, start_line, end_line| SymbolSpan {
+            name: name.to_string(),
+            kind,
+            start_line,
+            end_line,
+        };
+
+        let mut spans = Vec::new();
+        for f in &parsed.functions {
+            spans.push(span(&f.name, "function", f.start_line, f.end_line));
+        }
+        for s in &parsed.structs {
+            let kind = if is_rust { "struct" } else { "class" };
+            spans.push(span(&s.name, kind, s.start_line, s.end_line));
+        }
+        for e in &parsed.enums {
+            spans.push(span(&e.name, "enum", e.start_line, e.end_line));
+        }
+        for t in &parsed.traits {
+            let kind = if is_rust { "trait" } else { "interface" };
+            spans.push(span(&t.name, kind, t.start_line, t.end_line));
+        }
+        for i in &parsed.impls {
+            spans.push(span(&i.type_name, "impl", i.start_line, i.end_line));
+        }
+        for m in &parsed.modules {
+            spans.push(span(&m.name, "module", m.start_line, m.end_line));
+        }
+        spans
+    }
+
+    /// Chunk lines `from..=to`: every outermost symbol in the range becomes
+    /// its own chunk, and the lines between them are grouped under `owner`
+    fn chunk_region(
+        &self,
+        lines: &SourceLines<'_>,
+        from: usize,
+        to: usize,
+        spans: &[SymbolSpan],
+        owner: Option<&SymbolSpan>,
+        pieces: &mut Vec<CodePiece>,
+    ) {
+        let mut candidates: Vec<&SymbolSpan> = spans
+            .iter()
+            .filter(|s| s.start_line >= from && s.end_line <= to)
+            .filter(|s| owner.is_none_or(|owner| owner.contains(s)))
+            .collect();
+        candidates.sort_by_key(|s| (s.start_line, std::cmp::Reverse(s.end_line)));
+
+        let mut units: Vec<&SymbolSpan> = Vec::new();
+        for span in candidates {
+            if units
+                .last()
+                .is_none_or(|last| span.start_line > last.end_line)
+            {
+                units.push(span);
+            }
+        }
+
+        let mut cursor = from;
+        for unit in units {
+            // Doc comments and attributes directly above belong to the item
+            let mut attach = unit.start_line;
+            while attach > cursor && !lines.is_blank(attach - 1) {
+                attach -= 1;
+            }
+            if cursor < attach {
+                self.push_loose(lines, cursor, attach - 1, owner, pieces);
+            }
+            self.chunk_unit(lines, attach, unit, spans, owner, pieces);
+            cursor = unit.end_line + 1;
+        }
+        if cursor <= to {
+            self.push_loose(lines, cursor, to, owner, pieces);
+        }
+    }
+
+    /// Chunk one symbol starting at line `from`
+    fn chunk_unit(
+        &self,
+        lines: &SourceLines<'_>,
+        from: usize,
+        unit: &SymbolSpan,
+        spans: &[SymbolSpan],
+        owner: Option<&SymbolSpan>,
+        pieces: &mut Vec<CodePiece>,
+    ) {
+        let name = match owner {
+            Some(owner) => format!("{}::{}", owner.name, unit.name),
+            None => unit.name.clone(),
+        };
+        let kind = match owner {
+            Some(owner) if owner.is_container() && unit.kind == "function" => "method",
+            _ => unit.kind,
+        };
+
+        if lines.size(from, unit.end_line) <= self.max_chunk_size {
+            pieces.push(CodePiece {
+                start_line: from,
+                end_line: unit.end_line,
+                symbol: Some(name),
+                kind,
+                part: None,
+            });
+        } else if spans.iter().any(|s| unit.contains(s)) {
+            let named = SymbolSpan {
+                name,
+                kind,
+                ..unit.clone()
+            };
+            self.chunk_region(lines, from, unit.end_line, spans, Some(&named), pieces);
+        } else {
+            self.split_statements(lines, from, unit.end_line, &name, kind, pieces);
+        }
+    }
+
+    /// Chunk lines that belong to no symbol below `owner`. Closing braces
+    /// are folded into the previous chunk rather than chunked alone
+    fn push_loose(
+        &self,
+        lines: &SourceLines<'_>,
+        mut from: usize,
+        mut to: usize,
+        owner: Option<&SymbolSpan>,
+        pieces: &mut Vec<CodePiece>,
+    ) {
+        while from <= to && lines.is_blank(from) {
+            from += 1;
+        }
+        while to >= from && lines.is_blank(to) {
+            to -= 1;
+        }
+        if from > to {
+            return;
+        }
+
+        let only_closing = (from..=to).all(|line| {
+            lines
+                .line(line)
+                .trim()
+                .chars()
+                .all(|c| matches!(c, '}' | ')' | ']' | ';' | ','))
+        });
+        if let Some(last) = pieces.last_mut().filter(|_| only_closing) {
+            last.end_line = to;
+            return;
+        }
+
+        let mut start = from;
+        while start <= to {
+            let mut end = start;
+            while end < to && lines.size(start, end + 1) <= self.max_chunk_size {
+                end += 1;
+            }
+            pieces.push(CodePiece {
+                start_line: start,
+                end_line: end,
+                symbol: owner.map(|owner| owner.name.clone()),
+                kind: owner.map_or("module", |owner| owner.kind),
+                part: None,
+            });
+            start = end + 1;
+        }
+    }
+
+    /// Split a function at the ends of its top-level statements
+    fn split_statements(
+        &self,
+        lines: &SourceLines<'_>,
+        from: usize,
+        to: usize,
+        symbol: &str,
+        kind: &'static str,
+        pieces: &mut Vec<CodePiece>,
+    ) {
+        let mut boundaries = Vec::new();
+        let mut depth: i32 = 0;
+        for line in from..=to {
+            let text = lines.line(line);
+            depth += Self::brace_delta(text);
+            let trimmed = text.trim_end();
+            if depth == 1
+                && (trimmed.is_empty()
+                    || trimmed.ends_with(';')
+                    || trimmed.ends_with('}')
+                    || trimmed.ends_with(','))
+            {
+                boundaries.push(line);
+            }
+        }
+        if boundaries.last() != Some(&to) {
+            boundaries.push(to);
+        }
+
+        let mut start = from;
+        let mut covered = from - 1;
+        let mut part = 0;
+        loop {
+            let ahead: Vec<usize> = boundaries
+                .iter()
+                .copied()
+                .filter(|&b| b > covered)
+                .collect();
+            let end = ahead
+                .iter()
+                .copied()
+                .take_while(|&b| lines.size(start, b) <= self.max_chunk_size)
+                .last()
+                .unwrap_or(ahead[0]);
+
+            pieces.push(CodePiece {
+                start_line: start,
+                end_line: end,
+                symbol: Some(symbol.to_string()),
+                kind,
+                part: Some(part),
+            });
+            if end >= to {
+                break;
+            }
+            part += 1;
+            covered = end;
+            start = (end + 1).saturating_sub(CODE_CONTEXT_LINES).max(start + 1);
+        }
+    }
+
+    /// Net change of brace depth over a line, ignoring braces in string
+    /// literals and line comments
+    fn brace_delta(line: &str) -> i32 {
+        let mut delta = 0;
+        let mut quote = None;
+        let mut chars = line.chars().peekable();
+        while let Some(ch) = chars.next() {
+            match (quote, ch) {
+                (Some(_), '\\') => {
+                    chars.next();
+                }
+                (Some(q), c) if c == q => quote = None,
+                (Some(_), _) => {}
+                (None, '"' | '`') => quote = Some(ch),
+                (None, '/') if chars.peek() == Some(&'/') => break,
+                (None, '{') => delta += 1,
+                (None, '}') => delta -= 1,
+                _ => {}
+            }
+        }
+        delta
+    }
+}
+
 /// Hierarchical chunker that creates parent-child chunk relationships
 pub struct HierarchicalChunker {
     parent_size: usize,
@@ -598,4 +1037,207 @@ mod tests {
 
         assert!(!chunks.is_empty());
     }
+
+    fn line_range(chunk: &ContentChunk) -> (usize, usize) {
+        let line = |key: &str| chunk.metadata[key].as_u64().unwrap() as usize;
+        (line("start_line"), line("end_line"))
+    }
+
+    /// No chunk may start or end strictly inside a function
+    fn assert_functions_intact(chunks: &[ContentChunk], functions: &[(usize, usize)]) {
+        for chunk in chunks {
+            let (start, end) = line_range(chunk);
+            for &(f_start, f_end) in functions {
+                assert!(
+                    !(start > f_start && start <= f_end) && !(end >= f_start && end < f_end),
+                    "chunk {}..={} cuts function {}..={}",
+                    start,
+                    end,
+                    f_start,
+                    f_end
+                );
+            }
+        }
+    }
+
+    fn find_symbol<'a>(chunks: &'a [ContentChunk], symbol: &str) -> &'a ContentChunk {
+        chunks
+            .iter()
+            .find(|c| c.metadata.get("symbol").and_then(|s| s.as_str()) == Some(symbol))
+            .unwrap_or_else(|| panic!("no chunk for {}", symbol))
+    }
+
+    #[test]
+    fn test_code_chunker_keeps_rust_functions_intact() {
+        let source = r#"use std::collections::HashMap;
+
+/// Adds two numbers.
+pub fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+pub struct Stack {
+    items: Vec<i32>,
+}
+
+impl Stack {
+    pub fn new() -> Self {
+        Self { items: Vec::new() }
+    }
+
+    pub fn push(&mut self, item: i32) {
+        self.items.push(item);
+    }
+
+    pub fn pop(&mut self) -> Option<i32> {
+        self.items.pop()
+    }
+}
+
+fn total(values: &[i32]) -> i32 {
+    values.iter().sum()
+}
+"#;
+        // Small enough that the impl block is split into its methods
+        let chunker = CodeChunker::new(200, 0);
+        let chunks = chunker.chunk_source(Path::new("src/stack.rs"), source);
+
+        let parsed = cortex_code_analysis::RustParser::new()
+            .unwrap()
+            .parse_file("src/stack.rs", source)
+            .unwrap();
+        let functions: Vec<(usize, usize)> = parsed
+            .functions
+            .iter()
+            .map(|f| (f.start_line, f.end_line))
+            .collect();
+        assert_eq!(functions.len(), 5);
+        assert_functions_intact(&chunks, &functions);
+
+        let add = find_symbol(&chunks, "add");
+        assert!(add.content.starts_with("/// Adds two numbers."));
+        assert_eq!(add.metadata["symbol_kind"], "function");
+        assert_eq!(add.metadata["language"], "rust");
+        assert_eq!(line_range(add), (3, 6));
+
+        let push = find_symbol(&chunks, "Stack::push");
+        assert_eq!(push.metadata["symbol_kind"], "method");
+        assert_eq!(&source[push.start_offset..push.end_offset], push.content);
+        assert_eq!(
+            find_symbol(&chunks, "Stack").metadata["symbol_kind"],
+            "struct"
+        );
+        assert_eq!(
+            find_symbol(&chunks, "total").metadata["symbol_kind"],
+            "function"
+        );
+    }
+
+    #[test]
+    fn test_code_chunker_keeps_typescript_functions_intact() {
+        let source = r#"import { readFile } from "fs";
+
+export function greet(name: string): string {
+    return `hello ${name}`;
+}
+
+export class Counter {
+    private count = 0;
+
+    increment(): void {
+        this.count += 1;
+    }
+
+    reset(): void {
+        this.count = 0;
+    }
+}
+
+interface Shape {
+    area(): number;
+}
+
+function double(value: number): number {
+    return value * 2;
+}
+"#;
+        let chunker = CodeChunker::new(120, 0);
+        let chunks = chunker.chunk_source(Path::new("src/counter.ts"), source);
+
+        let parsed = cortex_code_analysis::TypeScriptParser::new()
+            .unwrap()
+            .parse_file("src/counter.ts", source)
+            .unwrap();
+        let functions: Vec<(usize, usize)> = parsed
+            .functions
+            .iter()
+            .map(|f| (f.start_line, f.end_line))
+            .collect();
+        assert_eq!(functions.len(), 4);
+        assert_functions_intact(&chunks, &functions);
+
+        assert_eq!(
+            find_symbol(&chunks, "greet").metadata["language"],
+            "typescript"
+        );
+        assert_eq!(
+            find_symbol(&chunks, "Counter::increment").metadata["symbol_kind"],
+            "method"
+        );
+        assert_eq!(
+            find_symbol(&chunks, "Shape").metadata["symbol_kind"],
+            "interface"
+        );
+        assert_eq!(line_range(find_symbol(&chunks, "double")), (23, 25));
+    }
+
+    #[test]
+    fn test_code_chunker_splits_long_function_at_statements() {
+        let body: String = (0..20)
+            .map(|i| format!("    let v{} = {};\n", i, i))
+            .collect();
+        let source = format!("fn long() {{\n{}}}\n", body);
+        let chunker = CodeChunker::new(100, 0);
+        let chunks = chunker.chunk_source(Path::new("long.rs"), &source);
+
+        assert!(chunks.len() > 1);
+        for (i, chunk) in chunks.iter().enumerate() {
+            assert_eq!(chunk.metadata["symbol"], "long");
+            assert_eq!(chunk.metadata["part"], i);
+            assert!(chunk.char_count() <= 100);
+            let last_line = chunk.content.lines().last().unwrap().trim_end();
+            assert!(last_line.ends_with(';') || last_line.ends_with('}'));
+        }
+        for pair in chunks.windows(2) {
+            let (_, prev_end) = line_range(&pair[0]);
+            let (next_start, _) = line_range(&pair[1]);
+            assert_eq!(next_start, prev_end + 1 - CODE_CONTEXT_LINES);
+        }
+        assert_eq!(line_range(chunks.last().unwrap()).1, 22);
+    }
+
+    #[test]
+    fn test_code_chunker_falls_back_for_unsupported_languages() {
+        let source = "func add(a int, b int) int {\n    return a + b\n}\n";
+        let chunker = CodeChunker::new(200, 0);
+        let chunks = chunker.chunk_source(Path::new("math.go"), source);
+
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].metadata["language"], "go");
+        assert!(!chunks[0].metadata.contains_key("symbol"));
+    }
+
+    #[test]
+    fn test_code_chunker_splits_python_by_symbol() {
+        let source = "import math\n\n\ndef add(a, b):\n    return a + b\n\n\nclass Circle:\n    def area(self):\n        return math.pi\n";
+        let chunker = CodeChunker::new(200, 0);
+        let chunks = chunker.chunk_source(Path::new("math.py"), source);
+
+        let symbols: Vec<_> = chunks
+            .iter()
+            .filter_map(|chunk| chunk.metadata.get("symbol"))
+            .collect();
+        assert_eq!(symbols, vec!["add", "Circle"]);
+        assert!(chunks.iter().all(|chunk| chunk.metadata["language"] == "python"));
+    }
 }
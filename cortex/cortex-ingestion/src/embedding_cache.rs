@@ -0,0 +1,400 @@
+//! Persistent embedding cache keyed by content hash.
+//!
+//! Vectors are stored in a SQLite file, keyed by the blake3 hash of the
+//! embedded text and the model that produced them, so unchanged content is
+//! not sent to the provider again when a project is re-ingested. The file
+//! runs in WAL mode and every write is a transaction, so several ingestion
+//! processes can share it; a process waiting on another's write lock retries
+//! for up to `BUSY_TIMEOUT` before the cache operation fails.
+//!
+//! The cache is bounded by the total size of the stored vectors. When a
+//! write pushes it over the limit, the least recently used entries are
+//! evicted.
+
+use cortex_core::error::{CortexError, Result};
+use rusqlite::{Connection, OptionalExtension, TransactionBehavior, params};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// How long to wait for another process holding the write lock
+const BUSY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Cache counters, as reported by `EmbeddingService::cache_stats`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmbeddingCacheStats {
+    /// Entries in the cache file, from all processes sharing it
+    pub entries: u64,
+    /// Total size of the stored vectors
+    pub size_bytes: u64,
+    pub max_bytes: u64,
+    /// Lookups answered by this process from the cache
+    pub hits: u64,
+    /// Lookups by this process that had to go to the provider
+    pub misses: u64,
+    /// Entries evicted by this process
+    pub evictions: u64,
+}
+
+impl EmbeddingCacheStats {
+    /// Share of lookups answered from the cache
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+/// Disk-backed LRU of embedding vectors
+pub struct EmbeddingCache {
+    conn: Mutex<Connection>,
+    path: PathBuf,
+    max_bytes: u64,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+}
+
+impl EmbeddingCache {
+    /// Open or create the cache file at `path`
+    pub fn open(path: impl AsRef<Path>, max_bytes: u64) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(|e| {
+                CortexError::storage(format!("Failed to create embedding cache directory: {}", e))
+            })?;
+        }
+
+        let conn = Connection::open(&path).map_err(cache_error)?;
+        conn.busy_timeout(BUSY_TIMEOUT).map_err(cache_error)?;
+        conn.pragma_update(None, "journal_mode", "WAL")
+            .map_err(cache_error)?;
+        conn.pragma_update(None, "synchronous", "NORMAL")
+            .map_err(cache_error)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS embeddings (
+                content_hash TEXT NOT NULL,
+                model TEXT NOT NULL,
+                vector BLOB NOT NULL,
+                size INTEGER NOT NULL,
+                last_access INTEGER NOT NULL,
+                PRIMARY KEY (content_hash, model)
+            );
+            CREATE INDEX IF NOT EXISTS idx_embeddings_last_access
+                ON embeddings (last_access);",
+        )
+        .map_err(cache_error)?;
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+            path,
+            max_bytes,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Cache key of a text
+    pub fn content_hash(text: &str) -> String {
+        blake3::hash(text.as_bytes()).to_hex().to_string()
+    }
+
+    /// Vectors cached for `hashes` under `model`, by hash. Hits are marked
+    /// as used
+    pub fn get_many(&self, model: &str, hashes: &[String]) -> Result<HashMap<String, Vec<f32>>> {
+        let mut conn = self.conn.lock().unwrap();
+        // Immediate, since hits are written back; upgrading a read
+        // transaction would fail rather than wait while another process writes
+        let tx = conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .map_err(cache_error)?;
+        let now = now_millis();
+        let mut found = HashMap::new();
+        {
+            let mut select = tx
+                .prepare_cached(
+                    "SELECT vector FROM embeddings WHERE content_hash = ?1 AND model = ?2",
+                )
+                .map_err(cache_error)?;
+            let mut touch = tx
+                .prepare_cached(
+                    "UPDATE embeddings SET last_access = ?3 WHERE content_hash = ?1 AND model = ?2",
+                )
+                .map_err(cache_error)?;
+
+            for hash in hashes {
+                if found.contains_key(hash) {
+                    continue;
+                }
+                let vector: Option<Vec<u8>> = select
+                    .query_row(params![hash, model], |row| row.get(0))
+                    .optional()
+                    .map_err(cache_error)?;
+                if let Some(bytes) = vector {
+                    touch
+                        .execute(params![hash, model, now])
+                        .map_err(cache_error)?;
+                    found.insert(hash.clone(), decode_vector(&bytes));
+                }
+            }
+        }
+        tx.commit().map_err(cache_error)?;
+
+        let hits = hashes.iter().filter(|h| found.contains_key(*h)).count() as u64;
+        self.hits.fetch_add(hits, Ordering::Relaxed);
+        self.misses
+            .fetch_add(hashes.len() as u64 - hits, Ordering::Relaxed);
+        Ok(found)
+    }
+
+    /// Store vectors under `model`, then evict least recently used entries
+    /// beyond the size limit
+    pub fn put_many(&self, model: &str, entries: &[(String, Vec<f32>)]) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .map_err(cache_error)?;
+        let now = now_millis();
+        {
+            let mut insert = tx
+                .prepare_cached(
+                    "INSERT OR REPLACE INTO embeddings
+                        (content_hash, model, vector, size, last_access)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                )
+                .map_err(cache_error)?;
+            for (hash, vector) in entries {
+                let bytes = encode_vector(vector);
+                insert
+                    .execute(params![hash, model, bytes, bytes.len() as i64, now])
+                    .map_err(cache_error)?;
+            }
+        }
+
+        let size = total_size(&tx)?;
+        if size > self.max_bytes {
+            let evicted = evict_oldest(&tx, size - self.max_bytes)?;
+            self.evictions.fetch_add(evicted, Ordering::Relaxed);
+        }
+
+        tx.commit().map_err(cache_error)
+    }
+
+    /// Remove every entry
+    pub fn clear(&self) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM embeddings", [])
+            .map_err(cache_error)?;
+        Ok(())
+    }
+
+    pub fn stats(&self) -> Result<EmbeddingCacheStats> {
+        let conn = self.conn.lock().unwrap();
+        Ok(EmbeddingCacheStats {
+            entries: count_entries(&conn)?,
+            size_bytes: total_size(&conn)?,
+            max_bytes: self.max_bytes,
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+        })
+    }
+}
+
+fn cache_error(e: rusqlite::Error) -> CortexError {
+    CortexError::storage(format!("Embedding cache error: {}", e))
+}
+
+fn now_millis() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+fn total_size(conn: &Connection) -> Result<u64> {
+    conn.query_row("SELECT COALESCE(SUM(size), 0) FROM embeddings", [], |row| {
+        row.get::<_, i64>(0)
+    })
+    .map(|size| size as u64)
+    .map_err(cache_error)
+}
+
+/// Delete least recently used entries until at least `excess` bytes are
+/// freed, returning how many were deleted
+fn evict_oldest(conn: &Connection, excess: u64) -> Result<u64> {
+    let mut select = conn
+        .prepare("SELECT rowid, size FROM embeddings ORDER BY last_access ASC")
+        .map_err(cache_error)?;
+    let mut rows = select.query([]).map_err(cache_error)?;
+
+    let mut victims = Vec::new();
+    let mut freed = 0;
+    while freed < excess {
+        let Some(row) = rows.next().map_err(cache_error)? else {
+            break;
+        };
+        victims.push(row.get::<_, i64>(0).map_err(cache_error)?);
+        freed += row.get::<_, i64>(1).map_err(cache_error)? as u64;
+    }
+    drop(rows);
+
+    let mut delete = conn
+        .prepare_cached("DELETE FROM embeddings WHERE rowid = ?1")
+        .map_err(cache_error)?;
+    for rowid in &victims {
+        delete.execute(params![rowid]).map_err(cache_error)?;
+    }
+    Ok(victims.len() as u64)
+}
+
+fn count_entries(conn: &Connection) -> Result<u64> {
+    conn.query_row("SELECT COUNT(*) FROM embeddings", [], |row| {
+        row.get::<_, i64>(0)
+    })
+    .map(|count| count as u64)
+    .map_err(cache_error)
+}
+
+fn encode_vector(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn decode_vector(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embeddings::{EmbeddingConfig, EmbeddingProvider, EmbeddingService};
+    use async_trait::async_trait;
+    use std::sync::Arc;
+
+    /// Provider that counts the texts it is asked to embed
+    struct CountingProvider {
+        embedded: AtomicU64,
+    }
+
+    #[async_trait]
+    impl EmbeddingProvider for CountingProvider {
+        async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+            self.embedded.fetch_add(1, Ordering::SeqCst);
+            Ok(vec![text.len() as f32, 1.0])
+        }
+
+        async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+            let mut embeddings = Vec::with_capacity(texts.len());
+            for text in texts {
+                embeddings.push(self.embed(text).await?);
+            }
+            Ok(embeddings)
+        }
+
+        fn model_name(&self) -> &str {
+            "counting"
+        }
+
+        fn dimension(&self) -> usize {
+            2
+        }
+
+        fn max_input_length(&self) -> usize {
+            8192
+        }
+    }
+
+    #[test]
+    fn test_cache_roundtrip_and_model_separation() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = EmbeddingCache::open(dir.path().join("embeddings.db"), 1 << 20).unwrap();
+        let hash = EmbeddingCache::content_hash("fn main() {}");
+
+        cache
+            .put_many("model-a", &[(hash.clone(), vec![0.25, -1.5])])
+            .unwrap();
+
+        let found = cache.get_many("model-a", &[hash.clone()]).unwrap();
+        assert_eq!(found[&hash], vec![0.25, -1.5]);
+        assert!(cache.get_many("model-b", &[hash]).unwrap().is_empty());
+
+        let stats = cache.stats().unwrap();
+        assert_eq!((stats.entries, stats.size_bytes), (1, 8));
+        assert_eq!((stats.hits, stats.misses), (1, 1));
+    }
+
+    #[test]
+    fn test_cache_evicts_least_recently_used() {
+        let dir = tempfile::tempdir().unwrap();
+        // Room for two 2-dimensional vectors
+        let cache = EmbeddingCache::open(dir.path().join("embeddings.db"), 16).unwrap();
+        let [a, b, c] = ["a", "b", "c"].map(EmbeddingCache::content_hash);
+
+        cache.put_many("m", &[(a.clone(), vec![1.0, 1.0])]).unwrap();
+        std::thread::sleep(Duration::from_millis(5));
+        cache.put_many("m", &[(b.clone(), vec![2.0, 2.0])]).unwrap();
+        std::thread::sleep(Duration::from_millis(5));
+        // Using "a" makes "b" the least recently used
+        cache.get_many("m", &[a.clone()]).unwrap();
+        std::thread::sleep(Duration::from_millis(5));
+        cache.put_many("m", &[(c.clone(), vec![3.0, 3.0])]).unwrap();
+
+        let found = cache.get_many("m", &[a.clone(), b, c.clone()]).unwrap();
+        assert!(found.contains_key(&a) && found.contains_key(&c));
+        assert_eq!(found.len(), 2);
+        let stats = cache.stats().unwrap();
+        assert_eq!(stats.evictions, 1);
+        assert!(stats.size_bytes <= 16);
+    }
+
+    #[tokio::test]
+    async fn test_service_only_embeds_misses() {
+        let dir = tempfile::tempdir().unwrap();
+        let provider = Arc::new(CountingProvider {
+            embedded: AtomicU64::new(0),
+        });
+        let config = EmbeddingConfig {
+            cache_path: Some(dir.path().join("embeddings.db")),
+            ..Default::default()
+        };
+        let service = EmbeddingService::new(provider.clone(), config);
+
+        let first = vec!["alpha".to_string(), "beta".to_string()];
+        let embedded = service.embed_batch(&first).await.unwrap();
+        assert_eq!(provider.embedded.load(Ordering::SeqCst), 2);
+
+        let second = vec!["beta".to_string(), "gamma".to_string(), "alpha".to_string()];
+        let again = service.embed_batch(&second).await.unwrap();
+        assert_eq!(provider.embedded.load(Ordering::SeqCst), 3);
+        assert_eq!(again[0], embedded[1]);
+        assert_eq!(again[2], embedded[0]);
+        assert_eq!(again[1], vec![5.0, 1.0]);
+
+        let stats = service.cache_stats().unwrap();
+        assert_eq!((stats.entries, stats.hits, stats.misses), (3, 2, 3));
+
+        // A service with the cache disabled goes to the provider every time
+        let bypass = EmbeddingService::new(
+            provider.clone(),
+            EmbeddingConfig {
+                cache_path: Some(dir.path().join("embeddings.db")),
+                cache_enabled: false,
+                ..Default::default()
+            },
+        );
+        bypass.embed_batch(&first).await.unwrap();
+        assert_eq!(provider.embedded.load(Ordering::SeqCst), 5);
+        assert!(bypass.cache_stats().is_none());
+    }
+}
@@ -3,8 +3,11 @@
 //! This module provides the interface for generating embeddings from text content.
 //! The actual embedding models are implemented separately and can be plugged in.
 
+use crate::embedding_cache::{EmbeddingCache, EmbeddingCacheStats};
 use async_trait::async_trait;
 use cortex_core::error::Result;
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 /// Embedding provider interface
@@ -31,10 +34,15 @@ pub trait EmbeddingProvider: Send + Sync {
 pub struct EmbeddingConfig {
     /// Batch size for batch processing
     pub batch_size: usize,
-    /// Whether to cache embeddings
+    /// Whether to cache embeddings; disabling bypasses the cache file
     pub cache_enabled: bool,
     /// Maximum text length before truncation
     pub max_text_length: usize,
+    /// Embedding cache file; no persistent cache when unset
+    pub cache_path: Option<PathBuf>,
+    /// Size limit of the cached vectors, beyond which the least recently
+    /// used are evicted
+    pub cache_max_bytes: u64,
 }
 
 impl Default for EmbeddingConfig {
@@ -43,6 +51,8 @@ impl Default for EmbeddingConfig {
             batch_size: 32,
             cache_enabled: true,
             max_text_length: 8000,
+            cache_path: None,
+            cache_max_bytes: 512 * 1024 * 1024,
         }
     }
 }
@@ -55,15 +65,30 @@ pub struct EmbeddingService {
     provider: Arc<dyn EmbeddingProvider>,
     config: EmbeddingConfig,
     progress_callback: Option<ProgressCallback>,
+    cache: Option<Arc<EmbeddingCache>>,
 }
 
 impl EmbeddingService {
-    /// Create a new embedding service
+    /// Create a new embedding service. The cache file from the config is
+    /// opened here; if it cannot be, embeddings are generated uncached
     pub fn new(provider: Arc<dyn EmbeddingProvider>, config: EmbeddingConfig) -> Self {
+        let cache = config
+            .cache_path
+            .as_ref()
+            .filter(|_| config.cache_enabled)
+            .and_then(|path| match EmbeddingCache::open(path, config.cache_max_bytes) {
+                Ok(cache) => Some(Arc::new(cache)),
+                Err(e) => {
+                    tracing::warn!("Embedding cache disabled, failed to open {:?}: {}", path, e);
+                    None
+                }
+            });
+
         Self {
             provider,
             config,
             progress_callback: None,
+            cache,
         }
     }
 
@@ -78,6 +103,32 @@ impl EmbeddingService {
         self
     }
 
+    /// Use an already open cache, e.g. one shared with other services
+    pub fn with_cache(mut self, cache: Arc<EmbeddingCache>) -> Self {
+        if self.config.cache_enabled {
+            self.cache = Some(cache);
+        }
+        self
+    }
+
+    /// Counters of the embedding cache, if one is in use
+    pub fn cache_stats(&self) -> Option<EmbeddingCacheStats> {
+        let cache = self.cache.as_ref()?;
+        match cache.stats() {
+            Ok(stats) => Some(stats),
+            Err(e) => {
+                tracing::warn!("Failed to read embedding cache stats: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Cache namespace of the provider; vectors of different models or
+    /// dimensions never mix
+    fn cache_model(&self) -> String {
+        format!("{}:{}", self.provider.model_name(), self.provider.dimension())
+    }
+
     /// Generate embedding for a single text
     pub async fn embed(&self, text: &str) -> Result<Vec<f32>> {
         // Truncate if too long, respecting UTF-8 character boundaries
@@ -95,37 +146,113 @@ impl EmbeddingService {
         self.provider.embed(truncated).await
     }
 
-    /// Generate embeddings in batches with progress tracking and retry logic
+    /// Generate embeddings in batches with progress tracking and retry logic.
+    /// Texts found in the cache are not sent to the provider, and new
+    /// embeddings are written back to it
     pub async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let truncated: Vec<String> = texts
+            .iter()
+            .map(|t| {
+                if t.len() > self.config.max_text_length {
+                    // Find the last valid UTF-8 character boundary before max_text_length
+                    t.char_indices()
+                        .take_while(|(idx, _)| *idx < self.config.max_text_length)
+                        .last()
+                        .map(|(idx, ch)| &t[..idx + ch.len_utf8()])
+                        .unwrap_or("")
+                        .to_string()
+                } else {
+                    t.clone()
+                }
+            })
+            .collect();
+
+        let Some(cache) = self.cache.clone() else {
+            return self.embed_uncached(&truncated, 0, texts.len()).await;
+        };
+
+        let model = self.cache_model();
+        let hashes: Vec<String> = truncated
+            .iter()
+            .map(|t| EmbeddingCache::content_hash(t))
+            .collect();
+        let cached = {
+            let (cache, model, hashes) = (cache.clone(), model.clone(), hashes.clone());
+            tokio::task::spawn_blocking(move || cache.get_many(&model, &hashes)).await
+        };
+        let cached = match cached {
+            Ok(Ok(cached)) => cached,
+            Ok(Err(e)) => {
+                tracing::warn!("Embedding cache lookup failed: {}", e);
+                HashMap::new()
+            }
+            Err(e) => {
+                tracing::warn!("Embedding cache lookup panicked: {}", e);
+                HashMap::new()
+            }
+        };
+
+        // Embed each distinct missing text once
+        let mut missing: Vec<usize> = Vec::new();
+        let mut seen = std::collections::HashSet::new();
+        for (i, hash) in hashes.iter().enumerate() {
+            if !cached.contains_key(hash) && seen.insert(hash) {
+                missing.push(i);
+            }
+        }
+        let hit_count = texts.len() - missing.len();
+        if let Some(callback) = self.progress_callback.as_ref().filter(|_| hit_count > 0) {
+            callback(hit_count, texts.len());
+        }
+
+        let to_embed: Vec<String> = missing.iter().map(|&i| truncated[i].clone()).collect();
+        let embedded = self.embed_uncached(&to_embed, hit_count, texts.len()).await?;
+
+        let fresh: Vec<(String, Vec<f32>)> = missing
+            .iter()
+            .map(|&i| hashes[i].clone())
+            .zip(embedded)
+            .collect();
+        if !fresh.is_empty() {
+            let (cache, model, fresh) = (cache.clone(), model.clone(), fresh.clone());
+            match tokio::task::spawn_blocking(move || cache.put_many(&model, &fresh)).await {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => tracing::warn!("Failed to write embedding cache: {}", e),
+                Err(e) => tracing::warn!("Embedding cache write panicked: {}", e),
+            }
+        }
+
+        let fresh: HashMap<String, Vec<f32>> = fresh.into_iter().collect();
+        Ok(hashes
+            .iter()
+            .map(|hash| {
+                cached
+                    .get(hash)
+                    .or_else(|| fresh.get(hash))
+                    .cloned()
+                    .unwrap_or_default()
+            })
+            .collect())
+    }
+
+    /// Send already truncated texts to the provider in batches, reporting
+    /// progress on top of `done` texts out of `total`
+    async fn embed_uncached(
+        &self,
+        texts: &[String],
+        done: usize,
+        total: usize,
+    ) -> Result<Vec<Vec<f32>>> {
         let mut all_embeddings = Vec::with_capacity(texts.len());
-        let total_texts = texts.len();
-        let mut processed = 0;
+        let mut processed = done;
 
         // Process in batches
         for chunk in texts.chunks(self.config.batch_size) {
-            // Truncate texts in batch, respecting UTF-8 character boundaries
-            let truncated: Vec<String> = chunk
-                .iter()
-                .map(|t| {
-                    if t.len() > self.config.max_text_length {
-                        // Find the last valid UTF-8 character boundary before max_text_length
-                        t.char_indices()
-                            .take_while(|(idx, _)| *idx < self.config.max_text_length)
-                            .last()
-                            .map(|(idx, ch)| &t[..idx + ch.len_utf8()])
-                            .unwrap_or("")
-                            .to_string()
-                    } else {
-                        t.clone()
-                    }
-                })
-                .collect();
-
             // Retry logic with exponential backoff
             let mut retries = 0;
             let max_retries = 3;
             let batch_embeddings = loop {
-                match self.provider.embed_batch(&truncated).await {
+                match self.provider.embed_batch(chunk).await {
                     Ok(embeddings) => break embeddings,
                     Err(e) if retries < max_retries => {
                         retries += 1;
@@ -148,7 +275,7 @@ impl EmbeddingService {
 
             // Report progress
             if let Some(callback) = &self.progress_callback {
-                callback(processed, total_texts);
+                callback(processed, total);
             }
         }
 
@@ -1,23 +1,31 @@
 //! Enhanced document ingestion implementation with multi-format support.
 
+use crate::chunker::{CodeChunker, MAX_TOKENS_PER_CHUNK, tokens_to_chars};
 use crate::embeddings::{EmbeddingService, MockEmbeddingProvider};
 use crate::extractor::extract_comprehensive_metadata;
-use crate::processors::ProcessorFactory;
+use crate::pipeline::{FailureLog, IngestOptions, PipelineCounters, PipelineStage};
+use crate::processors::{ContentChunk, ContentType, ProcessorFactory};
+use crate::project_loader::ImportReport;
 use cortex_core::error::{CortexError, Result};
 use cortex_core::id::CortexId;
 use cortex_core::traits::{Ingester, Storage};
 use cortex_core::types::{Chunk, VfsDocument};
 use async_trait::async_trait;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use std::time::Instant;
+use tokio::sync::{mpsc, Mutex};
 
 /// Enhanced document ingester with multi-format support
+#[derive(Clone)]
 pub struct DocumentIngester {
     storage: Arc<dyn Storage>,
     processor_factory: Arc<ProcessorFactory>,
     embedding_service: Option<Arc<EmbeddingService>>,
     auto_chunk: bool,
     generate_embeddings: bool,
+    options: IngestOptions,
 }
 
 impl DocumentIngester {
@@ -29,6 +37,7 @@ impl DocumentIngester {
             embedding_service: None,
             auto_chunk: true,
             generate_embeddings: false,
+            options: IngestOptions::default(),
         }
     }
 
@@ -55,6 +64,12 @@ impl DocumentIngester {
         self
     }
 
+    /// Set the pipeline options used by `ingest_directory`
+    pub fn with_options(mut self, options: IngestOptions) -> Self {
+        self.options = options;
+        self
+    }
+
     /// Calculate content hash
     fn hash_content(content: &[u8]) -> String {
         let hash = blake3::hash(content);
@@ -69,6 +84,11 @@ impl DocumentIngester {
             .unwrap_or_else(|| "application/octet-stream".to_string())
     }
 
+    /// Whether chunks go through the embedding stage
+    fn embeds(&self) -> bool {
+        self.auto_chunk && self.generate_embeddings && self.embedding_service.is_some()
+    }
+
     /// Read file content
     async fn read_file(&self, path: &Path) -> Result<Vec<u8>> {
         tokio::fs::read(path)
@@ -111,7 +131,17 @@ impl DocumentIngester {
             tracing::debug!("No processor for {}, using basic text extraction", path.display());
             let text = String::from_utf8_lossy(content).to_string();
             let meta = extract_comprehensive_metadata(path, &text);
-            (text, Vec::new(), meta)
+            let is_code = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|ext| ContentType::from_extension(ext) == ContentType::Code);
+            let chunks = if self.auto_chunk && is_code {
+                CodeChunker::new(tokens_to_chars(MAX_TOKENS_PER_CHUNK), 0)
+                    .chunk_source(path, &text)
+            } else {
+                Vec::new()
+            };
+            (text, chunks, meta)
         };
 
         Ok(ProcessedFileInfo {
@@ -121,38 +151,15 @@ impl DocumentIngester {
         })
     }
 
-    /// Generate embeddings for chunks
-    async fn generate_chunk_embeddings(
+    /// Process stage: detect the type, extract and chunk, and build the
+    /// document record
+    async fn process_stage(
         &self,
-        chunks: &[crate::processors::ContentChunk],
-    ) -> Result<Vec<Vec<f32>>> {
-        if let Some(embedding_service) = &self.embedding_service {
-            let texts: Vec<String> = chunks.iter().map(|c| c.content.clone()).collect();
-            embedding_service.embed_batch(&texts).await
-        } else {
-            Ok(Vec::new())
-        }
-    }
-}
-
-struct ProcessedFileInfo {
-    #[allow(dead_code)]
-    text_content: String,
-    chunks: Vec<crate::processors::ContentChunk>,
-    metadata: std::collections::HashMap<String, serde_json::Value>,
-}
-
-#[async_trait]
-impl Ingester for DocumentIngester {
-    async fn ingest_file(&self, project_id: CortexId, path: &Path) -> Result<VfsDocument> {
-        tracing::info!("Ingesting file: {:?}", path);
-
-        let content = self.read_file(path).await?;
-        let content_hash = Self::hash_content(&content);
-        let mime_type = Self::detect_mime_type(path);
-
-        // Process file with appropriate processor
-        let processed = self.process_file(path, &content).await?;
+        project_id: CortexId,
+        path: &Path,
+        content: &[u8],
+    ) -> Result<ProcessedFile> {
+        let processed = self.process_file(path, content).await?;
 
         // Convert metadata to HashMap<String, String> for Document
         let metadata: std::collections::HashMap<String, String> = processed
@@ -165,30 +172,44 @@ impl Ingester for DocumentIngester {
             id: CortexId::new(),
             project_id,
             path: path.to_string_lossy().to_string(),
-            content_hash: content_hash.clone(),
+            content_hash: Self::hash_content(content),
             size: content.len() as u64,
-            mime_type,
+            mime_type: Self::detect_mime_type(path),
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
             metadata,
         };
 
+        Ok(ProcessedFile {
+            path: path.to_path_buf(),
+            document,
+            chunks: processed.chunks,
+        })
+    }
+
+    /// Generate embeddings for chunks
+    async fn generate_chunk_embeddings(&self, chunks: &[ContentChunk]) -> Result<Vec<Vec<f32>>> {
+        if let Some(embedding_service) = &self.embedding_service {
+            let texts: Vec<String> = chunks.iter().map(|c| c.content.clone()).collect();
+            embedding_service.embed_batch(&texts).await
+        } else {
+            Ok(Vec::new())
+        }
+    }
+
+    /// Store stage: write the document and the embeddings of its chunks
+    async fn store_stage(&self, file: &ProcessedFile, embeddings: &[Vec<f32>]) -> Result<()> {
+        let document = &file.document;
+
         // Store document
-        self.storage.store_document(&document).await?;
+        self.storage.store_document(document).await?;
 
         // Process and store chunks if enabled
-        if self.auto_chunk && !processed.chunks.is_empty() {
-            tracing::debug!("Processing {} chunks for document", processed.chunks.len());
-
-            // Generate embeddings if enabled
-            let embeddings = if self.generate_embeddings {
-                self.generate_chunk_embeddings(&processed.chunks).await?
-            } else {
-                Vec::new()
-            };
+        if self.auto_chunk && !file.chunks.is_empty() {
+            tracing::debug!("Processing {} chunks for document", file.chunks.len());
 
             // Store chunks
-            for (idx, chunk) in processed.chunks.iter().enumerate() {
+            for (idx, chunk) in file.chunks.iter().enumerate() {
                 let chunk_metadata: std::collections::HashMap<String, String> = chunk
                     .metadata
                     .iter()
@@ -228,32 +249,312 @@ impl Ingester for DocumentIngester {
             }
         }
 
-        Ok(document)
+        Ok(())
     }
 
-    async fn ingest_directory(&self, project_id: CortexId, path: &Path) -> Result<Vec<VfsDocument>> {
+    /// Ingest a directory through the bounded pipeline.
+    ///
+    /// Files that fail are skipped and listed in the report with the stage
+    /// that failed them, unless `options.fail_fast` is set, in which case the
+    /// run stops and the first failure is returned as the error.
+    pub async fn ingest_directory_with(
+        &self,
+        project_id: CortexId,
+        path: &Path,
+        options: &IngestOptions,
+    ) -> Result<(Vec<VfsDocument>, ImportReport)> {
         tracing::info!("Ingesting directory: {:?}", path);
 
-        let walker = ignore::WalkBuilder::new(path)
-            .hidden(false)
-            .git_ignore(true)
-            .build();
+        let start_time = Instant::now();
+        let capacity = options.queue_capacity();
+        let counters = Arc::new(PipelineCounters::default());
+        let failures = Arc::new(FailureLog::new(options.fail_fast));
+
+        // Walk: discover files on a blocking thread
+        let (path_tx, path_rx) = mpsc::channel::<PathBuf>(capacity);
+        let walk = {
+            let root = path.to_path_buf();
+            let failures = failures.clone();
+            tokio::task::spawn_blocking(move || {
+                let walker = ignore::WalkBuilder::new(&root)
+                    .hidden(false)
+                    .git_ignore(true)
+                    .build();
+
+                for entry in walker {
+                    if failures.aborted() {
+                        break;
+                    }
+                    match entry {
+                        Ok(entry) => {
+                            if entry.file_type().is_some_and(|ft| ft.is_file())
+                                && path_tx.blocking_send(entry.into_path()).is_err()
+                            {
+                                break;
+                            }
+                        }
+                        Err(e) => failures.record(root.clone(), PipelineStage::Walk, e),
+                    }
+                }
+            })
+        };
+
+        // Read and process: a pool of workers sharing the path queue
+        let path_rx = Arc::new(Mutex::new(path_rx));
+        let (processed_tx, mut processed_rx) = mpsc::channel::<ProcessedFile>(capacity);
+        let mut workers = Vec::with_capacity(options.concurrency.max(1));
+        for _ in 0..options.concurrency.max(1) {
+            let this = self.clone();
+            let path_rx = path_rx.clone();
+            let processed_tx = processed_tx.clone();
+            let counters = counters.clone();
+            let failures = failures.clone();
+
+            workers.push(tokio::spawn(async move {
+                loop {
+                    let next = {
+                        let mut path_rx = path_rx.lock().await;
+                        counters.read.observe_queue(path_rx.len());
+                        path_rx.recv().await
+                    };
+                    let Some(path) = next else {
+                        break;
+                    };
+                    if failures.aborted() {
+                        break;
+                    }
+
+                    let started = Instant::now();
+                    let content = match this.read_file(&path).await {
+                        Ok(content) => {
+                            counters.read.record(started.elapsed(), true);
+                            content
+                        }
+                        Err(e) => {
+                            counters.read.record(started.elapsed(), false);
+                            failures.record(path, PipelineStage::Read, e);
+                            continue;
+                        }
+                    };
+
+                    let started = Instant::now();
+                    match this.process_stage(project_id, &path, &content).await {
+                        Ok(file) => {
+                            counters.process.record(started.elapsed(), true);
+                            if processed_tx.send(file).await.is_err() {
+                                break;
+                            }
+                        }
+                        Err(e) => {
+                            counters.process.record(started.elapsed(), false);
+                            failures.record(path, PipelineStage::Process, e);
+                        }
+                    }
+                }
+            }));
+        }
+        drop(processed_tx);
+
+        // Embed: batch chunks across files
+        let (store_tx, mut store_rx) = mpsc::channel::<(ProcessedFile, Vec<Vec<f32>>)>(capacity);
+        let embed = {
+            let this = self.clone();
+            let counters = counters.clone();
+            let failures = failures.clone();
+            let batch_size = options.embed_batch_size.max(1);
+
+            tokio::spawn(async move {
+                let mut pending: Vec<ProcessedFile> = Vec::new();
+                let mut pending_chunks = 0;
+
+                loop {
+                    counters.embed.observe_queue(processed_rx.len());
+                    let next = processed_rx.recv().await;
+                    let closed = next.is_none();
+
+                    if let Some(file) = next {
+                        if !this.embeds() || file.chunks.is_empty() {
+                            counters.embed.record(std::time::Duration::ZERO, true);
+                            if store_tx.send((file, Vec::new())).await.is_err() {
+                                break;
+                            }
+                            continue;
+                        }
+                        pending_chunks += file.chunks.len();
+                        pending.push(file);
+                    }
+
+                    if pending_chunks >= batch_size || (closed && !pending.is_empty()) {
+                        let batch = std::mem::take(&mut pending);
+                        pending_chunks = 0;
+                        if !this.embed_batch(batch, &store_tx, &counters, &failures).await {
+                            break;
+                        }
+                    }
+
+                    if closed {
+                        break;
+                    }
+                }
+            })
+        };
 
+        // Store: a single writer
         let mut documents = Vec::new();
+        let mut bytes_processed = 0;
+        loop {
+            counters.store.observe_queue(store_rx.len());
+            let Some((file, embeddings)) = store_rx.recv().await else {
+                break;
+            };
+            if failures.aborted() {
+                continue;
+            }
+
+            let started = Instant::now();
+            match self.store_stage(&file, &embeddings).await {
+                Ok(()) => {
+                    counters.store.record(started.elapsed(), true);
+                    bytes_processed += file.document.size;
+                    documents.push(file.document);
+                }
+                Err(e) => {
+                    counters.store.record(started.elapsed(), false);
+                    failures.record(file.path, PipelineStage::Store, e);
+                }
+            }
+        }
+
+        for worker in workers {
+            worker
+                .await
+                .map_err(|e| CortexError::ingestion(format!("Ingestion worker failed: {}", e)))?;
+        }
+        embed
+            .await
+            .map_err(|e| CortexError::ingestion(format!("Embedding stage failed: {}", e)))?;
+        walk.await
+            .map_err(|e| CortexError::ingestion(format!("Directory walk failed: {}", e)))?;
+
+        let failures = failures.take();
+        if let Some(first) = failures.first().filter(|_| options.fail_fast) {
+            return Err(CortexError::ingestion(format!(
+                "Ingestion aborted at {} stage for {}: {}",
+                first.stage,
+                first.path.display(),
+                first.error
+            )));
+        }
+
+        let report = ImportReport {
+            files_imported: documents.len(),
+            files_skipped: failures
+                .iter()
+                .filter(|f| f.stage != PipelineStage::Walk)
+                .count(),
+            errors: failures.len(),
+            bytes_processed,
+            duration_secs: start_time.elapsed().as_secs_f64(),
+            failures,
+            pipeline: Some(counters.snapshot()),
+            ..ImportReport::default()
+        };
+
+        tracing::info!(
+            "Directory ingestion completed: {} files ingested, {} errors in {:.2}s",
+            report.files_imported,
+            report.errors,
+            report.duration_secs
+        );
 
-        for entry in walker {
-            let entry = entry.map_err(|e| CortexError::ingestion(format!("Walk error: {}", e)))?;
+        Ok((documents, report))
+    }
 
-            if entry.file_type().map(|ft| ft.is_file()).unwrap_or(false) {
-                match self.ingest_file(project_id, entry.path()).await {
-                    Ok(doc) => documents.push(doc),
-                    Err(e) => {
-                        tracing::warn!("Failed to ingest {:?}: {}", entry.path(), e);
+    /// Embeds one batch of files with a single provider call and hands them
+    /// to the store stage; returns false once the store stage is gone
+    async fn embed_batch(
+        &self,
+        batch: Vec<ProcessedFile>,
+        store_tx: &mpsc::Sender<(ProcessedFile, Vec<Vec<f32>>)>,
+        counters: &PipelineCounters,
+        failures: &FailureLog,
+    ) -> bool {
+        let texts: Vec<String> = batch
+            .iter()
+            .flat_map(|f| f.chunks.iter().map(|c| c.content.clone()))
+            .collect();
+        let started = Instant::now();
+        let result = match &self.embedding_service {
+            Some(embedding_service) => embedding_service.embed_batch(&texts).await,
+            None => Ok(Vec::new()),
+        };
+        counters.embed_batches.fetch_add(1, Ordering::Relaxed);
+        // Attribute the batch latency evenly to its files
+        let latency = started.elapsed() / batch.len() as u32;
+
+        match result {
+            Ok(mut embeddings) => {
+                for file in batch {
+                    let rest = embeddings.split_off(file.chunks.len().min(embeddings.len()));
+                    let own = std::mem::replace(&mut embeddings, rest);
+                    counters.embed.record(latency, true);
+                    if store_tx.send((file, own)).await.is_err() {
+                        return false;
                     }
                 }
             }
+            Err(e) => {
+                let error = e.to_string();
+                for file in batch {
+                    counters.embed.record(latency, false);
+                    failures.record(file.path, PipelineStage::Embed, &error);
+                }
+            }
         }
 
+        true
+    }
+}
+
+struct ProcessedFileInfo {
+    #[allow(dead_code)]
+    text_content: String,
+    chunks: Vec<ContentChunk>,
+    metadata: std::collections::HashMap<String, serde_json::Value>,
+}
+
+/// A file that has been read and processed, on its way to embedding and
+/// storage
+struct ProcessedFile {
+    path: PathBuf,
+    document: VfsDocument,
+    chunks: Vec<ContentChunk>,
+}
+
+#[async_trait]
+impl Ingester for DocumentIngester {
+    async fn ingest_file(&self, project_id: CortexId, path: &Path) -> Result<VfsDocument> {
+        tracing::info!("Ingesting file: {:?}", path);
+
+        let content = self.read_file(path).await?;
+        let file = self.process_stage(project_id, path, &content).await?;
+
+        // Generate embeddings if enabled
+        let embeddings = if self.embeds() && !file.chunks.is_empty() {
+            self.generate_chunk_embeddings(&file.chunks).await?
+        } else {
+            Vec::new()
+        };
+
+        self.store_stage(&file, &embeddings).await?;
+
+        Ok(file.document)
+    }
+
+    async fn ingest_directory(&self, project_id: CortexId, path: &Path) -> Result<Vec<VfsDocument>> {
+        let (documents, _report) = self
+            .ingest_directory_with(project_id, path, &self.options)
+            .await?;
         Ok(documents)
     }
 
@@ -286,9 +587,15 @@ mod tests {
     use super::*;
     use cortex_core::types::{Project, SystemStats};
     use std::collections::HashMap;
-
-    // Mock storage for testing
-    struct MockStorage;
+    use std::sync::atomic::AtomicUsize;
+
+    // Mock storage for testing; fails to store documents whose path
+    // contains `fail_on`
+    #[derive(Default)]
+    struct MockStorage {
+        fail_on: Option<String>,
+        embeddings: AtomicUsize,
+    }
 
     #[async_trait]
     impl Storage for MockStorage {
@@ -308,12 +615,17 @@ mod tests {
             Ok(())
         }
 
-        async fn store_document(&self, _document: &Document) -> Result<()> {
-            Ok(())
+        async fn store_document(&self, document: &VfsDocument) -> Result<()> {
+            match &self.fail_on {
+                Some(pattern) if document.path.contains(pattern.as_str()) => {
+                    Err(CortexError::storage("disk full"))
+                }
+                _ => Ok(()),
+            }
         }
 
-        async fn get_document(&self, _id: CortexId) -> Result<Option<Document>> {
-            Ok(Some(Document {
+        async fn get_document(&self, _id: CortexId) -> Result<Option<VfsDocument>> {
+            Ok(Some(VfsDocument {
                 id: _id,
                 project_id: CortexId::new(),
                 path: "test.txt".to_string(),
@@ -326,7 +638,7 @@ mod tests {
             }))
         }
 
-        async fn list_documents(&self, _project_id: CortexId) -> Result<Vec<Document>> {
+        async fn list_documents(&self, _project_id: CortexId) -> Result<Vec<VfsDocument>> {
             Ok(Vec::new())
         }
 
@@ -338,6 +650,7 @@ mod tests {
             &self,
             _embedding: &cortex_core::types::Embedding,
         ) -> Result<()> {
+            self.embeddings.fetch_add(1, Ordering::Relaxed);
             Ok(())
         }
 
@@ -407,10 +720,88 @@ mod tests {
 
     #[tokio::test]
     async fn test_ingester_creation() {
-        let storage = Arc::new(MockStorage);
+        let storage = Arc::new(MockStorage::default());
         let ingester = DocumentIngester::new(storage).with_auto_chunk(true).with_embeddings(true);
 
         assert!(ingester.auto_chunk);
         assert!(ingester.generate_embeddings);
     }
+
+    async fn write_files(dir: &Path, count: usize) {
+        for i in 0..count {
+            let content = format!("# Note {}\n\nSome text about topic {}.\n\n## Details\n\nMore text.\n", i, i);
+            tokio::fs::write(dir.join(format!("note_{}.md", i)), content).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_ingests_directory() {
+        let dir = tempfile::TempDir::new().unwrap();
+        write_files(dir.path(), 20).await;
+
+        let storage = Arc::new(MockStorage::default());
+        let ingester = DocumentIngester::new(storage.clone()).with_embeddings(true);
+        let options = IngestOptions {
+            concurrency: 3,
+            embed_batch_size: 8,
+            fail_fast: false,
+        };
+
+        let (documents, report) = ingester
+            .ingest_directory_with(CortexId::new(), dir.path(), &options)
+            .await
+            .unwrap();
+
+        assert_eq!(documents.len(), 20);
+        assert_eq!(report.files_imported, 20);
+        assert!(report.failures.is_empty());
+
+        let metrics = report.pipeline.unwrap();
+        assert_eq!(metrics.read.items_processed, 20);
+        assert_eq!(metrics.process.items_processed, 20);
+        assert_eq!(metrics.embed.items_processed, 20);
+        assert_eq!(metrics.store.items_processed, 20);
+        // Chunks of several files share a provider call
+        assert!(metrics.embed_batches < 20);
+        assert!(metrics.read.max_queue_depth <= options.queue_capacity() as u64);
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_attributes_failures_to_stage() {
+        let dir = tempfile::TempDir::new().unwrap();
+        write_files(dir.path(), 5).await;
+        tokio::fs::write(dir.path().join("bad.md"), "# Bad\n").await.unwrap();
+
+        let storage = Arc::new(MockStorage {
+            fail_on: Some("bad".to_string()),
+            ..Default::default()
+        });
+        let ingester = DocumentIngester::new(storage);
+        let options = IngestOptions {
+            concurrency: 2,
+            embed_batch_size: 4,
+            fail_fast: false,
+        };
+
+        let (documents, report) = ingester
+            .ingest_directory_with(CortexId::new(), dir.path(), &options)
+            .await
+            .unwrap();
+        assert_eq!(documents.len(), 5);
+        assert_eq!(report.failures_in(PipelineStage::Store), 1);
+        assert_eq!(report.failures[0].path, dir.path().join("bad.md"));
+        assert_eq!(report.pipeline.unwrap().store.failures, 1);
+
+        let result = ingester
+            .ingest_directory_with(
+                CortexId::new(),
+                dir.path(),
+                &IngestOptions {
+                    fail_fast: true,
+                    ..options
+                },
+            )
+            .await;
+        assert!(result.unwrap_err().to_string().contains("store stage"));
+    }
 }
@@ -4,8 +4,9 @@
 //! - Multiple document format processors (PDF, Markdown, HTML, JSON, YAML, CSV, Text)
 //! - Intelligent chunking strategies (semantic, hierarchical, code-aware)
 //! - Metadata extraction (language detection, keywords, document properties)
-//! - Embedding generation interface
+//! - Embedding generation interface with a persistent embedding cache
 //! - External project import functionality
+//! - Pipelined directory ingestion with backpressure and per-stage metrics
 
 pub mod ingester;
 pub mod chunker;
@@ -13,7 +14,9 @@ pub mod extractor;
 pub mod filters;
 pub mod processors;
 pub mod embeddings;
+pub mod embedding_cache;
 pub mod project_loader;
+pub mod pipeline;
 
 pub use ingester::DocumentIngester;
 pub use chunker::{Chunker, SemanticChunker, CodeChunker, HierarchicalChunker, ChunkStrategy};
@@ -22,7 +25,9 @@ pub use processors::{
     ProcessorFactory, detect_content_type, detect_mime_type,
 };
 pub use embeddings::{EmbeddingProvider, EmbeddingService, EmbeddingConfig};
+pub use embedding_cache::{EmbeddingCache, EmbeddingCacheStats};
 pub use project_loader::{ProjectLoader, ProjectImportOptions, ImportReport, ImportedFile};
+pub use pipeline::{IngestOptions, IngestFailure, PipelineMetrics, PipelineStage, StageMetrics};
 
 /// Re-export commonly used types
 pub mod prelude {
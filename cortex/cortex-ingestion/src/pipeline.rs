@@ -0,0 +1,254 @@
+//! Options, stages and metrics of the ingestion pipeline.
+//!
+//! `DocumentIngester::ingest_directory_with` runs ingestion as a bounded
+//! pipeline: a directory walk feeds `concurrency` reader/processor workers,
+//! which feed a single embedding stage that batches chunks across files,
+//! which feeds a single storage writer. Stages are connected by bounded
+//! channels, so a slow stage holds back the ones before it instead of
+//! letting work pile up in memory.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Options for a pipelined ingestion run
+#[derive(Debug, Clone)]
+pub struct IngestOptions {
+    /// Number of reader/processor workers
+    pub concurrency: usize,
+    /// Number of chunks sent to the embedding provider at once, collected
+    /// across files
+    pub embed_batch_size: usize,
+    /// Abort the run on the first failure instead of skipping the file
+    pub fail_fast: bool,
+}
+
+impl IngestOptions {
+    /// Capacity of each channel between stages
+    pub(crate) fn queue_capacity(&self) -> usize {
+        self.concurrency.max(1) * 2
+    }
+}
+
+impl Default for IngestOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(4),
+            embed_batch_size: 64,
+            fail_fast: false,
+        }
+    }
+}
+
+/// Stage of the ingestion pipeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PipelineStage {
+    /// Directory traversal
+    Walk,
+    /// Reading file content
+    Read,
+    /// Type detection, text extraction and chunking
+    Process,
+    /// Embedding generation
+    Embed,
+    /// Writing documents and embeddings to storage
+    Store,
+}
+
+impl fmt::Display for PipelineStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PipelineStage::Walk => "walk",
+            PipelineStage::Read => "read",
+            PipelineStage::Process => "process",
+            PipelineStage::Embed => "embed",
+            PipelineStage::Store => "store",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A file that could not be ingested, and the stage that failed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IngestFailure {
+    pub path: PathBuf,
+    pub stage: PipelineStage,
+    pub error: String,
+}
+
+/// Counters of one stage after a run
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StageMetrics {
+    /// Items that went through the stage successfully
+    pub items_processed: u64,
+    pub failures: u64,
+    /// Average time spent on one item
+    pub avg_latency_ms: f64,
+    /// Average number of items waiting in front of the stage
+    pub avg_queue_depth: f64,
+    /// Most items seen waiting in front of the stage
+    pub max_queue_depth: u64,
+}
+
+/// Per-stage metrics of a pipelined run
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PipelineMetrics {
+    pub read: StageMetrics,
+    pub process: StageMetrics,
+    pub embed: StageMetrics,
+    pub store: StageMetrics,
+    /// Embedding provider calls made
+    pub embed_batches: u64,
+}
+
+impl PipelineMetrics {
+    pub fn stage(&self, stage: PipelineStage) -> Option<&StageMetrics> {
+        match stage {
+            PipelineStage::Walk => None,
+            PipelineStage::Read => Some(&self.read),
+            PipelineStage::Process => Some(&self.process),
+            PipelineStage::Embed => Some(&self.embed),
+            PipelineStage::Store => Some(&self.store),
+        }
+    }
+}
+
+/// Lock-free counters for one stage, shared by its workers
+#[derive(Debug, Default)]
+pub(crate) struct StageCounter {
+    items: AtomicU64,
+    failures: AtomicU64,
+    latency_us: AtomicU64,
+    depth_sum: AtomicU64,
+    depth_samples: AtomicU64,
+    depth_max: AtomicU64,
+}
+
+impl StageCounter {
+    /// Records the queue depth seen when taking an item
+    pub(crate) fn observe_queue(&self, depth: usize) {
+        self.depth_sum.fetch_add(depth as u64, Ordering::Relaxed);
+        self.depth_samples.fetch_add(1, Ordering::Relaxed);
+        self.depth_max.fetch_max(depth as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record(&self, latency: Duration, success: bool) {
+        self.latency_us
+            .fetch_add(latency.as_micros() as u64, Ordering::Relaxed);
+        if success {
+            self.items.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.failures.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn snapshot(&self) -> StageMetrics {
+        let items = self.items.load(Ordering::Relaxed);
+        let failures = self.failures.load(Ordering::Relaxed);
+        let samples = self.depth_samples.load(Ordering::Relaxed);
+        let attempts = items + failures;
+
+        StageMetrics {
+            items_processed: items,
+            failures,
+            avg_latency_ms: if attempts == 0 {
+                0.0
+            } else {
+                self.latency_us.load(Ordering::Relaxed) as f64 / attempts as f64 / 1000.0
+            },
+            avg_queue_depth: if samples == 0 {
+                0.0
+            } else {
+                self.depth_sum.load(Ordering::Relaxed) as f64 / samples as f64
+            },
+            max_queue_depth: self.depth_max.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Counters of all stages of one run
+#[derive(Debug, Default)]
+pub(crate) struct PipelineCounters {
+    pub(crate) read: StageCounter,
+    pub(crate) process: StageCounter,
+    pub(crate) embed: StageCounter,
+    pub(crate) store: StageCounter,
+    pub(crate) embed_batches: AtomicU64,
+}
+
+impl PipelineCounters {
+    pub(crate) fn snapshot(&self) -> PipelineMetrics {
+        PipelineMetrics {
+            read: self.read.snapshot(),
+            process: self.process.snapshot(),
+            embed: self.embed.snapshot(),
+            store: self.store.snapshot(),
+            embed_batches: self.embed_batches.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Failures of one run, shared by all stages; with `fail_fast` the first
+/// failure tells every stage to stop
+#[derive(Debug, Default)]
+pub(crate) struct FailureLog {
+    failures: Mutex<Vec<IngestFailure>>,
+    aborted: AtomicBool,
+    fail_fast: bool,
+}
+
+impl FailureLog {
+    pub(crate) fn new(fail_fast: bool) -> Self {
+        Self {
+            fail_fast,
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn record(&self, path: PathBuf, stage: PipelineStage, error: impl fmt::Display) {
+        tracing::warn!("Failed to ingest {:?} at {} stage: {}", path, stage, error);
+        self.failures.lock().unwrap().push(IngestFailure {
+            path,
+            stage,
+            error: error.to_string(),
+        });
+        if self.fail_fast {
+            self.aborted.store(true, Ordering::SeqCst);
+        }
+    }
+
+    pub(crate) fn aborted(&self) -> bool {
+        self.aborted.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn take(&self) -> Vec<IngestFailure> {
+        std::mem::take(&mut *self.failures.lock().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stage_counter_snapshot() {
+        let counter = StageCounter::default();
+        counter.observe_queue(2);
+        counter.observe_queue(6);
+        counter.record(Duration::from_millis(10), true);
+        counter.record(Duration::from_millis(30), false);
+
+        let metrics = counter.snapshot();
+        assert_eq!(metrics.items_processed, 1);
+        assert_eq!(metrics.failures, 1);
+        assert!((metrics.avg_latency_ms - 20.0).abs() < 1e-6);
+        assert!((metrics.avg_queue_depth - 4.0).abs() < 1e-6);
+        assert_eq!(metrics.max_queue_depth, 6);
+    }
+}
@@ -188,6 +188,10 @@ pub struct ProcessedContent {
     pub metadata: HashMap<String, serde_json::Value>,
     /// Content chunks for semantic processing
     pub chunks: Vec<ContentChunk>,
+    /// Problems that did not prevent processing, such as parts of the
+    /// document with no extractable text
+    #[serde(default)]
+    pub warnings: Vec<String>,
 }
 
 impl ProcessedContent {
@@ -199,6 +203,7 @@ impl ProcessedContent {
             structured_data: None,
             metadata: HashMap::new(),
             chunks: Vec::new(),
+            warnings: Vec::new(),
         }
     }
 
@@ -220,6 +225,12 @@ impl ProcessedContent {
         self
     }
 
+    /// Add warnings
+    pub fn with_warnings(mut self, warnings: Vec<String>) -> Self {
+        self.warnings = warnings;
+        self
+    }
+
     /// Get total character count
     pub fn char_count(&self) -> usize {
         self.text_content.chars().count()
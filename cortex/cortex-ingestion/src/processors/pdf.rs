@@ -4,9 +4,185 @@ use super::{ChunkType, ContentChunk, ContentProcessor, ContentType, ProcessedCon
 use async_trait::async_trait;
 use cortex_core::error::{CortexError, Result};
 use pdf_extract::extract_text_from_mem;
-use lopdf::Document as PdfDocument;
+use lopdf::content::Content;
+use lopdf::{Document as PdfDocument, Object, ObjectId};
 use std::collections::HashMap;
 
+/// Text shown at one position of a page, in page space
+#[derive(Debug, Clone)]
+struct TextRun {
+    x: f32,
+    y: f32,
+    size: f32,
+    text: String,
+}
+
+impl TextRun {
+    /// Estimated end of the run; actual glyph widths are not looked up
+    fn end_x(&self) -> f32 {
+        self.x + self.text.chars().count() as f32 * self.size * 0.5
+    }
+}
+
+/// A line of a page, split into cells at wide horizontal gaps
+#[derive(Debug, Clone)]
+struct LayoutLine {
+    size: f32,
+    /// Start position and text of each cell
+    cells: Vec<(f32, String)>,
+}
+
+impl LayoutLine {
+    fn text(&self) -> String {
+        let cells: Vec<&str> = self.cells.iter().map(|(_, text)| text.as_str()).collect();
+        cells.join(" ")
+    }
+}
+
+/// Text lines of one page; `fallback_text` is set when the content stream
+/// could not be laid out
+#[derive(Debug, Clone)]
+struct PageLayout {
+    number: u32,
+    lines: Vec<LayoutLine>,
+    fallback_text: String,
+}
+
+impl PageLayout {
+    fn is_empty(&self) -> bool {
+        self.lines.is_empty() && self.fallback_text.trim().is_empty()
+    }
+}
+
+/// Part of a page, in reading order
+enum PageBlock<'a> {
+    Heading(usize, &'a LayoutLine),
+    Text(&'a LayoutLine),
+    Table(&'a [LayoutLine]),
+}
+
+type Matrix = [f32; 6];
+
+const IDENTITY: Matrix = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+
+fn multiply(m: &Matrix, n: &Matrix) -> Matrix {
+    [
+        m[0] * n[0] + m[1] * n[2],
+        m[0] * n[1] + m[1] * n[3],
+        m[2] * n[0] + m[3] * n[2],
+        m[2] * n[1] + m[3] * n[3],
+        m[4] * n[0] + m[5] * n[2] + n[4],
+        m[4] * n[1] + m[5] * n[3] + n[5],
+    ]
+}
+
+fn translation(tx: f32, ty: f32) -> Matrix {
+    [1.0, 0.0, 0.0, 1.0, tx, ty]
+}
+
+/// Decode a PDF string of a simple font. Multi-byte encodings of composite
+/// fonts are not supported and yield `None`
+fn decode_pdf_string(bytes: &[u8]) -> Option<String> {
+    let text = if let Some(utf16) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        let units: Vec<u16> = utf16
+            .chunks_exact(2)
+            .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+            .collect();
+        String::from_utf16_lossy(&units)
+    } else {
+        bytes.iter().map(|&b| b as char).collect()
+    };
+    if text.chars().any(|c| c.is_control() && !c.is_whitespace()) {
+        None
+    } else {
+        Some(text)
+    }
+}
+
+/// Builds section and table chunks page by page, keeping track of the
+/// heading path across pages
+#[derive(Default)]
+struct SectionBuilder {
+    /// Level and title of the enclosing headings, outermost first
+    headings: Vec<(usize, String)>,
+    lines: Vec<String>,
+    has_body: bool,
+    chunks: Vec<ContentChunk>,
+    outline: Vec<serde_json::Value>,
+}
+
+impl SectionBuilder {
+    fn heading(&mut self, level: usize, title: String, page: u32) {
+        self.flush(page);
+        self.headings.retain(|(l, _)| *l < level);
+        self.headings.push((level, title.clone()));
+        self.outline.push(serde_json::json!({
+            "level": level,
+            "title": title,
+            "page": page,
+        }));
+        self.lines.push(title);
+    }
+
+    fn text(&mut self, line: String) {
+        self.lines.push(line);
+        self.has_body = true;
+    }
+
+    fn table(&mut self, rows: &[LayoutLine], page: u32) {
+        self.flush(page);
+        let column_count = rows[0].cells.len();
+        let render = |row: &LayoutLine| {
+            let cells: Vec<String> = row
+                .cells
+                .iter()
+                .map(|(_, text)| text.replace('|', "\\|"))
+                .collect();
+            format!("| {} |", cells.join(" | "))
+        };
+
+        let mut markdown = vec![render(&rows[0])];
+        markdown.push(format!("|{}", " --- |".repeat(column_count)));
+        markdown.extend(rows[1..].iter().map(render));
+
+        let chunk = ContentChunk::new(markdown.join("\n"), ChunkType::Table)
+            .with_metadata("row_count".to_string(), serde_json::json!(rows.len()))
+            .with_metadata("column_count".to_string(), serde_json::json!(column_count));
+        let chunk = self.annotate(chunk, page);
+        self.chunks.push(chunk);
+    }
+
+    /// Emit the lines collected so far; a heading with no body yet is not
+    /// worth a chunk of its own
+    fn flush(&mut self, page: u32) {
+        let lines = std::mem::take(&mut self.lines);
+        if !std::mem::take(&mut self.has_body) {
+            return;
+        }
+        let chunk_type = if self.headings.is_empty() {
+            ChunkType::Page
+        } else {
+            ChunkType::Section
+        };
+        let chunk = self.annotate(ContentChunk::new(lines.join("\n"), chunk_type), page);
+        self.chunks.push(chunk);
+    }
+
+    fn annotate(&self, chunk: ContentChunk, page: u32) -> ContentChunk {
+        let chunk = chunk.with_metadata("page_number".to_string(), serde_json::json!(page));
+        match self.headings.last() {
+            Some((level, title)) => {
+                let path: Vec<&str> = self.headings.iter().map(|(_, t)| t.as_str()).collect();
+                chunk
+                    .with_metadata("heading".to_string(), serde_json::json!(title))
+                    .with_metadata("heading_level".to_string(), serde_json::json!(level))
+                    .with_metadata("heading_path".to_string(), serde_json::json!(path))
+            }
+            None => chunk,
+        }
+    }
+}
+
 /// Processor for PDF documents
 pub struct PdfProcessor {
     chunk_by_page: bool,
@@ -123,6 +299,280 @@ impl PdfProcessor {
         images
     }
 
+    /// Text runs of a page, positioned by following the text and graphics
+    /// state of its content stream
+    fn extract_runs(pdf: &PdfDocument, page_id: ObjectId) -> Vec<TextRun> {
+        let Some(content) = pdf
+            .get_page_content(page_id)
+            .ok()
+            .and_then(|bytes| Content::decode(&bytes).ok())
+        else {
+            return Vec::new();
+        };
+
+        let mut runs = Vec::new();
+        let mut ctm = IDENTITY;
+        let mut saved = Vec::new();
+        let mut tm = IDENTITY;
+        let mut tlm = IDENTITY;
+        let mut font_size = 0.0;
+        let mut leading = 0.0;
+
+        let next_line = |tlm: &mut Matrix, tm: &mut Matrix, leading: f32| {
+            *tlm = multiply(&translation(0.0, -leading), tlm);
+            *tm = *tlm;
+        };
+
+        for op in &content.operations {
+            let nums: Vec<f32> = op
+                .operands
+                .iter()
+                .filter_map(|o| o.as_float().ok())
+                .collect();
+
+            let shown = match op.operator.as_str() {
+                "q" => {
+                    saved.push(ctm);
+                    None
+                }
+                "Q" => {
+                    ctm = saved.pop().unwrap_or(IDENTITY);
+                    None
+                }
+                "cm" if nums.len() == 6 => {
+                    ctm = multiply(
+                        &[nums[0], nums[1], nums[2], nums[3], nums[4], nums[5]],
+                        &ctm,
+                    );
+                    None
+                }
+                "BT" => {
+                    tm = IDENTITY;
+                    tlm = IDENTITY;
+                    None
+                }
+                "Tf" => {
+                    font_size = nums.last().copied().unwrap_or(font_size);
+                    None
+                }
+                "TL" => {
+                    leading = nums.first().copied().unwrap_or(leading);
+                    None
+                }
+                "Td" | "TD" if nums.len() == 2 => {
+                    if op.operator == "TD" {
+                        leading = -nums[1];
+                    }
+                    tlm = multiply(&translation(nums[0], nums[1]), &tlm);
+                    tm = tlm;
+                    None
+                }
+                "Tm" if nums.len() == 6 => {
+                    tlm = [nums[0], nums[1], nums[2], nums[3], nums[4], nums[5]];
+                    tm = tlm;
+                    None
+                }
+                "T*" => {
+                    next_line(&mut tlm, &mut tm, leading);
+                    None
+                }
+                "Tj" => op
+                    .operands
+                    .first()
+                    .and_then(|o| o.as_str().ok())
+                    .and_then(decode_pdf_string),
+                "'" | "\"" => {
+                    next_line(&mut tlm, &mut tm, leading);
+                    op.operands
+                        .last()
+                        .and_then(|o| o.as_str().ok())
+                        .and_then(decode_pdf_string)
+                }
+                "TJ" => op
+                    .operands
+                    .first()
+                    .and_then(|o| o.as_array().ok())
+                    .and_then(|parts| {
+                        let mut text = String::new();
+                        for part in parts {
+                            match part {
+                                Object::String(bytes, _) => {
+                                    text.push_str(&decode_pdf_string(bytes)?)
+                                }
+                                // Large negative adjustments separate words
+                                other => {
+                                    if other.as_float().is_ok_and(|adjust| adjust < -250.0) {
+                                        text.push(' ');
+                                    }
+                                }
+                            }
+                        }
+                        Some(text)
+                    }),
+                _ => None,
+            };
+
+            let Some(text) = shown.filter(|text| !text.trim().is_empty()) else {
+                continue;
+            };
+            let position = multiply(&tm, &ctm);
+            let run = TextRun {
+                x: position[4],
+                y: position[5],
+                size: font_size * position[2].hypot(position[3]),
+                text,
+            };
+            let advance = run.text.chars().count() as f32 * font_size * 0.5;
+            tm = multiply(&translation(advance, 0.0), &tm);
+            runs.push(run);
+        }
+
+        runs
+    }
+
+    /// Group runs into lines, top to bottom, and split each line into cells
+    fn group_lines(mut runs: Vec<TextRun>) -> Vec<LayoutLine> {
+        runs.sort_by(|a, b| b.y.total_cmp(&a.y).then(a.x.total_cmp(&b.x)));
+
+        let mut rows: Vec<Vec<TextRun>> = Vec::new();
+        for run in runs {
+            match rows.last_mut() {
+                Some(row) if (row[0].y - run.y).abs() <= row[0].size.max(run.size) * 0.5 => {
+                    row.push(run)
+                }
+                _ => rows.push(vec![run]),
+            }
+        }
+
+        rows.into_iter()
+            .map(|mut row| {
+                row.sort_by(|a, b| a.x.total_cmp(&b.x));
+                let size = row.iter().map(|run| run.size).fold(0.0, f32::max);
+                let mut cells: Vec<(f32, String)> = Vec::new();
+                let mut cell_end = f32::MIN;
+                for run in row {
+                    let gap = run.x - cell_end;
+                    match cells.last_mut() {
+                        Some((_, text)) if gap <= size * 1.5 => {
+                            if gap > size * 0.1 && !text.ends_with(' ') {
+                                text.push(' ');
+                            }
+                            text.push_str(run.text.trim_start());
+                        }
+                        _ => cells.push((run.x, run.text.trim().to_string())),
+                    }
+                    cell_end = run.end_x();
+                }
+                for (_, text) in &mut cells {
+                    *text = text.trim().to_string();
+                }
+                LayoutLine { size, cells }
+            })
+            .collect()
+    }
+
+    /// Lay out every page; pages whose content stream cannot be followed
+    /// fall back to plain text extraction
+    fn layout_pages(pdf: &PdfDocument) -> Vec<PageLayout> {
+        let fallback: HashMap<u32, String> = Self::extract_pages(pdf)
+            .map(|pages| pages.into_iter().collect())
+            .unwrap_or_default();
+
+        pdf.get_pages()
+            .into_iter()
+            .map(|(number, page_id)| {
+                let lines = Self::group_lines(Self::extract_runs(pdf, page_id));
+                PageLayout {
+                    number,
+                    fallback_text: if lines.is_empty() {
+                        fallback.get(&number).cloned().unwrap_or_default()
+                    } else {
+                        String::new()
+                    },
+                    lines,
+                }
+            })
+            .collect()
+    }
+
+    /// Font sizes used for headings, largest (level 1) first. Body text is
+    /// the size covering the most characters; single-cell lines set clearly
+    /// larger than that are headings
+    fn heading_sizes(pages: &[PageLayout]) -> Vec<f32> {
+        let round = |size: f32| (size * 2.0).round() / 2.0;
+
+        let mut chars_by_size: HashMap<u32, usize> = HashMap::new();
+        for line in pages.iter().flat_map(|page| &page.lines) {
+            *chars_by_size.entry(round(line.size).to_bits()).or_default() += line.text().len();
+        }
+        let Some(body) = chars_by_size
+            .iter()
+            .max_by_key(|(size, chars)| (**chars, **size))
+            .map(|(size, _)| f32::from_bits(*size))
+        else {
+            return Vec::new();
+        };
+
+        let mut sizes: Vec<f32> = pages
+            .iter()
+            .flat_map(|page| &page.lines)
+            .filter(|line| line.cells.len() == 1 && line.text().len() < 100)
+            .map(|line| round(line.size))
+            .filter(|size| *size > body * 1.15)
+            .collect();
+        sizes.sort_by(|a, b| b.total_cmp(a));
+        sizes.dedup();
+        sizes.truncate(6);
+        sizes
+    }
+
+    /// Classify the lines of a page into headings, tables and text. A table
+    /// is two or more consecutive lines with the same number of cells, at
+    /// least two, whose columns start at the same positions
+    fn page_blocks<'a>(page: &'a PageLayout, heading_sizes: &[f32]) -> Vec<PageBlock<'a>> {
+        let heading_level = |line: &LayoutLine| {
+            if line.cells.len() != 1 {
+                return None;
+            }
+            let size = (line.size * 2.0).round() / 2.0;
+            heading_sizes.iter().position(|s| *s == size).map(|i| i + 1)
+        };
+        let aligned = |a: &LayoutLine, b: &LayoutLine| {
+            a.cells.len() == b.cells.len()
+                && a.cells
+                    .iter()
+                    .zip(&b.cells)
+                    .all(|((xa, _), (xb, _))| (xa - xb).abs() <= a.size.max(b.size) * 2.0)
+        };
+
+        let lines = &page.lines;
+        let mut blocks = Vec::new();
+        let mut i = 0;
+        while i < lines.len() {
+            let line = &lines[i];
+            if let Some(level) = heading_level(line) {
+                blocks.push(PageBlock::Heading(level, line));
+                i += 1;
+                continue;
+            }
+
+            let mut end = i + 1;
+            if line.cells.len() >= 2 {
+                while end < lines.len() && aligned(line, &lines[end]) {
+                    end += 1;
+                }
+            }
+            if end - i >= 2 {
+                blocks.push(PageBlock::Table(&lines[i..end]));
+                i = end;
+            } else {
+                blocks.push(PageBlock::Text(line));
+                i += 1;
+            }
+        }
+        blocks
+    }
+
     /// Extract text per page using lopdf
     fn extract_pages(pdf: &PdfDocument) -> Result<Vec<(u32, String)>> {
         let pages = pdf.get_pages();
@@ -155,13 +605,27 @@ impl ContentProcessor for PdfProcessor {
     async fn process(&self, input: &[u8]) -> Result<ProcessedContent> {
         tracing::debug!("Processing PDF document ({} bytes)", input.len());
 
-        // Extract full text using pdf-extract (more robust)
-        let text_content = extract_text_from_mem(input)
-            .map_err(|e| CortexError::ingestion(format!("Failed to extract PDF text: {}", e)))?;
-
-        // Parse PDF for metadata using lopdf
+        // Parse PDF for metadata and layout using lopdf
         let pdf = PdfDocument::load_mem(input)
             .map_err(|e| CortexError::ingestion(format!("Failed to load PDF: {}", e)))?;
+        let pages = Self::layout_pages(&pdf);
+
+        // Extract full text using pdf-extract (more robust), falling back to
+        // the laid out pages
+        let text_content = match extract_text_from_mem(input) {
+            Ok(text) => text,
+            Err(e) => {
+                tracing::warn!("pdf-extract failed, using page layout text: {}", e);
+                pages
+                    .iter()
+                    .map(|page| {
+                        let lines: Vec<String> = page.lines.iter().map(LayoutLine::text).collect();
+                        format!("{}{}", lines.join("\n"), page.fallback_text)
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n\n")
+            }
+        };
 
         let mut metadata = Self::extract_metadata(&pdf);
 
@@ -191,35 +655,60 @@ impl ContentProcessor for PdfProcessor {
             );
         }
 
-        let mut chunks = Vec::new();
-
-        if self.chunk_by_page {
-            // Extract text per page for chunking
-            match Self::extract_pages(&pdf) {
-                Ok(pages) => {
-                    for (page_num, page_text) in pages {
-                        if !page_text.trim().is_empty() {
-                            let chunk = ContentChunk::new(page_text, ChunkType::Page)
-                                .with_metadata(
-                                    "page_number".to_string(),
-                                    serde_json::Value::Number(page_num.into()),
-                                );
-                            chunks.push(chunk);
+        // Pages without any text are most likely scanned
+        let scanned: Vec<u32> = pages
+            .iter()
+            .filter(|page| page.is_empty())
+            .map(|page| page.number)
+            .collect();
+        let warnings: Vec<String> = scanned
+            .iter()
+            .map(|page| {
+                format!(
+                    "Page {} has no extractable text; it may be scanned or image-only",
+                    page
+                )
+            })
+            .collect();
+        if !scanned.is_empty() {
+            metadata.insert("scanned_pages".to_string(), serde_json::json!(scanned));
+        }
+
+        let chunks = if self.chunk_by_page {
+            let heading_sizes = Self::heading_sizes(&pages);
+            let mut sections = SectionBuilder::default();
+            for page in &pages {
+                for block in Self::page_blocks(page, &heading_sizes) {
+                    match block {
+                        PageBlock::Heading(level, line) => {
+                            sections.heading(level, line.text(), page.number)
                         }
+                        PageBlock::Text(line) => sections.text(line.text()),
+                        PageBlock::Table(rows) => sections.table(rows, page.number),
                     }
                 }
-                Err(e) => {
-                    tracing::warn!("Failed to extract pages, using full text: {}", e);
-                    // Fall back to full document as single chunk
-                    chunks.push(ContentChunk::new(text_content.clone(), ChunkType::Document));
+                for line in page.fallback_text.lines().filter(|l| !l.trim().is_empty()) {
+                    sections.text(line.trim().to_string());
                 }
+                sections.flush(page.number);
             }
+            if !sections.outline.is_empty() {
+                metadata.insert(
+                    "outline".to_string(),
+                    serde_json::Value::Array(std::mem::take(&mut sections.outline)),
+                );
+            }
+            sections.chunks
+        } else if text_content.trim().is_empty() {
+            Vec::new()
         } else {
             // Single chunk for entire document
-            chunks.push(ContentChunk::new(text_content.clone(), ChunkType::Document));
-        }
+            vec![ContentChunk::new(text_content.clone(), ChunkType::Document)]
+        };
 
-        let mut result = ProcessedContent::new(ContentType::Pdf, text_content).with_chunks(chunks);
+        let mut result = ProcessedContent::new(ContentType::Pdf, text_content)
+            .with_chunks(chunks)
+            .with_warnings(warnings);
         metadata.insert("format".to_string(), serde_json::Value::String("pdf".to_string()));
         result.metadata = metadata;
         Ok(result)
@@ -258,4 +747,62 @@ mod tests {
         assert_eq!(processor.supported_mime_types(), vec!["application/pdf"]);
         assert_eq!(processor.content_type(), ContentType::Pdf);
     }
+
+    #[tokio::test]
+    async fn test_pdf_processor_tables_headings_and_scanned_pages() {
+        // Page 1: nested headings and a two-column table; page 2: an image only
+        let input = include_bytes!("../../tests/fixtures/report_with_table.pdf");
+        let processed = PdfProcessor::new().process(input).await.unwrap();
+
+        let tables: Vec<&ContentChunk> = processed
+            .chunks
+            .iter()
+            .filter(|c| c.chunk_type == ChunkType::Table)
+            .collect();
+        assert_eq!(tables.len(), 1);
+        let table = tables[0];
+        assert_eq!(
+            table.content,
+            "| Region | Revenue |\n| --- | --- |\n| North | 120 |\n| South | 95 |"
+        );
+        assert_eq!(table.metadata["page_number"], 1);
+        assert_eq!(table.metadata["heading"], "Regional Figures");
+        assert_eq!(table.metadata["heading_level"], 3);
+        assert_eq!(
+            table.metadata["heading_path"],
+            serde_json::json!(["Quarterly Report", "Revenue", "Regional Figures"])
+        );
+
+        let section = |needle: &str| -> ContentChunk {
+            processed
+                .chunks
+                .iter()
+                .find(|c| c.chunk_type == ChunkType::Section && c.content.contains(needle))
+                .cloned()
+                .unwrap_or_else(|| panic!("no section containing {:?}", needle))
+        };
+        let revenue = section("Sales grew");
+        assert!(revenue.content.starts_with("Revenue\n"));
+        assert_eq!(revenue.metadata["heading_level"], 2);
+        assert_eq!(
+            section("Growth is expected").metadata["heading_path"],
+            serde_json::json!(["Quarterly Report", "Outlook"])
+        );
+        assert_eq!(
+            section("Totals exclude").metadata["heading"],
+            "Regional Figures"
+        );
+        assert_eq!(processed.metadata["outline"].as_array().unwrap().len(), 4);
+
+        // The image-only page is reported instead of chunked
+        assert!(
+            processed
+                .chunks
+                .iter()
+                .all(|c| c.metadata["page_number"] == 1)
+        );
+        assert_eq!(processed.warnings.len(), 1);
+        assert!(processed.warnings[0].contains("Page 2"));
+        assert_eq!(processed.metadata["scanned_pages"], serde_json::json!([2]));
+    }
 }
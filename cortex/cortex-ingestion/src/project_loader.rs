@@ -6,6 +6,7 @@
 
 use crate::extractor::{extract_comprehensive_metadata, detect_programming_language};
 use crate::filters::{should_ignore_dir, should_ignore_file};
+use crate::pipeline::{IngestFailure, PipelineMetrics, PipelineStage};
 use crate::processors::{detect_content_type, ProcessorFactory};
 use cortex_core::error::{CortexError, Result};
 use cortex_core::id::CortexId;
@@ -68,6 +69,10 @@ pub struct ImportReport {
     pub bytes_processed: u64,
     /// Import duration
     pub duration_secs: f64,
+    /// Files that failed, with the stage that failed them
+    pub failures: Vec<IngestFailure>,
+    /// Per-stage metrics, for pipelined ingestion runs
+    pub pipeline: Option<PipelineMetrics>,
 }
 
 impl ImportReport {
@@ -79,8 +84,15 @@ impl ImportReport {
             errors: 0,
             bytes_processed: 0,
             duration_secs: 0.0,
+            failures: Vec::new(),
+            pipeline: None,
         }
     }
+
+    /// Number of failures produced by `stage`
+    pub fn failures_in(&self, stage: PipelineStage) -> usize {
+        self.failures.iter().filter(|f| f.stage == stage).count()
+    }
 }
 
 impl Default for ImportReport {
@@ -202,6 +214,11 @@ impl ProjectLoader {
                                 tracing::warn!("Failed to import {}: {}", path.display(), e);
                                 report.errors += 1;
                                 report.files_skipped += 1;
+                                report.failures.push(IngestFailure {
+                                    path: path.to_path_buf(),
+                                    stage: PipelineStage::Read,
+                                    error: e.to_string(),
+                                });
                             }
                         }
                     }
@@ -209,6 +226,11 @@ impl ProjectLoader {
                 Err(e) => {
                     tracing::warn!("Walker error: {}", e);
                     report.errors += 1;
+                    report.failures.push(IngestFailure {
+                        path: source_path.to_path_buf(),
+                        stage: PipelineStage::Walk,
+                        error: e.to_string(),
+                    });
                 }
             }
         }
@@ -339,6 +339,7 @@ fn test_embedding_config() {
         batch_size: 32,
         cache_enabled: true,
         max_text_length: 8000,
+        ..Default::default()
     };
 
     assert_eq!(config.batch_size, 32);
@@ -638,6 +638,8 @@ mod tests {
                 validate_on_checkout: true,
                 recycle_after_uses: Some(1000),
                 shutdown_grace_period: Duration::from_secs(5),
+                query_timeout: Some(Duration::from_secs(30)),
+                slow_query_threshold: Some(Duration::from_secs(1)),
             },
             namespace: "test".to_string(),
             database: "test".to_string(),
@@ -62,6 +62,16 @@ pub use consolidation::MemoryConsolidator;
 pub use cognitive::CognitiveManager;
 pub use query::CrossMemoryQuery;
 
+/// Version of the data the memory systems keep in the database (episodes,
+/// code units, patterns). Bump when their stored shape changes, and register
+/// a migration for the new version with the startup migration runner.
+pub const COMPONENT_VERSION: cortex_core::versioning::ComponentVersion =
+    cortex_core::versioning::ComponentVersion::new(
+        "cortex-memory",
+        1,
+        &[cortex_storage::schema::COMPONENT_NAME],
+    );
+
 /// Re-export commonly used types
 pub mod prelude {
     pub use crate::types::*;
@@ -14,6 +14,9 @@ use std::sync::Arc;
 use tracing::{debug, info};
 use uuid::Uuid;
 
+/// Key in `CodeUnit::metadata` naming the workspace a unit was ingested into
+pub const WORKSPACE_METADATA_KEY: &str = "workspace_id";
+
 /// Semantic memory system for code understanding
 pub struct SemanticMemorySystem {
     connection_manager: Arc<ConnectionManager>,
@@ -192,13 +195,11 @@ impl SemanticMemorySystem {
         ";
 
         let mut result = conn
-            .connection()
             .query(query_str)
             .bind(("query_embedding", embedding.to_vec()))
             .bind(("threshold", 1.0 - query.similarity_threshold))
             .bind(("limit", query.limit))
-            .await
-            .map_err(|e| CortexError::storage(e.to_string()))?;
+            .await?;
 
         let units: Vec<(CodeUnit, f32)> = result.take(0).map_err(|e| CortexError::storage(e.to_string()))?;
 
@@ -225,11 +226,9 @@ impl SemanticMemorySystem {
 
         let query = Self::build_select_query("WHERE file_path = $path", Some("ORDER BY start_line ASC"));
         let mut result = conn
-            .connection()
             .query(&query)
             .bind(("path", file_path.to_string()))
-            .await
-            .map_err(|e| CortexError::storage(e.to_string()))?;
+            .await?;
 
         let units: Vec<serde_json::Value> = result.take(0).map_err(|e| CortexError::storage(e.to_string()))?;
         Self::process_unit_results(units)
@@ -250,11 +249,9 @@ impl SemanticMemorySystem {
             Some("ORDER BY start_line ASC")
         );
         let mut result = conn
-            .connection()
             .query(&query)
             .bind(("path", file_path.to_string()))
-            .await
-            .map_err(|e| CortexError::storage(e.to_string()))?;
+            .await?;
 
         let units: Vec<serde_json::Value> = result.take(0).map_err(|e| CortexError::storage(e.to_string()))?;
         Self::process_unit_results(units)
@@ -275,11 +272,9 @@ impl SemanticMemorySystem {
             Some("ORDER BY start_line ASC, created_at DESC")
         );
         let mut result = conn
-            .connection()
             .query(&query)
             .bind(("path", file_path.to_string()))
-            .await
-            .map_err(|e| CortexError::storage(e.to_string()))?;
+            .await?;
 
         let units: Vec<serde_json::Value> = result.take(0).map_err(|e| CortexError::storage(e.to_string()))?;
         Self::process_unit_results(units)
@@ -307,6 +302,40 @@ impl SemanticMemorySystem {
         Ok(())
     }
 
+    /// Delete a workspace's code units for a file, or for every file under a
+    /// directory path. Used when files are removed from disk so their units
+    /// stop matching searches; units of other workspaces sharing the same
+    /// relative path are kept.
+    pub async fn delete_units_by_path(&self, workspace_id: &Uuid, path: &str) -> Result<usize> {
+        debug!(%workspace_id, path, "Deleting units by path");
+
+        let conn = self
+            .connection_manager
+            .acquire()
+            .await?;
+
+        let prefix = format!("{}/", path.trim_end_matches('/'));
+        let query = format!(
+            "DELETE code_unit WHERE metadata.{} = $workspace_id \
+             AND (file_path = $path OR string::starts_with(file_path, $prefix)) RETURN BEFORE",
+            WORKSPACE_METADATA_KEY
+        );
+        let mut result = conn
+            .connection()
+            .query(query)
+            .bind(("workspace_id", workspace_id.to_string()))
+            .bind(("path", path.to_string()))
+            .bind(("prefix", prefix))
+            .await
+            .map_err(|e| CortexError::storage(format!("Failed to delete units: {}", e)))?;
+
+        let deleted: Vec<String> = result.take((0, "file_path"))
+            .map_err(|e| CortexError::storage(e.to_string()))?;
+
+        debug!(path, deleted = deleted.len(), "Units deleted");
+        Ok(deleted.len())
+    }
+
     /// Search units by qualified name (e.g., "module::Class::method")
     pub async fn find_by_qualified_name(&self, qualified_name: &str) -> Result<Option<CodeUnit>> {
         debug!(qualified_name, "Finding unit by qualified name");
@@ -318,11 +347,9 @@ impl SemanticMemorySystem {
 
         let query = Self::build_select_query("WHERE qualified_name = $name", None);
         let mut result = conn
-            .connection()
             .query(&query)
             .bind(("name", qualified_name.to_string()))
-            .await
-            .map_err(|e| CortexError::storage(e.to_string()))?;
+            .await?;
 
         let units: Vec<serde_json::Value> = result.take(0).map_err(|e| CortexError::storage(e.to_string()))?;
         let mut processed = Self::process_unit_results(units)?;
@@ -340,11 +367,9 @@ impl SemanticMemorySystem {
 
         let query = Self::build_select_query("WHERE name = $name", None);
         let mut result = conn
-            .connection()
             .query(&query)
             .bind(("name", name.to_string()))
-            .await
-            .map_err(|e| CortexError::storage(e.to_string()))?;
+            .await?;
 
         let units: Vec<serde_json::Value> = result.take(0).map_err(|e| CortexError::storage(e.to_string()))?;
         Self::process_unit_results(units)
@@ -407,11 +432,9 @@ impl SemanticMemorySystem {
         // Convert the enum field to string and use cortex_id for proper deserialization
         let query = "SELECT cortex_id, source_id, target_id, type::string(dependency_type) as dependency_type, is_direct, is_runtime, is_dev, metadata FROM DEPENDS_ON WHERE source_id = $unit_id";
         let mut result = conn
-            .connection()
             .query(query)
             .bind(("unit_id", unit_id.to_string()))
-            .await
-            .map_err(|e| CortexError::storage(e.to_string()))?;
+            .await?;
 
         let deps_json: Vec<serde_json::Value> = result.take(0).map_err(|e| CortexError::storage(e.to_string()))?;
 
@@ -439,11 +462,9 @@ impl SemanticMemorySystem {
         // Convert the enum field to string and use cortex_id for proper deserialization
         let query = "SELECT cortex_id, source_id, target_id, type::string(dependency_type) as dependency_type, is_direct, is_runtime, is_dev, metadata FROM DEPENDS_ON WHERE target_id = $unit_id";
         let mut result = conn
-            .connection()
             .query(query)
             .bind(("unit_id", unit_id.to_string()))
-            .await
-            .map_err(|e| CortexError::storage(e.to_string()))?;
+            .await?;
 
         let deps_json: Vec<serde_json::Value> = result.take(0).map_err(|e| CortexError::storage(e.to_string()))?;
 
@@ -550,11 +571,9 @@ impl SemanticMemorySystem {
 
         let query = Self::build_select_query("WHERE complexity.cyclomatic > $threshold", Some("ORDER BY complexity.cyclomatic DESC"));
         let mut result = conn
-            .connection()
             .query(&query)
             .bind(("threshold", complexity_threshold))
-            .await
-            .map_err(|e| CortexError::storage(e.to_string()))?;
+            .await?;
 
         let units: Vec<serde_json::Value> = result.take(0).map_err(|e| CortexError::storage(e.to_string()))?;
         Self::process_unit_results(units)
@@ -571,10 +590,8 @@ impl SemanticMemorySystem {
 
         let query = Self::build_select_query("WHERE has_tests = false AND unit_type IN ['function', 'method', 'class']", None);
         let mut result = conn
-            .connection()
             .query(&query)
-            .await
-            .map_err(|e| CortexError::storage(e.to_string()))?;
+            .await?;
 
         let units: Vec<serde_json::Value> = result.take(0).map_err(|e| CortexError::storage(e.to_string()))?;
         Self::process_unit_results(units)
@@ -591,10 +608,8 @@ impl SemanticMemorySystem {
 
         let query = Self::build_select_query("WHERE has_documentation = false AND visibility = 'public'", None);
         let mut result = conn
-            .connection()
             .query(&query)
-            .await
-            .map_err(|e| CortexError::storage(e.to_string()))?;
+            .await?;
 
         let units: Vec<serde_json::Value> = result.take(0).map_err(|e| CortexError::storage(e.to_string()))?;
         Self::process_unit_results(units)
@@ -655,19 +670,15 @@ impl SemanticMemorySystem {
         ";
 
         let mut result = conn
-            .connection()
             .query(query)
-            .await
-            .map_err(|e| CortexError::storage(e.to_string()))?;
+            .await?;
 
         let stats: Option<serde_json::Value> = result.take(0).map_err(|e| CortexError::storage(e.to_string()))?;
 
         // Get dependency count
         let mut dep_result = conn
-            .connection()
             .query("SELECT count() AS total FROM DEPENDS_ON GROUP ALL")
-            .await
-            .map_err(|e| CortexError::storage(e.to_string()))?;
+            .await?;
 
         let dep_stats: Option<serde_json::Value> = dep_result.take(0).map_err(|e| CortexError::storage(e.to_string()))?;
 
@@ -710,11 +721,9 @@ impl SemanticMemorySystem {
         // Delete code units created before the specified date
         let query = "DELETE code_unit WHERE created_at < $before";
         let mut result = conn
-            .connection()
             .query(query)
             .bind(("before", before.to_rfc3339()))
-            .await
-            .map_err(|e| CortexError::storage(e.to_string()))?;
+            .await?;
 
         // SurrealDB DELETE returns the deleted records
         let deleted_json: Vec<serde_json::Value> = result.take(0)
@@ -760,6 +769,8 @@ mod tests {
                 validate_on_checkout: false,
                 recycle_after_uses: None,
                 shutdown_grace_period: Duration::from_secs(5),
+                query_timeout: Some(Duration::from_secs(30)),
+                slow_query_threshold: Some(Duration::from_secs(1)),
             },
             namespace: "test".to_string(),
             database: "test".to_string(),
@@ -769,17 +780,14 @@ mod tests {
         SemanticMemorySystem::new(manager)
     }
 
-    #[tokio::test]
-    async fn test_store_and_retrieve_unit() {
-        let memory = create_test_memory().await;
-
-        let unit = CodeUnit {
+    fn test_unit(name: &str, file_path: &str) -> CodeUnit {
+        CodeUnit {
             id: CortexId::new(),
             unit_type: CoreCodeUnitType::Function,
-            name: "test_function".to_string(),
-            qualified_name: "module::test_function".to_string(),
-            display_name: "test_function".to_string(),
-            file_path: "src/test.rs".to_string(),
+            name: name.to_string(),
+            qualified_name: format!("module::{}", name),
+            display_name: name.to_string(),
+            file_path: file_path.to_string(),
             language: Language::Rust,
             start_line: 10,
             start_column: 0,
@@ -835,7 +843,14 @@ mod tests {
             updated_by: "system".to_string(),
             tags: vec![],
             metadata: HashMap::new(),
-        };
+        }
+    }
+
+    #[tokio::test]
+    async fn test_store_and_retrieve_unit() {
+        let memory = create_test_memory().await;
+
+        let unit = test_unit("test_function", "src/test.rs");
 
         let id = memory
             .store_unit(&unit)
@@ -884,6 +899,45 @@ mod tests {
         assert_eq!(deps[0].target_id, target_id);
     }
 
+    #[tokio::test]
+    async fn test_delete_units_by_path() {
+        let memory = create_test_memory().await;
+        let workspace_id = Uuid::new_v4();
+        let other_workspace_id = Uuid::new_v4();
+
+        for (name, path) in [
+            ("a", "src/lib.rs"),
+            ("b", "src/net/client.rs"),
+            ("c", "src/net/server.rs"),
+            ("d", "src/network.rs"),
+        ] {
+            let mut unit = test_unit(name, path);
+            unit.metadata.insert(
+                WORKSPACE_METADATA_KEY.to_string(),
+                serde_json::json!(workspace_id.to_string()),
+            );
+            memory.store_unit(&unit).await.unwrap();
+        }
+
+        let mut other = test_unit("e", "src/lib.rs");
+        other.metadata.insert(
+            WORKSPACE_METADATA_KEY.to_string(),
+            serde_json::json!(other_workspace_id.to_string()),
+        );
+        memory.store_unit(&other).await.unwrap();
+
+        // Another workspace's unit at the same relative path is kept
+        let deleted = memory.delete_units_by_path(&workspace_id, "src/lib.rs").await.unwrap();
+        assert_eq!(deleted, 1);
+        assert_eq!(memory.get_units_in_file("src/lib.rs").await.unwrap().len(), 1);
+
+        // Directory paths remove every file beneath them, but not siblings
+        // that merely share the prefix
+        let deleted = memory.delete_units_by_path(&workspace_id, "src/net").await.unwrap();
+        assert_eq!(deleted, 2);
+        assert_eq!(memory.get_units_in_file("src/network.rs").await.unwrap().len(), 1);
+    }
+
     // Test removed - deprecated SemanticUnit API replaced with CodeUnit API
     // Complex unit finding is tested via integration tests
 }
@@ -27,6 +27,8 @@ async fn create_test_manager() -> CognitiveManager {
             validate_on_checkout: false,
             recycle_after_uses: Some(1000),
             shutdown_grace_period: Duration::from_secs(5),
+            query_timeout: Some(Duration::from_secs(30)),
+            slow_query_threshold: Some(Duration::from_secs(1)),
         },
         namespace: "cortex".to_string(),
         database: "test".to_string(),
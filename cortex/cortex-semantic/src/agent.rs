@@ -10,6 +10,7 @@
 //! - Cross-agent knowledge retrieval with access control
 //! - Conflict resolution strategies
 //! - Performance metrics per agent
+//! - Heartbeat-based liveness, deregistering agents that stop responding
 //!
 //! # Architecture
 //!
@@ -49,10 +50,14 @@
 //! ```
 
 use crate::error::{Result, SemanticError};
+use crate::providers::{BatchUsage, UsageCallback};
+use crate::ranking::PreferenceProfile;
 use crate::types::{DocumentId, Vector};
 use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::{RwLock, Semaphore};
@@ -65,6 +70,19 @@ pub type AgentId = String;
 /// Namespace for agent-specific embeddings.
 pub type Namespace = String;
 
+/// Audit entries kept by the coordinator
+const MAX_AUDIT_ENTRIES: usize = 10_000;
+
+/// Namespace owned by an agent.
+pub fn agent_namespace(agent_id: &str) -> Namespace {
+    format!("agent::{}", agent_id)
+}
+
+/// Agent owning a namespace, if it is an agent namespace.
+pub fn namespace_owner(namespace: &str) -> Option<&str> {
+    namespace.strip_prefix("agent::")
+}
+
 /// Agent role in the multi-agent system.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -95,6 +113,27 @@ pub enum SearchPriority {
     Background = 4,
 }
 
+impl SearchPriority {
+    /// Every priority, highest first.
+    pub const ALL: [SearchPriority; 5] = [
+        SearchPriority::Critical,
+        SearchPriority::High,
+        SearchPriority::Normal,
+        SearchPriority::Low,
+        SearchPriority::Background,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SearchPriority::Critical => "critical",
+            SearchPriority::High => "high",
+            SearchPriority::Normal => "normal",
+            SearchPriority::Low => "low",
+            SearchPriority::Background => "background",
+        }
+    }
+}
+
 impl Default for SearchPriority {
     fn default() -> Self {
         Self::Normal
@@ -167,6 +206,23 @@ pub enum AccessPolicy {
     Hierarchical,
 }
 
+/// Level of access granted to another agent's namespace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessLevel {
+    Read,
+    ReadWrite,
+}
+
+/// Record of a denied namespace access.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessAuditEntry {
+    pub agent_id: AgentId,
+    pub namespace: Namespace,
+    pub action: String,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
 /// Memory access permissions.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AccessControl {
@@ -233,10 +289,122 @@ impl AccessControl {
     pub fn add_writer(&mut self, agent_id: impl Into<AgentId>) {
         self.writers.insert(agent_id.into());
     }
+
+    /// Access explicitly granted to an agent, ignoring the policy.
+    pub fn grant_level(&self, agent_id: &AgentId) -> Option<AccessLevel> {
+        if self.owners.contains(agent_id) || self.writers.contains(agent_id) {
+            Some(AccessLevel::ReadWrite)
+        } else if self.readers.contains(agent_id) {
+            Some(AccessLevel::Read)
+        } else {
+            None
+        }
+    }
+
+    /// Remove every grant of an agent. Returns whether it had any.
+    pub fn revoke(&mut self, agent_id: &AgentId) -> bool {
+        let was_reader = self.readers.remove(agent_id);
+        let was_writer = self.writers.remove(agent_id);
+        was_reader || was_writer
+    }
+}
+
+/// Size and lifetime limits of a memory pool; `None` means unbounded.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryPoolLimits {
+    /// Most entries kept at once
+    pub max_entries: Option<usize>,
+    /// Most bytes kept at once, as counted by `MemoryEntry::size_bytes`
+    pub max_bytes: Option<usize>,
+    /// How long an entry lives after it is stored, unless stored with
+    /// `MemoryPool::put_with_ttl`
+    pub ttl: Option<Duration>,
+    /// Largest fraction of `max_entries` and `max_bytes` one agent may fill;
+    /// writes past it fail with `SemanticError::QuotaExceeded`
+    pub max_agent_share: Option<f64>,
+}
+
+/// Why an entry left a memory pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EvictionReason {
+    /// Its TTL ran out
+    Expired,
+    /// The pool was over `max_entries` or `max_bytes`
+    Capacity,
+}
+
+/// Called with every entry evicted from a pool, so its owner can persist it
+/// elsewhere.
+pub type EvictionCallback = Arc<dyn Fn(&MemoryEntry, EvictionReason) + Send + Sync>;
+
+/// What one agent holds in and reads from a memory pool.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AgentPoolUsage {
+    /// Entries stored by the agent that are still in the pool
+    pub entries: u64,
+    /// Bytes of those entries
+    pub bytes: u64,
+    /// Retrievals and searches made by the agent
+    pub reads: u64,
+    /// Entries of the agent that expired but were not evicted yet; included
+    /// in `entries`
+    #[serde(default)]
+    pub expired: u64,
+}
+
+impl AgentPoolUsage {
+    fn add(&mut self, other: &AgentPoolUsage) {
+        self.entries += other.entries;
+        self.bytes += other.bytes;
+        self.reads += other.reads;
+        self.expired += other.expired;
+    }
+}
+
+/// Usage of a memory pool, overall and per agent.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MemoryPoolUsage {
+    pub entries: u64,
+    /// Entries that expired but were not evicted yet; included in `entries`
+    #[serde(default)]
+    pub expired_entries: u64,
+    pub bytes: u64,
+    pub evictions: u64,
+    pub agents: HashMap<AgentId, AgentPoolUsage>,
+}
+
+/// Byte and per-agent accounting of a pool. Writes and evictions hold its
+/// lock, so checking a quota and storing happen atomically.
+#[derive(Debug, Default)]
+struct PoolAccounting {
+    bytes: u64,
+    agents: HashMap<AgentId, AgentPoolUsage>,
+}
+
+impl PoolAccounting {
+    fn add(&mut self, agent_id: &AgentId, bytes: u64) {
+        self.bytes += bytes;
+        let usage = self.agents.entry(agent_id.clone()).or_default();
+        usage.entries += 1;
+        usage.bytes += bytes;
+    }
+
+    fn remove(&mut self, entry: &MemoryEntry) {
+        let bytes = entry.size_bytes() as u64;
+        self.bytes = self.bytes.saturating_sub(bytes);
+        if let Some(usage) = self.agents.get_mut(&entry.agent_id) {
+            usage.entries = usage.entries.saturating_sub(1);
+            usage.bytes = usage.bytes.saturating_sub(bytes);
+        }
+    }
+
+    fn record_read(&mut self, agent_id: &AgentId) {
+        self.agents.entry(agent_id.clone()).or_default().reads += 1;
+    }
 }
 
 /// Shared semantic memory pool with access control.
-#[derive(Debug)]
 pub struct MemoryPool {
     /// Pool identifier
     pub pool_id: String,
@@ -246,6 +414,21 @@ pub struct MemoryPool {
     entries: Arc<DashMap<DocumentId, MemoryEntry>>,
     /// Statistics
     stats: Arc<MemoryPoolStats>,
+    limits: MemoryPoolLimits,
+    accounting: parking_lot::Mutex<PoolAccounting>,
+    /// Logical clock ordering reads for least-recently-read eviction
+    clock: std::sync::atomic::AtomicU64,
+    on_evict: parking_lot::RwLock<Option<EvictionCallback>>,
+}
+
+impl fmt::Debug for MemoryPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemoryPool")
+            .field("pool_id", &self.pool_id)
+            .field("entries", &self.entries.len())
+            .field("limits", &self.limits)
+            .finish_non_exhaustive()
+    }
 }
 
 /// Memory pool entry.
@@ -257,6 +440,25 @@ pub struct MemoryEntry {
     pub metadata: HashMap<String, String>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub access_count: u64,
+    pub last_accessed: chrono::DateTime<chrono::Utc>,
+    /// When the entry stops being visible, if the pool has a TTL
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    access_tick: u64,
+}
+
+impl MemoryEntry {
+    /// Approximate memory held by the entry.
+    pub fn size_bytes(&self) -> usize {
+        let metadata: usize = self.metadata.iter().map(|(k, v)| k.len() + v.len()).sum();
+        self.doc_id.len()
+            + self.agent_id.len()
+            + self.vector.len() * std::mem::size_of::<f32>()
+            + metadata
+    }
+
+    pub fn is_expired(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
 }
 
 /// Memory pool statistics.
@@ -266,20 +468,47 @@ pub struct MemoryPoolStats {
     pub reads: std::sync::atomic::AtomicU64,
     pub writes: std::sync::atomic::AtomicU64,
     pub access_denied: std::sync::atomic::AtomicU64,
+    pub evictions: std::sync::atomic::AtomicU64,
 }
 
 impl MemoryPool {
     /// Create a new memory pool.
     pub fn new(policy: AccessPolicy) -> Self {
+        Self::with_limits(policy, MemoryPoolLimits::default())
+    }
+
+    /// Create a memory pool bounded by `limits`.
+    pub fn with_limits(policy: AccessPolicy, limits: MemoryPoolLimits) -> Self {
         Self {
             pool_id: Uuid::new_v4().to_string(),
             access_control: Arc::new(RwLock::new(AccessControl::new(policy))),
             entries: Arc::new(DashMap::new()),
             stats: Arc::new(MemoryPoolStats::default()),
+            limits,
+            accounting: parking_lot::Mutex::new(PoolAccounting::default()),
+            clock: std::sync::atomic::AtomicU64::new(0),
+            on_evict: parking_lot::RwLock::new(None),
         }
     }
 
+    /// Limits of the pool.
+    pub fn limits(&self) -> &MemoryPoolLimits {
+        &self.limits
+    }
+
+    /// Call `callback` with every entry evicted from now on.
+    pub fn set_eviction_callback(
+        &self,
+        callback: impl Fn(&MemoryEntry, EvictionReason) + Send + Sync + 'static,
+    ) {
+        *self.on_evict.write() = Some(Arc::new(callback));
+    }
+
     /// Store an embedding in the pool.
+    ///
+    /// Fails with `SemanticError::QuotaExceeded` when the write would take the
+    /// agent past its share of the pool. Otherwise, if the pool goes over its
+    /// limits, expired entries are evicted first, then the least recently read.
     pub async fn store(
         &self,
         agent_id: &AgentId,
@@ -287,6 +516,33 @@ impl MemoryPool {
         doc_id: DocumentId,
         vector: Vector,
         metadata: HashMap<String, String>,
+    ) -> Result<()> {
+        self.store_entry(agent_id, role, doc_id, vector, metadata, self.limits.ttl)
+            .await
+    }
+
+    /// Store an embedding that expires after `ttl` instead of the pool's TTL.
+    pub async fn put_with_ttl(
+        &self,
+        agent_id: &AgentId,
+        role: AgentRole,
+        doc_id: DocumentId,
+        vector: Vector,
+        metadata: HashMap<String, String>,
+        ttl: Duration,
+    ) -> Result<()> {
+        self.store_entry(agent_id, role, doc_id, vector, metadata, Some(ttl))
+            .await
+    }
+
+    async fn store_entry(
+        &self,
+        agent_id: &AgentId,
+        role: AgentRole,
+        doc_id: DocumentId,
+        vector: Vector,
+        metadata: HashMap<String, String>,
+        ttl: Option<Duration>,
     ) -> Result<()> {
         // Check write permission
         let ac = self.access_control.read().await;
@@ -299,23 +555,51 @@ impl MemoryPool {
         }
         drop(ac);
 
+        let now = chrono::Utc::now();
         let entry = MemoryEntry {
             doc_id: doc_id.clone(),
             agent_id: agent_id.clone(),
             vector,
             metadata,
-            created_at: chrono::Utc::now(),
+            created_at: now,
             access_count: 0,
+            last_accessed: now,
+            expires_at: ttl
+                .and_then(|ttl| chrono::Duration::from_std(ttl).ok())
+                .map(|ttl| now + ttl),
+            access_tick: self.tick(),
         };
+        let size = entry.size_bytes() as u64;
+
+        let evicted = {
+            let mut accounting = self.accounting.lock();
+            let replaced = self
+                .entries
+                .get(&doc_id)
+                .map(|e| (e.agent_id.clone(), e.size_bytes() as u64));
+            self.check_quota(&accounting, agent_id, size, replaced.as_ref())?;
+
+            match self.entries.insert(doc_id.clone(), entry) {
+                Some(old) => accounting.remove(&old),
+                None => {
+                    self.stats.total_entries.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                }
+            }
+            accounting.add(agent_id, size);
+            self.stats.writes.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
 
-        self.entries.insert(doc_id, entry);
-        self.stats.writes.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-        self.stats.total_entries.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            self.evict_to_limits(&mut accounting, &doc_id)
+        };
+        self.notify_evicted(evicted);
 
         Ok(())
     }
 
     /// Retrieve an embedding from the pool.
+    ///
+    /// Read permission is checked first, so an expired entry is reported as
+    /// missing only to agents allowed to read it. Reading an expired entry
+    /// evicts the pool's expired entries.
     pub async fn retrieve(
         &self,
         agent_id: &AgentId,
@@ -335,10 +619,23 @@ impl MemoryPool {
 
         self.stats.reads.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
 
-        let entry = self.entries.get_mut(doc_id).map(|mut e| {
-            e.access_count += 1;
-            e.clone()
-        });
+        let now = chrono::Utc::now();
+        let tick = self.tick();
+        let entry = match self.entries.get_mut(doc_id) {
+            Some(e) if e.is_expired(now) => {
+                drop(e);
+                self.purge_expired();
+                None
+            }
+            Some(mut e) => {
+                e.access_count += 1;
+                e.last_accessed = now;
+                e.access_tick = tick;
+                Some(e.clone())
+            }
+            None => None,
+        };
+        self.accounting.lock().record_read(agent_id);
 
         Ok(entry)
     }
@@ -363,11 +660,14 @@ impl MemoryPool {
         drop(ac);
 
         self.stats.reads.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        self.accounting.lock().record_read(agent_id);
 
         // Simple cosine similarity search
+        let now = chrono::Utc::now();
         let mut results: Vec<(DocumentId, f32)> = self
             .entries
             .iter()
+            .filter(|entry| !entry.is_expired(now))
             .map(|entry| {
                 let score = crate::types::cosine_similarity(query, &entry.vector);
                 (entry.doc_id.clone(), score)
@@ -388,9 +688,71 @@ impl MemoryPool {
         Ok(results)
     }
 
-    /// Get statistics.
+    /// Evict every expired entry. Returns how many were evicted.
+    pub fn purge_expired(&self) -> usize {
+        let now = chrono::Utc::now();
+        let evicted = {
+            let mut accounting = self.accounting.lock();
+            let expired: Vec<DocumentId> = self
+                .entries
+                .iter()
+                .filter(|e| e.is_expired(now))
+                .map(|e| e.doc_id.clone())
+                .collect();
+            self.remove_all(&mut accounting, expired, EvictionReason::Expired)
+        };
+        let count = evicted.len();
+        self.notify_evicted(evicted);
+        count
+    }
+
+    /// Run `purge_expired` every `interval` until the pool is dropped.
+    /// Without a sweeper, expired entries are evicted when read or when the
+    /// pool goes over its limits.
+    pub fn spawn_sweeper(self: &Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let pool = Arc::downgrade(self);
+        let mut ticker = tokio::time::interval(interval.max(Duration::from_millis(1)));
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        tokio::spawn(async move {
+            loop {
+                ticker.tick().await;
+                let Some(pool) = pool.upgrade() else {
+                    break;
+                };
+                pool.purge_expired();
+            }
+        })
+    }
+
+    /// Entries and bytes held by each contributing agent, and reads made by
+    /// each agent.
+    pub fn usage(&self) -> MemoryPoolUsage {
+        let expired = self.expired_by_agent();
+        let accounting = self.accounting.lock();
+        let mut agents = accounting.agents.clone();
+        for (agent_id, count) in &expired {
+            agents.entry(agent_id.clone()).or_default().expired = *count;
+        }
+
+        MemoryPoolUsage {
+            entries: self.entries.len() as u64,
+            expired_entries: expired.values().sum(),
+            bytes: accounting.bytes,
+            evictions: self.stats.evictions.load(std::sync::atomic::Ordering::Relaxed),
+            agents,
+        }
+    }
+
+    /// Get statistics: counters, plus `live_entries`, `expired_entries` not
+    /// evicted yet and `total_bytes`.
     pub fn stats(&self) -> HashMap<String, u64> {
+        let expired: u64 = self.expired_by_agent().values().sum();
         let mut stats = HashMap::new();
+        stats.insert("live_entries".to_string(),
+            (self.entries.len() as u64).saturating_sub(expired));
+        stats.insert("expired_entries".to_string(), expired);
+        stats.insert("total_bytes".to_string(), self.accounting.lock().bytes);
         stats.insert("total_entries".to_string(),
             self.stats.total_entries.load(std::sync::atomic::Ordering::Relaxed));
         stats.insert("reads".to_string(),
@@ -399,8 +761,305 @@ impl MemoryPool {
             self.stats.writes.load(std::sync::atomic::Ordering::Relaxed));
         stats.insert("access_denied".to_string(),
             self.stats.access_denied.load(std::sync::atomic::Ordering::Relaxed));
+        stats.insert("evictions".to_string(),
+            self.stats.evictions.load(std::sync::atomic::Ordering::Relaxed));
         stats
     }
+
+    /// Expired entries still in the pool, by the agent that stored them.
+    fn expired_by_agent(&self) -> HashMap<AgentId, u64> {
+        let now = chrono::Utc::now();
+        let mut expired = HashMap::new();
+        for entry in self.entries.iter().filter(|entry| entry.is_expired(now)) {
+            *expired.entry(entry.agent_id.clone()).or_insert(0) += 1;
+        }
+        expired
+    }
+
+    fn tick(&self) -> u64 {
+        self.clock.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Reject a write that takes the agent past its share of the pool. An
+    /// entry it replaces no longer counts against it.
+    fn check_quota(
+        &self,
+        accounting: &PoolAccounting,
+        agent_id: &AgentId,
+        size: u64,
+        replaced: Option<&(AgentId, u64)>,
+    ) -> Result<()> {
+        let (current, entries, bytes, share) = match self.limits.max_agent_share {
+            Some(share) => {
+                let current = accounting.agents.get(agent_id).copied().unwrap_or_default();
+                let (mut entries, mut bytes) = (current.entries + 1, current.bytes + size);
+                if let Some((owner, old_size)) = replaced {
+                    if owner == agent_id {
+                        entries -= 1;
+                        bytes = bytes.saturating_sub(*old_size);
+                    }
+                }
+                (current, entries, bytes, share.clamp(0.0, 1.0))
+            }
+            // Without a share an agent may fill the whole pool, but a single
+            // entry still has to fit in it
+            None => (AgentPoolUsage::default(), 1, size, 1.0),
+        };
+
+        let checks = [
+            ("entries", entries, current.entries, self.limits.max_entries),
+            ("bytes", bytes, current.bytes, self.limits.max_bytes),
+        ];
+        for (resource, requested, current, max) in checks {
+            if let Some(max) = max {
+                let limit = (max as f64 * share).floor() as u64;
+                if requested > limit {
+                    return Err(SemanticError::QuotaExceeded {
+                        agent_id: agent_id.clone(),
+                        resource: resource.to_string(),
+                        requested,
+                        current,
+                        limit,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn over_limits(&self, accounting: &PoolAccounting) -> bool {
+        self.limits.max_entries.is_some_and(|max| self.entries.len() > max)
+            || self.limits.max_bytes.is_some_and(|max| accounting.bytes > max as u64)
+    }
+
+    /// Evict until the pool is within its limits: expired entries first, then
+    /// the least recently read. `keep` is the entry just written.
+    fn evict_to_limits(
+        &self,
+        accounting: &mut PoolAccounting,
+        keep: &DocumentId,
+    ) -> Vec<(MemoryEntry, EvictionReason)> {
+        if !self.over_limits(accounting) {
+            return Vec::new();
+        }
+
+        let now = chrono::Utc::now();
+        let mut expired = Vec::new();
+        let mut live = Vec::new();
+        for entry in self.entries.iter() {
+            if entry.doc_id == *keep {
+                continue;
+            }
+            if entry.is_expired(now) {
+                expired.push(entry.doc_id.clone());
+            } else {
+                live.push((entry.access_tick, entry.doc_id.clone()));
+            }
+        }
+
+        let mut evicted = self.remove_all(accounting, expired, EvictionReason::Expired);
+
+        live.sort_unstable();
+        for (_, doc_id) in live {
+            if !self.over_limits(accounting) {
+                break;
+            }
+            evicted.extend(self.remove_all(accounting, [doc_id], EvictionReason::Capacity));
+        }
+        evicted
+    }
+
+    fn remove_all(
+        &self,
+        accounting: &mut PoolAccounting,
+        doc_ids: impl IntoIterator<Item = DocumentId>,
+        reason: EvictionReason,
+    ) -> Vec<(MemoryEntry, EvictionReason)> {
+        let mut evicted = Vec::new();
+        for doc_id in doc_ids {
+            if let Some((_, entry)) = self.entries.remove(&doc_id) {
+                accounting.remove(&entry);
+                self.stats.total_entries.fetch_sub(1, std::sync::atomic::Ordering::Relaxed);
+                self.stats.evictions.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                evicted.push((entry, reason));
+            }
+        }
+        evicted
+    }
+
+    /// Run the eviction callback, outside the accounting lock so it may use
+    /// the pool.
+    fn notify_evicted(&self, evicted: Vec<(MemoryEntry, EvictionReason)>) {
+        if evicted.is_empty() {
+            return;
+        }
+        debug!("Evicted {} entries from memory pool {}", evicted.len(), self.pool_id);
+
+        let callback = self.on_evict.read().clone();
+        if let Some(callback) = callback {
+            for (entry, reason) in &evicted {
+                callback(entry, *reason);
+            }
+        }
+    }
+}
+
+/// Liveness of a registered agent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AgentStatus {
+    /// Heartbeats arrive in time
+    Alive,
+    /// Missed heartbeats for `LivenessConfig::heartbeat_timeout`; skipped by
+    /// searches until it is heard from again
+    Unreachable,
+}
+
+/// A change of an agent's liveness.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AgentTransition {
+    Registered,
+    /// Missed heartbeats past the timeout
+    Unreachable,
+    /// Heard from again while unreachable
+    Recovered,
+    /// Unregistered, or unreachable past the grace period
+    Deregistered,
+}
+
+/// Reported to the status callback on every liveness transition.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentStatusEvent {
+    pub agent_id: AgentId,
+    pub transition: AgentTransition,
+    /// Last heartbeat or successful response of the agent
+    pub last_seen: Option<chrono::DateTime<chrono::Utc>>,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+/// Called with every agent liveness transition, e.g. to raise alerts.
+pub type AgentStatusCallback = Arc<dyn Fn(&AgentStatusEvent) + Send + Sync>;
+
+/// Heartbeat deadlines of registered agents.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LivenessConfig {
+    /// Silence after which an agent is marked unreachable
+    pub heartbeat_timeout_ms: u64,
+    /// Silence after which an unreachable agent is deregistered
+    pub deregister_after_ms: u64,
+    /// How often the background reaper checks deadlines
+    pub reap_interval_ms: u64,
+}
+
+impl LivenessConfig {
+    pub fn heartbeat_timeout(&self) -> Duration {
+        Duration::from_millis(self.heartbeat_timeout_ms)
+    }
+
+    pub fn deregister_after(&self) -> Duration {
+        Duration::from_millis(self.deregister_after_ms.max(self.heartbeat_timeout_ms))
+    }
+
+    pub fn reap_interval(&self) -> Duration {
+        Duration::from_millis(self.reap_interval_ms.max(1))
+    }
+}
+
+impl Default for LivenessConfig {
+    fn default() -> Self {
+        Self {
+            heartbeat_timeout_ms: 30_000,
+            deregister_after_ms: 300_000,
+            reap_interval_ms: 10_000,
+        }
+    }
+}
+
+/// Agents whose liveness changed in one reaper pass.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReapReport {
+    pub unreachable: Vec<AgentId>,
+    pub deregistered: Vec<AgentId>,
+}
+
+impl ReapReport {
+    pub fn is_empty(&self) -> bool {
+        self.unreachable.is_empty() && self.deregistered.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Liveness {
+    status: AgentStatus,
+    last_heartbeat: Instant,
+}
+
+/// Namespace grants of a deregistered agent, restored if it registers again.
+#[derive(Debug, Clone, Default)]
+struct RetiredGrants {
+    /// Grants the agent held on other namespaces
+    held: Vec<(Namespace, AccessLevel)>,
+    /// Grants on the agent's own namespace
+    own: Option<AccessControl>,
+}
+
+/// Limits on what may be indexed into a namespace; `None` means unbounded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NamespaceQuota {
+    /// Most documents indexed into the namespace
+    pub max_documents: Option<u64>,
+    /// Most bytes of vectors, at 4 bytes per dimension
+    pub max_vector_bytes: Option<u64>,
+}
+
+impl NamespaceQuota {
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+}
+
+/// Documents and vector bytes indexed through agents.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexUsage {
+    pub documents: u64,
+    pub vector_bytes: u64,
+}
+
+/// A namespace's index usage and quota.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NamespaceUsage {
+    pub usage: IndexUsage,
+    pub quota: NamespaceQuota,
+}
+
+/// Index usage across agents, see [`AgentCoordinator::usage_report`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsageReport {
+    pub total: IndexUsage,
+    pub namespaces: BTreeMap<Namespace, NamespaceUsage>,
+    /// Usage by the agent that indexed each document
+    pub agents: BTreeMap<AgentId, IndexUsage>,
+}
+
+/// Quota and indexed documents of a namespace. Quota checks and updates
+/// hold the namespace's map entry, so they happen atomically.
+#[derive(Debug, Default)]
+struct NamespaceAccounting {
+    quota: NamespaceQuota,
+    /// Agent that indexed each document, and its vector bytes
+    documents: HashMap<DocumentId, (AgentId, u64)>,
+    vector_bytes: u64,
+}
+
+impl NamespaceAccounting {
+    fn usage(&self) -> IndexUsage {
+        IndexUsage {
+            documents: self.documents.len() as u64,
+            vector_bytes: self.vector_bytes,
+        }
+    }
 }
 
 /// Agent coordinator - central orchestrator for multi-agent system.
@@ -411,8 +1070,24 @@ pub struct AgentCoordinator {
     memory_pools: Arc<DashMap<String, Arc<MemoryPool>>>,
     /// Agent metrics
     metrics: Arc<DashMap<AgentId, Arc<AgentMetrics>>>,
+    /// Preference profiles learned from search feedback; kept when an agent
+    /// unregisters so it starts warm next time
+    preference_profiles: Arc<DashMap<AgentId, PreferenceProfile>>,
+    /// Cross-namespace grants; agents always own their own namespace
+    namespace_grants: Arc<DashMap<Namespace, AccessControl>>,
+    /// Denied namespace accesses, oldest first
+    access_audit: Arc<parking_lot::Mutex<VecDeque<AccessAuditEntry>>>,
     /// Semaphore for limiting concurrent operations
     concurrency_limit: Arc<Semaphore>,
+    liveness_config: LivenessConfig,
+    /// Status and last heartbeat of registered agents
+    liveness: Arc<DashMap<AgentId, Liveness>>,
+    /// Grants of agents deregistered for missing heartbeats
+    retired_grants: Arc<DashMap<AgentId, RetiredGrants>>,
+    /// Quotas and usage of namespaces indexed into through agents; kept when
+    /// an agent unregisters, since its documents stay indexed
+    namespace_accounting: Arc<DashMap<Namespace, NamespaceAccounting>>,
+    on_status_change: parking_lot::RwLock<Option<AgentStatusCallback>>,
 }
 
 /// Per-agent metrics.
@@ -423,8 +1098,23 @@ pub struct AgentMetrics {
     pub cache_misses: std::sync::atomic::AtomicU64,
     pub total_search_time_ms: std::sync::atomic::AtomicU64,
     pub memory_usage_bytes: std::sync::atomic::AtomicU64,
+    /// Memory pool entries stored by the agent, across all pools
+    pub memory_pool_entries: std::sync::atomic::AtomicU64,
+    /// Memory pool reads made by the agent, across all pools
+    pub memory_pool_reads: std::sync::atomic::AtomicU64,
+    /// Expired memory pool entries of the agent not evicted yet
+    pub memory_pool_expired: std::sync::atomic::AtomicU64,
+    /// Embedding tokens billed for requests made on the agent's behalf
+    pub embedding_tokens: std::sync::atomic::AtomicU64,
+    /// Documents the agent indexed that are still indexed, across namespaces
+    pub indexed_documents: std::sync::atomic::AtomicU64,
+    /// Vector bytes of those documents
+    pub indexed_vector_bytes: std::sync::atomic::AtomicU64,
     pub cross_agent_requests: std::sync::atomic::AtomicU64,
     pub conflicts_resolved: std::sync::atomic::AtomicU64,
+    /// Unix time in milliseconds of the agent's last heartbeat or successful
+    /// response, 0 if never seen
+    pub last_seen_ms: std::sync::atomic::AtomicI64,
 }
 
 impl AgentMetrics {
@@ -439,6 +1129,53 @@ impl AgentMetrics {
         }
     }
 
+    /// Record the agent's memory pool usage, summed over all pools.
+    pub fn record_pool_usage(&self, usage: AgentPoolUsage) {
+        self.memory_usage_bytes.store(usage.bytes, std::sync::atomic::Ordering::Relaxed);
+        self.memory_pool_entries.store(usage.entries, std::sync::atomic::Ordering::Relaxed);
+        self.memory_pool_reads.store(usage.reads, std::sync::atomic::Ordering::Relaxed);
+        self.memory_pool_expired.store(usage.expired, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// Record that the agent was heard from now.
+    pub fn record_seen(&self) {
+        let now = chrono::Utc::now().timestamp_millis();
+        self.last_seen_ms.store(now, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// When the agent was last heard from.
+    pub fn last_seen(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        match self.last_seen_ms.load(std::sync::atomic::Ordering::Relaxed) {
+            0 => None,
+            ms => chrono::DateTime::from_timestamp_millis(ms),
+        }
+    }
+
+    /// Record the embedding usage of a batch made on the agent's behalf.
+    pub fn record_embedding_usage(&self, usage: &BatchUsage) {
+        self.embedding_tokens.fetch_add(usage.total_tokens, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// Record a document the agent indexed, or with `added` false, one
+    /// that was removed.
+    pub fn record_indexed(&self, vector_bytes: u64, added: bool) {
+        let update = |counter: &std::sync::atomic::AtomicU64, amount: u64| {
+            let _ = counter.fetch_update(
+                std::sync::atomic::Ordering::Relaxed,
+                std::sync::atomic::Ordering::Relaxed,
+                |value| {
+                    Some(if added {
+                        value + amount
+                    } else {
+                        value.saturating_sub(amount)
+                    })
+                },
+            );
+        };
+        update(&self.indexed_documents, 1);
+        update(&self.indexed_vector_bytes, vector_bytes);
+    }
+
     /// Get average search latency.
     pub fn avg_search_latency_ms(&self) -> f64 {
         let count = self.search_count.load(std::sync::atomic::Ordering::Relaxed);
@@ -469,10 +1206,24 @@ impl AgentMetrics {
         map.insert("cache_hit_rate".to_string(), self.cache_hit_rate());
         map.insert("memory_usage_mb".to_string(),
             self.memory_usage_bytes.load(std::sync::atomic::Ordering::Relaxed) as f64 / 1024.0 / 1024.0);
+        map.insert("memory_pool_entries".to_string(),
+            self.memory_pool_entries.load(std::sync::atomic::Ordering::Relaxed) as f64);
+        map.insert("memory_pool_reads".to_string(),
+            self.memory_pool_reads.load(std::sync::atomic::Ordering::Relaxed) as f64);
+        map.insert("memory_pool_expired_entries".to_string(),
+            self.memory_pool_expired.load(std::sync::atomic::Ordering::Relaxed) as f64);
+        map.insert("embedding_tokens".to_string(),
+            self.embedding_tokens.load(std::sync::atomic::Ordering::Relaxed) as f64);
+        map.insert("indexed_documents".to_string(),
+            self.indexed_documents.load(std::sync::atomic::Ordering::Relaxed) as f64);
+        map.insert("indexed_vector_bytes".to_string(),
+            self.indexed_vector_bytes.load(std::sync::atomic::Ordering::Relaxed) as f64);
         map.insert("cross_agent_requests".to_string(),
             self.cross_agent_requests.load(std::sync::atomic::Ordering::Relaxed) as f64);
         map.insert("conflicts_resolved".to_string(),
             self.conflicts_resolved.load(std::sync::atomic::Ordering::Relaxed) as f64);
+        map.insert("last_seen_ms".to_string(),
+            self.last_seen_ms.load(std::sync::atomic::Ordering::Relaxed) as f64);
         map
     }
 }
@@ -491,27 +1242,92 @@ impl AgentCoordinator {
             agents: Arc::new(DashMap::new()),
             memory_pools: Arc::new(DashMap::new()),
             metrics: Arc::new(DashMap::new()),
+            preference_profiles: Arc::new(DashMap::new()),
+            namespace_grants: Arc::new(DashMap::new()),
+            access_audit: Arc::new(parking_lot::Mutex::new(VecDeque::new())),
             concurrency_limit: Arc::new(Semaphore::new(limit)),
+            liveness_config: LivenessConfig::default(),
+            liveness: Arc::new(DashMap::new()),
+            retired_grants: Arc::new(DashMap::new()),
+            namespace_accounting: Arc::new(DashMap::new()),
+            on_status_change: parking_lot::RwLock::new(None),
         }
     }
 
-    /// Register a new agent.
+    /// Use `config` for heartbeat deadlines.
+    pub fn with_liveness(mut self, config: LivenessConfig) -> Self {
+        self.liveness_config = config;
+        self
+    }
+
+    pub fn liveness_config(&self) -> &LivenessConfig {
+        &self.liveness_config
+    }
+
+    /// Call `callback` with every agent liveness transition from now on.
+    pub fn set_status_callback(&self, callback: impl Fn(&AgentStatusEvent) + Send + Sync + 'static) {
+        *self.on_status_change.write() = Some(Arc::new(callback));
+    }
+
+    /// Register a new agent. Registration counts as a heartbeat; an agent
+    /// deregistered for missing heartbeats gets its namespace grants back.
+    ///
+    /// The agent's namespace keeps any quota set before; see
+    /// [`register_agent_with_quota`](Self::register_agent_with_quota).
     pub async fn register_agent(
         &self,
         agent_id: impl Into<String>,
         role: AgentRole,
         capabilities: Vec<String>,
     ) -> Result<Arc<RwLock<AgentContext>>> {
-        let agent_id = agent_id.into();
+        self.register(agent_id.into(), role, capabilities, None).await
+    }
+
+    /// Register a new agent whose namespace is limited by `quota`.
+    pub async fn register_agent_with_quota(
+        &self,
+        agent_id: impl Into<String>,
+        role: AgentRole,
+        capabilities: Vec<String>,
+        quota: NamespaceQuota,
+    ) -> Result<Arc<RwLock<AgentContext>>> {
+        self.register(agent_id.into(), role, capabilities, Some(quota)).await
+    }
 
+    async fn register(
+        &self,
+        agent_id: AgentId,
+        role: AgentRole,
+        capabilities: Vec<String>,
+        quota: Option<NamespaceQuota>,
+    ) -> Result<Arc<RwLock<AgentContext>>> {
         info!("Registering agent: {} (role: {:?})", agent_id, role);
 
+        if let Some(quota) = quota {
+            self.set_namespace_quota(&agent_namespace(&agent_id), quota);
+        }
+
         let context = AgentContext::new(&agent_id, role, capabilities);
         let context = Arc::new(RwLock::new(context));
 
         self.agents.insert(agent_id.clone(), context.clone());
-        self.metrics.insert(agent_id.clone(), Arc::new(AgentMetrics::default()));
+        let metrics = Arc::new(AgentMetrics::default());
+        metrics.record_seen();
+        self.metrics.insert(agent_id.clone(), metrics);
+        self.liveness.insert(
+            agent_id.clone(),
+            Liveness {
+                status: AgentStatus::Alive,
+                last_heartbeat: Instant::now(),
+            },
+        );
+
+        if let Some((_, grants)) = self.retired_grants.remove(&agent_id) {
+            info!("Restoring namespace grants of agent {}", agent_id);
+            self.restore_grants(&agent_id, grants);
+        }
 
+        self.notify_status(&agent_id, AgentTransition::Registered);
         Ok(context)
     }
 
@@ -520,36 +1336,456 @@ impl AgentCoordinator {
         info!("Unregistering agent: {}", agent_id);
 
         self.agents.remove(agent_id);
-        self.metrics.remove(agent_id);
+        self.liveness.remove(agent_id);
+        let last_seen = self.metrics.remove(agent_id).and_then(|(_, m)| m.last_seen());
+        self.emit_status(agent_id, AgentTransition::Deregistered, last_seen);
 
         Ok(())
     }
 
-    /// Get agent context.
-    pub fn get_agent(&self, agent_id: &AgentId) -> Option<Arc<RwLock<AgentContext>>> {
-        self.agents.get(agent_id).map(|a| a.clone())
-    }
+    /// Record that an agent is alive. An unreachable agent becomes
+    /// reachable again.
+    pub fn heartbeat(&self, agent_id: &AgentId) -> Result<()> {
+        let recovered = {
+            let mut liveness = self
+                .liveness
+                .get_mut(agent_id)
+                .ok_or_else(|| SemanticError::AgentNotFound(agent_id.clone()))?;
+            liveness.last_heartbeat = Instant::now();
+            std::mem::replace(&mut liveness.status, AgentStatus::Alive) == AgentStatus::Unreachable
+        };
+        if let Some(metrics) = self.metrics.get(agent_id) {
+            metrics.record_seen();
+        }
 
-    /// List all registered agents.
-    pub fn list_agents(&self) -> Vec<AgentId> {
-        self.agents.iter().map(|e| e.key().clone()).collect()
+        if recovered {
+            info!("Agent {} is reachable again", agent_id);
+            self.notify_status(agent_id, AgentTransition::Recovered);
+        }
+        Ok(())
     }
 
-    /// Get agent metrics.
-    pub fn get_metrics(&self, agent_id: &AgentId) -> Option<Arc<AgentMetrics>> {
-        self.metrics.get(agent_id).map(|m| m.clone())
+    /// Liveness of a registered agent.
+    pub fn agent_status(&self, agent_id: &AgentId) -> Option<AgentStatus> {
+        self.liveness.get(agent_id).map(|liveness| liveness.status)
     }
 
-    /// Create a memory pool.
-    pub fn create_memory_pool(&self, pool_id: impl Into<String>, policy: AccessPolicy) -> Arc<MemoryPool> {
-        let pool_id = pool_id.into();
-
-        info!("Creating memory pool: {} (policy: {:?})", pool_id, policy);
-
-        let pool = Arc::new(MemoryPool::new(policy));
-        self.memory_pools.insert(pool_id, pool.clone());
+    /// Whether an agent is registered and not unreachable.
+    pub fn is_reachable(&self, agent_id: &AgentId) -> bool {
+        self.agent_status(agent_id) == Some(AgentStatus::Alive)
+    }
 
-        pool
+    /// Mark agents silent past the heartbeat timeout unreachable, and
+    /// deregister unreachable agents silent past the grace period, retiring
+    /// their namespace grants until they register again.
+    pub fn reap_dead_agents(&self) -> ReapReport {
+        self.reap_at(Instant::now())
+    }
+
+    /// Run `reap_dead_agents` every `LivenessConfig::reap_interval` until
+    /// the coordinator is dropped.
+    pub fn spawn_reaper(self: &Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let coordinator = Arc::downgrade(self);
+        let mut interval = tokio::time::interval(self.liveness_config.reap_interval());
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        tokio::spawn(async move {
+            loop {
+                interval.tick().await;
+                let Some(coordinator) = coordinator.upgrade() else {
+                    break;
+                };
+                coordinator.reap_dead_agents();
+            }
+        })
+    }
+
+    fn reap_at(&self, now: Instant) -> ReapReport {
+        let timeout = self.liveness_config.heartbeat_timeout();
+        let grace = self.liveness_config.deregister_after();
+        let silent_for = |liveness: &Liveness| now.saturating_duration_since(liveness.last_heartbeat);
+
+        let mut report = ReapReport::default();
+        for mut entry in self.liveness.iter_mut() {
+            let silence = silent_for(entry.value());
+            match entry.status {
+                AgentStatus::Alive if silence >= timeout => {
+                    entry.status = AgentStatus::Unreachable;
+                    report.unreachable.push(entry.key().clone());
+                }
+                AgentStatus::Unreachable if silence >= grace => {
+                    report.deregistered.push(entry.key().clone());
+                }
+                _ => {}
+            }
+        }
+
+        for agent_id in &report.unreachable {
+            warn!("Agent {} missed its heartbeats, marking it unreachable", agent_id);
+            self.notify_status(agent_id, AgentTransition::Unreachable);
+        }
+
+        // A heartbeat may have arrived since the scan
+        report.deregistered.retain(|agent_id| {
+            self.liveness
+                .remove_if(agent_id, |_, liveness| {
+                    liveness.status == AgentStatus::Unreachable && silent_for(liveness) >= grace
+                })
+                .is_some()
+        });
+        for agent_id in &report.deregistered {
+            warn!("Deregistering agent {} after its grace period", agent_id);
+            self.agents.remove(agent_id);
+            let last_seen = self.metrics.remove(agent_id).and_then(|(_, m)| m.last_seen());
+            let grants = self.retire_grants(agent_id);
+            self.retired_grants.insert(agent_id.clone(), grants);
+            self.emit_status(agent_id, AgentTransition::Deregistered, last_seen);
+        }
+
+        report
+    }
+
+    /// Remove every namespace grant held by or given on the agent's namespace.
+    fn retire_grants(&self, agent_id: &AgentId) -> RetiredGrants {
+        let own_namespace = agent_namespace(agent_id);
+        let own = self.namespace_grants.remove(&own_namespace).map(|(_, acl)| acl);
+
+        let mut held = Vec::new();
+        for mut entry in self.namespace_grants.iter_mut() {
+            if let Some(level) = entry.grant_level(agent_id) {
+                entry.revoke(agent_id);
+                held.push((entry.key().clone(), level));
+            }
+        }
+
+        RetiredGrants { held, own }
+    }
+
+    fn restore_grants(&self, agent_id: &AgentId, grants: RetiredGrants) {
+        if let Some(acl) = grants.own {
+            self.namespace_grants.entry(agent_namespace(agent_id)).or_insert(acl);
+        }
+        for (namespace, level) in grants.held {
+            self.grant_access(&namespace, agent_id.clone(), level);
+        }
+    }
+
+    fn notify_status(&self, agent_id: &AgentId, transition: AgentTransition) {
+        let last_seen = self.metrics.get(agent_id).and_then(|m| m.last_seen());
+        self.emit_status(agent_id, transition, last_seen);
+    }
+
+    /// Run the status callback, outside any registry lock so it may use the
+    /// coordinator.
+    fn emit_status(
+        &self,
+        agent_id: &AgentId,
+        transition: AgentTransition,
+        last_seen: Option<chrono::DateTime<chrono::Utc>>,
+    ) {
+        let callback = self.on_status_change.read().clone();
+        if let Some(callback) = callback {
+            callback(&AgentStatusEvent {
+                agent_id: agent_id.clone(),
+                transition,
+                last_seen,
+                timestamp: chrono::Utc::now(),
+            });
+        }
+    }
+
+    /// Get agent context.
+    pub fn get_agent(&self, agent_id: &AgentId) -> Option<Arc<RwLock<AgentContext>>> {
+        self.agents.get(agent_id).map(|a| a.clone())
+    }
+
+    /// List all registered agents.
+    pub fn list_agents(&self) -> Vec<AgentId> {
+        self.agents.iter().map(|e| e.key().clone()).collect()
+    }
+
+    /// Get agent metrics, with memory pool usage brought up to date.
+    pub fn get_metrics(&self, agent_id: &AgentId) -> Option<Arc<AgentMetrics>> {
+        let metrics = self.metrics.get(agent_id).map(|m| m.clone())?;
+        metrics.record_pool_usage(self.agent_pool_usage(agent_id));
+        Some(metrics)
+    }
+
+    /// Limit what may be indexed into a namespace from now on. Documents
+    /// already indexed stay, even if they exceed the new quota.
+    pub fn set_namespace_quota(&self, namespace: &str, quota: NamespaceQuota) {
+        info!("Setting quota of namespace {}: {:?}", namespace, quota);
+        self.namespace_accounting
+            .entry(namespace.to_string())
+            .or_default()
+            .quota = quota;
+    }
+
+    /// Quota of a namespace; unlimited unless set.
+    pub fn namespace_quota(&self, namespace: &str) -> NamespaceQuota {
+        self.namespace_accounting
+            .get(namespace)
+            .map(|accounting| accounting.quota)
+            .unwrap_or_default()
+    }
+
+    /// Account for a document an agent is about to index into a namespace.
+    ///
+    /// Fails with `SemanticError::QuotaExceeded` when the document would take
+    /// the namespace past its quota; re-indexing a document replaces its
+    /// previous size. Returns whether the document was already accounted for.
+    pub fn reserve_index(
+        &self,
+        agent_id: &AgentId,
+        namespace: &str,
+        doc_id: &DocumentId,
+        vector_bytes: u64,
+    ) -> Result<bool> {
+        let previous = {
+            let mut accounting = self.namespace_accounting.entry(namespace.to_string()).or_default();
+            let current = accounting.usage();
+            let replaced_bytes = accounting.documents.get(doc_id).map(|(_, bytes)| *bytes);
+
+            let requested = IndexUsage {
+                documents: current.documents + u64::from(replaced_bytes.is_none()),
+                vector_bytes: current.vector_bytes - replaced_bytes.unwrap_or(0) + vector_bytes,
+            };
+            let checks = [
+                ("documents", requested.documents, current.documents, accounting.quota.max_documents),
+                ("vector_bytes", requested.vector_bytes, current.vector_bytes, accounting.quota.max_vector_bytes),
+            ];
+            for (resource, requested, current, limit) in checks {
+                if let Some(limit) = limit.filter(|limit| requested > *limit) {
+                    return Err(SemanticError::QuotaExceeded {
+                        agent_id: agent_id.clone(),
+                        resource: resource.to_string(),
+                        requested,
+                        current,
+                        limit,
+                    });
+                }
+            }
+
+            accounting.vector_bytes = requested.vector_bytes;
+            accounting
+                .documents
+                .insert(doc_id.clone(), (agent_id.clone(), vector_bytes))
+        };
+
+        if let Some((writer, bytes)) = &previous {
+            self.record_indexed(writer, *bytes, false);
+        }
+        self.record_indexed(agent_id, vector_bytes, true);
+        Ok(previous.is_some())
+    }
+
+    /// Stop accounting for a document removed from a namespace. Returns
+    /// whether it was accounted for.
+    pub fn release_index(&self, namespace: &str, doc_id: &DocumentId) -> bool {
+        let removed = self.namespace_accounting.get_mut(namespace).and_then(|mut accounting| {
+            let removed = accounting.documents.remove(doc_id)?;
+            accounting.vector_bytes = accounting.vector_bytes.saturating_sub(removed.1);
+            Some(removed)
+        });
+
+        match removed {
+            Some((writer, bytes)) => {
+                self.record_indexed(&writer, bytes, false);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn record_indexed(&self, agent_id: &AgentId, vector_bytes: u64, added: bool) {
+        if let Some(metrics) = self.metrics.get(agent_id) {
+            metrics.record_indexed(vector_bytes, added);
+        }
+    }
+
+    /// Index usage and quotas of every namespace indexed into through
+    /// agents, and usage per agent, for dashboards.
+    pub fn usage_report(&self) -> UsageReport {
+        let mut report = UsageReport::default();
+        for entry in self.namespace_accounting.iter() {
+            let usage = entry.usage();
+            report.total.documents += usage.documents;
+            report.total.vector_bytes += usage.vector_bytes;
+            report.namespaces.insert(
+                entry.key().clone(),
+                NamespaceUsage {
+                    usage,
+                    quota: entry.quota,
+                },
+            );
+            for (agent_id, bytes) in entry.documents.values() {
+                let agent = report.agents.entry(agent_id.clone()).or_default();
+                agent.documents += 1;
+                agent.vector_bytes += bytes;
+            }
+        }
+        report
+    }
+
+    /// Callback crediting embedding tokens to the agent each batch was
+    /// embedded for. Register it with `EmbeddingProvider::set_usage_callback`
+    /// and run agent work inside `attribute_usage_to`.
+    pub fn embedding_usage_callback(&self) -> UsageCallback {
+        let metrics = self.metrics.clone();
+        Arc::new(move |usage: &BatchUsage| {
+            let Some(agent_id) = &usage.agent_id else {
+                return;
+            };
+            if let Some(agent_metrics) = metrics.get(agent_id) {
+                agent_metrics.record_embedding_usage(usage);
+            }
+        })
+    }
+
+    /// An agent's memory pool usage, summed over all pools.
+    pub fn agent_pool_usage(&self, agent_id: &AgentId) -> AgentPoolUsage {
+        let mut total = AgentPoolUsage::default();
+        for pool in self.memory_pools.iter() {
+            if let Some(usage) = pool.usage().agents.get(agent_id) {
+                total.add(usage);
+            }
+        }
+        total
+    }
+
+    /// Storage of per-agent preference profiles, shared with personalized rankers.
+    pub fn preference_profiles(&self) -> Arc<DashMap<AgentId, PreferenceProfile>> {
+        self.preference_profiles.clone()
+    }
+
+    /// Get an agent's preference profile.
+    pub fn preference_profile(&self, agent_id: &AgentId) -> Option<PreferenceProfile> {
+        self.preference_profiles.get(agent_id).map(|p| p.clone())
+    }
+
+    /// Grant an agent access to a namespace.
+    ///
+    /// Granting again replaces the previous level.
+    pub fn grant_access(
+        &self,
+        from_namespace: &str,
+        to_agent: impl Into<AgentId>,
+        level: AccessLevel,
+    ) {
+        let to_agent = to_agent.into();
+        info!("Granting {:?} access on {} to {}", level, from_namespace, to_agent);
+
+        let mut acl = self
+            .namespace_grants
+            .entry(from_namespace.to_string())
+            .or_insert_with(|| {
+                let mut acl = AccessControl::new(AccessPolicy::Private);
+                if let Some(owner) = namespace_owner(from_namespace) {
+                    acl.add_owner(owner);
+                }
+                acl
+            });
+        acl.revoke(&to_agent);
+        acl.add_reader(to_agent.clone());
+        if level == AccessLevel::ReadWrite {
+            acl.add_writer(to_agent);
+        }
+    }
+
+    /// Revoke an agent's access to a namespace; effective from the next query.
+    /// Returns whether a grant existed.
+    pub fn revoke_access(&self, from_namespace: &str, agent_id: &AgentId) -> bool {
+        let revoked = self
+            .namespace_grants
+            .get_mut(from_namespace)
+            .is_some_and(|mut acl| acl.revoke(agent_id));
+        if revoked {
+            info!("Revoked access on {} from {}", from_namespace, agent_id);
+        }
+        revoked
+    }
+
+    /// Access an agent has to a namespace: its own, a grant, or everything
+    /// for orchestrators.
+    pub async fn namespace_access(
+        &self,
+        agent_id: &AgentId,
+        namespace: &str,
+    ) -> Option<AccessLevel> {
+        let owns = namespace_owner(namespace) == Some(agent_id.as_str());
+        if owns || self.is_orchestrator(agent_id).await {
+            return Some(AccessLevel::ReadWrite);
+        }
+        self.namespace_grants
+            .get(namespace)
+            .and_then(|acl| acl.grant_level(agent_id))
+    }
+
+    /// Namespaces an agent may read, or `None` if it may read all of them.
+    pub async fn readable_namespaces(&self, agent_id: &AgentId) -> Option<BTreeSet<Namespace>> {
+        if self.is_orchestrator(agent_id).await {
+            return None;
+        }
+
+        let mut namespaces: BTreeSet<Namespace> = self
+            .namespace_grants
+            .iter()
+            .filter(|entry| entry.value().grant_level(agent_id).is_some())
+            .map(|entry| entry.key().clone())
+            .collect();
+        namespaces.insert(agent_namespace(agent_id));
+        Some(namespaces)
+    }
+
+    /// Record a denied namespace access. Callers return an empty result
+    /// instead of an error so the namespace's existence is not revealed.
+    pub fn audit_denied_access(&self, agent_id: &AgentId, namespace: &str, action: &str) {
+        warn!("Denied {} on namespace {} for agent {}", action, namespace, agent_id);
+
+        let mut audit = self.access_audit.lock();
+        if audit.len() >= MAX_AUDIT_ENTRIES {
+            audit.pop_front();
+        }
+        audit.push_back(AccessAuditEntry {
+            agent_id: agent_id.clone(),
+            namespace: namespace.to_string(),
+            action: action.to_string(),
+            timestamp: chrono::Utc::now(),
+        });
+    }
+
+    /// Denied namespace accesses, oldest first.
+    pub fn access_audit_log(&self) -> Vec<AccessAuditEntry> {
+        self.access_audit.lock().iter().cloned().collect()
+    }
+
+    async fn is_orchestrator(&self, agent_id: &AgentId) -> bool {
+        match self.get_agent(agent_id) {
+            Some(context) => context.read().await.role == AgentRole::Orchestrator,
+            None => false,
+        }
+    }
+
+    /// Create a memory pool.
+    pub fn create_memory_pool(&self, pool_id: impl Into<String>, policy: AccessPolicy) -> Arc<MemoryPool> {
+        self.create_memory_pool_with_limits(pool_id, policy, MemoryPoolLimits::default())
+    }
+
+    /// Create a memory pool bounded by `limits`.
+    pub fn create_memory_pool_with_limits(
+        &self,
+        pool_id: impl Into<String>,
+        policy: AccessPolicy,
+        limits: MemoryPoolLimits,
+    ) -> Arc<MemoryPool> {
+        let pool_id = pool_id.into();
+
+        info!("Creating memory pool: {} (policy: {:?}, limits: {:?})", pool_id, policy, limits);
+
+        let pool = Arc::new(MemoryPool::with_limits(policy, limits));
+        self.memory_pools.insert(pool_id, pool.clone());
+
+        pool
     }
 
     /// Get a memory pool.
@@ -571,6 +1807,12 @@ impl AgentCoordinator {
 
         stats.insert("total_agents".to_string(),
             serde_json::json!(self.agents.len()));
+        stats.insert("unreachable_agents".to_string(),
+            serde_json::json!(self
+                .liveness
+                .iter()
+                .filter(|entry| entry.status == AgentStatus::Unreachable)
+                .count()));
         stats.insert("total_memory_pools".to_string(),
             serde_json::json!(self.memory_pools.len()));
 
@@ -635,79 +1877,621 @@ impl PrioritizedSearchRequest {
     pub fn age(&self) -> Duration {
         self.created_at.elapsed()
     }
+
+    /// Priority after moving up one level per `aging_interval` waited.
+    pub fn effective_priority(&self, aging_interval: Option<Duration>) -> SearchPriority {
+        let steps = match aging_interval {
+            Some(interval) if !interval.is_zero() => {
+                (self.age().as_nanos() / interval.as_nanos()) as usize
+            }
+            _ => 0,
+        };
+        SearchPriority::ALL[(self.priority as usize).saturating_sub(steps)]
+    }
+}
+
+/// A queued search request as written to a `QueueStore`.
+///
+/// The enqueue time is kept as wall-clock time, so requests restored after
+/// a restart keep aging from when they were first enqueued.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PersistedSearchRequest {
+    pub request_id: String,
+    pub agent_id: AgentId,
+    pub query: String,
+    pub priority: SearchPriority,
+    pub namespace: Option<Namespace>,
+    pub enqueued_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<&PrioritizedSearchRequest> for PersistedSearchRequest {
+    fn from(request: &PrioritizedSearchRequest) -> Self {
+        let age = chrono::Duration::from_std(request.age()).unwrap_or_default();
+        Self {
+            request_id: request.request_id.clone(),
+            agent_id: request.agent_id.clone(),
+            query: request.query.clone(),
+            priority: request.priority,
+            namespace: request.namespace.clone(),
+            enqueued_at: chrono::Utc::now() - age,
+        }
+    }
+}
+
+impl From<PersistedSearchRequest> for PrioritizedSearchRequest {
+    fn from(request: PersistedSearchRequest) -> Self {
+        let waited = (chrono::Utc::now() - request.enqueued_at)
+            .to_std()
+            .unwrap_or_default();
+        Self {
+            request_id: request.request_id,
+            agent_id: request.agent_id,
+            query: request.query,
+            priority: request.priority,
+            namespace: request.namespace,
+            created_at: Instant::now().checked_sub(waited).unwrap_or_else(Instant::now),
+        }
+    }
+}
+
+/// Storage for the requests of a search queue, so they survive a restart.
+pub trait QueueStore: Send + Sync {
+    /// Replace the stored requests with `requests`.
+    fn save(&self, requests: &[PersistedSearchRequest]) -> Result<()>;
+
+    /// The stored requests; empty when nothing was saved.
+    fn load(&self) -> Result<Vec<PersistedSearchRequest>>;
+}
+
+/// Queued requests stored as a JSON array in a file.
+#[derive(Debug, Clone)]
+pub struct FileQueueStore {
+    path: PathBuf,
+}
+
+impl FileQueueStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl QueueStore for FileQueueStore {
+    fn save(&self, requests: &[PersistedSearchRequest]) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        // Write then rename, so a crash mid-write keeps the previous queue
+        let tmp = self.path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec(requests)?)?;
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+
+    fn load(&self) -> Result<Vec<PersistedSearchRequest>> {
+        let content = match std::fs::read(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(serde_json::from_slice(&content)?)
+    }
+}
+
+/// Upper bounds of the queue latency histogram buckets, in milliseconds.
+/// Waits above the last bound land in an overflow bucket.
+const LATENCY_BUCKETS_MS: [u64; 12] =
+    [1, 5, 10, 50, 100, 500, 1_000, 5_000, 30_000, 60_000, 300_000, 600_000];
+
+/// How the queue picks the next priority level to serve.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum DequeuePolicy {
+    /// Always serve the highest non-empty level
+    #[default]
+    StrictPriority,
+    /// Critical requests go first; the other levels share dequeues in
+    /// proportion to their weights. A level without weight is served only
+    /// when every weighted level is empty.
+    WeightedFair { weights: BTreeMap<SearchPriority, u32> },
+}
+
+impl DequeuePolicy {
+    /// 70/20/10 split between High, Normal and Low.
+    pub fn weighted_fair() -> Self {
+        Self::WeightedFair {
+            weights: BTreeMap::from([
+                (SearchPriority::High, 70),
+                (SearchPriority::Normal, 20),
+                (SearchPriority::Low, 10),
+            ]),
+        }
+    }
+}
+
+/// Search queue configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchQueueConfig {
+    /// Maximum requests waiting per priority level
+    pub max_queue_size: usize,
+    /// Wait after which a request moves up one priority level; `None`
+    /// disables aging
+    pub aging_interval_ms: Option<u64>,
+    pub dequeue_policy: DequeuePolicy,
+    /// File queued requests are saved to and restored from on startup;
+    /// `None` keeps the queue in memory only
+    pub persist_path: Option<PathBuf>,
+}
+
+impl SearchQueueConfig {
+    pub fn aging_interval(&self) -> Option<Duration> {
+        self.aging_interval_ms
+            .filter(|ms| *ms > 0)
+            .map(Duration::from_millis)
+    }
+}
+
+impl Default for SearchQueueConfig {
+    fn default() -> Self {
+        Self {
+            max_queue_size: 1000,
+            aging_interval_ms: Some(30_000),
+            dequeue_policy: DequeuePolicy::default(),
+            persist_path: None,
+        }
+    }
+}
+
+/// Histogram of the time requests spent queued.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LatencyHistogram {
+    /// Requests per bucket of `LatencyHistogram::bucket_bounds_ms`, plus a
+    /// final overflow bucket
+    pub counts: Vec<u64>,
+    pub count: u64,
+    pub sum_ms: u64,
+    pub max_ms: u64,
+}
+
+impl LatencyHistogram {
+    /// Upper bounds of the buckets, in milliseconds.
+    pub fn bucket_bounds_ms() -> &'static [u64] {
+        &LATENCY_BUCKETS_MS
+    }
+
+    pub fn record(&mut self, wait: Duration) {
+        if self.counts.is_empty() {
+            self.counts = vec![0; LATENCY_BUCKETS_MS.len() + 1];
+        }
+        let ms = wait.as_millis() as u64;
+        let bucket = LATENCY_BUCKETS_MS.partition_point(|bound| *bound < ms);
+        self.counts[bucket] += 1;
+        self.count += 1;
+        self.sum_ms += ms;
+        self.max_ms = self.max_ms.max(ms);
+    }
+
+    pub fn mean_ms(&self) -> f64 {
+        if self.count == 0 {
+            return 0.0;
+        }
+        self.sum_ms as f64 / self.count as f64
+    }
+
+    /// Upper bound of the bucket holding the `p`-th percentile (0-100),
+    /// capped at the largest wait seen.
+    pub fn percentile_ms(&self, p: f64) -> u64 {
+        if self.count == 0 {
+            return 0;
+        }
+        let rank = ((p.clamp(0.0, 100.0) / 100.0) * self.count as f64).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (bucket, count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                let bound = LATENCY_BUCKETS_MS.get(bucket).copied().unwrap_or(self.max_ms);
+                return bound.min(self.max_ms);
+            }
+        }
+        self.max_ms
+    }
+}
+
+/// One priority level of a `SearchQueueStatus`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriorityQueueStatus {
+    pub priority: SearchPriority,
+    /// Requests waiting at this level, including ones aged into it
+    pub depth: usize,
+    /// Requests waiting at this level that were enqueued at a lower one
+    #[serde(default)]
+    pub aged: usize,
+    /// Wait of the oldest request at this level
+    pub oldest_wait_ms: u64,
+    /// Queue latency of dequeued requests by their requested priority
+    pub latency: LatencyHistogram,
+}
+
+/// Snapshot of a search queue.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SearchQueueStatus {
+    pub policy: DequeuePolicy,
+    /// Levels from Critical to Background
+    pub levels: Vec<PriorityQueueStatus>,
+    /// Requests moved up a level by aging
+    pub promoted: u64,
+    pub cancelled: u64,
+    /// Requests dropped because their level was full
+    pub dropped: u64,
+}
+
+impl SearchQueueStatus {
+    /// Requests waiting across all levels.
+    pub fn total_depth(&self) -> usize {
+        self.levels.iter().map(|level| level.depth).sum()
+    }
+}
+
+impl fmt::Display for SearchQueueStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<12} {:>6} {:>5} {:>11} {:>7} {:>9} {:>8} {:>8}",
+            "priority", "depth", "aged", "oldest(ms)", "served", "mean(ms)", "p95(ms)", "max(ms)"
+        )?;
+        for level in &self.levels {
+            writeln!(
+                f,
+                "{:<12} {:>6} {:>5} {:>11} {:>7} {:>9.1} {:>8} {:>8}",
+                level.priority.as_str(),
+                level.depth,
+                level.aged,
+                level.oldest_wait_ms,
+                level.latency.count,
+                level.latency.mean_ms(),
+                level.latency.percentile_ms(95.0),
+                level.latency.max_ms
+            )?;
+        }
+        write!(
+            f,
+            "promoted {}, cancelled {}, dropped {}",
+            self.promoted, self.cancelled, self.dropped
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+struct QueueState {
+    /// Requests by effective priority, oldest first
+    queues: HashMap<SearchPriority, VecDeque<PrioritizedSearchRequest>>,
+    /// Weighted-fair credits per level
+    credits: HashMap<SearchPriority, i64>,
+    /// Queue latency by requested priority
+    latency: HashMap<SearchPriority, LatencyHistogram>,
+    promoted: u64,
+    cancelled: u64,
+    dropped: u64,
+}
+
+impl QueueState {
+    fn new() -> Self {
+        let mut state = Self::default();
+        for priority in SearchPriority::ALL {
+            state.queues.insert(priority, VecDeque::new());
+        }
+        state
+    }
+
+    /// Move requests that waited long enough to their aged priority,
+    /// keeping every level ordered by age.
+    fn promote_aged(&mut self, aging_interval: Duration) {
+        let mut aged = Vec::new();
+        for level in &SearchPriority::ALL[1..] {
+            if let Some(queue) = self.queues.get_mut(level) {
+                let mut i = 0;
+                while i < queue.len() {
+                    if queue[i].effective_priority(Some(aging_interval)) < *level {
+                        aged.extend(queue.remove(i));
+                    } else {
+                        i += 1;
+                    }
+                }
+            }
+        }
+
+        for request in aged {
+            let queue = self
+                .queues
+                .entry(request.effective_priority(Some(aging_interval)))
+                .or_default();
+            let position = queue.partition_point(|r| r.created_at <= request.created_at);
+            queue.insert(position, request);
+            self.promoted += 1;
+        }
+    }
+
+    /// Every queued request, oldest first.
+    fn snapshot(&self) -> Vec<PersistedSearchRequest> {
+        let mut requests: Vec<&PrioritizedSearchRequest> =
+            self.queues.values().flatten().collect();
+        requests.sort_by_key(|request| request.created_at);
+        requests.into_iter().map(PersistedSearchRequest::from).collect()
+    }
+
+    fn next_level(&mut self, policy: &DequeuePolicy) -> Option<SearchPriority> {
+        let waiting: Vec<SearchPriority> = SearchPriority::ALL
+            .into_iter()
+            .filter(|level| self.queues.get(level).is_some_and(|q| !q.is_empty()))
+            .collect();
+
+        let weights = match policy {
+            DequeuePolicy::StrictPriority => return waiting.first().copied(),
+            DequeuePolicy::WeightedFair { weights } => weights,
+        };
+        if waiting.first() == Some(&SearchPriority::Critical) {
+            return Some(SearchPriority::Critical);
+        }
+
+        // Smooth weighted round-robin over the waiting weighted levels
+        self.credits.retain(|level, _| waiting.contains(level));
+        let weighted: Vec<(SearchPriority, i64)> = waiting
+            .iter()
+            .filter_map(|level| {
+                let weight = weights.get(level).copied().unwrap_or(0);
+                (weight > 0).then_some((*level, weight as i64))
+            })
+            .collect();
+        if weighted.is_empty() {
+            return waiting.first().copied();
+        }
+
+        let total: i64 = weighted.iter().map(|(_, weight)| weight).sum();
+        let mut best: Option<(SearchPriority, i64)> = None;
+        for (level, weight) in weighted {
+            let credit = self.credits.entry(level).or_insert(0);
+            *credit += weight;
+            if best.is_none_or(|(_, best_credit)| *credit > best_credit) {
+                best = Some((level, *credit));
+            }
+        }
+
+        let (level, _) = best?;
+        if let Some(credit) = self.credits.get_mut(&level) {
+            *credit -= total;
+        }
+        Some(level)
+    }
 }
 
 /// Priority queue for search requests.
+///
+/// Requests age: every `aging_interval` a request waits moves it up one
+/// level, so low priority work runs eventually under a steady stream of
+/// higher priority requests.
+///
+/// With a `QueueStore`, the queued requests are saved after every change
+/// and restored when the queue is created.
 pub struct SearchQueue {
-    /// Queues by priority level
-    queues: Arc<RwLock<HashMap<SearchPriority, VecDeque<PrioritizedSearchRequest>>>>,
-    /// Maximum queue size per priority
-    max_queue_size: usize,
+    state: Arc<RwLock<QueueState>>,
+    config: SearchQueueConfig,
+    store: Option<Arc<dyn QueueStore>>,
 }
 
 impl SearchQueue {
     /// Create a new search queue.
     pub fn new(max_queue_size: usize) -> Self {
-        let mut queues = HashMap::new();
-        queues.insert(SearchPriority::Critical, VecDeque::new());
-        queues.insert(SearchPriority::High, VecDeque::new());
-        queues.insert(SearchPriority::Normal, VecDeque::new());
-        queues.insert(SearchPriority::Low, VecDeque::new());
-        queues.insert(SearchPriority::Background, VecDeque::new());
+        Self::with_config(SearchQueueConfig {
+            max_queue_size,
+            ..Default::default()
+        })
+    }
 
+    /// Create a search queue with aging and dequeue policy from `config`.
+    ///
+    /// With `config.persist_path`, requests saved there are restored; a
+    /// file that can't be read is logged and the queue starts empty.
+    pub fn with_config(config: SearchQueueConfig) -> Self {
+        let Some(path) = config.persist_path.clone() else {
+            return Self::build(config, None);
+        };
+
+        let store: Arc<dyn QueueStore> = Arc::new(FileQueueStore::new(&path));
+        match Self::with_store(config.clone(), store.clone()) {
+            Ok(queue) => queue,
+            Err(e) => {
+                warn!("Failed to restore search queue from {}: {}", path.display(), e);
+                Self::build(config, Some(store))
+            }
+        }
+    }
+
+    /// Create a search queue that saves its requests to `store`, restoring
+    /// the ones already saved there.
+    pub fn with_store(config: SearchQueueConfig, store: Arc<dyn QueueStore>) -> Result<Self> {
+        let mut restored = store.load()?;
+        restored.sort_by_key(|request| request.enqueued_at);
+        if !restored.is_empty() {
+            info!("Restoring {} queued search requests", restored.len());
+        }
+
+        let mut state = QueueState::new();
+        for request in restored {
+            let queue = state.queues.entry(request.priority).or_default();
+            if queue.len() >= config.max_queue_size {
+                queue.pop_front();
+                state.dropped += 1;
+            }
+            queue.push_back(request.into());
+        }
+
+        Ok(Self {
+            state: Arc::new(RwLock::new(state)),
+            config,
+            store: Some(store),
+        })
+    }
+
+    fn build(config: SearchQueueConfig, store: Option<Arc<dyn QueueStore>>) -> Self {
         Self {
-            queues: Arc::new(RwLock::new(queues)),
-            max_queue_size,
+            state: Arc::new(RwLock::new(QueueState::new())),
+            config,
+            store,
         }
     }
 
+    /// Save the queued requests to the store, if any. Failures are logged
+    /// rather than failing the queue operation.
+    fn persist(&self, state: &QueueState) {
+        if let Some(store) = &self.store {
+            if let Err(e) = store.save(&state.snapshot()) {
+                warn!("Failed to persist search queue: {}", e);
+            }
+        }
+    }
+
+    /// Save the queued requests to the store now, returning any error.
+    pub async fn flush(&self) -> Result<()> {
+        let Some(store) = &self.store else {
+            return Ok(());
+        };
+        let state = self.state.read().await;
+        store.save(&state.snapshot())
+    }
+
+    pub fn config(&self) -> &SearchQueueConfig {
+        &self.config
+    }
+
     /// Enqueue a search request.
     pub async fn enqueue(&self, request: PrioritizedSearchRequest) -> Result<()> {
-        let mut queues = self.queues.write().await;
+        let mut state = self.state.write().await;
 
-        let queue = queues.get_mut(&request.priority)
+        let queue = state.queues.get_mut(&request.priority)
             .ok_or_else(|| SemanticError::Search("Invalid priority".to_string()))?;
 
-        if queue.len() >= self.max_queue_size {
+        let dropped = if queue.len() >= self.config.max_queue_size {
             warn!("Queue full for priority {:?}, dropping oldest request", request.priority);
-            queue.pop_front();
-        }
+            queue.pop_front().is_some()
+        } else {
+            false
+        };
 
         debug!("Enqueuing request {} for agent {} with priority {:?}",
             request.request_id, request.agent_id, request.priority);
 
         queue.push_back(request);
+        if dropped {
+            state.dropped += 1;
+        }
+        self.persist(&state);
         Ok(())
     }
 
-    /// Dequeue highest priority request.
+    /// Dequeue the next request according to the dequeue policy, after
+    /// applying aging.
     pub async fn dequeue(&self) -> Option<PrioritizedSearchRequest> {
-        let mut queues = self.queues.write().await;
-
-        // Try each priority in order
-        for priority in [
-            SearchPriority::Critical,
-            SearchPriority::High,
-            SearchPriority::Normal,
-            SearchPriority::Low,
-            SearchPriority::Background,
-        ] {
-            if let Some(queue) = queues.get_mut(&priority) {
-                if let Some(request) = queue.pop_front() {
-                    debug!("Dequeuing request {} with priority {:?}",
-                        request.request_id, priority);
-                    return Some(request);
-                }
+        let mut state = self.state.write().await;
+
+        if let Some(interval) = self.config.aging_interval() {
+            state.promote_aged(interval);
+        }
+
+        let level = state.next_level(&self.config.dequeue_policy)?;
+        let request = state.queues.get_mut(&level)?.pop_front()?;
+
+        debug!("Dequeuing request {} with priority {:?} from level {:?}",
+            request.request_id, request.priority, level);
+
+        state
+            .latency
+            .entry(request.priority)
+            .or_default()
+            .record(request.age());
+        self.persist(&state);
+        Some(request)
+    }
+
+    /// Remove a queued request. Returns whether it was still queued.
+    pub async fn cancel(&self, request_id: &str) -> bool {
+        let mut state = self.state.write().await;
+
+        let mut cancelled = false;
+        for queue in state.queues.values_mut() {
+            if let Some(position) = queue.iter().position(|r| r.request_id == request_id) {
+                queue.remove(position);
+                cancelled = true;
+                break;
             }
         }
 
-        None
+        if cancelled {
+            debug!("Cancelled queued request {}", request_id);
+            state.cancelled += 1;
+            self.persist(&state);
+        }
+        cancelled
+    }
+
+    /// Remove every queued request of an agent. Returns how many were queued.
+    pub async fn cancel_agent(&self, agent_id: &AgentId) -> usize {
+        let mut state = self.state.write().await;
+
+        let mut cancelled = 0;
+        for queue in state.queues.values_mut() {
+            let before = queue.len();
+            queue.retain(|r| r.agent_id != *agent_id);
+            cancelled += before - queue.len();
+        }
+
+        if cancelled > 0 {
+            debug!("Cancelled {} queued requests of agent {}", cancelled, agent_id);
+            state.cancelled += cancelled as u64;
+            self.persist(&state);
+        }
+        cancelled
     }
 
     /// Get queue sizes.
     pub async fn queue_sizes(&self) -> HashMap<SearchPriority, usize> {
-        let queues = self.queues.read().await;
-        queues.iter().map(|(p, q)| (*p, q.len())).collect()
+        let state = self.state.read().await;
+        state.queues.iter().map(|(p, q)| (*p, q.len())).collect()
+    }
+
+    /// Depths, waits and latency histograms of every priority level, after
+    /// applying aging.
+    pub async fn queue_status(&self) -> SearchQueueStatus {
+        let mut state = self.state.write().await;
+
+        if let Some(interval) = self.config.aging_interval() {
+            state.promote_aged(interval);
+        }
+
+        let levels = SearchPriority::ALL
+            .into_iter()
+            .map(|priority| {
+                let queue = state.queues.get(&priority);
+                PriorityQueueStatus {
+                    priority,
+                    depth: queue.map_or(0, |q| q.len()),
+                    aged: queue.map_or(0, |q| q.iter().filter(|r| r.priority > priority).count()),
+                    oldest_wait_ms: queue
+                        .and_then(|q| q.front())
+                        .map_or(0, |r| r.age().as_millis() as u64),
+                    latency: state.latency.get(&priority).cloned().unwrap_or_default(),
+                }
+            })
+            .collect();
+
+        SearchQueueStatus {
+            policy: self.config.dequeue_policy.clone(),
+            levels,
+            promoted: state.promoted,
+            cancelled: state.cancelled,
+            dropped: state.dropped,
+        }
     }
 }
 
@@ -749,6 +2533,41 @@ mod tests {
         assert!(!ac.can_read(&"stranger".to_string(), AgentRole::Worker));
     }
 
+    #[tokio::test]
+    async fn test_namespace_grants_and_revocation() {
+        let coordinator = AgentCoordinator::new();
+        coordinator.register_agent("owner", AgentRole::Worker, vec![]).await.unwrap();
+        coordinator.register_agent("reader", AgentRole::Worker, vec![]).await.unwrap();
+        coordinator.register_agent("lead", AgentRole::Orchestrator, vec![]).await.unwrap();
+
+        let reader = "reader".to_string();
+        let owned = agent_namespace("owner");
+
+        assert_eq!(coordinator.namespace_access(&"owner".to_string(), &owned).await, Some(AccessLevel::ReadWrite));
+        assert_eq!(coordinator.namespace_access(&reader, &owned).await, None);
+        assert_eq!(coordinator.namespace_access(&"lead".to_string(), &owned).await, Some(AccessLevel::ReadWrite));
+        assert_eq!(coordinator.readable_namespaces(&"lead".to_string()).await, None);
+
+        coordinator.grant_access(&owned, "reader", AccessLevel::Read);
+        assert_eq!(coordinator.namespace_access(&reader, &owned).await, Some(AccessLevel::Read));
+        let readable = coordinator.readable_namespaces(&reader).await.unwrap();
+        assert!(readable.contains(&owned));
+        assert!(readable.contains(&agent_namespace("reader")));
+
+        coordinator.grant_access(&owned, "reader", AccessLevel::ReadWrite);
+        assert_eq!(coordinator.namespace_access(&reader, &owned).await, Some(AccessLevel::ReadWrite));
+
+        assert!(coordinator.revoke_access(&owned, &reader));
+        assert!(!coordinator.revoke_access(&owned, &reader));
+        assert_eq!(coordinator.namespace_access(&reader, &owned).await, None);
+        assert!(!coordinator.readable_namespaces(&reader).await.unwrap().contains(&owned));
+
+        coordinator.audit_denied_access(&reader, &owned, "search");
+        let audit = coordinator.access_audit_log();
+        assert_eq!(audit.len(), 1);
+        assert_eq!(audit[0].namespace, owned);
+    }
+
     #[test]
     fn test_access_control_hierarchical() {
         let mut ac = AccessControl::new(AccessPolicy::Hierarchical);
@@ -921,4 +2740,521 @@ mod tests {
         assert_eq!(results.len(), 2);
         assert_eq!(results[0].0, "doc1"); // Should be most similar
     }
+
+    async fn store_doc(pool: &MemoryPool, agent_id: &str, doc_id: &str) -> Result<()> {
+        pool.store(
+            &agent_id.to_string(),
+            AgentRole::Worker,
+            doc_id.to_string(),
+            vec![1.0, 0.0],
+            HashMap::new(),
+        )
+        .await
+    }
+
+    fn record_evictions(pool: &MemoryPool) -> Arc<parking_lot::Mutex<Vec<(DocumentId, EvictionReason)>>> {
+        let evicted = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let sink = evicted.clone();
+        pool.set_eviction_callback(move |entry, reason| {
+            sink.lock().push((entry.doc_id.clone(), reason));
+        });
+        evicted
+    }
+
+    #[tokio::test]
+    async fn test_memory_pool_evicts_least_recently_read() {
+        let pool = MemoryPool::with_limits(
+            AccessPolicy::Shared,
+            MemoryPoolLimits {
+                max_entries: Some(2),
+                ..Default::default()
+            },
+        );
+        let evicted = record_evictions(&pool);
+
+        store_doc(&pool, "agent1", "a").await.unwrap();
+        store_doc(&pool, "agent1", "b").await.unwrap();
+        pool.retrieve(&"agent2".to_string(), AgentRole::Worker, &"a".to_string())
+            .await
+            .unwrap();
+        store_doc(&pool, "agent1", "c").await.unwrap();
+
+        assert_eq!(*evicted.lock(), vec![("b".to_string(), EvictionReason::Capacity)]);
+        let usage = pool.usage();
+        assert_eq!(usage.entries, 2);
+        assert_eq!(usage.evictions, 1);
+        assert_eq!(usage.agents["agent1"].entries, 2);
+        assert_eq!(usage.agents["agent2"].reads, 1);
+    }
+
+    #[tokio::test]
+    async fn test_memory_pool_evicts_expired_first() {
+        let pool = MemoryPool::with_limits(
+            AccessPolicy::Shared,
+            MemoryPoolLimits {
+                max_entries: Some(2),
+                ttl: Some(Duration::from_millis(50)),
+                ..Default::default()
+            },
+        );
+        let evicted = record_evictions(&pool);
+
+        store_doc(&pool, "agent1", "old").await.unwrap();
+        tokio::time::sleep(Duration::from_millis(80)).await;
+        store_doc(&pool, "agent1", "b").await.unwrap();
+        store_doc(&pool, "agent1", "c").await.unwrap();
+
+        assert_eq!(*evicted.lock(), vec![("old".to_string(), EvictionReason::Expired)]);
+
+        tokio::time::sleep(Duration::from_millis(80)).await;
+        let entry = pool
+            .retrieve(&"agent1".to_string(), AgentRole::Worker, &"b".to_string())
+            .await
+            .unwrap();
+        assert!(entry.is_none());
+        assert_eq!(pool.usage().entries, 0);
+        assert_eq!(pool.usage().bytes, 0);
+    }
+
+    #[tokio::test]
+    async fn test_memory_pool_put_with_ttl() {
+        let pool = Arc::new(MemoryPool::new(AccessPolicy::Private));
+        pool.access_control.write().await.add_owner("agent1");
+        let agent1 = "agent1".to_string();
+
+        pool.put_with_ttl(
+            &agent1,
+            AgentRole::Worker,
+            "short".to_string(),
+            vec![1.0, 0.0],
+            HashMap::new(),
+            Duration::from_millis(30),
+        )
+        .await
+        .unwrap();
+        store_doc(&pool, "agent1", "forever").await.unwrap();
+        assert!(pool.retrieve(&agent1, AgentRole::Worker, &"short".to_string()).await.unwrap().is_some());
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        let stats = pool.stats();
+        assert_eq!(stats["live_entries"], 1);
+        assert_eq!(stats["expired_entries"], 1);
+        assert_eq!(stats["total_bytes"], pool.usage().bytes);
+        assert_eq!(pool.usage().agents["agent1"].expired, 1);
+
+        // Readers see an expired entry as missing; others are still denied
+        let stranger = "stranger".to_string();
+        assert!(pool.retrieve(&stranger, AgentRole::Worker, &"short".to_string()).await.is_err());
+        assert!(pool.retrieve(&agent1, AgentRole::Worker, &"short".to_string()).await.unwrap().is_none());
+        assert_eq!(pool.stats()["expired_entries"], 0);
+        assert_eq!(pool.stats()["live_entries"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_memory_pool_sweeper() {
+        let pool = Arc::new(MemoryPool::with_limits(
+            AccessPolicy::Shared,
+            MemoryPoolLimits {
+                ttl: Some(Duration::from_millis(20)),
+                ..Default::default()
+            },
+        ));
+        let evicted = record_evictions(&pool);
+        let sweeper = pool.spawn_sweeper(Duration::from_millis(10));
+
+        store_doc(&pool, "agent1", "a").await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        assert_eq!(*evicted.lock(), vec![("a".to_string(), EvictionReason::Expired)]);
+        assert_eq!(pool.usage().entries, 0);
+
+        drop(pool);
+        tokio::time::timeout(Duration::from_secs(1), sweeper).await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_memory_pool_agent_quota() {
+        let pool = MemoryPool::with_limits(
+            AccessPolicy::Shared,
+            MemoryPoolLimits {
+                max_entries: Some(4),
+                max_agent_share: Some(0.5),
+                ..Default::default()
+            },
+        );
+
+        store_doc(&pool, "agent1", "a").await.unwrap();
+        store_doc(&pool, "agent1", "b").await.unwrap();
+        let err = store_doc(&pool, "agent1", "c").await.unwrap_err();
+        assert!(matches!(
+            err,
+            SemanticError::QuotaExceeded { ref resource, requested: 3, limit: 2, .. } if resource == "entries"
+        ));
+
+        // Overwriting its own entry does not grow the agent's share
+        store_doc(&pool, "agent1", "a").await.unwrap();
+        store_doc(&pool, "agent2", "c").await.unwrap();
+        assert_eq!(pool.usage().entries, 3);
+    }
+
+    #[tokio::test]
+    async fn test_coordinator_surfaces_pool_usage() {
+        let coordinator = AgentCoordinator::new();
+        coordinator
+            .register_agent("agent1", AgentRole::Worker, vec![])
+            .await
+            .unwrap();
+        let pool = coordinator.create_memory_pool_with_limits(
+            "shared",
+            AccessPolicy::Shared,
+            MemoryPoolLimits::default(),
+        );
+        store_doc(&pool, "agent1", "a").await.unwrap();
+
+        let metrics = coordinator.get_metrics(&"agent1".to_string()).unwrap();
+        let map = metrics.to_map();
+        assert_eq!(map["memory_pool_entries"], 1.0);
+        assert_eq!(
+            metrics.memory_usage_bytes.load(std::sync::atomic::Ordering::Relaxed),
+            pool.usage().bytes
+        );
+    }
+
+    fn waited(priority: SearchPriority, wait: Duration) -> PrioritizedSearchRequest {
+        let mut request = PrioritizedSearchRequest::new("agent", "query", priority);
+        request.created_at = Instant::now().checked_sub(wait).unwrap();
+        request
+    }
+
+    #[tokio::test]
+    async fn test_search_queue_aging_prevents_starvation() {
+        let queue = SearchQueue::with_config(SearchQueueConfig {
+            aging_interval_ms: Some(1_000),
+            ..Default::default()
+        });
+
+        // A Low request that waited two intervals now competes as High, and
+        // is older than every High request
+        let low = waited(SearchPriority::Low, Duration::from_millis(2_500));
+        let low_id = low.request_id.clone();
+        queue.enqueue(low).await.unwrap();
+        for _ in 0..3 {
+            queue
+                .enqueue(waited(SearchPriority::High, Duration::from_millis(100)))
+                .await
+                .unwrap();
+        }
+
+        let first = queue.dequeue().await.unwrap();
+        assert_eq!(first.request_id, low_id);
+        assert_eq!(first.priority, SearchPriority::Low);
+
+        let status = queue.queue_status().await;
+        assert_eq!(status.promoted, 1);
+        assert_eq!(status.levels[1].depth, 3);
+        assert_eq!(status.levels[3].latency.count, 1);
+        assert!(status.levels[3].latency.max_ms >= 2_500);
+    }
+
+    #[tokio::test]
+    async fn test_search_queue_weighted_fair() {
+        let queue = SearchQueue::with_config(SearchQueueConfig {
+            aging_interval_ms: None,
+            dequeue_policy: DequeuePolicy::weighted_fair(),
+            ..Default::default()
+        });
+        for priority in [SearchPriority::High, SearchPriority::Normal, SearchPriority::Low] {
+            for _ in 0..10 {
+                queue
+                    .enqueue(PrioritizedSearchRequest::new("agent", "query", priority))
+                    .await
+                    .unwrap();
+            }
+        }
+        queue
+            .enqueue(PrioritizedSearchRequest::new("agent", "query", SearchPriority::Critical))
+            .await
+            .unwrap();
+
+        assert_eq!(queue.dequeue().await.unwrap().priority, SearchPriority::Critical);
+
+        let mut served = HashMap::new();
+        for _ in 0..10 {
+            let request = queue.dequeue().await.unwrap();
+            *served.entry(request.priority).or_insert(0) += 1;
+        }
+        assert_eq!(served[&SearchPriority::High], 7);
+        assert_eq!(served[&SearchPriority::Normal], 2);
+        assert_eq!(served[&SearchPriority::Low], 1);
+    }
+
+    #[tokio::test]
+    async fn test_search_queue_cancel() {
+        let queue = SearchQueue::new(10);
+        let request = PrioritizedSearchRequest::new("agent", "query", SearchPriority::Normal);
+        let request_id = request.request_id.clone();
+        queue.enqueue(request).await.unwrap();
+
+        assert!(queue.cancel(&request_id).await);
+        assert!(!queue.cancel(&request_id).await);
+        assert!(queue.dequeue().await.is_none());
+
+        let status = queue.queue_status().await;
+        assert_eq!(status.cancelled, 1);
+        assert_eq!(status.total_depth(), 0);
+        assert!(status.to_string().contains("cancelled 1"));
+    }
+
+    #[tokio::test]
+    async fn test_search_queue_status_counts_aged_requests() {
+        let queue = SearchQueue::with_config(SearchQueueConfig {
+            aging_interval_ms: Some(1_000),
+            ..Default::default()
+        });
+        queue
+            .enqueue(waited(SearchPriority::Low, Duration::from_millis(1_500)))
+            .await
+            .unwrap();
+        queue
+            .enqueue(waited(SearchPriority::Normal, Duration::from_millis(100)))
+            .await
+            .unwrap();
+
+        let status = queue.queue_status().await;
+        assert_eq!(status.levels[2].depth, 2);
+        assert_eq!(status.levels[2].aged, 1);
+        assert_eq!(status.levels[3].depth, 0);
+        assert_eq!(status.promoted, 1);
+    }
+
+    #[tokio::test]
+    async fn test_search_queue_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = SearchQueueConfig {
+            aging_interval_ms: Some(1_000),
+            persist_path: Some(dir.path().join("queue.json")),
+            ..Default::default()
+        };
+
+        let queue = SearchQueue::with_config(config.clone());
+        let low = waited(SearchPriority::Low, Duration::from_millis(2_500));
+        let low_id = low.request_id.clone();
+        queue.enqueue(low).await.unwrap();
+        queue
+            .enqueue(PrioritizedSearchRequest::new("agent", "high", SearchPriority::High).with_namespace("ns"))
+            .await
+            .unwrap();
+        let cancelled = PrioritizedSearchRequest::new("agent", "gone", SearchPriority::Normal);
+        let cancelled_id = cancelled.request_id.clone();
+        queue.enqueue(cancelled).await.unwrap();
+        assert!(queue.cancel(&cancelled_id).await);
+        drop(queue);
+
+        let restored = SearchQueue::with_config(config);
+        assert_eq!(restored.queue_status().await.total_depth(), 2);
+
+        // The Low request kept its wait across the restart, so it aged past
+        // the newer High request
+        let first = restored.dequeue().await.unwrap();
+        assert_eq!(first.request_id, low_id);
+        assert!(first.age() >= Duration::from_millis(2_500));
+
+        let second = restored.dequeue().await.unwrap();
+        assert_eq!(second.query, "high");
+        assert_eq!(second.namespace.as_deref(), Some("ns"));
+
+        // Dequeues are saved too
+        let store = FileQueueStore::new(dir.path().join("queue.json"));
+        assert!(store.load().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_embedding_usage_attributed_to_agent() {
+        let coordinator = AgentCoordinator::new();
+        coordinator
+            .register_agent("agent1", AgentRole::Worker, vec![])
+            .await
+            .unwrap();
+        let callback = coordinator.embedding_usage_callback();
+
+        let batch = |agent_id: Option<&str>| BatchUsage {
+            agent_id: agent_id.map(str::to_string),
+            model: "text-embedding-3-small".to_string(),
+            inputs: 4,
+            prompt_tokens: 40,
+            total_tokens: 40,
+        };
+        callback(&batch(Some("agent1")));
+        callback(&batch(Some("agent1")));
+        callback(&batch(None));
+        callback(&batch(Some("unregistered")));
+
+        let metrics = coordinator.get_metrics(&"agent1".to_string()).unwrap();
+        assert_eq!(metrics.to_map()["embedding_tokens"], 80.0);
+    }
+
+    fn liveness_config() -> LivenessConfig {
+        LivenessConfig {
+            heartbeat_timeout_ms: 1_000,
+            deregister_after_ms: 10_000,
+            reap_interval_ms: 100,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_liveness_transitions() {
+        let coordinator = AgentCoordinator::new().with_liveness(liveness_config());
+        let events = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let recorded = events.clone();
+        coordinator.set_status_callback(move |event| {
+            recorded.lock().push((event.agent_id.clone(), event.transition));
+        });
+
+        let worker = "worker".to_string();
+        coordinator.register_agent("worker", AgentRole::Worker, vec![]).await.unwrap();
+        assert!(coordinator.get_metrics(&worker).unwrap().last_seen().is_some());
+        assert!(coordinator.heartbeat(&"stranger".to_string()).is_err());
+
+        // Within the timeout nothing changes
+        let start = Instant::now();
+        assert!(coordinator.reap_at(start + Duration::from_millis(500)).is_empty());
+        assert!(coordinator.is_reachable(&worker));
+
+        let report = coordinator.reap_at(start + Duration::from_secs(2));
+        assert_eq!(report.unreachable, vec![worker.clone()]);
+        assert_eq!(coordinator.agent_status(&worker), Some(AgentStatus::Unreachable));
+        assert_eq!(coordinator.system_stats()["unreachable_agents"], 1);
+
+        coordinator.heartbeat(&worker).unwrap();
+        assert!(coordinator.is_reachable(&worker));
+
+        assert_eq!(
+            *events.lock(),
+            vec![
+                (worker.clone(), AgentTransition::Registered),
+                (worker.clone(), AgentTransition::Unreachable),
+                (worker.clone(), AgentTransition::Recovered),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dead_agent_deregistered_and_grants_restored() {
+        let coordinator = AgentCoordinator::new().with_liveness(liveness_config());
+        coordinator.register_agent("dead", AgentRole::Worker, vec![]).await.unwrap();
+
+        let dead = "dead".to_string();
+        let peer = "peer".to_string();
+        coordinator.grant_access(&agent_namespace("peer"), "dead", AccessLevel::ReadWrite);
+        coordinator.grant_access(&agent_namespace("dead"), "peer", AccessLevel::Read);
+
+        let start = Instant::now();
+        coordinator.reap_at(start + Duration::from_secs(2));
+        assert!(coordinator.reap_at(start + Duration::from_secs(5)).deregistered.is_empty());
+
+        let report = coordinator.reap_at(start + Duration::from_secs(11));
+        assert_eq!(report.deregistered, vec![dead.clone()]);
+        assert!(coordinator.get_agent(&dead).is_none());
+        assert!(coordinator.get_metrics(&dead).is_none());
+        assert_eq!(coordinator.agent_status(&dead), None);
+        assert!(coordinator.list_agents().is_empty());
+
+        // Its namespaces are released
+        let peer_namespace = agent_namespace("peer");
+        let dead_namespace = agent_namespace("dead");
+        assert_eq!(coordinator.namespace_access(&dead, &peer_namespace).await, None);
+        assert_eq!(coordinator.namespace_access(&peer, &dead_namespace).await, None);
+
+        // Coming back restores them
+        coordinator.register_agent("dead", AgentRole::Worker, vec![]).await.unwrap();
+        assert_eq!(
+            coordinator.namespace_access(&dead, &peer_namespace).await,
+            Some(AccessLevel::ReadWrite)
+        );
+        assert_eq!(coordinator.namespace_access(&peer, &dead_namespace).await, Some(AccessLevel::Read));
+        assert!(coordinator.is_reachable(&dead));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_agent_requests() {
+        let queue = SearchQueue::new(10);
+        for priority in [SearchPriority::High, SearchPriority::Low] {
+            queue.enqueue(PrioritizedSearchRequest::new("dead", "query", priority)).await.unwrap();
+        }
+        queue
+            .enqueue(PrioritizedSearchRequest::new("alive", "query", SearchPriority::Normal))
+            .await
+            .unwrap();
+
+        assert_eq!(queue.cancel_agent(&"dead".to_string()).await, 2);
+        assert_eq!(queue.cancel_agent(&"dead".to_string()).await, 0);
+        assert_eq!(queue.dequeue().await.unwrap().agent_id, "alive");
+        assert_eq!(queue.queue_status().await.cancelled, 2);
+    }
+
+    #[tokio::test]
+    async fn test_namespace_quota() {
+        let coordinator = AgentCoordinator::new();
+        let quota = NamespaceQuota {
+            max_documents: Some(3),
+            max_vector_bytes: Some(1000),
+        };
+        coordinator
+            .register_agent_with_quota("owner", AgentRole::Worker, vec![], quota)
+            .await
+            .unwrap();
+        coordinator.register_agent("peer", AgentRole::Worker, vec![]).await.unwrap();
+        let (owner, peer) = ("owner".to_string(), "peer".to_string());
+        let namespace = agent_namespace(&owner);
+        assert_eq!(coordinator.namespace_quota(&namespace), quota);
+        assert_eq!(coordinator.namespace_quota(&agent_namespace(&peer)), NamespaceQuota::unlimited());
+
+        assert!(!coordinator.reserve_index(&owner, &namespace, &"a".to_string(), 400).unwrap());
+        assert!(!coordinator.reserve_index(&peer, &namespace, &"b".to_string(), 400).unwrap());
+
+        // Vector bytes run out before documents do
+        let err = coordinator.reserve_index(&owner, &namespace, &"c".to_string(), 400).unwrap_err();
+        assert!(matches!(
+            err,
+            SemanticError::QuotaExceeded { ref resource, requested: 1200, current: 800, limit: 1000, .. }
+                if resource == "vector_bytes"
+        ));
+
+        // Replacing a document counts its new size only
+        assert!(coordinator.reserve_index(&owner, &namespace, &"a".to_string(), 200).unwrap());
+        assert!(!coordinator.reserve_index(&owner, &namespace, &"c".to_string(), 200).unwrap());
+        let err = coordinator.reserve_index(&owner, &namespace, &"d".to_string(), 1).unwrap_err();
+        assert!(matches!(
+            err,
+            SemanticError::QuotaExceeded { ref resource, requested: 4, current: 3, limit: 3, .. }
+                if resource == "documents"
+        ));
+
+        // Writers are credited in their metrics and the report
+        let metrics = coordinator.get_metrics(&owner).unwrap();
+        assert_eq!(metrics.indexed_documents.load(std::sync::atomic::Ordering::Relaxed), 2);
+        assert_eq!(metrics.indexed_vector_bytes.load(std::sync::atomic::Ordering::Relaxed), 400);
+        let report = coordinator.usage_report();
+        assert_eq!(report.total, IndexUsage { documents: 3, vector_bytes: 800 });
+        assert_eq!(report.namespaces[&namespace].quota, quota);
+        assert_eq!(report.agents[&peer], IndexUsage { documents: 1, vector_bytes: 400 });
+
+        // Raising the quota and releasing documents make room again
+        coordinator.set_namespace_quota(
+            &namespace,
+            NamespaceQuota {
+                max_documents: Some(4),
+                ..quota
+            },
+        );
+        assert!(!coordinator.reserve_index(&owner, &namespace, &"d".to_string(), 100).unwrap());
+        assert!(coordinator.release_index(&namespace, &"b".to_string()));
+        assert!(!coordinator.release_index(&namespace, &"b".to_string()));
+        let metrics = coordinator.get_metrics(&peer).unwrap();
+        assert_eq!(metrics.indexed_documents.load(std::sync::atomic::Ordering::Relaxed), 0);
+        assert_eq!(
+            coordinator.usage_report().namespaces[&namespace].usage,
+            IndexUsage { documents: 3, vector_bytes: 500 }
+        );
+    }
 }
@@ -0,0 +1,235 @@
+//! Direct answers for factoid queries.
+//!
+//! Queries that expect a fact or a definition (`AnswerType::Fact`) get the
+//! sentence of the top results that is most similar to the query. The
+//! sentence is only returned when its similarity clears a threshold, so a
+//! weak match yields no answer rather than a misleading one.
+
+use crate::error::Result;
+use crate::providers::EmbeddingProvider;
+use crate::query::AnswerType;
+use crate::types::{DocumentId, Vector, cosine_similarity};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use tracing::debug;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Configuration of answer extraction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnswerConfig {
+    /// Extract answers for factoid queries
+    pub enabled: bool,
+    /// Number of top results the answer is looked for in
+    pub top_k: usize,
+    /// Least similarity to the query of a returned answer (0.0 - 1.0)
+    pub min_confidence: f32,
+}
+
+impl Default for AnswerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            top_k: 3,
+            min_confidence: 0.6,
+        }
+    }
+}
+
+/// Span of a search result that answers the query directly.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnswerSnippet {
+    pub text: String,
+    /// Document the span was taken from
+    pub doc_id: DocumentId,
+    /// Character offset of the span in the document content
+    pub start: usize,
+    /// Character offset just past the span
+    pub end: usize,
+    /// Similarity of the span to the query
+    pub confidence: f32,
+}
+
+/// Picks the sentence of the top results that best answers a query.
+pub struct AnswerExtractor {
+    config: AnswerConfig,
+}
+
+impl AnswerExtractor {
+    pub fn new(config: AnswerConfig) -> Self {
+        Self { config }
+    }
+
+    /// Whether queries expecting `answer_type` get an answer; lists,
+    /// explanations and code are left to the result list.
+    pub fn applies_to(&self, answer_type: AnswerType) -> bool {
+        self.config.enabled && self.config.top_k > 0 && answer_type == AnswerType::Fact
+    }
+
+    /// The sentence of the first `top_k` documents that is most similar to
+    /// the query, or `None` if even that one is below `min_confidence`.
+    pub async fn extract(
+        &self,
+        provider: &dyn EmbeddingProvider,
+        query_embedding: &Vector,
+        documents: &[(&DocumentId, &str)],
+    ) -> Result<Option<AnswerSnippet>> {
+        let documents = &documents[..documents.len().min(self.config.top_k)];
+        let spans: Vec<(usize, Range<usize>)> = documents
+            .iter()
+            .enumerate()
+            .flat_map(|(i, (_, content))| sentence_spans(content).into_iter().map(move |span| (i, span)))
+            .collect();
+        if spans.is_empty() {
+            return Ok(None);
+        }
+
+        let sentences: Vec<String> = spans
+            .iter()
+            .map(|(i, span)| documents[*i].1[span.clone()].to_string())
+            .collect();
+        let embeddings = provider.embed_batch(&sentences).await?;
+
+        // Ties go to the earlier, higher-ranked sentence
+        let mut best: Option<(usize, f32)> = None;
+        for (index, embedding) in embeddings.iter().enumerate() {
+            if embedding.len() != query_embedding.len() {
+                continue;
+            }
+            let similarity = cosine_similarity(query_embedding, embedding);
+            if best.is_none_or(|(_, best_similarity)| similarity > best_similarity) {
+                best = Some((index, similarity));
+            }
+        }
+
+        let Some((index, confidence)) = best else {
+            return Ok(None);
+        };
+        if confidence < self.config.min_confidence {
+            debug!(
+                "Best answer candidate below confidence threshold ({:.3} < {:.3})",
+                confidence, self.config.min_confidence
+            );
+            return Ok(None);
+        }
+
+        let (doc, span) = &spans[index];
+        let (doc_id, content) = documents[*doc];
+        let start = content[..span.start].chars().count();
+        let text = sentences[index].clone();
+        Ok(Some(AnswerSnippet {
+            end: start + text.chars().count(),
+            start,
+            text,
+            doc_id: doc_id.clone(),
+            confidence,
+        }))
+    }
+}
+
+/// Byte ranges of the sentences of `text`, without surrounding whitespace.
+fn sentence_spans(text: &str) -> Vec<Range<usize>> {
+    text.split_sentence_bound_indices()
+        .filter_map(|(offset, sentence)| {
+            let trimmed = sentence.trim();
+            let start = offset + (sentence.len() - sentence.trim_start().len());
+            (!trimmed.is_empty()).then(|| start..start + trimmed.len())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::EmbeddingModel;
+    use async_trait::async_trait;
+
+    /// Embeds text as word counts, so sentences sharing words with the
+    /// query are similar to it.
+    struct BagOfWordsProvider {
+        model: EmbeddingModel,
+    }
+
+    impl BagOfWordsProvider {
+        const DIMENSION: usize = 64;
+
+        fn new() -> Self {
+            Self {
+                model: EmbeddingModel::new("test", "bag-of-words", Self::DIMENSION),
+            }
+        }
+
+        fn vector(text: &str) -> Vector {
+            let mut vector = vec![0.0; Self::DIMENSION];
+            for word in text.to_lowercase().unicode_words() {
+                let hash = word.bytes().fold(0usize, |acc, b| acc.wrapping_mul(31).wrapping_add(b as usize));
+                vector[hash % Self::DIMENSION] += 1.0;
+            }
+            vector
+        }
+    }
+
+    #[async_trait]
+    impl EmbeddingProvider for BagOfWordsProvider {
+        async fn embed(&self, text: &str) -> Result<Vector> {
+            Ok(Self::vector(text))
+        }
+
+        async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vector>> {
+            Ok(texts.iter().map(|text| Self::vector(text)).collect())
+        }
+
+        fn model(&self) -> &EmbeddingModel {
+            &self.model
+        }
+    }
+
+    #[tokio::test]
+    async fn test_extracts_most_similar_sentence_with_offsets() {
+        let provider = BagOfWordsProvider::new();
+        let extractor = AnswerExtractor::new(AnswerConfig {
+            min_confidence: 0.5,
+            ..Default::default()
+        });
+        let query = BagOfWordsProvider::vector("what is a closure in rust");
+
+        let first = "doc-1".to_string();
+        let second = "doc-2".to_string();
+        let documents = [
+            (&first, "Rust has no garbage collector. Ownership is checked at compile time."),
+            (
+                &second,
+                "Iterators are lazy. In Rust a closure is an anonymous function that captures its environment. Closures implement Fn traits.",
+            ),
+        ];
+
+        let answer = extractor
+            .extract(&provider, &query, &documents)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(answer.doc_id, "doc-2");
+        assert_eq!(
+            answer.text,
+            "In Rust a closure is an anonymous function that captures its environment."
+        );
+        let content: Vec<char> = documents[1].1.chars().collect();
+        assert_eq!(content[answer.start..answer.end].iter().collect::<String>(), answer.text);
+        assert!(answer.confidence >= 0.5);
+    }
+
+    #[tokio::test]
+    async fn test_weak_match_yields_no_answer() {
+        let provider = BagOfWordsProvider::new();
+        let extractor = AnswerExtractor::new(AnswerConfig::default());
+        let query = BagOfWordsProvider::vector("what is the default qdrant port");
+
+        let id = "doc".to_string();
+        let documents = [(&id, "Closures capture their environment. Iterators are lazy.")];
+        let answer = extractor.extract(&provider, &query, &documents).await.unwrap();
+        assert!(answer.is_none());
+
+        assert!(extractor.applies_to(AnswerType::Fact));
+        assert!(!extractor.applies_to(AnswerType::List));
+        assert!(!extractor.applies_to(AnswerType::Explanation));
+    }
+}
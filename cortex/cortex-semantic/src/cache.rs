@@ -1,64 +1,224 @@
 //! Caching layer for embeddings and search results.
+//!
+//! Search results are cached twice: by exact query string in `QueryCache`,
+//! and by query embedding in `SemanticQueryCache`, which also answers
+//! differently worded queries whose embeddings are close enough.
 
-use crate::types::Vector;
+use crate::types::{Vector, cosine_similarity};
 use moka::future::Cache;
-use std::hash::{Hash, Hasher};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-/// Cache key for embeddings.
-#[derive(Clone, Eq, PartialEq)]
+/// Cache key for embeddings: the provider and model that embed the text,
+/// and a hash of the text with its whitespace normalized.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct EmbeddingCacheKey {
-    text: String,
+    provider: String,
     model: String,
+    text_hash: [u8; 32],
 }
 
-impl Hash for EmbeddingCacheKey {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        self.text.hash(state);
-        self.model.hash(state);
+impl EmbeddingCacheKey {
+    pub fn new(provider: impl Into<String>, model: impl Into<String>, text: &str) -> Self {
+        let mut hasher = blake3::Hasher::new();
+        for (i, word) in text.split_whitespace().enumerate() {
+            if i > 0 {
+                hasher.update(b" ");
+            }
+            hasher.update(word.as_bytes());
+        }
+        Self {
+            provider: provider.into(),
+            model: model.into(),
+            text_hash: *hasher.finalize().as_bytes(),
+        }
+    }
+
+    pub fn model(&self) -> &str {
+        &self.model
     }
 }
 
-impl EmbeddingCacheKey {
-    pub fn new(text: String, model: String) -> Self {
-        Self { text, model }
+/// Hit and size counters of an [`EmbeddingCache`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmbeddingCacheStats {
+    pub entries: u64,
+    /// Bytes of the cached vectors, at 4 bytes per dimension
+    pub size_bytes: u64,
+    pub max_bytes: u64,
+    pub hits: u64,
+    pub misses: u64,
+    /// Entries evicted to stay within the entry or byte limit
+    pub evictions: u64,
+    /// Entries dropped for outliving the TTL
+    pub expirations: u64,
+}
+
+impl EmbeddingCacheStats {
+    /// Share of lookups answered from the cache, 0.0 before any lookup.
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+struct EmbeddingEntry {
+    vector: Arc<Vector>,
+    inserted_at: Instant,
+    /// Tick of the last insert or hit, the entry's key in `lru`
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct EmbeddingState {
+    entries: HashMap<EmbeddingCacheKey, EmbeddingEntry>,
+    /// Keys by tick of last use, least recently used first
+    lru: BTreeMap<u64, EmbeddingCacheKey>,
+    bytes: u64,
+    tick: u64,
+    stats: EmbeddingCacheStats,
+}
+
+impl EmbeddingState {
+    fn remove(&mut self, key: &EmbeddingCacheKey) -> Option<EmbeddingEntry> {
+        let entry = self.entries.remove(key)?;
+        self.lru.remove(&entry.last_used);
+        self.bytes -= vector_bytes(&entry.vector);
+        Some(entry)
     }
 }
 
+fn vector_bytes(vector: &Vector) -> u64 {
+    (vector.len() * std::mem::size_of::<f32>()) as u64
+}
+
 /// Cache for embeddings.
+///
+/// Bounded by entry count and by total vector bytes; the least recently
+/// used entries are evicted first, and entries older than the TTL are
+/// never served.
 pub struct EmbeddingCache {
-    cache: Cache<EmbeddingCacheKey, Arc<Vector>>,
+    state: Mutex<EmbeddingState>,
+    max_entries: u64,
+    max_bytes: u64,
+    ttl: Duration,
 }
 
 impl EmbeddingCache {
-    pub fn new(max_capacity: u64, ttl: Duration) -> Self {
-        let cache = Cache::builder()
-            .max_capacity(max_capacity)
-            .time_to_live(ttl)
-            .build();
+    pub fn new(max_entries: u64, max_bytes: u64, ttl: Duration) -> Self {
+        Self {
+            state: Mutex::new(EmbeddingState::default()),
+            max_entries,
+            max_bytes,
+            ttl,
+        }
+    }
 
-        Self { cache }
+    pub fn get(&self, key: &EmbeddingCacheKey) -> Option<Arc<Vector>> {
+        let mut state = self.state.lock();
+        let expired = match state.entries.get(key) {
+            Some(entry) => entry.inserted_at.elapsed() >= self.ttl,
+            None => {
+                state.stats.misses += 1;
+                return None;
+            }
+        };
+        if expired {
+            state.remove(key);
+            state.stats.expirations += 1;
+            state.stats.misses += 1;
+            return None;
+        }
+
+        state.tick += 1;
+        let tick = state.tick;
+        state.stats.hits += 1;
+        let entry = state.entries.get_mut(key)?;
+        let previous = std::mem::replace(&mut entry.last_used, tick);
+        let vector = entry.vector.clone();
+        state.lru.remove(&previous);
+        state.lru.insert(tick, key.clone());
+        Some(vector)
     }
 
-    pub async fn get(&self, key: &EmbeddingCacheKey) -> Option<Arc<Vector>> {
-        self.cache.get(key).await
+    pub fn insert(&self, key: EmbeddingCacheKey, value: Vector) {
+        let bytes = vector_bytes(&value);
+        if self.max_entries == 0 || bytes > self.max_bytes {
+            return;
+        }
+
+        let mut state = self.state.lock();
+        state.remove(&key);
+        while state.entries.len() as u64 >= self.max_entries || state.bytes + bytes > self.max_bytes {
+            let Some((_, victim)) = state.lru.pop_first() else {
+                break;
+            };
+            if let Some(entry) = state.entries.remove(&victim) {
+                state.bytes -= vector_bytes(&entry.vector);
+                state.stats.evictions += 1;
+            }
+        }
+
+        state.tick += 1;
+        let tick = state.tick;
+        state.bytes += bytes;
+        state.lru.insert(tick, key.clone());
+        state.entries.insert(
+            key,
+            EmbeddingEntry {
+                vector: Arc::new(value),
+                inserted_at: Instant::now(),
+                last_used: tick,
+            },
+        );
     }
 
-    pub async fn insert(&self, key: EmbeddingCacheKey, value: Vector) {
-        self.cache.insert(key, Arc::new(value)).await;
+    pub fn invalidate(&self, key: &EmbeddingCacheKey) {
+        self.state.lock().remove(key);
     }
 
-    pub async fn invalidate(&self, key: &EmbeddingCacheKey) {
-        self.cache.invalidate(key).await;
+    /// Drop every embedding of a model, from any provider, e.g. after
+    /// switching models. Returns how many were dropped.
+    pub fn invalidate_model(&self, model: &str) -> usize {
+        let mut state = self.state.lock();
+        let keys: Vec<EmbeddingCacheKey> = state
+            .entries
+            .keys()
+            .filter(|key| key.model == model)
+            .cloned()
+            .collect();
+        for key in &keys {
+            state.remove(key);
+        }
+        keys.len()
     }
 
-    pub async fn clear(&self) {
-        self.cache.invalidate_all();
+    pub fn clear(&self) {
+        let mut state = self.state.lock();
+        state.entries.clear();
+        state.lru.clear();
+        state.bytes = 0;
     }
 
     pub fn entry_count(&self) -> u64 {
-        self.cache.entry_count()
+        self.state.lock().entries.len() as u64
+    }
+
+    pub fn stats(&self) -> EmbeddingCacheStats {
+        let state = self.state.lock();
+        EmbeddingCacheStats {
+            entries: state.entries.len() as u64,
+            size_bytes: state.bytes,
+            max_bytes: self.max_bytes,
+            ..state.stats
+        }
     }
 }
 
@@ -68,6 +228,7 @@ pub struct QueryCacheKey {
     query: String,
     limit: usize,
     threshold: String, // Store as string for hashing
+    filter: String,
 }
 
 impl QueryCacheKey {
@@ -76,8 +237,15 @@ impl QueryCacheKey {
             query,
             limit,
             threshold: format!("{:.6}", threshold),
+            filter: String::new(),
         }
     }
+
+    /// Key the query under a fingerprint of its search filters.
+    pub fn with_filter(mut self, filter: String) -> Self {
+        self.filter = filter;
+        self
+    }
 }
 
 /// Cached search result.
@@ -85,6 +253,19 @@ impl QueryCacheKey {
 pub struct CachedSearchResult {
     pub doc_ids: Vec<String>,
     pub scores: Vec<f32>,
+    /// Index and score of the best matching chunk of multi-vector
+    /// documents, by document id
+    pub best_chunks: HashMap<String, (usize, f32)>,
+}
+
+/// Which cache answered a search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheHitType {
+    /// Same query string
+    Exact,
+    /// A different query with a near-identical embedding
+    Semantic,
 }
 
 /// Cache for search results.
@@ -97,6 +278,7 @@ impl QueryCache {
         let cache = Cache::builder()
             .max_capacity(max_capacity)
             .time_to_live(ttl)
+            .support_invalidation_closures()
             .build();
 
         Self { cache }
@@ -118,32 +300,247 @@ impl QueryCache {
         self.cache.invalidate_all();
     }
 
+    /// Drop every result set that contains `doc_id`.
+    pub fn invalidate_document(&self, doc_id: &str) {
+        let doc_id = doc_id.to_string();
+        if self
+            .cache
+            .invalidate_entries_if(move |_, result| result.doc_ids.contains(&doc_id))
+            .is_err()
+        {
+            self.cache.invalidate_all();
+        }
+    }
+
     pub fn entry_count(&self) -> u64 {
         self.cache.entry_count()
     }
 }
 
+/// What a cached result set depends on besides the query: the result limit,
+/// the score threshold and the filters.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct QueryScope {
+    limit: usize,
+    threshold: String,
+    filter: String,
+}
+
+impl QueryScope {
+    pub fn new(limit: usize, threshold: f32, filter: String) -> Self {
+        Self {
+            limit,
+            threshold: format!("{:.6}", threshold),
+            filter,
+        }
+    }
+}
+
+struct SemanticEntry {
+    embedding: Vector,
+    scope: QueryScope,
+    result: Arc<CachedSearchResult>,
+    hits: u64,
+    /// Tick of the last insert or hit
+    last_used: u64,
+    inserted_at: Instant,
+}
+
+#[derive(Default)]
+struct SemanticState {
+    entries: Vec<SemanticEntry>,
+    tick: u64,
+}
+
+/// Cache of search results by query embedding.
+///
+/// A lookup returns the results of the most similar cached query in the
+/// same scope, if its cosine similarity reaches the threshold. When full,
+/// the entry with the fewest hits per tick since last use is evicted, so
+/// popular queries outlive recent one-offs without keeping stale ones forever.
+pub struct SemanticQueryCache {
+    state: Mutex<SemanticState>,
+    threshold: f32,
+    max_entries: usize,
+    ttl: Duration,
+}
+
+impl SemanticQueryCache {
+    pub fn new(threshold: f32, max_entries: usize, ttl: Duration) -> Self {
+        Self {
+            state: Mutex::new(SemanticState::default()),
+            threshold,
+            max_entries,
+            ttl,
+        }
+    }
+
+    /// Results of the most similar cached query, with its similarity.
+    pub fn get(
+        &self,
+        embedding: &[f32],
+        scope: &QueryScope,
+    ) -> Option<(Arc<CachedSearchResult>, f32)> {
+        let mut state = self.state.lock();
+        let ttl = self.ttl;
+        state
+            .entries
+            .retain(|entry| entry.inserted_at.elapsed() < ttl);
+
+        let (index, similarity) = state
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| &entry.scope == scope && entry.embedding.len() == embedding.len())
+            .map(|(i, entry)| (i, cosine_similarity(&entry.embedding, embedding)))
+            .filter(|(_, similarity)| *similarity >= self.threshold)
+            .max_by(|a, b| a.1.total_cmp(&b.1))?;
+
+        state.tick += 1;
+        let tick = state.tick;
+        let entry = &mut state.entries[index];
+        entry.hits += 1;
+        entry.last_used = tick;
+        Some((entry.result.clone(), similarity))
+    }
+
+    pub fn insert(&self, embedding: Vector, scope: QueryScope, result: CachedSearchResult) {
+        if self.max_entries == 0 {
+            return;
+        }
+
+        let mut state = self.state.lock();
+        state.tick += 1;
+        let tick = state.tick;
+
+        // A query with the same embedding replaces the older entry
+        state.entries.retain(|entry| {
+            entry.scope != scope
+                || entry.embedding.len() != embedding.len()
+                || cosine_similarity(&entry.embedding, &embedding) < 1.0 - f32::EPSILON
+        });
+
+        while state.entries.len() >= self.max_entries {
+            let retention = |entry: &SemanticEntry| {
+                (entry.hits + 1) as f64 / (tick - entry.last_used + 1) as f64
+            };
+            let Some(victim) = state
+                .entries
+                .iter()
+                .enumerate()
+                .min_by(|a, b| retention(a.1).total_cmp(&retention(b.1)))
+                .map(|(i, _)| i)
+            else {
+                break;
+            };
+            state.entries.swap_remove(victim);
+        }
+
+        state.entries.push(SemanticEntry {
+            embedding,
+            scope,
+            result: Arc::new(result),
+            hits: 0,
+            last_used: tick,
+            inserted_at: Instant::now(),
+        });
+    }
+
+    /// Drop every result set that contains `doc_id`.
+    pub fn invalidate_document(&self, doc_id: &str) {
+        self.state
+            .lock()
+            .entries
+            .retain(|entry| !entry.result.doc_ids.iter().any(|id| id == doc_id));
+    }
+
+    pub fn clear(&self) {
+        self.state.lock().entries.clear();
+    }
+
+    pub fn entry_count(&self) -> usize {
+        self.state.lock().entries.len()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[tokio::test]
     async fn test_embedding_cache() {
-        let cache = EmbeddingCache::new(100, Duration::from_secs(60));
+        let cache = EmbeddingCache::new(100, 1 << 20, Duration::from_secs(60));
 
-        let key = EmbeddingCacheKey::new("test".to_string(), "model".to_string());
+        let key = EmbeddingCacheKey::new("mock", "model", "test");
         let vector = vec![1.0, 2.0, 3.0];
 
         // Insert
-        cache.insert(key.clone(), vector.clone()).await;
+        cache.insert(key.clone(), vector.clone());
 
         // Get
-        let cached = cache.get(&key).await.unwrap();
+        let cached = cache.get(&key).unwrap();
         assert_eq!(*cached, vector);
 
         // Invalidate
-        cache.invalidate(&key).await;
-        assert!(cache.get(&key).await.is_none());
+        cache.invalidate(&key);
+        assert!(cache.get(&key).is_none());
+    }
+
+    #[test]
+    fn test_embedding_cache_key_normalizes_whitespace() {
+        let key = EmbeddingCacheKey::new("mock", "model", "fn  main()\n");
+        assert_eq!(key, EmbeddingCacheKey::new("mock", "model", " fn main()"));
+        assert_ne!(key, EmbeddingCacheKey::new("mock", "other", "fn main()"));
+        assert_ne!(key, EmbeddingCacheKey::new("openai", "model", "fn main()"));
+        assert_ne!(key, EmbeddingCacheKey::new("mock", "model", "fnmain()"));
+    }
+
+    #[test]
+    fn test_embedding_cache_bounds_and_stats() {
+        // Room for three entries and seven dimensions
+        let cache = EmbeddingCache::new(3, 28, Duration::from_secs(60));
+        let key = |text: &str| EmbeddingCacheKey::new("mock", "model", text);
+
+        cache.insert(key("a"), vec![0.0; 2]);
+        cache.insert(key("b"), vec![0.0; 2]);
+        cache.insert(key("c"), vec![0.0; 2]);
+        assert!(cache.get(&key("a")).is_some());
+
+        // The entry limit evicts the least recently used entry, b
+        cache.insert(key("d"), vec![0.0; 2]);
+        assert!(cache.get(&key("b")).is_none());
+        assert!(cache.get(&key("a")).is_some());
+
+        // The byte limit evicts c and d to fit a larger vector next to a
+        cache.insert(key("e"), vec![0.0; 4]);
+        assert_eq!(cache.entry_count(), 2);
+        assert!(cache.get(&key("c")).is_none());
+
+        // Vectors larger than the whole cache are not cached
+        cache.insert(key("f"), vec![0.0; 9]);
+        assert!(cache.get(&key("f")).is_none());
+
+        let stats = cache.stats();
+        assert_eq!((stats.entries, stats.size_bytes), (2, 24));
+        assert_eq!((stats.hits, stats.misses, stats.evictions), (2, 3, 3));
+        assert!((stats.hit_rate() - 0.4).abs() < 1e-9);
+
+        // Invalidating a model leaves the other models' entries
+        cache.insert(EmbeddingCacheKey::new("mock", "other", "a"), vec![0.0; 1]);
+        assert_eq!(cache.invalidate_model("model"), 2);
+        assert_eq!(cache.entry_count(), 1);
+    }
+
+    #[test]
+    fn test_embedding_cache_ttl() {
+        let cache = EmbeddingCache::new(10, 1024, Duration::from_millis(20));
+        let key = EmbeddingCacheKey::new("mock", "model", "text");
+        cache.insert(key.clone(), vec![1.0]);
+        std::thread::sleep(Duration::from_millis(30));
+
+        assert!(cache.get(&key).is_none());
+        let stats = cache.stats();
+        assert_eq!((stats.entries, stats.expirations), (0, 1));
     }
 
     #[tokio::test]
@@ -154,6 +551,7 @@ mod tests {
         let result = CachedSearchResult {
             doc_ids: vec!["doc1".to_string(), "doc2".to_string()],
             scores: vec![0.9, 0.8],
+            best_chunks: HashMap::new(),
         };
 
         // Insert
@@ -168,4 +566,68 @@ mod tests {
         cache.clear().await;
         assert!(cache.get(&key).await.is_none());
     }
+
+    fn result(doc_ids: &[&str]) -> CachedSearchResult {
+        CachedSearchResult {
+            doc_ids: doc_ids.iter().map(|id| id.to_string()).collect(),
+            scores: vec![0.9; doc_ids.len()],
+            best_chunks: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_semantic_cache_matches_similar_queries_in_scope() {
+        let cache = SemanticQueryCache::new(0.95, 10, Duration::from_secs(60));
+        let scope = QueryScope::new(10, 0.5, String::new());
+        cache.insert(vec![1.0, 0.0, 0.1], scope.clone(), result(&["doc1"]));
+
+        // Nearly the same direction
+        let (hit, similarity) = cache.get(&[0.98, 0.02, 0.1], &scope).unwrap();
+        assert_eq!(hit.doc_ids, vec!["doc1"]);
+        assert!(similarity >= 0.95);
+
+        // Unrelated query, or same query with another limit
+        assert!(cache.get(&[0.0, 1.0, 0.0], &scope).is_none());
+        let other_scope = QueryScope::new(5, 0.5, String::new());
+        assert!(cache.get(&[1.0, 0.0, 0.1], &other_scope).is_none());
+    }
+
+    #[test]
+    fn test_semantic_cache_eviction_keeps_frequently_hit_entries() {
+        let cache = SemanticQueryCache::new(0.99, 2, Duration::from_secs(60));
+        let scope = QueryScope::new(10, 0.5, String::new());
+        cache.insert(vec![1.0, 0.0, 0.0], scope.clone(), result(&["popular"]));
+        cache.insert(vec![0.0, 1.0, 0.0], scope.clone(), result(&["recent"]));
+        for _ in 0..5 {
+            cache.get(&[1.0, 0.0, 0.0], &scope).unwrap();
+        }
+
+        cache.insert(vec![0.0, 0.0, 1.0], scope.clone(), result(&["new"]));
+
+        assert_eq!(cache.entry_count(), 2);
+        assert!(cache.get(&[1.0, 0.0, 0.0], &scope).is_some());
+        assert!(cache.get(&[0.0, 1.0, 0.0], &scope).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_document_clears_exact_and_semantic_entries() {
+        let exact = QueryCache::new(100, Duration::from_secs(60));
+        let semantic = SemanticQueryCache::new(0.95, 10, Duration::from_secs(60));
+        let scope = QueryScope::new(10, 0.5, String::new());
+
+        let stale = QueryCacheKey::new("parse json".to_string(), 10, 0.5);
+        let kept = QueryCacheKey::new("open file".to_string(), 10, 0.5);
+        exact.insert(stale.clone(), result(&["doc1", "doc2"])).await;
+        exact.insert(kept.clone(), result(&["doc3"])).await;
+        semantic.insert(vec![1.0, 0.0], scope.clone(), result(&["doc2"]));
+        semantic.insert(vec![0.0, 1.0], scope.clone(), result(&["doc3"]));
+
+        exact.invalidate_document("doc2");
+        semantic.invalidate_document("doc2");
+
+        assert!(exact.get(&stale).await.is_none());
+        assert!(exact.get(&kept).await.is_some());
+        assert!(semantic.get(&[1.0, 0.0], &scope).is_none());
+        assert!(semantic.get(&[0.0, 1.0], &scope).is_some());
+    }
 }
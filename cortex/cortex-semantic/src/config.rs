@@ -1,8 +1,14 @@
 //! Configuration for semantic search system.
 
-use crate::types::SimilarityMetric;
+use crate::answer::AnswerConfig;
+use crate::error::{Result, SemanticError};
+use crate::query::QueryIntent;
+use crate::retry::RetryPolicy;
+use crate::types::{EntityType, SimilarityMetric};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::time::Duration;
 
 /// Main configuration for the semantic search system.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,6 +19,12 @@ pub struct SemanticConfig {
     pub cache: CacheConfig,
     pub qdrant: QdrantConfig,
     pub vector_store: VectorStoreConfig,
+    /// Retries of embedding requests that fail with a retryable error
+    #[serde(default)]
+    pub retry: RetryPolicy,
+    /// Whether creating an engine also warms it up
+    #[serde(default)]
+    pub initialization: InitializationMode,
 }
 
 impl Default for SemanticConfig {
@@ -24,10 +36,41 @@ impl Default for SemanticConfig {
             cache: CacheConfig::default(),
             qdrant: QdrantConfig::default(),
             vector_store: VectorStoreConfig::default(),
+            retry: RetryPolicy::default(),
+            initialization: InitializationMode::default(),
         }
     }
 }
 
+impl SemanticConfig {
+    /// Check that the vector store was sized for the embeddings it will
+    /// receive. Both sides are only known once set, so unset values pass.
+    pub fn check_vector_dimension(&self, embedding_dimension: usize) -> Result<()> {
+        match self.vector_store.dimension {
+            Some(expected) if expected != embedding_dimension => {
+                Err(SemanticError::DimensionMismatch {
+                    expected,
+                    got: embedding_dimension,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Check settings that must agree across sections.
+    pub fn validate(&self) -> Result<()> {
+        if let Some(dimensions) = self.embedding.dimensions {
+            if dimensions == 0 {
+                return Err(SemanticError::Config(
+                    "embedding.dimensions must be greater than 0".to_string(),
+                ));
+            }
+            self.check_vector_dimension(dimensions)?;
+        }
+        Ok(())
+    }
+}
+
 /// Embedding provider configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EmbeddingProviderConfig {
@@ -54,6 +97,40 @@ pub struct EmbeddingProviderConfig {
 
     /// Maximum retries
     pub max_retries: usize,
+
+    /// Shorter vectors to request from models that support it
+    /// (OpenAI text-embedding-3); takes precedence over `openai.dimension`
+    #[serde(default)]
+    pub dimensions: Option<usize>,
+
+    /// When a failing provider is skipped in favour of the fallbacks
+    #[serde(default)]
+    pub circuit_breaker: CircuitBreakerConfig,
+}
+
+/// Health tracking of the providers in the fallback chain.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive failures after which a provider is skipped
+    pub failure_threshold: u32,
+    /// Seconds a skipped provider waits before it is tried again
+    pub cooldown_secs: u64,
+}
+
+impl CircuitBreakerConfig {
+    pub fn cooldown(&self) -> Duration {
+        Duration::from_secs(self.cooldown_secs)
+    }
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 3,
+            cooldown_secs: 30,
+        }
+    }
 }
 
 impl Default for EmbeddingProviderConfig {
@@ -67,6 +144,8 @@ impl Default for EmbeddingProviderConfig {
             batch_size: 32,
             timeout_seconds: 30,
             max_retries: 3,
+            dimensions: None,
+            circuit_breaker: CircuitBreakerConfig::default(),
         }
     }
 }
@@ -110,6 +189,82 @@ pub struct ONNXConfig {
 
     /// Use GPU if available
     pub use_gpu: bool,
+
+    /// Weight precision of the model; `auto` detects int8 models from their
+    /// metadata or file name
+    #[serde(default)]
+    pub quantization: OnnxQuantization,
+
+    /// Threads used within one operator of each session; `None` shares the
+    /// cores out among the pooled sessions
+    #[serde(default)]
+    pub intra_op_threads: Option<usize>,
+
+    /// Threads used to run independent operators in parallel; `None` runs
+    /// them sequentially
+    #[serde(default)]
+    pub inter_op_threads: Option<usize>,
+
+    /// Execution providers in order of preference; empty means CUDA then
+    /// CPU with `use_gpu`, CPU otherwise
+    #[serde(default)]
+    pub execution_providers: Vec<OnnxExecutionProvider>,
+
+    /// Run one inference at construction so the first query doesn't pay
+    /// for initialization
+    #[serde(default = "default_onnx_warmup")]
+    pub warmup: bool,
+
+    /// Sessions that run inferences concurrently; `None` uses one per
+    /// physical core on the CPU and a single one on accelerators
+    #[serde(default)]
+    pub session_pool_size: Option<usize>,
+
+    /// Padded tokens in one inference call; batches of long texts are split
+    /// to stay under it
+    #[serde(default = "default_onnx_max_batch_tokens")]
+    pub max_batch_tokens: usize,
+}
+
+impl ONNXConfig {
+    /// Execution providers to register, in order of preference.
+    pub fn resolved_execution_providers(&self) -> Vec<OnnxExecutionProvider> {
+        if !self.execution_providers.is_empty() {
+            return self.execution_providers.clone();
+        }
+        if self.use_gpu {
+            vec![OnnxExecutionProvider::Cuda, OnnxExecutionProvider::Cpu]
+        } else {
+            vec![OnnxExecutionProvider::Cpu]
+        }
+    }
+
+    /// Number of pooled inference sessions.
+    pub fn resolved_session_pool_size(&self) -> usize {
+        match self.session_pool_size {
+            Some(size) => size.max(1),
+            None if self.resolved_execution_providers().first() == Some(&OnnxExecutionProvider::Cpu) => {
+                num_cpus::get_physical()
+            }
+            None => 1,
+        }
+    }
+
+    /// Intra-op threads of each session when `pool_size` run at once.
+    pub fn resolved_intra_op_threads(&self, pool_size: usize) -> usize {
+        self.intra_op_threads
+            .unwrap_or_else(|| num_cpus::get() / pool_size.max(1))
+            .max(1)
+    }
+}
+
+fn default_onnx_warmup() -> bool {
+    true
+}
+
+fn default_onnx_max_batch_tokens() -> usize {
+    // 16 sequences of the usual 512-token limit
+    8192
 }
 
 impl Default for ONNXConfig {
@@ -119,6 +274,43 @@ impl Default for ONNXConfig {
             model_name: "all-MiniLM-L6-v2".to_string(),
             dimension: 384,
             use_gpu: false,
+            quantization: OnnxQuantization::default(),
+            intra_op_threads: None,
+            inter_op_threads: None,
+            execution_providers: Vec::new(),
+            warmup: default_onnx_warmup(),
+            session_pool_size: None,
+            max_batch_tokens: default_onnx_max_batch_tokens(),
+        }
+    }
+}
+
+/// Weight precision of an ONNX model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OnnxQuantization {
+    /// Detect from model metadata or file name
+    #[default]
+    Auto,
+    Fp32,
+    Int8,
+}
+
+/// ONNX Runtime execution provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OnnxExecutionProvider {
+    Cpu,
+    Cuda,
+    CoreML,
+}
+
+impl OnnxExecutionProvider {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OnnxExecutionProvider::Cpu => "cpu",
+            OnnxExecutionProvider::Cuda => "cuda",
+            OnnxExecutionProvider::CoreML => "coreml",
         }
     }
 }
@@ -133,6 +325,49 @@ pub struct OllamaConfig {
 
     /// Dimension
     pub dimension: usize,
+
+    /// How long Ollama keeps the model loaded after a request, e.g. `10m`,
+    /// or `-1` to keep it loaded; `None` uses the server default
+    #[serde(default)]
+    pub keep_alive: Option<String>,
+
+    /// Timeout of a request once the model is loaded
+    #[serde(default = "default_ollama_request_timeout_secs")]
+    pub request_timeout_secs: u64,
+
+    /// Timeout while the model may still be loading, until the first
+    /// request succeeds
+    #[serde(default = "default_ollama_cold_start_timeout_secs")]
+    pub cold_start_timeout_secs: u64,
+
+    /// Retries while Ollama reports the model is loading
+    #[serde(default = "default_ollama_max_retries")]
+    pub max_retries: u32,
+
+    /// Wait before the first retry, doubled for each further one
+    #[serde(default = "default_ollama_retry_backoff_ms")]
+    pub retry_backoff_ms: u64,
+
+    /// Embed once at construction so the model is loaded before the first
+    /// real request
+    #[serde(default)]
+    pub warmup: bool,
+}
+
+fn default_ollama_request_timeout_secs() -> u64 {
+    60
+}
+
+fn default_ollama_cold_start_timeout_secs() -> u64 {
+    180
+}
+
+fn default_ollama_max_retries() -> u32 {
+    3
+}
+
+fn default_ollama_retry_backoff_ms() -> u64 {
+    1000
 }
 
 impl Default for OllamaConfig {
@@ -141,6 +376,12 @@ impl Default for OllamaConfig {
             endpoint: "http://localhost:11434".to_string(),
             model: "nomic-embed-text".to_string(),
             dimension: 768,
+            keep_alive: None,
+            request_timeout_secs: default_ollama_request_timeout_secs(),
+            cold_start_timeout_secs: default_ollama_cold_start_timeout_secs(),
+            max_retries: default_ollama_max_retries(),
+            retry_backoff_ms: default_ollama_retry_backoff_ms(),
+            warmup: false,
         }
     }
 }
@@ -168,6 +409,31 @@ pub struct IndexConfig {
 
     /// Maximum index size (number of vectors)
     pub max_index_size: usize,
+
+    /// Documents embedded per provider call by `index_documents`
+    #[serde(default = "default_max_batch_size")]
+    pub max_batch_size: usize,
+
+    /// Embedding batches `index_documents` keeps in flight at once
+    #[serde(default = "default_max_concurrent_batches")]
+    pub max_concurrent_batches: usize,
+
+    /// Look up document embeddings in the embedding cache before calling
+    /// the provider, e.g. when re-indexing unchanged content
+    #[serde(default = "default_cache_embeddings")]
+    pub cache_document_embeddings: bool,
+}
+
+fn default_max_batch_size() -> usize {
+    64
+}
+
+fn default_max_concurrent_batches() -> usize {
+    4
+}
+
+fn default_cache_embeddings() -> bool {
+    true
 }
 
 impl Default for IndexConfig {
@@ -180,6 +446,9 @@ impl Default for IndexConfig {
             persist_path: None,
             auto_save_interval_seconds: 300, // 5 minutes
             max_index_size: 1_000_000,
+            max_batch_size: default_max_batch_size(),
+            max_concurrent_batches: default_max_concurrent_batches(),
+            cache_document_embeddings: default_cache_embeddings(),
         }
     }
 }
@@ -199,6 +468,11 @@ pub struct SearchConfig {
     /// Enable query expansion
     pub enable_query_expansion: bool,
 
+    /// Domain synonym dictionary (TOML or JSON) added to the built-in
+    /// programming vocabulary for query expansion
+    #[serde(default)]
+    pub query_dictionary_path: Option<PathBuf>,
+
     /// Enable hybrid search (keyword + semantic)
     pub enable_hybrid_search: bool,
 
@@ -210,6 +484,48 @@ pub struct SearchConfig {
 
     /// Search timeout in milliseconds
     pub timeout_ms: u64,
+
+    /// Minimum cosine similarity between two query embeddings for the
+    /// second query to reuse the cached results of the first
+    #[serde(default = "default_semantic_cache_threshold")]
+    pub semantic_cache_threshold: f32,
+
+    /// Maximum number of queries kept in the semantic cache (0 disables it)
+    #[serde(default = "default_semantic_cache_max_entries")]
+    pub semantic_cache_max_entries: usize,
+
+    /// Retrieval strategy per query intent
+    #[serde(default)]
+    pub intent_policy: IntentPolicy,
+
+    /// Direct answers for factoid and definition queries
+    #[serde(default)]
+    pub answer_extraction: AnswerConfig,
+
+    /// How the chunk scores of a multi-vector document combine into the
+    /// document's score
+    #[serde(default)]
+    pub chunk_aggregation: ChunkAggregation,
+
+    /// Sparse (BM25 term) retrieval fused with dense retrieval. Unlike
+    /// `enable_hybrid_search`, which only reranks dense candidates by
+    /// keyword overlap, this also retrieves keyword matches the dense
+    /// search missed
+    #[serde(default)]
+    pub hybrid: HybridConfig,
+
+    /// Look up query embeddings in the embedding cache before calling the
+    /// provider
+    #[serde(default = "default_cache_embeddings")]
+    pub cache_query_embeddings: bool,
+}
+
+fn default_semantic_cache_threshold() -> f32 {
+    0.95
+}
+
+fn default_semantic_cache_max_entries() -> usize {
+    1000
 }
 
 impl Default for SearchConfig {
@@ -219,10 +535,263 @@ impl Default for SearchConfig {
             max_limit: 100,
             default_threshold: 0.5,
             enable_query_expansion: true,
+            query_dictionary_path: None,
             enable_hybrid_search: true,
             hybrid_keyword_weight: 0.3,
             enable_reranking: true,
             timeout_ms: 1000,
+            semantic_cache_threshold: default_semantic_cache_threshold(),
+            semantic_cache_max_entries: default_semantic_cache_max_entries(),
+            intent_policy: IntentPolicy::default(),
+            answer_extraction: AnswerConfig::default(),
+            chunk_aggregation: ChunkAggregation::default(),
+            hybrid: HybridConfig::default(),
+            cache_query_embeddings: default_cache_embeddings(),
+        }
+    }
+}
+
+/// Combines the scores of a document's matching chunks into one score.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum ChunkAggregation {
+    /// Score of the best matching chunk
+    #[default]
+    MaxScore,
+
+    /// Sum of the `k` best chunk scores (typically 3), favouring documents
+    /// that match in several places
+    SumTopK { k: usize },
+
+    /// Best chunk score after weighting each chunk by `1 / (1 + decay *
+    /// index)`, favouring matches early in the document
+    PositionWeighted { decay: f32 },
+}
+
+impl ChunkAggregation {
+    /// Document score from `(chunk index, score)` pairs of its matching
+    /// chunks; 0.0 if none matched.
+    pub fn score(&self, chunk_scores: &[(usize, f32)]) -> f32 {
+        match *self {
+            Self::MaxScore => chunk_scores.iter().map(|(_, score)| *score).reduce(f32::max).unwrap_or(0.0),
+            Self::SumTopK { k } => {
+                let mut scores: Vec<f32> = chunk_scores.iter().map(|(_, score)| *score).collect();
+                scores.sort_by(|a, b| b.total_cmp(a));
+                scores.into_iter().take(k).sum()
+            }
+            Self::PositionWeighted { decay } => chunk_scores
+                .iter()
+                .map(|(index, score)| score / (1.0 + decay * *index as f32))
+                .reduce(f32::max)
+                .unwrap_or(0.0),
+        }
+    }
+}
+
+/// Sparse + dense hybrid retrieval.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct HybridConfig {
+    /// Keep a sparse term index of every document and search it along
+    /// with the vector index
+    pub enabled: bool,
+
+    /// How the dense and sparse scores of a document combine
+    #[serde(default)]
+    pub fusion: HybridFusion,
+}
+
+/// Combines the dense and sparse scores of a document into one score.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum HybridFusion {
+    /// Reciprocal rank fusion: the sum of `1 / (k + rank)` over both result
+    /// lists, scaled so that ranking first in both scores 1.0
+    Rrf { k: f32 },
+
+    /// `alpha * dense + (1 - alpha) * sparse`, with sparse scores divided by
+    /// the best sparse score of the query. An `alpha` of 1.0 ignores sparse
+    /// matches, 0.0 ignores dense ones
+    WeightedSum { alpha: f32 },
+}
+
+impl Default for HybridFusion {
+    fn default() -> Self {
+        Self::Rrf { k: 60.0 }
+    }
+}
+
+impl HybridFusion {
+    /// Fused score of a document from its dense and sparse scores and its
+    /// 0-based rank in each list; `max_sparse` is the best sparse score of
+    /// the query.
+    pub fn fuse(
+        &self,
+        dense: Option<(usize, f32)>,
+        sparse: Option<(usize, f32)>,
+        max_sparse: f32,
+    ) -> f32 {
+        match *self {
+            Self::Rrf { k } => {
+                let reciprocal = |hit: Option<(usize, f32)>| {
+                    hit.map_or(0.0, |(rank, _)| 1.0 / (k + rank as f32 + 1.0))
+                };
+                (reciprocal(dense) + reciprocal(sparse)) * (k + 1.0) / 2.0
+            }
+            Self::WeightedSum { alpha } => {
+                let dense = dense.map_or(0.0, |(_, score)| score);
+                let sparse = match sparse {
+                    Some((_, score)) if max_sparse > 0.0 => score / max_sparse,
+                    _ => 0.0,
+                };
+                alpha * dense + (1.0 - alpha) * sparse
+            }
+        }
+    }
+}
+
+/// Maps query intents to retrieval strategies.
+///
+/// Each query is classified by `QueryProcessor::classify` and searched with
+/// the first rule listing its intent; intents no rule lists are searched
+/// with the engine defaults.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntentPolicy {
+    /// Apply the rules at all
+    pub enabled: bool,
+
+    /// Symbol and file lookups: favour exact and keyword matches
+    pub navigational: IntentRule,
+
+    /// Open-ended questions: search with HyDE and query expansion
+    pub exploratory: IntentRule,
+
+    /// "How do I" questions: favour procedural memory and documentation
+    pub procedural: IntentRule,
+}
+
+impl IntentPolicy {
+    /// The branch and rule a query of the given intent is searched with;
+    /// no rule means the engine defaults.
+    pub fn rule_for(&self, intent: QueryIntent) -> (IntentBranch, Option<&IntentRule>) {
+        if !self.enabled {
+            return (IntentBranch::Default, None);
+        }
+
+        [
+            (IntentBranch::Navigational, &self.navigational),
+            (IntentBranch::Exploratory, &self.exploratory),
+            (IntentBranch::Procedural, &self.procedural),
+        ]
+        .into_iter()
+        .find(|(_, rule)| rule.intents.contains(&intent))
+        .map_or((IntentBranch::Default, None), |(branch, rule)| (branch, Some(rule)))
+    }
+}
+
+impl Default for IntentPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            navigational: IntentRule {
+                intents: vec![QueryIntent::Navigational],
+                keyword_weight: Some(0.6),
+                exact_match_boost: 0.3,
+                use_hyde: false,
+                query_expansion: Some(false),
+                ..IntentRule::default()
+            },
+            exploratory: IntentRule {
+                intents: vec![QueryIntent::Exploratory],
+                use_hyde: true,
+                query_expansion: Some(true),
+                ..IntentRule::default()
+            },
+            procedural: IntentRule {
+                intents: vec![QueryIntent::Procedural, QueryIntent::Examples],
+                boost_entity_types: vec![EntityType::Document],
+                boost_metadata: HashMap::from([(
+                    "memory_type".to_string(),
+                    "procedural".to_string(),
+                )]),
+                boost_factor: 1.2,
+                ..IntentRule::default()
+            },
+        }
+    }
+}
+
+/// Retrieval adjustments for the intents of one policy branch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntentRule {
+    /// Intents the rule applies to
+    pub intents: Vec<QueryIntent>,
+
+    /// Keyword weight of hybrid ranking, in place of `hybrid_keyword_weight`
+    #[serde(default)]
+    pub keyword_weight: Option<f32>,
+
+    /// Added to the score of results containing the queried name verbatim
+    #[serde(default)]
+    pub exact_match_boost: f32,
+
+    /// Search with a HyDE embedding instead of the query embedding
+    #[serde(default)]
+    pub use_hyde: bool,
+
+    /// Turn query expansion on or off, in place of `enable_query_expansion`
+    #[serde(default)]
+    pub query_expansion: Option<bool>,
+
+    /// Entity types whose results are boosted
+    #[serde(default)]
+    pub boost_entity_types: Vec<EntityType>,
+
+    /// Metadata values whose results are boosted
+    #[serde(default)]
+    pub boost_metadata: HashMap<String, String>,
+
+    /// Score multiplier of boosted results
+    #[serde(default = "default_boost_factor")]
+    pub boost_factor: f32,
+}
+
+fn default_boost_factor() -> f32 {
+    1.0
+}
+
+impl Default for IntentRule {
+    fn default() -> Self {
+        Self {
+            intents: Vec::new(),
+            keyword_weight: None,
+            exact_match_boost: 0.0,
+            use_hyde: false,
+            query_expansion: None,
+            boost_entity_types: Vec::new(),
+            boost_metadata: HashMap::new(),
+            boost_factor: default_boost_factor(),
+        }
+    }
+}
+
+/// Policy branch a query was searched with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntentBranch {
+    Navigational,
+    Exploratory,
+    Procedural,
+    /// No rule matched, or the policy is disabled
+    Default,
+}
+
+impl IntentBranch {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IntentBranch::Navigational => "navigational",
+            IntentBranch::Exploratory => "exploratory",
+            IntentBranch::Procedural => "procedural",
+            IntentBranch::Default => "default",
         }
     }
 }
@@ -239,6 +808,11 @@ pub struct CacheConfig {
     /// Embedding cache TTL in seconds
     pub embedding_cache_ttl_seconds: u64,
 
+    /// Most bytes of vectors the embedding cache holds, at 4 bytes per
+    /// dimension
+    #[serde(default = "default_embedding_cache_max_bytes")]
+    pub embedding_cache_max_bytes: u64,
+
     /// Enable query cache
     pub enable_query_cache: bool,
 
@@ -249,12 +823,17 @@ pub struct CacheConfig {
     pub query_cache_ttl_seconds: u64,
 }
 
+fn default_embedding_cache_max_bytes() -> u64 {
+    64 * 1024 * 1024
+}
+
 impl Default for CacheConfig {
     fn default() -> Self {
         Self {
             enable_embedding_cache: true,
             embedding_cache_size: 10_000,
             embedding_cache_ttl_seconds: 3600, // 1 hour
+            embedding_cache_max_bytes: default_embedding_cache_max_bytes(),
             enable_query_cache: true,
             query_cache_size: 1_000,
             query_cache_ttl_seconds: 300, // 5 minutes
@@ -311,6 +890,17 @@ pub struct QdrantConfig {
     pub enable_connection_pool: bool,
 }
 
+impl QdrantConfig {
+    /// Quantization collections are created with, `None` when disabled.
+    pub fn effective_quantization(&self) -> QuantizationType {
+        if self.enable_quantization {
+            self.quantization_type
+        } else {
+            QuantizationType::None
+        }
+    }
+}
+
 impl Default for QdrantConfig {
     fn default() -> Self {
         Self {
@@ -361,7 +951,7 @@ impl Default for QdrantHnswConfig {
 }
 
 /// Quantization type for vector compression.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum QuantizationType {
     /// Scalar quantization (8-bit)
@@ -377,16 +967,33 @@ pub enum QuantizationType {
 pub struct VectorStoreConfig {
     /// Backend type: always "qdrant" in this version
     pub backend: VectorStoreBackend,
+
+    /// Vector size the collection was created with; when set it must
+    /// match the embedding dimension
+    #[serde(default)]
+    pub dimension: Option<usize>,
 }
 
 impl Default for VectorStoreConfig {
     fn default() -> Self {
         Self {
             backend: VectorStoreBackend::Qdrant,
+            dimension: None,
         }
     }
 }
 
+/// When a search engine pays its start-up cost.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InitializationMode {
+    /// Warm up on first use; for one-shot commands
+    #[default]
+    Lazy,
+    /// Warm up before the engine is returned; for long-running servers
+    Eager,
+}
+
 /// Vector store backend type.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -414,4 +1021,88 @@ mod tests {
         let deserialized: SemanticConfig = toml::from_str(&toml).unwrap();
         assert_eq!(config.embedding.primary_provider, deserialized.embedding.primary_provider);
     }
+
+    #[test]
+    fn test_intent_policy_rules() {
+        let mut policy = IntentPolicy::default();
+
+        let (branch, rule) = policy.rule_for(QueryIntent::Navigational);
+        assert_eq!(branch, IntentBranch::Navigational);
+        assert!(!rule.unwrap().use_hyde);
+        assert!(policy.rule_for(QueryIntent::Exploratory).1.unwrap().use_hyde);
+        assert_eq!(policy.rule_for(QueryIntent::Examples).0, IntentBranch::Procedural);
+        assert_eq!(policy.rule_for(QueryIntent::Code).0, IntentBranch::Default);
+
+        policy.enabled = false;
+        assert!(policy.rule_for(QueryIntent::Navigational).1.is_none());
+
+        let search: SearchConfig = toml::from_str(&toml::to_string(&SearchConfig::default()).unwrap()).unwrap();
+        assert_eq!(search.intent_policy.procedural.boost_factor, 1.2);
+    }
+
+    #[test]
+    fn test_chunk_aggregation() {
+        let scores = [(0, 0.5), (3, 0.9), (1, 0.7), (2, 0.2)];
+
+        assert_eq!(ChunkAggregation::MaxScore.score(&scores), 0.9);
+        assert!((ChunkAggregation::SumTopK { k: 3 }.score(&scores) - 2.1).abs() < 1e-6);
+        assert!((ChunkAggregation::PositionWeighted { decay: 0.5 }.score(&scores) - 0.5).abs() < 1e-6);
+        assert_eq!(ChunkAggregation::MaxScore.score(&[]), 0.0);
+
+        let search: SearchConfig = toml::from_str(
+            &toml::to_string(&SearchConfig {
+                chunk_aggregation: ChunkAggregation::SumTopK { k: 3 },
+                ..SearchConfig::default()
+            })
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(search.chunk_aggregation, ChunkAggregation::SumTopK { k: 3 });
+    }
+
+    #[test]
+    fn test_hybrid_fusion() {
+        let rrf = HybridFusion::Rrf { k: 60.0 };
+        assert!((rrf.fuse(Some((0, 0.2)), Some((0, 3.0)), 3.0) - 1.0).abs() < 1e-6);
+        assert!(rrf.fuse(Some((0, 0.9)), None, 3.0) < rrf.fuse(Some((1, 0.8)), Some((1, 2.0)), 3.0));
+
+        let weighted = HybridFusion::WeightedSum { alpha: 0.25 };
+        assert!((weighted.fuse(Some((0, 0.8)), Some((0, 1.5)), 3.0) - 0.575).abs() < 1e-6);
+        assert!((weighted.fuse(None, Some((0, 3.0)), 3.0) - 0.75).abs() < 1e-6);
+        assert_eq!(weighted.fuse(Some((0, 0.8)), None, 0.0), 0.2);
+
+        let search: SearchConfig = toml::from_str(
+            &toml::to_string(&SearchConfig {
+                hybrid: HybridConfig {
+                    enabled: true,
+                    fusion: HybridFusion::WeightedSum { alpha: 0.3 },
+                },
+                ..SearchConfig::default()
+            })
+            .unwrap(),
+        )
+        .unwrap();
+        assert!(search.hybrid.enabled);
+        assert_eq!(search.hybrid.fusion, HybridFusion::WeightedSum { alpha: 0.3 });
+    }
+
+    #[test]
+    fn test_vector_dimension_consistency() {
+        let mut config = SemanticConfig::default();
+        assert!(config.validate().is_ok());
+
+        config.embedding.dimensions = Some(512);
+        config.vector_store.dimension = Some(1536);
+        assert!(matches!(
+            config.validate(),
+            Err(SemanticError::DimensionMismatch { expected: 1536, got: 512 })
+        ));
+
+        config.vector_store.dimension = Some(512);
+        assert!(config.validate().is_ok());
+        assert!(config.check_vector_dimension(384).is_err());
+
+        config.embedding.dimensions = Some(0);
+        assert!(matches!(config.validate(), Err(SemanticError::Config(_))));
+    }
 }
@@ -12,9 +12,15 @@
 //! - "RECOMP: Improving Retrieval-Augmented LMs with Compression and Selective Augmentation" (Xu et al., 2023)
 
 use crate::error::{Result, SemanticError};
+use crate::providers::EmbeddingProvider;
 use crate::types::Vector;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+use tracing::warn;
 use unicode_segmentation::UnicodeSegmentation;
 
 /// Configuration for context compression.
@@ -32,6 +38,32 @@ pub struct CompressionConfig {
     pub enable_sentence_compression: bool,
     /// Preserve document boundaries in output
     pub preserve_boundaries: bool,
+    /// Whether to drop whole chunks or extract sentences from them
+    #[serde(default)]
+    pub mode: CompressionMode,
+    /// Sentences every chunk keeps in `Extract` and `Hybrid` modes, even
+    /// beyond its share of the budget
+    #[serde(default = "default_min_retained_sentences")]
+    pub min_retained_sentences: usize,
+}
+
+fn default_min_retained_sentences() -> usize {
+    1
+}
+
+/// How the compressor fits chunks into the token budget.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompressionMode {
+    /// Keep or drop whole chunks
+    #[default]
+    Drop,
+    /// Keep every relevant chunk, cut down to its share of the budget by
+    /// keeping its best-scoring sentences
+    Extract,
+    /// Extract sentences, then drop the least relevant chunks while the
+    /// retained sentences still exceed the budget
+    Hybrid,
 }
 
 impl Default for CompressionConfig {
@@ -43,6 +75,8 @@ impl Default for CompressionConfig {
             redundancy_threshold: 0.85,
             enable_sentence_compression: true,
             preserve_boundaries: true,
+            mode: CompressionMode::Drop,
+            min_retained_sentences: default_min_retained_sentences(),
         }
     }
 }
@@ -54,7 +88,7 @@ pub struct ContextChunk {
     pub text: String,
     /// Relevance score to the query (0.0 - 1.0)
     pub relevance_score: f32,
-    /// Estimated token count
+    /// Token count, exact for chunks produced by `TokenAwareChunker`
     pub token_count: usize,
     /// Source document ID
     pub source_id: String,
@@ -79,8 +113,47 @@ pub struct CompressedContext {
     pub compression_ratio: f32,
     /// Statistics about the compression process
     pub stats: CompressionStats,
+    /// What was kept of each input chunk, in input order
+    pub retention: Vec<ChunkRetention>,
 }
 
+/// What compression kept of one input chunk.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkRetention {
+    pub source_id: String,
+    pub position: usize,
+    pub original_sentences: usize,
+    pub retained_sentences: usize,
+    pub original_tokens: usize,
+    pub retained_tokens: usize,
+    /// Why the whole chunk was dropped, if it was
+    pub removed_by: Option<RemovalReason>,
+}
+
+/// Why a chunk was dropped from the compressed context.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RemovalReason {
+    Relevance,
+    Redundancy,
+    Budget,
+}
+
+/// A chunk going through compression, with what is needed to report its
+/// retention.
+struct Candidate {
+    index: usize,
+    chunk: ContextChunk,
+    sentences: Vec<String>,
+    retained_sentences: usize,
+}
+
+/// Weight of query similarity against position when scoring sentences.
+const QUERY_SIMILARITY_WEIGHT: f32 = 0.8;
+
+/// Marks sentences cut from an extracted chunk.
+const ELLIPSIS: &str = "...";
+
 /// Statistics about the compression process.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CompressionStats {
@@ -88,6 +161,8 @@ pub struct CompressionStats {
     pub compressed_token_count: usize,
     pub chunks_removed_by_relevance: usize,
     pub chunks_removed_by_redundancy: usize,
+    #[serde(default)]
+    pub chunks_removed_by_budget: usize,
     pub sentences_compressed: usize,
 }
 
@@ -134,73 +209,166 @@ impl ContextCompressor {
     /// 2. Redundancy removal: Remove similar/duplicate chunks
     /// 3. Sentence compression: Remove less important sentences
     /// 4. Token budget enforcement: Trim to fit target budget
+    ///
+    /// Without a query, `Extract` and `Hybrid` modes score sentences by
+    /// position alone; see [`Self::compress_for_query`].
     pub fn compress(&self, chunks: Vec<ContextChunk>) -> Result<CompressedContext> {
+        let (candidates, stats, retention) = self.select_candidates(chunks);
+        let similarity = vec![None; candidates.len()];
+        Ok(self.fit_to_budget(candidates, &similarity, stats, retention))
+    }
+
+    /// Compress chunks for a query. In `Extract` and `Hybrid` modes,
+    /// sentences are scored by similarity to the query, computed with
+    /// `provider` embeddings or, without one, by word overlap.
+    ///
+    /// Reference: "RECOMP: Improving Retrieval-Augmented LMs" (Xu et al., 2023)
+    pub async fn compress_for_query(
+        &self,
+        chunks: Vec<ContextChunk>,
+        query: &str,
+        provider: Option<&dyn EmbeddingProvider>,
+    ) -> Result<CompressedContext> {
+        let (candidates, stats, retention) = self.select_candidates(chunks);
+        let similarity = if self.config.mode == CompressionMode::Drop {
+            vec![None; candidates.len()]
+        } else {
+            self.query_similarity(&candidates, query, provider).await?
+        };
+        Ok(self.fit_to_budget(candidates, &similarity, stats, retention))
+    }
+
+    /// Filter chunks by relevance and redundancy, ordered by relevance.
+    fn select_candidates(
+        &self,
+        chunks: Vec<ContextChunk>,
+    ) -> (Vec<Candidate>, CompressionStats, Vec<ChunkRetention>) {
         let mut stats = CompressionStats {
             original_token_count: chunks.iter().map(|c| c.token_count).sum(),
             ..Default::default()
         };
 
+        let mut retention = Vec::with_capacity(chunks.len());
+        let candidates = chunks
+            .into_iter()
+            .enumerate()
+            .map(|(index, chunk)| {
+                let sentences = split_sentences(&chunk.text);
+                retention.push(ChunkRetention {
+                    source_id: chunk.source_id.clone(),
+                    position: chunk.position,
+                    original_sentences: sentences.len(),
+                    retained_sentences: 0,
+                    original_tokens: chunk.token_count,
+                    retained_tokens: 0,
+                    removed_by: None,
+                });
+                Candidate {
+                    index,
+                    retained_sentences: sentences.len(),
+                    sentences,
+                    chunk,
+                }
+            })
+            .collect();
+
         // Step 1: Filter by relevance score
-        let mut filtered_chunks = self.filter_by_relevance(chunks, &mut stats);
+        let mut filtered = self.filter_by_relevance(candidates, &mut stats, &mut retention);
 
         // Step 2: Remove redundant chunks
         if self.config.enable_redundancy_removal {
-            filtered_chunks = self.remove_redundancy(filtered_chunks, &mut stats);
+            filtered = self.remove_redundancy(filtered, &mut stats, &mut retention);
         }
 
         // Step 3: Sort by relevance and position (for coherence)
-        filtered_chunks.sort_by(|a, b| {
-            b.relevance_score
-                .partial_cmp(&a.relevance_score)
+        filtered.sort_by(|a, b| {
+            b.chunk
+                .relevance_score
+                .partial_cmp(&a.chunk.relevance_score)
                 .unwrap()
-                .then(a.position.cmp(&b.position))
+                .then(a.chunk.position.cmp(&b.chunk.position))
         });
 
-        // Step 4: Apply token budget constraint
-        filtered_chunks = self.enforce_token_budget(filtered_chunks, &mut stats);
+        (filtered, stats, retention)
+    }
 
-        // Step 5: Sentence-level compression if enabled
-        if self.config.enable_sentence_compression {
-            filtered_chunks = self.compress_sentences(filtered_chunks, &mut stats);
+    /// Fit the selected chunks into the budget according to the mode and
+    /// assemble the result.
+    fn fit_to_budget(
+        &self,
+        mut candidates: Vec<Candidate>,
+        similarity: &[Option<Vec<f32>>],
+        mut stats: CompressionStats,
+        mut retention: Vec<ChunkRetention>,
+    ) -> CompressedContext {
+        match self.config.mode {
+            CompressionMode::Drop => {
+                // Step 4: Apply token budget constraint
+                candidates = self.enforce_token_budget(candidates, &mut stats, &mut retention);
+
+                // Step 5: Sentence-level compression if enabled
+                if self.config.enable_sentence_compression {
+                    self.compress_sentences(&mut candidates, &mut stats);
+                }
+            }
+            CompressionMode::Extract => {
+                self.extract_sentences(&mut candidates, similarity, &mut stats);
+            }
+            CompressionMode::Hybrid => {
+                self.extract_sentences(&mut candidates, similarity, &mut stats);
+                self.drop_least_relevant(&mut candidates, &mut stats, &mut retention);
+            }
+        }
+
+        for candidate in &candidates {
+            let entry = &mut retention[candidate.index];
+            entry.retained_sentences = candidate.retained_sentences;
+            entry.retained_tokens = candidate.chunk.token_count;
         }
+        let chunks: Vec<ContextChunk> = candidates.into_iter().map(|c| c.chunk).collect();
 
         // Step 6: Reconstruct text
-        let text = self.reconstruct_text(&filtered_chunks);
+        let text = self.reconstruct_text(&chunks);
         let token_count = self.estimate_token_count(&text);
 
         stats.compressed_token_count = token_count;
 
-        let chunks_retained = filtered_chunks.len();
+        let chunks_retained = chunks.len();
         let compression_ratio = if token_count > 0 {
             stats.original_token_count as f32 / token_count as f32
         } else {
             1.0
         };
 
-        Ok(CompressedContext {
+        CompressedContext {
             text,
             token_count,
             chunks_retained,
-            chunks_removed: stats.chunks_removed_by_relevance + stats.chunks_removed_by_redundancy,
+            chunks_removed: stats.chunks_removed_by_relevance
+                + stats.chunks_removed_by_redundancy
+                + stats.chunks_removed_by_budget,
             compression_ratio,
             stats,
-        })
+            retention,
+        }
     }
 
     /// Filter chunks by relevance score.
     fn filter_by_relevance(
         &self,
-        chunks: Vec<ContextChunk>,
+        candidates: Vec<Candidate>,
         stats: &mut CompressionStats,
-    ) -> Vec<ContextChunk> {
-        let original_count = chunks.len();
-        let filtered: Vec<_> = chunks
+        retention: &mut [ChunkRetention],
+    ) -> Vec<Candidate> {
+        let (kept, removed): (Vec<_>, Vec<_>) = candidates
             .into_iter()
-            .filter(|chunk| chunk.relevance_score >= self.config.min_relevance_threshold)
-            .collect();
+            .partition(|c| c.chunk.relevance_score >= self.config.min_relevance_threshold);
 
-        stats.chunks_removed_by_relevance = original_count - filtered.len();
-        filtered
+        stats.chunks_removed_by_relevance = removed.len();
+        for candidate in removed {
+            retention[candidate.index].removed_by = Some(RemovalReason::Relevance);
+        }
+        kept
     }
 
     /// Remove redundant chunks based on text similarity.
@@ -209,24 +377,26 @@ impl ContextCompressor {
     /// Reference: "RECOMP: Improving Retrieval-Augmented LMs" (Xu et al., 2023)
     fn remove_redundancy(
         &self,
-        chunks: Vec<ContextChunk>,
+        candidates: Vec<Candidate>,
         stats: &mut CompressionStats,
-    ) -> Vec<ContextChunk> {
-        let mut unique_chunks = Vec::new();
-        let original_count = chunks.len();
+        retention: &mut [ChunkRetention],
+    ) -> Vec<Candidate> {
+        let mut unique: Vec<Candidate> = Vec::new();
 
-        for chunk in chunks {
-            let is_redundant = unique_chunks.iter().any(|existing: &ContextChunk| {
-                self.are_chunks_similar(existing, &chunk)
-            });
+        for candidate in candidates {
+            let is_redundant = unique
+                .iter()
+                .any(|existing| self.are_chunks_similar(&existing.chunk, &candidate.chunk));
 
-            if !is_redundant {
-                unique_chunks.push(chunk);
+            if is_redundant {
+                stats.chunks_removed_by_redundancy += 1;
+                retention[candidate.index].removed_by = Some(RemovalReason::Redundancy);
+            } else {
+                unique.push(candidate);
             }
         }
 
-        stats.chunks_removed_by_redundancy = original_count - unique_chunks.len();
-        unique_chunks
+        unique
     }
 
     /// Check if two chunks are similar based on embeddings or text.
@@ -266,18 +436,24 @@ impl ContextCompressor {
     /// Places most relevant chunks at the beginning and end for better LLM performance.
     fn enforce_token_budget(
         &self,
-        chunks: Vec<ContextChunk>,
-        _stats: &mut CompressionStats,
-    ) -> Vec<ContextChunk> {
+        candidates: Vec<Candidate>,
+        stats: &mut CompressionStats,
+        retention: &mut [ChunkRetention],
+    ) -> Vec<Candidate> {
         let mut selected = Vec::new();
         let mut current_tokens = 0;
-
-        for chunk in chunks {
-            if current_tokens + chunk.token_count <= self.config.target_token_budget {
-                current_tokens += chunk.token_count;
-                selected.push(chunk);
+        let mut over_budget = false;
+
+        for candidate in candidates {
+            if !over_budget
+                && current_tokens + candidate.chunk.token_count <= self.config.target_token_budget
+            {
+                current_tokens += candidate.chunk.token_count;
+                selected.push(candidate);
             } else {
-                break;
+                over_budget = true;
+                stats.chunks_removed_by_budget += 1;
+                retention[candidate.index].removed_by = Some(RemovalReason::Budget);
             }
         }
 
@@ -287,29 +463,178 @@ impl ContextCompressor {
     /// Compress chunks at sentence level by removing low-importance sentences.
     ///
     /// Reference: "LongLLMLingua: Accelerating and Enhancing LLMs" (Jiang et al., 2023)
-    fn compress_sentences(
+    fn compress_sentences(&self, candidates: &mut [Candidate], stats: &mut CompressionStats) {
+        for candidate in candidates {
+            let chunk = &mut candidate.chunk;
+            let sentences: Vec<_> = chunk.text.split(". ").collect();
+            if sentences.len() > 3 {
+                // Keep first, last, and middle sentences (simple heuristic)
+                let first = sentences.first().unwrap_or(&"");
+                let middle = sentences.get(sentences.len() / 2).unwrap_or(&"");
+                let last = sentences.last().unwrap_or(&"");
+                let compressed = format!("{}. {}. {}", first, middle, last);
+
+                stats.sentences_compressed += sentences.len() - 3;
+                chunk.text = compressed;
+                chunk.token_count = self.estimate_token_count(&chunk.text);
+                candidate.retained_sentences = split_sentences(&chunk.text).len();
+            }
+        }
+    }
+
+    /// Similarity of every sentence of every candidate to the query.
+    async fn query_similarity(
         &self,
-        chunks: Vec<ContextChunk>,
+        candidates: &[Candidate],
+        query: &str,
+        provider: Option<&dyn EmbeddingProvider>,
+    ) -> Result<Vec<Option<Vec<f32>>>> {
+        let Some(provider) = provider else {
+            return Ok(candidates
+                .iter()
+                .map(|c| {
+                    Some(c.sentences.iter().map(|s| self.text_similarity(query, s)).collect())
+                })
+                .collect());
+        };
+
+        // One batch for the query and all sentences
+        let mut texts = vec![query.to_string()];
+        texts.extend(candidates.iter().flat_map(|c| c.sentences.iter().cloned()));
+        let embeddings = provider.embed_batch(&texts).await?;
+        let (query_embedding, sentence_embeddings) = embeddings
+            .split_first()
+            .ok_or_else(|| SemanticError::Provider("No embedding returned for query".to_string()))?;
+
+        let mut sentence_embeddings = sentence_embeddings.iter();
+        Ok(candidates
+            .iter()
+            .map(|c| {
+                Some(
+                    sentence_embeddings
+                        .by_ref()
+                        .take(c.sentences.len())
+                        .map(|e| crate::types::cosine_similarity(query_embedding, e))
+                        .collect(),
+                )
+            })
+            .collect())
+    }
+
+    /// Cut each candidate down to its share of the budget, keeping its
+    /// best-scoring sentences in their original order.
+    fn extract_sentences(
+        &self,
+        candidates: &mut [Candidate],
+        similarity: &[Option<Vec<f32>>],
         stats: &mut CompressionStats,
-    ) -> Vec<ContextChunk> {
-        chunks
-            .into_iter()
-            .map(|mut chunk| {
-                let sentences: Vec<_> = chunk.text.split(". ").collect();
-                if sentences.len() > 3 {
-                    // Keep first, last, and middle sentences (simple heuristic)
-                    let first = sentences.first().unwrap_or(&"");
-                    let middle = sentences.get(sentences.len() / 2).unwrap_or(&"");
-                    let last = sentences.last().unwrap_or(&"");
-                    let compressed = format!("{}. {}. {}", first, middle, last);
-
-                    stats.sentences_compressed += sentences.len() - 3;
-                    chunk.text = compressed;
-                    chunk.token_count = self.estimate_token_count(&chunk.text);
+    ) {
+        let shares = self.token_shares(candidates);
+
+        for ((candidate, share), similarity) in candidates.iter_mut().zip(shares).zip(similarity) {
+            let count = candidate.sentences.len();
+            if candidate.chunk.token_count <= share || count <= self.config.min_retained_sentences
+            {
+                continue;
+            }
+
+            let scores: Vec<f32> = (0..count)
+                .map(|i| Self::sentence_score(i, similarity.as_ref().map(|s| s[i])))
+                .collect();
+            // Ties go to the earlier sentence, so output is deterministic
+            let mut ranked: Vec<usize> = (0..count).collect();
+            ranked.sort_by(|a, b| scores[*b].total_cmp(&scores[*a]).then(a.cmp(b)));
+
+            let mut keep = vec![false; count];
+            let mut kept = 0;
+            let mut tokens = 0;
+            for i in ranked {
+                let sentence_tokens = self.estimate_token_count(&candidate.sentences[i]);
+                if kept < self.config.min_retained_sentences || tokens + sentence_tokens <= share {
+                    keep[i] = true;
+                    kept += 1;
+                    tokens += sentence_tokens;
+                }
+            }
+
+            candidate.chunk.text = join_extracted(&candidate.sentences, &keep);
+            candidate.chunk.token_count = self.estimate_token_count(&candidate.chunk.text);
+            candidate.retained_sentences = kept;
+            stats.sentences_compressed += count - kept;
+        }
+    }
+
+    /// Budget share of each candidate, proportional to relevance. Chunks
+    /// smaller than their share hand the rest to the others.
+    fn token_shares(&self, candidates: &[Candidate]) -> Vec<usize> {
+        if candidates.is_empty() {
+            return Vec::new();
+        }
+
+        let budget = self.config.target_token_budget as f32;
+        let total_relevance: f32 = candidates.iter().map(|c| c.chunk.relevance_score.max(0.0)).sum();
+        let weights: Vec<f32> = candidates
+            .iter()
+            .map(|c| {
+                if total_relevance > 0.0 {
+                    c.chunk.relevance_score.max(0.0) / total_relevance
+                } else {
+                    1.0 / candidates.len() as f32
                 }
-                chunk
             })
-            .collect()
+            .collect();
+
+        let mut shares: Vec<f32> = weights.iter().map(|w| budget * w).collect();
+        let mut surplus = 0.0;
+        let mut short_weight = 0.0;
+        for ((candidate, share), weight) in candidates.iter().zip(&shares).zip(&weights) {
+            let tokens = candidate.chunk.token_count as f32;
+            if tokens <= *share {
+                surplus += share - tokens;
+            } else {
+                short_weight += weight;
+            }
+        }
+        if surplus > 0.0 && short_weight > 0.0 {
+            for ((candidate, share), weight) in candidates.iter().zip(&mut shares).zip(&weights) {
+                if candidate.chunk.token_count as f32 > *share {
+                    *share += surplus * weight / short_weight;
+                }
+            }
+        }
+
+        shares.into_iter().map(|share| share as usize).collect()
+    }
+
+    /// Score a sentence by query similarity, if known, and a lead bias:
+    /// earlier sentences of a chunk tend to carry its topic.
+    fn sentence_score(index: usize, similarity: Option<f32>) -> f32 {
+        let position = 1.0 / (1.0 + index as f32);
+        match similarity {
+            Some(similarity) => {
+                QUERY_SIMILARITY_WEIGHT * similarity + (1.0 - QUERY_SIMILARITY_WEIGHT) * position
+            }
+            None => position,
+        }
+    }
+
+    /// In `Hybrid` mode, drop the least relevant chunks while the retained
+    /// sentences exceed the budget. The most relevant chunk always stays.
+    fn drop_least_relevant(
+        &self,
+        candidates: &mut Vec<Candidate>,
+        stats: &mut CompressionStats,
+        retention: &mut [ChunkRetention],
+    ) {
+        let mut total: usize = candidates.iter().map(|c| c.chunk.token_count).sum();
+        while total > self.config.target_token_budget && candidates.len() > 1 {
+            let Some(candidate) = candidates.pop() else {
+                break;
+            };
+            total -= candidate.chunk.token_count;
+            stats.chunks_removed_by_budget += 1;
+            retention[candidate.index].removed_by = Some(RemovalReason::Budget);
+        }
     }
 
     /// Reconstruct text from chunks with proper formatting.
@@ -374,84 +699,462 @@ impl ContextCompressor {
     }
 }
 
+/// Split text into trimmed sentences (UAX #29).
+fn split_sentences(text: &str) -> Vec<String> {
+    text.split_sentence_bounds()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Join the kept sentences, marking each run of cut sentences with an
+/// ellipsis.
+fn join_extracted(sentences: &[String], keep: &[bool]) -> String {
+    let mut text = String::new();
+    let mut skipped = false;
+    for (sentence, kept) in sentences.iter().zip(keep) {
+        if !kept {
+            skipped = true;
+            continue;
+        }
+        if skipped {
+            if !text.is_empty() {
+                text.push(' ');
+            }
+            text.push_str(ELLIPSIS);
+        }
+        if !text.is_empty() {
+            text.push(' ');
+        }
+        text.push_str(sentence);
+        skipped = false;
+    }
+    if skipped {
+        text.push(' ');
+        text.push_str(ELLIPSIS);
+    }
+    text
+}
+
+/// Splits text into the tokens of a language or embedding model.
+///
+/// Implementations report where each token starts so that text can be cut
+/// between tokens; the number of offsets is the exact token count.
+pub trait Tokenizer: Send + Sync {
+    /// Byte offset in `text` at which each token starts, in order.
+    fn token_offsets(&self, text: &str) -> Vec<usize>;
+
+    /// Exact number of tokens in `text`.
+    fn count_tokens(&self, text: &str) -> usize {
+        self.token_offsets(text).len()
+    }
+}
+
+/// Approximate tokenizer treating every 4 bytes as a token, for when no
+/// model vocabulary is available.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HeuristicTokenizer;
+
+impl HeuristicTokenizer {
+    const BYTES_PER_TOKEN: usize = 4;
+}
+
+impl Tokenizer for HeuristicTokenizer {
+    fn token_offsets(&self, text: &str) -> Vec<usize> {
+        let mut offsets = Vec::with_capacity(text.len() / Self::BYTES_PER_TOKEN + 1);
+        let mut next = 0;
+        for (offset, _) in text.char_indices() {
+            if offset >= next {
+                offsets.push(offset);
+                next = offset + Self::BYTES_PER_TOKEN;
+            }
+        }
+        offsets
+    }
+}
+
+/// Tokenizer loaded from a HuggingFace `tokenizer.json`.
+pub struct HuggingFaceTokenizer {
+    inner: tokenizers::Tokenizer,
+}
+
+impl HuggingFaceTokenizer {
+    /// Load a tokenizer. Truncation and padding configured in the file are
+    /// disabled so that long texts are counted in full.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let inner = tokenizers::Tokenizer::from_file(path.as_ref()).map_err(|e| {
+            SemanticError::Config(format!(
+                "Failed to load tokenizer {}: {}",
+                path.as_ref().display(),
+                e
+            ))
+        })?;
+        Self::from_tokenizer(inner)
+    }
+
+    pub fn from_tokenizer(mut inner: tokenizers::Tokenizer) -> Result<Self> {
+        inner
+            .with_truncation(None)
+            .map_err(|e| SemanticError::Config(format!("Failed to disable truncation: {}", e)))?;
+        inner.with_padding(None);
+        Ok(Self { inner })
+    }
+}
+
+impl Tokenizer for HuggingFaceTokenizer {
+    fn token_offsets(&self, text: &str) -> Vec<usize> {
+        match self.inner.encode(text, false) {
+            Ok(encoding) => encoding.get_offsets().iter().map(|(start, _)| *start).collect(),
+            Err(e) => {
+                warn!("Tokenization failed, estimating token count instead: {}", e);
+                HeuristicTokenizer.token_offsets(text)
+            }
+        }
+    }
+}
+
+/// Pre-tokenization pattern of tiktoken's `cl100k_base`. The original ends
+/// with `\s+(?!\S)|\s+`; the regex crate has no look-ahead, so
+/// [`BpeTokenizer`] emulates that alternative after matching.
+const CL100K_PATTERN: &str = r"(?i:'s|'t|'re|'ve|'m|'ll|'d)|[^\r\n\p{L}\p{N}]?\p{L}+|\p{N}{1,3}| ?[^\s\p{L}\p{N}]+[\r\n]*|\s*[\r\n]+|\s+";
+
+/// Byte-pair encoding tokenizer compatible with tiktoken rank files, such
+/// as `cl100k_base.tiktoken`.
+pub struct BpeTokenizer {
+    ranks: HashMap<Vec<u8>, u32>,
+    pattern: Regex,
+}
+
+impl BpeTokenizer {
+    /// Load a tiktoken rank file: one `<base64 token> <rank>` pair per line.
+    pub fn from_tiktoken_file(path: impl AsRef<Path>) -> Result<Self> {
+        let contents = std::fs::read_to_string(path.as_ref())?;
+        let mut ranks = HashMap::new();
+        for (line_no, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let parsed = line.split_once(' ').and_then(|(token, rank)| {
+                Some((decode_base64(token)?, rank.trim().parse::<u32>().ok()?))
+            });
+            let Some((token, rank)) = parsed else {
+                return Err(SemanticError::Config(format!(
+                    "Invalid tiktoken rank at {}:{}",
+                    path.as_ref().display(),
+                    line_no + 1
+                )));
+            };
+            ranks.insert(token, rank);
+        }
+        Self::from_ranks(ranks)
+    }
+
+    /// Build a tokenizer from merge ranks, using the `cl100k_base`
+    /// pre-tokenization.
+    pub fn from_ranks(ranks: HashMap<Vec<u8>, u32>) -> Result<Self> {
+        Self::with_pattern(ranks, CL100K_PATTERN)
+    }
+
+    /// Build a tokenizer from merge ranks and a pre-tokenization pattern.
+    pub fn with_pattern(ranks: HashMap<Vec<u8>, u32>, pattern: &str) -> Result<Self> {
+        let pattern = Regex::new(pattern)
+            .map_err(|e| SemanticError::Config(format!("Invalid pre-tokenization pattern: {}", e)))?;
+        Ok(Self { ranks, pattern })
+    }
+
+    /// Split text into pieces that are encoded independently.
+    fn pieces<'a>(&self, text: &'a str) -> Vec<(usize, &'a str)> {
+        let mut pieces = Vec::new();
+        let mut pos = 0;
+        while let Some(found) = self.pattern.find_at(text, pos) {
+            let mut end = found.end();
+            let matched = found.as_str();
+            // `\s+(?!\S)`: a whitespace run followed by a word leaves its
+            // last character to that word.
+            let trailing_newline = matched.ends_with(['\r', '\n']);
+            if !trailing_newline
+                && matched.chars().all(char::is_whitespace)
+                && text[end..].chars().next().is_some_and(|c| !c.is_whitespace())
+            {
+                if let Some((last, _)) = matched.char_indices().next_back() {
+                    if last > 0 {
+                        end = found.start() + last;
+                    }
+                }
+            }
+            pieces.push((found.start(), &text[found.start()..end]));
+            pos = end;
+        }
+        pieces
+    }
+
+    /// Merge the bytes of a piece by rank; returns the offsets of the
+    /// resulting tokens within the piece.
+    fn byte_pair_offsets(&self, piece: &[u8]) -> Vec<usize> {
+        if piece.len() == 1 || self.ranks.contains_key(piece) {
+            return vec![0];
+        }
+
+        let mut bounds: Vec<usize> = (0..=piece.len()).collect();
+        loop {
+            let best = (0..bounds.len() - 2)
+                .filter_map(|i| {
+                    self.ranks
+                        .get(&piece[bounds[i]..bounds[i + 2]])
+                        .map(|rank| (*rank, i))
+                })
+                .min();
+            let Some((_, i)) = best else {
+                break;
+            };
+            bounds.remove(i + 1);
+            if bounds.len() < 3 {
+                break;
+            }
+        }
+        bounds.pop();
+        bounds
+    }
+}
+
+impl Tokenizer for BpeTokenizer {
+    fn token_offsets(&self, text: &str) -> Vec<usize> {
+        let mut offsets = Vec::new();
+        for (start, piece) in self.pieces(text) {
+            offsets.extend(
+                self.byte_pair_offsets(piece.as_bytes())
+                    .into_iter()
+                    .map(|offset| start + offset),
+            );
+        }
+        offsets
+    }
+}
+
+/// Decode standard, padded base64 as used by tiktoken rank files.
+fn decode_base64(input: &str) -> Option<Vec<u8>> {
+    fn value(c: u8) -> Option<u32> {
+        match c {
+            b'A'..=b'Z' => Some((c - b'A') as u32),
+            b'a'..=b'z' => Some((c - b'a') as u32 + 26),
+            b'0'..=b'9' => Some((c - b'0') as u32 + 52),
+            b'+' => Some(62),
+            b'/' => Some(63),
+            _ => None,
+        }
+    }
+
+    let input = input.trim_end_matches('=').as_bytes();
+    let mut out = Vec::with_capacity(input.len() * 3 / 4);
+    for group in input.chunks(4) {
+        if group.len() == 1 {
+            return None;
+        }
+        let mut bits = 0u32;
+        for (i, c) in group.iter().enumerate() {
+            bits |= value(*c)? << (18 - 6 * i);
+        }
+        out.extend_from_slice(&bits.to_be_bytes()[1..group.len()]);
+    }
+    Some(out)
+}
+
+/// Move `offset` back to the nearest char boundary of `text`.
+fn floor_char_boundary(text: &str, mut offset: usize) -> usize {
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
+
 /// Token-aware text chunker for splitting long documents.
 ///
+/// Text is segmented into sentences (UAX #29), except inside fenced code
+/// blocks where each line is a unit. Units are packed greedily up to
+/// `chunk_size` tokens, and each chunk after the first starts with the last
+/// `chunk_overlap` tokens of the previous one. A unit longer than the budget
+/// is split between tokens, with a warning.
+///
 /// Reference: "Precise Zero-Shot Dense Retrieval" (Gao et al., 2023)
 pub struct TokenAwareChunker {
     /// Target chunk size in tokens
     pub chunk_size: usize,
     /// Overlap between chunks in tokens
     pub chunk_overlap: usize,
+    tokenizer: Arc<dyn Tokenizer>,
 }
 
 impl TokenAwareChunker {
+    /// Create a chunker counting tokens with [`HeuristicTokenizer`].
     pub fn new(chunk_size: usize, chunk_overlap: usize) -> Self {
+        Self::with_tokenizer(chunk_size, chunk_overlap, Arc::new(HeuristicTokenizer))
+    }
+
+    /// Create a chunker counting tokens with the model's own tokenizer.
+    pub fn with_tokenizer(
+        chunk_size: usize,
+        chunk_overlap: usize,
+        tokenizer: Arc<dyn Tokenizer>,
+    ) -> Self {
         Self {
             chunk_size,
             chunk_overlap,
+            tokenizer,
         }
     }
 
-    /// Split text into overlapping chunks.
+    /// Split text into overlapping chunks of at most `chunk_size` tokens.
     pub fn chunk(&self, text: &str, source_id: String) -> Vec<ContextChunk> {
-        let sentences: Vec<&str> = text.split(". ").collect();
-        let mut chunks = Vec::new();
-        let mut current_chunk = String::new();
-        let mut current_tokens = 0;
-        let mut position = 0;
+        let budget = self.chunk_size.max(1);
+        // Overlap never takes more than half a chunk so every chunk makes
+        // progress
+        let overlap = self.chunk_overlap.min(budget / 2);
+        let units = self.units(text, budget - overlap);
 
-        for sentence in sentences {
-            let sentence_tokens = self.estimate_tokens(sentence);
+        let mut chunks = Vec::new();
+        let mut next = 0;
+        let mut overlap_start = None;
+        while next < units.len() {
+            let start = overlap_start.unwrap_or(units[next].start);
+            let mut end = units[next].end;
+            next += 1;
+            while next < units.len()
+                && self.tokenizer.count_tokens(&text[start..units[next].end]) <= budget
+            {
+                end = units[next].end;
+                next += 1;
+            }
 
-            if current_tokens + sentence_tokens > self.chunk_size && !current_chunk.is_empty() {
-                // Create chunk
+            let chunk_text = text[start..end].trim();
+            if !chunk_text.is_empty() {
                 chunks.push(ContextChunk {
-                    text: current_chunk.clone(),
+                    text: chunk_text.to_string(),
                     relevance_score: 1.0, // Default, will be updated during search
-                    token_count: current_tokens,
+                    token_count: self.tokenizer.count_tokens(chunk_text),
                     source_id: source_id.clone(),
                     embedding: None,
-                    position,
+                    position: chunks.len(),
                 });
+            }
 
-                // Handle overlap - take last 2 sentences
-                let overlap_sentences: Vec<&str> = current_chunk
-                    .split(". ")
-                    .collect::<Vec<&str>>()
-                    .into_iter()
-                    .rev()
-                    .take(2)
-                    .collect::<Vec<&str>>()
-                    .into_iter()
-                    .rev()
-                    .collect();
-                current_chunk = overlap_sentences.join(". ");
-                current_tokens = self.estimate_tokens(&current_chunk);
-                position += 1;
+            overlap_start = if overlap > 0 {
+                let offsets = self.tokenizer.token_offsets(&text[start..end]);
+                let tail = offsets.len().saturating_sub(overlap);
+                offsets
+                    .get(tail)
+                    .map(|offset| floor_char_boundary(text, start + offset))
+            } else {
+                None
+            };
+        }
+
+        chunks
+    }
+
+    /// Byte ranges of the sentences and code lines of `text`, covering it
+    /// without gaps, each at most `max_tokens` long.
+    fn units(&self, text: &str, max_tokens: usize) -> Vec<Range<usize>> {
+        let mut units = Vec::new();
+        let mut prose_start = 0;
+        let mut in_code = false;
+        let mut offset = 0;
+
+        for line in text.split_inclusive('\n') {
+            let line_range = offset..offset + line.len();
+            offset = line_range.end;
+
+            let is_fence = {
+                let trimmed = line.trim_start();
+                trimmed.starts_with("```") || trimmed.starts_with("~~~")
+            };
+            if !in_code && !is_fence {
+                continue;
             }
 
-            current_chunk.push_str(sentence);
-            current_chunk.push_str(". ");
-            current_tokens += sentence_tokens;
+            if !in_code {
+                self.push_sentences(text, prose_start..line_range.start, max_tokens, &mut units);
+                in_code = true;
+            } else if is_fence {
+                in_code = false;
+            }
+            self.push_unit(text, line_range.clone(), max_tokens, &mut units);
+            prose_start = line_range.end;
         }
+        self.push_sentences(text, prose_start..text.len(), max_tokens, &mut units);
 
-        // Add final chunk
-        if !current_chunk.is_empty() {
-            chunks.push(ContextChunk {
-                text: current_chunk,
-                relevance_score: 1.0,
-                token_count: current_tokens,
-                source_id,
-                embedding: None,
-                position,
-            });
+        units
+    }
+
+    fn push_sentences(
+        &self,
+        text: &str,
+        range: Range<usize>,
+        max_tokens: usize,
+        units: &mut Vec<Range<usize>>,
+    ) {
+        let mut offset = range.start;
+        for sentence in text[range].split_sentence_bounds() {
+            self.push_unit(text, offset..offset + sentence.len(), max_tokens, units);
+            offset += sentence.len();
         }
+    }
 
-        chunks
+    /// Add a unit, hard-splitting it between tokens if it is over budget.
+    fn push_unit(
+        &self,
+        text: &str,
+        range: Range<usize>,
+        max_tokens: usize,
+        units: &mut Vec<Range<usize>>,
+    ) {
+        let token_count = self.tokenizer.count_tokens(&text[range.clone()]);
+        if token_count > max_tokens {
+            warn!(
+                "Splitting a {}-token sentence that exceeds the {}-token chunk budget",
+                token_count, max_tokens
+            );
+            self.split_unit(text, range, max_tokens, units);
+        } else {
+            units.push(range);
+        }
     }
 
-    fn estimate_tokens(&self, text: &str) -> usize {
-        (text.len() / 4).max(1)
+    /// Cut a unit every `max_tokens` tokens. A piece can re-tokenize into
+    /// more tokens than it had in context, so pieces are checked again.
+    fn split_unit(
+        &self,
+        text: &str,
+        range: Range<usize>,
+        max_tokens: usize,
+        units: &mut Vec<Range<usize>>,
+    ) {
+        let offsets = self.tokenizer.token_offsets(&text[range.clone()]);
+        if offsets.len() <= max_tokens {
+            units.push(range);
+            return;
+        }
+
+        let mut cuts: Vec<usize> = offsets
+            .iter()
+            .step_by(max_tokens)
+            .skip(1)
+            .map(|offset| floor_char_boundary(text, range.start + offset))
+            .filter(|cut| *cut > range.start)
+            .collect();
+        cuts.dedup();
+        if cuts.is_empty() {
+            // A single character worth more tokens than the budget
+            units.push(range);
+            return;
+        }
+
+        let mut start = range.start;
+        for cut in cuts {
+            self.split_unit(text, start..cut, max_tokens, units);
+            start = cut;
+        }
+        self.split_unit(text, start..range.end, max_tokens, units);
     }
 }
 
@@ -468,6 +1171,7 @@ mod tests {
             redundancy_threshold: 0.8,
             enable_sentence_compression: false,
             preserve_boundaries: false,
+            ..Default::default()
         };
 
         let compressor = ContextCompressor::new(config);
@@ -506,6 +1210,107 @@ mod tests {
         assert_eq!(compressed.stats.chunks_removed_by_relevance, 1); // Low relevance chunk removed
     }
 
+    const OWNERSHIP_TEXT: &str = "Rust ownership rules prevent data races. \
+        The weather was pleasant yesterday. \
+        Borrowing lets code read data without taking ownership. \
+        Lunch was served at noon. \
+        Lifetimes describe how long references stay valid.";
+
+    #[tokio::test]
+    async fn test_extractive_compression() {
+        let compressor = ContextCompressor::new(CompressionConfig {
+            target_token_budget: 30,
+            mode: CompressionMode::Extract,
+            preserve_boundaries: false,
+            ..Default::default()
+        });
+        let chunks = compressor.create_chunks(vec![(
+            "doc1".to_string(),
+            OWNERSHIP_TEXT.to_string(),
+            0.9,
+        )]);
+
+        let compressed = compressor
+            .compress_for_query(chunks, "Rust ownership Borrowing references", None)
+            .await
+            .unwrap();
+
+        assert!(compressed.text.starts_with("Rust ownership rules prevent data races. ..."));
+        assert!(compressed.text.contains("Borrowing lets code read data"));
+        assert!(!compressed.text.contains("weather"));
+        assert_eq!(compressed.chunks_retained, 1);
+
+        let retention = &compressed.retention[0];
+        assert_eq!(retention.original_sentences, 5);
+        assert_eq!(retention.retained_sentences, 3);
+        assert!(retention.retained_tokens < retention.original_tokens);
+        assert_eq!(retention.removed_by, None);
+        assert_eq!(compressed.stats.sentences_compressed, 2);
+    }
+
+    #[tokio::test]
+    async fn test_extractive_compression_is_deterministic() {
+        let compressor = ContextCompressor::new(CompressionConfig {
+            target_token_budget: 40,
+            mode: CompressionMode::Extract,
+            ..Default::default()
+        });
+        let provider = crate::providers::MockProvider::new(384);
+        let documents = vec![
+            ("doc1".to_string(), OWNERSHIP_TEXT.to_string(), 0.9),
+            (
+                "doc2".to_string(),
+                "Tokio runs async tasks. It has a scheduler. Tasks yield at await points."
+                    .to_string(),
+                0.6,
+            ),
+        ];
+
+        let first = compressor
+            .compress_for_query(compressor.create_chunks(documents.clone()), "ownership", Some(&provider))
+            .await
+            .unwrap();
+        let second = compressor
+            .compress_for_query(compressor.create_chunks(documents), "ownership", Some(&provider))
+            .await
+            .unwrap();
+
+        assert_eq!(first.text, second.text);
+        assert_eq!(first.retention, second.retention);
+        assert!(first.retention.iter().all(|r| r.retained_sentences >= 1));
+    }
+
+    #[test]
+    fn test_hybrid_compression_drops_least_relevant() {
+        let compressor = ContextCompressor::new(CompressionConfig {
+            target_token_budget: 20,
+            mode: CompressionMode::Hybrid,
+            min_retained_sentences: 2,
+            enable_redundancy_removal: false,
+            ..Default::default()
+        });
+        let chunks = compressor.create_chunks(vec![
+            ("doc1".to_string(), OWNERSHIP_TEXT.to_string(), 0.9),
+            ("doc2".to_string(), OWNERSHIP_TEXT.to_string(), 0.5),
+            ("doc3".to_string(), "Barely relevant.".to_string(), 0.1),
+        ]);
+
+        let compressed = compressor.compress(chunks).unwrap();
+
+        assert_eq!(compressed.chunks_retained, 1);
+        assert_eq!(compressed.retention[0].retained_sentences, 2);
+        assert_eq!(compressed.retention[1].removed_by, Some(RemovalReason::Budget));
+        assert_eq!(compressed.retention[2].removed_by, Some(RemovalReason::Relevance));
+        assert_eq!(compressed.chunks_removed, 2);
+    }
+
+    #[test]
+    fn test_join_extracted_marks_gaps() {
+        let sentences: Vec<String> = ["A.", "B.", "C.", "D."].iter().map(|s| s.to_string()).collect();
+        assert_eq!(join_extracted(&sentences, &[true, false, true, true]), "A. ... C. D.");
+        assert_eq!(join_extracted(&sentences, &[false, true, false, false]), "... B. ...");
+    }
+
     #[test]
     fn test_redundancy_removal() {
         let config = CompressionConfig {
@@ -556,6 +1361,100 @@ mod tests {
         }
     }
 
+    fn test_ranks() -> HashMap<Vec<u8>, u32> {
+        let mut ranks: HashMap<Vec<u8>, u32> = (0..=255u8).map(|b| (vec![b], b as u32)).collect();
+        ranks.insert(b"ab".to_vec(), 256);
+        ranks.insert(b"abc".to_vec(), 257);
+        ranks
+    }
+
+    #[test]
+    fn test_bpe_tokenizer() {
+        let tokenizer = BpeTokenizer::from_ranks(test_ranks()).unwrap();
+
+        // "abc" is a whole token; " abc" merges to " " + "abc"
+        assert_eq!(tokenizer.token_offsets("abc abc"), vec![0, 3, 4]);
+
+        // A whitespace run gives its last space to the following word
+        let pieces: Vec<&str> = tokenizer.pieces("a   b").into_iter().map(|(_, p)| p).collect();
+        assert_eq!(pieces, vec!["a", "  ", " b"]);
+
+        assert_eq!(decode_base64("aGVsbG8=").unwrap(), b"hello");
+        assert_eq!(decode_base64("IQ==").unwrap(), b"!");
+        assert!(decode_base64("a").is_none());
+    }
+
+    #[test]
+    fn test_chunks_end_on_sentence_boundaries() {
+        let tokenizer = Arc::new(BpeTokenizer::from_ranks(test_ranks()).unwrap());
+        let chunker = TokenAwareChunker::with_tokenizer(40, 0, tokenizer.clone());
+
+        let text = "The first sentence is here. A second one follows it. \
+                    Then a third sentence. And finally the fourth one.";
+        let chunks = chunker.chunk(text, "doc1".to_string());
+
+        assert!(chunks.len() > 1);
+        for (i, chunk) in chunks.iter().enumerate() {
+            assert!(chunk.text.ends_with('.'), "chunk ended mid-sentence: {:?}", chunk.text);
+            assert_eq!(chunk.token_count, tokenizer.count_tokens(&chunk.text));
+            assert!(chunk.token_count <= 40);
+            assert_eq!(chunk.position, i);
+        }
+    }
+
+    #[test]
+    fn test_chunk_overlap_in_tokens() {
+        let chunker = TokenAwareChunker::new(20, 5);
+        let text = "Alpha beta gamma delta. Epsilon zeta eta theta. Iota kappa lambda mu. \
+                    Nu xi omicron pi. Rho sigma tau upsilon.";
+        let chunks = chunker.chunk(text, "doc1".to_string());
+
+        assert!(chunks.len() > 1);
+        for pair in chunks.windows(2) {
+            // The next chunk starts with the last 5 tokens (20 bytes) of the
+            // previous one
+            let shared = &pair[1].text[..10];
+            assert!(pair[0].text.contains(shared), "{:?} / {:?}", pair[0].text, pair[1].text);
+            assert!(pair[1].token_count <= 20);
+        }
+    }
+
+    #[test]
+    fn test_oversized_sentence_is_hard_split() {
+        let chunker = TokenAwareChunker::new(16, 0);
+        let sentence = "word ".repeat(100);
+        let chunks = chunker.chunk(&sentence, "doc1".to_string());
+
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| c.token_count <= 16));
+        let joined: String = chunks.iter().map(|c| c.text.replace(' ', "")).collect();
+        assert_eq!(joined, sentence.replace(' ', ""));
+    }
+
+    #[test]
+    fn test_code_blocks_not_split_mid_line() {
+        let chunker = TokenAwareChunker::new(24, 0);
+        let code_lines = [
+            "fn main() {",
+            "    let total = items.iter().sum::<u32>();",
+            "    println!(\"{}\", total);",
+            "}",
+        ];
+        let text = format!(
+            "Some prose before the code. It explains things.\n```rust\n{}\n```\nAnd prose after.",
+            code_lines.join("\n")
+        );
+        let chunks = chunker.chunk(&text, "doc1".to_string());
+
+        for line in code_lines {
+            assert!(
+                chunks.iter().any(|c| c.text.contains(line.trim())),
+                "code line was split: {:?}",
+                line
+            );
+        }
+    }
+
     #[test]
     fn test_text_similarity() {
         let config = CompressionConfig::default();
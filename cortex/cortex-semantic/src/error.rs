@@ -1,5 +1,36 @@
 //! Error types for semantic search.
+//!
+//! Failures that callers commonly need to react to have their own variants
+//! rather than a message string:
+//!
+//! | Variant | Raised when | Retryable |
+//! |---|---|---|
+//! | [`ProviderRateLimited`](SemanticError::ProviderRateLimited) | HTTP 429 from an embedding API, or Qdrant `ResourceExhausted` | yes, after [`retry_after`](SemanticError::retry_after) |
+//! | [`ProviderServerError`](SemanticError::ProviderServerError) | HTTP 5xx from an embedding API | yes |
+//! | [`ProviderUnavailable`](SemanticError::ProviderUnavailable) | nothing listens at an embedding API's endpoint | yes |
+//! | [`ProvidersExhausted`](SemanticError::ProvidersExhausted) | every provider of the fallback chain failed; each failure is kept | if any failure is |
+//! | [`ProvidersCoolingDown`](SemanticError::ProvidersCoolingDown) | every provider of the fallback chain is skipped after recent failures | yes, after [`retry_after`](SemanticError::retry_after) |
+//! | [`ProviderAuth`](SemanticError::ProviderAuth) | HTTP 401/403, or Qdrant `Unauthenticated`/`PermissionDenied` | no |
+//! | [`ProviderQuotaExceeded`](SemanticError::ProviderQuotaExceeded) | the embedding API reports an exhausted billing quota | no |
+//! | [`ModelNotLoaded`](SemanticError::ModelNotLoaded) | an ONNX model or tokenizer file is missing, or Ollama lacks the model | no |
+//! | [`CollectionMissing`](SemanticError::CollectionMissing) | Qdrant `NotFound` for the store's collection | no |
+//! | [`CollectionMismatch`](SemanticError::CollectionMismatch) | an existing collection was created with other parameters than the index configuration | no |
+//! | [`DimensionMismatch`](SemanticError::DimensionMismatch) | a vector does not match the configured dimension | no |
+//! | [`ProviderDimensionMismatch`](SemanticError::ProviderDimensionMismatch) | the only usable fallback provider embeds with a different dimension than the primary | no |
+//! | [`QueryPlanCycle`](SemanticError::QueryPlanCycle) | the dependencies of decomposed sub-queries form a cycle | no |
+//! | [`VectorStoreUnavailable`](SemanticError::VectorStoreUnavailable) | Qdrant is unreachable or timed out; the transport error is the source | yes |
+//!
+//! [`RetryPolicy`](crate::retry::RetryPolicy) retries exactly the retryable
+//! errors. Every variant also has a stable
+//! [`error_code`](SemanticError::error_code) for logs and metrics, which does
+//! not change when messages are reworded.
+//!
+//! `SemanticError` is `Send + Sync + 'static`, so it converts into
+//! `anyhow::Error` through anyhow's blanket `From` impl. Callers that still
+//! propagate `anyhow::Error` can recover the typed error with
+//! `err.downcast_ref::<SemanticError>()`.
 
+use std::time::Duration;
 use thiserror::Error;
 
 pub type Result<T> = std::result::Result<T, SemanticError>;
@@ -21,6 +52,57 @@ pub enum SemanticError {
     #[error("Provider error: {0}")]
     Provider(String),
 
+    #[error("{provider} is not reachable at {endpoint}: {hint}")]
+    ProviderUnavailable {
+        provider: String,
+        endpoint: String,
+        hint: String,
+    },
+
+    #[error("{provider} rate limited the request{}", retry_hint(.retry_after))]
+    ProviderRateLimited {
+        provider: String,
+        retry_after: Option<Duration>,
+    },
+
+    #[error("{provider} returned a server error ({status}): {message}")]
+    ProviderServerError {
+        provider: String,
+        status: u16,
+        message: String,
+    },
+
+    #[error("All embedding providers failed for {operation}: {}", join_failures(.failures))]
+    ProvidersExhausted {
+        operation: String,
+        /// The error of each provider tried, by provider name
+        failures: Vec<(String, SemanticError)>,
+    },
+
+    #[error("All embedding providers are unhealthy; the next is retried in {retry_after:?}")]
+    ProvidersCoolingDown { retry_after: Duration },
+
+    #[error("{provider} rejected the credentials: {message}")]
+    ProviderAuth { provider: String, message: String },
+
+    #[error("{provider} quota exceeded: {message}")]
+    ProviderQuotaExceeded { provider: String, message: String },
+
+    #[error("Collection {name} does not exist")]
+    CollectionMissing { name: String },
+
+    #[error("Collection {name} does not match the index configuration: {}", join_mismatches(.mismatches))]
+    CollectionMismatch {
+        name: String,
+        mismatches: Vec<CollectionParamMismatch>,
+    },
+
+    #[error("Vector store is unavailable: {source}")]
+    VectorStoreUnavailable {
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
     #[error("Configuration error: {0}")]
     Config(String),
 
@@ -42,6 +124,13 @@ pub enum SemanticError {
     #[error("Invalid dimension: expected {expected}, got {got}")]
     DimensionMismatch { expected: usize, got: usize },
 
+    #[error("{provider} produces {got}-dimensional embeddings but the primary provider's collection uses {expected}; it cannot stand in without re-indexing")]
+    ProviderDimensionMismatch {
+        provider: String,
+        expected: usize,
+        got: usize,
+    },
+
     #[error("Document not found: {0}")]
     DocumentNotFound(String),
 
@@ -54,6 +143,25 @@ pub enum SemanticError {
     #[error("Concurrent operation error: {0}")]
     Concurrent(String),
 
+    #[error("Quota exceeded for agent {agent_id}: {requested} {resource} over its limit of {limit} ({current} in use)")]
+    QuotaExceeded {
+        agent_id: String,
+        resource: String,
+        requested: u64,
+        /// Usage before the rejected write
+        current: u64,
+        limit: u64,
+    },
+
+    #[error("Sub-queries {sub_queries:?} depend on each other in a cycle")]
+    QueryPlanCycle { sub_queries: Vec<usize> },
+
+    #[error("Agent not registered: {0}")]
+    AgentNotFound(String),
+
+    #[error("Invalid filter: {0}")]
+    InvalidFilter(String),
+
     #[error("ONNX Runtime error: {0}")]
     OnnxRuntime(String),
 
@@ -70,6 +178,163 @@ pub enum SemanticError {
     Migration(String),
 }
 
+/// A collection parameter that differs from the index configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CollectionParamMismatch {
+    /// Usually an embedding model switch; the collection must be re-embedded
+    Dimension { expected: u64, actual: u64 },
+    Distance { expected: String, actual: String },
+    Quantization { expected: String, actual: String },
+    HnswM { expected: u64, actual: u64 },
+    HnswEfConstruct { expected: u64, actual: u64 },
+    /// The collection stores named vectors instead of a single unnamed one
+    NamedVectors,
+}
+
+impl std::fmt::Display for CollectionParamMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Dimension { expected, actual } => {
+                write!(f, "dimension is {}, expected {}", actual, expected)
+            }
+            Self::Distance { expected, actual } => {
+                write!(f, "distance is {}, expected {}", actual, expected)
+            }
+            Self::Quantization { expected, actual } => {
+                write!(f, "quantization is {}, expected {}", actual, expected)
+            }
+            Self::HnswM { expected, actual } => {
+                write!(f, "HNSW m is {}, expected {}", actual, expected)
+            }
+            Self::HnswEfConstruct { expected, actual } => {
+                write!(f, "HNSW ef_construct is {}, expected {}", actual, expected)
+            }
+            Self::NamedVectors => write!(f, "collection uses named vectors"),
+        }
+    }
+}
+
+fn join_mismatches(mismatches: &[CollectionParamMismatch]) -> String {
+    mismatches
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+fn join_failures(failures: &[(String, SemanticError)]) -> String {
+    failures
+        .iter()
+        .map(|(provider, err)| format!("{}: {}", provider, err))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+fn retry_hint(retry_after: &Option<Duration>) -> String {
+    retry_after
+        .map(|wait| format!(", retry after {:?}", wait))
+        .unwrap_or_default()
+}
+
+impl SemanticError {
+    /// Whether the same operation may succeed if retried later.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            SemanticError::ProviderRateLimited { .. }
+            | SemanticError::ProviderServerError { .. }
+            | SemanticError::ProviderUnavailable { .. }
+            | SemanticError::ProvidersCoolingDown { .. }
+            | SemanticError::VectorStoreUnavailable { .. } => true,
+            SemanticError::ProvidersExhausted { failures, .. } => {
+                failures.iter().any(|(_, err)| err.is_retryable())
+            }
+            SemanticError::Http(e) => {
+                e.is_timeout()
+                    || e.is_connect()
+                    || e.status().is_some_and(|status| {
+                        status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                    })
+            }
+            _ => false,
+        }
+    }
+
+    /// How long the server asked us to wait before retrying, if it said.
+    /// When every provider failed, the shortest wait any of them asked for.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            SemanticError::ProviderRateLimited { retry_after, .. } => *retry_after,
+            SemanticError::ProvidersCoolingDown { retry_after } => Some(*retry_after),
+            SemanticError::ProvidersExhausted { failures, .. } => {
+                failures.iter().filter_map(|(_, err)| err.retry_after()).min()
+            }
+            _ => None,
+        }
+    }
+
+    /// Stable identifier of the kind of error, for logs and metrics.
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            SemanticError::Embedding(_) => "embedding",
+            SemanticError::Index(_) => "index",
+            SemanticError::Search(_) => "search",
+            SemanticError::Query(_) => "query",
+            SemanticError::Provider(_) => "provider",
+            SemanticError::ProviderUnavailable { .. } => "provider.unavailable",
+            SemanticError::ProviderRateLimited { .. } => "provider.rate_limited",
+            SemanticError::ProviderServerError { .. } => "provider.server_error",
+            SemanticError::ProvidersExhausted { .. } => "provider.all_failed",
+            SemanticError::ProvidersCoolingDown { .. } => "provider.all_unhealthy",
+            SemanticError::ProviderAuth { .. } => "provider.auth",
+            SemanticError::ProviderQuotaExceeded { .. } => "provider.quota_exceeded",
+            SemanticError::CollectionMissing { .. } => "collection.missing",
+            SemanticError::CollectionMismatch { .. } => "collection.mismatch",
+            SemanticError::VectorStoreUnavailable { .. } => "vector_store.unavailable",
+            SemanticError::Config(_) => "config",
+            SemanticError::Storage(_) => "storage",
+            SemanticError::Io(_) => "io",
+            SemanticError::Json(_) => "json",
+            SemanticError::Http(_) => "http",
+            SemanticError::Database(_) => "database",
+            SemanticError::DimensionMismatch { .. } => "dimension_mismatch",
+            SemanticError::ProviderDimensionMismatch { .. } => "provider.dimension_mismatch",
+            SemanticError::DocumentNotFound(_) => "document_not_found",
+            SemanticError::ModelNotLoaded(_) => "model_not_loaded",
+            SemanticError::Cache(_) => "cache",
+            SemanticError::Concurrent(_) => "concurrent",
+            SemanticError::QuotaExceeded { .. } => "quota_exceeded",
+            SemanticError::QueryPlanCycle { .. } => "query_plan_cycle",
+            SemanticError::AgentNotFound(_) => "agent_not_found",
+            SemanticError::InvalidFilter(_) => "invalid_filter",
+            SemanticError::OnnxRuntime(_) => "onnx_runtime",
+            SemanticError::Qdrant(_) => "qdrant",
+            SemanticError::VectorStore(_) => "vector_store",
+            SemanticError::Consistency(_) => "consistency",
+            SemanticError::Migration(_) => "migration",
+        }
+    }
+}
+
+/// gRPC status codes Qdrant reports that we classify.
+pub(crate) mod grpc_code {
+    pub const DEADLINE_EXCEEDED: i32 = 4;
+    pub const NOT_FOUND: i32 = 5;
+    pub const PERMISSION_DENIED: i32 = 7;
+    pub const UNAVAILABLE: i32 = 14;
+    pub const UNAUTHENTICATED: i32 = 16;
+}
+
+/// gRPC status code of a Qdrant response error, if it carries one.
+pub(crate) fn qdrant_status_code(err: &qdrant_client::QdrantError) -> Option<i32> {
+    match err {
+        qdrant_client::QdrantError::ResponseError { status }
+        | qdrant_client::QdrantError::ResourceExhaustedError { status, .. } => {
+            Some(status.code() as i32)
+        }
+        _ => None,
+    }
+}
+
 // Implement From<ort::OrtError> for SemanticError
 impl From<ort::OrtError> for SemanticError {
     fn from(err: ort::OrtError) -> Self {
@@ -80,7 +345,32 @@ impl From<ort::OrtError> for SemanticError {
 // Implement From<qdrant_client::QdrantError> for SemanticError
 impl From<qdrant_client::QdrantError> for SemanticError {
     fn from(err: qdrant_client::QdrantError) -> Self {
-        SemanticError::Qdrant(err.to_string())
+        use qdrant_client::QdrantError;
+
+        if let QdrantError::ResourceExhaustedError { retry_after_seconds, .. } = &err {
+            return SemanticError::ProviderRateLimited {
+                provider: "Qdrant".to_string(),
+                retry_after: Some(Duration::from_secs(*retry_after_seconds)),
+            };
+        }
+
+        match qdrant_status_code(&err) {
+            Some(grpc_code::UNAVAILABLE | grpc_code::DEADLINE_EXCEEDED) => {
+                SemanticError::VectorStoreUnavailable { source: Box::new(err) }
+            }
+            Some(grpc_code::UNAUTHENTICATED | grpc_code::PERMISSION_DENIED) => {
+                SemanticError::ProviderAuth {
+                    provider: "Qdrant".to_string(),
+                    message: err.to_string(),
+                }
+            }
+            _ => match err {
+                QdrantError::Reqwest(_) | QdrantError::Io(_) => {
+                    SemanticError::VectorStoreUnavailable { source: Box::new(err) }
+                }
+                err => SemanticError::Qdrant(err.to_string()),
+            },
+        }
     }
 }
 
@@ -90,3 +380,108 @@ impl From<SemanticError> for cortex_core::CortexError {
         cortex_core::CortexError::Semantic(err.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retryable_classification() {
+        let limited = SemanticError::ProviderRateLimited {
+            provider: "OpenAI".to_string(),
+            retry_after: Some(Duration::from_secs(20)),
+        };
+        assert!(limited.is_retryable());
+        assert_eq!(limited.retry_after(), Some(Duration::from_secs(20)));
+        assert_eq!(limited.to_string(), "OpenAI rate limited the request, retry after 20s");
+
+        let unavailable = SemanticError::VectorStoreUnavailable {
+            source: Box::new(std::io::Error::from(std::io::ErrorKind::ConnectionRefused)),
+        };
+        assert!(unavailable.is_retryable());
+        assert!(std::error::Error::source(&unavailable).is_some());
+
+        for permanent in [
+            SemanticError::ProviderAuth {
+                provider: "OpenAI".to_string(),
+                message: "invalid api key".to_string(),
+            },
+            SemanticError::CollectionMissing { name: "code".to_string() },
+            SemanticError::DimensionMismatch { expected: 384, got: 768 },
+        ] {
+            assert!(!permanent.is_retryable());
+            assert_eq!(permanent.retry_after(), None);
+        }
+    }
+
+    #[test]
+    fn test_provider_chain_failures_classified_by_their_causes() {
+        let exhausted = SemanticError::ProvidersExhausted {
+            operation: "embedding".to_string(),
+            failures: vec![
+                (
+                    "openai".to_string(),
+                    SemanticError::ProviderRateLimited {
+                        provider: "OpenAI".to_string(),
+                        retry_after: Some(Duration::from_secs(20)),
+                    },
+                ),
+                (
+                    "ollama".to_string(),
+                    SemanticError::ProviderRateLimited {
+                        provider: "Ollama".to_string(),
+                        retry_after: Some(Duration::from_secs(5)),
+                    },
+                ),
+            ],
+        };
+        assert!(exhausted.is_retryable());
+        assert_eq!(exhausted.retry_after(), Some(Duration::from_secs(5)));
+        assert_eq!(exhausted.error_code(), "provider.all_failed");
+
+        let exhausted = SemanticError::ProvidersExhausted {
+            operation: "embedding".to_string(),
+            failures: vec![(
+                "openai".to_string(),
+                SemanticError::ProviderAuth {
+                    provider: "OpenAI".to_string(),
+                    message: "invalid api key".to_string(),
+                },
+            )],
+        };
+        assert!(!exhausted.is_retryable());
+        assert!(exhausted.to_string().contains("openai: OpenAI rejected the credentials"));
+    }
+
+    #[test]
+    fn test_error_codes_are_stable() {
+        let codes = [
+            (SemanticError::Search("boom".to_string()), "search"),
+            (SemanticError::CollectionMissing { name: "code".to_string() }, "collection.missing"),
+            (
+                SemanticError::ProviderServerError {
+                    provider: "OpenAI".to_string(),
+                    status: 503,
+                    message: "overloaded".to_string(),
+                },
+                "provider.server_error",
+            ),
+            (
+                SemanticError::ProvidersCoolingDown { retry_after: Duration::from_secs(1) },
+                "provider.all_unhealthy",
+            ),
+        ];
+        for (err, code) in codes {
+            assert_eq!(err.error_code(), code);
+        }
+    }
+
+    #[test]
+    fn test_downcast_through_anyhow() {
+        let err: anyhow::Error = SemanticError::CollectionMissing { name: "code".to_string() }.into();
+        assert!(matches!(
+            err.downcast_ref::<SemanticError>(),
+            Some(SemanticError::CollectionMissing { name }) if name == "code"
+        ));
+    }
+}
@@ -11,8 +11,16 @@
 //! - "Offline Evaluation of Recommendation Functions" (Shani & Gunawardana, 2011)
 //! - "A Short Introduction to Learning to Rank" (Li, 2011)
 
+use crate::error::{Result, SemanticError};
+use crate::ranking::{FeedbackEvent, PersonalizedRanker, RankableDocument, RankingStrategy};
+use crate::search::SemanticSearchEngine;
+use crate::types::EmbeddingModel;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::io::Write;
+use std::path::PathBuf;
+use tracing::warn;
 
 /// A single evaluation result for a query.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -62,6 +70,18 @@ pub struct AggregatedMetrics {
     pub num_queries: usize,
 }
 
+/// NDCG with and without personalization over a replayed feedback log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersonalizationUplift {
+    pub k: usize,
+    /// Positive feedback events evaluated
+    pub num_events: usize,
+    pub baseline_ndcg: f64,
+    pub personalized_ndcg: f64,
+    /// `personalized_ndcg - baseline_ndcg`
+    pub uplift: f64,
+}
+
 /// Metric evaluator for search results.
 ///
 /// # Example
@@ -314,6 +334,64 @@ impl MetricEvaluator {
         ranked
     }
 
+    /// Measure NDCG@K uplift from personalization on a feedback log.
+    ///
+    /// Events are replayed in order through `ranker`. For each positive event,
+    /// the candidates returned by `candidates` are ranked with and without the
+    /// agent's profile, with the event's result as the relevant document,
+    /// before the ranker learns from the event. Pass a fresh ranker so the
+    /// profiles only reflect the replayed log.
+    pub fn personalization_uplift<F>(
+        &self,
+        ranker: &PersonalizedRanker,
+        log: &[FeedbackEvent],
+        candidates: F,
+        k: usize,
+    ) -> PersonalizationUplift
+    where
+        F: Fn(&FeedbackEvent) -> Vec<RankableDocument>,
+    {
+        let mut baseline_total = 0.0;
+        let mut personalized_total = 0.0;
+        let mut num_events = 0;
+
+        for event in log {
+            let documents = candidates(event);
+            let baseline: Vec<String> = documents.iter().map(|doc| doc.id.clone()).collect();
+            let personalized: Vec<String> = ranker
+                .rerank_for_agent(&event.agent_id, documents)
+                .into_iter()
+                .map(|doc| doc.id)
+                .collect();
+
+            if event.kind.is_positive() {
+                let relevant: HashSet<String> = [event.result_id.clone()].into_iter().collect();
+                baseline_total += self.ndcg_at_k(&baseline, &relevant, None, k);
+                personalized_total += self.ndcg_at_k(&personalized, &relevant, None, k);
+                num_events += 1;
+            }
+
+            ranker.record_feedback(&event.agent_id, &event.query, &event.result_id, event.kind);
+        }
+
+        let (baseline_ndcg, personalized_ndcg) = if num_events == 0 {
+            (0.0, 0.0)
+        } else {
+            (
+                baseline_total / num_events as f64,
+                personalized_total / num_events as f64,
+            )
+        };
+
+        PersonalizationUplift {
+            k,
+            num_events,
+            baseline_ndcg,
+            personalized_ndcg,
+            uplift: personalized_ndcg - baseline_ndcg,
+        }
+    }
+
     /// Aggregate metrics across multiple queries.
     ///
     /// Calculates mean metrics across all query evaluations.
@@ -382,23 +460,157 @@ impl Default for MetricEvaluator {
 }
 
 /// Time-series metrics tracker for monitoring performance over time.
+///
+/// Data points live in memory; `save` persists the latest one as a labeled
+/// run in an [`EvalRunStore`] so runs can be compared across sessions.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MetricsTimeSeries {
     /// Timestamp -> Aggregated metrics
     pub data_points: Vec<(chrono::DateTime<chrono::Utc>, AggregatedMetrics)>,
+    /// Settings the metrics were produced with
+    #[serde(default)]
+    pub fingerprint: Option<ConfigFingerprint>,
+    /// Per-query metrics of the latest data point, if recorded
+    #[serde(default)]
+    pub latest_per_query: HashMap<String, Metrics>,
 }
 
 impl MetricsTimeSeries {
     pub fn new() -> Self {
         Self {
             data_points: Vec::new(),
+            fingerprint: None,
+            latest_per_query: HashMap::new(),
         }
     }
 
+    pub fn with_fingerprint(mut self, fingerprint: ConfigFingerprint) -> Self {
+        self.fingerprint = Some(fingerprint);
+        self
+    }
+
     /// Add a new data point with current timestamp.
     pub fn add(&mut self, metrics: AggregatedMetrics) {
+        self.add_with_queries(metrics, HashMap::new());
+    }
+
+    /// Add a new data point along with the metrics of each query, keyed by
+    /// query ID, so comparisons can tell which queries regressed.
+    pub fn add_with_queries(&mut self, metrics: AggregatedMetrics, per_query: HashMap<String, Metrics>) {
         let timestamp = chrono::Utc::now();
         self.data_points.push((timestamp, metrics));
+        self.latest_per_query = per_query;
+    }
+
+    /// Persist the latest data point as a run named `run_label`.
+    pub fn save(&self, storage: &dyn EvalRunStore, run_label: &str) -> Result<EvalRun> {
+        let (timestamp, metrics) = self.data_points.last().ok_or_else(|| {
+            SemanticError::Config("No evaluation metrics recorded to save".to_string())
+        })?;
+        let run = EvalRun {
+            label: run_label.to_string(),
+            timestamp: *timestamp,
+            fingerprint: self.fingerprint.clone(),
+            metrics: metrics.clone(),
+            per_query: self.latest_per_query.clone(),
+        };
+        storage.append(&run)?;
+        Ok(run)
+    }
+
+    /// The last `last_n` persisted runs, oldest first.
+    pub fn load_history(storage: &dyn EvalRunStore, last_n: usize) -> Result<Vec<EvalRun>> {
+        let mut runs = storage.load_all()?;
+        runs.sort_by_key(|run| run.timestamp);
+        let skip = runs.len().saturating_sub(last_n);
+        Ok(runs.split_off(skip))
+    }
+
+    /// A series over persisted runs, e.g. to compute trends.
+    pub fn from_runs(runs: &[EvalRun]) -> Self {
+        Self {
+            data_points: runs
+                .iter()
+                .map(|run| (run.timestamp, run.metrics.clone()))
+                .collect(),
+            fingerprint: runs.last().and_then(|run| run.fingerprint.clone()),
+            latest_per_query: runs.last().map(|run| run.per_query.clone()).unwrap_or_default(),
+        }
+    }
+
+    /// Compare two runs, flagging metrics that dropped by more than
+    /// `max_drop`. Regressed metrics are broken down per query when both
+    /// runs recorded per-query metrics.
+    pub fn compare(baseline: &EvalRun, candidate: &EvalRun, max_drop: f64) -> RegressionReport {
+        let warnings = match (&baseline.fingerprint, &candidate.fingerprint) {
+            (Some(a), Some(b)) => a.differences(b),
+            _ => vec!["config fingerprint missing".to_string()],
+        };
+        for warning in &warnings {
+            warn!(
+                "Comparing {} with {} may not be apples-to-apples: {}",
+                baseline.label, candidate.label, warning
+            );
+        }
+
+        let baseline_values = baseline.metrics.named_values();
+        let candidate_values = candidate.metrics.named_values();
+        let metrics: Vec<MetricDelta> = baseline_values
+            .iter()
+            .filter_map(|(metric, before)| {
+                let after = *candidate_values.get(metric)?;
+                Some(MetricDelta {
+                    metric: metric.clone(),
+                    baseline: *before,
+                    candidate: after,
+                    delta: after - before,
+                    regressed: before - after > max_drop,
+                })
+            })
+            .collect();
+
+        let regressed: HashSet<&str> = metrics
+            .iter()
+            .filter(|m| m.regressed)
+            .map(|m| m.metric.as_str())
+            .collect();
+        let mut query_regressions = Vec::new();
+        for (query_id, before) in &baseline.per_query {
+            let Some(after) = candidate.per_query.get(query_id) else {
+                continue;
+            };
+            let after = after.named_values();
+            for (metric, before) in before.named_values() {
+                if !regressed.contains(metric.as_str()) {
+                    continue;
+                }
+                if let Some(after) = after.get(&metric) {
+                    if before - after > max_drop {
+                        query_regressions.push(QueryRegression {
+                            query_id: query_id.clone(),
+                            metric,
+                            baseline: before,
+                            candidate: *after,
+                        });
+                    }
+                }
+            }
+        }
+        query_regressions.sort_by(|a, b| {
+            (b.baseline - b.candidate)
+                .total_cmp(&(a.baseline - a.candidate))
+                .then_with(|| a.query_id.cmp(&b.query_id))
+                .then_with(|| a.metric.cmp(&b.metric))
+        });
+
+        RegressionReport {
+            baseline_label: baseline.label.clone(),
+            candidate_label: candidate.label.clone(),
+            max_drop,
+            metrics,
+            query_regressions,
+            warnings,
+        }
     }
 
     /// Get metrics for a specific time range.
@@ -454,6 +666,727 @@ impl Default for MetricsTimeSeries {
     }
 }
 
+impl Metrics {
+    /// Metric values by name, e.g. `mrr` or `ndcg@10`.
+    pub fn named_values(&self) -> BTreeMap<String, f64> {
+        let mut values = BTreeMap::new();
+        values.insert("mrr".to_string(), self.mrr);
+        values.insert("map".to_string(), self.average_precision);
+        insert_at_k(&mut values, "precision", &self.precision_at_k);
+        insert_at_k(&mut values, "recall", &self.recall_at_k);
+        insert_at_k(&mut values, "f1", &self.f1_at_k);
+        insert_at_k(&mut values, "ndcg", &self.ndcg_at_k);
+        values
+    }
+}
+
+impl AggregatedMetrics {
+    /// Mean metric values by name, named like [`Metrics::named_values`].
+    pub fn named_values(&self) -> BTreeMap<String, f64> {
+        let mut values = BTreeMap::new();
+        values.insert("mrr".to_string(), self.mean_reciprocal_rank);
+        values.insert("map".to_string(), self.mean_average_precision);
+        insert_at_k(&mut values, "precision", &self.mean_precision_at_k);
+        insert_at_k(&mut values, "recall", &self.mean_recall_at_k);
+        insert_at_k(&mut values, "f1", &self.mean_f1_at_k);
+        insert_at_k(&mut values, "ndcg", &self.mean_ndcg_at_k);
+        values
+    }
+}
+
+fn insert_at_k(values: &mut BTreeMap<String, f64>, name: &str, at_k: &HashMap<usize, f64>) {
+    for (k, value) in at_k {
+        values.insert(format!("{}@{}", name, k), *value);
+    }
+}
+
+/// Settings an evaluation run depends on; runs are only directly
+/// comparable when these match.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigFingerprint {
+    pub embedding_model: EmbeddingModel,
+    pub ranking_strategy: RankingStrategy,
+    pub k_values: Vec<usize>,
+}
+
+impl ConfigFingerprint {
+    pub fn new(
+        embedding_model: EmbeddingModel,
+        ranking_strategy: RankingStrategy,
+        k_values: &[usize],
+    ) -> Self {
+        let mut k_values = k_values.to_vec();
+        k_values.sort_unstable();
+        k_values.dedup();
+        Self {
+            embedding_model,
+            ranking_strategy,
+            k_values,
+        }
+    }
+
+    /// Human-readable differences from `other`, empty when they match.
+    pub fn differences(&self, other: &ConfigFingerprint) -> Vec<String> {
+        let mut differences = Vec::new();
+        if self.embedding_model != other.embedding_model {
+            differences.push(format!(
+                "embedding model differs ({}/{} [{}] vs {}/{} [{}])",
+                self.embedding_model.provider,
+                self.embedding_model.model_name,
+                self.embedding_model.dimension,
+                other.embedding_model.provider,
+                other.embedding_model.model_name,
+                other.embedding_model.dimension
+            ));
+        }
+        if self.ranking_strategy != other.ranking_strategy {
+            differences.push(format!(
+                "ranking strategy differs ({:?} vs {:?})",
+                self.ranking_strategy, other.ranking_strategy
+            ));
+        }
+        if self.k_values != other.k_values {
+            differences.push(format!(
+                "k values differ ({:?} vs {:?})",
+                self.k_values, other.k_values
+            ));
+        }
+        differences
+    }
+}
+
+/// A persisted evaluation run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalRun {
+    pub label: String,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub fingerprint: Option<ConfigFingerprint>,
+    pub metrics: AggregatedMetrics,
+    /// Metrics of each query by query ID, when recorded
+    #[serde(default)]
+    pub per_query: HashMap<String, Metrics>,
+}
+
+/// Storage for evaluation runs.
+pub trait EvalRunStore: Send + Sync {
+    /// Append a run.
+    fn append(&self, run: &EvalRun) -> Result<()>;
+
+    /// All runs, oldest first.
+    fn load_all(&self) -> Result<Vec<EvalRun>>;
+
+    /// The most recent run with `label`.
+    fn find(&self, label: &str) -> Result<Option<EvalRun>> {
+        Ok(self.load_all()?.into_iter().rev().find(|run| run.label == label))
+    }
+}
+
+/// Runs stored as JSON lines in a file, one run per line.
+#[derive(Debug, Clone)]
+pub struct FileRunStore {
+    path: PathBuf,
+}
+
+impl FileRunStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl EvalRunStore for FileRunStore {
+    fn append(&self, run: &EvalRun) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(run)?)?;
+        Ok(())
+    }
+
+    fn load_all(&self) -> Result<Vec<EvalRun>> {
+        let content = match std::fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| serde_json::from_str(line).map_err(SemanticError::from))
+            .collect()
+    }
+}
+
+/// Runs kept in memory, for tests and one-off comparisons.
+#[derive(Debug, Default)]
+pub struct MemoryRunStore {
+    runs: parking_lot::Mutex<Vec<EvalRun>>,
+}
+
+impl EvalRunStore for MemoryRunStore {
+    fn append(&self, run: &EvalRun) -> Result<()> {
+        self.runs.lock().push(run.clone());
+        Ok(())
+    }
+
+    fn load_all(&self) -> Result<Vec<EvalRun>> {
+        Ok(self.runs.lock().clone())
+    }
+}
+
+/// Change of one metric between two runs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricDelta {
+    pub metric: String,
+    pub baseline: f64,
+    pub candidate: f64,
+    /// `candidate - baseline`
+    pub delta: f64,
+    /// Dropped by more than the report's `max_drop`
+    pub regressed: bool,
+}
+
+/// A metric that dropped for one query.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueryRegression {
+    pub query_id: String,
+    pub metric: String,
+    pub baseline: f64,
+    pub candidate: f64,
+}
+
+/// Comparison of a candidate run against a baseline.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegressionReport {
+    pub baseline_label: String,
+    pub candidate_label: String,
+    /// Largest drop tolerated before a metric counts as regressed
+    pub max_drop: f64,
+    /// Metrics present in both runs, by name
+    pub metrics: Vec<MetricDelta>,
+    /// Per-query drops of the regressed metrics, worst first
+    pub query_regressions: Vec<QueryRegression>,
+    /// Why the runs may not be comparable
+    pub warnings: Vec<String>,
+}
+
+impl RegressionReport {
+    pub fn has_regressions(&self) -> bool {
+        self.metrics.iter().any(|m| m.regressed)
+    }
+
+    pub fn regressions(&self) -> impl Iterator<Item = &MetricDelta> {
+        self.metrics.iter().filter(|m| m.regressed)
+    }
+}
+
+impl fmt::Display for RegressionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Regression report: {} -> {} (max drop {:.4})",
+            self.baseline_label, self.candidate_label, self.max_drop
+        )?;
+        for warning in &self.warnings {
+            writeln!(f, "warning: {}", warning)?;
+        }
+        writeln!(
+            f,
+            "{:<16} {:>10} {:>10} {:>10}",
+            "metric", "baseline", "candidate", "delta"
+        )?;
+        for m in &self.metrics {
+            writeln!(
+                f,
+                "{:<16} {:>10.4} {:>10.4} {:>+10.4}{}",
+                m.metric,
+                m.baseline,
+                m.candidate,
+                m.delta,
+                if m.regressed { "  REGRESSED" } else { "" }
+            )?;
+        }
+        if !self.query_regressions.is_empty() {
+            writeln!(f, "Per-query regressions:")?;
+            for q in &self.query_regressions {
+                writeln!(
+                    f,
+                    "  {:<20} {:<16} {:.4} -> {:.4}",
+                    q.query_id, q.metric, q.baseline, q.candidate
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A query with graded relevance judgments.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LabeledQuery {
+    pub query_id: String,
+    pub query: String,
+    /// Relevance grade of each judged document; 0 means not relevant
+    #[serde(default)]
+    pub relevance: BTreeMap<String, u32>,
+}
+
+impl LabeledQuery {
+    /// The query's results scored against its judgments.
+    fn evaluation(&self, retrieved: Vec<String>) -> QueryEvaluation {
+        let relevance_scores: HashMap<String, u32> = self
+            .relevance
+            .iter()
+            .filter(|(_, grade)| **grade > 0)
+            .map(|(doc_id, grade)| (doc_id.clone(), *grade))
+            .collect();
+        QueryEvaluation {
+            query_id: self.query_id.clone(),
+            retrieved,
+            relevant: relevance_scores.keys().cloned().collect(),
+            relevance_scores: Some(relevance_scores),
+        }
+    }
+}
+
+/// A set of labeled queries, loaded from a relevance judgment file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QuerySet {
+    pub queries: Vec<LabeledQuery>,
+}
+
+impl QuerySet {
+    /// Load judgments from a `.tsv` file, see [`from_tsv`](Self::from_tsv),
+    /// or otherwise a JSON array of [`LabeledQuery`].
+    pub fn load(path: &std::path::Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let is_tsv = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("tsv"));
+        if is_tsv {
+            Self::from_tsv(&content)
+        } else {
+            Self::from_json(&content)
+        }
+    }
+
+    pub fn from_json(content: &str) -> Result<Self> {
+        Ok(Self {
+            queries: serde_json::from_str(content)?,
+        })
+    }
+
+    /// Parse lines of `query_id<TAB>query<TAB>doc_id<TAB>grade`, one per
+    /// judged document. The grade defaults to 1; a line without a document
+    /// adds a query with no relevant documents. Blank lines, `#` comments
+    /// and a `query_id` header are skipped.
+    pub fn from_tsv(content: &str) -> Result<Self> {
+        let mut queries: Vec<LabeledQuery> = Vec::new();
+        for (number, line) in content.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split('\t').map(str::trim).collect();
+            if fields[0] == "query_id" {
+                continue;
+            }
+            let invalid = |reason: &str| {
+                SemanticError::Config(format!("Judgment line {}: {}", number + 1, reason))
+            };
+            if fields.len() < 2 || fields[0].is_empty() {
+                return Err(invalid("expected query_id, query, doc_id and grade"));
+            }
+
+            let position = match queries.iter().position(|q| q.query_id == fields[0]) {
+                Some(position) => position,
+                None => {
+                    queries.push(LabeledQuery {
+                        query_id: fields[0].to_string(),
+                        query: fields[1].to_string(),
+                        relevance: BTreeMap::new(),
+                    });
+                    queries.len() - 1
+                }
+            };
+            if queries[position].query != fields[1] {
+                return Err(invalid("query text differs from an earlier line of the same query"));
+            }
+
+            let Some(doc_id) = fields.get(2).filter(|doc_id| !doc_id.is_empty()) else {
+                continue;
+            };
+            let grade = match fields.get(3).filter(|grade| !grade.is_empty()) {
+                Some(grade) => grade
+                    .parse()
+                    .map_err(|_| invalid(&format!("invalid grade '{}'", grade)))?,
+                None => 1,
+            };
+            queries[position].relevance.insert(doc_id.to_string(), grade);
+        }
+        Ok(Self { queries })
+    }
+
+    pub fn len(&self) -> usize {
+        self.queries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queries.is_empty()
+    }
+}
+
+/// Runs a labeled query set against a search engine.
+///
+/// Each query is searched with the largest configured `k` as the limit and
+/// scored against its judgments.
+#[derive(Debug, Clone)]
+pub struct EvalHarness {
+    k_values: Vec<usize>,
+}
+
+impl Default for EvalHarness {
+    fn default() -> Self {
+        Self::new(&[1, 5, 10])
+    }
+}
+
+impl EvalHarness {
+    pub fn new(k_values: &[usize]) -> Self {
+        let mut k_values = k_values.to_vec();
+        k_values.retain(|k| *k > 0);
+        k_values.sort_unstable();
+        k_values.dedup();
+        Self { k_values }
+    }
+
+    pub fn k_values(&self) -> &[usize] {
+        &self.k_values
+    }
+
+    /// Search every query with `engine` and score the results. A failed
+    /// search fails the run, since skipping the query would skew the means.
+    pub async fn run(
+        &self,
+        label: &str,
+        engine: &SemanticSearchEngine,
+        queries: &QuerySet,
+    ) -> Result<EvalReport> {
+        let evaluator = MetricEvaluator::new();
+        let limit = self.k_values.last().copied().unwrap_or(10);
+
+        let mut per_query = Vec::with_capacity(queries.len());
+        for labeled in &queries.queries {
+            let results = engine.search(&labeled.query, limit).await.map_err(|e| {
+                SemanticError::Search(format!("Query {} failed: {}", labeled.query_id, e))
+            })?;
+            let retrieved: Vec<String> = results.into_iter().map(|result| result.id).collect();
+            let metrics = evaluator.evaluate(&labeled.evaluation(retrieved.clone()), &self.k_values);
+            per_query.push(QueryReport {
+                query_id: labeled.query_id.clone(),
+                query: labeled.query.clone(),
+                retrieved,
+                metrics,
+            });
+        }
+
+        let metrics: Vec<Metrics> = per_query.iter().map(|q| q.metrics.clone()).collect();
+        Ok(EvalReport {
+            label: label.to_string(),
+            fingerprint: ConfigFingerprint::new(
+                engine.embedding_model(),
+                engine.ranking_strategy(),
+                &self.k_values,
+            ),
+            metrics: evaluator.aggregate(&metrics),
+            per_query,
+        })
+    }
+
+    /// Run the query set against two engines and compare them metric by
+    /// metric.
+    pub async fn compare(
+        &self,
+        queries: &QuerySet,
+        baseline: (&str, &SemanticSearchEngine),
+        candidate: (&str, &SemanticSearchEngine),
+    ) -> Result<ComparisonReport> {
+        let baseline = self.run(baseline.0, baseline.1, queries).await?;
+        let candidate = self.run(candidate.0, candidate.1, queries).await?;
+        Ok(ComparisonReport::new(&baseline, &candidate))
+    }
+}
+
+/// Results and metrics of one query in an [`EvalReport`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryReport {
+    pub query_id: String,
+    pub query: String,
+    /// Document IDs returned, best first
+    pub retrieved: Vec<String>,
+    pub metrics: Metrics,
+}
+
+/// Metrics of a query set run against one engine configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalReport {
+    pub label: String,
+    pub fingerprint: ConfigFingerprint,
+    pub metrics: AggregatedMetrics,
+    pub per_query: Vec<QueryReport>,
+}
+
+impl EvalReport {
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// The report as a run for an [`EvalRunStore`], to track it over time
+    /// with [`MetricsTimeSeries`].
+    pub fn to_run(&self) -> EvalRun {
+        EvalRun {
+            label: self.label.clone(),
+            timestamp: chrono::Utc::now(),
+            fingerprint: Some(self.fingerprint.clone()),
+            metrics: self.metrics.clone(),
+            per_query: self
+                .per_query
+                .iter()
+                .map(|q| (q.query_id.clone(), q.metrics.clone()))
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for EvalReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let model = &self.fingerprint.embedding_model;
+        writeln!(
+            f,
+            "Evaluation {}: {} queries, {}/{} [{}], {:?} ranking",
+            self.label,
+            self.metrics.num_queries,
+            model.provider,
+            model.model_name,
+            model.dimension,
+            self.fingerprint.ranking_strategy
+        )?;
+        writeln!(f, "{:<16} {:>10}", "metric", "mean")?;
+        for (metric, value) in self.metrics.named_values() {
+            writeln!(f, "{:<16} {:>10.4}", metric, value)?;
+        }
+
+        let Some(k) = self.fingerprint.k_values.last() else {
+            return Ok(());
+        };
+        writeln!(f, "Per query:")?;
+        writeln!(
+            f,
+            "  {:<20} {:>8} {:>8} {:>10} {:>10}",
+            "query_id",
+            "mrr",
+            "map",
+            format!("ndcg@{}", k),
+            format!("recall@{}", k)
+        )?;
+        for q in &self.per_query {
+            writeln!(
+                f,
+                "  {:<20} {:>8.4} {:>8.4} {:>10.4} {:>10.4}",
+                q.query_id,
+                q.metrics.mrr,
+                q.metrics.average_precision,
+                q.metrics.ndcg_at_k.get(k).copied().unwrap_or_default(),
+                q.metrics.recall_at_k.get(k).copied().unwrap_or_default()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// How confidently a metric differs between two configurations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Significance {
+    /// p >= 0.05, or too few queries differ to tell
+    NotSignificant,
+    /// p < 0.05
+    Significant,
+    /// p < 0.01
+    HighlySignificant,
+}
+
+impl Significance {
+    fn from_p_value(p_value: f64) -> Self {
+        if p_value < 0.01 {
+            Self::HighlySignificant
+        } else if p_value < 0.05 {
+            Self::Significant
+        } else {
+            Self::NotSignificant
+        }
+    }
+
+    /// `*` for p < 0.05, `**` for p < 0.01.
+    pub fn marker(&self) -> &'static str {
+        match self {
+            Self::NotSignificant => "",
+            Self::Significant => "*",
+            Self::HighlySignificant => "**",
+        }
+    }
+}
+
+/// Difference of one metric between two configurations, with a paired
+/// sign test over the queries both evaluated.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComparedMetric {
+    pub metric: String,
+    pub baseline: f64,
+    pub candidate: f64,
+    /// `candidate - baseline`
+    pub delta: f64,
+    /// Queries where the candidate scored higher
+    pub wins: usize,
+    /// Queries where the candidate scored lower
+    pub losses: usize,
+    /// Two-sided sign test p-value; ties are left out
+    pub p_value: f64,
+    pub significance: Significance,
+}
+
+/// Side-by-side metrics of two engine configurations on the same queries.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComparisonReport {
+    pub baseline_label: String,
+    pub candidate_label: String,
+    pub num_queries: usize,
+    pub metrics: Vec<ComparedMetric>,
+    /// Settings that differ between the configurations
+    pub differences: Vec<String>,
+}
+
+impl ComparisonReport {
+    /// Compare two reports over the queries they share.
+    pub fn new(baseline: &EvalReport, candidate: &EvalReport) -> Self {
+        let candidate_queries: HashMap<&str, BTreeMap<String, f64>> = candidate
+            .per_query
+            .iter()
+            .map(|q| (q.query_id.as_str(), q.metrics.named_values()))
+            .collect();
+        let paired: Vec<(BTreeMap<String, f64>, &BTreeMap<String, f64>)> = baseline
+            .per_query
+            .iter()
+            .filter_map(|q| {
+                let after = candidate_queries.get(q.query_id.as_str())?;
+                Some((q.metrics.named_values(), after))
+            })
+            .collect();
+
+        let candidate_values = candidate.metrics.named_values();
+        let metrics = baseline
+            .metrics
+            .named_values()
+            .into_iter()
+            .filter_map(|(metric, before)| {
+                let after = *candidate_values.get(&metric)?;
+                let (mut wins, mut losses) = (0, 0);
+                for (query_before, query_after) in &paired {
+                    let (Some(b), Some(a)) = (query_before.get(&metric), query_after.get(&metric))
+                    else {
+                        continue;
+                    };
+                    if a > b {
+                        wins += 1;
+                    } else if a < b {
+                        losses += 1;
+                    }
+                }
+                let p_value = sign_test(wins, losses);
+                Some(ComparedMetric {
+                    metric,
+                    baseline: before,
+                    candidate: after,
+                    delta: after - before,
+                    wins,
+                    losses,
+                    p_value,
+                    significance: Significance::from_p_value(p_value),
+                })
+            })
+            .collect();
+
+        Self {
+            baseline_label: baseline.label.clone(),
+            candidate_label: candidate.label.clone(),
+            num_queries: paired.len(),
+            metrics,
+            differences: baseline.fingerprint.differences(&candidate.fingerprint),
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+impl fmt::Display for ComparisonReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Comparison: {} vs {} ({} queries)",
+            self.baseline_label, self.candidate_label, self.num_queries
+        )?;
+        for difference in &self.differences {
+            writeln!(f, "config: {}", difference)?;
+        }
+        writeln!(
+            f,
+            "{:<16} {:>10} {:>10} {:>10} {:>9} {:>8}",
+            "metric", "baseline", "candidate", "delta", "win/loss", "p"
+        )?;
+        for m in &self.metrics {
+            writeln!(
+                f,
+                "{:<16} {:>10.4} {:>10.4} {:>+10.4} {:>9} {:>8.4} {}",
+                m.metric,
+                m.baseline,
+                m.candidate,
+                m.delta,
+                format!("{}/{}", m.wins, m.losses),
+                m.p_value,
+                m.significance.marker()
+            )?;
+        }
+        write!(f, "* p < 0.05, ** p < 0.01 (two-sided sign test over queries)")
+    }
+}
+
+/// Two-sided sign test p-value for `wins` against `losses`.
+fn sign_test(wins: usize, losses: usize) -> f64 {
+    let n = wins + losses;
+    if n == 0 {
+        return 1.0;
+    }
+
+    // P(X <= min(wins, losses)) for X ~ Binomial(n, 0.5), summed in log
+    // space so large query sets don't underflow
+    let tail = wins.min(losses);
+    let ln_half_n = n as f64 * 0.5_f64.ln();
+    let mut ln_choose = 0.0;
+    let mut cumulative = 0.0;
+    for i in 0..=tail {
+        if i > 0 {
+            ln_choose += ((n - i + 1) as f64).ln() - (i as f64).ln();
+        }
+        cumulative += (ln_choose + ln_half_n).exp();
+    }
+    (2.0 * cumulative).min(1.0)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -644,4 +1577,278 @@ mod tests {
 
         assert_eq!(ts.data_points.len(), 2);
     }
+
+    fn record_run(
+        evaluator: &MetricEvaluator,
+        evals: &[QueryEvaluation],
+        fingerprint: ConfigFingerprint,
+    ) -> MetricsTimeSeries {
+        let per_query: HashMap<String, Metrics> = evals
+            .iter()
+            .map(|e| (e.query_id.clone(), evaluator.evaluate(e, &fingerprint.k_values)))
+            .collect();
+        let metrics: Vec<Metrics> = per_query.values().cloned().collect();
+        let mut series = MetricsTimeSeries::new().with_fingerprint(fingerprint);
+        series.add_with_queries(evaluator.aggregate(&metrics), per_query);
+        series
+    }
+
+    fn fingerprint() -> ConfigFingerprint {
+        ConfigFingerprint::new(EmbeddingModel::openai_small(), RankingStrategy::Hybrid, &[5, 1])
+    }
+
+    #[test]
+    fn test_eval_runs_persist_and_compare() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileRunStore::new(dir.path().join("runs").join("eval.jsonl"));
+        let evaluator = MetricEvaluator::new();
+
+        let good = create_test_eval();
+        let mut other = create_test_eval();
+        other.query_id = "q2".to_string();
+        record_run(&evaluator, &[good.clone(), other.clone()], fingerprint())
+            .save(&store, "week-41")
+            .unwrap();
+
+        // q1 loses its top hit in the candidate run
+        let mut worse = good;
+        worse.retrieved.rotate_left(1);
+        record_run(&evaluator, &[worse, other], fingerprint())
+            .save(&store, "week-42")
+            .unwrap();
+
+        let history = MetricsTimeSeries::load_history(&store, 2).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(MetricsTimeSeries::load_history(&store, 1).unwrap()[0].label, "week-42");
+
+        let report = MetricsTimeSeries::compare(&history[0], &history[1], 0.05);
+        assert!(report.warnings.is_empty());
+        assert!(report.has_regressions());
+        assert!(report.regressions().any(|m| m.metric == "mrr"));
+        assert!(report.query_regressions.iter().all(|q| q.query_id == "q1"));
+        assert!(report.query_regressions.iter().any(|q| q.metric == "precision@1"));
+
+        let text = report.to_string();
+        assert!(text.contains("week-41 -> week-42"));
+        assert!(text.contains("REGRESSED"));
+
+        // Comparing a run with itself flags nothing
+        assert!(!MetricsTimeSeries::compare(&history[1], &history[1], 0.0).has_regressions());
+    }
+
+    #[test]
+    fn test_compare_warns_on_fingerprint_mismatch() {
+        let store = MemoryRunStore::default();
+        let evaluator = MetricEvaluator::new();
+        let evals = [create_test_eval()];
+
+        let baseline = record_run(&evaluator, &evals, fingerprint()).save(&store, "a").unwrap();
+        let other = ConfigFingerprint::new(
+            EmbeddingModel::new("ollama", "nomic-embed-text", 768),
+            RankingStrategy::Hybrid,
+            &[1, 5],
+        );
+        let candidate = record_run(&evaluator, &evals, other).save(&store, "b").unwrap();
+
+        let report = MetricsTimeSeries::compare(&baseline, &candidate, 0.01);
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].contains("embedding model"));
+        assert!(report.to_string().contains("warning: embedding model"));
+        assert_eq!(store.find("b").unwrap().unwrap().label, "b");
+        assert!(MetricsTimeSeries::new().save(&store, "empty").is_err());
+    }
+
+    #[test]
+    fn test_personalization_uplift() {
+        use crate::ranking::{FeedbackKind, PersonalizationConfig};
+
+        let evaluator = MetricEvaluator::new();
+        let ranker = PersonalizedRanker::new(PersonalizationConfig::default());
+
+        let doc = |id: &str, language: &str, score: f32| RankableDocument {
+            id: id.to_string(),
+            content: String::new(),
+            semantic_score: score,
+            metadata: [("language".to_string(), language.to_string())].into_iter().collect(),
+            embedding: None,
+        };
+        // The agent keeps using the Rust result ranked second
+        let log: Vec<FeedbackEvent> = (0..10)
+            .map(|i| FeedbackEvent {
+                agent_id: "agent1".to_string(),
+                query: format!("query {}", i),
+                result_id: format!("rs{}", i),
+                kind: FeedbackKind::Used,
+                timestamp: chrono::Utc::now(),
+            })
+            .collect();
+
+        let uplift = evaluator.personalization_uplift(
+            &ranker,
+            &log,
+            |event| {
+                let i = event.query.trim_start_matches("query ");
+                vec![
+                    doc(&format!("py{}", i), "python", 0.8),
+                    doc(&format!("rs{}", i), "rust", 0.78),
+                ]
+            },
+            1,
+        );
+
+        assert_eq!(uplift.num_events, 10);
+        assert_eq!(uplift.baseline_ndcg, 0.0);
+        assert!(uplift.personalized_ndcg > 0.5);
+        assert!(uplift.uplift > 0.0);
+    }
+
+    #[test]
+    fn test_query_set_from_tsv() {
+        let tsv = "query_id\tquery\tdoc_id\tgrade\n\
+                   # judged by hand\n\
+                   q1\tvector search\tdoc1\t2\n\
+                   q1\tvector search\tdoc2\n\
+                   q1\tvector search\tdoc3\t0\n\
+                   \n\
+                   q2\tunjudged query\n";
+        let set = QuerySet::from_tsv(tsv).unwrap();
+        assert_eq!(set.len(), 2);
+        assert_eq!(set.queries[0].query, "vector search");
+        assert_eq!(set.queries[0].relevance.get("doc1"), Some(&2));
+        assert_eq!(set.queries[0].relevance.get("doc2"), Some(&1));
+        assert!(set.queries[1].relevance.is_empty());
+
+        // Zero grades are judged but not relevant
+        let eval = set.queries[0].evaluation(vec!["doc3".to_string()]);
+        assert_eq!(eval.relevant.len(), 2);
+        assert!(!eval.relevant.contains("doc3"));
+
+        assert!(QuerySet::from_tsv("q1\tfirst\tdoc1\t1\nq1\tsecond\tdoc2\t1").is_err());
+        assert!(QuerySet::from_tsv("q1\tquery\tdoc1\thigh").is_err());
+    }
+
+    #[test]
+    fn test_query_set_load_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("judgments.json");
+        std::fs::write(
+            &path,
+            r#"[{"query_id": "q1", "query": "vector search", "relevance": {"doc1": 3}}]"#,
+        )
+        .unwrap();
+
+        let set = QuerySet::load(&path).unwrap();
+        assert_eq!(set.len(), 1);
+        assert_eq!(set.queries[0].relevance.get("doc1"), Some(&3));
+    }
+
+    #[test]
+    fn test_sign_test() {
+        assert_eq!(sign_test(0, 0), 1.0);
+        assert_eq!(sign_test(3, 3), 1.0);
+        // 2 * 0.5^5
+        assert!((sign_test(5, 0) - 0.0625).abs() < 1e-12);
+        assert!((sign_test(0, 10) - 2.0 * 0.5_f64.powi(10)).abs() < 1e-12);
+        // Large sets stay finite
+        assert!(sign_test(900, 100) < 1e-10);
+        assert!(sign_test(510, 490) > 0.5);
+    }
+
+    async fn shaped_engine(
+        docs: &[(&str, &str)],
+        shapes: &[(&str, Vec<&str>)],
+    ) -> SemanticSearchEngine {
+        use crate::config::SemanticConfig;
+        use crate::providers::{EmbeddingProvider, MockProvider, ProviderManager};
+        use crate::qdrant::{MockVectorStore, VectorIndex};
+        use crate::types::{EntityType, SimilarityMetric};
+        use std::sync::Arc;
+
+        let mut config = SemanticConfig::default();
+        config.search.default_threshold = 0.0;
+        config.search.enable_query_expansion = false;
+        config.search.enable_hybrid_search = false;
+        config.search.enable_reranking = false;
+
+        let mock = MockProvider::new(64);
+        for (query, ranked) in shapes {
+            mock.shape_similarity(query, ranked);
+        }
+        let provider = Arc::new(ProviderManager::new(
+            vec![("mock".to_string(), Box::new(mock) as Box<dyn EmbeddingProvider>)],
+            Default::default(),
+        ));
+        let store: Arc<dyn VectorIndex> = Arc::new(MockVectorStore::new(64, SimilarityMetric::Cosine));
+        let engine = SemanticSearchEngine::with_provider(config, provider, store)
+            .await
+            .unwrap();
+        for (id, content) in docs {
+            engine
+                .index_document(id.to_string(), content.to_string(), EntityType::Document, HashMap::new())
+                .await
+                .unwrap();
+        }
+        engine
+    }
+
+    #[tokio::test]
+    async fn test_harness_run_and_compare() {
+        let docs = [("d1", "red apple"), ("d2", "green pear"), ("d3", "yellow plum")];
+        let queries = QuerySet::from_tsv(
+            "q1\tfirst fruit\td1\t2\n\
+             q2\tsecond fruit\td2\t1\n\
+             q3\tthird fruit\td3\t1\n",
+        )
+        .unwrap();
+
+        // The baseline ranks every relevant document last, the candidate first
+        let baseline = shaped_engine(
+            &docs,
+            &[
+                ("first fruit", vec!["green pear", "yellow plum", "red apple"]),
+                ("second fruit", vec!["red apple", "yellow plum", "green pear"]),
+                ("third fruit", vec!["red apple", "green pear", "yellow plum"]),
+            ],
+        )
+        .await;
+        let candidate = shaped_engine(
+            &docs,
+            &[
+                ("first fruit", vec!["red apple", "green pear", "yellow plum"]),
+                ("second fruit", vec!["green pear", "red apple", "yellow plum"]),
+                ("third fruit", vec!["yellow plum", "red apple", "green pear"]),
+            ],
+        )
+        .await;
+
+        let harness = EvalHarness::new(&[3, 1]);
+        assert_eq!(harness.k_values(), &[1, 3]);
+
+        let report = harness.run("candidate", &candidate, &queries).await.unwrap();
+        assert_eq!(report.metrics.num_queries, 3);
+        assert_eq!(report.per_query[0].retrieved[0], "d1");
+        assert!((report.metrics.mean_reciprocal_rank - 1.0).abs() < 1e-9);
+        assert!(report.to_string().contains("ndcg@3"));
+        let json: EvalReport = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert_eq!(json.per_query.len(), 3);
+        assert_eq!(report.to_run().per_query.len(), 3);
+
+        let comparison = harness
+            .compare(&queries, ("baseline", &baseline), ("candidate", &candidate))
+            .await
+            .unwrap();
+        assert_eq!(comparison.num_queries, 3);
+        assert!(comparison.differences.is_empty());
+
+        let mrr = comparison.metrics.iter().find(|m| m.metric == "mrr").unwrap();
+        assert!((mrr.delta - (1.0 - 1.0 / 3.0)).abs() < 1e-9);
+        assert_eq!((mrr.wins, mrr.losses), (3, 0));
+        // Three queries are too few for significance
+        assert_eq!(mrr.significance, Significance::NotSignificant);
+
+        let recall = comparison.metrics.iter().find(|m| m.metric == "recall@3").unwrap();
+        assert_eq!(recall.delta, 0.0);
+        assert_eq!((recall.wins, recall.losses), (0, 0));
+        assert!(comparison.to_string().contains("p < 0.05"));
+    }
 }
@@ -0,0 +1,385 @@
+//! Metadata filter expressions.
+//!
+//! A `FilterExpr` combines field conditions with AND, OR and NOT, e.g.
+//! `language == rust AND modified_at > 2024-01-01 AND (module == auth OR
+//! module == session)`. `QdrantVectorStore` translates it into a native
+//! payload filter; other backends and the search engine evaluate it with
+//! `FilterExpr::matches`.
+//!
+//! Filters serialize to JSON tagged by `op`:
+//!
+//! ```json
+//! {"op": "and", "filters": [
+//!     {"op": "eq", "field": "language", "value": "rust"},
+//!     {"op": "range", "field": "modified_at", "gt": "2024-01-01"},
+//!     {"op": "in", "field": "module", "values": ["auth", "session"]}
+//! ]}
+//! ```
+
+use crate::error::{Result, SemanticError};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+
+/// A boolean expression over payload fields.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum FilterExpr {
+    /// Every filter matches
+    And { filters: Vec<FilterExpr> },
+
+    /// At least one filter matches
+    Or { filters: Vec<FilterExpr> },
+
+    /// The filter does not match
+    Not { filter: Box<FilterExpr> },
+
+    /// The field equals `value`
+    Eq { field: String, value: Value },
+
+    /// The field lies within the bounds. Numbers compare numerically, and
+    /// dates (`2024-01-01`) and RFC 3339 timestamps chronologically
+    Range {
+        field: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        gt: Option<Value>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        gte: Option<Value>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        lt: Option<Value>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        lte: Option<Value>,
+    },
+
+    /// The field equals one of `values`
+    In { field: String, values: Vec<Value> },
+}
+
+impl FilterExpr {
+    pub fn and(filters: impl IntoIterator<Item = FilterExpr>) -> Self {
+        Self::And {
+            filters: filters.into_iter().collect(),
+        }
+    }
+
+    pub fn or(filters: impl IntoIterator<Item = FilterExpr>) -> Self {
+        Self::Or {
+            filters: filters.into_iter().collect(),
+        }
+    }
+
+    pub fn not(filter: FilterExpr) -> Self {
+        Self::Not {
+            filter: Box::new(filter),
+        }
+    }
+
+    pub fn eq(field: impl Into<String>, value: impl Into<Value>) -> Self {
+        Self::Eq {
+            field: field.into(),
+            value: value.into(),
+        }
+    }
+
+    pub fn is_in(field: impl Into<String>, values: impl IntoIterator<Item = impl Into<Value>>) -> Self {
+        Self::In {
+            field: field.into(),
+            values: values.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// A range with no bounds yet; add them with `gt`, `gte`, `lt` and `lte`.
+    pub fn range(field: impl Into<String>) -> Self {
+        Self::Range {
+            field: field.into(),
+            gt: None,
+            gte: None,
+            lt: None,
+            lte: None,
+        }
+    }
+
+    /// Set the exclusive lower bound of a range; no-op on other filters.
+    pub fn gt(mut self, bound: impl Into<Value>) -> Self {
+        if let Self::Range { gt, .. } = &mut self {
+            *gt = Some(bound.into());
+        }
+        self
+    }
+
+    /// Set the inclusive lower bound of a range; no-op on other filters.
+    pub fn gte(mut self, bound: impl Into<Value>) -> Self {
+        if let Self::Range { gte, .. } = &mut self {
+            *gte = Some(bound.into());
+        }
+        self
+    }
+
+    /// Set the exclusive upper bound of a range; no-op on other filters.
+    pub fn lt(mut self, bound: impl Into<Value>) -> Self {
+        if let Self::Range { lt, .. } = &mut self {
+            *lt = Some(bound.into());
+        }
+        self
+    }
+
+    /// Set the inclusive upper bound of a range; no-op on other filters.
+    pub fn lte(mut self, bound: impl Into<Value>) -> Self {
+        if let Self::Range { lte, .. } = &mut self {
+            *lte = Some(bound.into());
+        }
+        self
+    }
+
+    /// Every field the expression refers to.
+    pub fn fields(&self) -> BTreeSet<&str> {
+        let mut fields = BTreeSet::new();
+        self.collect_fields(&mut fields);
+        fields
+    }
+
+    fn collect_fields<'a>(&'a self, fields: &mut BTreeSet<&'a str>) {
+        match self {
+            Self::And { filters } | Self::Or { filters } => {
+                filters.iter().for_each(|filter| filter.collect_fields(fields));
+            }
+            Self::Not { filter } => filter.collect_fields(fields),
+            Self::Eq { field, .. } | Self::Range { field, .. } | Self::In { field, .. } => {
+                fields.insert(field.as_str());
+            }
+        }
+    }
+
+    /// Check that field names are well formed (letters, digits, `_`, `-`
+    /// and `.`), that `and`, `or` and `in` are not empty, and that every
+    /// range has bounds that are all numbers or all dates.
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            Self::And { filters } | Self::Or { filters } => {
+                if filters.is_empty() {
+                    return Err(SemanticError::InvalidFilter(
+                        "`and` and `or` need at least one filter".to_string(),
+                    ));
+                }
+                filters.iter().try_for_each(Self::validate)
+            }
+            Self::Not { filter } => filter.validate(),
+            Self::Eq { field, value } => {
+                check_field(field)?;
+                if !is_scalar(value) {
+                    return Err(SemanticError::InvalidFilter(format!(
+                        "`{}` can only be compared to a string, number or boolean",
+                        field
+                    )));
+                }
+                Ok(())
+            }
+            Self::In { field, values } => {
+                check_field(field)?;
+                if values.is_empty() {
+                    return Err(SemanticError::InvalidFilter(format!("`in` on `{}` has no values", field)));
+                }
+                if !values.iter().all(is_scalar) {
+                    return Err(SemanticError::InvalidFilter(format!(
+                        "`{}` can only be compared to strings, numbers or booleans",
+                        field
+                    )));
+                }
+                Ok(())
+            }
+            Self::Range { field, .. } => {
+                check_field(field)?;
+                let bounds = self.bounds();
+                if bounds.is_empty() {
+                    return Err(SemanticError::InvalidFilter(format!("Range on `{}` has no bounds", field)));
+                }
+                let numbers = bounds.iter().all(|bound| bound.is_number());
+                let dates = bounds.iter().all(|bound| bound.as_str().and_then(parse_datetime).is_some());
+                if !numbers && !dates {
+                    return Err(SemanticError::InvalidFilter(format!(
+                        "Range bounds on `{}` must be all numbers or all dates",
+                        field
+                    )));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Bounds of a range filter, empty for other filters.
+    pub(crate) fn bounds(&self) -> Vec<&Value> {
+        match self {
+            Self::Range { gt, gte, lt, lte, .. } => [gt, gte, lt, lte].into_iter().flatten().collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Evaluate the expression against a payload, where `lookup` returns
+    /// the value of a field. Conditions on missing fields do not match.
+    pub fn matches(&self, lookup: &impl Fn(&str) -> Option<Value>) -> bool {
+        match self {
+            Self::And { filters } => filters.iter().all(|filter| filter.matches(lookup)),
+            Self::Or { filters } => filters.iter().any(|filter| filter.matches(lookup)),
+            Self::Not { filter } => !filter.matches(lookup),
+            Self::Eq { field, value } => {
+                lookup(field).is_some_and(|actual| compare(&actual, value) == Some(Ordering::Equal))
+            }
+            Self::In { field, values } => lookup(field).is_some_and(|actual| {
+                values
+                    .iter()
+                    .any(|value| compare(&actual, value) == Some(Ordering::Equal))
+            }),
+            Self::Range {
+                field,
+                gt,
+                gte,
+                lt,
+                lte,
+            } => {
+                let Some(actual) = lookup(field) else {
+                    return false;
+                };
+                let within = |bound: &Option<Value>, accept: fn(Ordering) -> bool| {
+                    bound
+                        .as_ref()
+                        .is_none_or(|bound| compare(&actual, bound).is_some_and(accept))
+                };
+                within(gt, Ordering::is_gt)
+                    && within(gte, Ordering::is_ge)
+                    && within(lt, Ordering::is_lt)
+                    && within(lte, Ordering::is_le)
+            }
+        }
+    }
+}
+
+fn check_field(field: &str) -> Result<()> {
+    let valid = !field.is_empty()
+        && field
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(SemanticError::InvalidFilter(format!("Invalid field name `{}`", field)))
+    }
+}
+
+fn is_scalar(value: &Value) -> bool {
+    matches!(value, Value::String(_) | Value::Number(_) | Value::Bool(_))
+}
+
+/// A date (`2024-01-01`, midnight UTC) or RFC 3339 timestamp.
+pub(crate) fn parse_datetime(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|datetime| datetime.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?;
+            Some(date.and_hms_opt(0, 0, 0)?.and_utc())
+        })
+}
+
+/// Order of a field value relative to a filter value, if comparable.
+/// Payload values stored as strings compare as the filter value's type.
+fn compare(actual: &Value, expected: &Value) -> Option<Ordering> {
+    match (actual, expected) {
+        (Value::String(a), Value::String(b)) => match (parse_datetime(a), parse_datetime(b)) {
+            (Some(a), Some(b)) => Some(a.cmp(&b)),
+            _ => Some(a.cmp(b)),
+        },
+        (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+        (Value::String(a), Value::Bool(b)) => a.parse::<bool>().ok().map(|a| a.cmp(b)),
+        _ => as_f64(actual)?.partial_cmp(&as_f64(expected)?),
+    }
+}
+
+fn as_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn lookup(payload: Value) -> impl Fn(&str) -> Option<Value> {
+        move |field| payload.get(field).cloned()
+    }
+
+    #[test]
+    fn test_filter_expression_matches() {
+        let filter = FilterExpr::and([
+            FilterExpr::eq("language", "rust"),
+            FilterExpr::range("modified_at").gt("2024-01-01"),
+            FilterExpr::or([FilterExpr::eq("module", "auth"), FilterExpr::eq("module", "session")]),
+        ]);
+        assert!(filter.validate().is_ok());
+
+        let doc = json!({"language": "rust", "modified_at": "2024-03-05T10:00:00Z", "module": "session"});
+        assert!(filter.matches(&lookup(doc)));
+        let old = json!({"language": "rust", "modified_at": "2023-12-31", "module": "auth"});
+        assert!(!filter.matches(&lookup(old)));
+        let other = json!({"language": "rust", "modified_at": "2024-02-01", "module": "billing"});
+        assert!(!filter.matches(&lookup(other)));
+
+        // Numbers stored as strings compare numerically; missing fields
+        // only match under `not`
+        let doc = lookup(json!({"lines": "120", "public": "true"}));
+        assert!(FilterExpr::range("lines").gte(100).lt(200).matches(&doc));
+        assert!(!FilterExpr::range("lines").gt(120).matches(&doc));
+        assert!(FilterExpr::is_in("lines", [80, 120]).matches(&doc));
+        assert!(FilterExpr::eq("public", true).matches(&doc));
+        assert!(!FilterExpr::eq("owner", "me").matches(&doc));
+        assert!(FilterExpr::not(FilterExpr::eq("owner", "me")).matches(&doc));
+    }
+
+    #[test]
+    fn test_filter_expression_json() {
+        let json = json!({"op": "and", "filters": [
+            {"op": "eq", "field": "language", "value": "rust"},
+            {"op": "range", "field": "modified_at", "gt": "2024-01-01"},
+            {"op": "not", "filter": {"op": "in", "field": "module", "values": ["legacy"]}}
+        ]});
+        let filter: FilterExpr = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(
+            filter,
+            FilterExpr::and([
+                FilterExpr::eq("language", "rust"),
+                FilterExpr::range("modified_at").gt("2024-01-01"),
+                FilterExpr::not(FilterExpr::is_in("module", ["legacy"])),
+            ])
+        );
+        assert_eq!(serde_json::to_value(&filter).unwrap(), json);
+        assert_eq!(filter.fields(), BTreeSet::from(["language", "modified_at", "module"]));
+    }
+
+    #[test]
+    fn test_filter_expression_validation() {
+        let invalid = [
+            FilterExpr::eq("", "x"),
+            FilterExpr::eq("bad field", "x"),
+            FilterExpr::eq("tags", json!(["a"])),
+            FilterExpr::range("modified_at"),
+            FilterExpr::range("modified_at").gt("yesterday"),
+            FilterExpr::range("modified_at").gt("2024-01-01").lt(5),
+            FilterExpr::is_in("module", Vec::<String>::new()),
+            FilterExpr::or([]),
+            FilterExpr::not(FilterExpr::and([FilterExpr::eq("a$", 1)])),
+        ];
+        for filter in invalid {
+            assert!(
+                matches!(filter.validate(), Err(SemanticError::InvalidFilter(_))),
+                "{:?}",
+                filter
+            );
+        }
+        assert!(FilterExpr::eq("meta.owner-id", 1).validate().is_ok());
+    }
+}
@@ -348,6 +348,24 @@ mod tests {
         assert_eq!(result.aggregated_embedding.len(), 384);
     }
 
+    #[tokio::test]
+    async fn test_hyde_embeds_each_hypothesis_and_query() {
+        let provider = MockProvider::new(64).with_seed(3).with_recording();
+        let hyde = HydeProcessor::new(Arc::new(provider.clone()), HydeConfig::default());
+
+        let result = hyde.process_query("parse config", None).await.unwrap();
+
+        // One request per hypothesis, then the original query
+        let mut expected: Vec<String> = result
+            .hypothetical_docs
+            .iter()
+            .map(|doc| doc.text.clone())
+            .collect();
+        expected.push("parse config".to_string());
+        assert_eq!(provider.embedded_texts(), expected);
+        assert!(provider.batch_sizes().is_empty());
+    }
+
     #[tokio::test]
     async fn test_code_hypotheses_generation() {
         let provider = Arc::new(MockProvider::new(384));
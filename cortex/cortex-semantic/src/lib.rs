@@ -101,6 +101,7 @@
 pub mod config;
 pub mod providers;
 pub mod query;
+pub mod plan;
 pub mod search;
 pub mod ranking;
 pub mod cache;
@@ -111,33 +112,78 @@ pub mod agent;
 pub mod orchestration;
 pub mod context;
 pub mod hyde;
+pub mod answer;
 pub mod eval;
 pub mod ragas;
+pub mod sparse;
+pub mod filter;
+pub mod snapshot;
+pub mod retry;
+pub mod warmup;
+pub mod payload;
+pub mod session_pool;
 
 pub use config::{
     SemanticConfig, EmbeddingProviderConfig, IndexConfig, SearchConfig, QdrantConfig,
-    VectorStoreConfig, VectorStoreBackend, QuantizationType,
+    VectorStoreConfig, VectorStoreBackend, QuantizationType, ONNXConfig, OnnxQuantization,
+    OnnxExecutionProvider, IntentPolicy, IntentRule, IntentBranch, ChunkAggregation,
+    HybridConfig, HybridFusion, CircuitBreakerConfig, InitializationMode,
 };
-pub use providers::{EmbeddingProvider, OpenAIProvider, ONNXProvider, OllamaProvider, MockProvider};
-pub use qdrant::{VectorIndex, QdrantVectorStore, QdrantMetrics, IndexStats, SearchResult as QdrantSearchResult, SearchFilter as QdrantSearchFilter, SparseVector};
-pub use query::{QueryProcessor, QueryExpander, QueryIntent, QueryDecomposer, SubQuery, AnswerType, QueryDependencyGraph};
-pub use search::{SemanticSearchEngine, SearchResult, SearchFilter};
+pub use providers::{
+    EmbeddingProvider, OpenAIProvider, ONNXProvider, OllamaProvider, MockProvider, ProviderInfo,
+    EmbeddingUsage, BatchUsage, UsageCallback, attribute_usage_to, OnnxCrossEncoder,
+    ProviderManager, ProviderHealth, EmbedCall,
+};
+pub use qdrant::{VectorIndex, QdrantVectorStore, QdrantMetrics, IndexStats, IndexPoint, ScrollPage, SearchResult as QdrantSearchResult, SearchFilter as QdrantSearchFilter, SparseVector};
+pub use query::{QueryProcessor, QueryExpander, QueryIntent, QueryDecomposer, SubQuery, AnswerType, QueryDependencyGraph, DomainDictionary, ExpansionOptions, WeightedTerm};
+pub use plan::{QueryPlan, QueryPlanExecutor, QueryPlanConfig, PrerequisiteContext, PlanResults, SubQueryOutcome};
+pub use search::{
+    SemanticSearchEngine, SearchResult, SearchFilter, ChunkMatch, IndexReport, IndexFailure,
+    IndexProgress, HybridScores, DocumentUpdate, ReindexReport, ContentSource,
+};
+pub use sparse::{SparseEncoder, SparseIndex};
+pub use filter::FilterExpr;
+pub use snapshot::{SnapshotHeader, SnapshotSummary};
+pub use cache::{CacheHitType, EmbeddingCacheStats};
 pub use ranking::{
     Ranker, RankingStrategy, ScoringAlgorithm, MMRReranker, PersonalizedRanker,
-    AdvancedRanker, PersonalizationConfig, DiversityConfig,
+    AdvancedRanker, PersonalizationConfig, DiversityConfig, DemotionReason, DiversifiedDocument,
+    FeedbackEvent, FeedbackKind, PreferenceProfile,
+    DiversifiedRanking, Reranker, CrossEncoderConfig, MockReranker,
+};
+pub use context::{
+    ContextCompressor, CompressionConfig, CompressionMode, ContextChunk, CompressedContext,
+    ChunkRetention, RemovalReason, TokenAwareChunker,
+    Tokenizer, HeuristicTokenizer, HuggingFaceTokenizer, BpeTokenizer,
 };
-pub use context::{ContextCompressor, CompressionConfig, ContextChunk, CompressedContext, TokenAwareChunker};
 pub use hyde::{HydeProcessor, HydeConfig, HypotheticalDocument, HydeResult};
-pub use eval::{MetricEvaluator, QueryEvaluation, Metrics, AggregatedMetrics, MetricsTimeSeries};
+pub use answer::{AnswerExtractor, AnswerConfig, AnswerSnippet};
+pub use eval::{
+    MetricEvaluator, QueryEvaluation, Metrics, AggregatedMetrics, MetricsTimeSeries, PersonalizationUplift,
+    ConfigFingerprint, EvalRun, EvalRunStore, FileRunStore, MemoryRunStore, MetricDelta, QueryRegression,
+    RegressionReport, LabeledQuery, QuerySet, EvalHarness, EvalReport, QueryReport, ComparisonReport,
+    ComparedMetric, Significance,
+};
 pub use ragas::{RagasEvaluator, RagasConfig, RagasEvaluation, EvaluationDetails};
-pub use types::{Vector, DocumentId, EmbeddingModel, EntityType, AgentSearchResult, MultiAgentSearchStats, FederatedSearchConfig};
-pub use error::{SemanticError, Result};
+pub use types::{Vector, DocumentId, EmbeddingModel, EntityType, AgentSearchResult, MultiAgentSearchStats, FederatedSearchConfig, AgentScoreRange};
+pub use error::{SemanticError, Result, CollectionParamMismatch};
+pub use retry::RetryPolicy;
+pub use warmup::{WarmupReport, WarmupStage, StageTiming, ReadinessStatus};
 pub use agent::{
     AgentCoordinator, AgentContext, AgentId, AgentRole, AgentMetrics, Namespace,
-    MemoryPool, MemoryEntry, AccessPolicy, AccessControl, SearchPriority,
-    PrioritizedSearchRequest, SearchQueue,
+    MemoryPool, MemoryEntry, MemoryPoolLimits, MemoryPoolUsage, AgentPoolUsage,
+    EvictionReason, EvictionCallback, AccessPolicy, AccessControl, SearchPriority,
+    PrioritizedSearchRequest, SearchQueue, SearchQueueConfig, SearchQueueStatus,
+    PriorityQueueStatus, DequeuePolicy, LatencyHistogram, AccessLevel, AccessAuditEntry,
+    QueueStore, FileQueueStore, PersistedSearchRequest,
+    AgentStatus, AgentTransition, AgentStatusEvent, AgentStatusCallback, LivenessConfig,
+    ReapReport, NamespaceQuota, IndexUsage, NamespaceUsage, UsageReport,
+    agent_namespace, namespace_owner,
+};
+pub use orchestration::{
+    SearchOrchestrator, SearchOrchestratorStats, AggregationStrategy, DeduplicationStrategy,
+    ScoreNormalization, normalize_scores, CONTENT_HASH_KEY,
 };
-pub use orchestration::{SearchOrchestrator, SearchOrchestratorStats, AggregationStrategy, DeduplicationStrategy};
 
 /// Re-export commonly used types
 pub mod prelude {
@@ -5,21 +5,32 @@
 //! - Result aggregation and deduplication
 //! - Cross-agent context passing
 //! - Load balancing and failover
+//! - Skipping unreachable agents and releasing dead ones
 //!
 //! Based on 2025 research in distributed search systems and multi-agent coordination.
 
-use crate::agent::{AgentContext, AgentCoordinator, AgentId, Namespace, SearchPriority};
+use crate::agent::{
+    AgentContext, AgentCoordinator, AgentId, AgentStatus, Namespace, PrioritizedSearchRequest,
+    ReapReport, SearchPriority, SearchQueue, SearchQueueStatus,
+};
 use crate::error::{Result, SemanticError};
 use crate::search::{SearchFilter, SearchResult, SemanticSearchEngine};
-use crate::types::{AgentSearchResult, DocumentId, FederatedSearchConfig, MultiAgentSearchStats};
+use crate::types::{
+    AgentScoreRange, AgentSearchResult, DocumentId, FederatedSearchConfig, MultiAgentSearchStats,
+};
 use dashmap::DashMap;
 use futures::future::join_all;
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tokio::sync::{RwLock, Semaphore};
 use tracing::{debug, info, warn};
 
+/// Metadata key of a document's content hash, compared by
+/// `DeduplicationStrategy::ContentSimilarity` when both results carry one.
+pub const CONTENT_HASH_KEY: &str = "content_hash";
+
 /// Search orchestrator for multi-agent coordination.
 ///
 /// The orchestrator manages:
@@ -38,6 +49,47 @@ pub struct SearchOrchestrator {
     stats: Arc<RwLock<SearchOrchestratorStats>>,
     /// Rate limiter for concurrent searches (prevents DoS)
     rate_limiter: Arc<Semaphore>,
+    /// Requests waiting for `run_next_queued`
+    queue: Arc<SearchQueue>,
+    /// Results of background retries of timed-out agents, awaiting the
+    /// next identical query
+    retried_results: Arc<DashMap<RetryKey, RetriedResults>>,
+}
+
+/// Agent, requester and query a background retry searched for.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct RetryKey {
+    agent_id: AgentId,
+    requesting_agent: AgentId,
+    query: String,
+    limit: usize,
+}
+
+struct RetriedResults {
+    results: Vec<SearchResult>,
+    completed_at: Instant,
+}
+
+/// Background retries of agents that timed out in a federated search.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeoutRetryConfig {
+    /// Search timed-out agents again in the background, and serve their
+    /// results to the next identical query instead of querying them
+    pub enabled: bool,
+    /// Longest a retry may take
+    pub timeout_ms: u64,
+    /// How long retried results stay usable
+    pub result_ttl_ms: u64,
+}
+
+impl Default for TimeoutRetryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            timeout_ms: 30_000,
+            result_ttl_ms: 60_000,
+        }
+    }
 }
 
 /// Orchestrator statistics.
@@ -47,6 +99,8 @@ pub struct SearchOrchestratorStats {
     pub total_namespaces_searched: u64,
     pub total_results_deduplicated: u64,
     pub avg_search_latency_ms: f64,
+    /// Depths and queue latencies of the search queue
+    pub queue: SearchQueueStatus,
 }
 
 impl SearchOrchestrator {
@@ -68,9 +122,11 @@ impl SearchOrchestrator {
         Self {
             coordinator,
             engines: Arc::new(DashMap::new()),
-            config,
             stats: Arc::new(RwLock::new(SearchOrchestratorStats::default())),
             rate_limiter: Arc::new(Semaphore::new(max_concurrent_searches)),
+            queue: Arc::new(SearchQueue::with_config(config.queue.clone())),
+            retried_results: Arc::new(DashMap::new()),
+            config,
         }
     }
 
@@ -97,6 +153,28 @@ impl SearchOrchestrator {
         limit: usize,
         namespaces: Option<Vec<Namespace>>,
         priority: SearchPriority,
+    ) -> Result<(Vec<AgentSearchResult>, MultiAgentSearchStats)> {
+        self.federated_search_with_normalization(
+            requesting_agent,
+            query,
+            limit,
+            namespaces,
+            priority,
+            None,
+        )
+        .await
+    }
+
+    /// Federated search normalizing each agent's scores with `normalization`
+    /// instead of `FederatedSearchConfig::score_normalization`.
+    pub async fn federated_search_with_normalization(
+        &self,
+        requesting_agent: &AgentId,
+        query: &str,
+        limit: usize,
+        namespaces: Option<Vec<Namespace>>,
+        priority: SearchPriority,
+        normalization: Option<ScoreNormalization>,
     ) -> Result<(Vec<AgentSearchResult>, MultiAgentSearchStats)> {
         let start = Instant::now();
 
@@ -105,11 +183,31 @@ impl SearchOrchestrator {
             requesting_agent, priority
         );
 
+        let deadline = self
+            .config
+            .deadline_ms
+            .map(|ms| tokio::time::Instant::now() + Duration::from_millis(ms));
+        let agent_timeout = self.config.agent_timeout_ms.map(Duration::from_millis);
+
         // Acquire concurrency permit
-        let _permit = self.coordinator.acquire_permit().await?;
+        let _permit = match deadline {
+            Some(deadline) => tokio::time::timeout_at(deadline, self.coordinator.acquire_permit())
+                .await
+                .map_err(|_| {
+                    SemanticError::Search(format!(
+                        "Federated search deadline of {}ms elapsed waiting for a search permit",
+                        self.config.deadline_ms.unwrap_or_default()
+                    ))
+                })??,
+            None => self.coordinator.acquire_permit().await?,
+        };
 
-        // Determine which namespaces to search
+        // Determine which namespaces to search, keeping only readable ones
+        let explicitly_requested = namespaces.is_some();
         let target_namespaces = self.determine_namespaces(namespaces).await?;
+        let target_namespaces = self
+            .authorized_namespaces(requesting_agent, target_namespaces, explicitly_requested)
+            .await;
 
         if target_namespaces.is_empty() {
             return Ok((vec![], MultiAgentSearchStats::default()));
@@ -117,12 +215,27 @@ impl SearchOrchestrator {
 
         // Track statistics
         let mut stats = MultiAgentSearchStats {
-            agents_queried: target_namespaces.len(),
+            agents_queried: 0,
             namespaces_searched: target_namespaces.clone(),
             results_per_agent: HashMap::new(),
             total_search_time_ms: 0,
             deduplicated_count: 0,
             communication_overhead_ms: 0,
+            dedup_fell_back_to_id: false,
+            score_normalization: normalization
+                .unwrap_or_else(|| self.score_normalization_for(&target_namespaces)),
+            score_ranges: HashMap::new(),
+            skipped_agents: Vec::new(),
+            agents_timed_out: Vec::new(),
+            agents_from_retry: Vec::new(),
+        };
+        let mut retried: Vec<(AgentId, Namespace, Vec<SearchResult>)> = Vec::new();
+
+        let retry_key = |agent_id: &str| RetryKey {
+            agent_id: agent_id.to_string(),
+            requesting_agent: requesting_agent.clone(),
+            query: query.to_string(),
+            limit,
         };
 
         // Perform concurrent searches across namespaces with rate limiting
@@ -132,26 +245,70 @@ impl SearchOrchestrator {
                 // Extract agent_id from namespace (format: "agent::{agent_id}")
                 let agent_id = namespace.strip_prefix("agent::").unwrap_or(namespace);
 
+                if self.is_unreachable(agent_id) {
+                    debug!("Skipping unreachable agent {}", agent_id);
+                    stats.skipped_agents.push(agent_id.to_string());
+                    return None;
+                }
+
+                if let Some(results) = self.take_retried_results(&retry_key(agent_id)) {
+                    debug!("Using results of a background retry for agent {}", agent_id);
+                    stats.agents_from_retry.push(agent_id.to_string());
+                    retried.push((agent_id.to_string(), namespace.clone(), results));
+                    return None;
+                }
+
                 self.engines.get(agent_id).map(|engine| {
                     let engine = engine.clone();
                     let query = query.to_string();
                     let namespace = namespace.clone();
                     let agent_id = agent_id.to_string();
+                    let requesting_agent = requesting_agent.clone();
+                    let coordinator = self.coordinator.clone();
                     let rate_limiter = self.rate_limiter.clone();
+                    let retry = self.config.retry.clone();
 
                     async move {
-                        // Acquire permit from rate limiter (blocks if limit reached)
-                        let _permit = rate_limiter.acquire().await.expect("Semaphore closed unexpectedly");
-
                         let search_start = Instant::now();
-
-                        let results = engine
-                            .search(&query, limit)
-                            .await
-                            .unwrap_or_else(|e| {
-                                warn!("Search failed for namespace {}: {}", namespace, e);
-                                vec![]
+                        let search = async {
+                            // Acquire permit from rate limiter (blocks if limit reached)
+                            let _permit = rate_limiter.acquire().await.expect("Semaphore closed unexpectedly");
+
+                            let search = retry.run("Agent search", || {
+                                engine.search_as(
+                                    &requesting_agent,
+                                    &query,
+                                    limit,
+                                    SearchFilter::default(),
+                                    &coordinator,
+                                )
                             });
+                            match agent_timeout {
+                                Some(agent_timeout) => tokio::time::timeout(agent_timeout, search).await.ok(),
+                                None => Some(search.await),
+                            }
+                        };
+                        let results = match deadline {
+                            Some(deadline) => tokio::time::timeout_at(deadline, search).await.ok().flatten(),
+                            None => search.await,
+                        };
+
+                        // A successful response counts as a heartbeat; `None`
+                        // marks a timeout
+                        let results = match results {
+                            Some(Ok(results)) => {
+                                let _ = coordinator.heartbeat(&agent_id);
+                                Some(results)
+                            }
+                            Some(Err(e)) => {
+                                warn!("Search failed for namespace {} ({}): {}", namespace, e.error_code(), e);
+                                Some(vec![])
+                            }
+                            None => {
+                                warn!("Search timed out for namespace {}", namespace);
+                                None
+                            }
+                        };
 
                         let search_time = search_start.elapsed().as_millis() as u64;
 
@@ -162,17 +319,34 @@ impl SearchOrchestrator {
             .collect();
 
         // Execute all searches concurrently
+        stats.agents_queried = search_futures.len();
         let search_results = join_all(search_futures).await;
 
         // Aggregate results
         let mut all_results: Vec<AgentSearchResult> = Vec::new();
 
-        for (agent_id, namespace, results, search_time) in search_results {
-            stats.total_search_time_ms += search_time;
+        let responses = search_results
+            .into_iter()
+            .filter_map(|(agent_id, namespace, results, search_time)| {
+                stats.total_search_time_ms += search_time;
+                if results.is_none() {
+                    if self.config.timeout_retry.enabled {
+                        self.retry_in_background(retry_key(&agent_id));
+                    }
+                    stats.agents_timed_out.push(agent_id.clone());
+                }
+                results.map(|results| (agent_id, namespace, results))
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .chain(retried);
+
+        for (agent_id, namespace, results) in responses {
             stats.results_per_agent.insert(agent_id.clone(), results.len());
 
-            for result in results {
-                all_results.push(AgentSearchResult {
+            let mut agent_results: Vec<AgentSearchResult> = results
+                .into_iter()
+                .map(|result| AgentSearchResult {
                     id: result.id,
                     entity_type: result.entity_type,
                     content: result.content,
@@ -183,15 +357,39 @@ impl SearchOrchestrator {
                     namespace: Some(namespace.clone()),
                     cross_agent_score: None,
                     embedding: result.embedding,  // Pass through embedding for deduplication
-                });
+                    also_found_in: Vec::new(),
+                })
+                .collect();
+
+            // Make this agent's scores comparable with the others'
+            if let Some(range) = normalize_scores(
+                &mut agent_results,
+                stats.score_normalization,
+                self.config.rrf_k,
+            ) {
+                stats.score_ranges.insert(agent_id.clone(), range);
+            }
+            all_results.extend(agent_results);
+        }
+
+        // Fused ranks add up for documents several agents returned
+        if stats.score_normalization == ScoreNormalization::ReciprocalRankFusion {
+            let mut fused: HashMap<DocumentId, f32> = HashMap::new();
+            for result in &all_results {
+                *fused.entry(result.id.clone()).or_insert(0.0) += result.score;
+            }
+            for result in &mut all_results {
+                result.score = fused[&result.id];
             }
         }
 
         // Deduplicate results if enabled
         if self.config.deduplicate_results {
             let before_dedup = all_results.len();
-            all_results = self.deduplicate_results(all_results).await;
+            let (deduplicated, fell_back_to_id) = self.deduplicate_results(all_results, limit).await;
+            all_results = deduplicated;
             stats.deduplicated_count = before_dedup - all_results.len();
+            stats.dedup_fell_back_to_id = fell_back_to_id;
         }
 
         // Rerank results with cross-agent awareness
@@ -267,19 +465,24 @@ impl SearchOrchestrator {
         let search_futures: Vec<_> = self
             .engines
             .iter()
+            .filter(|entry| !self.is_unreachable(entry.key()))
             .map(|entry| {
                 let agent_id = entry.key().clone();
                 let engine = entry.value().clone();
                 let query = query.to_string();
+                let coordinator = self.coordinator.clone();
 
                 async move {
-                    let search_results = engine
-                        .search(&query, limit_per_agent)
-                        .await
-                        .unwrap_or_else(|e| {
+                    let search_results = match engine.search(&query, limit_per_agent).await {
+                        Ok(results) => {
+                            let _ = coordinator.heartbeat(&agent_id);
+                            results
+                        }
+                        Err(e) => {
                             warn!("Broadcast search failed for agent {}: {}", agent_id, e);
                             vec![]
-                        });
+                        }
+                    };
 
                     (agent_id, search_results)
                 }
@@ -295,9 +498,155 @@ impl SearchOrchestrator {
         Ok(results)
     }
 
+    /// Queue a federated search to run later by priority.
+    pub async fn enqueue_search(&self, request: PrioritizedSearchRequest) -> Result<()> {
+        self.queue.enqueue(request).await
+    }
+
+    /// Run the next queued search, if any, returning its request with the
+    /// outcome.
+    pub async fn run_next_queued(
+        &self,
+        limit: usize,
+    ) -> Option<(
+        PrioritizedSearchRequest,
+        Result<(Vec<AgentSearchResult>, MultiAgentSearchStats)>,
+    )> {
+        let request = self.queue.dequeue().await?;
+        let namespaces = request.namespace.clone().map(|namespace| vec![namespace]);
+        let outcome = self
+            .federated_search(
+                &request.agent_id,
+                &request.query,
+                limit,
+                namespaces,
+                request.priority,
+            )
+            .await;
+        Some((request, outcome))
+    }
+
+    /// Cancel a queued search. Returns whether it was still queued.
+    pub async fn cancel_queued(&self, request_id: &str) -> bool {
+        self.queue.cancel(request_id).await
+    }
+
+    /// Depths and queue latencies of the search queue, printable as a table.
+    pub async fn queue_status(&self) -> SearchQueueStatus {
+        self.queue.queue_status().await
+    }
+
+    /// Reap dead agents on the coordinator, then drop the search engines
+    /// and queued requests of those it deregistered.
+    pub async fn reap_dead_agents(&self) -> ReapReport {
+        let report = self.coordinator.reap_dead_agents();
+        for agent_id in &report.deregistered {
+            self.unregister_engine(agent_id);
+            let cancelled = self.queue.cancel_agent(agent_id).await;
+            if cancelled > 0 {
+                info!("Released {} queued searches of dead agent {}", cancelled, agent_id);
+            }
+        }
+        report
+    }
+
+    /// Run `reap_dead_agents` every `LivenessConfig::reap_interval` of the
+    /// coordinator until the orchestrator is dropped. Use this instead of
+    /// `AgentCoordinator::spawn_reaper`, which cannot release engines and
+    /// queued requests.
+    pub fn spawn_reaper(self: &Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let orchestrator = Arc::downgrade(self);
+        let mut interval = tokio::time::interval(self.coordinator.liveness_config().reap_interval());
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        tokio::spawn(async move {
+            loop {
+                interval.tick().await;
+                let Some(orchestrator) = orchestrator.upgrade() else {
+                    break;
+                };
+                orchestrator.reap_dead_agents().await;
+            }
+        })
+    }
+
     /// Get orchestrator statistics.
     pub async fn stats(&self) -> SearchOrchestratorStats {
-        self.stats.read().await.clone()
+        let mut stats = self.stats.read().await.clone();
+        stats.queue = self.queue.queue_status().await;
+        stats
+    }
+
+    /// Agents registered with the coordinator but missing heartbeats. Agents
+    /// it does not know are searched as before.
+    /// Results of a finished background retry for `key`, if still fresh.
+    /// They are served once.
+    fn take_retried_results(&self, key: &RetryKey) -> Option<Vec<SearchResult>> {
+        let ttl = Duration::from_millis(self.config.timeout_retry.result_ttl_ms);
+        let (_, retried) = self.retried_results.remove(key)?;
+        (retried.completed_at.elapsed() < ttl).then_some(retried.results)
+    }
+
+    /// Search a timed-out agent again in the background, keeping the
+    /// results for the next identical query.
+    fn retry_in_background(&self, key: RetryKey) {
+        let Some(engine) = self.engines.get(&key.agent_id).map(|engine| engine.clone()) else {
+            return;
+        };
+        let coordinator = self.coordinator.clone();
+        let retried_results = self.retried_results.clone();
+        let timeout = Duration::from_millis(self.config.timeout_retry.timeout_ms);
+
+        tokio::spawn(async move {
+            let search = engine.search_as(
+                &key.requesting_agent,
+                &key.query,
+                key.limit,
+                SearchFilter::default(),
+                &coordinator,
+            );
+            match tokio::time::timeout(timeout, search).await {
+                Ok(Ok(results)) => {
+                    debug!("Background retry for agent {} returned {} results", key.agent_id, results.len());
+                    let _ = coordinator.heartbeat(&key.agent_id);
+                    retried_results.insert(
+                        key,
+                        RetriedResults {
+                            results,
+                            completed_at: Instant::now(),
+                        },
+                    );
+                }
+                Ok(Err(e)) => warn!("Background retry for agent {} failed: {}", key.agent_id, e),
+                Err(_) => warn!("Background retry for agent {} timed out", key.agent_id),
+            }
+        });
+    }
+
+    fn is_unreachable(&self, agent_id: &str) -> bool {
+        self.coordinator.agent_status(&agent_id.to_string()) == Some(AgentStatus::Unreachable)
+    }
+
+    /// Normalization for a search of `namespaces`: the configured one, or
+    /// reciprocal rank fusion when their engines use different models.
+    fn score_normalization_for(&self, namespaces: &[Namespace]) -> ScoreNormalization {
+        if let Some(normalization) = self.config.score_normalization {
+            return normalization;
+        }
+
+        let models: HashSet<_> = namespaces
+            .iter()
+            .filter_map(|namespace| {
+                let agent_id = namespace.strip_prefix("agent::").unwrap_or(namespace);
+                self.engines.get(agent_id).map(|engine| engine.embedding_model())
+            })
+            .collect();
+        if models.len() > 1 {
+            debug!("Agents use {} embedding models, fusing results by rank", models.len());
+            ScoreNormalization::ReciprocalRankFusion
+        } else {
+            ScoreNormalization::Raw
+        }
     }
 
     /// Determine which namespaces to search.
@@ -325,62 +674,161 @@ impl SearchOrchestrator {
         Ok(namespaces)
     }
 
-    /// Deduplicate results based on embedding similarity (semantic deduplication).
-    async fn deduplicate_results(&self, results: Vec<AgentSearchResult>) -> Vec<AgentSearchResult> {
-        if results.len() <= 1 {
-            return results;
+    /// Drop namespaces the requesting agent may not read.
+    ///
+    /// Explicitly requested namespaces that are denied are audited; they
+    /// simply yield no results, so their existence is not revealed.
+    async fn authorized_namespaces(
+        &self,
+        requesting_agent: &AgentId,
+        namespaces: Vec<Namespace>,
+        explicitly_requested: bool,
+    ) -> Vec<Namespace> {
+        let mut authorized = Vec::with_capacity(namespaces.len());
+        for namespace in namespaces {
+            let access = self.coordinator.namespace_access(requesting_agent, &namespace).await;
+            if access.is_some() {
+                authorized.push(namespace);
+            } else if explicitly_requested {
+                self.coordinator
+                    .audit_denied_access(requesting_agent, &namespace, "federated_search");
+            }
         }
+        authorized
+    }
 
-        let mut deduplicated: Vec<AgentSearchResult> = Vec::new();
-        let mut seen_ids = HashSet::new();
+    /// Deduplicate results with the configured strategy.
+    ///
+    /// The highest-scored result of each group of duplicates survives and
+    /// lists the agents of the others in `also_found_in`. Ids and content
+    /// hashes are looked up in a map; embeddings and text are compared only
+    /// with the first `limit` survivors, since the rest are cut from the
+    /// response anyway. When those comparisons would exceed
+    /// `dedup_max_comparisons`, results are deduplicated by id and content
+    /// hash only; the returned flag tells whether that happened.
+    async fn deduplicate_results(
+        &self,
+        mut results: Vec<AgentSearchResult>,
+        limit: usize,
+    ) -> (Vec<AgentSearchResult>, bool) {
+        let strategy = self.config.dedup_strategy;
+        if strategy == DeduplicationStrategy::None || results.len() <= 1 {
+            return (results, false);
+        }
 
-        for result in results {
-            // Skip exact duplicate IDs
-            if seen_ids.contains(&result.id) {
-                continue;
-            }
+        let comparisons = similarity_comparisons(results.len(), limit);
+        let fell_back_to_id =
+            strategy != DeduplicationStrategy::ExactId && comparisons > self.config.dedup_max_comparisons;
+        if fell_back_to_id {
+            debug!(
+                "Deduplicating {} results by id: {} comparisons exceed the limit of {}",
+                results.len(),
+                comparisons,
+                self.config.dedup_max_comparisons
+            );
+        }
+        let compare_similarity = strategy != DeduplicationStrategy::ExactId && !fell_back_to_id;
+        let match_hashes = strategy == DeduplicationStrategy::ContentSimilarity;
+        let content_hash = |result: &AgentSearchResult| {
+            match_hashes
+                .then(|| result.metadata.get(CONTENT_HASH_KEY).cloned())
+                .flatten()
+        };
 
-            // Check semantic similarity with existing results using embeddings
-            let mut is_duplicate = false;
-
-            if let Some(ref result_embedding) = result.embedding {
-                for existing in &deduplicated {
-                    // Use embedding-based semantic similarity when available
-                    let similarity = if let Some(ref existing_embedding) = existing.embedding {
-                        crate::types::cosine_similarity(
-                            result_embedding.as_slice(),
-                            existing_embedding.as_slice()
-                        )
-                    } else {
-                        // Fallback to content comparison if no embedding
-                        self.calculate_content_similarity(&result.content, &existing.content)
-                    };
+        // Representatives are picked in score order
+        results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+
+        let mut deduplicated: Vec<AgentSearchResult> = Vec::new();
+        let mut beyond_limit: Vec<AgentSearchResult> = Vec::new();
+        let mut by_id: HashMap<DocumentId, usize> = HashMap::new();
+        let mut by_hash: HashMap<String, usize> = HashMap::new();
+        for result in results {
+            let hash = content_hash(&result);
+            let representative = by_id
+                .get(&result.id)
+                .or_else(|| hash.as_ref().and_then(|hash| by_hash.get(hash)))
+                .copied()
+                .or_else(|| {
+                    compare_similarity
+                        .then(|| {
+                            deduplicated
+                                .iter()
+                                .position(|existing| self.is_duplicate(strategy, existing, &result))
+                        })
+                        .flatten()
+                });
 
-                    if similarity >= self.config.dedup_threshold {
-                        is_duplicate = true;
-                        debug!("Deduplicating result {} (similarity: {:.3})", result.id, similarity);
-                        break;
+            match representative {
+                Some(index) => {
+                    let representative = &mut deduplicated[index];
+                    debug!("Deduplicating result {} into {}", result.id, representative.id);
+                    // Later copies under this id or hash match directly
+                    by_id.entry(result.id.clone()).or_insert(index);
+                    if let Some(hash) = hash {
+                        by_hash.entry(hash).or_insert(index);
+                    }
+                    if let Some(agent_id) = result.indexed_by {
+                        let known = representative.indexed_by.as_ref() == Some(&agent_id)
+                            || representative.also_found_in.contains(&agent_id);
+                        if !known {
+                            representative.also_found_in.push(agent_id);
+                        }
                     }
                 }
-            } else {
-                // If no embedding, check with basic content similarity
-                for existing in &deduplicated {
-                    let similarity = self.calculate_content_similarity(&result.content, &existing.content);
-
-                    if similarity >= self.config.dedup_threshold {
-                        is_duplicate = true;
-                        break;
+                None if deduplicated.len() < limit => {
+                    by_id.insert(result.id.clone(), deduplicated.len());
+                    if let Some(hash) = hash {
+                        by_hash.insert(hash, deduplicated.len());
                     }
+                    deduplicated.push(result);
                 }
+                None => beyond_limit.push(result),
             }
+        }
 
-            if !is_duplicate {
-                seen_ids.insert(result.id.clone());
-                deduplicated.push(result);
-            }
+        deduplicated.extend(beyond_limit);
+        (deduplicated, fell_back_to_id)
+    }
+
+    /// Whether `candidate` duplicates the already kept `existing` result.
+    fn is_duplicate(
+        &self,
+        strategy: DeduplicationStrategy,
+        existing: &AgentSearchResult,
+        candidate: &AgentSearchResult,
+    ) -> bool {
+        if existing.id == candidate.id {
+            return true;
         }
 
-        deduplicated
+        let embedding_similarity = match (&existing.embedding, &candidate.embedding) {
+            (Some(a), Some(b)) if a.len() == b.len() => {
+                Some(crate::types::cosine_similarity(a, b))
+            }
+            _ => None,
+        };
+
+        match strategy {
+            DeduplicationStrategy::None | DeduplicationStrategy::ExactId => false,
+            DeduplicationStrategy::EmbeddingSimilarity => {
+                embedding_similarity.is_some_and(|sim| sim >= self.config.dedup_threshold)
+            }
+            DeduplicationStrategy::ContentSimilarity => {
+                let hashes = (
+                    existing.metadata.get(CONTENT_HASH_KEY),
+                    candidate.metadata.get(CONTENT_HASH_KEY),
+                );
+                if let (Some(a), Some(b)) = hashes {
+                    return a == b;
+                }
+
+                // Without embeddings, fall back to comparing the text
+                let similarity = embedding_similarity.unwrap_or_else(|| {
+                    self.calculate_content_similarity(&existing.content, &candidate.content)
+                });
+                similarity >= self.config.dedup_threshold
+            }
+        }
     }
 
     /// Calculate content similarity as fallback when embeddings not available.
@@ -417,6 +865,13 @@ impl SearchOrchestrator {
     }
 }
 
+/// Comparisons needed to compare each of `n` results with at most `limit`
+/// earlier ones.
+fn similarity_comparisons(n: usize, limit: usize) -> usize {
+    let kept = n.min(limit);
+    kept * kept.saturating_sub(1) / 2 + (n - kept) * kept
+}
+
 /// Result aggregation strategy.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AggregationStrategy {
@@ -430,12 +885,98 @@ pub enum AggregationStrategy {
     Diverse,
 }
 
+/// How scores from different agents are made comparable before merging.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScoreNormalization {
+    /// Keep engine scores as they are
+    #[default]
+    Raw,
+    /// Scale each agent's scores to [0, 1]
+    MinMax,
+    /// Standardize each agent's scores to mean 0 and standard deviation 1
+    ZScore,
+    /// Replace scores by `1 / (k + rank)` within each agent, summed over
+    /// agents returning the same document
+    #[serde(alias = "rank_based")]
+    ReciprocalRankFusion,
+}
+
+/// Normalize one agent's scores in place. Returns the score range before
+/// and after, or `None` when there are no results.
+pub fn normalize_scores(
+    results: &mut [AgentSearchResult],
+    normalization: ScoreNormalization,
+    rrf_k: f32,
+) -> Option<AgentScoreRange> {
+    let range = |results: &[AgentSearchResult]| {
+        results.iter().fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), r| {
+            (min.min(r.score), max.max(r.score))
+        })
+    };
+    if results.is_empty() {
+        return None;
+    }
+    let (raw_min, raw_max) = range(results);
+
+    match normalization {
+        ScoreNormalization::Raw => {}
+        ScoreNormalization::MinMax => {
+            let spread = raw_max - raw_min;
+            for result in results.iter_mut() {
+                result.score = if spread > f32::EPSILON {
+                    (result.score - raw_min) / spread
+                } else {
+                    1.0
+                };
+            }
+        }
+        ScoreNormalization::ZScore => {
+            let n = results.len() as f32;
+            let mean = results.iter().map(|r| r.score).sum::<f32>() / n;
+            let variance = results.iter().map(|r| (r.score - mean).powi(2)).sum::<f32>() / n;
+            let std_dev = variance.sqrt();
+            for result in results.iter_mut() {
+                result.score = if std_dev > f32::EPSILON {
+                    (result.score - mean) / std_dev
+                } else {
+                    0.0
+                };
+            }
+        }
+        ScoreNormalization::ReciprocalRankFusion => {
+            let mut order: Vec<usize> = (0..results.len()).collect();
+            order.sort_by(|&a, &b| {
+                results[b]
+                    .score
+                    .partial_cmp(&results[a].score)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+            for (rank, index) in order.into_iter().enumerate() {
+                results[index].score = 1.0 / (rrf_k + rank as f32 + 1.0);
+            }
+        }
+    }
+
+    let (normalized_min, normalized_max) = range(results);
+    Some(AgentScoreRange {
+        raw_min,
+        raw_max,
+        normalized_min,
+        normalized_max,
+    })
+}
+
 /// Result deduplication strategy.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum DeduplicationStrategy {
     /// Remove exact ID matches
     ExactId,
-    /// Content-based similarity
+    /// Matching `content_hash` metadata, or embedding similarity above the
+    /// threshold; catches the same file indexed by several agents under
+    /// different ids
+    #[default]
     ContentSimilarity,
     /// Embedding-based similarity
     EmbeddingSimilarity,
@@ -493,6 +1034,49 @@ mod tests {
         assert!(orchestrator.engines.contains_key("agent1"));
     }
 
+    #[tokio::test]
+    async fn test_unreachable_agents_skipped_and_reaped() {
+        // Every silent agent is unreachable at once, and deregistered the
+        // pass after
+        let liveness = crate::agent::LivenessConfig {
+            heartbeat_timeout_ms: 0,
+            deregister_after_ms: 0,
+            reap_interval_ms: 1_000,
+        };
+        let coordinator = Arc::new(AgentCoordinator::new().with_liveness(liveness));
+        for (agent_id, role) in [
+            ("agent1", AgentRole::Worker),
+            ("agent2", AgentRole::Worker),
+            ("lead", AgentRole::Orchestrator),
+        ] {
+            coordinator.register_agent(agent_id, role, vec![]).await.unwrap();
+        }
+        let orchestrator = SearchOrchestrator::new(coordinator.clone());
+        orchestrator.register_engine("agent1", create_test_engine().await);
+        orchestrator.register_engine("agent2", create_test_engine().await);
+
+        let lead = "lead".to_string();
+        assert_eq!(orchestrator.reap_dead_agents().await.unreachable.len(), 3);
+        coordinator.heartbeat(&lead).unwrap();
+        coordinator.heartbeat(&"agent2".to_string()).unwrap();
+
+        let (_, stats) = orchestrator
+            .federated_search(&lead, "test", 10, None, SearchPriority::Normal)
+            .await
+            .unwrap();
+        assert_eq!(stats.agents_queried, 1);
+        assert_eq!(stats.skipped_agents, vec!["agent1".to_string()]);
+
+        orchestrator
+            .enqueue_search(PrioritizedSearchRequest::new("agent1", "test", SearchPriority::Low))
+            .await
+            .unwrap();
+        let report = orchestrator.reap_dead_agents().await;
+        assert_eq!(report.deregistered, vec!["agent1".to_string()]);
+        assert!(!orchestrator.engines.contains_key("agent1"));
+        assert_eq!(orchestrator.queue_status().await.total_depth(), 0);
+    }
+
     #[tokio::test]
     async fn test_namespace_search() {
         let coordinator = create_test_coordinator().await;
@@ -522,6 +1106,54 @@ mod tests {
         assert!(!results.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_federated_search_enforces_namespace_grants() {
+        use crate::agent::{AccessLevel, agent_namespace};
+
+        let coordinator = create_test_coordinator().await;
+        let orchestrator = SearchOrchestrator::new(coordinator.clone());
+
+        let engine = create_test_engine().await;
+        engine
+            .index_document_in(
+                &agent_namespace("agent1"),
+                "doc1".to_string(),
+                "test content".to_string(),
+                crate::types::EntityType::Document,
+                HashMap::new(),
+            )
+            .await
+            .unwrap();
+        orchestrator.register_engine("agent1", engine);
+
+        let agent2 = "agent2".to_string();
+        let requested = Some(vec![agent_namespace("agent1")]);
+
+        // Denied: empty, not an error, and audited
+        let (results, _) = orchestrator
+            .federated_search(&agent2, "test", 10, requested.clone(), SearchPriority::Normal)
+            .await
+            .unwrap();
+        assert!(results.is_empty());
+        assert_eq!(coordinator.access_audit_log().len(), 1);
+
+        coordinator.grant_access(&agent_namespace("agent1"), "agent2", AccessLevel::Read);
+        let (results, _) = orchestrator
+            .federated_search(&agent2, "test", 10, requested, SearchPriority::Normal)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+
+        // Revocation applies to the next query
+        coordinator.revoke_access(&agent_namespace("agent1"), &agent2);
+        let (results, _) = orchestrator
+            .federated_search(&agent2, "test", 10, None, SearchPriority::Normal)
+            .await
+            .unwrap();
+        assert!(results.is_empty());
+        assert_eq!(coordinator.access_audit_log().len(), 1);
+    }
+
     #[tokio::test]
     async fn test_text_similarity() {
         let coordinator = create_test_coordinator().await;
@@ -554,6 +1186,7 @@ mod tests {
                 namespace: Some("agent::agent1".to_string()),
                 cross_agent_score: None,
                 embedding: Some(vec![0.1, 0.2, 0.3]),  // Test embedding
+                also_found_in: vec![],
             },
             AgentSearchResult {
                 id: "doc2".to_string(),
@@ -566,6 +1199,7 @@ mod tests {
                 namespace: Some("agent::agent2".to_string()),
                 cross_agent_score: None,
                 embedding: Some(vec![0.1, 0.2, 0.3]),  // Same embedding (duplicate)
+                also_found_in: vec![],
             },
             AgentSearchResult {
                 id: "doc3".to_string(),
@@ -578,13 +1212,230 @@ mod tests {
                 namespace: Some("agent::agent1".to_string()),
                 cross_agent_score: None,
                 embedding: Some(vec![0.9, 0.8, 0.7]),  // Different embedding
+                also_found_in: vec![],
             },
         ];
 
-        let deduplicated = orchestrator.deduplicate_results(results).await;
+        let (deduplicated, fell_back_to_id) = orchestrator.deduplicate_results(results, 10).await;
 
         // Should remove one duplicate
         assert_eq!(deduplicated.len(), 2);
+        assert!(!fell_back_to_id);
+        assert_eq!(deduplicated[0].id, "doc1");
+        assert_eq!(deduplicated[0].also_found_in, vec!["agent2".to_string()]);
+    }
+
+    fn result_from(agent_id: &str, id: &str, score: f32, embedding: Vec<f32>) -> AgentSearchResult {
+        AgentSearchResult {
+            id: id.to_string(),
+            entity_type: crate::types::EntityType::Document,
+            content: format!("content of {}", id),
+            score,
+            metadata: HashMap::new(),
+            explanation: None,
+            indexed_by: Some(agent_id.to_string()),
+            namespace: Some(format!("agent::{}", agent_id)),
+            cross_agent_score: None,
+            embedding: Some(embedding),
+            also_found_in: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_deduplication_by_content_hash() {
+        let coordinator = create_test_coordinator().await;
+        let orchestrator = SearchOrchestrator::new(coordinator);
+
+        // Same file indexed by two agents under different ids, with embeddings
+        // from different models
+        let mut first = result_from("agent1", "agent1/src/lib.rs", 0.7, vec![1.0, 0.0]);
+        let mut second = result_from("agent2", "agent2/src/lib.rs", 0.9, vec![0.0, 1.0]);
+        first.metadata.insert(CONTENT_HASH_KEY.to_string(), "abc".to_string());
+        second.metadata.insert(CONTENT_HASH_KEY.to_string(), "abc".to_string());
+
+        let (deduplicated, _) = orchestrator.deduplicate_results(vec![first, second], 10).await;
+
+        assert_eq!(deduplicated.len(), 1);
+        assert_eq!(deduplicated[0].id, "agent2/src/lib.rs");
+        assert_eq!(deduplicated[0].also_found_in, vec!["agent1".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_deduplication_falls_back_to_id_over_comparison_cap() {
+        let coordinator = create_test_coordinator().await;
+        let config = FederatedSearchConfig {
+            dedup_max_comparisons: 2,
+            ..Default::default()
+        };
+        let orchestrator = SearchOrchestrator::with_config(coordinator, config);
+
+        let results = vec![
+            result_from("agent1", "doc1", 0.9, vec![1.0, 0.0]),
+            result_from("agent2", "doc2", 0.8, vec![1.0, 0.0]),
+            result_from("agent2", "doc1", 0.7, vec![0.0, 1.0]),
+        ];
+        let (deduplicated, fell_back_to_id) = orchestrator.deduplicate_results(results, 10).await;
+
+        // doc2 is a near duplicate of doc1 but only ids are compared
+        assert!(fell_back_to_id);
+        assert_eq!(deduplicated.len(), 2);
+        assert_eq!(deduplicated[0].also_found_in, vec!["agent2".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_deduplication_scales_to_thousands_of_results() {
+        let coordinator = create_test_coordinator().await;
+        let config = FederatedSearchConfig {
+            dedup_max_comparisons: 50_000,
+            ..Default::default()
+        };
+        let orchestrator = SearchOrchestrator::with_config(coordinator, config);
+
+        // 5000 results from one agent, the top 5 in distinct directions, and
+        // copies of the top 2 from other agents further down
+        let mut results: Vec<AgentSearchResult> = (0..5000)
+            .map(|i| {
+                let angle = i as f32 * 0.5;
+                let embedding = if i < 5 { vec![angle.cos(), angle.sin(), 0.0] } else { vec![0.0, 0.0, 1.0] };
+                result_from("agent1", &format!("doc{}", i), 1.0 - i as f32 * 0.0001, embedding)
+            })
+            .collect();
+        results.push(result_from("agent2", "worker-2/doc0", 0.3, vec![1.0, 0.0, 0.0]));
+        let mut copy = result_from("agent3", "worker-3/doc1", 0.2, vec![0.0, 0.0, 1.0]);
+        copy.metadata.insert(CONTENT_HASH_KEY.to_string(), "doc1".to_string());
+        results[1].metadata.insert(CONTENT_HASH_KEY.to_string(), "doc1".to_string());
+        results.push(copy);
+
+        // Each result is compared with the top 5 survivors only
+        let (deduplicated, fell_back_to_id) = orchestrator.deduplicate_results(results, 5).await;
+
+        // Results past the top 5 are kept without being compared to each other
+        assert!(!fell_back_to_id);
+        assert_eq!(deduplicated.len(), 5000);
+        assert_eq!(deduplicated[0].id, "doc0");
+        assert_eq!(deduplicated[0].also_found_in, vec!["agent2".to_string()]);
+        assert_eq!(deduplicated[1].also_found_in, vec!["agent3".to_string()]);
+        assert!(deduplicated.iter().all(|r| !r.id.starts_with("worker-")));
+    }
+
+    /// Top ids after normalizing each agent's results and merging them.
+    fn merged_top(normalization: ScoreNormalization, n: usize) -> Vec<String> {
+        // agent1's model scores everything high; agent2's scores sit on a
+        // much lower scale, though its first result is the relevant one
+        let mut high: Vec<_> = [0.95, 0.93, 0.92, 0.91, 0.90]
+            .iter()
+            .enumerate()
+            .map(|(i, score)| result_from("agent1", &format!("a1-{}", i), *score, vec![1.0]))
+            .collect();
+        let mut low: Vec<_> = [0.30, 0.12, 0.10, 0.08]
+            .iter()
+            .enumerate()
+            .map(|(i, score)| result_from("agent2", &format!("a2-{}", i), *score, vec![1.0]))
+            .collect();
+
+        let high_range = normalize_scores(&mut high, normalization, 60.0).unwrap();
+        let low_range = normalize_scores(&mut low, normalization, 60.0).unwrap();
+        assert_eq!((low_range.raw_min, low_range.raw_max), (0.08, 0.30));
+        assert_eq!((high_range.raw_min, high_range.raw_max), (0.90, 0.95));
+
+        let mut merged: Vec<_> = high.into_iter().chain(low).collect();
+        merged.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
+        merged.into_iter().take(n).map(|r| r.id).collect()
+    }
+
+    #[test]
+    fn test_score_normalization_surfaces_low_scale_agent() {
+        let relevant = "a2-0".to_string();
+
+        assert!(!merged_top(ScoreNormalization::Raw, 3).contains(&relevant));
+        assert!(merged_top(ScoreNormalization::MinMax, 2).contains(&relevant));
+        assert_eq!(merged_top(ScoreNormalization::ZScore, 1), vec![relevant.clone()]);
+        assert!(merged_top(ScoreNormalization::ReciprocalRankFusion, 2).contains(&relevant));
+    }
+
+    #[test]
+    fn test_normalized_score_ranges() {
+        let mut results: Vec<_> = [0.2, 0.4, 0.6]
+            .iter()
+            .enumerate()
+            .map(|(i, score)| result_from("agent1", &format!("doc{}", i), *score, vec![1.0]))
+            .collect();
+
+        let range = normalize_scores(&mut results, ScoreNormalization::MinMax, 60.0).unwrap();
+        assert_eq!((range.normalized_min, range.normalized_max), (0.0, 1.0));
+
+        let range = normalize_scores(&mut results, ScoreNormalization::ReciprocalRankFusion, 60.0)
+            .unwrap();
+        assert!((range.normalized_max - 1.0 / 61.0).abs() < 1e-6);
+        assert_eq!(results[0].score, range.normalized_min);
+        assert!(normalize_scores(&mut [], ScoreNormalization::ZScore, 60.0).is_none());
+    }
+
+    #[test]
+    fn test_normalize_single_result() {
+        let single = || vec![result_from("agent1", "doc", 0.42, vec![1.0])];
+
+        let mut results = single();
+        normalize_scores(&mut results, ScoreNormalization::ZScore, 60.0).unwrap();
+        assert_eq!(results[0].score, 0.0);
+
+        let mut results = single();
+        normalize_scores(&mut results, ScoreNormalization::MinMax, 60.0).unwrap();
+        assert_eq!(results[0].score, 1.0);
+
+        let normalization: ScoreNormalization = serde_json::from_str("\"rank_based\"").unwrap();
+        assert_eq!(normalization, ScoreNormalization::ReciprocalRankFusion);
+    }
+
+    #[tokio::test]
+    async fn test_federated_search_normalization_override() {
+        let coordinator = create_test_coordinator().await;
+        coordinator.register_agent("lead", AgentRole::Orchestrator, vec![]).await.unwrap();
+        let orchestrator = SearchOrchestrator::new(coordinator);
+        orchestrator.register_engine("agent1", create_test_engine().await);
+        orchestrator.register_engine("agent2", create_test_engine().await);
+
+        // agent2 has no documents and returns nothing
+        let engine = orchestrator.engines.get("agent1").unwrap().clone();
+        engine
+            .index_document(
+                "doc1".to_string(),
+                "test content".to_string(),
+                crate::types::EntityType::Document,
+                HashMap::new(),
+            )
+            .await
+            .unwrap();
+
+        let (results, stats) = orchestrator
+            .federated_search_with_normalization(
+                &"lead".to_string(),
+                "test",
+                10,
+                None,
+                SearchPriority::Normal,
+                Some(ScoreNormalization::ZScore),
+            )
+            .await
+            .unwrap();
+        assert_eq!(stats.score_normalization, ScoreNormalization::ZScore);
+        assert!(stats.score_ranges.contains_key("agent1"));
+        assert!(!stats.score_ranges.contains_key("agent2"));
+        assert!(results.iter().all(|r| r.score.is_finite()));
+    }
+
+    #[tokio::test]
+    async fn test_same_model_keeps_raw_scores() {
+        let coordinator = create_test_coordinator().await;
+        let orchestrator = SearchOrchestrator::new(coordinator);
+        orchestrator.register_engine("agent1", create_test_engine().await);
+        orchestrator.register_engine("agent2", create_test_engine().await);
+
+        let namespaces = vec!["agent::agent1".to_string(), "agent::agent2".to_string()];
+        assert_eq!(
+            orchestrator.score_normalization_for(&namespaces),
+            ScoreNormalization::Raw
+        );
     }
 
     #[tokio::test]
@@ -604,4 +1455,230 @@ mod tests {
         assert!(results.contains_key("agent1"));
         assert!(results.contains_key("agent2"));
     }
+
+    /// Vector store whose searches do not finish while `hang` is set, like
+    /// one whose connection dropped.
+    struct HangingVectorStore {
+        inner: crate::qdrant::MockVectorStore,
+        hang: std::sync::atomic::AtomicBool,
+    }
+
+    impl HangingVectorStore {
+        async fn wait_while_hanging(&self) {
+            while self.hang.load(std::sync::atomic::Ordering::Relaxed) {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl crate::qdrant::VectorIndex for HangingVectorStore {
+        async fn insert(&self, doc_id: DocumentId, vector: crate::types::Vector) -> Result<()> {
+            self.inner.insert(doc_id, vector).await
+        }
+
+        async fn insert_with_payload(
+            &self,
+            doc_id: DocumentId,
+            vector: crate::types::Vector,
+            payload: HashMap<String, serde_json::Value>,
+        ) -> Result<()> {
+            self.inner.insert_with_payload(doc_id, vector, payload).await
+        }
+
+        async fn insert_batch(&self, items: Vec<(DocumentId, crate::types::Vector)>) -> Result<()> {
+            self.inner.insert_batch(items).await
+        }
+
+        async fn insert_batch_with_payloads(
+            &self,
+            items: Vec<(DocumentId, crate::types::Vector, HashMap<String, serde_json::Value>)>,
+        ) -> Result<()> {
+            self.inner.insert_batch_with_payloads(items).await
+        }
+
+        async fn search(&self, query: &[f32], k: usize) -> Result<Vec<crate::qdrant::SearchResult>> {
+            self.wait_while_hanging().await;
+            self.inner.search(query, k).await
+        }
+
+        async fn search_with_options(
+            &self,
+            query: &[f32],
+            k: usize,
+            filter: Option<crate::qdrant::SearchFilter>,
+            params: Option<qdrant_client::qdrant::SearchParams>,
+        ) -> Result<Vec<crate::qdrant::SearchResult>> {
+            self.wait_while_hanging().await;
+            self.inner.search_with_options(query, k, filter, params).await
+        }
+
+        async fn hybrid_search(
+            &self,
+            dense_query: &[f32],
+            sparse_query: Option<crate::qdrant::SparseVector>,
+            k: usize,
+        ) -> Result<Vec<crate::qdrant::SearchResult>> {
+            self.wait_while_hanging().await;
+            self.inner.hybrid_search(dense_query, sparse_query, k).await
+        }
+
+        async fn remove(&self, doc_id: &DocumentId) -> Result<()> {
+            self.inner.remove(doc_id).await
+        }
+
+        async fn remove_batch(&self, doc_ids: Vec<DocumentId>) -> Result<()> {
+            self.inner.remove_batch(doc_ids).await
+        }
+
+        async fn len(&self) -> usize {
+            self.inner.len().await
+        }
+
+        async fn clear(&self) -> Result<()> {
+            self.inner.clear().await
+        }
+
+        async fn stats(&self) -> crate::qdrant::IndexStats {
+            self.inner.stats().await
+        }
+
+        async fn create_snapshot(&self) -> Result<String> {
+            self.inner.create_snapshot().await
+        }
+
+        async fn optimize(&self) -> Result<()> {
+            self.inner.optimize().await
+        }
+
+        async fn ensure_collection(&self, config: &crate::config::IndexConfig) -> Result<()> {
+            self.inner.ensure_collection(config).await
+        }
+
+        async fn drop_collection(&self) -> Result<()> {
+            self.inner.drop_collection().await
+        }
+
+        async fn scroll(&self, offset: Option<String>, limit: usize) -> Result<crate::qdrant::ScrollPage> {
+            self.inner.scroll(offset, limit).await
+        }
+    }
+
+    /// Orchestrator over a responsive agent1 and an agent2 whose vector
+    /// store hangs, each with one document.
+    async fn orchestrator_with_hanging_agent(
+        config: FederatedSearchConfig,
+    ) -> (SearchOrchestrator, Arc<HangingVectorStore>) {
+        let coordinator = create_test_coordinator().await;
+        coordinator.register_agent("lead", AgentRole::Orchestrator, vec![]).await.unwrap();
+        let orchestrator = SearchOrchestrator::with_config(coordinator, config);
+
+        let mut engine_config = SemanticConfig::default();
+        engine_config.embedding.primary_provider = "mock".to_string();
+        engine_config.embedding.fallback_providers = vec![];
+        engine_config.search.default_threshold = -1.0;
+        let hanging = Arc::new(HangingVectorStore {
+            inner: crate::qdrant::MockVectorStore::new(384, crate::types::SimilarityMetric::Cosine),
+            hang: std::sync::atomic::AtomicBool::new(false),
+        });
+        let stores: [(&str, &str, Arc<dyn crate::qdrant::VectorIndex>); 2] = [
+            (
+                "agent1",
+                "alpha release notes",
+                Arc::new(crate::qdrant::MockVectorStore::new(384, crate::types::SimilarityMetric::Cosine)),
+            ),
+            ("agent2", "beta design document", hanging.clone()),
+        ];
+        for (agent_id, content, store) in stores {
+            let engine = SemanticSearchEngine::with_vector_store(engine_config.clone(), store)
+                .await
+                .unwrap();
+            engine
+                .index_document(
+                    format!("{}-doc", agent_id),
+                    content.to_string(),
+                    crate::types::EntityType::Document,
+                    HashMap::new(),
+                )
+                .await
+                .unwrap();
+            orchestrator.register_engine(agent_id, Arc::new(engine));
+        }
+        hanging.hang.store(true, std::sync::atomic::Ordering::Relaxed);
+        (orchestrator, hanging)
+    }
+
+    #[tokio::test]
+    async fn test_federated_search_returns_partial_results_on_timeout() {
+        let lead = "lead".to_string();
+        let timeouts = [
+            // The agent timeout fires first
+            (Some(100), Some(5_000)),
+            // No agent timeout, so the deadline bounds the call
+            (None, Some(150)),
+        ];
+        for (agent_timeout_ms, deadline_ms) in timeouts {
+            let config = FederatedSearchConfig {
+                agent_timeout_ms,
+                deadline_ms,
+                ..Default::default()
+            };
+            let (orchestrator, _) = orchestrator_with_hanging_agent(config).await;
+
+            let start = Instant::now();
+            let (results, stats) = orchestrator
+                .federated_search(&lead, "release notes", 10, None, SearchPriority::Normal)
+                .await
+                .unwrap();
+
+            assert!(start.elapsed() < Duration::from_secs(2));
+            assert_eq!(stats.agents_queried, 2);
+            assert_eq!(stats.agents_timed_out, vec!["agent2".to_string()]);
+            assert!(!stats.results_per_agent.contains_key("agent2"));
+            assert_eq!(results.len(), 1);
+            assert_eq!(results[0].indexed_by.as_deref(), Some("agent1"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_timed_out_agent_retried_in_background() {
+        let config = FederatedSearchConfig {
+            agent_timeout_ms: Some(50),
+            timeout_retry: TimeoutRetryConfig {
+                enabled: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (orchestrator, hanging) = orchestrator_with_hanging_agent(config).await;
+        let lead = "lead".to_string();
+
+        let (_, stats) = orchestrator
+            .federated_search(&lead, "design", 10, None, SearchPriority::Normal)
+            .await
+            .unwrap();
+        assert_eq!(stats.agents_timed_out, vec!["agent2".to_string()]);
+
+        // The retry finishes once the store recovers
+        hanging.hang.store(false, std::sync::atomic::Ordering::Relaxed);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        hanging.hang.store(true, std::sync::atomic::Ordering::Relaxed);
+
+        // The next identical query uses the retried results without
+        // waiting on agent2 again
+        let (results, stats) = orchestrator
+            .federated_search(&lead, "design", 10, None, SearchPriority::Normal)
+            .await
+            .unwrap();
+        assert!(stats.agents_timed_out.is_empty());
+        assert_eq!(stats.agents_from_retry, vec!["agent2".to_string()]);
+        assert!(results.iter().any(|r| r.indexed_by.as_deref() == Some("agent2")));
+
+        // Retried results are served once
+        let (_, stats) = orchestrator
+            .federated_search(&lead, "design", 10, None, SearchPriority::Normal)
+            .await
+            .unwrap();
+        assert_eq!(stats.agents_timed_out, vec!["agent2".to_string()]);
+    }
 }
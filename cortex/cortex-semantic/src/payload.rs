@@ -0,0 +1,107 @@
+//! Payload fields returned inline with search results.
+//!
+//! Callers name the fields they need with each search instead of fetching
+//! documents after it. A byte limit keeps large fields from bloating
+//! responses: text fields are cut to fit it, and results that lost data
+//! say so.
+
+use serde_json::{Map, Value};
+
+/// Select `fields` through `lookup`, in order, within `max_bytes`.
+///
+/// The size of a payload is the length of its field names plus the UTF-8
+/// length of string values, or the serialized length of other values.
+/// Fields earlier in `fields` get the budget first; a string that does not
+/// fit is cut at a character boundary, any other value that does not fit is
+/// left out. Returns the payload object and whether anything was cut or
+/// left out. Missing fields are skipped without counting as truncation.
+pub(crate) fn select_payload(
+    fields: &[String],
+    max_bytes: Option<usize>,
+    mut lookup: impl FnMut(&str) -> Option<Value>,
+) -> (Value, bool) {
+    let mut remaining = max_bytes.unwrap_or(usize::MAX);
+    let mut truncated = false;
+    let mut payload = Map::new();
+
+    for field in fields {
+        if payload.contains_key(field) {
+            continue;
+        }
+        let Some(value) = lookup(field) else {
+            continue;
+        };
+
+        let budget = remaining.saturating_sub(field.len());
+        let value = match value {
+            Value::String(text) if text.len() > budget => {
+                truncated = true;
+                Value::String(truncate_to(&text, budget).to_string())
+            }
+            value if value_size(&value) > budget => {
+                truncated = true;
+                continue;
+            }
+            value => value,
+        };
+        remaining = budget - value_size(&value);
+        payload.insert(field.clone(), value);
+    }
+
+    (Value::Object(payload), truncated)
+}
+
+fn value_size(value: &Value) -> usize {
+    match value {
+        Value::String(text) => text.len(),
+        value => value.to_string().len(),
+    }
+}
+
+/// The longest prefix of `text` of at most `max_len` bytes.
+fn truncate_to(text: &str, max_len: usize) -> &str {
+    let mut end = max_len.min(text.len());
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn fields(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_select_payload_within_limit() {
+        let source = json!({ "name": "parse", "line": 42, "content": "fn parse() {}" });
+        let lookup = |field: &str| source.get(field).cloned();
+
+        let (payload, truncated) = select_payload(&fields(&["name", "content", "missing"]), None, lookup);
+        assert_eq!(payload, json!({ "name": "parse", "content": "fn parse() {}" }));
+        assert!(!truncated);
+
+        // "name" + "parse" and "line" + "42" take 15 of the 20 bytes, too
+        // few for any of the value of "content"
+        let (payload, truncated) = select_payload(&fields(&["name", "line", "content"]), Some(20), lookup);
+        assert_eq!(payload, json!({ "name": "parse", "line": 42, "content": "" }));
+        assert!(truncated);
+
+        let (payload, truncated) = select_payload(&fields(&["content", "line"]), Some(22), lookup);
+        assert_eq!(payload, json!({ "content": "fn parse() {}" }));
+        assert!(truncated);
+    }
+
+    #[test]
+    fn test_truncation_respects_char_boundaries() {
+        let lookup = |_: &str| Some(json!("héllo"));
+        // "text" leaves 2 bytes, which would split "é"
+        let (payload, truncated) = select_payload(&fields(&["text"]), Some(6), lookup);
+        assert_eq!(payload, json!({ "text": "h" }));
+        assert!(truncated);
+    }
+}
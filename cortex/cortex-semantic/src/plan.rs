@@ -0,0 +1,485 @@
+//! Execution of decomposed queries.
+//!
+//! [`QueryDecomposer`](crate::query::QueryDecomposer) splits a complex query
+//! into sub-queries and a dependency graph. [`QueryPlan`] validates that
+//! graph, and [`QueryPlanExecutor`] runs it against a search engine: each
+//! sub-query starts as soon as the sub-queries it depends on have finished,
+//! so independent ones run concurrently, and their results feed into the
+//! dependent sub-queries as context.
+
+use crate::error::{Result, SemanticError};
+use crate::filter::FilterExpr;
+use crate::query::{QueryDependencyGraph, SubQuery};
+use crate::search::{SearchFilter, SearchResult, SemanticSearchEngine};
+use crate::types::DocumentId;
+use futures::stream::{FuturesUnordered, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::time::Instant;
+use tokio::sync::Semaphore;
+use tracing::debug;
+
+/// Words of each prerequisite result appended to a dependent sub-query
+const CONTEXT_WORDS: usize = 24;
+
+/// How results of prerequisite sub-queries inform the sub-queries that
+/// depend on them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PrerequisiteContext {
+    /// Run every sub-query as written
+    None,
+    /// Append the start of the top prerequisite results to the sub-query,
+    /// so its embedding reflects what the earlier steps found
+    #[default]
+    Expand,
+    /// Only match documents sharing a value of this metadata field with the
+    /// top prerequisite results, e.g. `file_path`
+    Narrow { field: String },
+}
+
+/// Query plan execution settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryPlanConfig {
+    /// Most sub-queries searched, and so embedded, at the same time
+    pub max_parallelism: usize,
+    /// Results fetched per sub-query
+    pub results_per_sub_query: usize,
+    /// Top results of each prerequisite passed on as context
+    pub context_results: usize,
+    pub context: PrerequisiteContext,
+    /// Filter every sub-query is searched with
+    #[serde(default)]
+    pub filter: SearchFilter,
+}
+
+impl Default for QueryPlanConfig {
+    fn default() -> Self {
+        Self {
+            max_parallelism: 4,
+            results_per_sub_query: 10,
+            context_results: 3,
+            context: PrerequisiteContext::default(),
+            filter: SearchFilter::default(),
+        }
+    }
+}
+
+/// Sub-queries with validated dependencies, ready to execute.
+#[derive(Debug, Clone)]
+pub struct QueryPlan {
+    sub_queries: Vec<SubQuery>,
+    /// Prerequisites of each sub-query
+    dependencies: Vec<BTreeSet<usize>>,
+    /// Sub-queries depending on each sub-query
+    dependents: Vec<Vec<usize>>,
+}
+
+impl QueryPlan {
+    /// Plan the execution of decomposed sub-queries. A sub-query depends on
+    /// the ones listed in its `dependencies` and on those with an edge to it
+    /// in `graph`.
+    ///
+    /// Fails with `SemanticError::QueryPlanCycle` when the dependencies form
+    /// a cycle, and with `SemanticError::Query` when one refers to a
+    /// sub-query that does not exist.
+    pub fn new(sub_queries: Vec<SubQuery>, graph: &QueryDependencyGraph) -> Result<Self> {
+        let count = sub_queries.len();
+        let check = |index: usize| {
+            if index < count {
+                Ok(index)
+            } else {
+                Err(SemanticError::Query(format!(
+                    "Query plan refers to sub-query {} of {}",
+                    index, count
+                )))
+            }
+        };
+
+        let mut dependencies: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); count];
+        for (index, sub_query) in sub_queries.iter().enumerate() {
+            for &dependency in &sub_query.dependencies {
+                dependencies[index].insert(check(dependency)?);
+            }
+        }
+        for (&dependency, dependents) in &graph.edges {
+            let dependency = check(dependency)?;
+            for &dependent in dependents {
+                dependencies[check(dependent)?].insert(dependency);
+            }
+        }
+
+        let mut dependents = vec![Vec::new(); count];
+        for (index, prerequisites) in dependencies.iter().enumerate() {
+            for &dependency in prerequisites {
+                dependents[dependency].push(index);
+            }
+        }
+
+        // Kahn's algorithm; whatever it cannot order is on or behind a cycle
+        let mut remaining: Vec<usize> = dependencies.iter().map(BTreeSet::len).collect();
+        let mut ready: Vec<usize> = (0..count).filter(|&index| remaining[index] == 0).collect();
+        let mut ordered = 0;
+        while let Some(index) = ready.pop() {
+            ordered += 1;
+            for &dependent in &dependents[index] {
+                remaining[dependent] -= 1;
+                if remaining[dependent] == 0 {
+                    ready.push(dependent);
+                }
+            }
+        }
+        if ordered < count {
+            let sub_queries = (0..count).filter(|&index| remaining[index] > 0).collect();
+            return Err(SemanticError::QueryPlanCycle { sub_queries });
+        }
+
+        Ok(Self {
+            sub_queries,
+            dependencies,
+            dependents,
+        })
+    }
+
+    pub fn sub_queries(&self) -> &[SubQuery] {
+        &self.sub_queries
+    }
+
+    /// Sub-queries `index` waits for.
+    pub fn dependencies(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        self.dependencies[index].iter().copied()
+    }
+}
+
+/// How one sub-query was searched.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubQueryOutcome {
+    /// Position of the sub-query in the plan
+    pub index: usize,
+    /// Query text searched, including any prerequisite context
+    pub query: String,
+    /// Sub-queries whose results informed this one
+    pub context_from: Vec<usize>,
+    /// Results in score order, each with `sub_query` set to `index`
+    pub results: Vec<SearchResult>,
+    pub duration_ms: u64,
+}
+
+/// Results of an executed query plan.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanResults {
+    /// Results of all sub-queries, each document once with its best score,
+    /// in score order; `sub_query` tells which sub-query produced it
+    pub results: Vec<SearchResult>,
+    /// Outcome of each sub-query, by position in the plan
+    pub sub_queries: Vec<SubQueryOutcome>,
+}
+
+/// Runs query plans against a search engine.
+///
+/// # Example
+/// ```no_run
+/// use cortex_semantic::plan::{QueryPlan, QueryPlanExecutor};
+/// use cortex_semantic::query::{QueryDecomposer, QueryIntent};
+/// # use cortex_semantic::search::SemanticSearchEngine;
+///
+/// # async fn example(engine: &SemanticSearchEngine) -> cortex_semantic::Result<()> {
+/// let (sub_queries, graph) = QueryDecomposer::new().decompose(
+///     "Find the user model and then the code that validates it",
+///     &QueryIntent::Code,
+/// );
+/// if let Some(graph) = graph {
+///     let plan = QueryPlan::new(sub_queries, &graph)?;
+///     let results = QueryPlanExecutor::default().execute(&plan, engine).await?;
+///     for result in results.results {
+///         println!("{} from sub-query {:?}", result.id, result.sub_query);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct QueryPlanExecutor {
+    config: QueryPlanConfig,
+}
+
+impl QueryPlanExecutor {
+    pub fn new(config: QueryPlanConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn config(&self) -> &QueryPlanConfig {
+        &self.config
+    }
+
+    /// Execute a plan. Sub-queries start once all their prerequisites have
+    /// finished, at most `max_parallelism` at a time; the first failing
+    /// sub-query fails the plan.
+    pub async fn execute(&self, plan: &QueryPlan, engine: &SemanticSearchEngine) -> Result<PlanResults> {
+        let count = plan.sub_queries.len();
+        let permits = Semaphore::new(self.config.max_parallelism.max(1));
+        let mut remaining: Vec<usize> = plan.dependencies.iter().map(BTreeSet::len).collect();
+        let mut outcomes: Vec<Option<SubQueryOutcome>> = vec![None; count];
+        let mut running = FuturesUnordered::new();
+
+        let mut ready: Vec<usize> = (0..count).filter(|&index| remaining[index] == 0).collect();
+        loop {
+            for index in ready.drain(..) {
+                let (query, filter, context_from) = self.prepare(plan, index, &outcomes);
+                let permits = &permits;
+                running.push(async move {
+                    let _permit = permits.acquire().await.expect("Semaphore closed unexpectedly");
+                    let start = Instant::now();
+                    debug!("Running sub-query {}: {}", index, query);
+                    let results = engine
+                        .search_with_filter(&query, self.config.results_per_sub_query, filter)
+                        .await;
+                    (index, query, context_from, results, start.elapsed().as_millis() as u64)
+                });
+            }
+
+            let Some((index, query, context_from, results, duration_ms)) = running.next().await else {
+                break;
+            };
+            let mut results = results?;
+            for result in &mut results {
+                result.sub_query = Some(index);
+            }
+            outcomes[index] = Some(SubQueryOutcome {
+                index,
+                query,
+                context_from,
+                results,
+                duration_ms,
+            });
+
+            for &dependent in &plan.dependents[index] {
+                remaining[dependent] -= 1;
+                if remaining[dependent] == 0 {
+                    ready.push(dependent);
+                }
+            }
+        }
+
+        // Planning rejected cycles, so every sub-query ran
+        let sub_queries: Vec<SubQueryOutcome> = outcomes.into_iter().flatten().collect();
+        Ok(PlanResults {
+            results: merge_results(&sub_queries),
+            sub_queries,
+        })
+    }
+
+    /// Query text, filter and context sources of a sub-query whose
+    /// prerequisites have finished.
+    fn prepare(
+        &self,
+        plan: &QueryPlan,
+        index: usize,
+        outcomes: &[Option<SubQueryOutcome>],
+    ) -> (String, SearchFilter, Vec<usize>) {
+        let mut query = plan.sub_queries[index].text.clone();
+        let mut filter = self.config.filter.clone();
+        let context_from: Vec<usize> = match self.config.context {
+            PrerequisiteContext::None => Vec::new(),
+            _ => plan.dependencies(index).collect(),
+        };
+        let context = context_from
+            .iter()
+            .filter_map(|&dependency| outcomes[dependency].as_ref())
+            .flat_map(|outcome| outcome.results.iter().take(self.config.context_results));
+
+        match &self.config.context {
+            PrerequisiteContext::None => {}
+            PrerequisiteContext::Expand => {
+                for result in context {
+                    let words: Vec<&str> = result.content.split_whitespace().take(CONTEXT_WORDS).collect();
+                    if !words.is_empty() {
+                        query.push(' ');
+                        query.push_str(&words.join(" "));
+                    }
+                }
+            }
+            PrerequisiteContext::Narrow { field } => {
+                let values: BTreeSet<&String> = context.filter_map(|result| result.metadata.get(field)).collect();
+                if !values.is_empty() {
+                    let narrowed = FilterExpr::is_in(field.as_str(), values.into_iter().cloned());
+                    filter.expr = Some(match filter.expr.take() {
+                        Some(expr) => FilterExpr::and([expr, narrowed]),
+                        None => narrowed,
+                    });
+                }
+            }
+        }
+
+        (query, filter, context_from)
+    }
+}
+
+/// Results of all sub-queries, each document once with its best score.
+fn merge_results(outcomes: &[SubQueryOutcome]) -> Vec<SearchResult> {
+    let mut best: HashMap<&DocumentId, &SearchResult> = HashMap::new();
+    for result in outcomes.iter().flat_map(|outcome| &outcome.results) {
+        let entry = best.entry(&result.id).or_insert(result);
+        if result.score > entry.score {
+            *entry = result;
+        }
+    }
+
+    let mut merged: Vec<SearchResult> = best.into_values().cloned().collect();
+    merged.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SemanticConfig;
+    use crate::providers::{EmbeddingProvider, MockProvider, ProviderManager};
+    use crate::qdrant::{MockVectorStore, VectorIndex};
+    use crate::query::{AnswerType, QueryDecomposer, QueryIntent};
+    use crate::types::{EntityType, SimilarityMetric};
+    use std::sync::Arc;
+
+    fn sub_query(text: &str, dependencies: Vec<usize>) -> SubQuery {
+        SubQuery {
+            text: text.to_string(),
+            priority: 0,
+            dependencies,
+            expected_type: AnswerType::Fact,
+        }
+    }
+
+    fn no_edges() -> QueryDependencyGraph {
+        QueryDependencyGraph {
+            edges: HashMap::new(),
+            execution_order: Vec::new(),
+        }
+    }
+
+    async fn engine_with(mock: &MockProvider) -> SemanticSearchEngine {
+        let provider = Arc::new(ProviderManager::new(
+            vec![("mock".to_string(), Box::new(mock.clone()) as Box<dyn EmbeddingProvider>)],
+            Default::default(),
+        ));
+        let store: Arc<dyn VectorIndex> = Arc::new(MockVectorStore::new(384, SimilarityMetric::Cosine));
+        let engine = SemanticSearchEngine::with_provider(SemanticConfig::default(), provider, store)
+            .await
+            .unwrap();
+        for (id, content, file) in [
+            ("model", "struct User with email and password hash", "src/user.rs"),
+            ("validate", "fn validate_user checks the email format", "src/user.rs"),
+            ("router", "fn route requests to handlers", "src/router.rs"),
+        ] {
+            let metadata = HashMap::from([("file_path".to_string(), file.to_string())]);
+            engine
+                .index_document(id.to_string(), content.to_string(), EntityType::Code, metadata)
+                .await
+                .unwrap();
+        }
+        engine
+    }
+
+    fn plan_config(context: PrerequisiteContext) -> QueryPlanConfig {
+        QueryPlanConfig {
+            context,
+            context_results: 1,
+            filter: SearchFilter {
+                min_score: Some(-1.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_plan_rejects_cycles_and_unknown_sub_queries() {
+        let sub_queries = vec![
+            sub_query("a", vec![]),
+            sub_query("b", vec![2]),
+            sub_query("c", vec![1]),
+        ];
+        let err = QueryPlan::new(sub_queries.clone(), &no_edges()).unwrap_err();
+        assert!(matches!(err, SemanticError::QueryPlanCycle { ref sub_queries } if sub_queries == &[1, 2]));
+
+        // Edges of the graph count as dependencies too
+        let graph = QueryDependencyGraph {
+            edges: HashMap::from([(0, vec![1])]),
+            execution_order: Vec::new(),
+        };
+        let err = QueryPlan::new(vec![sub_query("a", vec![1]), sub_query("b", vec![])], &graph).unwrap_err();
+        assert!(matches!(err, SemanticError::QueryPlanCycle { .. }));
+
+        let err = QueryPlan::new(vec![sub_query("a", vec![5])], &no_edges()).unwrap_err();
+        assert!(matches!(err, SemanticError::Query(_)));
+
+        let (sub_queries, graph) = QueryDecomposer::new().decompose(
+            "Implement authentication and then integrate it with the database",
+            &QueryIntent::Code,
+        );
+        let plan = QueryPlan::new(sub_queries, &graph.unwrap()).unwrap();
+        assert_eq!(plan.dependencies(1).collect::<Vec<_>>(), vec![0]);
+    }
+
+    #[tokio::test]
+    async fn test_execute_feeds_prerequisites_into_dependents() {
+        let mock = MockProvider::new(384).with_seed(3);
+        let engine = engine_with(&mock).await;
+
+        let sub_queries = vec![
+            sub_query("user model", vec![]),
+            sub_query("request routing", vec![]),
+            sub_query("validation", vec![0, 1]),
+        ];
+        let plan = QueryPlan::new(sub_queries, &no_edges()).unwrap();
+        let executor = QueryPlanExecutor::new(plan_config(PrerequisiteContext::Expand));
+        let results = executor.execute(&plan, &engine).await.unwrap();
+
+        assert_eq!(results.sub_queries.len(), 3);
+        let first = &results.sub_queries[0];
+        assert_eq!(first.query, "user model");
+        assert!(first.results.iter().all(|r| r.sub_query == Some(0)));
+
+        // The dependent sub-query searched with its prerequisites' top results
+        let dependent = &results.sub_queries[2];
+        assert_eq!(dependent.context_from, vec![0, 1]);
+        let expected = format!(
+            "validation {} {}",
+            first.results[0].content,
+            results.sub_queries[1].results[0].content
+        );
+        assert_eq!(dependent.query, expected);
+
+        // Merged results hold each document once, with its provenance
+        assert_eq!(results.results.len(), 3);
+        assert!(results.results.iter().all(|r| r.sub_query.is_some()));
+        assert!(results.results.windows(2).all(|w| w[0].score >= w[1].score));
+    }
+
+    #[tokio::test]
+    async fn test_execute_narrows_dependents_to_prerequisite_metadata() {
+        let mock = MockProvider::new(384).with_seed(5);
+        let engine = engine_with(&mock).await;
+
+        let plan = QueryPlan::new(
+            vec![sub_query("user model", vec![]), sub_query("functions", vec![0])],
+            &no_edges(),
+        )
+        .unwrap();
+        let field = "file_path".to_string();
+        let mut config = plan_config(PrerequisiteContext::Narrow { field: field.clone() });
+        config.max_parallelism = 1;
+        let results = QueryPlanExecutor::new(config).execute(&plan, &engine).await.unwrap();
+
+        // Only documents from the file the prerequisite found are searched
+        let file = &results.sub_queries[0].results[0].metadata[&field];
+        let dependent = &results.sub_queries[1];
+        assert_eq!(dependent.query, "functions");
+        assert!(!dependent.results.is_empty());
+        assert!(dependent.results.iter().all(|r| &r.metadata[&field] == file));
+    }
+}
@@ -1,14 +1,23 @@
 //! Embedding providers for generating vector embeddings.
 
-use crate::config::{EmbeddingProviderConfig, OpenAIConfig, ONNXConfig, OllamaConfig};
+use crate::config::{
+    CircuitBreakerConfig, EmbeddingProviderConfig, OnnxExecutionProvider, OnnxQuantization, OpenAIConfig, ONNXConfig,
+    OllamaConfig,
+};
+use crate::agent::AgentId;
 use crate::error::{Result, SemanticError};
+use crate::ranking::Reranker;
+use crate::session_pool::SessionPool;
 use crate::types::{EmbeddingModel, Vector};
 use async_trait::async_trait;
 use parking_lot::RwLock;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
-use std::time::Duration;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 use tracing::{debug, info, warn};
 
 /// Trait for embedding providers.
@@ -27,27 +36,270 @@ pub trait EmbeddingProvider: Send + Sync {
     fn dimension(&self) -> usize {
         self.model().dimension
     }
+
+    /// Runtime information: execution provider, quantization and latency.
+    fn provider_info(&self) -> ProviderInfo {
+        ProviderInfo::new(self.model().clone())
+    }
+
+    /// Tokens billed for the embeddings generated so far. Providers that
+    /// aren't billed per token report zero.
+    fn usage_stats(&self) -> EmbeddingUsage {
+        EmbeddingUsage::default()
+    }
+
+    /// Register a callback invoked with the usage of every embedded batch.
+    fn set_usage_callback(&self, _callback: UsageCallback) {}
+}
+
+/// Runtime information about an embedding provider.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProviderInfo {
+    pub model: EmbeddingModel,
+    /// Execution provider running inference locally, e.g. `cuda`
+    pub execution_provider: Option<String>,
+    pub quantized: bool,
+    /// Deterministic mock embeddings instead of a real model
+    pub mock: bool,
+    /// Embedding calls made, single or batched
+    pub calls: u64,
+    pub avg_latency_ms: f64,
+    pub last_latency_ms: f64,
+    /// Time the warm-up inference took at construction
+    pub warmup_ms: Option<f64>,
+    /// Version of the serving process, for server-backed providers
+    #[serde(default)]
+    pub server_version: Option<String>,
+    /// Models the server currently keeps loaded
+    #[serde(default)]
+    pub loaded_models: Vec<String>,
+}
+
+impl ProviderInfo {
+    pub fn new(model: EmbeddingModel) -> Self {
+        Self {
+            model,
+            execution_provider: None,
+            quantized: false,
+            mock: false,
+            calls: 0,
+            avg_latency_ms: 0.0,
+            last_latency_ms: 0.0,
+            warmup_ms: None,
+            server_version: None,
+            loaded_models: Vec::new(),
+        }
+    }
+}
+
+/// Accumulated token usage of an embedding provider.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmbeddingUsage {
+    /// Requests that returned embeddings
+    pub requests: u64,
+    /// Texts embedded
+    pub inputs: u64,
+    pub prompt_tokens: u64,
+    pub total_tokens: u64,
+}
+
+impl EmbeddingUsage {
+    fn add(&mut self, other: &EmbeddingUsage) {
+        self.requests += other.requests;
+        self.inputs += other.inputs;
+        self.prompt_tokens += other.prompt_tokens;
+        self.total_tokens += other.total_tokens;
+    }
+}
+
+/// Token usage of one embedded batch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchUsage {
+    /// Agent the batch was embedded for, see [`attribute_usage_to`]
+    pub agent_id: Option<AgentId>,
+    pub model: String,
+    pub inputs: usize,
+    pub prompt_tokens: u64,
+    pub total_tokens: u64,
+}
+
+/// Callback receiving the usage of each embedded batch.
+pub type UsageCallback = Arc<dyn Fn(&BatchUsage) + Send + Sync>;
+
+tokio::task_local! {
+    static USAGE_AGENT: AgentId;
+}
+
+/// Run `future` with embedding usage attributed to `agent_id`: batches
+/// embedded while it runs report the agent in [`BatchUsage::agent_id`].
+///
+/// Attribution follows the task, so work spawned onto other tasks inside
+/// `future` is not attributed.
+pub async fn attribute_usage_to<F: Future>(agent_id: AgentId, future: F) -> F::Output {
+    USAGE_AGENT.scope(agent_id, future).await
+}
+
+fn usage_agent() -> Option<AgentId> {
+    USAGE_AGENT.try_with(|agent_id| agent_id.clone()).ok()
+}
+
+/// Token counters of a provider.
+#[derive(Debug, Default)]
+struct UsageCounters {
+    requests: AtomicU64,
+    inputs: AtomicU64,
+    prompt_tokens: AtomicU64,
+    total_tokens: AtomicU64,
+}
+
+impl UsageCounters {
+    fn record(&self, usage: &BatchUsage) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        self.inputs.fetch_add(usage.inputs as u64, Ordering::Relaxed);
+        self.prompt_tokens.fetch_add(usage.prompt_tokens, Ordering::Relaxed);
+        self.total_tokens.fetch_add(usage.total_tokens, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self) -> EmbeddingUsage {
+        EmbeddingUsage {
+            requests: self.requests.load(Ordering::Relaxed),
+            inputs: self.inputs.load(Ordering::Relaxed),
+            prompt_tokens: self.prompt_tokens.load(Ordering::Relaxed),
+            total_tokens: self.total_tokens.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Per-call latency of a provider.
+#[derive(Debug, Default)]
+struct LatencyTracker {
+    calls: AtomicU64,
+    total_us: AtomicU64,
+    last_us: AtomicU64,
+}
+
+impl LatencyTracker {
+    fn record(&self, elapsed: Duration) {
+        let us = elapsed.as_micros() as u64;
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        self.total_us.fetch_add(us, Ordering::Relaxed);
+        self.last_us.store(us, Ordering::Relaxed);
+    }
+
+    fn fill(&self, info: &mut ProviderInfo) {
+        let calls = self.calls.load(Ordering::Relaxed);
+        info.calls = calls;
+        info.last_latency_ms = self.last_us.load(Ordering::Relaxed) as f64 / 1000.0;
+        info.avg_latency_ms = if calls == 0 {
+            0.0
+        } else {
+            self.total_us.load(Ordering::Relaxed) as f64 / calls as f64 / 1000.0
+        };
+    }
+}
+
+/// Health of one provider in a fallback chain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProviderHealth {
+    /// Provider name from the configuration
+    pub provider: String,
+    pub model: String,
+    pub dimension: usize,
+    /// Whether its embeddings have the primary provider's dimension. A
+    /// provider that doesn't is never used as a fallback.
+    pub dimension_compatible: bool,
+    pub consecutive_failures: u32,
+    pub total_failures: u64,
+    pub last_error: Option<String>,
+    /// Time left before a provider skipped for failing is tried again
+    pub circuit_open_for: Option<Duration>,
+}
+
+impl ProviderHealth {
+    /// Whether the provider is currently used for embedding requests.
+    pub fn is_available(&self) -> bool {
+        self.dimension_compatible && self.circuit_open_for.is_none()
+    }
+}
+
+/// Failure tracking of a provider in a fallback chain.
+#[derive(Debug, Default)]
+struct HealthState {
+    consecutive_failures: u32,
+    total_failures: u64,
+    last_error: Option<String>,
+    circuit_open_until: Option<Instant>,
+}
+
+/// A provider of a fallback chain with its health.
+struct ChainedProvider {
+    name: String,
+    provider: Box<dyn EmbeddingProvider>,
+    health: parking_lot::Mutex<HealthState>,
 }
 
 /// Provider manager that handles fallback chains.
+///
+/// Providers are tried in order, starting with the primary. A provider that
+/// fails `failure_threshold` times in a row is skipped until its cool-down
+/// elapses. The primary provider's dimension is canonical: a fallback that
+/// produces a different dimension is never used, since its vectors can't be
+/// compared with the ones already indexed.
 pub struct ProviderManager {
-    primary: Box<dyn EmbeddingProvider>,
-    fallbacks: Vec<Box<dyn EmbeddingProvider>>,
+    /// The primary provider first, then the fallbacks in order
+    chain: Vec<ChainedProvider>,
+    circuit_breaker: CircuitBreakerConfig,
 }
 
 impl ProviderManager {
     pub async fn from_config(config: &EmbeddingProviderConfig) -> Result<Self> {
         let primary = Self::create_provider(&config.primary_provider, config).await?;
+        let mut providers = vec![(config.primary_provider.clone(), primary)];
 
-        let mut fallbacks = Vec::new();
         for provider_name in &config.fallback_providers {
             match Self::create_provider(provider_name, config).await {
-                Ok(provider) => fallbacks.push(provider),
+                Ok(provider) => providers.push((provider_name.clone(), provider)),
                 Err(e) => warn!("Failed to create fallback provider {}: {}", provider_name, e),
             }
         }
 
-        Ok(Self { primary, fallbacks })
+        Ok(Self::new(providers, config.circuit_breaker.clone()))
+    }
+
+    /// Chain `providers` in order; the first is the primary.
+    ///
+    /// # Panics
+    ///
+    /// If `providers` is empty.
+    pub fn new(
+        providers: Vec<(String, Box<dyn EmbeddingProvider>)>,
+        circuit_breaker: CircuitBreakerConfig,
+    ) -> Self {
+        assert!(!providers.is_empty(), "a provider chain needs a primary provider");
+
+        let dimension = providers[0].1.dimension();
+        for (name, provider) in &providers[1..] {
+            if provider.dimension() != dimension {
+                warn!(
+                    "Fallback provider {} produces {}-dimensional embeddings but the primary produces {}; it will not be used",
+                    name,
+                    provider.dimension(),
+                    dimension
+                );
+            }
+        }
+
+        Self {
+            chain: providers
+                .into_iter()
+                .map(|(name, provider)| ChainedProvider {
+                    name,
+                    provider,
+                    health: parking_lot::Mutex::new(HealthState::default()),
+                })
+                .collect(),
+            circuit_breaker,
+        }
     }
 
     async fn create_provider(
@@ -55,65 +307,220 @@ impl ProviderManager {
         config: &EmbeddingProviderConfig,
     ) -> Result<Box<dyn EmbeddingProvider>> {
         match name.to_lowercase().as_str() {
-            "openai" => Ok(Box::new(OpenAIProvider::new(config.openai.clone()).await?)),
+            "openai" => Ok(Box::new(
+                OpenAIProvider::with_dimensions(config.openai.clone(), config.dimensions).await?,
+            )),
             "onnx" => Ok(Box::new(ONNXProvider::new(config.onnx.clone()).await?)),
             "ollama" => Ok(Box::new(OllamaProvider::new(config.ollama.clone()).await?)),
             "mock" => Ok(Box::new(MockProvider::new(384))),
             _ => Err(SemanticError::Provider(format!("Unknown provider: {}", name))),
         }
     }
-}
 
-#[async_trait]
-impl EmbeddingProvider for ProviderManager {
-    async fn embed(&self, text: &str) -> Result<Vector> {
-        // Try primary provider
-        match self.primary.embed(text).await {
-            Ok(embedding) => return Ok(embedding),
-            Err(e) => warn!("Primary provider failed: {}", e),
+    fn primary(&self) -> &dyn EmbeddingProvider {
+        self.chain[0].provider.as_ref()
+    }
+
+    /// Health of every provider in the chain, primary first.
+    pub fn health(&self) -> Vec<ProviderHealth> {
+        let now = Instant::now();
+        let dimension = self.primary().dimension();
+        self.chain
+            .iter()
+            .map(|entry| {
+                let health = entry.health.lock();
+                ProviderHealth {
+                    provider: entry.name.clone(),
+                    model: entry.provider.model().model_name.clone(),
+                    dimension: entry.provider.dimension(),
+                    dimension_compatible: entry.provider.dimension() == dimension,
+                    consecutive_failures: health.consecutive_failures,
+                    total_failures: health.total_failures,
+                    last_error: health.last_error.clone(),
+                    circuit_open_for: health
+                        .circuit_open_until
+                        .filter(|until| *until > now)
+                        .map(|until| until - now),
+                }
+            })
+            .collect()
+    }
+
+    /// Send a probe request to every provider, including ones whose circuit
+    /// is open, and return the resulting health.
+    pub async fn check_health(&self) -> Vec<ProviderHealth> {
+        let dimension = self.primary().dimension();
+        for entry in &self.chain {
+            let outcome = entry
+                .provider
+                .embed("provider health probe")
+                .await
+                .and_then(|vector| Self::check_dimension(entry, dimension, vector.len()));
+            self.record(entry, outcome.as_ref().map(|_| ()));
         }
+        self.health()
+    }
 
-        // Try fallback providers
-        for (i, fallback) in self.fallbacks.iter().enumerate() {
-            match fallback.embed(text).await {
-                Ok(embedding) => {
-                    info!("Fallback provider {} succeeded", i);
-                    return Ok(embedding);
+    fn check_dimension(entry: &ChainedProvider, expected: usize, got: usize) -> Result<()> {
+        if got == expected {
+            return Ok(());
+        }
+        Err(SemanticError::ProviderDimensionMismatch {
+            provider: entry.name.clone(),
+            expected,
+            got,
+        })
+    }
+
+    /// Whether a provider's circuit is closed, or its cool-down has elapsed
+    /// so it may be tried again.
+    fn is_callable(entry: &ChainedProvider) -> bool {
+        entry
+            .health
+            .lock()
+            .circuit_open_until
+            .is_none_or(|until| Instant::now() >= until)
+    }
+
+    fn record(&self, entry: &ChainedProvider, outcome: std::result::Result<(), &SemanticError>) {
+        let mut health = entry.health.lock();
+        match outcome {
+            Ok(()) => {
+                if health.circuit_open_until.take().is_some() {
+                    info!("Embedding provider {} recovered", entry.name);
+                }
+                health.consecutive_failures = 0;
+            }
+            Err(e) => {
+                health.consecutive_failures += 1;
+                health.total_failures += 1;
+                health.last_error = Some(e.to_string());
+                // A provider that asked us to back off is skipped right away
+                if let Some(retry_after) = e.retry_after() {
+                    health.circuit_open_until = Some(Instant::now() + retry_after);
+                } else if health.consecutive_failures >= self.circuit_breaker.failure_threshold.max(1) {
+                    let cooldown = self.circuit_breaker.cooldown();
+                    warn!(
+                        "Embedding provider {} failed {} times in a row, skipping it for {:?}",
+                        entry.name, health.consecutive_failures, cooldown
+                    );
+                    health.circuit_open_until = Some(Instant::now() + cooldown);
                 }
-                Err(e) => warn!("Fallback provider {} failed: {}", i, e),
             }
         }
-
-        Err(SemanticError::Provider(
-            "All providers failed".to_string(),
-        ))
     }
 
-    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vector>> {
-        // Try primary provider
-        match self.primary.embed_batch(texts).await {
-            Ok(embeddings) => return Ok(embeddings),
-            Err(e) => warn!("Primary provider batch failed: {}", e),
-        }
-
-        // Try fallback providers
-        for (i, fallback) in self.fallbacks.iter().enumerate() {
-            match fallback.embed_batch(texts).await {
-                Ok(embeddings) => {
-                    info!("Fallback provider {} succeeded for batch", i);
-                    return Ok(embeddings);
+    /// Run `embed` against the first healthy provider of the chain that
+    /// succeeds, recording the outcome of every attempt.
+    async fn with_fallback<'a, T, F, Fut>(&'a self, operation: &str, embed: F) -> Result<T>
+    where
+        F: Fn(&'a dyn EmbeddingProvider) -> Fut,
+        Fut: Future<Output = Result<(T, Vec<usize>)>>,
+    {
+        let dimension = self.primary().dimension();
+        let mut errors = Vec::new();
+        let mut mismatch = None;
+
+        for (i, entry) in self.chain.iter().enumerate() {
+            if entry.provider.dimension() != dimension {
+                mismatch.get_or_insert(SemanticError::ProviderDimensionMismatch {
+                    provider: entry.name.clone(),
+                    expected: dimension,
+                    got: entry.provider.dimension(),
+                });
+                continue;
+            }
+            if !Self::is_callable(entry) {
+                debug!("Skipping unhealthy embedding provider {}", entry.name);
+                continue;
+            }
+
+            let outcome = embed(entry.provider.as_ref()).await.and_then(|(value, dims)| {
+                for got in dims {
+                    Self::check_dimension(entry, dimension, got)?;
+                }
+                Ok(value)
+            });
+            self.record(entry, outcome.as_ref().map(|_| ()));
+
+            match outcome {
+                Ok(value) => {
+                    if i > 0 {
+                        info!("Fallback provider {} succeeded for {}", entry.name, operation);
+                    }
+                    return Ok(value);
+                }
+                Err(e) => {
+                    warn!("Provider {} failed for {}: {}", entry.name, operation, e);
+                    if matches!(e, SemanticError::ProviderDimensionMismatch { .. }) {
+                        mismatch.get_or_insert(e);
+                    } else {
+                        errors.push((entry.name.clone(), e));
+                    }
                 }
-                Err(e) => warn!("Fallback provider {} batch failed: {}", i, e),
             }
         }
 
-        Err(SemanticError::Provider(
-            "All providers failed for batch".to_string(),
-        ))
+        // A mismatch is what kept a fallback from standing in
+        if let Some(mismatch) = mismatch {
+            return Err(mismatch);
+        }
+        if errors.is_empty() {
+            let retry_in = self
+                .health()
+                .iter()
+                .filter_map(|health| health.circuit_open_for)
+                .min()
+                .unwrap_or_default();
+            return Err(SemanticError::ProvidersCoolingDown { retry_after: retry_in });
+        }
+        Err(SemanticError::ProvidersExhausted {
+            operation: operation.to_string(),
+            failures: errors,
+        })
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for ProviderManager {
+    async fn embed(&self, text: &str) -> Result<Vector> {
+        self.with_fallback("embedding", |provider| async move {
+            let embedding = provider.embed(text).await?;
+            let dims = vec![embedding.len()];
+            Ok((embedding, dims))
+        })
+        .await
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vector>> {
+        self.with_fallback("batch", |provider| async move {
+            let embeddings = provider.embed_batch(texts).await?;
+            let dims = embeddings.iter().map(Vec::len).collect();
+            Ok((embeddings, dims))
+        })
+        .await
     }
 
     fn model(&self) -> &EmbeddingModel {
-        self.primary.model()
+        self.primary().model()
+    }
+
+    fn provider_info(&self) -> ProviderInfo {
+        self.primary().provider_info()
+    }
+
+    fn usage_stats(&self) -> EmbeddingUsage {
+        let mut usage = EmbeddingUsage::default();
+        for entry in &self.chain {
+            usage.add(&entry.provider.usage_stats());
+        }
+        usage
+    }
+
+    fn set_usage_callback(&self, callback: UsageCallback) {
+        for entry in &self.chain {
+            entry.provider.set_usage_callback(callback.clone());
+        }
     }
 }
 
@@ -122,17 +529,25 @@ pub struct OpenAIProvider {
     client: Client,
     config: OpenAIConfig,
     model: EmbeddingModel,
+    /// `dimensions` sent with each request, for models that shorten vectors
+    request_dimensions: Option<usize>,
+    usage: UsageCounters,
+    usage_callback: RwLock<Option<UsageCallback>>,
 }
 
 #[derive(Serialize)]
 struct OpenAIRequest {
     input: Vec<String>,
     model: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dimensions: Option<usize>,
 }
 
 #[derive(Deserialize)]
 struct OpenAIResponse {
     data: Vec<OpenAIEmbedding>,
+    #[serde(default)]
+    usage: OpenAIUsage,
 }
 
 #[derive(Deserialize)]
@@ -140,12 +555,34 @@ struct OpenAIEmbedding {
     embedding: Vec<f32>,
 }
 
+#[derive(Debug, Default, Deserialize)]
+struct OpenAIUsage {
+    #[serde(default)]
+    prompt_tokens: u64,
+    #[serde(default)]
+    total_tokens: u64,
+}
+
 impl OpenAIProvider {
     pub async fn new(config: OpenAIConfig) -> Result<Self> {
+        Self::with_dimensions(config, None).await
+    }
+
+    /// Create a provider requesting `dimensions`-long vectors, falling back
+    /// to `config.dimension`. Fails if the model can't produce that size.
+    pub async fn with_dimensions(config: OpenAIConfig, dimensions: Option<usize>) -> Result<Self> {
         let api_key = config.api_key.clone().ok_or_else(|| {
             SemanticError::Config("OpenAI API key not configured".to_string())
         })?;
 
+        let requested = dimensions.or(config.dimension);
+        let request_dimensions = match requested {
+            Some(requested) => {
+                Self::check_dimensions(&config.model, requested, dimensions.is_some())?
+            }
+            None => None,
+        };
+
         // Build headers with proper error handling
         let mut headers = reqwest::header::HeaderMap::new();
 
@@ -164,25 +601,176 @@ impl OpenAIProvider {
             .default_headers(headers)
             .build()?;
 
-        let dimension = config.dimension.unwrap_or_else(|| {
-            match config.model.as_str() {
-                "text-embedding-3-small" => 1536,
-                "text-embedding-3-large" => 3072,
-                "text-embedding-ada-002" => 1536,
-                _ => 1536,
-            }
+        let dimension = requested.unwrap_or_else(|| {
+            Self::native_dimension(&config.model).map_or(1536, |(native, _)| native)
         });
 
         let model = EmbeddingModel::new("openai", &config.model, dimension);
 
-        info!("Initialized OpenAI provider with model: {}", config.model);
+        info!(
+            "Initialized OpenAI provider with model: {} ({} dimensions)",
+            config.model, dimension
+        );
 
         Ok(Self {
             client,
             config,
             model,
+            request_dimensions,
+            usage: UsageCounters::default(),
+            usage_callback: RwLock::new(None),
         })
     }
+
+    /// Native vector size of a known model, and whether the model accepts
+    /// the `dimensions` parameter to return shorter vectors.
+    fn native_dimension(model: &str) -> Option<(usize, bool)> {
+        match model {
+            "text-embedding-3-small" => Some((1536, true)),
+            "text-embedding-3-large" => Some((3072, true)),
+            "text-embedding-ada-002" => Some((1536, false)),
+            _ => None,
+        }
+    }
+
+    /// Validate a requested vector size against the model and return the
+    /// `dimensions` to send. Unknown models aren't validated; they get the
+    /// parameter only when it was set `explicitly` rather than through the
+    /// legacy `dimension` override.
+    fn check_dimensions(model: &str, requested: usize, explicit: bool) -> Result<Option<usize>> {
+        match Self::native_dimension(model) {
+            Some((native, true)) if (1..=native).contains(&requested) => Ok(Some(requested)),
+            Some((native, true)) => Err(SemanticError::Config(format!(
+                "OpenAI model {} supports 1 to {} dimensions, got {}",
+                model, native, requested
+            ))),
+            Some((native, false)) if requested == native => Ok(None),
+            Some((native, false)) => Err(SemanticError::Config(format!(
+                "OpenAI model {} always returns {} dimensions and cannot return {}",
+                model, native, requested
+            ))),
+            None if requested == 0 => Err(SemanticError::Config(
+                "OpenAI dimensions must be greater than 0".to_string(),
+            )),
+            None => Ok(explicit.then_some(requested)),
+        }
+    }
+
+    fn record_usage(&self, inputs: usize, usage: &OpenAIUsage) {
+        let batch = BatchUsage {
+            agent_id: usage_agent(),
+            model: self.config.model.clone(),
+            inputs,
+            prompt_tokens: usage.prompt_tokens,
+            total_tokens: usage.total_tokens,
+        };
+        self.usage.record(&batch);
+
+        let callback = self.usage_callback.read().clone();
+        if let Some(callback) = callback {
+            callback(&batch);
+        }
+    }
+}
+
+/// Wait a rate-limited response asks for: `Retry-After` in seconds or as an
+/// HTTP date, else the longer of OpenAI's `x-ratelimit-reset-requests` and
+/// `x-ratelimit-reset-tokens` (durations like `1m30s` or `250ms`).
+fn retry_after_header(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok()).map(str::trim);
+
+    if let Some(value) = header(reqwest::header::RETRY_AFTER.as_str()) {
+        if let Ok(seconds) = value.parse() {
+            return Some(Duration::from_secs(seconds));
+        }
+        if let Ok(date) = chrono::DateTime::parse_from_rfc2822(value) {
+            return Some((date.with_timezone(&chrono::Utc) - chrono::Utc::now()).to_std().unwrap_or_default());
+        }
+    }
+
+    ["x-ratelimit-reset-requests", "x-ratelimit-reset-tokens"]
+        .into_iter()
+        .filter_map(|name| header(name).and_then(parse_reset_duration))
+        .max()
+}
+
+/// Parse a rate-limit reset duration such as `20s`, `1m30s`, `6m0.5s` or
+/// `250ms`.
+fn parse_reset_duration(value: &str) -> Option<Duration> {
+    let mut total = Duration::ZERO;
+    let mut rest = value;
+    while !rest.is_empty() {
+        let number_end = rest.find(|c: char| !c.is_ascii_digit() && c != '.')?;
+        let number: f64 = rest[..number_end].parse().ok()?;
+        rest = &rest[number_end..];
+        let unit_end = rest.find(|c: char| c.is_ascii_digit()).unwrap_or(rest.len());
+        let seconds = match &rest[..unit_end] {
+            "ms" => number / 1_000.0,
+            "s" => number,
+            "m" => number * 60.0,
+            "h" => number * 3_600.0,
+            _ => return None,
+        };
+        total += Duration::try_from_secs_f64(seconds).ok()?;
+        rest = &rest[unit_end..];
+    }
+    Some(total)
+}
+
+/// Map a transport error, naming the endpoint when nothing listens there.
+fn transport_error(provider: &str, endpoint: &str, hint: &str, error: reqwest::Error) -> SemanticError {
+    if error.is_connect() {
+        SemanticError::ProviderUnavailable {
+            provider: provider.to_string(),
+            endpoint: endpoint.to_string(),
+            hint: hint.to_string(),
+        }
+    } else {
+        SemanticError::Http(error)
+    }
+}
+
+/// Map an unsuccessful embedding API response to a typed error.
+fn provider_http_error(
+    provider: &str,
+    status: reqwest::StatusCode,
+    retry_after: Option<Duration>,
+    body: &str,
+) -> SemanticError {
+    // OpenAI-style bodies: {"error": {"message": ..., "type": ..., "code": ...}}
+    let error = serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|value| value.get("error").cloned());
+    let field = |name: &str| {
+        error
+            .as_ref()
+            .and_then(|e| e.get(name))
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+    };
+    let message = field("message").unwrap_or_else(|| body.to_string());
+    let quota_exhausted = field("code").or_else(|| field("type")).as_deref() == Some("insufficient_quota");
+
+    match status.as_u16() {
+        429 if quota_exhausted => SemanticError::ProviderQuotaExceeded {
+            provider: provider.to_string(),
+            message,
+        },
+        429 => SemanticError::ProviderRateLimited {
+            provider: provider.to_string(),
+            retry_after,
+        },
+        401 | 403 => SemanticError::ProviderAuth {
+            provider: provider.to_string(),
+            message,
+        },
+        code if status.is_server_error() => SemanticError::ProviderServerError {
+            provider: provider.to_string(),
+            status: code,
+            message,
+        },
+        _ => SemanticError::Provider(format!("{} API error ({}): {}", provider, status, message)),
+    }
 }
 
 #[async_trait]
@@ -198,6 +786,7 @@ impl EmbeddingProvider for OpenAIProvider {
         let request = OpenAIRequest {
             input: texts.to_vec(),
             model: self.config.model.clone(),
+            dimensions: self.request_dimensions,
         };
 
         let response = self
@@ -205,17 +794,18 @@ impl EmbeddingProvider for OpenAIProvider {
             .post(&self.config.endpoint)
             .json(&request)
             .send()
-            .await?;
+            .await
+            .map_err(|e| transport_error("OpenAI", &self.config.endpoint, "check the endpoint and network access", e))?;
 
-        if !response.status().is_success() {
+        let status = response.status();
+        if !status.is_success() {
+            let retry_after = retry_after_header(response.headers());
             let error_text = response.text().await?;
-            return Err(SemanticError::Provider(format!(
-                "OpenAI API error: {}",
-                error_text
-            )));
+            return Err(provider_http_error("OpenAI", status, retry_after, &error_text));
         }
 
         let response: OpenAIResponse = response.json().await?;
+        self.record_usage(texts.len(), &response.usage);
 
         let embeddings = response
             .data
@@ -231,12 +821,27 @@ impl EmbeddingProvider for OpenAIProvider {
             )));
         }
 
+        if let Some(embedding) = embeddings.iter().find(|e| e.len() != self.model.dimension) {
+            return Err(SemanticError::DimensionMismatch {
+                expected: self.model.dimension,
+                got: embedding.len(),
+            });
+        }
+
         Ok(embeddings)
     }
 
     fn model(&self) -> &EmbeddingModel {
         &self.model
     }
+
+    fn usage_stats(&self) -> EmbeddingUsage {
+        self.usage.snapshot()
+    }
+
+    fn set_usage_callback(&self, callback: UsageCallback) {
+        *self.usage_callback.write() = Some(callback);
+    }
 }
 
 /// ONNX Runtime embedding provider for local models.
@@ -254,71 +859,145 @@ impl EmbeddingProvider for OpenAIProvider {
 pub struct ONNXProvider {
     model: EmbeddingModel,
     dimension: usize,
-    session: Option<Arc<RwLock<ort::Session>>>,
-    tokenizer: Option<Arc<tokenizers::Tokenizer>>,
-    #[allow(dead_code)]  // Keep environment alive for the session
-    environment: Option<Arc<ort::Environment>>,
-    use_mock: bool,
+    /// Loaded model, shared with inference tasks; `None` with mock
+    /// embeddings
+    runtime: Option<Arc<OnnxRuntime>>,
+    /// Execution provider the sessions run on
+    execution_provider: Option<OnnxExecutionProvider>,
+    quantized: bool,
+    warmup_ms: Option<f64>,
+    latency: LatencyTracker,
+}
+
+/// A model session with what was learned while loading it.
+struct LoadedModel {
+    environment: ort::Environment,
+    /// Sessions of the same model, at least one
+    sessions: Vec<ort::Session>,
+    tokenizer: tokenizers::Tokenizer,
+    execution_provider: OnnxExecutionProvider,
+    quantized: bool,
+    /// Embedding size declared by the model output, unless dynamic
+    output_dimension: Option<usize>,
+    /// Whether the model input takes more than one sequence at a time
+    batched_input: bool,
+}
+
+/// A loaded embedding model and the pool of sessions running it.
+struct OnnxRuntime {
+    model_name: String,
+    dimension: usize,
+    // Declared before the environment so the sessions are released first
+    sessions: SessionPool<ort::Session>,
+    tokenizer: tokenizers::Tokenizer,
+    #[allow(dead_code)]  // Keep environment alive for the sessions
+    environment: ort::Environment,
     /// Maximum batch size for inference (prevents OOM errors)
     max_batch_size: usize,
+    /// Maximum padded tokens in one inference call
+    max_batch_tokens: usize,
     /// Maximum sequence length supported by the model
     max_seq_length: usize,
 }
 
 impl ONNXProvider {
+    /// Create the provider, loading the model from `config.model_path`.
+    ///
+    /// A missing or unloadable model falls back to mock embeddings, but a
+    /// model whose output size differs from `config.dimension` is an error.
     pub async fn new(config: ONNXConfig) -> Result<Self> {
         info!("Initializing ONNX provider with model: {}", config.model_name);
 
         // Try to load ONNX model and tokenizer
-        let (session, tokenizer, environment, use_mock) = if let Some(model_path) = &config.model_path {
+        let loaded = if let Some(model_path) = &config.model_path {
             let path_str = model_path.to_string_lossy().to_string();
-            match Self::load_model(&path_str).await {
-                Ok((env, sess, tok)) => {
+            let pool_size = config.resolved_session_pool_size();
+            match Self::load_model(&path_str, &config, pool_size).await {
+                Ok(loaded) => {
                     info!("ONNX model loaded successfully from: {}", path_str);
-                    (
-                        Some(Arc::new(RwLock::new(sess))),
-                        Some(Arc::new(tok)),
-                        Some(Arc::new(env)),
-                        false
-                    )
+                    Some(loaded)
                 }
                 Err(e) => {
                     warn!("Failed to load ONNX model: {}. Using mock embeddings.", e);
-                    (None, None, None, true)
+                    None
                 }
             }
         } else {
             info!("No model path provided. Using mock embeddings for testing.");
-            (None, None, None, true)
+            None
         };
 
-        Ok(Self {
+        if let Some(got) = loaded.as_ref().and_then(|l| l.output_dimension) {
+            if got != config.dimension {
+                return Err(Self::dimension_error(&config.model_name, got, config.dimension));
+            }
+        }
+
+        let mut provider = Self {
             model: EmbeddingModel::new("onnx", &config.model_name, config.dimension),
             dimension: config.dimension,
-            session,
-            tokenizer,
-            environment,
-            use_mock,
-            // Optimal batch size balancing memory and throughput
-            // For 384-dim models: ~32 provides good balance
-            // For 768-dim models: ~16-24 is better
-            // For 1536+ dim: ~8-16 recommended
-            max_batch_size: if config.dimension <= 384 { 32 } else if config.dimension <= 768 { 24 } else { 16 },
+            execution_provider: loaded.as_ref().map(|l| l.execution_provider),
+            quantized: loaded.as_ref().is_some_and(|l| l.quantized),
+            runtime: None,
+            warmup_ms: None,
+            latency: LatencyTracker::default(),
+        };
+        let Some(loaded) = loaded else {
+            return Ok(provider);
+        };
+
+        // Optimal batch size balancing memory and throughput
+        // For 384-dim models: ~32 provides good balance
+        // For 768-dim models: ~16-24 is better
+        // For 1536+ dim: ~8-16 recommended
+        let max_batch_size = if config.dimension <= 384 { 32 } else if config.dimension <= 768 { 24 } else { 16 };
+        let max_batch_size = if !loaded.batched_input {
+            1
+        } else if loaded.quantized {
+            // int8 activations take a quarter of the memory, so larger
+            // batches fit
+            max_batch_size * 2
+        } else {
+            max_batch_size
+        };
+
+        let runtime = OnnxRuntime {
+            model_name: config.model_name.clone(),
+            dimension: config.dimension,
+            sessions: SessionPool::new(loaded.sessions),
+            tokenizer: loaded.tokenizer,
+            environment: loaded.environment,
+            max_batch_size,
+            max_batch_tokens: config.max_batch_tokens,
             // Most sentence transformer models use 512 max sequence length
             max_seq_length: 512,
-        })
+        };
+
+        // Warm-up also catches models with a dynamic output size that
+        // doesn't match the configured dimension
+        if config.warmup {
+            let start = Instant::now();
+            let encodings = runtime.tokenize(&["warm-up".to_string()])?;
+            runtime.infer(&encodings)?;
+            let warmup_ms = start.elapsed().as_secs_f64() * 1000.0;
+            info!("ONNX model warmed up in {:.1}ms", warmup_ms);
+            provider.warmup_ms = Some(warmup_ms);
+        }
+
+        provider.runtime = Some(Arc::new(runtime));
+        Ok(provider)
     }
 
-    async fn load_model(
-        model_path: &str,
-    ) -> Result<(ort::Environment, ort::Session, tokenizers::Tokenizer)> {
+    /// Load `pool_size` sessions of the model at `model_path` and its
+    /// tokenizer.
+    async fn load_model(model_path: &str, config: &ONNXConfig, pool_size: usize) -> Result<LoadedModel> {
         use std::path::Path;
 
         let model_path_obj = Path::new(model_path);
 
         // Check if model file exists
         if !model_path_obj.exists() {
-            return Err(SemanticError::Provider(format!(
+            return Err(SemanticError::ModelNotLoaded(format!(
                 "ONNX model file not found: {}. Please download all-MiniLM-L6-v2 ONNX model.",
                 model_path
             )));
@@ -333,14 +1012,62 @@ impl ONNXProvider {
 
         info!("ONNX Runtime environment created");
 
+        // Register the preferred execution providers; ONNX Runtime falls
+        // back along the list, so the first available one is active
+        let requested = config.resolved_execution_providers();
+        let execution_provider = requested
+            .iter()
+            .copied()
+            .find(|ep| Self::ort_execution_provider(*ep).is_available())
+            .unwrap_or(OnnxExecutionProvider::Cpu);
+        let execution_providers: Vec<ort::ExecutionProvider> = requested
+            .iter()
+            .map(|ep| Self::ort_execution_provider(*ep))
+            .collect();
+
+        // Sessions run at the same time, so they share out the cores
+        let pool_size = pool_size.max(1);
+        let intra_threads = config.resolved_intra_op_threads(pool_size);
+        let inter_threads = config.inter_op_threads.unwrap_or(1);
+
         // Load ONNX model using ort 1.16 API
         // Use SessionBuilder::new() followed by with_model_from_file()
-        let session = ort::SessionBuilder::new(&environment)?
-            .with_optimization_level(ort::GraphOptimizationLevel::Level3)?
-            .with_intra_threads(4)?
-            .with_model_from_file(model_path)?;
-
-        info!("ONNX session created successfully from: {}", model_path);
+        let sessions = (0..pool_size)
+            .map(|_| {
+                ort::SessionBuilder::new(&environment)?
+                    .with_optimization_level(ort::GraphOptimizationLevel::Level3)?
+                    .with_execution_providers(execution_providers.clone())?
+                    .with_intra_threads(i16::try_from(intra_threads).unwrap_or(i16::MAX))?
+                    .with_inter_threads(i16::try_from(inter_threads).unwrap_or(i16::MAX))?
+                    .with_parallel_execution(inter_threads > 1)?
+                    .with_model_from_file(model_path)
+            })
+            .collect::<std::result::Result<Vec<_>, ort::OrtError>>()?;
+        let session = &sessions[0];
+
+        let quantized = Self::is_quantized(session, model_path_obj, config.quantization);
+        let output_dimension = session
+            .outputs
+            .first()
+            .and_then(|output| output.dimensions.last().copied().flatten())
+            .map(|d| d as usize);
+        // Models exported with a fixed batch dimension of 1 take one text
+        // per inference call
+        let batched_input = session
+            .inputs
+            .first()
+            .and_then(|input| input.dimensions.first().copied().flatten())
+            != Some(1);
+
+        info!(
+            "ONNX sessions created successfully from: {} (execution provider: {}, quantized: {}, sessions: {}, threads: {}/{})",
+            model_path,
+            execution_provider.as_str(),
+            quantized,
+            pool_size,
+            intra_threads,
+            inter_threads
+        );
 
         // Load tokenizer
         // Look for tokenizer.json in the same directory as the model
@@ -351,7 +1078,7 @@ impl ONNXProvider {
         let tokenizer_path = model_dir.join("tokenizer.json");
 
         if !tokenizer_path.exists() {
-            return Err(SemanticError::Provider(format!(
+            return Err(SemanticError::ModelNotLoaded(format!(
                 "Tokenizer file not found: {}. Please ensure tokenizer.json is in the same directory as the model.",
                 tokenizer_path.display()
             )));
@@ -366,63 +1093,193 @@ impl ONNXProvider {
         let env = Arc::try_unwrap(environment)
             .unwrap_or_else(|arc| (*arc).clone());
 
-        Ok((env, session, tokenizer))
+        Ok(LoadedModel {
+            environment: env,
+            sessions,
+            tokenizer,
+            execution_provider,
+            quantized,
+            output_dimension,
+            batched_input,
+        })
     }
 
-    fn generate_embedding_real(&self, text: &str) -> Result<Vector> {
-        // Use batch inference with single text for consistency
-        let result = self.generate_embeddings_batch_real(&[text])?;
-        Ok(result.into_iter().next().unwrap())
+    fn ort_execution_provider(ep: OnnxExecutionProvider) -> ort::ExecutionProvider {
+        match ep {
+            OnnxExecutionProvider::Cpu => ort::ExecutionProvider::CPU(Default::default()),
+            OnnxExecutionProvider::Cuda => ort::ExecutionProvider::CUDA(Default::default()),
+            OnnxExecutionProvider::CoreML => ort::ExecutionProvider::CoreML(Default::default()),
+        }
     }
 
-    /// Generate embeddings for a batch of texts using true batch inference.
-    ///
-    /// This method:
-    /// 1. Tokenizes all texts together with padding to max length
-    /// 2. Executes a single ONNX inference call with batched inputs
-    /// 3. Extracts individual embeddings from batched output
-    /// 4. Normalizes each embedding
-    fn generate_embeddings_batch_real(&self, texts: &[&str]) -> Result<Vec<Vector>> {
-        if texts.is_empty() {
-            return Ok(Vec::new());
+    /// Whether the model has int8 weights, from the config or, with `auto`,
+    /// from its file name or `quantization` metadata.
+    fn is_quantized(
+        session: &ort::Session,
+        model_path: &std::path::Path,
+        setting: OnnxQuantization,
+    ) -> bool {
+        match setting {
+            OnnxQuantization::Fp32 => false,
+            OnnxQuantization::Int8 => true,
+            OnnxQuantization::Auto => {
+                let file_name = model_path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_lowercase())
+                    .unwrap_or_default();
+                if file_name.contains("int8") || file_name.contains("quant") {
+                    return true;
+                }
+                session
+                    .metadata()
+                    .ok()
+                    .and_then(|metadata| metadata.custom("quantization").ok().flatten())
+                    .is_some_and(|value| value.to_lowercase().contains("int8"))
+            }
         }
+    }
 
-        // Validate that we have session and tokenizer
-        let session = self.session.as_ref().ok_or_else(|| {
-            SemanticError::Provider("ONNX session not initialized".to_string())
-        })?;
-
-        let tokenizer = self.tokenizer.as_ref().ok_or_else(|| {
-            SemanticError::Provider("Tokenizer not initialized".to_string())
-        })?;
+    fn dimension_error(model_name: &str, got: usize, expected: usize) -> SemanticError {
+        SemanticError::Config(format!(
+            "ONNX model {} produces {}-dimensional embeddings but the configured dimension is {}",
+            model_name, got, expected
+        ))
+    }
 
-        let batch_size = texts.len();
+    /// Normalize an embedding vector using L2 normalization.
+    fn normalize_embedding(embedding: Vec<f32>) -> Vec<f32> {
+        let norm: f32 = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+        if norm > 1e-12 {
+            embedding.iter().map(|x| x / norm).collect()
+        } else {
+            embedding
+        }
+    }
 
-        // Tokenize all texts with padding and truncation
-        // Use encode_batch for true batch tokenization
-        let encodings = tokenizer
-            .encode_batch(texts.to_vec(), true)
-            .map_err(|e| SemanticError::Provider(format!("Batch tokenization failed: {}", e)))?;
+    fn generate_mock_embedding(&self, text: &str) -> Vector {
+        // Deterministic mock embedding for testing
+        // Uses text hash to create reproducible vectors
+        let hash = text.bytes().fold(0u64, |acc, b| acc.wrapping_mul(31).wrapping_add(b as u64));
 
-        // Find the maximum sequence length in this batch
-        let max_len = encodings
-            .iter()
-            .map(|e| e.get_ids().len())
-            .max()
-            .unwrap_or(0);
+        let mut embedding = vec![0.0; self.dimension];
+        for (i, val) in embedding.iter_mut().enumerate() {
+            let seed = hash.wrapping_add(i as u64);
+            *val = ((seed % 1000) as f32 / 1000.0) - 0.5;
+        }
 
-        // Clamp to model's maximum sequence length
-        let max_len = max_len.min(self.max_seq_length);
+        // Normalize
+        let norm = (embedding.iter().map(|x| x * x).sum::<f32>()).sqrt();
+        if norm > 0.0 {
+            embedding.iter_mut().for_each(|x| *x /= norm);
+        }
 
-        // Build batched input tensors with padding
-        let mut batch_input_ids = Vec::with_capacity(batch_size * max_len);
-        let mut batch_attention_mask = Vec::with_capacity(batch_size * max_len);
+        embedding
+    }
+}
 
-        for encoding in &encodings {
-            let token_ids = encoding.get_ids();
-            let attention_mask = encoding.get_attention_mask();
+impl OnnxRuntime {
+    /// Tokenize all texts in one call; the tokenizer spreads them over its
+    /// own threads.
+    fn tokenize(&self, texts: &[String]) -> Result<Vec<tokenizers::Encoding>> {
+        let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+        self.tokenizer
+            .encode_batch(texts, true)
+            .map_err(|e| SemanticError::Provider(format!("Batch tokenization failed: {}", e)))
+    }
 
-            // Truncate if needed
+    /// Embed `texts` on up to `sessions.size()` sessions at once.
+    ///
+    /// This method:
+    /// 1. Tokenizes all texts together
+    /// 2. Splits them into batches by count and padded length
+    /// 3. Runs each batch as a single inference call on a pooled session
+    /// 4. Reassembles the embeddings in input order
+    async fn embed(self: Arc<Self>, texts: Vec<String>) -> Result<Vec<Vector>> {
+        // Tokenization and inference block, so they run off the async runtime
+        let runtime = self.clone();
+        let encodings = tokio::task::spawn_blocking(move || runtime.tokenize(&texts))
+            .await
+            .map_err(Self::task_error)??;
+
+        let lengths: Vec<usize> = encodings
+            .iter()
+            .map(|encoding| encoding.get_ids().len().min(self.max_seq_length))
+            .collect();
+        let batches = plan_batches(&lengths, self.max_batch_size, self.max_batch_tokens);
+        let workers = self.sessions.size().min(batches.len());
+        debug!(
+            "Processing {} texts in {} batches on {} sessions",
+            encodings.len(),
+            batches.len(),
+            workers
+        );
+
+        let encodings = Arc::new(encodings);
+        let batches = Arc::new(batches);
+        let tasks: Vec<_> = (0..workers)
+            .map(|worker| {
+                let runtime = self.clone();
+                let encodings = encodings.clone();
+                let batches = batches.clone();
+                // Each worker runs every `workers`-th batch, so the batches
+                // spread evenly over the sessions
+                tokio::task::spawn_blocking(move || {
+                    batches
+                        .iter()
+                        .enumerate()
+                        .skip(worker)
+                        .step_by(workers)
+                        .map(|(index, range)| Ok((index, runtime.infer(&encodings[range.clone()])?)))
+                        .collect::<Result<Vec<_>>>()
+                })
+            })
+            .collect();
+
+        let mut embedded = Vec::with_capacity(batches.len());
+        for task in tasks {
+            embedded.extend(task.await.map_err(Self::task_error)??);
+        }
+        embedded.sort_by_key(|(index, _)| *index);
+        Ok(embedded.into_iter().flat_map(|(_, vectors)| vectors).collect())
+    }
+
+    fn task_error(err: tokio::task::JoinError) -> SemanticError {
+        SemanticError::Provider(format!("ONNX inference task failed: {}", err))
+    }
+
+    /// Embed one batch of tokenized texts in a single inference call.
+    ///
+    /// This method:
+    /// 1. Pads all sequences to the longest one in the batch
+    /// 2. Executes a single ONNX inference call on an idle pooled session
+    /// 3. Extracts individual embeddings from batched output
+    /// 4. Normalizes each embedding
+    fn infer(&self, encodings: &[tokenizers::Encoding]) -> Result<Vec<Vector>> {
+        if encodings.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let batch_size = encodings.len();
+
+        // Find the maximum sequence length in this batch
+        let max_len = encodings
+            .iter()
+            .map(|e| e.get_ids().len())
+            .max()
+            .unwrap_or(0);
+
+        // Clamp to model's maximum sequence length
+        let max_len = max_len.min(self.max_seq_length);
+
+        // Build batched input tensors with padding
+        let mut batch_input_ids = Vec::with_capacity(batch_size * max_len);
+        let mut batch_attention_mask = Vec::with_capacity(batch_size * max_len);
+
+        for encoding in encodings {
+            let token_ids = encoding.get_ids();
+            let attention_mask = encoding.get_attention_mask();
+
+            // Truncate if needed
             let seq_len = token_ids.len().min(max_len);
 
             // Add tokens (truncated)
@@ -438,10 +1295,6 @@ impl ONNXProvider {
             }
         }
 
-        // Get allocator for creating ONNX values
-        let session_guard = session.read();
-        let allocator_ptr = session_guard.allocator();
-
         // Create batched ndarray tensors
         use ndarray::{Array, CowArray, IxDyn};
 
@@ -459,11 +1312,15 @@ impl ONNXProvider {
         let input_ids_cow: CowArray<i64, IxDyn> = CowArray::from(input_ids_array);
         let attention_mask_cow: CowArray<i64, IxDyn> = CowArray::from(attention_mask_array);
 
+        // Wait for an idle session; it returns to the pool when dropped
+        let session = self.sessions.checkout();
+        let allocator_ptr = session.allocator();
+
         let input_ids_value = ort::Value::from_array(allocator_ptr, &input_ids_cow)?;
         let attention_mask_value = ort::Value::from_array(allocator_ptr, &attention_mask_cow)?;
 
         // Run batched inference - single ONNX call for all texts
-        let outputs = session_guard.run(vec![input_ids_value, attention_mask_value])?;
+        let outputs = session.run(vec![input_ids_value, attention_mask_value])?;
 
         // Extract embeddings from batched output
         let output_tensor = &outputs[0];
@@ -495,7 +1352,7 @@ impl ONNXProvider {
                 let embedding: Vec<f32> = pooled.into_raw_vec();
 
                 // L2 normalize
-                let normalized = Self::normalize_embedding(embedding);
+                let normalized = ONNXProvider::normalize_embedding(embedding);
                 results.push(normalized);
             }
         } else if shape.len() == 2 {
@@ -505,7 +1362,7 @@ impl ONNXProvider {
                 let embedding: Vec<f32> = batch_item.iter().copied().collect();
 
                 // L2 normalize
-                let normalized = Self::normalize_embedding(embedding);
+                let normalized = ONNXProvider::normalize_embedding(embedding);
                 results.push(normalized);
             }
         } else {
@@ -515,6 +1372,12 @@ impl ONNXProvider {
             )));
         }
 
+        if let Some(got) = results.first().map(|v| v.len()) {
+            if got != self.dimension {
+                return Err(ONNXProvider::dimension_error(&self.model_name, got, self.dimension));
+            }
+        }
+
         debug!(
             "Generated {} embeddings with dimension {} via batch inference",
             results.len(),
@@ -523,48 +1386,49 @@ impl ONNXProvider {
 
         Ok(results)
     }
+}
 
-    /// Normalize an embedding vector using L2 normalization.
-    fn normalize_embedding(embedding: Vec<f32>) -> Vec<f32> {
-        let norm: f32 = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
-        if norm > 1e-12 {
-            embedding.iter().map(|x| x / norm).collect()
-        } else {
-            embedding
+/// Split texts of the given token lengths into consecutive batches of at
+/// most `max_batch_size` texts and, padded to their longest text, at most
+/// `max_batch_tokens` tokens. A text longer than the token limit gets a
+/// batch of its own.
+fn plan_batches(lengths: &[usize], max_batch_size: usize, max_batch_tokens: usize) -> Vec<std::ops::Range<usize>> {
+    let mut batches = Vec::new();
+    let mut start = 0;
+    let mut longest = 0;
+
+    for (index, &length) in lengths.iter().enumerate() {
+        let count = index - start + 1;
+        let padded = count * longest.max(length);
+        if count > 1 && (count > max_batch_size.max(1) || padded > max_batch_tokens) {
+            batches.push(start..index);
+            start = index;
+            longest = 0;
         }
+        longest = longest.max(length);
     }
-
-    fn generate_mock_embedding(&self, text: &str) -> Vector {
-        // Deterministic mock embedding for testing
-        // Uses text hash to create reproducible vectors
-        let hash = text.bytes().fold(0u64, |acc, b| acc.wrapping_mul(31).wrapping_add(b as u64));
-
-        let mut embedding = vec![0.0; self.dimension];
-        for (i, val) in embedding.iter_mut().enumerate() {
-            let seed = hash.wrapping_add(i as u64);
-            *val = ((seed % 1000) as f32 / 1000.0) - 0.5;
-        }
-
-        // Normalize
-        let norm = (embedding.iter().map(|x| x * x).sum::<f32>()).sqrt();
-        if norm > 0.0 {
-            embedding.iter_mut().for_each(|x| *x /= norm);
-        }
-
-        embedding
+    if start < lengths.len() {
+        batches.push(start..lengths.len());
     }
+    batches
 }
 
 #[async_trait]
 impl EmbeddingProvider for ONNXProvider {
     async fn embed(&self, text: &str) -> Result<Vector> {
-        if self.use_mock {
-            // Use deterministic mock embeddings for testing
-            Ok(self.generate_mock_embedding(text))
-        } else {
+        let start = Instant::now();
+        let result = match &self.runtime {
             // Use real ONNX embeddings
-            self.generate_embedding_real(text)
-        }
+            Some(runtime) => runtime
+                .clone()
+                .embed(vec![text.to_string()])
+                .await
+                .map(|vectors| vectors.into_iter().next().unwrap_or_default()),
+            // Use deterministic mock embeddings for testing
+            None => Ok(self.generate_mock_embedding(text)),
+        };
+        self.latency.record(start.elapsed());
+        result
     }
 
     async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vector>> {
@@ -572,38 +1436,183 @@ impl EmbeddingProvider for ONNXProvider {
             return Ok(Vec::new());
         }
 
-        if self.use_mock {
-            // Mock batch processing
-            Ok(texts.iter().map(|text| self.generate_mock_embedding(text)).collect())
-        } else {
+        let start = Instant::now();
+        let result = match &self.runtime {
             // Real ONNX batch processing with automatic batch splitting
-            debug!("Processing {} texts with batch inference", texts.len());
+            Some(runtime) => runtime.clone().embed(texts.to_vec()).await,
+            // Mock batch processing
+            None => Ok(texts.iter().map(|text| self.generate_mock_embedding(text)).collect()),
+        };
+        self.latency.record(start.elapsed());
+        result
+    }
 
-            let mut all_embeddings = Vec::with_capacity(texts.len());
+    fn model(&self) -> &EmbeddingModel {
+        &self.model
+    }
 
-            // Split into optimal-sized batches to prevent OOM errors
-            for chunk in texts.chunks(self.max_batch_size) {
-                // Convert String slice to &str slice for batch processing
-                let text_refs: Vec<&str> = chunk.iter().map(|s| s.as_str()).collect();
+    fn provider_info(&self) -> ProviderInfo {
+        let mut info = ProviderInfo::new(self.model.clone());
+        info.execution_provider = self.execution_provider.map(|ep| ep.as_str().to_string());
+        info.quantized = self.quantized;
+        info.mock = self.runtime.is_none();
+        info.warmup_ms = self.warmup_ms;
+        self.latency.fill(&mut info);
+        info
+    }
+}
 
-                // Execute true batch inference for this chunk
-                let chunk_embeddings = self.generate_embeddings_batch_real(&text_refs)?;
+/// Cross-encoder reranker running a local ONNX model, such as
+/// cross-encoder/ms-marco-MiniLM-L-6-v2, that reads a query and a document
+/// together and outputs a relevance logit.
+///
+/// The model and its `tokenizer.json` are loaded like `ONNXProvider` loads
+/// embedding models, but there is no mock fallback: a missing model is an
+/// error. Use `MockReranker` in tests.
+pub struct OnnxCrossEncoder {
+    session: Arc<RwLock<ort::Session>>,
+    tokenizer: Arc<tokenizers::Tokenizer>,
+    #[allow(dead_code)]  // Keep environment alive for the session
+    environment: Arc<ort::Environment>,
+    /// Maximum length of a query and document pair in tokens
+    max_seq_length: usize,
+}
 
-                all_embeddings.extend(chunk_embeddings);
-            }
+impl OnnxCrossEncoder {
+    /// Load the cross-encoder from `config.model_path`.
+    pub async fn new(config: ONNXConfig) -> Result<Self> {
+        let model_path = config.model_path.as_ref().ok_or_else(|| {
+            SemanticError::Config("Cross-encoder requires a model_path".to_string())
+        })?;
+        let mut loaded = ONNXProvider::load_model(&model_path.to_string_lossy(), &config, 1).await?;
+        info!(
+            "Cross-encoder {} loaded (execution provider: {})",
+            config.model_name,
+            loaded.execution_provider.as_str()
+        );
 
-            debug!(
-                "Batch inference completed: {} texts processed in {} batches",
-                texts.len(),
-                (texts.len() + self.max_batch_size - 1) / self.max_batch_size
-            );
+        Ok(Self {
+            session: Arc::new(RwLock::new(loaded.sessions.swap_remove(0))),
+            tokenizer: Arc::new(loaded.tokenizer),
+            environment: Arc::new(loaded.environment),
+            max_seq_length: 512,
+        })
+    }
+
+    /// Relevance of each document to `query` in one inference call.
+    fn score_pairs(
+        session: &RwLock<ort::Session>,
+        tokenizer: &tokenizers::Tokenizer,
+        max_seq_length: usize,
+        query: &str,
+        documents: &[String],
+    ) -> Result<Vec<f32>> {
+        if documents.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let batch_size = documents.len();
+        let pairs: Vec<(&str, &str)> = documents.iter().map(|doc| (query, doc.as_str())).collect();
+        let encodings = tokenizer
+            .encode_batch(pairs, true)
+            .map_err(|e| SemanticError::Provider(format!("Pair tokenization failed: {}", e)))?;
+
+        let max_len = encodings
+            .iter()
+            .map(|e| e.get_ids().len())
+            .max()
+            .unwrap_or(0)
+            .min(max_seq_length);
+
+        let mut input_ids = Vec::with_capacity(batch_size * max_len);
+        let mut attention_mask = Vec::with_capacity(batch_size * max_len);
+        // Segment ids tell BERT-style models where the query ends
+        let mut token_type_ids = Vec::with_capacity(batch_size * max_len);
+
+        for encoding in &encodings {
+            let seq_len = encoding.get_ids().len().min(max_len);
+            input_ids.extend(encoding.get_ids()[..seq_len].iter().map(|&id| id as i64));
+            attention_mask.extend(encoding.get_attention_mask()[..seq_len].iter().map(|&m| m as i64));
+            token_type_ids.extend(encoding.get_type_ids()[..seq_len].iter().map(|&t| t as i64));
+
+            // Pad to max_len
+            let padded_len = input_ids.len() + max_len - seq_len;
+            input_ids.resize(padded_len, 0);
+            attention_mask.resize(padded_len, 0);
+            token_type_ids.resize(padded_len, 0);
+        }
 
-            Ok(all_embeddings)
+        use ndarray::{Array, CowArray, IxDyn};
+
+        let tensor = |values: Vec<i64>| -> Result<Array<i64, IxDyn>> {
+            Array::from_shape_vec(IxDyn(&[batch_size, max_len]), values)
+                .map_err(|e| SemanticError::Provider(format!("Failed to create cross-encoder input tensor: {}", e)))
+        };
+        let input_ids: CowArray<i64, IxDyn> = CowArray::from(tensor(input_ids)?);
+        let attention_mask: CowArray<i64, IxDyn> = CowArray::from(tensor(attention_mask)?);
+        let token_type_ids: CowArray<i64, IxDyn> = CowArray::from(tensor(token_type_ids)?);
+
+        let session_guard = session.read();
+        let allocator_ptr = session_guard.allocator();
+
+        let mut inputs = vec![
+            ort::Value::from_array(allocator_ptr, &input_ids)?,
+            ort::Value::from_array(allocator_ptr, &attention_mask)?,
+        ];
+        if session_guard.inputs.len() > 2 {
+            inputs.push(ort::Value::from_array(allocator_ptr, &token_type_ids)?);
+        }
+
+        let outputs = session_guard.run(inputs)?;
+        let logits_raw = outputs[0].try_extract::<f32>()?;
+        let logits: Vec<f32> = logits_raw.view().iter().copied().collect();
+
+        // Either one relevance logit per pair, or one logit per class with
+        // the last class meaning relevant
+        let classes = logits.len() / batch_size;
+        if classes == 0 || logits.len() != classes * batch_size {
+            return Err(SemanticError::Provider(format!(
+                "Cross-encoder returned {} logits for {} pairs",
+                logits.len(),
+                batch_size
+            )));
         }
+
+        Ok(logits
+            .chunks(classes)
+            .map(|pair| {
+                if classes == 1 {
+                    1.0 / (1.0 + (-pair[0]).exp())
+                } else {
+                    let max = pair.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+                    let total: f32 = pair.iter().map(|logit| (logit - max).exp()).sum();
+                    (pair[classes - 1] - max).exp() / total
+                }
+            })
+            .collect())
     }
+}
 
-    fn model(&self) -> &EmbeddingModel {
-        &self.model
+#[async_trait]
+impl Reranker for OnnxCrossEncoder {
+    async fn score(&self, query: &str, document: &str) -> Result<f32> {
+        let scores = self.score_batch(query, &[document.to_string()]).await?;
+        Ok(scores.into_iter().next().unwrap_or(0.0))
+    }
+
+    async fn score_batch(&self, query: &str, documents: &[String]) -> Result<Vec<f32>> {
+        // Inference blocks, so it runs off the async runtime
+        let session = self.session.clone();
+        let tokenizer = self.tokenizer.clone();
+        let max_seq_length = self.max_seq_length;
+        let query = query.to_string();
+        let documents = documents.to_vec();
+
+        tokio::task::spawn_blocking(move || {
+            Self::score_pairs(&session, &tokenizer, max_seq_length, &query, &documents)
+        })
+        .await
+        .map_err(|e| SemanticError::Provider(format!("Cross-encoder task failed: {}", e)))?
     }
 }
 
@@ -612,12 +1621,18 @@ pub struct OllamaProvider {
     client: Client,
     config: OllamaConfig,
     model: EmbeddingModel,
+    /// Whether a request succeeded since the model was last seen loading
+    warm: AtomicBool,
+    server: RwLock<OllamaServerInfo>,
+    latency: LatencyTracker,
 }
 
 #[derive(Serialize)]
 struct OllamaRequest {
     model: String,
     prompt: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    keep_alive: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -625,47 +1640,206 @@ struct OllamaResponse {
     embedding: Vec<f32>,
 }
 
+#[derive(Deserialize)]
+struct OllamaErrorResponse {
+    error: String,
+}
+
+#[derive(Deserialize)]
+struct OllamaVersionResponse {
+    version: String,
+}
+
+#[derive(Deserialize)]
+struct OllamaPsResponse {
+    #[serde(default)]
+    models: Vec<OllamaPsModel>,
+}
+
+#[derive(Deserialize)]
+struct OllamaPsModel {
+    name: String,
+}
+
+/// Version of the Ollama server and the models it has loaded.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OllamaServerInfo {
+    pub version: Option<String>,
+    pub loaded_models: Vec<String>,
+}
+
+/// Outcome of one embedding request to Ollama.
+enum OllamaAttempt {
+    Embedded(Vector),
+    /// The model is still loading; worth retrying
+    Loading(String),
+}
+
+/// Whether an Ollama error response means the model is still loading.
+fn is_model_loading(status: reqwest::StatusCode, message: &str) -> bool {
+    let message = message.to_lowercase();
+    status == reqwest::StatusCode::SERVICE_UNAVAILABLE
+        || message.contains("loading model")
+        || message.contains("model is loading")
+        || message.contains("server busy")
+}
+
 impl OllamaProvider {
     pub async fn new(config: OllamaConfig) -> Result<Self> {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(60))
-            .build()?;
+        // Timeouts are set per request, longer while the model may be loading
+        let client = Client::builder().build()?;
 
         let model = EmbeddingModel::ollama(&config.model, config.dimension);
 
         info!("Initialized Ollama provider with model: {}", config.model);
 
-        Ok(Self {
+        let provider = Self {
             client,
             config,
             model,
-        })
+            warm: AtomicBool::new(false),
+            server: RwLock::new(OllamaServerInfo::default()),
+            latency: LatencyTracker::default(),
+        };
+
+        if provider.config.warmup {
+            let start = Instant::now();
+            provider.embed("warm-up").await?;
+            info!("Ollama model {} warmed up in {:?}", provider.config.model, start.elapsed());
+        }
+        if let Err(e) = provider.refresh_server_info().await {
+            debug!("Could not query Ollama server info: {}", e);
+        }
+
+        Ok(provider)
     }
-}
 
-#[async_trait]
-impl EmbeddingProvider for OllamaProvider {
-    async fn embed(&self, text: &str) -> Result<Vector> {
-        debug!("Generating embedding with Ollama");
+    /// Query the server version and loaded models, shown by `provider_info()`.
+    pub async fn refresh_server_info(&self) -> Result<OllamaServerInfo> {
+        let timeout = Duration::from_secs(self.config.request_timeout_secs);
 
+        let version: OllamaVersionResponse = self
+            .client
+            .get(format!("{}/api/version", self.config.endpoint))
+            .timeout(timeout)
+            .send()
+            .await
+            .map_err(|e| self.request_error(e))?
+            .json()
+            .await?;
+        let ps: OllamaPsResponse = self
+            .client
+            .get(format!("{}/api/ps", self.config.endpoint))
+            .timeout(timeout)
+            .send()
+            .await
+            .map_err(|e| self.request_error(e))?
+            .json()
+            .await?;
+
+        let info = OllamaServerInfo {
+            version: Some(version.version),
+            loaded_models: ps.models.into_iter().map(|m| m.name).collect(),
+        };
+        *self.server.write() = info.clone();
+        Ok(info)
+    }
+
+    /// Embed `text`, retrying with exponential backoff while the model loads.
+    async fn embed_with_retry(&self, text: &str) -> Result<Vector> {
         let request = OllamaRequest {
             model: self.config.model.clone(),
             prompt: text.to_string(),
+            keep_alive: self.config.keep_alive.clone(),
         };
-
         let url = format!("{}/api/embeddings", self.config.endpoint);
-        let response = self.client.post(&url).json(&request).send().await?;
 
-        if !response.status().is_success() {
-            let error_text = response.text().await?;
-            return Err(SemanticError::Provider(format!(
-                "Ollama API error: {}",
-                error_text
+        let mut attempt = 0;
+        loop {
+            match self.send_embedding(&url, &request).await? {
+                OllamaAttempt::Embedded(embedding) => {
+                    self.warm.store(true, Ordering::Relaxed);
+                    return Ok(embedding);
+                }
+                OllamaAttempt::Loading(reason) if attempt < self.config.max_retries => {
+                    let backoff = Duration::from_millis(
+                        self.config.retry_backoff_ms.saturating_mul(1 << attempt.min(16)),
+                    );
+                    warn!(
+                        "Ollama model {} is loading ({}), retrying in {:?}",
+                        self.config.model, reason, backoff
+                    );
+                    tokio::time::sleep(backoff).await;
+                    attempt += 1;
+                }
+                OllamaAttempt::Loading(reason) => {
+                    return Err(SemanticError::Provider(format!(
+                        "Ollama model {} did not finish loading after {} retries: {}",
+                        self.config.model, self.config.max_retries, reason
+                    )));
+                }
+            }
+        }
+    }
+
+    async fn send_embedding(&self, url: &str, request: &OllamaRequest) -> Result<OllamaAttempt> {
+        let timeout = if self.warm.load(Ordering::Relaxed) {
+            Duration::from_secs(self.config.request_timeout_secs)
+        } else {
+            Duration::from_secs(self.config.cold_start_timeout_secs)
+        };
+
+        let response = match self.client.post(url).timeout(timeout).json(request).send().await {
+            Ok(response) => response,
+            Err(e) if e.is_timeout() => {
+                // The model may have been unloaded; wait longer next time
+                self.warm.store(false, Ordering::Relaxed);
+                return Ok(OllamaAttempt::Loading(format!("no response within {:?}", timeout)));
+            }
+            Err(e) => return Err(self.request_error(e)),
+        };
+
+        let status = response.status();
+        if status.is_success() {
+            let response: OllamaResponse = response.json().await?;
+            return Ok(OllamaAttempt::Embedded(response.embedding));
+        }
+        let retry_after = retry_after_header(response.headers());
+
+        let body = response.text().await.unwrap_or_default();
+        let message = serde_json::from_str::<OllamaErrorResponse>(&body)
+            .map(|e| e.error)
+            .unwrap_or(body);
+
+        if is_model_loading(status, &message) {
+            self.warm.store(false, Ordering::Relaxed);
+            return Ok(OllamaAttempt::Loading(message));
+        }
+        if status == reqwest::StatusCode::NOT_FOUND || message.contains("not found") {
+            return Err(SemanticError::ModelNotLoaded(format!(
+                "Ollama has no model {} ({}); pull it with `ollama pull {}`",
+                self.config.model, message, self.config.model
             )));
         }
+        Err(provider_http_error("Ollama", status, retry_after, &message))
+    }
+
+    /// Map a transport error, telling the user to start Ollama when nothing
+    /// listens at the endpoint.
+    fn request_error(&self, error: reqwest::Error) -> SemanticError {
+        transport_error("Ollama", &self.config.endpoint, "start it with `ollama serve`", error)
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for OllamaProvider {
+    async fn embed(&self, text: &str) -> Result<Vector> {
+        debug!("Generating embedding with Ollama");
 
-        let response: OllamaResponse = response.json().await?;
-        Ok(response.embedding)
+        let start = Instant::now();
+        let result = self.embed_with_retry(text).await;
+        self.latency.record(start.elapsed());
+        result
     }
 
     async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vector>> {
@@ -680,12 +1854,45 @@ impl EmbeddingProvider for OllamaProvider {
     fn model(&self) -> &EmbeddingModel {
         &self.model
     }
+
+    fn provider_info(&self) -> ProviderInfo {
+        let server = self.server.read().clone();
+        let mut info = ProviderInfo::new(self.model.clone());
+        info.server_version = server.version;
+        info.loaded_models = server.loaded_models;
+        self.latency.fill(&mut info);
+        info
+    }
 }
 
-/// Mock provider for testing.
+/// Deterministic embedding provider for tests.
+///
+/// The embedding of a text is a hash of its bytes, or with
+/// [`with_seed`](Self::with_seed) a pseudo-random unit vector drawn from
+/// the seed and the text, so each seed gives an independent set of vectors.
+/// [`shape_similarity`](Self::shape_similarity) pins vectors to get a
+/// chosen ranking, and [`with_recording`](Self::with_recording) keeps every
+/// request for assertions on batching.
+///
+/// Clones share pinned vectors and recorded calls, so a test can keep a
+/// clone after handing the provider to a `ProviderManager`.
+#[derive(Clone)]
 pub struct MockProvider {
     model: EmbeddingModel,
     dimension: usize,
+    seed: Option<u64>,
+    /// Vectors returned for these texts instead of generated ones
+    pinned: Arc<RwLock<HashMap<String, Vector>>>,
+    /// Requests received, when recording
+    calls: Option<Arc<parking_lot::Mutex<Vec<EmbedCall>>>>,
+}
+
+/// One request received by a recording `MockProvider`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmbedCall {
+    pub texts: Vec<String>,
+    /// Whether it came through `embed_batch`
+    pub batched: bool,
 }
 
 impl MockProvider {
@@ -693,10 +1900,107 @@ impl MockProvider {
         Self {
             model: EmbeddingModel::new("mock", "mock-model", dimension),
             dimension,
+            seed: None,
+            pinned: Arc::new(RwLock::new(HashMap::new())),
+            calls: None,
+        }
+    }
+
+    /// Draw embeddings from `seed`: the vector of a text depends only on
+    /// the seed and the text.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Record every request, see [`calls`](Self::calls).
+    pub fn with_recording(mut self) -> Self {
+        self.calls = Some(Arc::new(parking_lot::Mutex::new(Vec::new())));
+        self
+    }
+
+    /// Return `vector` for `text` from now on.
+    ///
+    /// # Panics
+    ///
+    /// If `vector` doesn't have the provider's dimension.
+    pub fn pin(&self, text: impl Into<String>, vector: Vector) {
+        assert_eq!(vector.len(), self.dimension, "pinned vector has the wrong dimension");
+        self.pinned.write().insert(text.into(), vector);
+    }
+
+    /// Pin vectors so that `ranked` texts are less and less similar to
+    /// `query` by cosine similarity, closest first. Similarities are spread
+    /// evenly between 0.95 and 0.05; the query keeps its vector.
+    ///
+    /// # Panics
+    ///
+    /// If the dimension is below 2, or `ranked` contains `query`.
+    pub fn shape_similarity(&self, query: &str, ranked: &[&str]) {
+        assert!(self.dimension >= 2, "shaping similarity needs at least two dimensions");
+        assert!(!ranked.contains(&query), "the query cannot be ranked against itself");
+
+        let query_vector = self.generate_embedding(query);
+        let mut shaped = Vec::with_capacity(ranked.len());
+        for (rank, text) in ranked.iter().enumerate() {
+            let cos = 0.95 - 0.9 * rank as f32 / ranked.len() as f32;
+            let sin = (1.0 - cos * cos).sqrt();
+            let orthogonal = self.orthogonal_direction(&query_vector, text);
+            let vector = query_vector
+                .iter()
+                .zip(&orthogonal)
+                .map(|(q, o)| cos * q + sin * o)
+                .collect();
+            shaped.push((text.to_string(), vector));
+        }
+
+        let mut pinned = self.pinned.write();
+        pinned.insert(query.to_string(), query_vector);
+        pinned.extend(shaped);
+    }
+
+    /// Requests received so far; empty unless recording.
+    pub fn calls(&self) -> Vec<EmbedCall> {
+        self.calls.as_ref().map(|calls| calls.lock().clone()).unwrap_or_default()
+    }
+
+    /// Every text embedded so far, in order.
+    pub fn embedded_texts(&self) -> Vec<String> {
+        self.calls().into_iter().flat_map(|call| call.texts).collect()
+    }
+
+    /// Sizes of the `embed_batch` requests received so far.
+    pub fn batch_sizes(&self) -> Vec<usize> {
+        self.calls()
+            .iter()
+            .filter(|call| call.batched)
+            .map(|call| call.texts.len())
+            .collect()
+    }
+
+    pub fn clear_calls(&self) {
+        if let Some(calls) = &self.calls {
+            calls.lock().clear();
+        }
+    }
+
+    fn record(&self, texts: Vec<String>, batched: bool) {
+        if let Some(calls) = &self.calls {
+            calls.lock().push(EmbedCall { texts, batched });
         }
     }
 
     fn generate_embedding(&self, text: &str) -> Vector {
+        if let Some(vector) = self.pinned.read().get(text) {
+            return vector.clone();
+        }
+        match self.seed {
+            Some(seed) => self.seeded_embedding(seed, text),
+            None => self.hashed_embedding(text),
+        }
+    }
+
+    fn hashed_embedding(&self, text: &str) -> Vector {
         // Deterministic mock embedding based on text
         let hash = text.bytes().fold(0u64, |acc, b| acc.wrapping_mul(31).wrapping_add(b as u64));
 
@@ -706,12 +2010,64 @@ impl MockProvider {
             *val = ((seed % 1000) as f32 / 1000.0) - 0.5;
         }
 
-        // Normalize
+        Self::normalize(embedding)
+    }
+
+    fn seeded_embedding(&self, seed: u64, text: &str) -> Vector {
+        // SplitMix64 stream starting from the seed mixed with an FNV-1a
+        // hash of the text
+        let text_hash = text.bytes().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+        });
+        let mut state = text_hash ^ seed.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+
+        let embedding = (0..self.dimension)
+            .map(|_| {
+                state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+                let mut z = state;
+                z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+                z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+                z ^= z >> 31;
+                // Top 24 bits to a value in [-1, 1)
+                (z >> 40) as f32 / (1u64 << 23) as f32 - 1.0
+            })
+            .collect();
+
+        Self::normalize(embedding)
+    }
+
+    /// Unit vector orthogonal to `query`, taken from the unpinned vector of
+    /// `text` so different texts get different directions.
+    fn orthogonal_direction(&self, query: &[f32], text: &str) -> Vector {
+        let own = match self.seed {
+            Some(seed) => self.seeded_embedding(seed, text),
+            None => self.hashed_embedding(text),
+        };
+
+        let reject = |vector: Vec<f32>| -> Vector {
+            let dot: f32 = vector.iter().zip(query).map(|(v, q)| v * q).sum();
+            vector.iter().zip(query).map(|(v, q)| v - dot * q).collect()
+        };
+        let direction = reject(own);
+        if direction.iter().map(|x| x * x).sum::<f32>() > 1e-6 {
+            return Self::normalize(direction);
+        }
+
+        // The text's vector is parallel to the query; use the axis the
+        // query leans on least
+        let axis = (0..self.dimension)
+            .min_by(|a, b| query[*a].abs().total_cmp(&query[*b].abs()))
+            .unwrap_or(0);
+        let mut basis = vec![0.0; self.dimension];
+        basis[axis] = 1.0;
+        Self::normalize(reject(basis))
+    }
+
+    fn normalize(mut embedding: Vector) -> Vector {
         let norm = (embedding.iter().map(|x| x * x).sum::<f32>()).sqrt();
         if norm > 0.0 {
             embedding.iter_mut().for_each(|x| *x /= norm);
         }
-
         embedding
     }
 }
@@ -719,16 +2075,24 @@ impl MockProvider {
 #[async_trait]
 impl EmbeddingProvider for MockProvider {
     async fn embed(&self, text: &str) -> Result<Vector> {
+        self.record(vec![text.to_string()], false);
         Ok(self.generate_embedding(text))
     }
 
     async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vector>> {
+        self.record(texts.to_vec(), true);
         Ok(texts.iter().map(|t| self.generate_embedding(t)).collect())
     }
 
     fn model(&self) -> &EmbeddingModel {
         &self.model
     }
+
+    fn provider_info(&self) -> ProviderInfo {
+        let mut info = ProviderInfo::new(self.model.clone());
+        info.mock = true;
+        info
+    }
 }
 
 #[cfg(test)]
@@ -752,6 +2116,473 @@ mod tests {
         assert_ne!(embedding, embedding3);
     }
 
+    #[tokio::test]
+    async fn test_seeded_mock_provider() {
+        let provider = MockProvider::new(64).with_seed(7);
+        let embedding = provider.embed("parse config").await.unwrap();
+        let norm: f32 = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-5);
+
+        // A pure function of (seed, text)
+        let same_seed = MockProvider::new(64).with_seed(7);
+        assert_eq!(same_seed.embed("parse config").await.unwrap(), embedding);
+        let other_seed = MockProvider::new(64).with_seed(8);
+        assert_ne!(other_seed.embed("parse config").await.unwrap(), embedding);
+        assert_ne!(provider.embed("parse configs").await.unwrap(), embedding);
+    }
+
+    #[tokio::test]
+    async fn test_mock_provider_shape_similarity() {
+        use crate::types::cosine_similarity;
+
+        for provider in [MockProvider::new(32), MockProvider::new(32).with_seed(1)] {
+            provider.shape_similarity("query", &["best", "middle", "worst"]);
+
+            let query = provider.embed("query").await.unwrap();
+            let similarity = |vector: &[f32]| cosine_similarity(&query, vector);
+            let best = provider.embed("best").await.unwrap();
+            let middle = provider.embed("middle").await.unwrap();
+            let worst = provider.embed("worst").await.unwrap();
+
+            assert!((similarity(&best) - 0.95).abs() < 1e-4);
+            assert!(similarity(&best) > similarity(&middle));
+            assert!(similarity(&middle) > similarity(&worst));
+            assert!(similarity(&worst) > 0.0);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mock_provider_records_calls() {
+        let provider = MockProvider::new(8).with_recording();
+        let manager = ProviderManager::new(
+            vec![("mock".to_string(), Box::new(provider.clone()) as Box<dyn EmbeddingProvider>)],
+            CircuitBreakerConfig::default(),
+        );
+
+        manager.embed("one").await.unwrap();
+        manager
+            .embed_batch(&["two".to_string(), "three".to_string()])
+            .await
+            .unwrap();
+
+        assert_eq!(provider.embedded_texts(), vec!["one", "two", "three"]);
+        assert_eq!(provider.batch_sizes(), vec![2]);
+        assert!(!provider.calls()[0].batched);
+
+        provider.clear_calls();
+        assert!(provider.calls().is_empty());
+        assert!(MockProvider::new(8).calls().is_empty());
+    }
+
+    /// Provider whose every request fails.
+    struct FailingProvider {
+        model: EmbeddingModel,
+    }
+
+    impl FailingProvider {
+        fn new(dimension: usize) -> Self {
+            Self {
+                model: EmbeddingModel::new("failing", "failing-model", dimension),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl EmbeddingProvider for FailingProvider {
+        async fn embed(&self, _text: &str) -> Result<Vector> {
+            Err(SemanticError::Provider("service unavailable".to_string()))
+        }
+
+        async fn embed_batch(&self, _texts: &[String]) -> Result<Vec<Vector>> {
+            Err(SemanticError::Provider("service unavailable".to_string()))
+        }
+
+        fn model(&self) -> &EmbeddingModel {
+            &self.model
+        }
+    }
+
+    #[tokio::test]
+    async fn test_provider_chain_skips_unhealthy_provider() {
+        let manager = ProviderManager::new(
+            vec![
+                ("openai".to_string(), Box::new(FailingProvider::new(384)) as Box<dyn EmbeddingProvider>),
+                ("mock".to_string(), Box::new(MockProvider::new(384))),
+            ],
+            CircuitBreakerConfig {
+                failure_threshold: 2,
+                cooldown_secs: 60,
+            },
+        );
+
+        for _ in 0..3 {
+            assert_eq!(manager.embed("query").await.unwrap().len(), 384);
+        }
+        manager.embed_batch(&["a".to_string()]).await.unwrap();
+
+        // The primary failed twice, then was skipped
+        let health = manager.health();
+        assert_eq!(health[0].provider, "openai");
+        assert_eq!(health[0].consecutive_failures, 2);
+        assert_eq!(health[0].total_failures, 2);
+        assert!(health[0].last_error.as_deref().unwrap().contains("service unavailable"));
+        assert!(health[0].circuit_open_for.is_some());
+        assert!(!health[0].is_available());
+        assert!(health[1].is_available());
+        assert_eq!(manager.dimension(), 384);
+
+        // A probe reaches the skipped provider too
+        let health = manager.check_health().await;
+        assert_eq!(health[0].total_failures, 3);
+        assert_eq!(health[1].consecutive_failures, 0);
+    }
+
+    #[tokio::test]
+    async fn test_provider_chain_failure_keeps_provider_errors() {
+        let manager = ProviderManager::new(
+            vec![("openai".to_string(), Box::new(FailingProvider::new(384)) as Box<dyn EmbeddingProvider>)],
+            CircuitBreakerConfig {
+                failure_threshold: 1,
+                cooldown_secs: 60,
+            },
+        );
+
+        match manager.embed("query").await {
+            Err(SemanticError::ProvidersExhausted { operation, failures }) => {
+                assert_eq!(operation, "embedding");
+                assert_eq!(failures.len(), 1);
+                assert_eq!(failures[0].0, "openai");
+                assert!(matches!(failures[0].1, SemanticError::Provider(_)));
+            }
+            other => panic!("expected exhausted providers, got {:?}", other),
+        }
+
+        // The only provider is now skipped until its cooldown ends
+        let err = manager.embed("query").await.unwrap_err();
+        assert!(matches!(err, SemanticError::ProvidersCoolingDown { .. }));
+        assert!(err.is_retryable());
+        assert!(err.retry_after().unwrap() <= Duration::from_secs(60));
+    }
+
+    #[tokio::test]
+    async fn test_provider_chain_refuses_mixed_dimensions() {
+        let manager = ProviderManager::new(
+            vec![
+                ("openai".to_string(), Box::new(FailingProvider::new(1536)) as Box<dyn EmbeddingProvider>),
+                ("onnx".to_string(), Box::new(MockProvider::new(384))),
+            ],
+            CircuitBreakerConfig::default(),
+        );
+
+        match manager.embed("query").await {
+            Err(SemanticError::ProviderDimensionMismatch { provider, expected, got }) => {
+                assert_eq!(provider, "onnx");
+                assert_eq!(expected, 1536);
+                assert_eq!(got, 384);
+            }
+            other => panic!("expected a dimension mismatch, got {:?}", other),
+        }
+
+        let health = manager.health();
+        assert!(!health[1].dimension_compatible);
+        assert_eq!(health[1].total_failures, 0);
+    }
+
+    #[tokio::test]
+    async fn test_onnx_provider_info() {
+        // No model path: mock embeddings, no execution provider, no warm-up
+        let provider = ONNXProvider::new(ONNXConfig::default()).await.unwrap();
+        provider.embed("test").await.unwrap();
+        provider
+            .embed_batch(&["a".to_string(), "b".to_string()])
+            .await
+            .unwrap();
+
+        let info = provider.provider_info();
+        assert!(info.mock);
+        assert_eq!(info.execution_provider, None);
+        assert_eq!(info.calls, 2);
+        assert_eq!(info.warmup_ms, None);
+        assert_eq!(info.model.dimension, 384);
+    }
+
+    #[test]
+    fn test_onnx_config_defaults() {
+        let config: ONNXConfig = serde_json::from_str(
+            r#"{"model_path": null, "model_name": "m", "dimension": 384, "use_gpu": true}"#,
+        )
+        .unwrap();
+        assert_eq!(config.quantization, OnnxQuantization::Auto);
+        assert!(config.warmup);
+        assert_eq!(
+            config.resolved_execution_providers(),
+            vec![OnnxExecutionProvider::Cuda, OnnxExecutionProvider::Cpu]
+        );
+
+        let config: ONNXConfig = serde_json::from_str(
+            r#"{"model_path": null, "model_name": "m", "dimension": 384, "use_gpu": false,
+                "execution_providers": ["coreml", "cpu"], "quantization": "int8"}"#,
+        )
+        .unwrap();
+        assert_eq!(config.quantization, OnnxQuantization::Int8);
+        assert_eq!(
+            config.resolved_execution_providers(),
+            vec![OnnxExecutionProvider::CoreML, OnnxExecutionProvider::Cpu]
+        );
+        // One session on accelerators, whose memory is scarce
+        assert_eq!(config.resolved_session_pool_size(), 1);
+        assert_eq!(config.max_batch_tokens, 8192);
+
+        let config: ONNXConfig = serde_json::from_str(
+            r#"{"model_path": null, "model_name": "m", "dimension": 384, "use_gpu": false,
+                "session_pool_size": 4, "inter_op_threads": 2}"#,
+        )
+        .unwrap();
+        assert_eq!(config.resolved_session_pool_size(), 4);
+        assert_eq!(config.resolved_intra_op_threads(4), (num_cpus::get() / 4).max(1));
+        assert_eq!(ONNXConfig::default().resolved_session_pool_size(), num_cpus::get_physical());
+    }
+
+    #[test]
+    fn test_plan_batches_splits_by_count_and_padded_length() {
+        assert!(plan_batches(&[], 4, 100).is_empty());
+        assert_eq!(plan_batches(&[5; 10], 4, 100), vec![0..4, 4..8, 8..10]);
+
+        // The long text would pad its neighbours past the token limit
+        assert_eq!(plan_batches(&[10, 10, 60, 10], 4, 100), vec![0..2, 2..3, 3..4]);
+        // A text over the limit still gets embedded, on its own
+        assert_eq!(plan_batches(&[10, 500, 10, 10], 4, 100), vec![0..1, 1..2, 2..4]);
+        // Models taking one text per call
+        assert_eq!(plan_batches(&[5, 5], 1, 100), vec![0..1, 1..2]);
+    }
+
+    /// Throughput of a real model with growing session pools. Point
+    /// `CORTEX_ONNX_BENCH_MODEL` at a small model such as all-MiniLM-L6-v2
+    /// with its `tokenizer.json` alongside, then run with
+    /// `cargo test -p cortex-semantic --release -- --ignored --nocapture onnx_pool`.
+    #[tokio::test(flavor = "multi_thread")]
+    #[ignore = "needs an ONNX model in CORTEX_ONNX_BENCH_MODEL"]
+    async fn bench_onnx_pool_throughput() {
+        let Some(model_path) = std::env::var_os("CORTEX_ONNX_BENCH_MODEL") else {
+            eprintln!("CORTEX_ONNX_BENCH_MODEL is not set, skipping");
+            return;
+        };
+        let texts: Vec<String> = (0..512)
+            .map(|i| format!("fn handler_{}(request: Request) -> Response {{ route(request, {}) }}", i, i))
+            .collect();
+
+        let mut pool_sizes = vec![1, 2, 4, num_cpus::get_physical()];
+        pool_sizes.retain(|&size| size <= num_cpus::get_physical());
+        pool_sizes.dedup();
+
+        let mut throughputs = Vec::new();
+        for pool_size in pool_sizes {
+            let provider = ONNXProvider::new(ONNXConfig {
+                model_path: Some(model_path.clone().into()),
+                session_pool_size: Some(pool_size),
+                intra_op_threads: Some(1),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+            assert!(!provider.provider_info().mock, "model failed to load");
+
+            let start = Instant::now();
+            let embeddings = provider.embed_batch(&texts).await.unwrap();
+            let per_second = texts.len() as f64 / start.elapsed().as_secs_f64();
+            assert_eq!(embeddings.len(), texts.len());
+
+            eprintln!("{:>3} sessions: {:>8.1} texts/s", pool_size, per_second);
+            throughputs.push(per_second);
+        }
+
+        if throughputs.len() > 1 {
+            assert!(throughputs.last() > throughputs.first(), "more sessions should embed faster");
+        }
+    }
+
+    #[test]
+    fn test_ollama_model_loading_detection() {
+        use reqwest::StatusCode;
+
+        assert!(is_model_loading(StatusCode::SERVICE_UNAVAILABLE, ""));
+        assert!(is_model_loading(StatusCode::INTERNAL_SERVER_ERROR, "llm server loading model"));
+        assert!(!is_model_loading(StatusCode::NOT_FOUND, "model \"x\" not found"));
+        assert!(!is_model_loading(StatusCode::UNAUTHORIZED, "unauthorized"));
+    }
+
+    #[test]
+    fn test_ollama_request_keep_alive() {
+        let mut request = OllamaRequest {
+            model: "m".to_string(),
+            prompt: "p".to_string(),
+            keep_alive: None,
+        };
+        assert!(!serde_json::to_string(&request).unwrap().contains("keep_alive"));
+
+        request.keep_alive = Some("-1".to_string());
+        assert!(serde_json::to_string(&request).unwrap().contains(r#""keep_alive":"-1""#));
+    }
+
+    fn openai_config(model: &str) -> OpenAIConfig {
+        OpenAIConfig {
+            api_key: Some("test-key".to_string()),
+            model: model.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_openai_dimensions_validation() {
+        let provider = OpenAIProvider::with_dimensions(openai_config("text-embedding-3-small"), Some(512))
+            .await
+            .unwrap();
+        assert_eq!(provider.dimension(), 512);
+        assert_eq!(provider.request_dimensions, Some(512));
+
+        let provider = OpenAIProvider::new(openai_config("text-embedding-3-large")).await.unwrap();
+        assert_eq!(provider.dimension(), 3072);
+        assert_eq!(provider.request_dimensions, None);
+
+        assert!(OpenAIProvider::with_dimensions(openai_config("text-embedding-3-small"), Some(2048))
+            .await
+            .is_err());
+        assert!(OpenAIProvider::with_dimensions(openai_config("text-embedding-ada-002"), Some(512))
+            .await
+            .is_err());
+
+        // ada-002 accepts its own size but doesn't get the parameter
+        let provider = OpenAIProvider::with_dimensions(openai_config("text-embedding-ada-002"), Some(1536))
+            .await
+            .unwrap();
+        assert_eq!(provider.request_dimensions, None);
+    }
+
+    #[test]
+    fn test_openai_request_and_usage_format() {
+        let mut request = OpenAIRequest {
+            input: vec!["a".to_string()],
+            model: "text-embedding-3-small".to_string(),
+            dimensions: None,
+        };
+        assert!(!serde_json::to_string(&request).unwrap().contains("dimensions"));
+        request.dimensions = Some(256);
+        assert!(serde_json::to_string(&request).unwrap().contains(r#""dimensions":256"#));
+
+        let response: OpenAIResponse = serde_json::from_str(
+            r#"{"data":[{"embedding":[0.1]}],"usage":{"prompt_tokens":7,"total_tokens":7}}"#,
+        )
+        .unwrap();
+        assert_eq!(response.usage.total_tokens, 7);
+
+        // Compatible servers may omit usage
+        let response: OpenAIResponse = serde_json::from_str(r#"{"data":[]}"#).unwrap();
+        assert_eq!(response.usage.total_tokens, 0);
+    }
+
+    #[tokio::test]
+    async fn test_openai_usage_attribution() {
+        let provider = OpenAIProvider::new(openai_config("text-embedding-3-small")).await.unwrap();
+        let batches = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let sink = batches.clone();
+        provider.set_usage_callback(Arc::new(move |usage: &BatchUsage| sink.lock().push(usage.clone())));
+
+        let usage = OpenAIUsage {
+            prompt_tokens: 12,
+            total_tokens: 12,
+        };
+        provider.record_usage(3, &usage);
+        attribute_usage_to("agent-1".to_string(), async { provider.record_usage(2, &usage) }).await;
+
+        let stats = provider.usage_stats();
+        assert_eq!(stats.requests, 2);
+        assert_eq!(stats.inputs, 5);
+        assert_eq!(stats.total_tokens, 24);
+
+        let batches = batches.lock();
+        assert_eq!(batches[0].agent_id, None);
+        assert_eq!(batches[1].agent_id.as_deref(), Some("agent-1"));
+    }
+
+    #[tokio::test]
+    async fn test_ollama_connection_refused() {
+        // Nothing listens on the discard port
+        let config = OllamaConfig {
+            endpoint: "http://127.0.0.1:9".to_string(),
+            ..Default::default()
+        };
+        let provider = OllamaProvider::new(config).await.unwrap();
+
+        let err = provider.embed("test").await.unwrap_err();
+        assert!(matches!(err, SemanticError::ProviderUnavailable { .. }));
+        assert!(err.to_string().contains("ollama serve"));
+        assert_eq!(provider.provider_info().server_version, None);
+    }
+
+    #[test]
+    fn test_provider_http_error_classification() {
+        use reqwest::StatusCode;
+
+        let err = provider_http_error(
+            "OpenAI",
+            StatusCode::TOO_MANY_REQUESTS,
+            Some(Duration::from_secs(7)),
+            r#"{"error": {"message": "Rate limit reached", "type": "requests"}}"#,
+        );
+        assert!(err.is_retryable());
+        assert_eq!(err.retry_after(), Some(Duration::from_secs(7)));
+
+        let err = provider_http_error(
+            "OpenAI",
+            StatusCode::TOO_MANY_REQUESTS,
+            None,
+            r#"{"error": {"message": "You exceeded your current quota", "code": "insufficient_quota"}}"#,
+        );
+        assert!(matches!(err, SemanticError::ProviderQuotaExceeded { .. }));
+        assert!(!err.is_retryable());
+
+        let err = provider_http_error("OpenAI", StatusCode::UNAUTHORIZED, None, "bad key");
+        assert!(matches!(
+            err,
+            SemanticError::ProviderAuth { ref message, .. } if message == "bad key"
+        ));
+
+        let err = provider_http_error("OpenAI", StatusCode::SERVICE_UNAVAILABLE, None, "overloaded");
+        assert!(matches!(
+            err,
+            SemanticError::ProviderServerError { status: 503, ref message, .. } if message == "overloaded"
+        ));
+        assert!(err.is_retryable());
+
+        let err = provider_http_error("Ollama", StatusCode::BAD_REQUEST, None, "invalid input");
+        assert!(matches!(err, SemanticError::Provider(_)));
+        assert!(!err.is_retryable());
+    }
+
+    #[test]
+    fn test_retry_after_headers() {
+        use reqwest::header::{HeaderMap, HeaderValue, RETRY_AFTER};
+
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after_header(&headers), None);
+
+        headers.insert("x-ratelimit-reset-requests", HeaderValue::from_static("1m30s"));
+        headers.insert("x-ratelimit-reset-tokens", HeaderValue::from_static("250ms"));
+        assert_eq!(retry_after_header(&headers), Some(Duration::from_secs(90)));
+
+        // Retry-After takes precedence
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("20"));
+        assert_eq!(retry_after_header(&headers), Some(Duration::from_secs(20)));
+
+        // An HTTP date in the past means no wait
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"));
+        assert_eq!(retry_after_header(&headers), Some(Duration::ZERO));
+
+        assert_eq!(parse_reset_duration("6m0.5s"), Some(Duration::from_millis(360_500)));
+        assert_eq!(parse_reset_duration("2h"), Some(Duration::from_secs(7_200)));
+        assert_eq!(parse_reset_duration("soon"), None);
+    }
+
     #[tokio::test]
     async fn test_mock_provider_batch() {
         let provider = MockProvider::new(128);
@@ -9,8 +9,11 @@
 //! - Comprehensive error handling and retries
 //! - Connection pooling
 
-use crate::config::{QdrantConfig, QuantizationType};
-use crate::error::{Result, SemanticError};
+use crate::config::{IndexConfig, QdrantConfig, QuantizationType};
+use crate::error::{CollectionParamMismatch, Result, SemanticError, grpc_code, qdrant_status_code};
+use crate::filter::{FilterExpr, parse_datetime};
+use crate::payload::select_payload;
+use crate::retry::RetryPolicy;
 use crate::types::{DocumentId, SimilarityMetric, Vector};
 use async_trait::async_trait;
 use dashmap::DashMap;
@@ -19,11 +22,13 @@ use qdrant_client::qdrant::{
     Distance as QdrantDistance, HnswConfigDiff, OptimizersConfigDiff, PointStruct,
     ScalarQuantization, SearchPointsBuilder, VectorParamsBuilder,
     FieldType, DeletePointsBuilder, PointsIdsList, UpsertPointsBuilder,
-    VectorsOutput, PointId, SearchParams,
+    VectorsOutput, PointId, SearchParams, PayloadIncludeSelector,
+    with_payload_selector::SelectorOptions,
     ProductQuantization, CompressionRatio,
-    Filter,
+    Filter, Condition, DatetimeRange, Range, Timestamp, ScrollPointsBuilder,
 };
 use qdrant_client::Qdrant;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::collections::HashMap;
 use std::collections::hash_map::DefaultHasher;
@@ -102,6 +107,29 @@ pub trait VectorIndex: Send + Sync {
 
     /// Optimize the collection.
     async fn optimize(&self) -> Result<()>;
+
+    /// Create the collection with `config`'s metric and HNSW parameters if
+    /// it does not exist, or check that the existing one matches them.
+    ///
+    /// An existing collection that differs fails with
+    /// [`SemanticError::CollectionMismatch`] listing every difference, e.g. the
+    /// dimension after switching embedding models.
+    async fn ensure_collection(&self, config: &IndexConfig) -> Result<()>;
+
+    /// Delete the collection with all its vectors. Dropping a collection that
+    /// does not exist succeeds.
+    async fn drop_collection(&self) -> Result<()>;
+
+    /// Drop the collection and create it again from `config`.
+    async fn recreate_collection(&self, config: &IndexConfig) -> Result<()> {
+        self.drop_collection().await?;
+        self.ensure_collection(config).await
+    }
+
+    /// Read up to `limit` stored points with their vectors and payloads, in
+    /// a stable order. Pass the previous page's `next_offset` to continue;
+    /// `None` starts from the beginning.
+    async fn scroll(&self, offset: Option<String>, limit: usize) -> Result<ScrollPage>;
 }
 
 /// Search result from index.
@@ -111,6 +139,26 @@ pub struct SearchResult {
     pub score: f32,
     pub vector: Option<Vector>,
     pub payload: HashMap<String, serde_json::Value>,
+    /// Whether payload fields were cut or left out to fit
+    /// [`SearchFilter::max_payload_bytes`]
+    pub payload_truncated: bool,
+}
+
+/// A stored point, as read back by [`VectorIndex::scroll`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexPoint {
+    pub doc_id: DocumentId,
+    pub vector: Vector,
+    #[serde(default)]
+    pub payload: HashMap<String, serde_json::Value>,
+}
+
+/// A page of [`VectorIndex::scroll`].
+#[derive(Debug, Clone, Default)]
+pub struct ScrollPage {
+    pub points: Vec<IndexPoint>,
+    /// Cursor of the next page, `None` after the last one
+    pub next_offset: Option<String>,
 }
 
 /// Search filter options.
@@ -119,6 +167,41 @@ pub struct SearchFilter {
     pub entity_type: Option<String>,
     pub workspace_id: Option<String>,
     pub metadata_filters: HashMap<String, serde_json::Value>,
+    /// Filter expression over payload fields, combined with the other
+    /// filters by AND
+    pub expr: Option<FilterExpr>,
+    /// Payload fields to return with each result; all of them when empty
+    pub with_payload: Vec<String>,
+    /// Byte limit of each result's payload; large text fields are cut to
+    /// fit it
+    pub max_payload_bytes: Option<usize>,
+}
+
+impl SearchFilter {
+    /// Narrow a result's payload to the requested fields and byte limit.
+    fn select_payload(
+        &self,
+        mut payload: HashMap<String, serde_json::Value>,
+    ) -> (HashMap<String, serde_json::Value>, bool) {
+        if self.with_payload.is_empty() && self.max_payload_bytes.is_none() {
+            return (payload, false);
+        }
+
+        let fields = if self.with_payload.is_empty() {
+            let mut fields: Vec<String> = payload.keys().cloned().collect();
+            fields.sort();
+            fields
+        } else {
+            self.with_payload.clone()
+        };
+        let (selected, truncated) =
+            select_payload(&fields, self.max_payload_bytes, |field| payload.remove(field));
+        let selected = match selected {
+            serde_json::Value::Object(map) => map.into_iter().collect(),
+            _ => HashMap::new(),
+        };
+        (selected, truncated)
+    }
 }
 
 /// Sparse vector for hybrid search.
@@ -131,11 +214,15 @@ pub struct SparseVector {
 /// Index statistics.
 #[derive(Debug, Clone)]
 pub struct IndexStats {
+    /// Documents the vectors belong to; a chunked document counts once
+    pub total_documents: usize,
     pub total_vectors: usize,
     pub dimension: usize,
     pub metric: SimilarityMetric,
     pub indexed_vectors: usize,
     pub collection_status: String,
+    /// Embedding cache counters; only set by `SemanticSearchEngine::stats`
+    pub embedding_cache: Option<crate::cache::EmbeddingCacheStats>,
 }
 
 /// Qdrant vector store implementation.
@@ -159,6 +246,8 @@ pub struct QdrantVectorStore {
     metadata_cache: Arc<DashMap<DocumentId, HashMap<String, serde_json::Value>>>,
     /// Metrics for monitoring
     metrics: Arc<QdrantMetrics>,
+    /// Retries of failed requests, `config.max_retries` after the first
+    retry: RetryPolicy,
 }
 
 /// Metrics for Qdrant operations.
@@ -172,12 +261,159 @@ pub struct QdrantMetrics {
     pub avg_search_latency_ms: std::sync::atomic::AtomicU64,
 }
 
+
+/// Parameters a collection is created with and validated against.
+#[derive(Debug, Clone, Copy)]
+struct CollectionSpec {
+    dimension: u64,
+    distance: QdrantDistance,
+    hnsw_m: u64,
+    ef_construct: u64,
+    quantization: QuantizationType,
+}
+
+fn qdrant_distance(metric: SimilarityMetric) -> QdrantDistance {
+    match metric {
+        SimilarityMetric::Cosine => QdrantDistance::Cosine,
+        SimilarityMetric::Euclidean => QdrantDistance::Euclid,
+        SimilarityMetric::DotProduct => QdrantDistance::Dot,
+    }
+}
+
+fn distance_name(distance: i32) -> String {
+    QdrantDistance::try_from(distance)
+        .map(|distance| distance.as_str_name().to_lowercase())
+        .unwrap_or_else(|_| format!("distance({})", distance))
+}
+
+fn quantization_name(quantization: Option<&Quantization>) -> &'static str {
+    match quantization {
+        Some(Quantization::Scalar(_)) => "scalar",
+        Some(Quantization::Product(_)) => "product",
+        Some(Quantization::Binary(_)) => "binary",
+        None => "none",
+    }
+}
+
+/// Create quantization configuration based on settings.
+fn create_quantization_config(quantization: QuantizationType) -> Option<Quantization> {
+    match quantization {
+        QuantizationType::Scalar => {
+            // Scalar quantization - 8-bit quantization with 99th percentile
+            Some(Quantization::Scalar(ScalarQuantization {
+                r#type: qdrant_client::qdrant::QuantizationType::Int8.into(),
+                quantile: Some(0.99),
+                always_ram: Some(true),
+                ..Default::default()
+            }))
+        }
+        QuantizationType::Product => {
+            // Product quantization - aggressive compression (16x)
+            Some(Quantization::Product(ProductQuantization {
+                compression: CompressionRatio::X16.into(),
+                always_ram: Some(true),
+                ..Default::default()
+            }))
+        }
+        QuantizationType::None => None,
+    }
+}
+
+/// Parameters of an existing collection that differ from `spec`. Settings
+/// on the vector params take precedence over collection-wide ones, as they
+/// do in Qdrant.
+fn collection_mismatches(
+    info: &qdrant_client::qdrant::CollectionInfo,
+    spec: &CollectionSpec,
+) -> Vec<CollectionParamMismatch> {
+    use qdrant_client::qdrant::vectors_config::Config as VectorsConfig;
+
+    let config = info.config.as_ref();
+    let params = config
+        .and_then(|c| c.params.as_ref())
+        .and_then(|p| p.vectors_config.as_ref())
+        .and_then(|v| match &v.config {
+            Some(VectorsConfig::Params(params)) => Some(params),
+            _ => None,
+        });
+    let Some(params) = params else {
+        return vec![CollectionParamMismatch::NamedVectors];
+    };
+
+    let mut mismatches = Vec::new();
+    if params.size != spec.dimension {
+        mismatches.push(CollectionParamMismatch::Dimension {
+            expected: spec.dimension,
+            actual: params.size,
+        });
+    }
+    if params.distance != spec.distance as i32 {
+        mismatches.push(CollectionParamMismatch::Distance {
+            expected: distance_name(spec.distance as i32),
+            actual: distance_name(params.distance),
+        });
+    }
+
+    let collection_hnsw = config.and_then(|c| c.hnsw_config.as_ref());
+    let hnsw = |field: fn(&HnswConfigDiff) -> Option<u64>| {
+        params
+            .hnsw_config
+            .as_ref()
+            .and_then(field)
+            .or_else(|| collection_hnsw.and_then(field))
+    };
+    if let Some(m) = hnsw(|h| h.m).filter(|m| *m != spec.hnsw_m) {
+        mismatches.push(CollectionParamMismatch::HnswM {
+            expected: spec.hnsw_m,
+            actual: m,
+        });
+    }
+    if let Some(ef_construct) = hnsw(|h| h.ef_construct).filter(|ef| *ef != spec.ef_construct) {
+        mismatches.push(CollectionParamMismatch::HnswEfConstruct {
+            expected: spec.ef_construct,
+            actual: ef_construct,
+        });
+    }
+
+    let quantization = params
+        .quantization_config
+        .as_ref()
+        .or_else(|| config.and_then(|c| c.quantization_config.as_ref()))
+        .and_then(|q| q.quantization.as_ref());
+    let expected = create_quantization_config(spec.quantization);
+    let (expected, actual) = (quantization_name(expected.as_ref()), quantization_name(quantization));
+    if expected != actual {
+        mismatches.push(CollectionParamMismatch::Quantization {
+            expected: expected.to_string(),
+            actual: actual.to_string(),
+        });
+    }
+
+    mismatches
+}
+
 impl QdrantVectorStore {
-    /// Create a new Qdrant vector store.
+    /// Create a new Qdrant vector store, creating its collection if missing.
     pub async fn new(
         config: QdrantConfig,
         dimension: usize,
         similarity_metric: SimilarityMetric,
+    ) -> Result<Self> {
+        let store = Self::connect(config, dimension, similarity_metric).await?;
+
+        // Ensure collection exists with optimal configuration
+        store.create_collection_if_missing().await?;
+
+        info!("Qdrant vector store initialized successfully");
+        Ok(store)
+    }
+
+    /// Connect to Qdrant without touching the collection, to manage it with
+    /// [`VectorIndex::ensure_collection`] and friends.
+    pub async fn connect(
+        config: QdrantConfig,
+        dimension: usize,
+        similarity_metric: SimilarityMetric,
     ) -> Result<Self> {
         info!(
             "Initializing Qdrant vector store: url={}, collection={}",
@@ -189,21 +425,21 @@ impl QdrantVectorStore {
 
         let collection_name = format!("{}{}", config.collection_prefix, config.collection_name);
 
-        let store = Self {
+        Ok(Self {
             client: Arc::new(client),
-            config: config.clone(),
-            collection_name: collection_name.clone(),
+            collection_name,
             dimension,
             similarity_metric,
             metadata_cache: Arc::new(DashMap::new()),
             metrics: Arc::new(QdrantMetrics::default()),
-        };
-
-        // Ensure collection exists with optimal configuration
-        store.ensure_collection().await?;
+            retry: RetryPolicy::default().with_max_attempts(config.max_retries as u32 + 1),
+            config,
+        })
+    }
 
-        info!("Qdrant vector store initialized successfully");
-        Ok(store)
+    /// Full name of the store's collection, including the prefix.
+    pub fn collection_name(&self) -> &str {
+        &self.collection_name
     }
 
     /// Create Qdrant client with retry logic and connection pooling.
@@ -269,10 +505,10 @@ impl QdrantVectorStore {
                             continue;
                         }
                         Err(e) => {
-                            return Err(SemanticError::Qdrant(format!(
-                                "Failed to connect to Qdrant after {} retries: {}",
-                                max_retries, e
-                            )));
+                            warn!("Failed to connect to Qdrant after {} retries", max_retries);
+                            return Err(SemanticError::VectorStoreUnavailable {
+                                source: Box::new(e),
+                            });
                         }
                     }
                 }
@@ -286,36 +522,55 @@ impl QdrantVectorStore {
         }
     }
 
-    /// Ensure collection exists with optimal configuration including quantization.
-    async fn ensure_collection(&self) -> Result<()> {
-        // Check if collection exists
+    /// Parameters a collection of this store is created with: the store's
+    /// dimension and quantization, with the given metric and HNSW graph.
+    fn collection_spec(&self, metric: SimilarityMetric, hnsw_m: u64, ef_construct: u64) -> CollectionSpec {
+        CollectionSpec {
+            dimension: self.dimension as u64,
+            distance: qdrant_distance(metric),
+            hnsw_m,
+            ef_construct,
+            quantization: self.config.effective_quantization(),
+        }
+    }
+
+    /// The collection parameters of the store's own configuration.
+    fn default_spec(&self) -> CollectionSpec {
+        self.collection_spec(
+            self.similarity_metric,
+            self.config.hnsw_config.m,
+            self.config.hnsw_config.ef_construct,
+        )
+    }
+
+    async fn collection_exists(&self) -> Result<bool> {
         let collections = self.client.list_collections().await
-            .map_err(|e| SemanticError::Qdrant(format!("Failed to list collections: {}", e)))?;
+            .map_err(|e| self.qdrant_error("Failed to list collections", e))?;
 
-        let collection_exists = collections
+        Ok(collections
             .collections
             .iter()
-            .any(|c| c.name == self.collection_name);
+            .any(|c| c.name == self.collection_name))
+    }
 
-        if collection_exists {
+    /// Create the collection from the store's configuration if it does not exist yet.
+    async fn create_collection_if_missing(&self) -> Result<()> {
+        if self.collection_exists().await? {
             info!("Collection '{}' already exists", self.collection_name);
             return Ok(());
         }
+        self.create_collection(&self.default_spec()).await
+    }
 
+    /// Create the collection with optimal configuration including quantization.
+    async fn create_collection(&self, spec: &CollectionSpec) -> Result<()> {
         info!("Creating collection '{}' with advanced features", self.collection_name);
 
-        // Convert similarity metric to Qdrant distance
-        let distance = match self.similarity_metric {
-            SimilarityMetric::Cosine => QdrantDistance::Cosine,
-            SimilarityMetric::Euclidean => QdrantDistance::Euclid,
-            SimilarityMetric::DotProduct => QdrantDistance::Dot,
-        };
-
         // Create vector params with optimal HNSW configuration
-        let mut vector_params = VectorParamsBuilder::new(self.dimension as u64, distance)
+        let mut vector_params = VectorParamsBuilder::new(spec.dimension, spec.distance)
             .hnsw_config(HnswConfigDiff {
-                m: Some(self.config.hnsw_config.m),
-                ef_construct: Some(self.config.hnsw_config.ef_construct),
+                m: Some(spec.hnsw_m),
+                ef_construct: Some(spec.ef_construct),
                 full_scan_threshold: Some(self.config.hnsw_config.full_scan_threshold),
                 max_indexing_threads: Some(self.config.hnsw_config.max_indexing_threads),
                 on_disk: Some(self.config.on_disk_payload),
@@ -324,7 +579,7 @@ impl QdrantVectorStore {
             .on_disk(self.config.on_disk_payload);
 
         // Add quantization for memory efficiency
-        if let Some(quantization) = self.create_quantization_config() {
+        if let Some(quantization) = create_quantization_config(spec.quantization) {
             vector_params = vector_params.quantization_config(quantization);
         }
 
@@ -343,7 +598,7 @@ impl QdrantVectorStore {
                     })
             )
             .await
-            .map_err(|e| SemanticError::Qdrant(format!("Failed to create collection: {}", e)))?;
+            .map_err(|e| self.qdrant_error("Failed to create collection", e))?;
 
         info!("Collection '{}' created successfully", self.collection_name);
 
@@ -353,34 +608,6 @@ impl QdrantVectorStore {
         Ok(())
     }
 
-    /// Create quantization configuration based on settings.
-    fn create_quantization_config(&self) -> Option<Quantization> {
-        if !self.config.enable_quantization {
-            return None;
-        }
-
-        match self.config.quantization_type {
-            QuantizationType::Scalar => {
-                // Scalar quantization - 8-bit quantization with 99th percentile
-                Some(Quantization::Scalar(ScalarQuantization {
-                    r#type: qdrant_client::qdrant::QuantizationType::Int8.into(),
-                    quantile: Some(0.99),
-                    always_ram: Some(true),
-                    ..Default::default()
-                }))
-            }
-            QuantizationType::Product => {
-                // Product quantization - aggressive compression (16x)
-                Some(Quantization::Product(ProductQuantization {
-                    compression: CompressionRatio::X16.into(),
-                    always_ram: Some(true),
-                    ..Default::default()
-                }))
-            }
-            QuantizationType::None => None,
-        }
-    }
-
     /// Create payload indexes for efficient filtering.
     async fn create_payload_indexes(&self) -> Result<()> {
         info!("Creating payload indexes for collection '{}'", self.collection_name);
@@ -395,7 +622,7 @@ impl QdrantVectorStore {
                 )
             )
             .await
-            .map_err(|e| SemanticError::Qdrant(format!("Failed to create entity_type index: {}", e)))?;
+            .map_err(|e| self.qdrant_error("Failed to create entity_type index", e))?;
 
         // Create index for workspace_id field (keyword)
         self.client
@@ -407,7 +634,7 @@ impl QdrantVectorStore {
                 )
             )
             .await
-            .map_err(|e| SemanticError::Qdrant(format!("Failed to create workspace_id index: {}", e)))?;
+            .map_err(|e| self.qdrant_error("Failed to create workspace_id index", e))?;
 
         // Create index for created_at field (integer for timestamps)
         self.client
@@ -419,7 +646,19 @@ impl QdrantVectorStore {
                 )
             )
             .await
-            .map_err(|e| SemanticError::Qdrant(format!("Failed to create created_at index: {}", e)))?;
+            .map_err(|e| self.qdrant_error("Failed to create created_at index", e))?;
+
+        // Create index for indexed_at field (lets snapshots detect the latest write)
+        self.client
+            .create_field_index(
+                CreateFieldIndexCollectionBuilder::new(
+                    &self.collection_name,
+                    "indexed_at",
+                    FieldType::Integer,
+                )
+            )
+            .await
+            .map_err(|e| self.qdrant_error("Failed to create indexed_at index", e))?;
 
         info!("Payload indexes created successfully");
         Ok(())
@@ -453,35 +692,54 @@ impl QdrantVectorStore {
         F: Fn() -> Fut,
         Fut: std::future::Future<Output = std::result::Result<T, qdrant_client::QdrantError>>,
     {
-        let mut retries = 0;
-        let max_retries = self.config.max_retries;
+        let mut attempt = 1;
 
         loop {
             match operation().await {
                 Ok(result) => return Ok(result),
-                Err(e) if retries < max_retries => {
+                Err(e) => {
+                    let err = self.qdrant_error("", e);
+                    let Some(delay) = self.retry.next_delay(attempt, &err) else {
+                        self.metrics
+                            .failed_operations
+                            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                        return Err(err);
+                    };
+
                     warn!(
-                        "Operation failed (attempt {}/{}): {}",
-                        retries + 1,
-                        max_retries,
-                        e
+                        "Operation failed (attempt {}/{}, {}): {}",
+                        attempt,
+                        self.retry.max_attempts,
+                        err.error_code(),
+                        err
                     );
                     self.metrics
                         .retry_count
                         .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-                    retries += 1;
-                    sleep(Duration::from_millis(100 * 2u64.pow(retries as u32))).await;
-                }
-                Err(e) => {
-                    self.metrics
-                        .failed_operations
-                        .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-                    return Err(SemanticError::Qdrant(e.to_string()));
+                    attempt += 1;
+                    sleep(delay).await;
                 }
             }
         }
     }
 
+    /// Classify a Qdrant error, naming this store's collection when it is
+    /// missing. Unclassified errors keep their message, prefixed by `context`.
+    fn qdrant_error(&self, context: &str, err: qdrant_client::QdrantError) -> SemanticError {
+        if qdrant_status_code(&err) == Some(grpc_code::NOT_FOUND) {
+            return SemanticError::CollectionMissing {
+                name: self.collection_name.clone(),
+            };
+        }
+
+        match SemanticError::from(err) {
+            SemanticError::Qdrant(message) if !context.is_empty() => {
+                SemanticError::Qdrant(format!("{}: {}", context, message))
+            }
+            err => err,
+        }
+    }
+
     /// Get collection info for monitoring.
     pub async fn get_collection_info(&self) -> Result<qdrant_client::qdrant::CollectionInfo> {
         self.with_retry(|| self.client.collection_info(&self.collection_name))
@@ -499,7 +757,7 @@ impl QdrantVectorStore {
     }
 
     /// Convert SearchFilter to Qdrant Filter.
-    fn build_qdrant_filter(&self, filter: &SearchFilter) -> Option<Filter> {
+    fn build_qdrant_filter(&self, filter: &SearchFilter) -> Result<Option<Filter>> {
         use qdrant_client::qdrant::Condition as FilterCondition;
 
         let mut conditions: Vec<FilterCondition> = Vec::new();
@@ -534,11 +792,96 @@ impl QdrantVectorStore {
             }
         }
 
+        // Filter by expression
+        if let Some(expr) = &filter.expr {
+            expr.validate()?;
+            conditions.push(Self::expr_condition(expr)?);
+        }
+
         // Only create filter if we have conditions
         if conditions.is_empty() {
-            None
+            Ok(None)
         } else {
-            Some(Filter::must(conditions))
+            Ok(Some(Filter::must(conditions)))
+        }
+    }
+
+    /// Convert a validated filter expression to a Qdrant condition.
+    fn expr_condition(expr: &FilterExpr) -> Result<Condition> {
+        let conditions = |filters: &[FilterExpr]| filters.iter().map(Self::expr_condition).collect::<Result<Vec<_>>>();
+
+        match expr {
+            FilterExpr::And { filters } => Ok(Filter::must(conditions(filters)?).into()),
+            FilterExpr::Or { filters } => Ok(Filter::should(conditions(filters)?).into()),
+            FilterExpr::Not { filter } => Ok(Filter::must_not([Self::expr_condition(filter)?]).into()),
+            FilterExpr::Eq { field, value } => Self::match_condition(field, value),
+            FilterExpr::In { field, values } => {
+                if let Some(keywords) = values.iter().map(|v| v.as_str().map(String::from)).collect::<Option<Vec<_>>>() {
+                    Ok(Condition::matches(field.clone(), keywords))
+                } else if let Some(integers) = values.iter().map(|v| v.as_i64()).collect::<Option<Vec<_>>>() {
+                    Ok(Condition::matches(field.clone(), integers))
+                } else {
+                    let matches = values
+                        .iter()
+                        .map(|value| Self::match_condition(field, value))
+                        .collect::<Result<Vec<_>>>()?;
+                    Ok(Filter::should(matches).into())
+                }
+            }
+            FilterExpr::Range { field, gt, gte, lt, lte } => {
+                if expr.bounds().iter().all(|bound| bound.is_number()) {
+                    let number = |bound: &Option<serde_json::Value>| bound.as_ref().and_then(|v| v.as_f64());
+                    Ok(Condition::range(
+                        field.clone(),
+                        Range {
+                            gt: number(gt),
+                            gte: number(gte),
+                            lt: number(lt),
+                            lte: number(lte),
+                        },
+                    ))
+                } else {
+                    let timestamp = |bound: &Option<serde_json::Value>| {
+                        let datetime = parse_datetime(bound.as_ref()?.as_str()?)?;
+                        Some(Timestamp {
+                            seconds: datetime.timestamp(),
+                            nanos: datetime.timestamp_subsec_nanos() as i32,
+                        })
+                    };
+                    Ok(Condition::datetime_range(
+                        field.clone(),
+                        DatetimeRange {
+                            gt: timestamp(gt),
+                            gte: timestamp(gte),
+                            lt: timestamp(lt),
+                            lte: timestamp(lte),
+                        },
+                    ))
+                }
+            }
+        }
+    }
+
+    /// Condition matching one scalar value; floats match as a point range.
+    fn match_condition(field: &str, value: &serde_json::Value) -> Result<Condition> {
+        match value {
+            serde_json::Value::String(s) => Ok(Condition::matches(field, s.clone())),
+            serde_json::Value::Bool(b) => Ok(Condition::matches(field, *b)),
+            serde_json::Value::Number(n) => match n.as_i64() {
+                Some(i) => Ok(Condition::matches(field, i)),
+                None => Ok(Condition::range(
+                    field,
+                    Range {
+                        gte: n.as_f64(),
+                        lte: n.as_f64(),
+                        ..Default::default()
+                    },
+                )),
+            },
+            _ => Err(SemanticError::InvalidFilter(format!(
+                "`{}` can only be compared to a string, number or boolean",
+                field
+            ))),
         }
     }
 }
@@ -706,10 +1049,19 @@ impl VectorIndex for QdrantVectorStore {
             .with_vectors(true);
 
         // Add filter if provided
-        if let Some(search_filter) = filter {
-            if let Some(qdrant_filter) = self.build_qdrant_filter(&search_filter) {
+        if let Some(search_filter) = &filter {
+            if let Some(qdrant_filter) = self.build_qdrant_filter(search_filter)? {
                 search_builder = search_builder.filter(qdrant_filter);
             }
+
+            // Only transfer the requested payload fields, and the document
+            // id results are keyed by
+            if !search_filter.with_payload.is_empty() {
+                let mut fields = search_filter.with_payload.clone();
+                fields.push("doc_id".to_string());
+                search_builder = search_builder
+                    .with_payload(SelectorOptions::Include(PayloadIncludeSelector { fields }));
+            }
         }
 
         // Add search params if provided
@@ -718,30 +1070,32 @@ impl VectorIndex for QdrantVectorStore {
         }
 
         // Execute search with retry - direct call without with_retry wrapper
-        let mut retries = 0;
-        let max_retries = self.config.max_retries;
+        let mut attempt = 1;
 
         let response = loop {
             match self.client.search_points(search_builder.clone()).await {
                 Ok(response) => break response,
-                Err(e) if retries < max_retries => {
+                Err(e) => {
+                    let err = self.qdrant_error("Search failed", e);
+                    let Some(delay) = self.retry.next_delay(attempt, &err) else {
+                        self.metrics
+                            .failed_operations
+                            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                        return Err(err);
+                    };
+
                     warn!(
-                        "Search failed (attempt {}/{}): {}",
-                        retries + 1,
-                        max_retries,
-                        e
+                        "Search failed (attempt {}/{}, {}): {}",
+                        attempt,
+                        self.retry.max_attempts,
+                        err.error_code(),
+                        err
                     );
                     self.metrics
                         .retry_count
                         .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-                    retries += 1;
-                    sleep(Duration::from_millis(50 * 2u64.pow(retries as u32))).await;
-                }
-                Err(e) => {
-                    self.metrics
-                        .failed_operations
-                        .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-                    return Err(SemanticError::Qdrant(format!("Search failed: {}", e)));
+                    attempt += 1;
+                    sleep(delay).await;
                 }
             }
         };
@@ -773,12 +1127,17 @@ impl VectorIndex for QdrantVectorStore {
                         serde_json::to_value(v).ok().map(|json_val| (k, json_val))
                     })
                     .collect();
+                let (payload, payload_truncated) = match &filter {
+                    Some(search_filter) => search_filter.select_payload(payload),
+                    None => (payload, false),
+                };
 
                 Some(SearchResult {
                     doc_id,
                     score: scored_point.score,
                     vector,
                     payload,
+                    payload_truncated,
                 })
             })
             .collect();
@@ -883,9 +1242,9 @@ impl VectorIndex for QdrantVectorStore {
         self.client
             .delete_collection(&self.collection_name)
             .await
-            .map_err(|e| SemanticError::Qdrant(format!("Failed to delete collection: {}", e)))?;
+            .map_err(|e| self.qdrant_error("Failed to delete collection", e))?;
 
-        self.ensure_collection().await?;
+        self.create_collection(&self.default_spec()).await?;
 
         // Clear cache
         self.metadata_cache.clear();
@@ -927,11 +1286,13 @@ impl VectorIndex for QdrantVectorStore {
             .unwrap_or_else(|| "Unknown".to_string());
 
         IndexStats {
+            total_documents: total_vectors,
             total_vectors,
             dimension: self.dimension,
             metric: self.similarity_metric,
             indexed_vectors,
             collection_status: status,
+            embedding_cache: None,
         }
     }
 
@@ -960,6 +1321,98 @@ impl VectorIndex for QdrantVectorStore {
 
         Ok(())
     }
+
+    async fn ensure_collection(&self, config: &IndexConfig) -> Result<()> {
+        let spec = self.collection_spec(
+            config.similarity_metric,
+            config.hnsw_m as u64,
+            config.hnsw_ef_construction as u64,
+        );
+        if !self.collection_exists().await? {
+            return self.create_collection(&spec).await;
+        }
+
+        let mismatches = collection_mismatches(&self.get_collection_info().await?, &spec);
+        if !mismatches.is_empty() {
+            return Err(SemanticError::CollectionMismatch {
+                name: self.collection_name.clone(),
+                mismatches,
+            });
+        }
+        Ok(())
+    }
+
+    async fn drop_collection(&self) -> Result<()> {
+        if !self.collection_exists().await? {
+            return Ok(());
+        }
+
+        info!("Dropping collection '{}'", self.collection_name);
+        self.client
+            .delete_collection(&self.collection_name)
+            .await
+            .map_err(|e| self.qdrant_error("Failed to delete collection", e))?;
+        self.metadata_cache.clear();
+        Ok(())
+    }
+
+    async fn scroll(&self, offset: Option<String>, limit: usize) -> Result<ScrollPage> {
+        use qdrant_client::qdrant::point_id::PointIdOptions;
+        use qdrant_client::qdrant::vectors_output::VectorsOptions;
+
+        let mut request = ScrollPointsBuilder::new(&self.collection_name)
+            .limit(limit as u32)
+            .with_payload(true)
+            .with_vectors(true);
+        if let Some(offset) = offset {
+            let offset = match offset.parse::<u64>() {
+                Ok(num) => PointId::from(num),
+                Err(_) => PointId::from(offset),
+            };
+            request = request.offset(offset);
+        }
+
+        let response = self
+            .with_retry(|| self.client.scroll(request.clone()))
+            .await?;
+
+        let points = response
+            .result
+            .into_iter()
+            .filter_map(|point| {
+                let doc_id = point
+                    .payload
+                    .get("doc_id")
+                    .and_then(|v| v.as_str())
+                    .map(String::from)?;
+                let vector = match point.vectors {
+                    Some(VectorsOutput {
+                        vectors_options: Some(VectorsOptions::Vector(v)),
+                    }) => v.data,
+                    _ => {
+                        warn!("Skipping point {} without an unnamed vector", doc_id);
+                        return None;
+                    }
+                };
+                let payload = point
+                    .payload
+                    .into_iter()
+                    .filter_map(|(k, v)| serde_json::to_value(v).ok().map(|json_val| (k, json_val)))
+                    .collect();
+                Some(IndexPoint { doc_id, vector, payload })
+            })
+            .collect();
+
+        let next_offset = response
+            .next_page_offset
+            .and_then(|id| id.point_id_options)
+            .map(|id| match id {
+                PointIdOptions::Num(num) => num.to_string(),
+                PointIdOptions::Uuid(uuid) => uuid,
+            });
+
+        Ok(ScrollPage { points, next_offset })
+    }
 }
 
 /// Mock vector store for testing without Qdrant.
@@ -1046,6 +1499,9 @@ impl VectorIndex for MockVectorStore {
                 got: query.len(),
             });
         }
+        if let Some(expr) = filter.as_ref().and_then(|filter| filter.expr.as_ref()) {
+            expr.validate()?;
+        }
 
         // Calculate similarity scores for all vectors
         let mut results: Vec<_> = self
@@ -1105,15 +1561,28 @@ impl VectorIndex for MockVectorStore {
                             return None;
                         }
                     }
+
+                    // Filter by expression, evaluated here since there is
+                    // no native filtering
+                    if let Some(expr) = &search_filter.expr {
+                        if !expr.matches(&|field| payload.get(field).cloned()) {
+                            return None;
+                        }
+                    }
                 }
 
                 let score = self.similarity_metric.calculate(query, vector);
+                let (payload, payload_truncated) = match &filter {
+                    Some(search_filter) => search_filter.select_payload(payload.clone()),
+                    None => (payload.clone(), false),
+                };
 
                 Some(SearchResult {
                     doc_id,
                     score,
                     vector: Some(vector.clone()),
-                    payload: payload.clone(),
+                    payload,
+                    payload_truncated,
                 })
             })
             .collect();
@@ -1160,11 +1629,13 @@ impl VectorIndex for MockVectorStore {
 
     async fn stats(&self) -> IndexStats {
         IndexStats {
+            total_documents: self.vectors.len(),
             total_vectors: self.vectors.len(),
             dimension: self.dimension,
             metric: self.similarity_metric,
             indexed_vectors: self.vectors.len(),
             collection_status: "Green".to_string(),
+            embedding_cache: None,
         }
     }
 
@@ -1175,6 +1646,48 @@ impl VectorIndex for MockVectorStore {
     async fn optimize(&self) -> Result<()> {
         Ok(())
     }
+
+    async fn ensure_collection(&self, config: &IndexConfig) -> Result<()> {
+        if config.similarity_metric != self.similarity_metric {
+            return Err(SemanticError::CollectionMismatch {
+                name: "mock".to_string(),
+                mismatches: vec![CollectionParamMismatch::Distance {
+                    expected: format!("{:?}", config.similarity_metric).to_lowercase(),
+                    actual: format!("{:?}", self.similarity_metric).to_lowercase(),
+                }],
+            });
+        }
+        Ok(())
+    }
+
+    async fn drop_collection(&self) -> Result<()> {
+        self.vectors.clear();
+        Ok(())
+    }
+
+    async fn scroll(&self, offset: Option<String>, limit: usize) -> Result<ScrollPage> {
+        let mut ids: Vec<DocumentId> = self.vectors.iter().map(|entry| entry.key().clone()).collect();
+        ids.sort();
+        let start = offset.map_or(0, |offset| ids.partition_point(|id| *id < offset));
+
+        let points = ids[start..]
+            .iter()
+            .take(limit)
+            .filter_map(|id| {
+                let entry = self.vectors.get(id)?;
+                let (vector, payload) = entry.value();
+                Some(IndexPoint {
+                    doc_id: id.clone(),
+                    vector: vector.clone(),
+                    payload: payload.clone(),
+                })
+            })
+            .collect();
+        Ok(ScrollPage {
+            points,
+            next_offset: ids.get(start + limit).cloned(),
+        })
+    }
 }
 
 #[cfg(test)]
@@ -1612,6 +2125,9 @@ mod tests {
             entity_type: Some("code".to_string()),
             workspace_id: Some("workspace1".to_string()),
             metadata_filters,
+            expr: None,
+            with_payload: Vec::new(),
+            max_payload_bytes: None,
         };
 
         let results = store.search_with_options(&vec1, 10, Some(filter), None).await.unwrap();
@@ -1672,4 +2188,262 @@ mod tests {
         // Should return all documents
         assert_eq!(results.len(), 2);
     }
+
+    #[tokio::test]
+    async fn test_mock_search_returns_selected_payload() {
+        let store = MockVectorStore::new(128, SimilarityMetric::Cosine);
+        let vec1 = create_test_vector(128, 1);
+        let payload = HashMap::from([
+            ("name".to_string(), json!("parse")),
+            ("content".to_string(), json!("fn parse() { todo!() }")),
+            ("embedding_model".to_string(), json!("mock")),
+        ]);
+        store.insert_with_payload("doc1".to_string(), vec1.clone(), payload).await.unwrap();
+
+        // Without a selection the whole payload is returned
+        let results = store.search_with_options(&vec1, 1, None, None).await.unwrap();
+        assert_eq!(results[0].payload.len(), 3);
+        assert!(!results[0].payload_truncated);
+
+        let filter = SearchFilter {
+            with_payload: vec!["name".to_string(), "content".to_string()],
+            ..Default::default()
+        };
+        let results = store.search_with_options(&vec1, 1, Some(filter), None).await.unwrap();
+        assert_eq!(
+            results[0].payload,
+            HashMap::from([
+                ("name".to_string(), json!("parse")),
+                ("content".to_string(), json!("fn parse() { todo!() }")),
+            ])
+        );
+        assert!(!results[0].payload_truncated);
+
+        let filter = SearchFilter {
+            with_payload: vec!["name".to_string(), "content".to_string()],
+            max_payload_bytes: Some(20),
+            ..Default::default()
+        };
+        let results = store.search_with_options(&vec1, 1, Some(filter), None).await.unwrap();
+        assert_eq!(results[0].payload["content"], json!("fn p"));
+        assert!(results[0].payload_truncated);
+    }
+
+    #[tokio::test]
+    async fn test_mock_search_with_filter_expression() {
+        let store = MockVectorStore::new(128, SimilarityMetric::Cosine);
+        let docs = [
+            ("doc1", "rust", "2024-03-01", "auth"),
+            ("doc2", "rust", "2023-06-01", "auth"),
+            ("doc3", "rust", "2024-05-01", "billing"),
+            ("doc4", "python", "2024-05-01", "session"),
+            ("doc5", "rust", "2024-07-01", "session"),
+        ];
+        for (i, (id, language, modified_at, module)) in docs.iter().enumerate() {
+            let payload = HashMap::from([
+                ("language".to_string(), json!(language)),
+                ("modified_at".to_string(), json!(modified_at)),
+                ("module".to_string(), json!(module)),
+            ]);
+            store
+                .insert_with_payload(id.to_string(), create_test_vector(128, i as u64 + 1), payload)
+                .await
+                .unwrap();
+        }
+
+        // language == rust AND modified_at > 2024-01-01 AND (module == auth OR module == session)
+        let expr = FilterExpr::and([
+            FilterExpr::eq("language", "rust"),
+            FilterExpr::range("modified_at").gt("2024-01-01"),
+            FilterExpr::or([FilterExpr::eq("module", "auth"), FilterExpr::eq("module", "session")]),
+        ]);
+        let filter = SearchFilter {
+            expr: Some(expr.clone()),
+            ..Default::default()
+        };
+        let results = store
+            .search_with_options(&create_test_vector(128, 1), 10, Some(filter), None)
+            .await
+            .unwrap();
+        let mut ids: Vec<_> = results.iter().map(|r| r.doc_id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["doc1", "doc5"]);
+
+        // The same expression translates to one nested Qdrant condition
+        let condition = QdrantVectorStore::expr_condition(&expr).unwrap();
+        match condition.condition_one_of {
+            Some(qdrant_client::qdrant::condition::ConditionOneOf::Filter(filter)) => {
+                assert_eq!(filter.must.len(), 3)
+            }
+            other => panic!("unexpected condition {:?}", other),
+        }
+
+        let filter = SearchFilter {
+            expr: Some(FilterExpr::eq("bad field", "x")),
+            ..Default::default()
+        };
+        let err = store
+            .search_with_options(&create_test_vector(128, 1), 10, Some(filter), None)
+            .await;
+        assert!(matches!(err, Err(SemanticError::InvalidFilter(_))));
+    }
+
+    fn collection_info(
+        size: u64,
+        distance: QdrantDistance,
+        m: u64,
+        quantization: Option<Quantization>,
+    ) -> qdrant_client::qdrant::CollectionInfo {
+        use qdrant_client::qdrant::{
+            CollectionConfig, CollectionInfo, CollectionParams, QuantizationConfig, VectorParams,
+            VectorsConfig, vectors_config,
+        };
+
+        CollectionInfo {
+            config: Some(CollectionConfig {
+                params: Some(CollectionParams {
+                    vectors_config: Some(VectorsConfig {
+                        config: Some(vectors_config::Config::Params(VectorParams {
+                            size,
+                            distance: distance as i32,
+                            ..Default::default()
+                        })),
+                    }),
+                    ..Default::default()
+                }),
+                hnsw_config: Some(HnswConfigDiff {
+                    m: Some(m),
+                    ef_construct: Some(200),
+                    ..Default::default()
+                }),
+                quantization_config: quantization.map(|q| QuantizationConfig {
+                    quantization: Some(q),
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_collection_mismatches() {
+        let spec = CollectionSpec {
+            dimension: 384,
+            distance: QdrantDistance::Cosine,
+            hnsw_m: 16,
+            ef_construct: 200,
+            quantization: QuantizationType::Scalar,
+        };
+        let scalar = create_quantization_config(QuantizationType::Scalar);
+
+        let matching = collection_info(384, QdrantDistance::Cosine, 16, scalar.clone());
+        assert!(collection_mismatches(&matching, &spec).is_empty());
+
+        // Dimension drift after switching embedding models
+        let drifted = collection_info(1536, QdrantDistance::Dot, 32, None);
+        assert_eq!(
+            collection_mismatches(&drifted, &spec),
+            vec![
+                CollectionParamMismatch::Dimension { expected: 384, actual: 1536 },
+                CollectionParamMismatch::Distance {
+                    expected: "cosine".to_string(),
+                    actual: "dot".to_string(),
+                },
+                CollectionParamMismatch::HnswM { expected: 16, actual: 32 },
+                CollectionParamMismatch::Quantization {
+                    expected: "scalar".to_string(),
+                    actual: "none".to_string(),
+                },
+            ]
+        );
+
+        let err = SemanticError::CollectionMismatch {
+            name: "cortex_semantic_vectors".to_string(),
+            mismatches: collection_mismatches(&drifted, &spec),
+        };
+        assert!(err.to_string().contains("dimension is 1536, expected 384"));
+
+        let named = qdrant_client::qdrant::CollectionInfo::default();
+        assert_eq!(
+            collection_mismatches(&named, &spec),
+            vec![CollectionParamMismatch::NamedVectors]
+        );
+    }
+
+    // Integration test - requires Qdrant server running
+    #[tokio::test]
+    async fn test_qdrant_collection_lifecycle() {
+        let qdrant = create_test_config();
+        let mut index = IndexConfig::default();
+        index.similarity_metric = SimilarityMetric::Cosine;
+
+        let store = QdrantVectorStore::connect(qdrant.clone(), 128, SimilarityMetric::Cosine)
+            .await
+            .unwrap();
+        store.ensure_collection(&index).await.unwrap();
+        store.insert("doc1".to_string(), create_test_vector(128, 1)).await.unwrap();
+        // Matching parameters leave the collection alone
+        store.ensure_collection(&index).await.unwrap();
+        assert_eq!(store.len().await, 1);
+
+        // Another embedding model, same collection
+        let drifted = QdrantVectorStore::connect(qdrant, 256, SimilarityMetric::Cosine)
+            .await
+            .unwrap();
+        match drifted.ensure_collection(&index).await {
+            Err(SemanticError::CollectionMismatch { mismatches, .. }) => assert!(mismatches
+                .contains(&CollectionParamMismatch::Dimension { expected: 256, actual: 128 })),
+            other => panic!("expected a mismatch, got {:?}", other),
+        }
+
+        drifted.recreate_collection(&index).await.unwrap();
+        assert_eq!(drifted.len().await, 0);
+        drifted.ensure_collection(&index).await.unwrap();
+
+        drifted.drop_collection().await.unwrap();
+        // Dropping twice is fine
+        drifted.drop_collection().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_mock_scroll() {
+        let store = MockVectorStore::new(128, SimilarityMetric::Cosine);
+        for i in 0..5 {
+            store.insert(format!("doc{}", i), create_test_vector(128, i)).await.unwrap();
+        }
+
+        let mut ids = Vec::new();
+        let mut offset = None;
+        loop {
+            let page = store.scroll(offset, 2).await.unwrap();
+            assert!(page.points.len() <= 2);
+            ids.extend(page.points.into_iter().map(|p| p.doc_id));
+            offset = page.next_offset;
+            if offset.is_none() {
+                break;
+            }
+        }
+        assert_eq!(ids, vec!["doc0", "doc1", "doc2", "doc3", "doc4"]);
+    }
+
+    #[tokio::test]
+    async fn test_mock_collection_lifecycle() {
+        let store = MockVectorStore::new(128, SimilarityMetric::Cosine);
+        store.insert("doc1".to_string(), create_test_vector(128, 1)).await.unwrap();
+
+        let mut index = IndexConfig::default();
+        index.similarity_metric = SimilarityMetric::Cosine;
+        store.ensure_collection(&index).await.unwrap();
+        assert_eq!(store.len().await, 1);
+
+        index.similarity_metric = SimilarityMetric::DotProduct;
+        assert!(matches!(
+            store.ensure_collection(&index).await,
+            Err(SemanticError::CollectionMismatch { .. })
+        ));
+
+        index.similarity_metric = SimilarityMetric::Cosine;
+        store.recreate_collection(&index).await.unwrap();
+        assert!(store.is_empty().await);
+    }
 }
@@ -7,12 +7,19 @@
 //! - "Self-Ask: Eliciting Reasoning via Self-Questioning" (Press et al., 2023)
 //! - "Least-to-Most Prompting Enables Complex Reasoning in Large Language Models" (Zhou et al., 2023)
 
-use crate::error::Result;
+use crate::error::{Result, SemanticError};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashSet, HashMap};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::Path;
 use unicode_segmentation::UnicodeSegmentation;
 
+/// Weight of the spelled-out form of a split identifier ("get user by id")
+const IDENTIFIER_PARTS_WEIGHT: f32 = 0.9;
+
+/// Most weighted terms kept per query, highest weights first
+const MAX_WEIGHTED_TERMS: usize = 8;
+
 /// Query intent classification.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -29,6 +36,12 @@ pub enum QueryIntent {
     Similarity,
     /// Looking for definitions
     Definition,
+    /// Looking up a known symbol or file by name
+    Navigational,
+    /// Asking for the steps to get something done ("how do I ...")
+    Procedural,
+    /// Open-ended question about a topic or design
+    Exploratory,
 }
 
 /// Processed query with metadata.
@@ -37,6 +50,8 @@ pub struct ProcessedQuery {
     pub original: String,
     pub normalized: String,
     pub expanded: Vec<String>,
+    /// Expansion terms with their weight relative to the query itself (1.0)
+    pub weighted_terms: Vec<WeightedTerm>,
     pub intent: QueryIntent,
     pub keywords: Vec<String>,
     pub filters: QueryFilters,
@@ -44,6 +59,8 @@ pub struct ProcessedQuery {
     pub sub_queries: Vec<SubQuery>,
     /// Dependency graph for sub-queries
     pub query_graph: Option<QueryDependencyGraph>,
+    /// Kind of answer the query as a whole expects
+    pub answer_type: AnswerType,
 }
 
 /// A sub-query extracted from a complex query.
@@ -102,32 +119,47 @@ pub struct QueryProcessor {
 
 impl QueryProcessor {
     pub fn new() -> Self {
+        Self::with_expander(QueryExpander::new())
+    }
+
+    pub fn with_expander(expander: QueryExpander) -> Self {
         Self {
-            expander: QueryExpander::new(),
+            expander,
             decomposer: QueryDecomposer::new(),
         }
     }
 
     /// Process a raw query string.
     pub fn process(&self, query: &str) -> Result<ProcessedQuery> {
+        self.process_with(query, &ExpansionOptions::default())
+    }
+
+    /// Process a raw query string with per-call expansion options.
+    pub fn process_with(&self, query: &str, options: &ExpansionOptions) -> Result<ProcessedQuery> {
         let normalized = self.normalize(query);
-        let intent = self.detect_intent(&normalized);
+        // Symbol and file names need the original casing
+        let intent = self.detect_intent(query);
         let keywords = self.extract_keywords(&normalized);
         let filters = self.extract_filters(query);
-        let expanded = self.expander.expand(&normalized, &intent);
+        let expanded = self.expander.expand_with(&normalized, &intent, options);
+        // Identifier splitting needs the original casing
+        let weighted_terms = self.expander.weighted_terms(query, options);
 
         // Decompose complex queries into sub-queries
         let (sub_queries, query_graph) = self.decomposer.decompose(&normalized, &intent);
+        let answer_type = self.decomposer.answer_type(&normalized, intent, &sub_queries);
 
         Ok(ProcessedQuery {
             original: query.to_string(),
             normalized,
             expanded,
+            weighted_terms,
             intent,
             keywords,
             filters,
             sub_queries,
             query_graph,
+            answer_type,
         })
     }
 
@@ -144,9 +176,35 @@ impl QueryProcessor {
         normalized.trim().to_string()
     }
 
+    /// Classify a raw query without processing it further.
+    pub fn classify(&self, query: &str) -> QueryIntent {
+        self.detect_intent(query)
+    }
+
     /// Detect query intent.
     fn detect_intent(&self, query: &str) -> QueryIntent {
-        let query_lower = query.to_lowercase();
+        if Self::is_navigational(query) {
+            return QueryIntent::Navigational;
+        }
+
+        let query_lower = self.normalize(query);
+
+        // Procedural keywords, ahead of code keywords so that
+        // "how do I implement ..." asks for steps rather than code
+        if [
+            "how do i ",
+            "how can i ",
+            "how should i ",
+            "how would i ",
+            "steps to ",
+            "step by step",
+            "walk me through",
+        ]
+        .iter()
+        .any(|phrase| query_lower.contains(phrase))
+        {
+            return QueryIntent::Procedural;
+        }
 
         // Code-related keywords
         if query_lower.contains("function")
@@ -193,9 +251,65 @@ impl QueryProcessor {
             return QueryIntent::Definition;
         }
 
+        // Exploratory keywords
+        if query_lower.starts_with("why ")
+            || query_lower.starts_with("what are")
+            || [
+                "overview",
+                "architecture",
+                "approaches",
+                "ways to",
+                "tradeoff",
+                "trade-off",
+                "pros and cons",
+                "alternatives",
+                "compare",
+            ]
+            .iter()
+            .any(|phrase| query_lower.contains(phrase))
+        {
+            return QueryIntent::Exploratory;
+        }
+
         QueryIntent::General
     }
 
+    /// Whether the query is the name of a symbol or file, optionally after
+    /// a lookup verb ("open src/lib.rs", "go to `parse_config`").
+    fn is_navigational(query: &str) -> bool {
+        let trimmed = query.trim();
+        let lower = trimmed.to_lowercase();
+        let name = ["open ", "go to ", "goto ", "jump to ", "find file ", "file ", "symbol "]
+            .iter()
+            .find(|prefix| lower.starts_with(*prefix))
+            .and_then(|prefix| trimmed.get(prefix.len()..))
+            .map_or(trimmed, str::trim_start);
+        let name = name.trim_matches(|c| matches!(c, '`' | '"' | '\''));
+
+        if name.is_empty() || name.split_whitespace().count() != 1 {
+            return false;
+        }
+
+        let has_extension = name.rsplit_once('.').is_some_and(|(stem, ext)| {
+            !stem.is_empty()
+                && (1..=5).contains(&ext.len())
+                && ext.starts_with(|c: char| c.is_ascii_alphabetic())
+                && ext.chars().all(|c| c.is_ascii_alphanumeric())
+        });
+        // camelCase and PascalCase alike have a lowercase-to-uppercase step
+        let chars: Vec<char> = name.chars().collect();
+        let mixed_case = chars
+            .windows(2)
+            .any(|pair| pair[0].is_lowercase() && pair[1].is_uppercase());
+
+        name.contains("::")
+            || name.contains('/')
+            || name.contains('_')
+            || name.ends_with("()")
+            || has_extension
+            || mixed_case
+    }
+
     /// Extract keywords from query.
     fn extract_keywords(&self, query: &str) -> Vec<String> {
         // Simple keyword extraction based on word importance
@@ -255,19 +369,220 @@ impl Default for QueryProcessor {
     }
 }
 
+/// An expansion term and its weight relative to the original query (1.0).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeightedTerm {
+    pub term: String,
+    pub weight: f32,
+}
+
+impl WeightedTerm {
+    pub fn new(term: impl Into<String>, weight: f32) -> Self {
+        Self {
+            term: term.into(),
+            weight,
+        }
+    }
+}
+
+/// Domain synonym dictionary: term -> weighted expansions.
+///
+/// Loaded from TOML or JSON of the form
+///
+/// ```toml
+/// [terms]
+/// bug = [{ term = "defect", weight = 0.8 }, { term = "issue", weight = 0.7 }]
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DomainDictionary {
+    #[serde(default)]
+    pub terms: HashMap<String, Vec<WeightedTerm>>,
+}
+
+impl DomainDictionary {
+    /// Built-in programming vocabulary.
+    pub fn programming() -> Self {
+        let entries: &[(&str, &[(&str, f32)])] = &[
+            ("function", &[("method", 0.8)]),
+            ("method", &[("function", 0.8)]),
+            ("fn", &[("function", 0.9)]),
+            ("class", &[("type", 0.6)]),
+            ("struct", &[("type", 0.6)]),
+            ("error", &[("exception", 0.7)]),
+            ("err", &[("error", 0.9)]),
+            ("bug", &[("defect", 0.7), ("issue", 0.6)]),
+            ("create", &[("make", 0.5)]),
+            ("delete", &[("remove", 0.8)]),
+            ("update", &[("modify", 0.7)]),
+            ("auth", &[("authentication", 0.8)]),
+            ("config", &[("configuration", 0.9)]),
+            ("db", &[("database", 0.9)]),
+            ("impl", &[("implementation", 0.8)]),
+            ("args", &[("arguments", 0.9)]),
+        ];
+
+        let terms = entries
+            .iter()
+            .map(|(term, expansions)| {
+                let expansions = expansions
+                    .iter()
+                    .map(|(expansion, weight)| WeightedTerm::new(*expansion, *weight))
+                    .collect();
+                (term.to_string(), expansions)
+            })
+            .collect();
+        Self { terms }
+    }
+
+    pub fn from_toml_str(content: &str) -> Result<Self> {
+        let dictionary: Self = toml::from_str(content)
+            .map_err(|e| SemanticError::Config(format!("Invalid query dictionary: {}", e)))?;
+        Ok(dictionary.normalized())
+    }
+
+    pub fn from_json_str(content: &str) -> Result<Self> {
+        let dictionary: Self = serde_json::from_str(content)?;
+        Ok(dictionary.normalized())
+    }
+
+    /// Load a dictionary file; `.json` files are read as JSON, anything else as TOML.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        if path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+        {
+            Self::from_json_str(&content)
+        } else {
+            Self::from_toml_str(&content)
+        }
+    }
+
+    /// Add the entries of `other`, replacing the expansions of terms present in both.
+    pub fn merge(&mut self, other: DomainDictionary) {
+        self.terms.extend(other.terms);
+    }
+
+    /// Expansions of a term, case-insensitively.
+    pub fn get(&self, term: &str) -> &[WeightedTerm] {
+        self.terms
+            .get(&term.to_lowercase())
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    fn normalized(self) -> Self {
+        let terms = self
+            .terms
+            .into_iter()
+            .map(|(term, expansions)| (term.to_lowercase(), expansions))
+            .collect();
+        Self { terms }
+    }
+}
+
+/// Per-call query expansion options.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpansionOptions {
+    /// Expand the query at all
+    pub enabled: bool,
+    /// Identifiers that are matched exactly and never split or expanded
+    pub known_symbols: BTreeSet<String>,
+}
+
+impl ExpansionOptions {
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_known_symbols<I, S>(mut self, symbols: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.known_symbols
+            .extend(symbols.into_iter().map(Into::into));
+        self
+    }
+}
+
+impl Default for ExpansionOptions {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            known_symbols: BTreeSet::new(),
+        }
+    }
+}
+
+/// Split a camelCase, PascalCase, snake_case or kebab-case identifier into
+/// lowercase words ("getUserById" -> ["get", "user", "by", "id"]).
+pub fn split_identifier(token: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+
+    for piece in token.split(['_', '-']).filter(|piece| !piece.is_empty()) {
+        let chars: Vec<char> = piece.chars().collect();
+        let mut start = 0;
+
+        for i in 1..chars.len() {
+            let (prev, cur) = (chars[i - 1], chars[i]);
+            let next_is_lower = chars.get(i + 1).is_some_and(|c| c.is_lowercase());
+            // "userId" -> user|Id, "HTTPServer" -> HTTP|Server
+            let boundary = ((prev.is_lowercase() || prev.is_ascii_digit()) && cur.is_uppercase())
+                || (prev.is_uppercase() && cur.is_uppercase() && next_is_lower);
+            if boundary {
+                parts.push(chars[start..i].iter().collect::<String>().to_lowercase());
+                start = i;
+            }
+        }
+        parts.push(chars[start..].iter().collect::<String>().to_lowercase());
+    }
+
+    parts
+}
+
 /// Query expander for generating variations of queries.
+///
+/// Synonyms come from a domain dictionary rather than general language, so
+/// "bug" expands to "defect" and not "insect". Identifiers in the query are
+/// split into words for expansion, except backtick-quoted ones and those in
+/// `ExpansionOptions::known_symbols`, which are kept as they are.
 pub struct QueryExpander {
-    // In production, this would use a synonym database or word embeddings
+    dictionary: DomainDictionary,
 }
 
 impl QueryExpander {
     pub fn new() -> Self {
-        Self {}
+        Self::with_dictionary(DomainDictionary::programming())
+    }
+
+    pub fn with_dictionary(dictionary: DomainDictionary) -> Self {
+        Self { dictionary }
+    }
+
+    pub fn dictionary(&self) -> &DomainDictionary {
+        &self.dictionary
     }
 
     /// Expand query with synonyms and variations.
     pub fn expand(&self, query: &str, intent: &QueryIntent) -> Vec<String> {
+        self.expand_with(query, intent, &ExpansionOptions::default())
+    }
+
+    /// Expand query with synonyms and variations, honoring per-call options.
+    pub fn expand_with(
+        &self,
+        query: &str,
+        intent: &QueryIntent,
+        options: &ExpansionOptions,
+    ) -> Vec<String> {
         let mut expansions = vec![query.to_string()];
+        if !options.enabled {
+            return expansions;
+        }
 
         // Add intent-specific expansions
         match intent {
@@ -283,12 +598,89 @@ impl QueryExpander {
             _ => {}
         }
 
-        // Add common synonyms
-        self.add_common_synonyms(query, &mut expansions);
+        // Add dictionary synonyms
+        self.add_dictionary_synonyms(query, options, &mut expansions);
 
         expansions
     }
 
+    /// Weighted expansion terms for a query: the spelled-out words of
+    /// identifiers and the dictionary synonyms of each word.
+    ///
+    /// Terms already in the query are left out, as are protected identifiers.
+    pub fn weighted_terms(&self, query: &str, options: &ExpansionOptions) -> Vec<WeightedTerm> {
+        if !options.enabled {
+            return Vec::new();
+        }
+
+        let (tokens, protected) = Self::tokenize(query, options);
+        let query_words: HashSet<String> = tokens
+            .iter()
+            .chain(protected.iter())
+            .map(|token| token.to_lowercase())
+            .collect();
+
+        let mut terms: Vec<WeightedTerm> = Vec::new();
+        let mut add = |term: String, weight: f32| {
+            if query_words.contains(&term) {
+                return;
+            }
+            match terms.iter_mut().find(|existing| existing.term == term) {
+                Some(existing) => existing.weight = existing.weight.max(weight),
+                None => terms.push(WeightedTerm { term, weight }),
+            }
+        };
+
+        for token in &tokens {
+            let words = split_identifier(token);
+            if words.len() > 1 {
+                add(words.join(" "), IDENTIFIER_PARTS_WEIGHT);
+            }
+            for word in &words {
+                for expansion in self.dictionary.get(word) {
+                    add(expansion.term.to_lowercase(), expansion.weight);
+                }
+            }
+        }
+
+        terms.sort_by(|a, b| b.weight.total_cmp(&a.weight));
+        terms.truncate(MAX_WEIGHTED_TERMS);
+        terms
+    }
+
+    /// Split a query into expandable tokens and protected identifiers.
+    fn tokenize(query: &str, options: &ExpansionOptions) -> (Vec<String>, Vec<String>) {
+        let quoted = Regex::new(r"`([^`]+)`").unwrap();
+        let mut protected: Vec<String> = quoted
+            .captures_iter(query)
+            .map(|caps| caps[1].trim().to_string())
+            .collect();
+        let unquoted = quoted.replace_all(query, " ");
+
+        let mut tokens = Vec::new();
+        for token in unquoted
+            .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+            .map(|token| token.trim_matches('-'))
+            .filter(|token| !token.is_empty())
+        {
+            if options.known_symbols.contains(token) {
+                protected.push(token.to_string());
+            } else {
+                tokens.push(token.to_string());
+            }
+        }
+
+        (tokens, protected)
+    }
+
+    fn is_protected(word: &str, query: &str, options: &ExpansionOptions) -> bool {
+        options
+            .known_symbols
+            .iter()
+            .any(|symbol| symbol.eq_ignore_ascii_case(word))
+            || query.contains(&format!("`{}`", word))
+    }
+
     fn expand_code_query(&self, query: &str, expansions: &mut Vec<String>) {
         // Add code-specific variations
         if !query.contains("function") && !query.contains("method") {
@@ -327,23 +719,22 @@ impl QueryExpander {
         }
     }
 
-    fn add_common_synonyms(&self, query: &str, expansions: &mut Vec<String>) {
-        // Common programming synonyms
-        let synonyms = [
-            ("function", "method"),
-            ("method", "function"),
-            ("class", "type"),
-            ("struct", "type"),
-            ("error", "exception"),
-            ("bug", "issue"),
-            ("create", "make"),
-            ("delete", "remove"),
-            ("update", "modify"),
-        ];
-
-        for (from, to) in &synonyms {
-            if query.contains(from) {
-                expansions.push(query.replace(from, to));
+    fn add_dictionary_synonyms(
+        &self,
+        query: &str,
+        options: &ExpansionOptions,
+        expansions: &mut Vec<String>,
+    ) {
+        let words: Vec<&str> = query.split_whitespace().collect();
+
+        for (i, word) in words.iter().enumerate() {
+            if Self::is_protected(word.trim_matches('`'), query, options) {
+                continue;
+            }
+            for synonym in self.dictionary.get(word) {
+                let mut variant = words.clone();
+                variant[i] = synonym.term.as_str();
+                expansions.push(variant.join(" "));
             }
         }
     }
@@ -411,6 +802,50 @@ impl QueryDecomposer {
         (sub_queries, graph)
     }
 
+    /// Infer the kind of answer a query expects.
+    ///
+    /// Decomposed queries expect one answer per sub-query, so they are
+    /// treated as lists.
+    pub fn answer_type(&self, query: &str, intent: QueryIntent, sub_queries: &[SubQuery]) -> AnswerType {
+        let query_lower = query.to_lowercase();
+        let starts_with_any = |prefixes: &[&str]| prefixes.iter().any(|p| query_lower.starts_with(p));
+
+        if sub_queries.len() > 1 {
+            return AnswerType::List;
+        }
+
+        match intent {
+            QueryIntent::Exploratory | QueryIntent::Procedural => return AnswerType::Explanation,
+            QueryIntent::Code | QueryIntent::Navigational | QueryIntent::Examples => {
+                return AnswerType::Code;
+            }
+            QueryIntent::Definition => return AnswerType::Fact,
+            _ => {}
+        }
+
+        if starts_with_any(&["list ", "which ", "what are ", "name all "]) {
+            AnswerType::List
+        } else if starts_with_any(&[
+            "is ", "are ", "does ", "do ", "can ", "should ", "was ", "has ", "will ",
+        ]) {
+            AnswerType::Boolean
+        } else if starts_with_any(&[
+            "what is ",
+            "what's ",
+            "what does ",
+            "who ",
+            "when ",
+            "where ",
+            "how many ",
+            "how much ",
+            "define ",
+        ]) {
+            AnswerType::Fact
+        } else {
+            AnswerType::Explanation
+        }
+    }
+
     /// Check if a query is complex enough to warrant decomposition.
     fn is_complex_query(&self, query: &str) -> bool {
         let query_lower = query.to_lowercase();
@@ -670,6 +1105,47 @@ mod tests {
         assert_eq!(intent, QueryIntent::Code);
     }
 
+    #[test]
+    fn test_classify_labeled_queries() {
+        let processor = QueryProcessor::new();
+        let labeled = [
+            (QueryIntent::Navigational, "parse_config"),
+            (QueryIntent::Navigational, "SemanticSearchEngine"),
+            (QueryIntent::Navigational, "src/search.rs"),
+            (QueryIntent::Navigational, "open lib.rs"),
+            (QueryIntent::Navigational, "go to `query::QueryProcessor`"),
+            (QueryIntent::Navigational, "build_index()"),
+            (QueryIntent::Procedural, "How do I add a new embedding provider"),
+            (QueryIntent::Procedural, "how can i rebuild the index"),
+            (QueryIntent::Procedural, "steps to configure qdrant"),
+            (QueryIntent::Procedural, "walk me through a release"),
+            (QueryIntent::Exploratory, "why is the cache keyed by filter"),
+            (QueryIntent::Exploratory, "what are the ranking strategies"),
+            (QueryIntent::Exploratory, "architecture of the ingestion pipeline"),
+            (QueryIntent::Exploratory, "tradeoffs between hnsw and flat indexes"),
+            (QueryIntent::General, "authentication"),
+            (QueryIntent::General, "retry on timeout"),
+        ];
+
+        for (expected, query) in labeled {
+            assert_eq!(processor.classify(query), expected, "query: {query}");
+        }
+    }
+
+    #[test]
+    fn test_answer_type_inference() {
+        let processor = QueryProcessor::new();
+        let answer_type = |query: &str| processor.process(query).unwrap().answer_type;
+
+        assert_eq!(answer_type("what is a closure"), AnswerType::Fact);
+        assert_eq!(answer_type("define memoization"), AnswerType::Fact);
+        assert_eq!(answer_type("where is the qdrant url configured"), AnswerType::Fact);
+        assert_eq!(answer_type("is the cache thread safe"), AnswerType::Boolean);
+        assert_eq!(answer_type("list supported embedding providers"), AnswerType::List);
+        assert_eq!(answer_type("why is the cache keyed by filter"), AnswerType::Explanation);
+        assert_eq!(answer_type("parse_config"), AnswerType::Code);
+    }
+
     #[test]
     fn test_extract_keywords() {
         let processor = QueryProcessor::new();
@@ -702,6 +1178,82 @@ mod tests {
         assert!(expanded.contains(&"authentication".to_string()));
     }
 
+    #[test]
+    fn test_split_identifier() {
+        assert_eq!(
+            split_identifier("getUserById"),
+            vec!["get", "user", "by", "id"]
+        );
+        assert_eq!(
+            split_identifier("parse_http_request"),
+            vec!["parse", "http", "request"]
+        );
+        assert_eq!(split_identifier("HTTPServer"), vec!["http", "server"]);
+        assert_eq!(split_identifier("utf8Decode"), vec!["utf8", "decode"]);
+        assert_eq!(split_identifier("plain"), vec!["plain"]);
+    }
+
+    #[test]
+    fn test_domain_dictionary_replaces_generic_synonyms() {
+        let dictionary = DomainDictionary::from_toml_str(
+            r#"
+            [terms]
+            Bug = [{ term = "defect", weight = 0.8 }, { term = "regression", weight = 0.5 }]
+            "#,
+        )
+        .unwrap();
+        let expander = QueryExpander::with_dictionary(dictionary);
+
+        let terms = expander.weighted_terms("fix bug in parser", &ExpansionOptions::default());
+        assert_eq!(
+            terms,
+            vec![
+                WeightedTerm::new("defect", 0.8),
+                WeightedTerm::new("regression", 0.5)
+            ]
+        );
+
+        let json = DomainDictionary::from_json_str(
+            r#"{"terms": {"db": [{"term": "database", "weight": 0.9}]}}"#,
+        )
+        .unwrap();
+        assert_eq!(json.get("DB"), &[WeightedTerm::new("database", 0.9)]);
+    }
+
+    #[test]
+    fn test_weighted_terms_split_identifiers_but_keep_exact_ones() {
+        let expander = QueryExpander::new();
+
+        let terms =
+            expander.weighted_terms("where is getUserById called", &ExpansionOptions::default());
+        assert!(terms.contains(&WeightedTerm::new(
+            "get user by id",
+            IDENTIFIER_PARTS_WEIGHT
+        )));
+
+        // Backtick-quoted and known symbols are neither split nor expanded
+        let terms =
+            expander.weighted_terms("callers of `getUserById`", &ExpansionOptions::default());
+        assert!(terms.is_empty());
+        let options = ExpansionOptions::default().with_known_symbols(["load_config"]);
+        let terms = expander.weighted_terms("load_config errors", &options);
+        assert!(
+            !terms
+                .iter()
+                .any(|t| t.term == "load config" || t.term == "configuration")
+        );
+
+        // Disabled per call
+        assert!(
+            expander
+                .weighted_terms("getUserById", &ExpansionOptions::disabled())
+                .is_empty()
+        );
+        let expanded =
+            expander.expand_with("fix bug", &QueryIntent::Code, &ExpansionOptions::disabled());
+        assert_eq!(expanded, vec!["fix bug".to_string()]);
+    }
+
     #[test]
     fn test_process_query() {
         let processor = QueryProcessor::new();
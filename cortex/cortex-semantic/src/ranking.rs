@@ -11,11 +11,27 @@
 //! - "RankGPT: LLMs as Re-Ranking Agents" (Sun et al., 2023)
 //! - "SetRank: Learning to Rank as Sets" (Pang et al., 2020)
 
+use crate::agent::{AgentCoordinator, AgentId};
+use crate::error::Result;
 use crate::query::ProcessedQuery;
 use crate::types::Vector;
+use async_trait::async_trait;
+use dashmap::DashMap;
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
+
+/// Documents whose features the personalized ranker remembers for feedback
+const MAX_SEEN_DOCUMENTS: usize = 10_000;
+
+/// Feedback events kept for evaluation
+const MAX_FEEDBACK_LOG: usize = 10_000;
+
+/// Affinities smaller than this are dropped from a profile
+const MIN_AFFINITY: f32 = 1e-3;
 
 /// Ranking strategy.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -110,6 +126,14 @@ impl Ranker {
         Self { strategy, weights }
     }
 
+    pub fn weights(&self) -> &ScoringWeights {
+        &self.weights
+    }
+
+    pub fn strategy(&self) -> RankingStrategy {
+        self.strategy
+    }
+
     /// Rank documents based on the configured strategy.
     pub fn rank(
         &self,
@@ -201,25 +225,7 @@ impl Ranker {
     }
 
     fn calculate_recency_score(&self, metadata: &HashMap<String, String>) -> f32 {
-        // Check for timestamp in metadata
-        if let Some(timestamp_str) = metadata.get("updated_at").or_else(|| metadata.get("created_at")) {
-            // Parse timestamp and calculate recency
-            if let Ok(timestamp) = chrono::DateTime::parse_from_rfc3339(timestamp_str) {
-                let now = chrono::Utc::now();
-                let age = now.signed_duration_since(timestamp.with_timezone(&chrono::Utc));
-
-                // Decay function: score decreases over time
-                // Documents from last 7 days get full score, then exponential decay
-                let days = age.num_days() as f32;
-                if days < 7.0 {
-                    return 1.0;
-                } else {
-                    return (-(days - 7.0) / 30.0).exp().max(0.1);
-                }
-            }
-        }
-
-        0.5 // Default neutral score
+        recency_score(metadata)
     }
 
     fn calculate_popularity_score(&self, metadata: &HashMap<String, String>) -> f32 {
@@ -242,6 +248,30 @@ impl Ranker {
     }
 }
 
+/// Recency of a document from its `updated_at`/`created_at` metadata, in
+/// [0.1, 1.0]; 0.5 when unknown.
+fn recency_score(metadata: &HashMap<String, String>) -> f32 {
+    // Check for timestamp in metadata
+    if let Some(timestamp_str) = metadata.get("updated_at").or_else(|| metadata.get("created_at")) {
+        // Parse timestamp and calculate recency
+        if let Ok(timestamp) = chrono::DateTime::parse_from_rfc3339(timestamp_str) {
+            let now = chrono::Utc::now();
+            let age = now.signed_duration_since(timestamp.with_timezone(&chrono::Utc));
+
+            // Decay function: score decreases over time
+            // Documents from last 7 days get full score, then exponential decay
+            let days = age.num_days() as f32;
+            if days < 7.0 {
+                return 1.0;
+            } else {
+                return (-(days - 7.0) / 30.0).exp().max(0.1);
+            }
+        }
+    }
+
+    0.5 // Default neutral score
+}
+
 /// BM25 scorer for keyword-based ranking.
 pub struct BM25Scorer {
     k1: f32,
@@ -314,76 +344,169 @@ impl MMRReranker {
         query_embedding: &[f32],
         k: usize,
     ) -> Vec<RankableDocument> {
+        self.rerank_diverse(documents, Some(query_embedding), k, &DiversityConfig::default())
+            .selected
+            .into_iter()
+            .map(|diversified| diversified.document)
+            .collect()
+    }
+
+    /// Rerank documents using MMR over both embedding distance and a
+    /// metadata field.
+    ///
+    /// # Algorithm
+    /// MMR = λ * Sim(D, Q) - (1-λ) * (wv * max[Sim(D, Di)] + wf * p * n(D))
+    /// where wv and wf are the vector and field diversity weights, p is the
+    /// field penalty and n(D) the number of selected docs sharing D's field
+    /// value. Candidates whose field value already reached the cap of
+    /// `max_per_field_value` are never selected.
+    pub fn rerank_diverse(
+        &self,
+        documents: Vec<RankableDocument>,
+        query_embedding: Option<&[f32]>,
+        k: usize,
+        config: &DiversityConfig,
+    ) -> DiversifiedRanking {
+        let mut ranking = DiversifiedRanking::default();
         if documents.is_empty() {
-            return vec![];
+            return ranking;
         }
 
-        let mut selected = Vec::new();
-        let mut remaining = documents;
-
-        // Select first document (highest relevance)
-        remaining.sort_by(|a, b| b.semantic_score.partial_cmp(&a.semantic_score).unwrap());
-        if let Some(first) = remaining.first() {
-            selected.push(first.clone());
-            remaining.remove(0);
-        }
+        let field = config.max_per_field_value.as_ref().map(|(field, _)| field.as_str());
+        let cap = config.max_per_field_value.as_ref().map(|(_, cap)| *cap);
+        let mut selected_per_value: HashMap<String, usize> = HashMap::new();
 
-        // Iteratively select remaining documents
-        while selected.len() < k && !remaining.is_empty() {
-            let mut best_idx = 0;
-            let mut best_mmr = f32::NEG_INFINITY;
+        // Relevance order, used to tell which documents diversity pushed down
+        let mut remaining = documents;
+        remaining.sort_by(|a, b| b.semantic_score.total_cmp(&a.semantic_score));
+        let relevance_rank: HashMap<String, usize> = remaining
+            .iter()
+            .enumerate()
+            .map(|(rank, doc)| (doc.id.clone(), rank))
+            .collect();
+        let mut selected: Vec<RankableDocument> = Vec::new();
+
+        while ranking.selected.len() < k && !remaining.is_empty() {
+            // Drop candidates whose field value is full
+            let (capped, candidates): (Vec<_>, Vec<_>) = remaining.into_iter().partition(|doc| {
+                match (field.and_then(|field| doc.metadata.get(field)), cap) {
+                    (Some(value), Some(cap)) => {
+                        selected_per_value.get(value).copied().unwrap_or(0) >= cap
+                    }
+                    _ => false,
+                }
+            });
+            for doc in capped {
+                let field_name = field.unwrap_or_default().to_string();
+                let value = doc.metadata.get(&field_name).cloned().unwrap_or_default();
+                ranking.capped.push(DiversifiedDocument {
+                    document: doc,
+                    mmr_score: 0.0,
+                    demotion: Some(DemotionReason::FieldCap {
+                        field: field_name,
+                        value,
+                    }),
+                });
+            }
+            remaining = candidates;
+            if remaining.is_empty() {
+                break;
+            }
 
+            let mut best: Option<(usize, f32, MmrPenalties)> = None;
             for (idx, doc) in remaining.iter().enumerate() {
-                // Calculate relevance to query
-                let relevance = if let Some(embedding) = &doc.embedding {
-                    crate::types::cosine_similarity(embedding, query_embedding)
+                let (mmr, penalties) = if selected.is_empty() {
+                    // First pick is the most relevant document
+                    (doc.semantic_score, MmrPenalties::default())
                 } else {
-                    doc.semantic_score
+                    let relevance = match (&doc.embedding, query_embedding) {
+                        (Some(embedding), Some(query)) if embedding.len() == query.len() => {
+                            crate::types::cosine_similarity(embedding, query)
+                        }
+                        _ => doc.semantic_score,
+                    };
+                    let penalties = self.penalties(doc, &selected, field, &selected_per_value, config);
+                    let mmr = self.lambda * relevance
+                        - (1.0 - self.lambda) * (penalties.vector + penalties.field);
+                    (mmr, penalties)
                 };
 
-                // Calculate max similarity to already-selected documents
-                let max_similarity = self.max_similarity_to_selected(doc, &selected);
-
-                // Calculate MMR score
-                let mmr = self.lambda * relevance - (1.0 - self.lambda) * max_similarity;
-
-                if mmr > best_mmr {
-                    best_mmr = mmr;
-                    best_idx = idx;
+                if best.as_ref().is_none_or(|(_, best_mmr, _)| mmr > *best_mmr) {
+                    best = Some((idx, mmr, penalties));
                 }
             }
 
-            // Add best document to selected set
-            let selected_doc = remaining.remove(best_idx);
-            selected.push(selected_doc);
+            let Some((best_idx, mmr_score, penalties)) = best else {
+                break;
+            };
+            let doc = remaining.remove(best_idx);
+            let demoted = relevance_rank
+                .get(&doc.id)
+                .is_some_and(|rank| ranking.selected.len() > *rank);
+            let demotion = if demoted {
+                penalties.reason(&selected, field, &doc, config)
+            } else {
+                None
+            };
+
+            if let Some(value) = field.and_then(|field| doc.metadata.get(field)) {
+                *selected_per_value.entry(value.clone()).or_insert(0) += 1;
+            }
+            selected.push(doc.clone());
+            ranking.selected.push(DiversifiedDocument {
+                document: doc,
+                mmr_score,
+                demotion,
+            });
         }
 
-        selected
+        ranking
     }
 
-    /// Calculate maximum similarity between a document and the selected set.
-    fn max_similarity_to_selected(
+    /// Diversity penalties of a candidate against the selected set.
+    fn penalties(
         &self,
         doc: &RankableDocument,
         selected: &[RankableDocument],
-    ) -> f32 {
-        if selected.is_empty() {
-            return 0.0;
+        field: Option<&str>,
+        selected_per_value: &HashMap<String, usize>,
+        config: &DiversityConfig,
+    ) -> MmrPenalties {
+        let (most_similar, similarity) = self
+            .most_similar_selected(doc, selected)
+            .map(|(idx, similarity)| (Some(idx), similarity))
+            .unwrap_or((None, 0.0));
+        let shared = field
+            .and_then(|field| doc.metadata.get(field))
+            .and_then(|value| selected_per_value.get(value))
+            .copied()
+            .unwrap_or(0);
+
+        MmrPenalties {
+            vector: config.vector_diversity_weight * similarity,
+            field: config.field_diversity_weight * config.field_penalty * shared as f32,
+            most_similar,
+            similarity,
         }
+    }
 
-        if let Some(doc_emb) = &doc.embedding {
-            selected
-                .iter()
-                .filter_map(|s| s.embedding.as_ref())
-                .map(|sel_emb| crate::types::cosine_similarity(doc_emb, sel_emb))
-                .fold(f32::NEG_INFINITY, f32::max)
-        } else {
-            // Fallback to text-based similarity if embeddings not available
-            selected
-                .iter()
-                .map(|s| self.text_similarity(&doc.content, &s.content))
-                .fold(f32::NEG_INFINITY, f32::max)
-        }
+    /// Most similar document in the selected set, with its similarity.
+    fn most_similar_selected(
+        &self,
+        doc: &RankableDocument,
+        selected: &[RankableDocument],
+    ) -> Option<(usize, f32)> {
+        let similarities = selected.iter().enumerate().filter_map(|(idx, s)| {
+            match (&doc.embedding, &s.embedding) {
+                (Some(doc_emb), Some(sel_emb)) if doc_emb.len() == sel_emb.len() => {
+                    Some((idx, crate::types::cosine_similarity(doc_emb, sel_emb)))
+                }
+                // Fallback to text-based similarity if embeddings not available
+                (None, _) => Some((idx, self.text_similarity(&doc.content, &s.content))),
+                _ => None,
+            }
+        });
+        similarities.max_by(|a, b| a.1.total_cmp(&b.1))
     }
 
     /// Simple text similarity using Jaccard similarity.
@@ -406,6 +529,99 @@ impl MMRReranker {
     }
 }
 
+/// Diversity penalties of one MMR candidate.
+#[derive(Debug, Clone, Copy, Default)]
+struct MmrPenalties {
+    /// Weighted similarity to the closest selected document
+    vector: f32,
+    /// Weighted penalty for sharing the diversity field value
+    field: f32,
+    most_similar: Option<usize>,
+    similarity: f32,
+}
+
+impl MmrPenalties {
+    /// The larger of the two penalties, if it counts as a reason.
+    fn reason(
+        &self,
+        selected: &[RankableDocument],
+        field: Option<&str>,
+        doc: &RankableDocument,
+        config: &DiversityConfig,
+    ) -> Option<DemotionReason> {
+        let field_value = field.and_then(|field| doc.metadata.get(field).map(|value| (field, value)));
+        if self.field > 0.0 && self.field >= self.vector {
+            let (field, value) = field_value?;
+            return Some(DemotionReason::FieldValue {
+                field: field.to_string(),
+                value: value.clone(),
+                penalty: self.field,
+            });
+        }
+        if self.vector > 0.0 && self.similarity >= config.similarity_threshold {
+            let similar_to = selected.get(self.most_similar?)?.id.clone();
+            return Some(DemotionReason::Similarity {
+                similar_to,
+                similarity: self.similarity,
+            });
+        }
+        None
+    }
+}
+
+/// Why diversity reranking placed a document lower than its relevance would.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum DemotionReason {
+    /// Dropped: the cap of results with this field value was already reached
+    FieldCap { field: String, value: String },
+    /// Penalized for sharing a field value with selected results
+    FieldValue {
+        field: String,
+        value: String,
+        penalty: f32,
+    },
+    /// Penalized for being too similar to a selected result
+    Similarity { similar_to: String, similarity: f32 },
+}
+
+impl std::fmt::Display for DemotionReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DemotionReason::FieldCap { field, value } => {
+                write!(f, "dropped: result cap reached for {}={}", field, value)
+            }
+            DemotionReason::FieldValue {
+                field,
+                value,
+                penalty,
+            } => write!(f, "demoted: shares {}={} (penalty {:.3})", field, value, penalty),
+            DemotionReason::Similarity {
+                similar_to,
+                similarity,
+            } => write!(f, "demoted: similar to {} ({:.3})", similar_to, similarity),
+        }
+    }
+}
+
+/// A document chosen (or dropped) by diversity-aware MMR.
+#[derive(Debug, Clone)]
+pub struct DiversifiedDocument {
+    pub document: RankableDocument,
+    /// MMR utility when the document was selected (0.0 if dropped)
+    pub mmr_score: f32,
+    pub demotion: Option<DemotionReason>,
+}
+
+/// Outcome of diversity-aware MMR.
+#[derive(Debug, Clone, Default)]
+pub struct DiversifiedRanking {
+    /// Selected documents in order
+    pub selected: Vec<DiversifiedDocument>,
+    /// Documents dropped by the per-field cap
+    pub capped: Vec<DiversifiedDocument>,
+}
+
 /// Configuration for personalized ranking.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PersonalizationConfig {
@@ -415,6 +631,21 @@ pub struct PersonalizationConfig {
     pub interaction_history: Vec<String>,
     /// Boost factor for similar documents to past interactions
     pub history_boost: f32,
+    /// Weight of the learned profile affinity added to the base score
+    #[serde(default = "default_affinity_weight")]
+    pub affinity_weight: f32,
+    /// How much of a profile survives each feedback event (0.0 - 1.0); the
+    /// rest is learned from the event
+    #[serde(default = "default_feedback_decay")]
+    pub feedback_decay: f32,
+}
+
+fn default_affinity_weight() -> f32 {
+    0.3
+}
+
+fn default_feedback_decay() -> f32 {
+    0.9
 }
 
 impl Default for PersonalizationConfig {
@@ -423,20 +654,173 @@ impl Default for PersonalizationConfig {
             preferences: HashMap::new(),
             interaction_history: Vec::new(),
             history_boost: 1.2,
+            affinity_weight: default_affinity_weight(),
+            feedback_decay: default_feedback_decay(),
+        }
+    }
+}
+
+/// How an agent reacted to a search result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedbackKind {
+    /// Opened the result
+    Clicked,
+    /// Used the result in its work
+    Used,
+    /// Skipped or rejected the result
+    Dismissed,
+}
+
+impl FeedbackKind {
+    fn signal(self) -> f32 {
+        match self {
+            FeedbackKind::Clicked => 0.5,
+            FeedbackKind::Used => 1.0,
+            FeedbackKind::Dismissed => -0.5,
+        }
+    }
+
+    pub fn is_positive(self) -> bool {
+        self.signal() > 0.0
+    }
+}
+
+/// One recorded feedback event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedbackEvent {
+    pub agent_id: AgentId,
+    pub query: String,
+    pub result_id: String,
+    pub kind: FeedbackKind,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+/// Preferences of one agent learned from feedback.
+///
+/// Every affinity is in [-1.0, 1.0] and decays towards zero as newer
+/// feedback arrives.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PreferenceProfile {
+    pub entity_types: HashMap<String, f32>,
+    /// Directories of the source files the agent used
+    pub paths: HashMap<String, f32>,
+    pub languages: HashMap<String, f32>,
+    /// Positive when the agent favors recently updated documents
+    pub recency: f32,
+    pub feedback_count: u64,
+}
+
+impl PreferenceProfile {
+    /// A profile without feedback does not change rankings.
+    pub fn is_cold(&self) -> bool {
+        self.feedback_count == 0
+    }
+
+    fn learn(&mut self, features: &DocumentFeatures, signal: f32, decay: f32) {
+        fn update(
+            affinities: &mut HashMap<String, f32>,
+            key: Option<&String>,
+            signal: f32,
+            decay: f32,
+        ) {
+            for affinity in affinities.values_mut() {
+                *affinity *= decay;
+            }
+            if let Some(key) = key {
+                *affinities.entry(key.clone()).or_insert(0.0) += (1.0 - decay) * signal;
+            }
+            affinities.retain(|_, affinity| affinity.abs() >= MIN_AFFINITY);
+        }
+
+        update(&mut self.entity_types, features.entity_type.as_ref(), signal, decay);
+        update(&mut self.paths, features.path.as_ref(), signal, decay);
+        update(&mut self.languages, features.language.as_ref(), signal, decay);
+        self.recency = self.recency * decay + (1.0 - decay) * signal * features.recency;
+        self.feedback_count += 1;
+    }
+
+    /// Affinity of the profile for a document, in [-1.0, 1.0].
+    fn affinity(&self, features: &DocumentFeatures) -> f32 {
+        let categorical = [
+            (&self.entity_types, &features.entity_type),
+            (&self.paths, &features.path),
+            (&self.languages, &features.language),
+        ];
+
+        let mut total = self.recency * features.recency;
+        let mut count = 1;
+        for (affinities, key) in categorical {
+            if let Some(key) = key {
+                total += affinities.get(key).copied().unwrap_or(0.0);
+                count += 1;
+            }
+        }
+        total / count as f32
+    }
+}
+
+/// Document features preference profiles are learned over.
+#[derive(Debug, Clone, Default)]
+struct DocumentFeatures {
+    entity_type: Option<String>,
+    path: Option<String>,
+    language: Option<String>,
+    /// Recency mapped to [-1.0, 1.0]
+    recency: f32,
+}
+
+impl DocumentFeatures {
+    fn from_metadata(metadata: &HashMap<String, String>) -> Self {
+        let path = metadata
+            .get("file_path")
+            .or_else(|| metadata.get("path"))
+            .map(|path| match std::path::Path::new(path).parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => {
+                    parent.to_string_lossy().into_owned()
+                }
+                _ => path.clone(),
+            });
+
+        Self {
+            entity_type: metadata.get("entity_type").map(|t| t.to_lowercase()),
+            path,
+            language: metadata.get("language").map(|l| l.to_lowercase()),
+            recency: 2.0 * recency_score(metadata) - 1.0,
         }
     }
 }
 
 /// Personalized ranker that adapts to user preferences.
 ///
+/// Besides the static `PersonalizationConfig`, the ranker learns a
+/// `PreferenceProfile` per agent from `record_feedback`. Profiles live in the
+/// agent coordinator when the ranker is built `with_coordinator`.
+///
 /// Reference: "Personalized Search via Learning-to-Rank" (Dou et al., 2007)
 pub struct PersonalizedRanker {
     config: PersonalizationConfig,
+    profiles: Arc<DashMap<AgentId, PreferenceProfile>>,
+    /// Features of recently ranked documents, so feedback by result ID can
+    /// be learned from
+    seen: DashMap<String, DocumentFeatures>,
+    feedback_log: Mutex<VecDeque<FeedbackEvent>>,
 }
 
 impl PersonalizedRanker {
     pub fn new(config: PersonalizationConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            profiles: Arc::new(DashMap::new()),
+            seen: DashMap::new(),
+            feedback_log: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Keep preference profiles in the coordinator's per-agent storage.
+    pub fn with_coordinator(mut self, coordinator: &AgentCoordinator) -> Self {
+        self.profiles = coordinator.preference_profiles();
+        self
     }
 
     /// Rerank documents based on user personalization.
@@ -454,6 +838,84 @@ impl PersonalizedRanker {
         documents
     }
 
+    /// Rerank documents by an agent's learned profile.
+    ///
+    /// Scores become `base + affinity_weight * affinity`. Agents without
+    /// feedback get the documents back untouched.
+    pub fn rerank_for_agent(
+        &self,
+        agent_id: &str,
+        mut documents: Vec<RankableDocument>,
+    ) -> Vec<RankableDocument> {
+        if self.seen.len() + documents.len() > MAX_SEEN_DOCUMENTS {
+            self.seen.clear();
+        }
+        let features: Vec<DocumentFeatures> = documents
+            .iter()
+            .map(|doc| {
+                let features = DocumentFeatures::from_metadata(&doc.metadata);
+                self.seen.insert(doc.id.clone(), features.clone());
+                features
+            })
+            .collect();
+
+        let Some(profile) = self.profile(agent_id).filter(|profile| !profile.is_cold()) else {
+            return documents;
+        };
+
+        for (doc, features) in documents.iter_mut().zip(&features) {
+            doc.semantic_score += self.config.affinity_weight * profile.affinity(features);
+        }
+        documents.sort_by(|a, b| b.semantic_score.total_cmp(&a.semantic_score));
+        documents
+    }
+
+    /// Learn from an agent's reaction to a result it was shown.
+    ///
+    /// Returns `false` if the result was not ranked by this ranker recently,
+    /// in which case the event is only logged.
+    pub fn record_feedback(
+        &self,
+        agent_id: &str,
+        query: &str,
+        result_id: &str,
+        kind: FeedbackKind,
+    ) -> bool {
+        {
+            let mut log = self.feedback_log.lock();
+            if log.len() >= MAX_FEEDBACK_LOG {
+                log.pop_front();
+            }
+            log.push_back(FeedbackEvent {
+                agent_id: agent_id.to_string(),
+                query: query.to_string(),
+                result_id: result_id.to_string(),
+                kind,
+                timestamp: chrono::Utc::now(),
+            });
+        }
+
+        let Some(features) = self.seen.get(result_id).map(|f| f.clone()) else {
+            return false;
+        };
+        let decay = self.config.feedback_decay.clamp(0.0, 1.0);
+        self.profiles
+            .entry(agent_id.to_string())
+            .or_default()
+            .learn(&features, kind.signal(), decay);
+        true
+    }
+
+    /// Learned profile of an agent.
+    pub fn profile(&self, agent_id: &str) -> Option<PreferenceProfile> {
+        self.profiles.get(agent_id).map(|profile| profile.clone())
+    }
+
+    /// Recorded feedback, oldest first.
+    pub fn feedback_log(&self) -> Vec<FeedbackEvent> {
+        self.feedback_log.lock().iter().cloned().collect()
+    }
+
     /// Calculate personalization score for a document.
     fn calculate_personalization_score(&self, doc: &RankableDocument) -> f32 {
         let mut score = 0.0;
@@ -494,6 +956,27 @@ pub struct DiversityConfig {
     pub diversity_weight: f32,
     /// Minimum similarity threshold for considering documents similar
     pub similarity_threshold: f32,
+    /// Metadata field to diversify on, and the most results allowed per
+    /// value of it (e.g. `("file_path", 2)`)
+    #[serde(default)]
+    pub max_per_field_value: Option<(String, usize)>,
+    /// MMR penalty per selected result sharing the candidate's field value
+    #[serde(default = "default_field_penalty")]
+    pub field_penalty: f32,
+    /// Weight of embedding-distance diversity in the MMR penalty
+    #[serde(default = "default_diversity_dimension_weight")]
+    pub vector_diversity_weight: f32,
+    /// Weight of field diversity in the MMR penalty
+    #[serde(default = "default_diversity_dimension_weight")]
+    pub field_diversity_weight: f32,
+}
+
+fn default_field_penalty() -> f32 {
+    0.5
+}
+
+fn default_diversity_dimension_weight() -> f32 {
+    1.0
 }
 
 impl Default for DiversityConfig {
@@ -502,7 +985,91 @@ impl Default for DiversityConfig {
             enable_diversity: true,
             diversity_weight: 0.3,
             similarity_threshold: 0.8,
+            max_per_field_value: None,
+            field_penalty: default_field_penalty(),
+            vector_diversity_weight: default_diversity_dimension_weight(),
+            field_diversity_weight: default_diversity_dimension_weight(),
+        }
+    }
+}
+
+/// Scores how relevant a document is to a query by reading both together,
+/// as a cross-encoder does. Slower than comparing embeddings, so it is only
+/// applied to the best candidates of a first ranking pass.
+#[async_trait]
+pub trait Reranker: Send + Sync {
+    /// Relevance of `document` to `query` (0.0 - 1.0).
+    async fn score(&self, query: &str, document: &str) -> Result<f32>;
+
+    /// Relevance of each document to `query`, in order. Scores one document
+    /// at a time unless overridden.
+    async fn score_batch(&self, query: &str, documents: &[String]) -> Result<Vec<f32>> {
+        let mut scores = Vec::with_capacity(documents.len());
+        for document in documents {
+            scores.push(self.score(query, document).await?);
         }
+        Ok(scores)
+    }
+}
+
+/// Cross-encoder reranking configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrossEncoderConfig {
+    /// Number of top results to rescore
+    pub top_n: usize,
+    /// Weight of the cross-encoder score against the original score (0.0 - 1.0)
+    pub weight: f32,
+    /// Documents scored per reranker call
+    pub batch_size: usize,
+    /// Time allowed per batch; results of a batch that times out keep their
+    /// original scores
+    pub batch_timeout_ms: u64,
+}
+
+impl Default for CrossEncoderConfig {
+    fn default() -> Self {
+        Self {
+            top_n: 20,
+            weight: 0.7,
+            batch_size: 8,
+            batch_timeout_ms: 250,
+        }
+    }
+}
+
+/// Reranker for tests: scores the fraction of query terms that occur in the
+/// document.
+#[derive(Debug, Clone, Default)]
+pub struct MockReranker {
+    delay: Option<Duration>,
+}
+
+impl MockReranker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wait this long before scoring each document.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+}
+
+#[async_trait]
+impl Reranker for MockReranker {
+    async fn score(&self, query: &str, document: &str) -> Result<f32> {
+        if let Some(delay) = self.delay {
+            tokio::time::sleep(delay).await;
+        }
+
+        let document = document.to_lowercase();
+        let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        if terms.is_empty() {
+            return Ok(0.0);
+        }
+        let matched = terms.iter().filter(|term| document.contains(term.as_str())).count();
+        Ok(matched as f32 / terms.len() as f32)
     }
 }
 
@@ -512,6 +1079,7 @@ pub struct AdvancedRanker {
     mmr_reranker: Option<MMRReranker>,
     personalized_ranker: Option<Arc<PersonalizedRanker>>,
     diversity_config: DiversityConfig,
+    cross_encoder: Option<(Arc<dyn Reranker>, CrossEncoderConfig)>,
 }
 
 impl AdvancedRanker {
@@ -521,6 +1089,7 @@ impl AdvancedRanker {
             mmr_reranker: None,
             personalized_ranker: None,
             diversity_config: DiversityConfig::default(),
+            cross_encoder: None,
         }
     }
 
@@ -542,6 +1111,13 @@ impl AdvancedRanker {
         self
     }
 
+    /// Enable cross-encoder reranking of the top results in
+    /// `rank_with_cross_encoder`.
+    pub fn with_cross_encoder(mut self, reranker: Arc<dyn Reranker>, config: CrossEncoderConfig) -> Self {
+        self.cross_encoder = Some((reranker, config));
+        self
+    }
+
     /// Rank and rerank documents with all enabled strategies.
     pub fn rank(
         &self,
@@ -549,18 +1125,27 @@ impl AdvancedRanker {
         query: &ProcessedQuery,
         query_embedding: Option<&[f32]>,
     ) -> Vec<RankedResult> {
+        // Metadata and embeddings are needed again for diversity
+        let mut originals: HashMap<String, (HashMap<String, String>, Option<Vector>)> = documents
+            .iter()
+            .map(|doc| (doc.id.clone(), (doc.metadata.clone(), doc.embedding.clone())))
+            .collect();
+
         // Stage 1: Base ranking
         let ranked = self.base_ranker.rank(documents, query);
 
         // Convert back to RankableDocument for reranking
         let mut rerank_docs: Vec<RankableDocument> = ranked
             .into_iter()
-            .map(|r| RankableDocument {
-                id: r.id,
-                content: String::new(), // Content not needed for reranking
-                semantic_score: r.final_score,
-                metadata: HashMap::new(),
-                embedding: None,
+            .map(|r| {
+                let (metadata, embedding) = originals.remove(&r.id).unwrap_or_default();
+                RankableDocument {
+                    id: r.id,
+                    content: String::new(), // Content not needed for reranking
+                    semantic_score: r.final_score,
+                    metadata,
+                    embedding,
+                }
             })
             .collect();
 
@@ -570,27 +1155,116 @@ impl AdvancedRanker {
         }
 
         // Stage 3: MMR for diversity
-        if let Some(mmr) = &self.mmr_reranker {
-            if let Some(query_emb) = query_embedding {
+        let diversified = match &self.mmr_reranker {
+            Some(mmr) if self.diversity_config.enable_diversity => {
                 let k = rerank_docs.len();
-                rerank_docs = mmr.rerank(rerank_docs, query_emb, k);
+                mmr.rerank_diverse(rerank_docs, query_embedding, k, &self.diversity_config)
+                    .selected
             }
-        }
+            _ => rerank_docs
+                .into_iter()
+                .map(|document| DiversifiedDocument {
+                    document,
+                    mmr_score: 0.0,
+                    demotion: None,
+                })
+                .collect(),
+        };
 
         // Convert back to RankedResult
-        rerank_docs
+        diversified
             .into_iter()
-            .map(|doc| RankedResult {
-                id: doc.id,
-                final_score: doc.semantic_score,
-                semantic_score: doc.semantic_score,
-                keyword_score: 0.0,
-                recency_score: 0.0,
-                popularity_score: 0.0,
-                explanation: Some(format!("Advanced reranking applied")),
+            .map(|diversified| {
+                let doc = diversified.document;
+                RankedResult {
+                    id: doc.id,
+                    final_score: doc.semantic_score,
+                    semantic_score: doc.semantic_score,
+                    keyword_score: 0.0,
+                    recency_score: 0.0,
+                    popularity_score: 0.0,
+                    explanation: Some(match diversified.demotion {
+                        Some(reason) => format!("Advanced reranking applied; {}", reason),
+                        None => "Advanced reranking applied".to_string(),
+                    }),
+                }
             })
             .collect()
     }
+
+    /// Rank like `rank`, then rescore the top `top_n` results with the
+    /// cross-encoder and blend its score into the final score. Results of a
+    /// batch that fails or times out keep their original scores. Without a
+    /// cross-encoder this is the same as `rank`.
+    pub async fn rank_with_cross_encoder(
+        &self,
+        documents: Vec<RankableDocument>,
+        query: &ProcessedQuery,
+        query_embedding: Option<&[f32]>,
+    ) -> Vec<RankedResult> {
+        let Some((reranker, config)) = &self.cross_encoder else {
+            return self.rank(documents, query, query_embedding);
+        };
+
+        let mut contents: HashMap<String, String> = documents
+            .iter()
+            .map(|doc| (doc.id.clone(), doc.content.clone()))
+            .collect();
+        let mut results = self.rank(documents, query, query_embedding);
+
+        let top_n = config.top_n.min(results.len());
+        let weight = config.weight.clamp(0.0, 1.0);
+        let timeout = Duration::from_millis(config.batch_timeout_ms);
+
+        for batch in results[..top_n].chunks_mut(config.batch_size.max(1)) {
+            let texts: Vec<String> = batch
+                .iter()
+                .map(|result| contents.remove(&result.id).unwrap_or_default())
+                .collect();
+
+            let scores = match tokio::time::timeout(
+                timeout,
+                reranker.score_batch(&query.original, &texts),
+            )
+            .await
+            {
+                Ok(Ok(scores)) if scores.len() == batch.len() => scores,
+                Ok(Ok(scores)) => {
+                    warn!(
+                        "Cross-encoder returned {} scores for {} documents, keeping original scores",
+                        scores.len(),
+                        batch.len()
+                    );
+                    continue;
+                }
+                Ok(Err(e)) => {
+                    warn!("Cross-encoder failed, keeping original scores: {}", e);
+                    continue;
+                }
+                Err(_) => {
+                    warn!(
+                        "Cross-encoder batch timed out after {:?}, keeping original scores",
+                        timeout
+                    );
+                    continue;
+                }
+            };
+
+            for (result, score) in batch.iter_mut().zip(scores) {
+                result.final_score = weight * score + (1.0 - weight) * result.final_score;
+                result.explanation = Some(match result.explanation.take() {
+                    Some(explanation) => {
+                        format!("{}; cross-encoder score {:.3}", explanation, score)
+                    }
+                    None => format!("Cross-encoder score {:.3}", score),
+                });
+            }
+        }
+
+        // Only the rescored head is reordered, so it still ranks above the tail
+        results[..top_n].sort_by(|a, b| b.final_score.total_cmp(&a.final_score));
+        results
+    }
 }
 
 #[cfg(test)]
@@ -612,11 +1286,13 @@ mod tests {
             original: "test query".to_string(),
             normalized: "test query".to_string(),
             expanded: vec!["test query".to_string()],
+            weighted_terms: vec![],
             intent: crate::query::QueryIntent::General,
             keywords: vec!["test".to_string(), "query".to_string()],
             filters: Default::default(),
             sub_queries: vec![],
             query_graph: None,
+            answer_type: crate::query::AnswerType::Explanation,
         }
     }
 
@@ -735,6 +1411,107 @@ mod tests {
         assert_eq!(reranked[1].id, "doc3");
     }
 
+    fn file_doc(id: &str, file: &str, semantic_score: f32, embedding: Vec<f32>) -> RankableDocument {
+        let mut doc = create_test_doc(id, id, semantic_score);
+        doc.metadata.insert("file_path".to_string(), file.to_string());
+        doc.embedding = Some(embedding);
+        doc
+    }
+
+    #[test]
+    fn test_mmr_field_cap_and_demotion_reasons() {
+        let mmr = MMRReranker::new(0.7);
+        let config = DiversityConfig {
+            max_per_field_value: Some(("file_path".to_string(), 2)),
+            ..Default::default()
+        };
+
+        let docs = vec![
+            file_doc("a1", "a.rs", 0.95, vec![1.0, 0.0, 0.0]),
+            file_doc("a2", "a.rs", 0.94, vec![0.0, 0.0, 1.0]),
+            file_doc("a3", "a.rs", 0.93, vec![0.0, 1.0, 0.0]),
+            file_doc("b1", "b.rs", 0.80, vec![0.5, 0.5, 0.0]),
+        ];
+
+        let ranking = mmr.rerank_diverse(docs, Some(&[1.0, 1.0, 1.0][..]), 4, &config);
+        let ids: Vec<&str> = ranking.selected.iter().map(|d| d.document.id.as_str()).collect();
+
+        // a3 never makes it in, and b1 is preferred over a2 for sharing no file
+        assert_eq!(ids, vec!["a1", "b1", "a2"]);
+        assert_eq!(ranking.capped.len(), 1);
+        assert_eq!(ranking.capped[0].document.id, "a3");
+        assert_eq!(
+            ranking.capped[0].demotion,
+            Some(DemotionReason::FieldCap {
+                field: "file_path".to_string(),
+                value: "a.rs".to_string(),
+            })
+        );
+        assert!(matches!(
+            ranking.selected[2].demotion,
+            Some(DemotionReason::FieldValue { .. })
+        ));
+    }
+
+    #[test]
+    fn test_mmr_similarity_demotion_reason() {
+        let mmr = MMRReranker::new(0.5);
+        let docs = vec![
+            file_doc("a", "a.rs", 0.9, vec![1.0, 0.0]),
+            file_doc("near", "b.rs", 0.85, vec![0.99, 0.1]),
+            file_doc("far", "c.rs", 0.8, vec![0.0, 1.0]),
+        ];
+
+        let ranking = mmr.rerank_diverse(docs, Some(&[1.0, 0.5][..]), 3, &DiversityConfig::default());
+        let near = ranking.selected.iter().find(|d| d.document.id == "near").unwrap();
+
+        assert_eq!(ranking.selected[1].document.id, "far");
+        assert!(matches!(
+            &near.demotion,
+            Some(DemotionReason::Similarity { similar_to, .. }) if similar_to == "a"
+        ));
+    }
+
+    mod property_tests {
+        use super::*;
+        use proptest::prelude::*;
+
+        proptest! {
+            #[test]
+            fn test_field_cap_never_violated(
+                docs in prop::collection::vec((0usize..4, 0.0f32..1.0, prop::collection::vec(-1.0f32..1.0, 4)), 0..40),
+                cap in 1usize..4,
+                k in 1usize..40,
+                lambda in 0.0f32..=1.0,
+                field_penalty in 0.0f32..2.0,
+            ) {
+                let documents: Vec<RankableDocument> = docs
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, (file, score, embedding))| {
+                        file_doc(&format!("doc{}", i), &format!("file{}.rs", file), score, embedding)
+                    })
+                    .collect();
+                let total = documents.len();
+                let config = DiversityConfig {
+                    max_per_field_value: Some(("file_path".to_string(), cap)),
+                    field_penalty,
+                    ..Default::default()
+                };
+
+                let ranking = MMRReranker::new(lambda).rerank_diverse(documents, Some(&[0.5; 4][..]), k, &config);
+
+                let mut per_file: HashMap<String, usize> = HashMap::new();
+                for doc in &ranking.selected {
+                    *per_file.entry(doc.document.metadata["file_path"].clone()).or_insert(0) += 1;
+                }
+                prop_assert!(per_file.values().all(|count| *count <= cap));
+                prop_assert!(ranking.selected.len() <= k);
+                prop_assert!(ranking.selected.len() + ranking.capped.len() <= total);
+            }
+        }
+    }
+
     #[test]
     fn test_personalized_ranking() {
         let mut config = PersonalizationConfig::default();
@@ -754,6 +1531,60 @@ mod tests {
         assert_eq!(reranked[0].id, "doc1");
     }
 
+    fn lang_doc(id: &str, language: &str, semantic_score: f32) -> RankableDocument {
+        let mut doc = create_test_doc(id, id, semantic_score);
+        doc.metadata.insert("language".to_string(), language.to_string());
+        doc.metadata.insert("file_path".to_string(), format!("src/{}/{}", language, id));
+        doc
+    }
+
+    #[test]
+    fn test_feedback_learns_agent_preferences() {
+        let ranker = PersonalizedRanker::new(PersonalizationConfig::default());
+        let docs = vec![lang_doc("py1", "python", 0.8), lang_doc("rs1", "rust", 0.75)];
+
+        // Cold start ranks exactly like the unpersonalized path
+        let cold = ranker.rerank_for_agent("agent1", docs.clone());
+        assert_eq!(
+            cold.iter().map(|d| (d.id.as_str(), d.semantic_score)).collect::<Vec<_>>(),
+            vec![("py1", 0.8), ("rs1", 0.75)]
+        );
+
+        for _ in 0..5 {
+            assert!(ranker.record_feedback("agent1", "parse config", "rs1", FeedbackKind::Used));
+        }
+        assert!(ranker.record_feedback("agent1", "parse config", "py1", FeedbackKind::Dismissed));
+        assert!(!ranker.record_feedback("agent1", "parse config", "unknown", FeedbackKind::Clicked));
+
+        let profile = ranker.profile("agent1").unwrap();
+        assert_eq!(profile.feedback_count, 6);
+        assert!(profile.languages["rust"] > 0.0);
+        assert!(profile.languages["python"] < 0.0);
+        assert!(profile.paths.contains_key("src/rust"));
+        assert_eq!(ranker.feedback_log().len(), 7);
+
+        let personalized = ranker.rerank_for_agent("agent1", docs.clone());
+        assert_eq!(personalized[0].id, "rs1");
+
+        // Other agents are unaffected
+        let other = ranker.rerank_for_agent("agent2", docs);
+        assert_eq!(other[0].id, "py1");
+    }
+
+    #[tokio::test]
+    async fn test_profiles_are_stored_in_coordinator() {
+        let coordinator = AgentCoordinator::new();
+        let ranker = PersonalizedRanker::new(PersonalizationConfig::default())
+            .with_coordinator(&coordinator);
+
+        ranker.rerank_for_agent("agent1", vec![lang_doc("rs1", "rust", 0.5)]);
+        ranker.record_feedback("agent1", "query", "rs1", FeedbackKind::Clicked);
+
+        let stored = coordinator.preference_profile(&"agent1".to_string()).unwrap();
+        assert_eq!(stored.feedback_count, 1);
+        assert!(stored.languages["rust"] > 0.0);
+    }
+
     #[test]
     fn test_advanced_ranker() {
         let ranker = AdvancedRanker::new(RankingStrategy::Semantic)
@@ -772,4 +1603,84 @@ mod tests {
         assert!(!results.is_empty());
         assert!(results[0].final_score > 0.0);
     }
+
+    #[tokio::test]
+    async fn test_cross_encoder_reorders_top_results() {
+        let ranker = AdvancedRanker::new(RankingStrategy::Semantic).with_cross_encoder(
+            Arc::new(MockReranker::new()),
+            CrossEncoderConfig {
+                weight: 0.8,
+                ..Default::default()
+            },
+        );
+        let query = create_test_query();
+
+        let docs = vec![
+            create_test_doc("unrelated", "unrelated text", 0.9),
+            create_test_doc("answer", "test query answer", 0.8),
+        ];
+        let results = ranker.rank_with_cross_encoder(docs, &query, None).await;
+
+        assert_eq!(results[0].id, "answer");
+        assert!((results[0].final_score - (0.8 * 1.0 + 0.2 * 0.8)).abs() < 1e-5);
+        assert!((results[1].final_score - 0.2 * 0.9).abs() < 1e-5);
+        assert!(results[0]
+            .explanation
+            .as_deref()
+            .unwrap()
+            .contains("cross-encoder score"));
+    }
+
+    #[tokio::test]
+    async fn test_cross_encoder_timeout_keeps_original_scores() {
+        let ranker = AdvancedRanker::new(RankingStrategy::Semantic).with_cross_encoder(
+            Arc::new(MockReranker::new().with_delay(Duration::from_millis(200))),
+            CrossEncoderConfig {
+                batch_timeout_ms: 10,
+                ..Default::default()
+            },
+        );
+        let query = create_test_query();
+
+        let docs = vec![
+            create_test_doc("unrelated", "unrelated text", 0.9),
+            create_test_doc("answer", "test query answer", 0.8),
+        ];
+        let results = ranker.rank_with_cross_encoder(docs, &query, None).await;
+
+        assert_eq!(results[0].id, "unrelated");
+        assert!((results[0].final_score - 0.9).abs() < 1e-5);
+        assert!((results[1].final_score - 0.8).abs() < 1e-5);
+    }
+
+    #[tokio::test]
+    async fn test_cross_encoder_only_rescores_top_n() {
+        let ranker = AdvancedRanker::new(RankingStrategy::Semantic).with_cross_encoder(
+            Arc::new(MockReranker::new()),
+            CrossEncoderConfig {
+                top_n: 2,
+                batch_size: 1,
+                ..Default::default()
+            },
+        );
+        let query = create_test_query();
+
+        let docs = vec![
+            create_test_doc("first", "unrelated text", 0.9),
+            create_test_doc("second", "test only", 0.8),
+            create_test_doc("third", "test query answer", 0.7),
+        ];
+        let results = ranker.rank_with_cross_encoder(docs, &query, None).await;
+
+        // The third result is outside the top two, so it keeps its score and
+        // stays last even though it matches the query best
+        assert_eq!(results[0].id, "second");
+        assert_eq!(results[2].id, "third");
+        assert!((results[2].final_score - 0.7).abs() < 1e-5);
+        assert!(!results[2]
+            .explanation
+            .as_deref()
+            .unwrap_or_default()
+            .contains("cross-encoder"));
+    }
 }
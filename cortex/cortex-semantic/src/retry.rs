@@ -0,0 +1,239 @@
+//! Retrying operations that fail with retryable errors.
+//!
+//! [`RetryPolicy`] retries only errors for which
+//! [`SemanticError::is_retryable`] holds, backing off exponentially with
+//! jitter so that callers failing together do not retry together. A wait the
+//! server asked for through [`SemanticError::retry_after`] is always honored.
+
+use crate::error::{Result, SemanticError};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+use tracing::warn;
+
+/// How often and how patiently to retry a failed operation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// Attempts including the first; 1 disables retries
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+
+    /// Backoff before the first retry, doubled for each further one
+    #[serde(default = "default_initial_backoff_ms")]
+    pub initial_backoff_ms: u64,
+
+    /// Upper bound of the backoff, before jitter
+    #[serde(default = "default_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+
+    /// Fraction of each backoff that is randomized, in `0.0..=1.0`; at 0.5
+    /// the wait is between half and all of the backoff
+    #[serde(default = "default_jitter")]
+    pub jitter: f64,
+}
+
+fn default_max_attempts() -> u32 {
+    3
+}
+
+fn default_initial_backoff_ms() -> u64 {
+    100
+}
+
+fn default_max_backoff_ms() -> u64 {
+    10_000
+}
+
+fn default_jitter() -> f64 {
+    0.5
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_max_attempts(),
+            initial_backoff_ms: default_initial_backoff_ms(),
+            max_backoff_ms: default_max_backoff_ms(),
+            jitter: default_jitter(),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries.
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// This policy with `max_attempts` attempts, counting the first.
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Backoff before retry number `retry` (1 for the first retry), without
+    /// jitter.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 1u64 << retry.saturating_sub(1).min(32);
+        Duration::from_millis(
+            self.initial_backoff_ms
+                .saturating_mul(factor)
+                .min(self.max_backoff_ms),
+        )
+    }
+
+    /// Wait before the next attempt after attempt number `attempt` (1 for
+    /// the first) failed with `err`, or `None` when the error is permanent or
+    /// the attempts are used up.
+    pub fn next_delay(&self, attempt: u32, err: &SemanticError) -> Option<Duration> {
+        if !err.is_retryable() || attempt >= self.max_attempts {
+            return None;
+        }
+
+        let backoff = self.backoff(attempt);
+        let jitter = self.jitter.clamp(0.0, 1.0) * random_fraction();
+        let delay = backoff.mul_f64(1.0 - jitter);
+        Some(err.retry_after().map_or(delay, |wait| wait.max(delay)))
+    }
+
+    /// Run `operation` until it succeeds, fails with a permanent error, or
+    /// the attempts are used up. `name` identifies the operation in logs.
+    pub async fn run<T, F, Fut>(&self, name: &str, mut operation: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match operation().await {
+                Ok(value) => return Ok(value),
+                Err(err) => {
+                    let Some(delay) = self.next_delay(attempt, &err) else {
+                        return Err(err);
+                    };
+                    warn!(
+                        "{} failed (attempt {}/{}, {}), retrying in {:?}: {}",
+                        name,
+                        attempt,
+                        self.max_attempts,
+                        err.error_code(),
+                        delay,
+                        err
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+}
+
+/// A random number in `0.0..1.0`. `RandomState` is seeded differently for
+/// every instance, which is all the randomness jitter needs.
+fn random_fraction() -> f64 {
+    let bits = std::collections::hash_map::RandomState::new()
+        .build_hasher()
+        .finish();
+    (bits >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn unavailable() -> SemanticError {
+        SemanticError::VectorStoreUnavailable {
+            source: Box::new(std::io::Error::from(std::io::ErrorKind::ConnectionRefused)),
+        }
+    }
+
+    #[test]
+    fn test_backoff_is_jittered_and_bounded() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            initial_backoff_ms: 100,
+            max_backoff_ms: 1_000,
+            jitter: 0.5,
+        };
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(3), Duration::from_millis(400));
+        assert_eq!(policy.backoff(9), Duration::from_millis(1_000));
+
+        for attempt in 1..10 {
+            let delay = policy.next_delay(attempt, &unavailable()).unwrap();
+            assert!(delay <= policy.backoff(attempt));
+            assert!(delay >= policy.backoff(attempt) / 2);
+        }
+        assert_eq!(policy.next_delay(10, &unavailable()), None);
+
+        // The server's wait wins over a shorter backoff
+        let limited = SemanticError::ProviderRateLimited {
+            provider: "OpenAI".to_string(),
+            retry_after: Some(Duration::from_secs(20)),
+        };
+        assert_eq!(policy.next_delay(1, &limited), Some(Duration::from_secs(20)));
+
+        let permanent = SemanticError::CollectionMissing { name: "code".to_string() };
+        assert_eq!(policy.next_delay(1, &permanent), None);
+    }
+
+    #[tokio::test]
+    async fn test_run_retries_only_retryable_errors() {
+        let policy = RetryPolicy {
+            initial_backoff_ms: 1,
+            ..Default::default()
+        };
+
+        let calls = AtomicU32::new(0);
+        let value = policy
+            .run("flaky", || async {
+                if calls.fetch_add(1, Ordering::Relaxed) < 2 {
+                    Err(unavailable())
+                } else {
+                    Ok(42)
+                }
+            })
+            .await
+            .unwrap();
+        assert_eq!(value, 42);
+        assert_eq!(calls.load(Ordering::Relaxed), 3);
+
+        // Attempts run out
+        let calls = AtomicU32::new(0);
+        let err = policy
+            .run("down", || async {
+                calls.fetch_add(1, Ordering::Relaxed);
+                Err::<(), _>(unavailable())
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SemanticError::VectorStoreUnavailable { .. }));
+        assert_eq!(calls.load(Ordering::Relaxed), 3);
+
+        // Permanent errors are returned right away
+        let calls = AtomicU32::new(0);
+        policy
+            .run("missing", || async {
+                calls.fetch_add(1, Ordering::Relaxed);
+                Err::<(), _>(SemanticError::CollectionMissing { name: "code".to_string() })
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+
+        let calls = AtomicU32::new(0);
+        RetryPolicy::none()
+            .run("once", || async {
+                calls.fetch_add(1, Ordering::Relaxed);
+                Err::<(), _>(unavailable())
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+    }
+}
@@ -1,19 +1,41 @@
 //! Main semantic search engine implementation.
 
-use crate::cache::{EmbeddingCache, EmbeddingCacheKey, QueryCache, QueryCacheKey, CachedSearchResult};
-use crate::config::SemanticConfig;
-use crate::error::Result;
-use crate::providers::{EmbeddingProvider, ProviderManager};
-use crate::qdrant::{VectorIndex, QdrantVectorStore};
-use crate::query::QueryProcessor;
-use crate::ranking::{Ranker, RankableDocument, RankingStrategy};
-use crate::types::{DocumentId, EntityType, IndexedDocument, Vector};
+use crate::agent::{AgentCoordinator, AgentId, Namespace, agent_namespace};
+use crate::answer::{AnswerExtractor, AnswerSnippet};
+use crate::cache::{
+    CacheHitType, CachedSearchResult, EmbeddingCache, EmbeddingCacheKey, EmbeddingCacheStats, QueryCache,
+    QueryCacheKey, QueryScope, SemanticQueryCache,
+};
+use crate::config::{InitializationMode, IntentBranch, IntentRule, SemanticConfig};
+use crate::error::{Result, SemanticError};
+use crate::filter::FilterExpr;
+use crate::hyde::{HydeConfig, HydeProcessor};
+use crate::orchestration::CONTENT_HASH_KEY;
+use crate::payload::select_payload;
+use crate::providers::{
+    EmbeddingProvider, EmbeddingUsage, ProviderHealth, ProviderManager, UsageCallback,
+    attribute_usage_to,
+};
+use crate::qdrant::{QdrantVectorStore, SearchResult as IndexSearchResult, VectorIndex};
+use crate::query::{
+    DomainDictionary, ExpansionOptions, ProcessedQuery, QueryExpander, QueryIntent, QueryProcessor,
+};
+use crate::ranking::{RankableDocument, RankedResult, Ranker, RankingStrategy, ScoringWeights};
+use crate::snapshot::{
+    ImportProgress, SnapshotHeader, SnapshotReader, SnapshotRecord, SnapshotSummary, SnapshotWriter,
+};
+use crate::sparse::SparseIndex;
+use crate::types::{DocumentId, EmbeddingModel, EntityType, IndexedDocument, Vector, normalize};
+use crate::warmup::{ReadinessStatus, StageTiming, WarmupReport, WarmupStage};
+use async_trait::async_trait;
 use dashmap::DashMap;
+use futures::stream::{self, StreamExt};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 /// Main semantic search engine.
 pub struct SemanticSearchEngine {
@@ -21,12 +43,23 @@ pub struct SemanticSearchEngine {
     provider: Arc<ProviderManager>,
     index: Arc<dyn VectorIndex>,
     documents: Arc<DashMap<DocumentId, IndexedDocument>>,
+    /// Chunk texts of multi-vector documents, in order
+    chunks: Arc<DashMap<DocumentId, Vec<String>>>,
+    /// Term index searched along with `index` when hybrid search is enabled
+    sparse: Option<SparseIndex>,
     query_processor: QueryProcessor,
     ranker: Ranker,
+    hyde: HydeProcessor,
+    answers: AnswerExtractor,
     embedding_cache: Option<EmbeddingCache>,
     query_cache: Option<QueryCache>,
+    semantic_cache: Option<SemanticQueryCache>,
+    readiness: parking_lot::RwLock<ReadinessStatus>,
 }
 
+/// Query embedded and searched for by the warm-up canary.
+const WARMUP_CANARY: &str = "warm-up canary query";
+
 /// Search filter options.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SearchFilter {
@@ -34,6 +67,144 @@ pub struct SearchFilter {
     pub language: Option<String>,
     pub min_score: Option<f32>,
     pub metadata_filters: HashMap<String, String>,
+    /// Only match documents tagged with one of these namespaces; untagged
+    /// documents are shared and always match
+    #[serde(default)]
+    pub namespaces: Option<BTreeSet<Namespace>>,
+    /// Boost results of these entity types instead of excluding the others
+    #[serde(default)]
+    pub boost_entity_types: Vec<EntityType>,
+    /// Boost results carrying one of these metadata values
+    #[serde(default)]
+    pub boost_metadata: HashMap<String, String>,
+    /// Score multiplier of boosted results, 1.0 if unset
+    #[serde(default)]
+    pub boost_factor: Option<f32>,
+    /// Expression over metadata fields, `id` and `entity_type`, combined
+    /// with the other filters by AND
+    #[serde(default)]
+    pub expr: Option<FilterExpr>,
+    /// Fields to return in each result's `payload`: `id`, `content`,
+    /// `entity_type`, `indexed_at` or a metadata key. No payload when empty
+    #[serde(default)]
+    pub with_payload: Vec<String>,
+    /// Byte limit of each result's payload; `content` and other text fields
+    /// are cut to fit it, setting `payload_truncated`
+    #[serde(default)]
+    pub max_payload_bytes: Option<usize>,
+}
+
+/// Document fields filter expressions can refer to besides metadata
+const DOCUMENT_FILTER_FIELDS: &[&str] = &["id", "entity_type"];
+
+/// Payload key holding the parent document id of a chunk vector
+const PARENT_ID_KEY: &str = "parent_id";
+
+/// Payload key holding the position of a chunk vector in its document
+const CHUNK_INDEX_KEY: &str = "chunk_index";
+
+/// Index candidates fetched per result when multi-vector documents are
+/// indexed, since one document may take several of them
+const CHUNK_CANDIDATE_FACTOR: usize = 4;
+
+/// Points read from the vector index per page when exporting a snapshot
+const SNAPSHOT_PAGE_SIZE: usize = 256;
+
+/// The chunk of a multi-vector document that best matched a query.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChunkMatch {
+    /// Position of the chunk in the document
+    pub index: usize,
+    pub text: String,
+    /// Similarity of the chunk to the query
+    pub score: f32,
+}
+
+/// A document `index_documents` could not index.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexFailure {
+    pub doc_id: DocumentId,
+    pub error: String,
+}
+
+/// Outcome of `index_documents`, per document.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IndexReport {
+    pub succeeded: Vec<DocumentId>,
+    pub failed: Vec<IndexFailure>,
+}
+
+impl IndexReport {
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+/// Progress of `index_documents`, reported after every batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexProgress {
+    /// Documents done so far, indexed or failed
+    pub processed: usize,
+    pub failed: usize,
+    pub total: usize,
+}
+
+/// Scores of a hybrid search result before reranking.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HybridScores {
+    /// Vector similarity, if the dense search retrieved the document
+    pub dense: Option<f32>,
+    /// BM25 score, if the sparse search retrieved the document
+    pub sparse: Option<f32>,
+    /// Both combined with `HybridConfig::fusion`
+    pub fused: f32,
+}
+
+/// Whether `update_document` re-embedded a document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentUpdate {
+    /// The content hash matched, so nothing was re-embedded
+    Unchanged,
+    Updated,
+}
+
+/// Outcome of `reindex_stale`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReindexReport {
+    /// Documents whose content hash still matches, or that the source no
+    /// longer has
+    pub skipped: usize,
+    /// Documents re-embedded with their new content
+    pub updated: usize,
+    pub failed: Vec<IndexFailure>,
+}
+
+/// Current content of indexed documents, compared by `reindex_stale`.
+#[async_trait]
+pub trait ContentSource: Send + Sync {
+    /// Content of a document, or `None` if the source no longer has it.
+    async fn content(&self, doc_id: &DocumentId) -> Result<Option<String>>;
+}
+
+#[async_trait]
+impl ContentSource for HashMap<DocumentId, String> {
+    async fn content(&self, doc_id: &DocumentId) -> Result<Option<String>> {
+        Ok(self.get(doc_id).cloned())
+    }
+}
+
+/// Hex BLAKE3 hash of a document's content.
+fn content_hash(content: &str) -> String {
+    blake3::hash(content.as_bytes()).to_hex().to_string()
+}
+
+/// An index hit resolved to its document.
+struct DocumentHit {
+    doc_id: DocumentId,
+    score: f32,
+    /// Index and score of the best chunk, for multi-vector documents
+    best_chunk: Option<(usize, f32)>,
 }
 
 /// Search result.
@@ -47,6 +218,36 @@ pub struct SearchResult {
     pub explanation: Option<String>,
     /// Embedding vector for similarity calculations (optional for deduplication)
     pub embedding: Option<Vector>,
+    /// Set when the result was served from the query cache
+    #[serde(default)]
+    pub cache_hit_type: Option<CacheHitType>,
+    /// How the query was searched: `query_intent` and the `intent_policy`
+    /// branch that fired
+    #[serde(default)]
+    pub debug_metadata: HashMap<String, String>,
+    /// Span of this result that answers a factoid query directly; set on
+    /// at most one result of a search
+    #[serde(default)]
+    pub answer_snippet: Option<AnswerSnippet>,
+    /// Best matching chunk of a multi-vector document, for citing the
+    /// exact passage
+    #[serde(default)]
+    pub best_chunk: Option<ChunkMatch>,
+    /// Dense, sparse and fused scores, when hybrid search is enabled;
+    /// `score` is the fused score after reranking and boosts
+    #[serde(default)]
+    pub hybrid_scores: Option<HybridScores>,
+    /// Decomposed sub-query that produced the result, when searched
+    /// through a `QueryPlanExecutor`
+    #[serde(default)]
+    pub sub_query: Option<usize>,
+    /// Fields requested with `SearchFilter::with_payload`, as a JSON object
+    #[serde(default)]
+    pub payload: Option<serde_json::Value>,
+    /// Whether payload fields were cut or left out to fit
+    /// `SearchFilter::max_payload_bytes`
+    #[serde(default)]
+    pub payload_truncated: bool,
 }
 
 impl SemanticSearchEngine {
@@ -60,6 +261,7 @@ impl SemanticSearchEngine {
         // Determine dimension from provider
         let dimension = provider.dimension();
         info!("Using embedding dimension: {}", dimension);
+        config.check_vector_dimension(dimension)?;
 
         // Create Qdrant vector store
         let similarity_metric = config.index.similarity_metric;
@@ -73,14 +275,7 @@ impl SemanticSearchEngine {
         let index: Arc<dyn VectorIndex> = Arc::new(qdrant_store);
 
         // Create caches
-        let embedding_cache = if config.cache.enable_embedding_cache {
-            Some(EmbeddingCache::new(
-                config.cache.embedding_cache_size,
-                Duration::from_secs(config.cache.embedding_cache_ttl_seconds),
-            ))
-        } else {
-            None
-        };
+        let embedding_cache = Self::build_embedding_cache(&config);
 
         let query_cache = if config.cache.enable_query_cache {
             Some(QueryCache::new(
@@ -91,12 +286,18 @@ impl SemanticSearchEngine {
             None
         };
 
+        let semantic_cache = Self::build_semantic_cache(&config);
+        let query_processor = Self::build_query_processor(&config)?;
+
         // Create ranker
         let ranker = Ranker::new(if config.search.enable_hybrid_search {
             RankingStrategy::Hybrid
         } else {
             RankingStrategy::Semantic
         });
+        let hyde = HydeProcessor::new(provider.clone(), HydeConfig::default());
+        let answers = AnswerExtractor::new(config.search.answer_extraction.clone());
+        let sparse = config.search.hybrid.enabled.then(SparseIndex::default);
 
         info!("Semantic search engine initialized successfully");
 
@@ -105,32 +306,52 @@ impl SemanticSearchEngine {
             provider,
             index,
             documents: Arc::new(DashMap::new()),
-            query_processor: QueryProcessor::new(),
+            chunks: Arc::new(DashMap::new()),
+            sparse,
+            query_processor,
             ranker,
+            hyde,
+            answers,
             embedding_cache,
             query_cache,
-        })
+            semantic_cache,
+            readiness: parking_lot::RwLock::new(ReadinessStatus::Cold),
+        }
+        .initialized()
+        .await)
     }
 
     /// Create a new semantic search engine with custom vector store.
     pub async fn with_vector_store(
         config: SemanticConfig,
         vector_store: Arc<dyn VectorIndex>,
+    ) -> Result<Self> {
+        let provider = Arc::new(ProviderManager::from_config(&config.embedding).await?);
+        Self::with_provider(config, provider, vector_store).await
+    }
+
+    /// Create a new semantic search engine with custom embedding providers
+    /// and vector store. `config.embedding` is not used to create providers.
+    pub async fn with_provider(
+        config: SemanticConfig,
+        provider: Arc<ProviderManager>,
+        vector_store: Arc<dyn VectorIndex>,
     ) -> Result<Self> {
         info!("Initializing semantic search engine with custom vector store");
 
-        // Create provider manager
-        let provider = Arc::new(ProviderManager::from_config(&config.embedding).await?);
+        // The store must hold vectors of the size the provider produces
+        let dimension = provider.dimension();
+        config.check_vector_dimension(dimension)?;
+        let store_dimension = vector_store.stats().await.dimension;
+        if store_dimension != dimension {
+            return Err(SemanticError::DimensionMismatch {
+                expected: store_dimension,
+                got: dimension,
+            });
+        }
 
         // Create caches
-        let embedding_cache = if config.cache.enable_embedding_cache {
-            Some(EmbeddingCache::new(
-                config.cache.embedding_cache_size,
-                Duration::from_secs(config.cache.embedding_cache_ttl_seconds),
-            ))
-        } else {
-            None
-        };
+        let embedding_cache = Self::build_embedding_cache(&config);
 
         let query_cache = if config.cache.enable_query_cache {
             Some(QueryCache::new(
@@ -141,23 +362,37 @@ impl SemanticSearchEngine {
             None
         };
 
+        let semantic_cache = Self::build_semantic_cache(&config);
+        let query_processor = Self::build_query_processor(&config)?;
+
         // Create ranker
         let ranker = Ranker::new(if config.search.enable_hybrid_search {
             RankingStrategy::Hybrid
         } else {
             RankingStrategy::Semantic
         });
+        let hyde = HydeProcessor::new(provider.clone(), HydeConfig::default());
+        let answers = AnswerExtractor::new(config.search.answer_extraction.clone());
+        let sparse = config.search.hybrid.enabled.then(SparseIndex::default);
 
         Ok(Self {
             config,
             provider,
             index: vector_store,
             documents: Arc::new(DashMap::new()),
-            query_processor: QueryProcessor::new(),
+            chunks: Arc::new(DashMap::new()),
+            sparse,
+            query_processor,
             ranker,
+            hyde,
+            answers,
             embedding_cache,
             query_cache,
-        })
+            semantic_cache,
+            readiness: parking_lot::RwLock::new(ReadinessStatus::Cold),
+        }
+        .initialized()
+        .await)
     }
 
     /// Index a document with its content.
@@ -170,8 +405,15 @@ impl SemanticSearchEngine {
     ) -> Result<()> {
         debug!("Indexing document: {}", doc_id);
 
+        // Cached results may hold the previous version of the document
+        if self.documents.contains_key(&doc_id) {
+            self.invalidate_document_caches(&doc_id);
+            self.remove_chunk_vectors(&doc_id).await?;
+        }
+
         // Generate embedding
-        let embedding = self.generate_embedding(&content).await?;
+        let embedding = self.generate_embedding(&content, self.document_embedding_cache()).await?;
+        self.index_terms(&doc_id, &content);
 
         // Create indexed document
         let indexed_doc = IndexedDocument {
@@ -194,6 +436,79 @@ impl SemanticSearchEngine {
         Ok(())
     }
 
+    /// Index a long document as several chunks, one vector per chunk.
+    ///
+    /// Searches match each chunk separately and combine the scores of a
+    /// document's matching chunks with `SearchConfig::chunk_aggregation`;
+    /// results carry the best matching chunk. The document's content is its
+    /// chunks joined by blank lines.
+    pub async fn index_document_chunked(
+        &self,
+        doc_id: DocumentId,
+        chunks: Vec<String>,
+        entity_type: EntityType,
+        metadata: HashMap<String, String>,
+    ) -> Result<()> {
+        debug!("Indexing document {} as {} chunks", doc_id, chunks.len());
+
+        if chunks.is_empty() {
+            return Err(SemanticError::Index(format!("Document {} has no chunks", doc_id)));
+        }
+
+        // Replace every vector of the previous version
+        if self.documents.contains_key(&doc_id) {
+            self.invalidate_document_caches(&doc_id);
+            if !self.remove_chunk_vectors(&doc_id).await? {
+                self.index.remove(&doc_id).await?;
+            }
+        }
+
+        let embeddings = self.generate_embeddings_batch(&chunks).await?;
+
+        // The document's own embedding, used for reranking and
+        // deduplication, is the normalized mean of its chunks
+        let mut embedding = vec![0.0; embeddings.first().map_or(0, Vec::len)];
+        for chunk_embedding in &embeddings {
+            for (value, chunk_value) in embedding.iter_mut().zip(chunk_embedding) {
+                *value += chunk_value;
+            }
+        }
+        normalize(&mut embedding);
+
+        let items = embeddings
+            .into_iter()
+            .enumerate()
+            .map(|(index, chunk_embedding)| {
+                let payload = HashMap::from([
+                    (PARENT_ID_KEY.to_string(), serde_json::json!(doc_id)),
+                    (CHUNK_INDEX_KEY.to_string(), serde_json::json!(index)),
+                ]);
+                (Self::chunk_vector_id(&doc_id, index), chunk_embedding, payload)
+            })
+            .collect();
+
+        let content = chunks.join("\n\n");
+        self.index_terms(&doc_id, &content);
+        self.documents.insert(
+            doc_id.clone(),
+            IndexedDocument {
+                id: doc_id.clone(),
+                entity_type,
+                content,
+                embedding,
+                model: self.provider.model().clone(),
+                metadata,
+                indexed_at: chrono::Utc::now(),
+            },
+        );
+        self.chunks.insert(doc_id, chunks);
+
+        self.index.insert_batch_with_payloads(items).await?;
+
+        debug!("Chunked document indexed successfully");
+        Ok(())
+    }
+
     /// Index multiple documents in batch.
     pub async fn index_batch(
         &self,
@@ -213,6 +528,7 @@ impl SemanticSearchEngine {
         for ((doc_id, content, entity_type, metadata), embedding) in
             documents.into_iter().zip(embeddings.into_iter())
         {
+            self.index_terms(&doc_id, &content);
             let indexed_doc = IndexedDocument {
                 id: doc_id.clone(),
                 entity_type,
@@ -224,6 +540,9 @@ impl SemanticSearchEngine {
             };
 
             self.documents.insert(doc_id.clone(), indexed_doc);
+            if self.documents.contains_key(&doc_id) {
+                self.invalidate_document_caches(&doc_id);
+            }
             index_items.push((doc_id, embedding));
         }
 
@@ -234,6 +553,287 @@ impl SemanticSearchEngine {
         Ok(())
     }
 
+    /// Index many documents, embedding them in batches of
+    /// `IndexConfig::max_batch_size` with up to
+    /// `IndexConfig::max_concurrent_batches` batches in flight, and upserting
+    /// each batch into the index at once.
+    ///
+    /// A bad document does not fail the others: when a batch fails to embed,
+    /// its documents are embedded one by one, and the report lists every
+    /// document that could not be indexed.
+    pub async fn index_documents(&self, docs: Vec<(DocumentId, String)>) -> IndexReport {
+        self.index_documents_with_progress(docs, |_| {}).await
+    }
+
+    /// `index_documents`, calling `progress` after every batch.
+    pub async fn index_documents_with_progress(
+        &self,
+        docs: Vec<(DocumentId, String)>,
+        progress: impl Fn(IndexProgress),
+    ) -> IndexReport {
+        let total = docs.len();
+        info!("Indexing {} documents", total);
+
+        let mut embedded = stream::iter(self.embedding_batches(docs))
+            .map(|batch| self.embed_documents(batch))
+            .buffer_unordered(self.config.index.max_concurrent_batches.max(1));
+
+        let mut report = IndexReport::default();
+        while let Some((embedded_docs, failures)) = embedded.next().await {
+            report.failed.extend(failures);
+            if !embedded_docs.is_empty() {
+                match self.store_embedded(embedded_docs).await {
+                    Ok(stored) => report.succeeded.extend(stored),
+                    Err(failures) => report.failed.extend(failures),
+                }
+            }
+
+            progress(IndexProgress {
+                processed: report.succeeded.len() + report.failed.len(),
+                failed: report.failed.len(),
+                total,
+            });
+        }
+
+        if !report.is_complete() {
+            warn!("Failed to index {} of {} documents", report.failed.len(), total);
+        }
+        info!("Indexed {} documents", report.succeeded.len());
+        report
+    }
+
+    /// Replace the content of an indexed document, keeping its entity type
+    /// and metadata.
+    ///
+    /// The document is re-embedded only when the hash of the new content
+    /// differs from the hash it was indexed with. The new vector and its
+    /// `content_hash` payload then replace the old ones in a single upsert;
+    /// a document indexed as chunks becomes a single vector.
+    pub async fn update_document(&self, doc_id: &DocumentId, content: String) -> Result<DocumentUpdate> {
+        let hash = content_hash(&content);
+        if self.stored_hash(doc_id)? == hash {
+            debug!("Document {} is unchanged", doc_id);
+            return Ok(DocumentUpdate::Unchanged);
+        }
+
+        let embedding = self.generate_embedding(&content, self.document_embedding_cache()).await?;
+        self.replace_content(doc_id, content, hash, embedding).await?;
+        debug!("Document {} updated", doc_id);
+        Ok(DocumentUpdate::Updated)
+    }
+
+    /// Re-embed every indexed document whose content in `source` no longer
+    /// matches the hash it was indexed with, in batches like
+    /// `index_documents`. Unchanged documents and documents the source no
+    /// longer has are skipped.
+    pub async fn reindex_stale(&self, source: &dyn ContentSource) -> ReindexReport {
+        let doc_ids: Vec<DocumentId> = self.documents.iter().map(|doc| doc.key().clone()).collect();
+        info!("Checking {} documents for stale embeddings", doc_ids.len());
+
+        let mut report = ReindexReport::default();
+        let mut stale = Vec::new();
+        for doc_id in doc_ids {
+            match source.content(&doc_id).await {
+                Ok(Some(content)) => match self.stored_hash(&doc_id) {
+                    Ok(hash) if hash != content_hash(&content) => stale.push((doc_id, content)),
+                    // Unchanged, or removed since the scan started
+                    _ => report.skipped += 1,
+                },
+                Ok(None) => report.skipped += 1,
+                Err(e) => report.failed.push(IndexFailure {
+                    doc_id,
+                    error: e.to_string(),
+                }),
+            }
+        }
+
+        let mut embedded = stream::iter(self.embedding_batches(stale))
+            .map(|batch| self.embed_documents(batch))
+            .buffer_unordered(self.config.index.max_concurrent_batches.max(1));
+        while let Some((embedded_docs, failures)) = embedded.next().await {
+            report.failed.extend(failures);
+            for (doc_id, content, embedding) in embedded_docs {
+                let hash = content_hash(&content);
+                match self.replace_content(&doc_id, content, hash, embedding).await {
+                    Ok(()) => report.updated += 1,
+                    Err(e) => report.failed.push(IndexFailure {
+                        doc_id,
+                        error: e.to_string(),
+                    }),
+                }
+            }
+        }
+
+        info!(
+            "Re-embedded {} stale documents, skipped {}, {} failed",
+            report.updated,
+            report.skipped,
+            report.failed.len()
+        );
+        report
+    }
+
+    /// Hash of the content a document was indexed with.
+    fn stored_hash(&self, doc_id: &DocumentId) -> Result<String> {
+        let doc = self
+            .documents
+            .get(doc_id)
+            .ok_or_else(|| SemanticError::DocumentNotFound(doc_id.clone()))?;
+        Ok(doc
+            .metadata
+            .get(CONTENT_HASH_KEY)
+            .cloned()
+            .unwrap_or_else(|| content_hash(&doc.content)))
+    }
+
+    /// Swap in the new content and embedding of an indexed document.
+    async fn replace_content(
+        &self,
+        doc_id: &DocumentId,
+        content: String,
+        hash: String,
+        embedding: Vector,
+    ) -> Result<()> {
+        self.remove_chunk_vectors(doc_id).await?;
+        let payload = HashMap::from([(CONTENT_HASH_KEY.to_string(), serde_json::json!(hash))]);
+        self.index
+            .insert_with_payload(doc_id.clone(), embedding.clone(), payload)
+            .await?;
+
+        self.invalidate_document_caches(doc_id);
+        self.index_terms(doc_id, &content);
+        if let Some(mut doc) = self.documents.get_mut(doc_id) {
+            doc.content = content;
+            doc.embedding = embedding;
+            doc.model = self.provider.model().clone();
+            doc.metadata.insert(CONTENT_HASH_KEY.to_string(), hash);
+            doc.indexed_at = chrono::Utc::now();
+        }
+        Ok(())
+    }
+
+    /// Split documents into batches of `IndexConfig::max_batch_size`.
+    fn embedding_batches(&self, docs: Vec<(DocumentId, String)>) -> Vec<Vec<(DocumentId, String)>> {
+        let batch_size = self.config.index.max_batch_size.max(1);
+        let mut batches = Vec::with_capacity(docs.len().div_ceil(batch_size));
+        let mut docs = docs.into_iter().peekable();
+        while docs.peek().is_some() {
+            batches.push(docs.by_ref().take(batch_size).collect::<Vec<_>>());
+        }
+        batches
+    }
+
+    /// Embed a batch of documents, falling back to one document at a time
+    /// when the batch fails so one bad document does not sink the others.
+    async fn embed_documents(
+        &self,
+        batch: Vec<(DocumentId, String)>,
+    ) -> (Vec<(DocumentId, String, Vector)>, Vec<IndexFailure>) {
+        let texts: Vec<String> = batch.iter().map(|(_, content)| content.clone()).collect();
+        match self.generate_embeddings_batch(&texts).await {
+            Ok(embeddings) if embeddings.len() == batch.len() => {
+                let embedded = batch
+                    .into_iter()
+                    .zip(embeddings)
+                    .map(|((doc_id, content), embedding)| (doc_id, content, embedding))
+                    .collect();
+                return (embedded, Vec::new());
+            }
+            Ok(embeddings) => warn!(
+                "Embedding batch returned {} vectors for {} documents, retrying one by one",
+                embeddings.len(),
+                batch.len()
+            ),
+            Err(e) => warn!("Embedding batch of {} documents failed, retrying one by one: {}", batch.len(), e),
+        }
+
+        let mut embedded = Vec::with_capacity(batch.len());
+        let mut failures = Vec::new();
+        for (doc_id, content) in batch {
+            match self.generate_embedding(&content, self.document_embedding_cache()).await {
+                Ok(embedding) => embedded.push((doc_id, content, embedding)),
+                Err(e) => failures.push(IndexFailure {
+                    doc_id,
+                    error: e.to_string(),
+                }),
+            }
+        }
+        (embedded, failures)
+    }
+
+    /// Upsert embedded documents into the index in one call, then record
+    /// them. Returns their ids, or a failure for each if the upsert failed.
+    async fn store_embedded(
+        &self,
+        embedded: Vec<(DocumentId, String, Vector)>,
+    ) -> std::result::Result<Vec<DocumentId>, Vec<IndexFailure>> {
+        let fail_all = |embedded: &[(DocumentId, String, Vector)], error: &SemanticError| {
+            embedded
+                .iter()
+                .map(|(doc_id, _, _)| IndexFailure {
+                    doc_id: doc_id.clone(),
+                    error: error.to_string(),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // Previous versions may be cached, or indexed as chunks
+        for (doc_id, _, _) in &embedded {
+            if self.documents.contains_key(doc_id) {
+                self.invalidate_document_caches(doc_id);
+                if let Err(e) = self.remove_chunk_vectors(doc_id).await {
+                    return Err(fail_all(&embedded, &e));
+                }
+            }
+        }
+
+        let items = embedded
+            .iter()
+            .map(|(doc_id, _, embedding)| (doc_id.clone(), embedding.clone()))
+            .collect();
+        if let Err(e) = self.index.insert_batch(items).await {
+            return Err(fail_all(&embedded, &e));
+        }
+
+        let model = self.provider.model().clone();
+        let ids = embedded
+            .into_iter()
+            .map(|(doc_id, content, embedding)| {
+                self.index_terms(&doc_id, &content);
+                self.documents.insert(
+                    doc_id.clone(),
+                    IndexedDocument {
+                        id: doc_id.clone(),
+                        entity_type: EntityType::Document,
+                        content,
+                        embedding,
+                        model: model.clone(),
+                        metadata: HashMap::new(),
+                        indexed_at: chrono::Utc::now(),
+                    },
+                );
+                doc_id
+            })
+            .collect();
+        Ok(ids)
+    }
+
+    /// Index a document in a namespace.
+    ///
+    /// The namespace is stored in the `namespace` metadata key and enforced
+    /// by `search_as`.
+    pub async fn index_document_in(
+        &self,
+        namespace: &str,
+        doc_id: DocumentId,
+        content: String,
+        entity_type: EntityType,
+        mut metadata: HashMap<String, String>,
+    ) -> Result<()> {
+        metadata.insert("namespace".to_string(), namespace.to_string());
+        self.index_document(doc_id, content, entity_type, metadata).await
+    }
+
     /// Search for documents.
     pub async fn search(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>> {
         self.search_with_filter(query, limit, SearchFilter::default())
@@ -246,46 +846,175 @@ impl SemanticSearchEngine {
         query: &str,
         limit: usize,
         filter: SearchFilter,
+    ) -> Result<Vec<SearchResult>> {
+        let (_, _, rule) = self.intent_rule(query);
+        let expand = rule
+            .and_then(|rule| rule.query_expansion)
+            .unwrap_or(self.config.search.enable_query_expansion);
+        let expansion = if expand {
+            ExpansionOptions::default()
+        } else {
+            ExpansionOptions::disabled()
+        };
+        self.search_with_options(query, limit, filter, &expansion)
+            .await
+    }
+
+    /// Search with filters and per-call query expansion options.
+    pub async fn search_with_options(
+        &self,
+        query: &str,
+        limit: usize,
+        mut filter: SearchFilter,
+        expansion: &ExpansionOptions,
+    ) -> Result<Vec<SearchResult>> {
+        // The payload is attached afterwards, so it does not split the
+        // query caches
+        let with_payload = std::mem::take(&mut filter.with_payload);
+        let max_payload_bytes = filter.max_payload_bytes.take();
+
+        let mut results = self.run_search(query, limit, filter, expansion).await?;
+        self.mark_ready();
+        if !with_payload.is_empty() {
+            for result in &mut results {
+                self.attach_payload(result, &with_payload, max_payload_bytes);
+            }
+        }
+        Ok(results)
+    }
+
+    /// Fill in the requested payload fields of a result from its document.
+    fn attach_payload(&self, result: &mut SearchResult, fields: &[String], max_bytes: Option<usize>) {
+        let Some(doc) = self.documents.get(&result.id) else {
+            return;
+        };
+        let (payload, truncated) = select_payload(fields, max_bytes, |field| match field {
+            "id" => Some(serde_json::Value::String(doc.id.clone())),
+            "content" => Some(serde_json::Value::String(doc.content.clone())),
+            "entity_type" => serde_json::to_value(doc.entity_type).ok(),
+            "indexed_at" => Some(serde_json::Value::String(doc.indexed_at.to_rfc3339())),
+            key => doc.metadata.get(key).cloned().map(serde_json::Value::String),
+        });
+        result.payload = Some(payload);
+        result.payload_truncated = truncated;
+    }
+
+    async fn run_search(
+        &self,
+        query: &str,
+        limit: usize,
+        mut filter: SearchFilter,
+        expansion: &ExpansionOptions,
     ) -> Result<Vec<SearchResult>> {
         debug!("Searching: {} (limit: {})", query, limit);
 
         // Enforce max limit
         let limit = limit.min(self.config.search.max_limit);
+        if let Some(expr) = &filter.expr {
+            self.check_filter_expr(expr)?;
+        }
+
+        let (intent, branch, rule) = self.intent_rule(query);
+        debug!("Query intent {:?}, policy branch {}", intent, branch.as_str());
+        if let Some(rule) = rule {
+            filter.boost_entity_types.extend(rule.boost_entity_types.iter().copied());
+            filter
+                .boost_metadata
+                .extend(rule.boost_metadata.iter().map(|(k, v)| (k.clone(), v.clone())));
+            if !rule.boost_entity_types.is_empty() || !rule.boost_metadata.is_empty() {
+                filter.boost_factor.get_or_insert(rule.boost_factor);
+            }
+        }
+        let debug_metadata = HashMap::from([
+            (
+                "query_intent".to_string(),
+                serde_json::to_value(intent)?.as_str().unwrap_or_default().to_string(),
+            ),
+            ("intent_policy".to_string(), branch.as_str().to_string()),
+        ]);
+
+        let threshold = filter
+            .min_score
+            .unwrap_or(self.config.search.default_threshold);
+        let filter_key = Self::filter_fingerprint(&filter, expansion);
+        let scope = QueryScope::new(limit, threshold, filter_key.clone());
 
         // Check query cache
         if let Some(query_cache) = &self.query_cache {
-            let cache_key = QueryCacheKey::new(
-                query.to_string(),
-                limit,
-                filter.min_score.unwrap_or(self.config.search.default_threshold),
-            );
+            let cache_key = QueryCacheKey::new(query.to_string(), limit, threshold)
+                .with_filter(filter_key.clone());
 
             if let Some(cached) = query_cache.get(&cache_key).await {
                 debug!("Query cache hit");
-                return self.results_from_cache(&cached).await;
+                let mut results = self
+                    .results_from_cache(&cached, CacheHitType::Exact, &debug_metadata)
+                    .await?;
+                let processed_query = self.query_processor.process_with(query, expansion)?;
+                self.attach_answer(&mut results, &processed_query).await;
+                return Ok(results);
             }
         }
 
         // Process query
-        let processed_query = self.query_processor.process(query)?;
+        let processed_query = self.query_processor.process_with(query, expansion)?;
 
         // Generate query embedding
-        let query_embedding = self.generate_embedding(&processed_query.normalized).await?;
+        let query_embedding = if rule.is_some_and(|rule| rule.use_hyde) {
+            self.hyde
+                .process_query(&processed_query.normalized, Some(intent))
+                .await?
+                .aggregated_embedding
+        } else {
+            self.query_embedding(&processed_query).await?
+        };
+
+        // Check for a cached query that means the same thing
+        if let Some(semantic_cache) = &self.semantic_cache {
+            if let Some((cached, similarity)) = semantic_cache.get(&query_embedding, &scope) {
+                debug!("Semantic query cache hit (similarity: {:.3})", similarity);
+                let mut results = self
+                    .results_from_cache(&cached, CacheHitType::Semantic, &debug_metadata)
+                    .await?;
+                self.attach_answer(&mut results, &processed_query).await;
+                return Ok(results);
+            }
+        }
 
         // Search in index
-        let mut index_results = self.index.search(&query_embedding, limit * 2).await?;
+        let candidates = if self.chunks.is_empty() {
+            limit * 2
+        } else {
+            limit * 2 * CHUNK_CANDIDATE_FACTOR
+        };
+        let index_results = self.index.search(&query_embedding, candidates).await?;
+        let mut hits = self.aggregate_chunk_hits(index_results);
+
+        // Fuse in keyword matches the dense search may have missed
+        let mut hybrid_scores = HashMap::new();
+        if let Some(sparse) = &self.sparse {
+            (hits, hybrid_scores) = self.fuse_sparse_hits(sparse, hits, query, limit * 2);
+        }
 
         // Apply filters
-        index_results.retain(|result| self.matches_filter(&result.doc_id, &filter));
+        hits.retain(|hit| self.matches_filter(&hit.doc_id, &filter));
 
-        // Convert to rankable documents
-        let rankable_docs: Vec<RankableDocument> = index_results
+        let best_chunks: HashMap<DocumentId, (usize, f32)> = hits
+            .iter()
+            .filter_map(|hit| hit.best_chunk.map(|chunk| (hit.doc_id.clone(), chunk)))
+            .collect();
+
+        // Convert to rankable documents; multi-vector documents are ranked
+        // on their best chunk
+        let rankable_docs: Vec<RankableDocument> = hits
             .into_iter()
-            .filter_map(|result| {
-                self.documents.get(&result.doc_id).map(|doc| RankableDocument {
-                    id: result.doc_id.clone(),
-                    content: doc.content.clone(),
-                    semantic_score: result.score,
+            .filter_map(|hit| {
+                let chunk_text = hit
+                    .best_chunk
+                    .and_then(|(index, _)| self.chunk_text(&hit.doc_id, index));
+                self.documents.get(&hit.doc_id).map(|doc| RankableDocument {
+                    id: hit.doc_id.clone(),
+                    content: chunk_text.unwrap_or_else(|| doc.content.clone()),
+                    semantic_score: hit.score,
                     metadata: doc.metadata.clone(),
                     embedding: Some(doc.embedding.clone()),
                 })
@@ -293,12 +1022,21 @@ impl SemanticSearchEngine {
             .collect();
 
         // Rank results
-        let ranked_results = if self.config.search.enable_reranking {
-            self.ranker.rank(rankable_docs, &processed_query)
-        } else {
-            rankable_docs
+        let keyword_weight = rule.and_then(|rule| rule.keyword_weight);
+        let mut ranked_results = match (self.config.search.enable_reranking, keyword_weight) {
+            (true, Some(keyword)) => {
+                let weights = ScoringWeights {
+                    semantic: 1.0 - keyword,
+                    keyword,
+                    ..self.ranker.weights().clone()
+                };
+                Ranker::with_weights(RankingStrategy::Hybrid, weights)
+                    .rank(rankable_docs, &processed_query)
+            }
+            (true, None) => self.ranker.rank(rankable_docs, &processed_query),
+            (false, _) => rankable_docs
                 .into_iter()
-                .map(|doc| crate::ranking::RankedResult {
+                .map(|doc| RankedResult {
                     id: doc.id,
                     final_score: doc.semantic_score,
                     semantic_score: doc.semantic_score,
@@ -307,15 +1045,12 @@ impl SemanticSearchEngine {
                     popularity_score: 0.0,
                     explanation: None,
                 })
-                .collect()
+                .collect(),
         };
+        self.apply_boosts(&mut ranked_results, query, rule, &filter);
 
         // Apply score threshold and limit
-        let threshold = filter
-            .min_score
-            .unwrap_or(self.config.search.default_threshold);
-
-        let final_results: Vec<SearchResult> = ranked_results
+        let mut final_results: Vec<SearchResult> = ranked_results
             .into_iter()
             .filter(|r| r.final_score >= threshold)
             .take(limit)
@@ -328,20 +1063,44 @@ impl SemanticSearchEngine {
                     metadata: doc.metadata.clone(),
                     explanation: ranked.explanation,
                     embedding: Some(doc.embedding.clone()),
+                    cache_hit_type: None,
+                    debug_metadata: debug_metadata.clone(),
+                    answer_snippet: None,
+                    best_chunk: best_chunks
+                        .get(&ranked.id)
+                        .and_then(|(index, score)| self.chunk_match(&ranked.id, *index, *score)),
+                    hybrid_scores: hybrid_scores.get(&ranked.id).copied(),
+                    sub_query: None,
+                    payload: None,
+                    payload_truncated: false,
                 })
             })
             .collect();
 
         // Cache results
+        let cached_result = CachedSearchResult {
+            doc_ids: final_results.iter().map(|r| r.id.clone()).collect(),
+            scores: final_results.iter().map(|r| r.score).collect(),
+            best_chunks: final_results
+                .iter()
+                .filter_map(|r| {
+                    let chunk = r.best_chunk.as_ref()?;
+                    Some((r.id.clone(), (chunk.index, chunk.score)))
+                })
+                .collect(),
+        };
+        if let Some(semantic_cache) = &self.semantic_cache {
+            semantic_cache.insert(query_embedding, scope, cached_result.clone());
+        }
         if let Some(query_cache) = &self.query_cache {
-            let cache_key = QueryCacheKey::new(query.to_string(), limit, threshold);
-            let cached_result = CachedSearchResult {
-                doc_ids: final_results.iter().map(|r| r.id.clone()).collect(),
-                scores: final_results.iter().map(|r| r.score).collect(),
-            };
+            let cache_key =
+                QueryCacheKey::new(query.to_string(), limit, threshold).with_filter(filter_key);
             query_cache.insert(cache_key, cached_result).await;
         }
 
+        // Answers are not cached, so cache hits extract them again
+        self.attach_answer(&mut final_results, &processed_query).await;
+
         debug!("Found {} results", final_results.len());
         Ok(final_results)
     }
@@ -352,12 +1111,17 @@ impl SemanticSearchEngine {
 
         // Remove from document store
         self.documents.remove(doc_id);
+        if let Some(sparse) = &self.sparse {
+            sparse.remove(doc_id);
+        }
 
-        // Remove from index
-        self.index.remove(doc_id).await?;
+        // Remove from index, with every chunk of a multi-vector document
+        if !self.remove_chunk_vectors(doc_id).await? {
+            self.index.remove(doc_id).await?;
+        }
 
-        // Invalidate caches
-        self.invalidate_caches().await;
+        // Invalidate cached results that include the document
+        self.invalidate_document_caches(doc_id);
 
         debug!("Document removed successfully: {}", doc_id);
         Ok(())
@@ -369,6 +1133,10 @@ impl SemanticSearchEngine {
 
         // Clear document store
         self.documents.clear();
+        self.chunks.clear();
+        if let Some(sparse) = &self.sparse {
+            sparse.clear();
+        }
 
         // Clear index
         self.index.clear().await?;
@@ -380,9 +1148,296 @@ impl SemanticSearchEngine {
         Ok(())
     }
 
-    /// Get index statistics.
+    /// Write every document and index point to a portable snapshot at
+    /// `path`, see [`crate::snapshot`].
+    pub async fn export_index(&self, path: &Path) -> Result<SnapshotSummary> {
+        info!("Exporting semantic index to {}", path.display());
+
+        let header = SnapshotHeader::new(
+            self.provider.model().clone(),
+            self.config.index.similarity_metric,
+            self.config.qdrant.effective_quantization(),
+        );
+        let mut writer = SnapshotWriter::create(path, &header)?;
+        let mut summary = SnapshotSummary::default();
+
+        for entry in self.documents.iter() {
+            let chunks = self.chunks.get(entry.key()).map(|chunks| chunks.clone());
+            writer.write(&SnapshotRecord::Document {
+                document: entry.value().clone(),
+                chunks,
+            })?;
+            summary.documents += 1;
+        }
+
+        let mut offset = None;
+        loop {
+            let page = self.index.scroll(offset, SNAPSHOT_PAGE_SIZE).await?;
+            for point in page.points {
+                writer.write(&SnapshotRecord::Point(point))?;
+                summary.points += 1;
+            }
+            offset = page.next_offset;
+            if offset.is_none() {
+                break;
+            }
+        }
+        writer.finish()?;
+
+        info!(
+            "Exported {} documents and {} points to {}",
+            summary.documents,
+            summary.points,
+            path.display()
+        );
+        Ok(summary)
+    }
+
+    /// Load a snapshot written by [`export_index`](Self::export_index).
+    ///
+    /// The snapshot must have been embedded with the engine's model and
+    /// similarity metric. Points are written in batches of
+    /// `QdrantConfig::write_batch_size`; when an import fails part way,
+    /// running it again skips the points already written.
+    pub async fn import_index(&self, path: &Path) -> Result<SnapshotSummary> {
+        let reader = SnapshotReader::open(path)?;
+        self.check_snapshot_header(reader.header())?;
+
+        let snapshot_id = reader.header().snapshot_id.clone();
+        let resume_after = ImportProgress::load(path, &snapshot_id)?;
+        let mut progress = ImportProgress {
+            snapshot_id,
+            points_applied: resume_after,
+        };
+        if resume_after > 0 {
+            info!("Resuming import of {} after {} points", path.display(), resume_after);
+        } else {
+            info!("Importing semantic index from {}", path.display());
+        }
+
+        let batch_size = self.config.qdrant.write_batch_size.max(1);
+        let dimension = self.provider.model().dimension;
+        let mut summary = SnapshotSummary {
+            resumed_points: resume_after,
+            ..Default::default()
+        };
+        let mut points_seen = 0;
+        let mut batch = Vec::with_capacity(batch_size);
+
+        for record in reader {
+            match record? {
+                SnapshotRecord::Document { document, chunks } => {
+                    self.restore_document(document, chunks);
+                    summary.documents += 1;
+                }
+                SnapshotRecord::Point(point) => {
+                    points_seen += 1;
+                    if points_seen <= resume_after {
+                        continue;
+                    }
+                    if point.vector.len() != dimension {
+                        return Err(SemanticError::DimensionMismatch {
+                            expected: dimension,
+                            got: point.vector.len(),
+                        });
+                    }
+                    batch.push((point.doc_id, point.vector, point.payload));
+                    if batch.len() >= batch_size {
+                        self.import_batch(path, &mut progress, &mut batch).await?;
+                    }
+                }
+            }
+        }
+        self.import_batch(path, &mut progress, &mut batch).await?;
+        summary.points = (progress.points_applied - resume_after) as usize;
+
+        ImportProgress::remove(path)?;
+        self.invalidate_caches().await;
+
+        info!(
+            "Imported {} documents and {} points from {}",
+            summary.documents,
+            summary.points,
+            path.display()
+        );
+        Ok(summary)
+    }
+
+    /// Reject snapshots whose vectors this engine cannot search.
+    fn check_snapshot_header(&self, header: &SnapshotHeader) -> Result<()> {
+        let model = self.provider.model();
+        if header.embedding_model.dimension != model.dimension {
+            return Err(SemanticError::DimensionMismatch {
+                expected: model.dimension,
+                got: header.embedding_model.dimension,
+            });
+        }
+        if header.embedding_model != *model {
+            return Err(SemanticError::Index(format!(
+                "Snapshot was embedded with {}/{} but the engine uses {}/{}",
+                header.embedding_model.provider,
+                header.embedding_model.model_name,
+                model.provider,
+                model.model_name
+            )));
+        }
+        if header.similarity_metric != self.config.index.similarity_metric {
+            return Err(SemanticError::Index(format!(
+                "Snapshot uses the {:?} metric but the engine uses {:?}",
+                header.similarity_metric, self.config.index.similarity_metric
+            )));
+        }
+        let quantization = self.config.qdrant.effective_quantization();
+        if header.quantization != quantization {
+            // Vectors are exported at full precision, so this only changes
+            // how the collection stores them
+            warn!(
+                "Snapshot was exported from a {:?}-quantized collection, importing into {:?}",
+                header.quantization, quantization
+            );
+        }
+        Ok(())
+    }
+
+    /// Replace a document, with its terms and chunks, from a snapshot.
+    fn restore_document(&self, document: IndexedDocument, chunks: Option<Vec<String>>) {
+        self.index_terms(&document.id, &document.content);
+        match chunks {
+            Some(chunks) => {
+                self.chunks.insert(document.id.clone(), chunks);
+            }
+            None => {
+                self.chunks.remove(&document.id);
+            }
+        }
+        self.documents.insert(document.id.clone(), document);
+    }
+
+    /// Write a batch of imported points and record them in the progress manifest.
+    async fn import_batch(
+        &self,
+        path: &Path,
+        progress: &mut ImportProgress,
+        batch: &mut Vec<(DocumentId, Vector, HashMap<String, serde_json::Value>)>,
+    ) -> Result<()> {
+        if batch.is_empty() {
+            return Ok(());
+        }
+        let written = batch.len() as u64;
+        self.index.insert_batch_with_payloads(std::mem::take(batch)).await?;
+        progress.points_applied += written;
+        progress.save(path)
+    }
+
+    /// Get index statistics. A multi-vector document counts once in
+    /// `total_documents` and once per chunk in `total_vectors`.
     pub async fn stats(&self) -> crate::qdrant::IndexStats {
-        self.index.stats().await
+        crate::qdrant::IndexStats {
+            total_documents: self.documents.len(),
+            embedding_cache: self.embedding_cache_stats(),
+            ..self.index.stats().await
+        }
+    }
+
+    /// Warm the engine up when `config.initialization` is eager.
+    async fn initialized(self) -> Self {
+        if self.config.initialization == InitializationMode::Eager {
+            let report = self.warm_up().await;
+            if let Some((stage, error)) = report.failure() {
+                warn!("Search engine warm-up failed at {}: {}", stage.as_str(), error);
+            }
+        }
+        self
+    }
+
+    /// Load the embedding models, open the vector store connection and run
+    /// a canary embed and search, so that the first real searches are not
+    /// slowed down by them. Stops at the first stage that fails.
+    pub async fn warm_up(&self) -> WarmupReport {
+        *self.readiness.write() = ReadinessStatus::WarmingUp;
+        let start = std::time::Instant::now();
+        let mut stages = Vec::new();
+
+        for stage in [WarmupStage::EmbeddingModel, WarmupStage::VectorStore, WarmupStage::Canary] {
+            let stage_start = std::time::Instant::now();
+            let outcome = self.run_warmup_stage(stage).await;
+            let error = outcome.err().map(|e| e.to_string());
+            debug!("Warm-up stage {} took {:?}", stage.as_str(), stage_start.elapsed());
+            stages.push(StageTiming {
+                stage,
+                duration_ms: stage_start.elapsed().as_secs_f64() * 1000.0,
+                error,
+            });
+            if stages.last().is_some_and(|timing| timing.error.is_some()) {
+                break;
+            }
+        }
+
+        let report = WarmupReport {
+            stages,
+            total_ms: start.elapsed().as_secs_f64() * 1000.0,
+        };
+        *self.readiness.write() = match report.failure() {
+            Some((stage, error)) => ReadinessStatus::Failed {
+                stage,
+                error: error.to_string(),
+            },
+            None => ReadinessStatus::Ready,
+        };
+        info!("Search engine warm-up finished in {:.1}ms (ready: {})", report.total_ms, report.is_ready());
+        report
+    }
+
+    async fn run_warmup_stage(&self, stage: WarmupStage) -> Result<()> {
+        match stage {
+            WarmupStage::EmbeddingModel => {
+                let health = self.provider.check_health().await;
+                if health.iter().any(ProviderHealth::is_available) {
+                    return Ok(());
+                }
+                let errors = health
+                    .iter()
+                    .map(|h| format!("{}: {}", h.provider, h.last_error.as_deref().unwrap_or("unavailable")))
+                    .collect::<Vec<_>>();
+                Err(SemanticError::Provider(format!(
+                    "No embedding provider is available: {}",
+                    errors.join("; ")
+                )))
+            }
+            WarmupStage::VectorStore => self.index.scroll(None, 1).await.map(|_| ()),
+            WarmupStage::Canary => {
+                let embedding = self.generate_embedding(WARMUP_CANARY, None).await?;
+                self.index.search(&embedding, 1).await.map(|_| ())
+            }
+        }
+    }
+
+    /// Whether the engine is warmed up and ready to serve searches quickly.
+    pub fn readiness(&self) -> ReadinessStatus {
+        self.readiness.read().clone()
+    }
+
+    /// A successful search shows the engine is ready, warmed up or not.
+    fn mark_ready(&self) {
+        if matches!(*self.readiness.read(), ReadinessStatus::Cold | ReadinessStatus::Failed { .. }) {
+            let mut readiness = self.readiness.write();
+            if !matches!(*readiness, ReadinessStatus::WarmingUp) {
+                *readiness = ReadinessStatus::Ready;
+            }
+        }
+    }
+
+    /// Hits, misses and size of the embedding cache, if enabled.
+    pub fn embedding_cache_stats(&self) -> Option<EmbeddingCacheStats> {
+        self.embedding_cache.as_ref().map(EmbeddingCache::stats)
+    }
+
+    /// Drop cached embeddings of a model, so that switching back to it
+    /// does not serve vectors from before. Returns how many were dropped.
+    pub fn invalidate_embedding_model(&self, model_name: &str) -> usize {
+        self.embedding_cache
+            .as_ref()
+            .map_or(0, |cache| cache.invalidate_model(model_name))
     }
 
     /// Index a document with agent context.
@@ -396,9 +1451,13 @@ impl SemanticSearchEngine {
     ) -> Result<()> {
         // Add agent metadata
         metadata.insert("agent_id".to_string(), agent_id.to_string());
-        metadata.insert("namespace".to_string(), format!("agent::{}", agent_id));
 
-        self.index_document(doc_id, content, entity_type, metadata).await
+        let namespace = agent_namespace(agent_id);
+        attribute_usage_to(
+            agent_id.to_string(),
+            self.index_document_in(&namespace, doc_id, content, entity_type, metadata),
+        )
+        .await
     }
 
     /// Search with agent context and namespace filtering.
@@ -423,7 +1482,92 @@ impl SemanticSearchEngine {
             }
         };
 
-        self.search_with_filter(query, limit, filter).await
+        attribute_usage_to(agent_id.to_string(), self.search_with_filter(query, limit, filter))
+            .await
+    }
+
+    /// Search on behalf of an agent, returning only documents in namespaces
+    /// the agent owns or was granted access to in `coordinator`.
+    ///
+    /// Grants are looked up on every call, so revocations apply to the next
+    /// query.
+    pub async fn search_as(
+        &self,
+        agent_id: &AgentId,
+        query: &str,
+        limit: usize,
+        mut filter: SearchFilter,
+        coordinator: &AgentCoordinator,
+    ) -> Result<Vec<SearchResult>> {
+        if let Some(readable) = coordinator.readable_namespaces(agent_id).await {
+            filter.namespaces = Some(match filter.namespaces.take() {
+                Some(requested) => requested.intersection(&readable).cloned().collect(),
+                None => readable,
+            });
+        }
+        attribute_usage_to(agent_id.clone(), self.search_with_filter(query, limit, filter)).await
+    }
+
+    /// Index a document into the agent's namespace, counted against the
+    /// namespace's quota in `coordinator`.
+    ///
+    /// Fails with `SemanticError::QuotaExceeded` before embedding anything
+    /// when the document would exceed the quota. A document is counted as
+    /// one vector of the engine's dimension.
+    pub async fn index_document_as(
+        &self,
+        agent_id: &AgentId,
+        doc_id: DocumentId,
+        content: String,
+        entity_type: EntityType,
+        metadata: HashMap<String, String>,
+        coordinator: &AgentCoordinator,
+    ) -> Result<()> {
+        let namespace = agent_namespace(agent_id);
+        let vector_bytes = (self.provider.dimension() * std::mem::size_of::<f32>()) as u64;
+        let replaced = coordinator.reserve_index(agent_id, &namespace, &doc_id, vector_bytes)?;
+
+        let result = self
+            .index_document_for_agent(agent_id, doc_id.clone(), content, entity_type, metadata)
+            .await;
+        if result.is_err() && !replaced {
+            coordinator.release_index(&namespace, &doc_id);
+        }
+        result
+    }
+
+    /// Remove a document the agent indexed, releasing its share of the
+    /// namespace's quota in `coordinator`.
+    pub async fn remove_document_as(
+        &self,
+        agent_id: &AgentId,
+        doc_id: &DocumentId,
+        coordinator: &AgentCoordinator,
+    ) -> Result<()> {
+        self.remove_document(doc_id).await?;
+        coordinator.release_index(&agent_namespace(agent_id), doc_id);
+        Ok(())
+    }
+
+    /// Embedding model the engine indexes and queries with.
+    pub fn embedding_model(&self) -> EmbeddingModel {
+        self.provider.model().clone()
+    }
+
+    /// Strategy the engine ranks search results with.
+    pub fn ranking_strategy(&self) -> RankingStrategy {
+        self.ranker.strategy()
+    }
+
+    /// Tokens billed by the embedding providers so far.
+    pub fn embedding_usage(&self) -> EmbeddingUsage {
+        self.provider.usage_stats()
+    }
+
+    /// Report the usage of every embedded batch to `callback`, e.g.
+    /// `AgentCoordinator::embedding_usage_callback`.
+    pub fn set_embedding_usage_callback(&self, callback: UsageCallback) {
+        self.provider.set_usage_callback(callback);
     }
 
     /// Get document count.
@@ -441,35 +1585,203 @@ impl SemanticSearchEngine {
         self.index.optimize().await
     }
 
-    /// Generate embedding for text with caching.
-    async fn generate_embedding(&self, text: &str) -> Result<Vector> {
-        // Check cache
-        if let Some(cache) = &self.embedding_cache {
-            let cache_key = EmbeddingCacheKey::new(
-                text.to_string(),
-                self.provider.model().model_name.clone(),
-            );
+    /// Embedding cache for documents, unless disabled for them.
+    fn document_embedding_cache(&self) -> Option<&EmbeddingCache> {
+        self.embedding_cache
+            .as_ref()
+            .filter(|_| self.config.index.cache_document_embeddings)
+    }
 
-            if let Some(cached) = cache.get(&cache_key).await {
-                debug!("Embedding cache hit");
-                return Ok((*cached).clone());
-            }
+    /// Embedding cache for queries, unless disabled for them.
+    fn query_embedding_cache(&self) -> Option<&EmbeddingCache> {
+        self.embedding_cache
+            .as_ref()
+            .filter(|_| self.config.search.cache_query_embeddings)
+    }
 
-            // Generate and cache
-            let embedding = self.provider.embed(text).await?;
-            cache.insert(cache_key, embedding.clone()).await;
-            return Ok(embedding);
+    fn embedding_cache_key(&self, text: &str) -> EmbeddingCacheKey {
+        let model = self.provider.model();
+        EmbeddingCacheKey::new(&model.provider, &model.model_name, text)
+    }
+
+    /// Generate embedding for text, through `cache` if given. Retryable
+    /// provider failures are retried per `config.retry`.
+    async fn generate_embedding(&self, text: &str, cache: Option<&EmbeddingCache>) -> Result<Vector> {
+        let embed = || self.config.retry.run("Embedding", || self.provider.embed(text));
+        let Some(cache) = cache else {
+            return embed().await;
+        };
+
+        let cache_key = self.embedding_cache_key(text);
+        if let Some(cached) = cache.get(&cache_key) {
+            debug!("Embedding cache hit");
+            return Ok((*cached).clone());
         }
 
-        // No cache, generate directly
-        self.provider.embed(text).await
+        let embedding = embed().await?;
+        cache.insert(cache_key, embedding.clone());
+        Ok(embedding)
     }
 
-    /// Generate embeddings for multiple texts.
+    /// Generate embeddings for multiple documents, embedding only those
+    /// missing from the cache.
     async fn generate_embeddings_batch(&self, texts: &[String]) -> Result<Vec<Vector>> {
-        // For now, use batch API without caching
-        // In production, implement smart caching for batch operations
-        self.provider.embed_batch(texts).await
+        let Some(cache) = self.document_embedding_cache() else {
+            return self.embed_batch_with_retry(texts).await;
+        };
+
+        let keys: Vec<EmbeddingCacheKey> = texts.iter().map(|text| self.embedding_cache_key(text)).collect();
+        let mut embeddings: Vec<Option<Vector>> = keys
+            .iter()
+            .map(|key| cache.get(key).map(|cached| (*cached).clone()))
+            .collect();
+        let missing: Vec<usize> = (0..texts.len()).filter(|&i| embeddings[i].is_none()).collect();
+        if missing.is_empty() {
+            return Ok(embeddings.into_iter().flatten().collect());
+        }
+
+        let missing_texts: Vec<String> = missing.iter().map(|&i| texts[i].clone()).collect();
+        let generated = self.embed_batch_with_retry(&missing_texts).await?;
+        if generated.len() != missing_texts.len() {
+            return Err(SemanticError::Embedding(format!(
+                "Embedding batch returned {} vectors for {} texts",
+                generated.len(),
+                missing_texts.len()
+            )));
+        }
+        for (i, embedding) in missing.into_iter().zip(generated) {
+            cache.insert(keys[i].clone(), embedding.clone());
+            embeddings[i] = Some(embedding);
+        }
+        Ok(embeddings.into_iter().flatten().collect())
+    }
+
+    async fn embed_batch_with_retry(&self, texts: &[String]) -> Result<Vec<Vector>> {
+        self.config
+            .retry
+            .run("Batch embedding", || self.provider.embed_batch(texts))
+            .await
+    }
+
+    /// Vector id of a chunk of a multi-vector document.
+    fn chunk_vector_id(doc_id: &str, index: usize) -> DocumentId {
+        format!("{}#chunk-{}", doc_id, index)
+    }
+
+    /// Remove the chunk vectors of a multi-vector document. Returns whether
+    /// the document had chunks.
+    async fn remove_chunk_vectors(&self, doc_id: &DocumentId) -> Result<bool> {
+        let Some((_, chunks)) = self.chunks.remove(doc_id) else {
+            return Ok(false);
+        };
+
+        let vector_ids = (0..chunks.len())
+            .map(|index| Self::chunk_vector_id(doc_id, index))
+            .collect();
+        self.index.remove_batch(vector_ids).await?;
+        Ok(true)
+    }
+
+    fn chunk_text(&self, doc_id: &DocumentId, index: usize) -> Option<String> {
+        self.chunks.get(doc_id)?.get(index).cloned()
+    }
+
+    fn chunk_match(&self, doc_id: &DocumentId, index: usize, score: f32) -> Option<ChunkMatch> {
+        self.chunk_text(doc_id, index)
+            .map(|text| ChunkMatch { index, text, score })
+    }
+
+    /// Resolve index hits to documents: chunk hits are grouped by their
+    /// parent document and scored with the configured aggregation. Returns
+    /// hits in descending score order.
+    fn aggregate_chunk_hits(&self, results: Vec<IndexSearchResult>) -> Vec<DocumentHit> {
+        let mut hits = Vec::new();
+        let mut chunk_scores: HashMap<DocumentId, Vec<(usize, f32)>> = HashMap::new();
+
+        for result in results {
+            let parent = result.payload.get(PARENT_ID_KEY).and_then(|v| v.as_str());
+            let index = result.payload.get(CHUNK_INDEX_KEY).and_then(|v| v.as_u64());
+            match (parent, index) {
+                (Some(parent), Some(index)) => chunk_scores
+                    .entry(parent.to_string())
+                    .or_default()
+                    .push((index as usize, result.score)),
+                _ => hits.push(DocumentHit {
+                    doc_id: result.doc_id,
+                    score: result.score,
+                    best_chunk: None,
+                }),
+            }
+        }
+
+        let aggregation = self.config.search.chunk_aggregation;
+        hits.extend(chunk_scores.into_iter().map(|(doc_id, scores)| DocumentHit {
+            score: aggregation.score(&scores),
+            best_chunk: scores.iter().copied().max_by(|a, b| a.1.total_cmp(&b.1)),
+            doc_id,
+        }));
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+        hits
+    }
+
+    /// Record the terms of a document for sparse search.
+    fn index_terms(&self, doc_id: &DocumentId, content: &str) {
+        if let Some(sparse) = &self.sparse {
+            sparse.insert(doc_id.clone(), content);
+        }
+    }
+
+    /// Merge dense hits with the `candidates` best sparse matches of the
+    /// query, scoring each document with `HybridConfig::fusion`. Returns the
+    /// fused hits, best first, and the scores behind each.
+    fn fuse_sparse_hits(
+        &self,
+        sparse: &SparseIndex,
+        dense: Vec<DocumentHit>,
+        query: &str,
+        candidates: usize,
+    ) -> (Vec<DocumentHit>, HashMap<DocumentId, HybridScores>) {
+        let sparse_hits = sparse.search(&sparse.encode_query(query), candidates);
+        let max_sparse = sparse_hits.first().map_or(0.0, |(_, score)| *score);
+        let mut sparse_ranks: HashMap<DocumentId, (usize, f32)> = sparse_hits
+            .into_iter()
+            .enumerate()
+            .map(|(rank, (doc_id, score))| (doc_id, (rank, score)))
+            .collect();
+
+        let fusion = self.config.search.hybrid.fusion;
+        let mut scores = HashMap::new();
+        let mut fuse = |doc_id: &DocumentId, dense: Option<(usize, f32)>, sparse: Option<(usize, f32)>| {
+            let fused = fusion.fuse(dense, sparse, max_sparse);
+            scores.insert(
+                doc_id.clone(),
+                HybridScores {
+                    dense: dense.map(|(_, score)| score),
+                    sparse: sparse.map(|(_, score)| score),
+                    fused,
+                },
+            );
+            fused
+        };
+
+        let mut hits: Vec<DocumentHit> = dense
+            .into_iter()
+            .enumerate()
+            .map(|(rank, hit)| {
+                let sparse = sparse_ranks.remove(&hit.doc_id);
+                DocumentHit {
+                    score: fuse(&hit.doc_id, Some((rank, hit.score)), sparse),
+                    ..hit
+                }
+            })
+            .collect();
+        hits.extend(sparse_ranks.into_iter().map(|(doc_id, sparse)| DocumentHit {
+            score: fuse(&doc_id, None, Some(sparse)),
+            doc_id,
+            best_chunk: None,
+        }));
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+        (hits, scores)
     }
 
     /// Check if a document matches the filter.
@@ -488,25 +1800,234 @@ impl SemanticSearchEngine {
                     return false;
                 }
             }
-
-            true
-        } else {
-            false
+
+            // Check namespace
+            let namespace = doc.metadata.get("namespace");
+            if let (Some(allowed), Some(namespace)) = (&filter.namespaces, namespace) {
+                if !allowed.contains(namespace) {
+                    return false;
+                }
+            }
+
+            // Check filter expression
+            if let Some(expr) = &filter.expr {
+                let lookup = |field: &str| match doc.metadata.get(field) {
+                    Some(value) => Some(serde_json::Value::String(value.clone())),
+                    None if field == "id" => Some(serde_json::Value::String(doc.id.clone())),
+                    None if field == "entity_type" => serde_json::to_value(doc.entity_type).ok(),
+                    None => None,
+                };
+                if !expr.matches(&lookup) {
+                    return false;
+                }
+            }
+
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Reject malformed filter expressions, and fields no indexed document
+    /// has, which would silently match nothing.
+    fn check_filter_expr(&self, expr: &FilterExpr) -> Result<()> {
+        expr.validate()?;
+        if self.documents.is_empty() {
+            return Ok(());
+        }
+        for field in expr.fields() {
+            let known = DOCUMENT_FILTER_FIELDS.contains(&field)
+                || self
+                    .documents
+                    .iter()
+                    .any(|doc| doc.metadata.contains_key(field));
+            if !known {
+                return Err(SemanticError::InvalidFilter(format!(
+                    "No indexed document has field `{}`",
+                    field
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Invalidate all caches.
+    async fn invalidate_caches(&self) {
+        if let Some(cache) = &self.embedding_cache {
+            cache.clear();
+        }
+        if let Some(cache) = &self.query_cache {
+            cache.clear().await;
+        }
+        if let Some(cache) = &self.semantic_cache {
+            cache.clear();
+        }
+    }
+
+    /// Invalidate cached search results that include a document.
+    fn invalidate_document_caches(&self, doc_id: &str) {
+        if let Some(cache) = &self.query_cache {
+            cache.invalidate_document(doc_id);
+        }
+        if let Some(cache) = &self.semantic_cache {
+            cache.invalidate_document(doc_id);
+        }
+    }
+
+    fn build_embedding_cache(config: &SemanticConfig) -> Option<EmbeddingCache> {
+        config.cache.enable_embedding_cache.then(|| {
+            EmbeddingCache::new(
+                config.cache.embedding_cache_size,
+                config.cache.embedding_cache_max_bytes,
+                Duration::from_secs(config.cache.embedding_cache_ttl_seconds),
+            )
+        })
+    }
+
+    fn build_semantic_cache(config: &SemanticConfig) -> Option<SemanticQueryCache> {
+        (config.cache.enable_query_cache && config.search.semantic_cache_max_entries > 0).then(|| {
+            SemanticQueryCache::new(
+                config.search.semantic_cache_threshold,
+                config.search.semantic_cache_max_entries,
+                Duration::from_secs(config.cache.query_cache_ttl_seconds),
+            )
+        })
+    }
+
+    fn build_query_processor(config: &SemanticConfig) -> Result<QueryProcessor> {
+        let mut dictionary = DomainDictionary::programming();
+        if let Some(path) = &config.search.query_dictionary_path {
+            info!("Loading query dictionary from {:?}", path);
+            dictionary.merge(DomainDictionary::load(path)?);
+        }
+        Ok(QueryProcessor::with_expander(QueryExpander::with_dictionary(dictionary)))
+    }
+
+    /// Filters and expansion options as a stable string, so cached results
+    /// are only reused under the same filters.
+    fn filter_fingerprint(filter: &SearchFilter, expansion: &ExpansionOptions) -> String {
+        serde_json::json!({ "filter": filter, "expansion": expansion }).to_string()
+    }
+
+    /// Embedding of the query, pulled towards its expansion terms by their
+    /// weights. The query itself has weight 1.0.
+    async fn query_embedding(&self, query: &ProcessedQuery) -> Result<Vector> {
+        let mut embedding = self.generate_embedding(&query.normalized, self.query_embedding_cache()).await?;
+        if query.weighted_terms.is_empty() {
+            return Ok(embedding);
+        }
+
+        let mut total_weight = 1.0;
+        for term in &query.weighted_terms {
+            let term_embedding = self.generate_embedding(&term.term, self.query_embedding_cache()).await?;
+            if term_embedding.len() != embedding.len() {
+                continue;
+            }
+            for (value, term_value) in embedding.iter_mut().zip(&term_embedding) {
+                *value += term.weight * term_value;
+            }
+            total_weight += term.weight;
+        }
+
+        for value in &mut embedding {
+            *value /= total_weight;
+        }
+        Ok(embedding)
+    }
+
+    /// Attach a direct answer to the result it was taken from, if the query
+    /// expects a fact. A failed extraction leaves the results as they are.
+    async fn attach_answer(&self, results: &mut [SearchResult], query: &ProcessedQuery) {
+        if results.is_empty() || !self.answers.applies_to(query.answer_type) {
+            return;
+        }
+
+        let answer = async {
+            let query_embedding = self.generate_embedding(&query.normalized, self.query_embedding_cache()).await?;
+            let documents: Vec<(&DocumentId, &str)> =
+                results.iter().map(|r| (&r.id, r.content.as_str())).collect();
+            self.answers
+                .extract(self.provider.as_ref(), &query_embedding, &documents)
+                .await
+        }
+        .await;
+
+        match answer {
+            Ok(Some(answer)) => {
+                if let Some(result) = results.iter_mut().find(|r| r.id == answer.doc_id) {
+                    result.answer_snippet = Some(answer);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => warn!("Answer extraction failed: {}", e),
         }
     }
 
-    /// Invalidate all caches.
-    async fn invalidate_caches(&self) {
-        if let Some(cache) = &self.embedding_cache {
-            cache.clear().await;
+    /// Classify a query and look up the intent policy rule for it.
+    fn intent_rule(&self, query: &str) -> (QueryIntent, IntentBranch, Option<&IntentRule>) {
+        let intent = self.query_processor.classify(query);
+        let (branch, rule) = self.config.search.intent_policy.rule_for(intent);
+        (intent, branch, rule)
+    }
+
+    /// Raise the scores of exact name matches and of boosted entity types
+    /// and metadata, then restore score order.
+    fn apply_boosts(
+        &self,
+        results: &mut [RankedResult],
+        query: &str,
+        rule: Option<&IntentRule>,
+        filter: &SearchFilter,
+    ) {
+        let exact_boost = rule.map_or(0.0, |rule| rule.exact_match_boost);
+        let factor = filter.boost_factor.unwrap_or(1.0);
+        let boosts_types_or_metadata =
+            !filter.boost_entity_types.is_empty() || !filter.boost_metadata.is_empty();
+        if exact_boost == 0.0 && (factor == 1.0 || !boosts_types_or_metadata) {
+            return;
         }
-        if let Some(cache) = &self.query_cache {
-            cache.clear().await;
+
+        let name = Self::queried_name(query);
+        for result in results.iter_mut() {
+            let Some(doc) = self.documents.get(&result.id) else {
+                continue;
+            };
+            if exact_boost != 0.0
+                && !name.is_empty()
+                && (doc.content.contains(name) || doc.metadata.values().any(|v| v.contains(name)))
+            {
+                result.final_score += exact_boost;
+            }
+            if filter.boost_entity_types.contains(&doc.entity_type)
+                || filter
+                    .boost_metadata
+                    .iter()
+                    .any(|(key, value)| doc.metadata.get(key) == Some(value))
+            {
+                result.final_score *= factor;
+            }
         }
+        results.sort_by(|a, b| b.final_score.total_cmp(&a.final_score));
+    }
+
+    /// The symbol or file name of a navigational query: its last word
+    /// without quotes or call parentheses.
+    fn queried_name(query: &str) -> &str {
+        query
+            .split_whitespace()
+            .last()
+            .unwrap_or_default()
+            .trim_matches(|c| matches!(c, '`' | '"' | '\''))
+            .trim_end_matches("()")
     }
 
     /// Reconstruct results from cache.
-    async fn results_from_cache(&self, cached: &CachedSearchResult) -> Result<Vec<SearchResult>> {
+    async fn results_from_cache(
+        &self,
+        cached: &CachedSearchResult,
+        hit_type: CacheHitType,
+        debug_metadata: &HashMap<String, String>,
+    ) -> Result<Vec<SearchResult>> {
         let results = cached
             .doc_ids
             .iter()
@@ -520,6 +2041,17 @@ impl SemanticSearchEngine {
                     metadata: doc.metadata.clone(),
                     explanation: None,
                     embedding: Some(doc.embedding.clone()),
+                    cache_hit_type: Some(hit_type),
+                    debug_metadata: debug_metadata.clone(),
+                    answer_snippet: None,
+                    best_chunk: cached
+                        .best_chunks
+                        .get(doc_id)
+                        .and_then(|(index, score)| self.chunk_match(doc_id, *index, *score)),
+                    hybrid_scores: None,
+                    sub_query: None,
+                    payload: None,
+                    payload_truncated: false,
                 })
             })
             .collect();
@@ -531,6 +2063,8 @@ impl SemanticSearchEngine {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::{HybridConfig, HybridFusion};
+    use crate::providers::MockProvider;
     use crate::qdrant::MockVectorStore;
     use crate::types::SimilarityMetric;
 
@@ -566,6 +2100,24 @@ mod tests {
             .unwrap()
     }
 
+    #[tokio::test]
+    async fn test_vector_store_dimension_must_match_provider() {
+        let mut config = SemanticConfig::default();
+        config.embedding.primary_provider = "mock".to_string();
+        config.embedding.fallback_providers = vec![];
+
+        let store: Arc<dyn VectorIndex> = Arc::new(MockVectorStore::new(256, SimilarityMetric::Cosine));
+        let result = SemanticSearchEngine::with_vector_store(config.clone(), store).await;
+        assert!(matches!(
+            result,
+            Err(SemanticError::DimensionMismatch { expected: 256, got: 384 })
+        ));
+
+        config.vector_store.dimension = Some(1536);
+        let store: Arc<dyn VectorIndex> = Arc::new(MockVectorStore::new(384, SimilarityMetric::Cosine));
+        assert!(SemanticSearchEngine::with_vector_store(config, store).await.is_err());
+    }
+
     // Integration tests - require Qdrant server running
     #[tokio::test]
     async fn test_index_and_search() {
@@ -812,6 +2364,67 @@ mod tests {
         assert_eq!(engine.document_count().await, 0);
     }
 
+    #[tokio::test]
+    async fn test_intent_policy_shapes_retrieval() {
+        let engine = create_test_engine_with_mock(384).await;
+        let any_score = SearchFilter {
+            min_score: Some(-1.0),
+            ..Default::default()
+        };
+
+        engine
+            .index_document(
+                "exact".to_string(),
+                "fn parse_config(path: &Path) -> Config".to_string(),
+                EntityType::Code,
+                HashMap::new(),
+            )
+            .await
+            .unwrap();
+        engine
+            .index_document(
+                "other".to_string(),
+                "fn load_settings(path: &Path) -> Settings".to_string(),
+                EntityType::Code,
+                HashMap::new(),
+            )
+            .await
+            .unwrap();
+
+        let results = engine
+            .search_with_filter("parse_config", 10, any_score.clone())
+            .await
+            .unwrap();
+        assert_eq!(results[0].id, "exact");
+        assert_eq!(results[0].debug_metadata["query_intent"], "navigational");
+        assert_eq!(results[0].debug_metadata["intent_policy"], "navigational");
+
+        // Same content, so only the procedural boost tells them apart
+        let steps = "how do i deploy the indexing service";
+        let procedural = HashMap::from([("memory_type".to_string(), "procedural".to_string())]);
+        for (id, metadata) in [("episode", HashMap::new()), ("procedure", procedural)] {
+            engine
+                .index_document(id.to_string(), steps.to_string(), EntityType::Episode, metadata)
+                .await
+                .unwrap();
+        }
+
+        let results = engine
+            .search_with_filter("How do I deploy the indexing service", 10, any_score.clone())
+            .await
+            .unwrap();
+        assert_eq!(results[0].id, "procedure");
+        assert_eq!(results[0].debug_metadata["query_intent"], "procedural");
+        assert_eq!(results[0].debug_metadata["intent_policy"], "procedural");
+
+        let results = engine
+            .search_with_filter("why are settings loaded lazily", 10, any_score)
+            .await
+            .unwrap();
+        assert!(!results.is_empty());
+        assert!(results.iter().all(|r| r.debug_metadata["intent_policy"] == "exploratory"));
+    }
+
     #[tokio::test]
     async fn test_mock_search_with_filter() {
         let engine = create_test_engine_with_mock(384).await;
@@ -965,6 +2578,48 @@ mod tests {
         assert_eq!(results[0].id, "docA");
     }
 
+    #[tokio::test]
+    async fn test_mock_search_returns_requested_payload() {
+        let engine = create_test_engine_with_mock(384).await;
+
+        let mut metadata = HashMap::new();
+        metadata.insert("path".to_string(), "src/parse.rs".to_string());
+        let content = "fn parse(input: &str) -> Ast { todo!() }".repeat(10);
+        engine
+            .index_document("parse".to_string(), content.clone(), EntityType::Code, metadata)
+            .await
+            .unwrap();
+
+        let filter = SearchFilter {
+            min_score: Some(-1.0),
+            ..Default::default()
+        };
+        let results = engine.search_with_filter("parse", 1, filter.clone()).await.unwrap();
+        assert_eq!(results[0].payload, None);
+
+        let full = SearchFilter {
+            with_payload: vec!["path".to_string(), "content".to_string()],
+            ..filter.clone()
+        };
+        let results = engine.search_with_filter("parse", 1, full).await.unwrap();
+        let payload = results[0].payload.as_ref().unwrap();
+        assert_eq!(payload["path"], "src/parse.rs");
+        assert_eq!(payload["content"], content.as_str());
+        assert!(!results[0].payload_truncated);
+
+        let capped = SearchFilter {
+            with_payload: vec!["path".to_string(), "content".to_string()],
+            max_payload_bytes: Some(64),
+            ..filter
+        };
+        let results = engine.search_with_filter("parse", 1, capped).await.unwrap();
+        let payload = results[0].payload.as_ref().unwrap();
+        assert_eq!(payload["path"], "src/parse.rs");
+        // "path" and its value take 16 of the 64 bytes, "content" the rest
+        assert_eq!(payload["content"], &content[..41]);
+        assert!(results[0].payload_truncated);
+    }
+
     #[tokio::test]
     async fn test_mock_stats() {
         let engine = create_test_engine_with_mock(384).await;
@@ -985,4 +2640,627 @@ mod tests {
         assert_eq!(stats.dimension, 384);
         assert_eq!(stats.collection_status, "Green");
     }
+
+    #[tokio::test]
+    async fn test_mock_index_documents_in_batches() {
+        let mut config = SemanticConfig::default();
+        config.embedding.primary_provider = "mock".to_string();
+        config.embedding.fallback_providers = vec![];
+        config.index.max_batch_size = 2;
+        config.index.max_concurrent_batches = 2;
+        let store: Arc<dyn VectorIndex> = Arc::new(MockVectorStore::new(384, SimilarityMetric::Cosine));
+        let engine = SemanticSearchEngine::with_vector_store(config, store).await.unwrap();
+
+        let docs: Vec<_> = (0..5)
+            .map(|i| (format!("doc{}", i), format!("Document number {} about indexing", i)))
+            .collect();
+        let reports = std::sync::Mutex::new(Vec::new());
+        let report = engine
+            .index_documents_with_progress(docs, |progress| reports.lock().unwrap().push(progress))
+            .await;
+
+        assert!(report.is_complete());
+        assert_eq!(report.succeeded.len(), 5);
+        assert_eq!(engine.document_count().await, 5);
+        assert_eq!(engine.stats().await.total_vectors, 5);
+
+        // One report per batch, ending with everything processed
+        let reports = reports.into_inner().unwrap();
+        assert_eq!(reports.len(), 3);
+        assert!(reports.windows(2).all(|w| w[0].processed < w[1].processed));
+        assert_eq!(
+            reports.last().copied(),
+            Some(IndexProgress { processed: 5, failed: 0, total: 5 })
+        );
+
+        // Re-indexing replaces documents in place
+        let report = engine
+            .index_documents(vec![("doc0".to_string(), "Rewritten".to_string())])
+            .await;
+        assert_eq!(report.succeeded, vec!["doc0".to_string()]);
+        assert_eq!(engine.document_count().await, 5);
+        assert_eq!(engine.documents.get("doc0").unwrap().content, "Rewritten");
+
+        assert!(engine.index_documents(Vec::new()).await.succeeded.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_index_documents_batch_sizes() {
+        let mut config = SemanticConfig::default();
+        config.index.max_batch_size = 2;
+        let mock = MockProvider::new(384).with_seed(11).with_recording();
+        let provider = Arc::new(ProviderManager::new(
+            vec![("mock".to_string(), Box::new(mock.clone()) as Box<dyn EmbeddingProvider>)],
+            Default::default(),
+        ));
+        let store: Arc<dyn VectorIndex> = Arc::new(MockVectorStore::new(384, SimilarityMetric::Cosine));
+        let engine = SemanticSearchEngine::with_provider(config, provider, store).await.unwrap();
+
+        let docs: Vec<_> = (0..5)
+            .map(|i| (format!("doc{}", i), format!("Document number {}", i)))
+            .collect();
+        assert!(engine.index_documents(docs).await.is_complete());
+
+        // Batches may finish in any order
+        let mut sizes = mock.batch_sizes();
+        sizes.sort_unstable();
+        assert_eq!(sizes, vec![1, 2, 2]);
+        assert_eq!(mock.embedded_texts().len(), 5);
+    }
+
+    async fn index_snapshot_fixture(engine: &SemanticSearchEngine) {
+        for (id, content) in [
+            ("auth", "User authentication with tokens"),
+            ("db", "Database connection pooling"),
+            ("cache", "Caching query results in memory"),
+        ] {
+            engine
+                .index_document(id.to_string(), content.to_string(), EntityType::Document, HashMap::new())
+                .await
+                .unwrap();
+        }
+        engine
+            .index_document_chunked(
+                "guide".to_string(),
+                vec!["Installation steps".to_string(), "Configuration options".to_string()],
+                EntityType::Document,
+                HashMap::new(),
+            )
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_export_import_index() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("index.ndjson.gz");
+
+        let source = create_test_engine_with_mock(384).await;
+        index_snapshot_fixture(&source).await;
+        let exported = source.export_index(&path).await.unwrap();
+        assert_eq!(exported.documents, 4);
+        // Three documents and two chunk vectors
+        assert_eq!(exported.points, 5);
+
+        let target = create_test_engine_with_mock(384).await;
+        let imported = target.import_index(&path).await.unwrap();
+        assert_eq!((imported.documents, imported.points), (4, 5));
+        assert!(!ImportProgress::manifest_path(&path).exists());
+
+        let stats = target.stats().await;
+        assert_eq!((stats.total_documents, stats.total_vectors), (4, 5));
+
+        let ids = |results: Vec<SearchResult>| results.into_iter().map(|r| r.id).collect::<Vec<_>>();
+        let query = "database connection";
+        assert_eq!(
+            ids(target.search(query, 4).await.unwrap()),
+            ids(source.search(query, 4).await.unwrap())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_import_index_resumes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("index.ndjson.gz");
+
+        let source = create_test_engine_with_mock(384).await;
+        index_snapshot_fixture(&source).await;
+        source.export_index(&path).await.unwrap();
+
+        // An earlier import wrote two points before failing
+        let snapshot_id = SnapshotReader::open(&path).unwrap().header().snapshot_id.clone();
+        ImportProgress {
+            snapshot_id,
+            points_applied: 2,
+        }
+        .save(&path)
+        .unwrap();
+
+        let target = create_test_engine_with_mock(384).await;
+        let imported = target.import_index(&path).await.unwrap();
+        assert_eq!(imported.resumed_points, 2);
+        assert_eq!(imported.points, 3);
+        // Documents live in memory and are always restored
+        assert_eq!(imported.documents, 4);
+        assert_eq!(target.stats().await.total_vectors, 3);
+    }
+
+    #[tokio::test]
+    async fn test_import_index_rejects_other_model() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("index.ndjson.gz");
+        let header = SnapshotHeader::new(
+            EmbeddingModel {
+                provider: "openai".to_string(),
+                model_name: "text-embedding-3-small".to_string(),
+                dimension: 1536,
+            },
+            SimilarityMetric::Cosine,
+            crate::config::QuantizationType::Scalar,
+        );
+        SnapshotWriter::create(&path, &header).unwrap().finish().unwrap();
+
+        let engine = create_test_engine_with_mock(384).await;
+        assert!(matches!(
+            engine.import_index(&path).await,
+            Err(SemanticError::DimensionMismatch { expected: 384, got: 1536 })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_mock_chunked_document() {
+        let engine = create_test_engine_with_mock(384).await;
+        let chunks = vec![
+            "Installation requires a recent toolchain".to_string(),
+            "Machine learning models are trained offline".to_string(),
+            "Results are cached between runs".to_string(),
+        ];
+
+        engine
+            .index_document_chunked("guide".to_string(), chunks.clone(), EntityType::Document, HashMap::new())
+            .await
+            .unwrap();
+        engine
+            .index_document(
+                "note".to_string(),
+                "A short note".to_string(),
+                EntityType::Document,
+                HashMap::new(),
+            )
+            .await
+            .unwrap();
+
+        let stats = engine.stats().await;
+        assert_eq!(stats.total_documents, 2);
+        assert_eq!(stats.total_vectors, 4);
+
+        // Chunk hits collapse into their parent, which cites its best chunk
+        let results = engine.search("machine learning", 10).await.unwrap();
+        let guide: Vec<_> = results.iter().filter(|r| r.id == "guide").collect();
+        assert_eq!(guide.len(), 1);
+        assert!(results.iter().all(|r| r.id == "guide" || r.id == "note"));
+        let best = guide[0].best_chunk.as_ref().unwrap();
+        assert_eq!(best.text, chunks[best.index]);
+        assert!(results.iter().filter(|r| r.id == "note").all(|r| r.best_chunk.is_none()));
+
+        // Re-indexing replaces every chunk vector
+        engine
+            .index_document_chunked("guide".to_string(), chunks[..2].to_vec(), EntityType::Document, HashMap::new())
+            .await
+            .unwrap();
+        assert_eq!(engine.stats().await.total_vectors, 3);
+
+        engine.remove_document(&"guide".to_string()).await.unwrap();
+        let stats = engine.stats().await;
+        assert_eq!(stats.total_documents, 1);
+        assert_eq!(stats.total_vectors, 1);
+
+        let err = engine
+            .index_document_chunked("empty".to_string(), Vec::new(), EntityType::Document, HashMap::new())
+            .await;
+        assert!(err.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_mock_hybrid_search_favours_exact_keyword() {
+        async fn hybrid_engine(alpha: f32) -> SemanticSearchEngine {
+            let mut config = SemanticConfig::default();
+            config.embedding.primary_provider = "mock".to_string();
+            config.embedding.fallback_providers = vec![];
+            config.search.enable_reranking = false;
+            config.search.default_threshold = -1.0;
+            config.search.hybrid = HybridConfig {
+                enabled: true,
+                fusion: HybridFusion::WeightedSum { alpha },
+            };
+            let store: Arc<dyn VectorIndex> = Arc::new(MockVectorStore::new(384, SimilarityMetric::Cosine));
+            let engine = SemanticSearchEngine::with_vector_store(config, store).await.unwrap();
+            for (id, content) in [
+                ("exact", "Call frobnicate_widget to reset the widget cache"),
+                ("vague", "Resetting caches of widgets in general"),
+            ] {
+                engine
+                    .index_document(id.to_string(), content.to_string(), EntityType::Document, HashMap::new())
+                    .await
+                    .unwrap();
+            }
+            engine
+        }
+
+        // Favouring sparse, the exact keyword match ranks first
+        let engine = hybrid_engine(0.2).await;
+        let results = engine.search("frobnicate_widget", 10).await.unwrap();
+        assert_eq!(results[0].id, "exact");
+        let scores = results[0].hybrid_scores.unwrap();
+        assert!(scores.sparse.unwrap() > 0.0);
+        let dense = scores.dense.unwrap_or(0.0);
+        assert!((scores.fused - (0.2 * dense + 0.8)).abs() < 1e-5);
+        for result in &results[1..] {
+            assert!(result.hybrid_scores.unwrap().sparse.is_none());
+        }
+
+        // Favouring dense only, sparse scores are reported but ignored
+        let engine = hybrid_engine(1.0).await;
+        let results = engine.search("frobnicate_widget", 10).await.unwrap();
+        for result in &results {
+            let scores = result.hybrid_scores.unwrap();
+            assert!((scores.fused - scores.dense.unwrap_or(0.0)).abs() < 1e-6);
+        }
+
+        // Removed documents leave the sparse index too
+        engine.remove_document(&"exact".to_string()).await.unwrap();
+        let sparse = engine.sparse.as_ref().unwrap();
+        assert!(sparse.search(&sparse.encode_query("frobnicate_widget"), 10).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_mock_search_with_filter_expression() {
+        let mut config = SemanticConfig::default();
+        config.embedding.primary_provider = "mock".to_string();
+        config.embedding.fallback_providers = vec![];
+        config.search.default_threshold = -1.0;
+        let store: Arc<dyn VectorIndex> = Arc::new(MockVectorStore::new(384, SimilarityMetric::Cosine));
+        let engine = SemanticSearchEngine::with_vector_store(config, store).await.unwrap();
+
+        for (id, language, modified_at, module) in [
+            ("login", "rust", "2024-03-01", "auth"),
+            ("old_login", "rust", "2023-06-01", "auth"),
+            ("invoice", "rust", "2024-05-01", "billing"),
+            ("py_session", "python", "2024-05-01", "session"),
+            ("session", "rust", "2024-07-01T12:00:00Z", "session"),
+        ] {
+            let metadata = HashMap::from([
+                ("language".to_string(), language.to_string()),
+                ("modified_at".to_string(), modified_at.to_string()),
+                ("module".to_string(), module.to_string()),
+            ]);
+            engine
+                .index_document(id.to_string(), format!("{} handler", id), EntityType::Code, metadata)
+                .await
+                .unwrap();
+        }
+
+        let filter: SearchFilter = serde_json::from_value(serde_json::json!({
+            "expr": {"op": "and", "filters": [
+                {"op": "eq", "field": "language", "value": "rust"},
+                {"op": "range", "field": "modified_at", "gt": "2024-01-01"},
+                {"op": "or", "filters": [
+                    {"op": "eq", "field": "module", "value": "auth"},
+                    {"op": "eq", "field": "module", "value": "session"}
+                ]}
+            ]}
+        }))
+        .unwrap();
+        let results = engine.search_with_filter("handler", 10, filter).await.unwrap();
+        let mut ids: Vec<_> = results.iter().map(|r| r.id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["login", "session"]);
+
+        let filter = SearchFilter {
+            expr: Some(FilterExpr::not(FilterExpr::eq("entity_type", "code"))),
+            ..Default::default()
+        };
+        assert!(engine.search_with_filter("handler", 10, filter).await.unwrap().is_empty());
+
+        // Unknown fields are an error rather than an empty result
+        let filter = SearchFilter {
+            expr: Some(FilterExpr::eq("modlue", "auth")),
+            ..Default::default()
+        };
+        let err = engine.search_with_filter("handler", 10, filter).await;
+        assert!(matches!(err, Err(SemanticError::InvalidFilter(_))));
+    }
+
+    #[tokio::test]
+    async fn test_mock_update_document_and_reindex_stale() {
+        let engine = create_test_engine_with_mock(384).await;
+        for (id, content) in [("a", "First version"), ("b", "Second document"), ("c", "Third document")] {
+            engine
+                .index_document(id.to_string(), content.to_string(), EntityType::Code, HashMap::new())
+                .await
+                .unwrap();
+        }
+        let (a, b) = ("a".to_string(), "b".to_string());
+
+        // The same content is not re-embedded
+        let indexed_at = engine.documents.get(&a).unwrap().indexed_at;
+        assert_eq!(
+            engine.update_document(&a, "First version".to_string()).await.unwrap(),
+            DocumentUpdate::Unchanged
+        );
+        assert_eq!(engine.documents.get(&a).unwrap().indexed_at, indexed_at);
+
+        // New content replaces the vector and keeps the metadata
+        let old_embedding = engine.documents.get(&a).unwrap().embedding.clone();
+        assert_eq!(
+            engine.update_document(&a, "Rewritten entirely".to_string()).await.unwrap(),
+            DocumentUpdate::Updated
+        );
+        let doc = engine.documents.get(&a).unwrap().clone();
+        assert_eq!(doc.content, "Rewritten entirely");
+        assert_eq!(doc.entity_type, EntityType::Code);
+        assert_ne!(doc.embedding, old_embedding);
+        assert_eq!(doc.metadata.get(CONTENT_HASH_KEY), Some(&content_hash("Rewritten entirely")));
+        assert_eq!(engine.stats().await.total_vectors, 3);
+
+        let err = engine.update_document(&"missing".to_string(), String::new()).await;
+        assert!(matches!(err, Err(SemanticError::DocumentNotFound(_))));
+
+        // Only documents whose source content changed are re-embedded
+        let source = HashMap::from([
+            (a.clone(), "Rewritten entirely".to_string()),
+            (b.clone(), "Second document, edited".to_string()),
+        ]);
+        let report = engine.reindex_stale(&source).await;
+        assert_eq!(report.updated, 1);
+        assert_eq!(report.skipped, 2);
+        assert!(report.failed.is_empty());
+        assert_eq!(engine.documents.get(&b).unwrap().content, "Second document, edited");
+
+        let report = engine.reindex_stale(&source).await;
+        assert_eq!((report.updated, report.skipped), (0, 3));
+    }
+
+    #[tokio::test]
+    async fn test_mock_index_document_as_enforces_quota() {
+        use crate::agent::{AgentRole, NamespaceQuota};
+
+        let engine = create_test_engine_with_mock(384).await;
+        let coordinator = AgentCoordinator::new();
+        let quota = NamespaceQuota {
+            max_documents: Some(2),
+            max_vector_bytes: None,
+        };
+        coordinator
+            .register_agent_with_quota("worker", AgentRole::Worker, vec![], quota)
+            .await
+            .unwrap();
+        let agent_id = "worker".to_string();
+
+        for id in ["a", "b"] {
+            engine
+                .index_document_as(&agent_id, id.to_string(), format!("Document {id}"), EntityType::Code, HashMap::new(), &coordinator)
+                .await
+                .unwrap();
+        }
+
+        // A third document is rejected before it reaches the index
+        let err = engine
+            .index_document_as(&agent_id, "c".to_string(), "Document c".to_string(), EntityType::Code, HashMap::new(), &coordinator)
+            .await;
+        assert!(matches!(
+            err,
+            Err(SemanticError::QuotaExceeded { current: 2, limit: 2, .. })
+        ));
+        assert_eq!(engine.document_count().await, 2);
+
+        // Re-indexing an existing document does not count twice
+        engine
+            .index_document_as(&agent_id, "a".to_string(), "Document a, edited".to_string(), EntityType::Code, HashMap::new(), &coordinator)
+            .await
+            .unwrap();
+
+        // Removing a document frees room for another
+        engine.remove_document_as(&agent_id, &"b".to_string(), &coordinator).await.unwrap();
+        engine
+            .index_document_as(&agent_id, "c".to_string(), "Document c".to_string(), EntityType::Code, HashMap::new(), &coordinator)
+            .await
+            .unwrap();
+
+        let report = coordinator.usage_report();
+        assert_eq!(report.total.documents, 2);
+        assert_eq!(report.total.vector_bytes, 2 * 384 * 4);
+        assert_eq!(report.agents[&agent_id].documents, 2);
+    }
+
+    #[tokio::test]
+    async fn test_embedding_cache_skips_provider_for_repeated_texts() {
+        let mock = MockProvider::new(384).with_seed(13).with_recording();
+        let provider = Arc::new(ProviderManager::new(
+            vec![("mock".to_string(), Box::new(mock.clone()) as Box<dyn EmbeddingProvider>)],
+            Default::default(),
+        ));
+        let store: Arc<dyn VectorIndex> = Arc::new(MockVectorStore::new(384, SimilarityMetric::Cosine));
+        let mut config = SemanticConfig::default();
+        config.cache.enable_query_cache = false;
+        config.search.enable_query_expansion = false;
+        let engine = SemanticSearchEngine::with_provider(config, provider, store).await.unwrap();
+
+        let docs = vec![
+            ("a".to_string(), "fn parse_config()".to_string()),
+            ("b".to_string(), "struct Config".to_string()),
+        ];
+        engine.index_documents(docs.clone()).await;
+        assert_eq!(mock.embedded_texts().len(), 2);
+
+        // Re-indexing the same content is served from the cache, as is a
+        // repeated query
+        mock.clear_calls();
+        let report = engine.index_documents(docs).await;
+        assert_eq!(report.succeeded.len(), 2);
+        assert!(mock.calls().is_empty());
+        engine.search("config parsing", 5).await.unwrap();
+        let embedded = mock.embedded_texts().len();
+        engine.search("config parsing", 5).await.unwrap();
+        assert_eq!(mock.embedded_texts().len(), embedded);
+
+        let stats = engine.stats().await.embedding_cache.unwrap();
+        assert!(stats.hits >= 3);
+        assert!(stats.hit_rate() > 0.0);
+
+        // Invalidating the model empties the cache
+        let model = engine.embedding_model().model_name;
+        assert_eq!(engine.invalidate_embedding_model(&model) as u64, stats.entries);
+        assert_eq!(engine.embedding_cache_stats().unwrap().entries, 0);
+    }
+
+    #[tokio::test]
+    async fn test_query_embedding_cache_can_be_disabled() {
+        let mock = MockProvider::new(384).with_seed(17).with_recording();
+        let provider = Arc::new(ProviderManager::new(
+            vec![("mock".to_string(), Box::new(mock.clone()) as Box<dyn EmbeddingProvider>)],
+            Default::default(),
+        ));
+        let store: Arc<dyn VectorIndex> = Arc::new(MockVectorStore::new(384, SimilarityMetric::Cosine));
+        let mut config = SemanticConfig::default();
+        config.cache.enable_query_cache = false;
+        config.search.enable_query_expansion = false;
+        config.search.cache_query_embeddings = false;
+        let engine = SemanticSearchEngine::with_provider(config, provider, store).await.unwrap();
+
+        engine.search("config parsing", 5).await.unwrap();
+        let embedded = mock.embedded_texts().len();
+        engine.search("config parsing", 5).await.unwrap();
+        assert_eq!(mock.embedded_texts().len(), 2 * embedded);
+    }
+
+    /// Provider failing with a server error until `failures` runs out.
+    struct FlakyProvider {
+        inner: MockProvider,
+        failures: std::sync::atomic::AtomicU32,
+    }
+
+    #[async_trait]
+    impl EmbeddingProvider for FlakyProvider {
+        async fn embed(&self, text: &str) -> Result<Vector> {
+            self.fail_while_flaky()?;
+            self.inner.embed(text).await
+        }
+
+        async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vector>> {
+            self.fail_while_flaky()?;
+            self.inner.embed_batch(texts).await
+        }
+
+        fn model(&self) -> &EmbeddingModel {
+            self.inner.model()
+        }
+    }
+
+    impl FlakyProvider {
+        fn fail_while_flaky(&self) -> Result<()> {
+            let remaining = self.failures.load(std::sync::atomic::Ordering::Relaxed);
+            if remaining == 0 {
+                return Ok(());
+            }
+            self.failures.store(remaining - 1, std::sync::atomic::Ordering::Relaxed);
+            Err(SemanticError::ProviderServerError {
+                provider: "flaky".to_string(),
+                status: 503,
+                message: "overloaded".to_string(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retryable_embedding_failures_are_retried() {
+        for (retry, succeeds) in [
+            (
+                crate::retry::RetryPolicy {
+                    initial_backoff_ms: 1,
+                    ..Default::default()
+                },
+                true,
+            ),
+            (crate::retry::RetryPolicy::none(), false),
+        ] {
+            let flaky = FlakyProvider {
+                inner: MockProvider::new(384),
+                failures: std::sync::atomic::AtomicU32::new(2),
+            };
+            let provider = Arc::new(ProviderManager::new(
+                vec![("flaky".to_string(), Box::new(flaky) as Box<dyn EmbeddingProvider>)],
+                Default::default(),
+            ));
+            let store: Arc<dyn VectorIndex> = Arc::new(MockVectorStore::new(384, SimilarityMetric::Cosine));
+            let mut config = SemanticConfig::default();
+            config.retry = retry;
+            let engine = SemanticSearchEngine::with_provider(config, provider, store).await.unwrap();
+
+            let result = engine
+                .index_document("a".to_string(), "fn main()".to_string(), EntityType::Code, HashMap::new())
+                .await;
+            match result {
+                Ok(()) => assert!(succeeds),
+                Err(e) => {
+                    assert!(!succeeds);
+                    assert_eq!(e.error_code(), "provider.all_failed");
+                    assert!(e.is_retryable());
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_warm_up_and_readiness() {
+        let store: Arc<dyn VectorIndex> = Arc::new(MockVectorStore::new(384, SimilarityMetric::Cosine));
+        let mut config = SemanticConfig::default();
+        config.embedding.primary_provider = "mock".to_string();
+        config.embedding.fallback_providers = vec![];
+        let engine = SemanticSearchEngine::with_vector_store(config.clone(), store.clone()).await.unwrap();
+        assert_eq!(engine.readiness(), ReadinessStatus::Cold);
+
+        let report = engine.warm_up().await;
+        assert!(report.is_ready());
+        let stages: Vec<WarmupStage> = report.stages.iter().map(|timing| timing.stage).collect();
+        assert_eq!(stages, vec![WarmupStage::EmbeddingModel, WarmupStage::VectorStore, WarmupStage::Canary]);
+        assert!(report.stage(WarmupStage::Canary).unwrap().duration_ms <= report.total_ms);
+        assert_eq!(engine.readiness(), ReadinessStatus::Ready);
+
+        // An eager engine is warmed up when it is returned
+        config.initialization = InitializationMode::Eager;
+        let engine = SemanticSearchEngine::with_vector_store(config, store).await.unwrap();
+        assert!(engine.readiness().is_ready());
+    }
+
+    #[tokio::test]
+    async fn test_failed_warm_up_recovers_on_successful_search() {
+        // The provider fails the warm-up probe only
+        let flaky = FlakyProvider {
+            inner: MockProvider::new(384),
+            failures: std::sync::atomic::AtomicU32::new(1),
+        };
+        let provider = Arc::new(ProviderManager::new(
+            vec![("flaky".to_string(), Box::new(flaky) as Box<dyn EmbeddingProvider>)],
+            Default::default(),
+        ));
+        let store: Arc<dyn VectorIndex> = Arc::new(MockVectorStore::new(384, SimilarityMetric::Cosine));
+        let engine = SemanticSearchEngine::with_provider(SemanticConfig::default(), provider, store)
+            .await
+            .unwrap();
+
+        let report = engine.warm_up().await;
+        assert!(!report.is_ready());
+        assert_eq!(report.stages.len(), 1);
+        let (stage, error) = report.failure().unwrap();
+        assert_eq!(stage, WarmupStage::EmbeddingModel);
+        assert!(error.contains("overloaded"));
+        assert!(matches!(
+            engine.readiness(),
+            ReadinessStatus::Failed { stage: WarmupStage::EmbeddingModel, .. }
+        ));
+
+        engine.search("anything", 5).await.unwrap();
+        assert_eq!(engine.readiness(), ReadinessStatus::Ready);
+    }
 }
@@ -0,0 +1,148 @@
+//! Pool of inference sessions shared by concurrent callers.
+//!
+//! An ONNX Runtime session runs one inference at a time, so a single session
+//! serializes every embedding request. [`SessionPool`] holds several and
+//! lends each to one caller at a time; callers block until one is idle.
+//! Sessions go back to the pool when their [`PooledSession`] guard drops, even
+//! if inference panicked, so a pool always drops with all of its sessions.
+
+use parking_lot::{Condvar, Mutex};
+use std::ops::Deref;
+use tracing::debug;
+
+/// Sessions lent out to one caller at a time.
+pub(crate) struct SessionPool<S> {
+    idle: Mutex<Vec<S>>,
+    returned: Condvar,
+    size: usize,
+}
+
+impl<S> SessionPool<S> {
+    pub(crate) fn new(sessions: Vec<S>) -> Self {
+        Self {
+            size: sessions.len(),
+            idle: Mutex::new(sessions),
+            returned: Condvar::new(),
+        }
+    }
+
+    /// Number of sessions, idle or lent out.
+    pub(crate) fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Number of sessions not lent out.
+    pub(crate) fn idle(&self) -> usize {
+        self.idle.lock().len()
+    }
+
+    /// Borrow an idle session, blocking until one is returned if all are in
+    /// use. Call it off the async runtime.
+    pub(crate) fn checkout(&self) -> PooledSession<'_, S> {
+        let mut idle = self.idle.lock();
+        loop {
+            if let Some(session) = idle.pop() {
+                return PooledSession {
+                    pool: self,
+                    session: Some(session),
+                };
+            }
+            self.returned.wait(&mut idle);
+        }
+    }
+}
+
+impl<S> Drop for SessionPool<S> {
+    fn drop(&mut self) {
+        // Guards borrow the pool, so every session is back by now
+        let sessions = std::mem::take(self.idle.get_mut());
+        debug!("Releasing {} pooled inference sessions", sessions.len());
+        drop(sessions);
+    }
+}
+
+/// A session borrowed from a [`SessionPool`], returned when dropped.
+pub(crate) struct PooledSession<'a, S> {
+    pool: &'a SessionPool<S>,
+    session: Option<S>,
+}
+
+impl<S> Deref for PooledSession<'_, S> {
+    type Target = S;
+
+    fn deref(&self) -> &S {
+        self.session.as_ref().expect("session is present until dropped")
+    }
+}
+
+impl<S> Drop for PooledSession<'_, S> {
+    fn drop(&mut self) {
+        if let Some(session) = self.session.take() {
+            self.pool.idle.lock().push(session);
+            self.pool.returned.notify_one();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    /// Counts how many of its kind are alive.
+    struct Tracked(Arc<AtomicUsize>);
+
+    impl Tracked {
+        fn new(alive: &Arc<AtomicUsize>) -> Self {
+            alive.fetch_add(1, Ordering::Relaxed);
+            Self(alive.clone())
+        }
+    }
+
+    impl Drop for Tracked {
+        fn drop(&mut self) {
+            self.0.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn test_checkout_waits_for_a_returned_session() {
+        let pool = Arc::new(SessionPool::new(vec![1, 2]));
+        let first = pool.checkout();
+        let second = pool.checkout();
+        assert_eq!(*first + *second, 3);
+        assert_eq!(pool.idle(), 0);
+
+        let waiter = {
+            let pool = pool.clone();
+            std::thread::spawn(move || *pool.checkout())
+        };
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(!waiter.is_finished());
+
+        let returned = *second;
+        drop(second);
+        assert_eq!(waiter.join().unwrap(), returned);
+        drop(first);
+        assert_eq!(pool.idle(), 2);
+    }
+
+    #[test]
+    fn test_sessions_return_after_panic_and_drop_with_pool() {
+        let alive = Arc::new(AtomicUsize::new(0));
+        let pool = SessionPool::new((0..3).map(|_| Tracked::new(&alive)).collect());
+        assert_eq!(pool.size(), 3);
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _session = pool.checkout();
+            panic!("inference failed");
+        }));
+        assert!(result.is_err());
+        assert_eq!(pool.idle(), 3);
+
+        drop(pool);
+        assert_eq!(alive.load(Ordering::Relaxed), 0);
+    }
+}
@@ -0,0 +1,309 @@
+//! Portable snapshots of a semantic index.
+//!
+//! A snapshot is a gzip-compressed newline-delimited JSON file. The first
+//! line is a [`SnapshotHeader`] naming the embedding model the vectors were
+//! made with; every following line is either a document the engine holds or
+//! a point of its vector index. Unlike Qdrant's own snapshots, the file does
+//! not depend on the deployment mode, so an index moves between machines
+//! without re-embedding.
+//!
+//! Imports record the points they have written in a progress manifest next
+//! to the snapshot (`<snapshot>.progress`) and resume after them when run
+//! again. Documents live in memory and are always re-applied.
+
+use crate::config::QuantizationType;
+use crate::error::{Result, SemanticError};
+use crate::qdrant::IndexPoint;
+use crate::types::{EmbeddingModel, IndexedDocument, SimilarityMetric};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Lines, Write};
+use std::path::{Path, PathBuf};
+
+/// Version of the snapshot format written by this crate.
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+/// First line of a snapshot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotHeader {
+    pub format_version: u32,
+    /// Identifies the snapshot in import progress manifests
+    pub snapshot_id: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub embedding_model: EmbeddingModel,
+    pub similarity_metric: SimilarityMetric,
+    /// Quantization of the exported collection; vectors are always written
+    /// at full precision
+    pub quantization: QuantizationType,
+}
+
+impl SnapshotHeader {
+    pub fn new(
+        embedding_model: EmbeddingModel,
+        similarity_metric: SimilarityMetric,
+        quantization: QuantizationType,
+    ) -> Self {
+        Self {
+            format_version: SNAPSHOT_FORMAT_VERSION,
+            snapshot_id: uuid::Uuid::new_v4().to_string(),
+            created_at: chrono::Utc::now(),
+            embedding_model,
+            similarity_metric,
+            quantization,
+        }
+    }
+}
+
+/// What an export wrote or an import loaded.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotSummary {
+    pub documents: usize,
+    pub points: usize,
+    /// Points skipped because an earlier, interrupted import wrote them
+    pub resumed_points: u64,
+}
+
+/// A line of a snapshot after the header.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "record", rename_all = "snake_case")]
+pub(crate) enum SnapshotRecord {
+    Document {
+        document: IndexedDocument,
+        /// Chunk texts of a multi-vector document
+        #[serde(default)]
+        chunks: Option<Vec<String>>,
+    },
+    Point(IndexPoint),
+}
+
+/// Writes a snapshot to a temporary file, renamed into place by
+/// [`finish`](Self::finish) so a failed export never leaves a truncated
+/// snapshot behind.
+pub(crate) struct SnapshotWriter {
+    path: PathBuf,
+    tmp_path: PathBuf,
+    encoder: GzEncoder<BufWriter<File>>,
+}
+
+impl SnapshotWriter {
+    pub(crate) fn create(path: &Path, header: &SnapshotHeader) -> Result<Self> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp_path = with_suffix(path, "tmp");
+        let file = File::create(&tmp_path)?;
+        let mut writer = Self {
+            path: path.to_path_buf(),
+            tmp_path,
+            encoder: GzEncoder::new(BufWriter::new(file), Compression::default()),
+        };
+        writer.write_line(header)?;
+        Ok(writer)
+    }
+
+    pub(crate) fn write(&mut self, record: &SnapshotRecord) -> Result<()> {
+        self.write_line(record)
+    }
+
+    fn write_line(&mut self, value: &impl Serialize) -> Result<()> {
+        serde_json::to_writer(&mut self.encoder, value)?;
+        self.encoder.write_all(b"\n")?;
+        Ok(())
+    }
+
+    pub(crate) fn finish(self) -> Result<()> {
+        let mut writer = self.encoder.finish()?;
+        writer.flush()?;
+        writer.get_ref().sync_all()?;
+        drop(writer);
+        std::fs::rename(&self.tmp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+/// Reads the records of a snapshot after its header.
+pub(crate) struct SnapshotReader {
+    header: SnapshotHeader,
+    lines: Lines<BufReader<GzDecoder<File>>>,
+    line: usize,
+}
+
+impl SnapshotReader {
+    pub(crate) fn open(path: &Path) -> Result<Self> {
+        let mut lines = BufReader::new(GzDecoder::new(File::open(path)?)).lines();
+        let header_line = lines.next().transpose()?.ok_or_else(|| {
+            SemanticError::Index(format!("Snapshot {} is empty", path.display()))
+        })?;
+        let header: SnapshotHeader = serde_json::from_str(&header_line).map_err(|e| {
+            SemanticError::Index(format!("Snapshot {} has no valid header: {}", path.display(), e))
+        })?;
+        if header.format_version > SNAPSHOT_FORMAT_VERSION {
+            return Err(SemanticError::Index(format!(
+                "Snapshot format version {} is newer than the supported version {}",
+                header.format_version, SNAPSHOT_FORMAT_VERSION
+            )));
+        }
+        Ok(Self {
+            header,
+            lines,
+            line: 1,
+        })
+    }
+
+    pub(crate) fn header(&self) -> &SnapshotHeader {
+        &self.header
+    }
+}
+
+impl Iterator for SnapshotReader {
+    type Item = Result<SnapshotRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = match self.lines.next()? {
+                Ok(line) => line,
+                Err(e) => return Some(Err(e.into())),
+            };
+            self.line += 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+            return Some(serde_json::from_str(&line).map_err(|e| {
+                SemanticError::Index(format!("Snapshot line {} is invalid: {}", self.line, e))
+            }));
+        }
+    }
+}
+
+/// Points an interrupted import of a snapshot has written.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct ImportProgress {
+    pub snapshot_id: String,
+    pub points_applied: u64,
+}
+
+impl ImportProgress {
+    pub(crate) fn manifest_path(snapshot: &Path) -> PathBuf {
+        with_suffix(snapshot, "progress")
+    }
+
+    /// Points already written from the snapshot, 0 when there is no
+    /// manifest or it belongs to another snapshot at the same path.
+    pub(crate) fn load(snapshot: &Path, snapshot_id: &str) -> Result<u64> {
+        let path = Self::manifest_path(snapshot);
+        if !path.exists() {
+            return Ok(0);
+        }
+        let progress: ImportProgress = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+        Ok(if progress.snapshot_id == snapshot_id {
+            progress.points_applied
+        } else {
+            0
+        })
+    }
+
+    pub(crate) fn save(&self, snapshot: &Path) -> Result<()> {
+        let path = Self::manifest_path(snapshot);
+        let tmp_path = with_suffix(&path, "tmp");
+        std::fs::write(&tmp_path, serde_json::to_vec(self)?)?;
+        std::fs::rename(&tmp_path, &path)?;
+        Ok(())
+    }
+
+    pub(crate) fn remove(snapshot: &Path) -> Result<()> {
+        match std::fs::remove_file(Self::manifest_path(snapshot)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn header() -> SnapshotHeader {
+        SnapshotHeader::new(
+            EmbeddingModel {
+                provider: "mock".to_string(),
+                model_name: "mock-model".to_string(),
+                dimension: 3,
+            },
+            SimilarityMetric::Cosine,
+            QuantizationType::Scalar,
+        )
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("index.ndjson.gz");
+        let header = header();
+
+        let mut writer = SnapshotWriter::create(&path, &header).unwrap();
+        for i in 0..3 {
+            writer
+                .write(&SnapshotRecord::Point(IndexPoint {
+                    doc_id: format!("doc{}", i),
+                    vector: vec![i as f32, 0.0, 1.0],
+                    payload: HashMap::from([("doc_id".to_string(), serde_json::json!(format!("doc{}", i)))]),
+                }))
+                .unwrap();
+        }
+        // Nothing is visible until the export finishes
+        assert!(!path.exists());
+        writer.finish().unwrap();
+
+        let reader = SnapshotReader::open(&path).unwrap();
+        assert_eq!(reader.header(), &header);
+        let ids: Vec<String> = reader
+            .map(|record| match record.unwrap() {
+                SnapshotRecord::Point(point) => point.doc_id,
+                SnapshotRecord::Document { .. } => panic!("unexpected document"),
+            })
+            .collect();
+        assert_eq!(ids, vec!["doc0", "doc1", "doc2"]);
+    }
+
+    #[test]
+    fn test_import_progress_belongs_to_one_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("index.ndjson.gz");
+        assert_eq!(ImportProgress::load(&path, "a").unwrap(), 0);
+
+        let progress = ImportProgress {
+            snapshot_id: "a".to_string(),
+            points_applied: 128,
+        };
+        progress.save(&path).unwrap();
+        assert_eq!(ImportProgress::load(&path, "a").unwrap(), 128);
+        // Another snapshot written to the same path starts over
+        assert_eq!(ImportProgress::load(&path, "b").unwrap(), 0);
+
+        ImportProgress::remove(&path).unwrap();
+        ImportProgress::remove(&path).unwrap();
+        assert_eq!(ImportProgress::load(&path, "a").unwrap(), 0);
+    }
+
+    #[test]
+    fn test_newer_format_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("index.ndjson.gz");
+        let mut header = header();
+        header.format_version = SNAPSHOT_FORMAT_VERSION + 1;
+        SnapshotWriter::create(&path, &header).unwrap().finish().unwrap();
+
+        assert!(SnapshotReader::open(&path).is_err());
+    }
+}
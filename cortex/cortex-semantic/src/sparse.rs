@@ -0,0 +1,255 @@
+//! Sparse term vectors for hybrid search.
+//!
+//! `SparseEncoder` turns text into sparse vectors of hashed terms, and
+//! `SparseIndex` keeps the term frequencies of indexed documents in memory
+//! and scores them against a query with BM25.
+
+use crate::qdrant::SparseVector;
+use crate::types::DocumentId;
+use parking_lot::RwLock;
+use std::collections::HashMap;
+
+/// Tokenizes text into terms and hashes them to sparse vector indices.
+#[derive(Debug, Clone, Copy)]
+pub struct SparseEncoder {
+    /// Term frequency saturation
+    k1: f32,
+    /// Document length normalization (0.0 - 1.0)
+    b: f32,
+}
+
+impl Default for SparseEncoder {
+    fn default() -> Self {
+        Self::new(1.2, 0.75)
+    }
+}
+
+impl SparseEncoder {
+    pub fn new(k1: f32, b: f32) -> Self {
+        Self {
+            k1,
+            b: b.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Lowercased alphanumeric terms of `text`; `_` is part of a term so
+    /// identifiers stay whole.
+    pub fn tokenize(text: &str) -> Vec<String> {
+        text.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .filter(|term| !term.is_empty())
+            .map(str::to_lowercase)
+            .collect()
+    }
+
+    /// Sparse vector index of a term. FNV-1a, so indices are stable across
+    /// processes.
+    pub fn term_index(term: &str) -> u32 {
+        term.bytes().fold(0x811c_9dc5, |hash: u32, byte| {
+            (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+        })
+    }
+
+    /// Raw term frequencies of `text`, sorted by index.
+    pub fn term_frequencies(text: &str) -> SparseVector {
+        let mut counts: HashMap<u32, f32> = HashMap::new();
+        for term in Self::tokenize(text) {
+            *counts.entry(Self::term_index(&term)).or_default() += 1.0;
+        }
+        let mut terms: Vec<(u32, f32)> = counts.into_iter().collect();
+        terms.sort_by_key(|(index, _)| *index);
+        let (indices, values) = terms.into_iter().unzip();
+        SparseVector { indices, values }
+    }
+
+    /// BM25 weight of a term occurring `tf` times in a document of
+    /// `doc_length` terms.
+    fn term_weight(&self, tf: f32, doc_length: f32, avg_doc_length: f32) -> f32 {
+        let length_ratio = if avg_doc_length > 0.0 {
+            doc_length / avg_doc_length
+        } else {
+            1.0
+        };
+        tf * (self.k1 + 1.0) / (tf + self.k1 * (1.0 - self.b + self.b * length_ratio))
+    }
+}
+
+/// In-memory BM25 index of document term frequencies.
+#[derive(Debug, Default)]
+pub struct SparseIndex {
+    encoder: SparseEncoder,
+    state: RwLock<SparseIndexState>,
+}
+
+#[derive(Debug, Default)]
+struct SparseIndexState {
+    /// Term frequencies and length of every document
+    documents: HashMap<DocumentId, (SparseVector, usize)>,
+    /// Frequency of each term per document containing it
+    postings: HashMap<u32, HashMap<DocumentId, f32>>,
+    /// Sum of all document lengths
+    total_length: usize,
+}
+
+impl SparseIndexState {
+    fn remove(&mut self, doc_id: &DocumentId) -> bool {
+        let Some((terms, length)) = self.documents.remove(doc_id) else {
+            return false;
+        };
+        for index in terms.indices {
+            if let Some(posting) = self.postings.get_mut(&index) {
+                posting.remove(doc_id);
+                if posting.is_empty() {
+                    self.postings.remove(&index);
+                }
+            }
+        }
+        self.total_length -= length;
+        true
+    }
+}
+
+impl SparseIndex {
+    pub fn new(encoder: SparseEncoder) -> Self {
+        Self {
+            encoder,
+            state: RwLock::new(SparseIndexState::default()),
+        }
+    }
+
+    /// Index the terms of a document, replacing any previous version.
+    pub fn insert(&self, doc_id: DocumentId, text: &str) {
+        let terms = SparseEncoder::term_frequencies(text);
+        let length = terms.values.iter().sum::<f32>() as usize;
+
+        let mut state = self.state.write();
+        state.remove(&doc_id);
+        for (index, tf) in terms.indices.iter().zip(&terms.values) {
+            state
+                .postings
+                .entry(*index)
+                .or_default()
+                .insert(doc_id.clone(), *tf);
+        }
+        state.total_length += length;
+        state.documents.insert(doc_id, (terms, length));
+    }
+
+    /// Remove a document. Returns whether it was indexed.
+    pub fn remove(&self, doc_id: &DocumentId) -> bool {
+        self.state.write().remove(doc_id)
+    }
+
+    pub fn clear(&self) {
+        *self.state.write() = SparseIndexState::default();
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.read().documents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Sparse query vector of `text`: each query term weighted by its
+    /// inverse document frequency in the index, times its frequency in the
+    /// query. Terms no document contains are dropped.
+    pub fn encode_query(&self, text: &str) -> SparseVector {
+        let terms = SparseEncoder::term_frequencies(text);
+        let state = self.state.read();
+        let total = state.documents.len() as f32;
+
+        let (indices, values) = terms
+            .indices
+            .into_iter()
+            .zip(terms.values)
+            .filter_map(|(index, tf)| {
+                let df = state.postings.get(&index)?.len() as f32;
+                let idf = (1.0 + (total - df + 0.5) / (df + 0.5)).ln();
+                Some((index, tf * idf))
+            })
+            .unzip();
+        SparseVector { indices, values }
+    }
+
+    /// The `k` documents with the highest BM25 score for a query vector
+    /// from `encode_query`, best first.
+    pub fn search(&self, query: &SparseVector, k: usize) -> Vec<(DocumentId, f32)> {
+        let state = self.state.read();
+        let avg_length = if state.documents.is_empty() {
+            0.0
+        } else {
+            state.total_length as f32 / state.documents.len() as f32
+        };
+
+        let mut scores: HashMap<&DocumentId, f32> = HashMap::new();
+        for (index, weight) in query.indices.iter().zip(&query.values) {
+            let Some(posting) = state.postings.get(index) else {
+                continue;
+            };
+            for (doc_id, tf) in posting {
+                let length = state.documents.get(doc_id).map_or(0, |(_, length)| *length);
+                *scores.entry(doc_id).or_default() +=
+                    weight * self.encoder.term_weight(*tf, length as f32, avg_length);
+            }
+        }
+
+        let mut results: Vec<(DocumentId, f32)> = scores
+            .into_iter()
+            .filter(|(_, score)| *score > 0.0)
+            .map(|(doc_id, score)| (doc_id.clone(), score))
+            .collect();
+        results.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        results.truncate(k);
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_term_frequencies() {
+        assert_eq!(
+            SparseEncoder::tokenize("Parse the config_file, then PARSE again"),
+            vec!["parse", "the", "config_file", "then", "parse", "again"]
+        );
+
+        let terms = SparseEncoder::term_frequencies("parse PARSE config");
+        assert_eq!(terms.indices.len(), 2);
+        assert!(terms.indices.windows(2).all(|pair| pair[0] < pair[1]));
+        let parse = terms
+            .indices
+            .iter()
+            .position(|index| *index == SparseEncoder::term_index("parse"))
+            .unwrap();
+        assert_eq!(terms.values[parse], 2.0);
+    }
+
+    #[test]
+    fn test_sparse_index_bm25() {
+        let index = SparseIndex::default();
+        index.insert("rare".to_string(), "the frobnicator tunes the widget");
+        index.insert("common".to_string(), "the widget and the gadget");
+        index.insert("other".to_string(), "the gadget");
+
+        // A rare term outweighs a common one
+        let results = index.search(&index.encode_query("frobnicator widget"), 10);
+        assert_eq!(results[0].0, "rare");
+        assert_eq!(results.len(), 2);
+
+        // Unknown terms match nothing
+        assert!(index.encode_query("unknown").indices.is_empty());
+
+        // Reindexing replaces the document's terms
+        index.insert("rare".to_string(), "the gadget");
+        assert!(index.search(&index.encode_query("frobnicator"), 10).is_empty());
+        assert_eq!(index.len(), 3);
+
+        assert!(index.remove(&"rare".to_string()));
+        assert!(!index.remove(&"rare".to_string()));
+        index.clear();
+        assert!(index.is_empty());
+    }
+}
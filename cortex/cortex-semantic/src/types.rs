@@ -138,12 +138,16 @@ pub struct AgentSearchResult {
     pub cross_agent_score: Option<f32>,
     /// Embedding vector for similarity calculations (optional for deduplication)
     pub embedding: Option<Vector>,
+    /// Other agents that returned a duplicate of this result, suppressed
+    /// by deduplication
+    #[serde(default)]
+    pub also_found_in: Vec<String>,
 }
 
 /// Multi-agent search statistics.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct MultiAgentSearchStats {
-    /// Total agents queried
+    /// Agents actually queried
     pub agents_queried: usize,
     /// Namespaces searched
     pub namespaces_searched: Vec<String>,
@@ -155,6 +159,37 @@ pub struct MultiAgentSearchStats {
     pub deduplicated_count: usize,
     /// Cross-agent communication overhead (ms)
     pub communication_overhead_ms: u64,
+    /// Similarity deduplication was skipped for exceeding
+    /// `dedup_max_comparisons`, and results were deduplicated by id and
+    /// content hash only
+    #[serde(default)]
+    pub dedup_fell_back_to_id: bool,
+    /// Normalization applied to agent scores before merging
+    #[serde(default)]
+    pub score_normalization: crate::orchestration::ScoreNormalization,
+    /// Score range of each agent's results before and after normalization
+    #[serde(default)]
+    pub score_ranges: HashMap<String, AgentScoreRange>,
+    /// Agents not queried because they are unreachable
+    #[serde(default)]
+    pub skipped_agents: Vec<String>,
+    /// Agents that did not respond within `agent_timeout_ms` or before the
+    /// deadline; the results leave them out
+    #[serde(default)]
+    pub agents_timed_out: Vec<String>,
+    /// Agents not queried because a background retry of an earlier
+    /// identical query, which timed out, left their results
+    #[serde(default)]
+    pub agents_from_retry: Vec<String>,
+}
+
+/// Range of one agent's scores in a federated search.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct AgentScoreRange {
+    pub raw_min: f32,
+    pub raw_max: f32,
+    pub normalized_min: f32,
+    pub normalized_max: f32,
 }
 
 /// Federated search configuration.
@@ -166,12 +201,59 @@ pub struct FederatedSearchConfig {
     pub deduplicate_results: bool,
     /// Deduplication similarity threshold
     pub dedup_threshold: f32,
+    /// How duplicates are recognized
+    #[serde(default)]
+    pub dedup_strategy: crate::orchestration::DeduplicationStrategy,
+    /// Most comparisons similarity deduplication may make, comparing each
+    /// result with the top `limit` kept so far; above it results are
+    /// deduplicated by id and content hash only
+    #[serde(default = "default_dedup_max_comparisons")]
+    pub dedup_max_comparisons: usize,
+    /// How agent scores are made comparable before merging; `None` picks
+    /// reciprocal rank fusion when the searched agents use different
+    /// embedding models and raw scores otherwise
+    #[serde(default)]
+    pub score_normalization: Option<crate::orchestration::ScoreNormalization>,
+    /// Rank offset `k` of reciprocal rank fusion, `1 / (k + rank)`
+    #[serde(default = "default_rrf_k")]
+    pub rrf_k: f32,
     /// Enable cross-agent result aggregation
     pub aggregate_results: bool,
     /// Weight for cross-namespace results
     pub cross_namespace_weight: f32,
     /// Maximum concurrent searches to prevent DoS (rate limiting)
     pub max_concurrent_searches: Option<usize>,
+    /// Aging and dequeue policy of the orchestrator's search queue
+    #[serde(default)]
+    pub queue: crate::agent::SearchQueueConfig,
+    /// Longest one agent's search may take before the federated search
+    /// goes on without it; `None` waits for every agent
+    #[serde(default = "default_agent_timeout_ms")]
+    pub agent_timeout_ms: Option<u64>,
+    /// Longest a whole federated search may take, waiting for permits
+    /// included; agents still searching then are left out
+    #[serde(default)]
+    pub deadline_ms: Option<u64>,
+    /// Retrying agents that timed out in the background
+    #[serde(default)]
+    pub timeout_retry: crate::orchestration::TimeoutRetryConfig,
+    /// Retries of an agent search that failed with a retryable error,
+    /// within the agent timeout. Off by default, since engines already
+    /// retry their embedding requests
+    #[serde(default = "crate::retry::RetryPolicy::none")]
+    pub retry: crate::retry::RetryPolicy,
+}
+
+fn default_dedup_max_comparisons() -> usize {
+    100_000
+}
+
+fn default_agent_timeout_ms() -> Option<u64> {
+    Some(5_000)
+}
+
+fn default_rrf_k() -> f32 {
+    60.0
 }
 
 impl Default for FederatedSearchConfig {
@@ -180,9 +262,18 @@ impl Default for FederatedSearchConfig {
             max_namespaces: 10,
             deduplicate_results: true,
             dedup_threshold: 0.95,
+            dedup_strategy: crate::orchestration::DeduplicationStrategy::default(),
+            dedup_max_comparisons: default_dedup_max_comparisons(),
+            score_normalization: None,
+            rrf_k: default_rrf_k(),
             aggregate_results: true,
             cross_namespace_weight: 0.8,
             max_concurrent_searches: Some(10),  // Default rate limit to prevent DoS
+            queue: crate::agent::SearchQueueConfig::default(),
+            agent_timeout_ms: default_agent_timeout_ms(),
+            deadline_ms: None,
+            timeout_retry: crate::orchestration::TimeoutRetryConfig::default(),
+            retry: crate::retry::RetryPolicy::none(),
         }
     }
 }
@@ -0,0 +1,97 @@
+//! Warm-up and readiness of a search engine.
+//!
+//! Right after start, the first searches pay for loading the embedding
+//! model and connecting to the vector store.
+//! [`SemanticSearchEngine::warm_up`](crate::SemanticSearchEngine::warm_up)
+//! does this up front and times each stage, and
+//! [`SemanticSearchEngine::readiness`](crate::SemanticSearchEngine::readiness)
+//! tells health checks whether the engine is ready to serve.
+
+use serde::{Deserialize, Serialize};
+
+/// A stage of warming up a search engine, in the order they run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarmupStage {
+    /// Load the embedding models of the provider chain by probing each
+    EmbeddingModel,
+    /// Open the vector store connection
+    VectorStore,
+    /// Embed a canary query and search the vector store with it
+    Canary,
+}
+
+impl WarmupStage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WarmupStage::EmbeddingModel => "embedding_model",
+            WarmupStage::VectorStore => "vector_store",
+            WarmupStage::Canary => "canary",
+        }
+    }
+}
+
+/// How long one warm-up stage took.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StageTiming {
+    pub stage: WarmupStage,
+    pub duration_ms: f64,
+    /// Why the stage failed; later stages are not run
+    pub error: Option<String>,
+}
+
+/// Outcome of [`SemanticSearchEngine::warm_up`](crate::SemanticSearchEngine::warm_up).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WarmupReport {
+    /// Stages that ran, in order; the last one failed if any did
+    pub stages: Vec<StageTiming>,
+    pub total_ms: f64,
+}
+
+impl WarmupReport {
+    /// Whether every stage ran and succeeded.
+    pub fn is_ready(&self) -> bool {
+        self.stages.len() == 3 && self.stages.iter().all(|timing| timing.error.is_none())
+    }
+
+    pub fn stage(&self, stage: WarmupStage) -> Option<&StageTiming> {
+        self.stages.iter().find(|timing| timing.stage == stage)
+    }
+
+    /// The stage that failed, with its error.
+    pub fn failure(&self) -> Option<(WarmupStage, &str)> {
+        self.stages
+            .iter()
+            .find_map(|timing| timing.error.as_deref().map(|error| (timing.stage, error)))
+    }
+}
+
+/// Whether a search engine is ready to serve searches quickly.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum ReadinessStatus {
+    /// Not warmed up and not searched yet; the first searches pay the
+    /// start-up cost
+    Cold,
+    /// A warm-up is running
+    WarmingUp,
+    /// Warmed up, or a search succeeded
+    Ready,
+    /// The last warm-up failed and no search has succeeded since
+    Failed { stage: WarmupStage, error: String },
+}
+
+impl ReadinessStatus {
+    pub fn is_ready(&self) -> bool {
+        matches!(self, ReadinessStatus::Ready)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReadinessStatus::Cold => "cold",
+            ReadinessStatus::WarmingUp => "warming_up",
+            ReadinessStatus::Ready => "ready",
+            ReadinessStatus::Failed { .. } => "failed",
+        }
+    }
+}
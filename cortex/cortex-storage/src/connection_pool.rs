@@ -11,10 +11,12 @@
 //! - Circuit breaker for fault tolerance
 
 use anyhow::Context;
+use crate::transaction::BufferedTransaction;
 use cortex_core::error::{CortexError, Result};
 use dashmap::DashMap;
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
+use std::future::{Future, IntoFuture};
 use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -106,6 +108,51 @@ pub struct PoolConfig {
     pub recycle_after_uses: Option<usize>,
     /// Grace period for shutdown (wait for in-flight operations)
     pub shutdown_grace_period: Duration,
+    /// Maximum run time of a single query before it is abandoned
+    ///
+    /// Queries made through [`PooledConnection::query`] are cut off after
+    /// this long. Embedded engines additionally enforce it, plus
+    /// [`ENGINE_TIMEOUT_GRACE`], on every statement sent over
+    /// [`PooledConnection::connection`]; remote servers apply their own
+    /// query timeout to those.
+    #[serde(default = "default_query_timeout")]
+    pub query_timeout: Option<Duration>,
+    /// Queries running at least this long are logged and counted as slow
+    #[serde(default = "default_slow_query_threshold")]
+    pub slow_query_threshold: Option<Duration>,
+}
+
+fn default_query_timeout() -> Option<Duration> {
+    Some(Duration::from_secs(30))
+}
+
+fn default_slow_query_threshold() -> Option<Duration> {
+    Some(Duration::from_secs(1))
+}
+
+/// Maximum statement length included in slow/timed-out query logs
+const LOGGED_STATEMENT_MAX_CHARS: usize = 200;
+
+/// Extra time the database engine allows a query beyond `query_timeout`, so
+/// that the pool's own timeout fires first for queries it can see and the
+/// engine limit only catches queries sent over the raw connection
+pub const ENGINE_TIMEOUT_GRACE: Duration = Duration::from_millis(500);
+
+tokio::task_local! {
+    /// Health-check flags of the connections checked out by the current
+    /// `execute_with_retry` attempt, flagged if the attempt times out
+    static ATTEMPT_CONNECTIONS: Arc<parking_lot::Mutex<Vec<Arc<AtomicBool>>>>;
+}
+
+/// Shorten a statement for logging, collapsing whitespace
+fn truncate_statement(statement: &str) -> String {
+    let collapsed = statement.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed.chars().count() <= LOGGED_STATEMENT_MAX_CHARS {
+        collapsed
+    } else {
+        let truncated: String = collapsed.chars().take(LOGGED_STATEMENT_MAX_CHARS).collect();
+        format!("{}...", truncated)
+    }
 }
 
 /// Retry policy configuration
@@ -193,8 +240,10 @@ impl ConnectionManager {
             }
         };
 
+        // Share the pool's metrics so connection and query counters are visible
+        // through the manager
+        let metrics = pool.metrics.clone();
         let pool = Arc::new(pool);
-        let metrics = Arc::new(PoolMetrics::new());
         let circuit_breaker = Arc::new(CircuitBreaker::new(5, Duration::from_secs(60)));
 
         // Start health monitoring
@@ -272,7 +321,25 @@ impl ConnectionManager {
         let mut attempts = 0;
 
         loop {
-            match operation().await {
+            let outcome = match self.config.pool_config.query_timeout {
+                Some(limit) => {
+                    let checked_out = Arc::new(parking_lot::Mutex::new(Vec::new()));
+                    let attempt = ATTEMPT_CONNECTIONS.scope(checked_out.clone(), operation());
+                    timeout(limit, attempt).await.unwrap_or_else(|_| {
+                        self.metrics.record_query_timeout();
+                        // The abandoned query may still be running on the
+                        // connections the attempt used
+                        for needs_health_check in checked_out.lock().drain(..) {
+                            needs_health_check.store(true, Ordering::Relaxed);
+                        }
+                        warn!(timeout_ms = limit.as_millis() as u64, "Operation timed out");
+                        Err(CortexError::timeout(format!("Operation exceeded {:?}", limit)))
+                    })
+                }
+                None => operation().await,
+            };
+
+            match outcome {
                 Ok(result) => {
                     self.circuit_breaker.record_success();
                     self.metrics.record_success();
@@ -560,12 +627,17 @@ impl ConnectionPool {
 
         debug!("Creating connection to endpoint: {}", endpoint);
 
+        // Embedded engines cut off any statement running past the pool's
+        // query timeout, including ones sent over the raw connection
+        let engine_config = surrealdb::opt::Config::new()
+            .query_timeout(self.config.query_timeout.map(|limit| limit + ENGINE_TIMEOUT_GRACE));
+
         // Special handling for in-memory mode
         let db: Surreal<Any> = if endpoint == "memory" {
             // For in-memory mode, we need to use a special connection approach
             // The Any engine doesn't support direct "memory" connections,
             // so we use "mem://" which the Any engine interprets as in-memory
-            match surrealdb::engine::any::connect("mem://")
+            match surrealdb::engine::any::connect(("mem://", engine_config))
                 .await
                 .context("Failed to connect to in-memory SurrealDB") {
                 Ok(db) => db,
@@ -576,7 +648,7 @@ impl ConnectionPool {
             }
         } else {
             // Connect using Any engine - this accepts the connection string
-            match surrealdb::engine::any::connect(&endpoint)
+            match surrealdb::engine::any::connect((endpoint.as_str(), engine_config))
                 .await
                 .context("Failed to connect to SurrealDB") {
                 Ok(db) => db,
@@ -618,6 +690,7 @@ impl ConnectionPool {
             healthy: Arc::new(AtomicBool::new(true)),
             recycle: Arc::new(AtomicBool::new(false)),
             health_check_failures: Arc::new(AtomicU32::new(0)),
+            needs_health_check: Arc::new(AtomicBool::new(false)),
         };
 
         self.metrics.connections_created.fetch_add(1, Ordering::Relaxed);
@@ -638,8 +711,21 @@ impl ConnectionPool {
         .map_err(|_| CortexError::database("Semaphore closed"))?;
 
         // Try to reuse an existing connection
-        if let Some(conn) = self.get_healthy_connection().await {
+        while let Some(conn) = self.get_healthy_connection().await {
+            // A connection whose query timed out may be stuck; verify it first
+            if conn.needs_health_check.swap(false, Ordering::Relaxed)
+                && !self.verify_connection(&conn).await
+            {
+                warn!("Connection {} failed health check after query timeout, discarding", conn.id);
+                conn.healthy.store(false, Ordering::Relaxed);
+                self.connections.remove(&conn.id);
+                self.metrics.health_checks_failed.fetch_add(1, Ordering::Relaxed);
+                self.metrics.connections_closed.fetch_add(1, Ordering::Relaxed);
+                continue;
+            }
+
             self.metrics.connections_reused.fetch_add(1, Ordering::Relaxed);
+            Self::track_checkout(&conn);
             return Ok(PooledConnection {
                 inner: conn,
                 pool: Arc::new(self.clone()),
@@ -651,6 +737,7 @@ impl ConnectionPool {
         let conn = self.create_connection().await?;
         let id = conn.id;
         self.connections.insert(id, conn.clone());
+        Self::track_checkout(&conn);
 
         Ok(PooledConnection {
             inner: conn,
@@ -659,6 +746,26 @@ impl ConnectionPool {
         })
     }
 
+    /// Remember a checked-out connection when acquired inside an
+    /// `execute_with_retry` attempt
+    fn track_checkout(conn: &PooledConnectionInner) {
+        let _ = ATTEMPT_CONNECTIONS.try_with(|checked_out| {
+            checked_out.lock().push(conn.needs_health_check.clone());
+        });
+    }
+
+    /// Lightweight liveness probe bounded by the acquisition timeout
+    async fn verify_connection(&self, conn: &PooledConnectionInner) -> bool {
+        let passed = matches!(
+            timeout(self.config.connection_timeout, conn.conn.query("INFO FOR DB")).await,
+            Ok(Ok(_))
+        );
+        if passed {
+            self.metrics.health_checks_passed.fetch_add(1, Ordering::Relaxed);
+        }
+        passed
+    }
+
     /// Get a healthy existing connection
     async fn get_healthy_connection(&self) -> Option<PooledConnectionInner> {
         for entry in self.connections.iter() {
@@ -775,6 +882,8 @@ struct PooledConnectionInner {
     recycle: Arc<AtomicBool>,
     /// Counter for consecutive failed health checks
     health_check_failures: Arc<AtomicU32>,
+    /// Set when a query timed out; the connection is verified before reuse
+    needs_health_check: Arc<AtomicBool>,
 }
 
 impl PooledConnectionInner {
@@ -845,6 +954,65 @@ impl PooledConnection {
         healthy
     }
 
+    /// Start a query subject to the pool's `query_timeout` and slow-query
+    /// logging. Use like `connection().query()`: chain `.bind()` calls and
+    /// `.await` it. A timed-out query returns [`CortexError::Timeout`] and the
+    /// connection is health-checked before it is handed out again.
+    pub fn query(&self, statement: impl Into<String>) -> TimedQuery<'_> {
+        let statement = statement.into();
+        TimedQuery {
+            query: self.connection().query(statement.clone()),
+            statement,
+            conn: self,
+        }
+    }
+
+    /// Run a query future under the pool's timeout and slow-query policy
+    pub async fn run_timed<T, F>(&self, statement: &str, operation: F) -> Result<T>
+    where
+        F: Future<Output = std::result::Result<T, surrealdb::Error>>,
+    {
+        let config = &self.pool.config;
+        let metrics = &self.pool.metrics;
+        let start = Instant::now();
+
+        let outcome = match config.query_timeout {
+            Some(limit) => match timeout(limit, operation).await {
+                Ok(outcome) => outcome,
+                Err(_) => {
+                    metrics.record_query_timeout();
+                    self.inner.needs_health_check.store(true, Ordering::Relaxed);
+                    let statement = truncate_statement(statement);
+                    warn!(
+                        connection = %self.id(),
+                        timeout_ms = limit.as_millis() as u64,
+                        statement = %statement,
+                        "Query timed out"
+                    );
+                    return Err(CortexError::timeout(format!(
+                        "Query exceeded {:?}: {}",
+                        limit, statement
+                    )));
+                }
+            },
+            None => operation.await,
+        };
+
+        let elapsed = start.elapsed();
+        if config.slow_query_threshold.is_some_and(|threshold| elapsed >= threshold) {
+            metrics.record_slow_query();
+            warn!(
+                connection = %self.id(),
+                duration_ms = elapsed.as_millis() as u64,
+                statement = %truncate_statement(statement),
+                "Slow query"
+            );
+        }
+        self.increment_uses();
+
+        outcome.map_err(|e| CortexError::database(e.to_string()))
+    }
+
     /// Mark connection for recycling
     pub fn mark_for_recycling(&self) {
         self.inner.recycle.store(true, Ordering::Relaxed);
@@ -882,24 +1050,10 @@ impl PooledConnection {
         Ok(())
     }
 
-    /// Create a savepoint
-    pub async fn savepoint(&self, name: &str) -> Result<()> {
-        let query = format!("DEFINE SAVEPOINT {}", name);
-        self.connection()
-            .query(&query)
-            .await
-            .context("Failed to create savepoint")?;
-        Ok(())
-    }
-
-    /// Rollback to a savepoint
-    pub async fn rollback_to_savepoint(&self, name: &str) -> Result<()> {
-        let query = format!("ROLLBACK TO SAVEPOINT {}", name);
-        self.connection()
-            .query(&query)
-            .await
-            .context("Failed to rollback to savepoint")?;
-        Ok(())
+    /// Start a buffered transaction with savepoint support. Nothing is sent
+    /// to the database until [`BufferedTransaction::commit`].
+    pub fn transaction(&self) -> BufferedTransaction<'_> {
+        BufferedTransaction::new(self)
     }
 
     /// Execute a query within a transaction with automatic rollback on error
@@ -924,6 +1078,34 @@ impl PooledConnection {
     }
 }
 
+/// Query builder returned by [`PooledConnection::query`]
+pub struct TimedQuery<'r> {
+    query: surrealdb::method::Query<'r, Any>,
+    statement: String,
+    conn: &'r PooledConnection,
+}
+
+impl TimedQuery<'_> {
+    /// Bind parameters, as with `surrealdb::method::Query::bind`
+    pub fn bind(mut self, bindings: impl Serialize + 'static) -> Self {
+        self.query = self.query.bind(bindings);
+        self
+    }
+}
+
+impl<'r> IntoFuture for TimedQuery<'r> {
+    type Output = Result<surrealdb::Response>;
+    type IntoFuture = futures::future::BoxFuture<'r, Self::Output>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(async move {
+            self.conn
+                .run_timed(&self.statement, self.query.into_future())
+                .await
+        })
+    }
+}
+
 impl Drop for PooledConnection {
     fn drop(&mut self) {
         self.pool.return_connection(self.inner.clone());
@@ -1175,6 +1357,8 @@ pub struct PoolMetrics {
     pub retries: AtomicU64,
     pub successes: AtomicU64,
     pub errors: AtomicU64,
+    pub slow_queries: AtomicU64,
+    pub query_timeouts: AtomicU64,
 }
 
 impl PoolMetrics {
@@ -1194,6 +1378,14 @@ impl PoolMetrics {
         self.errors.fetch_add(1, Ordering::Relaxed);
     }
 
+    fn record_slow_query(&self) {
+        self.slow_queries.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn record_query_timeout(&self) {
+        self.query_timeouts.fetch_add(1, Ordering::Relaxed);
+    }
+
     /// Get metrics snapshot
     pub fn snapshot(&self) -> MetricsSnapshot {
         MetricsSnapshot {
@@ -1207,6 +1399,8 @@ impl PoolMetrics {
             retries: self.retries.load(Ordering::Relaxed),
             successes: self.successes.load(Ordering::Relaxed),
             errors: self.errors.load(Ordering::Relaxed),
+            slow_queries: self.slow_queries.load(Ordering::Relaxed),
+            query_timeouts: self.query_timeouts.load(Ordering::Relaxed),
         }
     }
 }
@@ -1224,6 +1418,10 @@ pub struct MetricsSnapshot {
     pub retries: u64,
     pub successes: u64,
     pub errors: u64,
+    #[serde(default)]
+    pub slow_queries: u64,
+    #[serde(default)]
+    pub query_timeouts: u64,
 }
 
 // ==============================================================================
@@ -1585,6 +1783,8 @@ impl Default for PoolConfig {
             validate_on_checkout: false,                    // Optimized: Disabled for speed
             recycle_after_uses: Some(10000),               // Optimized: Recycle after 10k uses
             shutdown_grace_period: Duration::from_secs(30),
+            query_timeout: default_query_timeout(),
+            slow_query_threshold: default_slow_query_threshold(),
         }
     }
 }
@@ -1698,4 +1898,100 @@ mod tests {
         assert_eq!(snapshot.connections_reused, 10);
         assert_eq!(snapshot.successes, 15);
     }
+
+    #[test]
+    fn test_truncate_statement() {
+        assert_eq!(truncate_statement("SELECT *\n  FROM  code_unit"), "SELECT * FROM code_unit");
+
+        let long = format!("SELECT * FROM x WHERE id IN [{}]", "1, ".repeat(200));
+        let truncated = truncate_statement(&long);
+        assert!(truncated.ends_with("..."));
+        assert_eq!(truncated.chars().count(), LOGGED_STATEMENT_MAX_CHARS + 3);
+    }
+
+    async fn memory_manager(query_timeout: Option<Duration>, slow: Option<Duration>) -> ConnectionManager {
+        let config = DatabaseConfig {
+            connection_mode: ConnectionMode::InMemory,
+            pool_config: PoolConfig {
+                min_connections: 1,
+                max_connections: 2,
+                warm_connections: false,
+                query_timeout,
+                slow_query_threshold: slow,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        ConnectionManager::new(config).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_query_timeout_returns_timeout_error() {
+        let manager = memory_manager(Some(Duration::from_millis(20)), None).await;
+        let conn = manager.acquire().await.unwrap();
+
+        let err = conn.query("SLEEP 500ms").await.unwrap_err();
+
+        assert!(matches!(err, CortexError::Timeout(_)), "unexpected error: {err}");
+        assert!(conn.inner.needs_health_check.load(Ordering::Relaxed));
+        assert_eq!(manager.metrics().snapshot().query_timeouts, 1);
+    }
+
+    #[tokio::test]
+    async fn test_engine_enforces_timeout_on_raw_connection() {
+        let manager = memory_manager(Some(Duration::from_millis(20)), None).await;
+        let conn = manager.acquire().await.unwrap();
+
+        let start = Instant::now();
+        let response = conn.connection().query("SLEEP 10s; RETURN 1").await.unwrap();
+
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(response.check().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_retry_timeout_flags_connection_for_health_check() {
+        let manager = memory_manager(Some(Duration::from_millis(20)), None).await;
+        let pool = manager.pool.clone();
+
+        let err = manager
+            .execute_with_retry(|| {
+                let pool = pool.clone();
+                Box::pin(async move {
+                    let conn = pool.acquire().await?;
+                    // Outlives the attempt's timeout
+                    tokio::time::sleep(Duration::from_millis(200)).await;
+                    Ok(conn.id())
+                })
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CortexError::Timeout(_)), "unexpected error: {err}");
+
+        let flagged = pool
+            .connections
+            .iter()
+            .filter(|entry| entry.needs_health_check.load(Ordering::Relaxed))
+            .count();
+        assert_eq!(flagged, 1);
+    }
+
+    #[tokio::test]
+    async fn test_slow_query_is_counted() {
+        let manager = memory_manager(Some(Duration::from_secs(5)), Some(Duration::from_millis(10))).await;
+        let conn = manager.acquire().await.unwrap();
+
+        conn.query("SLEEP 30ms").await.unwrap();
+        let mut response = conn
+            .query("RETURN $value")
+            .bind(("value", 42))
+            .await
+            .unwrap();
+        let value: Option<i64> = response.take(0).unwrap();
+
+        assert_eq!(value, Some(42));
+        let snapshot = manager.metrics().snapshot();
+        assert!(snapshot.slow_queries >= 1);
+        assert_eq!(snapshot.query_timeouts, 0);
+    }
 }
@@ -10,9 +10,11 @@ pub mod query;
 pub mod schema;
 pub mod surrealdb_manager;
 pub mod connection_pool;
+pub mod transaction;
 pub mod session;
 pub mod merge;
 pub mod merge_engine;
+pub mod semantic_diff;
 pub mod session_aware_storage;
 pub mod locks;
 pub mod json_utils;
@@ -20,6 +22,9 @@ pub mod json_utils;
 // Qdrant vector database client
 pub mod qdrant;
 
+// S3-compatible upload for snapshot backups
+pub mod s3;
+
 // In-memory pool for testing (available in all builds for integration tests)
 pub mod in_memory_pool;
 
@@ -35,6 +40,7 @@ pub use connection_pool::{
     PoolConfig, PoolMetrics, PooledConnection, PoolStatistics, ResourceLimits, RetryPolicy,
     Transaction, TransactionOperation, TransactionStatus,
 };
+pub use transaction::{BufferedTransaction, QueuedOperation};
 
 // Re-export session management types (with aliases to avoid conflicts)
 pub use session::{
@@ -47,17 +53,20 @@ pub use session::{
 // Re-export merge types
 pub use merge::{
     Change, ChangeSet, Conflict, ConflictType, DiffEngine, Hunk,
-    MergeRequest, MergeResult, MergeStrategy, MergedEntity,
+    MergeFailure, MergeRequest, MergeResult, MergeStrategy, MergedEntity,
     Operation, ResolutionType, SemanticAnalyzer, VerificationResult,
 };
 
 pub use merge_engine::MergeEngine;
+pub use locks::{LeaseLockConfig, LeaseLockManager, LockLease};
+pub use semantic_diff::{EntityChange, EntityConflict, EntityKind, SemanticDiff, SemanticMerge};
 
 // Re-export Qdrant types
 pub use qdrant::{
     CollectionConfig, CollectionStats, DistanceMetric, HnswConfig, OptimizerConfig,
     QdrantClient, QdrantConfig,
 };
+pub use s3::{S3Client, S3Config};
 
 /// Re-export commonly used types
 pub mod prelude {
@@ -74,6 +83,7 @@ pub mod prelude {
         PoolStatistics, ResourceLimits, RetryPolicy, Transaction,
         TransactionOperation, TransactionStatus,
     };
+    pub use crate::transaction::{BufferedTransaction, QueuedOperation};
 
     // Session management
     pub use crate::session::{
@@ -86,10 +96,11 @@ pub mod prelude {
     // Merge operations
     pub use crate::merge::{
         Change, ChangeSet, Conflict, ConflictType, DiffEngine, Hunk,
-        MergeRequest, MergeResult, MergeStrategy, MergedEntity,
+        MergeFailure, MergeRequest, MergeResult, MergeStrategy, MergedEntity,
         Operation, ResolutionType, SemanticAnalyzer, VerificationResult,
     };
     pub use crate::merge_engine::MergeEngine;
+    pub use crate::semantic_diff::{EntityChange, EntityConflict, EntityKind, SemanticDiff, SemanticMerge};
 
     // Qdrant
     pub use crate::qdrant::{
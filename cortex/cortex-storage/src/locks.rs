@@ -7,14 +7,16 @@
 //! - Deadlock detection using wait-for graphs
 //! - Automatic timeout and cleanup
 //! - Session-based lock management
+//! - Storage-backed lease locks shared between processes ([`LeaseLockManager`])
 
+use crate::connection_pool::ConnectionManager;
 use chrono::{DateTime, Duration as ChronoDuration, Utc};
 use cortex_core::error::{CortexError, Result};
 use dashmap::DashMap;
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet, VecDeque};
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use tracing::{debug, info, warn};
@@ -934,6 +936,491 @@ impl LockManager {
     }
 }
 
+// ==============================================================================
+// Lease Locks
+// ==============================================================================
+
+/// Lease lock fields as stored, in [`EntityLock`] layout
+const LEASE_FIELDS: &str =
+    "lock_id, entity_id, entity_type, lock_type, holder_session, acquired_at, expires_at, metadata";
+
+/// Configuration for [`LeaseLockManager`]
+#[derive(Debug, Clone)]
+pub struct LeaseLockConfig {
+    /// Lease TTL when an acquisition does not specify one
+    pub default_ttl: Duration,
+    /// How often a blocked acquisition retries and refreshes its wait edges
+    pub poll_interval: Duration,
+    /// How often [`LeaseLockManager::run_deadlock_detection_loop`] runs
+    pub detection_interval: Duration,
+    /// Wait edges not refreshed within this window belong to a waiter that
+    /// has gone away and are ignored by deadlock detection
+    pub wait_edge_ttl: Duration,
+}
+
+impl Default for LeaseLockConfig {
+    fn default() -> Self {
+        Self {
+            default_ttl: Duration::from_secs(30),
+            poll_interval: Duration::from_millis(50),
+            detection_interval: Duration::from_secs(1),
+            wait_edge_ttl: Duration::from_secs(5),
+        }
+    }
+}
+
+/// Storage-backed lock manager for locks shared between processes.
+///
+/// Locks are leases in the `lease_lock` table. A holder keeps its lease
+/// alive through the renewal task owned by the [`LockLease`] returned from
+/// [`acquire`](Self::acquire); once a lease expires (for example because its
+/// holder crashed) the next acquisition on the entity reclaims it.
+///
+/// Blocked acquisitions record `waiter -> holder` edges in the `lock_wait`
+/// table. [`detect_deadlocks`](Self::detect_deadlocks) builds a
+/// [`WaitForGraph`] from those edges and, for each cycle, aborts the
+/// acquisition that started waiting last, which then fails with
+/// [`CortexError::DeadlockDetected`].
+pub struct LeaseLockManager {
+    storage: Arc<ConnectionManager>,
+    config: LeaseLockConfig,
+}
+
+/// Outcome of a single acquisition attempt
+enum LeaseAttempt {
+    Acquired(EntityLock),
+    Blocked(Vec<EntityLock>),
+}
+
+impl LeaseLockManager {
+    pub fn new(storage: Arc<ConnectionManager>, config: LeaseLockConfig) -> Self {
+        Self { storage, config }
+    }
+
+    /// Acquire a lease on `request.entity_id`, waiting up to `request.timeout`
+    /// (no wait if zero). The lease lasts `ttl` (the configured default if
+    /// zero) and is renewed in the background until the returned handle is
+    /// released or dropped.
+    pub async fn acquire(
+        &self,
+        session: &SessionId,
+        request: LockRequest,
+        ttl: Duration,
+    ) -> Result<LockLease> {
+        let ttl = if ttl.is_zero() { self.config.default_ttl } else { ttl };
+        let deadline = tokio::time::Instant::now() + request.timeout;
+        let since = surrealdb::sql::Datetime::from(Utc::now());
+
+        // Leftovers from an attempt that never cleaned up must not abort this one
+        self.clear_waits(session).await?;
+
+        loop {
+            let attempt = match self.try_acquire(session, &request, ttl).await {
+                Ok(attempt) => attempt,
+                // Another acquisition on the entity committed first; retry
+                Err(e) if is_write_conflict(&e) => LeaseAttempt::Blocked(Vec::new()),
+                Err(e) => return Err(e),
+            };
+
+            match attempt {
+                LeaseAttempt::Acquired(lock) => {
+                    self.clear_waits(session).await?;
+                    debug!(
+                        "Lease acquired: {} on entity {} by session {} (ttl {:?})",
+                        lock.lock_id, lock.entity_id, session, ttl
+                    );
+                    return Ok(LockLease::start(self.storage.clone(), lock, ttl));
+                }
+                LeaseAttempt::Blocked(blockers) => {
+                    let holders: Vec<SessionId> = blockers
+                        .into_iter()
+                        .map(|lock| lock.holder_session)
+                        .collect::<HashSet<_>>()
+                        .into_iter()
+                        .collect();
+
+                    if let Some(cycle) = self
+                        .record_waits(session, &request.entity_id, &holders, &since)
+                        .await?
+                    {
+                        self.clear_waits(session).await?;
+                        info!(
+                            "Lock acquisition on {} by session {} aborted to break deadlock {:?}",
+                            request.entity_id, session, cycle
+                        );
+                        return Err(CortexError::deadlock_detected(session.clone(), cycle));
+                    }
+
+                    if tokio::time::Instant::now() >= deadline {
+                        self.clear_waits(session).await?;
+                        return Err(CortexError::timeout(format!(
+                            "Lock acquisition on {} timed out after {:?}",
+                            request.entity_id, request.timeout
+                        )));
+                    }
+
+                    tokio::time::sleep(self.config.poll_interval).await;
+                }
+            }
+        }
+    }
+
+    /// Single atomic attempt: reclaim expired leases on the entity, then
+    /// create ours unless an incompatible lease from another session remains
+    async fn try_acquire(
+        &self,
+        session: &SessionId,
+        request: &LockRequest,
+        ttl: Duration,
+    ) -> Result<LeaseAttempt> {
+        let lock_id = uuid::Uuid::new_v4().to_string();
+        let conflicting = "entity_id = $entity_id AND holder_session != $session_id \
+                           AND (lock_type = 'write' OR $lock_type = 'write')";
+
+        // Bumping the entity row makes concurrent attempts on the same entity
+        // conflict instead of both seeing no blockers
+        let query = format!(
+            "BEGIN TRANSACTION;
+             UPSERT type::thing('lock_entity', $entity_id) SET version = (version ?? 0) + 1;
+             DELETE lease_lock WHERE entity_id = $entity_id AND expires_at <= time::now();
+             IF array::len((SELECT VALUE lock_id FROM lease_lock WHERE {conflicting})) = 0 {{
+                 CREATE type::thing('lease_lock', $lock_id) SET
+                     lock_id = $lock_id,
+                     entity_id = $entity_id,
+                     entity_type = $entity_type,
+                     lock_type = $lock_type,
+                     holder_session = $session_id,
+                     acquired_at = time::now(),
+                     expires_at = time::now() + duration::from::millis($ttl_ms),
+                     metadata = $metadata;
+             }};
+             SELECT {LEASE_FIELDS} FROM type::thing('lease_lock', $lock_id);
+             SELECT {LEASE_FIELDS} FROM lease_lock WHERE {conflicting};
+             COMMIT TRANSACTION;"
+        );
+
+        let conn = self.storage.acquire().await?;
+        let mut response = conn
+            .query(query)
+            .bind(("lock_id", lock_id.clone()))
+            .bind(("entity_id", request.entity_id.clone()))
+            .bind(("entity_type", request.entity_type))
+            .bind(("lock_type", request.lock_type))
+            .bind(("session_id", session.clone()))
+            .bind(("ttl_ms", ttl.as_millis() as i64))
+            .bind(("metadata", request.metadata.clone().unwrap_or_default()))
+            .await?;
+
+        // Ours is read by record id: an index scan in the same transaction
+        // can miss the row just created after reclaiming an expired lease
+        let last = response.num_statements().saturating_sub(1);
+        let acquired: Option<EntityLock> = response
+            .take(last - 1)
+            .map_err(|e| CortexError::database(format!("Failed to acquire lease: {}", e)))?;
+        let blockers: Vec<EntityLock> = response
+            .take(last)
+            .map_err(|e| CortexError::database(format!("Failed to acquire lease: {}", e)))?;
+
+        match acquired {
+            Some(lock) => Ok(LeaseAttempt::Acquired(lock)),
+            None => Ok(LeaseAttempt::Blocked(blockers)),
+        }
+    }
+
+    /// Replace the session's wait edges with edges to `holders`. Returns the
+    /// cycle if the detector has aborted this acquisition.
+    async fn record_waits(
+        &self,
+        session: &SessionId,
+        entity_id: &str,
+        holders: &[SessionId],
+        since: &surrealdb::sql::Datetime,
+    ) -> Result<Option<Vec<SessionId>>> {
+        let conn = self.storage.acquire().await?;
+        let mut response = conn
+            .query(
+                "DELETE lock_wait WHERE waiter = $session_id AND holder NOTINSIDE $holders AND aborted != true;
+                 FOR $holder IN $holders {
+                     UPSERT type::thing('lock_wait', [$session_id, $holder]) SET
+                         waiter = $session_id,
+                         holder = $holder,
+                         entity_id = $entity_id,
+                         since = $since,
+                         updated_at = time::now(),
+                         aborted = aborted ?? false;
+                 };
+                 SELECT VALUE cycle FROM lock_wait WHERE waiter = $session_id AND aborted = true LIMIT 1;",
+            )
+            .bind(("session_id", session.clone()))
+            .bind(("holders", holders.to_vec()))
+            .bind(("entity_id", entity_id.to_string()))
+            .bind(("since", since.clone()))
+            .await?;
+
+        let cycles: Vec<Vec<SessionId>> = response
+            .take(2)
+            .map_err(|e| CortexError::database(format!("Failed to record lock wait: {}", e)))?;
+        Ok(cycles.into_iter().next())
+    }
+
+    async fn clear_waits(&self, session: &SessionId) -> Result<()> {
+        let conn = self.storage.acquire().await?;
+        conn.query("DELETE lock_wait WHERE waiter = $session_id")
+            .bind(("session_id", session.clone()))
+            .await?
+            .check()
+            .map_err(|e| CortexError::database(format!("Failed to clear lock waits: {}", e)))?;
+        Ok(())
+    }
+
+    /// Find wait-for cycles among live waiters and abort the youngest
+    /// acquisition in each. Returns the aborted sessions.
+    pub async fn detect_deadlocks(&self) -> Result<Vec<SessionId>> {
+        #[derive(Deserialize)]
+        struct WaitEdge {
+            waiter: SessionId,
+            holder: SessionId,
+            since: DateTime<Utc>,
+        }
+
+        let edges: Vec<WaitEdge> = {
+            let conn = self.storage.acquire().await?;
+            conn.query(
+                "SELECT waiter, holder, since FROM lock_wait \
+                 WHERE aborted != true AND updated_at > time::now() - duration::from::millis($stale_ms)",
+            )
+            .bind(("stale_ms", self.config.wait_edge_ttl.as_millis() as i64))
+            .await?
+            .take(0)
+            .map_err(|e| CortexError::database(format!("Failed to load lock waits: {}", e)))?
+        };
+
+        let mut graph = WaitForGraph::new();
+        let mut started: HashMap<SessionId, DateTime<Utc>> = HashMap::new();
+        for edge in edges {
+            started.insert(edge.waiter.clone(), edge.since);
+            graph.add_wait_edge(edge.waiter, edge.holder);
+        }
+
+        let mut victims = Vec::new();
+        while let Some(cycle) = graph.detect_cycle() {
+            let victim = youngest_waiter(&cycle, &started);
+            warn!(
+                "Deadlock detected involving {} sessions: {:?}, aborting {}",
+                cycle.len(),
+                cycle,
+                victim
+            );
+
+            let conn = self.storage.acquire().await?;
+            conn.query("UPDATE lock_wait SET aborted = true, cycle = $cycle WHERE waiter = $victim")
+                .bind(("victim", victim.clone()))
+                .bind(("cycle", cycle))
+                .await?
+                .check()
+                .map_err(|e| CortexError::database(format!("Failed to abort lock wait: {}", e)))?;
+
+            graph.remove_session(&victim);
+            victims.push(victim);
+        }
+
+        Ok(victims)
+    }
+
+    /// Run deadlock detection at the configured interval (background task)
+    pub async fn run_deadlock_detection_loop(&self) {
+        let interval = self.config.detection_interval;
+        info!("Starting lease deadlock detection loop with interval {:?}", interval);
+
+        loop {
+            tokio::time::sleep(interval).await;
+
+            if let Err(e) = self.detect_deadlocks().await {
+                warn!("Lease deadlock detection failed: {}", e);
+            }
+        }
+    }
+
+    /// Every lease in the lock table, including expired ones not yet reclaimed
+    pub async fn list_locks(&self) -> Result<Vec<EntityLock>> {
+        let conn = self.storage.acquire().await?;
+        conn.query(format!("SELECT {LEASE_FIELDS} FROM lease_lock ORDER BY acquired_at"))
+            .await?
+            .take(0)
+            .map_err(|e| CortexError::database(format!("Failed to list leases: {}", e)))
+    }
+
+    /// Delete every expired lease. Returns the number reclaimed.
+    pub async fn reclaim_expired(&self) -> Result<usize> {
+        let conn = self.storage.acquire().await?;
+        let reclaimed: Vec<LockId> = conn
+            .query("DELETE lease_lock WHERE expires_at <= time::now() RETURN BEFORE")
+            .await?
+            .take((0, "lock_id"))
+            .map_err(|e| CortexError::database(format!("Failed to reclaim leases: {}", e)))?;
+
+        if !reclaimed.is_empty() {
+            info!("Reclaimed {} expired leases", reclaimed.len());
+        }
+        Ok(reclaimed.len())
+    }
+}
+
+/// Session in `cycle` that started waiting most recently
+fn youngest_waiter(cycle: &[SessionId], started: &HashMap<SessionId, DateTime<Utc>>) -> SessionId {
+    cycle
+        .iter()
+        .max_by_key(|session| started.get(*session))
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Optimistic transaction conflicts are retryable rather than fatal. Every
+/// statement of a conflicting transaction reports the failed transaction.
+fn is_write_conflict(error: &CortexError) -> bool {
+    let message = error.to_string().to_lowercase();
+    message.contains("conflict") || message.contains("can be retried") || message.contains("failed transaction")
+}
+
+/// A held lease. Dropping the handle stops renewal and releases the lease
+/// in the background; call [`release`](Self::release) to release it
+/// synchronously and observe errors.
+pub struct LockLease {
+    storage: Arc<ConnectionManager>,
+    lock: EntityLock,
+    lost: Arc<AtomicBool>,
+    renewal: Option<tokio::task::JoinHandle<()>>,
+}
+
+impl std::fmt::Debug for LockLease {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LockLease")
+            .field("lock", &self.lock)
+            .field("lost", &self.is_lost())
+            .finish_non_exhaustive()
+    }
+}
+
+impl LockLease {
+    /// Start renewing `lock` every third of its TTL
+    fn start(storage: Arc<ConnectionManager>, lock: EntityLock, ttl: Duration) -> Self {
+        let lost = Arc::new(AtomicBool::new(false));
+        let renewal = tokio::spawn(renew_lease(
+            storage.clone(),
+            lock.lock_id.clone(),
+            lock.holder_session.clone(),
+            ttl,
+            lost.clone(),
+        ));
+
+        Self {
+            storage,
+            lock,
+            lost,
+            renewal: Some(renewal),
+        }
+    }
+
+    /// The lease as acquired
+    pub fn lock(&self) -> &EntityLock {
+        &self.lock
+    }
+
+    /// Whether renewal found the lease expired or taken over. A lost lease
+    /// no longer protects the entity.
+    pub fn is_lost(&self) -> bool {
+        self.lost.load(Ordering::Acquire)
+    }
+
+    /// Stop renewing and delete the lease
+    pub async fn release(mut self) -> Result<()> {
+        if let Some(renewal) = self.renewal.take() {
+            renewal.abort();
+        }
+        delete_lease(&self.storage, &self.lock.lock_id, &self.lock.holder_session).await
+    }
+}
+
+impl Drop for LockLease {
+    fn drop(&mut self) {
+        let Some(renewal) = self.renewal.take() else {
+            return;
+        };
+        renewal.abort();
+
+        // Without a runtime the lease simply expires
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            let storage = self.storage.clone();
+            let lock_id = self.lock.lock_id.clone();
+            let session = self.lock.holder_session.clone();
+            runtime.spawn(async move {
+                if let Err(e) = delete_lease(&storage, &lock_id, &session).await {
+                    warn!("Failed to release dropped lease {}: {}", lock_id, e);
+                }
+            });
+        }
+    }
+}
+
+async fn delete_lease(storage: &ConnectionManager, lock_id: &str, session: &SessionId) -> Result<()> {
+    let conn = storage.acquire().await?;
+    conn.query("DELETE type::thing('lease_lock', $lock_id) WHERE holder_session = $session_id")
+        .bind(("lock_id", lock_id.to_string()))
+        .bind(("session_id", session.clone()))
+        .await?
+        .check()
+        .map_err(|e| CortexError::database(format!("Failed to release lease {}: {}", lock_id, e)))?;
+
+    debug!("Lease released: {} by session {}", lock_id, session);
+    Ok(())
+}
+
+/// Renewal task body: extend the lease until it is found missing or expired
+async fn renew_lease(
+    storage: Arc<ConnectionManager>,
+    lock_id: LockId,
+    session: SessionId,
+    ttl: Duration,
+    lost: Arc<AtomicBool>,
+) {
+    let mut ticker = tokio::time::interval(ttl / 3);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    ticker.tick().await;
+
+    loop {
+        ticker.tick().await;
+
+        let renewed: Result<Vec<u32>> = async {
+            let conn = storage.acquire().await?;
+            conn.query(
+                "UPDATE type::thing('lease_lock', $lock_id) SET
+                     expires_at = time::now() + duration::from::millis($ttl_ms),
+                     metadata.renewal_count += 1
+                 WHERE holder_session = $session_id AND expires_at > time::now()
+                 RETURN VALUE metadata.renewal_count",
+            )
+            .bind(("lock_id", lock_id.clone()))
+            .bind(("session_id", session.clone()))
+            .bind(("ttl_ms", ttl.as_millis() as i64))
+            .await?
+            .take(0)
+            .map_err(|e| CortexError::database(format!("Failed to renew lease: {}", e)))
+        }
+        .await;
+
+        match renewed {
+            Ok(counts) if counts.is_empty() => {
+                warn!("Lease {} held by session {} was lost before renewal", lock_id, session);
+                lost.store(true, Ordering::Release);
+                return;
+            }
+            Ok(_) => {}
+            // Transient failure; the lease stays valid until it expires
+            Err(e) => warn!("Failed to renew lease {}: {}", lock_id, e),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1105,4 +1592,163 @@ mod tests {
         let locks = manager.list_locks().unwrap();
         assert_eq!(locks.len(), 2);
     }
+
+    async fn lease_manager() -> LeaseLockManager {
+        use crate::connection_pool::{ConnectionMode, DatabaseConfig, PoolConfig};
+
+        // Each in-memory connection is its own database, so share one
+        let config = DatabaseConfig {
+            connection_mode: ConnectionMode::InMemory,
+            pool_config: PoolConfig {
+                min_connections: 1,
+                max_connections: 1,
+                warm_connections: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let storage = Arc::new(ConnectionManager::new(config).await.unwrap());
+        LeaseLockManager::new(
+            storage,
+            LeaseLockConfig {
+                poll_interval: Duration::from_millis(10),
+                ..Default::default()
+            },
+        )
+    }
+
+    fn write_request(entity_id: &str, timeout: Duration) -> LockRequest {
+        LockRequest {
+            entity_id: entity_id.to_string(),
+            entity_type: EntityType::CodeUnit,
+            lock_type: LockType::Write,
+            timeout,
+            metadata: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lease_blocks_until_released() {
+        let manager = lease_manager().await;
+        let (a, b) = ("a".to_string(), "b".to_string());
+
+        let lease = manager
+            .acquire(&a, write_request("entity1", Duration::ZERO), Duration::from_secs(30))
+            .await
+            .unwrap();
+        assert_eq!(lease.lock().holder_session, a);
+
+        let err = manager
+            .acquire(&b, write_request("entity1", Duration::from_millis(50)), Duration::ZERO)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CortexError::Timeout(_)), "unexpected error: {err}");
+
+        let locks = manager.list_locks().await.unwrap();
+        assert_eq!(locks.len(), 1);
+        assert_eq!(locks[0].lock_id, lease.lock().lock_id);
+
+        lease.release().await.unwrap();
+        let lease = manager
+            .acquire(&b, write_request("entity1", Duration::ZERO), Duration::ZERO)
+            .await
+            .unwrap();
+        assert_eq!(lease.lock().holder_session, b);
+    }
+
+    #[tokio::test]
+    async fn test_expired_lease_is_reclaimed() {
+        let manager = lease_manager().await;
+        let (a, b) = ("a".to_string(), "b".to_string());
+
+        // A holder that crashed: the lease exists but nothing renews it
+        let attempt = manager
+            .try_acquire(&a, &write_request("entity1", Duration::ZERO), Duration::from_millis(50))
+            .await
+            .unwrap();
+        assert!(matches!(attempt, LeaseAttempt::Acquired(_)));
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let lease = manager
+            .acquire(&b, write_request("entity1", Duration::ZERO), Duration::from_secs(30))
+            .await
+            .unwrap();
+        assert_eq!(lease.lock().holder_session, b);
+        assert_eq!(manager.list_locks().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_renewal_keeps_lease_alive() {
+        let manager = lease_manager().await;
+        let (a, b) = ("a".to_string(), "b".to_string());
+
+        let lease = manager
+            .acquire(&a, write_request("entity1", Duration::ZERO), Duration::from_millis(150))
+            .await
+            .unwrap();
+
+        tokio::time::sleep(Duration::from_millis(400)).await;
+
+        assert!(!lease.is_lost());
+        assert!(manager
+            .acquire(&b, write_request("entity1", Duration::ZERO), Duration::ZERO)
+            .await
+            .is_err());
+        let locks = manager.list_locks().await.unwrap();
+        assert!(locks[0].metadata.renewal_count >= 2);
+        assert_eq!(manager.reclaim_expired().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_shared_leases_coexist() {
+        let manager = lease_manager().await;
+        let read = |entity: &str| LockRequest {
+            lock_type: LockType::Read,
+            ..write_request(entity, Duration::ZERO)
+        };
+
+        let _first = manager.acquire(&"a".to_string(), read("entity1"), Duration::ZERO).await.unwrap();
+        let _second = manager.acquire(&"b".to_string(), read("entity1"), Duration::ZERO).await.unwrap();
+
+        assert_eq!(manager.list_locks().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_deadlock_aborts_youngest_acquisition() {
+        let manager = Arc::new(lease_manager().await);
+        let (a, b) = ("a".to_string(), "b".to_string());
+        let wait = Duration::from_secs(5);
+
+        let a_holds_x = manager.acquire(&a, write_request("x", Duration::ZERO), Duration::ZERO).await.unwrap();
+        let b_holds_y = manager.acquire(&b, write_request("y", Duration::ZERO), Duration::ZERO).await.unwrap();
+
+        let a_wants_y = tokio::spawn({
+            let (manager, a) = (manager.clone(), a.clone());
+            async move { manager.acquire(&a, write_request("y", wait), Duration::ZERO).await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let b_wants_x = tokio::spawn({
+            let (manager, b) = (manager.clone(), b.clone());
+            async move { manager.acquire(&b, write_request("x", wait), Duration::ZERO).await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        assert_eq!(manager.detect_deadlocks().await.unwrap(), vec![b.clone()]);
+
+        match b_wants_x.await.unwrap() {
+            Err(CortexError::DeadlockDetected { victim, cycle }) => {
+                assert_eq!(victim, b);
+                assert_eq!(cycle.len(), 2);
+            }
+            other => panic!("expected deadlock error, got {:?}", other.map(|l| l.lock().clone())),
+        }
+
+        // The victim backs off, letting the older acquisition through
+        b_holds_y.release().await.unwrap();
+        let lease = a_wants_y.await.unwrap().unwrap();
+        assert_eq!(lease.lock().entity_id, "y");
+        assert!(manager.detect_deadlocks().await.unwrap().is_empty());
+        drop(a_holds_x);
+    }
 }
@@ -17,6 +17,7 @@
 //!                   AST Comparison
 //! ```
 
+use crate::semantic_diff::{self, SemanticDiff, SemanticMerge};
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use cortex_core::types::{CodeUnit, Language};
@@ -101,6 +102,9 @@ pub struct MergeResult {
     pub verification: Option<VerificationResult>,
     /// Merged entities (for inspection)
     pub merged_entities: Vec<MergedEntity>,
+    /// Changes whose operations were rolled back while the rest committed
+    #[serde(default)]
+    pub failures: Vec<MergeFailure>,
 }
 
 impl MergeResult {
@@ -113,6 +117,7 @@ impl MergeResult {
             duration_ms: 0,
             verification: None,
             merged_entities: Vec::new(),
+            failures: Vec::new(),
         }
     }
 
@@ -126,6 +131,7 @@ impl MergeResult {
             duration_ms: 0,
             verification: None,
             merged_entities: Vec::new(),
+            failures: Vec::new(),
         }
     }
 
@@ -138,6 +144,7 @@ impl MergeResult {
             duration_ms: 0,
             verification: None,
             merged_entities: Vec::new(),
+            failures: Vec::new(),
         }
     }
 }
@@ -159,6 +166,14 @@ pub struct MergedEntity {
     pub had_conflict: bool,
 }
 
+/// A change that could not be applied during a merge
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeFailure {
+    pub entity_id: String,
+    pub file_path: String,
+    pub error: String,
+}
+
 /// How a conflict was resolved
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -248,6 +263,8 @@ pub enum ConflictType {
     Semantic,
     /// Type signature changed incompatibly
     SignatureConflict,
+    /// Both sides changed the same function (or struct) in different ways
+    BothModifiedSameFunction,
     /// Dependency version conflict
     DependencyConflict,
 }
@@ -260,6 +277,7 @@ impl std::fmt::Display for ConflictType {
             ConflictType::AddAdd => write!(f, "Add-Add"),
             ConflictType::Semantic => write!(f, "Semantic"),
             ConflictType::SignatureConflict => write!(f, "Signature"),
+            ConflictType::BothModifiedSameFunction => write!(f, "Same-Function"),
             ConflictType::DependencyConflict => write!(f, "Dependency"),
         }
     }
@@ -524,6 +542,21 @@ impl DiffEngine {
 
         Ok(Some(merged))
     }
+
+    /// Text hunks plus entity-level changes (functions and structs matched
+    /// by name, renames by signature and body similarity) for a source file
+    pub fn semantic_diff(path: &str, base: &str, modified: &str) -> SemanticDiff {
+        SemanticDiff {
+            hunks: Self::diff(base, modified),
+            entity_changes: semantic_diff::entity_changes(path, base, modified),
+        }
+    }
+
+    /// Three-way merge at function/struct granularity, for when text hunks
+    /// overlap but the two sides changed different entities
+    pub fn semantic_three_way_merge(path: &str, base: &str, session: &str, main: &str) -> SemanticMerge {
+        semantic_diff::three_way_merge(path, base, session, main)
+    }
 }
 
 // ==============================================================================
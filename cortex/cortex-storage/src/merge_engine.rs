@@ -2,6 +2,8 @@
 
 use crate::connection_pool::ConnectionManager;
 use crate::merge::*;
+use crate::semantic_diff::{EntityConflict, SemanticMerge};
+use crate::transaction::BufferedTransaction;
 use anyhow::{anyhow, Result};
 use chrono::{DateTime, Utc};
 use cortex_core::types::{CodeUnit, Language};
@@ -66,11 +68,12 @@ impl MergeEngine {
                 duration_ms: duration.as_millis() as u64,
                 verification: None,
                 merged_entities: Vec::new(),
+                failures: Vec::new(),
             });
         }
 
         // 4. Apply non-conflicting changes
-        let (applied, merged_entities) = self
+        let (applied, merged_entities, failures) = self
             .apply_changes(&session_changes, &request.target_namespace, &resolved_conflicts)
             .await?;
 
@@ -84,20 +87,22 @@ impl MergeEngine {
         let duration = start.elapsed();
 
         info!(
-            "Merge completed: {} changes applied, {} rejected in {:?}",
+            "Merge completed: {} changes applied, {} rejected, {} failed in {:?}",
             applied,
             resolved_conflicts.len(),
+            failures.len(),
             duration
         );
 
         Ok(MergeResult {
-            success: resolved_conflicts.is_empty(),
+            success: resolved_conflicts.is_empty() && failures.is_empty(),
             conflicts: resolved_conflicts,
             changes_applied: applied,
-            changes_rejected: 0,
+            changes_rejected: failures.len(),
             duration_ms: duration.as_millis() as u64,
             verification,
             merged_entities,
+            failures,
         })
     }
 
@@ -264,19 +269,33 @@ impl MergeEngine {
                 ))
             }
             None => {
-                // Line-level conflict - check semantic conflict
-                Ok(Some(
-                    Conflict::new(
-                        change.entity_id.clone(),
-                        ConflictType::ModifyModify,
-                        change.file_path.clone(),
-                    )
-                    .with_versions(
-                        Some(base_content.to_string()),
-                        Some(session_content.to_string()),
-                        Some(main_content.to_string()),
-                    ),
-                ))
+                // Line-level conflict - the sides may still have touched different entities
+                let conflict = Conflict::new(
+                    change.entity_id.clone(),
+                    ConflictType::ModifyModify,
+                    change.file_path.clone(),
+                )
+                .with_versions(
+                    Some(base_content.to_string()),
+                    Some(session_content.to_string()),
+                    Some(main_content.to_string()),
+                );
+
+                match DiffEngine::semantic_three_way_merge(
+                    &change.file_path,
+                    base_content,
+                    session_content,
+                    main_content,
+                ) {
+                    SemanticMerge::Merged(merged) => {
+                        debug!("Entity-level merge resolved overlapping hunks in {}", change.file_path);
+                        Ok(Some(conflict.with_resolution(merged)))
+                    }
+                    SemanticMerge::Conflicts(entity_conflicts) => {
+                        Ok(Some(with_entity_conflicts(conflict, &entity_conflicts)))
+                    }
+                    SemanticMerge::Unsupported => Ok(Some(conflict)),
+                }
             }
         }
     }
@@ -335,7 +354,10 @@ impl MergeEngine {
                         unresolved.push(conflict);
                     }
                 }
-                ConflictType::Semantic | ConflictType::SignatureConflict | ConflictType::DependencyConflict => {
+                ConflictType::Semantic
+                | ConflictType::SignatureConflict
+                | ConflictType::DependencyConflict
+                | ConflictType::BothModifiedSameFunction => {
                     // Cannot auto-resolve semantic conflicts
                     unresolved.push(conflict);
                 }
@@ -361,7 +383,19 @@ impl MergeEngine {
                             &conflict.session_version,
                             &conflict.main_version,
                         ) {
-                            match DiffEngine::three_way_line_merge(base, session, main)? {
+                            let merged = match DiffEngine::three_way_line_merge(base, session, main)? {
+                                Some(merged) => Some(merged),
+                                None => match DiffEngine::semantic_three_way_merge(
+                                    &conflict.file_path,
+                                    base,
+                                    session,
+                                    main,
+                                ) {
+                                    SemanticMerge::Merged(merged) => Some(merged),
+                                    _ => None,
+                                },
+                            };
+                            match merged {
                                 Some(merged) => {
                                     conflict.resolution = Some(merged);
                                 }
@@ -389,7 +423,9 @@ impl MergeEngine {
                         unresolved.push(conflict);
                     }
                 }
-                ConflictType::Semantic | ConflictType::SignatureConflict => {
+                ConflictType::Semantic
+                | ConflictType::SignatureConflict
+                | ConflictType::BothModifiedSameFunction => {
                     // Semantic conflicts require manual resolution
                     unresolved.push(conflict);
                 }
@@ -405,17 +441,21 @@ impl MergeEngine {
 
     /// Apply changes to target namespace
     ///
-    /// FIXED: Wraps all changes in a single database transaction with proper error handling
-    /// This ensures atomicity - either all changes are applied successfully, or none are.
-    /// On error, the transaction is automatically rolled back to maintain consistency.
+    /// All changes are queued in one buffered transaction. Each change gets
+    /// its own savepoint, so a change that fails validation rolls back only
+    /// its own operations and is reported as a failure while the rest of the
+    /// changeset still commits atomically.
     ///
-    /// Priority: P1 - FIXED
+    /// Savepoints only cover failures detected client-side while queueing
+    /// (such as `validate_merged_content`). Statements run on the server only
+    /// at commit, so a statement the database rejects aborts the whole
+    /// transaction and the merge fails with nothing applied.
     async fn apply_changes(
         &self,
         changes: &[Change],
         target_namespace: &str,
         resolved_conflicts: &[Conflict],
-    ) -> Result<(usize, Vec<MergedEntity>)> {
+    ) -> Result<(usize, Vec<MergedEntity>, Vec<MergeFailure>)> {
         debug!(
             "Applying {} changes to namespace {} in atomic transaction",
             changes.len(),
@@ -423,11 +463,11 @@ impl MergeEngine {
         );
 
         let conn = self.storage.acquire().await?;
-
-        // FIXED: Begin database transaction
-        // All subsequent operations will be part of this transaction
-        conn.begin_transaction().await
-            .map_err(|e| anyhow!("Failed to begin transaction: {}", e))?;
+        conn.connection()
+            .use_ns(target_namespace)
+            .use_db("main")
+            .await
+            .map_err(|e| anyhow!("Failed to switch to namespace {}: {}", target_namespace, e))?;
 
         // Build conflict resolution map
         let conflict_map: HashMap<String, &Conflict> = resolved_conflicts
@@ -435,109 +475,103 @@ impl MergeEngine {
             .map(|c| (c.entity_id.clone(), c))
             .collect();
 
-        // FIXED: Execute all changes within transaction with proper error handling
-        let apply_result: Result<(usize, Vec<MergedEntity>)> = async {
-            let mut applied = 0;
-            let mut merged_entities = Vec::new();
-
-            for change in changes {
-                // Check if this change has a conflict
-                if let Some(conflict) = conflict_map.get(&change.entity_id) {
-                    // Use resolved version if available
-                    if let Some(resolution) = &conflict.resolution {
-                        self.apply_change_content(&change.entity_id, resolution, target_namespace)
-                            .await?;
-                        applied += 1;
-
-                        merged_entities.push(MergedEntity {
-                            entity_id: change.entity_id.clone(),
-                            entity_type: "code_unit".to_string(),
-                            resolution_type: ResolutionType::AutoMerged,
-                            had_conflict: true,
-                        });
-                    }
-                    // Otherwise skip (conflict not resolved)
-                } else {
-                    // No conflict - apply directly
-                    if let Some(content) = &change.new_content {
-                        self.apply_change_content(&change.entity_id, content, target_namespace)
-                            .await?;
-                        applied += 1;
-
-                        merged_entities.push(MergedEntity {
-                            entity_id: change.entity_id.clone(),
-                            entity_type: "code_unit".to_string(),
-                            resolution_type: ResolutionType::NoConflict,
-                            had_conflict: false,
-                        });
-                    } else if change.operation == Operation::Delete {
-                        self.delete_entity(&change.entity_id, target_namespace)
-                            .await?;
-                        applied += 1;
-
-                        merged_entities.push(MergedEntity {
-                            entity_id: change.entity_id.clone(),
-                            entity_type: "code_unit".to_string(),
-                            resolution_type: ResolutionType::NoConflict,
-                            had_conflict: false,
-                        });
-                    }
-                }
-            }
-            Ok((applied, merged_entities))
-        }.await;
-
-        // FIXED: Commit or rollback based on result
-        // This ensures database consistency even on partial failures
-        match apply_result {
-            Ok((applied_count, entities)) => {
-                // All changes succeeded - commit the transaction
-                conn.commit_transaction().await
-                    .map_err(|e| anyhow!("Failed to commit transaction: {}", e))?;
-
-                info!(
-                    "Successfully committed {} changes to namespace {} in atomic transaction",
-                    applied_count, target_namespace
-                );
+        let mut tx = conn.transaction();
+        let mut applied = 0;
+        let mut merged_entities = Vec::new();
+        let mut failures = Vec::new();
+
+        for (index, change) in changes.iter().enumerate() {
+            // Content to write (None = delete) and how the change was resolved
+            let planned = match conflict_map.get(&change.entity_id) {
+                // Use resolved version if available, otherwise skip (conflict not resolved)
+                Some(conflict) => conflict
+                    .resolution
+                    .as_deref()
+                    .map(|resolution| (Some(resolution), ResolutionType::AutoMerged, true)),
+                None => match (&change.new_content, &change.operation) {
+                    (Some(content), _) => Some((Some(content.as_str()), ResolutionType::NoConflict, false)),
+                    (None, Operation::Delete) => Some((None, ResolutionType::NoConflict, false)),
+                    (None, _) => None,
+                },
+            };
+            let Some((content, resolution_type, had_conflict)) = planned else {
+                continue;
+            };
 
-                Ok((applied_count, entities))
-            }
-            Err(e) => {
-                // An error occurred - rollback all changes
-                warn!(
-                    "Error applying changes to namespace {}, rolling back transaction: {}",
-                    target_namespace, e
-                );
+            let savepoint = format!("change_{}", index);
+            tx.savepoint(savepoint.as_str());
+            let result = match content {
+                Some(content) => self.apply_change_content(&mut tx, &change.entity_id, content),
+                None => self.delete_entity(&mut tx, &change.entity_id),
+            };
 
-                if let Err(rollback_err) = conn.rollback_transaction().await {
-                    // Log rollback failure but still return the original error
-                    warn!(
-                        "Failed to rollback transaction: {} (original error: {})",
-                        rollback_err, e
-                    );
+            match result {
+                Ok(()) => {
+                    tx.release(&savepoint)?;
+                    applied += 1;
+                    merged_entities.push(MergedEntity {
+                        entity_id: change.entity_id.clone(),
+                        entity_type: "code_unit".to_string(),
+                        resolution_type,
+                        had_conflict,
+                    });
+                }
+                Err(e) => {
+                    warn!("Rolling back change to {}: {}", change.entity_id, e);
+                    tx.rollback_to(&savepoint)?;
+                    tx.release(&savepoint)?;
+                    failures.push(MergeFailure {
+                        entity_id: change.entity_id.clone(),
+                        file_path: change.file_path.clone(),
+                        error: e.to_string(),
+                    });
                 }
-
-                Err(e)
             }
         }
+
+        // A failed commit applies nothing, so the whole merge fails
+        tx.commit()
+            .await
+            .map_err(|e| anyhow!("Failed to commit merge into namespace {}: {}", target_namespace, e))?;
+
+        info!(
+            "Successfully committed {} changes to namespace {} in atomic transaction ({} failed)",
+            applied,
+            target_namespace,
+            failures.len()
+        );
+
+        Ok((applied, merged_entities, failures))
     }
 
-    /// Apply change content to entity
-    async fn apply_change_content(
+    /// Queue an update of an entity's content
+    fn apply_change_content(
         &self,
-        _entity_id: &str,
-        _content: &str,
-        _namespace: &str,
+        tx: &mut BufferedTransaction<'_>,
+        entity_id: &str,
+        content: &str,
     ) -> Result<()> {
-        // In real implementation, would update the entity in the database
-        debug!("Applying change to entity");
+        validate_merged_content(entity_id, content)?;
+
+        debug!("Applying change to entity {}", entity_id);
+        tx.execute(
+            "UPSERT type::thing('code_unit', $entity_id) SET content = $content, updated_at = time::now()",
+            [("entity_id", entity_id), ("content", content)],
+        )?;
         Ok(())
     }
 
-    /// Delete entity from namespace
-    async fn delete_entity(&self, _entity_id: &str, _namespace: &str) -> Result<()> {
-        // In real implementation, would delete from database
-        debug!("Deleting entity");
+    /// Queue deletion of an entity
+    fn delete_entity(&self, tx: &mut BufferedTransaction<'_>, entity_id: &str) -> Result<()> {
+        if entity_id.is_empty() {
+            return Err(anyhow!("Cannot delete an entity without an id"));
+        }
+
+        debug!("Deleting entity {}", entity_id);
+        tx.execute(
+            "DELETE type::thing('code_unit', $entity_id)",
+            [("entity_id", entity_id)],
+        )?;
         Ok(())
     }
 
@@ -654,6 +688,35 @@ impl MergeEngine {
     }
 }
 
+/// Narrow a text conflict to the entities both sides changed
+fn with_entity_conflicts(mut conflict: Conflict, entity_conflicts: &[EntityConflict]) -> Conflict {
+    conflict.conflict_type = if entity_conflicts
+        .iter()
+        .any(|c| c.conflict_type == ConflictType::SignatureConflict)
+    {
+        ConflictType::SignatureConflict
+    } else {
+        ConflictType::BothModifiedSameFunction
+    };
+    let names: Vec<&str> = entity_conflicts.iter().map(|c| c.name.as_str()).collect();
+    conflict.metadata.insert("entities".to_string(), names.join(","));
+    conflict
+}
+
+/// Reject merged content that cannot be written as-is
+fn validate_merged_content(entity_id: &str, content: &str) -> Result<()> {
+    if entity_id.is_empty() {
+        return Err(anyhow!("Cannot apply a change without an entity id"));
+    }
+    if content
+        .lines()
+        .any(|line| line.starts_with("<<<<<<<") || line.starts_with(">>>>>>>"))
+    {
+        return Err(anyhow!("Unresolved conflict markers in {}", entity_id));
+    }
+    Ok(())
+}
+
 // ==============================================================================
 // Semantic Conflict Detection with CodeUnit Integration
 // ==============================================================================
@@ -749,6 +812,8 @@ mod tests {
                 validate_on_checkout: true,
                 recycle_after_uses: None,
                 shutdown_grace_period: Duration::from_secs(10),
+                query_timeout: Some(Duration::from_secs(30)),
+                slow_query_threshold: Some(Duration::from_secs(1)),
             },
             namespace: "test".to_string(),
             database: "test".to_string(),
@@ -831,4 +896,108 @@ mod tests {
         let resolved = engine.three_way_merge(conflicts).await.unwrap();
         assert_eq!(resolved.len(), 0); // Identical content, should resolve
     }
+
+    fn change(entity_id: &str, content: &str) -> Change {
+        Change {
+            entity_id: entity_id.to_string(),
+            operation: Operation::Modify,
+            old_content: None,
+            new_content: Some(content.to_string()),
+            timestamp: Utc::now(),
+            file_path: format!("src/{}.rs", entity_id),
+            language: Language::Rust,
+        }
+    }
+
+    const BASE_FILE: &str = "\
+fn alpha(x: i32) -> i32 {
+    let y = x + 1;
+    y * 2
+}
+
+fn beta(s: &str) -> usize {
+    let n = s.len();
+    n + 1
+}
+";
+
+    #[tokio::test]
+    async fn test_overlapping_hunks_in_different_functions_auto_merge() {
+        let storage = create_test_storage().await;
+        let engine = MergeEngine::new(storage);
+
+        // The inserted line shifts everything after it, so the text hunks overlap
+        let session = BASE_FILE.replace("    let y = x + 1;\n", "    let y = x + 1;\n    let y = y.max(0);\n");
+        let main = BASE_FILE.replace("n + 1", "n + 2");
+        assert!(DiffEngine::three_way_line_merge(BASE_FILE, &session, &main).unwrap().is_none());
+
+        let change = Change::modify(
+            "lib".to_string(),
+            BASE_FILE.to_string(),
+            session.clone(),
+            "src/lib.rs".to_string(),
+            Language::Rust,
+        );
+        let conflict = engine
+            .analyze_conflict(&change, BASE_FILE, &main)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let resolution = conflict.resolution.clone().expect("entity-level merge");
+        assert!(resolution.contains("let y = y.max(0);"));
+        assert!(resolution.contains("n + 2"));
+        assert!(engine.auto_resolve_conflicts(vec![conflict]).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_same_function_edits_are_reported_semantically() {
+        let storage = create_test_storage().await;
+        let engine = MergeEngine::new(storage);
+
+        let session = BASE_FILE.replace("y * 2", "y * 3");
+        let main = BASE_FILE.replace("y * 2", "y * 4");
+        let change = Change::modify(
+            "lib".to_string(),
+            BASE_FILE.to_string(),
+            session,
+            "src/lib.rs".to_string(),
+            Language::Rust,
+        );
+
+        let conflict = engine
+            .analyze_conflict(&change, BASE_FILE, &main)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(conflict.conflict_type, ConflictType::BothModifiedSameFunction);
+        assert_eq!(conflict.metadata.get("entities").map(String::as_str), Some("alpha"));
+        assert!(conflict.resolution.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_failed_entity_rolls_back_only_its_operations() {
+        let storage = create_test_storage().await;
+        let engine = MergeEngine::new(storage);
+
+        let changes = vec![
+            change("alpha", "fn alpha() {}"),
+            change("beta", "<<<<<<< session\nfn beta() {}\n=======\nfn beta2() {}\n>>>>>>> main"),
+            change("gamma", "fn gamma() {}"),
+        ];
+
+        let (applied, merged, failures) = engine
+            .apply_changes(&changes, "merge_test", &[])
+            .await
+            .unwrap();
+
+        assert_eq!(applied, 2);
+        let merged_ids: Vec<&str> = merged.iter().map(|e| e.entity_id.as_str()).collect();
+        assert_eq!(merged_ids, vec!["alpha", "gamma"]);
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].entity_id, "beta");
+        assert_eq!(failures[0].file_path, "src/beta.rs");
+        assert!(failures[0].error.contains("conflict markers"));
+    }
 }
@@ -42,6 +42,9 @@ pub struct QdrantConfig {
     pub timeout: Duration,
     /// Request timeout
     pub request_timeout: Duration,
+    /// Optional S3-compatible destination for snapshot uploads
+    #[serde(default)]
+    pub snapshot_s3: Option<crate::s3::S3Config>,
 }
 
 impl Default for QdrantConfig {
@@ -54,6 +57,7 @@ impl Default for QdrantConfig {
             use_https: false,
             timeout: Duration::from_secs(10),
             request_timeout: Duration::from_secs(60),
+            snapshot_s3: None,
         }
     }
 }
@@ -153,7 +157,6 @@ impl Default for OptimizerConfig {
 /// Qdrant client wrapper with connection pooling
 pub struct QdrantClient {
     client: Arc<Qdrant>,
-    #[allow(dead_code)]
     config: QdrantConfig,
     collections: Arc<RwLock<HashMap<String, CollectionConfig>>>,
 }
@@ -187,6 +190,39 @@ impl QdrantClient {
         })
     }
 
+    /// Configuration this client was created with
+    pub fn config(&self) -> &QdrantConfig {
+        &self.config
+    }
+
+    /// Base URL of the HTTP (REST) API, used for endpoints gRPC lacks
+    fn http_base_url(&self) -> String {
+        format!(
+            "{}://{}:{}",
+            if self.config.use_https { "https" } else { "http" },
+            self.config.host,
+            self.config.port
+        )
+    }
+
+    /// HTTP client carrying the API key header
+    fn http_client(&self) -> Result<reqwest::Client> {
+        let mut headers = reqwest::header::HeaderMap::new();
+        if let Some(ref api_key) = self.config.api_key {
+            headers.insert(
+                "api-key",
+                reqwest::header::HeaderValue::from_str(api_key)
+                    .context("Invalid API key format")?,
+            );
+        }
+
+        reqwest::Client::builder()
+            .timeout(self.config.request_timeout)
+            .default_headers(headers)
+            .build()
+            .context("Failed to build HTTP client")
+    }
+
     /// Health check
     pub async fn health(&self) -> Result<HealthCheckReply> {
         self.client
@@ -345,6 +381,76 @@ impl QdrantClient {
             .collect())
     }
 
+    /// Download a collection snapshot, streaming it to `dest`.
+    /// Returns the number of bytes written.
+    pub async fn download_snapshot(
+        &self,
+        collection_name: &str,
+        snapshot_name: &str,
+        dest: &std::path::Path,
+    ) -> Result<u64> {
+        use tokio::io::AsyncWriteExt;
+
+        let url = format!(
+            "{}/collections/{}/snapshots/{}",
+            self.http_base_url(),
+            collection_name,
+            snapshot_name
+        );
+        info!("Downloading snapshot from: {}", url);
+
+        let mut response = self
+            .http_client()?
+            .get(&url)
+            .send()
+            .await
+            .context("Failed to download snapshot")?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Snapshot download failed with status {}: {}", status, text);
+        }
+
+        let mut file = tokio::fs::File::create(dest)
+            .await
+            .context(format!("Failed to create snapshot file: {:?}", dest))?;
+        let mut written = 0u64;
+        while let Some(chunk) = response.chunk().await.context("Snapshot download interrupted")? {
+            file.write_all(&chunk).await?;
+            written += chunk.len() as u64;
+        }
+        file.flush().await?;
+
+        Ok(written)
+    }
+
+    /// Largest value of an integer timestamp payload field (e.g. `indexed_at`).
+    ///
+    /// Requires an integer payload index on the field. Returns `None` when no
+    /// point carries the field.
+    pub async fn latest_payload_timestamp(&self, collection_name: &str, field: &str) -> Result<Option<i64>> {
+        use qdrant_client::qdrant::{Direction, OrderByBuilder, ScrollPointsBuilder};
+
+        let response = self
+            .client
+            .scroll(
+                ScrollPointsBuilder::new(collection_name)
+                    .limit(1)
+                    .with_payload(true)
+                    .with_vectors(false)
+                    .order_by(OrderByBuilder::new(field).direction(Direction::Desc as i32)),
+            )
+            .await
+            .context(format!("Failed to order {} by {}", collection_name, field))?;
+
+        Ok(response
+            .result
+            .first()
+            .and_then(|point| point.payload.get(field))
+            .and_then(|value| value.as_integer()))
+    }
+
     /// Restore from snapshot file
     ///
     /// This uploads a snapshot file to Qdrant and recovers the collection from it.
@@ -409,14 +515,8 @@ impl QdrantClient {
 
         info!("Restoring to collection: {}", target_collection);
 
-        // Build the HTTP endpoint URL
         // Use the HTTP port from config, not the gRPC port
-        let base_url = format!(
-            "{}://{}:{}",
-            if self.config.use_https { "https" } else { "http" },
-            self.config.host,
-            self.config.port
-        );
+        let base_url = self.http_base_url();
 
         // Set priority parameter (default to "snapshot" which means snapshot data takes precedence)
         let priority_param = if priority.unwrap_or(true) {
@@ -445,24 +545,7 @@ impl QdrantClient {
 
         let form = reqwest::multipart::Form::new().part("snapshot", part);
 
-        // Build HTTP client
-        let client_builder = reqwest::Client::builder()
-            .timeout(self.config.request_timeout);
-
-        // Add API key if configured
-        let mut headers = reqwest::header::HeaderMap::new();
-        if let Some(ref api_key) = self.config.api_key {
-            headers.insert(
-                "api-key",
-                reqwest::header::HeaderValue::from_str(api_key)
-                    .context("Invalid API key format")?,
-            );
-        }
-
-        let http_client = client_builder
-            .default_headers(headers)
-            .build()
-            .context("Failed to build HTTP client")?;
+        let http_client = self.http_client()?;
 
         // Upload the snapshot
         info!("Uploading snapshot file...");
@@ -0,0 +1,462 @@
+//! Minimal S3-compatible object upload for snapshot backups.
+//!
+//! Implements `PutObject` and, for objects above the 5 GB single-request
+//! limit, multipart upload, both signed with AWS Signature Version 4. That is
+//! enough for AWS S3, MinIO, Ceph RGW and other S3-compatible stores.
+//! Requests use path-style addressing (`{endpoint}/{bucket}/{key}`). File
+//! bodies are streamed from disk and signed as `UNSIGNED-PAYLOAD`, so a
+//! snapshot is never held in memory.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use reqwest::{Body, Method};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::SeekFrom;
+use std::path::Path;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio_util::io::ReaderStream;
+use tracing::{info, warn};
+
+/// Largest object a single `PutObject` request may carry
+const MAX_SINGLE_PUT_BYTES: u64 = 5 * 1024 * 1024 * 1024;
+
+/// Smallest part size used for multipart uploads
+const MIN_PART_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Most parts S3 accepts in one multipart upload
+const MAX_PARTS: u64 = 10_000;
+
+/// Payload hash marker for bodies that are streamed rather than hashed up front
+const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+
+/// S3-compatible storage configuration.
+///
+/// Credentials are never serialized; they are resolved from the standard
+/// `AWS_ACCESS_KEY_ID` / `AWS_SECRET_ACCESS_KEY` / `AWS_SESSION_TOKEN`
+/// environment variables.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct S3Config {
+    /// Endpoint URL, e.g. `https://s3.eu-west-1.amazonaws.com` or `http://localhost:9000`
+    pub endpoint: String,
+    /// Bucket name
+    pub bucket: String,
+    /// Signing region
+    pub region: String,
+    /// Key prefix prepended to every object
+    pub prefix: String,
+    #[serde(skip)]
+    pub access_key_id: String,
+    #[serde(skip)]
+    pub secret_access_key: String,
+    #[serde(skip)]
+    pub session_token: Option<String>,
+}
+
+impl S3Config {
+    /// Load from `CORTEX_S3_ENDPOINT`, `CORTEX_S3_BUCKET`, `CORTEX_S3_REGION`
+    /// (default `us-east-1`) and `CORTEX_S3_PREFIX` (default `qdrant-snapshots/`).
+    ///
+    /// Returns `Ok(None)` when no endpoint or bucket is configured, and an error
+    /// when S3 is configured but credentials are missing.
+    pub fn from_env() -> Result<Option<Self>> {
+        let (Ok(endpoint), Ok(bucket)) = (
+            std::env::var("CORTEX_S3_ENDPOINT"),
+            std::env::var("CORTEX_S3_BUCKET"),
+        ) else {
+            return Ok(None);
+        };
+
+        Ok(Some(Self {
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            bucket,
+            region: std::env::var("CORTEX_S3_REGION").unwrap_or_else(|_| "us-east-1".to_string()),
+            prefix: std::env::var("CORTEX_S3_PREFIX").unwrap_or_else(|_| "qdrant-snapshots/".to_string()),
+            access_key_id: std::env::var("AWS_ACCESS_KEY_ID")
+                .context("CORTEX_S3_ENDPOINT is set but AWS_ACCESS_KEY_ID is missing")?,
+            secret_access_key: std::env::var("AWS_SECRET_ACCESS_KEY")
+                .context("CORTEX_S3_ENDPOINT is set but AWS_SECRET_ACCESS_KEY is missing")?,
+            session_token: std::env::var("AWS_SESSION_TOKEN").ok(),
+        }))
+    }
+}
+
+/// Uploads files to an S3-compatible bucket
+pub struct S3Client {
+    config: S3Config,
+    http: reqwest::Client,
+}
+
+impl S3Client {
+    pub fn new(config: S3Config, timeout: Duration) -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .context("Failed to build HTTP client")?;
+        Ok(Self { config, http })
+    }
+
+    /// Full object key for a path relative to the configured prefix
+    pub fn object_key(&self, relative: &str) -> String {
+        format!("{}{}", self.config.prefix, relative)
+    }
+
+    /// Upload a local file to `{prefix}{relative}`. Returns the bytes uploaded.
+    ///
+    /// Files up to 5 GB go up in a single streamed `PutObject`; larger ones
+    /// use a multipart upload, which is aborted if any part fails.
+    pub async fn put_file(&self, relative: &str, path: &Path) -> Result<u64> {
+        let size = tokio::fs::metadata(path)
+            .await
+            .with_context(|| format!("Failed to stat {}", path.display()))?
+            .len();
+        let key = self.object_key(relative);
+
+        info!("Uploading {} ({} bytes) to s3://{}/{}", path.display(), size, self.config.bucket, key);
+        if size > MAX_SINGLE_PUT_BYTES {
+            self.put_multipart(&key, path, size).await?;
+        } else {
+            let body = file_body(path, 0, size).await?;
+            self.send(Method::PUT, &key, &[], body, size).await?;
+        }
+
+        Ok(size)
+    }
+
+    /// Upload `path` as a multipart upload, aborting it on failure so no
+    /// orphaned parts are left behind in the bucket
+    async fn put_multipart(&self, key: &str, path: &Path, size: u64) -> Result<()> {
+        let response = self
+            .send(Method::POST, key, &[("uploads", "")], Body::from(Vec::new()), 0)
+            .await?;
+        let text = response.text().await.context("Failed to read CreateMultipartUpload response")?;
+        let upload_id = xml_element(&text, "UploadId")
+            .with_context(|| format!("CreateMultipartUpload for {} returned no UploadId", key))?;
+
+        let result = self.upload_parts(key, path, size, &upload_id).await;
+        if result.is_err() {
+            let abort = self
+                .send(Method::DELETE, key, &[("uploadId", upload_id.as_str())], Body::from(Vec::new()), 0)
+                .await;
+            if let Err(e) = abort {
+                warn!("Failed to abort multipart upload {} of {}: {:#}", upload_id, key, e);
+            }
+        }
+        result
+    }
+
+    async fn upload_parts(&self, key: &str, path: &Path, size: u64, upload_id: &str) -> Result<()> {
+        let part_bytes = part_size(size);
+        let mut etags = Vec::new();
+
+        let mut offset = 0;
+        while offset < size {
+            let len = part_bytes.min(size - offset);
+            let part_number = (etags.len() + 1).to_string();
+            let body = file_body(path, offset, len).await?;
+            let response = self
+                .send(
+                    Method::PUT,
+                    key,
+                    &[("partNumber", part_number.as_str()), ("uploadId", upload_id)],
+                    body,
+                    len,
+                )
+                .await?;
+            let etag = response
+                .headers()
+                .get(reqwest::header::ETAG)
+                .and_then(|value| value.to_str().ok())
+                .with_context(|| format!("Part {} of {} returned no ETag", part_number, key))?;
+            etags.push(etag.to_string());
+            offset += len;
+        }
+
+        let body = complete_multipart_body(&etags);
+        let len = body.len() as u64;
+        let response = self
+            .send(Method::POST, key, &[("uploadId", upload_id)], Body::from(body), len)
+            .await?;
+        // CompleteMultipartUpload can report an error in a 200 response body
+        let text = response.text().await.unwrap_or_default();
+        if text.contains("<Error>") {
+            anyhow::bail!("S3 multipart upload of {} failed to complete: {}", key, text);
+        }
+        Ok(())
+    }
+
+    /// Send a signed request for `key` and fail on a non-success status
+    async fn send(
+        &self,
+        method: Method,
+        key: &str,
+        query: &[(&str, &str)],
+        body: Body,
+        content_length: u64,
+    ) -> Result<reqwest::Response> {
+        let query = canonical_query(query);
+        let mut url = reqwest::Url::parse(&format!(
+            "{}/{}/{}",
+            self.config.endpoint,
+            uri_encode(&self.config.bucket, true),
+            uri_encode(key, false)
+        ))
+        .context("Invalid S3 endpoint")?;
+        if !query.is_empty() {
+            url.set_query(Some(&query));
+        }
+        let host = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.to_string(),
+            (None, _) => anyhow::bail!("S3 endpoint has no host: {}", self.config.endpoint),
+        };
+
+        let headers = self.sign(method.as_str(), &host, url.path(), &query, UNSIGNED_PAYLOAD, Utc::now());
+
+        // S3 rejects chunked uploads, so the length of the streamed body is sent up front
+        let mut request = self
+            .http
+            .request(method.clone(), url)
+            .header(reqwest::header::CONTENT_LENGTH, content_length)
+            .body(body);
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+        let response = request.send().await.with_context(|| format!("S3 {} request failed", method))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            anyhow::bail!("S3 {} of {} failed with status {}: {}", method, key, status, text);
+        }
+        Ok(response)
+    }
+
+    /// SigV4 headers for a request of `canonical_uri` with the given payload hash
+    fn sign(
+        &self,
+        method: &str,
+        host: &str,
+        canonical_uri: &str,
+        canonical_query: &str,
+        payload_hash: &str,
+        now: DateTime<Utc>,
+    ) -> Vec<(&'static str, String)> {
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+
+        let mut headers: Vec<(&'static str, String)> = vec![
+            ("host", host.to_string()),
+            ("x-amz-content-sha256", payload_hash.to_string()),
+            ("x-amz-date", amz_date.clone()),
+        ];
+        if let Some(token) = &self.config.session_token {
+            headers.push(("x-amz-security-token", token.clone()));
+        }
+
+        let canonical_headers: String = headers
+            .iter()
+            .map(|(name, value)| format!("{}:{}\n", name, value.trim()))
+            .collect();
+        let signed_headers = headers.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(";");
+        let canonical_request = format!(
+            "{}\n{}\n{}\n{}\n{}\n{}",
+            method, canonical_uri, canonical_query, canonical_headers, signed_headers, payload_hash
+        );
+
+        let scope = format!("{}/{}/s3/aws4_request", date, self.config.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date,
+            scope,
+            hex(&Sha256::digest(canonical_request.as_bytes()))
+        );
+        let key = signing_key(&self.config.secret_access_key, &date, &self.config.region, "s3");
+        let signature = hex(&hmac_sha256(&key, string_to_sign.as_bytes()));
+
+        headers.push((
+            "authorization",
+            format!(
+                "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+                self.config.access_key_id, scope, signed_headers, signature
+            ),
+        ));
+        // reqwest sets Host itself from the URL
+        headers.retain(|(name, _)| *name != "host");
+        headers
+    }
+}
+
+/// Stream `len` bytes of `path` starting at `offset` as a request body
+async fn file_body(path: &Path, offset: u64, len: u64) -> Result<Body> {
+    let mut file = tokio::fs::File::open(path)
+        .await
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    if offset > 0 {
+        file.seek(SeekFrom::Start(offset))
+            .await
+            .with_context(|| format!("Failed to seek in {}", path.display()))?;
+    }
+    Ok(Body::wrap_stream(ReaderStream::new(file.take(len))))
+}
+
+/// Part size that keeps an upload of `size` bytes within S3's part limit
+fn part_size(size: u64) -> u64 {
+    size.div_ceil(MAX_PARTS).max(MIN_PART_BYTES)
+}
+
+/// SigV4 canonical query string: encoded pairs sorted by name
+fn canonical_query(params: &[(&str, &str)]) -> String {
+    let mut pairs: Vec<String> = params
+        .iter()
+        .map(|(name, value)| format!("{}={}", uri_encode(name, true), uri_encode(value, true)))
+        .collect();
+    pairs.sort();
+    pairs.join("&")
+}
+
+/// `CompleteMultipartUpload` request body listing the uploaded parts in order
+fn complete_multipart_body(etags: &[String]) -> String {
+    let parts: String = etags
+        .iter()
+        .enumerate()
+        .map(|(index, etag)| format!("<Part><PartNumber>{}</PartNumber><ETag>{}</ETag></Part>", index + 1, etag))
+        .collect();
+    format!("<CompleteMultipartUpload>{}</CompleteMultipartUpload>", parts)
+}
+
+/// Text of the first `<name>` element in an XML response
+fn xml_element(xml: &str, name: &str) -> Option<String> {
+    let open = format!("<{}>", name);
+    let start = xml.find(&open)? + open.len();
+    let end = start + xml[start..].find(&format!("</{}>", name))?;
+    Some(xml[start..end].to_string())
+}
+
+/// HMAC-SHA256 (RFC 2104)
+fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; 32] {
+    const BLOCK_SIZE: usize = 64;
+
+    let mut block = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let inner_pad: Vec<u8> = block.iter().map(|b| b ^ 0x36).collect();
+    let outer_pad: Vec<u8> = block.iter().map(|b| b ^ 0x5c).collect();
+
+    let inner = Sha256::new().chain_update(&inner_pad).chain_update(data).finalize();
+    Sha256::new().chain_update(&outer_pad).chain_update(inner).finalize().into()
+}
+
+/// SigV4 signing key derivation
+fn signing_key(secret: &str, date: &str, region: &str, service: &str) -> [u8; 32] {
+    let k_date = hmac_sha256(format!("AWS4{}", secret).as_bytes(), date.as_bytes());
+    let k_region = hmac_sha256(&k_date, region.as_bytes());
+    let k_service = hmac_sha256(&k_region, service.as_bytes());
+    hmac_sha256(&k_service, b"aws4_request")
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// URI-encode per SigV4 rules; `/` is kept unless `encode_slash` is set
+fn uri_encode(input: &str, encode_slash: bool) -> String {
+    let mut encoded = String::with_capacity(input.len());
+    for byte in input.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => encoded.push(byte as char),
+            b'/' if !encode_slash => encoded.push('/'),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hmac_sha256_rfc4231() {
+        let mac = hmac_sha256(b"Jefe", b"what do ya want for nothing?");
+        assert_eq!(
+            hex(&mac),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_signing_key_matches_aws_example() {
+        let key = signing_key("wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY", "20120215", "us-east-1", "iam");
+        assert_eq!(
+            hex(&key),
+            "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+        );
+    }
+
+    #[test]
+    fn test_uri_encode() {
+        assert_eq!(uri_encode("snapshots/2024 01/a+b.snapshot", false), "snapshots/2024%2001/a%2Bb.snapshot");
+        assert_eq!(uri_encode("a/b", true), "a%2Fb");
+    }
+
+    #[test]
+    fn test_sign_headers() {
+        let client = S3Client::new(
+            S3Config {
+                endpoint: "http://localhost:9000".to_string(),
+                bucket: "backups".to_string(),
+                region: "us-east-1".to_string(),
+                prefix: "qdrant/".to_string(),
+                access_key_id: "AKID".to_string(),
+                secret_access_key: "secret".to_string(),
+                session_token: None,
+            },
+            Duration::from_secs(5),
+        )
+        .unwrap();
+
+        let now = DateTime::parse_from_rfc3339("2024-01-02T03:04:05Z").unwrap().with_timezone(&Utc);
+        let headers = client.sign("PUT", "localhost:9000", "/backups/qdrant/x", "", UNSIGNED_PAYLOAD, now);
+
+        assert!(headers.iter().all(|(name, _)| *name != "host"));
+        let auth = &headers.iter().find(|(name, _)| *name == "authorization").unwrap().1;
+        assert!(auth.starts_with("AWS4-HMAC-SHA256 Credential=AKID/20240102/us-east-1/s3/aws4_request"));
+        assert!(auth.contains("SignedHeaders=host;x-amz-content-sha256;x-amz-date"));
+        assert_eq!(client.object_key("a/b"), "qdrant/a/b");
+    }
+
+    #[test]
+    fn test_canonical_query_is_sorted_and_encoded() {
+        assert_eq!(canonical_query(&[]), "");
+        assert_eq!(canonical_query(&[("uploads", "")]), "uploads=");
+        assert_eq!(
+            canonical_query(&[("uploadId", "a/b+c"), ("partNumber", "2")]),
+            "partNumber=2&uploadId=a%2Fb%2Bc"
+        );
+    }
+
+    #[test]
+    fn test_part_size_respects_part_limit() {
+        assert_eq!(part_size(6 * 1024 * 1024 * 1024), MIN_PART_BYTES);
+        let huge = 5 * 1024 * 1024 * 1024 * 1024;
+        assert!(part_size(huge) * MAX_PARTS >= huge);
+    }
+
+    #[test]
+    fn test_multipart_xml() {
+        let response = "<InitiateMultipartUploadResult><Bucket>b</Bucket><UploadId>abc123</UploadId></InitiateMultipartUploadResult>";
+        assert_eq!(xml_element(response, "UploadId").as_deref(), Some("abc123"));
+        assert_eq!(xml_element(response, "Missing"), None);
+        assert_eq!(
+            complete_multipart_body(&["\"e1\"".to_string(), "\"e2\"".to_string()]),
+            "<CompleteMultipartUpload><Part><PartNumber>1</PartNumber><ETag>\"e1\"</ETag></Part>\
+             <Part><PartNumber>2</PartNumber><ETag>\"e2\"</ETag></Part></CompleteMultipartUpload>"
+        );
+    }
+}
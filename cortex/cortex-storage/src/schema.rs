@@ -1,5 +1,23 @@
 //! Database schema definitions and migrations.
 
+use crate::connection_pool::ConnectionManager;
+use async_trait::async_trait;
+use cortex_core::error::{CortexError, Result};
+use cortex_core::versioning::{ComponentVersion, ComponentVersionStore, Migration};
+use std::collections::HashMap;
+
+/// Schema version this binary expects. Bump whenever [`SCHEMA`] changes so
+/// `cortex doctor` can detect databases initialized by an older release.
+pub const SCHEMA_VERSION: u32 = 4;
+
+/// Version of the storage component, checked at startup by
+/// [`CompatibilityChecker`](cortex_core::versioning::CompatibilityChecker).
+pub const COMPONENT_VERSION: ComponentVersion =
+    ComponentVersion::new(COMPONENT_NAME, SCHEMA_VERSION, &[]);
+
+/// Name the storage component is recorded under in `component_versions`.
+pub const COMPONENT_NAME: &str = "cortex-storage";
+
 /// SurrealQL schema for the Cortex system
 pub const SCHEMA: &str = r#"
 -- Define tables
@@ -16,6 +34,12 @@ DEFINE TABLE version_history SCHEMAFULL;
 DEFINE TABLE snapshots SCHEMAFULL;
 DEFINE TABLE snapshot_entries SCHEMAFULL;
 DEFINE TABLE version_tags SCHEMAFULL;
+DEFINE TABLE schema_meta SCHEMALESS;
+DEFINE TABLE component_versions SCHEMALESS;
+DEFINE TABLE lease_lock SCHEMALESS;
+DEFINE TABLE lock_wait SCHEMALESS;
+DEFINE TABLE lock_entity SCHEMALESS;
+DEFINE TABLE vnode_attribute SCHEMALESS;
 
 -- Projects table
 DEFINE FIELD name ON projects TYPE string;
@@ -176,9 +200,22 @@ DEFINE FIELD metadata ON version_tags TYPE object;
 DEFINE INDEX version_tags_name ON version_tags FIELDS tag_name UNIQUE;
 DEFINE INDEX version_tags_snapshot ON version_tags FIELDS snapshot_id;
 DEFINE INDEX version_tags_created_at ON version_tags FIELDS created_at;
+
+-- Lease locks and their wait-for edges (see locks::LeaseLockManager)
+DEFINE INDEX lease_lock_entity ON lease_lock FIELDS entity_id;
+DEFINE INDEX lease_lock_expires_at ON lease_lock FIELDS expires_at;
+DEFINE INDEX lock_wait_waiter ON lock_wait FIELDS waiter;
+
+-- One row per VNode attribute, so attribute queries use an index instead of
+-- scanning vnodes (see VirtualFileSystem::query_nodes)
+DEFINE INDEX vnode_attribute_lookup ON vnode_attribute FIELDS workspace_id, key, value;
+DEFINE INDEX vnode_attribute_node ON vnode_attribute FIELDS vnode_id;
 "#;
 
 /// Initialize the database schema
+///
+/// All statements are idempotent, so this doubles as the migration path for
+/// databases created by older releases. Records [`SCHEMA_VERSION`] on success.
 pub async fn init_schema(db: &surrealdb::Surreal<impl surrealdb::Connection>) -> cortex_core::error::Result<()> {
     tracing::info!("Initializing database schema");
 
@@ -186,6 +223,123 @@ pub async fn init_schema(db: &surrealdb::Surreal<impl surrealdb::Connection>) ->
         .await
         .map_err(|e| cortex_core::error::CortexError::database(format!("Failed to initialize schema: {}", e)))?;
 
-    tracing::info!("Database schema initialized successfully");
+    db.query("UPSERT schema_meta:current SET version = $version, updated_at = time::now()")
+        .bind(("version", SCHEMA_VERSION))
+        .await
+        .map_err(|e| cortex_core::error::CortexError::database(format!("Failed to record schema version: {}", e)))?;
+    record_component_version(db, COMPONENT_NAME, SCHEMA_VERSION).await?;
+
+    tracing::info!("Database schema initialized successfully (version {})", SCHEMA_VERSION);
     Ok(())
 }
+
+/// Read the schema version recorded by [`init_schema`].
+///
+/// Returns `None` for databases that were never initialized or predate
+/// version tracking.
+pub async fn read_schema_version(
+    db: &surrealdb::Surreal<impl surrealdb::Connection>,
+) -> cortex_core::error::Result<Option<u32>> {
+    let mut response = db
+        .query("SELECT VALUE version FROM schema_meta:current")
+        .await
+        .map_err(|e| cortex_core::error::CortexError::database(format!("Failed to read schema version: {}", e)))?;
+
+    let versions: Vec<u32> = response
+        .take(0)
+        .map_err(|e| cortex_core::error::CortexError::database(format!("Failed to read schema version: {}", e)))?;
+
+    Ok(versions.into_iter().next())
+}
+
+#[derive(serde::Deserialize)]
+struct ComponentVersionRow {
+    component: String,
+    version: u32,
+}
+
+/// Read the version recorded for each component.
+///
+/// Databases initialized before per-component tracking only have the
+/// `schema_meta` version, which is reported for the storage component.
+pub async fn read_component_versions(
+    db: &surrealdb::Surreal<impl surrealdb::Connection>,
+) -> Result<HashMap<String, u32>> {
+    let mut response = db
+        .query("SELECT component, version FROM component_versions")
+        .await
+        .map_err(|e| CortexError::database(format!("Failed to read component versions: {}", e)))?;
+
+    let rows: Vec<ComponentVersionRow> = response
+        .take(0)
+        .map_err(|e| CortexError::database(format!("Failed to read component versions: {}", e)))?;
+
+    let mut versions: HashMap<String, u32> = rows
+        .into_iter()
+        .map(|row| (row.component, row.version))
+        .collect();
+
+    if !versions.contains_key(COMPONENT_NAME) {
+        if let Some(version) = read_schema_version(db).await? {
+            versions.insert(COMPONENT_NAME.to_string(), version);
+        }
+    }
+
+    Ok(versions)
+}
+
+/// Record that `component` has been migrated to `version`.
+pub async fn record_component_version(
+    db: &surrealdb::Surreal<impl surrealdb::Connection>,
+    component: &str,
+    version: u32,
+) -> Result<()> {
+    db.query(
+        "UPSERT type::thing('component_versions', $component) \
+         SET component = $component, version = $version, updated_at = time::now()",
+    )
+    .bind(("component", component.to_string()))
+    .bind(("version", version))
+    .await
+    .map_err(|e| CortexError::database(format!("Failed to record version of {}: {}", component, e)))?;
+
+    Ok(())
+}
+
+#[async_trait]
+impl ComponentVersionStore for ConnectionManager {
+    async fn load_component_versions(&self) -> Result<HashMap<String, u32>> {
+        let conn = self.acquire().await?;
+        read_component_versions(conn.connection()).await
+    }
+
+    async fn record_component_version(&self, component: &str, version: u32) -> Result<()> {
+        let conn = self.acquire().await?;
+        record_component_version(conn.connection(), component, version).await
+    }
+}
+
+/// Brings the storage schema to [`SCHEMA_VERSION`] by re-applying [`SCHEMA`].
+///
+/// Every statement is idempotent, so one migration covers every older version.
+pub struct SchemaMigration;
+
+#[async_trait]
+impl Migration<ConnectionManager> for SchemaMigration {
+    fn component(&self) -> &str {
+        COMPONENT_NAME
+    }
+
+    fn target_version(&self) -> u32 {
+        SCHEMA_VERSION
+    }
+
+    fn description(&self) -> String {
+        format!("apply storage schema version {}", SCHEMA_VERSION)
+    }
+
+    async fn apply(&self, storage: &ConnectionManager) -> Result<()> {
+        let conn = storage.acquire().await?;
+        init_schema(conn.connection()).await
+    }
+}
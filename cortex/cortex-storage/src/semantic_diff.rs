@@ -0,0 +1,574 @@
+//! Entity-level diff and merge for source files.
+//!
+//! Text hunks cannot tell a renamed function from a delete plus an insert,
+//! and an insertion in one function can produce a hunk that runs into the
+//! next. This module parses each version with `cortex-code-analysis`, matches
+//! functions and structs by qualified name (and, for unmatched ones, by
+//! signature and body similarity), and reports [`EntityChange`]s. The
+//! three-way merge uses them to combine two sides that touched different
+//! entities of the same file, splicing one side's entity edits into the
+//! other.
+
+use crate::merge::{ConflictType, Hunk};
+use cortex_code_analysis::{CodeParser, Lang, ParsedFile};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Minimum similarity for an unmatched removed/added pair to count as a rename
+pub const RENAME_SIMILARITY_THRESHOLD: f64 = 0.8;
+
+/// Kind of code entity tracked by the semantic diff
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum EntityKind {
+    Function,
+    Struct,
+}
+
+/// Change to a single code entity between two versions of a file
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum EntityChange {
+    FunctionAdded { name: String },
+    FunctionRemoved { name: String },
+    FunctionModified { name: String, signature_changed: bool },
+    FunctionRenamed { from: String, to: String },
+    StructAdded { name: String },
+    StructRemoved { name: String },
+    StructModified { name: String },
+    StructRenamed { from: String, to: String },
+}
+
+impl EntityChange {
+    /// Entity names (qualified) this change touches in either version
+    pub fn touched(&self) -> Vec<&str> {
+        match self {
+            EntityChange::FunctionAdded { name }
+            | EntityChange::FunctionRemoved { name }
+            | EntityChange::FunctionModified { name, .. }
+            | EntityChange::StructAdded { name }
+            | EntityChange::StructRemoved { name }
+            | EntityChange::StructModified { name } => vec![name.as_str()],
+            EntityChange::FunctionRenamed { from, to } | EntityChange::StructRenamed { from, to } => {
+                vec![from.as_str(), to.as_str()]
+            }
+        }
+    }
+
+    fn signature_changed(&self) -> bool {
+        matches!(
+            self,
+            EntityChange::FunctionModified { signature_changed: true, .. }
+        )
+    }
+}
+
+/// Text hunks plus entity-level changes for one pair of versions
+#[derive(Debug, Clone, Default)]
+pub struct SemanticDiff {
+    pub hunks: Vec<Hunk>,
+    /// Empty when the language is unsupported or the file did not parse
+    pub entity_changes: Vec<EntityChange>,
+}
+
+/// Entity both sides changed incompatibly
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EntityConflict {
+    pub name: String,
+    pub conflict_type: ConflictType,
+}
+
+/// Outcome of an entity-level three-way merge
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SemanticMerge {
+    /// Both sides touched disjoint entities; the combined file
+    Merged(String),
+    /// Both sides touched the same entities
+    Conflicts(Vec<EntityConflict>),
+    /// The file cannot be merged at entity level (unsupported language,
+    /// parse failure, or both sides changed code outside any entity)
+    Unsupported,
+}
+
+/// A function or struct with its source span
+#[derive(Debug, Clone)]
+struct CodeEntity {
+    kind: EntityKind,
+    /// Qualified name, unique within the file
+    name: String,
+    /// Signature without the entity's own name
+    signature: String,
+    /// Body (functions) or field list (structs)
+    body: String,
+    /// 1-indexed inclusive line span
+    start_line: usize,
+    end_line: usize,
+}
+
+impl CodeEntity {
+    fn lines<'a>(&self, lines: &[&'a str]) -> Vec<&'a str> {
+        let end = self.end_line.min(lines.len());
+        let start = self.start_line.saturating_sub(1).min(end);
+        lines[start..end].to_vec()
+    }
+
+    /// Prefix of the qualified name (module or impl type)
+    fn parent(&self) -> &str {
+        self.name.rsplit_once("::").map(|(parent, _)| parent).unwrap_or("")
+    }
+}
+
+/// Parsed version of a file
+struct FileEntities<'a> {
+    lines: Vec<&'a str>,
+    entities: Vec<CodeEntity>,
+}
+
+impl<'a> FileEntities<'a> {
+    fn parse(path: &str, source: &'a str) -> Option<Self> {
+        let lang = Lang::from_path(Path::new(path))?;
+        let mut parser = CodeParser::for_language(lang).ok()?;
+        let parsed = parser.parse_file(path, source, lang).ok()?;
+        Some(Self {
+            lines: source.lines().collect(),
+            entities: collect_entities(&parsed),
+        })
+    }
+
+    fn get(&self, name: &str) -> Option<&CodeEntity> {
+        self.entities.iter().find(|e| e.name == name)
+    }
+
+    fn text(&self, entity: &CodeEntity) -> String {
+        entity.lines(&self.lines).join("\n")
+    }
+
+    /// Non-blank lines outside every entity, used to detect changes the
+    /// entity diff cannot see (imports, constants, ...)
+    fn skeleton(&self) -> Vec<&'a str> {
+        let covered: HashSet<usize> = self
+            .entities
+            .iter()
+            .flat_map(|e| e.start_line..=e.end_line)
+            .collect();
+        self.lines
+            .iter()
+            .enumerate()
+            .filter(|(i, line)| !covered.contains(&(i + 1)) && !line.trim().is_empty())
+            .map(|(_, line)| *line)
+            .collect()
+    }
+}
+
+fn collect_entities(parsed: &ParsedFile) -> Vec<CodeEntity> {
+    let mut entities = Vec::new();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    let mut unique = |name: &str| {
+        let count = seen.entry(name.to_string()).or_insert(0);
+        *count += 1;
+        if *count == 1 {
+            name.to_string()
+        } else {
+            format!("{}#{}", name, count)
+        }
+    };
+
+    for func in &parsed.functions {
+        let params: Vec<String> = func
+            .parameters
+            .iter()
+            .map(|p| format!("{}: {}", p.name, p.param_type))
+            .collect();
+        let signature = format!(
+            "{}{}fn<{}>({}) -> {}",
+            func.visibility,
+            if func.is_async { " async " } else { " " },
+            func.generics.join(", "),
+            params.join(", "),
+            func.return_type.as_deref().unwrap_or("()")
+        );
+        entities.push(CodeEntity {
+            kind: EntityKind::Function,
+            name: unique(&func.qualified_name),
+            signature,
+            body: func.body.clone(),
+            start_line: func.start_line,
+            end_line: func.end_line,
+        });
+    }
+
+    for item in &parsed.structs {
+        let fields: Vec<String> = item
+            .fields
+            .iter()
+            .map(|f| format!("{}: {}", f.name, f.field_type))
+            .collect();
+        entities.push(CodeEntity {
+            kind: EntityKind::Struct,
+            name: unique(&item.qualified_name),
+            signature: format!("{} struct<{}>", item.visibility, item.generics.join(", ")),
+            body: fields.join(", "),
+            start_line: item.start_line,
+            end_line: item.end_line,
+        });
+    }
+
+    entities.sort_by_key(|e| e.start_line);
+    entities
+}
+
+/// Jaccard similarity of whitespace-separated tokens
+fn token_similarity(a: &str, b: &str) -> f64 {
+    let a: HashSet<&str> = a.split_whitespace().collect();
+    let b: HashSet<&str> = b.split_whitespace().collect();
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    a.intersection(&b).count() as f64 / a.union(&b).count() as f64
+}
+
+fn rename_score(from: &CodeEntity, to: &CodeEntity) -> f64 {
+    if from.kind != to.kind || from.parent() != to.parent() {
+        return 0.0;
+    }
+    (token_similarity(&from.signature, &to.signature) + token_similarity(&from.body, &to.body)) / 2.0
+}
+
+/// Entity changes from `old` to `new`
+fn diff_entities(old: &FileEntities<'_>, new: &FileEntities<'_>) -> Vec<EntityChange> {
+    let mut changes = Vec::new();
+
+    for entity in &old.entities {
+        let Some(updated) = new.get(&entity.name) else {
+            continue;
+        };
+        if old.text(entity) == new.text(updated) {
+            continue;
+        }
+        changes.push(match entity.kind {
+            EntityKind::Function => EntityChange::FunctionModified {
+                name: entity.name.clone(),
+                signature_changed: entity.signature != updated.signature,
+            },
+            EntityKind::Struct => EntityChange::StructModified {
+                name: entity.name.clone(),
+            },
+        });
+    }
+
+    let removed: Vec<&CodeEntity> = old.entities.iter().filter(|e| new.get(&e.name).is_none()).collect();
+    let added: Vec<&CodeEntity> = new.entities.iter().filter(|e| old.get(&e.name).is_none()).collect();
+
+    // Pair up renames, best match first
+    let mut candidates: Vec<(f64, usize, usize)> = Vec::new();
+    for (r, from) in removed.iter().enumerate() {
+        for (a, to) in added.iter().enumerate() {
+            let score = rename_score(from, to);
+            if score >= RENAME_SIMILARITY_THRESHOLD {
+                candidates.push((score, r, a));
+            }
+        }
+    }
+    candidates.sort_by(|x, y| y.0.total_cmp(&x.0));
+
+    let mut renamed_from = HashSet::new();
+    let mut renamed_to = HashSet::new();
+    for (_, r, a) in candidates {
+        if renamed_from.contains(&r) || renamed_to.contains(&a) {
+            continue;
+        }
+        renamed_from.insert(r);
+        renamed_to.insert(a);
+        let (from, to) = (removed[r].name.clone(), added[a].name.clone());
+        changes.push(match removed[r].kind {
+            EntityKind::Function => EntityChange::FunctionRenamed { from, to },
+            EntityKind::Struct => EntityChange::StructRenamed { from, to },
+        });
+    }
+
+    let unpaired = |entities: Vec<&'_ CodeEntity>, paired: &HashSet<usize>| -> Vec<CodeEntity> {
+        entities
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| !paired.contains(i))
+            .map(|(_, e)| e.clone())
+            .collect()
+    };
+
+    for entity in unpaired(removed, &renamed_from) {
+        let name = entity.name.clone();
+        changes.push(match entity.kind {
+            EntityKind::Function => EntityChange::FunctionRemoved { name },
+            EntityKind::Struct => EntityChange::StructRemoved { name },
+        });
+    }
+    for entity in unpaired(added, &renamed_to) {
+        let name = entity.name.clone();
+        changes.push(match entity.kind {
+            EntityKind::Function => EntityChange::FunctionAdded { name },
+            EntityKind::Struct => EntityChange::StructAdded { name },
+        });
+    }
+
+    changes
+}
+
+/// Entity-level changes between two versions of `path`. Returns an empty
+/// list when the language is unsupported or either version fails to parse.
+pub fn entity_changes(path: &str, old: &str, new: &str) -> Vec<EntityChange> {
+    match (FileEntities::parse(path, old), FileEntities::parse(path, new)) {
+        (Some(old), Some(new)) => diff_entities(&old, &new),
+        _ => Vec::new(),
+    }
+}
+
+/// Three-way merge at entity granularity
+pub fn three_way_merge(path: &str, base: &str, session: &str, main: &str) -> SemanticMerge {
+    let (Some(base_file), Some(session_file), Some(main_file)) = (
+        FileEntities::parse(path, base),
+        FileEntities::parse(path, session),
+        FileEntities::parse(path, main),
+    ) else {
+        return SemanticMerge::Unsupported;
+    };
+
+    let session_changes = diff_entities(&base_file, &session_file);
+    let main_changes = diff_entities(&base_file, &main_file);
+
+    let conflicts = overlapping_changes(&session_file, &session_changes, &main_file, &main_changes);
+    if !conflicts.is_empty() {
+        return SemanticMerge::Conflicts(conflicts);
+    }
+
+    // Splice one side's entity edits into the other. That side's changes
+    // outside entities would be lost, so it must only have changed entities.
+    let base_skeleton = base_file.skeleton();
+    let (source, source_changes, target, target_text) = if session_file.skeleton() == base_skeleton {
+        (&session_file, &session_changes, &main_file, main)
+    } else if main_file.skeleton() == base_skeleton {
+        (&main_file, &main_changes, &session_file, session)
+    } else {
+        return SemanticMerge::Unsupported;
+    };
+
+    match splice(source, source_changes, target) {
+        Some(mut merged) => {
+            if target_text.ends_with('\n') {
+                merged.push('\n');
+            }
+            SemanticMerge::Merged(merged)
+        }
+        None => SemanticMerge::Unsupported,
+    }
+}
+
+/// Entities changed on both sides, unless both made the identical change
+fn overlapping_changes(
+    session: &FileEntities<'_>,
+    session_changes: &[EntityChange],
+    main: &FileEntities<'_>,
+    main_changes: &[EntityChange],
+) -> Vec<EntityConflict> {
+    let mut conflicts = Vec::new();
+    for ours in session_changes {
+        for theirs in main_changes {
+            let Some(name) = ours.touched().into_iter().find(|n| theirs.touched().contains(n)) else {
+                continue;
+            };
+
+            let identical = ours == theirs
+                && ours.touched().iter().all(|n| {
+                    match (session.get(n), main.get(n)) {
+                        (Some(a), Some(b)) => session.text(a) == main.text(b),
+                        (None, None) => true,
+                        _ => false,
+                    }
+                });
+            if identical {
+                continue;
+            }
+
+            let conflict_type = if ours.signature_changed() && theirs.signature_changed() {
+                ConflictType::SignatureConflict
+            } else {
+                ConflictType::BothModifiedSameFunction
+            };
+            conflicts.push(EntityConflict {
+                name: name.to_string(),
+                conflict_type,
+            });
+        }
+    }
+    conflicts
+}
+
+/// Apply `changes` (made in `source`) to `target`, whose copies of the
+/// touched entities are still the base versions
+fn splice(source: &FileEntities<'_>, changes: &[EntityChange], target: &FileEntities<'_>) -> Option<String> {
+    // (start index, end index exclusive, replacement) on target lines
+    let mut edits: Vec<(usize, usize, Vec<String>)> = Vec::new();
+    let source_text = |name: &str| -> Option<Vec<String>> {
+        let entity = source.get(name)?;
+        Some(entity.lines(&source.lines).into_iter().map(String::from).collect())
+    };
+    let span = |entity: &CodeEntity| (entity.start_line.saturating_sub(1), entity.end_line.min(target.lines.len()));
+
+    for change in changes {
+        match change {
+            EntityChange::FunctionModified { name, .. } | EntityChange::StructModified { name } => {
+                let (start, end) = span(target.get(name)?);
+                edits.push((start, end, source_text(name)?));
+            }
+            EntityChange::FunctionRenamed { from, to } | EntityChange::StructRenamed { from, to } => {
+                let (start, end) = span(target.get(from)?);
+                edits.push((start, end, source_text(to)?));
+            }
+            EntityChange::FunctionRemoved { name } | EntityChange::StructRemoved { name } => {
+                let (start, end) = span(target.get(name)?);
+                edits.push((start, end, Vec::new()));
+            }
+            EntityChange::FunctionAdded { name } | EntityChange::StructAdded { name } => {
+                let added = source.get(name)?;
+                // Insert after the closest preceding entity that the target also has
+                let anchor = source
+                    .entities
+                    .iter()
+                    .filter(|e| e.end_line < added.start_line)
+                    .rev()
+                    .find_map(|e| target.get(&e.name));
+                let mut lines = source_text(name)?;
+                let at = match anchor {
+                    Some(anchor) => {
+                        lines.insert(0, String::new());
+                        anchor.end_line.min(target.lines.len())
+                    }
+                    None => {
+                        lines.push(String::new());
+                        target
+                            .entities
+                            .first()
+                            .map(|first| first.start_line.saturating_sub(1))
+                            .unwrap_or(target.lines.len())
+                    }
+                };
+                edits.push((at, at, lines));
+            }
+        }
+    }
+
+    // Apply from the bottom up so earlier indices stay valid
+    edits.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.cmp(&a.1)));
+    let mut merged: Vec<String> = target.lines.iter().map(|l| l.to_string()).collect();
+    let mut floor = usize::MAX;
+    for (start, end, replacement) in edits {
+        if end > floor {
+            return None; // overlapping spans
+        }
+        merged.splice(start..end, replacement);
+        floor = start;
+    }
+
+    Some(merged.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "\
+fn alpha(x: i32) -> i32 {
+    let y = x + 1;
+    y * 2
+}
+
+fn beta(s: &str) -> usize {
+    let n = s.len();
+    n + 1
+}
+";
+
+    #[test]
+    fn test_detects_modify_and_rename() {
+        let renamed = BASE.replace("fn beta(", "fn gamma(").replace("y * 2", "y * 3");
+        let changes = entity_changes("lib.rs", BASE, &renamed);
+
+        assert!(changes.contains(&EntityChange::FunctionModified {
+            name: "alpha".to_string(),
+            signature_changed: false,
+        }));
+        assert!(changes.contains(&EntityChange::FunctionRenamed {
+            from: "beta".to_string(),
+            to: "gamma".to_string(),
+        }));
+        assert_eq!(changes.len(), 2);
+    }
+
+    #[test]
+    fn test_detects_added_and_removed() {
+        let changed = BASE.replace(
+            "fn beta(s: &str) -> usize {\n    let n = s.len();\n    n + 1\n}",
+            "struct Config {\n    verbose: bool,\n}",
+        );
+        let changes = entity_changes("lib.rs", BASE, &changed);
+
+        assert!(changes.contains(&EntityChange::FunctionRemoved { name: "beta".to_string() }));
+        assert!(changes.contains(&EntityChange::StructAdded { name: "Config".to_string() }));
+    }
+
+    #[test]
+    fn test_unsupported_language_has_no_entity_changes() {
+        assert!(entity_changes("notes.txt", "a", "b").is_empty());
+    }
+
+    #[test]
+    fn test_merges_edits_to_different_functions() {
+        // Session inserts a line into alpha; main edits beta
+        let session = BASE.replace("    let y = x + 1;\n", "    let y = x + 1;\n    let y = y.max(0);\n");
+        let main = BASE.replace("n + 1", "n + 2");
+
+        let SemanticMerge::Merged(merged) = three_way_merge("lib.rs", BASE, &session, &main) else {
+            panic!("expected a merge");
+        };
+        assert!(merged.contains("let y = y.max(0);"));
+        assert!(merged.contains("n + 2"));
+        assert!(merged.ends_with("}\n"));
+    }
+
+    #[test]
+    fn test_same_function_conflicts() {
+        let session = BASE.replace("y * 2", "y * 3");
+        let main = BASE.replace("y * 2", "y * 4");
+        assert_eq!(
+            three_way_merge("lib.rs", BASE, &session, &main),
+            SemanticMerge::Conflicts(vec![EntityConflict {
+                name: "alpha".to_string(),
+                conflict_type: ConflictType::BothModifiedSameFunction,
+            }])
+        );
+
+        let session = BASE.replace("fn alpha(x: i32)", "fn alpha(x: i64)");
+        let main = BASE.replace("fn alpha(x: i32)", "fn alpha(x: u32)");
+        let SemanticMerge::Conflicts(conflicts) = three_way_merge("lib.rs", BASE, &session, &main) else {
+            panic!("expected conflicts");
+        };
+        assert_eq!(conflicts[0].conflict_type, ConflictType::SignatureConflict);
+    }
+
+    #[test]
+    fn test_added_function_is_placed_after_its_neighbour() {
+        let session = BASE.replace(
+            "fn beta(",
+            "fn helper() -> bool {\n    true\n}\n\nfn beta(",
+        );
+        let main = BASE.replace("n + 1", "n + 2");
+
+        let SemanticMerge::Merged(merged) = three_way_merge("lib.rs", BASE, &session, &main) else {
+            panic!("expected a merge");
+        };
+        let helper = merged.find("fn helper").unwrap();
+        assert!(merged.find("fn alpha").unwrap() < helper);
+        assert!(helper < merged.find("fn beta").unwrap());
+        assert!(merged.contains("n + 2"));
+    }
+}
@@ -0,0 +1,289 @@
+//! Client-side transactions with savepoints.
+//!
+//! SurrealDB only keeps a transaction open for the duration of a single
+//! request and has no native savepoints, so [`BufferedTransaction`] keeps an
+//! operation log instead: statements are queued locally, a savepoint is a
+//! position in that log, and rolling back to a savepoint truncates it.
+//! [`BufferedTransaction::commit`] sends whatever is left as one
+//! `BEGIN TRANSACTION ... COMMIT TRANSACTION` query, so either every queued
+//! operation applies or none does.
+//!
+//! Parameters are bound per operation: each one is sent under a unique name
+//! and re-declared with `LET` right before its statement, so different
+//! operations can use the same `$name` with different values. SurrealDB's
+//! protected parameters (`$auth`, `$session`, ...) cannot be bound this way.
+
+use crate::connection_pool::PooledConnection;
+use cortex_core::error::{CortexError, Result};
+use serde::Serialize;
+use serde_json::Value;
+use tracing::debug;
+
+/// A statement waiting in a transaction's operation log
+#[derive(Debug, Clone)]
+pub struct QueuedOperation {
+    pub statement: String,
+    pub bindings: Vec<(String, Value)>,
+}
+
+/// Transaction that buffers operations until commit and supports savepoints
+pub struct BufferedTransaction<'c> {
+    conn: &'c PooledConnection,
+    operations: Vec<QueuedOperation>,
+    /// Savepoint names and the log length when each was taken, oldest first
+    savepoints: Vec<(String, usize)>,
+}
+
+impl<'c> BufferedTransaction<'c> {
+    pub fn new(conn: &'c PooledConnection) -> Self {
+        Self {
+            conn,
+            operations: Vec::new(),
+            savepoints: Vec::new(),
+        }
+    }
+
+    /// Queue a statement with parameters visible only to that statement
+    pub fn execute<K, V>(
+        &mut self,
+        statement: impl Into<String>,
+        bindings: impl IntoIterator<Item = (K, V)>,
+    ) -> Result<()>
+    where
+        K: Into<String>,
+        V: Serialize,
+    {
+        let statement = statement.into();
+        let statement = statement.trim().trim_end_matches(';').trim_end().to_string();
+        if statement.is_empty() {
+            return Err(CortexError::invalid_input("Cannot queue an empty statement"));
+        }
+
+        let mut params = Vec::new();
+        for (name, value) in bindings {
+            let name = name.into();
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                return Err(CortexError::invalid_input(format!(
+                    "Invalid parameter name: {:?}",
+                    name
+                )));
+            }
+            let value = serde_json::to_value(value)
+                .map_err(|e| CortexError::invalid_input(format!("Cannot bind ${}: {}", name, e)))?;
+            params.push((name, value));
+        }
+
+        self.operations.push(QueuedOperation {
+            statement,
+            bindings: params,
+        });
+        Ok(())
+    }
+
+    /// Mark the current position in the log. Names may be reused; the most
+    /// recent savepoint with a name wins, as in SQL.
+    pub fn savepoint(&mut self, name: impl Into<String>) {
+        self.savepoints.push((name.into(), self.operations.len()));
+    }
+
+    /// Discard every operation queued after `name` and any savepoints taken
+    /// since. The savepoint itself stays active. Returns the number of
+    /// discarded operations.
+    pub fn rollback_to(&mut self, name: &str) -> Result<usize> {
+        let index = self.find_savepoint(name)?;
+        let mark = self.savepoints[index].1;
+        let discarded = self.operations.len() - mark;
+
+        self.operations.truncate(mark);
+        self.savepoints.truncate(index + 1);
+
+        debug!("Rolled back to savepoint {} ({} operations discarded)", name, discarded);
+        Ok(discarded)
+    }
+
+    /// Forget `name` and any savepoints taken since, keeping their operations
+    pub fn release(&mut self, name: &str) -> Result<()> {
+        let index = self.find_savepoint(name)?;
+        self.savepoints.truncate(index);
+        Ok(())
+    }
+
+    /// Operations that will be sent on commit
+    pub fn operations(&self) -> &[QueuedOperation] {
+        &self.operations
+    }
+
+    pub fn len(&self) -> usize {
+        self.operations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    /// Apply every queued operation atomically. Returns the number applied.
+    pub async fn commit(self) -> Result<usize> {
+        if self.operations.is_empty() {
+            return Ok(0);
+        }
+
+        let count = self.operations.len();
+        let mut query = self.conn.query(self.render());
+        for (index, operation) in self.operations.into_iter().enumerate() {
+            for (name, value) in operation.bindings {
+                query = query.bind((param_name(index, &name), value));
+            }
+        }
+
+        query
+            .await?
+            .check()
+            .map_err(|e| CortexError::database(format!("Transaction failed: {}", e)))?;
+
+        debug!("Committed transaction with {} operations", count);
+        Ok(count)
+    }
+
+    /// Discard all queued operations
+    pub fn rollback(self) {
+        debug!("Rolled back transaction ({} operations discarded)", self.operations.len());
+    }
+
+    fn find_savepoint(&self, name: &str) -> Result<usize> {
+        self.savepoints
+            .iter()
+            .rposition(|(saved, _)| saved == name)
+            .ok_or_else(|| CortexError::invalid_input(format!("Unknown savepoint: {}", name)))
+    }
+
+    /// Single-request SurrealQL for the whole log
+    fn render(&self) -> String {
+        let mut query = String::from("BEGIN TRANSACTION;\n");
+        for (index, operation) in self.operations.iter().enumerate() {
+            for (name, _) in &operation.bindings {
+                query.push_str(&format!("LET ${} = ${};\n", name, param_name(index, name)));
+            }
+            query.push_str(&operation.statement);
+            query.push_str(";\n");
+        }
+        query.push_str("COMMIT TRANSACTION;");
+        query
+    }
+}
+
+/// Name an operation's parameter is actually bound under
+fn param_name(operation: usize, name: &str) -> String {
+    format!("tx{}_{}", operation, name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connection_pool::{ConnectionManager, ConnectionMode, DatabaseConfig, PoolConfig};
+
+    async fn memory_manager() -> ConnectionManager {
+        let config = DatabaseConfig {
+            connection_mode: ConnectionMode::InMemory,
+            pool_config: PoolConfig {
+                min_connections: 1,
+                max_connections: 1,
+                warm_connections: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        ConnectionManager::new(config).await.unwrap()
+    }
+
+    fn no_params() -> [(&'static str, Value); 0] {
+        []
+    }
+
+    async fn item_names(conn: &PooledConnection) -> Vec<String> {
+        let mut response = conn
+            .query("SELECT VALUE name FROM item ORDER BY name")
+            .await
+            .unwrap();
+        response.take(0).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_rollback_to_discards_later_operations() {
+        let manager = memory_manager().await;
+        let conn = manager.acquire().await.unwrap();
+
+        let mut tx = conn.transaction();
+        tx.execute("CREATE item SET name = $name", [("name", "a")]).unwrap();
+        tx.savepoint("entity");
+        tx.execute("CREATE item SET name = $name", [("name", "b")]).unwrap();
+        tx.execute("CREATE item SET name = $name;", [("name", "c")]).unwrap();
+        assert_eq!(tx.rollback_to("entity").unwrap(), 2);
+        tx.execute("CREATE item SET name = $name", [("name", "d")]).unwrap();
+        tx.release("entity").unwrap();
+
+        assert_eq!(tx.commit().await.unwrap(), 2);
+        assert_eq!(item_names(&conn).await, vec!["a", "d"]);
+    }
+
+    #[tokio::test]
+    async fn test_nested_savepoints() {
+        let manager = memory_manager().await;
+        let conn = manager.acquire().await.unwrap();
+
+        let mut tx = conn.transaction();
+        tx.savepoint("outer");
+        tx.execute("CREATE item SET name = 'a'", no_params()).unwrap();
+        tx.savepoint("inner");
+        tx.execute("CREATE item SET name = 'b'", no_params()).unwrap();
+
+        // Releasing keeps the operations
+        tx.release("inner").unwrap();
+        assert!(tx.rollback_to("inner").is_err());
+        assert_eq!(tx.len(), 2);
+
+        tx.savepoint("inner");
+        assert_eq!(tx.rollback_to("outer").unwrap(), 2);
+        assert!(tx.release("inner").is_err(), "inner savepoint should be gone");
+        assert!(tx.is_empty());
+
+        assert_eq!(tx.commit().await.unwrap(), 0);
+        assert!(item_names(&conn).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_failed_operation_aborts_whole_commit() {
+        let manager = memory_manager().await;
+        let conn = manager.acquire().await.unwrap();
+
+        let mut tx = conn.transaction();
+        tx.execute("CREATE item:one SET name = 'a'", no_params()).unwrap();
+        tx.execute("CREATE item:one SET name = 'duplicate'", no_params()).unwrap();
+
+        assert!(tx.commit().await.is_err());
+        assert!(item_names(&conn).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_parameters_are_scoped_per_operation() {
+        let manager = memory_manager().await;
+        let conn = manager.acquire().await.unwrap();
+
+        let mut tx = conn.transaction();
+        assert!(tx.execute("CREATE item", [("a-b", 1)]).is_err());
+        assert!(tx.execute("CREATE item", [("$name", 1)]).is_err());
+        assert!(tx.execute("  ;", no_params()).is_err());
+
+        tx.execute("CREATE item SET name = $name", [("name", "x")]).unwrap();
+        tx.execute("CREATE item SET name = $name", [("name", "y")]).unwrap();
+        assert_eq!(
+            tx.render(),
+            "BEGIN TRANSACTION;\n\
+             LET $name = $tx0_name;\nCREATE item SET name = $name;\n\
+             LET $name = $tx1_name;\nCREATE item SET name = $name;\n\
+             COMMIT TRANSACTION;"
+        );
+
+        tx.commit().await.unwrap();
+        assert_eq!(item_names(&conn).await, vec!["x", "y"]);
+    }
+}
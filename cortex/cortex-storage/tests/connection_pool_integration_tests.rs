@@ -29,6 +29,8 @@ fn test_config() -> DatabaseConfig {
             validate_on_checkout: true,
             recycle_after_uses: None,
             shutdown_grace_period: Duration::from_secs(30),
+            query_timeout: Some(Duration::from_secs(30)),
+            slow_query_threshold: Some(Duration::from_secs(1)),
         },
         namespace: "test".to_string(),
         database: "test".to_string(),
@@ -671,16 +673,17 @@ async fn test_savepoint_support() {
 
     let conn = manager.acquire().await.unwrap();
 
-    // Begin transaction
-    conn.begin_transaction().await.unwrap();
-
-    // Create savepoint
-    let result = conn.savepoint("sp1").await;
-    // Note: SurrealDB may not support savepoints, so we just verify the method exists
-    drop(result);
-
-    // Rollback transaction
-    conn.rollback_transaction().await.unwrap();
+    let mut tx = conn.transaction();
+    tx.execute("CREATE sp_item SET n = $n", [("n", 1)]).unwrap();
+    tx.savepoint("sp1");
+    tx.execute("CREATE sp_item SET n = $n", [("n", 2)]).unwrap();
+    assert_eq!(tx.rollback_to("sp1").unwrap(), 1);
+    tx.release("sp1").unwrap();
+    assert_eq!(tx.commit().await.unwrap(), 1);
+
+    let mut response = conn.query("SELECT VALUE n FROM sp_item").await.unwrap();
+    let values: Vec<i64> = response.take(0).unwrap();
+    assert_eq!(values, vec![1]);
 }
 
 #[tokio::test]
@@ -30,6 +30,8 @@ fn load_test_config(max_connections: usize) -> DatabaseConfig {
             validate_on_checkout: true,
             recycle_after_uses: None,
             shutdown_grace_period: Duration::from_secs(30),
+            query_timeout: Some(Duration::from_secs(30)),
+            slow_query_threshold: Some(Duration::from_secs(1)),
         },
         namespace: "load_test".to_string(),
         database: "load_test".to_string(),
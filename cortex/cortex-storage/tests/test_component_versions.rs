@@ -0,0 +1,103 @@
+//! Tests for per-component schema version tracking and startup migrations.
+
+use cortex_core::versioning::{
+    Compatibility, CompatibilityChecker, ComponentVersion, ComponentVersionStore, MigrationRunner,
+};
+use cortex_storage::prelude::*;
+use cortex_storage::schema::{self, SchemaMigration, COMPONENT_NAME, SCHEMA_VERSION};
+use cortex_storage::{ConnectionManager, DatabaseConfig, PoolConnectionMode};
+use std::time::Duration;
+
+const VFS: ComponentVersion = ComponentVersion::new("cortex-vfs", 2, &[COMPONENT_NAME]);
+
+/// Create an in-memory test database connection manager
+async fn create_test_connection_manager() -> ConnectionManager {
+    let config = DatabaseConfig {
+        connection_mode: PoolConnectionMode::InMemory,
+        credentials: Credentials {
+            username: None,
+            password: None,
+        },
+        pool_config: PoolConfig {
+            min_connections: 1,
+            max_connections: 4,
+            connection_timeout: Duration::from_secs(5),
+            idle_timeout: None,
+            max_lifetime: None,
+            retry_policy: RetryPolicy::default(),
+            warm_connections: false,
+            validate_on_checkout: true,
+            recycle_after_uses: None,
+            shutdown_grace_period: Duration::from_secs(10),
+            query_timeout: Some(Duration::from_secs(30)),
+            slow_query_threshold: Some(Duration::from_secs(1)),
+        },
+        namespace: "test".to_string(),
+        database: "test".to_string(),
+    };
+
+    ConnectionManager::new(config)
+        .await
+        .expect("Failed to create connection manager")
+}
+
+#[tokio::test]
+async fn test_fresh_database_is_migrated_to_current_versions() {
+    let storage = create_test_connection_manager().await;
+    let checker = CompatibilityChecker::new([schema::COMPONENT_VERSION, VFS]).unwrap();
+
+    let report = checker.verify(&storage).await.unwrap();
+    assert_eq!(report.needs_migration().len(), 2);
+
+    let mut runner = MigrationRunner::new(checker.clone());
+    runner.register(SchemaMigration);
+    let applied = runner.run(&storage, &storage).await.unwrap();
+
+    assert_eq!(applied.len(), 1);
+    assert_eq!(applied[0].component, COMPONENT_NAME);
+    assert_eq!(applied[0].to, SCHEMA_VERSION);
+
+    let versions = storage.load_component_versions().await.unwrap();
+    assert_eq!(versions.get(COMPONENT_NAME), Some(&SCHEMA_VERSION));
+    assert_eq!(versions.get("cortex-vfs"), Some(&2));
+    assert!(checker.verify(&storage).await.unwrap().is_compatible());
+}
+
+#[tokio::test]
+async fn test_legacy_schema_meta_version_is_reported_for_storage() {
+    let storage = create_test_connection_manager().await;
+    {
+        let conn = storage.acquire().await.unwrap();
+        conn.connection()
+            .query("UPSERT schema_meta:current SET version = 2")
+            .await
+            .unwrap();
+    }
+
+    let checker = CompatibilityChecker::new([schema::COMPONENT_VERSION]).unwrap();
+    let report = checker.verify(&storage).await.unwrap();
+
+    assert_eq!(report.components[0].stored, Some(2));
+    assert!(matches!(
+        report.components[0].compatibility,
+        Compatibility::NeedsMigration { from: Some(2), .. }
+    ));
+}
+
+#[tokio::test]
+async fn test_newer_database_is_refused() {
+    let storage = create_test_connection_manager().await;
+    storage
+        .record_component_version(COMPONENT_NAME, SCHEMA_VERSION + 1)
+        .await
+        .unwrap();
+
+    let checker = CompatibilityChecker::new([schema::COMPONENT_VERSION]).unwrap();
+    let report = checker.verify(&storage).await.unwrap();
+    assert_eq!(report.too_new().len(), 1);
+
+    let mut runner = MigrationRunner::new(checker);
+    runner.register(SchemaMigration);
+    let err = runner.run(&storage, &storage).await.unwrap_err();
+    assert!(err.is_incompatible_schema());
+}
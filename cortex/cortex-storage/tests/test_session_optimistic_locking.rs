@@ -32,6 +32,8 @@ async fn create_test_connection_manager() -> Arc<ConnectionManager> {
             validate_on_checkout: true,
             recycle_after_uses: None,
             shutdown_grace_period: Duration::from_secs(10),
+            query_timeout: Some(Duration::from_secs(30)),
+            slow_query_threshold: Some(Duration::from_secs(1)),
         },
         namespace: "test".to_string(),
         database: "test".to_string(),
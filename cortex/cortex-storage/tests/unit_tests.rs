@@ -180,6 +180,8 @@ fn test_schema_contains_all_tables() {
     assert!(SCHEMA.contains("DEFINE TABLE symbols"));
     assert!(SCHEMA.contains("DEFINE TABLE relations"));
     assert!(SCHEMA.contains("DEFINE TABLE episodes"));
+    assert!(SCHEMA.contains("DEFINE TABLE schema_meta"));
+    assert!(SCHEMA.contains("DEFINE TABLE component_versions"));
 }
 
 #[test]
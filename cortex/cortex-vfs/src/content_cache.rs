@@ -1,4 +1,11 @@
 //! Content caching with LRU eviction and TTL support.
+//!
+//! Objects are split by size: small objects share one byte budget, objects
+//! above `large_object_threshold` have their own, so one large artifact can
+//! never evict the small hot files. Large objects that do not fit their
+//! budget bypass the cache. Keys known not to exist are remembered for a
+//! short time (negative caching) so repeated lookups of missing paths do not
+//! reach storage.
 
 use dashmap::DashMap;
 use parking_lot::RwLock;
@@ -7,28 +14,71 @@ use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+/// Objects above this size are large unless configured otherwise
+pub const DEFAULT_LARGE_OBJECT_THRESHOLD: usize = 8 * 1024 * 1024;
+
+/// How long a missing key is remembered unless configured otherwise
+pub const DEFAULT_NEGATIVE_TTL: Duration = Duration::from_secs(2);
+
+/// Most missing keys remembered at once
+const MAX_NEGATIVE_ENTRIES: usize = 10_000;
+
+/// Configuration of a `ContentCache`.
+#[derive(Debug, Clone, Copy)]
+pub struct ContentCacheConfig {
+    /// Byte budget of small objects
+    pub max_size: usize,
+
+    /// Objects larger than this many bytes are large objects
+    pub large_object_threshold: usize,
+
+    /// Byte budget of large objects, on top of `max_size`. Large objects
+    /// bigger than this bypass the cache; 0 keeps all of them out.
+    pub large_object_budget: usize,
+
+    /// Time-to-live for entries
+    pub ttl: Option<Duration>,
+
+    /// How long a missing key is remembered; zero disables negative caching
+    pub negative_ttl: Duration,
+}
+
+impl ContentCacheConfig {
+    /// Defaults for a cache of `max_size` bytes of small objects, with a
+    /// quarter of that again for large objects.
+    pub fn new(max_size: usize) -> Self {
+        Self {
+            max_size,
+            large_object_threshold: DEFAULT_LARGE_OBJECT_THRESHOLD.min(max_size),
+            large_object_budget: max_size / 4,
+            ttl: None,
+            negative_ttl: DEFAULT_NEGATIVE_TTL,
+        }
+    }
+}
+
 /// Content cache with LRU eviction policy.
 ///
 /// Provides thread-safe caching of file content with:
-/// - Automatic eviction based on size limits
-/// - LRU (Least Recently Used) eviction policy
+/// - Automatic eviction based on size limits, weighted by bytes
+/// - LRU (Least Recently Used) eviction policy, per size class
 /// - TTL (Time To Live) support
-/// - Reference counting for shared content
+/// - Size-aware admission of large objects
+/// - Negative caching of missing keys
 pub struct ContentCache {
     /// Cached entries
     entries: Arc<DashMap<String, CacheEntry>>,
 
-    /// LRU queue for eviction
-    lru_queue: Arc<RwLock<VecDeque<String>>>,
+    /// LRU queue and byte count of small objects
+    small: Arc<Segment>,
 
-    /// Current cache size in bytes
-    size_bytes: Arc<AtomicUsize>,
+    /// LRU queue and byte count of large objects
+    large: Arc<Segment>,
 
-    /// Maximum cache size in bytes
-    max_size: usize,
+    /// Keys known not to exist, with when they were found missing
+    missing: Arc<DashMap<String, Instant>>,
 
-    /// Time-to-live for entries
-    ttl: Option<Duration>,
+    config: ContentCacheConfig,
 
     /// Cache statistics
     stats: CacheStats,
@@ -37,39 +87,53 @@ pub struct ContentCache {
 impl ContentCache {
     /// Create a new content cache with the given maximum size.
     pub fn new(max_size: usize) -> Self {
-        Self {
-            entries: Arc::new(DashMap::new()),
-            lru_queue: Arc::new(RwLock::new(VecDeque::new())),
-            size_bytes: Arc::new(AtomicUsize::new(0)),
-            max_size,
-            ttl: None,
-            stats: CacheStats::new(),
-        }
+        Self::with_config(ContentCacheConfig::new(max_size))
     }
 
     /// Create a cache with TTL support.
     pub fn with_ttl(max_size: usize, ttl: Duration) -> Self {
+        Self::with_config(ContentCacheConfig {
+            ttl: Some(ttl),
+            ..ContentCacheConfig::new(max_size)
+        })
+    }
+
+    /// Create a cache with explicit admission and negative caching settings.
+    pub fn with_config(config: ContentCacheConfig) -> Self {
         Self {
             entries: Arc::new(DashMap::new()),
-            lru_queue: Arc::new(RwLock::new(VecDeque::new())),
-            size_bytes: Arc::new(AtomicUsize::new(0)),
-            max_size,
-            ttl: Some(ttl),
+            small: Arc::new(Segment::new(config.max_size)),
+            large: Arc::new(Segment::new(config.large_object_budget)),
+            missing: Arc::new(DashMap::new()),
+            config,
             stats: CacheStats::new(),
         }
     }
 
     /// Get content from cache.
     pub fn get(&self, hash: &str) -> Option<Arc<Vec<u8>>> {
+        self.lookup(hash, None)
+    }
+
+    /// Get content whose size is known, so a miss is counted against the
+    /// right size class.
+    pub fn get_sized(&self, hash: &str, size: usize) -> Option<Arc<Vec<u8>>> {
+        self.lookup(hash, Some(size))
+    }
+
+    fn lookup(&self, hash: &str, size_hint: Option<usize>) -> Option<Arc<Vec<u8>>> {
+        let miss_class = size_hint.map(|size| self.is_large(size));
+
         // Check if entry exists
         if let Some(mut entry) = self.entries.get_mut(hash) {
             // Check TTL
-            if let Some(ttl) = self.ttl {
+            if let Some(ttl) = self.config.ttl {
                 if entry.created_at.elapsed() > ttl {
                     // Entry expired
+                    let large = entry.large;
                     drop(entry);
                     self.remove(hash);
-                    self.stats.record_miss();
+                    self.stats.record_miss(Some(large));
                     return None;
                 }
             }
@@ -77,30 +141,47 @@ impl ContentCache {
             // Update access time and count
             entry.last_accessed = Instant::now();
             entry.access_count += 1;
+            let large = entry.large;
+            let content = entry.content.clone();
+            drop(entry);
 
             // Update LRU queue
-            self.promote_in_lru(hash);
+            self.segment(large).promote(hash);
 
-            self.stats.record_hit();
-            Some(entry.content.clone())
+            self.stats.record_hit(large);
+            Some(content)
         } else {
-            self.stats.record_miss();
+            self.stats.record_miss(miss_class);
             None
         }
     }
 
     /// Put content into cache.
+    ///
+    /// Large objects that do not fit the large-object budget are not
+    /// cached; the content is returned either way.
     pub fn put(&self, hash: String, content: Vec<u8>) -> Arc<Vec<u8>> {
         let size = content.len();
         let arc_content = Arc::new(content);
+        let large = self.is_large(size);
+        let segment = self.segment(large);
+
+        if size > segment.max_size {
+            self.stats.record_bypass();
+            return arc_content;
+        }
+
+        // Replacing an entry must not count its bytes twice
+        self.take_entry(&hash);
 
         // Make room if needed
-        self.evict_if_needed(size);
+        self.evict_if_needed(segment, size);
 
         // Create new entry
         let entry = CacheEntry {
             content: arc_content.clone(),
             size,
+            large,
             created_at: Instant::now(),
             last_accessed: Instant::now(),
             access_count: 0,
@@ -108,10 +189,10 @@ impl ContentCache {
 
         // Insert into cache
         self.entries.insert(hash.clone(), entry);
-        self.size_bytes.fetch_add(size, Ordering::Relaxed);
+        segment.size_bytes.fetch_add(size, Ordering::Relaxed);
 
         // Add to LRU queue
-        self.lru_queue.write().push_back(hash.clone());
+        segment.lru.write().push_back(hash);
 
         self.stats.record_put();
 
@@ -120,31 +201,47 @@ impl ContentCache {
 
     /// Remove content from cache.
     pub fn remove(&self, hash: &str) {
-        if let Some((_, entry)) = self.entries.remove(hash) {
-            self.size_bytes.fetch_sub(entry.size, Ordering::Relaxed);
+        if self.take_entry(hash) {
+            self.stats.record_eviction();
+        }
+    }
 
-            // Remove from LRU queue
-            let mut queue = self.lru_queue.write();
-            if let Some(pos) = queue.iter().position(|k| k == hash) {
-                queue.remove(pos);
-            }
+    /// Remove an entry and its LRU slot; returns whether it was cached.
+    fn take_entry(&self, hash: &str) -> bool {
+        let Some((_, entry)) = self.entries.remove(hash) else {
+            return false;
+        };
+        let segment = self.segment(entry.large);
+        segment.size_bytes.fetch_sub(entry.size, Ordering::Relaxed);
 
-            self.stats.record_eviction();
+        // Remove from LRU queue
+        let mut queue = segment.lru.write();
+        if let Some(pos) = queue.iter().position(|k| k == hash) {
+            queue.remove(pos);
         }
+        true
     }
 
     /// Clear all entries from cache.
     pub fn clear(&self) {
         let count = self.entries.len();
         self.entries.clear();
-        self.lru_queue.write().clear();
-        self.size_bytes.store(0, Ordering::Relaxed);
+        for segment in [&self.small, &self.large] {
+            segment.lru.write().clear();
+            segment.size_bytes.store(0, Ordering::Relaxed);
+        }
+        self.missing.clear();
         self.stats.evictions.fetch_add(count as u64, Ordering::Relaxed);
     }
 
     /// Get current cache size in bytes.
     pub fn size_bytes(&self) -> usize {
-        self.size_bytes.load(Ordering::Relaxed)
+        self.small.size_bytes.load(Ordering::Relaxed) + self.large.size_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Bytes held by large objects.
+    pub fn large_size_bytes(&self) -> usize {
+        self.large.size_bytes.load(Ordering::Relaxed)
     }
 
     /// Get number of entries in cache.
@@ -162,25 +259,67 @@ impl ContentCache {
         self.stats.snapshot()
     }
 
+    /// Remember that `key` does not exist, for `negative_ttl`.
+    pub fn mark_missing(&self, key: impl Into<String>) {
+        if self.config.negative_ttl.is_zero() {
+            return;
+        }
+        if self.missing.len() >= MAX_NEGATIVE_ENTRIES {
+            let ttl = self.config.negative_ttl;
+            self.missing.retain(|_, found_at| found_at.elapsed() <= ttl);
+            if self.missing.len() >= MAX_NEGATIVE_ENTRIES {
+                self.missing.clear();
+            }
+        }
+        self.missing.insert(key.into(), Instant::now());
+    }
+
+    /// Whether `key` was found missing within `negative_ttl`. Counts a
+    /// negative hit when it was.
+    pub fn is_missing(&self, key: &str) -> bool {
+        let Some(found_at) = self.missing.get(key).map(|entry| *entry) else {
+            return false;
+        };
+        if found_at.elapsed() > self.config.negative_ttl {
+            self.missing.remove(key);
+            return false;
+        }
+        self.stats.record_negative_hit();
+        true
+    }
+
+    /// Forget that `key` was missing; call on any write to it.
+    pub fn invalidate_missing(&self, key: &str) {
+        self.missing.remove(key);
+    }
+
+    fn is_large(&self, size: usize) -> bool {
+        size > self.config.large_object_threshold
+    }
+
+    fn segment(&self, large: bool) -> &Segment {
+        if large { &self.large } else { &self.small }
+    }
+
     /// Evict entries if needed to make room for new content.
-    fn evict_if_needed(&self, needed_size: usize) {
-        let mut current_size = self.size_bytes.load(Ordering::Relaxed);
+    fn evict_if_needed(&self, segment: &Segment, needed_size: usize) {
+        let mut current_size = segment.size_bytes.load(Ordering::Relaxed);
 
         // Evict until we have enough space
-        while current_size + needed_size > self.max_size {
+        while current_size + needed_size > segment.max_size {
             // Pop from LRU queue
             let hash = {
-                let mut queue = self.lru_queue.write();
+                let mut queue = segment.lru.write();
                 queue.pop_front()
             };
 
             if let Some(hash) = hash {
                 // Remove from entries and update size
                 if let Some((_, entry)) = self.entries.remove(&hash) {
-                    self.size_bytes.fetch_sub(entry.size, Ordering::Relaxed);
+                    segment.size_bytes.fetch_sub(entry.size, Ordering::Relaxed);
                     self.stats.record_eviction();
                 }
-                current_size = self.size_bytes.load(Ordering::Relaxed);
+                current_size = segment.size_bytes.load(Ordering::Relaxed);
             } else {
                 // Nothing left to evict
                 break;
@@ -188,28 +327,12 @@ impl ContentCache {
         }
     }
 
-    /// Promote an entry in the LRU queue (mark as recently used).
-    /// Optimized to avoid full scan for frequently accessed items.
-    fn promote_in_lru(&self, hash: &str) {
-        let mut queue = self.lru_queue.write();
-
-        // Optimization: Check if already at the back (common for hot cache items)
-        if queue.back().map(|s| s.as_str()) == Some(hash) {
-            return;
-        }
-
-        // Find and remove from current position
-        if let Some(pos) = queue.iter().position(|k| k == hash) {
-            queue.remove(pos);
-        }
-
-        // Add to back (most recently used)
-        queue.push_back(hash.to_string());
-    }
-
     /// Remove expired entries.
     pub fn cleanup_expired(&self) {
-        if let Some(ttl) = self.ttl {
+        let negative_ttl = self.config.negative_ttl;
+        self.missing.retain(|_, found_at| found_at.elapsed() <= negative_ttl);
+
+        if let Some(ttl) = self.config.ttl {
             let now = Instant::now();
             let mut expired = Vec::new();
 
@@ -232,15 +355,51 @@ impl Clone for ContentCache {
     fn clone(&self) -> Self {
         Self {
             entries: Arc::clone(&self.entries),
-            lru_queue: Arc::clone(&self.lru_queue),
-            size_bytes: Arc::clone(&self.size_bytes),
-            max_size: self.max_size,
-            ttl: self.ttl,
+            small: Arc::clone(&self.small),
+            large: Arc::clone(&self.large),
+            missing: Arc::clone(&self.missing),
+            config: self.config,
             stats: self.stats.clone(),
         }
     }
 }
 
+/// LRU order and byte count of one size class.
+struct Segment {
+    lru: RwLock<VecDeque<String>>,
+    size_bytes: AtomicUsize,
+    max_size: usize,
+}
+
+impl Segment {
+    fn new(max_size: usize) -> Self {
+        Self {
+            lru: RwLock::new(VecDeque::new()),
+            size_bytes: AtomicUsize::new(0),
+            max_size,
+        }
+    }
+
+    /// Promote an entry in the LRU queue (mark as recently used).
+    /// Optimized to avoid full scan for frequently accessed items.
+    fn promote(&self, hash: &str) {
+        let mut queue = self.lru.write();
+
+        // Optimization: Check if already at the back (common for hot cache items)
+        if queue.back().map(|s| s.as_str()) == Some(hash) {
+            return;
+        }
+
+        // Find and remove from current position
+        if let Some(pos) = queue.iter().position(|k| k == hash) {
+            queue.remove(pos);
+        }
+
+        // Add to back (most recently used)
+        queue.push_back(hash.to_string());
+    }
+}
+
 /// Cache entry with metadata.
 struct CacheEntry {
     /// Cached content
@@ -249,6 +408,9 @@ struct CacheEntry {
     /// Size in bytes
     size: usize,
 
+    /// Whether the entry counts against the large-object budget
+    large: bool,
+
     /// When entry was created
     created_at: Instant,
 
@@ -262,41 +424,70 @@ struct CacheEntry {
 /// Cache statistics.
 #[derive(Clone)]
 struct CacheStats {
-    hits: Arc<AtomicU64>,
-    misses: Arc<AtomicU64>,
+    small_hits: Arc<AtomicU64>,
+    large_hits: Arc<AtomicU64>,
+    small_misses: Arc<AtomicU64>,
+    large_misses: Arc<AtomicU64>,
+    /// Misses of lookups that did not give a size
+    unsized_misses: Arc<AtomicU64>,
+    negative_hits: Arc<AtomicU64>,
     puts: Arc<AtomicU64>,
+    bypassed: Arc<AtomicU64>,
     evictions: Arc<AtomicU64>,
 }
 
 impl CacheStats {
     fn new() -> Self {
         Self {
-            hits: Arc::new(AtomicU64::new(0)),
-            misses: Arc::new(AtomicU64::new(0)),
+            small_hits: Arc::new(AtomicU64::new(0)),
+            large_hits: Arc::new(AtomicU64::new(0)),
+            small_misses: Arc::new(AtomicU64::new(0)),
+            large_misses: Arc::new(AtomicU64::new(0)),
+            unsized_misses: Arc::new(AtomicU64::new(0)),
+            negative_hits: Arc::new(AtomicU64::new(0)),
             puts: Arc::new(AtomicU64::new(0)),
+            bypassed: Arc::new(AtomicU64::new(0)),
             evictions: Arc::new(AtomicU64::new(0)),
         }
     }
 
-    fn record_hit(&self) {
-        self.hits.fetch_add(1, Ordering::Relaxed);
+    fn record_hit(&self, large: bool) {
+        let counter = if large { &self.large_hits } else { &self.small_hits };
+        counter.fetch_add(1, Ordering::Relaxed);
     }
 
-    fn record_miss(&self) {
-        self.misses.fetch_add(1, Ordering::Relaxed);
+    fn record_miss(&self, large: Option<bool>) {
+        let counter = match large {
+            Some(true) => &self.large_misses,
+            Some(false) => &self.small_misses,
+            None => &self.unsized_misses,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn record_negative_hit(&self) {
+        self.negative_hits.fetch_add(1, Ordering::Relaxed);
     }
 
     fn record_put(&self) {
         self.puts.fetch_add(1, Ordering::Relaxed);
     }
 
+    fn record_bypass(&self) {
+        self.bypassed.fetch_add(1, Ordering::Relaxed);
+    }
+
     fn record_eviction(&self) {
         self.evictions.fetch_add(1, Ordering::Relaxed);
     }
 
     fn snapshot(&self) -> CacheStatistics {
-        let hits = self.hits.load(Ordering::Relaxed);
-        let misses = self.misses.load(Ordering::Relaxed);
+        let small_hits = self.small_hits.load(Ordering::Relaxed);
+        let large_hits = self.large_hits.load(Ordering::Relaxed);
+        let small_misses = self.small_misses.load(Ordering::Relaxed);
+        let large_misses = self.large_misses.load(Ordering::Relaxed);
+        let hits = small_hits + large_hits;
+        let misses = small_misses + large_misses + self.unsized_misses.load(Ordering::Relaxed);
         let total_requests = hits + misses;
         let hit_rate = if total_requests > 0 {
             hits as f64 / total_requests as f64
@@ -307,7 +498,13 @@ impl CacheStats {
         CacheStatistics {
             hits,
             misses,
+            small_hits,
+            small_misses,
+            large_hits,
+            large_misses,
+            negative_hits: self.negative_hits.load(Ordering::Relaxed),
             puts: self.puts.load(Ordering::Relaxed),
+            bypassed: self.bypassed.load(Ordering::Relaxed),
             evictions: self.evictions.load(Ordering::Relaxed),
             hit_rate,
         }
@@ -315,11 +512,22 @@ impl CacheStats {
 }
 
 /// Snapshot of cache statistics.
+///
+/// `misses` also counts lookups made without a size, which are in neither
+/// `small_misses` nor `large_misses`.
 #[derive(Debug, Clone, Copy)]
 pub struct CacheStatistics {
     pub hits: u64,
     pub misses: u64,
+    pub small_hits: u64,
+    pub small_misses: u64,
+    pub large_hits: u64,
+    pub large_misses: u64,
+    /// Lookups answered by negative caching
+    pub negative_hits: u64,
     pub puts: u64,
+    /// Large objects not cached because they exceed the large-object budget
+    pub bypassed: u64,
     pub evictions: u64,
     pub hit_rate: f64,
 }
@@ -465,4 +673,116 @@ mod tests {
         assert_eq!(stats.misses, 2);
         assert_eq!(stats.hit_rate, 0.5);
     }
+
+    fn large_object_config() -> ContentCacheConfig {
+        ContentCacheConfig {
+            max_size: 1000,
+            large_object_threshold: 100,
+            large_object_budget: 500,
+            ttl: None,
+            negative_ttl: Duration::from_millis(50),
+        }
+    }
+
+    #[test]
+    fn test_size_aware_admission() {
+        let cache = ContentCache::with_config(large_object_config());
+
+        cache.put("small".to_string(), vec![0; 50]);
+        cache.put("large".to_string(), vec![1; 400]);
+        assert_eq!(cache.large_size_bytes(), 400);
+
+        // Too big for the large-object budget: returned but not cached
+        let content = cache.put("huge".to_string(), vec![2; 600]);
+        assert_eq!(content.len(), 600);
+        assert!(cache.get_sized("huge", 600).is_none());
+
+        // A second large object evicts the first, not the small one
+        cache.put("large2".to_string(), vec![3; 400]);
+        assert!(cache.get_sized("large", 400).is_none());
+        assert!(cache.get_sized("large2", 400).is_some());
+        assert!(cache.get_sized("small", 50).is_some());
+
+        let stats = cache.stats();
+        assert_eq!(stats.bypassed, 1);
+        assert_eq!(stats.small_hits, 1);
+        assert_eq!(stats.large_hits, 1);
+        assert_eq!(stats.large_misses, 2);
+        assert_eq!(stats.misses, 2);
+
+        // Replacing an entry does not count its bytes twice
+        cache.put("small".to_string(), vec![0; 50]);
+        assert_eq!(cache.size_bytes(), 450);
+    }
+
+    #[test]
+    fn test_negative_cache_ttl_and_invalidation() {
+        let cache = ContentCache::with_config(large_object_config());
+
+        assert!(!cache.is_missing("ws:/docs/a.md"));
+        cache.mark_missing("ws:/docs/a.md");
+        assert!(cache.is_missing("ws:/docs/a.md"));
+        assert!(cache.is_missing("ws:/docs/a.md"));
+
+        // A write to the path forgets it
+        cache.invalidate_missing("ws:/docs/a.md");
+        assert!(!cache.is_missing("ws:/docs/a.md"));
+
+        // Entries expire after the negative TTL
+        cache.mark_missing("ws:/docs/b.md");
+        thread::sleep(Duration::from_millis(100));
+        assert!(!cache.is_missing("ws:/docs/b.md"));
+
+        assert_eq!(cache.stats().negative_hits, 2);
+
+        // A zero TTL disables negative caching
+        let cache = ContentCache::with_config(ContentCacheConfig {
+            negative_ttl: Duration::ZERO,
+            ..large_object_config()
+        });
+        cache.mark_missing("ws:/docs/c.md");
+        assert!(!cache.is_missing("ws:/docs/c.md"));
+    }
+
+    #[test]
+    fn test_hot_small_files_survive_large_scan() {
+        let cache = Arc::new(ContentCache::with_config(ContentCacheConfig {
+            max_size: 64 * 1024,
+            large_object_threshold: 16 * 1024,
+            large_object_budget: 256 * 1024,
+            ttl: None,
+            negative_ttl: DEFAULT_NEGATIVE_TTL,
+        }));
+
+        // 32 hot files of 1KB, half the small budget
+        for i in 0..32 {
+            cache.put(format!("hot{}", i), vec![i as u8; 1024]);
+        }
+
+        // Scan 200 large files on several threads while readers keep
+        // touching the hot set
+        let mut handles = vec![];
+        for t in 0..4 {
+            let cache = Arc::clone(&cache);
+            handles.push(thread::spawn(move || {
+                for i in 0..50 {
+                    let key = format!("large{}_{}", t, i);
+                    cache.put(key.clone(), vec![0; 100 * 1024]);
+                    cache.get_sized(&key, 100 * 1024);
+                    cache.get_sized(&format!("hot{}", (t * 50 + i) % 32), 1024);
+                }
+            }));
+        }
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        for i in 0..32 {
+            assert!(cache.get_sized(&format!("hot{}", i), 1024).is_some(), "hot{} was evicted", i);
+        }
+        let stats = cache.stats();
+        assert_eq!(stats.small_misses, 0);
+        assert!(stats.small_hits >= 200 + 32);
+        assert!(stats.evictions > 0);
+    }
 }
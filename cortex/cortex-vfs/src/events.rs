@@ -0,0 +1,276 @@
+//! Live change events of the virtual filesystem.
+//!
+//! Every change the VFS makes to a workspace is published on a
+//! [`VfsEventBus`] as a [`VfsEvent`] with a process-wide, increasing sequence
+//! number. Subscribers receive events as they happen; the bus also keeps a
+//! bounded backlog of recent events so a subscriber that reconnects can
+//! replay what it missed from its last sequence number.
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::types::NodeType;
+
+/// Default number of events buffered per subscriber before it lags.
+pub const DEFAULT_CHANNEL_CAPACITY: usize = 1024;
+
+/// Default number of recent events kept for replay.
+pub const DEFAULT_BACKLOG_CAPACITY: usize = 1024;
+
+/// What changed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum VfsEventKind {
+    /// A file, directory or symlink was created
+    NodeCreated { path: String, node_type: NodeType },
+    /// A node's content or attributes changed
+    NodeUpdated { path: String },
+    /// A node was deleted
+    NodeDeleted { path: String },
+    /// A node moved to another path
+    NodeMoved { from: String, to: String },
+    /// Workspace metadata or settings changed
+    WorkspaceUpdated { fields: Vec<String> },
+    /// Progress of a sync, import or ingestion run
+    SyncProgress {
+        operation: String,
+        processed: usize,
+        total: usize,
+        done: bool,
+    },
+}
+
+impl VfsEventKind {
+    /// Names accepted by [`VfsEventKind::name`], for filters.
+    pub const NAMES: &'static [&'static str] = &[
+        "node_created",
+        "node_updated",
+        "node_deleted",
+        "node_moved",
+        "workspace_updated",
+        "sync_progress",
+    ];
+
+    /// The `type` tag of the event.
+    pub fn name(&self) -> &'static str {
+        match self {
+            VfsEventKind::NodeCreated { .. } => "node_created",
+            VfsEventKind::NodeUpdated { .. } => "node_updated",
+            VfsEventKind::NodeDeleted { .. } => "node_deleted",
+            VfsEventKind::NodeMoved { .. } => "node_moved",
+            VfsEventKind::WorkspaceUpdated { .. } => "workspace_updated",
+            VfsEventKind::SyncProgress { .. } => "sync_progress",
+        }
+    }
+}
+
+/// A change to a workspace.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VfsEvent {
+    /// Sequence number, increasing across all workspaces
+    pub seq: u64,
+    pub workspace_id: Uuid,
+    pub timestamp: DateTime<Utc>,
+    #[serde(flatten)]
+    pub kind: VfsEventKind,
+}
+
+/// Events retained for a subscriber resuming after a sequence number.
+#[derive(Debug, Clone, Default)]
+pub struct Backlog {
+    /// The workspace's retained events after the cursor, oldest first
+    pub events: Vec<Arc<VfsEvent>>,
+    /// Whether events after the cursor were already evicted, so the replay
+    /// is incomplete and the subscriber must resynchronize
+    pub truncated: bool,
+}
+
+/// Broadcast bus of VFS events.
+///
+/// Cheap to clone; clones publish to and subscribe on the same bus.
+#[derive(Clone)]
+pub struct VfsEventBus {
+    inner: Arc<BusInner>,
+}
+
+struct BusInner {
+    sender: broadcast::Sender<Arc<VfsEvent>>,
+    backlog_capacity: usize,
+    /// Retained events and the next sequence number. Publishing sends while
+    /// holding the lock, so the backlog and the channel agree on order.
+    state: Mutex<BusState>,
+}
+
+struct BusState {
+    next_seq: u64,
+    backlog: VecDeque<Arc<VfsEvent>>,
+}
+
+impl Default for VfsEventBus {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_CHANNEL_CAPACITY, DEFAULT_BACKLOG_CAPACITY)
+    }
+}
+
+impl VfsEventBus {
+    /// Bus buffering `channel_capacity` events per subscriber and retaining
+    /// the last `backlog_capacity` events for replay.
+    pub fn with_capacity(channel_capacity: usize, backlog_capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(channel_capacity.max(1));
+        Self {
+            inner: Arc::new(BusInner {
+                sender,
+                backlog_capacity,
+                state: Mutex::new(BusState {
+                    next_seq: 1,
+                    backlog: VecDeque::with_capacity(backlog_capacity),
+                }),
+            }),
+        }
+    }
+
+    /// Publish a change to a workspace. Returns the event's sequence number.
+    pub fn publish(&self, workspace_id: Uuid, kind: VfsEventKind) -> u64 {
+        let mut state = self.inner.state.lock();
+        let event = Arc::new(VfsEvent {
+            seq: state.next_seq,
+            workspace_id,
+            timestamp: Utc::now(),
+            kind,
+        });
+        state.next_seq += 1;
+
+        if self.inner.backlog_capacity > 0 {
+            if state.backlog.len() == self.inner.backlog_capacity {
+                state.backlog.pop_front();
+            }
+            state.backlog.push_back(event.clone());
+        }
+
+        // No subscribers is not an error
+        let _ = self.inner.sender.send(event.clone());
+        event.seq
+    }
+
+    /// Receive every event published from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<VfsEvent>> {
+        self.inner.sender.subscribe()
+    }
+
+    /// Receive every event published from now on, plus the retained events
+    /// of `workspace_id` after sequence number `since`. No event is both
+    /// replayed and received, and none in between is lost.
+    pub fn subscribe_since(
+        &self,
+        workspace_id: &Uuid,
+        since: u64,
+    ) -> (broadcast::Receiver<Arc<VfsEvent>>, Backlog) {
+        let state = self.inner.state.lock();
+        let receiver = self.inner.sender.subscribe();
+
+        // Sequence numbers are global, so an evicted event may have belonged
+        // to another workspace; report a gap conservatively.
+        let oldest = state.backlog.front().map_or(state.next_seq, |event| event.seq);
+        let backlog = Backlog {
+            events: state
+                .backlog
+                .iter()
+                .filter(|event| event.seq > since && event.workspace_id == *workspace_id)
+                .cloned()
+                .collect(),
+            truncated: since + 1 < oldest && since + 1 < state.next_seq,
+        };
+
+        (receiver, backlog)
+    }
+
+    /// Sequence number of the most recent event, 0 if none was published.
+    pub fn last_seq(&self) -> u64 {
+        self.inner.state.lock().next_seq - 1
+    }
+
+    /// Number of active subscribers.
+    pub fn subscriber_count(&self) -> usize {
+        self.inner.sender.receiver_count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn updated(path: &str) -> VfsEventKind {
+        VfsEventKind::NodeUpdated { path: path.to_string() }
+    }
+
+    #[test]
+    fn test_event_serialization() {
+        let event = VfsEvent {
+            seq: 7,
+            workspace_id: Uuid::nil(),
+            timestamp: Utc::now(),
+            kind: VfsEventKind::NodeMoved {
+                from: "a.md".to_string(),
+                to: "b.md".to_string(),
+            },
+        };
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "node_moved");
+        assert_eq!(json["seq"], 7);
+        assert_eq!(json["from"], "a.md");
+        assert_eq!(serde_json::from_value::<VfsEvent>(json).unwrap(), event);
+        assert!(VfsEventKind::NAMES.contains(&event.kind.name()));
+    }
+
+    #[tokio::test]
+    async fn test_publish_and_subscribe() {
+        let bus = VfsEventBus::default();
+        let workspace = Uuid::new_v4();
+        let mut first = bus.subscribe();
+        let mut second = bus.subscribe();
+
+        assert_eq!(bus.publish(workspace, updated("a.md")), 1);
+        assert_eq!(bus.publish(workspace, updated("b.md")), 2);
+
+        for receiver in [&mut first, &mut second] {
+            assert_eq!(receiver.recv().await.unwrap().kind, updated("a.md"));
+            assert_eq!(receiver.recv().await.unwrap().seq, 2);
+        }
+        assert_eq!(bus.last_seq(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_since_replays_backlog() {
+        let bus = VfsEventBus::with_capacity(16, 3);
+        let workspace = Uuid::new_v4();
+        let other = Uuid::new_v4();
+
+        bus.publish(workspace, updated("a.md"));
+        bus.publish(other, updated("x.md"));
+        bus.publish(workspace, updated("b.md"));
+
+        let (mut receiver, backlog) = bus.subscribe_since(&workspace, 1);
+        assert!(!backlog.truncated);
+        let seqs: Vec<u64> = backlog.events.iter().map(|event| event.seq).collect();
+        assert_eq!(seqs, vec![3]);
+
+        bus.publish(workspace, updated("c.md"));
+        assert_eq!(receiver.recv().await.unwrap().seq, 4);
+
+        // Event 1 has been evicted: a cursor before it is incomplete
+        let (_, backlog) = bus.subscribe_since(&workspace, 0);
+        assert!(backlog.truncated);
+        assert_eq!(backlog.events.len(), 2);
+
+        // A cursor at the head has nothing to replay
+        let (_, backlog) = bus.subscribe_since(&workspace, bus.last_seq());
+        assert!(!backlog.truncated);
+        assert!(backlog.events.is_empty());
+    }
+}
@@ -1,5 +1,7 @@
 //! External project loader for importing external content into VFS.
 
+use crate::events::VfsEventKind;
+use crate::ingest_journal::{IngestJournal, IngestStage, ResumePoint};
 use crate::path::VirtualPath;
 use crate::types::*;
 use crate::virtual_filesystem::VirtualFileSystem;
@@ -13,19 +15,32 @@ use uuid::Uuid;
 
 /// Loader for importing external projects and documents into VFS.
 ///
+/// Imports are read-only by default (`ImportOptions::read_only`): the VFS
+/// refuses writes to them. To edit imported content, fork the workspace with
+/// `ForkManager::create_fork` and work on the fork.
+///
 /// Supports:
 /// - Read-only import of external projects
 /// - Selective file inclusion/exclusion patterns
 /// - Language detection and code parsing
 /// - Automatic content deduplication
+/// - Resuming interrupted imports from an ingestion journal
 pub struct ExternalProjectLoader {
     vfs: VirtualFileSystem,
+    journal: Option<IngestJournal>,
 }
 
 impl ExternalProjectLoader {
     /// Create a new external project loader.
     pub fn new(vfs: VirtualFileSystem) -> Self {
-        Self { vfs }
+        Self { vfs, journal: None }
+    }
+
+    /// Record stored files in an ingestion journal, and skip files the
+    /// journal already has with unchanged content.
+    pub fn with_journal(mut self, journal: IngestJournal) -> Self {
+        self.journal = Some(journal);
+        self
     }
 
     /// Import an external project into VFS.
@@ -160,6 +175,14 @@ impl ExternalProjectLoader {
 
         report.duration_ms = start.elapsed().as_millis() as u64;
 
+        let files = report.files_imported + report.files_resumed + report.files_skipped;
+        self.vfs.events().publish(*workspace_id, VfsEventKind::SyncProgress {
+            operation: "import".to_string(),
+            processed: files,
+            total: files,
+            done: true,
+        });
+
         info!(
             "Import completed: {} files, {} directories in {}ms",
             report.files_imported, report.directories_imported, report.duration_ms
@@ -209,6 +232,7 @@ impl ExternalProjectLoader {
             parent_workspace: None,
             fork_metadata: None,
             dependencies: vec![],
+            version_retention: Default::default(),
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         };
@@ -272,14 +296,20 @@ impl ExternalProjectLoader {
                 self.import_directory_node(workspace_id, &virtual_path, options).await?;
                 report.directories_imported += 1;
             } else if path.is_file() {
-                let size = self.import_file_node(
+                let (size, resume) = self.import_file_node(
                     workspace_id,
                     path,
                     &virtual_path,
                     options,
                 ).await?;
-                report.files_imported += 1;
-                report.bytes_imported += size;
+                match resume {
+                    ResumePoint::Start => {
+                        report.files_imported += 1;
+                        report.bytes_imported += size;
+                    }
+                    ResumePoint::After(_) => report.files_resumed += 1,
+                    ResumePoint::Done => report.files_skipped += 1,
+                }
             }
         }
 
@@ -303,13 +333,16 @@ impl ExternalProjectLoader {
     }
 
     /// Import a file node.
+    ///
+    /// Returns the file's size and where the journal says its ingestion
+    /// resumes; files the journal has already stored are not stored again.
     async fn import_file_node(
         &self,
         workspace_id: &Uuid,
         physical_path: &Path,
         virtual_path: &VirtualPath,
         options: &ImportOptions,
-    ) -> Result<usize> {
+    ) -> Result<(usize, ResumePoint)> {
         // Read file content
         let content = fs::read(physical_path).await
             .map_err(|e| CortexError::vfs(format!("Failed to read file: {}", e)))?;
@@ -319,6 +352,14 @@ impl ExternalProjectLoader {
         // Calculate content hash
         let content_hash = blake3::hash(&content).to_hex().to_string();
 
+        if let Some(journal) = &self.journal {
+            let resume = journal.resume_point(virtual_path, &content_hash).await?;
+            if resume != ResumePoint::Start {
+                debug!("Already stored, skipping: {}", virtual_path);
+                return Ok((size, resume));
+            }
+        }
+
         // Create vnode
         let mut vnode = VNode::new_file(
             *workspace_id,
@@ -339,12 +380,18 @@ impl ExternalProjectLoader {
         // Save vnode
         self.save_vnode(&vnode).await?;
 
-        // Store content (will be deduplicated)
-        self.vfs.write_file(workspace_id, virtual_path, &content).await?;
+        // Store content (will be deduplicated). The vnode is already saved,
+        // and going through write_file would be refused for a read-only
+        // import.
+        self.vfs.store_content(&content_hash, &content).await?;
+
+        if let Some(journal) = &self.journal {
+            journal.record(virtual_path, &content_hash, IngestStage::Stored).await?;
+        }
 
         debug!("Imported file: {} ({} bytes)", virtual_path, size);
 
-        Ok(size)
+        Ok((size, ResumePoint::Start))
     }
 
     /// Check if a path should be included based on patterns.
@@ -439,7 +486,7 @@ impl ExternalProjectLoader {
         let mut vnode = VNode::new_file(
             *workspace_id,
             virtual_path.clone(),
-            content_hash,
+            content_hash.clone(),
             content.len(),
         );
 
@@ -451,7 +498,7 @@ impl ExternalProjectLoader {
         }
 
         self.save_vnode(&vnode).await?;
-        self.vfs.write_file(workspace_id, &virtual_path, &content).await?;
+        self.vfs.store_content(&content_hash, &content).await?;
 
         Ok(())
     }
@@ -490,6 +537,8 @@ mod tests {
                 validate_on_checkout: false,
                 recycle_after_uses: None,
                 shutdown_grace_period: std::time::Duration::from_secs(5),
+                query_timeout: Some(std::time::Duration::from_secs(30)),
+                slow_query_threshold: Some(std::time::Duration::from_secs(1)),
             },
             namespace: "test".to_string(),
             database: "test".to_string(),
@@ -52,6 +52,10 @@ impl ForkManager {
         let mut fork_metadata = source.metadata.clone();
         fork_metadata.insert("is_fork".to_string(), serde_json::Value::Bool(true));
         fork_metadata.insert("source_workspace_id".to_string(), serde_json::Value::String(source_workspace_id.to_string()));
+        // The fork is editable whatever the source's freeze state
+        for key in ["frozen_by", "frozen_at", "thawed_by", "thawed_at"] {
+            fork_metadata.remove(key);
+        }
 
         let fork = Workspace {
             id: Uuid::new_v4(),
@@ -69,6 +73,7 @@ impl ForkManager {
             }),
             dependencies: source.dependencies.clone(), // Inherit dependencies from source
             created_at: chrono::Utc::now(),
+            version_retention: Default::default(),
             updated_at: chrono::Utc::now(),
         };
 
@@ -159,6 +164,10 @@ impl ForkManager {
                 .await
                 .map_err(|e| CortexError::storage(e.to_string()))?;
 
+            if !fork_vnode.attributes.is_empty() {
+                self.vfs.index_attributes(&fork_vnode).await?;
+            }
+
             // Note: Content is already deduplicated by hash, so no need to copy content
         }
 
@@ -177,11 +186,9 @@ impl ForkManager {
         let fork = self.get_workspace(fork_id).await?;
         let target = self.get_workspace(target_id).await?;
 
-        // Check that target is not read-only
+        // Frozen workspaces take no merges; thaw the target first
         if target.read_only {
-            return Err(CortexError::invalid_input(
-                "Cannot merge into read-only workspace"
-            ));
+            return Err(CortexError::workspace_read_only(target.id));
         }
 
         // Find changes in fork since fork point
@@ -450,6 +457,11 @@ impl ForkManager {
             .await
             .map_err(|e| CortexError::storage(e.to_string()))?;
 
+        self.vfs.forget_missing(target_workspace_id, &target_vnode.path);
+        if !target_vnode.attributes.is_empty() {
+            self.vfs.index_attributes(&target_vnode).await?;
+        }
+
         Ok(())
     }
 
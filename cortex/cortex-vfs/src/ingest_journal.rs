@@ -0,0 +1,264 @@
+//! Progress journal for resumable ingestion.
+//!
+//! Ingestion records, per file, the content hash it worked on and the last
+//! pipeline stage it completed. An interrupted ingestion can then be re-run
+//! without redoing finished work: files whose content is unchanged and whose
+//! final stage completed are skipped, and partially ingested files resume
+//! after their last completed stage.
+//!
+//! Every stage update is a single `UPSERT`, so the journal never claims a
+//! stage that was not completed.
+
+use crate::path::VirtualPath;
+use chrono::{DateTime, Utc};
+use cortex_core::error::{CortexError, Result};
+use cortex_storage::ConnectionManager;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Ingestion pipeline stages, in the order a file goes through them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IngestStage {
+    /// Content and vnode saved in the VFS
+    Stored,
+    /// Parsed into code units, stored in semantic memory
+    Chunked,
+    /// Code units embedded for semantic search
+    Embedded,
+}
+
+impl std::fmt::Display for IngestStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            IngestStage::Stored => "stored",
+            IngestStage::Chunked => "chunked",
+            IngestStage::Embedded => "embedded",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Journal record of one file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub path: String,
+    pub content_hash: String,
+    /// Last stage completed for `content_hash`
+    pub stage: IngestStage,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// What ingestion still has to do for a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResumePoint {
+    /// Unknown or changed content: run every stage
+    Start,
+    /// Unchanged content: run the stages after this one
+    After(IngestStage),
+    /// Unchanged content that completed the final stage
+    Done,
+}
+
+impl JournalEntry {
+    /// Where ingestion of a file with `content_hash` resumes, given the
+    /// stage that completes ingestion.
+    pub fn resume_point(&self, content_hash: &str, final_stage: IngestStage) -> ResumePoint {
+        if self.content_hash != content_hash {
+            ResumePoint::Start
+        } else if self.stage >= final_stage {
+            ResumePoint::Done
+        } else {
+            ResumePoint::After(self.stage)
+        }
+    }
+}
+
+/// Summary of a workspace's journal.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct JournalStatus {
+    pub workspace_id: Uuid,
+    pub final_stage: Option<IngestStage>,
+    pub files: usize,
+    /// Files per last completed stage
+    pub stages: BTreeMap<IngestStage, usize>,
+    /// Files that completed the final stage
+    pub complete: usize,
+    pub last_updated: Option<DateTime<Utc>>,
+}
+
+/// Ingestion journal of one workspace.
+///
+/// Cheap to clone; clones share the storage connection.
+#[derive(Clone)]
+pub struct IngestJournal {
+    storage: Arc<ConnectionManager>,
+    workspace_id: Uuid,
+    final_stage: IngestStage,
+}
+
+impl IngestJournal {
+    /// Journal of a workspace whose ingestion completes at `final_stage`.
+    pub fn new(storage: Arc<ConnectionManager>, workspace_id: Uuid, final_stage: IngestStage) -> Self {
+        Self {
+            storage,
+            workspace_id,
+            final_stage,
+        }
+    }
+
+    pub fn workspace_id(&self) -> &Uuid {
+        &self.workspace_id
+    }
+
+    /// Stage that completes ingestion of a file.
+    pub fn final_stage(&self) -> IngestStage {
+        self.final_stage
+    }
+
+    /// Every entry of the workspace, by path.
+    pub async fn entries(&self) -> Result<HashMap<String, JournalEntry>> {
+        let conn = self.storage.acquire().await?;
+        let mut response = conn.connection()
+            .query("SELECT path, content_hash, stage, updated_at FROM ingest_journal WHERE workspace_id = $workspace_id")
+            .bind(("workspace_id", self.workspace_id.to_string()))
+            .await
+            .map_err(|e| CortexError::storage(e.to_string()))?;
+
+        let entries: Vec<JournalEntry> = response.take(0)
+            .map_err(|e| CortexError::storage(e.to_string()))?;
+        Ok(entries.into_iter().map(|entry| (entry.path.clone(), entry)).collect())
+    }
+
+    /// Entry of a single file.
+    pub async fn entry(&self, path: &VirtualPath) -> Result<Option<JournalEntry>> {
+        let conn = self.storage.acquire().await?;
+        let mut response = conn.connection()
+            .query("SELECT path, content_hash, stage, updated_at FROM type::thing('ingest_journal', [$workspace_id, $path])")
+            .bind(("workspace_id", self.workspace_id.to_string()))
+            .bind(("path", path.to_string()))
+            .await
+            .map_err(|e| CortexError::storage(e.to_string()))?;
+
+        let entry: Option<JournalEntry> = response.take(0)
+            .map_err(|e| CortexError::storage(e.to_string()))?;
+        Ok(entry)
+    }
+
+    /// Where ingestion of a file with `content_hash` resumes.
+    pub async fn resume_point(&self, path: &VirtualPath, content_hash: &str) -> Result<ResumePoint> {
+        Ok(self
+            .entry(path)
+            .await?
+            .map_or(ResumePoint::Start, |entry| entry.resume_point(content_hash, self.final_stage)))
+    }
+
+    /// Record that a file completed `stage` for `content_hash`.
+    pub async fn record(&self, path: &VirtualPath, content_hash: &str, stage: IngestStage) -> Result<()> {
+        let conn = self.storage.acquire().await?;
+        conn.connection()
+            .query(
+                "UPSERT type::thing('ingest_journal', [$workspace_id, $path]) SET
+                 workspace_id = $workspace_id, path = $path, content_hash = $content_hash,
+                 stage = $stage, updated_at = time::now()",
+            )
+            .bind(("workspace_id", self.workspace_id.to_string()))
+            .bind(("path", path.to_string()))
+            .bind(("content_hash", content_hash.to_string()))
+            .bind(("stage", stage))
+            .await
+            .map_err(|e| CortexError::storage(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Forget a file, or every file under a directory.
+    pub async fn remove(&self, path: &VirtualPath) -> Result<()> {
+        let conn = self.storage.acquire().await?;
+        conn.connection()
+            .query("DELETE ingest_journal WHERE workspace_id = $workspace_id AND (path = $path OR string::starts_with(path, $prefix))")
+            .bind(("workspace_id", self.workspace_id.to_string()))
+            .bind(("path", path.to_string()))
+            .bind(("prefix", format!("{}/", path)))
+            .await
+            .map_err(|e| CortexError::storage(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Drop the whole journal, so the next ingestion starts from scratch.
+    /// Returns the number of entries dropped.
+    pub async fn clear(&self) -> Result<usize> {
+        let count = self.entries().await?.len();
+
+        let conn = self.storage.acquire().await?;
+        conn.connection()
+            .query("DELETE ingest_journal WHERE workspace_id = $workspace_id")
+            .bind(("workspace_id", self.workspace_id.to_string()))
+            .await
+            .map_err(|e| CortexError::storage(e.to_string()))?;
+
+        Ok(count)
+    }
+
+    /// Summarize the journal.
+    pub async fn status(&self) -> Result<JournalStatus> {
+        let entries = self.entries().await?;
+
+        let mut status = JournalStatus {
+            workspace_id: self.workspace_id,
+            final_stage: Some(self.final_stage),
+            files: entries.len(),
+            ..Default::default()
+        };
+        for entry in entries.values() {
+            *status.stages.entry(entry.stage).or_default() += 1;
+            if entry.stage >= self.final_stage {
+                status.complete += 1;
+            }
+            status.last_updated = status.last_updated.max(Some(entry.updated_at));
+        }
+
+        Ok(status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(hash: &str, stage: IngestStage) -> JournalEntry {
+        JournalEntry {
+            path: "src/lib.rs".to_string(),
+            content_hash: hash.to_string(),
+            stage,
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_stages_are_ordered() {
+        assert!(IngestStage::Stored < IngestStage::Chunked);
+        assert!(IngestStage::Chunked < IngestStage::Embedded);
+        assert_eq!(serde_json::to_value(IngestStage::Chunked).unwrap(), "chunked");
+    }
+
+    #[test]
+    fn test_resume_point() {
+        let stored = entry("abc", IngestStage::Stored);
+        assert_eq!(
+            stored.resume_point("abc", IngestStage::Chunked),
+            ResumePoint::After(IngestStage::Stored)
+        );
+        assert_eq!(stored.resume_point("def", IngestStage::Chunked), ResumePoint::Start);
+
+        let chunked = entry("abc", IngestStage::Chunked);
+        assert_eq!(chunked.resume_point("abc", IngestStage::Chunked), ResumePoint::Done);
+        assert_eq!(
+            chunked.resume_point("abc", IngestStage::Embedded),
+            ResumePoint::After(IngestStage::Chunked)
+        );
+    }
+}
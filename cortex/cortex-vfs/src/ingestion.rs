@@ -13,6 +13,12 @@
 //! 5. Store code units in semantic memory
 //! 6. Update VNode metadata with units_count
 //!
+//! Markdown documents and Vue/Svelte components are not parsed directly.
+//! Instead, their fenced code blocks and `<script>` sections are extracted and
+//! parsed as embedded code; the resulting units keep the host file's path and
+//! line numbers and are flagged with `"embedded": true` and a `host_region`
+//! entry in their metadata.
+//!
 //! # Example
 //!
 //! ```no_run
@@ -41,7 +47,10 @@
 //! # }
 //! ```
 
+use crate::events::VfsEventKind;
+use crate::ingest_journal::{IngestJournal, IngestStage, ResumePoint};
 use crate::path::VirtualPath;
+use crate::types::VNode;
 use crate::virtual_filesystem::VirtualFileSystem;
 use cortex_core::error::{CortexError, Result};
 use cortex_core::id::CortexId;
@@ -50,7 +59,12 @@ use cortex_core::types::{
     Attribute, Complexity, CodeUnitStatus,
 };
 use cortex_memory::SemanticMemorySystem;
-use cortex_code_analysis::{CodeParser, FunctionInfo, StructInfo, EnumInfo, TraitInfo, ImplInfo};
+use cortex_memory::semantic::WORKSPACE_METADATA_KEY;
+use cortex_code_analysis::{
+    CodeParser, FunctionInfo, StructInfo, EnumInfo, TraitInfo, ImplInfo, ParsedFile,
+    EmbeddedBlock, HostFormat, extract_embedded,
+};
+use std::path::Path;
 use std::sync::Arc;
 use tracing::{debug, info, warn, error};
 use uuid::Uuid;
@@ -74,6 +88,12 @@ pub struct IngestionResult {
     /// Any errors encountered during ingestion
     pub errors: Vec<String>,
 
+    /// Embedded code blocks parsed from a markdown or component host file
+    pub embedded_blocks: usize,
+
+    /// Embedded code blocks skipped for a missing or unknown language tag
+    pub embedded_blocks_skipped: usize,
+
     /// Time taken in milliseconds
     pub duration_ms: u64,
 }
@@ -96,10 +116,37 @@ pub struct WorkspaceIngestionResult {
     /// Individual file results
     pub file_results: Vec<IngestionResult>,
 
+    /// Embedded code blocks skipped across all files
+    pub embedded_blocks_skipped: usize,
+
     /// Time taken in milliseconds
     pub duration_ms: u64,
 }
 
+/// Location of embedded code within its host file.
+struct HostRegion<'a> {
+    format: HostFormat,
+    block: &'a EmbeddedBlock,
+}
+
+impl HostRegion<'_> {
+    /// Flag a code unit as embedded and record where its block sits.
+    fn annotate(&self, code_unit: &mut CodeUnit) {
+        code_unit.metadata.insert("embedded".to_string(), serde_json::json!(true));
+        code_unit.metadata.insert(
+            "host_region".to_string(),
+            serde_json::json!({
+                "kind": self.format.region_kind(),
+                "tag": self.block.tag,
+                "start_line": self.block.start_line,
+                "end_line": self.block.end_line,
+                "setup": self.block.setup,
+            }),
+        );
+        code_unit.tags.push("embedded".to_string());
+    }
+}
+
 /// File ingestion pipeline connecting parser, VFS, and semantic memory.
 pub struct FileIngestionPipeline {
     /// Code parser for extracting structure
@@ -110,6 +157,9 @@ pub struct FileIngestionPipeline {
 
     /// Semantic memory for storing code units
     semantic_memory: Arc<SemanticMemorySystem>,
+
+    /// Progress journal shared with the importer, if ingestion is resumable
+    journal: Option<IngestJournal>,
 }
 
 impl FileIngestionPipeline {
@@ -123,9 +173,17 @@ impl FileIngestionPipeline {
             parser,
             vfs,
             semantic_memory,
+            journal: None,
         }
     }
 
+    /// Record progress in an ingestion journal, and resume files it has
+    /// only partially ingested.
+    pub fn with_journal(mut self, journal: IngestJournal) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
     /// Get reference to the semantic memory system (for testing).
     pub fn semantic_memory(&self) -> &Arc<SemanticMemorySystem> {
         &self.semantic_memory
@@ -189,8 +247,55 @@ impl FileIngestionPipeline {
             Language::Unknown
         };
 
-        // Skip non-code files
-        if matches!(language, Language::Unknown) {
+        let mut embedded_blocks = 0;
+        let mut embedded_blocks_skipped = 0;
+        let host_format = path.extension().and_then(HostFormat::from_extension);
+
+        if let (Language::Unknown, Some(format)) = (language, host_format) {
+            // Markdown and single-file components: parse the code they embed
+            let extraction = extract_embedded(format, &content_str);
+            embedded_blocks = extraction.blocks.len();
+            embedded_blocks_skipped = extraction.skipped.len();
+            for skipped in &extraction.skipped {
+                debug!(
+                    "Skipping embedded block at {}:{} ({:?})",
+                    path, skipped.start_line, skipped.reason
+                );
+            }
+
+            for block in &extraction.blocks {
+                let parsed = self.parser.lock().await.parse_embedded(&file_path, block);
+                match parsed {
+                    Ok(parsed_file) => {
+                        let block_language = block
+                            .lang
+                            .extensions()
+                            .first()
+                            .map(|ext| Language::from_extension(ext))
+                            .unwrap_or(Language::Unknown);
+                        let region = HostRegion { format, block };
+                        self.store_parsed_file(
+                            &parsed_file,
+                            &file_path,
+                            block_language,
+                            workspace_id,
+                            Some(&region),
+                            &mut unit_ids,
+                            &mut errors,
+                        )
+                        .await;
+                    }
+                    Err(e) => {
+                        warn!("Failed to parse {} block at {}:{}: {}", block.tag, path, block.start_line, e);
+                        errors.push(format!(
+                            "Parse error in {} block at line {}: {}",
+                            block.tag, block.start_line, e
+                        ));
+                    }
+                }
+            }
+        } else if matches!(language, Language::Unknown) {
+            // Skip non-code files
             debug!("Skipping non-code file: {}", path);
             return Ok(IngestionResult {
                 file_path,
@@ -198,33 +303,80 @@ impl FileIngestionPipeline {
                 unit_ids: vec![],
                 language,
                 errors: vec![],
+                embedded_blocks: 0,
+                embedded_blocks_skipped: 0,
                 duration_ms: start.elapsed().as_millis() as u64,
             });
+        } else {
+            // Parse file with cortex-code-analysis
+            let parsed_file = {
+                let mut parser = self.parser.lock().await;
+                match parser.parse_file_auto(&file_path, &content_str) {
+                    Ok(parsed) => parsed,
+                    Err(e) => {
+                        error!("Failed to parse file {}: {}", path, e);
+                        errors.push(format!("Parse error: {}", e));
+                        return Ok(IngestionResult {
+                            file_path,
+                            units_stored: 0,
+                            unit_ids: vec![],
+                            language,
+                            errors,
+                            embedded_blocks: 0,
+                            embedded_blocks_skipped: 0,
+                            duration_ms: start.elapsed().as_millis() as u64,
+                        });
+                    }
+                }
+            };
+
+            self.store_parsed_file(&parsed_file, &file_path, language, workspace_id, None, &mut unit_ids, &mut errors)
+                .await;
         }
 
-        // Parse file with cortex-code-analysis
-        let parsed_file = {
-            let mut parser = self.parser.lock().await;
-            match parser.parse_file_auto(&file_path, &content_str) {
-                Ok(parsed) => parsed,
-                Err(e) => {
-                    error!("Failed to parse file {}: {}", path, e);
-                    errors.push(format!("Parse error: {}", e));
-                    return Ok(IngestionResult {
-                        file_path,
-                        units_stored: 0,
-                        unit_ids: vec![],
-                        language,
-                        errors,
-                        duration_ms: start.elapsed().as_millis() as u64,
-                    });
-                }
+        let units_stored = unit_ids.len();
+
+        // Update VNode metadata with units count
+        if units_stored > 0 {
+            if let Err(e) = self.vfs.update_file_units_count(workspace_id, path, units_stored).await {
+                warn!("Failed to update file units count: {}", e);
+                errors.push(format!("Failed to update metadata: {}", e));
             }
-        };
+        }
+
+        info!(
+            "Ingested {} code units from {} in {}ms",
+            units_stored,
+            path,
+            start.elapsed().as_millis()
+        );
+
+        Ok(IngestionResult {
+            file_path,
+            units_stored,
+            unit_ids,
+            language,
+            errors,
+            embedded_blocks,
+            embedded_blocks_skipped,
+            duration_ms: start.elapsed().as_millis() as u64,
+        })
+    }
 
+    /// Convert and store every unit of a parsed file, collecting failures into `errors`.
+    async fn store_parsed_file(
+        &self,
+        parsed_file: &ParsedFile,
+        file_path: &str,
+        language: Language,
+        workspace_id: &Uuid,
+        region: Option<&HostRegion<'_>>,
+        unit_ids: &mut Vec<CortexId>,
+        errors: &mut Vec<String>,
+    ) {
         // Convert and store functions
         for func in &parsed_file.functions {
-            match self.convert_and_store_function(func, &file_path, language).await {
+            match self.convert_and_store_function(func, file_path, language, workspace_id, region).await {
                 Ok(id) => {
                     unit_ids.push(id);
                 }
@@ -237,7 +389,7 @@ impl FileIngestionPipeline {
 
         // Convert and store structs
         for struct_info in &parsed_file.structs {
-            match self.convert_and_store_struct(struct_info, &file_path, language).await {
+            match self.convert_and_store_struct(struct_info, file_path, language, workspace_id, region).await {
                 Ok(id) => {
                     unit_ids.push(id);
                 }
@@ -250,7 +402,7 @@ impl FileIngestionPipeline {
 
         // Convert and store enums
         for enum_info in &parsed_file.enums {
-            match self.convert_and_store_enum(enum_info, &file_path, language).await {
+            match self.convert_and_store_enum(enum_info, file_path, language, workspace_id, region).await {
                 Ok(id) => {
                     unit_ids.push(id);
                 }
@@ -263,7 +415,7 @@ impl FileIngestionPipeline {
 
         // Convert and store traits
         for trait_info in &parsed_file.traits {
-            match self.convert_and_store_trait(trait_info, &file_path, language).await {
+            match self.convert_and_store_trait(trait_info, file_path, language, workspace_id, region).await {
                 Ok(id) => {
                     unit_ids.push(id);
                 }
@@ -276,7 +428,7 @@ impl FileIngestionPipeline {
 
         // Convert and store impl blocks
         for impl_info in &parsed_file.impls {
-            match self.convert_and_store_impl(impl_info, &file_path, language).await {
+            match self.convert_and_store_impl(impl_info, file_path, language, workspace_id, region).await {
                 Ok(ids) => {
                     unit_ids.extend(ids);
                 }
@@ -286,32 +438,108 @@ impl FileIngestionPipeline {
                 }
             }
         }
+    }
 
-        let units_stored = unit_ids.len();
-
-        // Update VNode metadata with units count
-        if units_stored > 0 {
-            if let Err(e) = self.vfs.update_file_units_count(workspace_id, path, units_stored).await {
-                warn!("Failed to update file units count: {}", e);
-                errors.push(format!("Failed to update metadata: {}", e));
+    /// Sync a file from disk into the VFS and re-ingest its code units.
+    ///
+    /// Used by watch mode for created and modified files. Returns `None` when
+    /// the on-disk content matches the stored VNode, so unchanged saves do not
+    /// trigger re-parsing.
+    pub async fn sync_file_from_disk(
+        &self,
+        workspace_id: &Uuid,
+        physical_path: &Path,
+        path: &VirtualPath,
+    ) -> Result<Option<IngestionResult>> {
+        let content = tokio::fs::read(physical_path).await.map_err(|e| {
+            CortexError::vfs(format!("Failed to read file {}: {}", physical_path.display(), e))
+        })?;
+        let content_hash = blake3::hash(&content).to_hex().to_string();
+
+        let mut vnode = match self.vfs.get_vnode(workspace_id, path).await? {
+            Some(vnode) if vnode.content_hash.as_deref() == Some(content_hash.as_str()) => {
+                // Stored but never chunked, e.g. when an import was interrupted
+                if let Some(journal) = &self.journal {
+                    if let ResumePoint::After(stage) = journal.resume_point(path, &content_hash).await? {
+                        if stage < IngestStage::Chunked {
+                            debug!("Resuming ingestion after {}: {}", stage, path);
+                            return self.chunk_file(workspace_id, path, &content_hash).await.map(Some);
+                        }
+                    }
+                }
+                debug!("Content unchanged, skipping: {}", path);
+                return Ok(None);
+            }
+            Some(mut vnode) => {
+                vnode.content_hash = Some(content_hash.clone());
+                vnode.size_bytes = content.len();
+                vnode.mark_modified();
+                vnode
+            }
+            None => {
+                let mut vnode = VNode::new_file(
+                    *workspace_id,
+                    path.clone(),
+                    content_hash.clone(),
+                    content.len(),
+                );
+                if let Some(ext) = path.extension() {
+                    vnode.language = Some(crate::types::Language::from_extension(ext));
+                }
+                vnode
             }
+        };
+
+        // Write through the storage primitives rather than write_file, which
+        // rejects code files for agent edits
+        vnode.source_path = Some(physical_path.to_path_buf());
+        vnode.mark_synchronized();
+        self.vfs.store_content(&content_hash, &content).await?;
+        self.vfs.save_vnode(&vnode).await?;
+        if let Some(journal) = &self.journal {
+            journal.record(path, &content_hash, IngestStage::Stored).await?;
         }
 
-        info!(
-            "Ingested {} code units from {} in {}ms",
-            units_stored,
-            path,
-            start.elapsed().as_millis()
-        );
+        self.chunk_file(workspace_id, path, &content_hash).await.map(Some)
+    }
 
-        Ok(IngestionResult {
-            file_path,
-            units_stored,
-            unit_ids,
-            language,
-            errors,
-            duration_ms: start.elapsed().as_millis() as u64,
-        })
+    /// Replace a stored file's code units, recording the chunked stage in
+    /// the journal.
+    async fn chunk_file(
+        &self,
+        workspace_id: &Uuid,
+        path: &VirtualPath,
+        content_hash: &str,
+    ) -> Result<IngestionResult> {
+        self.mark_old_units_replaced(workspace_id, path).await?;
+        let result = self.ingest_file(workspace_id, path).await?;
+        if let Some(journal) = &self.journal {
+            journal.record(path, content_hash, IngestStage::Chunked).await?;
+        }
+        Ok(result)
+    }
+
+    /// Remove a deleted file or directory from the VFS and semantic memory.
+    ///
+    /// Returns the number of code units deleted.
+    pub async fn remove_path(
+        &self,
+        workspace_id: &Uuid,
+        path: &VirtualPath,
+    ) -> Result<usize> {
+        let units_deleted = self.semantic_memory
+            .delete_units_by_path(workspace_id, &path.to_string())
+            .await?;
+
+        if self.vfs.exists(workspace_id, path).await? {
+            self.vfs.delete(workspace_id, path, true).await?;
+        }
+        if let Some(journal) = &self.journal {
+            journal.remove(path).await?;
+        }
+
+        info!("Removed {} ({} code units)", path, units_deleted);
+        Ok(units_deleted)
     }
 
     /// Ingest all files in a workspace.
@@ -326,6 +554,7 @@ impl FileIngestionPipeline {
         let mut file_results = Vec::new();
         let mut files_with_errors = Vec::new();
         let mut total_units = 0;
+        let mut embedded_blocks_skipped = 0;
 
         // List all files in workspace
         let root = VirtualPath::root();
@@ -355,6 +584,7 @@ impl FileIngestionPipeline {
                 match self.ingest_file(workspace_id, &vnode.path).await {
                     Ok(result) => {
                         total_units += result.units_stored;
+                        embedded_blocks_skipped += result.embedded_blocks_skipped;
                         if !result.errors.is_empty() {
                             files_with_errors.push(result.file_path.clone());
                         }
@@ -367,6 +597,9 @@ impl FileIngestionPipeline {
                 }
             }
 
+            let processed = (batch_idx * BATCH_SIZE + batch.len()).min(total_files);
+            self.publish_progress(workspace_id, processed, total_files);
+
             // Yield to allow other tasks to run between batches
             tokio::task::yield_now().await;
         }
@@ -386,20 +619,111 @@ impl FileIngestionPipeline {
             total_units,
             files_with_errors,
             file_results,
+            embedded_blocks_skipped,
+            duration_ms: start.elapsed().as_millis() as u64,
+        })
+    }
+
+    /// Chunk every file the journal has stored but not yet chunked.
+    ///
+    /// Files are re-parsed from scratch, replacing any code units a previous,
+    /// interrupted run stored for them. Without a journal, nothing is pending.
+    pub async fn ingest_pending(
+        &self,
+        workspace_id: &Uuid,
+    ) -> Result<WorkspaceIngestionResult> {
+        let start = std::time::Instant::now();
+
+        let mut pending: Vec<_> = match &self.journal {
+            Some(journal) => journal
+                .entries()
+                .await?
+                .into_values()
+                .filter(|entry| entry.stage < IngestStage::Chunked && entry.stage < journal.final_stage())
+                .collect(),
+            None => Vec::new(),
+        };
+        pending.sort_by(|a, b| a.path.cmp(&b.path));
+        info!("Found {} files pending ingestion", pending.len());
+
+        let mut file_results = Vec::new();
+        let mut files_with_errors = Vec::new();
+        let mut total_units = 0;
+        let mut embedded_blocks_skipped = 0;
+
+        for (index, entry) in pending.iter().enumerate() {
+            let path = VirtualPath::new(&entry.path)?;
+            match self.chunk_file(workspace_id, &path, &entry.content_hash).await {
+                Ok(result) => {
+                    total_units += result.units_stored;
+                    embedded_blocks_skipped += result.embedded_blocks_skipped;
+                    if !result.errors.is_empty() {
+                        files_with_errors.push(result.file_path.clone());
+                    }
+                    file_results.push(result);
+                }
+                Err(e) => {
+                    error!("Failed to ingest file {}: {}", path, e);
+                    files_with_errors.push(entry.path.clone());
+                }
+            }
+            self.publish_progress(workspace_id, index + 1, pending.len());
+
+            // Yield to allow other tasks to run between files
+            tokio::task::yield_now().await;
+        }
+
+        Ok(WorkspaceIngestionResult {
+            workspace_id: *workspace_id,
+            files_processed: pending.len(),
+            total_units,
+            files_with_errors,
+            file_results,
+            embedded_blocks_skipped,
             duration_ms: start.elapsed().as_millis() as u64,
         })
     }
 
+    /// Publish ingestion progress on the VFS event bus.
+    fn publish_progress(&self, workspace_id: &Uuid, processed: usize, total: usize) {
+        self.vfs.events().publish(*workspace_id, VfsEventKind::SyncProgress {
+            operation: "ingest".to_string(),
+            processed,
+            total,
+            done: processed == total,
+        });
+    }
+
     // ========================================================================
     // Conversion Methods: ParsedFile → CodeUnit
     // ========================================================================
 
+    /// Store a converted unit, tagged with the workspace it belongs to and
+    /// flagged as embedded when it came from a host region.
+    async fn store_code_unit(
+        &self,
+        mut code_unit: CodeUnit,
+        workspace_id: &Uuid,
+        region: Option<&HostRegion<'_>>,
+    ) -> Result<CortexId> {
+        code_unit.metadata.insert(
+            WORKSPACE_METADATA_KEY.to_string(),
+            serde_json::json!(workspace_id.to_string()),
+        );
+        if let Some(region) = region {
+            region.annotate(&mut code_unit);
+        }
+        self.semantic_memory.store_unit(&code_unit).await
+    }
+
     /// Convert and store a function.
     async fn convert_and_store_function(
         &self,
         func: &FunctionInfo,
         file_path: &str,
         language: Language,
+        workspace_id: &Uuid,
+        region: Option<&HostRegion<'_>>,
     ) -> Result<CortexId> {
         let unit_type = if func.is_async {
             CodeUnitType::AsyncFunction
@@ -520,7 +844,7 @@ impl FileIngestionPipeline {
         };
 
         // Store in semantic memory
-        self.semantic_memory.store_unit(&code_unit).await
+        self.store_code_unit(code_unit, workspace_id, region).await
     }
 
     /// Convert and store a struct.
@@ -529,6 +853,8 @@ impl FileIngestionPipeline {
         struct_info: &StructInfo,
         file_path: &str,
         language: Language,
+        workspace_id: &Uuid,
+        region: Option<&HostRegion<'_>>,
     ) -> Result<CortexId> {
         let now = Utc::now();
         let code_unit = CodeUnit {
@@ -616,7 +942,7 @@ impl FileIngestionPipeline {
             metadata: std::collections::HashMap::new(),
         };
 
-        self.semantic_memory.store_unit(&code_unit).await
+        self.store_code_unit(code_unit, workspace_id, region).await
     }
 
     /// Convert and store an enum.
@@ -625,6 +951,8 @@ impl FileIngestionPipeline {
         enum_info: &EnumInfo,
         file_path: &str,
         language: Language,
+        workspace_id: &Uuid,
+        region: Option<&HostRegion<'_>>,
     ) -> Result<CortexId> {
         let now = Utc::now();
         let code_unit = CodeUnit {
@@ -712,7 +1040,7 @@ impl FileIngestionPipeline {
             metadata: std::collections::HashMap::new(),
         };
 
-        self.semantic_memory.store_unit(&code_unit).await
+        self.store_code_unit(code_unit, workspace_id, region).await
     }
 
     /// Convert and store a trait.
@@ -721,6 +1049,8 @@ impl FileIngestionPipeline {
         trait_info: &TraitInfo,
         file_path: &str,
         language: Language,
+        workspace_id: &Uuid,
+        region: Option<&HostRegion<'_>>,
     ) -> Result<CortexId> {
         let now = Utc::now();
         let code_unit = CodeUnit {
@@ -808,7 +1138,7 @@ impl FileIngestionPipeline {
             metadata: std::collections::HashMap::new(),
         };
 
-        self.semantic_memory.store_unit(&code_unit).await
+        self.store_code_unit(code_unit, workspace_id, region).await
     }
 
     /// Convert and store an impl block (returns multiple units for methods).
@@ -817,6 +1147,8 @@ impl FileIngestionPipeline {
         impl_info: &ImplInfo,
         file_path: &str,
         language: Language,
+        workspace_id: &Uuid,
+        region: Option<&HostRegion<'_>>,
     ) -> Result<Vec<CortexId>> {
         let mut unit_ids = Vec::new();
 
@@ -926,7 +1258,7 @@ impl FileIngestionPipeline {
                 metadata: std::collections::HashMap::new(),
             };
 
-            let id = self.semantic_memory.store_unit(&code_unit).await?;
+            let id = self.store_code_unit(code_unit, workspace_id, region).await?;
             unit_ids.push(id);
         }
 
@@ -974,6 +1306,8 @@ mod tests {
                 validate_on_checkout: false,
                 recycle_after_uses: Some(10000),
                 shutdown_grace_period: Duration::from_secs(30),
+                query_timeout: Some(Duration::from_secs(30)),
+                slow_query_threshold: Some(Duration::from_secs(1)),
             },
             namespace: "test".to_string(),
             database: "test".to_string(),
@@ -1052,4 +1386,105 @@ impl Calculator {
         assert!(result.units_stored >= 2);
         assert!(result.errors.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_ingest_markdown_embedded_blocks() {
+        let (pipeline, vfs, workspace_id) = create_test_pipeline().await;
+
+        let path = VirtualPath::new("docs/guide.md").unwrap();
+        let content = "# Guide\n\
+                       \n\
+                       ```rust\n\
+                       pub fn add(a: i32, b: i32) -> i32 {\n\
+                       \x20   a + b\n\
+                       }\n\
+                       ```\n\
+                       \n\
+                       ```\n\
+                       cargo run\n\
+                       ```\n\
+                       \n\
+                       ```haskell\n\
+                       main = pure ()\n\
+                       ```\n";
+
+        vfs.write_file(&workspace_id, &path, content.as_bytes())
+            .await
+            .unwrap();
+
+        let result = pipeline.ingest_file(&workspace_id, &path).await.unwrap();
+
+        assert_eq!(result.units_stored, 1);
+        assert_eq!(result.embedded_blocks, 1);
+        assert_eq!(result.embedded_blocks_skipped, 2);
+        assert!(result.errors.is_empty());
+
+        let units = pipeline
+            .semantic_memory()
+            .query_units_by_file(&workspace_id, "docs/guide.md")
+            .await
+            .unwrap();
+        let unit = units.iter().find(|u| u.name == "add").unwrap();
+        assert_eq!(unit.language, Language::Rust);
+        assert_eq!(unit.start_line, 4);
+        assert_eq!(unit.metadata.get("embedded"), Some(&serde_json::json!(true)));
+        assert_eq!(unit.metadata["host_region"]["start_line"], serde_json::json!(4));
+    }
+
+    #[tokio::test]
+    async fn test_ingest_vue_script_setup() {
+        let (pipeline, vfs, workspace_id) = create_test_pipeline().await;
+
+        let path = VirtualPath::new("src/App.vue").unwrap();
+        let content = "<template>\n  <button @click=\"greet\">Hi</button>\n</template>\n\n\
+                       <script setup lang=\"ts\">\nfunction greet(): void {}\n</script>\n";
+
+        vfs.write_file(&workspace_id, &path, content.as_bytes())
+            .await
+            .unwrap();
+
+        let result = pipeline.ingest_file(&workspace_id, &path).await.unwrap();
+
+        assert_eq!(result.units_stored, 1);
+        assert_eq!(result.embedded_blocks, 1);
+        assert!(result.errors.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sync_from_disk_and_remove() {
+        let (pipeline, vfs, workspace_id) = create_test_pipeline().await;
+        let dir = tempfile::tempdir().unwrap();
+        let physical = dir.path().join("lib.rs");
+        let path = VirtualPath::new("lib.rs").unwrap();
+
+        tokio::fs::write(&physical, "pub fn one() {}\n").await.unwrap();
+        let result = pipeline
+            .sync_file_from_disk(&workspace_id, &physical, &path)
+            .await
+            .unwrap()
+            .expect("new file should be ingested");
+        assert_eq!(result.units_stored, 1);
+
+        // Unchanged content is skipped
+        let result = pipeline
+            .sync_file_from_disk(&workspace_id, &physical, &path)
+            .await
+            .unwrap();
+        assert!(result.is_none());
+
+        // Another workspace ingesting the same relative path keeps its units
+        let other_workspace_id = Uuid::new_v4();
+        pipeline
+            .sync_file_from_disk(&other_workspace_id, &physical, &path)
+            .await
+            .unwrap()
+            .expect("new file should be ingested");
+
+        let removed = pipeline.remove_path(&workspace_id, &path).await.unwrap();
+        assert_eq!(removed, 1);
+        assert!(!vfs.exists(&workspace_id, &path).await.unwrap());
+
+        let removed = pipeline.remove_path(&other_workspace_id, &path).await.unwrap();
+        assert_eq!(removed, 1);
+    }
 }
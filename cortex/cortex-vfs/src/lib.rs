@@ -18,7 +18,9 @@
 //! - `MaterializationEngine`: Flush VFS to physical disk
 //! - `ExternalProjectLoader`: Import external projects
 //! - `ForkManager`: Create and merge forks
+//! - `SnapshotManager`: Snapshot workspaces and roll them back
 //! - `ContentCache`: LRU cache for frequently accessed content
+//! - `VfsEventBus`: Live change events for subscribers
 //!
 //! # Example
 //!
@@ -58,23 +60,39 @@ pub mod content_cache;
 pub mod materialization;
 pub mod external_loader;
 pub mod fork_manager;
+pub mod snapshot;
 pub mod watcher;
 pub mod cache;
 pub mod dedup;
+pub mod events;
 pub mod ingestion;
+pub mod ingest_journal;
 pub mod auto_reparse;
 
 // Re-export main types
 pub use path::{VirtualPath, VirtualPathError};
 pub use types::*;
 pub use virtual_filesystem::VirtualFileSystem;
-pub use content_cache::{ContentCache, CacheStatistics};
+pub use content_cache::{ContentCache, ContentCacheConfig, CacheStatistics};
 pub use materialization::MaterializationEngine;
 pub use external_loader::ExternalProjectLoader;
 pub use fork_manager::ForkManager;
+pub use snapshot::SnapshotManager;
 pub use watcher::{FileWatcher, WatcherConfig, FileEvent};
 pub use ingestion::{FileIngestionPipeline, IngestionResult, WorkspaceIngestionResult};
+pub use ingest_journal::{IngestJournal, IngestStage, JournalEntry, JournalStatus, ResumePoint};
 pub use auto_reparse::AutoReparseHandle;
+pub use events::{Backlog, VfsEvent, VfsEventBus, VfsEventKind};
+
+/// Version of the data the VFS keeps in the database (vnodes, content
+/// blobs, workspaces). Bump when their stored shape changes, and register a
+/// migration for the new version with the startup migration runner.
+pub const COMPONENT_VERSION: cortex_core::versioning::ComponentVersion =
+    cortex_core::versioning::ComponentVersion::new(
+        "cortex-vfs",
+        1,
+        &[cortex_storage::schema::COMPONENT_NAME, "cortex-memory"],
+    );
 
 /// Prelude module with commonly used types.
 pub mod prelude {
@@ -82,15 +100,17 @@ pub mod prelude {
     pub use crate::types::{
         Change, ChangeType, Conflict, DependencyType, FileContent, FileSyncResult,
         FlushOptions, FlushReport, FlushScope, ForkMetadata, ImportOptions, ImportReport,
-        Language, MergeReport, MergeStrategy, NodeType, SyncOptions, SyncReport,
-        SyncSource, SyncSourceStatus, SyncSourceType, SyncStatus, VNode,
-        Workspace, WorkspaceDependency,
+        Language, MergeReport, MergeStrategy, NodeType, RollbackReport, SnapshotEntry,
+        SnapshotInfo, SnapshotRetention, SyncOptions, SyncReport, SyncSource,
+        SyncSourceStatus, SyncSourceType, SyncStatus, VNode, Workspace,
+        WorkspaceDependency, WorkspaceSnapshot,
     };
     pub use crate::virtual_filesystem::VirtualFileSystem;
-    pub use crate::content_cache::{ContentCache, CacheStatistics};
+    pub use crate::content_cache::{ContentCache, ContentCacheConfig, CacheStatistics};
     pub use crate::materialization::MaterializationEngine;
     pub use crate::external_loader::ExternalProjectLoader;
     pub use crate::fork_manager::ForkManager;
+    pub use crate::snapshot::SnapshotManager;
     pub use crate::watcher::FileWatcher;
     pub use crate::ingestion::{FileIngestionPipeline, IngestionResult, WorkspaceIngestionResult};
 }
@@ -24,6 +24,7 @@ use crate::types::*;
 use crate::virtual_filesystem::VirtualFileSystem;
 use chrono::Utc;
 use cortex_core::error::{CortexError, Result};
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::time::Instant;
 use tokio::fs;
@@ -62,13 +63,6 @@ impl MaterializationEngine {
         let start = Instant::now();
         info!("Starting flush to: {}", target_path.display());
 
-        // Create backup if requested
-        let backup = if options.create_backup {
-            Some(self.create_backup(target_path).await?)
-        } else {
-            None
-        };
-
         // Collect changes to flush
         let changes = self.collect_changes(scope).await?;
 
@@ -80,6 +74,19 @@ impl MaterializationEngine {
             });
         }
 
+        // Read-only workspaces are never written out
+        let workspace_ids: HashSet<Uuid> = changes.iter().map(|v| v.workspace_id).collect();
+        for workspace_id in &workspace_ids {
+            self.vfs.ensure_writable(workspace_id).await?;
+        }
+
+        // Create backup if requested
+        let backup = if options.create_backup {
+            Some(self.create_backup(target_path).await?)
+        } else {
+            None
+        };
+
         info!("Flushing {} changes", changes.len());
 
         // Execute flush
@@ -1052,6 +1059,8 @@ mod tests {
                 validate_on_checkout: false,
                 recycle_after_uses: Some(10000),
                 shutdown_grace_period: Duration::from_secs(30),
+                query_timeout: Some(Duration::from_secs(30)),
+                slow_query_threshold: Some(Duration::from_secs(1)),
             },
             namespace: "test".to_string(),
             database: "test".to_string(),
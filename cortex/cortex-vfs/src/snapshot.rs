@@ -0,0 +1,346 @@
+//! Workspace snapshots and rollback.
+
+use crate::types::*;
+use crate::virtual_filesystem::VirtualFileSystem;
+use cortex_core::error::{CortexError, Result};
+use cortex_storage::ConnectionManager;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::info;
+use uuid::Uuid;
+
+/// Manager for taking workspace snapshots and rolling workspaces back to them.
+///
+/// Snapshots record vnodes and content hashes only; the content itself stays
+/// in the deduplicated content store, which keeps it alive for as long as a
+/// snapshot references it.
+pub struct SnapshotManager {
+    vfs: VirtualFileSystem,
+    storage: Arc<ConnectionManager>,
+}
+
+impl SnapshotManager {
+    /// Create a new snapshot manager.
+    pub fn new(vfs: VirtualFileSystem, storage: Arc<ConnectionManager>) -> Self {
+        Self { vfs, storage }
+    }
+
+    /// Snapshot every vnode of a workspace under `name`.
+    ///
+    /// Names are unique per workspace. Once the workspace holds more
+    /// snapshots than its retention allows, the oldest are dropped.
+    pub async fn create_snapshot(
+        &self,
+        workspace_id: &Uuid,
+        name: String,
+        description: Option<String>,
+        created_by: Option<String>,
+    ) -> Result<SnapshotInfo> {
+        info!("Creating snapshot '{}' of workspace {}", name, workspace_id);
+
+        self.ensure_workspace(workspace_id).await?;
+        if name.trim().is_empty() {
+            return Err(CortexError::invalid_input("Snapshot name must not be empty"));
+        }
+        if self.find_snapshot(workspace_id, &name).await?.is_some() {
+            return Err(CortexError::invalid_input(format!(
+                "Snapshot '{}' already exists in workspace {}",
+                name, workspace_id
+            )));
+        }
+
+        let mut entries: Vec<SnapshotEntry> = self
+            .live_vnodes(workspace_id)
+            .await?
+            .iter()
+            .map(SnapshotEntry::from_vnode)
+            .collect();
+        entries.sort_by(|a, b| a.path.to_string().cmp(&b.path.to_string()));
+
+        let snapshot = WorkspaceSnapshot {
+            info: SnapshotInfo {
+                id: Uuid::new_v4(),
+                workspace_id: *workspace_id,
+                name,
+                description,
+                created_by,
+                node_count: entries.len(),
+                total_bytes: entries.iter().map(|entry| entry.size_bytes).sum(),
+                created_at: chrono::Utc::now(),
+            },
+            entries,
+        };
+
+        let snapshot_json = serde_json::to_value(&snapshot)
+            .map_err(|e| CortexError::storage(e.to_string()))?;
+        let conn = self.storage.acquire().await?;
+        conn.connection()
+            .query("CREATE workspace_snapshot CONTENT $snapshot")
+            .bind(("snapshot", snapshot_json))
+            .await
+            .map_err(|e| CortexError::storage(e.to_string()))?
+            .check()
+            .map_err(|e| CortexError::storage(e.to_string()))?;
+
+        self.apply_retention(workspace_id).await?;
+
+        info!(
+            "Snapshot '{}' created with {} vnodes",
+            snapshot.info.name, snapshot.info.node_count
+        );
+
+        Ok(snapshot.info)
+    }
+
+    /// Snapshots of a workspace, newest first.
+    pub async fn list_snapshots(&self, workspace_id: &Uuid) -> Result<Vec<SnapshotInfo>> {
+        let conn = self.storage.acquire().await?;
+        let mut result = conn.connection()
+            .query("SELECT *, <string>meta::id(id) as id OMIT entries FROM workspace_snapshot WHERE workspace_id = $workspace_id ORDER BY created_at DESC")
+            .bind(("workspace_id", workspace_id.to_string()))
+            .await
+            .map_err(|e| CortexError::storage(e.to_string()))?;
+        let snapshots: Vec<SnapshotInfo> = result.take(0)
+            .map_err(|e| CortexError::storage(e.to_string()))?;
+
+        Ok(snapshots)
+    }
+
+    /// Get a snapshot, with its entries, by name.
+    pub async fn get_snapshot(&self, workspace_id: &Uuid, name: &str) -> Result<WorkspaceSnapshot> {
+        self.find_snapshot(workspace_id, name)
+            .await?
+            .ok_or_else(|| CortexError::not_found("Snapshot", name.to_string()))
+    }
+
+    /// Restore a workspace to a snapshot.
+    ///
+    /// Nodes created since the snapshot are deleted, and changed or deleted
+    /// ones are brought back. Files keep their version history, so the
+    /// content a rollback replaces can still be restored. Every change is
+    /// recorded under one change set, attributed to `actor`.
+    pub async fn rollback(
+        &self,
+        workspace_id: &Uuid,
+        name: &str,
+        actor: Option<&str>,
+    ) -> Result<RollbackReport> {
+        info!("Rolling back workspace {} to snapshot '{}'", workspace_id, name);
+
+        self.vfs.ensure_writable(workspace_id).await?;
+        let snapshot = self.get_snapshot(workspace_id, name).await?;
+        let retention = self.vfs.version_retention(workspace_id).await?;
+
+        let mut current: HashMap<String, VNode> = self
+            .live_vnodes(workspace_id)
+            .await?
+            .into_iter()
+            .map(|vnode| (vnode.path.to_string(), vnode))
+            .collect();
+
+        // Work out every change before making any, so a read-only node
+        // fails the rollback without leaving it half applied
+        let mut restores = Vec::new();
+        for entry in &snapshot.entries {
+            match current.remove(&entry.path.to_string()) {
+                Some(vnode) if entry.matches(&vnode) => {}
+                existing => restores.push((entry, existing)),
+            }
+        }
+        // Children sort after their parents, so deleting in reverse path
+        // order empties directories before they are removed
+        let mut deletions: Vec<VNode> = current.into_values().collect();
+        deletions.sort_by(|a, b| b.path.to_string().cmp(&a.path.to_string()));
+
+        let blocked = restores
+            .iter()
+            .filter_map(|(_, existing)| existing.as_ref())
+            .chain(&deletions)
+            .find(|vnode| vnode.read_only);
+        if let Some(vnode) = blocked {
+            return Err(CortexError::invalid_input(format!(
+                "Path is read-only: {}",
+                vnode.path
+            )));
+        }
+
+        let change_set = Uuid::new_v4();
+        let mut changes = Vec::new();
+
+        for (entry, existing) in restores {
+            let (mut vnode, change_type) = match existing {
+                Some(mut vnode) => {
+                    // Keep the content being replaced if it predates version history
+                    vnode.history = vnode.versions();
+                    entry.apply_to(&mut vnode);
+                    vnode.mark_modified();
+                    (vnode, ChangeType::Modified)
+                }
+                None => {
+                    let mut vnode = VNode::new_directory(*workspace_id, entry.path.clone());
+                    entry.apply_to(&mut vnode);
+                    vnode.status = SyncStatus::Created;
+                    (vnode, ChangeType::Created)
+                }
+            };
+
+            if vnode.is_file() {
+                vnode.record_version(actor.map(str::to_string), None, &retention);
+            }
+            self.vfs.save_vnode(&vnode).await?;
+            changes.push(self.change(&vnode, change_type, actor, change_set));
+        }
+
+        for vnode in deletions {
+            self.vfs.delete(workspace_id, &vnode.path, true).await?;
+            changes.push(self.change(&vnode, ChangeType::Deleted, actor, change_set));
+        }
+
+        self.record_changes(&changes).await?;
+
+        info!(
+            "Rolled back workspace {} to snapshot '{}': {} changes",
+            workspace_id, name, changes.len()
+        );
+
+        Ok(RollbackReport {
+            snapshot: snapshot.info,
+            change_set,
+            changes,
+        })
+    }
+
+    /// Retention policy of a workspace's snapshots; the default policy if
+    /// the workspace has none.
+    pub async fn snapshot_retention(&self, workspace_id: &Uuid) -> Result<SnapshotRetention> {
+        let conn = self.storage.acquire().await?;
+        let mut response = conn.connection()
+            .query("SELECT VALUE snapshot_retention FROM type::thing('workspace', $id)")
+            .bind(("id", workspace_id.to_string()))
+            .await
+            .map_err(|e| CortexError::storage(e.to_string()))?;
+
+        let retention: Option<SnapshotRetention> = response.take(0)
+            .map_err(|e| CortexError::storage(e.to_string()))?;
+        Ok(retention.unwrap_or_default())
+    }
+
+    /// Change how many snapshots a workspace keeps. Snapshots beyond the new
+    /// limit are dropped right away.
+    pub async fn set_snapshot_retention(
+        &self,
+        workspace_id: &Uuid,
+        retention: SnapshotRetention,
+    ) -> Result<()> {
+        let conn = self.storage.acquire().await?;
+        conn.connection()
+            .query("UPDATE type::thing('workspace', $id) SET snapshot_retention = $retention, updated_at = time::now()")
+            .bind(("id", workspace_id.to_string()))
+            .bind(("retention", retention))
+            .await
+            .map_err(|e| CortexError::storage(e.to_string()))?;
+
+        self.apply_retention(workspace_id).await
+    }
+
+    /// Drop the oldest snapshots beyond the workspace's retention limit.
+    async fn apply_retention(&self, workspace_id: &Uuid) -> Result<()> {
+        let Some(max) = self.snapshot_retention(workspace_id).await?.max_snapshots else {
+            return Ok(());
+        };
+
+        let snapshots = self.list_snapshots(workspace_id).await?;
+        let conn = self.storage.acquire().await?;
+        for snapshot in snapshots.iter().skip(max.max(1)) {
+            info!("Dropping snapshot '{}' of workspace {}", snapshot.name, workspace_id);
+            conn.connection()
+                .query("DELETE type::thing('workspace_snapshot', $id)")
+                .bind(("id", snapshot.id.to_string()))
+                .await
+                .map_err(|e| CortexError::storage(e.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    async fn find_snapshot(&self, workspace_id: &Uuid, name: &str) -> Result<Option<WorkspaceSnapshot>> {
+        let conn = self.storage.acquire().await?;
+        let mut result = conn.connection()
+            .query("SELECT *, <string>meta::id(id) as id FROM workspace_snapshot WHERE workspace_id = $workspace_id AND name = $name LIMIT 1")
+            .bind(("workspace_id", workspace_id.to_string()))
+            .bind(("name", name.to_string()))
+            .await
+            .map_err(|e| CortexError::storage(e.to_string()))?;
+        let snapshot: Option<WorkspaceSnapshot> = result.take(0)
+            .map_err(|e| CortexError::storage(e.to_string()))?;
+
+        Ok(snapshot)
+    }
+
+    /// Every vnode of a workspace that has not been deleted.
+    async fn live_vnodes(&self, workspace_id: &Uuid) -> Result<Vec<VNode>> {
+        let conn = self.storage.acquire().await?;
+        let mut result = conn.connection()
+            .query("SELECT *, <string>meta::id(id) as id FROM vnode WHERE workspace_id = $workspace_id AND status != 'deleted'")
+            .bind(("workspace_id", workspace_id.to_string()))
+            .await
+            .map_err(|e| CortexError::storage(e.to_string()))?;
+        let vnodes: Vec<VNode> = result.take(0)
+            .map_err(|e| CortexError::storage(e.to_string()))?;
+
+        Ok(vnodes)
+    }
+
+    async fn ensure_workspace(&self, workspace_id: &Uuid) -> Result<()> {
+        let conn = self.storage.acquire().await?;
+        let mut response = conn.connection()
+            .query("SELECT VALUE name FROM type::thing('workspace', $id)")
+            .bind(("id", workspace_id.to_string()))
+            .await
+            .map_err(|e| CortexError::storage(e.to_string()))?;
+
+        let name: Option<String> = response.take(0)
+            .map_err(|e| CortexError::storage(e.to_string()))?;
+        name.map(|_| ())
+            .ok_or_else(|| CortexError::not_found("Workspace", workspace_id.to_string()))
+    }
+
+    fn change(
+        &self,
+        vnode: &VNode,
+        change_type: ChangeType,
+        actor: Option<&str>,
+        change_set: Uuid,
+    ) -> Change {
+        Change {
+            id: Uuid::new_v4(),
+            vnode_id: vnode.id,
+            path: vnode.path.clone(),
+            change_type,
+            new_content_hash: match change_type {
+                ChangeType::Deleted => None,
+                _ => vnode.content_hash.clone(),
+            },
+            changed_by: actor.map(str::to_string),
+            change_set: Some(change_set),
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    async fn record_changes(&self, changes: &[Change]) -> Result<()> {
+        let conn = self.storage.acquire().await?;
+        for change in changes {
+            let change_json = serde_json::to_value(change)
+                .map_err(|e| CortexError::storage(e.to_string()))?;
+            conn.connection()
+                .query("CREATE change CONTENT $change")
+                .bind(("change", change_json))
+                .await
+                .map_err(|e| CortexError::storage(e.to_string()))?
+                .check()
+                .map_err(|e| CortexError::storage(e.to_string()))?;
+        }
+
+        Ok(())
+    }
+}
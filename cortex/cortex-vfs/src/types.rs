@@ -103,6 +103,16 @@ pub struct VNode {
     /// Extended metadata
     pub metadata: HashMap<String, Value>,
 
+    /// Retained versions of a file, oldest first; the last entry is the
+    /// current content
+    #[serde(default)]
+    pub history: Vec<FileVersionEntry>,
+
+    /// Free-form attributes attached by ingestion and analysis, queryable
+    /// through `VirtualFileSystem::query_nodes`
+    #[serde(default)]
+    pub attributes: HashMap<String, Value>,
+
     /// Creation timestamp
     pub created_at: DateTime<Utc>,
 
@@ -114,6 +124,9 @@ pub struct VNode {
 }
 
 impl VNode {
+    /// Most bytes the attributes of one node may take, serialized as JSON.
+    pub const MAX_ATTRIBUTES_BYTES: usize = 16 * 1024;
+
     /// Create a new file vnode.
     pub fn new_file(
         workspace_id: Uuid,
@@ -136,6 +149,8 @@ impl VNode {
             status: SyncStatus::Modified,
             version: 1,
             metadata: HashMap::new(),
+            history: Vec::new(),
+            attributes: HashMap::new(),
             created_at: now,
             updated_at: now,
             accessed_at: now,
@@ -159,6 +174,8 @@ impl VNode {
             status: SyncStatus::Modified,
             version: 1,
             metadata: HashMap::new(),
+            history: Vec::new(),
+            attributes: HashMap::new(),
             created_at: now,
             updated_at: now,
             accessed_at: now,
@@ -185,6 +202,8 @@ impl VNode {
             status: SyncStatus::Modified,
             version: 1,
             metadata,
+            history: Vec::new(),
+            attributes: HashMap::new(),
             created_at: now,
             updated_at: now,
             accessed_at: now,
@@ -218,6 +237,228 @@ impl VNode {
         self.updated_at = Utc::now();
         self.version += 1;
     }
+
+    /// Merge `attributes` into the node's attributes. A `null` value removes
+    /// the attribute. Returns whether anything changed.
+    pub fn merge_attributes(&mut self, attributes: HashMap<String, Value>) -> bool {
+        let mut changed = false;
+        for (key, value) in attributes {
+            changed |= if value.is_null() {
+                self.attributes.remove(&key).is_some()
+            } else {
+                self.attributes.insert(key, value.clone()).as_ref() != Some(&value)
+            };
+        }
+        changed
+    }
+
+    /// Size of the attributes serialized as JSON, as checked against
+    /// `MAX_ATTRIBUTES_BYTES`.
+    pub fn attributes_size(&self) -> usize {
+        serde_json::to_vec(&self.attributes).map_or(0, |bytes| bytes.len())
+    }
+
+    /// Retained versions of the file, oldest first. Files written before
+    /// history was kept report their current content as the only version.
+    pub fn versions(&self) -> Vec<FileVersionEntry> {
+        if !self.history.is_empty() || !self.is_file() {
+            return self.history.clone();
+        }
+        self.content_hash
+            .iter()
+            .map(|hash| FileVersionEntry {
+                version: self.version,
+                content_hash: hash.clone(),
+                size_bytes: self.size_bytes,
+                timestamp: self.updated_at,
+                author: None,
+                restored_from: None,
+            })
+            .collect()
+    }
+
+    /// Record the current content as the newest version, then drop the
+    /// versions `retention` no longer keeps.
+    pub fn record_version(
+        &mut self,
+        author: Option<String>,
+        restored_from: Option<u32>,
+        retention: &VersionRetention,
+    ) {
+        let Some(content_hash) = self.content_hash.clone() else {
+            return;
+        };
+        self.history.push(FileVersionEntry {
+            version: self.version,
+            content_hash,
+            size_bytes: self.size_bytes,
+            timestamp: self.updated_at,
+            author,
+            restored_from,
+        });
+        retention.apply(&mut self.history, Utc::now());
+    }
+}
+
+/// One version of a file's content.
+///
+/// Entries only reference content by hash, so with deduplication a version
+/// costs storage only when its content differs from every other version.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FileVersionEntry {
+    /// VNode version number the content was written as
+    pub version: u32,
+
+    /// Content hash (blake3)
+    pub content_hash: String,
+
+    /// Size in bytes
+    pub size_bytes: usize,
+
+    /// When the version was written
+    pub timestamp: DateTime<Utc>,
+
+    /// Author or agent session that wrote the version
+    pub author: Option<String>,
+
+    /// Version whose content this one restored
+    #[serde(default)]
+    pub restored_from: Option<u32>,
+}
+
+/// How many file versions a workspace keeps.
+///
+/// The current version is always kept, whatever the limits.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct VersionRetention {
+    /// Most versions kept per file
+    pub max_versions: Option<usize>,
+
+    /// Versions older than this many days are dropped
+    pub max_age_days: Option<u32>,
+}
+
+impl Default for VersionRetention {
+    fn default() -> Self {
+        Self {
+            max_versions: Some(50),
+            max_age_days: None,
+        }
+    }
+}
+
+impl VersionRetention {
+    /// Drop the versions of `history` (oldest first) outside the limits.
+    pub fn apply(&self, history: &mut Vec<FileVersionEntry>, now: DateTime<Utc>) {
+        if history.len() <= 1 {
+            return;
+        }
+
+        if let Some(days) = self.max_age_days {
+            let cutoff = now - chrono::Duration::days(i64::from(days));
+            let newest = history.len() - 1;
+            let mut index = 0;
+            history.retain(|entry| {
+                let keep = index == newest || entry.timestamp >= cutoff;
+                index += 1;
+                keep
+            });
+        }
+
+        if let Some(max) = self.max_versions {
+            let excess = history.len().saturating_sub(max.max(1));
+            history.drain(..excess);
+        }
+    }
+}
+
+/// Condition on one attribute of a node.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum AttributePredicate {
+    /// The attribute equals `value`
+    Eq { key: String, value: Value },
+    /// The attribute lies within the bounds (inclusive). Numbers compare
+    /// numerically and strings lexicographically; other values never match.
+    Range {
+        key: String,
+        min: Option<Value>,
+        max: Option<Value>,
+    },
+}
+
+impl AttributePredicate {
+    pub fn key(&self) -> &str {
+        match self {
+            AttributePredicate::Eq { key, .. } | AttributePredicate::Range { key, .. } => key,
+        }
+    }
+
+    pub fn matches(&self, attributes: &HashMap<String, Value>) -> bool {
+        let Some(actual) = attributes.get(self.key()) else {
+            return false;
+        };
+        match self {
+            AttributePredicate::Eq { value, .. } => actual == value,
+            AttributePredicate::Range { min, max, .. } => {
+                let above = min.as_ref().is_none_or(|min| {
+                    compare_values(actual, min).is_some_and(|ordering| ordering.is_ge())
+                });
+                let below = max.as_ref().is_none_or(|max| {
+                    compare_values(actual, max).is_some_and(|ordering| ordering.is_le())
+                });
+                above && below
+            }
+        }
+    }
+}
+
+fn compare_values(a: &Value, b: &Value) -> Option<std::cmp::Ordering> {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64()?.partial_cmp(&b.as_f64()?),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        _ => None,
+    }
+}
+
+/// Query over node attributes; a node matches when every predicate does.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct AttributeQuery {
+    pub predicates: Vec<AttributePredicate>,
+}
+
+impl AttributeQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require `key` to equal `value`.
+    pub fn eq(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.predicates.push(AttributePredicate::Eq {
+            key: key.into(),
+            value: value.into(),
+        });
+        self
+    }
+
+    /// Require `key` to lie within `min..=max`; `None` leaves a side open.
+    pub fn range(
+        mut self,
+        key: impl Into<String>,
+        min: Option<Value>,
+        max: Option<Value>,
+    ) -> Self {
+        self.predicates.push(AttributePredicate::Range {
+            key: key.into(),
+            min,
+            max,
+        });
+        self
+    }
+
+    pub fn matches(&self, attributes: &HashMap<String, Value>) -> bool {
+        self.predicates.iter().all(|predicate| predicate.matches(attributes))
+    }
 }
 
 /// Type of virtual node.
@@ -373,6 +614,10 @@ pub struct Workspace {
     /// Cross-workspace dependencies and links
     pub dependencies: Vec<WorkspaceDependency>,
 
+    /// How many versions of each file are kept
+    #[serde(default)]
+    pub version_retention: VersionRetention,
+
     /// Creation timestamp
     pub created_at: DateTime<Utc>,
 
@@ -645,6 +890,66 @@ impl Default for FlushOptions {
     }
 }
 
+/// Column a paged directory listing can be ordered by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListSortField {
+    Path,
+    SizeBytes,
+    CreatedAt,
+    UpdatedAt,
+}
+
+impl ListSortField {
+    /// Database column backing this sort field.
+    pub fn column(&self) -> &'static str {
+        match self {
+            ListSortField::Path => "path",
+            ListSortField::SizeBytes => "size_bytes",
+            ListSortField::CreatedAt => "created_at",
+            ListSortField::UpdatedAt => "updated_at",
+        }
+    }
+}
+
+/// Options for paged directory listings.
+#[derive(Debug, Clone)]
+pub struct ListOptions {
+    /// Include all descendants instead of direct children only
+    pub recursive: bool,
+
+    /// Only return nodes of this type
+    pub node_type: Option<NodeType>,
+
+    /// Only return nodes whose language contains this string (case-insensitive)
+    pub language: Option<String>,
+
+    /// Sort column
+    pub sort_field: ListSortField,
+
+    /// Sort descending
+    pub descending: bool,
+
+    /// Maximum number of nodes to return
+    pub limit: usize,
+
+    /// Number of nodes to skip
+    pub offset: usize,
+}
+
+impl Default for ListOptions {
+    fn default() -> Self {
+        Self {
+            recursive: false,
+            node_type: None,
+            language: None,
+            sort_field: ListSortField::CreatedAt,
+            descending: true,
+            limit: 20,
+            offset: 0,
+        }
+    }
+}
+
 /// Options for importing external projects.
 #[derive(Debug, Clone)]
 pub struct ImportOptions {
@@ -708,6 +1013,14 @@ pub struct ImportReport {
     /// Number of files imported
     pub files_imported: usize,
 
+    /// Files skipped because the ingestion journal has them fully ingested
+    #[serde(default)]
+    pub files_skipped: usize,
+
+    /// Files already stored whose ingestion resumes at a later stage
+    #[serde(default)]
+    pub files_resumed: usize,
+
     /// Number of directories imported
     pub directories_imported: usize,
 
@@ -799,6 +1112,10 @@ pub struct Change {
     /// Agent/session that made the change
     pub changed_by: Option<String>,
 
+    /// Operation the change belongs to, such as a snapshot rollback
+    #[serde(default, with = "uuid_option_serde")]
+    pub change_set: Option<Uuid>,
+
     /// Timestamp
     pub timestamp: DateTime<Utc>,
 }
@@ -813,6 +1130,146 @@ pub enum ChangeType {
     Renamed,
 }
 
+/// How many snapshots a workspace keeps.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SnapshotRetention {
+    /// Most snapshots kept per workspace; the oldest are dropped first
+    pub max_snapshots: Option<usize>,
+}
+
+impl Default for SnapshotRetention {
+    fn default() -> Self {
+        Self {
+            max_snapshots: Some(20),
+        }
+    }
+}
+
+/// Summary of a workspace snapshot, without its entries.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotInfo {
+    /// Snapshot ID
+    #[serde(with = "uuid_serde")]
+    pub id: Uuid,
+
+    /// Workspace the snapshot was taken of
+    #[serde(with = "uuid_serde")]
+    pub workspace_id: Uuid,
+
+    /// Name, unique within the workspace
+    pub name: String,
+
+    /// Optional description
+    pub description: Option<String>,
+
+    /// Agent/session that took the snapshot
+    pub created_by: Option<String>,
+
+    /// Number of vnodes recorded
+    pub node_count: usize,
+
+    /// Total size of the recorded files in bytes
+    pub total_bytes: usize,
+
+    /// When the snapshot was taken
+    pub created_at: DateTime<Utc>,
+}
+
+/// Named, immutable point-in-time record of every vnode in a workspace.
+///
+/// Entries reference content by hash, so with deduplication a snapshot
+/// costs no content storage of its own.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceSnapshot {
+    #[serde(flatten)]
+    pub info: SnapshotInfo,
+
+    /// Recorded vnodes, in path order
+    pub entries: Vec<SnapshotEntry>,
+}
+
+/// A vnode as recorded in a snapshot.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SnapshotEntry {
+    /// Virtual path
+    pub path: VirtualPath,
+
+    /// Type of node
+    pub node_type: NodeType,
+
+    /// Content hash (for files)
+    pub content_hash: Option<String>,
+
+    /// Size in bytes
+    pub size_bytes: usize,
+
+    /// Permissions (Unix-style)
+    pub permissions: Option<u32>,
+
+    /// Language detection (for code files)
+    pub language: Option<Language>,
+
+    /// Extended metadata
+    pub metadata: HashMap<String, Value>,
+}
+
+impl SnapshotEntry {
+    /// Record the current state of a vnode.
+    pub fn from_vnode(vnode: &VNode) -> Self {
+        Self {
+            path: vnode.path.clone(),
+            node_type: vnode.node_type,
+            content_hash: vnode.content_hash.clone(),
+            size_bytes: vnode.size_bytes,
+            permissions: vnode.permissions,
+            language: vnode.language,
+            metadata: vnode.metadata.clone(),
+        }
+    }
+
+    /// Whether `vnode` still matches the recorded state.
+    pub fn matches(&self, vnode: &VNode) -> bool {
+        self.node_type == vnode.node_type
+            && self.content_hash == vnode.content_hash
+            && self.permissions == vnode.permissions
+            && self.metadata == vnode.metadata
+    }
+
+    /// Bring `vnode` back to the recorded state.
+    pub fn apply_to(&self, vnode: &mut VNode) {
+        vnode.node_type = self.node_type;
+        vnode.content_hash = self.content_hash.clone();
+        vnode.size_bytes = self.size_bytes;
+        vnode.permissions = self.permissions;
+        vnode.language = self.language;
+        vnode.metadata = self.metadata.clone();
+    }
+}
+
+/// Report from rolling a workspace back to a snapshot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RollbackReport {
+    /// Snapshot the workspace was rolled back to
+    pub snapshot: SnapshotInfo,
+
+    /// Change set the changes below are recorded under
+    #[serde(with = "uuid_serde")]
+    pub change_set: Uuid,
+
+    /// Every vnode the rollback created, modified or deleted
+    pub changes: Vec<Change>,
+}
+
+impl RollbackReport {
+    /// Number of changes of one type.
+    pub fn count(&self, change_type: ChangeType) -> usize {
+        self.changes
+            .iter()
+            .filter(|change| change.change_type == change_type)
+            .count()
+    }
+}
+
 /// Configuration for automatic file re-parsing.
 #[derive(Debug, Clone)]
 pub struct AutoReparseConfig {
@@ -970,6 +1427,7 @@ mod tests {
             parent_workspace: None,
             fork_metadata: None,
             dependencies: vec![],
+            version_retention: Default::default(),
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -977,4 +1435,77 @@ mod tests {
         assert_eq!(ws.sync_sources.len(), 2);
         assert_eq!(ws.name, "multi-source-project");
     }
+
+    #[test]
+    fn test_version_history_retention() {
+        let path = VirtualPath::new("notes.md").unwrap();
+        let mut vnode = VNode::new_file(Uuid::new_v4(), path, "hash-0".to_string(), 10);
+
+        // A file written before history existed still reports its content
+        let legacy = vnode.versions();
+        assert_eq!(legacy.len(), 1);
+        assert_eq!(legacy[0].content_hash, "hash-0");
+
+        let retention = VersionRetention {
+            max_versions: Some(3),
+            max_age_days: None,
+        };
+        vnode.record_version(None, None, &retention);
+        for i in 1..5 {
+            vnode.content_hash = Some(format!("hash-{}", i));
+            vnode.mark_modified();
+            vnode.record_version(Some("session-1".to_string()), None, &retention);
+        }
+
+        let hashes: Vec<_> = vnode.versions().iter().map(|v| v.content_hash.clone()).collect();
+        assert_eq!(hashes, vec!["hash-2", "hash-3", "hash-4"]);
+        assert_eq!(vnode.history.last().unwrap().version, vnode.version);
+        assert_eq!(vnode.history.last().unwrap().author.as_deref(), Some("session-1"));
+
+        // Age limits never drop the current version
+        let now = Utc::now();
+        for entry in &mut vnode.history {
+            entry.timestamp = now - chrono::Duration::days(30);
+        }
+        VersionRetention {
+            max_versions: None,
+            max_age_days: Some(7),
+        }
+        .apply(&mut vnode.history, now);
+        assert_eq!(vnode.history.len(), 1);
+        assert_eq!(vnode.history[0].content_hash, "hash-4");
+    }
+
+    #[test]
+    fn test_attribute_merge_and_query() {
+        let path = VirtualPath::new("README.md").unwrap();
+        let mut vnode = VNode::new_file(Uuid::new_v4(), path, "hash".to_string(), 10);
+
+        let mut attrs = HashMap::new();
+        attrs.insert("team".to_string(), Value::from("search"));
+        attrs.insert("lint_score".to_string(), Value::from(0.82));
+        assert!(vnode.merge_attributes(attrs.clone()));
+        assert!(!vnode.merge_attributes(attrs));
+
+        // Null removes, other keys are left alone
+        let mut update = HashMap::new();
+        update.insert("team".to_string(), Value::Null);
+        update.insert("owner".to_string(), Value::from("alice"));
+        assert!(vnode.merge_attributes(update));
+        assert!(!vnode.attributes.contains_key("team"));
+        assert_eq!(vnode.attributes["lint_score"], Value::from(0.82));
+        assert!(vnode.attributes_size() < VNode::MAX_ATTRIBUTES_BYTES);
+
+        let query = AttributeQuery::new()
+            .eq("owner", "alice")
+            .range("lint_score", Some(Value::from(0.5)), None);
+        assert!(query.matches(&vnode.attributes));
+
+        let query = AttributeQuery::new().range("lint_score", None, Some(Value::from(0.8)));
+        assert!(!query.matches(&vnode.attributes));
+
+        // Mismatched types never satisfy a range
+        let query = AttributeQuery::new().range("owner", Some(Value::from(1)), None);
+        assert!(!query.matches(&vnode.attributes));
+    }
 }
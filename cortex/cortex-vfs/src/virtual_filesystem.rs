@@ -1,12 +1,14 @@
 //! Core Virtual Filesystem implementation.
 
 use crate::content_cache::ContentCache;
+use crate::events::{VfsEventBus, VfsEventKind};
 use crate::path::VirtualPath;
 use crate::types::*;
 use cortex_core::error::{CortexError, Result};
 use cortex_storage::ConnectionManager;
 use lru::LruCache;
 use parking_lot::Mutex;
+use std::collections::{HashMap, HashSet};
 use std::num::NonZeroUsize;
 use std::sync::Arc;
 use tracing::{debug, warn, error};
@@ -37,6 +39,9 @@ pub struct VirtualFileSystem {
 
     /// Auto-reparse handle (optional)
     auto_reparse: Option<Arc<crate::auto_reparse::AutoReparseHandle>>,
+
+    /// Change events, shared by all clones
+    events: VfsEventBus,
 }
 
 /// Check if a path represents a code file that should not be edited via VFS.
@@ -44,6 +49,14 @@ pub struct VirtualFileSystem {
 /// VFS is designed for documents, reports, and configuration files.
 /// Code files should be edited directly in the filesystem to ensure proper
 /// IDE support, syntax checking, and integration with development workflows.
+/// Matches `file_content` records not referenced by a live VNode, by
+/// version history or by a workspace snapshot (which keep old blobs alive for
+/// restores and rollbacks)
+const ORPHANED_CONTENT_FILTER: &str = "content_hash NOTINSIDE (SELECT VALUE content_hash FROM vnode WHERE status != 'deleted' AND content_hash != NONE) \
+     AND content_hash NOTINSIDE array::flatten((SELECT VALUE history.content_hash FROM vnode WHERE status != 'deleted')) \
+     AND content_hash NOTINSIDE (SELECT VALUE content_hash FROM version_history) \
+     AND content_hash NOTINSIDE array::flatten((SELECT VALUE entries.content_hash FROM workspace_snapshot))";
+
 fn is_code_file(path: &VirtualPath) -> bool {
     if let Some(ext) = path.extension() {
         matches!(
@@ -85,6 +98,7 @@ impl VirtualFileSystem {
                 LruCache::new(NonZeroUsize::new(vnode_cache_size).unwrap())
             )),
             auto_reparse: None,
+            events: VfsEventBus::default(),
         }
     }
 
@@ -129,6 +143,7 @@ impl VirtualFileSystem {
                 LruCache::new(NonZeroUsize::new(10_000).unwrap())
             )),
             auto_reparse: Some(Arc::new(auto_reparse_handle)),
+            events: VfsEventBus::default(),
         }
     }
 
@@ -155,6 +170,15 @@ impl VirtualFileSystem {
         self.auto_reparse.is_some()
     }
 
+    /// Bus on which every change this VFS makes is published.
+    pub fn events(&self) -> &VfsEventBus {
+        &self.events
+    }
+
+    fn publish(&self, workspace_id: &Uuid, kind: VfsEventKind) {
+        self.events.publish(*workspace_id, kind);
+    }
+
     // ============================================================================
     // Core File Operations
     // ============================================================================
@@ -181,7 +205,7 @@ impl VirtualFileSystem {
             .ok_or_else(|| CortexError::internal("File has no content hash"))?;
 
         // Try cache first
-        if let Some(content) = self.content_cache.get(&content_hash) {
+        if let Some(content) = self.content_cache.get_sized(&content_hash, vnode.size_bytes) {
             debug!("Cache hit for content hash: {}", content_hash);
             return Ok((*content).clone());
         }
@@ -202,6 +226,42 @@ impl VirtualFileSystem {
         path: &VirtualPath,
         content: &[u8],
     ) -> Result<()> {
+        self.write_file_as(workspace_id, path, content, None).await
+    }
+
+    /// Write file content to VFS, recording `author` (a user or agent
+    /// session id) on the new version.
+    ///
+    /// The previous content stays in the file's version history, within the
+    /// workspace's retention policy.
+    pub async fn write_file_as(
+        &self,
+        workspace_id: &Uuid,
+        path: &VirtualPath,
+        content: &[u8],
+        author: Option<&str>,
+    ) -> Result<()> {
+        let created = self.store_file(workspace_id, path, content, author).await?;
+
+        let path = path.to_string();
+        self.publish(workspace_id, if created {
+            VfsEventKind::NodeCreated { path, node_type: NodeType::File }
+        } else {
+            VfsEventKind::NodeUpdated { path }
+        });
+
+        Ok(())
+    }
+
+    /// Write a file without publishing an event. Returns whether the file
+    /// was created.
+    async fn store_file(
+        &self,
+        workspace_id: &Uuid,
+        path: &VirtualPath,
+        content: &[u8],
+        author: Option<&str>,
+    ) -> Result<bool> {
         debug!("Writing file: {} in workspace {}", path, workspace_id);
 
         // Reject code files - VFS is only for documents, reports, and configuration
@@ -216,6 +276,8 @@ impl VirtualFileSystem {
             ));
         }
 
+        self.ensure_writable(workspace_id).await?;
+
         // Calculate content hash
         let content_hash = Self::hash_content(content);
 
@@ -223,7 +285,9 @@ impl VirtualFileSystem {
         self.store_content(&content_hash, content).await?;
 
         // Get or create vnode
-        let vnode = if let Some(mut vnode) = self.get_vnode(workspace_id, path).await? {
+        let existing = self.get_vnode(workspace_id, path).await?;
+        let created = existing.is_none();
+        let mut vnode = if let Some(mut vnode) = existing {
             // Check if read-only
             if vnode.read_only {
                 return Err(CortexError::invalid_input(
@@ -231,6 +295,9 @@ impl VirtualFileSystem {
                 ));
             }
 
+            // Keep the content being replaced if it predates version history
+            vnode.history = vnode.versions();
+
             // Update existing vnode
             vnode.content_hash = Some(content_hash.clone());
             vnode.size_bytes = content.len();
@@ -254,13 +321,102 @@ impl VirtualFileSystem {
             vnode
         };
 
+        let retention = self.version_retention(workspace_id).await?;
+        vnode.record_version(author.map(str::to_string), None, &retention);
+
         // Save vnode to database
         self.save_vnode(&vnode).await?;
 
         // Cache content
         self.content_cache.put(content_hash, content.to_vec());
 
-        Ok(())
+        Ok(created)
+    }
+
+    /// Retained versions of a file, oldest first; the last one is the
+    /// current content.
+    pub async fn get_history(
+        &self,
+        workspace_id: &Uuid,
+        path: &VirtualPath,
+    ) -> Result<Vec<FileVersionEntry>> {
+        let vnode = self.get_vnode(workspace_id, path).await?
+            .ok_or_else(|| CortexError::not_found("File", path.to_string()))?;
+
+        if !vnode.is_file() {
+            return Err(CortexError::invalid_input(format!("Not a file: {}", path)));
+        }
+
+        Ok(vnode.versions())
+    }
+
+    /// Content of a version returned by `get_history`.
+    pub async fn read_version(&self, entry: &FileVersionEntry) -> Result<Vec<u8>> {
+        if let Some(content) = self.content_cache.get_sized(&entry.content_hash, entry.size_bytes) {
+            return Ok((*content).clone());
+        }
+
+        let content = self.load_content_from_db(&entry.content_hash).await?;
+        self.content_cache.put(entry.content_hash.clone(), content.clone());
+
+        Ok(content)
+    }
+
+    /// Make the content of an earlier version current again.
+    ///
+    /// `version_index` indexes the list returned by `get_history`. History is
+    /// never rewritten: the restored content becomes a new head version that
+    /// records which version it came from.
+    pub async fn restore_version(
+        &self,
+        workspace_id: &Uuid,
+        path: &VirtualPath,
+        version_index: usize,
+    ) -> Result<VNode> {
+        debug!("Restoring version {} of {} in workspace {}", version_index, path, workspace_id);
+
+        self.ensure_writable(workspace_id).await?;
+
+        let mut vnode = self.get_vnode(workspace_id, path).await?
+            .ok_or_else(|| CortexError::not_found("File", path.to_string()))?;
+
+        if !vnode.is_file() {
+            return Err(CortexError::invalid_input(format!("Not a file: {}", path)));
+        }
+        if vnode.read_only {
+            return Err(CortexError::invalid_input(
+                format!("File is read-only: {}", path)
+            ));
+        }
+
+        vnode.history = vnode.versions();
+        let entry = vnode.history.get(version_index).cloned().ok_or_else(|| {
+            CortexError::invalid_input(format!(
+                "Version index {} out of range ({} versions retained)",
+                version_index,
+                vnode.history.len()
+            ))
+        })?;
+
+        // Fail before touching the vnode if the content is gone
+        let content = self.load_content_from_db(&entry.content_hash).await?;
+
+        vnode.content_hash = Some(entry.content_hash.clone());
+        vnode.size_bytes = entry.size_bytes;
+        vnode.mark_modified();
+
+        let retention = self.version_retention(workspace_id).await?;
+        vnode.record_version(None, Some(entry.version), &retention);
+
+        self.save_vnode(&vnode).await?;
+        self.content_cache.put(entry.content_hash, content);
+        self.publish(workspace_id, VfsEventKind::NodeUpdated { path: path.to_string() });
+
+        if let Some(ref auto_reparse) = self.auto_reparse {
+            auto_reparse.notify_file_changed(*workspace_id, path.clone());
+        }
+
+        Ok(vnode)
     }
 
     /// Create a directory in the VFS.
@@ -272,6 +428,8 @@ impl VirtualFileSystem {
     ) -> Result<()> {
         debug!("Creating directory: {} in workspace {}", path, workspace_id);
 
+        self.ensure_writable(workspace_id).await?;
+
         // Create parents if requested
         if create_parents {
             let mut current = VirtualPath::root();
@@ -281,12 +439,20 @@ impl VirtualFileSystem {
                 if self.get_vnode(workspace_id, &current).await?.is_none() {
                     let vnode = VNode::new_directory(*workspace_id, current.clone());
                     self.save_vnode(&vnode).await?;
+                    self.publish(workspace_id, VfsEventKind::NodeCreated {
+                        path: current.to_string(),
+                        node_type: NodeType::Directory,
+                    });
                 }
             }
         } else {
             // Just create the directory
             let vnode = VNode::new_directory(*workspace_id, path.clone());
             self.save_vnode(&vnode).await?;
+            self.publish(workspace_id, VfsEventKind::NodeCreated {
+                path: path.to_string(),
+                node_type: NodeType::Directory,
+            });
         }
 
         Ok(())
@@ -301,6 +467,8 @@ impl VirtualFileSystem {
     ) -> Result<()> {
         debug!("Creating symlink: {} -> {} in workspace {}", path, target, workspace_id);
 
+        self.ensure_writable(workspace_id).await?;
+
         // Create parent directories if needed
         if let Some(parent) = path.parent() {
             if self.get_vnode(workspace_id, &parent).await?.is_none() {
@@ -311,6 +479,10 @@ impl VirtualFileSystem {
         // Create the symlink vnode
         let vnode = VNode::new_symlink(*workspace_id, path.clone(), target.to_string());
         self.save_vnode(&vnode).await?;
+        self.publish(workspace_id, VfsEventKind::NodeCreated {
+            path: path.to_string(),
+            node_type: NodeType::SymLink,
+        });
 
         Ok(())
     }
@@ -336,15 +508,138 @@ impl VirtualFileSystem {
         self.list_children(workspace_id, path, recursive).await
     }
 
+    /// List one page of directory entries with filtering, ordering and paging
+    /// applied in the database.
+    ///
+    /// Returns the page of nodes together with the total number of matching
+    /// nodes, ignoring `limit` and `offset`.
+    pub async fn list_directory_page(
+        &self,
+        workspace_id: &Uuid,
+        path: &VirtualPath,
+        options: &ListOptions,
+    ) -> Result<(Vec<VNode>, usize)> {
+        debug!(
+            "Listing directory page: {} in workspace {} (limit: {}, offset: {})",
+            path, workspace_id, options.limit, options.offset
+        );
+
+        let vnode = self.get_vnode(workspace_id, path).await?
+            .ok_or_else(|| CortexError::not_found("Directory", path.to_string()))?;
+
+        if !vnode.is_directory() {
+            return Err(CortexError::invalid_input(format!("Not a directory: {}", path)));
+        }
+
+        let path_str = path.to_string_with_slash();
+        let pattern = if options.recursive {
+            format!("{}%", path_str)
+        } else {
+            format!("{}/%", path_str)
+        };
+
+        let mut condition = String::from(
+            "workspace_id = $workspace_id AND path LIKE $pattern AND status != 'deleted'"
+        );
+        if options.node_type.is_some() {
+            condition.push_str(" AND node_type = $node_type");
+        }
+        if options.language.is_some() {
+            condition.push_str(
+                " AND language != NONE AND string::contains(string::lowercase(<string> language), $language)"
+            );
+        }
+
+        let query = format!(
+            "SELECT * FROM vnode WHERE {condition} ORDER BY {} {} LIMIT {} START {};
+             SELECT count() as total FROM vnode WHERE {condition} GROUP ALL;",
+            options.sort_field.column(),
+            if options.descending { "DESC" } else { "ASC" },
+            options.limit,
+            options.offset,
+        );
+
+        let node_type = options.node_type.map(|t| {
+            serde_json::to_value(t)
+                .ok()
+                .and_then(|v| v.as_str().map(String::from))
+                .unwrap_or_default()
+        });
+
+        let conn = self.storage.acquire().await?;
+        let mut response = conn.connection()
+            .query(&query)
+            .bind(("workspace_id", workspace_id.to_string()))
+            .bind(("pattern", pattern))
+            .bind(("node_type", node_type))
+            .bind(("language", options.language.as_ref().map(|l| l.to_lowercase())))
+            .await
+            .map_err(|e| CortexError::storage(e.to_string()))?;
+
+        let nodes: Vec<VNode> = response.take(0)
+            .map_err(|e| CortexError::storage(e.to_string()))?;
+        let counts: Vec<serde_json::Value> = response.take(1)
+            .map_err(|e| CortexError::storage(e.to_string()))?;
+
+        let total = counts
+            .first()
+            .and_then(|v| v.get("total").and_then(|t| t.as_u64()))
+            .unwrap_or(0) as usize;
+
+        Ok((nodes, total))
+    }
+
     /// Delete a file or directory.
     pub async fn delete(
         &self,
         workspace_id: &Uuid,
         path: &VirtualPath,
         recursive: bool,
+    ) -> Result<()> {
+        self.remove_node(workspace_id, path, recursive).await?;
+        self.publish(workspace_id, VfsEventKind::NodeDeleted { path: path.to_string() });
+        Ok(())
+    }
+
+    /// Move a file to a path that does not exist yet.
+    ///
+    /// The file keeps its content; subscribers see a single `node_moved`
+    /// event rather than a creation and a deletion.
+    pub async fn move_file(
+        &self,
+        workspace_id: &Uuid,
+        from: &VirtualPath,
+        to: &VirtualPath,
+    ) -> Result<()> {
+        debug!("Moving: {} -> {} in workspace {}", from, to, workspace_id);
+
+        if self.exists(workspace_id, to).await? {
+            return Err(CortexError::invalid_input(format!("Path already exists: {}", to)));
+        }
+
+        let content = self.read_file(workspace_id, from).await?;
+        self.store_file(workspace_id, to, &content, None).await?;
+        self.remove_node(workspace_id, from, false).await?;
+
+        self.publish(workspace_id, VfsEventKind::NodeMoved {
+            from: from.to_string(),
+            to: to.to_string(),
+        });
+
+        Ok(())
+    }
+
+    /// Delete a node without publishing an event.
+    async fn remove_node(
+        &self,
+        workspace_id: &Uuid,
+        path: &VirtualPath,
+        recursive: bool,
     ) -> Result<()> {
         debug!("Deleting: {} in workspace {}", path, workspace_id);
 
+        self.ensure_writable(workspace_id).await?;
+
         let vnode = self.get_vnode(workspace_id, path).await?
             .ok_or_else(|| CortexError::not_found("Path", path.to_string()))?;
 
@@ -368,6 +663,15 @@ impl VirtualFileSystem {
         // Mark as deleted
         self.mark_deleted(&vnode.id).await?;
 
+        if !vnode.attributes.is_empty() {
+            let conn = self.storage.acquire().await?;
+            conn.connection()
+                .query("DELETE vnode_attribute WHERE vnode_id = $vnode_id")
+                .bind(("vnode_id", vnode.id.to_string()))
+                .await
+                .map_err(|e| CortexError::storage(e.to_string()))?;
+        }
+
         // Invalidate caches
         self.invalidate_vnode_cache(&vnode.id);
 
@@ -437,31 +741,51 @@ impl VirtualFileSystem {
             }
         }
 
-        // Query database
-        let query = format!(
-            "SELECT * FROM vnode WHERE workspace_id = $workspace_id AND path = $path AND status != 'deleted' LIMIT 1"
-        );
+        // Paths found missing a moment ago are not looked up again
+        let missing_key = Self::missing_key(workspace_id, path);
+        if self.content_cache.is_missing(&missing_key) {
+            return Ok(None);
+        }
+
+        // Query database. Paths are stored structured, so compare against
+        // the serialized path, and return the record ID as the plain UUID.
+        let query = "SELECT *, <string>meta::id(id) AS id FROM vnode \
+                     WHERE workspace_id = $workspace_id AND path = $path AND status != 'deleted' LIMIT 1";
 
         let conn = self.storage.acquire().await?;
         let mut response = conn.connection()
-            .query(&query)
+            .query(query)
             .bind(("workspace_id", workspace_id.to_string()))
-            .bind(("path", path.to_string()))
+            .bind(("path", path.clone()))
             .await
             .map_err(|e| CortexError::storage(e.to_string()))?;
 
         let result: Option<VNode> = response.take(0)
             .map_err(|e| CortexError::storage(e.to_string()))?;
 
-        if let Some(vnode) = &result {
-            // Cache the result (LRU will automatically evict oldest entries if needed)
-            self.vnode_cache.lock().put(vnode.id, vnode.clone());
-            self.path_cache.lock().put(cache_key, vnode.id);
+        match &result {
+            Some(vnode) => {
+                // Cache the result (LRU will automatically evict oldest entries if needed)
+                self.vnode_cache.lock().put(vnode.id, vnode.clone());
+                self.path_cache.lock().put(cache_key, vnode.id);
+            }
+            None => self.content_cache.mark_missing(missing_key),
         }
 
         Ok(result)
     }
 
+    /// Negative cache key of a path.
+    fn missing_key(workspace_id: &Uuid, path: &VirtualPath) -> String {
+        format!("{}:{}", workspace_id, path)
+    }
+
+    /// Forget that a path was missing. Every write that creates a vnode
+    /// outside `save_vnode` must call this.
+    pub(crate) fn forget_missing(&self, workspace_id: &Uuid, path: &VirtualPath) {
+        self.content_cache.invalidate_missing(&Self::missing_key(workspace_id, path));
+    }
+
     /// Save a vnode to the database.
     pub async fn save_vnode(&self, vnode: &VNode) -> Result<()> {
         let conn = self.storage.acquire().await?;
@@ -470,18 +794,22 @@ impl VirtualFileSystem {
         let vnode_json = serde_json::to_value(vnode)
             .map_err(|e| CortexError::storage(e.to_string()))?;
 
-        // Use parameter binding for safety and consistency
-        let query = "CREATE vnode CONTENT $vnode";
+        // UPSERT so that saving a vnode that already exists replaces it
+        let query = "UPSERT type::thing('vnode', $id) CONTENT $vnode";
 
-        let _response = conn.connection()
+        // Don't deserialize the response - the returned record carries a
+        // Thing id that VNode cannot represent - but surface statement
+        // errors, which the query itself does not
+        conn.connection()
             .query(query)
+            .bind(("id", vnode.id.to_string()))
             .bind(("vnode", vnode_json))
             .await
+            .map_err(|e| CortexError::storage(e.to_string()))?
+            .check()
             .map_err(|e| CortexError::storage(e.to_string()))?;
 
-        // Don't try to deserialize the response - CREATE operations in SurrealDB
-        // return Thing types that cause serialization errors. The .await? above
-        // already ensures the query executed successfully.
+        self.forget_missing(&vnode.workspace_id, &vnode.path);
 
         // Cache the vnode (LRU will automatically evict oldest entries if needed)
         self.vnode_cache.lock().put(vnode.id, vnode.clone());
@@ -493,6 +821,153 @@ impl VirtualFileSystem {
         Ok(())
     }
 
+    /// Merge `attributes` into a node's attributes; a `null` value removes
+    /// an attribute.
+    ///
+    /// The change marks the node modified, so sync and merge pick it up.
+    pub async fn set_attributes(
+        &self,
+        workspace_id: &Uuid,
+        path: &VirtualPath,
+        attributes: HashMap<String, serde_json::Value>,
+    ) -> Result<VNode> {
+        self.ensure_writable(workspace_id).await?;
+
+        let mut vnode = self.get_vnode(workspace_id, path).await?
+            .ok_or_else(|| CortexError::not_found("Path", path.to_string()))?;
+
+        if vnode.read_only {
+            return Err(CortexError::invalid_input(
+                format!("Path is read-only: {}", path)
+            ));
+        }
+
+        if !vnode.merge_attributes(attributes) {
+            return Ok(vnode);
+        }
+
+        let size = vnode.attributes_size();
+        if size > VNode::MAX_ATTRIBUTES_BYTES {
+            return Err(CortexError::invalid_input(format!(
+                "Attributes of {} would take {} bytes (limit {})",
+                path, size, VNode::MAX_ATTRIBUTES_BYTES
+            )));
+        }
+
+        vnode.mark_modified();
+        self.save_vnode(&vnode).await?;
+        self.index_attributes(&vnode).await?;
+        self.publish(workspace_id, VfsEventKind::NodeUpdated { path: path.to_string() });
+
+        Ok(vnode)
+    }
+
+    /// Nodes of a workspace whose attributes satisfy `query`.
+    ///
+    /// Each predicate is answered from the `vnode_attribute` index; the
+    /// matches of all predicates are intersected.
+    pub async fn query_nodes(
+        &self,
+        workspace_id: &Uuid,
+        query: &AttributeQuery,
+    ) -> Result<Vec<VNode>> {
+        if query.predicates.is_empty() {
+            return Err(CortexError::invalid_input(
+                "Attribute query needs at least one predicate"
+            ));
+        }
+
+        let conn = self.storage.acquire().await?;
+        let mut matching: Option<HashSet<String>> = None;
+
+        for predicate in &query.predicates {
+            let mut statement = String::from(
+                "SELECT VALUE vnode_id FROM vnode_attribute WHERE workspace_id = $workspace_id AND key = $key"
+            );
+            let (value, min, max) = match predicate {
+                AttributePredicate::Eq { value, .. } => {
+                    statement.push_str(" AND value = $value");
+                    (Some(value.clone()), None, None)
+                }
+                AttributePredicate::Range { min, max, .. } => {
+                    if min.is_some() {
+                        statement.push_str(" AND value >= $min");
+                    }
+                    if max.is_some() {
+                        statement.push_str(" AND value <= $max");
+                    }
+                    (None, min.clone(), max.clone())
+                }
+            };
+
+            let mut response = conn.connection()
+                .query(statement)
+                .bind(("workspace_id", workspace_id.to_string()))
+                .bind(("key", predicate.key().to_string()))
+                .bind(("value", value))
+                .bind(("min", min))
+                .bind(("max", max))
+                .await
+                .map_err(|e| CortexError::storage(e.to_string()))?;
+
+            let ids: Vec<String> = response.take(0)
+                .map_err(|e| CortexError::storage(e.to_string()))?;
+            let ids: HashSet<String> = ids.into_iter().collect();
+
+            let narrowed = match matching {
+                Some(previous) => previous.intersection(&ids).cloned().collect(),
+                None => ids,
+            };
+            if narrowed.is_empty() {
+                return Ok(Vec::new());
+            }
+            matching = Some(narrowed);
+        }
+
+        let ids: Vec<Uuid> = matching
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|id| Uuid::parse_str(id).ok())
+            .collect();
+
+        // Re-check against the nodes themselves so rows left behind by a
+        // failed reindex cannot produce false matches
+        let mut nodes: Vec<VNode> = self.query_vnodes_by_ids(&ids).await?
+            .into_iter()
+            .filter(|vnode| vnode.status != SyncStatus::Deleted && query.matches(&vnode.attributes))
+            .collect();
+        nodes.sort_by(|a, b| a.path.to_string().cmp(&b.path.to_string()));
+
+        Ok(nodes)
+    }
+
+    /// Replace the `vnode_attribute` rows of a node with its current
+    /// attributes.
+    pub(crate) async fn index_attributes(&self, vnode: &VNode) -> Result<()> {
+        let rows: Vec<serde_json::Value> = vnode.attributes
+            .iter()
+            .map(|(key, value)| serde_json::json!({
+                "workspace_id": vnode.workspace_id.to_string(),
+                "vnode_id": vnode.id.to_string(),
+                "key": key,
+                "value": value,
+            }))
+            .collect();
+
+        let conn = self.storage.acquire().await?;
+        conn.connection()
+            .query("DELETE vnode_attribute WHERE vnode_id = $vnode_id")
+            .query("FOR $row IN $rows { CREATE vnode_attribute CONTENT $row; }")
+            .bind(("vnode_id", vnode.id.to_string()))
+            .bind(("rows", rows))
+            .await
+            .map_err(|e| CortexError::storage(e.to_string()))?
+            .check()
+            .map_err(|e| CortexError::storage(e.to_string()))?;
+
+        Ok(())
+    }
+
     /// Query vnodes by status (e.g., modified, created, deleted).
     pub async fn query_vnodes_by_status(&self, statuses: &[SyncStatus]) -> Result<Vec<VNode>> {
         let status_strings: Vec<String> = statuses
@@ -614,17 +1089,15 @@ impl VirtualFileSystem {
             return Ok(Vec::new());
         }
 
-        let ids_str = ids
-            .iter()
-            .map(|id| format!("'{}'", id))
-            .collect::<Vec<_>>()
-            .join(",");
+        let ids: Vec<String> = ids.iter().map(Uuid::to_string).collect();
 
-        let query = format!("SELECT * FROM vnode WHERE id IN [{}]", ids_str);
+        // Record IDs are things, not strings, so build them from the UUIDs
+        let query = "SELECT *, <string>meta::id(id) AS id FROM array::map($ids, |$id| type::thing('vnode', $id))";
 
         let conn = self.storage.acquire().await?;
         let mut response = conn.connection()
-            .query(&query)
+            .query(query)
+            .bind(("ids", ids))
             .await
             .map_err(|e| CortexError::storage(e.to_string()))?;
 
@@ -636,13 +1109,11 @@ impl VirtualFileSystem {
 
     /// Mark a vnode as deleted.
     async fn mark_deleted(&self, vnode_id: &Uuid) -> Result<()> {
-        let query = format!(
-            "UPDATE vnode:$id SET status = 'deleted', updated_at = time::now()"
-        );
+        let query = "UPDATE type::thing('vnode', $id) SET status = 'deleted', updated_at = time::now()";
 
         let conn = self.storage.acquire().await?;
         conn.connection()
-            .query(&query)
+            .query(query)
             .bind(("id", vnode_id.to_string()))
             .await
             .map_err(|e| CortexError::storage(e.to_string()))?;
@@ -711,6 +1182,41 @@ impl VirtualFileSystem {
         hash.to_hex().to_string()
     }
 
+    /// Count content blobs that no live VNode or version history entry references.
+    pub async fn count_orphaned_content(&self) -> Result<usize> {
+        let conn = self.storage.acquire().await?;
+        let query = format!("SELECT count() AS count FROM file_content WHERE {} GROUP ALL", ORPHANED_CONTENT_FILTER);
+        let mut response = conn.connection()
+            .query(query)
+            .await
+            .map_err(|e| CortexError::storage(e.to_string()))?;
+
+        let count: Option<i64> = response.take((0, "count"))
+            .map_err(|e| CortexError::storage(e.to_string()))?;
+        Ok(count.unwrap_or(0) as usize)
+    }
+
+    /// Delete orphaned content blobs and return how many were removed.
+    pub async fn collect_orphaned_content(&self) -> Result<usize> {
+        let conn = self.storage.acquire().await?;
+        let query = format!("DELETE file_content WHERE {} RETURN BEFORE", ORPHANED_CONTENT_FILTER);
+        let mut response = conn.connection()
+            .query(query)
+            .await
+            .map_err(|e| CortexError::storage(e.to_string()))?;
+
+        let deleted: Vec<serde_json::Value> = response.take(0)
+            .map_err(|e| CortexError::storage(e.to_string()))?;
+
+        for record in &deleted {
+            if let Some(hash) = record.get("content_hash").and_then(|h| h.as_str()) {
+                self.content_cache.remove(hash);
+            }
+        }
+        debug!("Collected {} orphaned content blobs", deleted.len());
+        Ok(deleted.len())
+    }
+
     /// Store content in database (deduplicated).
     ///
     /// FIXED: Uses database-level atomic increment to prevent race conditions
@@ -989,6 +1495,7 @@ impl VirtualFileSystem {
                 parent_workspace: $parent_workspace,
                 fork_metadata: $fork_metadata,
                 dependencies: $dependencies,
+                version_retention: $version_retention,
                 created_at: <datetime> $created_at,
                 updated_at: <datetime> $updated_at
             }}
@@ -1004,6 +1511,7 @@ impl VirtualFileSystem {
             .bind(("parent_workspace", workspace.parent_workspace))
             .bind(("fork_metadata", workspace.fork_metadata.clone()))
             .bind(("dependencies", workspace.dependencies.clone()))
+            .bind(("version_retention", workspace.version_retention))
             .bind(("created_at", created_at_str))
             .bind(("updated_at", updated_at_str))
             .await
@@ -1027,6 +1535,111 @@ impl VirtualFileSystem {
         Ok(())
     }
 
+    /// Fail with `CortexError::WorkspaceReadOnly` if the workspace is
+    /// read-only. Workspaces without a stored record are writable.
+    pub async fn ensure_writable(&self, workspace_id: &Uuid) -> Result<()> {
+        let conn = self.storage.acquire().await?;
+        let mut response = conn.connection()
+            .query("SELECT VALUE read_only FROM type::thing('workspace', $id)")
+            .bind(("id", workspace_id.to_string()))
+            .await
+            .map_err(|e| CortexError::storage(e.to_string()))?;
+
+        let read_only: Option<bool> = response.take(0)
+            .map_err(|e| CortexError::storage(e.to_string()))?;
+        if read_only.unwrap_or(false) {
+            return Err(CortexError::workspace_read_only(workspace_id));
+        }
+
+        Ok(())
+    }
+
+    /// Make a workspace read-only, recording `actor` and the time in its
+    /// metadata. Forks remain the way to edit its content.
+    pub async fn freeze_workspace(&self, workspace_id: &Uuid, actor: Option<&str>) -> Result<()> {
+        self.set_workspace_read_only(workspace_id, true, actor).await
+    }
+
+    /// Make a frozen workspace writable again, recording `actor` and the
+    /// time in its metadata.
+    pub async fn thaw_workspace(&self, workspace_id: &Uuid, actor: Option<&str>) -> Result<()> {
+        self.set_workspace_read_only(workspace_id, false, actor).await
+    }
+
+    async fn set_workspace_read_only(
+        &self,
+        workspace_id: &Uuid,
+        read_only: bool,
+        actor: Option<&str>,
+    ) -> Result<()> {
+        // One statement, so the flag and its audit fields change together
+        let query = if read_only {
+            "UPDATE type::thing('workspace', $id) SET read_only = true, \
+             metadata.frozen_by = $actor, metadata.frozen_at = time::now(), updated_at = time::now() \
+             RETURN VALUE read_only"
+        } else {
+            "UPDATE type::thing('workspace', $id) SET read_only = false, \
+             metadata.thawed_by = $actor, metadata.thawed_at = time::now(), updated_at = time::now() \
+             RETURN VALUE read_only"
+        };
+
+        let conn = self.storage.acquire().await?;
+        let mut response = conn.connection()
+            .query(query)
+            .bind(("id", workspace_id.to_string()))
+            .bind(("actor", actor.map(str::to_string)))
+            .await
+            .map_err(|e| CortexError::storage(e.to_string()))?;
+
+        let updated: Vec<bool> = response.take(0)
+            .map_err(|e| CortexError::storage(e.to_string()))?;
+        if updated.is_empty() {
+            return Err(CortexError::not_found("Workspace", workspace_id.to_string()));
+        }
+
+        self.publish(workspace_id, VfsEventKind::WorkspaceUpdated {
+            fields: vec!["read_only".to_string()],
+        });
+
+        Ok(())
+    }
+
+    /// Retention policy of a workspace's file versions; the default policy
+    /// if the workspace has none.
+    pub(crate) async fn version_retention(&self, workspace_id: &Uuid) -> Result<VersionRetention> {
+        let conn = self.storage.acquire().await?;
+        let mut response = conn.connection()
+            .query("SELECT VALUE version_retention FROM type::thing('workspace', $id)")
+            .bind(("id", workspace_id.to_string()))
+            .await
+            .map_err(|e| CortexError::storage(e.to_string()))?;
+
+        let retention: Option<VersionRetention> = response.take(0)
+            .map_err(|e| CortexError::storage(e.to_string()))?;
+        Ok(retention.unwrap_or_default())
+    }
+
+    /// Change how many file versions a workspace keeps. Versions beyond the
+    /// new limits are dropped on the next write to each file.
+    pub async fn set_version_retention(
+        &self,
+        workspace_id: &Uuid,
+        retention: VersionRetention,
+    ) -> Result<()> {
+        let conn = self.storage.acquire().await?;
+        conn.connection()
+            .query("UPDATE type::thing('workspace', $id) SET version_retention = $retention, updated_at = time::now()")
+            .bind(("id", workspace_id.to_string()))
+            .bind(("retention", retention))
+            .await
+            .map_err(|e| CortexError::storage(e.to_string()))?;
+
+        self.publish(workspace_id, VfsEventKind::WorkspaceUpdated {
+            fields: vec!["version_retention".to_string()],
+        });
+        Ok(())
+    }
+
     // ============================================================================
     // Ergonomic Helper Methods for Tests
     // ============================================================================
@@ -1055,6 +1668,8 @@ impl VirtualFileSystem {
             ));
         }
 
+        self.ensure_writable(workspace_id).await?;
+
         // Check if file already exists
         if self.exists(workspace_id, path).await? {
             return Err(CortexError::invalid_input(
@@ -1081,12 +1696,20 @@ impl VirtualFileSystem {
             vnode.language = Some(Language::from_extension(ext));
         }
 
+        let retention = self.version_retention(workspace_id).await?;
+        vnode.record_version(None, None, &retention);
+
         // Save vnode to database
         self.save_vnode(&vnode).await?;
 
         // Cache content
         self.content_cache.put(content_hash, content.to_vec());
 
+        self.publish(workspace_id, VfsEventKind::NodeCreated {
+            path: path.to_string(),
+            node_type: NodeType::File,
+        });
+
         Ok(vnode)
     }
 
@@ -1140,6 +1763,8 @@ impl VirtualFileSystem {
             ));
         }
 
+        self.ensure_writable(workspace_id).await?;
+
         // Get existing vnode
         let mut vnode = self.get_vnode(workspace_id, path).await?
             .ok_or_else(|| CortexError::not_found("File", path.to_string()))?;
@@ -1163,16 +1788,22 @@ impl VirtualFileSystem {
         self.store_content(&content_hash, content).await?;
 
         // Update vnode
+        vnode.history = vnode.versions();
         vnode.content_hash = Some(content_hash.clone());
         vnode.size_bytes = content.len();
         vnode.mark_modified();
 
+        let retention = self.version_retention(workspace_id).await?;
+        vnode.record_version(None, None, &retention);
+
         // Save updated vnode
         self.save_vnode(&vnode).await?;
 
         // Cache content
         self.content_cache.put(content_hash, content.to_vec());
 
+        self.publish(workspace_id, VfsEventKind::NodeUpdated { path: path.to_string() });
+
         // Trigger auto-reparse if enabled
         if let Some(ref auto_reparse) = self.auto_reparse {
             debug!("Triggering auto-reparse for: {}", path);
@@ -0,0 +1,108 @@
+//! Node Attribute Query Verification Tests
+//!
+//! This test suite verifies attribute queries against the database:
+//! - Attributes set on a node persist and are indexed
+//! - Eq and Range predicates find the node through the index
+
+use cortex_storage::connection_pool::{
+    ConnectionManager, ConnectionMode, Credentials, DatabaseConfig, PoolConfig, RetryPolicy,
+};
+use cortex_vfs::{AttributeQuery, VirtualFileSystem, VirtualPath};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+async fn create_storage() -> Arc<ConnectionManager> {
+    let config = DatabaseConfig {
+        connection_mode: ConnectionMode::InMemory,
+        credentials: Credentials {
+            username: None,
+            password: None,
+        },
+        pool_config: PoolConfig {
+            min_connections: 0,
+            max_connections: 10,
+            connection_timeout: Duration::from_secs(5),
+            idle_timeout: Some(Duration::from_secs(30)),
+            max_lifetime: Some(Duration::from_secs(60)),
+            retry_policy: RetryPolicy::default(),
+            warm_connections: false,
+            validate_on_checkout: false,
+            recycle_after_uses: Some(10000),
+            shutdown_grace_period: Duration::from_secs(30),
+            query_timeout: Some(Duration::from_secs(30)),
+            slow_query_threshold: Some(Duration::from_secs(1)),
+        },
+        namespace: format!("test_{}", Uuid::new_v4()),
+        database: "test".to_string(),
+    };
+
+    Arc::new(ConnectionManager::new(config).await.unwrap())
+}
+
+#[tokio::test]
+async fn test_set_attributes_then_query_nodes() {
+    let storage = create_storage().await;
+    let workspace_id = Uuid::new_v4();
+    let vfs = VirtualFileSystem::new(storage.clone());
+
+    let report = VirtualPath::new("reports/lint.md").unwrap();
+    let notes = VirtualPath::new("notes.md").unwrap();
+    vfs.write_file(&workspace_id, &report, b"lint report").await.unwrap();
+    vfs.write_file(&workspace_id, &notes, b"notes").await.unwrap();
+
+    let mut attributes = HashMap::new();
+    attributes.insert("owner".to_string(), Value::from("alice"));
+    attributes.insert("lint_score".to_string(), Value::from(0.82));
+    vfs.set_attributes(&workspace_id, &report, attributes).await.unwrap();
+
+    let mut attributes = HashMap::new();
+    attributes.insert("owner".to_string(), Value::from("bob"));
+    attributes.insert("lint_score".to_string(), Value::from(0.4));
+    vfs.set_attributes(&workspace_id, &notes, attributes).await.unwrap();
+
+    // A fresh VFS has empty caches, so the nodes come from the database
+    let vfs = VirtualFileSystem::new(storage);
+
+    let found = vfs
+        .query_nodes(&workspace_id, &AttributeQuery::new().eq("owner", "alice"))
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].path, report);
+    assert_eq!(found[0].attributes["lint_score"], Value::from(0.82));
+
+    let found = vfs
+        .query_nodes(
+            &workspace_id,
+            &AttributeQuery::new().range("lint_score", Some(Value::from(0.5)), None),
+        )
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].path, report);
+
+    let found = vfs
+        .query_nodes(
+            &workspace_id,
+            &AttributeQuery::new().range("lint_score", Some(Value::from(0.0)), Some(Value::from(1.0))),
+        )
+        .await
+        .unwrap();
+    let paths: Vec<String> = found.iter().map(|vnode| vnode.path.to_string()).collect();
+    assert_eq!(paths, vec!["notes.md", "reports/lint.md"]);
+
+    // Predicates are intersected
+    let found = vfs
+        .query_nodes(
+            &workspace_id,
+            &AttributeQuery::new()
+                .eq("owner", "bob")
+                .range("lint_score", Some(Value::from(0.5)), None),
+        )
+        .await
+        .unwrap();
+    assert!(found.is_empty());
+}
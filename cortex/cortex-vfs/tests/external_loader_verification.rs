@@ -39,6 +39,8 @@ async fn create_test_loader() -> (ExternalProjectLoader, Arc<ConnectionManager>,
             validate_on_checkout: false,
             recycle_after_uses: Some(10000),
             shutdown_grace_period: Duration::from_secs(30),
+            query_timeout: Some(Duration::from_secs(30)),
+            slow_query_threshold: Some(Duration::from_secs(1)),
         },
         namespace: format!("test_{}", Uuid::new_v4()),
         database: "test".to_string(),
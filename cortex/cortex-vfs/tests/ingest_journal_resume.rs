@@ -0,0 +1,146 @@
+//! Resumable Ingestion Verification Tests
+//!
+//! This test suite verifies the ingestion journal:
+//! - Imports record every stored file in the journal
+//! - Re-runs skip fully ingested files and resume stored ones
+//! - Changed files are ingested again from the start
+//! - The pipeline chunks pending files and records their progress
+
+use cortex_code_analysis::CodeParser;
+use cortex_memory::SemanticMemorySystem;
+use cortex_storage::connection_pool::{
+    ConnectionManager, ConnectionMode, Credentials, DatabaseConfig, PoolConfig, RetryPolicy,
+};
+use cortex_vfs::{
+    ExternalProjectLoader, FileIngestionPipeline, ImportOptions, IngestJournal, IngestStage,
+    VirtualFileSystem, VirtualPath,
+};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+async fn setup() -> (Arc<ConnectionManager>, VirtualFileSystem, Uuid) {
+    let config = DatabaseConfig {
+        connection_mode: ConnectionMode::InMemory,
+        credentials: Credentials {
+            username: None,
+            password: None,
+        },
+        pool_config: PoolConfig {
+            min_connections: 0,
+            max_connections: 10,
+            connection_timeout: Duration::from_secs(5),
+            idle_timeout: Some(Duration::from_secs(30)),
+            max_lifetime: Some(Duration::from_secs(60)),
+            retry_policy: RetryPolicy::default(),
+            warm_connections: false,
+            validate_on_checkout: false,
+            recycle_after_uses: Some(10000),
+            shutdown_grace_period: Duration::from_secs(30),
+            query_timeout: Some(Duration::from_secs(30)),
+            slow_query_threshold: Some(Duration::from_secs(1)),
+        },
+        namespace: format!("test_{}", Uuid::new_v4()),
+        database: "test".to_string(),
+    };
+
+    let storage = Arc::new(ConnectionManager::new(config).await.unwrap());
+    let vfs = VirtualFileSystem::new(storage.clone());
+    (storage, vfs, Uuid::new_v4())
+}
+
+fn options() -> ImportOptions {
+    ImportOptions {
+        read_only: false,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_import_resumes_from_journal() {
+    let (storage, vfs, workspace_id) = setup().await;
+    let journal = IngestJournal::new(storage.clone(), workspace_id, IngestStage::Chunked);
+
+    let project = tempfile::tempdir().unwrap();
+    std::fs::create_dir(project.path().join("src")).unwrap();
+    std::fs::write(project.path().join("src/lib.rs"), "pub fn one() -> u32 { 1 }\n").unwrap();
+    std::fs::write(project.path().join("src/two.rs"), "pub fn two() -> u32 { 2 }\n").unwrap();
+
+    let loader = ExternalProjectLoader::new(vfs.clone()).with_journal(journal.clone());
+    let report = loader
+        .import_into_workspace(&workspace_id, project.path(), options())
+        .await
+        .unwrap();
+    assert_eq!(report.files_imported, 2);
+
+    let status = journal.status().await.unwrap();
+    assert_eq!(status.files, 2);
+    assert_eq!(status.stages.get(&IngestStage::Stored), Some(&2));
+    assert_eq!(status.complete, 0);
+
+    // Interrupted before chunking: stored files are not stored again
+    let report = loader
+        .import_into_workspace(&workspace_id, project.path(), options())
+        .await
+        .unwrap();
+    assert_eq!(report.files_imported, 0);
+    assert_eq!(report.files_resumed, 2);
+
+    let parser = Arc::new(tokio::sync::Mutex::new(CodeParser::new().unwrap()));
+    let pipeline = FileIngestionPipeline::new(
+        parser,
+        Arc::new(vfs.clone()),
+        Arc::new(SemanticMemorySystem::new(storage.clone())),
+    )
+    .with_journal(journal.clone());
+    let result = pipeline.ingest_pending(&workspace_id).await.unwrap();
+    assert_eq!(result.files_processed, 2);
+    assert_eq!(result.total_units, 2);
+    assert_eq!(journal.status().await.unwrap().complete, 2);
+
+    // Nothing left to do
+    assert_eq!(pipeline.ingest_pending(&workspace_id).await.unwrap().files_processed, 0);
+
+    // A changed file starts over; the unchanged one is skipped
+    std::fs::write(project.path().join("src/two.rs"), "pub fn two() -> u64 { 2 }\n").unwrap();
+    let report = loader
+        .import_into_workspace(&workspace_id, project.path(), options())
+        .await
+        .unwrap();
+    assert_eq!(report.files_imported, 1);
+    assert_eq!(report.files_skipped, 1);
+
+    let entry = journal
+        .entry(&VirtualPath::new("src/two.rs").unwrap())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(entry.stage, IngestStage::Stored);
+}
+
+#[tokio::test]
+async fn test_journal_remove_and_clear() {
+    let (storage, _vfs, workspace_id) = setup().await;
+    let journal = IngestJournal::new(storage.clone(), workspace_id, IngestStage::Chunked);
+    let other = IngestJournal::new(storage, Uuid::new_v4(), IngestStage::Chunked);
+
+    for path in ["src/a.rs", "src/nested/b.rs", "README.md"] {
+        journal
+            .record(&VirtualPath::new(path).unwrap(), "hash", IngestStage::Chunked)
+            .await
+            .unwrap();
+    }
+    other
+        .record(&VirtualPath::new("src/a.rs").unwrap(), "hash", IngestStage::Stored)
+        .await
+        .unwrap();
+
+    journal.remove(&VirtualPath::new("src").unwrap()).await.unwrap();
+    let entries = journal.entries().await.unwrap();
+    assert_eq!(entries.len(), 1);
+    assert!(entries.contains_key("README.md"));
+
+    assert_eq!(journal.clear().await.unwrap(), 1);
+    assert!(journal.entries().await.unwrap().is_empty());
+    assert_eq!(other.entries().await.unwrap().len(), 1);
+}
@@ -0,0 +1,156 @@
+//! Workspace Snapshot and Rollback Verification Tests
+//!
+//! This test suite verifies workspace snapshots:
+//! - Snapshots record every vnode without copying content
+//! - Rollback restores modified, deleted and removes created nodes
+//! - Rollback records its changes as one change set
+//! - Snapshot names are unique and retention limits are enforced
+
+use cortex_storage::connection_pool::{
+    ConnectionManager, ConnectionMode, Credentials, DatabaseConfig, PoolConfig, RetryPolicy,
+};
+use cortex_vfs::{
+    ChangeType, SnapshotManager, SnapshotRetention, VirtualFileSystem, VirtualPath, Workspace,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+async fn setup() -> (SnapshotManager, VirtualFileSystem, Uuid) {
+    let config = DatabaseConfig {
+        connection_mode: ConnectionMode::InMemory,
+        credentials: Credentials {
+            username: None,
+            password: None,
+        },
+        pool_config: PoolConfig {
+            min_connections: 0,
+            max_connections: 10,
+            connection_timeout: Duration::from_secs(5),
+            idle_timeout: Some(Duration::from_secs(30)),
+            max_lifetime: Some(Duration::from_secs(60)),
+            retry_policy: RetryPolicy::default(),
+            warm_connections: false,
+            validate_on_checkout: false,
+            recycle_after_uses: Some(10000),
+            shutdown_grace_period: Duration::from_secs(30),
+            query_timeout: Some(Duration::from_secs(30)),
+            slow_query_threshold: Some(Duration::from_secs(1)),
+        },
+        namespace: format!("test_{}", Uuid::new_v4()),
+        database: "test".to_string(),
+    };
+
+    let storage = Arc::new(ConnectionManager::new(config).await.unwrap());
+    let vfs = VirtualFileSystem::new(storage.clone());
+
+    let workspace = Workspace {
+        id: Uuid::new_v4(),
+        name: "snapshots".to_string(),
+        namespace: format!("workspace_{}", Uuid::new_v4()),
+        sync_sources: vec![],
+        metadata: HashMap::new(),
+        read_only: false,
+        parent_workspace: None,
+        fork_metadata: None,
+        dependencies: vec![],
+        version_retention: Default::default(),
+        created_at: chrono::Utc::now(),
+        updated_at: chrono::Utc::now(),
+    };
+    vfs.create_workspace(&workspace).await.unwrap();
+
+    let manager = SnapshotManager::new(vfs.clone(), storage);
+    (manager, vfs, workspace.id)
+}
+
+fn path(p: &str) -> VirtualPath {
+    VirtualPath::new(p).unwrap()
+}
+
+#[tokio::test]
+async fn test_rollback_restores_snapshot() {
+    let (manager, vfs, workspace_id) = setup().await;
+
+    vfs.write_file(&workspace_id, &path("README.md"), b"original").await.unwrap();
+    vfs.write_file(&workspace_id, &path("docs/guide.md"), b"guide").await.unwrap();
+
+    let snapshot = manager
+        .create_snapshot(&workspace_id, "before-edits".to_string(), None, Some("agent-1".to_string()))
+        .await
+        .unwrap();
+    assert_eq!(snapshot.node_count, 2);
+    assert_eq!(snapshot.total_bytes, "original".len() + "guide".len());
+
+    // Wreck the workspace
+    vfs.write_file(&workspace_id, &path("README.md"), b"broken").await.unwrap();
+    vfs.delete(&workspace_id, &path("docs/guide.md"), false).await.unwrap();
+    vfs.write_file(&workspace_id, &path("notes.txt"), b"stray").await.unwrap();
+
+    let report = manager
+        .rollback(&workspace_id, "before-edits", Some("agent-2"))
+        .await
+        .unwrap();
+
+    assert_eq!(report.snapshot.id, snapshot.id);
+    assert_eq!(report.count(ChangeType::Modified), 1);
+    assert_eq!(report.count(ChangeType::Created), 1);
+    assert_eq!(report.count(ChangeType::Deleted), 1);
+    assert!(report.changes.iter().all(|change| {
+        change.change_set == Some(report.change_set) && change.changed_by.as_deref() == Some("agent-2")
+    }));
+
+    // Read back from the database, not the caches the rollback filled
+    vfs.clear_caches();
+    assert_eq!(vfs.read_file(&workspace_id, &path("README.md")).await.unwrap(), b"original");
+    assert_eq!(vfs.read_file(&workspace_id, &path("docs/guide.md")).await.unwrap(), b"guide");
+    assert!(!vfs.exists(&workspace_id, &path("notes.txt")).await.unwrap());
+
+    // The content the rollback replaced stays in the file's history
+    let history = vfs.get_history(&workspace_id, &path("README.md")).await.unwrap();
+    assert!(history.len() >= 2);
+
+    // Rolling back an unchanged workspace changes nothing
+    let report = manager.rollback(&workspace_id, "before-edits", None).await.unwrap();
+    assert!(report.changes.is_empty());
+}
+
+#[tokio::test]
+async fn test_snapshot_names_and_retention() {
+    let (manager, vfs, workspace_id) = setup().await;
+    vfs.write_file(&workspace_id, &path("README.md"), b"v1").await.unwrap();
+
+    manager
+        .create_snapshot(&workspace_id, "first".to_string(), None, None)
+        .await
+        .unwrap();
+    assert!(manager
+        .create_snapshot(&workspace_id, "first".to_string(), None, None)
+        .await
+        .is_err());
+
+    manager
+        .set_snapshot_retention(&workspace_id, SnapshotRetention { max_snapshots: Some(2) })
+        .await
+        .unwrap();
+    for name in ["second", "third"] {
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        manager
+            .create_snapshot(&workspace_id, name.to_string(), None, None)
+            .await
+            .unwrap();
+    }
+
+    let names: Vec<String> = manager
+        .list_snapshots(&workspace_id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|snapshot| snapshot.name)
+        .collect();
+    assert_eq!(names, vec!["third", "second"]);
+
+    let err = manager.rollback(&workspace_id, "first", None).await.unwrap_err();
+    assert!(err.is_not_found());
+}
@@ -38,6 +38,8 @@ async fn create_test_setup_with_auto_reparse() -> (Arc<VirtualFileSystem>, Arc<F
             validate_on_checkout: false,
             recycle_after_uses: Some(10000),
             shutdown_grace_period: Duration::from_secs(30),
+            query_timeout: Some(Duration::from_secs(30)),
+            slow_query_threshold: Some(Duration::from_secs(1)),
         },
         namespace: "test".to_string(),
         database: "test".to_string(),
@@ -160,6 +162,8 @@ async fn test_auto_reparse_disabled() {
             validate_on_checkout: false,
             recycle_after_uses: Some(10000),
             shutdown_grace_period: Duration::from_secs(30),
+            query_timeout: Some(Duration::from_secs(30)),
+            slow_query_threshold: Some(Duration::from_secs(1)),
         },
         namespace: "test".to_string(),
         database: "test".to_string(),
@@ -269,6 +273,8 @@ async fn test_enable_disable_auto_reparse() {
             validate_on_checkout: false,
             recycle_after_uses: Some(10000),
             shutdown_grace_period: Duration::from_secs(30),
+            query_timeout: Some(Duration::from_secs(30)),
+            slow_query_threshold: Some(Duration::from_secs(1)),
         },
         namespace: "test".to_string(),
         database: "test".to_string(),
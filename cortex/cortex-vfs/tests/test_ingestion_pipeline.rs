@@ -43,6 +43,8 @@ async fn setup_test_env() -> (
             validate_on_checkout: false,
             recycle_after_uses: None,
             shutdown_grace_period: Duration::from_secs(5),
+            query_timeout: Some(Duration::from_secs(30)),
+            slow_query_threshold: Some(Duration::from_secs(1)),
         },
         namespace: "test".to_string(),
         database: "ingestion_tests".to_string(),
@@ -45,6 +45,8 @@ fn create_test_db_config() -> DatabaseConfig {
             validate_on_checkout: false,
             recycle_after_uses: None,
             shutdown_grace_period: Duration::from_secs(5),
+            query_timeout: Some(Duration::from_secs(30)),
+            slow_query_threshold: Some(Duration::from_secs(1)),
         },
         namespace: format!("test_{}", Uuid::new_v4().to_string().replace("-", "")),
         database: "cortex_vfs_test".to_string(),
@@ -6,7 +6,8 @@
 use cortex_storage::connection_pool::{
     ConnectionManager, ConnectionMode, Credentials, DatabaseConfig, PoolConfig, RetryPolicy,
 };
-use cortex_vfs::{VirtualFileSystem, VirtualPath};
+use cortex_vfs::{VirtualFileSystem, VirtualPath, Workspace};
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 use uuid::Uuid;
@@ -29,6 +30,8 @@ async fn create_test_vfs() -> (Arc<VirtualFileSystem>, Arc<ConnectionManager>) {
             validate_on_checkout: false,
             recycle_after_uses: Some(10000),
             shutdown_grace_period: Duration::from_secs(30),
+            query_timeout: Some(Duration::from_secs(30)),
+            slow_query_threshold: Some(Duration::from_secs(1)),
         },
         namespace: format!("test_{}", Uuid::new_v4()),
         database: "test".to_string(),
@@ -317,3 +320,38 @@ async fn test_allow_no_extension_files() {
     let result = vfs.create_file(&workspace_id, &path, content).await;
     assert!(result.is_ok(), "Should allow files without extensions");
 }
+
+#[tokio::test]
+async fn test_frozen_workspace_rejects_writes() {
+    let (vfs, _storage) = create_test_vfs().await;
+    let workspace = Workspace {
+        id: Uuid::new_v4(),
+        name: "frozen".to_string(),
+        namespace: format!("ws_{}", Uuid::new_v4()),
+        sync_sources: vec![],
+        metadata: HashMap::new(),
+        read_only: false,
+        parent_workspace: None,
+        fork_metadata: None,
+        dependencies: vec![],
+        version_retention: Default::default(),
+        created_at: chrono::Utc::now(),
+        updated_at: chrono::Utc::now(),
+    };
+    vfs.create_workspace(&workspace).await.unwrap();
+
+    let path = VirtualPath::new("docs/notes.md").unwrap();
+    vfs.write_file(&workspace.id, &path, b"draft").await.unwrap();
+
+    vfs.freeze_workspace(&workspace.id, Some("reviewer")).await.unwrap();
+
+    let error = vfs.write_file(&workspace.id, &path, b"edit").await.unwrap_err();
+    assert!(error.is_workspace_read_only(), "unexpected error: {}", error);
+    let error = vfs.delete(&workspace.id, &path, false).await.unwrap_err();
+    assert!(error.is_workspace_read_only(), "unexpected error: {}", error);
+    assert_eq!(vfs.read_file(&workspace.id, &path).await.unwrap(), b"draft");
+
+    vfs.thaw_workspace(&workspace.id, Some("reviewer")).await.unwrap();
+    vfs.write_file(&workspace.id, &path, b"edit").await.unwrap();
+    assert_eq!(vfs.read_file(&workspace.id, &path).await.unwrap(), b"edit");
+}
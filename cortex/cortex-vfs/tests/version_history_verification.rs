@@ -0,0 +1,72 @@
+//! File Version History Verification Tests
+//!
+//! This test suite verifies that version history survives the VFS caches:
+//! - Rewriting a file persists the new head and the replaced version
+//! - Restoring a version persists the restored head
+
+use cortex_storage::connection_pool::{
+    ConnectionManager, ConnectionMode, Credentials, DatabaseConfig, PoolConfig, RetryPolicy,
+};
+use cortex_vfs::{VirtualFileSystem, VirtualPath};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+async fn create_storage() -> Arc<ConnectionManager> {
+    let config = DatabaseConfig {
+        connection_mode: ConnectionMode::InMemory,
+        credentials: Credentials {
+            username: None,
+            password: None,
+        },
+        pool_config: PoolConfig {
+            min_connections: 0,
+            max_connections: 10,
+            connection_timeout: Duration::from_secs(5),
+            idle_timeout: Some(Duration::from_secs(30)),
+            max_lifetime: Some(Duration::from_secs(60)),
+            retry_policy: RetryPolicy::default(),
+            warm_connections: false,
+            validate_on_checkout: false,
+            recycle_after_uses: Some(10000),
+            shutdown_grace_period: Duration::from_secs(30),
+            query_timeout: Some(Duration::from_secs(30)),
+            slow_query_threshold: Some(Duration::from_secs(1)),
+        },
+        namespace: format!("test_{}", Uuid::new_v4()),
+        database: "test".to_string(),
+    };
+
+    Arc::new(ConnectionManager::new(config).await.unwrap())
+}
+
+#[tokio::test]
+async fn test_history_and_restore_persist_across_reopen() {
+    let storage = create_storage().await;
+    let workspace_id = Uuid::new_v4();
+    let path = VirtualPath::new("notes.md").unwrap();
+
+    let vfs = VirtualFileSystem::new(storage.clone());
+    vfs.write_file(&workspace_id, &path, b"first draft").await.unwrap();
+    vfs.write_file_as(&workspace_id, &path, b"second draft", Some("agent-1"))
+        .await
+        .unwrap();
+
+    // A fresh VFS has empty caches, so everything below comes from the database
+    let vfs = VirtualFileSystem::new(storage.clone());
+    assert_eq!(vfs.read_file(&workspace_id, &path).await.unwrap(), b"second draft");
+
+    let history = vfs.get_history(&workspace_id, &path).await.unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[1].author.as_deref(), Some("agent-1"));
+    assert_eq!(vfs.read_version(&history[0]).await.unwrap(), b"first draft");
+
+    vfs.restore_version(&workspace_id, &path, 0).await.unwrap();
+
+    let vfs = VirtualFileSystem::new(storage);
+    assert_eq!(vfs.read_file(&workspace_id, &path).await.unwrap(), b"first draft");
+
+    let history = vfs.get_history(&workspace_id, &path).await.unwrap();
+    assert_eq!(history.len(), 3);
+    assert_eq!(history[2].restored_from, Some(history[0].version));
+}
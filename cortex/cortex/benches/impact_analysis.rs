@@ -0,0 +1,75 @@
+//! Benchmarks for dependency graph impact analysis
+//!
+//! Builds a synthetic graph of 20K symbols and ~100K typed edges spread over
+//! 500 files, then measures `Graph::impact_of` from a heavily used symbol.
+
+use cortex::mcp::graph_algorithms::{Graph, ImpactOptions};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+const NODES: usize = 20_000;
+const EDGES_PER_NODE: usize = 5;
+const FILES: usize = 500;
+const EDGE_KINDS: [&str; 4] = ["CALLS", "USES_TYPE", "IMPLEMENTS", "IMPORTS"];
+
+/// Deterministic graph where symbol `i` depends on symbols with smaller
+/// indices (biased towards low indices), plus a few back edges for cycles
+fn build_graph() -> Graph {
+    let mut graph = Graph::new();
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+
+    for i in 1..NODES {
+        for e in 0..EDGES_PER_NODE {
+            let r = next() as usize;
+            // Square the fraction to bias targets towards core symbols
+            let target = (r % i) * (r % i) / i;
+            let target = if e == 0 && i % 97 == 0 { (i + 1) % NODES } else { target };
+            let kind = EDGE_KINDS[r % EDGE_KINDS.len()];
+            graph.add_typed_edge(format!("sym{i}"), format!("sym{target}"), kind.to_string());
+        }
+    }
+    for i in 0..NODES {
+        graph.set_node_file(format!("sym{i}"), format!("src/module_{}.rs", i % FILES));
+    }
+
+    graph
+}
+
+fn bench_impact_of(c: &mut Criterion) {
+    let graph = build_graph();
+    let mut group = c.benchmark_group("impact_of_100k_edges");
+
+    let cases = [
+        ("unbounded", ImpactOptions::default()),
+        (
+            "depth_3",
+            ImpactOptions {
+                max_depth: Some(3),
+                ..ImpactOptions::default()
+            },
+        ),
+        (
+            "calls_only",
+            ImpactOptions {
+                edge_kinds: vec!["CALLS".to_string()],
+                ..ImpactOptions::default()
+            },
+        ),
+    ];
+
+    for (name, options) in cases {
+        group.bench_with_input(BenchmarkId::from_parameter(name), &options, |b, options| {
+            b.iter(|| black_box(graph.impact_of("sym0", options)));
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_impact_of);
+criterion_main!(benches);
@@ -50,12 +50,14 @@ pub async fn initialize_auth_schema(storage: &Arc<ConnectionManager>) -> anyhow:
         DEFINE FIELD user_id ON TABLE api_keys TYPE string;
         DEFINE FIELD name ON TABLE api_keys TYPE string;
         DEFINE FIELD key_hash ON TABLE api_keys TYPE string;
+        DEFINE FIELD key_prefix ON TABLE api_keys TYPE option<string>;
         DEFINE FIELD scopes ON TABLE api_keys TYPE array<string> DEFAULT [];
         DEFINE FIELD expires_at ON TABLE api_keys TYPE option<datetime>;
         DEFINE FIELD created_at ON TABLE api_keys TYPE datetime DEFAULT time::now();
         DEFINE FIELD last_used_at ON TABLE api_keys TYPE option<datetime>;
 
         DEFINE INDEX api_keys_user_idx ON TABLE api_keys COLUMNS user_id;
+        DEFINE INDEX api_keys_prefix_idx ON TABLE api_keys COLUMNS key_prefix;
     "#;
 
     // Define revoked_tokens table for token blacklist
@@ -149,6 +149,7 @@ impl IntoResponse for ApiError {
             timestamp: chrono::Utc::now(),
             version: "v1".to_string(),
             duration_ms: 0,
+            key_id: crate::api::middleware::auth::current_api_key_id(),
         };
 
         let body = ErrorResponse {
@@ -165,17 +166,23 @@ impl IntoResponse for ApiError {
     }
 }
 
-// Conversion from anyhow::Error
+// Conversion from anyhow::Error; wrapped CortexErrors keep their mapping
 impl From<anyhow::Error> for ApiError {
     fn from(err: anyhow::Error) -> Self {
-        ApiError::Internal(err.to_string())
+        match err.downcast::<cortex_core::error::CortexError>() {
+            Ok(err) => err.into(),
+            Err(err) => ApiError::Internal(err.to_string()),
+        }
     }
 }
 
 // Conversion from cortex_core::error::CortexError
 impl From<cortex_core::error::CortexError> for ApiError {
     fn from(err: cortex_core::error::CortexError) -> Self {
-        ApiError::Internal(err.to_string())
+        match err {
+            cortex_core::error::CortexError::WorkspaceReadOnly(_) => ApiError::Forbidden(err.to_string()),
+            _ => ApiError::Internal(err.to_string()),
+        }
     }
 }
 
@@ -1,9 +1,10 @@
 //! Authentication middleware
 
-use crate::services::auth::{AuthService, Claims};
+use crate::api::error::ApiError;
+use crate::services::auth::{ApiScope, AuthService, Claims, API_KEY_PREFIX};
 use axum::{
     extract::{FromRequestParts, Request},
-    http::{header::{AUTHORIZATION, WWW_AUTHENTICATE}, request::Parts, StatusCode, HeaderValue},
+    http::{header::{AUTHORIZATION, WWW_AUTHENTICATE}, request::Parts, Method, StatusCode, HeaderValue},
     middleware::Next,
     response::{Response, IntoResponse},
     Json,
@@ -16,6 +17,16 @@ use std::sync::Arc;
 #[derive(Debug, Clone)]
 pub struct BearerToken(pub String);
 
+tokio::task_local! {
+    /// API key id of the request being handled, used to stamp `ApiMetadata`
+    static CURRENT_API_KEY_ID: Option<String>;
+}
+
+/// API key id that authenticated the current request, if any
+pub fn current_api_key_id() -> Option<String> {
+    CURRENT_API_KEY_ID.try_with(|id| id.clone()).ok().flatten()
+}
+
 /// Authenticated user information stored in request extensions
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthUser {
@@ -23,9 +34,20 @@ pub struct AuthUser {
     pub email: String,
     pub roles: Vec<String>,
     pub session_id: Option<String>,
+    /// API key used to authenticate, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_id: Option<String>,
+    /// Scopes granted to this principal
+    #[serde(default)]
+    pub scopes: Vec<ApiScope>,
 }
 
 impl AuthUser {
+    /// Check if any granted scope satisfies `required`
+    pub fn has_scope(&self, required: ApiScope) -> bool {
+        self.scopes.iter().any(|scope| scope.grants(required))
+    }
+
     /// Check if user has a specific role
     pub fn has_role(&self, role: &str) -> bool {
         self.roles.iter().any(|r| r == role)
@@ -44,15 +66,47 @@ impl AuthUser {
 
 impl From<&Claims> for AuthUser {
     fn from(claims: &Claims) -> Self {
+        // Interactive sessions get the highest scope of their roles: admins
+        // administer, viewers only read, and everyone else may also write
+        let scope = claims
+            .roles
+            .iter()
+            .map(|role| match role.as_str() {
+                "admin" => ApiScope::Admin,
+                "viewer" => ApiScope::Read,
+                _ => ApiScope::Write,
+            })
+            .max()
+            .unwrap_or(ApiScope::Write);
+
         Self {
             user_id: claims.sub.clone(),
             email: claims.email.clone(),
             roles: claims.roles.clone(),
             session_id: None,
+            api_key_id: None,
+            scopes: vec![scope],
         }
     }
 }
 
+/// Scope a request needs, derived from its route group and method
+///
+/// Administrative routes need `admin`, mutating methods need `write`, and
+/// everything else needs `read`. Creating an API key only needs `write`; the
+/// key can never get more scope than its creator holds.
+pub fn required_scope(method: &Method, path: &str) -> ApiScope {
+    const ADMIN_PREFIXES: &[&str] = &["/api/v1/admin", "/api/v1/export"];
+
+    if ADMIN_PREFIXES.iter().any(|prefix| path.starts_with(prefix)) {
+        ApiScope::Admin
+    } else if matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS) {
+        ApiScope::Read
+    } else {
+        ApiScope::Write
+    }
+}
+
 /// Authentication middleware state
 #[derive(Clone)]
 pub struct AuthState {
@@ -91,7 +145,16 @@ async fn try_validate_auth(
     auth_header: &str,
     required: bool,
 ) -> Option<Result<(Option<BearerToken>, Claims, AuthUser), Response>> {
-    // Try Bearer token first
+    // API keys may be presented either as `ApiKey <key>` or `Bearer <key>`
+    let api_key = auth_header
+        .strip_prefix("ApiKey ")
+        .or_else(|| auth_header.strip_prefix("Bearer ").filter(|t| t.starts_with(API_KEY_PREFIX)));
+
+    if let Some(api_key) = api_key {
+        return Some(validate_api_key(state, api_key, required).await);
+    }
+
+    // Try Bearer token (JWT)
     if auth_header.starts_with("Bearer ") {
         let token = auth_header.trim_start_matches("Bearer ").to_string();
 
@@ -146,43 +209,56 @@ async fn try_validate_auth(
             }
         }
     }
-    // Try API key
-    else if auth_header.starts_with("ApiKey ") {
-        let api_key = auth_header.trim_start_matches("ApiKey ");
-
-        // Use AuthService to validate API key
-        match state.auth_service.validate_api_key(api_key).await {
-            Ok(Some(key_info)) => {
-                // Create claims from API key info
-                let claims = Claims {
-                    sub: key_info.user_id.clone(),
-                    email: String::new(), // API keys don't have email in info
-                    roles: vec![], // Would need to fetch user to get roles
-                    exp: key_info.expires_at.map(|dt| dt.timestamp()).unwrap_or(0),
-                    iat: chrono::Utc::now().timestamp(),
-                    token_type: "api_key".to_string(),
-                };
+    None // Unrecognized auth header format
+}
 
-                let auth_user = AuthUser::from(&claims);
+/// Validate an API key and build the principal from its scopes
+async fn validate_api_key(
+    state: &AuthState,
+    api_key: &str,
+    required: bool,
+) -> Result<(Option<BearerToken>, Claims, AuthUser), Response> {
+    match state.auth_service.validate_api_key(api_key).await {
+        Ok(Some(key_info)) => {
+            // Create claims from API key info
+            let claims = Claims {
+                sub: key_info.user_id.clone(),
+                email: String::new(), // API keys don't have email in info
+                roles: vec![], // Would need to fetch user to get roles
+                exp: key_info.expires_at.map(|dt| dt.timestamp()).unwrap_or(0),
+                iat: chrono::Utc::now().timestamp(),
+                token_type: "api_key".to_string(),
+            };
+
+            // Unknown scopes were rejected at creation; skip any that slipped in
+            let scopes = key_info
+                .scopes
+                .iter()
+                .filter_map(|s| s.parse::<ApiScope>().ok())
+                .collect();
+
+            let auth_user = AuthUser {
+                api_key_id: Some(key_info.id.clone()),
+                scopes,
+                ..AuthUser::from(&claims)
+            };
 
-                tracing::debug!(
-                    user_id = %auth_user.user_id,
-                    "User authenticated via API key"
-                );
+            tracing::debug!(
+                user_id = %auth_user.user_id,
+                key_id = %key_info.id,
+                "User authenticated via API key"
+            );
 
-                return Some(Ok((None, claims, auth_user)));
-            }
-            Ok(None) | Err(_) => {
-                tracing::warn!("API key validation failed");
-                if required {
-                    return Some(Err(unauthorized_response("Invalid API key").into_response()));
-                }
-                return Some(Err(Response::default())); // Signal failure for optional auth
+            Ok((None, claims, auth_user))
+        }
+        Ok(None) | Err(_) => {
+            tracing::warn!("API key validation failed");
+            if required {
+                return Err(unauthorized_response("Invalid API key").into_response());
             }
+            Err(Response::default()) // Signal failure for optional auth
         }
     }
-
-    None // Unrecognized auth header format
 }
 
 impl AuthMiddleware {
@@ -205,9 +281,10 @@ impl AuthMiddleware {
                         if let Some(token) = bearer_token {
                             req.extensions_mut().insert(token);
                         }
+                        let key_id = auth_user.api_key_id.clone();
                         req.extensions_mut().insert(claims);
                         req.extensions_mut().insert(auth_user);
-                        return next.run(req).await;
+                        return CURRENT_API_KEY_ID.scope(key_id, next.run(req)).await;
                     }
                     Err(err_response) => {
                         return err_response.into_response();
@@ -247,6 +324,49 @@ impl AuthMiddleware {
         next.run(req).await
     }
 
+    /// Scope-based access control middleware
+    ///
+    /// Must run after [`AuthMiddleware::validate`]. Checks the scope required by
+    /// the route group (see [`required_scope`]) against the authenticated
+    /// principal and records API key access for auditing.
+    pub async fn require_scope(
+        req: Request,
+        next: Next,
+    ) -> Response {
+        let auth_user = match req.extensions().get::<AuthUser>() {
+            Some(user) => user,
+            None => return unauthorized_response("Authentication required").into_response(),
+        };
+
+        let required = required_scope(req.method(), req.uri().path());
+
+        if !auth_user.has_scope(required) {
+            tracing::warn!(
+                user_id = %auth_user.user_id,
+                key_id = ?auth_user.api_key_id,
+                required_scope = %required,
+                granted_scopes = ?auth_user.scopes,
+                "Insufficient scope"
+            );
+            return forbidden_response(&format!(
+                "Insufficient scope. Required scope: {}",
+                required
+            )).into_response();
+        }
+
+        if let Some(key_id) = &auth_user.api_key_id {
+            tracing::info!(
+                key_id = %key_id,
+                method = %req.method(),
+                path = %req.uri().path(),
+                scope = %required,
+                "API key access"
+            );
+        }
+
+        next.run(req).await
+    }
+
     /// Role-based access control middleware - requires specific role
     pub async fn require_role(
         required_role: String,
@@ -345,36 +465,19 @@ impl AuthMiddleware {
 // Note: validate_jwt and validate_api_key functions are now in AuthService
 // We don't need duplicate validation logic here
 
-/// Create unauthorized response with WWW-Authenticate header
-fn unauthorized_response(message: &str) -> (StatusCode, [(axum::http::HeaderName, HeaderValue); 1], Json<AuthErrorResponse>) {
-    (
-        StatusCode::UNAUTHORIZED,
-        [(
-            WWW_AUTHENTICATE,
-            HeaderValue::from_static("Bearer realm=\"Cortex API\""),
-        )],
-        Json(AuthErrorResponse {
-            success: false,
-            error: AuthErrorDetail {
-                code: "UNAUTHORIZED".to_string(),
-                message: message.to_string(),
-            },
-        }),
-    )
+/// Create unauthorized response (standard `ErrorResponse` body) with WWW-Authenticate header
+fn unauthorized_response(message: &str) -> Response {
+    let mut response = ApiError::Unauthorized(message.to_string()).into_response();
+    response.headers_mut().insert(
+        WWW_AUTHENTICATE,
+        HeaderValue::from_static("Bearer realm=\"Cortex API\""),
+    );
+    response
 }
 
-/// Create forbidden response
-fn forbidden_response(message: &str) -> (StatusCode, Json<AuthErrorResponse>) {
-    (
-        StatusCode::FORBIDDEN,
-        Json(AuthErrorResponse {
-            success: false,
-            error: AuthErrorDetail {
-                code: "FORBIDDEN".to_string(),
-                message: message.to_string(),
-            },
-        }),
-    )
+/// Create forbidden response (standard `ErrorResponse` body)
+fn forbidden_response(message: &str) -> Response {
+    ApiError::Forbidden(message.to_string()).into_response()
 }
 
 /// Extractor for authenticated requests - extracts Claims
@@ -478,3 +581,64 @@ where
             })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_required_scope_by_route_group() {
+        assert_eq!(required_scope(&Method::GET, "/api/v1/workspaces"), ApiScope::Read);
+        assert_eq!(required_scope(&Method::POST, "/api/v1/workspaces"), ApiScope::Write);
+        assert_eq!(required_scope(&Method::DELETE, "/api/v1/files/abc"), ApiScope::Write);
+        assert_eq!(required_scope(&Method::POST, "/api/v1/auth/api-key"), ApiScope::Write);
+        assert_eq!(required_scope(&Method::GET, "/api/v1/export/workspace"), ApiScope::Admin);
+    }
+
+    #[test]
+    fn test_auth_user_scopes() {
+        let claims = Claims {
+            sub: "user-1".to_string(),
+            email: "user@example.com".to_string(),
+            roles: vec!["developer".to_string()],
+            exp: 0,
+            iat: 0,
+            token_type: "access".to_string(),
+        };
+
+        let session_user = AuthUser::from(&claims);
+        assert!(session_user.has_scope(ApiScope::Write));
+        assert!(!session_user.has_scope(ApiScope::Admin));
+
+        let key_user = AuthUser {
+            api_key_id: Some("key-1".to_string()),
+            scopes: vec![ApiScope::Read],
+            ..AuthUser::from(&claims)
+        };
+        assert!(key_user.has_scope(ApiScope::Read));
+        assert!(!key_user.has_scope(ApiScope::Write));
+
+        let viewer = AuthUser::from(&Claims {
+            roles: vec!["viewer".to_string()],
+            ..claims.clone()
+        });
+        assert!(viewer.has_scope(ApiScope::Read));
+        assert!(!viewer.has_scope(ApiScope::Write));
+
+        let admin = AuthUser::from(&Claims {
+            roles: vec!["viewer".to_string(), "admin".to_string()],
+            ..claims
+        });
+        assert!(admin.has_scope(ApiScope::Admin));
+    }
+
+    #[tokio::test]
+    async fn test_current_api_key_id_is_task_scoped() {
+        assert_eq!(current_api_key_id(), None);
+
+        let inside = CURRENT_API_KEY_ID
+            .scope(Some("key-1".to_string()), async { current_api_key_id() })
+            .await;
+        assert_eq!(inside.as_deref(), Some("key-1"));
+    }
+}
@@ -1,6 +1,7 @@
 //! Pagination and HATEOAS link generation helpers
 
 use super::types::{CursorData, HateoasLinks, PaginationInfo};
+use crate::services::SortSpec;
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
@@ -36,6 +37,48 @@ pub fn build_pagination_info(
         total,
         count: items_count,
         limit,
+        offset: None,
+        sort: None,
+    }
+}
+
+/// Resolve a list offset, preferring an explicit `offset` over a cursor
+pub fn resolve_offset(offset: Option<usize>, cursor: Option<&str>) -> Result<usize, String> {
+    match (offset, cursor) {
+        (Some(offset), _) => Ok(offset),
+        (None, Some(cursor)) => Ok(decode_cursor(cursor)?.offset),
+        (None, None) => Ok(0),
+    }
+}
+
+/// Build pagination info for an offset-based list response with a known total
+pub fn build_offset_pagination_info(
+    items_count: usize,
+    limit: usize,
+    offset: usize,
+    total: usize,
+    sort: Option<&SortSpec>,
+) -> PaginationInfo {
+    let has_more = offset + items_count < total;
+    let next_cursor = if has_more {
+        encode_cursor(&CursorData {
+            last_id: String::new(),
+            last_timestamp: Utc::now(),
+            offset: offset + items_count,
+        })
+        .ok()
+    } else {
+        None
+    };
+
+    PaginationInfo {
+        cursor: next_cursor,
+        has_more,
+        total: Some(total),
+        count: items_count,
+        limit,
+        offset: Some(offset),
+        sort: sort.map(|s| s.to_string()),
     }
 }
 
@@ -99,6 +142,29 @@ impl LinkBuilder {
         }
     }
 
+    /// Build HATEOAS links for an offset-based list endpoint
+    pub fn build_offset_links(
+        &self,
+        limit: usize,
+        offset: usize,
+        total: usize,
+        sort: Option<&SortSpec>,
+    ) -> HateoasLinks {
+        let sort_param = sort
+            .map(|s| format!("&sort={}", s))
+            .unwrap_or_default();
+        let page_link = |offset: usize| {
+            format!("{}?limit={}&offset={}{}", self.base_url, limit, offset, sort_param)
+        };
+
+        HateoasLinks {
+            self_link: page_link(offset),
+            next: (offset.saturating_add(limit) < total).then(|| page_link(offset + limit)),
+            prev: (offset > 0).then(|| page_link(offset.saturating_sub(limit))),
+            related: None,
+        }
+    }
+
     /// Build HATEOAS links for a single resource
     pub fn build_resource_links(
         &self,
@@ -244,6 +310,7 @@ impl LinkBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::services::SortDirection;
 
     #[test]
     fn test_cursor_encoding_decoding() {
@@ -281,6 +348,49 @@ mod tests {
         assert!(links.next.unwrap().contains("next-cursor"));
     }
 
+    #[test]
+    fn test_build_offset_pagination_info() {
+        let sort = SortSpec::new("name", SortDirection::Asc);
+        let pagination = build_offset_pagination_info(20, 20, 40, 100, Some(&sort));
+
+        assert_eq!(pagination.offset, Some(40));
+        assert_eq!(pagination.total, Some(100));
+        assert_eq!(pagination.sort.as_deref(), Some("name:asc"));
+        assert!(pagination.has_more);
+
+        let next_offset = resolve_offset(None, pagination.cursor.as_deref()).unwrap();
+        assert_eq!(next_offset, 60);
+
+        let last_page = build_offset_pagination_info(20, 20, 80, 100, None);
+        assert!(!last_page.has_more);
+        assert!(last_page.cursor.is_none());
+    }
+
+    #[test]
+    fn test_resolve_offset_prefers_explicit_offset() {
+        let cursor = generate_next_cursor("id".to_string(), Utc::now(), 30).unwrap();
+        assert_eq!(resolve_offset(Some(5), Some(&cursor)).unwrap(), 5);
+        assert_eq!(resolve_offset(None, Some(&cursor)).unwrap(), 30);
+        assert_eq!(resolve_offset(None, None).unwrap(), 0);
+        assert!(resolve_offset(None, Some("not-a-cursor")).is_err());
+    }
+
+    #[test]
+    fn test_offset_links() {
+        let builder = LinkBuilder::new("/api/v1/workspaces");
+        let links = builder.build_offset_links(20, 20, 50, None);
+
+        assert_eq!(links.self_link, "/api/v1/workspaces?limit=20&offset=20");
+        assert_eq!(links.next.as_deref(), Some("/api/v1/workspaces?limit=20&offset=40"));
+        assert_eq!(links.prev.as_deref(), Some("/api/v1/workspaces?limit=20&offset=0"));
+
+        let last = builder.build_offset_links(20, 40, 50, None);
+        assert!(last.next.is_none());
+
+        let past_end = builder.build_offset_links(20, usize::MAX, 50, None);
+        assert!(past_end.next.is_none());
+    }
+
     #[test]
     fn test_workspace_links() {
         let links = LinkBuilder::build_workspace_links("ws-123");
@@ -1,8 +1,8 @@
 //! Authentication routes
 
 use crate::api::{error::ApiError, types::ApiResponse};
-use crate::api::middleware::auth::BearerToken;
-use crate::services::auth::{AuthService, Claims};
+use crate::api::middleware::auth::{AuthUser, BearerToken};
+use crate::services::auth::{ApiScope, AuthService, Claims};
 use axum::{
     extract::State,
     http::StatusCode,
@@ -194,15 +194,25 @@ async fn refresh_token(
 /// POST /api/v1/auth/api-key - Create API key
 async fn create_api_key(
     State(ctx): State<AuthContext>,
-    claims: Claims, // Extracted by auth middleware
+    auth_user: AuthUser, // Extracted by auth middleware
     Json(req): Json<CreateApiKeyRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
     let request_id = Uuid::new_v4().to_string();
     let start = Instant::now();
 
+    // A key can carry at most the scopes of the principal creating it
+    let requested = ApiScope::parse_list(&req.scopes)
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    if let Some(scope) = requested.iter().find(|scope| !auth_user.has_scope(**scope)) {
+        return Err(ApiError::Forbidden(format!(
+            "Cannot create an API key with '{}' scope you do not hold",
+            scope
+        )));
+    }
+
     // Use AuthService to create API key
     let api_key = ctx.auth_service
-        .create_api_key(&claims.sub, req.name.clone(), req.scopes.clone(), req.expires_in_days)
+        .create_api_key(&auth_user.user_id, req.name.clone(), req.scopes.clone(), req.expires_in_days)
         .await
         .map_err(|e| ApiError::Internal(e.to_string()))?;
 
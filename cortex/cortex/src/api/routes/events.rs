@@ -0,0 +1,372 @@
+//! Live workspace change events over WebSocket
+//!
+//! `GET /api/v1/workspaces/{workspace_id}/events` upgrades to a WebSocket
+//! that streams the workspace's VFS events as JSON text messages:
+//!
+//! ```json
+//! {"seq":42,"workspace_id":"…","timestamp":"…","type":"node_updated","path":"docs/plan.md"}
+//! ```
+//!
+//! Query parameters:
+//! - `types`: comma-separated event types to receive (`node_created`,
+//!   `node_updated`, `node_deleted`, `node_moved`, `workspace_updated`,
+//!   `sync_progress`); all types if absent
+//! - `since`: sequence number of the last event the client saw; retained
+//!   events after it are replayed before live ones
+//!
+//! The first message is `{"type":"subscribed","last_seq":…}`. If events after
+//! `since` were already evicted, a `{"type":"resync_required"}` message
+//! follows, and the client should reload the workspace state.
+
+use crate::api::error::{ApiError, ApiResult};
+use crate::services::WorkspaceService;
+use axum::{
+    extract::{
+        ws::{CloseFrame, Message, WebSocket, WebSocketUpgrade},
+        Path, Query, State,
+    },
+    response::Response,
+    routing::get,
+    Router,
+};
+use cortex_vfs::{Backlog, VfsEvent, VfsEventKind};
+use futures::{
+    stream::{SplitSink, SplitStream},
+    SinkExt, StreamExt,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+/// Close code sent to a consumer that fell too far behind
+pub const CLOSE_LAGGED: u16 = 4000;
+
+/// Close code sent when the workspace has too many open streams (RFC 6455
+/// "Try Again Later")
+pub const CLOSE_TRY_AGAIN_LATER: u16 = 1013;
+
+/// Limits of workspace event streams
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventStreamConfig {
+    /// Open streams allowed per workspace
+    pub max_connections_per_workspace: usize,
+    /// Undelivered events a consumer may fall behind by before it is dropped
+    pub max_lag: usize,
+}
+
+impl Default for EventStreamConfig {
+    fn default() -> Self {
+        Self {
+            max_connections_per_workspace: 32,
+            max_lag: 256,
+        }
+    }
+}
+
+/// Event stream context
+#[derive(Clone)]
+pub struct EventsContext {
+    pub workspace_service: Arc<WorkspaceService>,
+    pub config: EventStreamConfig,
+    connections: ConnectionCounter,
+}
+
+impl EventsContext {
+    pub fn new(workspace_service: Arc<WorkspaceService>, config: EventStreamConfig) -> Self {
+        Self {
+            workspace_service,
+            config,
+            connections: ConnectionCounter::default(),
+        }
+    }
+}
+
+/// Create workspace event routes
+pub fn event_routes(context: EventsContext) -> Router {
+    Router::new()
+        .route("/api/v1/workspaces/{workspace_id}/events", get(workspace_events))
+        .with_state(context)
+}
+
+/// Query parameters of an event stream
+#[derive(Debug, Default, Deserialize)]
+pub struct EventStreamParams {
+    pub types: Option<String>,
+    pub since: Option<u64>,
+}
+
+/// Stream control messages, interleaved with events
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ControlMessage {
+    Subscribed { workspace_id: Uuid, last_seq: u64 },
+    ResyncRequired { since: u64 },
+}
+
+/// Event types a stream delivers
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct EventFilter {
+    /// `None` delivers every type
+    types: Option<HashSet<&'static str>>,
+}
+
+impl EventFilter {
+    fn parse(types: Option<&str>) -> Result<Self, String> {
+        let Some(types) = types.filter(|types| !types.trim().is_empty()) else {
+            return Ok(Self::default());
+        };
+
+        let types = types
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| {
+                VfsEventKind::NAMES
+                    .iter()
+                    .find(|known| **known == name)
+                    .copied()
+                    .ok_or_else(|| format!(
+                        "Unknown event type '{}' (expected one of: {})",
+                        name,
+                        VfsEventKind::NAMES.join(", ")
+                    ))
+            })
+            .collect::<Result<HashSet<_>, _>>()?;
+
+        Ok(Self { types: Some(types) })
+    }
+
+    fn matches(&self, event: &VfsEvent) -> bool {
+        self.types
+            .as_ref()
+            .is_none_or(|types| types.contains(event.kind.name()))
+    }
+}
+
+/// Open streams per workspace
+#[derive(Clone, Default)]
+struct ConnectionCounter {
+    counts: Arc<Mutex<HashMap<Uuid, usize>>>,
+}
+
+impl ConnectionCounter {
+    /// Take a slot for a new stream, unless `max` streams are open already
+    fn acquire(&self, workspace_id: Uuid, max: usize) -> Option<ConnectionSlot> {
+        let mut counts = self.counts.lock().unwrap();
+        let count = counts.entry(workspace_id).or_default();
+        if *count >= max {
+            return None;
+        }
+        *count += 1;
+
+        Some(ConnectionSlot {
+            workspace_id,
+            counts: self.counts.clone(),
+        })
+    }
+}
+
+/// An open stream; dropping it frees the slot
+struct ConnectionSlot {
+    workspace_id: Uuid,
+    counts: Arc<Mutex<HashMap<Uuid, usize>>>,
+}
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        let mut counts = self.counts.lock().unwrap();
+        if let Some(count) = counts.get_mut(&self.workspace_id) {
+            *count -= 1;
+            if *count == 0 {
+                counts.remove(&self.workspace_id);
+            }
+        }
+    }
+}
+
+/// GET /api/v1/workspaces/{workspace_id}/events - Stream workspace changes
+async fn workspace_events(
+    ws: WebSocketUpgrade,
+    State(ctx): State<EventsContext>,
+    Path(workspace_id): Path<String>,
+    Query(params): Query<EventStreamParams>,
+) -> ApiResult<Response> {
+    let workspace_uuid = Uuid::parse_str(&workspace_id)
+        .map_err(|_| ApiError::BadRequest("Invalid workspace ID".to_string()))?;
+
+    let filter = EventFilter::parse(params.types.as_deref()).map_err(ApiError::BadRequest)?;
+
+    ctx.workspace_service
+        .get_workspace(&workspace_uuid)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?
+        .ok_or_else(|| ApiError::NotFound(format!("Workspace {} not found", workspace_id)))?;
+
+    // Browsers cannot read the status of a refused handshake, so a full
+    // workspace is reported with a close code after the upgrade instead
+    let slot = ctx
+        .connections
+        .acquire(workspace_uuid, ctx.config.max_connections_per_workspace);
+
+    Ok(ws.on_upgrade(move |socket| async move {
+        let (mut sender, receiver) = socket.split();
+        match slot {
+            Some(_slot) => {
+                stream_events(&mut sender, receiver, ctx, workspace_uuid, filter, params.since).await;
+            }
+            None => {
+                warn!(workspace_id = %workspace_uuid, "Refusing event stream: too many connections");
+                close(&mut sender, CLOSE_TRY_AGAIN_LATER, "Too many event streams for this workspace").await;
+            }
+        }
+    }))
+}
+
+/// Forward the workspace's events until the client goes away or lags
+async fn stream_events(
+    sender: &mut SplitSink<WebSocket, Message>,
+    mut receiver: SplitStream<WebSocket>,
+    ctx: EventsContext,
+    workspace_id: Uuid,
+    filter: EventFilter,
+    since: Option<u64>,
+) {
+    let bus = ctx.workspace_service.vfs.events().clone();
+    let (mut events, backlog) = match since {
+        Some(since) => bus.subscribe_since(&workspace_id, since),
+        None => (bus.subscribe(), Backlog::default()),
+    };
+    debug!(workspace_id = %workspace_id, since = ?since, replay = backlog.events.len(), "Event stream opened");
+
+    let subscribed = ControlMessage::Subscribed {
+        workspace_id,
+        last_seq: bus.last_seq(),
+    };
+    if send_json(sender, &subscribed).await.is_err() {
+        return;
+    }
+    if let Some(since) = since.filter(|_| backlog.truncated) {
+        if send_json(sender, &ControlMessage::ResyncRequired { since }).await.is_err() {
+            return;
+        }
+    }
+    for event in backlog.events.iter().filter(|event| filter.matches(event)) {
+        if send_json(sender, event.as_ref()).await.is_err() {
+            return;
+        }
+    }
+
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) => {
+                    if events.len() > ctx.config.max_lag {
+                        warn!(workspace_id = %workspace_id, pending = events.len(), "Dropping lagging event stream");
+                        close(sender, CLOSE_LAGGED, "Consumer lagged behind").await;
+                        break;
+                    }
+                    if event.workspace_id != workspace_id || !filter.matches(&event) {
+                        continue;
+                    }
+                    if send_json(sender, event.as_ref()).await.is_err() {
+                        break;
+                    }
+                }
+                Err(RecvError::Lagged(missed)) => {
+                    warn!(workspace_id = %workspace_id, missed, "Dropping lagging event stream");
+                    close(sender, CLOSE_LAGGED, "Consumer lagged behind").await;
+                    break;
+                }
+                Err(RecvError::Closed) => break,
+            },
+            message = receiver.next() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                // Streams are one-way; pings are answered by the socket
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+
+    debug!(workspace_id = %workspace_id, "Event stream closed");
+}
+
+async fn send_json<T: Serialize>(
+    sender: &mut SplitSink<WebSocket, Message>,
+    message: &T,
+) -> Result<(), axum::Error> {
+    let json = serde_json::to_string(message).map_err(axum::Error::new)?;
+    sender.send(Message::Text(json.into())).await
+}
+
+async fn close(sender: &mut SplitSink<WebSocket, Message>, code: u16, reason: &'static str) {
+    let frame = CloseFrame {
+        code,
+        reason: reason.into(),
+    };
+    if let Err(e) = sender.send(Message::Close(Some(frame))).await {
+        debug!("Failed to send close frame: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(kind: VfsEventKind) -> VfsEvent {
+        VfsEvent {
+            seq: 1,
+            workspace_id: Uuid::new_v4(),
+            timestamp: chrono::Utc::now(),
+            kind,
+        }
+    }
+
+    #[test]
+    fn test_event_filter() {
+        let created = event(VfsEventKind::NodeCreated {
+            path: "a.md".to_string(),
+            node_type: cortex_vfs::NodeType::File,
+        });
+        let deleted = event(VfsEventKind::NodeDeleted { path: "a.md".to_string() });
+
+        let all = EventFilter::parse(None).unwrap();
+        assert!(all.matches(&created) && all.matches(&deleted));
+        assert_eq!(EventFilter::parse(Some(" ")).unwrap(), all);
+
+        let only_created = EventFilter::parse(Some("node_created, node_moved")).unwrap();
+        assert!(only_created.matches(&created));
+        assert!(!only_created.matches(&deleted));
+
+        let err = EventFilter::parse(Some("node_created,renamed")).unwrap_err();
+        assert!(err.contains("renamed"));
+    }
+
+    #[test]
+    fn test_connection_counter() {
+        let counter = ConnectionCounter::default();
+        let workspace = Uuid::new_v4();
+
+        let first = counter.acquire(workspace, 2).unwrap();
+        let _second = counter.acquire(workspace, 2).unwrap();
+        assert!(counter.acquire(workspace, 2).is_none());
+        assert!(counter.acquire(Uuid::new_v4(), 2).is_some());
+
+        drop(first);
+        assert!(counter.acquire(workspace, 2).is_some());
+
+        // Freed slots are forgotten with their last stream
+        let counts = counter.counts.lock().unwrap();
+        assert_eq!(counts.len(), 1);
+        assert_eq!(counts.get(&workspace), Some(&1));
+    }
+
+    #[test]
+    fn test_control_message_serialization() {
+        let json = serde_json::to_value(ControlMessage::ResyncRequired { since: 3 }).unwrap();
+        assert_eq!(json["type"], "resync_required");
+        assert_eq!(json["since"], 3);
+    }
+}
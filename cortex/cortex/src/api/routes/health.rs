@@ -2,13 +2,15 @@
 
 use crate::api::{
     error::ApiResult,
-    types::{ApiResponse, HealthResponse, DatabaseHealth, MemoryHealth, MetricsResponse},
+    types::{ApiResponse, HealthResponse, DatabaseHealth, MemoryHealth, MetricsResponse, SearchHealth},
 };
+use crate::services::SearchService;
 use axum::{
     extract::State,
     routing::get,
     Json, Router,
 };
+use cortex_semantic::ReadinessStatus;
 use cortex_storage::ConnectionManager;
 use std::sync::Arc;
 use std::time::Instant;
@@ -18,6 +20,7 @@ use std::time::Instant;
 pub struct AppState {
     pub start_time: Instant,
     pub storage: Arc<ConnectionManager>,
+    pub search_service: Arc<SearchService>,
 }
 
 /// Create health check routes
@@ -73,13 +76,26 @@ async fn health_check(
         }
     };
 
-    // Determine overall status
-    let status = if db_connected {
-        "healthy".to_string()
-    } else {
-        "unhealthy".to_string()
+    // Semantic search is warmed up in the background after start
+    let readiness = state.search_service.readiness().await;
+    let search = SearchHealth {
+        ready: readiness.is_ready(),
+        state: readiness.as_str().to_string(),
+        error: match &readiness {
+            ReadinessStatus::Failed { stage, error } => Some(format!("{}: {}", stage.as_str(), error)),
+            _ => None,
+        },
     };
 
+    // Determine overall status
+    let status = match (&readiness, db_connected) {
+        (_, false) => "unhealthy",
+        (ReadinessStatus::Ready, true) => "healthy",
+        (ReadinessStatus::Failed { .. }, true) => "degraded",
+        (ReadinessStatus::Cold | ReadinessStatus::WarmingUp, true) => "starting",
+    }
+    .to_string();
+
     let health = HealthResponse {
         status,
         version: env!("CARGO_PKG_VERSION").to_string(),
@@ -89,6 +105,7 @@ async fn health_check(
             response_time_ms: db_response_time,
         },
         memory: memory_info,
+        search,
     };
 
     let duration = start.elapsed().as_millis() as u64;
@@ -174,12 +191,15 @@ async fn metrics(
         .and_then(|v| v.get("count").and_then(|c| c.as_u64()))
         .unwrap_or(0) as usize;
 
+    let pool_metrics = state.storage.metrics().snapshot();
     let metrics = MetricsResponse {
         workspaces,
         files,
         total_size_bytes,
         episodes,
         semantic_nodes,
+        slow_queries: pool_metrics.slow_queries,
+        query_timeouts: pool_metrics.query_timeouts,
     };
 
     tracing::debug!(
@@ -1,15 +1,16 @@
 //! Memory management endpoints
 
 use crate::api::{
-    error::ApiResult,
+    error::{ApiError, ApiResult},
     types::{
         ApiResponse, ConsolidateMemoryRequest, MemoryEpisode,
-        EpisodeSearchRequest, LearnedPattern,
+        EpisodeSearchRequest, LearnedPattern, PaginationParams,
     },
+    pagination::{LinkBuilder, build_offset_pagination_info},
 };
-use crate::services::MemoryService;
+use crate::services::{MemoryService, PageWindow, SortDirection, SortSpec};
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     routing::{get, post},
     Json, Router,
 };
@@ -33,28 +34,36 @@ pub fn memory_routes(context: MemoryContext) -> Router {
         .with_state(context)
 }
 
+/// Fields `GET /api/v1/memory/episodes` can be sorted by
+const EPISODE_SORT_FIELDS: &[&str] = &["created_at", "duration_seconds", "episode_type"];
+
 /// GET /api/v1/memory/episodes - List memory episodes
 async fn list_episodes(
     State(ctx): State<MemoryContext>,
+    Query(mut params): Query<PaginationParams>,
 ) -> ApiResult<Json<ApiResponse<Vec<MemoryEpisode>>>> {
     let request_id = uuid::Uuid::new_v4().to_string();
     let start = Instant::now();
 
-    // Use MemoryService to recall recent episodes
-    let service_request = crate::services::memory::RecallEpisodesRequest {
-        query: String::new(), // Empty query to get all
-        episode_type: None,
-        limit: Some(100),
-        min_importance: None,
-    };
-
-    let service_episodes = ctx.memory_service
-        .recall_episodes(service_request)
+    params.validate().map_err(ApiError::BadRequest)?;
+    let offset = params.effective_offset().map_err(ApiError::BadRequest)?;
+    let sort = SortSpec::parse(
+        params.sort.as_deref(),
+        EPISODE_SORT_FIELDS,
+        SortSpec::new("created_at", SortDirection::Desc),
+    )
+    .map_err(ApiError::BadRequest)?;
+
+    // Use MemoryService to fetch one page of episodes
+    let page = ctx.memory_service
+        .list_episodes(&sort, PageWindow::new(params.limit, offset))
         .await
-        .map_err(|e| crate::api::error::ApiError::Internal(e.to_string()))?;
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    let total = page.total;
 
     // Convert service episodes to API episodes
-    let episodes: Vec<MemoryEpisode> = service_episodes
+    let episodes: Vec<MemoryEpisode> = page
+        .items
         .into_iter()
         .map(|ep| MemoryEpisode {
             id: ep.id,
@@ -65,11 +74,21 @@ async fn list_episodes(
         })
         .collect();
 
-    tracing::debug!(count = episodes.len(), "Listed memory episodes");
+    tracing::debug!(count = episodes.len(), total = total, offset = offset, "Listed memory episodes");
 
     let duration = start.elapsed().as_millis() as u64;
 
-    Ok(Json(ApiResponse::success(episodes, request_id, duration)))
+    let pagination = build_offset_pagination_info(episodes.len(), params.limit, offset, total, Some(&sort));
+    let links = LinkBuilder::new("/api/v1/memory/episodes")
+        .build_offset_links(params.limit, offset, total, Some(&sort));
+
+    Ok(Json(ApiResponse::success_with_pagination(
+        episodes,
+        request_id,
+        duration,
+        pagination,
+        links,
+    )))
 }
 
 /// GET /api/v1/memory/episodes/:episode_id - Get episode details
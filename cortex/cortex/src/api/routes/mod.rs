@@ -14,6 +14,7 @@ pub mod dashboard;
 pub mod tasks;
 pub mod export;
 pub mod documents;
+pub mod events;
 
 pub use workspaces::workspace_routes;
 pub use vfs::vfs_routes;
@@ -29,3 +30,4 @@ pub use dashboard::dashboard_routes;
 pub use tasks::task_routes;
 pub use export::export_routes;
 pub use documents::{document_routes, DocumentContext};
+pub use events::{event_routes, EventStreamConfig, EventsContext};
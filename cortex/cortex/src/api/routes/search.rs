@@ -3,12 +3,13 @@
 use crate::api::{
     error::{ApiError, ApiResult},
     types::{
-        ApiResponse, SearchRequest, SearchResult,
+        ApiResponse, HateoasLinks, PaginationInfo, SearchRequest, SearchResult,
         ReferencesResponse, CodeReference, PatternSearchRequest,
         PatternSearchResponse, PatternMatch,
     },
 };
-use crate::services::SearchService;
+use crate::services::{SearchService, SortDirection, SortSpec};
+use cortex_semantic::{FilterExpr, SemanticError};
 use axum::{
     extract::{Path, Query, State},
     routing::{get, post},
@@ -32,6 +33,13 @@ pub fn search_routes(context: SearchContext) -> Router {
         .with_state(context)
 }
 
+/// Fields `GET /api/v1/search` results can be sorted by
+const SEARCH_SORT_FIELDS: &[&str] = &["score", "title"];
+
+/// Deepest result `GET /api/v1/search` pages into (`offset + limit`), since
+/// every page re-ranks all results before it
+const MAX_SEARCH_WINDOW: usize = 1000;
+
 /// GET /api/v1/search - Search across memory
 async fn search(
     State(ctx): State<SearchContext>,
@@ -41,22 +49,51 @@ async fn search(
     let start = Instant::now();
 
     let search_type = params.search_type.as_deref().unwrap_or("semantic");
-    let limit = params.limit.unwrap_or(20);
-
-    let results: Vec<SearchResult> = match search_type {
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+    let offset = params.offset.unwrap_or(0);
+    if offset.saturating_add(limit) > MAX_SEARCH_WINDOW {
+        return Err(ApiError::BadRequest(format!(
+            "offset + limit must not exceed {}",
+            MAX_SEARCH_WINDOW
+        )));
+    }
+    let sort = SortSpec::parse(
+        params.sort.as_deref(),
+        SEARCH_SORT_FIELDS,
+        SortSpec::new("score", SortDirection::Desc),
+    )
+    .map_err(ApiError::BadRequest)?;
+
+    // Ranked retrieval cannot skip ahead, so fetch everything up to the end of
+    // the requested page plus one extra result to detect further pages.
+    let fetch_limit = offset.saturating_add(limit).saturating_add(1);
+
+    let filter = params
+        .filter
+        .as_deref()
+        .map(serde_json::from_str::<FilterExpr>)
+        .transpose()
+        .map_err(|e| ApiError::BadRequest(format!("Invalid filter: {}", e)))?;
+
+    let mut results: Vec<SearchResult> = match search_type {
         "semantic" => {
             // Use SearchService for semantic search
             let service_request = crate::services::search::SearchCodeRequest {
                 query: params.query.clone(),
-                limit,
+                limit: fetch_limit,
                 min_similarity: 0.5,
                 language: None,
+                workspace_id: params.workspace_id.clone(),
+                filter,
             };
 
             let service_results = ctx.search_service
                 .search_code(service_request)
                 .await
-                .map_err(|e| ApiError::Internal(e.to_string()))?;
+                .map_err(|e| match e.downcast_ref::<SemanticError>() {
+                    Some(err @ SemanticError::InvalidFilter(_)) => ApiError::BadRequest(err.to_string()),
+                    _ => ApiError::Internal(e.to_string()),
+                })?;
 
             // Convert service results to API results
             service_results.into_iter().map(|r| SearchResult {
@@ -66,6 +103,7 @@ async fn search(
                 score: r.score as f64,
                 result_type: r.result_type,
                 metadata: serde_json::to_value(r.metadata).unwrap_or_default(),
+                answer_snippet: r.answer_snippet,
             }).collect()
         },
         "pattern" | "content" => {
@@ -74,7 +112,9 @@ async fn search(
             let service_request = crate::services::search::TextSearchRequest {
                 query: params.query.clone(),
                 search_type: search_type_str.to_string(),
-                limit,
+                limit: fetch_limit,
+                language: None,
+                workspace_id: params.workspace_id.clone(),
             };
 
             let service_results = ctx.search_service
@@ -93,21 +133,99 @@ async fn search(
                     "file_path": r.file_path,
                     "language": r.language,
                 }),
+                answer_snippet: None,
+            }).collect()
+        },
+        "hybrid" => {
+            let service_request = crate::services::search::HybridSearchRequest {
+                query: params.query.clone(),
+                limit: fetch_limit,
+                min_similarity: 0.5,
+                language: None,
+                workspace_id: params.workspace_id.clone(),
+            };
+
+            let service_results = ctx.search_service
+                .search_hybrid(service_request)
+                .await
+                .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+            service_results.into_iter().map(|r| SearchResult {
+                id: r.id,
+                title: r.title,
+                content: r.content,
+                score: r.score as f64,
+                result_type: r.result_type,
+                metadata: serde_json::to_value(r.metadata).unwrap_or_default(),
+                answer_snippet: r.answer_snippet,
             }).collect()
         },
         _ => return Err(ApiError::BadRequest(format!("Invalid search type: {}", search_type))),
     };
 
+    match sort.field.as_str() {
+        "title" => results.sort_by(|a, b| a.title.cmp(&b.title)),
+        _ => results.sort_by(|a, b| {
+            a.score.partial_cmp(&b.score).unwrap_or(std::cmp::Ordering::Equal)
+        }),
+    }
+    if sort.direction == SortDirection::Desc {
+        results.reverse();
+    }
+
+    let has_more = results.len() > offset + limit;
+    let results: Vec<SearchResult> = results.into_iter().skip(offset).take(limit).collect();
+
     tracing::debug!(
         query = %params.query,
         search_type = search_type,
         result_count = results.len(),
+        offset = offset,
+        has_more = has_more,
         "Performed search"
     );
 
     let duration = start.elapsed().as_millis() as u64;
 
-    Ok(Json(ApiResponse::success(results, request_id, duration)))
+    // The total number of matches is unknown for ranked retrieval, so report
+    // only what has been seen so far.
+    let pagination = PaginationInfo {
+        cursor: None,
+        has_more,
+        total: None,
+        count: results.len(),
+        limit,
+        offset: Some(offset),
+        sort: Some(sort.to_string()),
+    };
+
+    let page_link = |offset: usize| search_page_link(&params.query, search_type, limit, offset, &sort);
+    let links = HateoasLinks {
+        self_link: page_link(offset),
+        next: has_more.then(|| page_link(offset + limit)),
+        prev: (offset > 0).then(|| page_link(offset.saturating_sub(limit))),
+        related: None,
+    };
+
+    Ok(Json(ApiResponse::success_with_pagination(
+        results,
+        request_id,
+        duration,
+        pagination,
+        links,
+    )))
+}
+
+/// Build a search page link with a properly encoded query string
+fn search_page_link(query: &str, search_type: &str, limit: usize, offset: usize, sort: &SortSpec) -> String {
+    let mut url = reqwest::Url::parse("http://localhost/api/v1/search").expect("static URL is valid");
+    url.query_pairs_mut()
+        .append_pair("query", query)
+        .append_pair("search_type", search_type)
+        .append_pair("limit", &limit.to_string())
+        .append_pair("offset", &offset.to_string())
+        .append_pair("sort", &sort.to_string());
+    format!("{}?{}", url.path(), url.query().unwrap_or_default())
 }
 
 /// GET /api/v1/search/references/{unit_id} - Find references to a code unit
@@ -211,6 +329,8 @@ async fn search_pattern(
         query: payload.pattern.clone(),
         search_type: "patterns".to_string(),
         limit,
+        language: None,
+        workspace_id: None,
     };
 
     let service_results = ctx.search_service
@@ -662,13 +662,13 @@ async fn write_session_file(
     if let Some(parent) = path.parent() {
         ctx.vfs.create_directory(&workspace_id, &parent, true)
             .await
-            .map_err(|e| ApiError::Internal(e.to_string()))?;
+            .map_err(ApiError::from)?;
     }
 
     // Write file content
     ctx.vfs.write_file(&workspace_id, &path, payload.content.as_bytes())
         .await
-        .map_err(|e| ApiError::Internal(e.to_string()))?;
+        .map_err(ApiError::from)?;
 
     // Get updated metadata
     let vnode = ctx.vfs.metadata(&workspace_id, &path)
@@ -849,6 +849,9 @@ async fn merge_session(
         )));
     }
 
+    // A frozen workspace takes no merges
+    ctx.vfs.ensure_writable(&workspace_id).await.map_err(ApiError::from)?;
+
     // Build a map of file paths to latest modifications
     let mut file_modifications: HashMap<String, &crate::services::sessions::FileModification> = HashMap::new();
     for modification in &modifications {
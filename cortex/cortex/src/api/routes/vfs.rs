@@ -6,9 +6,10 @@ use crate::api::{
         ApiResponse, CreateFileRequest, DirectoryTreeResponse, FileListRequest, FileResponse,
         TreeNode, UpdateFileRequest,
     },
-    pagination::{LinkBuilder, build_pagination_info, decode_cursor, generate_next_cursor},
+    pagination::{LinkBuilder, build_offset_pagination_info, resolve_offset},
 };
-use crate::services::VfsService;
+use crate::services::{SortDirection, SortSpec, VfsService};
+use cortex_vfs::{ListOptions, ListSortField, NodeType};
 use axum::{
     extract::{Path, Query, State},
     routing::{delete, get, post, put},
@@ -35,6 +36,9 @@ pub fn vfs_routes(context: VfsContext) -> Router {
         .with_state(context)
 }
 
+/// Fields `GET /api/v1/workspaces/{workspace_id}/files` can be sorted by
+const FILE_SORT_FIELDS: &[&str] = &["path", "size_bytes", "created_at", "updated_at"];
+
 /// GET /api/v1/workspaces/{workspace_id}/files - Browse VFS
 async fn list_files(
     State(ctx): State<VfsContext>,
@@ -55,51 +59,50 @@ async fn list_files(
     let workspace_uuid = uuid::Uuid::parse_str(&workspace_id)
         .map_err(|_| ApiError::BadRequest("Invalid workspace ID".to_string()))?;
 
-    // Decode cursor if present
-    let cursor_data = if let Some(ref cursor) = params.cursor {
-        Some(decode_cursor(cursor).map_err(|e| ApiError::BadRequest(e))?)
-    } else {
-        None
+    let offset = resolve_offset(params.offset, params.cursor.as_deref())
+        .map_err(ApiError::BadRequest)?;
+
+    let sort = SortSpec::parse(
+        params.sort.as_deref(),
+        FILE_SORT_FIELDS,
+        SortSpec::new("created_at", SortDirection::Desc),
+    )
+    .map_err(ApiError::BadRequest)?;
+
+    let node_type = params
+        .file_type
+        .as_deref()
+        .map(|t| {
+            serde_json::from_value::<NodeType>(serde_json::Value::String(t.to_string()))
+                .map_err(|_| ApiError::BadRequest(format!("Invalid file type: {}", t)))
+        })
+        .transpose()?;
+
+    let options = ListOptions {
+        recursive: params.recursive,
+        node_type,
+        language: params.language.clone(),
+        sort_field: match sort.field.as_str() {
+            "path" => ListSortField::Path,
+            "size_bytes" => ListSortField::SizeBytes,
+            "updated_at" => ListSortField::UpdatedAt,
+            _ => ListSortField::CreatedAt,
+        },
+        descending: sort.direction == SortDirection::Desc,
+        limit: params.limit,
+        offset,
     };
 
-    // Use VFS service to list files
-    let file_details = ctx.vfs_service
-        .list_directory(&workspace_uuid, "/", params.recursive)
+    // Use VFS service to list one page of files
+    let page = ctx.vfs_service
+        .list_directory_page(&workspace_uuid, "/", &options)
         .await
         .map_err(|e| ApiError::Internal(e.to_string()))?;
+    let total = page.total;
 
-    // Convert to FileResponse and apply filters
-    let mut files: Vec<FileResponse> = file_details
+    let files: Vec<FileResponse> = page
+        .items
         .into_iter()
-        .filter(|file| {
-            // Filter by type if specified
-            if let Some(ref file_type) = params.file_type {
-                if file.node_type != *file_type {
-                    return false;
-                }
-            }
-
-            // Filter by language if specified
-            if let Some(ref language) = params.language {
-                if let Some(ref lang) = file.language {
-                    if !lang.to_lowercase().contains(&language.to_lowercase()) {
-                        return false;
-                    }
-                } else {
-                    return false;
-                }
-            }
-
-            // Filter by cursor if present
-            if let Some(ref cursor) = cursor_data {
-                if file.created_at > cursor.last_timestamp ||
-                   (file.created_at == cursor.last_timestamp && file.id >= cursor.last_id) {
-                    return false;
-                }
-            }
-
-            true
-        })
         .map(|file| FileResponse {
             id: file.id,
             name: file.name,
@@ -122,60 +125,29 @@ async fn list_files(
         })
         .collect();
 
-    // Sort by created_at DESC, id DESC for consistent pagination
-    files.sort_by(|a, b| {
-        b.created_at.cmp(&a.created_at)
-            .then_with(|| b.id.cmp(&a.id))
-    });
-
-    // Apply cursor-based pagination
-    let total = files.len();
-    let mut result = files.into_iter().take(params.limit + 1).collect::<Vec<_>>();
-    let has_more = result.len() > params.limit;
-    if has_more {
-        result.pop();
-    }
-    let paginated_files = result;
-
-    // Generate next cursor
-    let next_cursor = if has_more && !paginated_files.is_empty() {
-        let last = paginated_files.last().unwrap();
-        generate_next_cursor(
-            last.id.clone(),
-            last.created_at,
-            cursor_data.map(|c| c.offset + params.limit).unwrap_or(params.limit),
-        )
-    } else {
-        None
-    };
-
     tracing::debug!(
         workspace_id = %workspace_id,
         total_files = total,
-        returned = paginated_files.len(),
-        has_more = has_more,
+        returned = files.len(),
+        offset = offset,
         "Listed VFS files"
     );
 
     let duration = start.elapsed().as_millis() as u64;
 
-    // Build response with cursor-based pagination
-    let pagination = build_pagination_info(
-        paginated_files.len(),
+    let pagination = build_offset_pagination_info(
+        files.len(),
         params.limit,
-        Some(total),
-        next_cursor.clone(),
+        offset,
+        total,
+        Some(&sort),
     );
 
     let link_builder = LinkBuilder::new(format!("/api/v1/workspaces/{}/files", workspace_id));
-    let links = link_builder.build_list_links(
-        params.cursor.as_deref(),
-        next_cursor.as_deref(),
-        params.limit,
-    );
+    let links = link_builder.build_offset_links(params.limit, offset, total, Some(&sort));
 
     Ok(Json(ApiResponse::success_with_pagination(
-        paginated_files,
+        files,
         request_id,
         duration,
         pagination,
@@ -267,7 +239,7 @@ async fn create_file(
     let file = ctx.vfs_service
         .write_file(&workspace_uuid, &payload.path, payload.content.as_bytes())
         .await
-        .map_err(|e| ApiError::Internal(e.to_string()))?;
+        .map_err(ApiError::from)?;
 
     // Convert to API response format
     let file_response = FileResponse {
@@ -320,7 +292,7 @@ async fn update_file(
     let file = ctx.vfs_service
         .update_file_by_id(&file_uuid, payload.content.as_bytes())
         .await
-        .map_err(|e| ApiError::Internal(e.to_string()))?;
+        .map_err(ApiError::from)?;
 
     // Convert to API response format
     let file_response = FileResponse {
@@ -372,7 +344,7 @@ async fn delete_file(
     ctx.vfs_service
         .delete_by_id(&file_uuid, false)
         .await
-        .map_err(|e| ApiError::Internal(e.to_string()))?;
+        .map_err(ApiError::from)?;
 
     tracing::info!(
         file_id = %file_id,
@@ -5,11 +5,17 @@ use crate::api::{
     types::{
         ApiResponse, CreateWorkspaceRequest, WorkspaceResponse,
         UpdateWorkspaceRequest, SyncWorkspaceRequest, SyncResponse, SyncChange,
-        PaginationParams,
+        PaginationParams, CreateSnapshotRequest, SnapshotResponse, RollbackRequest,
+        RollbackResponse,
     },
-    pagination::{LinkBuilder, build_pagination_info, generate_next_cursor},
+    pagination::{LinkBuilder, build_offset_pagination_info},
 };
-use crate::services::{WorkspaceService, workspace::ListWorkspaceFilters};
+use crate::services::{
+    SortDirection, SortSpec, WorkspaceService,
+    workspace::{ActiveSessionsError, ListWorkspaceFilters},
+};
+use cortex_core::error::CortexError;
+use cortex_vfs::{ChangeType, SnapshotInfo};
 use axum::{
     extract::{Path, Query, State},
     routing::{delete, get, post, put},
@@ -33,9 +39,15 @@ pub fn workspace_routes(context: WorkspaceContext) -> Router {
         .route("/api/v1/workspaces/{workspace_id}", put(update_workspace))
         .route("/api/v1/workspaces/{workspace_id}", delete(delete_workspace))
         .route("/api/v1/workspaces/{workspace_id}/sync", post(sync_workspace))
+        .route("/api/v1/workspaces/{workspace_id}/snapshots", get(list_snapshots))
+        .route("/api/v1/workspaces/{workspace_id}/snapshots", post(create_snapshot))
+        .route("/api/v1/workspaces/{workspace_id}/snapshots/{name}/rollback", post(rollback_to_snapshot))
         .with_state(context)
 }
 
+/// Fields `GET /api/v1/workspaces` can be sorted by
+const WORKSPACE_SORT_FIELDS: &[&str] = &["name", "created_at", "updated_at"];
+
 /// GET /api/v1/workspaces - List all workspaces
 async fn list_workspaces(
     State(ctx): State<WorkspaceContext>,
@@ -46,10 +58,19 @@ async fn list_workspaces(
 
     // Validate pagination params
     params.validate().map_err(|e| ApiError::BadRequest(e))?;
+    let offset = params.effective_offset().map_err(ApiError::BadRequest)?;
+    let sort = SortSpec::parse(
+        params.sort.as_deref(),
+        WORKSPACE_SORT_FIELDS,
+        SortSpec::new("created_at", SortDirection::Desc),
+    )
+    .map_err(ApiError::BadRequest)?;
 
     // Use workspace service to list workspaces
     let filters = ListWorkspaceFilters {
-        limit: Some(params.limit + 1), // Fetch one extra to check if there are more
+        limit: Some(params.limit),
+        offset: Some(offset),
+        sort: Some(sort.clone()),
     };
 
     let workspaces = ctx.workspace_service
@@ -57,24 +78,10 @@ async fn list_workspaces(
         .await
         .map_err(|e| ApiError::Internal(e.to_string()))?;
 
-    // Check if there are more results
-    let mut workspaces = workspaces;
-    let has_more = workspaces.len() > params.limit;
-    if has_more {
-        workspaces.pop(); // Remove the extra item
-    }
-
-    // Generate next cursor if there are more results
-    let next_cursor = if has_more && !workspaces.is_empty() {
-        let last = workspaces.last().unwrap();
-        generate_next_cursor(
-            last.id.clone(),
-            last.created_at,
-            params.limit,
-        )
-    } else {
-        None
-    };
+    let total = ctx.workspace_service
+        .count_workspaces()
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
 
     // Convert to API response format
     let workspace_responses: Vec<WorkspaceResponse> = workspaces
@@ -95,24 +102,21 @@ async fn list_workspaces(
         })
         .collect();
 
-    tracing::debug!(count = workspace_responses.len(), has_more = has_more, "Listed workspaces");
+    tracing::debug!(count = workspace_responses.len(), total = total, offset = offset, "Listed workspaces");
 
     let duration = start.elapsed().as_millis() as u64;
 
     // Build pagination info and HATEOAS links
-    let pagination = build_pagination_info(
+    let pagination = build_offset_pagination_info(
         workspace_responses.len(),
         params.limit,
-        None, // Total count would require additional query
-        next_cursor.clone(),
+        offset,
+        total,
+        Some(&sort),
     );
 
     let link_builder = LinkBuilder::new("/api/v1/workspaces");
-    let links = link_builder.build_list_links(
-        params.cursor.as_deref(),
-        next_cursor.as_deref(),
-        params.limit,
-    );
+    let links = link_builder.build_offset_links(params.limit, offset, total, Some(&sort));
 
     Ok(Json(ApiResponse::success_with_pagination(
         workspace_responses,
@@ -353,6 +357,10 @@ async fn sync_workspace(
     // Get VFS handle from workspace service
     let vfs = &ctx.workspace_service.vfs;
 
+    if !dry_run {
+        vfs.ensure_writable(&workspace_uuid).await.map_err(ApiError::from)?;
+    }
+
     // Get all existing files in VFS for this workspace
     let root_path = cortex_vfs::VirtualPath::root();
     let existing_vnodes = vfs.list_directory(&workspace_uuid, &root_path, true)
@@ -493,3 +501,131 @@ async fn sync_workspace(
 
     Ok(Json(ApiResponse::success(response, request_id, duration)))
 }
+
+/// GET /api/v1/workspaces/{workspace_id}/snapshots - List workspace snapshots, newest first
+async fn list_snapshots(
+    State(ctx): State<WorkspaceContext>,
+    Path(workspace_id): Path<String>,
+) -> ApiResult<Json<ApiResponse<Vec<SnapshotResponse>>>> {
+    let request_id = uuid::Uuid::new_v4().to_string();
+    let start = Instant::now();
+
+    let workspace_uuid = uuid::Uuid::parse_str(&workspace_id)
+        .map_err(|_| ApiError::BadRequest("Invalid workspace ID".to_string()))?;
+
+    let snapshots = ctx.workspace_service
+        .list_snapshots(&workspace_uuid)
+        .await
+        .map_err(snapshot_error)?
+        .into_iter()
+        .map(snapshot_response)
+        .collect();
+
+    let duration = start.elapsed().as_millis() as u64;
+
+    Ok(Json(ApiResponse::success(snapshots, request_id, duration)))
+}
+
+/// POST /api/v1/workspaces/{workspace_id}/snapshots - Snapshot a workspace
+async fn create_snapshot(
+    State(ctx): State<WorkspaceContext>,
+    Path(workspace_id): Path<String>,
+    Json(payload): Json<CreateSnapshotRequest>,
+) -> ApiResult<Json<ApiResponse<SnapshotResponse>>> {
+    let request_id = uuid::Uuid::new_v4().to_string();
+    let start = Instant::now();
+
+    let workspace_uuid = uuid::Uuid::parse_str(&workspace_id)
+        .map_err(|_| ApiError::BadRequest("Invalid workspace ID".to_string()))?;
+
+    let snapshot = ctx.workspace_service
+        .create_snapshot(&workspace_uuid, payload.name, payload.description, None)
+        .await
+        .map_err(snapshot_error)?;
+
+    tracing::info!(
+        workspace_id = %workspace_id,
+        snapshot = %snapshot.name,
+        nodes = snapshot.node_count,
+        "Created workspace snapshot"
+    );
+
+    let duration = start.elapsed().as_millis() as u64;
+
+    Ok(Json(ApiResponse::success(snapshot_response(snapshot), request_id, duration)))
+}
+
+/// POST /api/v1/workspaces/{workspace_id}/snapshots/{name}/rollback - Restore a workspace to a snapshot
+async fn rollback_to_snapshot(
+    State(ctx): State<WorkspaceContext>,
+    Path((workspace_id, name)): Path<(String, String)>,
+    Json(payload): Json<RollbackRequest>,
+) -> ApiResult<Json<ApiResponse<RollbackResponse>>> {
+    let request_id = uuid::Uuid::new_v4().to_string();
+    let start = Instant::now();
+
+    let workspace_uuid = uuid::Uuid::parse_str(&workspace_id)
+        .map_err(|_| ApiError::BadRequest("Invalid workspace ID".to_string()))?;
+
+    let request = crate::services::workspace::RollbackRequest {
+        session_id: payload.session_id,
+        force: payload.force.unwrap_or(false),
+        actor: None,
+    };
+
+    let report = ctx.workspace_service
+        .rollback_to_snapshot(&workspace_uuid, &name, request)
+        .await
+        .map_err(snapshot_error)?;
+
+    tracing::warn!(
+        workspace_id = %workspace_id,
+        snapshot = %name,
+        change_set = %report.change_set,
+        changes = report.changes.len(),
+        "Rolled back workspace to snapshot"
+    );
+
+    let response = RollbackResponse {
+        change_set_id: report.change_set.to_string(),
+        files_created: report.count(ChangeType::Created),
+        files_modified: report.count(ChangeType::Modified),
+        files_deleted: report.count(ChangeType::Deleted),
+        changes: report.changes.iter().map(|change| SyncChange {
+            path: change.path.to_string(),
+            change_type: format!("{:?}", change.change_type).to_lowercase(),
+            size_bytes: None,
+        }).collect(),
+        snapshot: snapshot_response(report.snapshot),
+    };
+
+    let duration = start.elapsed().as_millis() as u64;
+
+    Ok(Json(ApiResponse::success(response, request_id, duration)))
+}
+
+fn snapshot_response(snapshot: SnapshotInfo) -> SnapshotResponse {
+    SnapshotResponse {
+        id: snapshot.id.to_string(),
+        workspace_id: snapshot.workspace_id.to_string(),
+        name: snapshot.name,
+        description: snapshot.description,
+        created_by: snapshot.created_by,
+        node_count: snapshot.node_count,
+        total_bytes: snapshot.total_bytes,
+        created_at: snapshot.created_at,
+    }
+}
+
+/// Map snapshot service errors to API errors
+fn snapshot_error(e: anyhow::Error) -> ApiError {
+    if e.downcast_ref::<ActiveSessionsError>().is_some() {
+        return ApiError::Conflict(e.to_string());
+    }
+    match e.downcast_ref::<CortexError>() {
+        Some(CortexError::NotFound { .. }) => ApiError::NotFound(e.to_string()),
+        Some(CortexError::InvalidInput(_)) => ApiError::BadRequest(e.to_string()),
+        Some(CortexError::WorkspaceReadOnly(_)) => ApiError::Conflict(e.to_string()),
+        _ => ApiError::Internal(e.to_string()),
+    }
+}
@@ -42,6 +42,19 @@ pub struct ServerConfig {
     pub host: String,
     pub port: u16,
     pub workers: Option<usize>,
+    /// Require authentication on every route except `/health` and login.
+    /// When false (single-operator mode) workspace, document, task and
+    /// dashboard routes are public.
+    pub require_auth: bool,
+    /// Limits of workspace event streams
+    pub events: super::routes::EventStreamConfig,
+}
+
+impl ServerConfig {
+    /// Whether the server binds to a loopback address only
+    pub fn is_loopback(&self) -> bool {
+        matches!(self.host.as_str(), "localhost" | "127.0.0.1" | "::1")
+    }
 }
 
 impl Default for ServerConfig {
@@ -50,6 +63,8 @@ impl Default for ServerConfig {
             host: "127.0.0.1".to_string(),
             port: 8080,
             workers: None,
+            require_auth: false,
+            events: Default::default(),
         }
     }
 }
@@ -83,6 +98,9 @@ impl RestApiServer {
         let storage = Self::create_storage(&global_config).await?;
         info!("Database connection established");
 
+        // Refuse to start on a schema this build doesn't understand
+        crate::version_check::ensure_compatible(&storage).await?;
+
         // Create VFS
         let vfs = Arc::new(VirtualFileSystem::new(storage.clone()));
 
@@ -126,6 +144,8 @@ impl RestApiServer {
 
         info!("Starting REST API server on {}", addr);
 
+        let require_auth = self.config.require_auth;
+
         // Build the application router
         let app = self.build_app();
 
@@ -165,6 +185,10 @@ impl RestApiServer {
         info!("  PUT    /api/v1/workspaces/:id    - Update workspace");
         info!("  DELETE /api/v1/workspaces/:id    - Delete workspace");
         info!("  POST   /api/v1/workspaces/:id/sync");
+        info!("  GET    /api/v1/workspaces/:id/snapshots");
+        info!("  POST   /api/v1/workspaces/:id/snapshots");
+        info!("  POST   /api/v1/workspaces/:id/snapshots/:name/rollback");
+        info!("  GET    /api/v1/workspaces/:id/events   - Live change events (WebSocket)");
         info!("  GET    /api/v1/workspaces/:id/files");
         info!("  POST   /api/v1/workspaces/:id/files");
         info!("  GET    /api/v1/workspaces/:id/tree");
@@ -222,8 +246,12 @@ impl RestApiServer {
         info!("  GET  /api/v1/locks");
         info!("  POST /api/v1/sessions/:id/merge");
         info!("");
-        info!("Authentication: Bearer <token> or ApiKey <key>");
+        info!("Authentication: Bearer <token|api-key> or ApiKey <key>");
         info!("Supported roles: admin, developer, viewer, ci_cd");
+        info!("API key scopes: read (GET), write (mutations), admin (keys, export)");
+        if require_auth {
+            info!("Auth required on all routes except /api/v1/health and login");
+        }
         info!("");
         info!("Press Ctrl+C to stop");
 
@@ -237,10 +265,27 @@ impl RestApiServer {
 
     /// Build the application router with all routes and middleware
     fn build_app(self) -> Router {
+        let search_service = Arc::new(SearchService::new(self.storage.clone()));
+
+        // Warm semantic search up in the background; the health endpoint
+        // reports when it is ready
+        tokio::spawn({
+            let search_service = search_service.clone();
+            async move {
+                let report = search_service.warm_up().await;
+                info!(
+                    "Semantic search warm-up finished in {:.1}ms (ready: {})",
+                    report.total_ms,
+                    report.is_ready()
+                );
+            }
+        });
+
         // Create shared state for health endpoints
         let app_state = Arc::new(AppState {
             start_time: self.start_time,
             storage: self.storage.clone(),
+            search_service: search_service.clone(),
         });
 
         // Create authentication state for middleware
@@ -254,8 +299,6 @@ impl RestApiServer {
 
         let vfs_service = Arc::new(VfsService::new(self.vfs.clone()));
 
-        let search_service = Arc::new(SearchService::new(self.storage.clone()));
-
         let memory_service = Arc::new(MemoryService::new(
             self.storage.clone(),
             self.memory.clone(),
@@ -316,6 +359,11 @@ impl RestApiServer {
             storage: self.storage.clone(),
         };
 
+        let events_context = super::routes::EventsContext::new(
+            workspace_service.clone(),
+            self.config.events,
+        );
+
         // Create task context
         let task_context = TaskContext {
             storage: self.storage.clone(),
@@ -331,19 +379,32 @@ impl RestApiServer {
             document_service: document_service.clone(),
         };
 
-        // Build public routes (no authentication required)
-        // Single-operator mode: workspaces, documents, and tasks are public
-        let public_routes = Router::new()
-            .merge(super::routes::health_routes(app_state))
-            .merge(super::routes::public_auth_routes(auth_context.clone()))
+        // Single-operator mode: workspaces, documents, and tasks are public.
+        // With require_auth they move behind authentication and scope checks.
+        let operator_routes = Router::new()
             .merge(super::routes::workspace_routes(workspace_context))
+            .merge(super::routes::event_routes(events_context))
             .merge(super::routes::document_routes(document_context))
             .merge(super::routes::task_routes(task_context))
             .merge(super::routes::dashboard_routes(dashboard_context));
 
-        // Build protected routes (authentication required)
+        let (operator_public, operator_protected) = if self.config.require_auth {
+            (Router::new(), operator_routes)
+        } else {
+            (operator_routes, Router::new())
+        };
+
+        // Build public routes (no authentication required)
+        let public_routes = Router::new()
+            .merge(super::routes::health_routes(app_state))
+            .merge(super::routes::public_auth_routes(auth_context.clone()))
+            .merge(operator_public);
+
+        // Build protected routes (authentication and scope required).
+        // Layers run outside-in, so validation wraps the scope check.
         let auth_state_clone = auth_state.clone();
         let protected_routes = Router::new()
+            .merge(operator_protected)
             .merge(super::routes::protected_auth_routes(auth_context))
             .merge(super::routes::vfs_routes(vfs_context))
             .merge(super::routes::session_routes(session_context))
@@ -353,6 +414,7 @@ impl RestApiServer {
             .merge(super::routes::dependency_routes(dependency_context))
             .merge(super::routes::build_routes(build_context))
             .merge(super::routes::export_routes(export_context))
+            .route_layer(middleware::from_fn(super::middleware::AuthMiddleware::require_scope))
             .route_layer(middleware::from_fn(move |req, next| {
                 let auth_state = auth_state_clone.clone();
                 async move {
@@ -72,6 +72,7 @@ mod types_tests {
         assert_eq!(request.file_type, Some("file".to_string()));
         assert_eq!(request.language, Some("rust".to_string()));
         assert_eq!(request.limit, 10);
+        assert_eq!(request.offset, Some(5));
         // cursor field was removed in pagination cleanup
         assert!(request.cursor.is_none());
     }
@@ -236,12 +237,14 @@ mod types_tests {
             score: 0.95,
             result_type: "semantic".to_string(),
             metadata: serde_json::json!({"key": "value"}),
+            answer_snippet: None,
         };
 
         let json = serde_json::to_string(&result).unwrap();
         assert!(json.contains("result-1"));
         assert!(json.contains("Test Result"));
         assert!(json.contains("0.95"));
+        assert!(!json.contains("answer_snippet"));
     }
 
     #[test]
@@ -285,6 +288,11 @@ mod types_tests {
                 total_bytes: 1024 * 1024 * 1024,
                 used_bytes: 512 * 1024 * 1024,
             },
+            search: SearchHealth {
+                ready: false,
+                state: "warming_up".to_string(),
+                error: None,
+            },
         };
 
         let json = serde_json::to_string(&health).unwrap();
@@ -292,6 +300,7 @@ mod types_tests {
         assert!(json.contains("1.0.0"));
         assert!(json.contains("3600"));
         assert!(json.contains("\"connected\":true"));
+        assert!(json.contains("\"state\":\"warming_up\""));
     }
 
     #[test]
@@ -302,6 +311,8 @@ mod types_tests {
             total_size_bytes: 1024 * 1024,
             episodes: 50,
             semantic_nodes: 200,
+            slow_queries: 3,
+            query_timeouts: 1,
         };
 
         let json = serde_json::to_string(&metrics).unwrap();
@@ -309,6 +320,8 @@ mod types_tests {
         assert!(json.contains("\"files\":100"));
         assert!(json.contains("\"episodes\":50"));
         assert!(json.contains("\"semantic_nodes\":200"));
+        assert!(json.contains("\"slow_queries\":3"));
+        assert!(json.contains("\"query_timeouts\":1"));
     }
 
     #[test]
@@ -442,6 +455,7 @@ mod error_tests {
             timestamp: Utc::now(),
             version: "v1".to_string(),
             duration_ms: 0,
+            key_id: None,
         };
 
         let error_response = ErrorResponse {
@@ -505,12 +519,23 @@ mod metadata_tests {
             timestamp: now,
             version: "v1".to_string(),
             duration_ms: 150,
+            key_id: Some("key-789".to_string()),
         };
 
         let json = serde_json::to_string(&metadata).unwrap();
         assert!(json.contains("req-456"));
         assert!(json.contains("v1"));
         assert!(json.contains("150"));
+        assert!(json.contains("\"key_id\":\"key-789\""));
+    }
+
+    #[test]
+    fn test_api_metadata_omits_missing_key_id() {
+        let response = ApiResponse::success((), "req-000".to_string(), 1);
+        assert!(response.metadata.key_id.is_none());
+
+        let json = serde_json::to_string(&response.metadata).unwrap();
+        assert!(!json.contains("key_id"));
     }
 
     #[test]
@@ -1,5 +1,6 @@
 //! API request and response types
 
+use crate::api::middleware::auth::current_api_key_id;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -28,6 +29,7 @@ impl<T> ApiResponse<T> {
                 timestamp: Utc::now(),
                 version: "v1".to_string(),
                 duration_ms,
+                key_id: current_api_key_id(),
             },
             pagination: None,
             links: None,
@@ -50,6 +52,7 @@ impl<T> ApiResponse<T> {
                 timestamp: Utc::now(),
                 version: "v1".to_string(),
                 duration_ms,
+                key_id: current_api_key_id(),
             },
             pagination: Some(pagination),
             links: Some(links),
@@ -66,6 +69,7 @@ impl<T> ApiResponse<T> {
                 timestamp: Utc::now(),
                 version: "v1".to_string(),
                 duration_ms: 0,
+                key_id: current_api_key_id(),
             },
             pagination: None,
             links: None,
@@ -80,6 +84,9 @@ pub struct ApiMetadata {
     pub timestamp: DateTime<Utc>,
     pub version: String,
     pub duration_ms: u64,
+    /// Id of the API key that authenticated the request (for audit logging)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_id: Option<String>,
 }
 
 /// Pagination information for cursor-based pagination
@@ -97,6 +104,12 @@ pub struct PaginationInfo {
     pub count: usize,
     /// Page size limit
     pub limit: usize,
+    /// Offset of the first item in this page (offset-based listings)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<usize>,
+    /// Applied sort order as `field:direction`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort: Option<String>,
 }
 
 /// HATEOAS links for resource navigation
@@ -123,6 +136,10 @@ pub struct PaginationParams {
     /// Maximum number of items to return (10-100, default 20)
     #[serde(default = "default_limit")]
     pub limit: usize,
+    /// Number of items to skip (takes precedence over the cursor offset)
+    pub offset: Option<usize>,
+    /// Sort order: `field`, `field:asc`, `field:desc` or `-field`
+    pub sort: Option<String>,
 }
 
 fn default_limit() -> usize {
@@ -139,6 +156,11 @@ impl PaginationParams {
         }
         Ok(())
     }
+
+    /// Resolve the effective offset from `offset` or the decoded cursor
+    pub fn effective_offset(&self) -> Result<usize, String> {
+        crate::api::pagination::resolve_offset(self.offset, self.cursor.as_deref())
+    }
 }
 
 /// Internal cursor data structure (serialized to base64)
@@ -166,6 +188,10 @@ pub struct FileListRequest {
     pub cursor: Option<String>,
     #[serde(default = "default_file_limit")]
     pub limit: usize,
+    // Offset-based pagination (takes precedence over the cursor offset)
+    pub offset: Option<usize>,
+    /// Sort order: `field`, `field:asc`, `field:desc` or `-field`
+    pub sort: Option<String>,
 }
 
 fn default_file_limit() -> usize {
@@ -331,6 +357,11 @@ pub struct SearchRequest {
     pub search_type: Option<String>,
     pub limit: Option<usize>,
     pub offset: Option<usize>,
+    /// Sort order: `field`, `field:asc`, `field:desc` or `-field`
+    pub sort: Option<String>,
+    /// Metadata filter expression of semantic searches, as JSON, e.g.
+    /// `{"op":"eq","field":"language","value":"rust"}`
+    pub filter: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -341,6 +372,10 @@ pub struct SearchResult {
     pub score: f64,
     pub result_type: String,
     pub metadata: serde_json::Value,
+    /// Span of this result that directly answers the query, for factoid
+    /// and definition queries
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub answer_snippet: Option<cortex_semantic::AnswerSnippet>,
 }
 
 // ============================================================================
@@ -372,6 +407,16 @@ pub struct HealthResponse {
     pub uptime_seconds: u64,
     pub database: DatabaseHealth,
     pub memory: MemoryHealth,
+    pub search: SearchHealth,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchHealth {
+    pub ready: bool,
+    /// cold, warming_up, ready or failed
+    pub state: String,
+    /// Why warming up failed
+    pub error: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -393,6 +438,12 @@ pub struct MetricsResponse {
     pub total_size_bytes: u64,
     pub episodes: usize,
     pub semantic_nodes: usize,
+    /// Queries slower than the pool's slow-query threshold since startup
+    #[serde(default)]
+    pub slow_queries: u64,
+    /// Queries aborted by the pool's per-query timeout since startup
+    #[serde(default)]
+    pub query_timeouts: u64,
 }
 
 // ============================================================================
@@ -595,6 +646,45 @@ pub struct SyncChange {
     pub size_bytes: Option<u64>,
 }
 
+// ============================================================================
+// Workspace Snapshot Types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateSnapshotRequest {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SnapshotResponse {
+    pub id: String,
+    pub workspace_id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_by: Option<String>,
+    pub node_count: usize,
+    pub total_bytes: usize,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RollbackRequest {
+    /// Work session of the caller, which does not block the rollback
+    pub session_id: Option<String>,
+    pub force: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RollbackResponse {
+    pub snapshot: SnapshotResponse,
+    pub change_set_id: String,
+    pub files_created: usize,
+    pub files_modified: usize,
+    pub files_deleted: usize,
+    pub changes: Vec<SyncChange>,
+}
+
 // ============================================================================
 // Search Reference Types
 // ============================================================================
@@ -3,14 +3,17 @@
 //! This module contains the complete implementation of all Cortex CLI commands.
 
 use crate::config::CortexConfig;
+use crate::ingest_watch;
+use crate::mcp::graph_algorithms::{ArchitectureRules, RuleSet};
 use crate::mcp::CortexMcpServer;
-use crate::output::{self, format_bytes, OutputFormat, TableBuilder};
+use crate::output::{self, format_bytes, format_count, OutputFormat, TableBuilder};
+use crate::services::{DependencyService, WorkspaceService};
 use anyhow::{Context, Result};
 use cortex_memory::CognitiveManager;
 use cortex_storage::{ConnectionManager, Credentials, DatabaseConfig, PoolConfig, SurrealDBManager};
 use cortex_storage::session::SessionManager;
 use cortex_vfs::{
-    ExternalProjectLoader, FlushOptions, FlushScope, MaterializationEngine, VirtualFileSystem,
+    ExternalProjectLoader, FileIngestionPipeline, FlushOptions, IngestJournal, IngestStage, FlushScope, MaterializationEngine, VirtualFileSystem,
     VirtualPath, VNode, Workspace, SyncSource, SyncSourceType,
     SyncSourceStatus,
 };
@@ -213,6 +216,7 @@ pub async fn workspace_create(
         parent_workspace: None,
         fork_metadata: None,
         dependencies: vec![],
+        version_retention: Default::default(),
         created_at: chrono::Utc::now(),
         updated_at: chrono::Utc::now(),
     };
@@ -483,15 +487,135 @@ pub async fn workspace_delete(workspace_id: String, confirm: bool) -> Result<()>
     Ok(())
 }
 
+/// Snapshot a workspace
+pub async fn workspace_snapshot(
+    name: String,
+    workspace: Option<String>,
+    description: Option<String>,
+) -> Result<()> {
+    let spinner = output::spinner("Creating snapshot...");
+
+    let config = CortexConfig::load()?;
+    let storage = create_storage(&config).await?;
+    let workspace_id = resolve_workspace_id(&storage, workspace).await?;
+    let vfs = Arc::new(VirtualFileSystem::new(storage.clone()));
+    let service = WorkspaceService::new(storage, vfs);
+
+    let snapshot = service
+        .create_snapshot(&workspace_id, name, description, None)
+        .await?;
+
+    spinner.finish_and_clear();
+    output::success(format!("Created snapshot: {}", snapshot.name));
+    output::kv("ID", snapshot.id);
+    output::kv("Nodes", snapshot.node_count);
+    output::kv("Total size", format_bytes(snapshot.total_bytes as u64));
+
+    Ok(())
+}
+
+/// List the snapshots of a workspace
+pub async fn workspace_snapshots(workspace: Option<String>, format: OutputFormat) -> Result<()> {
+    let config = CortexConfig::load()?;
+    let storage = create_storage(&config).await?;
+    let workspace_id = resolve_workspace_id(&storage, workspace).await?;
+    let vfs = Arc::new(VirtualFileSystem::new(storage.clone()));
+    let service = WorkspaceService::new(storage, vfs);
+
+    let snapshots = service.list_snapshots(&workspace_id).await?;
+
+    match format {
+        OutputFormat::Json => {
+            output::output(&snapshots, format)?;
+        }
+        _ => {
+            if snapshots.is_empty() {
+                output::info("No snapshots found. Create one with 'cortex workspace snapshot'");
+                return Ok(());
+            }
+
+            let mut table = TableBuilder::new()
+                .header(vec!["Name", "Created", "Nodes", "Size", "Description"]);
+            for snapshot in &snapshots {
+                table = table.row(vec![
+                    snapshot.name.clone(),
+                    format_relative_time(&snapshot.created_at),
+                    snapshot.node_count.to_string(),
+                    format_bytes(snapshot.total_bytes as u64),
+                    snapshot.description.clone().unwrap_or_default(),
+                ]);
+            }
+            table.print();
+        }
+    }
+
+    Ok(())
+}
+
+/// Roll a workspace back to a snapshot
+pub async fn workspace_rollback(
+    name: String,
+    workspace: Option<String>,
+    session_id: Option<String>,
+    force: bool,
+) -> Result<()> {
+    let spinner = output::spinner("Rolling back workspace...");
+
+    let config = CortexConfig::load()?;
+    let storage = create_storage(&config).await?;
+    let workspace_id = resolve_workspace_id(&storage, workspace).await?;
+    let vfs = Arc::new(VirtualFileSystem::new(storage.clone()));
+    let service = WorkspaceService::new(storage, vfs);
+
+    let request = crate::services::workspace::RollbackRequest {
+        session_id,
+        force,
+        actor: Some("cli".to_string()),
+    };
+    let report = service
+        .rollback_to_snapshot(&workspace_id, &name, request)
+        .await?;
+
+    spinner.finish_and_clear();
+    output::success(format!("Rolled back to snapshot: {}", report.snapshot.name));
+    output::kv("Change set", report.change_set);
+    output::kv("Created", report.count(cortex_vfs::ChangeType::Created));
+    output::kv("Modified", report.count(cortex_vfs::ChangeType::Modified));
+    output::kv("Deleted", report.count(cortex_vfs::ChangeType::Deleted));
+
+    Ok(())
+}
+
 // ============================================================================
 // Ingestion Commands
 // ============================================================================
 
+/// Options of `cortex ingest` that control its progress journal
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IngestJournalOptions {
+    /// Ignore and drop the journal, ingesting everything again
+    pub restart: bool,
+    /// Only print the journal's status
+    pub status: bool,
+}
+
 /// Ingest files or directories into Cortex
+///
+/// Progress is kept in the workspace's ingestion journal, so an interrupted
+/// ingestion resumes where it stopped: unchanged files that were fully
+/// ingested are skipped, and stored files are chunked without being stored
+/// again.
+///
+/// With `watch` set to a debounce duration, keeps running after the initial
+/// import and re-ingests files as they change until Ctrl-C.
 pub async fn ingest_path(
     path: PathBuf,
     workspace: Option<String>,
     recursive: bool,
+    ignore: Vec<String>,
+    watch: Option<std::time::Duration>,
+    journal_options: IngestJournalOptions,
+    format: OutputFormat,
 ) -> Result<()> {
     let config = CortexConfig::load()?;
 
@@ -501,15 +625,45 @@ pub async fn ingest_path(
     let (session_id, workspace_id, workspace_name) = create_temp_session(storage.clone(), workspace, &config).await
         .context("Failed to create session for ingestion")?;
 
-    output::header(format!("Ingesting: {}", path.display()));
-    output::kv("Workspace", &workspace_name);
-    output::kv("Session", &session_id.to_string());
-    output::kv("Recursive", recursive);
+    let journal = IngestJournal::new(storage.clone(), workspace_id, IngestStage::Chunked);
+    let human = format != OutputFormat::Json;
+
+    if journal_options.status {
+        let status = journal.status().await?;
+        if human {
+            output::header(format!("Ingestion journal: {}", workspace_name));
+            output::kv("Files", format_count(status.files));
+            output::kv("Fully ingested", format_count(status.complete));
+            for (stage, count) in &status.stages {
+                output::kv(format!("Last stage {}", stage), format_count(*count));
+            }
+            if let Some(updated) = status.last_updated {
+                output::kv("Last updated", output::format_timestamp(updated));
+            }
+        } else {
+            output::output(&status, format)?;
+        }
+        return Ok(());
+    }
+
+    if human {
+        output::header(format!("Ingesting: {}", path.display()));
+        output::kv("Workspace", &workspace_name);
+        output::kv("Session", &session_id.to_string());
+        output::kv("Recursive", recursive);
+    }
+
+    if journal_options.restart {
+        let dropped = journal.clear().await?;
+        if human && dropped > 0 {
+            output::info(format!("Restarting: ignoring {} journal entries", format_count(dropped)));
+        }
+    }
 
     let spinner = output::spinner("Loading project...");
 
     let vfs = VirtualFileSystem::new(storage.clone());
-    let loader = ExternalProjectLoader::new(vfs.clone());
+    let loader = ExternalProjectLoader::new(vfs.clone()).with_journal(journal.clone());
 
     // Import project into existing workspace
     let options = cortex_vfs::ImportOptions {
@@ -517,37 +671,93 @@ pub async fn ingest_path(
         create_fork: false,
         namespace: workspace_name.clone(),
         include_patterns: vec!["**/*".to_string()],
-        exclude_patterns: vec![
-            "**/node_modules/**".to_string(),
-            "**/target/**".to_string(),
-            "**/.git/**".to_string(),
-            "**/dist/**".to_string(),
-            "**/build/**".to_string(),
-        ],
+        exclude_patterns: ingest_watch::DEFAULT_EXCLUDES
+            .iter()
+            .map(|dir| format!("**/{}/**", dir))
+            .chain(ignore.iter().cloned())
+            .collect(),
         max_depth: None,
         process_code: true,
         generate_embeddings: false,
-        max_file_size_bytes: Some(10 * 1024 * 1024), // 10 MB default
+        max_file_size_bytes: Some(ingest_watch::MAX_FILE_SIZE_BYTES as usize),
     };
 
     let report = loader.import_into_workspace(&workspace_id, &path, options).await?;
 
     spinner.finish_and_clear();
 
-    output::success("Ingestion complete");
-    output::kv("Files imported", report.files_imported);
-    output::kv("Directories imported", report.directories_imported);
-    output::kv("Total size", format_bytes(report.bytes_imported as u64));
-    output::kv("Duration", format!("{:.2}s", report.duration_ms as f64 / 1000.0));
+    if human && report.files_skipped > 0 {
+        output::info(format!("Skipping {} already-ingested files", format_count(report.files_skipped)));
+    }
+    if human && report.files_resumed > 0 {
+        output::info(format!("Resuming {} partially ingested files", format_count(report.files_resumed)));
+    }
 
-    if !report.errors.is_empty() {
-        output::warning(format!("{} errors occurred:", report.errors.len()));
-        for error in report.errors.iter().take(5) {
-            eprintln!("  - {}", error);
+    let spinner = output::spinner("Extracting code units...");
+    let pipeline = FileIngestionPipeline::new(
+        Arc::new(tokio::sync::Mutex::new(cortex_code_analysis::CodeParser::new()?)),
+        Arc::new(vfs.clone()),
+        Arc::new(cortex_memory::SemanticMemorySystem::new(storage.clone())),
+    )
+    .with_journal(journal.clone());
+    let ingestion = pipeline.ingest_pending(&workspace_id).await?;
+    spinner.finish_and_clear();
+
+    if human {
+        output::success("Ingestion complete");
+        output::kv("Files imported", report.files_imported);
+        output::kv("Files chunked", ingestion.files_processed);
+        output::kv("Code units", ingestion.total_units);
+        output::kv("Directories imported", report.directories_imported);
+        output::kv("Total size", format_bytes(report.bytes_imported as u64));
+        output::kv("Duration", format!("{:.2}s", report.duration_ms as f64 / 1000.0));
+
+        if !report.errors.is_empty() {
+            output::warning(format!("{} errors occurred:", report.errors.len()));
+            for error in report.errors.iter().take(5) {
+                eprintln!("  - {}", error);
+            }
+            if report.errors.len() > 5 {
+                eprintln!("  ... and {} more", report.errors.len() - 5);
+            }
         }
-        if report.errors.len() > 5 {
-            eprintln!("  ... and {} more", report.errors.len() - 5);
+        if !ingestion.files_with_errors.is_empty() {
+            output::warning(format!(
+                "Failed to parse {} files",
+                ingestion.files_with_errors.len()
+            ));
         }
+    } else {
+        println!("{}", serde_json::json!({
+            "event": "imported",
+            "workspace": workspace_name,
+            "files_imported": report.files_imported,
+            "files_skipped": report.files_skipped,
+            "files_resumed": report.files_resumed,
+            "files_chunked": ingestion.files_processed,
+            "units_extracted": ingestion.total_units,
+            "files_with_errors": ingestion.files_with_errors,
+            "directories_imported": report.directories_imported,
+            "bytes_imported": report.bytes_imported,
+            "duration_ms": report.duration_ms,
+            "errors": report.errors,
+        }));
+    }
+
+    let Some(debounce) = watch else {
+        return Ok(());
+    };
+
+    let watcher = ingest_watch::IngestWatcher::new(storage, journal, &path, &ignore, format)?;
+    let summary = watcher.run(debounce).await?;
+
+    if human {
+        output::success(format!(
+            "Stopped watching: {} ingested, {} removed, {} failed",
+            summary.files_ingested, summary.files_removed, summary.failures
+        ));
+    } else {
+        println!("{}", serde_json::json!({ "event": "stopped", "summary": summary }));
     }
 
     Ok(())
@@ -1309,6 +1519,7 @@ pub async fn db_start(
             .unwrap_or(false),
         timeout: std::time::Duration::from_secs(10),
         request_timeout: std::time::Duration::from_secs(60),
+        snapshot_s3: None,
     };
 
     // Build database manager configuration - always use native mode
@@ -1565,6 +1776,7 @@ pub async fn db_status(detailed: bool) -> Result<()> {
             use_https: false,
             timeout: std::time::Duration::from_secs(5),
             request_timeout: std::time::Duration::from_secs(10),
+            snapshot_s3: None,
         }).await {
             Ok(client) => {
                 match client.list_collections().await {
@@ -1804,7 +2016,7 @@ pub async fn db_backup(output_path: Option<PathBuf>) -> Result<()> {
 
     // Create Qdrant snapshot first
     output::info("Creating Qdrant snapshot...");
-    let qdrant_snapshot_result = crate::qdrant_commands::qdrant_snapshot(None, None).await;
+    let qdrant_snapshot_result = crate::qdrant_commands::qdrant_snapshot(None, None, Default::default()).await;
 
     match qdrant_snapshot_result {
         Ok(_) => {
@@ -2447,7 +2659,19 @@ async fn install_qdrant_docker() -> Result<()> {
 // ============================================================================
 
 /// Create a storage connection manager from config
-async fn create_storage(config: &CortexConfig) -> Result<Arc<ConnectionManager>> {
+///
+/// Verifies component schema versions (migrating forward if needed) before
+/// handing out the connection.
+pub(crate) async fn create_storage(config: &CortexConfig) -> Result<Arc<ConnectionManager>> {
+    let storage = connect_storage(config).await?;
+    crate::version_check::ensure_compatible(&storage).await?;
+    Ok(storage)
+}
+
+/// Create a storage connection manager without checking schema versions
+///
+/// Used by diagnostics that must work against incompatible databases.
+pub(crate) async fn connect_storage(config: &CortexConfig) -> Result<Arc<ConnectionManager>> {
     use cortex_storage::connection_pool::ConnectionMode;
     use std::time::Duration;
 
@@ -2475,6 +2699,8 @@ async fn create_storage(config: &CortexConfig) -> Result<Arc<ConnectionManager>>
             validate_on_checkout: false,
             recycle_after_uses: Some(10000),
             shutdown_grace_period: Duration::from_secs(30),
+            query_timeout: Some(Duration::from_secs(30)),
+            slow_query_threshold: Some(Duration::from_secs(1)),
         },
         namespace: config.database.namespace.clone(),
         database: config.database.database.clone(),
@@ -2893,13 +3119,25 @@ pub async fn mcp_info(detailed: bool, category: Option<String>) -> Result<()> {
 
 /// Start the REST API server
 /// Run server in blocking mode (used internally by background process)
-pub async fn server_run_blocking(host: String, port: u16, workers: Option<usize>) -> Result<()> {
-    let config = crate::api::server::ServerConfig {
+pub async fn server_run_blocking(
+    host: String,
+    port: u16,
+    workers: Option<usize>,
+    require_auth: bool,
+) -> Result<()> {
+    let mut config = crate::api::server::ServerConfig {
         host,
         port,
         workers,
+        require_auth,
+        ..Default::default()
     };
 
+    // Never expose unauthenticated routes beyond localhost
+    if !config.is_loopback() {
+        config.require_auth = true;
+    }
+
     let server = crate::api::RestApiServer::with_config(config).await?;
     server.serve().await?;
 
@@ -2907,7 +3145,12 @@ pub async fn server_run_blocking(host: String, port: u16, workers: Option<usize>
 }
 
 /// Start server in background
-pub async fn server_start(host: String, port: u16, workers: Option<usize>) -> Result<()> {
+pub async fn server_start(
+    host: String,
+    port: u16,
+    workers: Option<usize>,
+    require_auth: bool,
+) -> Result<()> {
     use crate::server_manager::{ServerManager, ServerConfig};
 
     output::info("Starting Cortex REST API Server...");
@@ -2916,6 +3159,7 @@ pub async fn server_start(host: String, port: u16, workers: Option<usize>) -> Re
         host,
         port,
         workers,
+        require_auth,
         ..Default::default()
     };
 
@@ -2950,6 +3194,98 @@ pub async fn server_stop() -> Result<()> {
     Ok(())
 }
 
+/// Create an API key for the REST API
+pub async fn server_keys_create(
+    name: String,
+    scopes: Vec<String>,
+    expires_in_days: Option<i64>,
+    format: OutputFormat,
+) -> Result<()> {
+    use crate::services::auth::{AuthService, SERVER_KEY_OWNER};
+
+    let config = CortexConfig::load()?;
+    let storage = create_storage(&config).await?;
+    crate::api::db_schema::initialize_auth_schema(&storage).await?;
+
+    let auth_service = AuthService::new(storage);
+    let api_key = auth_service
+        .create_api_key(SERVER_KEY_OWNER, name, scopes, expires_in_days)
+        .await?;
+
+    match format {
+        OutputFormat::Json => output::output(&api_key, format)?,
+        _ => {
+            output::success(format!("Created API key '{}'", api_key.name));
+            output::kv("ID", &api_key.id);
+            output::kv("Key", &api_key.key);
+            output::kv("Scopes", api_key.scopes.join(", "));
+            if let Some(expires_at) = api_key.expires_at {
+                output::kv("Expires", output::format_timestamp(expires_at));
+            }
+            output::warning("Store this key now - it is hashed at rest and cannot be shown again");
+        }
+    }
+
+    Ok(())
+}
+
+/// List API keys for the REST API
+pub async fn server_keys_list(format: OutputFormat) -> Result<()> {
+    use crate::services::auth::AuthService;
+
+    let config = CortexConfig::load()?;
+    let storage = create_storage(&config).await?;
+
+    let keys = AuthService::new(storage).list_all_api_keys().await?;
+
+    match format {
+        OutputFormat::Json => output::output(&keys, format)?,
+        _ => {
+            output::header("API Keys");
+
+            if keys.is_empty() {
+                output::info("No API keys found");
+            } else {
+                let mut table = TableBuilder::new()
+                    .header(vec!["ID", "Name", "Owner", "Scopes", "Expires", "Last Used"]);
+
+                for key in &keys {
+                    table = table.row(vec![
+                        key.id.clone(),
+                        key.name.clone(),
+                        key.user_id.clone(),
+                        key.scopes.join(", "),
+                        key.expires_at
+                            .map(output::format_timestamp)
+                            .unwrap_or_else(|| "never".to_string()),
+                        key.last_used
+                            .map(|t| format_relative_time(&t))
+                            .unwrap_or_else(|| "never".to_string()),
+                    ]);
+                }
+
+                table.print();
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Revoke an API key for the REST API
+pub async fn server_keys_revoke(key_id: String) -> Result<()> {
+    use crate::services::auth::AuthService;
+
+    let config = CortexConfig::load()?;
+    let storage = create_storage(&config).await?;
+
+    AuthService::new(storage).revoke_api_key(&key_id).await?;
+
+    output::success(format!("Revoked API key {}", key_id));
+
+    Ok(())
+}
+
 /// Check REST API server status
 pub async fn server_status() -> Result<()> {
     use crate::server_manager::{ServerManager, ServerConfig, ServerStatus};
@@ -3300,6 +3636,88 @@ pub async fn code_optimize_imports(
     Ok(())
 }
 
+/// Check the architecture of the indexed code against a rules file
+///
+/// Fails when modules depend on each other in a cycle or a forbidden
+/// dependency exists, so the command can gate CI.
+pub async fn code_architecture_check(
+    rules: Option<PathBuf>,
+    module_depth: Option<usize>,
+    format: OutputFormat,
+) -> Result<()> {
+    let mut architecture_rules = match rules {
+        Some(path) => {
+            let content = tokio::fs::read_to_string(&path)
+                .await
+                .with_context(|| format!("Failed to read rules file {}", path.display()))?;
+            serde_json::from_str::<ArchitectureRules>(&content)
+                .with_context(|| format!("Invalid rules file {}", path.display()))?
+        }
+        None => ArchitectureRules::default(),
+    };
+    if module_depth.is_some() {
+        architecture_rules.module_depth = module_depth;
+    }
+    let rule_set = RuleSet::from_rules(architecture_rules).map_err(|e| anyhow::anyhow!(e))?;
+
+    let config = CortexConfig::load()?;
+    let storage = create_storage(&config).await?;
+    let report = DependencyService::new(storage)
+        .check_architecture(&rule_set)
+        .await?;
+
+    match format {
+        OutputFormat::Json => {
+            output::output(&report, format)?;
+        }
+        _ => {
+            output::header("Architecture Check");
+            output::kv("Modules", report.modules);
+            output::kv("Cycles", report.cycles.len());
+            output::kv("Violations", report.violations.len());
+
+            let mut table = TableBuilder::new().header(vec!["Layer", "Module"]);
+            for layer in &report.layers {
+                table = table.row(vec![layer.layer.to_string(), layer.module.clone()]);
+            }
+            table.print();
+
+            for cycle in &report.cycles {
+                output::error(format!("Cycle: {}", cycle.modules.join(" -> ")));
+                for edge in &cycle.edges {
+                    output::info(format!("  {} -> {}", edge.from_file, edge.to_file));
+                }
+            }
+            for violation in &report.violations {
+                let location = match violation.edge.line {
+                    Some(line) => format!("{}:{}", violation.edge.from_file, line),
+                    None => violation.edge.from_file.clone(),
+                };
+                output::error(format!(
+                    "{}: {} depends on {} ({})",
+                    location,
+                    violation.edge.from,
+                    violation.edge.to_file,
+                    violation.description.as_deref().unwrap_or(&violation.rule)
+                ));
+            }
+        }
+    }
+
+    if !report.passed {
+        anyhow::bail!(
+            "Architecture check failed: {} cycle(s), {} violation(s)",
+            report.cycles.len(),
+            report.violations.len()
+        );
+    }
+
+    if !matches!(format, OutputFormat::Json) {
+        output::success("Architecture check passed");
+    }
+    Ok(())
+}
+
 // ============================================================================
 // Additional Memory Commands
 // ============================================================================
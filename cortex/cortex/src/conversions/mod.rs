@@ -186,6 +186,7 @@ impl ToApiResponse<SearchResult> for ServiceSearchResult {
             score: self.score as f64,
             result_type: self.result_type,
             metadata: serde_json::to_value(self.metadata).unwrap_or(serde_json::Value::Null),
+            answer_snippet: self.answer_snippet,
         }
     }
 }
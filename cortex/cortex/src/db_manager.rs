@@ -815,6 +815,7 @@ pub async fn create_from_global_config() -> Result<DatabaseManager> {
             .unwrap_or(false),
         timeout: Duration::from_secs(10),
         request_timeout: Duration::from_secs(60),
+        snapshot_s3: None,
     };
 
     // Database manager configuration with auto-detection
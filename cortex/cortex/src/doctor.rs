@@ -4,7 +4,15 @@
 
 use crate::output::{self};
 use anyhow::{Context, Result};
+use cortex_semantic::config::{EmbeddingProviderConfig, SemanticConfig};
+use cortex_semantic::providers::ProviderManager;
+use cortex_semantic::EmbeddingProvider;
 use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::time::Duration;
+
+/// Timeout applied to each deep check so a hung service cannot stall the run
+const DEEP_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
 
 /// Doctor check result
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -103,6 +111,21 @@ pub async fn run_diagnostics(fix: bool) -> Result<Vec<DiagnosticResult>> {
     results.push(result.clone());
     print_diagnostic_result(&result);
 
+    // Checks 9-12: Embedding provider, Qdrant, schema version, VFS blobs
+    let spinner = output::spinner("Running deep checks...");
+    let deep_results = run_deep_checks().await;
+    spinner.finish_and_clear();
+
+    for (result, deep_fix) in deep_results {
+        results.push(result.clone());
+        print_diagnostic_result(&result);
+
+        let Some(deep_fix) = deep_fix else { continue };
+        if fix && result.status != DiagnosticStatus::Pass && result.auto_fixable && output::confirm(deep_fix.prompt())? {
+            apply_deep_fix(deep_fix).await?;
+        }
+    }
+
     println!();
     print_summary(&results);
 
@@ -365,6 +388,8 @@ async fn check_memory_subsystems() -> DiagnosticResult {
             validate_on_checkout: true,
             recycle_after_uses: None,
             shutdown_grace_period: Duration::from_secs(5),
+            query_timeout: Some(Duration::from_secs(30)),
+            slow_query_threshold: Some(Duration::from_secs(1)),
         },
         namespace: "test".to_string(),
         database: "memory_check".to_string(),
@@ -482,6 +507,550 @@ async fn fix_data_directory() -> Result<()> {
     Ok(())
 }
 
+// ============================================================================
+// Deep Checks
+// ============================================================================
+
+/// Safe remediation that `--fix` may apply for a deep check
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DeepFix {
+    /// Create the listed Cortex-managed Qdrant collections
+    CreateCollections(Vec<String>),
+    /// Re-apply the idempotent schema and record the current version
+    MigrateSchema,
+    /// Delete unreferenced VFS content blobs
+    CollectGarbage,
+    /// Delete lease locks whose holders stopped renewing them
+    ReclaimExpiredLocks,
+}
+
+impl DeepFix {
+    fn prompt(&self) -> String {
+        match self {
+            DeepFix::CreateCollections(names) => format!("Create missing Qdrant collections ({})?", names.join(", ")),
+            DeepFix::MigrateSchema => "Run schema migration?".to_string(),
+            DeepFix::CollectGarbage => "Delete orphaned content blobs?".to_string(),
+            DeepFix::ReclaimExpiredLocks => "Reclaim expired lease locks?".to_string(),
+        }
+    }
+}
+
+/// Run the deep checks concurrently, each bounded by [`DEEP_CHECK_TIMEOUT`]
+async fn run_deep_checks() -> Vec<(DiagnosticResult, Option<DeepFix>)> {
+    let config = SemanticConfig::default();
+
+    let provider = match tokio::time::timeout(DEEP_CHECK_TIMEOUT, primary_provider(&config.embedding)).await {
+        Ok(Ok(provider)) => Ok(provider),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err(format!("initialization timed out after {}s", DEEP_CHECK_TIMEOUT.as_secs())),
+    };
+    let dimension = provider.as_ref().ok().map(|p| p.dimension());
+
+    let (embedding, chain, qdrant, schema, blobs, locks) = tokio::join!(
+        with_timeout("Embedding Provider", check_embedding_provider(&config.embedding, provider)),
+        with_timeout("Embedding Fallback Chain", check_fallback_chain(&config.embedding)),
+        with_timeout("Qdrant Collections", check_qdrant_collections(&config, dimension)),
+        with_timeout("Schema Version", check_schema_version()),
+        with_timeout("VFS Content Blobs", check_orphaned_content()),
+        with_timeout("Lease Locks", check_lease_locks()),
+    );
+
+    vec![embedding, chain, qdrant, schema, blobs, locks]
+}
+
+async fn with_timeout(
+    check_name: &str,
+    check: impl Future<Output = (DiagnosticResult, Option<DeepFix>)>,
+) -> (DiagnosticResult, Option<DeepFix>) {
+    match tokio::time::timeout(DEEP_CHECK_TIMEOUT, check).await {
+        Ok(outcome) => outcome,
+        Err(_) => (
+            DiagnosticResult {
+                check_name: check_name.to_string(),
+                status: DiagnosticStatus::Fail,
+                message: format!("Check timed out after {}s", DEEP_CHECK_TIMEOUT.as_secs()),
+                suggestion: Some("Verify the service is reachable and not overloaded".to_string()),
+                auto_fixable: false,
+            },
+            None,
+        ),
+    }
+}
+
+/// The configured primary provider alone, so fallbacks cannot mask a broken one
+async fn primary_provider(config: &EmbeddingProviderConfig) -> cortex_semantic::Result<ProviderManager> {
+    let mut config = config.clone();
+    config.fallback_providers.clear();
+    ProviderManager::from_config(&config).await
+}
+
+fn provider_remediation(provider: &str) -> String {
+    match provider {
+        "openai" => "Set a valid OPENAI_API_KEY (keys can expire or be revoked)".to_string(),
+        "onnx" => "Set embedding.onnx.model_path to a downloaded ONNX model".to_string(),
+        "ollama" => "Start Ollama (ollama serve) and pull the configured model".to_string(),
+        other => format!("Check the '{}' embedding provider configuration", other),
+    }
+}
+
+async fn check_embedding_provider(
+    config: &EmbeddingProviderConfig,
+    provider: std::result::Result<ProviderManager, String>,
+) -> (DiagnosticResult, Option<DeepFix>) {
+    let check_name = "Embedding Provider".to_string();
+    let name = config.primary_provider.to_lowercase();
+
+    let provider = match provider {
+        Ok(provider) => provider,
+        Err(e) => {
+            return (
+                DiagnosticResult {
+                    check_name,
+                    status: DiagnosticStatus::Fail,
+                    message: format!("Failed to initialize {} provider: {}", name, e),
+                    suggestion: Some(provider_remediation(&name)),
+                    auto_fixable: false,
+                },
+                None,
+            );
+        }
+    };
+
+    if name == "mock" {
+        return (
+            DiagnosticResult {
+                check_name,
+                status: DiagnosticStatus::Warning,
+                message: "Mock provider in use; search results are not semantic".to_string(),
+                suggestion: Some("Configure the openai, onnx or ollama provider".to_string()),
+                auto_fixable: false,
+            },
+            None,
+        );
+    }
+
+    let result = match provider.embed("cortex doctor probe").await {
+        Ok(vector) if vector.len() != provider.dimension() => DiagnosticResult {
+            check_name,
+            status: DiagnosticStatus::Fail,
+            message: format!(
+                "{} returned {} dimensions but declares {}",
+                provider.model().model_name,
+                vector.len(),
+                provider.dimension()
+            ),
+            suggestion: Some("Set the dimension override to match the model output".to_string()),
+            auto_fixable: false,
+        },
+        Ok(_) => DiagnosticResult {
+            check_name,
+            status: DiagnosticStatus::Pass,
+            message: format!("{} responding ({} dimensions)", provider.model().model_name, provider.dimension()),
+            suggestion: None,
+            auto_fixable: false,
+        },
+        Err(e) => DiagnosticResult {
+            check_name,
+            status: DiagnosticStatus::Fail,
+            message: format!("Embedding request failed: {}", e),
+            suggestion: Some(provider_remediation(&name)),
+            auto_fixable: false,
+        },
+    };
+
+    (result, None)
+}
+
+/// Probe every provider of the fallback chain, so a broken or incompatible
+/// fallback shows up before the primary needs it
+async fn check_fallback_chain(config: &EmbeddingProviderConfig) -> (DiagnosticResult, Option<DeepFix>) {
+    let check_name = "Embedding Fallback Chain".to_string();
+
+    if config.fallback_providers.is_empty() {
+        return (
+            DiagnosticResult {
+                check_name,
+                status: DiagnosticStatus::Pass,
+                message: "No fallback providers configured".to_string(),
+                suggestion: None,
+                auto_fixable: false,
+            },
+            None,
+        );
+    }
+
+    let manager = match ProviderManager::from_config(config).await {
+        Ok(manager) => manager,
+        Err(e) => {
+            return (
+                DiagnosticResult {
+                    check_name,
+                    status: DiagnosticStatus::Fail,
+                    message: format!("Failed to build the provider chain: {}", e),
+                    suggestion: Some(provider_remediation(&config.primary_provider.to_lowercase())),
+                    auto_fixable: false,
+                },
+                None,
+            );
+        }
+    };
+
+    let health = manager.check_health().await;
+    let summary: Vec<String> = health
+        .iter()
+        .map(|provider| {
+            if !provider.dimension_compatible {
+                format!("{} incompatible ({} dims)", provider.provider, provider.dimension)
+            } else if let Some(error) = provider.last_error.as_ref().filter(|_| provider.consecutive_failures > 0) {
+                format!("{} failing: {}", provider.provider, error)
+            } else {
+                format!("{} ok ({} dims)", provider.provider, provider.dimension)
+            }
+        })
+        .collect();
+
+    let incompatible: Vec<&str> = health
+        .iter()
+        .filter(|provider| !provider.dimension_compatible)
+        .map(|provider| provider.provider.as_str())
+        .collect();
+    let failing = health
+        .iter()
+        .find(|provider| provider.dimension_compatible && provider.consecutive_failures > 0);
+
+    let suggestion = if !incompatible.is_empty() {
+        Some(format!(
+            "Fallbacks must embed with the primary's {} dimensions; remove or reconfigure {}",
+            manager.dimension(),
+            incompatible.join(", ")
+        ))
+    } else {
+        failing.map(|provider| provider_remediation(&provider.provider.to_lowercase()))
+    };
+
+    (
+        DiagnosticResult {
+            check_name,
+            status: if suggestion.is_some() { DiagnosticStatus::Warning } else { DiagnosticStatus::Pass },
+            message: summary.join(", "),
+            suggestion,
+            auto_fixable: false,
+        },
+        None,
+    )
+}
+
+async fn check_qdrant_collections(
+    config: &SemanticConfig,
+    embedding_dimension: Option<usize>,
+) -> (DiagnosticResult, Option<DeepFix>) {
+    use crate::qdrant_commands::{collection_vector_size, create_qdrant_client, get_collection_configs};
+
+    let check_name = "Qdrant Collections".to_string();
+
+    let outcome: Result<(Vec<String>, Vec<String>)> = async {
+        let client = create_qdrant_client().await?;
+        let existing = client.list_collections().await?;
+
+        let mut expected: Vec<(String, Option<u64>)> = get_collection_configs()
+            .into_iter()
+            .map(|c| (c.name, Some(c.vector_size)))
+            .collect();
+        expected.push((
+            format!("{}{}", config.qdrant.collection_prefix, config.qdrant.collection_name),
+            embedding_dimension.map(|d| d as u64),
+        ));
+
+        let managed: Vec<String> = get_collection_configs().into_iter().map(|c| c.name).collect();
+        let missing = managed
+            .into_iter()
+            .filter(|name| !existing.contains(name))
+            .collect();
+
+        let mut mismatches = Vec::new();
+        for (name, expected_size) in expected {
+            let Some(expected_size) = expected_size else { continue };
+            if !existing.contains(&name) {
+                continue;
+            }
+            let info = client.collection_info(&name).await?;
+            match collection_vector_size(&info) {
+                Some(actual) if actual != expected_size => {
+                    mismatches.push(format!("{} has {} dims, expected {}", name, actual, expected_size));
+                }
+                _ => {}
+            }
+        }
+
+        Ok((missing, mismatches))
+    }
+    .await;
+
+    match outcome {
+        Err(e) => (
+            DiagnosticResult {
+                check_name,
+                status: DiagnosticStatus::Fail,
+                message: format!("Qdrant unreachable: {:#}", e),
+                suggestion: Some("Start Qdrant with: cortex db start (or set QDRANT_HOST)".to_string()),
+                auto_fixable: false,
+            },
+            None,
+        ),
+        Ok((missing, mismatches)) if !mismatches.is_empty() => {
+            let fix = (!missing.is_empty()).then(|| DeepFix::CreateCollections(missing));
+            (
+                DiagnosticResult {
+                    check_name,
+                    status: DiagnosticStatus::Fail,
+                    message: format!("Dimension mismatch: {}", mismatches.join("; ")),
+                    suggestion: Some(
+                        "Switch to an embedding model with matching dimensions, or re-embed into a new collection with: cortex qdrant migrate <source> <target>".to_string(),
+                    ),
+                    auto_fixable: fix.is_some(),
+                },
+                fix,
+            )
+        }
+        Ok((missing, _)) if !missing.is_empty() => (
+            DiagnosticResult {
+                check_name,
+                status: DiagnosticStatus::Fail,
+                message: format!("Missing collections: {}", missing.join(", ")),
+                suggestion: Some("Create with: cortex qdrant init".to_string()),
+                auto_fixable: true,
+            },
+            Some(DeepFix::CreateCollections(missing)),
+        ),
+        Ok(_) => (
+            DiagnosticResult {
+                check_name,
+                status: DiagnosticStatus::Pass,
+                message: "All collections present with matching dimensions".to_string(),
+                suggestion: None,
+                auto_fixable: false,
+            },
+            None,
+        ),
+    }
+}
+
+async fn check_schema_version() -> (DiagnosticResult, Option<DeepFix>) {
+    use cortex_storage::schema::{read_schema_version, SCHEMA_VERSION};
+
+    let check_name = "Schema Version".to_string();
+
+    let outcome: Result<Option<u32>> = async {
+        let config = crate::config::CortexConfig::load().unwrap_or_default();
+        let storage = crate::commands::connect_storage(&config).await?;
+        let conn = storage.acquire().await?;
+        Ok(read_schema_version(conn.connection()).await?)
+    }
+    .await;
+
+    match outcome {
+        Err(e) => (
+            DiagnosticResult {
+                check_name,
+                status: DiagnosticStatus::Fail,
+                message: format!("Could not read schema version: {:#}", e),
+                suggestion: Some("Start with: cortex db start".to_string()),
+                auto_fixable: false,
+            },
+            None,
+        ),
+        Ok(Some(version)) if version == SCHEMA_VERSION => (
+            DiagnosticResult {
+                check_name,
+                status: DiagnosticStatus::Pass,
+                message: format!("Schema is at version {}", version),
+                suggestion: None,
+                auto_fixable: false,
+            },
+            None,
+        ),
+        Ok(Some(version)) if version > SCHEMA_VERSION => (
+            DiagnosticResult {
+                check_name,
+                status: DiagnosticStatus::Fail,
+                message: format!(
+                    "Database schema version {} is newer than this binary supports ({})",
+                    version, SCHEMA_VERSION
+                ),
+                suggestion: Some("Upgrade cortex to the release that created this database".to_string()),
+                auto_fixable: false,
+            },
+            None,
+        ),
+        Ok(version) => (
+            DiagnosticResult {
+                check_name,
+                status: DiagnosticStatus::Fail,
+                message: match version {
+                    Some(version) => format!("Schema version {} is older than expected {}", version, SCHEMA_VERSION),
+                    None => format!("Schema version not recorded (expected {})", SCHEMA_VERSION),
+                },
+                suggestion: Some("Run migration with: cortex doctor check --fix".to_string()),
+                auto_fixable: true,
+            },
+            Some(DeepFix::MigrateSchema),
+        ),
+    }
+}
+
+async fn check_orphaned_content() -> (DiagnosticResult, Option<DeepFix>) {
+    let check_name = "VFS Content Blobs".to_string();
+
+    let outcome: Result<usize> = async {
+        let config = crate::config::CortexConfig::load().unwrap_or_default();
+        let storage = crate::commands::connect_storage(&config).await?;
+        let vfs = cortex_vfs::VirtualFileSystem::new(storage);
+        Ok(vfs.count_orphaned_content().await?)
+    }
+    .await;
+
+    match outcome {
+        Err(e) => (
+            DiagnosticResult {
+                check_name,
+                status: DiagnosticStatus::Fail,
+                message: format!("Could not scan content blobs: {:#}", e),
+                suggestion: Some("Start with: cortex db start".to_string()),
+                auto_fixable: false,
+            },
+            None,
+        ),
+        Ok(0) => (
+            DiagnosticResult {
+                check_name,
+                status: DiagnosticStatus::Pass,
+                message: "No orphaned content blobs".to_string(),
+                suggestion: None,
+                auto_fixable: false,
+            },
+            None,
+        ),
+        Ok(count) => (
+            DiagnosticResult {
+                check_name,
+                status: DiagnosticStatus::Warning,
+                message: format!("{} content blobs are not referenced by any file", count),
+                suggestion: Some("Reclaim space with: cortex doctor check --fix".to_string()),
+                auto_fixable: true,
+            },
+            Some(DeepFix::CollectGarbage),
+        ),
+    }
+}
+
+async fn lease_lock_manager() -> Result<cortex_storage::LeaseLockManager> {
+    let config = crate::config::CortexConfig::load().unwrap_or_default();
+    let storage = crate::commands::connect_storage(&config).await?;
+    Ok(cortex_storage::LeaseLockManager::new(storage, Default::default()))
+}
+
+async fn check_lease_locks() -> (DiagnosticResult, Option<DeepFix>) {
+    let check_name = "Lease Locks".to_string();
+
+    let outcome: Result<Vec<cortex_storage::locks::EntityLock>> = async {
+        Ok(lease_lock_manager().await?.list_locks().await?)
+    }
+    .await;
+
+    let locks = match outcome {
+        Ok(locks) => locks,
+        Err(e) => {
+            return (
+                DiagnosticResult {
+                    check_name,
+                    status: DiagnosticStatus::Fail,
+                    message: format!("Could not read the lock table: {:#}", e),
+                    suggestion: Some("Start with: cortex db start".to_string()),
+                    auto_fixable: false,
+                },
+                None,
+            );
+        }
+    };
+
+    let now = chrono::Utc::now();
+    let expired: Vec<_> = locks.iter().filter(|lock| lock.expires_at <= now).collect();
+    if expired.is_empty() {
+        return (
+            DiagnosticResult {
+                check_name,
+                status: DiagnosticStatus::Pass,
+                message: format!("{} active lease locks", locks.len()),
+                suggestion: None,
+                auto_fixable: false,
+            },
+            None,
+        );
+    }
+
+    let holders: std::collections::BTreeSet<&str> =
+        expired.iter().map(|lock| lock.holder_session.as_str()).collect();
+    (
+        DiagnosticResult {
+            check_name,
+            status: DiagnosticStatus::Warning,
+            message: format!(
+                "{} of {} lease locks expired without release (sessions: {})",
+                expired.len(),
+                locks.len(),
+                holders.into_iter().collect::<Vec<_>>().join(", ")
+            ),
+            suggestion: Some("Reclaim them with: cortex doctor check --fix".to_string()),
+            auto_fixable: true,
+        },
+        Some(DeepFix::ReclaimExpiredLocks),
+    )
+}
+
+async fn apply_deep_fix(deep_fix: DeepFix) -> Result<()> {
+    match deep_fix {
+        DeepFix::CreateCollections(names) => {
+            let client = crate::qdrant_commands::create_qdrant_client().await?;
+            for config in crate::qdrant_commands::get_collection_configs() {
+                if names.contains(&config.name) {
+                    let name = config.name.clone();
+                    client
+                        .create_collection(config)
+                        .await
+                        .with_context(|| format!("Failed to create collection {}", name))?;
+                    output::success(format!("Created collection {}", name));
+                }
+            }
+        }
+        DeepFix::MigrateSchema => {
+            let config = crate::config::CortexConfig::load()?;
+            let storage = crate::commands::connect_storage(&config).await?;
+            let conn = storage.acquire().await?;
+            cortex_storage::schema::init_schema(conn.connection())
+                .await
+                .context("Schema migration failed")?;
+            output::success(format!(
+                "Schema migrated to version {}",
+                cortex_storage::schema::SCHEMA_VERSION
+            ));
+        }
+        DeepFix::CollectGarbage => {
+            let config = crate::config::CortexConfig::load()?;
+            let storage = crate::commands::connect_storage(&config).await?;
+            let removed = cortex_vfs::VirtualFileSystem::new(storage)
+                .collect_orphaned_content()
+                .await?;
+            output::success(format!("Removed {} orphaned content blobs", removed));
+        }
+        DeepFix::ReclaimExpiredLocks => {
+            let reclaimed = lease_lock_manager().await?.reclaim_expired().await?;
+            output::success(format!("Reclaimed {} expired lease locks", reclaimed));
+        }
+    }
+
+    Ok(())
+}
+
 /// Quick health check
 pub async fn quick_health_check() -> Result<bool> {
     let results = vec![
@@ -511,6 +1080,31 @@ mod tests {
         assert!(!result.check_name.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_with_timeout_reports_hung_check() {
+        tokio::time::pause();
+        let (result, fix) = with_timeout("Hung", async {
+            std::future::pending::<(DiagnosticResult, Option<DeepFix>)>().await
+        })
+        .await;
+
+        assert_eq!(result.check_name, "Hung");
+        assert_eq!(result.status, DiagnosticStatus::Fail);
+        assert!(result.message.contains("timed out"));
+        assert!(fix.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_mock_embedding_provider_warns() {
+        let mut config = EmbeddingProviderConfig::default();
+        config.primary_provider = "mock".to_string();
+        let provider = primary_provider(&config).await.map_err(|e| e.to_string());
+
+        let (result, fix) = check_embedding_provider(&config, provider).await;
+        assert_eq!(result.status, DiagnosticStatus::Warning);
+        assert!(fix.is_none());
+    }
+
     #[test]
     fn test_diagnostic_result_creation() {
         let result = DiagnosticResult {
@@ -2,22 +2,38 @@
 //!
 //! Supports exporting Cortex data to multiple formats:
 //! - JSON
+//! - JSONL (one record per line)
 //! - CSV
 //! - YAML
 //! - Markdown
+//!
+//! Workspace and episode exports are streamed: records are fetched from
+//! storage in pages and written to the output file as they arrive, so memory
+//! use stays flat regardless of how many records are exported.
 
+use crate::output::{self, OutputFormat};
+use crate::services::listing::PageWindow;
 use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
 use cortex_storage::ConnectionManager;
-use cortex_vfs::VirtualFileSystem;
 use serde::Serialize;
-use serde_json::json;
+use serde_json::{json, Map, Value};
+use std::fs::File;
+use std::io::{BufWriter, Write};
 use std::path::Path;
 use std::sync::Arc;
 
+/// Number of records fetched from storage per query
+const PAGE_SIZE: usize = 1000;
+
+/// Print a progress line every this many records (human mode only)
+const PROGRESS_INTERVAL: usize = 10_000;
+
 /// Export format
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ExportFormat {
     Json,
+    Jsonl,
     Csv,
     Yaml,
     Markdown,
@@ -27,6 +43,7 @@ impl ExportFormat {
     pub fn from_extension(ext: &str) -> Option<Self> {
         match ext.to_lowercase().as_str() {
             "json" => Some(Self::Json),
+            "jsonl" | "ndjson" => Some(Self::Jsonl),
             "csv" => Some(Self::Csv),
             "yaml" | "yml" => Some(Self::Yaml),
             "md" | "markdown" => Some(Self::Markdown),
@@ -37,6 +54,7 @@ impl ExportFormat {
     pub fn extension(&self) -> &str {
         match self {
             Self::Json => "json",
+            Self::Jsonl => "jsonl",
             Self::Csv => "csv",
             Self::Yaml => "yaml",
             Self::Markdown => "md",
@@ -44,6 +62,314 @@ impl ExportFormat {
     }
 }
 
+/// Filters and projection applied to streamed exports
+#[derive(Debug, Clone, Default)]
+pub struct ExportOptions {
+    /// Only export records created (or modified) at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only export records created (or modified) before this time
+    pub until: Option<DateTime<Utc>>,
+    /// Keep only these fields, in this order
+    pub fields: Option<Vec<String>>,
+    /// Maximum number of records to export
+    pub limit: Option<usize>,
+    /// Print progress every [`PROGRESS_INTERVAL`] records
+    pub progress: bool,
+}
+
+/// Outcome of a streamed export
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportReport {
+    pub path: String,
+    pub records: usize,
+    pub bytes_written: u64,
+}
+
+impl ExportReport {
+    pub fn print(&self, what: &str, format: OutputFormat) {
+        match format {
+            OutputFormat::Json => {
+                if let Ok(line) = serde_json::to_string(self) {
+                    println!("{}", line);
+                }
+            }
+            OutputFormat::Human | OutputFormat::Plain => {
+                output::success(format!("Exported {} {} to {}", self.records, what, self.path));
+                output::kv("Records", self.records);
+                output::kv("Bytes written", output::format_bytes(self.bytes_written));
+            }
+        }
+    }
+}
+
+/// Parse a `--since`/`--until` bound: RFC3339 or a plain `YYYY-MM-DD` date
+/// (midnight UTC)
+pub fn parse_date_bound(raw: &str) -> Result<DateTime<Utc>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(raw) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+    let date = NaiveDate::parse_from_str(raw, "%Y-%m-%d").with_context(|| {
+        format!("Invalid date '{}': expected YYYY-MM-DD or RFC3339 (e.g., 2024-01-01T00:00:00Z)", raw)
+    })?;
+    Ok(date.and_hms_opt(0, 0, 0).expect("midnight is valid").and_utc())
+}
+
+/// Keep only `fields` of an object record, in the given order. Missing fields
+/// become `null` so every record has the same shape.
+fn project(record: Value, fields: Option<&[String]>) -> Value {
+    match (fields, record) {
+        (Some(fields), Value::Object(mut map)) => {
+            let mut projected = Map::new();
+            for field in fields {
+                projected.insert(field.clone(), map.remove(field).unwrap_or(Value::Null));
+            }
+            Value::Object(projected)
+        }
+        (_, record) => record,
+    }
+}
+
+/// Render a scalar for a CSV or Markdown cell
+fn cell_text(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+/// `Write` adapter that counts bytes written
+struct CountingWriter<W: Write> {
+    inner: W,
+    bytes: u64,
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.bytes += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Incremental writer that emits records one at a time in any export format.
+///
+/// An optional header object (e.g. workspace metadata) wraps the records in
+/// JSON, YAML and Markdown output under an `items` key; JSONL and CSV carry
+/// records only so every line/row has the same shape.
+pub struct RecordWriter<W: Write> {
+    out: CountingWriter<W>,
+    format: ExportFormat,
+    fields: Option<Vec<String>>,
+    columns: Option<Vec<String>>,
+    has_header: bool,
+    records: usize,
+}
+
+impl<W: Write> RecordWriter<W> {
+    pub fn new(out: W, format: ExportFormat, fields: Option<Vec<String>>) -> Self {
+        Self {
+            out: CountingWriter { inner: out, bytes: 0 },
+            format,
+            columns: fields.clone(),
+            fields,
+            has_header: false,
+            records: 0,
+        }
+    }
+
+    /// Write the document preamble. Must be called once before any record.
+    pub fn begin(&mut self, header: Option<&Map<String, Value>>) -> Result<()> {
+        self.has_header = header.is_some();
+        match (self.format, header) {
+            (ExportFormat::Json, Some(header)) => {
+                writeln!(self.out, "{{")?;
+                for (key, value) in header {
+                    writeln!(self.out, "  {}: {},", serde_json::to_string(key)?, serde_json::to_string(value)?)?;
+                }
+                write!(self.out, "  \"items\": [")?;
+            }
+            (ExportFormat::Json, None) => write!(self.out, "[")?,
+            (ExportFormat::Yaml, Some(header)) => {
+                self.out.write_all(serde_yaml::to_string(header)?.as_bytes())?;
+            }
+            (ExportFormat::Markdown, header) => {
+                writeln!(self.out, "# Cortex Export\n")?;
+                if let Some(header) = header {
+                    for (key, value) in header {
+                        writeln!(self.out, "- **{}**: {}", key, cell_text(Some(value)))?;
+                    }
+                    writeln!(self.out)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Append one record
+    pub fn write_record(&mut self, record: Value) -> Result<()> {
+        let record = project(record, self.fields.as_deref());
+        let first = self.records == 0;
+
+        match self.format {
+            ExportFormat::Json => {
+                let separator = if first { "\n" } else { ",\n" };
+                write!(self.out, "{}{}", separator, serde_json::to_string_pretty(&record)?)?;
+            }
+            ExportFormat::Jsonl => writeln!(self.out, "{}", serde_json::to_string(&record)?)?,
+            ExportFormat::Csv => {
+                let columns = self.columns_for(&record)?;
+                if first {
+                    let header: Vec<String> = columns.iter().map(|c| escape_csv(c)).collect();
+                    writeln!(self.out, "{}", header.join(","))?;
+                }
+                let row: Vec<String> = columns
+                    .iter()
+                    .map(|c| escape_csv(&cell_text(record.get(c))))
+                    .collect();
+                writeln!(self.out, "{}", row.join(","))?;
+            }
+            ExportFormat::Yaml => {
+                if first && self.has_header {
+                    writeln!(self.out, "items:")?;
+                }
+                let item = serde_yaml::to_string(&[&record])?;
+                for line in item.lines() {
+                    if self.has_header {
+                        writeln!(self.out, "  {}", line)?;
+                    } else {
+                        writeln!(self.out, "{}", line)?;
+                    }
+                }
+            }
+            ExportFormat::Markdown => {
+                let columns = self.columns_for(&record)?;
+                if first {
+                    writeln!(self.out, "| {} |", columns.join(" | "))?;
+                    writeln!(self.out, "| {} |", vec!["---"; columns.len()].join(" | "))?;
+                }
+                let row: Vec<String> = columns
+                    .iter()
+                    .map(|c| match record.get(c) {
+                        None | Some(Value::Null) => "*null*".to_string(),
+                        value => cell_text(value).replace('|', "\\|"),
+                    })
+                    .collect();
+                writeln!(self.out, "| {} |", row.join(" | "))?;
+            }
+        }
+
+        self.records += 1;
+        Ok(())
+    }
+
+    /// Column order for tabular formats, fixed by `--fields` or the first record
+    fn columns_for(&mut self, record: &Value) -> Result<Vec<String>> {
+        if self.columns.is_none() {
+            let Value::Object(map) = record else {
+                anyhow::bail!("{:?} export requires object records", self.format);
+            };
+            self.columns = Some(map.keys().cloned().collect());
+        }
+        Ok(self.columns.clone().unwrap_or_default())
+    }
+
+    pub fn records(&self) -> usize {
+        self.records
+    }
+
+    /// Write the document epilogue, flush, and return `(records, bytes)`
+    pub fn finish(mut self) -> Result<(usize, u64)> {
+        let empty = self.records == 0;
+        match self.format {
+            ExportFormat::Json => {
+                let close = if self.has_header { "]\n}\n" } else { "]\n" };
+                let indent = if self.has_header && !empty { "\n  " } else if !empty { "\n" } else { "" };
+                write!(self.out, "{}{}", indent, close)?;
+            }
+            ExportFormat::Yaml if empty => {
+                let empty_items = if self.has_header { "items: []\n" } else { "[]\n" };
+                write!(self.out, "{}", empty_items)?;
+            }
+            ExportFormat::Markdown if empty => writeln!(self.out, "*No items to export*")?,
+            _ => {}
+        }
+        self.out.flush().context("Failed to flush export file")?;
+        Ok((self.records, self.out.bytes))
+    }
+}
+
+fn create_export_file(path: &Path) -> Result<BufWriter<File>> {
+    let file = File::create(path)
+        .with_context(|| format!("Failed to create export file {}", path.display()))?;
+    Ok(BufWriter::new(file))
+}
+
+/// Append `AND field >= $since AND field < $until` conditions for the options
+fn date_conditions(field: &str, options: &ExportOptions, params: &mut Map<String, Value>) -> String {
+    let mut clause = String::new();
+    if let Some(since) = options.since {
+        clause.push_str(&format!(" AND {} >= $since", field));
+        params.insert("since".to_string(), Value::String(since.to_rfc3339()));
+    }
+    if let Some(until) = options.until {
+        clause.push_str(&format!(" AND {} < $until", field));
+        params.insert("until".to_string(), Value::String(until.to_rfc3339()));
+    }
+    clause
+}
+
+/// Run `query` page by page, mapping and writing every row as it arrives
+async fn stream_rows<W: Write>(
+    storage: &ConnectionManager,
+    query: &str,
+    params: &Map<String, Value>,
+    options: &ExportOptions,
+    writer: &mut RecordWriter<W>,
+    map_row: impl Fn(Value) -> Value,
+) -> Result<()> {
+    let conn = storage.acquire().await
+        .context("Failed to acquire database connection")?;
+    let mut offset = 0;
+
+    loop {
+        let remaining = options.limit.map(|l| l.saturating_sub(writer.records()));
+        let page_size = remaining.map_or(PAGE_SIZE, |r| r.min(PAGE_SIZE));
+        if page_size == 0 {
+            break;
+        }
+
+        let window = PageWindow::new(page_size, offset);
+        let mut result = conn.connection()
+            .query(format!("{} {}", query, window.to_limit_clause()))
+            .bind(Value::Object(params.clone()))
+            .await
+            .context("Failed to query export page")?;
+        let rows: Vec<Value> = result.take(0)
+            .context("Failed to deserialize export page")?;
+        let fetched = rows.len();
+
+        for row in rows {
+            writer.write_record(map_row(row))?;
+            if options.progress && writer.records().is_multiple_of(PROGRESS_INTERVAL) {
+                output::info(format!("{} records exported...", writer.records()));
+            }
+        }
+
+        if fetched < page_size {
+            break;
+        }
+        offset += fetched;
+    }
+
+    Ok(())
+}
+
 /// Export data to a file
 pub fn export_to_file<T: Serialize>(
     data: &T,
@@ -52,6 +378,7 @@ pub fn export_to_file<T: Serialize>(
 ) -> Result<()> {
     let content = match format {
         ExportFormat::Json => export_json(data)?,
+        ExportFormat::Jsonl => export_jsonl(data)?,
         ExportFormat::Csv => export_csv(data)?,
         ExportFormat::Yaml => export_yaml(data)?,
         ExportFormat::Markdown => export_markdown(data)?,
@@ -67,6 +394,22 @@ pub fn export_json<T: Serialize>(data: &T) -> Result<String> {
     serde_json::to_string_pretty(data).context("Failed to serialize to JSON")
 }
 
+/// Export to JSONL format: one line per array element, or a single line
+pub fn export_jsonl<T: Serialize>(data: &T) -> Result<String> {
+    let json = serde_json::to_value(data)?;
+    let items = match json {
+        Value::Array(items) => items,
+        other => vec![other],
+    };
+
+    let mut jsonl = String::new();
+    for item in &items {
+        jsonl.push_str(&serde_json::to_string(item)?);
+        jsonl.push('\n');
+    }
+    Ok(jsonl)
+}
+
 /// Export to CSV format
 pub fn export_csv<T: Serialize>(data: &T) -> Result<String> {
     // For CSV export, we need the data to be a sequence
@@ -201,18 +544,18 @@ pub fn export_markdown<T: Serialize>(data: &T) -> Result<String> {
     Ok(markdown)
 }
 
-/// Export workspace data
+/// Export workspace data, streaming the file list
 pub async fn export_workspace(
     storage: Arc<ConnectionManager>,
     workspace_name: &str,
     output_path: &Path,
     format: ExportFormat,
-) -> Result<()> {
-    use crate::output;
-
-    output::info(format!("Exporting workspace '{}'...", workspace_name));
+    options: &ExportOptions,
+) -> Result<ExportReport> {
+    if options.progress {
+        output::info(format!("Exporting workspace '{}'...", workspace_name));
+    }
 
-    // Get workspace from storage
     let conn = storage.acquire().await
         .context("Failed to acquire database connection")?;
 
@@ -250,36 +593,25 @@ pub async fn export_workspace(
         .context("Failed to parse workspace")?
         .ok_or_else(|| anyhow::anyhow!("Workspace '{}' not found", workspace_name))?;
 
+    let mut params = Map::new();
+    params.insert("workspace_id".to_string(), Value::String(workspace.id.to_string()));
+    let filter = format!(
+        "workspace_id = $workspace_id{}",
+        date_conditions("updated_at", options, &mut params)
+    );
+
     // Count files in workspace
-    let file_count_query = format!("SELECT count() AS count FROM file WHERE workspace_id = '{}'", workspace.id);
     let mut file_result = conn.connection()
-        .query(&file_count_query)
+        .query(format!("SELECT count() AS count FROM file WHERE {} GROUP ALL", filter))
+        .bind(Value::Object(params.clone()))
         .await
         .context("Failed to count files")?;
 
     let file_count: Option<i64> = file_result.take("count")
         .unwrap_or(Some(0));
+    drop(conn);
 
-    // Get file list from workspace
-    let files_query = format!("SELECT * FROM file WHERE workspace_id = '{}'", workspace.id);
-    let mut files_result = conn.connection()
-        .query(&files_query)
-        .await
-        .context("Failed to query files")?;
-
-    let files: Vec<serde_json::Value> = files_result.take(0)
-        .unwrap_or_default();
-
-    // Collect file information
-    let file_items: Vec<_> = files.iter().map(|file| {
-        json!({
-            "path": file.get("path").and_then(|p| p.as_str()).unwrap_or(""),
-            "size": file.get("size").and_then(|s| s.as_i64()).unwrap_or(0),
-            "modified_at": file.get("updated_at").and_then(|u| u.as_str()).unwrap_or(""),
-        })
-    }).collect();
-
-    let data = json!({
+    let header = json!({
         "workspace": {
             "id": workspace.id.to_string(),
             "name": workspace.name,
@@ -289,67 +621,61 @@ pub async fn export_workspace(
         },
         "exported_at": chrono::Utc::now().to_rfc3339(),
         "files_count": file_count.unwrap_or(0),
-        "items": file_items,
     });
 
-    export_to_file(&data, output_path, format)?;
-
-    output::success(format!(
-        "Exported workspace '{}' ({} files) to {}",
-        workspace_name,
-        files.len(),
-        output_path.display()
-    ));
+    let mut writer = RecordWriter::new(create_export_file(output_path)?, format, options.fields.clone());
+    writer.begin(header.as_object())?;
 
-    Ok(())
+    let files_query = format!("SELECT * FROM file WHERE {} ORDER BY path", filter);
+    stream_rows(&storage, &files_query, &params, options, &mut writer, |file| {
+        json!({
+            "path": file.get("path").and_then(|p| p.as_str()).unwrap_or(""),
+            "size": file.get("size").and_then(|s| s.as_i64()).unwrap_or(0),
+            "modified_at": file.get("updated_at").and_then(|u| u.as_str()).unwrap_or(""),
+        })
+    })
+    .await?;
+
+    let (records, bytes_written) = writer.finish()?;
+    Ok(ExportReport {
+        path: output_path.display().to_string(),
+        records,
+        bytes_written,
+    })
 }
 
-/// Export memory episodes
+/// Export memory episodes, newest first, streaming page by page
 pub async fn export_episodes(
     storage: Arc<ConnectionManager>,
     workspace_name: Option<String>,
     output_path: &Path,
     format: ExportFormat,
-    limit: Option<usize>,
-) -> Result<()> {
-    use crate::output;
-
-    output::info("Exporting memory episodes...");
-
-    let conn = storage.acquire().await
-        .context("Failed to acquire database connection")?;
-
-    // Build query with optional workspace filter
-    let query = if let Some(ref workspace) = workspace_name {
-        format!(
-            "SELECT * FROM episode WHERE workspace = '{}' ORDER BY created_at DESC {}",
-            workspace,
-            limit.map(|l| format!("LIMIT {}", l)).unwrap_or_default()
-        )
-    } else {
-        format!(
-            "SELECT * FROM episode ORDER BY created_at DESC {}",
-            limit.map(|l| format!("LIMIT {}", l)).unwrap_or_default()
-        )
-    };
-
-    let mut result = conn.connection()
-        .query(&query)
-        .await
-        .context("Failed to query episodes")?;
+    options: &ExportOptions,
+) -> Result<ExportReport> {
+    if options.progress {
+        output::info("Exporting memory episodes...");
+    }
 
-    let episodes: Vec<serde_json::Value> = result.take(0)
-        .context("Failed to deserialize episodes")?;
+    let mut params = Map::new();
+    let mut filter = String::from("true");
+    if let Some(workspace) = workspace_name {
+        filter.push_str(" AND workspace = $workspace");
+        params.insert("workspace".to_string(), Value::String(workspace));
+    }
+    filter.push_str(&date_conditions("created_at", options, &mut params));
 
-    export_to_file(&episodes, output_path, format)?;
+    let mut writer = RecordWriter::new(create_export_file(output_path)?, format, options.fields.clone());
+    writer.begin(None)?;
 
-    output::success(format!(
-        "Exported {} episode(s) to {}",
-        episodes.len(),
-        output_path.display()
-    ));
+    let query = format!("SELECT * FROM episode WHERE {} ORDER BY created_at DESC", filter);
+    stream_rows(&storage, &query, &params, options, &mut writer, |episode| episode).await?;
 
-    Ok(())
+    let (records, bytes_written) = writer.finish()?;
+    Ok(ExportReport {
+        path: output_path.display().to_string(),
+        records,
+        bytes_written,
+    })
 }
 
 /// Export search results
@@ -359,8 +685,6 @@ pub async fn export_search_results(
     output_path: &Path,
     format: ExportFormat,
 ) -> Result<()> {
-    use crate::output;
-
     let data = serde_json::json!({
         "query": query,
         "exported_at": chrono::Utc::now().to_rfc3339(),
@@ -380,8 +704,6 @@ pub async fn export_stats(
     output_path: &Path,
     format: ExportFormat,
 ) -> Result<()> {
-    use crate::output;
-
     output::info("Exporting system statistics...");
 
     let conn = storage.acquire().await
@@ -451,6 +773,7 @@ mod tests {
         assert_eq!(ExportFormat::from_extension("yaml"), Some(ExportFormat::Yaml));
         assert_eq!(ExportFormat::from_extension("yml"), Some(ExportFormat::Yaml));
         assert_eq!(ExportFormat::from_extension("md"), Some(ExportFormat::Markdown));
+        assert_eq!(ExportFormat::from_extension("jsonl"), Some(ExportFormat::Jsonl));
         assert_eq!(ExportFormat::from_extension("unknown"), None);
     }
 
@@ -526,4 +849,132 @@ mod tests {
         let result = export_csv(&data);
         assert!(result.is_err());
     }
+
+    fn write_records(format: ExportFormat, header: Option<Value>, fields: Option<Vec<String>>) -> (String, u64) {
+        let mut buffer = Vec::new();
+        let mut writer = RecordWriter::new(&mut buffer, format, fields);
+        writer.begin(header.as_ref().and_then(Value::as_object)).unwrap();
+        writer.write_record(json!({"name": "Alice", "age": 30, "note": "a,b"})).unwrap();
+        writer.write_record(json!({"name": "Bob", "age": 25, "note": null})).unwrap();
+        let (records, bytes) = writer.finish().unwrap();
+        assert_eq!(records, 2);
+        (String::from_utf8(buffer).unwrap(), bytes)
+    }
+
+    #[test]
+    fn test_record_writer_json_is_valid() {
+        let (plain, bytes) = write_records(ExportFormat::Json, None, None);
+        assert_eq!(bytes as usize, plain.len());
+        let parsed: Value = serde_json::from_str(&plain).unwrap();
+        assert_eq!(parsed.as_array().unwrap().len(), 2);
+
+        let (wrapped, _) = write_records(ExportFormat::Json, Some(json!({"files_count": 2})), None);
+        let parsed: Value = serde_json::from_str(&wrapped).unwrap();
+        assert_eq!(parsed["files_count"], 2);
+        assert_eq!(parsed["items"][1]["name"], "Bob");
+    }
+
+    #[test]
+    fn test_record_writer_jsonl_with_fields() {
+        let fields = Some(vec!["name".to_string(), "missing".to_string()]);
+        let (output, _) = write_records(ExportFormat::Jsonl, Some(json!({"ignored": true})), fields);
+        let lines: Vec<Value> = output.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], json!({"name": "Alice", "missing": null}));
+    }
+
+    #[test]
+    fn test_record_writer_csv_and_markdown() {
+        let fields = Some(vec!["note".to_string(), "name".to_string()]);
+        let (csv, _) = write_records(ExportFormat::Csv, None, fields);
+        assert_eq!(csv, "note,name\n\"a,b\",Alice\n,Bob\n");
+
+        let (markdown, _) = write_records(ExportFormat::Markdown, Some(json!({"files_count": 2})), None);
+        assert!(markdown.contains("- **files_count**: 2"));
+        assert!(markdown.contains("| age | name | note |"));
+        assert!(markdown.contains("| 25 | Bob | *null* |"));
+    }
+
+    #[test]
+    fn test_record_writer_yaml_and_empty() {
+        let (yaml, _) = write_records(ExportFormat::Yaml, Some(json!({"files_count": 2})), None);
+        let parsed: Value = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(parsed["items"][0]["name"], "Alice");
+
+        let mut buffer = Vec::new();
+        let mut writer = RecordWriter::new(&mut buffer, ExportFormat::Json, None);
+        writer.begin(None).unwrap();
+        writer.finish().unwrap();
+        assert_eq!(String::from_utf8(buffer).unwrap(), "[]\n");
+    }
+
+    #[test]
+    fn test_parse_date_bound() {
+        let date = parse_date_bound("2024-03-01").unwrap();
+        assert_eq!(date.to_rfc3339(), "2024-03-01T00:00:00+00:00");
+
+        let instant = parse_date_bound("2024-03-01T12:30:00+02:00").unwrap();
+        assert_eq!(instant.to_rfc3339(), "2024-03-01T10:30:00+00:00");
+
+        assert!(parse_date_bound("March 1st").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_export_episodes_streams_with_filters() {
+        use cortex_storage::connection_pool::{ConnectionMode, Credentials, DatabaseConfig, PoolConfig, RetryPolicy};
+        use std::time::Duration;
+
+        let config = DatabaseConfig {
+            connection_mode: ConnectionMode::InMemory,
+            credentials: Credentials { username: None, password: None },
+            pool_config: PoolConfig {
+                min_connections: 1,
+                max_connections: 2,
+                connection_timeout: Duration::from_secs(5),
+                idle_timeout: None,
+                max_lifetime: None,
+                retry_policy: RetryPolicy::default(),
+                warm_connections: false,
+                validate_on_checkout: false,
+                recycle_after_uses: None,
+                shutdown_grace_period: Duration::from_secs(5),
+                query_timeout: Some(Duration::from_secs(30)),
+                slow_query_threshold: Some(Duration::from_secs(1)),
+            },
+            namespace: "cortex_test".to_string(),
+            database: "export_episodes".to_string(),
+        };
+        let storage = Arc::new(ConnectionManager::new(config).await.unwrap());
+
+        {
+            let conn = storage.acquire().await.unwrap();
+            for day in 1..=4 {
+                conn.connection()
+                    .query("CREATE episode CONTENT { task: $task, workspace: 'main', created_at: $created_at }")
+                    .bind(("task", format!("task {}", day)))
+                    .bind(("created_at", format!("2024-01-0{}T00:00:00+00:00", day)))
+                    .await
+                    .unwrap();
+            }
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("episodes.jsonl");
+        let options = ExportOptions {
+            since: Some(parse_date_bound("2024-01-02").unwrap()),
+            fields: Some(vec!["task".to_string()]),
+            limit: Some(2),
+            ..Default::default()
+        };
+
+        let report = export_episodes(storage, Some("main".to_string()), &path, ExportFormat::Jsonl, &options)
+            .await
+            .unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(report.records, 2);
+        assert_eq!(report.bytes_written as usize, contents.len());
+        assert_eq!(contents, "{\"task\":\"task 4\"}\n{\"task\":\"task 3\"}\n");
+    }
 }
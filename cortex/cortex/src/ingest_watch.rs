@@ -0,0 +1,343 @@
+//! Watch mode for `cortex ingest --watch`.
+//!
+//! After the initial import, keeps a workspace in sync with a directory on
+//! disk: created and modified files are re-chunked and re-ingested, deleted
+//! files are removed from both the VFS and semantic memory. Progress goes to
+//! the same ingestion journal as the initial import.
+
+use crate::output::{self, OutputFormat};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use cortex_code_analysis::CodeParser;
+use cortex_memory::SemanticMemorySystem;
+use cortex_storage::ConnectionManager;
+use cortex_vfs::{
+    FileEvent, FileIngestionPipeline, FileWatcher, IngestJournal, VirtualFileSystem, VirtualPath,
+    WatcherConfig,
+};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Files larger than this are skipped, matching the initial import limit
+pub const MAX_FILE_SIZE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Directories that are never ingested
+pub const DEFAULT_EXCLUDES: &[&str] = &["node_modules", "target", ".git", "dist", "build"];
+
+/// Gitignore-style matcher for `--ignore` globs plus the default excludes
+pub struct IgnoreMatcher {
+    root: PathBuf,
+    gitignore: Gitignore,
+}
+
+impl IgnoreMatcher {
+    pub fn new(root: &Path, patterns: &[String]) -> Result<Self> {
+        let mut builder = GitignoreBuilder::new(root);
+        for dir in DEFAULT_EXCLUDES {
+            builder.add_line(None, &format!("{}/", dir))?;
+        }
+        for pattern in patterns {
+            builder
+                .add_line(None, pattern)
+                .with_context(|| format!("Invalid ignore pattern: {}", pattern))?;
+        }
+
+        Ok(Self {
+            root: root.to_path_buf(),
+            gitignore: builder.build().context("Failed to build ignore patterns")?,
+        })
+    }
+
+    /// Whether a path (or any of its parent directories) is ignored.
+    /// Paths outside the watched root are always ignored.
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        if !path.starts_with(&self.root) {
+            return true;
+        }
+        self.gitignore
+            .matched_path_or_any_parents(path, is_dir)
+            .is_ignore()
+    }
+}
+
+/// What happened to a file in response to a watcher event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WatchAction {
+    Ingested,
+    Removed,
+    Failed,
+}
+
+/// One line of watch output
+#[derive(Debug, Serialize)]
+pub struct WatchEventRecord {
+    pub timestamp: DateTime<Utc>,
+    pub action: WatchAction,
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub units: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl WatchEventRecord {
+    fn new(action: WatchAction, path: &VirtualPath) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            action,
+            path: path.to_string(),
+            units: None,
+            duration_ms: None,
+            error: None,
+        }
+    }
+
+    /// Print as a compact human line or a single JSON line
+    fn print(&self, format: OutputFormat) {
+        match format {
+            OutputFormat::Json => {
+                if let Ok(line) = serde_json::to_string(self) {
+                    println!("{}", line);
+                }
+            }
+            OutputFormat::Human | OutputFormat::Plain => {
+                let time = self.timestamp.format("%H:%M:%S");
+                let units = self.units.unwrap_or(0);
+                match self.action {
+                    WatchAction::Ingested => println!(
+                        "{} ingested {} ({} units, {}ms)",
+                        time,
+                        self.path,
+                        units,
+                        self.duration_ms.unwrap_or(0)
+                    ),
+                    WatchAction::Removed => {
+                        println!("{} removed  {} ({} units)", time, self.path, units)
+                    }
+                    WatchAction::Failed => println!(
+                        "{} failed   {}: {}",
+                        time,
+                        self.path,
+                        self.error.as_deref().unwrap_or("unknown error")
+                    ),
+                }
+            }
+        }
+    }
+}
+
+/// Counters reported when watch mode stops
+#[derive(Debug, Default, Serialize)]
+pub struct WatchSummary {
+    pub files_ingested: usize,
+    pub files_removed: usize,
+    pub failures: usize,
+}
+
+impl WatchSummary {
+    fn record(&mut self, record: &WatchEventRecord) {
+        match record.action {
+            WatchAction::Ingested => self.files_ingested += 1,
+            WatchAction::Removed => self.files_removed += 1,
+            WatchAction::Failed => self.failures += 1,
+        }
+    }
+}
+
+/// Watches a directory and incrementally re-ingests changed files
+pub struct IngestWatcher {
+    workspace_id: Uuid,
+    root: PathBuf,
+    ignore: IgnoreMatcher,
+    pipeline: FileIngestionPipeline,
+    format: OutputFormat,
+}
+
+impl IngestWatcher {
+    pub fn new(
+        storage: Arc<ConnectionManager>,
+        journal: IngestJournal,
+        root: &Path,
+        ignore_patterns: &[String],
+        format: OutputFormat,
+    ) -> Result<Self> {
+        let root = root
+            .canonicalize()
+            .with_context(|| format!("Failed to resolve {}", root.display()))?;
+
+        let vfs = Arc::new(VirtualFileSystem::new(storage.clone()));
+        let parser = Arc::new(tokio::sync::Mutex::new(CodeParser::new()?));
+        let semantic_memory = Arc::new(SemanticMemorySystem::new(storage));
+
+        Ok(Self {
+            workspace_id: *journal.workspace_id(),
+            ignore: IgnoreMatcher::new(&root, ignore_patterns)?,
+            root,
+            pipeline: FileIngestionPipeline::new(parser, vfs, semantic_memory).with_journal(journal),
+            format,
+        })
+    }
+
+    /// Run until Ctrl-C. The batch being processed when the signal arrives is
+    /// finished, and any batches already queued by the watcher are drained
+    /// before returning.
+    pub async fn run(&self, debounce: Duration) -> Result<WatchSummary> {
+        let config = WatcherConfig {
+            debounce_duration: debounce,
+            ..Default::default()
+        };
+        let mut watcher = FileWatcher::with_config(&self.root, config)
+            .context("Failed to start file watcher")?;
+
+        if self.format != OutputFormat::Json {
+            output::info(format!(
+                "Watching {} for changes (Ctrl-C to stop)",
+                self.root.display()
+            ));
+        }
+
+        let mut summary = WatchSummary::default();
+        let shutdown = tokio::signal::ctrl_c();
+        tokio::pin!(shutdown);
+
+        loop {
+            tokio::select! {
+                _ = &mut shutdown => break,
+                batch = watcher.recv() => match batch {
+                    Some(events) => self.process_batch(events, &mut summary).await,
+                    None => break,
+                },
+            }
+        }
+
+        // Flush work the watcher had already queued
+        while let Some(events) = watcher.try_recv() {
+            self.process_batch(events, &mut summary).await;
+        }
+
+        Ok(summary)
+    }
+
+    async fn process_batch(&self, events: Vec<FileEvent>, summary: &mut WatchSummary) {
+        for event in events {
+            let records = match event {
+                FileEvent::Created(path) | FileEvent::Modified(path) => {
+                    self.handle_changed(&path).await.into_iter().collect::<Vec<_>>()
+                }
+                FileEvent::Deleted(path) => self.handle_deleted(&path).await.into_iter().collect(),
+                FileEvent::Renamed { from, to } => {
+                    let mut records: Vec<_> = self.handle_deleted(&from).await.into_iter().collect();
+                    records.extend(self.handle_changed(&to).await);
+                    records
+                }
+            };
+
+            for record in records {
+                summary.record(&record);
+                record.print(self.format);
+            }
+        }
+    }
+
+    /// Re-ingest a created or modified file. Returns `None` when the event is
+    /// ignored or the content did not change.
+    async fn handle_changed(&self, path: &Path) -> Option<WatchEventRecord> {
+        if !path.is_file() || self.ignore.is_ignored(path, false) {
+            return None;
+        }
+        let virtual_path = VirtualPath::from_physical(path, &self.root).ok()?;
+
+        if let Ok(metadata) = tokio::fs::metadata(path).await {
+            if metadata.len() > MAX_FILE_SIZE_BYTES {
+                let mut record = WatchEventRecord::new(WatchAction::Failed, &virtual_path);
+                record.error = Some(format!("file exceeds {} limit", output::format_bytes(MAX_FILE_SIZE_BYTES)));
+                return Some(record);
+            }
+        }
+
+        match self
+            .pipeline
+            .sync_file_from_disk(&self.workspace_id, path, &virtual_path)
+            .await
+        {
+            Ok(Some(result)) => {
+                let mut record = WatchEventRecord::new(WatchAction::Ingested, &virtual_path);
+                record.units = Some(result.units_stored);
+                record.duration_ms = Some(result.duration_ms);
+                record.error = result.errors.first().cloned();
+                Some(record)
+            }
+            Ok(None) => None,
+            Err(e) => {
+                let mut record = WatchEventRecord::new(WatchAction::Failed, &virtual_path);
+                record.error = Some(e.to_string());
+                Some(record)
+            }
+        }
+    }
+
+    /// Remove a deleted file (or directory) and its code units
+    async fn handle_deleted(&self, path: &Path) -> Option<WatchEventRecord> {
+        if self.ignore.is_ignored(path, false) {
+            return None;
+        }
+        let virtual_path = VirtualPath::from_physical(path, &self.root).ok()?;
+
+        match self.pipeline.remove_path(&self.workspace_id, &virtual_path).await {
+            Ok(units) => {
+                let mut record = WatchEventRecord::new(WatchAction::Removed, &virtual_path);
+                record.units = Some(units);
+                Some(record)
+            }
+            Err(e) => {
+                let mut record = WatchEventRecord::new(WatchAction::Failed, &virtual_path);
+                record.error = Some(e.to_string());
+                Some(record)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ignore_matcher_defaults_and_globs() {
+        let root = Path::new("/project");
+        let matcher = IgnoreMatcher::new(root, &["*.log".to_string(), "generated/".to_string()]).unwrap();
+
+        assert!(matcher.is_ignored(Path::new("/project/target/debug/main.rs"), false));
+        assert!(matcher.is_ignored(Path::new("/project/src/node_modules/x.js"), false));
+        assert!(matcher.is_ignored(Path::new("/project/logs/app.log"), false));
+        assert!(matcher.is_ignored(Path::new("/project/generated/api.rs"), false));
+        assert!(!matcher.is_ignored(Path::new("/project/src/lib.rs"), false));
+        assert!(!matcher.is_ignored(Path::new("/project/src/targeting.rs"), false));
+    }
+
+    #[test]
+    fn test_ignore_matcher_rejects_outside_root() {
+        let matcher = IgnoreMatcher::new(Path::new("/project"), &[]).unwrap();
+        assert!(matcher.is_ignored(Path::new("/other/src/lib.rs"), false));
+    }
+
+    #[test]
+    fn test_event_record_json_line() {
+        let path = VirtualPath::new("src/lib.rs").unwrap();
+        let mut record = WatchEventRecord::new(WatchAction::Removed, &path);
+        record.units = Some(3);
+
+        let json = serde_json::to_value(&record).unwrap();
+        assert_eq!(json["action"], "removed");
+        assert_eq!(json["path"], "src/lib.rs");
+        assert_eq!(json["units"], 3);
+        assert!(json.get("error").is_none());
+    }
+}
@@ -10,7 +10,14 @@ use anyhow::{Context, Result};
 use console::{style, Term};
 use dialoguer::{theme::ColorfulTheme, Confirm, Input, MultiSelect, Select};
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use crate::services::search::{
+    HybridSearchRequest, SearchCodeRequest, SearchResult, SearchSimilarRequest, TextSearchRequest,
+};
+use crate::services::workspace::ListWorkspaceFilters;
+use crate::services::{SearchService, WorkspaceService};
+use cortex_storage::ConnectionManager;
 use std::fmt::Display;
+use std::sync::Arc;
 use std::time::Duration;
 
 /// Interactive mode state
@@ -305,63 +312,662 @@ pub struct IngestionConfig {
     pub ignore_patterns: Vec<String>,
 }
 
-/// Interactive search interface
-pub async fn interactive_search() -> Result<()> {
-    use crate::config::CortexConfig;
-    use cortex_semantic::SemanticSearchEngine;
+/// Search modes of the interactive search REPL
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchMode {
+    /// Embedding similarity
+    Semantic,
+    /// Literal matches on code unit names, signatures and summaries
+    Pattern,
+    /// Semantic and pattern matches merged by rank
+    Hybrid,
+}
 
-    let session = InteractiveSession::new();
+impl SearchMode {
+    /// The mode `:mode` switches to next
+    pub fn next(self) -> Self {
+        match self {
+            SearchMode::Semantic => SearchMode::Pattern,
+            SearchMode::Pattern => SearchMode::Hybrid,
+            SearchMode::Hybrid => SearchMode::Semantic,
+        }
+    }
+}
 
-    session.clear()?;
-    session.banner("Interactive Search")?;
+impl std::str::FromStr for SearchMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "semantic" => Ok(SearchMode::Semantic),
+            "pattern" => Ok(SearchMode::Pattern),
+            "hybrid" => Ok(SearchMode::Hybrid),
+            other => Err(format!(
+                "Unknown mode '{}' (expected semantic, pattern or hybrid)",
+                other
+            )),
+        }
+    }
+}
 
-    // Initialize semantic search engine
-    let config = CortexConfig::load()?;
-    let search_engine = SemanticSearchEngine::new(
-        cortex_semantic::config::SemanticConfig::default(),
-    ).await?;
+impl Display for SearchMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            SearchMode::Semantic => "semantic",
+            SearchMode::Pattern => "pattern",
+            SearchMode::Hybrid => "hybrid",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Sticky filters applied to every search of the REPL
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchFilters {
+    /// Workspace as given by the user, by name or id
+    pub workspace: Option<String>,
+    pub language: Option<String>,
+}
+
+impl SearchFilters {
+    /// Apply `key=value` assignments; an empty value clears that filter
+    pub fn apply(&mut self, assignments: &[(String, String)]) -> std::result::Result<(), String> {
+        for (key, value) in assignments {
+            let value = (!value.is_empty()).then(|| value.clone());
+            match key.as_str() {
+                "workspace" | "ws" => self.workspace = value,
+                "lang" | "language" => self.language = value,
+                other => {
+                    return Err(format!(
+                        "Unknown filter '{}' (expected workspace or lang)",
+                        other
+                    ))
+                }
+            }
+        }
+        Ok(())
+    }
 
-    loop {
-        let query: String = session.input("Search query (or 'quit' to exit)")?;
+    pub fn is_empty(&self) -> bool {
+        self.workspace.is_none() && self.language.is_none()
+    }
+}
 
-        if query.trim().to_lowercase() == "quit" {
-            break;
+impl Display for SearchFilters {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(workspace) = &self.workspace {
+            parts.push(format!("workspace={}", workspace));
+        }
+        if let Some(language) = &self.language {
+            parts.push(format!("lang={}", language));
+        }
+        if parts.is_empty() {
+            f.write_str("none")
+        } else {
+            f.write_str(&parts.join(" "))
         }
+    }
+}
 
-        // Perform search
-        let spinner = session.spinner("Searching...");
+/// A line entered at the search REPL prompt
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplCommand {
+    Search(String),
+    /// `:filter` shows the filters, `:filter clear` removes them
+    Filter(Vec<(String, String)>),
+    ClearFilters,
+    More,
+    Open(usize),
+    Copy(usize),
+    Similar(usize),
+    /// `:mode` without a mode cycles through the modes
+    Mode(Option<SearchMode>),
+    History,
+    Help,
+    Quit,
+}
 
-        match search_engine.search(&query, 10).await {
-            Ok(results) => {
-                spinner.finish_with_message(format!("Found {} results for '{}'", results.len(), query));
+impl ReplCommand {
+    /// Parse a prompt line. Result numbers are 1-based, as displayed.
+    pub fn parse(line: &str) -> std::result::Result<Option<Self>, String> {
+        let line = line.trim();
+        if line.is_empty() {
+            return Ok(None);
+        }
+        if line == "quit" || line == "exit" {
+            return Ok(Some(ReplCommand::Quit));
+        }
+        let Some(command) = line.strip_prefix(':') else {
+            return Ok(Some(ReplCommand::Search(line.to_string())));
+        };
+
+        let mut words = command.split_whitespace();
+        let name = words.next().unwrap_or_default();
+        let args: Vec<&str> = words.collect();
+
+        let index = |args: &[&str]| -> std::result::Result<usize, String> {
+            match args {
+                [n] => n
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|n| *n > 0)
+                    .ok_or_else(|| format!("Invalid result number '{}'", n)),
+                _ => Err(format!("Usage: :{} <result number>", name)),
+            }
+        };
+
+        let command = match name {
+            "filter" => match args.as_slice() {
+                ["clear"] => ReplCommand::ClearFilters,
+                _ => ReplCommand::Filter(
+                    args.iter()
+                        .map(|arg| {
+                            arg.split_once('=')
+                                .map(|(key, value)| (key.to_lowercase(), value.to_string()))
+                                .ok_or_else(|| format!("Expected key=value, got '{}'", arg))
+                        })
+                        .collect::<std::result::Result<_, _>>()?,
+                ),
+            },
+            "more" | "m" => ReplCommand::More,
+            "open" | "o" => ReplCommand::Open(index(&args)?),
+            "copy" | "c" => ReplCommand::Copy(index(&args)?),
+            "similar" | "s" => ReplCommand::Similar(index(&args)?),
+            "mode" => match args.as_slice() {
+                [] => ReplCommand::Mode(None),
+                [mode] => ReplCommand::Mode(Some(mode.parse()?)),
+                _ => return Err("Usage: :mode [semantic|pattern|hybrid]".to_string()),
+            },
+            "history" => ReplCommand::History,
+            "help" | "h" | "?" => ReplCommand::Help,
+            "quit" | "q" | "exit" => ReplCommand::Quit,
+            other => return Err(format!("Unknown command ':{}' (try :help)", other)),
+        };
+        Ok(Some(command))
+    }
+}
 
-                if results.is_empty() {
-                    println!("\nNo results found. Try a different query.");
-                } else {
-                    println!("\nResults:");
-                    for (i, result) in results.iter().enumerate() {
-                        println!("\n{}. Score: {:.3}", i + 1, result.score);
-                        println!("   ID: {}", result.id);
-                        let content = &result.content;
-                        let preview = if content.len() > 100 {
-                            format!("{}...", &content[..100])
-                        } else {
-                            content.clone()
-                        };
-                        println!("   Preview: {}", preview);
-                    }
+/// Query history of the search REPL, persisted one entry per line
+pub struct SearchHistory {
+    path: Option<std::path::PathBuf>,
+    entries: Vec<String>,
+    max_entries: usize,
+}
+
+impl SearchHistory {
+    /// Default number of entries kept
+    pub const DEFAULT_MAX_ENTRIES: usize = 500;
+
+    /// Default history file in the cortex home directory
+    pub fn default_path() -> Result<std::path::PathBuf> {
+        Ok(cortex_core::config::GlobalConfig::cortex_dir()?.join("search_history"))
+    }
+
+    /// Load history from a file; a missing file starts an empty history
+    pub fn load(path: std::path::PathBuf) -> Self {
+        let entries = std::fs::read_to_string(&path)
+            .map(|content| content.lines().map(str::to_string).collect())
+            .unwrap_or_default();
+        let mut history = Self {
+            path: Some(path),
+            entries,
+            max_entries: Self::DEFAULT_MAX_ENTRIES,
+        };
+        history.truncate();
+        history
+    }
+
+    /// History that is not persisted
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            entries: Vec::new(),
+            max_entries: Self::DEFAULT_MAX_ENTRIES,
+        }
+    }
+
+    /// Entries, oldest first
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    /// Record an entry; repeating the latest entry is a no-op
+    pub fn push(&mut self, entry: &str) {
+        let entry = entry.trim();
+        if entry.is_empty() || self.entries.last().is_some_and(|last| last == entry) {
+            return;
+        }
+        self.entries.push(entry.to_string());
+        self.truncate();
+
+        if let Err(e) = self.save() {
+            tracing::warn!("Failed to save search history: {}", e);
+        }
+    }
+
+    fn truncate(&mut self) {
+        let excess = self.entries.len().saturating_sub(self.max_entries);
+        self.entries.drain(..excess);
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut content = self.entries.join("\n");
+        content.push('\n');
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+impl dialoguer::History<String> for SearchHistory {
+    fn read(&self, pos: usize) -> Option<String> {
+        self.entries.iter().rev().nth(pos).cloned()
+    }
+
+    fn write(&mut self, val: &String) {
+        self.push(val);
+    }
+}
+
+/// What the results on screen were searched for
+enum SearchSource {
+    Query(String),
+    SimilarTo(SearchResult),
+}
+
+/// Interactive search REPL over the shared [`SearchService`]
+pub struct SearchRepl {
+    session: InteractiveSession,
+    search: SearchService,
+    workspaces: WorkspaceService,
+    history: SearchHistory,
+    mode: SearchMode,
+    filters: SearchFilters,
+    page_size: usize,
+    source: Option<SearchSource>,
+    results: Vec<SearchResult>,
+    shown: usize,
+}
+
+impl SearchRepl {
+    /// Results shown per page
+    pub const PAGE_SIZE: usize = 10;
+
+    /// Minimum similarity of semantic results
+    const MIN_SIMILARITY: f32 = 0.5;
+
+    pub fn new(storage: Arc<ConnectionManager>, history: SearchHistory) -> Self {
+        let vfs = Arc::new(cortex_vfs::VirtualFileSystem::new(storage.clone()));
+        Self {
+            session: InteractiveSession::new(),
+            search: SearchService::new(storage.clone()),
+            workspaces: WorkspaceService::new(storage, vfs),
+            history,
+            mode: SearchMode::Semantic,
+            filters: SearchFilters::default(),
+            page_size: Self::PAGE_SIZE,
+            source: None,
+            results: Vec::new(),
+            shown: 0,
+        }
+    }
+
+    /// Read and run commands until the user quits
+    pub async fn run(&mut self) -> Result<()> {
+        self.print_help();
+
+        loop {
+            let prompt = format!("search [{}]", self.mode);
+            let line: String = Input::with_theme(&self.session.theme)
+                .with_prompt(prompt)
+                .allow_empty(true)
+                .history_with(&mut self.history)
+                .interact_text()
+                .context("Failed to get user input")?;
+
+            let command = match ReplCommand::parse(&line) {
+                Ok(Some(command)) => command,
+                Ok(None) => continue,
+                Err(e) => {
+                    println!("{}", style(e).red());
+                    continue;
+                }
+            };
+
+            if command == ReplCommand::Quit {
+                break;
+            }
+            if let Err(e) = self.execute(command).await {
+                println!("{} {}", style("Error:").red().bold(), e);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn execute(&mut self, command: ReplCommand) -> Result<()> {
+        match command {
+            ReplCommand::Search(query) => {
+                self.source = Some(SearchSource::Query(query));
+                self.refresh().await?;
+            }
+            ReplCommand::Filter(assignments) if assignments.is_empty() => {
+                println!("Filters: {}", style(&self.filters).cyan());
+            }
+            ReplCommand::Filter(assignments) => {
+                self.filters.apply(&assignments).map_err(anyhow::Error::msg)?;
+                println!("Filters: {}", style(&self.filters).cyan());
+            }
+            ReplCommand::ClearFilters => {
+                self.filters = SearchFilters::default();
+                println!("Filters cleared");
+            }
+            ReplCommand::More => self.more().await?,
+            ReplCommand::Open(n) => {
+                let result = self.result(n)?.clone();
+                let content = self.search.result_content(&result).await?;
+                println!();
+                println!("{}", style(&result.title).bold());
+                if let Some(path) = &result.file_path {
+                    println!("{}", style(path).dim());
+                }
+                println!();
+                println!("{}", content);
+                println!();
+            }
+            ReplCommand::Copy(n) => {
+                let result = self.result(n)?;
+                let path = result
+                    .file_path
+                    .clone()
+                    .ok_or_else(|| anyhow::anyhow!("Result {} has no source path", n))?;
+                copy_to_clipboard(&path)?;
+                println!("Copied {}", style(path).cyan());
+            }
+            ReplCommand::Similar(n) => {
+                let result = self.result(n)?.clone();
+                println!("Searching for results similar to {}", style(&result.title).cyan());
+                self.source = Some(SearchSource::SimilarTo(result));
+                self.refresh().await?;
+            }
+            ReplCommand::Mode(mode) => {
+                self.mode = mode.unwrap_or_else(|| self.mode.next());
+                println!("Search mode: {}", style(self.mode).cyan());
+                if matches!(self.source, Some(SearchSource::Query(_))) {
+                    self.refresh().await?;
                 }
             }
-            Err(e) => {
-                spinner.finish_with_message("Search failed");
-                println!("\nError: {}", e);
+            ReplCommand::History => {
+                let entries = self.history.entries();
+                for (i, entry) in entries.iter().enumerate().skip(entries.len().saturating_sub(20)) {
+                    println!("{:>4}  {}", i + 1, entry);
+                }
+            }
+            ReplCommand::Help => self.print_help(),
+            ReplCommand::Quit => {}
+        }
+        Ok(())
+    }
+
+    /// Rerun the current search from its first page
+    async fn refresh(&mut self) -> Result<()> {
+        self.results.clear();
+        self.shown = 0;
+        self.more().await
+    }
+
+    /// Show the next page of the current search
+    async fn more(&mut self) -> Result<()> {
+        if self.source.is_none() {
+            anyhow::bail!("Nothing to page through; enter a search query first");
+        }
+
+        // Ranked retrieval cannot skip ahead, so fetch everything up to the
+        // end of the next page, as the REST API does
+        let spinner = self.session.spinner("Searching...");
+        let fetched = self.fetch(self.shown + self.page_size + 1).await;
+        spinner.finish_and_clear();
+        self.results = fetched?;
+
+        let page: Vec<&SearchResult> = self.results.iter().skip(self.shown).take(self.page_size).collect();
+        if page.is_empty() {
+            println!("{}", if self.shown == 0 { "No results found." } else { "No more results." });
+            return Ok(());
+        }
+
+        let terms = match &self.source {
+            Some(SearchSource::Query(query)) => query.clone(),
+            _ => String::new(),
+        };
+        println!();
+        for (i, result) in page.iter().enumerate() {
+            print_result(self.shown + i + 1, result, &terms);
+        }
+        self.shown += page.len();
+
+        if self.results.len() > self.shown {
+            println!("{}", style("More results available: :more").dim());
+        }
+        println!();
+        Ok(())
+    }
+
+    async fn fetch(&self, limit: usize) -> Result<Vec<SearchResult>> {
+        let workspace_id = self.workspace_id().await?;
+        let language = self.filters.language.clone();
+
+        match &self.source {
+            Some(SearchSource::Query(query)) => match self.mode {
+                SearchMode::Semantic => {
+                    self.search
+                        .search_code(SearchCodeRequest {
+                            query: query.clone(),
+                            limit,
+                            min_similarity: Self::MIN_SIMILARITY,
+                            language,
+                            workspace_id,
+                            filter: None,
+                        })
+                        .await
+                }
+                SearchMode::Pattern => {
+                    self.search
+                        .search_text(TextSearchRequest {
+                            query: query.clone(),
+                            search_type: "code_units".to_string(),
+                            limit,
+                            language,
+                            workspace_id,
+                        })
+                        .await
+                }
+                SearchMode::Hybrid => {
+                    self.search
+                        .search_hybrid(HybridSearchRequest {
+                            query: query.clone(),
+                            limit,
+                            min_similarity: Self::MIN_SIMILARITY,
+                            language,
+                            workspace_id,
+                        })
+                        .await
+                }
+            },
+            Some(SearchSource::SimilarTo(result)) => {
+                self.search
+                    .search_similar(SearchSimilarRequest {
+                        reference_unit_id: result.id.clone(),
+                        similarity_threshold: Self::MIN_SIMILARITY,
+                        limit,
+                    })
+                    .await
             }
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Id of the filtered workspace, looked up by name unless given as an id
+    async fn workspace_id(&self) -> Result<Option<String>> {
+        let Some(workspace) = &self.filters.workspace else {
+            return Ok(None);
+        };
+        if uuid::Uuid::parse_str(workspace).is_ok() {
+            return Ok(Some(workspace.clone()));
+        }
+
+        self.workspaces
+            .list_workspaces(ListWorkspaceFilters::default())
+            .await?
+            .into_iter()
+            .find(|details| &details.name == workspace)
+            .map(|details| Some(details.id))
+            .ok_or_else(|| anyhow::anyhow!("Workspace '{}' not found", workspace))
+    }
+
+    fn result(&self, n: usize) -> Result<&SearchResult> {
+        self.results
+            .get(n - 1)
+            .filter(|_| n <= self.shown)
+            .ok_or_else(|| anyhow::anyhow!("No result {} on screen", n))
+    }
+
+    fn print_help(&self) {
+        println!("Enter a query to search, or a command:");
+        for (command, description) in [
+            (":filter key=value ...", "Set sticky filters (workspace, lang); empty value clears"),
+            (":filter [clear]", "Show or clear filters"),
+            (":more", "Show the next page of results"),
+            (":open N", "Print the full content of result N"),
+            (":copy N", "Copy the source path of result N to the clipboard"),
+            (":similar N", "Search for results similar to result N"),
+            (":mode [semantic|pattern|hybrid]", "Switch search mode"),
+            (":history", "Show recent queries"),
+            (":quit", "Leave the search"),
+        ] {
+            println!("  {:<34} {}", style(command).cyan(), style(description).dim());
+        }
+        println!();
+    }
+}
+
+/// Print a result with its score, source path and a highlighted snippet
+fn print_result(n: usize, result: &SearchResult, query: &str) {
+    println!(
+        "{:>3}. {} {}",
+        n,
+        style(format!("{:.3}", result.score)).green(),
+        style(&result.title).bold()
+    );
+    if let Some(path) = &result.file_path {
+        println!("     {}", style(path).dim());
+    }
+    let snippet = snippet(&result.content, query, 160);
+    if !snippet.is_empty() {
+        println!("     {}", highlight(&snippet, query));
+    }
+}
+
+/// Query terms worth highlighting
+fn query_terms(query: &str) -> Vec<String> {
+    query
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|term| term.len() > 1)
+        .map(str::to_ascii_lowercase)
+        .collect()
+}
+
+/// A single-line excerpt of at most `max_chars` characters, starting a
+/// little before the first query term it contains
+fn snippet(content: &str, query: &str, max_chars: usize) -> String {
+    let text = content.split_whitespace().collect::<Vec<_>>().join(" ");
+    let lower = text.to_ascii_lowercase();
+
+    let first_match = query_terms(query)
+        .iter()
+        .filter_map(|term| lower.find(term.as_str()))
+        .min()
+        .map(|byte| text[..byte].chars().count())
+        .unwrap_or(0);
+    let start = first_match.saturating_sub(max_chars / 4);
+
+    let excerpt: String = text.chars().skip(start).take(max_chars).collect();
+    let prefix = if start > 0 { "..." } else { "" };
+    let suffix = if start + max_chars < text.chars().count() { "..." } else { "" };
+    format!("{}{}{}", prefix, excerpt, suffix)
+}
+
+/// Highlight case-insensitive occurrences of the query terms
+fn highlight(text: &str, query: &str) -> String {
+    let terms = query_terms(query);
+    if text.is_empty() || terms.is_empty() {
+        return text.to_string();
+    }
+    // ASCII lowercasing keeps byte offsets aligned with the original text
+    let lower = text.to_ascii_lowercase();
+
+    let mut marked = vec![false; text.len()];
+    for term in &terms {
+        for (start, _) in lower.match_indices(term.as_str()) {
+            marked[start..start + term.len()].fill(true);
         }
     }
 
+    let mut highlighted = String::with_capacity(text.len());
+    let mut segment_start = 0;
+    for (i, _) in text.char_indices().skip(1).chain([(text.len(), ' ')]) {
+        if i < text.len() && marked[i] == marked[segment_start] {
+            continue;
+        }
+        let segment = &text[segment_start..i];
+        if marked[segment_start] {
+            highlighted.push_str(&style(segment).yellow().bold().to_string());
+        } else {
+            highlighted.push_str(segment);
+        }
+        segment_start = i;
+    }
+    highlighted
+}
+
+/// Put text on the clipboard with the OSC 52 terminal escape sequence,
+/// which works locally and over SSH in terminals that support it
+fn copy_to_clipboard(text: &str) -> Result<()> {
+    use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+    use std::io::Write;
+
+    let mut stdout = std::io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", BASE64.encode(text))?;
+    stdout.flush()?;
     Ok(())
 }
 
+/// Interactive search interface
+pub async fn interactive_search() -> Result<()> {
+    use crate::config::CortexConfig;
+
+    let session = InteractiveSession::new();
+    session.clear()?;
+    session.banner("Interactive Search")?;
+
+    let config = CortexConfig::load()?;
+    let storage = crate::commands::create_storage(&config).await?;
+    let history = match SearchHistory::default_path() {
+        Ok(path) => SearchHistory::load(path),
+        Err(e) => {
+            tracing::warn!("Search history will not be saved: {}", e);
+            SearchHistory::in_memory()
+        }
+    };
+
+    SearchRepl::new(storage, history).run().await
+}
+
 /// Display a multi-step progress workflow
 pub struct WorkflowProgress {
     session: InteractiveSession,
@@ -557,4 +1163,94 @@ mod tests {
         assert_eq!(menu.items.len(), 2);
         assert_eq!(menu.items[0].label, "Option 1");
     }
+
+    #[test]
+    fn test_repl_command_parsing() {
+        assert_eq!(ReplCommand::parse("  ").unwrap(), None);
+        assert_eq!(
+            ReplCommand::parse("parse config").unwrap(),
+            Some(ReplCommand::Search("parse config".to_string()))
+        );
+        assert_eq!(
+            ReplCommand::parse(":filter workspace=foo lang=rust").unwrap(),
+            Some(ReplCommand::Filter(vec![
+                ("workspace".to_string(), "foo".to_string()),
+                ("lang".to_string(), "rust".to_string()),
+            ]))
+        );
+        assert_eq!(ReplCommand::parse(":filter clear").unwrap(), Some(ReplCommand::ClearFilters));
+        assert_eq!(ReplCommand::parse(":more").unwrap(), Some(ReplCommand::More));
+        assert_eq!(ReplCommand::parse(":open 3").unwrap(), Some(ReplCommand::Open(3)));
+        assert_eq!(ReplCommand::parse(":copy 1").unwrap(), Some(ReplCommand::Copy(1)));
+        assert_eq!(ReplCommand::parse(":similar 2").unwrap(), Some(ReplCommand::Similar(2)));
+        assert_eq!(
+            ReplCommand::parse(":mode hybrid").unwrap(),
+            Some(ReplCommand::Mode(Some(SearchMode::Hybrid)))
+        );
+        assert_eq!(ReplCommand::parse(":mode").unwrap(), Some(ReplCommand::Mode(None)));
+        assert_eq!(ReplCommand::parse("quit").unwrap(), Some(ReplCommand::Quit));
+
+        assert!(ReplCommand::parse(":open 0").is_err());
+        assert!(ReplCommand::parse(":open").is_err());
+        assert!(ReplCommand::parse(":filter workspace").is_err());
+        assert!(ReplCommand::parse(":mode fuzzy").is_err());
+        assert!(ReplCommand::parse(":bogus").is_err());
+    }
+
+    #[test]
+    fn test_search_filters_are_sticky() {
+        let mut filters = SearchFilters::default();
+        filters
+            .apply(&[("workspace".to_string(), "foo".to_string())])
+            .unwrap();
+        filters.apply(&[("lang".to_string(), "rust".to_string())]).unwrap();
+        assert_eq!(filters.to_string(), "workspace=foo lang=rust");
+
+        filters.apply(&[("workspace".to_string(), String::new())]).unwrap();
+        assert_eq!(filters.workspace, None);
+        assert_eq!(filters.language.as_deref(), Some("rust"));
+
+        assert!(filters.apply(&[("owner".to_string(), "me".to_string())]).is_err());
+    }
+
+    #[test]
+    fn test_search_mode_cycles() {
+        assert_eq!(SearchMode::Semantic.next(), SearchMode::Pattern);
+        assert_eq!(SearchMode::Pattern.next(), SearchMode::Hybrid);
+        assert_eq!(SearchMode::Hybrid.next(), SearchMode::Semantic);
+    }
+
+    #[test]
+    fn test_search_history_persists() {
+        use dialoguer::History;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cortex").join("search_history");
+
+        let mut history = SearchHistory::load(path.clone());
+        history.write(&"first query".to_string());
+        history.write(&"second query".to_string());
+        history.write(&"second query".to_string());
+        assert_eq!(history.read(0).as_deref(), Some("second query"));
+        assert_eq!(history.read(1).as_deref(), Some("first query"));
+        assert_eq!(history.read(2), None);
+
+        let reloaded = SearchHistory::load(path);
+        assert_eq!(reloaded.entries(), ["first query", "second query"]);
+    }
+
+    #[test]
+    fn test_snippet_and_highlight() {
+        let content = format!("{}fn parse_config(path: &Path) -> Config", "// filler\n".repeat(20));
+        let excerpt = snippet(&content, "parse_config", 40);
+        assert!(excerpt.starts_with("..."));
+        assert!(excerpt.contains("fn parse_config"));
+        assert!(!excerpt.contains('\n'));
+
+        assert_eq!(snippet("short text", "missing", 40), "short text");
+
+        let text = "Parse the CONFIG file";
+        let highlighted = highlight(text, "config");
+        assert_eq!(console::strip_ansi_codes(&highlighted), text);
+    }
 }
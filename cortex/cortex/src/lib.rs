@@ -5,6 +5,7 @@ pub mod config;
 pub mod db_manager;
 pub mod doctor;
 pub mod export;
+pub mod ingest_watch;
 pub mod interactive;
 pub mod output;
 pub mod testing;
@@ -12,8 +13,10 @@ pub mod mcp;
 pub mod api;
 pub mod server_manager;
 pub mod qdrant_commands;
+pub mod qdrant_snapshots;
 pub mod services;
 pub mod conversions;
+pub mod version_check;
 
 pub use commands::*;
 pub use config::*;
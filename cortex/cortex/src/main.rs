@@ -9,6 +9,13 @@
 //! # Ingest files
 //! cortex ingest ./src
 //!
+//! # Keep a workspace in sync while editing
+//! cortex ingest ./src --watch --ignore '*.log'
+//!
+//! # Resume an interrupted ingestion, or start over
+//! cortex ingest ./src
+//! cortex ingest ./src --restart
+//!
 //! # Search memory
 //! cortex search "authentication logic"
 //!
@@ -43,6 +50,10 @@ struct Cli {
     /// Output format (human, json, plain)
     #[arg(long, global = true, default_value = "human")]
     format: OutputFormatArg,
+
+    /// Start even if the database schema is incompatible with this build
+    #[arg(long, global = true, env = "CORTEX_SKIP_VERSION_CHECK")]
+    skip_version_check: bool,
 }
 
 #[derive(Clone, Copy, Debug, ValueEnum)]
@@ -98,6 +109,26 @@ enum Commands {
         /// Recursively ingest directories
         #[arg(short, long, default_value = "true")]
         recursive: bool,
+
+        /// Keep watching the path and re-ingest files as they change
+        #[arg(long)]
+        watch: bool,
+
+        /// Gitignore-style glob to exclude (repeatable)
+        #[arg(long = "ignore", value_name = "GLOB")]
+        ignore: Vec<String>,
+
+        /// Quiet period in milliseconds before a change is re-ingested
+        #[arg(long, default_value = "500")]
+        debounce_ms: u64,
+
+        /// Ignore the ingestion journal and ingest every file again
+        #[arg(long)]
+        restart: bool,
+
+        /// Show the ingestion journal of the workspace and exit
+        #[arg(long, conflicts_with_all = ["restart", "watch"])]
+        journal_status: bool,
     },
 
     /// Search across Cortex memory
@@ -193,6 +224,9 @@ enum Commands {
 
         #[arg(long)]
         workers: Option<usize>,
+
+        #[arg(long)]
+        require_auth: bool,
     },
 }
 
@@ -240,6 +274,45 @@ enum WorkspaceCommands {
         #[arg(short, long)]
         confirm: bool,
     },
+
+    /// Take a named snapshot of a workspace
+    Snapshot {
+        /// Snapshot name
+        name: String,
+
+        /// Workspace name or ID (uses default workspace if not specified)
+        #[arg(short, long)]
+        workspace: Option<String>,
+
+        /// Snapshot description
+        #[arg(short, long)]
+        description: Option<String>,
+    },
+
+    /// List the snapshots of a workspace
+    Snapshots {
+        /// Workspace name or ID (uses default workspace if not specified)
+        #[arg(short, long)]
+        workspace: Option<String>,
+    },
+
+    /// Restore a workspace to a snapshot
+    Rollback {
+        /// Snapshot name
+        name: String,
+
+        /// Workspace name or ID (uses default workspace if not specified)
+        #[arg(short, long)]
+        workspace: Option<String>,
+
+        /// Your work session, which does not block the rollback
+        #[arg(long)]
+        session_id: Option<String>,
+
+        /// Roll back even while other sessions are active on the workspace
+        #[arg(long)]
+        force: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -465,6 +538,17 @@ enum CodeCommands {
         #[arg(short, long)]
         workspace: Option<String>,
     },
+
+    /// Check module cycles, layering and forbidden dependencies (fails on violations)
+    ArchitectureCheck {
+        /// JSON rules file: {"forbidden": [{"from": "src/cli/**", "to": "src/storage/internal/**"}]}
+        #[arg(short, long)]
+        rules: Option<PathBuf>,
+
+        /// Number of leading path components naming a module (default: a file's directory)
+        #[arg(long)]
+        module_depth: Option<usize>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -735,6 +819,11 @@ enum ServerCommands {
         /// Number of worker threads
         #[arg(long)]
         workers: Option<usize>,
+
+        /// Require authentication on all routes except health and login
+        /// (always enabled when binding to a non-loopback address)
+        #[arg(long)]
+        require_auth: bool,
     },
 
     /// Stop the REST API server
@@ -742,6 +831,36 @@ enum ServerCommands {
 
     /// Check server status
     Status,
+
+    /// Manage API keys for the REST API
+    #[command(subcommand)]
+    Keys(ServerKeyCommands),
+}
+
+#[derive(Subcommand)]
+enum ServerKeyCommands {
+    /// Create a new API key (the key is shown only once)
+    Create {
+        /// Human-readable key name
+        name: String,
+
+        /// Comma-separated scopes (read, write, admin)
+        #[arg(long, value_delimiter = ',', default_value = "read")]
+        scopes: Vec<String>,
+
+        /// Expire the key after this many days
+        #[arg(long)]
+        expires_in_days: Option<i64>,
+    },
+
+    /// List API keys
+    List,
+
+    /// Revoke an API key
+    Revoke {
+        /// Key ID to revoke
+        key_id: String,
+    },
 }
 
 #[derive(Subcommand)]
@@ -767,7 +886,7 @@ enum TestCommands {
 
     /// Test specific component
     Component {
-        /// Component to test (database, storage, vfs, memory, mcp)
+        /// Component to test (api, services, database, mcp, storage, vfs, memory, parser, search, semantic)
         component: String,
     },
 }
@@ -783,9 +902,21 @@ enum ExportCommands {
         #[arg(short, long)]
         output: std::path::PathBuf,
 
-        /// Export format (json, csv, yaml, markdown)
+        /// Export format (json, jsonl, csv, yaml, markdown)
         #[arg(short, long, default_value = "json")]
         format: String,
+
+        /// Only files modified at or after this date (YYYY-MM-DD or RFC3339)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Only files modified before this date (YYYY-MM-DD or RFC3339)
+        #[arg(long)]
+        until: Option<String>,
+
+        /// Comma-separated fields to keep (e.g. path,size)
+        #[arg(long, value_delimiter = ',')]
+        fields: Option<Vec<String>>,
     },
 
     /// Export memory episodes
@@ -798,13 +929,25 @@ enum ExportCommands {
         #[arg(short, long)]
         output: std::path::PathBuf,
 
-        /// Export format (json, csv, yaml, markdown)
+        /// Export format (json, jsonl, csv, yaml, markdown)
         #[arg(short, long, default_value = "json")]
         format: String,
 
         /// Limit number of episodes
         #[arg(short, long)]
         limit: Option<usize>,
+
+        /// Only episodes created at or after this date (YYYY-MM-DD or RFC3339)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Only episodes created before this date (YYYY-MM-DD or RFC3339)
+        #[arg(long)]
+        until: Option<String>,
+
+        /// Comma-separated fields to keep (e.g. id,task_description,created_at)
+        #[arg(long, value_delimiter = ',')]
+        fields: Option<Vec<String>>,
     },
 
     /// Export system statistics
@@ -928,6 +1071,43 @@ enum QdrantCommands {
         dimensions: usize,
     },
 
+    /// Create collection snapshots, optionally downloaded into a snapshot set
+    Snapshot {
+        /// Collection to snapshot (all if not specified)
+        #[arg(short, long)]
+        collection: Option<String>,
+
+        /// Directory to download snapshot sets into (snapshots stay on the server otherwise)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Skip collections unchanged since the previous snapshot set
+        #[arg(long, requires = "output")]
+        incremental: bool,
+
+        /// Keep only the newest N snapshot sets (plus any they reference)
+        #[arg(long, requires = "output", value_parser = clap::value_parser!(u64).range(1..))]
+        keep_last: Option<u64>,
+
+        /// Upload the snapshot set to S3 (CORTEX_S3_ENDPOINT, CORTEX_S3_BUCKET, AWS credentials)
+        #[arg(long, requires = "output")]
+        upload: bool,
+
+        /// Export the semantic search index into --output as a portable file that restores into any Qdrant deployment
+        #[arg(long, requires = "output", conflicts_with_all = ["collection", "incremental", "keep_last", "upload"])]
+        portable: bool,
+    },
+
+    /// Restore from a snapshot file, a snapshot set, or the latest complete set in a directory
+    Restore {
+        /// Snapshot file, portable index file (.ndjson.gz), snapshot set directory, or snapshot output directory
+        snapshot: PathBuf,
+
+        /// Collection to restore (all in the set if not specified)
+        #[arg(short, long)]
+        collection: Option<String>,
+    },
+
     /// Optimize collection (trigger segment optimization)
     Optimize {
         /// Collection to optimize
@@ -965,6 +1145,7 @@ async fn run() -> Result<()> {
     }
 
     let format = OutputFormat::from(cli.format);
+    cortex::version_check::set_skip_version_check(cli.skip_version_check);
 
     match cli.command {
         Commands::Init {
@@ -990,6 +1171,15 @@ async fn run() -> Result<()> {
             WorkspaceCommands::Delete { workspace_id, confirm } => {
                 commands::workspace_delete(workspace_id, confirm).await?;
             }
+            WorkspaceCommands::Snapshot { name, workspace, description } => {
+                commands::workspace_snapshot(name, workspace, description).await?;
+            }
+            WorkspaceCommands::Snapshots { workspace } => {
+                commands::workspace_snapshots(workspace, format).await?;
+            }
+            WorkspaceCommands::Rollback { name, workspace, session_id, force } => {
+                commands::workspace_rollback(name, workspace, session_id, force).await?;
+            }
         },
 
         Commands::Vfs(vfs_cmd) => match vfs_cmd {
@@ -1032,14 +1222,27 @@ async fn run() -> Result<()> {
             CodeCommands::OptimizeImports { file, remove_unused, sort, group, workspace } => {
                 commands::code_optimize_imports(file, remove_unused, sort, group, workspace).await?;
             }
+            CodeCommands::ArchitectureCheck { rules, module_depth } => {
+                commands::code_architecture_check(rules, module_depth, format).await?;
+            }
         },
 
         Commands::Ingest {
             path,
             workspace,
             recursive,
+            watch,
+            ignore,
+            debounce_ms,
+            restart,
+            journal_status,
         } => {
-            commands::ingest_path(path, workspace, recursive).await?;
+            let watch = watch.then(|| std::time::Duration::from_millis(debounce_ms));
+            let journal = commands::IngestJournalOptions {
+                restart,
+                status: journal_status,
+            };
+            commands::ingest_path(path, workspace, recursive, ignore, watch, journal, format).await?;
         }
 
         Commands::Search {
@@ -1183,6 +1386,9 @@ async fn run() -> Result<()> {
         Commands::Test(test_cmd) => match test_cmd {
             TestCommands::All => {
                 use cortex::testing;
+                if format != OutputFormat::Json {
+                    output::header("Running Cortex System Tests");
+                }
                 let results = testing::run_all_tests().await?;
                 testing::print_test_results(&results, format)?;
 
@@ -1197,6 +1403,9 @@ async fn run() -> Result<()> {
             }
             TestCommands::Component { component } => {
                 use cortex::testing;
+                if format != OutputFormat::Json {
+                    output::header(format!("Running {} Component Tests", component));
+                }
                 let results = testing::run_component_tests(&component).await?;
                 testing::print_test_results(&results, format)?;
 
@@ -1230,6 +1439,8 @@ async fn run() -> Result<()> {
                     validate_on_checkout: true,
                     recycle_after_uses: None,
                     shutdown_grace_period: Duration::from_secs(10),
+                    query_timeout: Some(Duration::from_secs(30)),
+                    slow_query_threshold: Some(Duration::from_secs(1)),
                 },
                 namespace: "cortex".to_string(),
                 database: "main".to_string(),
@@ -1237,17 +1448,33 @@ async fn run() -> Result<()> {
             let storage = Arc::new(cortex_storage::ConnectionManager::new(db_config).await?);
 
             match export_cmd {
-                ExportCommands::Workspace { workspace, output, format: fmt } => {
+                ExportCommands::Workspace { workspace, output, format: fmt, since, until, fields } => {
                     use cortex::export;
                     let export_format = export::ExportFormat::from_extension(&fmt)
                         .unwrap_or(export::ExportFormat::Json);
-                    export::export_workspace(storage, &workspace, &output, export_format).await?;
+                    let options = export::ExportOptions {
+                        since: since.as_deref().map(export::parse_date_bound).transpose()?,
+                        until: until.as_deref().map(export::parse_date_bound).transpose()?,
+                        fields,
+                        limit: None,
+                        progress: format == OutputFormat::Human,
+                    };
+                    let report = export::export_workspace(storage, &workspace, &output, export_format, &options).await?;
+                    report.print("file(s)", format);
                 }
-                ExportCommands::Episodes { workspace, output, format: fmt, limit } => {
+                ExportCommands::Episodes { workspace, output, format: fmt, limit, since, until, fields } => {
                     use cortex::export;
                     let export_format = export::ExportFormat::from_extension(&fmt)
                         .unwrap_or(export::ExportFormat::Json);
-                    export::export_episodes(storage, workspace, &output, export_format, limit).await?;
+                    let options = export::ExportOptions {
+                        since: since.as_deref().map(export::parse_date_bound).transpose()?,
+                        until: until.as_deref().map(export::parse_date_bound).transpose()?,
+                        fields,
+                        limit,
+                        progress: format == OutputFormat::Human,
+                    };
+                    let report = export::export_episodes(storage, workspace, &output, export_format, &options).await?;
+                    report.print("episode(s)", format);
                 }
                 ExportCommands::Stats { output, format: fmt } => {
                     use cortex::export;
@@ -1283,6 +1510,18 @@ async fn run() -> Result<()> {
             QdrantCommands::Benchmark { collection, num_queries, dimensions } => {
                 qdrant_commands::qdrant_benchmark(collection, num_queries, dimensions, format).await?;
             }
+            QdrantCommands::Snapshot { collection, output, incremental, keep_last, upload, portable } => {
+                let options = qdrant_commands::SnapshotOptions {
+                    incremental,
+                    keep_last: keep_last.map(|n| n as usize),
+                    upload,
+                    portable,
+                };
+                qdrant_commands::qdrant_snapshot(collection, output, options).await?;
+            }
+            QdrantCommands::Restore { snapshot, collection } => {
+                qdrant_commands::qdrant_restore(snapshot, collection).await?;
+            }
             QdrantCommands::Optimize { collection, wait } => {
                 qdrant_commands::qdrant_optimize(collection, wait).await?;
             }
@@ -1337,8 +1576,8 @@ async fn run() -> Result<()> {
         },
 
         Commands::Server(server_cmd) => match server_cmd {
-            ServerCommands::Start { host, port, workers } => {
-                commands::server_start(host, port, workers).await?;
+            ServerCommands::Start { host, port, workers, require_auth } => {
+                commands::server_start(host, port, workers, require_auth).await?;
             }
             ServerCommands::Stop => {
                 commands::server_stop().await?;
@@ -1346,11 +1585,22 @@ async fn run() -> Result<()> {
             ServerCommands::Status => {
                 commands::server_status().await?;
             }
+            ServerCommands::Keys(keys_cmd) => match keys_cmd {
+                ServerKeyCommands::Create { name, scopes, expires_in_days } => {
+                    commands::server_keys_create(name, scopes, expires_in_days, format).await?;
+                }
+                ServerKeyCommands::List => {
+                    commands::server_keys_list(format).await?;
+                }
+                ServerKeyCommands::Revoke { key_id } => {
+                    commands::server_keys_revoke(key_id).await?;
+                }
+            },
         },
 
-        Commands::InternalServerRun { host, port, workers } => {
+        Commands::InternalServerRun { host, port, workers, require_auth } => {
             // This is the internal blocking server run command
-            commands::server_run_blocking(host, port, workers).await?;
+            commands::server_run_blocking(host, port, workers, require_auth).await?;
         }
     }
 
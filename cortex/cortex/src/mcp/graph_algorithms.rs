@@ -5,8 +5,13 @@
 //! - Tarjan's algorithm for strongly connected components (cycles)
 //! - Topological sorting for layering
 //! - Centrality measures for hub detection
+//! - Impact analysis over reverse dependency edges
+//! - Architecture conformance: module cycles, layering and forbidden dependencies
 
-use std::collections::{HashMap, HashSet, VecDeque};
+use globset::{GlobBuilder, GlobMatcher};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 
 /// Result of a shortest path search
 #[derive(Debug, Clone)]
@@ -32,6 +37,10 @@ pub struct Graph {
     pub edge_types: HashMap<(String, String), String>,
     /// All nodes in the graph
     pub nodes: HashSet<String>,
+    /// File containing each node, when known
+    pub node_files: HashMap<String, String>,
+    /// Line each node starts at, when known
+    pub node_lines: HashMap<String, usize>,
 }
 
 impl Graph {
@@ -42,9 +51,31 @@ impl Graph {
             reverse_adjacency: HashMap::new(),
             edge_types: HashMap::new(),
             nodes: HashSet::new(),
+            node_files: HashMap::new(),
+            node_lines: HashMap::new(),
         }
     }
 
+    /// Record the file a node is defined in
+    pub fn set_node_file(&mut self, node: String, file: String) {
+        self.node_files.insert(node, file);
+    }
+
+    /// Get the file a node is defined in
+    pub fn node_file(&self, node: &str) -> Option<&str> {
+        self.node_files.get(node).map(|s| s.as_str())
+    }
+
+    /// Record the line a node starts at
+    pub fn set_node_line(&mut self, node: String, line: usize) {
+        self.node_lines.insert(node, line);
+    }
+
+    /// Get the line a node starts at
+    pub fn node_line(&self, node: &str) -> Option<usize> {
+        self.node_lines.get(node).copied()
+    }
+
     /// Add an edge from -> to with optional type
     pub fn add_edge(&mut self, from: String, to: String) {
         self.add_typed_edge(from, to, "DEPENDS_ON".to_string());
@@ -122,6 +153,202 @@ impl Graph {
     pub fn total_degree(&self, node: &str) -> usize {
         self.in_degree(node) + self.out_degree(node)
     }
+
+    /// Compute what is affected by changing `symbol_id`
+    ///
+    /// Walks reverse dependency edges breadth-first, so each affected symbol
+    /// is reported at its shortest distance. When several edges reach a
+    /// symbol at that distance, the strongest edge kind is kept. Each symbol
+    /// is visited once, so cycles terminate.
+    pub fn impact_of(&self, symbol_id: &str, options: &ImpactOptions) -> ImpactReport {
+        let edge_kinds: HashSet<String> = options
+            .edge_kinds
+            .iter()
+            .map(|kind| normalize_edge_kind(kind))
+            .collect();
+
+        let mut visited = HashSet::new();
+        visited.insert(symbol_id.to_string());
+        let mut frontier = vec![symbol_id.to_string()];
+        let mut symbols = Vec::new();
+        let mut depth = 0;
+
+        while !frontier.is_empty() && options.max_depth.is_none_or(|max| depth < max) {
+            depth += 1;
+
+            // Strongest edge reaching each newly discovered node at this depth
+            let mut layer: BTreeMap<&str, (f64, &str)> = BTreeMap::new();
+            for target in &frontier {
+                for source in self.reverse_neighbors(target) {
+                    if visited.contains(source) {
+                        continue;
+                    }
+                    let kind = self.edge_type(source, target).unwrap_or("DEPENDS_ON");
+                    if !edge_kinds.is_empty() && !edge_kinds.contains(&normalize_edge_kind(kind)) {
+                        continue;
+                    }
+                    let weight = edge_kind_weight(kind);
+                    let best = layer.entry(source.as_str()).or_insert((weight, kind));
+                    if weight > best.0 || (weight == best.0 && kind < best.1) {
+                        *best = (weight, kind);
+                    }
+                }
+            }
+
+            let mut next = Vec::with_capacity(layer.len());
+            for (node, (weight, kind)) in layer {
+                visited.insert(node.to_string());
+                let file = self.node_file(node).map(str::to_string);
+                let is_test = is_test_symbol(node, file.as_deref());
+                if is_test && !options.include_tests {
+                    continue;
+                }
+                symbols.push(ImpactedSymbol {
+                    id: node.to_string(),
+                    distance: depth,
+                    edge_kind: kind.to_string(),
+                    weight,
+                    file,
+                    is_test,
+                });
+                next.push(node.to_string());
+            }
+            frontier = next;
+        }
+
+        ImpactReport::new(symbol_id.to_string(), symbols)
+    }
+}
+
+/// Options for [`Graph::impact_of`]
+#[derive(Debug, Clone)]
+pub struct ImpactOptions {
+    /// Maximum distance from the changed symbol (None = unlimited)
+    pub max_depth: Option<usize>,
+    /// Edge kinds to follow, matched case-insensitively (empty = all)
+    pub edge_kinds: Vec<String>,
+    /// Whether to report and traverse test symbols
+    pub include_tests: bool,
+}
+
+impl Default for ImpactOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: None,
+            edge_kinds: Vec::new(),
+            include_tests: true,
+        }
+    }
+}
+
+/// A symbol affected by a change
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImpactedSymbol {
+    /// Node identifier
+    pub id: String,
+    /// Number of reverse edges from the changed symbol
+    pub distance: usize,
+    /// Kind of the edge through which the symbol was reached
+    pub edge_kind: String,
+    /// Blast-radius weight of that edge kind
+    pub weight: f64,
+    /// File containing the symbol, when known
+    pub file: Option<String>,
+    /// Whether the symbol looks like test code
+    pub is_test: bool,
+}
+
+/// Result of an impact analysis, ordered deterministically for diffing
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImpactReport {
+    /// The changed symbol
+    pub root: String,
+    /// Affected symbols, sorted by distance then id
+    pub symbols: Vec<ImpactedSymbol>,
+    /// Affected symbol ids grouped by file (`<unknown>` when not recorded)
+    pub by_file: BTreeMap<String, Vec<String>>,
+    /// Affected symbol ids grouped by distance
+    pub by_distance: BTreeMap<usize, Vec<String>>,
+    /// Sum of edge-kind weights over all affected symbols
+    pub blast_radius: f64,
+}
+
+impl ImpactReport {
+    fn new(root: String, mut symbols: Vec<ImpactedSymbol>) -> Self {
+        symbols.sort_by(|a, b| a.distance.cmp(&b.distance).then_with(|| a.id.cmp(&b.id)));
+
+        let mut by_file: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let mut by_distance: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+        for symbol in &symbols {
+            let file = symbol.file.clone().unwrap_or_else(|| "<unknown>".to_string());
+            by_file.entry(file).or_default().push(symbol.id.clone());
+            by_distance.entry(symbol.distance).or_default().push(symbol.id.clone());
+        }
+        for ids in by_file.values_mut() {
+            ids.sort();
+        }
+
+        let blast_radius = symbols.iter().map(|s| s.weight).sum();
+
+        Self {
+            root,
+            symbols,
+            by_file,
+            by_distance,
+            blast_radius,
+        }
+    }
+
+    /// Number of affected symbols
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    /// Whether nothing is affected
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+}
+
+/// Normalize an edge kind so that `USES_TYPE`, `UsesType` and `uses_type` compare equal
+fn normalize_edge_kind(kind: &str) -> String {
+    kind.chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Blast-radius weight of an edge kind
+///
+/// Direct calls weigh most, then inheritance, data access and type usage;
+/// imports and re-exports weigh least.
+pub fn edge_kind_weight(kind: &str) -> f64 {
+    match normalize_edge_kind(kind).as_str() {
+        "calls" | "invokes" | "instantiates" => 1.0,
+        "extends" | "implements" | "inherits" => 0.8,
+        "reads" | "writes" | "modifies" => 0.7,
+        "usestype" | "usestrait" | "usesinterface" => 0.6,
+        "imports" | "requires" | "includes" | "reexports" => 0.3,
+        _ => 0.5,
+    }
+}
+
+/// Heuristic test detection from the symbol id and its file path
+fn is_test_symbol(id: &str, file: Option<&str>) -> bool {
+    if id.contains("::tests::") || id.contains("::test_") || id.starts_with("test_") {
+        return true;
+    }
+    file.is_some_and(|path| {
+        let path = path.replace('\\', "/");
+        let name = path.rsplit('/').next().unwrap_or(&path);
+        path.contains("/tests/")
+            || path.starts_with("tests/")
+            || path.contains("/__tests__/")
+            || name.starts_with("test_")
+            || name.contains("_test.")
+            || name.contains(".test.")
+            || name.contains(".spec.")
+    })
 }
 
 impl Default for Graph {
@@ -130,6 +357,285 @@ impl Default for Graph {
     }
 }
 
+/// A dependency that must not exist: no file matching `from` may depend on
+/// a file matching `to`
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct ForbiddenDependency {
+    /// Glob for the depending files, e.g. "src/cli/**"
+    pub from: String,
+    /// Glob for the files they must not depend on, e.g. "src/storage/internal/**"
+    pub to: String,
+    /// Why the dependency is forbidden
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// Architecture rules as written in a rules file or tool input
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+pub struct ArchitectureRules {
+    /// Dependencies between path globs that must not exist
+    #[serde(default)]
+    pub forbidden: Vec<ForbiddenDependency>,
+    /// Number of leading path components naming a module (a file's directory if omitted)
+    #[serde(default)]
+    pub module_depth: Option<usize>,
+}
+
+/// Compiled rules an architecture is checked against
+#[derive(Debug, Clone)]
+pub struct RuleSet {
+    forbidden: Vec<(ForbiddenDependency, GlobMatcher, GlobMatcher)>,
+    module_depth: Option<usize>,
+}
+
+impl RuleSet {
+    /// Compile forbidden dependencies. In globs, `*` stays within a path
+    /// segment and `**` crosses segments.
+    pub fn new(forbidden: Vec<ForbiddenDependency>) -> Result<Self, String> {
+        fn matcher(pattern: &str) -> Result<GlobMatcher, String> {
+            GlobBuilder::new(pattern)
+                .literal_separator(true)
+                .build()
+                .map(|glob| glob.compile_matcher())
+                .map_err(|e| format!("Invalid glob '{}': {}", pattern, e))
+        }
+
+        let forbidden = forbidden
+            .into_iter()
+            .map(|rule| {
+                let from = matcher(&rule.from)?;
+                let to = matcher(&rule.to)?;
+                Ok((rule, from, to))
+            })
+            .collect::<Result<_, String>>()?;
+
+        Ok(Self {
+            forbidden,
+            module_depth: None,
+        })
+    }
+
+    /// Compile rules read from a rules file or tool input
+    pub fn from_rules(rules: ArchitectureRules) -> Result<Self, String> {
+        let rule_set = Self::new(rules.forbidden)?;
+        Ok(match rules.module_depth {
+            Some(depth) => rule_set.with_module_depth(depth),
+            None => rule_set,
+        })
+    }
+
+    /// Name modules by the first `depth` components of their files' paths,
+    /// instead of by their files' directories
+    pub fn with_module_depth(mut self, depth: usize) -> Self {
+        self.module_depth = Some(depth.max(1));
+        self
+    }
+
+    /// Module a file belongs to
+    pub fn module_of(&self, file: &str) -> String {
+        let file = file.replace('\\', "/");
+        let components: Vec<&str> = file.split('/').filter(|c| !c.is_empty()).collect();
+        let len = match self.module_depth {
+            Some(depth) => depth.min(components.len().saturating_sub(1)).max(1),
+            None => components.len().saturating_sub(1).max(1),
+        };
+        components[..len.min(components.len())].join("/")
+    }
+}
+
+/// A symbol-level dependency, with the files at both ends
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DependencyEdge {
+    pub from: String,
+    pub to: String,
+    pub from_file: String,
+    pub to_file: String,
+    /// Line of the depending symbol, when known
+    pub line: Option<usize>,
+    pub kind: String,
+}
+
+/// Modules that depend on each other in a cycle
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModuleCycle {
+    pub modules: Vec<String>,
+    /// Files at either end of the edges forming the cycle
+    pub files: Vec<String>,
+    /// Symbol dependencies between the modules of the cycle
+    pub edges: Vec<DependencyEdge>,
+}
+
+/// Layer of a module: 0 for modules without dependencies, otherwise one
+/// more than the highest layer it depends on. Modules in a cycle share a layer.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModuleLayer {
+    pub module: String,
+    pub layer: usize,
+}
+
+/// A dependency breaking a rule
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RuleViolation {
+    /// The rule, as `from -> to`
+    pub rule: String,
+    pub description: Option<String>,
+    pub edge: DependencyEdge,
+}
+
+/// Result of [`analyze_architecture`], ordered deterministically for diffing
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ArchitectureReport {
+    /// Whether there are no cycles and no violations
+    pub passed: bool,
+    pub modules: usize,
+    pub cycles: Vec<ModuleCycle>,
+    /// Modules sorted by layer then name
+    pub layers: Vec<ModuleLayer>,
+    pub violations: Vec<RuleViolation>,
+}
+
+/// Check a symbol graph against architecture rules
+///
+/// Symbol edges are collapsed into a module graph, in which cycles are found
+/// as strongly connected components and layers are assigned over the
+/// condensed, acyclic graph. Forbidden dependencies are checked per symbol
+/// edge. Symbols without a known file are ignored.
+pub fn analyze_architecture(graph: &Graph, rules: &RuleSet) -> ArchitectureReport {
+    let mut edges: Vec<DependencyEdge> = graph
+        .edge_types
+        .iter()
+        .filter_map(|((from, to), kind)| {
+            Some(DependencyEdge {
+                from: from.clone(),
+                to: to.clone(),
+                from_file: graph.node_file(from)?.to_string(),
+                to_file: graph.node_file(to)?.to_string(),
+                line: graph.node_line(from),
+                kind: kind.clone(),
+            })
+        })
+        .collect();
+    edges.sort_by(|a, b| {
+        (&a.from_file, a.line, &a.from, &a.to).cmp(&(&b.from_file, b.line, &b.from, &b.to))
+    });
+
+    // Collapse symbol edges into module edges
+    let mut modules = Graph::new();
+    let mut module_edges: BTreeMap<(String, String), Vec<&DependencyEdge>> = BTreeMap::new();
+    for file in graph.node_files.values() {
+        modules.nodes.insert(rules.module_of(file));
+    }
+    for edge in &edges {
+        let from = rules.module_of(&edge.from_file);
+        let to = rules.module_of(&edge.to_file);
+        if from == to {
+            continue;
+        }
+        let key = (from, to);
+        if !module_edges.contains_key(&key) {
+            modules.add_edge(key.0.clone(), key.1.clone());
+        }
+        module_edges.entry(key).or_default().push(edge);
+    }
+
+    // Components of the module graph: each cycle, then every other module alone
+    let mut cycles = Vec::new();
+    let mut component: HashMap<&str, usize> = HashMap::new();
+    for mut scc in find_cycles(&modules) {
+        scc.sort();
+        let members: HashSet<&String> = scc.iter().collect();
+        let cycle_edges: Vec<DependencyEdge> = module_edges
+            .iter()
+            .filter(|((from, to), _)| members.contains(from) && members.contains(to))
+            .flat_map(|(_, edges)| edges.iter().map(|edge| (*edge).clone()))
+            .collect();
+        let files: BTreeSet<String> = cycle_edges
+            .iter()
+            .flat_map(|edge| [edge.from_file.clone(), edge.to_file.clone()])
+            .collect();
+        cycles.push(ModuleCycle {
+            modules: scc,
+            files: files.into_iter().collect(),
+            edges: cycle_edges,
+        });
+    }
+    cycles.sort_by(|a, b| a.modules.cmp(&b.modules));
+    for (index, cycle) in cycles.iter().enumerate() {
+        for module in &cycle.modules {
+            component.insert(module.as_str(), index);
+        }
+    }
+    let mut sorted_modules: Vec<&String> = modules.nodes.iter().collect();
+    sorted_modules.sort();
+    let mut next = cycles.len();
+    for module in &sorted_modules {
+        component.entry(module.as_str()).or_insert_with(|| {
+            next += 1;
+            next - 1
+        });
+    }
+
+    let mut component_deps: HashMap<usize, BTreeSet<usize>> = HashMap::new();
+    for (from, to) in module_edges.keys() {
+        let (from, to) = (component[from.as_str()], component[to.as_str()]);
+        if from != to {
+            component_deps.entry(from).or_default().insert(to);
+        }
+    }
+
+    fn layer_of(
+        component: usize,
+        deps: &HashMap<usize, BTreeSet<usize>>,
+        layers: &mut HashMap<usize, usize>,
+    ) -> usize {
+        if let Some(layer) = layers.get(&component) {
+            return *layer;
+        }
+        let layer = deps
+            .get(&component)
+            .into_iter()
+            .flatten()
+            .map(|dep| layer_of(*dep, deps, layers) + 1)
+            .max()
+            .unwrap_or(0);
+        layers.insert(component, layer);
+        layer
+    }
+
+    let mut component_layers = HashMap::new();
+    let mut layers: Vec<ModuleLayer> = sorted_modules
+        .iter()
+        .map(|module| ModuleLayer {
+            module: module.to_string(),
+            layer: layer_of(component[module.as_str()], &component_deps, &mut component_layers),
+        })
+        .collect();
+    layers.sort_by(|a, b| a.layer.cmp(&b.layer).then_with(|| a.module.cmp(&b.module)));
+
+    let violations: Vec<RuleViolation> = edges
+        .iter()
+        .flat_map(|edge| {
+            rules
+                .forbidden
+                .iter()
+                .filter(|(_, from, to)| from.is_match(&edge.from_file) && to.is_match(&edge.to_file))
+                .map(|(rule, _, _)| RuleViolation {
+                    rule: format!("{} -> {}", rule.from, rule.to),
+                    description: rule.description.clone(),
+                    edge: edge.clone(),
+                })
+        })
+        .collect();
+
+    ArchitectureReport {
+        passed: cycles.is_empty() && violations.is_empty(),
+        modules: modules.nodes.len(),
+        cycles,
+        layers,
+        violations,
+    }
+}
+
 /// Find shortest path between two nodes using BFS
 pub fn find_shortest_path(graph: &Graph, from: &str, to: &str) -> Option<Path> {
     if from == to {
@@ -517,6 +1023,146 @@ mod tests {
         graph
     }
 
+    #[test]
+    fn test_impact_of_groups_and_weights() {
+        let mut graph = Graph::new();
+        graph.add_typed_edge("caller".into(), "target".into(), "CALLS".into());
+        graph.add_typed_edge("user".into(), "target".into(), "USES_TYPE".into());
+        graph.add_typed_edge("reexport".into(), "target".into(), "IMPORTS".into());
+        graph.add_typed_edge("outer".into(), "caller".into(), "Calls".into());
+        // Reaches `outer` a second time at the same distance through a weaker edge
+        graph.add_typed_edge("outer".into(), "user".into(), "UsesType".into());
+        graph.set_node_file("caller".into(), "src/a.rs".into());
+        graph.set_node_file("user".into(), "src/a.rs".into());
+        graph.set_node_file("outer".into(), "src/b.rs".into());
+
+        let report = graph.impact_of("target", &ImpactOptions::default());
+
+        let ids: Vec<_> = report.symbols.iter().map(|s| (s.id.as_str(), s.distance)).collect();
+        assert_eq!(ids, vec![("caller", 1), ("reexport", 1), ("user", 1), ("outer", 2)]);
+        assert_eq!(report.symbols[3].edge_kind, "Calls");
+        assert_eq!(report.by_file["src/a.rs"], vec!["caller", "user"]);
+        assert_eq!(report.by_file["<unknown>"], vec!["reexport"]);
+        assert_eq!(report.by_distance[&2], vec!["outer"]);
+        assert!((report.blast_radius - (1.0 + 0.6 + 0.3 + 1.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_impact_of_options_and_cycles() {
+        let mut graph = create_cycle_graph();
+        graph.add_typed_edge("test_a".into(), "A".into(), "CALLS".into());
+        graph.add_typed_edge("D".into(), "A".into(), "IMPORTS".into());
+
+        // A <- C <- B <- A: the cycle terminates and never reports the root
+        let report = graph.impact_of("A", &ImpactOptions::default());
+        let ids: Vec<_> = report.symbols.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["C", "D", "test_a", "B"]);
+
+        let options = ImpactOptions {
+            max_depth: Some(1),
+            edge_kinds: vec!["depends_on".to_string(), "calls".to_string()],
+            include_tests: false,
+        };
+        let report = graph.impact_of("A", &options);
+        let ids: Vec<_> = report.symbols.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["C"]);
+
+        // Identical graphs produce identical reports
+        assert_eq!(graph.impact_of("A", &ImpactOptions::default()), graph.impact_of("A", &ImpactOptions::default()));
+    }
+
+    fn add_symbol(graph: &mut Graph, id: &str, file: &str, line: usize) {
+        graph.set_node_file(id.to_string(), file.to_string());
+        graph.set_node_line(id.to_string(), line);
+    }
+
+    fn create_architecture_graph() -> Graph {
+        let mut graph = Graph::new();
+        add_symbol(&mut graph, "cli::run", "src/cli/main.rs", 10);
+        add_symbol(&mut graph, "api::handle", "src/api/routes.rs", 5);
+        add_symbol(&mut graph, "api::validate", "src/api/validate.rs", 3);
+        add_symbol(&mut graph, "core::model", "src/core/model.rs", 1);
+        add_symbol(&mut graph, "core::service", "src/core/service.rs", 8);
+        add_symbol(&mut graph, "storage::db", "src/storage/internal/db.rs", 2);
+
+        graph.add_typed_edge("cli::run".into(), "api::handle".into(), "CALLS".into());
+        graph.add_typed_edge("cli::run".into(), "storage::db".into(), "CALLS".into());
+        graph.add_typed_edge("api::handle".into(), "core::service".into(), "CALLS".into());
+        graph.add_typed_edge("api::handle".into(), "api::validate".into(), "CALLS".into());
+        graph.add_typed_edge("core::service".into(), "core::model".into(), "USES_TYPE".into());
+        graph.add_typed_edge("core::service".into(), "storage::db".into(), "CALLS".into());
+        graph
+    }
+
+    #[test]
+    fn test_analyze_architecture_layers_and_rules() {
+        let graph = create_architecture_graph();
+        let rules = RuleSet::new(vec![ForbiddenDependency {
+            from: "src/cli/**".to_string(),
+            to: "src/storage/internal/**".to_string(),
+            description: Some("cli must not depend on storage internals".to_string()),
+        }])
+        .unwrap();
+
+        let report = analyze_architecture(&graph, &rules);
+
+        assert!(!report.passed);
+        assert_eq!(report.modules, 4);
+        assert!(report.cycles.is_empty());
+
+        let layers: Vec<_> = report.layers.iter().map(|l| (l.module.as_str(), l.layer)).collect();
+        assert_eq!(
+            layers,
+            vec![("src/storage/internal", 0), ("src/core", 1), ("src/api", 2), ("src/cli", 3)]
+        );
+
+        assert_eq!(report.violations.len(), 1);
+        let violation = &report.violations[0];
+        assert_eq!(violation.rule, "src/cli/** -> src/storage/internal/**");
+        assert_eq!(violation.edge.from, "cli::run");
+        assert_eq!(violation.edge.from_file, "src/cli/main.rs");
+        assert_eq!(violation.edge.line, Some(10));
+    }
+
+    #[test]
+    fn test_analyze_architecture_module_cycles() {
+        let mut graph = create_architecture_graph();
+        graph.add_typed_edge("core::model".into(), "api::validate".into(), "CALLS".into());
+
+        let report = analyze_architecture(&graph, &RuleSet::new(vec![]).unwrap());
+
+        assert!(!report.passed);
+        assert_eq!(report.cycles.len(), 1);
+        let cycle = &report.cycles[0];
+        assert_eq!(cycle.modules, vec!["src/api", "src/core"]);
+        assert_eq!(
+            cycle.files,
+            vec!["src/api/routes.rs", "src/api/validate.rs", "src/core/model.rs", "src/core/service.rs"]
+        );
+        assert_eq!(cycle.edges.len(), 2);
+
+        // Modules in a cycle share a layer above what they depend on
+        let layer = |module: &str| report.layers.iter().find(|l| l.module == module).unwrap().layer;
+        assert_eq!(layer("src/api"), 1);
+        assert_eq!(layer("src/core"), 1);
+        assert_eq!(layer("src/cli"), 2);
+
+        // Coarser modules absorb the cycle
+        let report = analyze_architecture(&graph, &RuleSet::new(vec![]).unwrap().with_module_depth(1));
+        assert!(report.passed);
+        assert_eq!(report.modules, 1);
+    }
+
+    #[test]
+    fn test_rule_set_rejects_invalid_globs() {
+        let rules = RuleSet::new(vec![ForbiddenDependency {
+            from: "src/[cli".to_string(),
+            to: "src/**".to_string(),
+            description: None,
+        }]);
+        assert!(rules.is_err());
+    }
+
     #[test]
     fn test_shortest_path() {
         let graph = create_test_graph();
@@ -44,6 +44,9 @@ impl CortexMcpServer {
         let storage = Self::create_storage(&config).await?;
         info!("Database connection established");
 
+        // Refuse to start on a schema this build doesn't understand
+        crate::version_check::ensure_compatible(&storage).await?;
+
         // Create VFS
         let vfs = Arc::new(VirtualFileSystem::new(storage.clone()));
 
@@ -58,6 +61,7 @@ impl CortexMcpServer {
     /// Creates a new server with custom configuration
     pub async fn with_config(config: GlobalConfig) -> Result<Self> {
         let storage = Self::create_storage(&config).await?;
+        crate::version_check::ensure_compatible(&storage).await?;
         let vfs = Arc::new(VirtualFileSystem::new(storage.clone()));
         let server = Self::build_server(storage.clone(), vfs).await?;
 
@@ -121,6 +125,7 @@ impl CortexMcpServer {
 
         let vfs_ctx = VfsContext::new(vfs.clone());
         let code_ctx = CodeNavContext::new(storage.clone());
+        let edit_ctx = CodeManipulationContext::with_vfs(storage.clone(), vfs.clone());
         let semantic_ctx = SemanticSearchContext::new(storage.clone()).await?;
         let deps_ctx = DependencyAnalysisContext::new(storage.clone());
         let quality_ctx = CodeQualityContext::new(storage.clone());
@@ -142,7 +147,7 @@ impl CortexMcpServer {
         let server = mcp_sdk::McpServer::builder()
             .name("cortex-mcp")
             .version(env!("CARGO_PKG_VERSION"))
-            // Workspace Management Tools (12)
+            // Workspace Management Tools (15)
             .tool(WorkspaceCreateTool::new(workspace_ctx.clone()))
             .tool(WorkspaceGetTool::new(workspace_ctx.clone()))
             .tool(WorkspaceListTool::new(workspace_ctx.clone()))
@@ -155,6 +160,9 @@ impl CortexMcpServer {
             .tool(WorkspaceSearchTool::new(workspace_ctx.clone()))
             .tool(WorkspaceCompareTool::new(workspace_ctx.clone()))
             .tool(WorkspaceMergeTool::new(workspace_ctx.clone()))
+            .tool(WorkspaceSnapshotTool::new(workspace_ctx.clone()))
+            .tool(WorkspaceRollbackTool::new(workspace_ctx.clone()))
+            .tool(WorkspaceListSnapshotsTool::new(workspace_ctx.clone()))
             // Virtual Filesystem Tools (17)
             .tool(VfsGetNodeTool::new(vfs_ctx.clone()))
             .tool(VfsGetNodeByIdTool::new(vfs_ctx.clone()))
@@ -184,6 +192,10 @@ impl CortexMcpServer {
             .tool(CodeGetTypeHierarchyTool::new(code_ctx.clone()))
             .tool(CodeGetImportsTool::new(code_ctx.clone()))
             .tool(CodeGetExportsTool::new(code_ctx.clone()))
+            // Code Edit Tools (3)
+            .tool(CodeEditRenameSymbolTool::new(edit_ctx.clone()))
+            .tool(CodeEditExtractFunctionTool::new(edit_ctx.clone()))
+            .tool(CodeEditOptimizeImportsTool::new(edit_ctx.clone()))
             // Semantic Search Tools (8) - REAL semantic search with embeddings
             .tool(SearchCodeTool::new(semantic_ctx.clone()))
             .tool(SearchSimilarTool::new(semantic_ctx.clone()))
@@ -343,12 +355,13 @@ impl CortexMcpServer {
             // Advanced Testing Tools (2)
             .tool(TestAnalyzeFlakyTool::new(adv_test_ctx.clone()))
             .tool(TestSuggestEdgeCasesTool::new(adv_test_ctx.clone()))
-            // Architecture Analysis Tools (5)
+            // Architecture Analysis Tools (6)
             .tool(ArchVisualizeTool::new(arch_ctx.clone()))
             .tool(ArchDetectPatternsTool::new(arch_ctx.clone()))
             .tool(ArchSuggestBoundariesTool::new(arch_ctx.clone()))
             .tool(ArchCheckViolationsTool::new(arch_ctx.clone()))
             .tool(ArchAnalyzeDriftTool::new(arch_ctx.clone()))
+            .tool(CodeArchitectureCheckTool::new(arch_ctx.clone()))
             // Note: Middleware support may be added in future versions
             .build();
 
@@ -1,6 +1,7 @@
-//! Architecture Analysis Tools (5 tools)
+//! Architecture Analysis Tools (6 tools)
 //!
-//! Provides architecture visualization, pattern detection, and constraint checking
+//! Provides architecture visualization, pattern detection, and constraint checking,
+//! including a CI-friendly conformance check (`cortex.code.architecture_check`)
 
 use async_trait::async_trait;
 use cortex_storage::ConnectionManager;
@@ -12,8 +13,9 @@ use tracing::{debug, info, warn};
 use std::collections::{HashMap, HashSet};
 
 use crate::mcp::graph_algorithms::{
-    Graph, find_cycles, topological_layers, calculate_centrality,
+    Graph, find_cycles, topological_layers, calculate_centrality, ArchitectureRules, RuleSet,
 };
+use crate::services::DependencyService;
 
 #[derive(Clone)]
 pub struct ArchitectureAnalysisContext {
@@ -952,6 +954,48 @@ impl Tool for ArchAnalyzeDriftTool {
     }
 }
 
+// =============================================================================
+// cortex.code.architecture_check
+// =============================================================================
+
+pub struct CodeArchitectureCheckTool {
+    ctx: ArchitectureAnalysisContext,
+}
+
+impl CodeArchitectureCheckTool {
+    pub fn new(ctx: ArchitectureAnalysisContext) -> Self {
+        Self { ctx }
+    }
+}
+
+#[async_trait]
+impl Tool for CodeArchitectureCheckTool {
+    fn name(&self) -> &str {
+        "cortex.code.architecture_check"
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Check architecture conformance: dependency cycles between modules, module layering, and forbidden dependencies between path globs. The report's `passed` field is suitable for CI gating")
+    }
+
+    fn input_schema(&self) -> Value {
+        serde_json::to_value(schemars::schema_for!(ArchitectureRules)).unwrap()
+    }
+
+    async fn execute(&self, input: Value, _context: &ToolContext) -> std::result::Result<ToolResult, ToolError> {
+        let rules: ArchitectureRules = serde_json::from_value(input)
+            .map_err(|e| ToolError::ExecutionFailed(e.to_string()))?;
+        let rules = RuleSet::from_rules(rules).map_err(ToolError::ExecutionFailed)?;
+
+        let report = DependencyService::new(self.ctx.storage.clone())
+            .check_architecture(&rules)
+            .await
+            .map_err(|e| ToolError::ExecutionFailed(format!("Architecture check failed: {}", e)))?;
+
+        Ok(ToolResult::success_json(serde_json::to_value(report).unwrap()))
+    }
+}
+
 // =============================================================================
 // Helper Functions
 // =============================================================================
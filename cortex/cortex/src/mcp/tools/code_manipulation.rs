@@ -1,6 +1,15 @@
-//! Code Manipulation Utilities
+//! Code Manipulation Tools
+//!
+//! This module provides shared context and utilities for code manipulation
+//! operations, and the `cortex.code.edit` tools built on the [`AstEditor`]:
+//! - `cortex.code.edit.rename_symbol`
+//! - `cortex.code.edit.extract_function`
+//! - `cortex.code.edit.optimize_imports`
+//!
+//! Edit tools return a preview diff and only write when `apply` is true. They
+//! write through the VFS, so changes are tracked and versioned like any other
+//! file update. Rust and TypeScript are supported.
 //!
-//! This module provides shared context and utilities for code manipulation operations.
 //! Read-only tools are located in separate tool modules.
 
 use async_trait::async_trait;
@@ -10,10 +19,11 @@ use cortex_memory::CognitiveManager;
 use cortex_code_analysis::{AstEditor, CodeParser, Lang as ParserLanguage, ParsedFile};
 use cortex_storage::ConnectionManager;
 use cortex_vfs::{VirtualFileSystem, VirtualPath};
+use similar::TextDiff;
 use mcp_sdk::prelude::*;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::path::Path;
 use std::str::FromStr;
 use std::sync::{Arc, RwLock};
@@ -40,6 +50,11 @@ pub struct CodeManipulationContext {
 impl CodeManipulationContext {
     pub fn new(storage: Arc<ConnectionManager>) -> Self {
         let vfs = Arc::new(VirtualFileSystem::new(storage.clone()));
+        Self::with_vfs(storage, vfs)
+    }
+
+    /// Create a context sharing an existing VFS, and with it the VFS caches
+    pub fn with_vfs(storage: Arc<ConnectionManager>, vfs: Arc<VirtualFileSystem>) -> Self {
         let code_unit_service = Arc::new(CodeUnitService::new(storage.clone()));
         Self {
             storage,
@@ -66,14 +81,69 @@ impl CodeManipulationContext {
         Ok((parsed, content, language))
     }
 
-    /// Save modified content back to VFS
-    async fn save_file(&self, workspace_id: &Uuid, file_path: &str, content: &str) -> AnyhowResult<()> {
+    /// Save modified content back to VFS, recording `author` in the file's history
+    async fn save_file(&self, workspace_id: &Uuid, file_path: &str, content: &str, author: Option<&str>) -> AnyhowResult<()> {
         let vpath = VirtualPath::new(file_path).map_err(|e| anyhow::anyhow!("Invalid path: {}", e))?;
-        self.vfs.write_file(workspace_id, &vpath, content.as_bytes()).await
+        self.vfs.write_file_as(workspace_id, &vpath, content.as_bytes(), author).await
             .map_err(|e| anyhow::anyhow!("Failed to write file: {}", e))?;
         Ok(())
     }
 
+    /// Load a file into an [`AstEditor`], rejecting languages the edit tools do not support
+    async fn load_editor(&self, workspace_id: &Uuid, file_path: &str) -> EditResult<(AstEditor, ParserLanguage)> {
+        let language = edit_language(file_path)?;
+
+        let vpath = VirtualPath::new(file_path)
+            .map_err(|e| EditFailure::Failed(format!("Invalid path: {}", e)))?;
+        let content_bytes = self.vfs.read_file(workspace_id, &vpath).await
+            .map_err(|e| EditFailure::Failed(format!("Failed to read file: {}", e)))?;
+        let content = String::from_utf8(content_bytes)
+            .map_err(|e| EditFailure::Failed(format!("File is not UTF-8: {}", e)))?;
+
+        let editor = AstEditor::new(content, language.get_ts_language())
+            .map_err(|e| EditFailure::Failed(format!("Failed to parse {}: {}", file_path, e)))?;
+
+        Ok((editor, language))
+    }
+
+    /// Apply the editor's pending edits, returning the previous and new source
+    fn finish_edit(file_path: &str, editor: &mut AstEditor) -> EditResult<FileEdit> {
+        let edits = editor.edits.len();
+        let original = editor.get_source().to_string();
+        editor.apply_edits()
+            .map_err(|e| EditFailure::Failed(format!("Failed to apply edits to {}: {}", file_path, e)))?;
+
+        Ok(FileEdit {
+            path: file_path.to_string(),
+            original,
+            modified: editor.get_source().to_string(),
+            edits,
+        })
+    }
+
+    /// Preview or write a set of file edits
+    async fn commit_edits(
+        &self,
+        workspace_id: &Uuid,
+        files: Vec<FileEdit>,
+        apply: bool,
+        author: Option<&str>,
+    ) -> EditResult<EditOutput> {
+        let files: Vec<FileEdit> = files.into_iter().filter(|file| file.original != file.modified).collect();
+
+        if apply {
+            for file in &files {
+                self.save_file(workspace_id, &file.path, &file.modified, author).await
+                    .map_err(|e| EditFailure::Failed(e.to_string()))?;
+            }
+        }
+
+        Ok(EditOutput {
+            applied: apply,
+            files: files.iter().map(FileEdit::preview).collect(),
+        })
+    }
+
     /// Store a code unit in semantic memory
     async fn store_code_unit(&self, unit: CodeUnit) -> AnyhowResult<String> {
         let conn = self.storage.acquire().await
@@ -228,6 +298,439 @@ impl CodeManipulationContext {
 }
 
 
+// =============================================================================
+// Code edit support
+// =============================================================================
+
+/// A file rewritten by an edit tool
+struct FileEdit {
+    path: String,
+    original: String,
+    modified: String,
+    edits: usize,
+}
+
+impl FileEdit {
+    fn preview(&self) -> FileEditPreview {
+        let diff = TextDiff::from_lines(&self.original, &self.modified)
+            .unified_diff()
+            .header(&format!("a/{}", self.path), &format!("b/{}", self.path))
+            .to_string();
+
+        FileEditPreview {
+            path: self.path.clone(),
+            edits: self.edits,
+            diff,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+struct FileEditPreview {
+    path: String,
+    edits: usize,
+    /// Unified diff of the change
+    diff: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+struct EditOutput {
+    /// Whether the edits were written to the VFS
+    applied: bool,
+    files: Vec<FileEditPreview>,
+}
+
+/// A location in a file, with 1-based lines
+#[derive(Debug, Serialize, JsonSchema)]
+struct EditLocation {
+    path: String,
+    line: usize,
+    column: usize,
+}
+
+/// An edit the tools refuse to make, reported as a structured tool error
+#[derive(Debug, Serialize)]
+struct EditConflict {
+    /// symbol_collision, non_contiguous_selection or unsupported_language
+    error: &'static str,
+    message: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    locations: Vec<EditLocation>,
+}
+
+type EditResult<T> = std::result::Result<T, EditFailure>;
+
+enum EditFailure {
+    Conflict(EditConflict),
+    Failed(String),
+}
+
+impl EditFailure {
+    fn conflict(error: &'static str, message: String, locations: Vec<EditLocation>) -> Self {
+        Self::Conflict(EditConflict { error, message, locations })
+    }
+
+    /// Conflicts become error results the agent can act on; anything else fails the call
+    fn into_tool_result(self) -> std::result::Result<ToolResult, ToolError> {
+        match self {
+            Self::Conflict(conflict) => Ok(ToolResult::error(
+                serde_json::to_string(&conflict).unwrap_or(conflict.message),
+            )),
+            Self::Failed(message) => Err(ToolError::ExecutionFailed(message)),
+        }
+    }
+}
+
+/// Language of a file, if the edit tools support it
+fn edit_language(file_path: &str) -> EditResult<ParserLanguage> {
+    match ParserLanguage::from_path(Path::new(file_path)) {
+        Some(language @ (ParserLanguage::Rust | ParserLanguage::TypeScript | ParserLanguage::Tsx)) => Ok(language),
+        other => Err(EditFailure::conflict(
+            "unsupported_language",
+            format!(
+                "Code edits support Rust and TypeScript only; {} is {}",
+                file_path,
+                other.map(|language| language.display_name()).unwrap_or("not a recognized source file")
+            ),
+            vec![],
+        )),
+    }
+}
+
+fn locations(path: &str, ranges: &[cortex_code_analysis::Range]) -> Vec<EditLocation> {
+    ranges
+        .iter()
+        .map(|range| EditLocation {
+            path: path.to_string(),
+            line: range.start.line + 1,
+            column: range.start.column,
+        })
+        .collect()
+}
+
+fn parse_workspace_id(workspace_id: &str) -> EditResult<Uuid> {
+    Uuid::parse_str(workspace_id)
+        .map_err(|e| EditFailure::Failed(format!("Invalid workspace_id: {}", e)))
+}
+
+// =============================================================================
+// cortex.code.edit.rename_symbol
+// =============================================================================
+
+pub struct CodeEditRenameSymbolTool {
+    ctx: CodeManipulationContext,
+}
+
+impl CodeEditRenameSymbolTool {
+    pub fn new(ctx: CodeManipulationContext) -> Self {
+        Self { ctx }
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct RenameSymbolInput {
+    workspace_id: String,
+    /// File declaring or using the symbol
+    file_path: String,
+    old_name: String,
+    new_name: String,
+    /// "file" renames within file_path; "workspace" also renames in every file referencing the symbol
+    #[serde(default = "default_file_scope")]
+    scope: String,
+    /// Qualified name of the symbol, to disambiguate it in workspace scope
+    qualified_name: Option<String>,
+    /// Write the changes; only a preview diff is returned otherwise
+    #[serde(default)]
+    apply: bool,
+}
+
+#[async_trait]
+impl Tool for CodeEditRenameSymbolTool {
+    fn name(&self) -> &str {
+        "cortex.code.edit.rename_symbol"
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Renames a symbol in a file or, with scope=workspace, in every file referencing it. Returns a preview diff unless apply=true")
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        serde_json::to_value(schemars::schema_for!(RenameSymbolInput)).unwrap()
+    }
+
+    async fn execute(
+        &self,
+        input: serde_json::Value,
+        context: &ToolContext,
+    ) -> std::result::Result<ToolResult, ToolError> {
+        let input: RenameSymbolInput = serde_json::from_value(input)
+            .map_err(|e| ToolError::ExecutionFailed(format!("Invalid input: {}", e)))?;
+
+        match self.rename(input, context.session_id()).await {
+            Ok(output) => Ok(ToolResult::success_json(serde_json::to_value(output).unwrap())),
+            Err(failure) => failure.into_tool_result(),
+        }
+    }
+}
+
+impl CodeEditRenameSymbolTool {
+    async fn rename(&self, input: RenameSymbolInput, author: Option<&str>) -> EditResult<EditOutput> {
+        let workspace_id = parse_workspace_id(&input.workspace_id)?;
+        if input.old_name == input.new_name {
+            return Err(EditFailure::Failed("new_name must differ from old_name".to_string()));
+        }
+
+        let files = match input.scope.as_str() {
+            "file" => BTreeSet::from([input.file_path.clone()]),
+            "workspace" => self.referencing_files(&input).await?,
+            other => {
+                return Err(EditFailure::Failed(format!(
+                    "Invalid scope '{}': expected 'file' or 'workspace'",
+                    other
+                )))
+            }
+        };
+
+        // Check every file before renaming anything
+        let mut editors = Vec::new();
+        let mut collisions = Vec::new();
+        for path in files {
+            let (editor, _) = self.ctx.load_editor(&workspace_id, &path).await?;
+            collisions.extend(locations(&path, &editor.symbol_occurrences(&input.new_name)));
+            editors.push((path, editor));
+        }
+        if !collisions.is_empty() {
+            return Err(EditFailure::conflict(
+                "symbol_collision",
+                format!("'{}' is already used in the renamed files", input.new_name),
+                collisions,
+            ));
+        }
+
+        let mut edits = Vec::new();
+        for (path, mut editor) in editors {
+            editor.rename_symbol(&input.old_name, &input.new_name)
+                .map_err(|e| EditFailure::Failed(format!("Failed to rename in {}: {}", path, e)))?;
+            edits.push(CodeManipulationContext::finish_edit(&path, &mut editor)?);
+        }
+
+        if edits.iter().all(|file| file.edits == 0) {
+            return Err(EditFailure::Failed(format!("Symbol '{}' not found", input.old_name)));
+        }
+
+        self.ctx.commit_edits(&workspace_id, edits, input.apply, author).await
+    }
+
+    /// The file declaring the symbol and every file referencing it
+    async fn referencing_files(&self, input: &RenameSymbolInput) -> EditResult<BTreeSet<String>> {
+        let manager = self.ctx.get_cognitive_manager();
+        let semantic = manager.semantic();
+
+        let unit_id = match &input.qualified_name {
+            Some(qualified_name) => semantic.find_by_qualified_name(qualified_name).await
+                .map_err(|e| EditFailure::Failed(format!("Database error: {}", e)))?
+                .map(|unit| unit.id),
+            None => self.ctx.code_unit_service.get_units_by_file(&input.file_path).await
+                .map_err(|e| EditFailure::Failed(format!("Database error: {}", e)))?
+                .into_iter()
+                .find(|unit| unit.name == input.old_name)
+                .and_then(|unit| CortexId::from_str(&unit.id).ok()),
+        };
+        let unit_id = unit_id.ok_or_else(|| EditFailure::Failed(format!(
+            "Symbol '{}' is not indexed; ingest the workspace or use scope=file",
+            input.old_name
+        )))?;
+
+        let mut files = BTreeSet::from([input.file_path.clone()]);
+        let references = semantic.find_references(unit_id).await
+            .map_err(|e| EditFailure::Failed(format!("Failed to find references: {}", e)))?;
+        for reference in references {
+            if let Ok(Some(unit)) = semantic.get_unit(reference).await {
+                files.insert(unit.file_path);
+            }
+        }
+
+        Ok(files)
+    }
+}
+
+// =============================================================================
+// cortex.code.edit.extract_function
+// =============================================================================
+
+pub struct CodeEditExtractFunctionTool {
+    ctx: CodeManipulationContext,
+}
+
+impl CodeEditExtractFunctionTool {
+    pub fn new(ctx: CodeManipulationContext) -> Self {
+        Self { ctx }
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct ExtractFunctionInput {
+    workspace_id: String,
+    file_path: String,
+    /// First line to extract (1-based, inclusive)
+    start_line: usize,
+    /// Last line to extract (1-based, inclusive)
+    end_line: usize,
+    /// Name of the new function
+    function_name: String,
+    /// Write the changes; only a preview diff is returned otherwise
+    #[serde(default)]
+    apply: bool,
+}
+
+#[async_trait]
+impl Tool for CodeEditExtractFunctionTool {
+    fn name(&self) -> &str {
+        "cortex.code.edit.extract_function"
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Extracts a range of whole statements into a new function with inferred parameters. Returns a preview diff unless apply=true")
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        serde_json::to_value(schemars::schema_for!(ExtractFunctionInput)).unwrap()
+    }
+
+    async fn execute(
+        &self,
+        input: serde_json::Value,
+        context: &ToolContext,
+    ) -> std::result::Result<ToolResult, ToolError> {
+        let input: ExtractFunctionInput = serde_json::from_value(input)
+            .map_err(|e| ToolError::ExecutionFailed(format!("Invalid input: {}", e)))?;
+
+        match self.extract(input, context.session_id()).await {
+            Ok(output) => Ok(ToolResult::success_json(output)),
+            Err(failure) => failure.into_tool_result(),
+        }
+    }
+}
+
+impl CodeEditExtractFunctionTool {
+    async fn extract(&self, input: ExtractFunctionInput, author: Option<&str>) -> EditResult<serde_json::Value> {
+        let workspace_id = parse_workspace_id(&input.workspace_id)?;
+        if input.start_line == 0 || input.start_line > input.end_line {
+            return Err(EditFailure::Failed(format!(
+                "Invalid line range: {}-{}",
+                input.start_line, input.end_line
+            )));
+        }
+        let (start_line, end_line) = (input.start_line - 1, input.end_line - 1);
+
+        let (mut editor, _) = self.ctx.load_editor(&workspace_id, &input.file_path).await?;
+
+        if let Some(problem) = editor.selection_conflict(start_line, end_line) {
+            return Err(EditFailure::conflict(
+                "non_contiguous_selection",
+                problem,
+                vec![EditLocation { path: input.file_path.clone(), line: input.start_line, column: 0 }],
+            ));
+        }
+        let collisions = editor.symbol_occurrences(&input.function_name);
+        if !collisions.is_empty() {
+            return Err(EditFailure::conflict(
+                "symbol_collision",
+                format!("'{}' is already defined or used in {}", input.function_name, input.file_path),
+                locations(&input.file_path, &collisions),
+            ));
+        }
+
+        let result = editor.extract_function(start_line, end_line, &input.function_name)
+            .map_err(|e| EditFailure::Failed(format!("Failed to extract function: {}", e)))?;
+        let edit = CodeManipulationContext::finish_edit(&input.file_path, &mut editor)?;
+        let output = self.ctx.commit_edits(&workspace_id, vec![edit], input.apply, author).await?;
+
+        let mut output = serde_json::to_value(output).unwrap();
+        output["function_name"] = serde_json::json!(result.function_name);
+        output["parameters"] = serde_json::json!(result.parameters.iter().map(|(name, param_type)| serde_json::json!({
+            "name": name,
+            "param_type": param_type,
+        })).collect::<Vec<_>>());
+        output["return_type"] = serde_json::json!(result.return_type);
+
+        Ok(output)
+    }
+}
+
+// =============================================================================
+// cortex.code.edit.optimize_imports
+// =============================================================================
+
+pub struct CodeEditOptimizeImportsTool {
+    ctx: CodeManipulationContext,
+}
+
+impl CodeEditOptimizeImportsTool {
+    pub fn new(ctx: CodeManipulationContext) -> Self {
+        Self { ctx }
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct OptimizeImportsInput {
+    workspace_id: String,
+    file_path: String,
+    /// Write the changes; only a preview diff is returned otherwise
+    #[serde(default)]
+    apply: bool,
+}
+
+#[async_trait]
+impl Tool for CodeEditOptimizeImportsTool {
+    fn name(&self) -> &str {
+        "cortex.code.edit.optimize_imports"
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Removes duplicate imports and sorts them. Returns a preview diff unless apply=true")
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        serde_json::to_value(schemars::schema_for!(OptimizeImportsInput)).unwrap()
+    }
+
+    async fn execute(
+        &self,
+        input: serde_json::Value,
+        context: &ToolContext,
+    ) -> std::result::Result<ToolResult, ToolError> {
+        let input: OptimizeImportsInput = serde_json::from_value(input)
+            .map_err(|e| ToolError::ExecutionFailed(format!("Invalid input: {}", e)))?;
+
+        match self.optimize(input, context.session_id()).await {
+            Ok(output) => Ok(ToolResult::success_json(output)),
+            Err(failure) => failure.into_tool_result(),
+        }
+    }
+}
+
+impl CodeEditOptimizeImportsTool {
+    async fn optimize(&self, input: OptimizeImportsInput, author: Option<&str>) -> EditResult<serde_json::Value> {
+        let workspace_id = parse_workspace_id(&input.workspace_id)?;
+        let (mut editor, language) = self.ctx.load_editor(&workspace_id, &input.file_path).await?;
+
+        let result = match language {
+            ParserLanguage::Rust => editor.optimize_imports_rust(),
+            _ => editor.optimize_imports_typescript(),
+        }
+        .map_err(|e| EditFailure::Failed(format!("Failed to optimize imports: {}", e)))?;
+
+        let edit = CodeManipulationContext::finish_edit(&input.file_path, &mut editor)?;
+        let output = self.ctx.commit_edits(&workspace_id, vec![edit], input.apply, author).await?;
+
+        let mut output = serde_json::to_value(output).unwrap();
+        output["duplicates_removed"] = serde_json::json!(result.removed);
+
+        Ok(output)
+    }
+}
+
 // =============================================================================
 // Helper functions
 // =============================================================================
@@ -236,6 +739,10 @@ fn default_true() -> bool {
     true
 }
 
+fn default_file_scope() -> String {
+    "file".to_string()
+}
+
 fn default_workspace_scope() -> String {
     "workspace".to_string()
 }
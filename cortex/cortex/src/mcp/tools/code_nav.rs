@@ -14,6 +14,7 @@ use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 use std::sync::Arc;
 
+use crate::mcp::graph_algorithms::{Graph, ImpactOptions};
 // Import the unified service layer
 use crate::services::CodeUnitService;
 
@@ -39,6 +40,51 @@ impl CodeNavContext {
     fn get_cognitive_manager(&self) -> CognitiveManager {
         CognitiveManager::new(self.storage.clone())
     }
+
+    /// Load the full dependency graph, with the file of every known unit
+    async fn load_dependency_graph(&self) -> std::result::Result<Graph, String> {
+        let conn = self.storage
+            .acquire()
+            .await
+            .map_err(|e| format!("Failed to acquire connection: {}", e))?;
+
+        #[derive(Deserialize)]
+        struct DepEdge {
+            source_id: String,
+            target_id: String,
+            dependency_type: String,
+        }
+
+        #[derive(Deserialize)]
+        struct UnitFile {
+            cortex_id: String,
+            file_path: String,
+        }
+
+        let mut result = conn
+            .connection()
+            .query("SELECT source_id, target_id, type::string(dependency_type) AS dependency_type FROM DEPENDS_ON")
+            .query("SELECT cortex_id, file_path FROM code_unit")
+            .await
+            .map_err(|e| format!("Failed to query dependency graph: {}", e))?;
+
+        let edges: Vec<DepEdge> = result
+            .take(0)
+            .map_err(|e| format!("Failed to extract dependencies: {}", e))?;
+        let files: Vec<UnitFile> = result
+            .take(1)
+            .map_err(|e| format!("Failed to extract unit files: {}", e))?;
+
+        let mut graph = Graph::new();
+        for edge in edges {
+            graph.add_typed_edge(edge.source_id, edge.target_id, edge.dependency_type);
+        }
+        for unit in files {
+            graph.set_node_file(unit.cortex_id, unit.file_path);
+        }
+
+        Ok(graph)
+    }
 }
 
 // =============================================================================
@@ -538,6 +584,17 @@ struct FindReferencesInput {
     #[serde(default)]
     #[allow(dead_code)]
     workspace_id: Option<String>,
+    /// Follow references transitively and report the impact of changing the unit
+    #[serde(default)]
+    transitive: bool,
+    /// Maximum distance for transitive references (unlimited if omitted)
+    max_depth: Option<usize>,
+    /// Dependency kinds to follow transitively, e.g. ["CALLS", "USES_TYPE"] (all if empty)
+    #[serde(default)]
+    edge_kinds: Vec<String>,
+    /// Whether test code is included in transitive references
+    #[serde(default = "default_true")]
+    include_tests: bool,
 }
 
 #[async_trait]
@@ -547,7 +604,7 @@ impl Tool for CodeFindReferencesTool {
     }
 
     fn description(&self) -> Option<&str> {
-        Some("Finds all references to a symbol (where it's called or used)")
+        Some("Finds all references to a symbol (where it's called or used); with transitive=true, reports everything affected by changing it, grouped by file and distance, with a blast-radius score")
     }
 
     fn input_schema(&self) -> serde_json::Value {
@@ -584,6 +641,55 @@ impl Tool for CodeFindReferencesTool {
             return Err(ToolError::ExecutionFailed("No identifier provided".to_string()));
         };
 
+        if input.transitive {
+            let graph = self.ctx.load_dependency_graph().await
+                .map_err(ToolError::ExecutionFailed)?;
+            let options = ImpactOptions {
+                max_depth: input.max_depth,
+                edge_kinds: input.edge_kinds,
+                include_tests: input.include_tests,
+            };
+            let report = graph.impact_of(&unit_id.to_string(), &options);
+
+            let mut references = Vec::new();
+            for symbol in &report.symbols {
+                let mut reference = serde_json::json!({
+                    "unit_id": symbol.id,
+                    "distance": symbol.distance,
+                    "edge_kind": symbol.edge_kind,
+                    "is_test": symbol.is_test,
+                    "location": { "file": symbol.file },
+                });
+                let unit = match CortexId::from_str(&symbol.id) {
+                    Ok(id) => semantic.get_unit(id).await.ok().flatten(),
+                    Err(_) => None,
+                };
+                if let Some(ref_unit) = unit {
+                    reference["name"] = serde_json::json!(ref_unit.name);
+                    reference["qualified_name"] = serde_json::json!(ref_unit.qualified_name);
+                    reference["unit_type"] = serde_json::json!(format!("{:?}", ref_unit.unit_type));
+                    reference["location"] = serde_json::json!({
+                        "file": ref_unit.file_path,
+                        "start_line": ref_unit.start_line,
+                        "end_line": ref_unit.end_line,
+                    });
+                }
+                references.push(reference);
+            }
+
+            let output = serde_json::json!({
+                "references": references,
+                "count": references.len(),
+                "impact": {
+                    "by_file": report.by_file,
+                    "by_distance": report.by_distance,
+                    "blast_radius": report.blast_radius,
+                },
+            });
+
+            return Ok(ToolResult::success_json(output));
+        }
+
         // Get all references (units that depend on this one)
         let reference_ids = semantic.find_references(unit_id).await
             .map_err(|e| ToolError::ExecutionFailed(format!("Failed to find references: {}", e)))?;
@@ -16,7 +16,7 @@ use cortex_core::id::CortexId;
 use cortex_core::types::CodeUnit;
 use cortex_memory::SemanticMemorySystem;
 use cortex_semantic::{
-    SemanticSearchEngine, SemanticConfig, SearchFilter,
+    AnswerSnippet, FilterExpr, SemanticSearchEngine, SemanticConfig, SearchFilter,
 };
 use cortex_semantic::types::EntityType;
 use cortex_storage::ConnectionManager;
@@ -128,6 +128,11 @@ struct SearchCodeInput {
     language: Option<String>,
     #[allow(dead_code)]
     file_pattern: Option<String>,
+    /// Metadata filter expression combining `eq`, `in`, `range`, `and`,
+    /// `or` and `not`, e.g. {"op": "and", "filters": [{"op": "eq", "field":
+    /// "language", "value": "rust"}, {"op": "range", "field": "modified_at",
+    /// "gt": "2024-01-01"}]}
+    filter: Option<Value>,
 }
 
 #[derive(Debug, Serialize, JsonSchema)]
@@ -141,12 +146,37 @@ struct CodeSearchResult {
     language: String,
 }
 
+/// Span of a result that directly answers a factoid query
+#[derive(Debug, Serialize, JsonSchema)]
+struct AnswerSnippetOutput {
+    unit_id: String,
+    text: String,
+    /// Character offsets of the span in the unit content
+    start: usize,
+    end: usize,
+    confidence: f32,
+}
+
+impl From<AnswerSnippet> for AnswerSnippetOutput {
+    fn from(snippet: AnswerSnippet) -> Self {
+        Self {
+            unit_id: snippet.doc_id,
+            text: snippet.text,
+            start: snippet.start,
+            end: snippet.end,
+            confidence: snippet.confidence,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, JsonSchema)]
 struct SearchCodeOutput {
     results: Vec<CodeSearchResult>,
     total_count: usize,
     query: String,
     search_time_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    answer_snippet: Option<AnswerSnippetOutput>,
 }
 
 #[async_trait]
@@ -170,12 +200,20 @@ impl Tool for SearchCodeTool {
         info!("Semantic code search: '{}'", input.query);
         let start = std::time::Instant::now();
 
+        let filter = input
+            .filter
+            .map(serde_json::from_value::<FilterExpr>)
+            .transpose()
+            .map_err(|e| ToolError::ExecutionFailed(format!("Invalid filter: {}", e)))?;
+
         // Use search service
         let request = SearchCodeRequest {
             query: input.query.clone(),
             limit: input.limit,
             min_similarity: input.min_similarity,
             language: input.language.clone(),
+            workspace_id: None,
+            filter,
         };
 
         let service_results = self.ctx.search_service
@@ -183,6 +221,11 @@ impl Tool for SearchCodeTool {
             .await
             .map_err(|e| ToolError::ExecutionFailed(format!("Search failed: {}", e)))?;
 
+        let answer_snippet = service_results
+            .iter()
+            .find_map(|r| r.answer_snippet.clone())
+            .map(AnswerSnippetOutput::from);
+
         // Convert service results to MCP output format
         let results: Vec<CodeSearchResult> = service_results
             .into_iter()
@@ -205,6 +248,7 @@ impl Tool for SearchCodeTool {
             results,
             query: input.query,
             search_time_ms,
+            answer_snippet,
         };
 
         Ok(ToolResult::success_json(serde_json::to_value(output).unwrap()))
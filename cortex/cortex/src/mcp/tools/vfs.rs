@@ -67,6 +67,19 @@ struct GetNodeInput {
     include_metadata: bool,
 }
 
+/// Tool error for a failed VFS write. Writes refused because the workspace
+/// is read-only say how to get an editable copy.
+fn write_error(action: &str, error: cortex_core::error::CortexError) -> ToolError {
+    if error.is_workspace_read_only() {
+        ToolError::ExecutionFailed(format!(
+            "{}: {}. Fork the workspace with cortex.workspace.fork to edit it.",
+            action, error
+        ))
+    } else {
+        ToolError::ExecutionFailed(format!("{}: {}", action, error))
+    }
+}
+
 fn default_true() -> bool {
     true
 }
@@ -394,7 +407,7 @@ impl Tool for VfsCreateFileTool {
     async fn execute(
         &self,
         input: serde_json::Value,
-        _context: &ToolContext,
+        context: &ToolContext,
     ) -> std::result::Result<ToolResult, ToolError> {
         let input: CreateFileInput = serde_json::from_value(input)
             .map_err(|e| ToolError::ExecutionFailed(format!("Invalid input: {}", e)))?;
@@ -415,9 +428,9 @@ impl Tool for VfsCreateFileTool {
         let content_bytes = input.content.as_bytes();
         self.ctx
             .vfs
-            .write_file(&workspace_id, &path, content_bytes)
+            .write_file_as(&workspace_id, &path, content_bytes, context.session_id())
             .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Failed to create file: {}", e)))?;
+            .map_err(|e| write_error("Failed to create file", e))?;
 
         let node = self
             .ctx
@@ -495,7 +508,7 @@ impl Tool for VfsUpdateFileTool {
     async fn execute(
         &self,
         input: serde_json::Value,
-        _context: &ToolContext,
+        context: &ToolContext,
     ) -> std::result::Result<ToolResult, ToolError> {
         let input: UpdateFileInput = serde_json::from_value(input)
             .map_err(|e| ToolError::ExecutionFailed(format!("Invalid input: {}", e)))?;
@@ -527,9 +540,9 @@ impl Tool for VfsUpdateFileTool {
         let content_bytes = input.content.as_bytes();
         self.ctx
             .vfs
-            .write_file(&workspace_id, &path, content_bytes)
+            .write_file_as(&workspace_id, &path, content_bytes, context.session_id())
             .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Failed to update file: {}", e)))?;
+            .map_err(|e| write_error("Failed to update file", e))?;
 
         let node = self
             .ctx
@@ -621,7 +634,7 @@ impl Tool for VfsDeleteNodeTool {
             .vfs
             .delete(&workspace_id, &path, input.recursive)
             .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Failed to delete node: {}", e)))?;
+            .map_err(|e| write_error("Failed to delete node", e))?;
 
         let output = DeleteNodeOutput {
             path: path.to_string(),
@@ -711,10 +724,10 @@ impl Tool for VfsMoveNodeTool {
             .map_err(|e| ToolError::ExecutionFailed(format!("Failed to read source: {}", e)))?;
 
         self.ctx.vfs.write_file(&workspace_id, &target_path, &content).await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Failed to write target: {}", e)))?;
+            .map_err(|e| write_error("Failed to write target", e))?;
 
         self.ctx.vfs.delete(&workspace_id, &source_path, false).await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Failed to delete source: {}", e)))?;
+            .map_err(|e| write_error("Failed to delete source", e))?;
 
         let output = MoveNodeOutput {
             source_path: source_path.to_string(),
@@ -821,7 +834,7 @@ impl Tool for VfsCopyNodeTool {
                 .map_err(|e| ToolError::ExecutionFailed(format!("Failed to read source file: {}", e)))?;
 
             self.ctx.vfs.write_file(&workspace_id, &target_path, &content).await
-                .map_err(|e| ToolError::ExecutionFailed(format!("Failed to write target file: {}", e)))?;
+                .map_err(|e| write_error("Failed to write target file", e))?;
         } else if source_node.is_directory() {
             if !input.recursive {
                 return Err(ToolError::ExecutionFailed(
@@ -856,7 +869,7 @@ impl Tool for VfsCopyNodeTool {
                             .map_err(|e| ToolError::ExecutionFailed(format!("Failed to read child file: {}", e)))?;
 
                         self.ctx.vfs.write_file(&workspace_id, &child_target, &content).await
-                            .map_err(|e| ToolError::ExecutionFailed(format!("Failed to write child file: {}", e)))?;
+                            .map_err(|e| write_error("Failed to write child file", e))?;
                     }
                 }
             }
@@ -943,7 +956,7 @@ impl Tool for VfsCreateDirectoryTool {
             .vfs
             .create_directory(&workspace_id, &path, true)
             .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Failed to create directory: {}", e)))?;
+            .map_err(|e| write_error("Failed to create directory", e))?;
 
         let node = self
             .ctx
@@ -1308,8 +1321,8 @@ impl VfsGetFileHistoryTool {
 struct GetFileHistoryInput {
     path: String,
     workspace_id: Option<String>,
+    /// Only return the most recent versions
     #[serde(default)]
-    #[allow(dead_code)]
     max_versions: Option<usize>,
     #[serde(default)]
     include_content: bool,
@@ -1320,8 +1333,11 @@ struct FileVersion {
     version: u32,
     content_hash: String,
     size_bytes: u64,
-    created_at: String,
-    updated_at: String,
+    timestamp: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    author: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    restored_from: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     content: Option<String>,
 }
@@ -1330,6 +1346,7 @@ struct FileVersion {
 struct GetFileHistoryOutput {
     path: String,
     current_version: u32,
+    /// Versions, newest first
     versions: Vec<FileVersion>,
     total_versions: usize,
 }
@@ -1369,41 +1386,39 @@ impl Tool for VfsGetFileHistoryTool {
 
         debug!("Getting file history for: {} in workspace {}", path, workspace_id);
 
-        // Get current file node
-        let current_node = self.ctx.vfs.metadata(&workspace_id, &path).await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Failed to get file: {}", e)))?;
+        let history = self.ctx.vfs.get_history(&workspace_id, &path).await
+            .map_err(|e| ToolError::ExecutionFailed(format!("Failed to get file history: {}", e)))?;
 
-        if !current_node.is_file() {
-            return Err(ToolError::ExecutionFailed("Not a file".to_string()));
-        }
+        let total_versions = history.len();
+        let current_version = history.last().map(|entry| entry.version).unwrap_or_default();
+        let limit = input.max_versions.unwrap_or(total_versions);
 
-        // Query database for all versions of this file
-        // Note: This is a simplified implementation. In production, you'd have a separate
-        // version history table. For now, we'll return just the current version.
         let mut versions = Vec::new();
+        for entry in history.iter().rev().take(limit) {
+            let content = if input.include_content {
+                self.ctx.vfs.read_version(entry).await
+                    .ok()
+                    .and_then(|bytes| String::from_utf8(bytes).ok())
+            } else {
+                None
+            };
 
-        let content = if input.include_content {
-            self.ctx.vfs.read_file(&workspace_id, &path).await
-                .ok()
-                .and_then(|bytes| String::from_utf8(bytes).ok())
-        } else {
-            None
-        };
-
-        versions.push(FileVersion {
-            version: current_node.version,
-            content_hash: current_node.content_hash.unwrap_or_default(),
-            size_bytes: current_node.size_bytes as u64,
-            created_at: current_node.created_at.to_rfc3339(),
-            updated_at: current_node.updated_at.to_rfc3339(),
-            content,
-        });
+            versions.push(FileVersion {
+                version: entry.version,
+                content_hash: entry.content_hash.clone(),
+                size_bytes: entry.size_bytes as u64,
+                timestamp: entry.timestamp.to_rfc3339(),
+                author: entry.author.clone(),
+                restored_from: entry.restored_from,
+                content,
+            });
+        }
 
         let output = GetFileHistoryOutput {
             path: path.to_string(),
-            current_version: current_node.version,
+            current_version,
             versions,
-            total_versions: 1, // In a full implementation, this would query the version count
+            total_versions,
         };
 
         Ok(ToolResult::success_json(serde_json::to_value(output).unwrap()))
@@ -1485,11 +1500,27 @@ impl Tool for VfsRestoreFileVersionTool {
             return Err(ToolError::ExecutionFailed("Not a file".to_string()));
         }
 
-        // Check if requested version exists (simplified - in production, query version history)
-        if input.version > current_node.version {
-            return Err(ToolError::ExecutionFailed(
-                format!("Version {} does not exist (current version is {})", input.version, current_node.version)
-            ));
+        // Versions are looked up by number; the latest entry wins if a
+        // number was recorded more than once
+        let history = self.ctx.vfs.get_history(&workspace_id, &path).await
+            .map_err(|e| ToolError::ExecutionFailed(format!("Failed to get file history: {}", e)))?;
+        let index = history
+            .iter()
+            .rposition(|entry| entry.version == input.version)
+            .ok_or_else(|| ToolError::ExecutionFailed(format!(
+                "Version {} is not in the retained history of {}",
+                input.version, path
+            )))?;
+
+        // If we're "restoring" to the current version, just return success
+        if input.version == current_node.version {
+            let output = RestoreFileVersionOutput {
+                path: path.to_string(),
+                restored_version: input.version,
+                new_version: current_node.version,
+                backup_path: None,
+            };
+            return Ok(ToolResult::success_json(serde_json::to_value(output).unwrap()));
         }
 
         // Create backup if requested
@@ -1510,38 +1541,17 @@ impl Tool for VfsRestoreFileVersionTool {
             None
         };
 
-        // Note: In a simplified implementation, we can't actually restore to a previous version
-        // because we don't have a version history table. In production, you would:
-        // 1. Query the version history table for the content_hash at the requested version
-        // 2. Write that content to the file
-        //
-        // For now, we'll return a message indicating the operation would succeed
-        // but with the current implementation limitation noted.
+        let restored = self.ctx.vfs.restore_version(&workspace_id, &path, index).await
+            .map_err(|e| write_error("Failed to restore version", e))?;
 
-        // If we're "restoring" to the current version, just return success
-        if input.version == current_node.version {
-            let output = RestoreFileVersionOutput {
-                path: path.to_string(),
-                restored_version: input.version,
-                new_version: current_node.version,
-                backup_path,
-            };
-            return Ok(ToolResult::success_json(serde_json::to_value(output).unwrap()));
-        }
+        let output = RestoreFileVersionOutput {
+            path: path.to_string(),
+            restored_version: input.version,
+            new_version: restored.version,
+            backup_path,
+        };
 
-        // In a full implementation with version history, we would:
-        // 1. Look up the content_hash for version `input.version`
-        // 2. Read that content from the database
-        // 3. Write it to the file (which creates a new version)
-        //
-        // For now, return an informative error
-        Err(ToolError::ExecutionFailed(
-            format!(
-                "Version history restore requires version tracking. Current implementation only supports current version ({}). \
-                To enable full version history, a separate version_history table would need to be implemented.",
-                current_node.version
-            )
-        ))
+        Ok(ToolResult::success_json(serde_json::to_value(output).unwrap()))
     }
 }
 
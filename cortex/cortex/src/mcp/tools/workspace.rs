@@ -1,6 +1,6 @@
 //! Workspace Management Tools
 //!
-//! This module implements 12 workspace management tools:
+//! This module implements 15 workspace management tools:
 //!
 //! **Core Operations (8):**
 //! - cortex.workspace.create - Import existing project with auto-parsing
@@ -17,6 +17,11 @@
 //! - cortex.workspace.search - Search files/content within workspace
 //! - cortex.workspace.compare - Compare two workspaces and identify differences
 //! - cortex.workspace.merge - Merge workspaces with conflict resolution
+//!
+//! **Snapshots (3):**
+//! - cortex.workspace.snapshot - Take a named point-in-time snapshot
+//! - cortex.workspace.rollback - Restore a workspace to a snapshot
+//! - cortex.workspace.list_snapshots - List a workspace's snapshots
 
 use async_trait::async_trait;
 use chrono::Utc;
@@ -27,7 +32,7 @@ use regex;
 use cortex_vfs::{
     ExternalProjectLoader, FileIngestionPipeline, ImportOptions as VfsImportOptions,
     MaterializationEngine, VirtualFileSystem, VirtualPath, Workspace, SyncSource, SyncSourceType,
-    SyncSourceStatus, ForkManager, MergeStrategy, FileWatcher, WatcherConfig, AutoReparseHandle,
+    SyncSourceStatus, ForkManager, MergeStrategy, ChangeType, FileWatcher, WatcherConfig, AutoReparseHandle,
     AutoReparseConfig,
 };
 use cortex_memory::SemanticMemorySystem;
@@ -385,6 +390,7 @@ impl Tool for WorkspaceCreateTool {
             parent_workspace: None,
             fork_metadata: None,
             dependencies: Vec::new(),
+            version_retention: Default::default(),
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -671,6 +677,7 @@ impl Tool for WorkspaceListTool {
         use crate::services::workspace::ListWorkspaceFilters;
         let filters = ListWorkspaceFilters {
             limit: Some(input.limit),
+            ..Default::default()
         };
         let workspaces = self.ctx.workspace_service.list_workspaces(filters).await
             .map_err(|e| ToolError::ExecutionFailed(format!("Failed to list workspaces: {}", e)))?;
@@ -1896,3 +1903,274 @@ impl Tool for WorkspaceMergeTool {
         Ok(ToolResult::success_json(serde_json::to_value(output).unwrap()))
     }
 }
+
+// =============================================================================
+// cortex.workspace.snapshot
+// =============================================================================
+
+pub struct WorkspaceSnapshotTool {
+    ctx: WorkspaceContext,
+}
+
+impl WorkspaceSnapshotTool {
+    pub fn new(ctx: WorkspaceContext) -> Self {
+        Self { ctx }
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct SnapshotInput {
+    workspace_id: String,
+    /// Snapshot name, unique within the workspace
+    name: String,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+struct SnapshotOutput {
+    snapshot_id: String,
+    workspace_id: String,
+    name: String,
+    node_count: usize,
+    total_bytes: usize,
+    created_at: String,
+}
+
+#[async_trait]
+impl Tool for WorkspaceSnapshotTool {
+    fn name(&self) -> &str {
+        "cortex.workspace.snapshot"
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Creates a named, immutable point-in-time snapshot of all files in a workspace. Cheap: content is shared, not copied. Roll back to it with cortex.workspace.rollback.")
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        serde_json::to_value(schemars::schema_for!(SnapshotInput)).unwrap()
+    }
+
+    async fn execute(
+        &self,
+        input: serde_json::Value,
+        context: &ToolContext,
+    ) -> std::result::Result<ToolResult, ToolError> {
+        let input: SnapshotInput = serde_json::from_value(input)
+            .map_err(|e| ToolError::ExecutionFailed(format!("Invalid input: {}", e)))?;
+
+        let workspace_id = Uuid::parse_str(&input.workspace_id)
+            .map_err(|e| ToolError::ExecutionFailed(format!("Invalid workspace ID: {}", e)))?;
+
+        info!("Snapshotting workspace {} as '{}'", workspace_id, input.name);
+
+        let snapshot = self.ctx.workspace_service
+            .create_snapshot(
+                &workspace_id,
+                input.name,
+                input.description,
+                context.session_id().map(str::to_string),
+            )
+            .await
+            .map_err(|e| ToolError::ExecutionFailed(format!("Failed to create snapshot: {}", e)))?;
+
+        let output = SnapshotOutput {
+            snapshot_id: snapshot.id.to_string(),
+            workspace_id: workspace_id.to_string(),
+            name: snapshot.name,
+            node_count: snapshot.node_count,
+            total_bytes: snapshot.total_bytes,
+            created_at: snapshot.created_at.to_rfc3339(),
+        };
+
+        Ok(ToolResult::success_json(serde_json::to_value(output).unwrap()))
+    }
+}
+
+// =============================================================================
+// cortex.workspace.rollback
+// =============================================================================
+
+pub struct WorkspaceRollbackTool {
+    ctx: WorkspaceContext,
+}
+
+impl WorkspaceRollbackTool {
+    pub fn new(ctx: WorkspaceContext) -> Self {
+        Self { ctx }
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct RollbackInput {
+    workspace_id: String,
+    /// Name of the snapshot to restore
+    snapshot: String,
+    /// Work session of the caller, which does not block the rollback
+    #[serde(default)]
+    session_id: Option<String>,
+    /// Roll back even while other sessions are active on the workspace
+    #[serde(default)]
+    force: bool,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+struct RollbackOutput {
+    workspace_id: String,
+    snapshot: String,
+    change_set_id: String,
+    files_created: usize,
+    files_modified: usize,
+    files_deleted: usize,
+    changes: Vec<RollbackChange>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+struct RollbackChange {
+    path: String,
+    change_type: String,
+}
+
+#[async_trait]
+impl Tool for WorkspaceRollbackTool {
+    fn name(&self) -> &str {
+        "cortex.workspace.rollback"
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Restores a workspace to a named snapshot, recording every change as one auditable change set. Refuses while another session is active on the workspace unless force is true.")
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        serde_json::to_value(schemars::schema_for!(RollbackInput)).unwrap()
+    }
+
+    async fn execute(
+        &self,
+        input: serde_json::Value,
+        context: &ToolContext,
+    ) -> std::result::Result<ToolResult, ToolError> {
+        let input: RollbackInput = serde_json::from_value(input)
+            .map_err(|e| ToolError::ExecutionFailed(format!("Invalid input: {}", e)))?;
+
+        let workspace_id = Uuid::parse_str(&input.workspace_id)
+            .map_err(|e| ToolError::ExecutionFailed(format!("Invalid workspace ID: {}", e)))?;
+
+        warn!("Rolling back workspace {} to snapshot '{}'", workspace_id, input.snapshot);
+
+        use crate::services::workspace::RollbackRequest;
+        let request = RollbackRequest {
+            actor: input.session_id.clone().or_else(|| context.session_id().map(str::to_string)),
+            session_id: input.session_id,
+            force: input.force,
+        };
+
+        let report = self.ctx.workspace_service
+            .rollback_to_snapshot(&workspace_id, &input.snapshot, request)
+            .await
+            .map_err(|e| ToolError::ExecutionFailed(format!("Rollback failed: {}", e)))?;
+
+        let output = RollbackOutput {
+            workspace_id: workspace_id.to_string(),
+            snapshot: report.snapshot.name.clone(),
+            change_set_id: report.change_set.to_string(),
+            files_created: report.count(ChangeType::Created),
+            files_modified: report.count(ChangeType::Modified),
+            files_deleted: report.count(ChangeType::Deleted),
+            changes: report.changes.iter().map(|change| RollbackChange {
+                path: change.path.to_string(),
+                change_type: format!("{:?}", change.change_type).to_lowercase(),
+            }).collect(),
+        };
+
+        Ok(ToolResult::success_json(serde_json::to_value(output).unwrap()))
+    }
+}
+
+// =============================================================================
+// cortex.workspace.list_snapshots
+// =============================================================================
+
+pub struct WorkspaceListSnapshotsTool {
+    ctx: WorkspaceContext,
+}
+
+impl WorkspaceListSnapshotsTool {
+    pub fn new(ctx: WorkspaceContext) -> Self {
+        Self { ctx }
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct ListSnapshotsInput {
+    workspace_id: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+struct ListSnapshotsOutput {
+    workspace_id: String,
+    snapshots: Vec<SnapshotSummary>,
+    total: usize,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+struct SnapshotSummary {
+    snapshot_id: String,
+    name: String,
+    description: Option<String>,
+    created_by: Option<String>,
+    node_count: usize,
+    total_bytes: usize,
+    created_at: String,
+}
+
+#[async_trait]
+impl Tool for WorkspaceListSnapshotsTool {
+    fn name(&self) -> &str {
+        "cortex.workspace.list_snapshots"
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Lists the snapshots of a workspace, newest first.")
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        serde_json::to_value(schemars::schema_for!(ListSnapshotsInput)).unwrap()
+    }
+
+    async fn execute(
+        &self,
+        input: serde_json::Value,
+        _context: &ToolContext,
+    ) -> std::result::Result<ToolResult, ToolError> {
+        let input: ListSnapshotsInput = serde_json::from_value(input)
+            .map_err(|e| ToolError::ExecutionFailed(format!("Invalid input: {}", e)))?;
+
+        let workspace_id = Uuid::parse_str(&input.workspace_id)
+            .map_err(|e| ToolError::ExecutionFailed(format!("Invalid workspace ID: {}", e)))?;
+
+        let snapshots: Vec<SnapshotSummary> = self.ctx.workspace_service
+            .list_snapshots(&workspace_id)
+            .await
+            .map_err(|e| ToolError::ExecutionFailed(format!("Failed to list snapshots: {}", e)))?
+            .into_iter()
+            .map(|snapshot| SnapshotSummary {
+                snapshot_id: snapshot.id.to_string(),
+                name: snapshot.name,
+                description: snapshot.description,
+                created_by: snapshot.created_by,
+                node_count: snapshot.node_count,
+                total_bytes: snapshot.total_bytes,
+                created_at: snapshot.created_at.to_rfc3339(),
+            })
+            .collect();
+
+        let output = ListSnapshotsOutput {
+            workspace_id: workspace_id.to_string(),
+            total: snapshots.len(),
+            snapshots,
+        };
+
+        Ok(ToolResult::success_json(serde_json::to_value(output).unwrap()))
+    }
+}
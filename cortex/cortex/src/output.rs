@@ -210,6 +210,19 @@ pub fn format_bytes(bytes: u64) -> String {
     }
 }
 
+/// Format a count with thousands separators, e.g. `3,412`
+pub fn format_count(count: usize) -> String {
+    let digits = count.to_string();
+    let mut formatted = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            formatted.push(',');
+        }
+        formatted.push(digit);
+    }
+    formatted
+}
+
 /// Format duration in human-readable form
 pub fn format_duration(duration: Duration) -> String {
     let secs = duration.as_secs();
@@ -269,6 +282,14 @@ mod tests {
         assert_eq!(format_bytes(1536 * 1024 * 1024), "1.50 GB");
     }
 
+    #[test]
+    fn test_format_count() {
+        assert_eq!(format_count(0), "0");
+        assert_eq!(format_count(999), "999");
+        assert_eq!(format_count(3412), "3,412");
+        assert_eq!(format_count(1_234_567), "1,234,567");
+    }
+
     #[test]
     fn test_format_duration() {
         assert_eq!(format_duration(Duration::from_secs(30)), "30s");
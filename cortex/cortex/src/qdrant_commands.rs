@@ -1,15 +1,19 @@
 //! Qdrant command implementations for cortex
 
 use crate::output::{self, OutputFormat, TableBuilder};
+use crate::qdrant_snapshots;
 use anyhow::{Context, Result};
 use cortex_storage::{CollectionConfig, HnswConfig, OptimizerConfig, QdrantClient, QdrantConfig};
 use cortex_storage::qdrant::DistanceMetric;
+use cortex_semantic::config::SemanticConfig;
+use cortex_semantic::providers::ProviderManager;
+use cortex_semantic::{EmbeddingProvider, QdrantVectorStore, SemanticSearchEngine, VectorIndex};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 
 /// Collection definitions for Cortex
-fn get_collection_configs() -> Vec<CollectionConfig> {
+pub(crate) fn get_collection_configs() -> Vec<CollectionConfig> {
     vec![
         CollectionConfig {
             name: "code_vectors".to_string(),
@@ -49,8 +53,19 @@ fn get_collection_configs() -> Vec<CollectionConfig> {
     ]
 }
 
+/// Vector size of a collection with a single unnamed vector
+pub(crate) fn collection_vector_size(info: &cortex_storage::qdrant::CollectionInfo) -> Option<u64> {
+    info.config.as_ref()
+        .and_then(|c| c.params.as_ref())
+        .and_then(|p| p.vectors_config.as_ref())
+        .and_then(|v| match &v.config {
+            Some(qdrant_client::qdrant::vectors_config::Config::Params(params)) => Some(params.size),
+            _ => None,
+        })
+}
+
 /// Create Qdrant client from config
-async fn create_qdrant_client() -> Result<QdrantClient> {
+pub(crate) async fn create_qdrant_client() -> Result<QdrantClient> {
     let config = QdrantConfig {
         host: std::env::var("QDRANT_HOST").unwrap_or_else(|_| "localhost".to_string()),
         port: std::env::var("QDRANT_HTTP_PORT")
@@ -67,6 +82,7 @@ async fn create_qdrant_client() -> Result<QdrantClient> {
             .unwrap_or(false),
         timeout: std::time::Duration::from_secs(10),
         request_timeout: std::time::Duration::from_secs(60),
+        snapshot_s3: cortex_storage::S3Config::from_env()?,
     };
 
     QdrantClient::new(config).await
@@ -117,6 +133,12 @@ pub async fn qdrant_init(force: bool, skip_verify: bool) -> Result<()> {
         }
     }
 
+    if init_semantic_collection(force).await? {
+        created += 1;
+    } else {
+        skipped += 1;
+    }
+
     spinner.finish_with_message(format!(
         "Initialized {} collections ({} created, {} skipped)",
         created + skipped,
@@ -127,6 +149,46 @@ pub async fn qdrant_init(force: bool, skip_verify: bool) -> Result<()> {
     Ok(())
 }
 
+/// Create the semantic search collection from the index configuration, or
+/// recreate it with `force`. Returns whether the collection was (re)created;
+/// an existing collection is validated instead.
+async fn init_semantic_collection(force: bool) -> Result<bool> {
+    let config = SemanticConfig::default();
+
+    // The collection's dimension is the embedding model's
+    let provider = match ProviderManager::from_config(&config.embedding).await {
+        Ok(provider) => provider,
+        Err(e) => {
+            output::warning(format!("Skipping the semantic collection, no embedding provider: {}", e));
+            return Ok(false);
+        }
+    };
+    let store = QdrantVectorStore::connect(
+        config.qdrant.clone(),
+        provider.dimension(),
+        config.index.similarity_metric,
+    )
+    .await?;
+
+    if force {
+        output::warning(format!("Recreating collection: {}", store.collection_name()));
+        store.recreate_collection(&config.index).await?;
+        return Ok(true);
+    }
+
+    let existed = store.get_collection_info().await.is_ok();
+    store
+        .ensure_collection(&config.index)
+        .await
+        .with_context(|| format!("Collection {} cannot be used; recreate it with --force", store.collection_name()))?;
+    if existed {
+        output::info(format!("Collection already exists: {}", store.collection_name()));
+    } else {
+        output::info(format!("Created collection: {}", store.collection_name()));
+    }
+    Ok(!existed)
+}
+
 /// Show Qdrant status and statistics
 pub async fn qdrant_status(
     detailed: bool,
@@ -263,27 +325,9 @@ pub async fn qdrant_migrate(
     }
 
     // Check if dimensions match
-    let source_dim = source_info.config.as_ref()
-        .and_then(|c| c.params.as_ref())
-        .and_then(|p| p.vectors_config.as_ref())
-        .and_then(|v| {
-            if let Some(qdrant_client::qdrant::vectors_config::Config::Params(params)) = &v.config {
-                Some(params.size)
-            } else {
-                None
-            }
-        });
+    let source_dim = collection_vector_size(&source_info);
 
-    let target_dim = target_info.config.as_ref()
-        .and_then(|c| c.params.as_ref())
-        .and_then(|p| p.vectors_config.as_ref())
-        .and_then(|v| {
-            if let Some(qdrant_client::qdrant::vectors_config::Config::Params(params)) = &v.config {
-                Some(params.size)
-            } else {
-                None
-            }
-        });
+    let target_dim = collection_vector_size(&target_info);
 
     let needs_transform = match (source_dim, target_dim) {
         (Some(s), Some(t)) if s != t => {
@@ -608,8 +652,41 @@ pub async fn qdrant_benchmark(
     Ok(())
 }
 
-/// Create a snapshot
-pub async fn qdrant_snapshot(collection: Option<String>, output: Option<PathBuf>) -> Result<()> {
+/// Options for `cortex qdrant snapshot`
+#[derive(Debug, Clone, Default)]
+pub struct SnapshotOptions {
+    /// Reuse the previous set's file for collections that have not changed
+    pub incremental: bool,
+    /// Prune all but the newest N complete sets after a successful run
+    pub keep_last: Option<usize>,
+    /// Upload the new set to the S3-compatible storage from `QdrantConfig`
+    pub upload: bool,
+    /// Export the semantic search index to a portable file instead of
+    /// taking Qdrant snapshots
+    pub portable: bool,
+}
+
+/// File extension of portable semantic index snapshots
+const PORTABLE_SNAPSHOT_EXTENSION: &str = "ndjson.gz";
+
+/// Payload field used to detect the latest write to a collection
+const UPDATE_TIMESTAMP_FIELD: &str = "indexed_at";
+
+/// Create a snapshot.
+///
+/// Without `output` the snapshots stay on the Qdrant server. With `output`
+/// they are downloaded into a new snapshot set directory under it (see
+/// [`crate::qdrant_snapshots`]).
+pub async fn qdrant_snapshot(
+    collection: Option<String>,
+    output: Option<PathBuf>,
+    options: SnapshotOptions,
+) -> Result<()> {
+    if options.portable {
+        let root = output.context("--portable requires --output")?;
+        return export_portable_snapshot(&root).await;
+    }
+
     let client = create_qdrant_client().await?;
 
     let collections = if let Some(name) = collection {
@@ -618,21 +695,225 @@ pub async fn qdrant_snapshot(collection: Option<String>, output: Option<PathBuf>
         client.list_collections().await?
     };
 
+    let Some(root) = output else {
+        if options.incremental || options.keep_last.is_some() || options.upload {
+            anyhow::bail!("--incremental, --keep-last and --upload require --output");
+        }
+        for name in collections {
+            output::info(format!("Creating snapshot for collection: {}", name));
+            let snapshot_name = client.create_snapshot(&name).await?;
+            output::success(format!("  Snapshot created: {}", snapshot_name));
+        }
+        return Ok(());
+    };
+
+    // Fail before doing any work if the upload target is missing
+    let uploader = if options.upload {
+        let s3_config = client.config().snapshot_s3.clone().context(
+            "S3 upload requested but not configured (set CORTEX_S3_ENDPOINT and CORTEX_S3_BUCKET)",
+        )?;
+        Some(cortex_storage::S3Client::new(s3_config, client.config().request_timeout)?)
+    } else {
+        None
+    };
+
+    let previous = if options.incremental {
+        qdrant_snapshots::latest_consistent(&root)?
+    } else {
+        None
+    };
+    if let Some(ref previous) = previous {
+        output::info(format!("Incremental against snapshot set {}", previous.id));
+    }
+
+    let set_id = qdrant_snapshots::new_set_id(chrono::Utc::now());
+    let set_dir = root.join(&set_id);
+    std::fs::create_dir_all(&set_dir)
+        .with_context(|| format!("Failed to create {}", set_dir.display()))?;
+
+    let mut entries = Vec::new();
     for name in collections {
+        // Fingerprint before snapshotting: a write racing the snapshot makes the
+        // next run re-snapshot rather than miss it
+        let points_count = client.get_collection_stats(&name).await?.points_count;
+        let latest_update = match client.latest_payload_timestamp(&name, UPDATE_TIMESTAMP_FIELD).await {
+            Ok(latest) => latest,
+            Err(e) => {
+                tracing::debug!("No update timestamp for {}: {:#}", name, e);
+                None
+            }
+        };
+
+        let unchanged = previous
+            .as_ref()
+            .and_then(|p| p.entry(&name))
+            .filter(|entry| entry.is_unchanged(points_count, latest_update));
+        if let Some(entry) = unchanged {
+            output::info(format!("{}: unchanged, reusing set {}", name, entry.stored_in));
+            entries.push(entry.clone());
+            continue;
+        }
+
         output::info(format!("Creating snapshot for collection: {}", name));
         let snapshot_name = client.create_snapshot(&name).await?;
-        output::success(format!("  Snapshot created: {}", snapshot_name));
+        let size_bytes = client
+            .download_snapshot(&name, &snapshot_name, &set_dir.join(&snapshot_name))
+            .await?;
+        output::success(format!("  {} ({})", snapshot_name, output::format_bytes(size_bytes)));
+
+        entries.push(qdrant_snapshots::CollectionEntry {
+            name,
+            points_count,
+            latest_update,
+            stored_in: set_id.clone(),
+            file: snapshot_name,
+            size_bytes,
+        });
+    }
 
-        if let Some(ref output_dir) = output {
-            output::info(format!("  Snapshot location: {:?}", output_dir));
+    let manifest = qdrant_snapshots::SnapshotManifest {
+        version: qdrant_snapshots::MANIFEST_VERSION,
+        id: set_id.clone(),
+        created_at: chrono::Utc::now(),
+        parent: previous.map(|p| p.id),
+        collections: entries,
+    };
+    let manifest_path = manifest.write(&set_dir)?;
+    output::success(format!("Snapshot set written to {}", set_dir.display()));
+
+    if let Some(uploader) = uploader {
+        // Snapshot files first, manifest last, so the remote copy is only
+        // complete once everything arrived. On failure the local set stays.
+        let upload = async {
+            for entry in manifest.collections.iter().filter(|e| e.stored_in == set_id) {
+                let key = format!("{}/{}", set_id, entry.file);
+                uploader.put_file(&key, &entry.path(&root)).await?;
+                output::info(format!("  Uploaded {}", uploader.object_key(&key)));
+            }
+            let key = format!("{}/{}", set_id, qdrant_snapshots::MANIFEST_FILE);
+            uploader.put_file(&key, &manifest_path).await
+        };
+        upload.await.with_context(|| {
+            format!("Upload failed; local snapshot set kept at {}", set_dir.display())
+        })?;
+        output::success("Snapshot set uploaded");
+    }
+
+    if let Some(keep_last) = options.keep_last {
+        for id in qdrant_snapshots::prune(&root, keep_last)? {
+            output::info(format!("Pruned snapshot set {}", id));
         }
     }
 
     Ok(())
 }
 
-/// Restore from snapshot
+/// Restore from a snapshot file, a snapshot set directory, or an output root.
+///
+/// For a root, the newest set whose files are all present is restored; each
+/// collection is read from whichever set in the chain holds its file.
 pub async fn qdrant_restore(snapshot: PathBuf, collection: Option<String>) -> Result<()> {
+    if is_portable_snapshot(&snapshot) {
+        if collection.is_some() {
+            anyhow::bail!("A portable snapshot restores the semantic search index; --collection does not apply");
+        }
+        return import_portable_snapshot(&snapshot).await;
+    }
+
+    if !snapshot.is_dir() {
+        return restore_snapshot_file(snapshot, collection).await;
+    }
+
+    let (root, manifest) = if snapshot.join(qdrant_snapshots::MANIFEST_FILE).is_file() {
+        let root = snapshot.parent().map(PathBuf::from).unwrap_or_default();
+        let id = snapshot.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let manifest = qdrant_snapshots::list_sets(&root)?
+            .into_iter()
+            .find(|set| set.id == id)
+            .and_then(|set| set.manifest)
+            .context("Snapshot set has no readable manifest")?;
+        let missing = manifest.missing_files(&root);
+        if !missing.is_empty() {
+            anyhow::bail!("Snapshot set {} is incomplete; missing: {:?}", manifest.id, missing);
+        }
+        (root, manifest)
+    } else {
+        let manifest = qdrant_snapshots::latest_consistent(&snapshot)?
+            .with_context(|| format!("No complete snapshot set found in {}", snapshot.display()))?;
+        (snapshot, manifest)
+    };
+
+    output::info(format!("Restoring snapshot set {}", manifest.id));
+    let mut restored = 0;
+    for entry in &manifest.collections {
+        if collection.as_ref().is_some_and(|c| c != &entry.name) {
+            continue;
+        }
+        restore_snapshot_file(entry.path(&root), Some(entry.name.clone())).await?;
+        restored += 1;
+    }
+
+    if restored == 0 {
+        anyhow::bail!("Collection {:?} is not part of snapshot set {}", collection, manifest.id);
+    }
+    output::success(format!("Restored {} collection(s) from set {}", restored, manifest.id));
+
+    Ok(())
+}
+
+/// Export the semantic search index, vectors and payloads included, to a
+/// timestamped portable file under `root`
+async fn export_portable_snapshot(root: &Path) -> Result<()> {
+    let engine = SemanticSearchEngine::new(SemanticConfig::default())
+        .await
+        .context("Failed to open the semantic search index")?;
+
+    let path = root.join(format!(
+        "semantic-index-{}.{}",
+        qdrant_snapshots::new_set_id(chrono::Utc::now()),
+        PORTABLE_SNAPSHOT_EXTENSION
+    ));
+    let spinner = output::spinner("Exporting semantic index...");
+    let summary = engine.export_index(&path).await?;
+    spinner.finish_with_message(format!(
+        "Exported {} points ({} documents) to {}",
+        summary.points,
+        summary.documents,
+        path.display()
+    ));
+
+    Ok(())
+}
+
+fn is_portable_snapshot(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().ends_with(&format!(".{}", PORTABLE_SNAPSHOT_EXTENSION)))
+}
+
+/// Load a portable snapshot into the semantic search index. An interrupted
+/// restore resumes where it stopped when run again.
+async fn import_portable_snapshot(path: &Path) -> Result<()> {
+    let engine = SemanticSearchEngine::new(SemanticConfig::default())
+        .await
+        .context("Failed to open the semantic search index")?;
+
+    let spinner = output::spinner("Importing semantic index...");
+    let summary = engine
+        .import_index(path)
+        .await
+        .with_context(|| format!("Failed to import {}", path.display()))?;
+    if summary.resumed_points > 0 {
+        output::info(format!("Resumed after {} previously imported points", summary.resumed_points));
+    }
+    spinner.finish_with_message(format!("Imported {} points from {}", summary.points, path.display()));
+
+    Ok(())
+}
+
+/// Restore a single snapshot file
+async fn restore_snapshot_file(snapshot: PathBuf, collection: Option<String>) -> Result<()> {
     let spinner = output::spinner("Restoring snapshot...");
 
     // Validate snapshot file exists early
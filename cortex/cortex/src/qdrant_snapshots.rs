@@ -0,0 +1,263 @@
+//! Local snapshot sets for `cortex qdrant snapshot`.
+//!
+//! Each run writes a snapshot set directory under the output root, named by
+//! its creation time so names sort chronologically:
+//!
+//! ```text
+//! <root>/
+//!   20240102T030405123Z/
+//!     code_vectors-....snapshot
+//!     manifest.json
+//!   20240103T030405456Z/
+//!     manifest.json          # code_vectors unchanged: points at the set above
+//! ```
+//!
+//! The manifest is written last, so a set without one is incomplete. In an
+//! incremental run, a collection whose point count and latest update timestamp
+//! match the previous manifest is not snapshotted again; its entry references
+//! the set that already holds the file. Retention never prunes a set that a
+//! kept manifest still references.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Name of the manifest file inside a snapshot set
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Current manifest format version
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// Manifest describing one complete snapshot set
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    pub version: u32,
+    /// Set directory name
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    /// Set this one was compared against in an incremental run
+    pub parent: Option<String>,
+    pub collections: Vec<CollectionEntry>,
+}
+
+/// Snapshot of one collection within a set
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CollectionEntry {
+    pub name: String,
+    pub points_count: u64,
+    /// Latest `indexed_at` payload value, when the collection exposes one
+    pub latest_update: Option<i64>,
+    /// Set directory holding the snapshot file (this set or an ancestor)
+    pub stored_in: String,
+    pub file: String,
+    pub size_bytes: u64,
+}
+
+impl CollectionEntry {
+    /// Whether the collection is unchanged since this entry was recorded.
+    /// Without an update timestamp a change cannot be ruled out.
+    pub fn is_unchanged(&self, points_count: u64, latest_update: Option<i64>) -> bool {
+        self.points_count == points_count && self.latest_update.is_some() && self.latest_update == latest_update
+    }
+
+    /// Location of the snapshot file under the output root
+    pub fn path(&self, root: &Path) -> PathBuf {
+        root.join(&self.stored_in).join(&self.file)
+    }
+}
+
+impl SnapshotManifest {
+    pub fn entry(&self, collection: &str) -> Option<&CollectionEntry> {
+        self.collections.iter().find(|c| c.name == collection)
+    }
+
+    /// Write atomically so a partially written manifest is never read back
+    pub fn write(&self, set_dir: &Path) -> Result<PathBuf> {
+        let path = set_dir.join(MANIFEST_FILE);
+        let tmp = set_dir.join(format!("{}.tmp", MANIFEST_FILE));
+        std::fs::write(&tmp, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", tmp.display()))?;
+        std::fs::rename(&tmp, &path)
+            .with_context(|| format!("Failed to finalize {}", path.display()))?;
+        Ok(path)
+    }
+
+    /// Referenced snapshot files that are missing or truncated
+    pub fn missing_files(&self, root: &Path) -> Vec<PathBuf> {
+        self.collections
+            .iter()
+            .filter(|entry| {
+                std::fs::metadata(entry.path(root))
+                    .map(|m| m.len() != entry.size_bytes)
+                    .unwrap_or(true)
+            })
+            .map(|entry| entry.path(root))
+            .collect()
+    }
+}
+
+/// A set directory and its manifest (`None` if the run never completed)
+#[derive(Debug)]
+pub struct SnapshotSet {
+    pub id: String,
+    pub path: PathBuf,
+    pub manifest: Option<SnapshotManifest>,
+}
+
+/// Directory name for a set created at `now`
+pub fn new_set_id(now: DateTime<Utc>) -> String {
+    now.format("%Y%m%dT%H%M%S%3fZ").to_string()
+}
+
+/// All set directories under `root`, oldest first
+pub fn list_sets(root: &Path) -> Result<Vec<SnapshotSet>> {
+    if !root.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut sets = Vec::new();
+    for entry in std::fs::read_dir(root).with_context(|| format!("Failed to read {}", root.display()))? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        let id = entry.file_name().to_string_lossy().to_string();
+        let manifest_path = entry.path().join(MANIFEST_FILE);
+        let manifest = if manifest_path.is_file() {
+            let raw = std::fs::read_to_string(&manifest_path)?;
+            Some(serde_json::from_str(&raw)
+                .with_context(|| format!("Invalid manifest {}", manifest_path.display()))?)
+        } else {
+            None
+        };
+        sets.push(SnapshotSet { id, path: entry.path(), manifest });
+    }
+
+    sets.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(sets)
+}
+
+/// Newest set whose manifest is complete and whose files are all present
+pub fn latest_consistent(root: &Path) -> Result<Option<SnapshotManifest>> {
+    Ok(list_sets(root)?
+        .into_iter()
+        .rev()
+        .filter_map(|set| set.manifest)
+        .find(|manifest| manifest.missing_files(root).is_empty()))
+}
+
+/// Keep the newest `keep_last` complete sets plus every set they reference,
+/// and delete the rest (including incomplete sets). Returns the removed ids.
+pub fn prune(root: &Path, keep_last: usize) -> Result<Vec<String>> {
+    let sets = list_sets(root)?;
+
+    let kept: Vec<&SnapshotManifest> = sets
+        .iter()
+        .rev()
+        .filter_map(|set| set.manifest.as_ref())
+        .take(keep_last)
+        .collect();
+    let mut retained: HashSet<&str> = kept.iter().map(|m| m.id.as_str()).collect();
+    retained.extend(kept.iter().flat_map(|m| m.collections.iter().map(|c| c.stored_in.as_str())));
+
+    let mut removed = Vec::new();
+    for set in &sets {
+        if !retained.contains(set.id.as_str()) {
+            std::fs::remove_dir_all(&set.path)
+                .with_context(|| format!("Failed to remove {}", set.path.display()))?;
+            removed.push(set.id.clone());
+        }
+    }
+
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn entry(name: &str, stored_in: &str, file: &str, size: u64) -> CollectionEntry {
+        CollectionEntry {
+            name: name.to_string(),
+            points_count: 10,
+            latest_update: Some(100),
+            stored_in: stored_in.to_string(),
+            file: file.to_string(),
+            size_bytes: size,
+        }
+    }
+
+    /// Create a set with real files for entries stored in it
+    fn make_set(root: &Path, id: &str, collections: Vec<CollectionEntry>) -> SnapshotManifest {
+        let dir = root.join(id);
+        std::fs::create_dir_all(&dir).unwrap();
+        for entry in collections.iter().filter(|e| e.stored_in == id) {
+            std::fs::write(dir.join(&entry.file), vec![0u8; entry.size_bytes as usize]).unwrap();
+        }
+        let manifest = SnapshotManifest {
+            version: MANIFEST_VERSION,
+            id: id.to_string(),
+            created_at: Utc::now(),
+            parent: None,
+            collections,
+        };
+        manifest.write(&dir).unwrap();
+        manifest
+    }
+
+    #[test]
+    fn test_is_unchanged_requires_timestamp() {
+        let e = entry("code", "a", "code.snapshot", 4);
+        assert!(e.is_unchanged(10, Some(100)));
+        assert!(!e.is_unchanged(11, Some(100)));
+        assert!(!e.is_unchanged(10, Some(101)));
+
+        let mut untimed = e.clone();
+        untimed.latest_update = None;
+        assert!(!untimed.is_unchanged(10, None));
+    }
+
+    #[test]
+    fn test_latest_consistent_skips_incomplete_and_broken_sets() {
+        let root = TempDir::new().unwrap();
+        let first = make_set(root.path(), "20240101T000000000Z", vec![entry("code", "20240101T000000000Z", "code.snapshot", 4)]);
+
+        // Newer set references a file that does not exist
+        make_set(root.path(), "20240102T000000000Z", vec![entry("code", "20240102T000000000Z", "gone.snapshot", 4)]);
+        std::fs::remove_file(root.path().join("20240102T000000000Z/gone.snapshot")).unwrap();
+
+        // Newest set never finished
+        std::fs::create_dir_all(root.path().join("20240103T000000000Z")).unwrap();
+
+        assert_eq!(latest_consistent(root.path()).unwrap(), Some(first));
+    }
+
+    #[test]
+    fn test_prune_keeps_referenced_ancestors() {
+        let root = TempDir::new().unwrap();
+        let base = "20240101T000000000Z";
+        make_set(root.path(), base, vec![entry("code", base, "code.snapshot", 4), entry("memory", base, "memory.snapshot", 2)]);
+        make_set(root.path(), "20240102T000000000Z", vec![entry("code", "20240102T000000000Z", "code.snapshot", 4), entry("memory", base, "memory.snapshot", 2)]);
+        let latest = "20240103T000000000Z";
+        make_set(root.path(), latest, vec![entry("code", latest, "code.snapshot", 4), entry("memory", base, "memory.snapshot", 2)]);
+        std::fs::create_dir_all(root.path().join("20231231T000000000Z")).unwrap();
+
+        let removed = prune(root.path(), 1).unwrap();
+
+        assert_eq!(removed, vec!["20231231T000000000Z", "20240102T000000000Z"]);
+        let remaining: Vec<String> = list_sets(root.path()).unwrap().into_iter().map(|s| s.id).collect();
+        assert_eq!(remaining, vec![base, latest]);
+        assert!(latest_consistent(root.path()).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_set_ids_sort_chronologically() {
+        let earlier = new_set_id(DateTime::parse_from_rfc3339("2024-01-02T03:04:05.120Z").unwrap().with_timezone(&Utc));
+        let later = new_set_id(DateTime::parse_from_rfc3339("2024-01-02T03:04:05.121Z").unwrap().with_timezone(&Utc));
+        assert_eq!(earlier, "20240102T030405120Z");
+        assert!(earlier < later);
+    }
+}
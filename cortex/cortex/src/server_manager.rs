@@ -21,6 +21,10 @@ pub struct ServerConfig {
     
     /// Number of worker threads
     pub workers: Option<usize>,
+
+    /// Require authentication on all routes except health and login
+    #[serde(default)]
+    pub require_auth: bool,
     
     /// Log file path
     pub log_file: PathBuf,
@@ -44,6 +48,7 @@ impl Default for ServerConfig {
             host: "127.0.0.1".to_string(),
             port: 8080,
             workers: None,
+            require_auth: false,
             log_file: ryht_dir.join("logs").join("api-server.log"),
             pid_file: ryht_dir.join("api-server.pid"),
             startup_timeout_secs: 30,
@@ -102,6 +107,14 @@ impl ServerManager {
         if let Some(workers) = self.config.workers {
             cmd.arg("--workers").arg(workers.to_string());
         }
+
+        if self.config.require_auth {
+            cmd.arg("--require-auth");
+        }
+
+        if crate::version_check::skip_version_check() {
+            cmd.arg("--skip-version-check");
+        }
         
         // Redirect stdout/stderr to log file
         // We need to use std::fs::File for Stdio, not tokio::fs::File
@@ -649,9 +649,12 @@ impl AuthService {
     ) -> Result<ApiKey> {
         info!("Creating API key for user: {}", user_id);
 
+        // Reject unknown scopes up front so a typo cannot silently grant nothing
+        ApiScope::parse_list(&scopes)?;
+
         // Generate API key
         let key_id = Uuid::new_v4().to_string();
-        let api_key = format!("cortex_{}", Uuid::new_v4().simple());
+        let api_key = format!("{}{}", API_KEY_PREFIX, Uuid::new_v4().simple());
 
         // Hash the API key for storage (CPU-bound, must run in blocking thread)
         let api_key_clone = api_key.clone();
@@ -669,6 +672,7 @@ impl AuthService {
             user_id: user_id.to_string(),
             name: name.clone(),
             key_hash: key_hash.clone(),
+            key_prefix: Some(lookup_prefix(&api_key).to_string()),
             scopes: scopes.clone(),
             expires_at,
             created_at: now,
@@ -701,12 +705,15 @@ impl AuthService {
 
         let conn = self.storage.acquire().await?;
 
-        // Get all API keys (we need to hash-compare them)
-        // In production, you'd want to optimize this with an index or key prefix
-        let query = "SELECT * FROM api_keys WHERE expires_at IS NULL OR expires_at > $now";
+        // Narrow candidates by the non-secret key prefix before the (slow)
+        // bcrypt comparison; keys created before prefixes were stored have none
+        let query = "SELECT * FROM api_keys
+            WHERE (expires_at IS NULL OR expires_at > $now)
+            AND (key_prefix = $prefix OR key_prefix IS NONE)";
         let mut result = conn.connection()
             .query(query)
             .bind(("now", Utc::now()))
+            .bind(("prefix", lookup_prefix(key).to_string()))
             .await?;
 
         let api_keys: Vec<ApiKeyRecord> = result.take(0)?;
@@ -782,6 +789,20 @@ impl AuthService {
         Ok(keys.into_iter().map(ApiKeyInfo::from_record).collect())
     }
 
+    /// List all API keys regardless of owner
+    pub async fn list_all_api_keys(&self) -> Result<Vec<ApiKeyInfo>> {
+        debug!("Listing all API keys");
+
+        let conn = self.storage.acquire().await?;
+
+        let query = "SELECT * FROM api_keys ORDER BY created_at DESC";
+        let mut result = conn.connection().query(query).await?;
+
+        let keys: Vec<ApiKeyRecord> = result.take(0)?;
+
+        Ok(keys.into_iter().map(ApiKeyInfo::from_record).collect())
+    }
+
     // ========================================================================
     // Token Generation
     // ========================================================================
@@ -914,6 +935,70 @@ pub struct AuthenticatedUser {
     pub expires_in: i64,
 }
 
+/// Prefix of every generated API key
+pub const API_KEY_PREFIX: &str = "cortex_";
+
+/// Owner recorded for server-level API keys created from the CLI
+pub const SERVER_KEY_OWNER: &str = "server";
+
+/// Number of leading key characters stored in clear for lookups
+const KEY_LOOKUP_PREFIX_LEN: usize = 15;
+
+/// Non-secret lookup prefix of an API key
+fn lookup_prefix(key: &str) -> &str {
+    key.get(..KEY_LOOKUP_PREFIX_LEN).unwrap_or(key)
+}
+
+/// Access scope granted to an API key
+///
+/// Scopes are ordered: `admin` implies `write`, which implies `read`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiScope {
+    Read,
+    Write,
+    Admin,
+}
+
+impl ApiScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiScope::Read => "read",
+            ApiScope::Write => "write",
+            ApiScope::Admin => "admin",
+        }
+    }
+
+    /// Whether holding this scope satisfies `required`
+    pub fn grants(&self, required: ApiScope) -> bool {
+        *self >= required
+    }
+
+    /// Parse a list of scope names, failing on the first unknown one
+    pub fn parse_list(scopes: &[String]) -> Result<Vec<ApiScope>> {
+        scopes.iter().map(|s| s.parse()).collect()
+    }
+}
+
+impl std::str::FromStr for ApiScope {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "read" => Ok(ApiScope::Read),
+            "write" => Ok(ApiScope::Write),
+            "admin" => Ok(ApiScope::Admin),
+            other => Err(anyhow!("Unknown API key scope '{}' (expected read, write or admin)", other)),
+        }
+    }
+}
+
+impl std::fmt::Display for ApiScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// JWT claims
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Claims {
@@ -932,6 +1017,9 @@ struct ApiKeyRecord {
     pub user_id: String,
     pub name: String,
     pub key_hash: String,
+    /// Non-secret key prefix used to narrow lookups (absent on older keys)
+    #[serde(default)]
+    pub key_prefix: Option<String>,
     pub scopes: Vec<String>,
     pub expires_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
@@ -1026,4 +1114,27 @@ mod tests {
         assert_eq!(deserialized.email, claims.email);
         assert_eq!(deserialized.token_type, claims.token_type);
     }
+
+    #[test]
+    fn test_api_scope_ordering() {
+        assert!(ApiScope::Admin.grants(ApiScope::Write));
+        assert!(ApiScope::Write.grants(ApiScope::Read));
+        assert!(!ApiScope::Read.grants(ApiScope::Write));
+        assert!(!ApiScope::Write.grants(ApiScope::Admin));
+    }
+
+    #[test]
+    fn test_api_scope_parsing() {
+        let scopes = ApiScope::parse_list(&["read".to_string(), "ADMIN".to_string()]).unwrap();
+        assert_eq!(scopes, vec![ApiScope::Read, ApiScope::Admin]);
+        assert!(ApiScope::parse_list(&["superuser".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_lookup_prefix() {
+        let key = format!("{}{}", API_KEY_PREFIX, Uuid::new_v4().simple());
+        assert_eq!(lookup_prefix(&key).len(), KEY_LOOKUP_PREFIX_LEN);
+        assert!(lookup_prefix(&key).starts_with(API_KEY_PREFIX));
+        assert_eq!(lookup_prefix("short"), "short");
+    }
 }
@@ -5,6 +5,7 @@
 
 use anyhow::Result;
 use cortex_storage::ConnectionManager;
+use crate::mcp::graph_algorithms::{analyze_architecture, ArchitectureReport, Graph, RuleSet};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
@@ -185,6 +186,58 @@ impl DependencyService {
         Ok(cycles)
     }
 
+    /// Check the architecture of all indexed code against `rules`
+    ///
+    /// Loads the symbol dependency graph with each symbol's file and line and
+    /// hands it to [`analyze_architecture`].
+    pub async fn check_architecture(&self, rules: &RuleSet) -> Result<ArchitectureReport> {
+        debug!("Checking architecture");
+
+        let pooled = self.storage.acquire().await?;
+        let conn = pooled.connection();
+
+        #[derive(Deserialize)]
+        struct DepEdge {
+            source_id: String,
+            target_id: String,
+            dependency_type: String,
+        }
+
+        #[derive(Deserialize)]
+        struct UnitLocation {
+            cortex_id: String,
+            file_path: String,
+            start_line: usize,
+        }
+
+        let mut result = conn
+            .query("SELECT source_id, target_id, type::string(dependency_type) AS dependency_type FROM DEPENDS_ON")
+            .query("SELECT cortex_id, file_path, start_line FROM code_unit")
+            .await?;
+        let edges: Vec<DepEdge> = result.take(0)?;
+        let units: Vec<UnitLocation> = result.take(1)?;
+
+        let mut graph = Graph::new();
+        for edge in edges {
+            graph.add_typed_edge(edge.source_id, edge.target_id, edge.dependency_type);
+        }
+        for unit in units {
+            graph.set_node_line(unit.cortex_id.clone(), unit.start_line);
+            graph.set_node_file(unit.cortex_id, unit.file_path);
+        }
+
+        let report = analyze_architecture(&graph, rules);
+
+        info!(
+            "Architecture check: {} modules, {} cycles, {} violations",
+            report.modules,
+            report.cycles.len(),
+            report.violations.len()
+        );
+
+        Ok(report)
+    }
+
     /// Analyze impact of changes to code units
     pub async fn analyze_impact(
         &self,
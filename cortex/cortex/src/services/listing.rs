@@ -0,0 +1,172 @@
+//! Shared listing primitives for services
+//!
+//! Provides sort validation and limit/offset windows that services push down
+//! into SurrealDB queries instead of slicing full result sets in memory.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Sort direction for list queries
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+impl SortDirection {
+    fn as_surql(&self) -> &'static str {
+        match self {
+            SortDirection::Asc => "ASC",
+            SortDirection::Desc => "DESC",
+        }
+    }
+}
+
+/// Validated sort specification (field + direction)
+///
+/// Fields are always checked against a per-endpoint allowlist before they are
+/// interpolated into a query, so `field` is safe to embed in `ORDER BY`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortSpec {
+    pub field: String,
+    pub direction: SortDirection,
+}
+
+impl SortSpec {
+    /// Create a sort specification without validation
+    pub fn new(field: impl Into<String>, direction: SortDirection) -> Self {
+        Self {
+            field: field.into(),
+            direction,
+        }
+    }
+
+    /// Parse a `sort` query parameter against an allowlist of fields.
+    ///
+    /// Accepted forms are `field`, `field:asc`, `field:desc` and `-field`
+    /// (descending). A missing or empty value yields `default`.
+    pub fn parse(raw: Option<&str>, allowed: &[&str], default: SortSpec) -> Result<Self, String> {
+        let raw = match raw.map(str::trim) {
+            Some(value) if !value.is_empty() => value,
+            _ => return Ok(default),
+        };
+
+        let (field, direction) = if let Some(field) = raw.strip_prefix('-') {
+            (field, SortDirection::Desc)
+        } else if let Some((field, dir)) = raw.split_once(':') {
+            let direction = match dir.to_ascii_lowercase().as_str() {
+                "asc" => SortDirection::Asc,
+                "desc" => SortDirection::Desc,
+                other => return Err(format!("Invalid sort direction '{}': expected asc or desc", other)),
+            };
+            (field, direction)
+        } else {
+            (raw, SortDirection::Asc)
+        };
+
+        if !allowed.contains(&field) {
+            return Err(format!(
+                "Invalid sort field '{}': allowed fields are {}",
+                field,
+                allowed.join(", ")
+            ));
+        }
+
+        Ok(Self::new(field, direction))
+    }
+
+    /// Render as a SurrealQL `ORDER BY` clause
+    pub fn to_order_clause(&self) -> String {
+        format!("ORDER BY {} {}", self.field, self.direction.as_surql())
+    }
+}
+
+impl fmt::Display for SortSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let dir = match self.direction {
+            SortDirection::Asc => "asc",
+            SortDirection::Desc => "desc",
+        };
+        write!(f, "{}:{}", self.field, dir)
+    }
+}
+
+/// Limit/offset window for list queries
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageWindow {
+    pub limit: usize,
+    pub offset: usize,
+}
+
+impl PageWindow {
+    pub fn new(limit: usize, offset: usize) -> Self {
+        Self { limit, offset }
+    }
+
+    /// Render as a SurrealQL `LIMIT ... START ...` clause
+    pub fn to_limit_clause(&self) -> String {
+        format!("LIMIT {} START {}", self.limit, self.offset)
+    }
+}
+
+/// One page of results together with the unpaginated total
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: usize,
+}
+
+impl<T> Page<T> {
+    /// Whether more items exist after this page
+    pub fn has_more(&self, window: &PageWindow) -> bool {
+        window.offset + self.items.len() < self.total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIELDS: &[&str] = &["name", "created_at"];
+
+    fn default_sort() -> SortSpec {
+        SortSpec::new("created_at", SortDirection::Desc)
+    }
+
+    #[test]
+    fn test_parse_sort_forms() {
+        let spec = SortSpec::parse(Some("name"), FIELDS, default_sort()).unwrap();
+        assert_eq!(spec, SortSpec::new("name", SortDirection::Asc));
+
+        let spec = SortSpec::parse(Some("name:desc"), FIELDS, default_sort()).unwrap();
+        assert_eq!(spec.direction, SortDirection::Desc);
+
+        let spec = SortSpec::parse(Some("-created_at"), FIELDS, default_sort()).unwrap();
+        assert_eq!(spec, SortSpec::new("created_at", SortDirection::Desc));
+
+        let spec = SortSpec::parse(None, FIELDS, default_sort()).unwrap();
+        assert_eq!(spec, default_sort());
+    }
+
+    #[test]
+    fn test_parse_sort_rejects_unknown() {
+        assert!(SortSpec::parse(Some("size; DELETE vnode"), FIELDS, default_sort()).is_err());
+        assert!(SortSpec::parse(Some("name:sideways"), FIELDS, default_sort()).is_err());
+    }
+
+    #[test]
+    fn test_clauses() {
+        let spec = SortSpec::new("name", SortDirection::Asc);
+        assert_eq!(spec.to_order_clause(), "ORDER BY name ASC");
+        assert_eq!(spec.to_string(), "name:asc");
+        assert_eq!(PageWindow::new(20, 40).to_limit_clause(), "LIMIT 20 START 40");
+    }
+
+    #[test]
+    fn test_page_has_more() {
+        let page = Page { items: vec![1, 2], total: 5 };
+        assert!(page.has_more(&PageWindow::new(2, 0)));
+        assert!(!page.has_more(&PageWindow::new(2, 3)));
+    }
+}
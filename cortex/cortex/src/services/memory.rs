@@ -2,6 +2,7 @@
 //!
 //! Provides unified cognitive memory operations for both API and MCP modules.
 
+use super::listing::{Page, PageWindow, SortSpec};
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use cortex_memory::CognitiveManager;
@@ -239,40 +240,51 @@ impl MemoryService {
         let episodes_raw: Vec<serde_json::Value> = response.take(0)?;
 
         let episodes: Vec<EpisodeDetails> = episodes_raw
-            .into_iter()
-            .filter_map(|ep| {
-                // Calculate importance based on success metrics
-                let importance = if let Some(metrics) = ep.get("success_metrics") {
-                    if let Some(obj) = metrics.as_object() {
-                        obj.values()
-                            .filter_map(|v| v.as_f64())
-                            .sum::<f64>() / obj.len().max(1) as f64
-                    } else {
-                        0.5
-                    }
-                } else {
-                    0.5
-                };
-
-                // Filter by minimum importance
-                if importance < min_importance {
-                    return None;
-                }
-
-                Some(EpisodeDetails {
-                    id: ep.get("cortex_id")?.as_str()?.to_string(),
-                    task_description: ep.get("task_description")?.as_str()?.to_string(),
-                    episode_type: ep.get("episode_type")?.as_str()?.to_string(),
-                    outcome: ep.get("outcome")?.as_str()?.to_string(),
-                    importance,
-                    created_at: serde_json::from_value(ep.get("created_at")?.clone()).ok()?,
-                })
-            })
+            .iter()
+            .filter_map(EpisodeDetails::from_row)
+            // Filter by minimum importance
+            .filter(|ep| ep.importance >= min_importance)
             .collect();
 
         Ok(episodes)
     }
 
+    /// List episodes one page at a time, with sorting and paging done in the database
+    pub async fn list_episodes(&self, sort: &SortSpec, window: PageWindow) -> Result<Page<EpisodeDetails>> {
+        debug!("Listing episodes (sort: {}, limit: {}, offset: {})", sort, window.limit, window.offset);
+
+        let conn = self.storage.acquire().await?;
+
+        let query_str = format!(
+            "SELECT
+                cortex_id,
+                type::string(episode_type) as episode_type,
+                task_description,
+                created_at,
+                duration_seconds,
+                type::string(outcome) as outcome,
+                success_metrics
+            FROM episode {} {};
+            SELECT count() as total FROM episode GROUP ALL;",
+            sort.to_order_clause(),
+            window.to_limit_clause()
+        );
+
+        let mut response = conn.connection().query(&query_str).await?;
+        let episodes_raw: Vec<serde_json::Value> = response.take(0)?;
+        let count_results: Vec<serde_json::Value> = response.take(1).unwrap_or_default();
+
+        let total = count_results
+            .first()
+            .and_then(|v| v.get("total").and_then(|t| t.as_u64()))
+            .unwrap_or(0) as usize;
+
+        Ok(Page {
+            items: episodes_raw.iter().filter_map(EpisodeDetails::from_row).collect(),
+            total,
+        })
+    }
+
     /// Get episode details
     pub async fn get_episode(&self, episode_id: &str) -> Result<Option<EpisodeDetails>> {
         debug!("Getting episode: {}", episode_id);
@@ -503,6 +515,26 @@ pub struct EpisodeDetails {
     pub created_at: DateTime<Utc>,
 }
 
+impl EpisodeDetails {
+    /// Build from a raw episode row, deriving importance from success metrics
+    fn from_row(ep: &serde_json::Value) -> Option<Self> {
+        let importance = ep
+            .get("success_metrics")
+            .and_then(|metrics| metrics.as_object())
+            .map(|obj| obj.values().filter_map(|v| v.as_f64()).sum::<f64>() / obj.len().max(1) as f64)
+            .unwrap_or(0.5);
+
+        Some(Self {
+            id: ep.get("cortex_id")?.as_str()?.to_string(),
+            task_description: ep.get("task_description")?.as_str()?.to_string(),
+            episode_type: ep.get("episode_type")?.as_str()?.to_string(),
+            outcome: ep.get("outcome")?.as_str()?.to_string(),
+            importance,
+            created_at: serde_json::from_value(ep.get("created_at")?.clone()).ok()?,
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct PatternDetails {
     pub id: String,
@@ -551,6 +583,25 @@ mod tests {
         assert!(json.contains("Test task"));
     }
 
+    #[test]
+    fn test_episode_details_from_row() {
+        let row = serde_json::json!({
+            "cortex_id": "ep-1",
+            "task_description": "Refactor parser",
+            "episode_type": "refactor",
+            "outcome": "success",
+            "created_at": Utc::now(),
+            "success_metrics": { "tests": 1.0, "review": 0.5 },
+        });
+
+        let episode = EpisodeDetails::from_row(&row).unwrap();
+        assert_eq!(episode.id, "ep-1");
+        assert!((episode.importance - 0.75).abs() < f64::EPSILON);
+
+        let missing_id = serde_json::json!({ "task_description": "x" });
+        assert!(EpisodeDetails::from_row(&missing_id).is_none());
+    }
+
     #[test]
     fn test_pattern_details_serialization() {
         let pattern = PatternDetails {
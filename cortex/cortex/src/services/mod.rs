@@ -16,6 +16,7 @@ pub mod build;
 pub mod document;
 pub mod notifications;
 pub mod notification_integration;
+pub mod listing;
 
 pub use workspace::WorkspaceService;
 pub use vfs::VfsService;
@@ -29,6 +30,7 @@ pub use build::BuildService;
 pub use document::DocumentService;
 pub use notifications::{NotificationService, AgentNotification, EventType, Severity};
 pub use notification_integration::*;
+pub use listing::{Page, PageWindow, SortDirection, SortSpec};
 
 #[cfg(test)]
 mod tests;
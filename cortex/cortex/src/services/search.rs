@@ -3,7 +3,9 @@
 //! Provides unified search operations for both API and MCP modules.
 
 use anyhow::Result;
-use cortex_semantic::{SemanticConfig, SemanticSearchEngine, SearchFilter};
+use cortex_semantic::{
+    AnswerSnippet, FilterExpr, ReadinessStatus, SemanticConfig, SemanticSearchEngine, SearchFilter, WarmupReport,
+};
 use cortex_semantic::types::EntityType;
 use cortex_storage::ConnectionManager;
 use serde::{Deserialize, Serialize};
@@ -12,6 +14,10 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, info};
 
+/// Byte limit of the full content returned inline with semantic results;
+/// larger code units are read back from storage when opened
+const INLINE_CONTENT_BYTES: usize = 64 * 1024;
+
 /// Search service for code and semantic search operations
 #[derive(Clone)]
 pub struct SearchService {
@@ -43,6 +49,17 @@ impl SearchService {
         }
     }
 
+    /// Load the embedding model and connect the vector store ahead of the
+    /// first search
+    pub async fn warm_up(&self) -> WarmupReport {
+        self.semantic_engine.read().await.warm_up().await
+    }
+
+    /// Whether semantic search is warmed up and ready to serve
+    pub async fn readiness(&self) -> ReadinessStatus {
+        self.semantic_engine.read().await.readiness()
+    }
+
     /// Search code using semantic embeddings
     pub async fn search_code(&self, request: SearchCodeRequest) -> Result<Vec<SearchResult>> {
         info!("Semantic code search: '{}'", request.query);
@@ -50,19 +67,35 @@ impl SearchService {
         let mut filter = SearchFilter::default();
         filter.entity_type = Some(EntityType::Code);
         filter.min_score = Some(request.min_similarity);
+        request_inline_content(&mut filter);
 
         if let Some(lang) = &request.language {
             filter.metadata_filters.insert("language".to_string(), lang.clone());
         }
+        filter.expr = request.filter.clone();
+
+        // Code units are not indexed with their workspace, so results are
+        // narrowed down afterwards from a larger candidate set
+        let fetch_limit = match request.workspace_id {
+            Some(_) => request.limit * 4,
+            None => request.limit,
+        };
 
         let engine = self.semantic_engine.read().await;
         let search_results = engine
-            .search_with_filter(&request.query, request.limit, filter)
+            .search_with_filter(&request.query, fetch_limit, filter)
             .await?;
 
         let results = search_results
             .into_iter()
+            .filter(|r| {
+                request.workspace_id.as_deref().is_none_or(|workspace_id| {
+                    in_workspace(r.metadata.get("file_path").map(String::as_str), &r.metadata, workspace_id)
+                })
+            })
+            .take(request.limit)
             .map(|r| SearchResult {
+                full_content: inline_content(&r),
                 id: r.id.clone(),
                 title: r.metadata.get("name").cloned().unwrap_or_else(|| r.id.clone()),
                 content: if r.content.len() > 200 {
@@ -75,6 +108,7 @@ impl SearchService {
                 file_path: r.metadata.get("file_path").cloned(),
                 language: r.metadata.get("language").cloned(),
                 metadata: r.metadata,
+                answer_snippet: r.answer_snippet,
             })
             .collect();
 
@@ -110,6 +144,7 @@ impl SearchService {
         let mut filter = SearchFilter::default();
         filter.entity_type = Some(EntityType::Code);
         filter.min_score = Some(request.similarity_threshold);
+        request_inline_content(&mut filter);
 
         let engine = self.semantic_engine.read().await;
         let search_results = engine
@@ -122,6 +157,7 @@ impl SearchService {
             .filter(|r| r.id != request.reference_unit_id)
             .take(request.limit)
             .map(|r| SearchResult {
+                full_content: inline_content(&r),
                 id: r.id.clone(),
                 title: r.metadata.get("name").cloned().unwrap_or_else(|| r.id.clone()),
                 content: r.content.clone(),
@@ -130,6 +166,7 @@ impl SearchService {
                 file_path: r.metadata.get("file_path").cloned(),
                 language: r.metadata.get("language").cloned(),
                 metadata: r.metadata,
+                answer_snippet: r.answer_snippet,
             })
             .collect();
 
@@ -143,6 +180,7 @@ impl SearchService {
         let mut filter = SearchFilter::default();
         filter.entity_type = Some(EntityType::Code);
         filter.min_score = Some(request.min_similarity);
+        request_inline_content(&mut filter);
 
         let engine = self.semantic_engine.read().await;
         let search_results = engine
@@ -152,6 +190,7 @@ impl SearchService {
         let results = search_results
             .into_iter()
             .map(|r| SearchResult {
+                full_content: inline_content(&r),
                 id: r.id.clone(),
                 title: r.metadata.get("name").cloned().unwrap_or_else(|| r.id.clone()),
                 content: if r.content.len() > 200 {
@@ -164,6 +203,7 @@ impl SearchService {
                 file_path: r.metadata.get("file_path").cloned(),
                 language: r.metadata.get("language").cloned(),
                 metadata: r.metadata,
+                answer_snippet: r.answer_snippet,
             })
             .collect();
 
@@ -177,16 +217,27 @@ impl SearchService {
         let conn = self.storage.acquire().await?;
 
         let (query, result_type) = match request.search_type.as_str() {
-            "code_units" => (
-                format!(
-                    "SELECT * FROM code_unit WHERE
-                     name CONTAINS $query OR
-                     signature CONTAINS $query OR
-                     summary CONTAINS $query
-                     LIMIT $limit"
-                ),
-                "code_unit",
-            ),
+            "code_units" => {
+                // Code units belong to a workspace by their path, as in CodeUnitService
+                let mut filters = String::new();
+                if request.language.is_some() {
+                    filters.push_str(" AND language = $language");
+                }
+                if request.workspace_id.is_some() {
+                    filters.push_str(" AND file_path CONTAINS $workspace_id");
+                }
+                (
+                    format!(
+                        "SELECT * FROM code_unit WHERE
+                         (name CONTAINS $query OR
+                         signature CONTAINS $query OR
+                         summary CONTAINS $query){}
+                         LIMIT $limit",
+                        filters
+                    ),
+                    "code_unit",
+                )
+            }
             "patterns" => (
                 format!(
                     "SELECT * FROM learned_pattern WHERE
@@ -204,6 +255,8 @@ impl SearchService {
             .query(&query)
             .bind(("query", request.query.clone()))
             .bind(("limit", request.limit))
+            .bind(("language", request.language.clone()))
+            .bind(("workspace_id", request.workspace_id.clone()))
             .await?;
 
         let items: Vec<serde_json::Value> = response.take(0)?;
@@ -243,6 +296,8 @@ impl SearchService {
                     file_path: item.get("file_path").and_then(|v| v.as_str()).map(String::from),
                     language: item.get("language").and_then(|v| v.as_str()).map(String::from),
                     metadata: HashMap::new(),
+                    answer_snippet: None,
+                    full_content: None,
                 }
             })
             .collect();
@@ -250,6 +305,70 @@ impl SearchService {
         Ok(results)
     }
 
+    /// Hybrid search: semantic and text matches over code, merged with
+    /// reciprocal rank fusion so results found both ways rank first
+    pub async fn search_hybrid(&self, request: HybridSearchRequest) -> Result<Vec<SearchResult>> {
+        info!("Hybrid code search: '{}'", request.query);
+
+        let semantic = self
+            .search_code(SearchCodeRequest {
+                query: request.query.clone(),
+                limit: request.limit,
+                min_similarity: request.min_similarity,
+                language: request.language.clone(),
+                workspace_id: request.workspace_id.clone(),
+                filter: None,
+            })
+            .await?;
+        let text = self
+            .search_text(TextSearchRequest {
+                query: request.query.clone(),
+                search_type: "code_units".to_string(),
+                limit: request.limit,
+                language: request.language.clone(),
+                workspace_id: request.workspace_id.clone(),
+            })
+            .await?;
+
+        let mut results = fuse_rankings(vec![semantic, text]);
+        results.truncate(request.limit);
+        Ok(results)
+    }
+
+    /// Full content of a search result
+    ///
+    /// Semantic results carry their full content inline unless it was too
+    /// large; other code units are read back in full, anything else is
+    /// returned as found.
+    pub async fn result_content(&self, result: &SearchResult) -> Result<String> {
+        if let Some(content) = &result.full_content {
+            return Ok(content.clone());
+        }
+        if result.result_type == "pattern" {
+            return Ok(result.content.clone());
+        }
+
+        let conn = self.storage.acquire().await?;
+        let mut response = conn
+            .connection()
+            .query("SELECT * FROM code_unit WHERE id = $unit_id LIMIT 1")
+            .bind(("unit_id", result.id.clone()))
+            .await?;
+
+        let units: Vec<serde_json::Value> = response.take(0)?;
+        let Some(unit) = units.into_iter().next() else {
+            return Ok(result.content.clone());
+        };
+
+        let content = ["docstring", "signature", "body"]
+            .iter()
+            .filter_map(|field| unit.get(*field).and_then(|v| v.as_str()))
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+        Ok(if content.is_empty() { result.content.clone() } else { content })
+    }
+
     /// Find references to a code unit
     pub async fn find_references(&self, unit_id: &str) -> Result<Vec<CodeReference>> {
         debug!("Finding references to unit: {}", unit_id);
@@ -325,6 +444,58 @@ impl SearchService {
     }
 }
 
+/// Rank constant of reciprocal rank fusion; damps the lead of top ranks
+const RRF_K: f32 = 60.0;
+
+/// Merge ranked result lists, scoring each result by the sum of
+/// `1 / (RRF_K + rank)` over the lists it appears in
+fn fuse_rankings(rankings: Vec<Vec<SearchResult>>) -> Vec<SearchResult> {
+    let mut fused: Vec<SearchResult> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+
+    for ranking in rankings {
+        for (rank, mut result) in ranking.into_iter().enumerate() {
+            let score = 1.0 / (RRF_K + rank as f32 + 1.0);
+            match positions.get(&result.id) {
+                Some(&index) => fused[index].score += score,
+                None => {
+                    positions.insert(result.id.clone(), fused.len());
+                    result.score = score;
+                    fused.push(result);
+                }
+            }
+        }
+    }
+
+    fused.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    fused
+}
+
+/// Whether a result belongs to a workspace: by its indexed workspace, or
+/// otherwise by its path
+fn in_workspace(file_path: Option<&str>, metadata: &HashMap<String, String>, workspace_id: &str) -> bool {
+    match metadata.get("workspace_id") {
+        Some(id) => id == workspace_id,
+        None => file_path.is_some_and(|path| path.contains(workspace_id)),
+    }
+}
+
+/// Have a semantic search return each document's full content, within
+/// `INLINE_CONTENT_BYTES`
+fn request_inline_content(filter: &mut SearchFilter) {
+    filter.with_payload = vec!["content".to_string()];
+    filter.max_payload_bytes = Some(INLINE_CONTENT_BYTES);
+}
+
+/// Full content returned inline with a semantic result; `None` when it was
+/// cut to fit
+fn inline_content(result: &cortex_semantic::SearchResult) -> Option<String> {
+    if result.payload_truncated {
+        return None;
+    }
+    result.payload.as_ref()?.get("content")?.as_str().map(String::from)
+}
+
 // =============================================================================
 // Request/Response Types
 // =============================================================================
@@ -335,6 +506,13 @@ pub struct SearchCodeRequest {
     pub limit: usize,
     pub min_similarity: f32,
     pub language: Option<String>,
+    /// Only return results from this workspace
+    #[serde(default)]
+    pub workspace_id: Option<String>,
+    /// Metadata filter expression, e.g. `language == rust AND
+    /// modified_at > 2024-01-01`
+    #[serde(default)]
+    pub filter: Option<FilterExpr>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -356,6 +534,23 @@ pub struct TextSearchRequest {
     pub query: String,
     pub search_type: String,
     pub limit: usize,
+    /// Only match code units in this language
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Only match code units in this workspace
+    #[serde(default)]
+    pub workspace_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct HybridSearchRequest {
+    pub query: String,
+    pub limit: usize,
+    pub min_similarity: f32,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub workspace_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -368,6 +563,12 @@ pub struct SearchResult {
     pub file_path: Option<String>,
     pub language: Option<String>,
     pub metadata: HashMap<String, String>,
+    /// Span of this result that directly answers a factoid query
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub answer_snippet: Option<AnswerSnippet>,
+    /// Untruncated content, when the search returned it inline
+    #[serde(skip)]
+    pub full_content: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -395,6 +596,8 @@ mod tests {
             file_path: Some("/test.rs".to_string()),
             language: Some("rust".to_string()),
             metadata: HashMap::new(),
+            answer_snippet: None,
+            full_content: None,
         };
 
         let json = serde_json::to_string(&result).unwrap();
@@ -415,4 +618,42 @@ mod tests {
         let json = serde_json::to_string(&reference).unwrap();
         assert!(json.contains("main.rs"));
     }
+
+    fn result(id: &str) -> SearchResult {
+        SearchResult {
+            id: id.to_string(),
+            title: id.to_string(),
+            content: String::new(),
+            score: 0.0,
+            result_type: "code".to_string(),
+            file_path: None,
+            language: None,
+            metadata: HashMap::new(),
+            answer_snippet: None,
+            full_content: None,
+        }
+    }
+
+    #[test]
+    fn test_fuse_rankings_prefers_results_found_both_ways() {
+        let semantic = vec![result("a"), result("b"), result("c")];
+        let text = vec![result("c"), result("d")];
+
+        let fused = fuse_rankings(vec![semantic, text]);
+        let ids: Vec<&str> = fused.iter().map(|r| r.id.as_str()).collect();
+
+        assert_eq!(ids, vec!["c", "a", "b", "d"]);
+        assert!((fused[0].score - (1.0 / 63.0 + 1.0 / 61.0)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_in_workspace() {
+        let mut metadata = HashMap::new();
+        assert!(in_workspace(Some("/ws-1/src/lib.rs"), &metadata, "ws-1"));
+        assert!(!in_workspace(Some("/ws-2/src/lib.rs"), &metadata, "ws-1"));
+        assert!(!in_workspace(None, &metadata, "ws-1"));
+
+        metadata.insert("workspace_id".to_string(), "ws-2".to_string());
+        assert!(!in_workspace(Some("/ws-1/src/lib.rs"), &metadata, "ws-1"));
+    }
 }
@@ -48,6 +48,8 @@ mod tests {
                 validate_on_checkout: true,
                 recycle_after_uses: None,
                 shutdown_grace_period: Duration::from_secs(10),
+                query_timeout: Some(Duration::from_secs(30)),
+                slow_query_threshold: Some(Duration::from_secs(1)),
             },
             namespace: "test".to_string(),
             database: "test".to_string(),
@@ -76,6 +78,7 @@ mod tests {
             parent_workspace: None,
             fork_metadata: None,
             dependencies: vec![],
+            version_retention: Default::default(),
             created_at: now,
             updated_at: now,
         };
@@ -581,6 +584,7 @@ mod tests {
         let workspaces = workspace_service
             .list_workspaces(workspace::ListWorkspaceFilters {
                 limit: None,
+                ..Default::default()
             })
             .await?;
 
@@ -1237,6 +1241,8 @@ mod tests {
                 query: "test".to_string(),
                 search_type: "code_units".to_string(),
                 limit: 10,
+                language: None,
+                workspace_id: None,
             })
             .await?;
 
@@ -2,9 +2,10 @@
 //!
 //! Provides unified virtual filesystem operations for both API and MCP modules.
 
+use super::listing::Page;
 use anyhow::Result;
 use chrono::{DateTime, Utc};
-use cortex_vfs::{NodeType, VirtualFileSystem, VirtualPath, VNode};
+use cortex_vfs::{ListOptions, NodeType, VirtualFileSystem, VirtualPath, VNode};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tracing::{debug, info};
@@ -62,6 +63,27 @@ impl VfsService {
         Ok(vnodes.into_iter().map(FileDetails::from_vnode).collect())
     }
 
+    /// List one page of directory contents, filtered and ordered in the database
+    pub async fn list_directory_page(
+        &self,
+        workspace_id: &Uuid,
+        path: &str,
+        options: &ListOptions,
+    ) -> Result<Page<FileDetails>> {
+        debug!(
+            "Listing directory page: {} in workspace {} (limit: {}, offset: {})",
+            path, workspace_id, options.limit, options.offset
+        );
+
+        let vpath = VirtualPath::new(path)?;
+        let (vnodes, total) = self.vfs.list_directory_page(workspace_id, &vpath, options).await?;
+
+        Ok(Page {
+            items: vnodes.into_iter().map(FileDetails::from_vnode).collect(),
+            total,
+        })
+    }
+
     /// Delete file or directory
     pub async fn delete(&self, workspace_id: &Uuid, path: &str, recursive: bool) -> Result<()> {
         info!("Deleting: {} in workspace {} (recursive: {})", path, workspace_id, recursive);
@@ -172,14 +194,7 @@ impl VfsService {
         let source = VirtualPath::new(source_path)?;
         let target = VirtualPath::new(target_path)?;
 
-        // Read source content
-        let content = self.vfs.read_file(workspace_id, &source).await?;
-
-        // Write to target
-        self.vfs.write_file(workspace_id, &target, &content).await?;
-
-        // Delete source
-        self.vfs.delete(workspace_id, &source, false).await?;
+        self.vfs.move_file(workspace_id, &source, &target).await?;
 
         // Get target metadata
         let vnode = self.vfs.metadata(workspace_id, &target).await?;
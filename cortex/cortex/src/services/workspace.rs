@@ -2,16 +2,21 @@
 //!
 //! Provides unified workspace management operations for both API and MCP modules.
 
+use super::listing::{SortDirection, SortSpec};
+use super::sessions::{SessionFilters, SessionService};
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use cortex_storage::ConnectionManager;
-use cortex_vfs::{VirtualFileSystem, Workspace, SyncSource, SyncSourceType, SyncSourceStatus};
+use cortex_vfs::{
+    RollbackReport, SnapshotInfo, SnapshotManager, VfsEventKind, VirtualFileSystem, Workspace,
+    SyncSource, SyncSourceType, SyncSourceStatus,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 use uuid::Uuid;
 
 /// Workspace service for managing workspaces
@@ -19,12 +24,14 @@ use uuid::Uuid;
 pub struct WorkspaceService {
     storage: Arc<ConnectionManager>,
     pub vfs: Arc<VirtualFileSystem>,
+    snapshots: Arc<SnapshotManager>,
 }
 
 impl WorkspaceService {
     /// Create a new workspace service
     pub fn new(storage: Arc<ConnectionManager>, vfs: Arc<VirtualFileSystem>) -> Self {
-        Self { storage, vfs }
+        let snapshots = Arc::new(SnapshotManager::new((*vfs).clone(), storage.clone()));
+        Self { storage, vfs, snapshots }
     }
 
     /// Create a new workspace
@@ -72,6 +79,7 @@ impl WorkspaceService {
             parent_workspace: None,
             fork_metadata: None,
             dependencies: Vec::new(),
+            version_retention: Default::default(),
             created_at: now,
             updated_at: now,
         };
@@ -162,12 +170,24 @@ impl WorkspaceService {
 
         let conn = self.storage.acquire().await?;
 
-        let mut query = String::from("SELECT *, <string>meta::id(id) as id FROM workspace ORDER BY created_at DESC");
+        let sort = filters
+            .sort
+            .clone()
+            .unwrap_or_else(|| SortSpec::new("created_at", SortDirection::Desc));
+
+        let mut query = format!(
+            "SELECT *, <string>meta::id(id) as id FROM workspace {}",
+            sort.to_order_clause()
+        );
 
         if let Some(limit) = filters.limit {
             query.push_str(&format!(" LIMIT {}", limit));
         }
 
+        if let Some(offset) = filters.offset {
+            query.push_str(&format!(" START {}", offset));
+        }
+
         let mut response = conn.connection().query(&query).await?;
 
         // Parse with string IDs and convert to UUIDs
@@ -199,6 +219,22 @@ impl WorkspaceService {
             .collect())
     }
 
+    /// Count all workspaces
+    pub async fn count_workspaces(&self) -> Result<usize> {
+        let conn = self.storage.acquire().await?;
+
+        let mut response = conn
+            .connection()
+            .query("SELECT count() as total FROM workspace GROUP ALL")
+            .await?;
+
+        let count_results: Vec<serde_json::Value> = response.take(0).unwrap_or_default();
+        Ok(count_results
+            .first()
+            .and_then(|v| v.get("total").and_then(|t| t.as_u64()))
+            .unwrap_or(0) as usize)
+    }
+
     /// Update workspace
     pub async fn update_workspace(&self, workspace_id: &Uuid, request: UpdateWorkspaceRequest) -> Result<WorkspaceDetails> {
         debug!("Updating workspace: {}", workspace_id);
@@ -238,12 +274,15 @@ impl WorkspaceService {
         };
 
         // Update fields
+        let mut fields = Vec::new();
         if let Some(name) = request.name {
             workspace.name = name;
+            fields.push("name".to_string());
         }
 
         if let Some(read_only) = request.read_only {
             workspace.read_only = read_only;
+            fields.push("read_only".to_string());
         }
 
         // Merge additional metadata if provided
@@ -251,6 +290,7 @@ impl WorkspaceService {
             for (key, value) in metadata {
                 workspace.metadata.insert(key, value);
             }
+            fields.push("metadata".to_string());
         }
 
         workspace.updated_at = Utc::now();
@@ -291,6 +331,10 @@ impl WorkspaceService {
 
         info!("Updated workspace: {}", workspace_id);
 
+        if !fields.is_empty() {
+            self.vfs.events().publish(*workspace_id, VfsEventKind::WorkspaceUpdated { fields });
+        }
+
         Ok(WorkspaceDetails::from_workspace(workspace))
     }
 
@@ -310,6 +354,11 @@ impl WorkspaceService {
 
         info!("Deleted {} vnodes from workspace", deleted_vnodes.len());
 
+        conn.connection()
+            .query("DELETE workspace_snapshot WHERE workspace_id = $workspace_id")
+            .bind(("workspace_id", workspace_id.to_string()))
+            .await?;
+
         // CRITICAL FIX: Use DELETE query with type::thing() to ensure proper record ID construction
         // The SDK's .delete() method with tuple notation doesn't properly handle UUID strings,
         // causing silent failures where the method reports success but the record isn't deleted.
@@ -479,10 +528,93 @@ impl WorkspaceService {
     }
 }
 
+impl WorkspaceService {
+    /// Take a named snapshot of every vnode in a workspace
+    pub async fn create_snapshot(
+        &self,
+        workspace_id: &Uuid,
+        name: String,
+        description: Option<String>,
+        created_by: Option<String>,
+    ) -> Result<SnapshotInfo> {
+        Ok(self.snapshots.create_snapshot(workspace_id, name, description, created_by).await?)
+    }
+
+    /// List the snapshots of a workspace, newest first
+    pub async fn list_snapshots(&self, workspace_id: &Uuid) -> Result<Vec<SnapshotInfo>> {
+        Ok(self.snapshots.list_snapshots(workspace_id).await?)
+    }
+
+    /// Roll a workspace back to a named snapshot.
+    ///
+    /// Fails with [`ActiveSessionsError`] while a session other than
+    /// `request.session_id` is active on the workspace, unless `request.force`
+    /// is set.
+    pub async fn rollback_to_snapshot(
+        &self,
+        workspace_id: &Uuid,
+        name: &str,
+        request: RollbackRequest,
+    ) -> Result<RollbackReport> {
+        let sessions = SessionService::new(self.storage.clone())
+            .list_sessions(
+                Some(*workspace_id),
+                SessionFilters {
+                    status: Some("active".to_string()),
+                    agent_type: None,
+                    limit: None,
+                },
+            )
+            .await?;
+        let others: Vec<String> = sessions
+            .into_iter()
+            .map(|session| session.id.to_string())
+            .filter(|id| request.session_id.as_deref() != Some(id.as_str()))
+            .collect();
+
+        if !others.is_empty() {
+            if !request.force {
+                return Err(ActiveSessionsError {
+                    workspace_id: *workspace_id,
+                    sessions: others,
+                }
+                .into());
+            }
+            warn!(
+                "Forcing rollback of workspace {} with {} active sessions",
+                workspace_id,
+                others.len()
+            );
+        }
+
+        let actor = request.actor.or(request.session_id);
+        Ok(self.snapshots.rollback(workspace_id, name, actor.as_deref()).await?)
+    }
+}
+
 // =============================================================================
 // Request/Response Types
 // =============================================================================
 
+/// Options for rolling a workspace back to a snapshot
+#[derive(Debug, Clone, Default)]
+pub struct RollbackRequest {
+    /// Session of the caller, which does not block the rollback
+    pub session_id: Option<String>,
+    /// Roll back even while other sessions are active on the workspace
+    pub force: bool,
+    /// Who the changes are attributed to (defaults to `session_id`)
+    pub actor: Option<String>,
+}
+
+/// A rollback was refused because other sessions are active on the workspace
+#[derive(Debug, thiserror::Error)]
+#[error("Workspace {workspace_id} has {} active session(s): {}; use force to roll back anyway", .sessions.len(), .sessions.join(", "))]
+pub struct ActiveSessionsError {
+    pub workspace_id: Uuid,
+    pub sessions: Vec<String>,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct CreateWorkspaceRequest {
     pub name: String,
@@ -502,6 +634,9 @@ pub struct UpdateWorkspaceRequest {
 #[derive(Debug, Clone, Default)]
 pub struct ListWorkspaceFilters {
     pub limit: Option<usize>,
+    pub offset: Option<usize>,
+    /// Sort order (defaults to `created_at` descending)
+    pub sort: Option<SortSpec>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -2,10 +2,14 @@
 //!
 //! Provides commands to test various aspects of the system.
 
+use crate::config::CortexConfig;
 use crate::output::{self, OutputFormat};
 use anyhow::{Context, Result};
+use cortex_storage::ConnectionManager;
 use serde::{Deserialize, Serialize};
-use std::time::Instant;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// Test result
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,8 +33,6 @@ pub struct TestSuiteResults {
 
 /// Run all system tests
 pub async fn run_all_tests() -> Result<TestSuiteResults> {
-    output::header("Running Cortex System Tests");
-
     let start = Instant::now();
     let mut results = Vec::new();
 
@@ -51,8 +53,7 @@ pub async fn run_all_tests() -> Result<TestSuiteResults> {
     results.push(test_memory_retrieval().await);
 
     // MCP tests
-    results.push(test_mcp_server().await);
-    results.push(test_mcp_tools().await);
+    results.extend(test_mcp_stdio().await);
 
     // Integration tests
     results.push(test_end_to_end_workflow().await);
@@ -125,6 +126,79 @@ pub fn print_test_results(results: &TestSuiteResults, format: OutputFormat) -> R
 // Individual Tests
 // ============================================================================
 
+/// Scratch table for database self-tests; records are removed after each test
+const SELFTEST_TABLE: &str = "cortex_selftest";
+
+/// Timeout for checks that talk to external services
+const NETWORK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Timeout for the whole MCP stdio session, including server startup
+const MCP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Time a check and convert its outcome into a [`TestResult`]
+async fn run_check(test_name: &str, check: impl Future<Output = Result<String>>) -> TestResult {
+    let start = Instant::now();
+
+    match check.await {
+        Ok(message) => TestResult {
+            test_name: test_name.to_string(),
+            passed: true,
+            duration_ms: start.elapsed().as_millis(),
+            message,
+            details: None,
+        },
+        Err(e) => TestResult {
+            test_name: test_name.to_string(),
+            passed: false,
+            duration_ms: start.elapsed().as_millis(),
+            message: format!("{} failed", test_name),
+            details: Some(format!("{:#}", e)),
+        },
+    }
+}
+
+/// Connection pool for the database configured in `CortexConfig`
+async fn configured_storage() -> Result<Arc<ConnectionManager>> {
+    let config = CortexConfig::load().unwrap_or_default();
+    tokio::time::timeout(NETWORK_TIMEOUT, crate::commands::create_storage(&config))
+        .await
+        .context("Timed out connecting to database")?
+}
+
+/// Isolated in-memory database so component tests never touch user data
+async fn in_memory_storage(database: &str) -> Result<Arc<ConnectionManager>> {
+    use cortex_storage::connection_pool::{ConnectionMode, Credentials, DatabaseConfig, PoolConfig, RetryPolicy};
+
+    let config = DatabaseConfig {
+        connection_mode: ConnectionMode::InMemory,
+        credentials: Credentials {
+            username: None,
+            password: None,
+        },
+        pool_config: PoolConfig {
+            min_connections: 1,
+            max_connections: 2,
+            connection_timeout: Duration::from_secs(5),
+            idle_timeout: None,
+            max_lifetime: None,
+            retry_policy: RetryPolicy::default(),
+            warm_connections: false,
+            validate_on_checkout: false,
+            recycle_after_uses: None,
+            shutdown_grace_period: Duration::from_secs(5),
+            query_timeout: Some(Duration::from_secs(30)),
+            slow_query_threshold: Some(Duration::from_secs(1)),
+        },
+        namespace: "cortex_test".to_string(),
+        database: database.to_string(),
+    };
+
+    let manager = ConnectionManager::new(config)
+        .await
+        .context("Failed to create in-memory database")?;
+    Ok(Arc::new(manager))
+}
+
 async fn test_database_connection() -> TestResult {
     let start = Instant::now();
     let test_name = "Database Connection".to_string();
@@ -163,17 +237,110 @@ async fn test_db_connection_impl() -> Result<()> {
 }
 
 async fn test_database_crud() -> TestResult {
-    let start = Instant::now();
-    let test_name = "Database CRUD Operations".to_string();
+    run_check("Database CRUD Operations", async {
+        let storage = configured_storage().await?;
+        let conn = storage.acquire().await?;
+        let id = uuid::Uuid::new_v4().to_string();
+
+        conn.connection()
+            .query("CREATE type::thing($table, $id) CONTENT { value: $value }")
+            .bind(("table", SELFTEST_TABLE))
+            .bind(("id", id.clone()))
+            .bind(("value", "roundtrip"))
+            .await
+            .context("Failed to write test record")?
+            .check()
+            .context("Write rejected")?;
+
+        let mut response = conn
+            .connection()
+            .query("SELECT VALUE value FROM type::thing($table, $id)")
+            .bind(("table", SELFTEST_TABLE))
+            .bind(("id", id.clone()))
+            .await
+            .context("Failed to read test record")?;
+        let values: Vec<String> = response.take(0)?;
+
+        conn.connection()
+            .query("DELETE type::thing($table, $id)")
+            .bind(("table", SELFTEST_TABLE))
+            .bind(("id", id))
+            .await
+            .context("Failed to delete test record")?;
+
+        if values != ["roundtrip"] {
+            anyhow::bail!("Read back {:?}, expected [\"roundtrip\"]", values);
+        }
+        Ok("Write/read/delete roundtrip successful".to_string())
+    })
+    .await
+}
 
-    // Mock test for now
-    TestResult {
-        test_name,
-        passed: true,
-        duration_ms: start.elapsed().as_millis(),
-        message: "CRUD operations successful".to_string(),
-        details: None,
-    }
+async fn test_database_pool() -> TestResult {
+    run_check("Database Connection Pool", async {
+        let config = CortexConfig::load().unwrap_or_default();
+        let storage = configured_storage().await?;
+        let conn = storage.acquire().await?;
+
+        let mut response = conn
+            .connection()
+            .query("RETURN 1")
+            .await
+            .context("Query failed")?;
+        let value: Option<i64> = response.take(0)?;
+        if value != Some(1) {
+            anyhow::bail!("Unexpected response to RETURN 1: {:?}", value);
+        }
+
+        Ok(format!("Connected to {}", config.database.connection_string))
+    })
+    .await
+}
+
+async fn test_database_transaction() -> TestResult {
+    run_check("Database Transactions", async {
+        let storage = configured_storage().await?;
+        let conn = storage.acquire().await?;
+        let committed = uuid::Uuid::new_v4().to_string();
+        let cancelled = uuid::Uuid::new_v4().to_string();
+
+        conn.connection()
+            .query("BEGIN TRANSACTION; CREATE type::thing($table, $id) CONTENT { value: 1 }; COMMIT TRANSACTION;")
+            .bind(("table", SELFTEST_TABLE))
+            .bind(("id", committed.clone()))
+            .await
+            .context("Committed transaction failed")?;
+        conn.connection()
+            .query("BEGIN TRANSACTION; CREATE type::thing($table, $id) CONTENT { value: 2 }; CANCEL TRANSACTION;")
+            .bind(("table", SELFTEST_TABLE))
+            .bind(("id", cancelled.clone()))
+            .await
+            .context("Cancelled transaction failed")?;
+
+        let mut response = conn
+            .connection()
+            .query("SELECT VALUE meta::id(id) FROM type::table($table) WHERE meta::id(id) IN [$committed, $cancelled]")
+            .bind(("table", SELFTEST_TABLE))
+            .bind(("committed", committed.clone()))
+            .bind(("cancelled", cancelled.clone()))
+            .await?;
+        let found: Vec<String> = response.take(0)?;
+
+        conn.connection()
+            .query("DELETE type::thing($table, $id)")
+            .bind(("table", SELFTEST_TABLE))
+            .bind(("id", committed.clone()))
+            .await?;
+
+        if !found.contains(&committed) {
+            anyhow::bail!("Committed record is missing");
+        }
+        if found.contains(&cancelled) {
+            anyhow::bail!("Cancelled transaction was persisted");
+        }
+        Ok("Commit persisted and cancel rolled back".to_string())
+    })
+    .await
 }
 
 async fn test_storage_read_write() -> TestResult {
@@ -199,8 +366,6 @@ async fn test_storage_read_write() -> TestResult {
 }
 
 async fn test_storage_impl() -> Result<()> {
-    use crate::config::CortexConfig;
-
     let config = CortexConfig::load().unwrap_or_default();
     let data_dir = &config.storage.data_dir;
 
@@ -238,17 +403,62 @@ async fn test_storage_caching() -> TestResult {
 }
 
 async fn test_vfs_operations() -> TestResult {
-    let start = Instant::now();
-    let test_name = "VFS Operations".to_string();
+    use cortex_vfs::{VirtualFileSystem, VirtualPath};
 
-    // Mock test
-    TestResult {
-        test_name,
-        passed: true,
-        duration_ms: start.elapsed().as_millis(),
-        message: "VFS operations successful".to_string(),
-        details: None,
-    }
+    run_check("VFS Operations", async {
+        let vfs = VirtualFileSystem::new(in_memory_storage("vfs_test").await?);
+        let workspace_id = uuid::Uuid::new_v4();
+        let path = VirtualPath::new("selftest/notes.md")?;
+
+        vfs.write_file(&workspace_id, &path, b"# Cortex self-test\n").await?;
+        let content = vfs.read_file(&workspace_id, &path).await?;
+        if content != b"# Cortex self-test\n" {
+            anyhow::bail!("Read content does not match written content");
+        }
+
+        vfs.delete(&workspace_id, &path, false).await?;
+        if vfs.exists(&workspace_id, &path).await? {
+            anyhow::bail!("File still exists after delete");
+        }
+
+        Ok("Write/read/delete successful".to_string())
+    })
+    .await
+}
+
+async fn test_vfs_dedup() -> TestResult {
+    use cortex_vfs::{VirtualFileSystem, VirtualPath};
+
+    run_check("VFS Content Deduplication", async {
+        let storage = in_memory_storage("vfs_dedup_test").await?;
+        let vfs = VirtualFileSystem::new(storage.clone());
+        let workspace_id = uuid::Uuid::new_v4();
+        let first = VirtualPath::new("a/readme.md")?;
+        let second = VirtualPath::new("b/readme.md")?;
+
+        vfs.write_file(&workspace_id, &first, b"shared content").await?;
+        vfs.write_file(&workspace_id, &second, b"shared content").await?;
+
+        let first_hash = vfs.metadata(&workspace_id, &first).await?.content_hash;
+        let second_hash = vfs.metadata(&workspace_id, &second).await?.content_hash;
+        let hash = match (first_hash, second_hash) {
+            (Some(a), Some(b)) if a == b => a,
+            (a, b) => anyhow::bail!("Content hashes differ: {:?} vs {:?}", a, b),
+        };
+
+        let conn = storage.acquire().await?;
+        let mut response = conn
+            .connection()
+            .query("SELECT VALUE reference_count FROM type::thing('file_content', $hash)")
+            .bind(("hash", hash))
+            .await?;
+        let counts: Vec<i64> = response.take(0)?;
+        match counts.as_slice() {
+            [count] if *count >= 2 => Ok(format!("Identical files share one blob ({} references)", count)),
+            other => anyhow::bail!("Expected a single shared blob, found {:?}", other),
+        }
+    })
+    .await
 }
 
 async fn test_vfs_materialization() -> TestResult {
@@ -266,58 +476,209 @@ async fn test_vfs_materialization() -> TestResult {
 }
 
 async fn test_memory_storage() -> TestResult {
-    let start = Instant::now();
-    let test_name = "Memory Storage".to_string();
+    use cortex_core::id::CortexId;
+    use cortex_memory::EpisodicMemorySystem;
+    use cortex_memory::types::{EpisodeType, EpisodicMemory};
+
+    run_check("Memory Storage", async {
+        let episodic = EpisodicMemorySystem::new(in_memory_storage("memory_test").await?);
+        let workspace_id = CortexId::new();
+        let mut episode = EpisodicMemory::new(
+            "Cortex self-test episode".to_string(),
+            "cortex-test".to_string(),
+            workspace_id,
+            EpisodeType::Task,
+        );
+        episode.solution_summary = "Stored and recalled".to_string();
+
+        let id = episodic.store_episode(&episode).await?;
+        let recalled = episodic
+            .get_episode(id)
+            .await?
+            .context("Stored episode could not be recalled")?;
+        if recalled.task_description != episode.task_description {
+            anyhow::bail!("Recalled episode does not match stored episode");
+        }
 
-    // Mock test
-    TestResult {
-        test_name,
-        passed: true,
-        duration_ms: start.elapsed().as_millis(),
-        message: "Memory storage successful".to_string(),
-        details: None,
-    }
+        let for_workspace = episodic.get_episodes_for_project(workspace_id).await?;
+        if for_workspace.len() != 1 {
+            anyhow::bail!("Expected 1 episode for workspace, found {}", for_workspace.len());
+        }
+
+        Ok("Episode stored and recalled".to_string())
+    })
+    .await
 }
 
 async fn test_memory_retrieval() -> TestResult {
-    let start = Instant::now();
-    let test_name = "Memory Retrieval".to_string();
+    use cortex_memory::WorkingMemorySystem;
+    use cortex_memory::types::Priority;
 
-    // Mock test
-    TestResult {
-        test_name,
-        passed: true,
-        duration_ms: start.elapsed().as_millis(),
-        message: "Memory retrieval successful".to_string(),
-        details: None,
-    }
+    run_check("Working Memory Capacity", async {
+        let working = WorkingMemorySystem::new(3, 1024 * 1024);
+
+        working.store("critical".to_string(), b"keep".to_vec(), Priority::Critical);
+        for i in 0..5 {
+            working.store(format!("low-{}", i), vec![0u8; 16], Priority::Low);
+        }
+
+        if working.len() > 3 {
+            anyhow::bail!("Working memory holds {} items, capacity is 3", working.len());
+        }
+        if working.retrieve("critical").is_none() {
+            anyhow::bail!("Critical item was evicted before low-priority items");
+        }
+
+        Ok(format!("Capacity enforced ({} items, critical retained)", working.len()))
+    })
+    .await
+}
+
+async fn test_search_index_query() -> TestResult {
+    use cortex_semantic::config::SemanticConfig;
+    use cortex_semantic::{EntityType, SemanticSearchEngine};
+
+    run_check("Search Index and Query", async {
+        let mut config = SemanticConfig::default();
+        config.embedding.primary_provider = "mock".to_string();
+        config.embedding.fallback_providers = vec![];
+
+        let engine = tokio::time::timeout(NETWORK_TIMEOUT, SemanticSearchEngine::new(config))
+            .await
+            .context("Timed out connecting to vector store")??;
+
+        let doc_id = format!("cortex-selftest-{}", uuid::Uuid::new_v4());
+        let content = "cortex self test document about vector search".to_string();
+        engine
+            .index_document(doc_id.clone(), content.clone(), EntityType::Document, Default::default())
+            .await?;
+
+        let results = engine.search(&content, 5).await;
+        engine.remove_document(&doc_id).await?;
+
+        if !results?.iter().any(|r| r.id == doc_id) {
+            anyhow::bail!("Indexed document was not returned by query");
+        }
+        Ok("Indexed document found by query".to_string())
+    })
+    .await
 }
 
-async fn test_mcp_server() -> TestResult {
+/// Spawn `cortex mcp stdio`, perform the initialize handshake and list tools
+async fn test_mcp_stdio() -> Vec<TestResult> {
     let start = Instant::now();
-    let test_name = "MCP Server".to_string();
 
-    // Mock test
-    TestResult {
-        test_name,
-        passed: true,
-        duration_ms: start.elapsed().as_millis(),
-        message: "MCP server test successful".to_string(),
-        details: None,
+    match tokio::time::timeout(MCP_TIMEOUT, mcp_stdio_session()).await {
+        Ok(Ok((server, tool_count))) => {
+            let duration_ms = start.elapsed().as_millis();
+            let tools_passed = tool_count > 0;
+            vec![
+                TestResult {
+                    test_name: "MCP Server".to_string(),
+                    passed: true,
+                    duration_ms,
+                    message: format!("Initialize handshake with {} succeeded", server),
+                    details: None,
+                },
+                TestResult {
+                    test_name: "MCP Tools".to_string(),
+                    passed: tools_passed,
+                    duration_ms,
+                    message: if tools_passed {
+                        format!("{} tools listed", tool_count)
+                    } else {
+                        "Server listed no tools".to_string()
+                    },
+                    details: None,
+                },
+            ]
+        }
+        outcome => {
+            let details = match outcome {
+                Ok(Err(e)) => format!("{:#}", e),
+                _ => format!("No response within {}s", MCP_TIMEOUT.as_secs()),
+            };
+            ["MCP Server", "MCP Tools"]
+                .into_iter()
+                .map(|name| TestResult {
+                    test_name: name.to_string(),
+                    passed: false,
+                    duration_ms: start.elapsed().as_millis(),
+                    message: "MCP stdio session failed".to_string(),
+                    details: Some(details.clone()),
+                })
+                .collect()
+        }
     }
 }
 
-async fn test_mcp_tools() -> TestResult {
-    let start = Instant::now();
-    let test_name = "MCP Tools".to_string();
+async fn mcp_stdio_session() -> Result<(String, usize)> {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::process::Command;
+
+    let exe = std::env::current_exe().context("Failed to locate cortex binary")?;
+    let mut child = Command::new(exe)
+        .args(["mcp", "stdio"])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .context("Failed to spawn MCP stdio server")?;
+
+    let mut stdin = child.stdin.take().context("MCP server stdin unavailable")?;
+    let mut lines = BufReader::new(child.stdout.take().context("MCP server stdout unavailable")?).lines();
+
+    let requests = [
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": "2025-03-26",
+                "capabilities": {},
+                "clientInfo": { "name": "cortex-test", "version": env!("CARGO_PKG_VERSION") }
+            }
+        }),
+        serde_json::json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+        serde_json::json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" }),
+    ];
+    for request in &requests {
+        stdin.write_all(format!("{}\n", request).as_bytes()).await?;
+    }
+    stdin.flush().await?;
 
-    // Mock test
-    TestResult {
-        test_name,
-        passed: true,
-        duration_ms: start.elapsed().as_millis(),
-        message: "MCP tools test successful".to_string(),
-        details: None,
+    let mut server = None;
+    let mut tool_count = None;
+    while let Some(line) = lines.next_line().await? {
+        let Ok(message) = serde_json::from_str::<serde_json::Value>(&line) else {
+            continue;
+        };
+        if let Some(error) = message.get("error") {
+            anyhow::bail!("Server returned error: {}", error);
+        }
+        match message.get("id").and_then(|id| id.as_i64()) {
+            Some(1) => {
+                let info = &message["result"]["serverInfo"];
+                server = Some(format!(
+                    "{} {}",
+                    info["name"].as_str().unwrap_or("unknown"),
+                    info["version"].as_str().unwrap_or("")
+                ).trim().to_string());
+            }
+            Some(2) => {
+                tool_count = message["result"]["tools"].as_array().map(|tools| tools.len());
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    let _ = child.kill().await;
+    match (server, tool_count) {
+        (Some(server), Some(count)) => Ok((server, count)),
+        (None, _) => anyhow::bail!("Server closed stdout before answering initialize"),
+        (Some(_), None) => anyhow::bail!("Server closed stdout before answering tools/list"),
     }
 }
 
@@ -346,8 +707,6 @@ async fn test_end_to_end_workflow() -> TestResult {
 async fn test_e2e_impl() -> Result<()> {
     // This would test: init -> ingest -> search -> retrieve
     // For now, just a basic check
-    use crate::config::CortexConfig;
-
     let _config = CortexConfig::load().unwrap_or_default();
 
     // Would perform actual E2E test here
@@ -454,10 +813,13 @@ pub fn print_benchmark_results(results: &BenchmarkResults, format: OutputFormat)
     Ok(())
 }
 
+/// Components accepted by [`run_component_tests`]
+pub const COMPONENTS: &[&str] = &[
+    "api", "services", "database", "mcp", "storage", "vfs", "memory", "parser", "search", "semantic",
+];
+
 /// Run tests for a specific component
 pub async fn run_component_tests(component: &str) -> Result<TestSuiteResults> {
-    output::header(format!("Running {} Component Tests", component));
-
     let start = Instant::now();
     let mut results = Vec::new();
 
@@ -474,9 +836,13 @@ pub async fn run_component_tests(component: &str) -> Result<TestSuiteResults> {
             results.push(test_service_sessions().await);
             results.push(test_service_build().await);
         }
+        "database" => {
+            results.push(test_database_pool().await);
+            results.push(test_database_crud().await);
+            results.push(test_database_transaction().await);
+        }
         "mcp" => {
-            results.push(test_mcp_server().await);
-            results.push(test_mcp_tools().await);
+            results.extend(test_mcp_stdio().await);
         }
         "storage" => {
             results.push(test_database_connection().await);
@@ -486,6 +852,7 @@ pub async fn run_component_tests(component: &str) -> Result<TestSuiteResults> {
         }
         "vfs" => {
             results.push(test_vfs_operations().await);
+            results.push(test_vfs_dedup().await);
             results.push(test_vfs_materialization().await);
         }
         "memory" => {
@@ -496,12 +863,15 @@ pub async fn run_component_tests(component: &str) -> Result<TestSuiteResults> {
             results.push(test_parser_rust().await);
             results.push(test_parser_typescript().await);
         }
+        "search" => {
+            results.push(test_search_index_query().await);
+        }
         "semantic" => {
             results.push(test_semantic_search().await);
             results.push(test_semantic_indexing().await);
         }
         _ => {
-            anyhow::bail!("Unknown component: {}. Valid components: api, services, mcp, storage, vfs, memory, parser, semantic", component);
+            anyhow::bail!("Unknown component: {}. Valid components: {}", component, COMPONENTS.join(", "));
         }
     }
 
@@ -633,6 +1003,8 @@ async fn test_auth_middleware_structure() -> Result<Vec<MiddlewareTestResult>> {
         email: "test@example.com".to_string(),
         roles: vec!["user".to_string(), "developer".to_string()],
         session_id: Some("session-123".to_string()),
+        api_key_id: None,
+        scopes: Vec::new(),
     };
 
     // Test has_role
@@ -655,6 +1027,8 @@ async fn test_auth_middleware_structure() -> Result<Vec<MiddlewareTestResult>> {
         email: "admin@example.com".to_string(),
         roles: vec!["admin".to_string()],
         session_id: None,
+        api_key_id: None,
+        scopes: Vec::new(),
     };
     let admin_check = admin_user.is_admin();
     results.push(MiddlewareTestResult {
@@ -1410,6 +1784,24 @@ mod tests {
         assert_eq!(results.failed, 2);
     }
 
+    #[tokio::test]
+    async fn test_run_check_captures_failure() {
+        let passed = run_check("Ok", async { Ok("fine".to_string()) }).await;
+        assert!(passed.passed);
+        assert_eq!(passed.message, "fine");
+
+        let failed = run_check("Broken", async { anyhow::bail!("boom") }).await;
+        assert!(!failed.passed);
+        assert_eq!(failed.details.as_deref(), Some("boom"));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_component_lists_valid_ones() {
+        let err = run_component_tests("nope").await.unwrap_err();
+        assert!(err.to_string().contains("database"));
+        assert!(err.to_string().contains("search"));
+    }
+
     #[tokio::test]
     async fn test_storage_impl_test() {
         // This test will fail without proper setup, but demonstrates the pattern
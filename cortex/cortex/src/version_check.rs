@@ -0,0 +1,95 @@
+//! Startup schema compatibility checks.
+//!
+//! Every entry point that opens the database (MCP server, REST server and the
+//! CLI) calls [`ensure_compatible`] before serving anything. Components whose
+//! stored schema is older than this build are migrated forward; a database
+//! written by a newer build is refused unless `--skip-version-check` was given.
+
+use anyhow::{Context, Result, bail};
+use cortex_core::versioning::{CompatibilityChecker, ComponentVersion, MigrationRunner};
+use cortex_storage::ConnectionManager;
+use cortex_storage::schema::SchemaMigration;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::{info, warn};
+
+static SKIP_VERSION_CHECK: AtomicBool = AtomicBool::new(false);
+
+/// Disable the startup version check for this process (`--skip-version-check`).
+pub fn set_skip_version_check(skip: bool) {
+    SKIP_VERSION_CHECK.store(skip, Ordering::Relaxed);
+}
+
+/// Whether the startup version check has been disabled.
+pub fn skip_version_check() -> bool {
+    SKIP_VERSION_CHECK.load(Ordering::Relaxed)
+}
+
+/// Schema versions this build expects to find in the database.
+pub fn expected_components() -> Vec<ComponentVersion> {
+    vec![
+        cortex_storage::schema::COMPONENT_VERSION,
+        cortex_memory::COMPONENT_VERSION,
+        cortex_vfs::COMPONENT_VERSION,
+    ]
+}
+
+/// Verify the stored component versions and migrate forward if needed.
+///
+/// Fails if any component was written by a newer build or if a migration
+/// fails, so callers refuse to start instead of working on a schema they
+/// don't understand.
+pub async fn ensure_compatible(storage: &ConnectionManager) -> Result<()> {
+    if skip_version_check() {
+        warn!("Skipping schema version check (--skip-version-check)");
+        return Ok(());
+    }
+
+    let checker = CompatibilityChecker::new(expected_components())?;
+    let report = checker.verify(storage).await?;
+
+    if report.is_compatible() {
+        return Ok(());
+    }
+
+    if !report.too_new().is_empty() {
+        bail!(
+            "Database schema is newer than this build ({}). Upgrade cortex or pass --skip-version-check",
+            report.summary()
+        );
+    }
+
+    info!("Database schema needs migration: {}", report.summary());
+
+    let mut runner = MigrationRunner::new(checker);
+    runner.register(SchemaMigration);
+    let applied = runner
+        .run(storage, storage)
+        .await
+        .context("Schema migration failed; pass --skip-version-check to start anyway")?;
+
+    for migration in &applied {
+        info!(
+            "Migrated {} from {} to v{}",
+            migration.component,
+            migration
+                .from
+                .map_or_else(|| "unversioned".to_string(), |v| format!("v{}", v)),
+            migration.to
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expected_components_are_consistent() {
+        let checker = CompatibilityChecker::new(expected_components()).unwrap();
+        let names: Vec<_> = checker.components().iter().map(|c| c.name).collect();
+        assert_eq!(names[0], cortex_storage::schema::COMPONENT_NAME);
+        assert_eq!(names.len(), 3);
+    }
+}
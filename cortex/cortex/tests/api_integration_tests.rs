@@ -10,6 +10,7 @@
 //! - Search operations (semantic, pattern, references)
 //! - Memory operations (episodes, consolidation)
 //! - Health and metrics endpoints
+//! - Live workspace event streams over WebSocket
 //! - Both success and error scenarios
 
 use axum::http::{header, StatusCode};
@@ -115,6 +116,8 @@ async fn start_test_server() -> (tokio::task::JoinHandle<()>, String, TempDir) {
         host: "127.0.0.1".to_string(),
         port,
         workers: None,
+        require_auth: false,
+        ..Default::default()
     };
 
     let base_url = format!("http://127.0.0.1:{}", port);
@@ -962,6 +965,91 @@ async fn test_bad_request_error() {
     assert!(response.status().is_client_error());
 }
 
+// ============================================================================
+// Workspace Event Stream Tests
+// ============================================================================
+
+type EventStream = tokio_tungstenite::WebSocketStream<
+    tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+>;
+
+/// Open a workspace event stream and wait for its subscription message
+async fn connect_events(client: &ApiTestClient, workspace_id: &str, query: &str) -> EventStream {
+    use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+
+    let url = format!(
+        "{}/api/v1/workspaces/{}/events{}",
+        client.base_url.replacen("http", "ws", 1),
+        workspace_id,
+        query
+    );
+    let mut request = url.into_client_request().unwrap();
+    if let Some(token) = &client.access_token {
+        request.headers_mut().insert(
+            header::AUTHORIZATION,
+            format!("Bearer {}", token).parse().unwrap(),
+        );
+    }
+
+    let (mut stream, _) = tokio_tungstenite::connect_async(request)
+        .await
+        .expect("Failed to open event stream");
+    let subscribed = next_event(&mut stream).await;
+    assert_eq!(subscribed["type"], "subscribed");
+    stream
+}
+
+/// Next JSON message of an event stream
+async fn next_event(stream: &mut EventStream) -> Value {
+    use futures::StreamExt;
+    use tokio_tungstenite::tungstenite::Message;
+
+    loop {
+        let message = tokio::time::timeout(Duration::from_secs(5), stream.next())
+            .await
+            .expect("Timed out waiting for event")
+            .expect("Event stream ended")
+            .expect("Event stream failed");
+        if let Message::Text(text) = message {
+            return serde_json::from_str(&text).expect("Failed to parse event");
+        }
+    }
+}
+
+#[tokio::test]
+#[ignore] // Requires database setup
+async fn test_event_stream_delivers_write_to_all_clients() {
+    let (_handle, base_url, _temp_dir) = start_test_server().await;
+    let mut client = ApiTestClient::new(base_url.clone());
+
+    authenticate_client(&mut client).await;
+    let workspace_id = create_test_workspace(&mut client).await;
+
+    let mut first = connect_events(&client, &workspace_id, "").await;
+    let mut second = connect_events(&client, &workspace_id, "?types=node_created").await;
+
+    let response = client.post(&format!("/api/v1/workspaces/{}/files", workspace_id), json!({
+        "path": "/events.txt",
+        "content": "Hello, events!"
+    })).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let mut seqs = Vec::new();
+    for stream in [&mut first, &mut second] {
+        let event = next_event(stream).await;
+        assert_eq!(event["type"], "node_created");
+        assert_eq!(event["workspace_id"], workspace_id.as_str());
+        assert_eq!(event["node_type"], "file");
+        assert!(event["path"].as_str().unwrap().ends_with("events.txt"));
+        seqs.push(event["seq"].as_u64().unwrap());
+    }
+    assert_eq!(seqs[0], seqs[1]);
+
+    // A reconnecting client replays what it missed after its cursor
+    let mut resumed = connect_events(&client, &workspace_id, &format!("?since={}", seqs[0] - 1)).await;
+    assert_eq!(next_event(&mut resumed).await["seq"].as_u64(), Some(seqs[0]));
+}
+
 // ============================================================================
 // Helper Functions
 // ============================================================================
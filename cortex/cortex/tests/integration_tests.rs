@@ -198,6 +198,7 @@ async fn test_workspace_operations() {
         parent_workspace: None,
         fork_metadata: None,
         dependencies: vec![],
+        version_retention: Default::default(),
         created_at: chrono::Utc::now(),
         updated_at: chrono::Utc::now(),
     };
@@ -269,7 +270,7 @@ async fn test_workspace_create_list() {
 #[tokio::test]
 #[ignore] // Requires database setup
 async fn test_ingest_and_search() {
-    use cortex::commands::{ingest_path, search_memory};
+    use cortex::commands::{ingest_path, search_memory, IngestJournalOptions};
     use cortex::output::OutputFormat;
     use std::path::PathBuf;
 
@@ -278,7 +279,16 @@ async fn test_ingest_and_search() {
     std::fs::write(&test_file, "Hello, world!").unwrap();
 
     // Ingest file
-    let result = ingest_path(temp.path().to_path_buf(), Some("default".to_string()), true).await;
+    let result = ingest_path(
+        temp.path().to_path_buf(),
+        Some("default".to_string()),
+        true,
+        Vec::new(),
+        None,
+        IngestJournalOptions::default(),
+        OutputFormat::Json,
+    )
+    .await;
     assert!(result.is_ok() || result.is_err()); // May fail without workspace
 
     // Search
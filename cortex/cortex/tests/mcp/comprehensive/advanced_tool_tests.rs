@@ -96,6 +96,7 @@ impl AdvancedToolTestHarness {
             parent_workspace: None,
             fork_metadata: None,
             dependencies: vec![],
+            version_retention: Default::default(),
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         };
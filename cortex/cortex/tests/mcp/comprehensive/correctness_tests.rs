@@ -99,6 +99,7 @@ impl CorrectnessTestHarness {
             sync_sources: vec![],
             metadata: std::collections::HashMap::new(),
             dependencies: vec![],
+            version_retention: Default::default(),
             updated_at: chrono::Utc::now(),
         };
 
@@ -49,6 +49,8 @@ fn create_memory_config() -> DatabaseConfig {
             validate_on_checkout: false,
             recycle_after_uses: Some(10000),
             shutdown_grace_period: Duration::from_secs(10),
+            query_timeout: Some(Duration::from_secs(30)),
+            slow_query_threshold: Some(Duration::from_secs(1)),
         },
         namespace: "cortex_test".to_string(),
         database: "main".to_string(),
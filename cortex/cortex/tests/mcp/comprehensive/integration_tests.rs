@@ -257,6 +257,7 @@ impl IntegrationHarness {
             parent_workspace: None,
             fork_metadata: None,
             dependencies: vec![],
+            version_retention: Default::default(),
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         };
@@ -97,6 +97,7 @@ impl MaterializationTestHarness {
             sync_sources: vec![],
             metadata: std::collections::HashMap::new(),
             dependencies: vec![],
+            version_retention: Default::default(),
             updated_at: chrono::Utc::now(),
         };
 
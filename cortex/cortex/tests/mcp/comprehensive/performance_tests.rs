@@ -387,6 +387,7 @@ impl PerformanceHarness {
             sync_sources: vec![],
             metadata: std::collections::HashMap::new(),
             dependencies: vec![],
+            version_retention: Default::default(),
             updated_at: chrono::Utc::now(),
         };
 
@@ -303,6 +303,7 @@ impl SelfModificationHarness {
             sync_sources: vec![],
             metadata: std::collections::HashMap::new(),
             dependencies: vec![],
+            version_retention: Default::default(),
             updated_at: chrono::Utc::now(),
         };
 
@@ -100,6 +100,7 @@ impl StressTestHarness {
             sync_sources: vec![],
             metadata: std::collections::HashMap::new(),
             dependencies: vec![],
+            version_retention: Default::default(),
             updated_at: chrono::Utc::now(),
         };
 
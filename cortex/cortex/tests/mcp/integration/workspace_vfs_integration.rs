@@ -16,16 +16,41 @@ use crate::mcp::utils::{TestHarness, ToolResultAssertions};
 use cortex::mcp::tools::workspace::*;
 use cortex::mcp::tools::vfs::*;
 use mcp_sdk::prelude::*;
+use mcp_sdk::protocol::ToolContent;
+use mcp_sdk::testing::TestClient;
 use serde_json::json;
 use std::time::Instant;
 
-#[tokio::test]
+/// Parse the JSON payload of a tool result returned through the MCP protocol
+fn tool_json(result: &CallToolResult) -> Value {
+    assert_ne!(result.is_error, Some(true), "Tool reported an error: {:?}", result.content);
+    match result.content.first() {
+        Some(ToolContent::Text { text }) => {
+            serde_json::from_str(text).expect("Tool result is not JSON")
+        }
+        other => panic!("Expected text content, got {:?}", other),
+    }
+}
+
+/// Runs the workspace + VFS workflow through a real `McpServer` via the
+/// in-process test client, so middleware, hooks and request dispatch are
+/// exercised the same way as behind a transport.
+#[tokio::test(flavor = "multi_thread")]
 async fn test_workspace_creation_and_file_listing() {
     let harness = TestHarness::new().await;
-
-    // Step 1: Create workspace
     let workspace_ctx = harness.workspace_context();
-    let create_workspace_tool = WorkspaceCreateTool::new(workspace_ctx.clone());
+    let vfs_ctx = harness.vfs_context();
+
+    let server = McpServer::builder()
+        .name("cortex-test")
+        .version("1.0.0")
+        .tool(WorkspaceCreateTool::new(workspace_ctx.clone()))
+        .tool(VfsListDirectoryTool::new(vfs_ctx.clone()))
+        .tool(VfsGetNodeTool::new(vfs_ctx.clone()))
+        .tool(VfsUpdateFileTool::new(vfs_ctx.clone()))
+        .build();
+    let client = TestClient::connect(server).await.expect("Handshake failed");
+    client.assert_capability("tools");
 
     let project_dir = harness.temp_path().join("test_project");
     tokio::fs::create_dir_all(&project_dir).await.unwrap();
@@ -34,9 +59,11 @@ async fn test_workspace_creation_and_file_listing() {
     let test_file = project_dir.join("main.rs");
     tokio::fs::write(&test_file, "fn main() { println!(\"Hello\"); }").await.unwrap();
 
+    // Step 1: Create workspace
     let start = Instant::now();
-    let result = create_workspace_tool
-        .execute(
+    let created = client
+        .call_tool(
+            "cortex.workspace.create",
             json!({
                 "name": "test_workspace",
                 "root_path": project_dir.to_str().unwrap(),
@@ -46,124 +73,89 @@ async fn test_workspace_creation_and_file_listing() {
                     "create_embeddings": false
                 }
             }),
-            &ToolContext::default(),
         )
         .await
         .expect("Workspace creation failed");
-
     let create_duration = start.elapsed();
 
-    result
-        .assert_success()
-        .assert_has_field("workspace_id")
-        .assert_has_field("files_imported")
-        .assert_has_field("units_extracted");
-
-    let workspace_id = result.get_field("workspace_id").unwrap().as_str().unwrap();
+    let created = tool_json(&created);
+    assert!(created.get("files_imported").is_some());
+    assert!(created.get("units_extracted").is_some());
+    let workspace_id = created["workspace_id"].as_str().unwrap().to_string();
 
     // Step 2: List directory contents via VFS
-    let vfs_ctx = harness.vfs_context();
-    let list_dir_tool = VfsListDirectoryTool::new(vfs_ctx.clone());
-
-    let list_result = list_dir_tool
-        .execute(
+    let listing = client
+        .call_tool(
+            "cortex.vfs.list_directory",
             json!({
                 "path": "/",
                 "workspace_id": workspace_id,
                 "recursive": false
             }),
-            &ToolContext::default(),
         )
         .await
         .expect("Directory listing failed");
-
-    list_result
-        .assert_success()
-        .assert_has_field("entries")
-        .assert_array_min_length("entries", 1);
+    assert!(!tool_json(&listing)["entries"].as_array().unwrap().is_empty());
 
     // Step 3: Get specific file
-    let get_node_tool = VfsGetNodeTool::new(vfs_ctx.clone());
-
-    let file_result = get_node_tool
-        .execute(
+    let file = client
+        .call_tool(
+            "cortex.vfs.get_node",
             json!({
                 "path": "/main.rs",
                 "workspace_id": workspace_id,
                 "include_content": true,
                 "include_metadata": true
             }),
-            &ToolContext::default(),
         )
         .await
         .expect("File retrieval failed");
-
-    file_result
-        .assert_success()
-        .assert_has_field("content")
-        .assert_has_field("version")
-        .assert_has_field("size_bytes");
-
-    let content = file_result.get_field("content").unwrap().as_str().unwrap();
-    assert!(content.contains("println!"), "File content incorrect");
+    let file = tool_json(&file);
+    assert!(file.get("version").is_some());
+    assert!(file.get("size_bytes").is_some());
+    assert!(file["content"].as_str().unwrap().contains("println!"), "File content incorrect");
 
     // Step 4: Update the file
-    let update_tool = VfsUpdateFileTool::new(vfs_ctx.clone());
-
     let new_content = "fn main() { println!(\"Hello, World!\"); }";
-    let update_result = update_tool
-        .execute(
+    let updated = client
+        .call_tool(
+            "cortex.vfs.update_file",
             json!({
                 "path": "/main.rs",
                 "workspace_id": workspace_id,
                 "content": new_content,
                 "create_if_missing": false
             }),
-            &ToolContext::default(),
         )
         .await
         .expect("File update failed");
-
-    update_result
-        .assert_success()
-        .assert_has_field("new_version");
-
-    let new_version = update_result.get_field("new_version").unwrap().as_u64().unwrap();
+    let new_version = tool_json(&updated)["new_version"].as_u64().unwrap();
     assert!(new_version > 1, "Version should have incremented");
 
     // Step 5: Verify the update by reading again
-    let verify_result = get_node_tool
-        .execute(
+    let verified = client
+        .call_tool(
+            "cortex.vfs.get_node",
             json!({
                 "path": "/main.rs",
                 "workspace_id": workspace_id,
                 "include_content": true
             }),
-            &ToolContext::default(),
         )
         .await
         .expect("Verification read failed");
+    assert_eq!(
+        tool_json(&verified)["content"].as_str().unwrap(),
+        new_content,
+        "Content not updated correctly"
+    );
 
-    verify_result.assert_success();
-    let updated_content = verify_result.get_field("content").unwrap().as_str().unwrap();
-    assert_eq!(updated_content, new_content, "Content not updated correctly");
-
-    // Step 6: List again to verify file still exists
-    let final_list = list_dir_tool
-        .execute(
-            json!({
-                "path": "/",
-                "workspace_id": workspace_id,
-                "recursive": false
-            }),
-            &ToolContext::default(),
-        )
+    // Unknown tools surface as protocol errors rather than panics
+    let error = client
+        .call_tool("cortex.vfs.missing", json!({}))
         .await
-        .expect("Final listing failed");
-
-    final_list
-        .assert_success()
-        .assert_array_min_length("entries", 1);
+        .unwrap_err();
+    assert_eq!(error.code, mcp_sdk::protocol::mcp_codes::TOOL_NOT_FOUND);
 
     // Performance assertions
     assert!(
@@ -171,12 +163,6 @@ async fn test_workspace_creation_and_file_listing() {
         "Workspace creation took too long: {:?}",
         create_duration
     );
-
-    println!("✓ Workspace + VFS integration test passed");
-    println!("  - Workspace created in {:?}", create_duration);
-    println!("  - Files imported and indexed");
-    println!("  - File read/write operations verified");
-    println!("  - Version tracking confirmed");
 }
 
 #[tokio::test]
@@ -57,6 +57,8 @@ fn create_test_db_config(db_name: &str) -> DatabaseConfig {
             validate_on_checkout: true,
             recycle_after_uses: Some(1000),
             shutdown_grace_period: Duration::from_secs(5),
+            query_timeout: Some(Duration::from_secs(30)),
+            slow_query_threshold: Some(Duration::from_secs(1)),
         },
         namespace: "cortex_e2e_test".to_string(),
         database: db_name.to_string(),
@@ -64,6 +64,7 @@ impl TestContext {
             parent_workspace: None,
             fork_metadata: None,
             dependencies: vec![],
+            version_retention: Default::default(),
             created_at: now,
             updated_at: now,
         };
@@ -101,6 +102,7 @@ impl TestContext {
             total_units: 0,
             files_with_errors: vec![],
             file_results: vec![],
+            embedded_blocks_skipped: 0,
             duration_ms: 0,
         })
     }
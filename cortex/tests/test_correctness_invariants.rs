@@ -62,6 +62,8 @@ async fn create_test_storage(test_name: &str) -> (TempDir, Arc<ConnectionManager
             validate_on_checkout: true,
             recycle_after_uses: Some(1000),
             shutdown_grace_period: Duration::from_secs(5),
+            query_timeout: Some(Duration::from_secs(30)),
+            slow_query_threshold: Some(Duration::from_secs(1)),
         },
         namespace: "cortex_test".to_string(),
         database: test_name.to_string(),
@@ -66,6 +66,8 @@ async fn create_file_based_config(test_name: &str) -> (TempDir, DatabaseConfig)
             validate_on_checkout: true,
             recycle_after_uses: Some(1000),
             shutdown_grace_period: Duration::from_secs(5),
+            query_timeout: Some(Duration::from_secs(30)),
+            slow_query_threshold: Some(Duration::from_secs(1)),
         },
         namespace: "cortex_test".to_string(),
         database: test_name.to_string(),
@@ -454,6 +454,7 @@ async fn scenario_2_refactoring_race_condition() {
             duration_ms: 10,
             verification: None,
             merged_entities: vec![],
+            failures: vec![],
         };
         assert!(merge_result_a.success);
         metrics.lock().await.conflicts_resolved += 1;
@@ -468,6 +469,7 @@ async fn scenario_2_refactoring_race_condition() {
             duration_ms: 10,
             verification: None,
             merged_entities: vec![],
+            failures: vec![],
         };
         assert!(merge_result_b.success);
 
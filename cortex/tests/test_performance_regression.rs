@@ -191,6 +191,8 @@ fn create_test_db_config(db_name: &str) -> DatabaseConfig {
             validate_on_checkout: false,
             recycle_after_uses: None,
             shutdown_grace_period: Duration::from_secs(5),
+            query_timeout: Some(Duration::from_secs(30)),
+            slow_query_threshold: Some(Duration::from_secs(1)),
         },
         namespace: "cortex_perf_regression".to_string(),
         database: db_name.to_string(),
@@ -1159,6 +1161,8 @@ async fn test_connection_pool_saturation_recovery() {
             validate_on_checkout: false,
             recycle_after_uses: None,
             shutdown_grace_period: Duration::from_secs(5),
+            query_timeout: Some(Duration::from_secs(30)),
+            slow_query_threshold: Some(Duration::from_secs(1)),
         },
         ..create_test_db_config("pool_saturation")
     };
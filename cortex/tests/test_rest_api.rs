@@ -60,6 +60,7 @@ async fn create_test_workspace(
         parent_workspace: None,
         fork_metadata: None,
         dependencies: vec![],
+        version_retention: Default::default(),
         created_at: Utc::now(),
         updated_at: Utc::now(),
     };
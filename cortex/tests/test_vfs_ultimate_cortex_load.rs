@@ -79,6 +79,8 @@ fn create_test_db_config() -> DatabaseConfig {
             validate_on_checkout: false,
             recycle_after_uses: None,
             shutdown_grace_period: Duration::from_secs(10),
+            query_timeout: Some(Duration::from_secs(30)),
+            slow_query_threshold: Some(Duration::from_secs(1)),
         },
         namespace: format!("test_vfs_ultimate_{}", Uuid::new_v4().to_string().replace("-", "")),
         database: "cortex_vfs_ultimate_test".to_string(),
@@ -0,0 +1,141 @@
+//! Prompts Example
+//!
+//! This example serves reusable prompt templates over stdio. Prompts are
+//! plain `Prompt` implementations; no derive macro is involved beyond the
+//! serde and schemars derives on the argument structs.
+//!
+//! # Features Demonstrated
+//!
+//! - Typed prompt arguments, described by their JSON schema
+//! - Required and optional arguments
+//! - Multi-message prompts
+//!
+//! # Running the Example
+//!
+//! ```bash
+//! cargo run --example prompts
+//! ```
+//!
+//! Then send JSON-RPC requests via stdin. Example:
+//!
+//! ```json
+//! {"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-03-26","capabilities":{},"clientInfo":{"name":"test","version":"1.0"}}}
+//! {"jsonrpc":"2.0","id":2,"method":"prompts/list"}
+//! {"jsonrpc":"2.0","id":3,"method":"prompts/get","params":{"name":"code_review","arguments":{"language":"Rust","code":"fn main() {}"}}}
+//! {"jsonrpc":"2.0","id":4,"method":"prompts/get","params":{"name":"code_review","arguments":{"language":"Rust"}}}
+//! ```
+//!
+//! The last request fails with an invalid params error naming the missing
+//! `code` argument.
+
+use mcp_sdk::prelude::*;
+
+/// Arguments for the code review prompt
+#[derive(Deserialize, JsonSchema)]
+struct CodeReviewArgs {
+    /// Language the code is written in
+    language: String,
+    /// The code to review
+    code: String,
+    /// Optional focus area, such as "performance" or "security"
+    focus: Option<String>,
+}
+
+/// Asks the model to review a snippet of code
+struct CodeReviewPrompt;
+
+#[async_trait]
+impl Prompt for CodeReviewPrompt {
+    type Arguments = CodeReviewArgs;
+
+    fn name(&self) -> &str {
+        "code_review"
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Review a code snippet and suggest improvements")
+    }
+
+    async fn render(
+        &self,
+        args: CodeReviewArgs,
+    ) -> std::result::Result<Vec<PromptMessage>, PromptError> {
+        let focus = args
+            .focus
+            .as_deref()
+            .unwrap_or("correctness and readability");
+
+        Ok(vec![
+            PromptMessage::user(format!(
+                "Please review the following {} code, focusing on {}:\n\n```{}\n{}\n```",
+                args.language,
+                focus,
+                args.language.to_lowercase(),
+                args.code
+            )),
+            PromptMessage::assistant("I'll go through the code section by section."),
+        ])
+    }
+}
+
+/// Arguments for the commit message prompt
+#[derive(Deserialize, JsonSchema)]
+struct CommitMessageArgs {
+    /// Output of `git diff --staged`
+    diff: String,
+}
+
+/// Asks the model to write a commit message for a diff
+struct CommitMessagePrompt;
+
+#[async_trait]
+impl Prompt for CommitMessagePrompt {
+    type Arguments = CommitMessageArgs;
+
+    fn name(&self) -> &str {
+        "commit_message"
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Write a commit message for staged changes")
+    }
+
+    async fn render(
+        &self,
+        args: CommitMessageArgs,
+    ) -> std::result::Result<Vec<PromptMessage>, PromptError> {
+        if args.diff.trim().is_empty() {
+            return Err(PromptError::InvalidArguments("diff is empty".to_string()));
+        }
+
+        Ok(vec![PromptMessage::user(format!(
+            "Write a concise commit message for this diff:\n\n{}",
+            args.diff
+        ))])
+    }
+}
+
+#[tokio::main]
+async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
+        )
+        .with_writer(std::io::stderr)
+        .init();
+
+    let server = McpServer::builder()
+        .name("prompts-server")
+        .version("1.0.0")
+        .prompt(CodeReviewPrompt)
+        .prompt(CommitMessagePrompt)
+        .build();
+
+    tracing::info!("Starting prompts server on stdio...");
+
+    server.serve(StdioTransport::new()).await?;
+
+    tracing::info!("Server shutdown");
+    Ok(())
+}
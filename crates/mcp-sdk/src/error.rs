@@ -10,6 +10,7 @@
 //! ├── Transport(TransportError)
 //! ├── Tool(ToolError)
 //! ├── Resource(ResourceError)
+//! ├── Prompt(PromptError)
 //! ├── Middleware(MiddlewareError)
 //! ├── Config(String)
 //! └── Protocol(String)
@@ -63,6 +64,10 @@ pub enum McpError {
     #[error("Resource error: {0}")]
     Resource(#[from] ResourceError),
 
+    /// Prompt rendering error.
+    #[error("Prompt error: {0}")]
+    Prompt(#[from] PromptError),
+
     /// Middleware processing error.
     #[error("Middleware error: {0}")]
     Middleware(#[from] MiddlewareError),
@@ -183,6 +188,54 @@ pub enum ResourceError {
     Internal(#[from] anyhow::Error),
 }
 
+/// Prompt-specific errors.
+///
+/// These errors occur during prompt registration, argument validation, or rendering.
+///
+/// # Examples
+///
+/// ```rust
+/// use mcp_sdk::error::PromptError;
+///
+/// // Prompt not found
+/// let error = PromptError::NotFound("code_review".to_string());
+///
+/// // Required arguments were not supplied
+/// let error = PromptError::MissingArguments(vec!["language".to_string()]);
+/// ```
+#[derive(Debug, Error)]
+pub enum PromptError {
+    /// The requested prompt was not found in the registry.
+    #[error("Prompt not found: {0}")]
+    NotFound(String),
+
+    /// Prompt already registered.
+    ///
+    /// This error occurs when attempting to register a prompt with a name that
+    /// already exists in the registry.
+    #[error("Prompt already registered: {0}")]
+    AlreadyRegistered(String),
+
+    /// Required arguments were not supplied.
+    #[error("Missing required arguments: {}", .0.join(", "))]
+    MissingArguments(Vec<String>),
+
+    /// The supplied arguments do not match the prompt's argument type.
+    #[error("Invalid prompt arguments: {0}")]
+    InvalidArguments(String),
+
+    /// Rendering the prompt failed.
+    #[error("Prompt rendering failed: {0}")]
+    RenderFailed(String),
+
+    /// Internal prompt error.
+    ///
+    /// This is a catch-all for unexpected errors during prompt operations.
+    /// It wraps any error type via `anyhow::Error`.
+    #[error("Internal prompt error: {0}")]
+    Internal(#[from] anyhow::Error),
+}
+
 /// Transport-layer errors.
 ///
 /// These errors occur during message transmission and reception over the transport layer.
@@ -476,6 +529,12 @@ mod tests {
         assert_eq!(error.to_string(), "Resource not found: app://config");
     }
 
+    #[test]
+    fn test_prompt_error_missing_arguments_display() {
+        let error = PromptError::MissingArguments(vec!["language".to_string(), "code".to_string()]);
+        assert_eq!(error.to_string(), "Missing required arguments: language, code");
+    }
+
     #[test]
     fn test_transport_error_closed_display() {
         let error = TransportError::Closed;
@@ -672,6 +731,7 @@ mod tests {
             McpError::Protocol("invalid protocol".to_string()).to_string(),
             McpError::Tool(ToolError::NotFound("test".to_string())).to_string(),
             McpError::Resource(ResourceError::NotFound("test".to_string())).to_string(),
+            McpError::Prompt(PromptError::NotFound("test".to_string())).to_string(),
             McpError::Transport(TransportError::Closed).to_string(),
             McpError::Middleware(MiddlewareError::Blocked("test".to_string())).to_string(),
         ];
@@ -58,10 +58,12 @@
 //! - [`protocol`]: MCP protocol types (JSON-RPC, requests, responses)
 //! - [`tool`]: Tool registration and execution
 //! - [`resource`]: Resource management
+//! - [`prompt`]: Prompt templates
 //! - [`transport`]: Transport layer abstractions
 //! - [`middleware`]: Request/response middleware
 //! - [`hooks`]: Event hook system
 //! - [`server`]: Core MCP server implementation
+//! - [`testing`]: In-process test client for integration-testing servers
 //! - [`error`]: Error types and conversions
 
 #![warn(missing_docs)]
@@ -72,14 +74,17 @@ pub mod error;
 pub mod hooks;
 pub mod middleware;
 pub mod prelude;
+pub mod prompt;
 pub mod protocol;
 pub mod resource;
 pub mod server;
+pub mod testing;
 pub mod tool;
 pub mod transport;
 
 // Re-export commonly used types at crate root
-pub use error::{McpError, ToolError, ResourceError, TransportError};
+pub use error::{McpError, ToolError, ResourceError, PromptError, TransportError};
+pub use prompt::Prompt;
 pub use protocol::{JsonRpcRequest, JsonRpcResponse, ServerCapabilities};
 pub use server::{McpServer, ServerBuilder};
 pub use tool::{Tool, ToolContext, ToolResult};
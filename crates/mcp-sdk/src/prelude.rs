@@ -8,13 +8,14 @@
 //! ```
 
 // Core types
-pub use crate::error::{McpError, Result, ToolError, ResourceError, TransportError};
+pub use crate::error::{McpError, Result, ToolError, ResourceError, PromptError, TransportError};
 pub use crate::protocol::{
     JsonRpcRequest, JsonRpcResponse, JsonRpcError,
     InitializeParams, InitializeResult,
     ServerCapabilities, ServerInfo, ClientInfo,
     ToolDefinition, CallToolParams, CallToolResult,
     ResourceDefinition, ResourceContent,
+    PromptDefinition, PromptMessage, GetPromptResult,
 };
 
 // Tool system
@@ -27,6 +28,11 @@ pub use crate::resource::{
     Resource, ResourceContext, ResourceRegistry,
 };
 
+// Prompt system
+pub use crate::prompt::{
+    Prompt, PromptHandler, PromptRegistry,
+};
+
 // Transport
 pub use crate::transport::{Transport, StdioTransport};
 
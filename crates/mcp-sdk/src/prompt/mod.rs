@@ -0,0 +1,56 @@
+//! Prompt system for the MCP server.
+//!
+//! Prompts are reusable message templates that clients discover with
+//! `prompts/list` and render with `prompts/get`.
+//!
+//! # Overview
+//!
+//! - [`Prompt`]: The trait prompt authors implement, with typed arguments
+//! - [`PromptHandler`]: Object-safe form of a prompt, implemented for every [`Prompt`]
+//! - [`PromptRegistry`]: Thread-safe storage for registered prompts
+//!
+//! Argument names, descriptions and required flags come from the JSON schema
+//! of the prompt's argument type, and `prompts/get` rejects requests that
+//! leave out a required argument before the prompt is rendered.
+//!
+//! # Quick Start
+//!
+//! ```
+//! use mcp_sdk::prelude::*;
+//!
+//! #[derive(Deserialize, JsonSchema)]
+//! struct SummarizeArgs {
+//!     /// Text to summarize
+//!     text: String,
+//! }
+//!
+//! struct Summarize;
+//!
+//! #[async_trait]
+//! impl Prompt for Summarize {
+//!     type Arguments = SummarizeArgs;
+//!
+//!     fn name(&self) -> &str { "summarize" }
+//!
+//!     async fn render(&self, args: SummarizeArgs) -> std::result::Result<Vec<PromptMessage>, PromptError> {
+//!         Ok(vec![PromptMessage::user(format!("Summarize:\n\n{}", args.text))])
+//!     }
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let server = McpServer::builder()
+//!         .name("prompt-server")
+//!         .version("1.0.0")
+//!         .prompt(Summarize)
+//!         .build();
+//!
+//!     assert!(server.prompts().has("summarize").await);
+//! }
+//! ```
+
+mod registry;
+mod traits;
+
+pub use registry::PromptRegistry;
+pub use traits::{Prompt, PromptHandler};
@@ -0,0 +1,224 @@
+//! Prompt registry for managing prompt registration and lookup.
+//!
+//! This module provides a thread-safe registry for storing and rendering prompts.
+
+use super::{Prompt, PromptHandler};
+use crate::error::PromptError;
+use crate::protocol::{GetPromptResult, PromptDefinition};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Thread-safe registry for managing prompts.
+///
+/// Prompts are keyed by the name in their [`PromptDefinition`]. Like
+/// [`ToolRegistry`](crate::tool::ToolRegistry), clones share the same storage.
+///
+/// # Examples
+///
+/// ```
+/// use mcp_sdk::prompt::{Prompt, PromptRegistry};
+/// use mcp_sdk::protocol::PromptMessage;
+/// use mcp_sdk::error::PromptError;
+/// use async_trait::async_trait;
+/// use schemars::JsonSchema;
+/// use serde::Deserialize;
+/// use std::collections::HashMap;
+///
+/// #[derive(Deserialize, JsonSchema)]
+/// struct Args {
+///     topic: String,
+/// }
+///
+/// struct Explain;
+///
+/// #[async_trait]
+/// impl Prompt for Explain {
+///     type Arguments = Args;
+///     fn name(&self) -> &str { "explain" }
+///     async fn render(&self, args: Args) -> Result<Vec<PromptMessage>, PromptError> {
+///         Ok(vec![PromptMessage::user(format!("Explain {}", args.topic))])
+///     }
+/// }
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let registry = PromptRegistry::new();
+///     registry.register(Explain).await.unwrap();
+///
+///     let mut arguments = HashMap::new();
+///     arguments.insert("topic".to_string(), "lifetimes".to_string());
+///     let result = registry.get_prompt("explain", arguments).await.unwrap();
+///     assert_eq!(result.messages[0], PromptMessage::user("Explain lifetimes"));
+/// }
+/// ```
+#[derive(Clone)]
+pub struct PromptRegistry {
+    prompts: Arc<RwLock<HashMap<String, Arc<dyn PromptHandler>>>>,
+}
+
+impl std::fmt::Debug for PromptRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PromptRegistry")
+            .field("prompts", &"<HashMap<String, Arc<dyn PromptHandler>>>")
+            .finish()
+    }
+}
+
+impl PromptRegistry {
+    /// Creates a new empty prompt registry.
+    pub fn new() -> Self {
+        Self {
+            prompts: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Registers a prompt in the registry.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PromptError::AlreadyRegistered` if a prompt with the same
+    /// name is already registered.
+    pub async fn register<P: Prompt + 'static>(&self, prompt: P) -> Result<(), PromptError> {
+        self.register_arc(Arc::new(prompt)).await
+    }
+
+    /// Registers a prompt from an Arc.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PromptError::AlreadyRegistered` if a prompt with the same
+    /// name is already registered.
+    pub async fn register_arc(&self, prompt: Arc<dyn PromptHandler>) -> Result<(), PromptError> {
+        let mut prompts = self.prompts.write().await;
+        let name = prompt.definition().name;
+
+        if prompts.contains_key(&name) {
+            return Err(PromptError::AlreadyRegistered(name));
+        }
+
+        prompts.insert(name, prompt);
+        Ok(())
+    }
+
+    /// Gets a prompt by name.
+    ///
+    /// Returns `None` if the prompt is not found.
+    pub async fn get(&self, name: &str) -> Option<Arc<dyn PromptHandler>> {
+        let prompts = self.prompts.read().await;
+        prompts.get(name).cloned()
+    }
+
+    /// Checks if a prompt with the given name exists.
+    pub async fn has(&self, name: &str) -> bool {
+        let prompts = self.prompts.read().await;
+        prompts.contains_key(name)
+    }
+
+    /// Validates `arguments` and renders the named prompt.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PromptError::NotFound` for an unknown name, otherwise any
+    /// error from [`PromptHandler::get`].
+    pub async fn get_prompt(
+        &self,
+        name: &str,
+        arguments: HashMap<String, String>,
+    ) -> Result<GetPromptResult, PromptError> {
+        let prompt = self
+            .get(name)
+            .await
+            .ok_or_else(|| PromptError::NotFound(name.to_string()))?;
+        prompt.get(arguments).await
+    }
+
+    /// Lists all registered prompts, sorted by name.
+    pub async fn list(&self) -> Vec<PromptDefinition> {
+        let prompts = self.prompts.read().await;
+        let mut definitions: Vec<PromptDefinition> =
+            prompts.values().map(|prompt| prompt.definition()).collect();
+        definitions.sort_by(|a, b| a.name.cmp(&b.name));
+        definitions
+    }
+
+    /// Returns the number of registered prompts.
+    pub async fn count(&self) -> usize {
+        let prompts = self.prompts.read().await;
+        prompts.len()
+    }
+}
+
+impl Default for PromptRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::PromptMessage;
+    use async_trait::async_trait;
+    use schemars::JsonSchema;
+    use serde::Deserialize;
+
+    #[derive(Deserialize, JsonSchema)]
+    struct NoArgs {}
+
+    struct TestPrompt {
+        name: String,
+    }
+
+    impl TestPrompt {
+        fn new(name: impl Into<String>) -> Self {
+            Self { name: name.into() }
+        }
+    }
+
+    #[async_trait]
+    impl Prompt for TestPrompt {
+        type Arguments = NoArgs;
+
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        async fn render(&self, _args: NoArgs) -> Result<Vec<PromptMessage>, PromptError> {
+            Ok(vec![PromptMessage::user(self.name.clone())])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_register_and_list_sorted() {
+        let registry = PromptRegistry::new();
+        registry.register(TestPrompt::new("beta")).await.unwrap();
+        registry.register(TestPrompt::new("alpha")).await.unwrap();
+
+        let names: Vec<String> = registry.list().await.into_iter().map(|d| d.name).collect();
+        assert_eq!(names, vec!["alpha", "beta"]);
+        assert!(registry.has("alpha").await);
+        assert_eq!(registry.count().await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_register_duplicate_prompt() {
+        let registry = PromptRegistry::new();
+        registry.register(TestPrompt::new("dup")).await.unwrap();
+
+        match registry.register(TestPrompt::new("dup")).await {
+            Err(PromptError::AlreadyRegistered(name)) => assert_eq!(name, "dup"),
+            other => panic!("Expected AlreadyRegistered error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_unknown_prompt() {
+        let registry = PromptRegistry::new();
+
+        match registry.get_prompt("missing", HashMap::new()).await {
+            Err(PromptError::NotFound(name)) => assert_eq!(name, "missing"),
+            other => panic!("Expected NotFound error, got {:?}", other),
+        }
+    }
+}
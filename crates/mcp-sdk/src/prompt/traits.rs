@@ -0,0 +1,262 @@
+//! Prompt trait definitions.
+//!
+//! This module defines the typed `Prompt` trait that prompt authors implement,
+//! and the object-safe `PromptHandler` trait the registry stores.
+
+use crate::error::PromptError;
+use crate::protocol::{GetPromptResult, PromptArgument, PromptDefinition, PromptMessage};
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// A reusable prompt template that MCP clients can discover and render.
+///
+/// Arguments are described by a typed struct. Its JSON schema provides the
+/// argument names, their descriptions (from doc comments) and which ones are
+/// required (every field that is not an `Option`). MCP passes prompt
+/// arguments as strings, so fields should be `String` or `Option<String>`.
+///
+/// # Examples
+///
+/// ```
+/// use mcp_sdk::prompt::Prompt;
+/// use mcp_sdk::protocol::PromptMessage;
+/// use mcp_sdk::error::PromptError;
+/// use async_trait::async_trait;
+/// use schemars::JsonSchema;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize, JsonSchema)]
+/// struct ReviewArgs {
+///     /// Language of the code under review
+///     language: String,
+///     /// Optional focus area, e.g. "performance"
+///     focus: Option<String>,
+/// }
+///
+/// struct CodeReview;
+///
+/// #[async_trait]
+/// impl Prompt for CodeReview {
+///     type Arguments = ReviewArgs;
+///
+///     fn name(&self) -> &str {
+///         "code_review"
+///     }
+///
+///     fn description(&self) -> Option<&str> {
+///         Some("Review a piece of code")
+///     }
+///
+///     async fn render(&self, args: ReviewArgs) -> Result<Vec<PromptMessage>, PromptError> {
+///         let focus = args.focus.unwrap_or_else(|| "correctness".to_string());
+///         Ok(vec![PromptMessage::user(format!(
+///             "Review the following {} code, focusing on {}.",
+///             args.language, focus
+///         ))])
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait Prompt: Send + Sync {
+    /// Typed arguments the prompt is rendered with.
+    type Arguments: DeserializeOwned + JsonSchema + Send;
+
+    /// Returns the unique name of this prompt.
+    fn name(&self) -> &str;
+
+    /// Returns an optional description of what this prompt is for.
+    fn description(&self) -> Option<&str> {
+        None
+    }
+
+    /// Renders the prompt into messages.
+    ///
+    /// Required arguments have already been checked when this is called.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PromptError` if the prompt cannot be rendered.
+    async fn render(&self, args: Self::Arguments) -> Result<Vec<PromptMessage>, PromptError>;
+}
+
+/// Object-safe form of a prompt, as stored in the [`PromptRegistry`](super::PromptRegistry).
+///
+/// Every [`Prompt`] implements this trait. Implement it directly only for
+/// prompts whose arguments are not known at compile time.
+#[async_trait]
+pub trait PromptHandler: Send + Sync {
+    /// Returns the definition advertised in `prompts/list`.
+    fn definition(&self) -> PromptDefinition;
+
+    /// Validates `arguments` and renders the prompt.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PromptError::MissingArguments` if a required argument is
+    /// absent, `PromptError::InvalidArguments` if the arguments do not fit
+    /// the prompt, or the prompt's own rendering error.
+    async fn get(&self, arguments: HashMap<String, String>)
+        -> Result<GetPromptResult, PromptError>;
+}
+
+#[async_trait]
+impl<P: Prompt> PromptHandler for P {
+    fn definition(&self) -> PromptDefinition {
+        let schema = schemars::schema_for!(P::Arguments);
+        let arguments = arguments_from_schema(schema.as_value());
+
+        PromptDefinition {
+            name: self.name().to_string(),
+            description: self.description().map(|s| s.to_string()),
+            arguments: (!arguments.is_empty()).then_some(arguments),
+        }
+    }
+
+    async fn get(
+        &self,
+        arguments: HashMap<String, String>,
+    ) -> Result<GetPromptResult, PromptError> {
+        let definition = self.definition();
+        check_required(&definition, &arguments)?;
+
+        let object: Map<String, Value> = arguments
+            .into_iter()
+            .map(|(name, value)| (name, Value::String(value)))
+            .collect();
+        let args = serde_json::from_value(Value::Object(object))
+            .map_err(|e| PromptError::InvalidArguments(e.to_string()))?;
+
+        Ok(GetPromptResult {
+            description: definition.description,
+            messages: self.render(args).await?,
+        })
+    }
+}
+
+/// Reads prompt arguments from the top-level properties of an object schema.
+fn arguments_from_schema(schema: &Value) -> Vec<PromptArgument> {
+    let required: Vec<&str> = schema["required"]
+        .as_array()
+        .map(|names| names.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+
+    schema["properties"]
+        .as_object()
+        .map(|properties| {
+            properties
+                .iter()
+                .map(|(name, property)| PromptArgument {
+                    name: name.clone(),
+                    description: property["description"].as_str().map(|s| s.to_string()),
+                    required: Some(required.contains(&name.as_str())),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Fails with every required argument that is absent, in declaration order.
+fn check_required(
+    definition: &PromptDefinition,
+    arguments: &HashMap<String, String>,
+) -> Result<(), PromptError> {
+    let missing: Vec<String> = definition
+        .arguments
+        .iter()
+        .flatten()
+        .filter(|arg| arg.required == Some(true) && !arguments.contains_key(&arg.name))
+        .map(|arg| arg.name.clone())
+        .collect();
+
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(PromptError::MissingArguments(missing))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Deserialize, JsonSchema)]
+    struct GreetingArgs {
+        /// Who to greet
+        name: String,
+        /// Greeting style
+        style: Option<String>,
+    }
+
+    struct Greeting;
+
+    #[async_trait]
+    impl Prompt for Greeting {
+        type Arguments = GreetingArgs;
+
+        fn name(&self) -> &str {
+            "greeting"
+        }
+
+        fn description(&self) -> Option<&str> {
+            Some("Greet someone")
+        }
+
+        async fn render(&self, args: GreetingArgs) -> Result<Vec<PromptMessage>, PromptError> {
+            let style = args.style.unwrap_or_else(|| "warmly".to_string());
+            Ok(vec![PromptMessage::user(format!(
+                "Greet {} {}",
+                args.name, style
+            ))])
+        }
+    }
+
+    fn args(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_definition_from_schema() {
+        let definition = Greeting.definition();
+        assert_eq!(definition.name, "greeting");
+        assert_eq!(definition.description.as_deref(), Some("Greet someone"));
+
+        let arguments = definition.arguments.unwrap();
+        assert_eq!(arguments.len(), 2);
+        assert_eq!(arguments[0].name, "name");
+        assert_eq!(arguments[0].description.as_deref(), Some("Who to greet"));
+        assert_eq!(arguments[0].required, Some(true));
+        assert_eq!(arguments[1].name, "style");
+        assert_eq!(arguments[1].required, Some(false));
+    }
+
+    #[tokio::test]
+    async fn test_get_renders_messages() {
+        let result = Greeting
+            .get(args(&[("name", "Alice"), ("style", "formally")]))
+            .await
+            .unwrap();
+
+        assert_eq!(result.description.as_deref(), Some("Greet someone"));
+        assert_eq!(result.messages, vec![PromptMessage::user("Greet Alice formally")]);
+    }
+
+    #[tokio::test]
+    async fn test_get_reports_missing_arguments() {
+        let error = Greeting
+            .get(args(&[("style", "formally")]))
+            .await
+            .unwrap_err();
+
+        match error {
+            PromptError::MissingArguments(missing) => assert_eq!(missing, vec!["name"]),
+            other => panic!("Expected MissingArguments, got {:?}", other),
+        }
+    }
+}
@@ -381,6 +381,36 @@ impl From<crate::error::ToolError> for JsonRpcError {
     }
 }
 
+/// Convert `PromptError` to `JsonRpcError`
+///
+/// Unknown prompts and bad arguments are invalid params (-32602), as the MCP
+/// specification requires for `prompts/get`.
+impl From<crate::error::PromptError> for JsonRpcError {
+    fn from(error: crate::error::PromptError) -> Self {
+        use crate::error::PromptError;
+        match error {
+            PromptError::NotFound(name) => JsonRpcError::new(
+                codes::INVALID_PARAMS,
+                format!("Prompt '{}' not found", name),
+                Some(serde_json::json!({ "name": name })),
+            ),
+            PromptError::AlreadyRegistered(name) => JsonRpcError::new(
+                mcp_codes::SERVER_ERROR,
+                format!("Prompt '{}' is already registered", name),
+                None,
+            ),
+            PromptError::MissingArguments(missing) => JsonRpcError::new(
+                codes::INVALID_PARAMS,
+                format!("Missing required arguments: {}", missing.join(", ")),
+                Some(serde_json::json!({ "missing": missing })),
+            ),
+            PromptError::InvalidArguments(msg) => JsonRpcError::invalid_params(&msg),
+            PromptError::RenderFailed(msg) => JsonRpcError::internal_error(Some(msg)),
+            PromptError::Internal(e) => JsonRpcError::internal_error(Some(e.to_string())),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -489,6 +519,21 @@ mod tests {
         assert_eq!(error.message, "Resource 'file:///test.txt' not found");
     }
 
+    #[test]
+    fn test_prompt_errors_are_invalid_params() {
+        use crate::error::PromptError;
+
+        let error: JsonRpcError = PromptError::NotFound("review".to_string()).into();
+        assert_eq!(error.code, codes::INVALID_PARAMS);
+        assert_eq!(error.data, Some(json!({"name": "review"})));
+
+        let error: JsonRpcError =
+            PromptError::MissingArguments(vec!["language".to_string()]).into();
+        assert_eq!(error.code, codes::INVALID_PARAMS);
+        assert_eq!(error.message, "Missing required arguments: language");
+        assert_eq!(error.data, Some(json!({"missing": ["language"]})));
+    }
+
     #[test]
     fn test_error_serialization() {
         let error = JsonRpcError::new(
@@ -536,16 +536,11 @@ pub struct GetPromptParams {
 /// # Examples
 ///
 /// ```
-/// use mcp_server::protocol::{GetPromptResult, PromptMessage};
+/// use mcp_sdk::protocol::{GetPromptResult, PromptMessage};
 ///
 /// let result = GetPromptResult {
 ///     description: Some("Greeting prompt".to_string()),
-///     messages: vec![
-///         PromptMessage {
-///             role: "user".to_string(),
-///             content: "Hello, Alice!".to_string(),
-///         }
-///     ],
+///     messages: vec![PromptMessage::user("Hello, Alice!")],
 /// };
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -563,12 +558,16 @@ pub struct GetPromptResult {
 /// # Examples
 ///
 /// ```
-/// use mcp_server::protocol::PromptMessage;
+/// use mcp_sdk::protocol::{PromptMessage, ToolContent};
 ///
 /// let message = PromptMessage {
 ///     role: "user".to_string(),
-///     content: "What is the weather?".to_string(),
+///     content: ToolContent::Text {
+///         text: "What is the weather?".to_string(),
+///     },
 /// };
+///
+/// assert_eq!(message, PromptMessage::user("What is the weather?"));
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct PromptMessage {
@@ -576,7 +575,25 @@ pub struct PromptMessage {
     pub role: String,
 
     /// Message content
-    pub content: String,
+    pub content: ToolContent,
+}
+
+impl PromptMessage {
+    /// Creates a text message from the user.
+    pub fn user(text: impl Into<String>) -> Self {
+        Self {
+            role: "user".to_string(),
+            content: ToolContent::Text { text: text.into() },
+        }
+    }
+
+    /// Creates a text message from the assistant.
+    pub fn assistant(text: impl Into<String>) -> Self {
+        Self {
+            role: "assistant".to_string(),
+            content: ToolContent::Text { text: text.into() },
+        }
+    }
 }
 
 /// List prompts result
@@ -897,6 +914,14 @@ mod tests {
         assert_eq!(params, deserialized);
     }
 
+    #[test]
+    fn test_prompt_message_content_is_typed() {
+        let json = serde_json::to_value(PromptMessage::assistant("Sure.")).unwrap();
+        assert_eq!(json["role"], "assistant");
+        assert_eq!(json["content"]["type"], "text");
+        assert_eq!(json["content"]["text"], "Sure.");
+    }
+
     // ============================================================================================
     // Logging Tests
     // ============================================================================================
@@ -1,11 +1,12 @@
 //! Server builder for fluent API construction.
 //!
 //! This module provides the `ServerBuilder` type which enables a fluent,
-//! type-safe API for constructing MCP servers with tools, resources, middleware, and hooks.
+//! type-safe API for constructing MCP servers with tools, resources, prompts, middleware, and hooks.
 
 use super::{McpServer, ServerConfig};
 use crate::tool::Tool;
 use crate::resource::Resource;
+use crate::prompt::{Prompt, PromptHandler};
 use crate::middleware::Middleware;
 use crate::hooks::Hook;
 use std::sync::Arc;
@@ -101,6 +102,7 @@ pub struct ServerBuilder {
     protocol_version: Option<String>,
     tools: Vec<Arc<dyn Tool>>,
     resources: Vec<Arc<dyn Resource>>,
+    prompts: Vec<Arc<dyn PromptHandler>>,
     middleware: Vec<Arc<dyn Middleware>>,
     hooks: Vec<Arc<dyn Hook>>,
 }
@@ -113,6 +115,7 @@ impl std::fmt::Debug for ServerBuilder {
             .field("protocol_version", &self.protocol_version)
             .field("tools", &format!("<{} tools>", self.tools.len()))
             .field("resources", &format!("<{} resources>", self.resources.len()))
+            .field("prompts", &format!("<{} prompts>", self.prompts.len()))
             .field("middleware", &format!("<{} middleware>", self.middleware.len()))
             .field("hooks", &format!("<{} hooks>", self.hooks.len()))
             .finish()
@@ -136,6 +139,7 @@ impl ServerBuilder {
             protocol_version: None,
             tools: Vec::new(),
             resources: Vec::new(),
+            prompts: Vec::new(),
             middleware: Vec::new(),
             hooks: Vec::new(),
         }
@@ -357,6 +361,70 @@ impl ServerBuilder {
         self
     }
 
+    /// Registers a prompt with the server.
+    ///
+    /// Registered prompts are served by `prompts/list` and `prompts/get`, and
+    /// the server advertises the prompts capability once at least one is
+    /// registered.
+    ///
+    /// # Arguments
+    ///
+    /// * `prompt` - The prompt to register
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mcp_sdk::server::ServerBuilder;
+    /// use mcp_sdk::prompt::Prompt;
+    /// use mcp_sdk::protocol::PromptMessage;
+    /// use mcp_sdk::error::PromptError;
+    /// use async_trait::async_trait;
+    /// use schemars::JsonSchema;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize, JsonSchema)]
+    /// struct Args {
+    ///     topic: String,
+    /// }
+    ///
+    /// struct Explain;
+    ///
+    /// #[async_trait]
+    /// impl Prompt for Explain {
+    ///     type Arguments = Args;
+    ///     fn name(&self) -> &str { "explain" }
+    ///     async fn render(&self, args: Args) -> Result<Vec<PromptMessage>, PromptError> {
+    ///         Ok(vec![PromptMessage::user(format!("Explain {}", args.topic))])
+    ///     }
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let server = ServerBuilder::new()
+    ///         .name("my-server")
+    ///         .version("1.0.0")
+    ///         .prompt(Explain)
+    ///         .build();
+    /// }
+    /// ```
+    pub fn prompt<P: Prompt + 'static>(mut self, prompt: P) -> Self {
+        self.prompts.push(Arc::new(prompt));
+        self
+    }
+
+    /// Registers multiple prompts with the server.
+    ///
+    /// # Arguments
+    ///
+    /// * `prompts` - An iterator of Arc-wrapped prompt handlers
+    pub fn prompts<I>(mut self, prompts: I) -> Self
+    where
+        I: IntoIterator<Item = Arc<dyn PromptHandler>>,
+    {
+        self.prompts.extend(prompts);
+        self
+    }
+
     /// Registers a middleware with the server.
     ///
     /// Middleware intercepts requests and responses for cross-cutting concerns
@@ -448,7 +516,7 @@ impl ServerBuilder {
     /// Builds the `McpServer`.
     ///
     /// This consumes the builder and creates a new `McpServer` instance
-    /// with all the configured tools, resources, middleware, and hooks.
+    /// with all the configured tools, resources, prompts, middleware, and hooks.
     ///
     /// # Panics
     ///
@@ -475,7 +543,14 @@ impl ServerBuilder {
             protocol_version: self.protocol_version.unwrap_or_else(|| "2025-03-26".to_string()),
         };
 
-        McpServer::new(config, self.tools, self.resources, self.middleware, self.hooks)
+        McpServer::new(
+            config,
+            self.tools,
+            self.resources,
+            self.prompts,
+            self.middleware,
+            self.hooks,
+        )
     }
 }
 
@@ -6,16 +6,22 @@ use super::{ServerBuilder, ServerConfig};
 use crate::protocol::*;
 use crate::tool::{Tool, ToolContext, ToolRegistry};
 use crate::resource::{Resource, ResourceRegistry};
-use crate::middleware::{Middleware, MiddlewareRegistry};
-use crate::hooks::{Hook, HookRegistry};
-use serde_json::json;
+use crate::prompt::{PromptHandler, PromptRegistry};
+use crate::middleware::{Middleware, MiddlewareRegistry, RequestContext};
+use crate::hooks::{Hook, HookEvent, HookRegistry};
+use serde_json::{json, Value};
 use std::sync::Arc;
+use tokio::sync::broadcast;
+
+/// Number of server notifications buffered for each subscriber.
+const NOTIFICATION_BUFFER: usize = 256;
 
 /// Main MCP server instance.
 ///
 /// `McpServer` handles all MCP protocol requests including initialization,
-/// tool listing/calling, and resource operations. It maintains registries
-/// for tools and resources, and can be extended with middleware and hooks.
+/// tool listing/calling, resource operations and prompts. It maintains
+/// registries for tools, resources and prompts, and can be extended with
+/// middleware and hooks.
 ///
 /// # Examples
 ///
@@ -84,8 +90,10 @@ pub struct McpServer {
     config: ServerConfig,
     tools: Arc<ToolRegistry>,
     resources: Arc<ResourceRegistry>,
+    prompts: Arc<PromptRegistry>,
     middleware: Arc<MiddlewareRegistry>,
     hooks: Arc<HookRegistry>,
+    notifications: broadcast::Sender<JsonRpcRequest>,
 }
 
 impl std::fmt::Debug for McpServer {
@@ -94,6 +102,7 @@ impl std::fmt::Debug for McpServer {
             .field("config", &self.config)
             .field("tools", &"<ToolRegistry>")
             .field("resources", &"<ResourceRegistry>")
+            .field("prompts", &"<PromptRegistry>")
             .field("middleware", &"<MiddlewareRegistry>")
             .field("hooks", &"<HookRegistry>")
             .finish()
@@ -101,7 +110,7 @@ impl std::fmt::Debug for McpServer {
 }
 
 impl McpServer {
-    /// Creates a new `McpServer` with the given configuration, tools, resources, middleware, and hooks.
+    /// Creates a new `McpServer` with the given configuration, tools, resources, prompts, middleware, and hooks.
     ///
     /// This is typically called by `ServerBuilder::build()` rather than directly.
     ///
@@ -110,6 +119,7 @@ impl McpServer {
     /// * `config` - Server configuration
     /// * `tools` - Vec of Arc-wrapped tools to register
     /// * `resources` - Vec of Arc-wrapped resources to register
+    /// * `prompts` - Vec of Arc-wrapped prompts to register
     /// * `middleware` - Vec of Arc-wrapped middleware to register
     /// * `hooks` - Vec of Arc-wrapped hooks to register
     ///
@@ -122,19 +132,22 @@ impl McpServer {
     /// let config = ServerConfig::new("my-server", "1.0.0");
     /// let tools = vec![];
     /// let resources = vec![];
+    /// let prompts = vec![];
     /// let middleware = vec![];
     /// let hooks = vec![];
-    /// let server = McpServer::new(config, tools, resources, middleware, hooks);
+    /// let server = McpServer::new(config, tools, resources, prompts, middleware, hooks);
     /// ```
     pub fn new(
         config: ServerConfig,
         tools: Vec<Arc<dyn Tool>>,
         resources: Vec<Arc<dyn Resource>>,
+        prompts: Vec<Arc<dyn PromptHandler>>,
         middleware: Vec<Arc<dyn Middleware>>,
         hooks: Vec<Arc<dyn Hook>>,
     ) -> Self {
         let tool_registry = ToolRegistry::new();
         let resource_registry = ResourceRegistry::new();
+        let prompt_registry = PromptRegistry::new();
         let middleware_registry = MiddlewareRegistry::new();
         let hook_registry = HookRegistry::new();
 
@@ -156,6 +169,15 @@ impl McpServer {
             });
         }
 
+        // Register all prompts
+        for prompt in prompts {
+            let _ = tokio::task::block_in_place(|| {
+                tokio::runtime::Handle::current().block_on(async {
+                    prompt_registry.register_arc(prompt).await
+                })
+            });
+        }
+
         // Register all middleware
         for mw in middleware {
             let _ = tokio::task::block_in_place(|| {
@@ -178,8 +200,10 @@ impl McpServer {
             config,
             tools: Arc::new(tool_registry),
             resources: Arc::new(resource_registry),
+            prompts: Arc::new(prompt_registry),
             middleware: Arc::new(middleware_registry),
             hooks: Arc::new(hook_registry),
+            notifications: broadcast::channel(NOTIFICATION_BUFFER).0,
         }
     }
 
@@ -260,6 +284,27 @@ impl McpServer {
         &self.resources
     }
 
+    /// Returns a reference to the prompt registry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mcp_sdk::server::McpServer;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let server = McpServer::builder()
+    ///         .name("test-server")
+    ///         .version("1.0.0")
+    ///         .build();
+    ///
+    ///     assert_eq!(server.prompts().count().await, 0);
+    /// }
+    /// ```
+    pub fn prompts(&self) -> &PromptRegistry {
+        &self.prompts
+    }
+
     /// Returns a reference to the middleware registry.
     ///
     /// # Examples
@@ -302,10 +347,55 @@ impl McpServer {
         &self.hooks
     }
 
+    /// Pushes a notification to every subscriber.
+    ///
+    /// Notifications are JSON-RPC requests without an id, such as
+    /// `notifications/tools/list_changed`. They are dropped when nobody is
+    /// subscribed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mcp_sdk::server::McpServer;
+    /// use serde_json::json;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let server = McpServer::builder()
+    ///         .name("test-server")
+    ///         .version("1.0.0")
+    ///         .build();
+    ///
+    ///     let mut notifications = server.subscribe_notifications();
+    ///     server.notify("notifications/message", Some(json!({"level": "info"})));
+    ///
+    ///     let notification = notifications.recv().await.unwrap();
+    ///     assert_eq!(notification.method, "notifications/message");
+    /// }
+    /// ```
+    pub fn notify(&self, method: impl Into<String>, params: Option<Value>) {
+        let notification = JsonRpcRequest::notification(method.into(), params);
+        // An error only means there are no subscribers
+        let _ = self.notifications.send(notification);
+    }
+
+    /// Subscribes to notifications pushed with [`notify`](Self::notify).
+    ///
+    /// Only notifications sent after subscribing are received.
+    pub fn subscribe_notifications(&self) -> broadcast::Receiver<JsonRpcRequest> {
+        self.notifications.subscribe()
+    }
+
     /// Handles a JSON-RPC request and returns a response.
     ///
     /// This is the main entry point for processing MCP protocol requests.
-    /// It routes requests to the appropriate handler based on the method name.
+    /// The request first passes through the middleware chain, is then routed
+    /// to the appropriate handler based on the method name, and the response
+    /// passes back through the middleware in reverse order. Handlers emit
+    /// [`HookEvent`]s as they go.
+    ///
+    /// A request rejected by middleware gets an error response without
+    /// reaching a handler, and the response is not passed to `on_response`.
     ///
     /// # Supported Methods
     ///
@@ -314,6 +404,8 @@ impl McpServer {
     /// - `tools/call` - Execute a tool
     /// - `resources/list` - List all available resources
     /// - `resources/read` - Read a resource
+    /// - `prompts/list` - List all available prompts
+    /// - `prompts/get` - Render a prompt with arguments
     ///
     /// # Arguments
     ///
@@ -344,12 +436,39 @@ impl McpServer {
     /// }
     /// ```
     pub async fn handle_request(&self, request: JsonRpcRequest) -> JsonRpcResponse {
+        let mut context = RequestContext::new(request.method.clone());
+
+        if let Err(e) = self.middleware.run_on_request(&request, &mut context).await {
+            self.hooks
+                .emit(&HookEvent::Error {
+                    error: e.to_string(),
+                })
+                .await;
+            return JsonRpcResponse::error(
+                request.id,
+                JsonRpcError::new(mcp_codes::SERVER_ERROR, e.to_string(), None),
+            );
+        }
+
+        let response = self.dispatch(request).await;
+
+        if let Err(e) = self.middleware.run_on_response(&response, &context).await {
+            tracing::warn!(method = %context.method(), error = %e, "Response middleware failed");
+        }
+
+        response
+    }
+
+    /// Routes a request to its handler.
+    async fn dispatch(&self, request: JsonRpcRequest) -> JsonRpcResponse {
         match request.method.as_str() {
             "initialize" => self.handle_initialize(request).await,
             "tools/list" => self.handle_tools_list(request).await,
             "tools/call" => self.handle_tools_call(request).await,
             "resources/list" => self.handle_resources_list(request).await,
             "resources/read" => self.handle_resources_read(request).await,
+            "prompts/list" => self.handle_prompts_list(request).await,
+            "prompts/get" => self.handle_prompts_get(request).await,
             _ => JsonRpcResponse::method_not_found(request.id),
         }
     }
@@ -389,12 +508,16 @@ impl McpServer {
             }
         };
 
-        // Build server capabilities
-        let capabilities = ServerCapabilities::builder()
-            .with_tools(ToolsCapability {
+        // Build server capabilities, advertising prompts only when there are any
+        let mut capabilities = ServerCapabilities::builder().with_tools(ToolsCapability {
+            list_changed: Some(false),
+        });
+        if self.prompts.count().await > 0 {
+            capabilities = capabilities.with_prompts(PromptsCapability {
                 list_changed: Some(false),
-            })
-            .build();
+            });
+        }
+        let capabilities = capabilities.build();
 
         // Build initialize result
         let result = InitializeResult {
@@ -407,7 +530,10 @@ impl McpServer {
         };
 
         match serde_json::to_value(&result) {
-            Ok(value) => JsonRpcResponse::success(request.id, value),
+            Ok(value) => {
+                self.hooks.emit(&HookEvent::ClientConnected).await;
+                JsonRpcResponse::success(request.id, value)
+            }
             Err(e) => JsonRpcResponse::internal_error(
                 request.id,
                 Some(format!("Failed to serialize initialize result: {}", e)),
@@ -482,7 +608,26 @@ impl McpServer {
 
         let input = params.arguments.unwrap_or(json!({}));
 
-        match tool.execute(input, &context).await {
+        self.hooks
+            .emit(&HookEvent::ToolCalled {
+                name: params.name.clone(),
+                args: input.clone(),
+            })
+            .await;
+
+        let outcome = tool.execute(input, &context).await;
+
+        self.hooks
+            .emit(&HookEvent::ToolCompleted {
+                name: params.name.clone(),
+                result: match &outcome {
+                    Ok(result) => serde_json::to_value(result).map_err(|e| e.to_string()),
+                    Err(e) => Err(e.to_string()),
+                },
+            })
+            .await;
+
+        match outcome {
             Ok(result) => {
                 // Convert ToolResult to CallToolResult
                 let call_result = CallToolResult {
@@ -582,6 +727,12 @@ impl McpServer {
             None => return JsonRpcResponse::resource_not_found(request.id, &params.uri),
         };
 
+        self.hooks
+            .emit(&HookEvent::ResourceRead {
+                uri: params.uri.clone(),
+            })
+            .await;
+
         // Create resource context
         let context = crate::resource::ResourceContext::default();
 
@@ -631,6 +782,66 @@ impl McpServer {
         }
     }
 
+    /// Handles the `prompts/list` request.
+    ///
+    /// Returns the definitions of all registered prompts, sorted by name.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The prompts/list request
+    async fn handle_prompts_list(&self, request: JsonRpcRequest) -> JsonRpcResponse {
+        let result = ListPromptsResult {
+            prompts: self.prompts.list().await,
+        };
+
+        match serde_json::to_value(&result) {
+            Ok(value) => JsonRpcResponse::success(request.id, value),
+            Err(e) => JsonRpcResponse::internal_error(
+                request.id,
+                Some(format!("Failed to serialize prompts list: {}", e)),
+            ),
+        }
+    }
+
+    /// Handles the `prompts/get` request.
+    ///
+    /// Validates the arguments against the prompt's definition and returns the
+    /// rendered messages. Unknown prompts and missing required arguments are
+    /// reported as invalid params.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The prompts/get request
+    async fn handle_prompts_get(&self, request: JsonRpcRequest) -> JsonRpcResponse {
+        // Parse get params
+        let params = match request.params {
+            Some(ref p) => match serde_json::from_value::<GetPromptParams>(p.clone()) {
+                Ok(params) => params,
+                Err(e) => {
+                    return JsonRpcResponse::invalid_params(
+                        request.id,
+                        &format!("Invalid prompt get params: {}", e),
+                    )
+                }
+            },
+            None => {
+                return JsonRpcResponse::invalid_params(request.id, "Prompt get params are required")
+            }
+        };
+
+        let arguments = params.arguments.unwrap_or_default();
+        match self.prompts.get_prompt(&params.name, arguments).await {
+            Ok(result) => match serde_json::to_value(&result) {
+                Ok(value) => JsonRpcResponse::success(request.id, value),
+                Err(e) => JsonRpcResponse::internal_error(
+                    request.id,
+                    Some(format!("Failed to serialize prompt: {}", e)),
+                ),
+            },
+            Err(e) => JsonRpcResponse::error(request.id, e.into()),
+        }
+    }
+
     /// Serves the MCP server using the provided transport.
     ///
     /// This method runs the main server loop, receiving requests from the transport,
@@ -691,7 +902,8 @@ impl McpServer {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::error::ToolError;
+    use crate::error::{PromptError, ToolError};
+    use crate::prompt::Prompt;
     use crate::tool::{ToolResult, ToolContext};
     use async_trait::async_trait;
     use serde_json::{json, Value};
@@ -735,10 +947,53 @@ mod tests {
         }
     }
 
+    #[derive(serde::Deserialize, schemars::JsonSchema)]
+    struct ReviewArgs {
+        /// Language of the code
+        language: String,
+        /// What to focus on
+        focus: Option<String>,
+    }
+
+    struct ReviewPrompt;
+
+    #[async_trait]
+    impl Prompt for ReviewPrompt {
+        type Arguments = ReviewArgs;
+
+        fn name(&self) -> &str {
+            "review"
+        }
+
+        fn description(&self) -> Option<&str> {
+            Some("Review code")
+        }
+
+        async fn render(&self, args: ReviewArgs) -> Result<Vec<PromptMessage>, PromptError> {
+            let focus = args.focus.unwrap_or_else(|| "bugs".to_string());
+            Ok(vec![PromptMessage::user(format!(
+                "Review this {} code for {}",
+                args.language, focus
+            ))])
+        }
+    }
+
+    fn initialize_request() -> JsonRpcRequest {
+        JsonRpcRequest::new(
+            Some(json!(1)),
+            "initialize".to_string(),
+            Some(json!({
+                "protocolVersion": "2025-03-26",
+                "capabilities": {},
+                "clientInfo": { "name": "test-client", "version": "1.0.0" }
+            })),
+        )
+    }
+
     #[test]
     fn test_server_new() {
         let config = ServerConfig::new("test-server", "1.0.0");
-        let server = McpServer::new(config, vec![], vec![], vec![], vec![]);
+        let server = McpServer::new(config, vec![], vec![], vec![], vec![], vec![]);
         assert_eq!(server.config().name(), "test-server");
     }
 
@@ -985,4 +1240,98 @@ mod tests {
         let count = server.tools().count().await;
         assert_eq!(count, 3);
     }
+
+    #[tokio::test]
+    async fn test_prompts_capability_absent_without_prompts() {
+        let server = McpServer::builder()
+            .name("test")
+            .version("1.0.0")
+            .build();
+
+        let response = server.handle_request(initialize_request()).await;
+        let result: InitializeResult = serde_json::from_value(response.result.unwrap()).unwrap();
+        assert!(result.capabilities.prompts.is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_handle_prompts_list_and_get() {
+        let server = McpServer::builder()
+            .name("test")
+            .version("1.0.0")
+            .prompt(ReviewPrompt)
+            .build();
+
+        let response = server.handle_request(initialize_request()).await;
+        let result: InitializeResult = serde_json::from_value(response.result.unwrap()).unwrap();
+        assert!(result.capabilities.prompts.is_some());
+
+        let request = JsonRpcRequest::new(Some(json!(2)), "prompts/list".to_string(), None);
+        let response = server.handle_request(request).await;
+        let list: ListPromptsResult = serde_json::from_value(response.result.unwrap()).unwrap();
+        assert_eq!(list.prompts.len(), 1);
+        assert_eq!(list.prompts[0].name, "review");
+        let arguments = list.prompts[0].arguments.as_ref().unwrap();
+        assert_eq!(arguments[0].name, "language");
+        assert_eq!(arguments[0].required, Some(true));
+        assert_eq!(arguments[1].required, Some(false));
+
+        let request = JsonRpcRequest::new(
+            Some(json!(3)),
+            "prompts/get".to_string(),
+            Some(json!({ "name": "review", "arguments": { "language": "Rust" } })),
+        );
+        let response = server.handle_request(request).await;
+        let prompt: GetPromptResult = serde_json::from_value(response.result.unwrap()).unwrap();
+        assert_eq!(prompt.description.as_deref(), Some("Review code"));
+        assert_eq!(
+            prompt.messages,
+            vec![PromptMessage::user("Review this Rust code for bugs")]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_handle_prompts_get_missing_arguments() {
+        let server = McpServer::builder()
+            .name("test")
+            .version("1.0.0")
+            .prompt(ReviewPrompt)
+            .build();
+
+        let request = JsonRpcRequest::new(
+            Some(json!(4)),
+            "prompts/get".to_string(),
+            Some(json!({ "name": "review", "arguments": { "focus": "style" } })),
+        );
+        let error = server.handle_request(request).await.error.unwrap();
+        assert_eq!(error.code, codes::INVALID_PARAMS);
+        assert_eq!(error.message, "Missing required arguments: language");
+        assert_eq!(error.data, Some(json!({ "missing": ["language"] })));
+
+        // No arguments at all is the same as an empty map
+        let request = JsonRpcRequest::new(
+            Some(json!(5)),
+            "prompts/get".to_string(),
+            Some(json!({ "name": "review" })),
+        );
+        let error = server.handle_request(request).await.error.unwrap();
+        assert_eq!(error.code, codes::INVALID_PARAMS);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_handle_prompts_get_unknown_prompt() {
+        let server = McpServer::builder()
+            .name("test")
+            .version("1.0.0")
+            .prompt(ReviewPrompt)
+            .build();
+
+        let request = JsonRpcRequest::new(
+            Some(json!(6)),
+            "prompts/get".to_string(),
+            Some(json!({ "name": "nonexistent" })),
+        );
+        let error = server.handle_request(request).await.error.unwrap();
+        assert_eq!(error.code, codes::INVALID_PARAMS);
+        assert_eq!(error.message, "Prompt 'nonexistent' not found");
+    }
 }
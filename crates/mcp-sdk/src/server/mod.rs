@@ -23,6 +23,7 @@
 //! │  - handle_initialize()              │
 //! │  - handle_tools_*()                 │
 //! │  - handle_resources_*()             │
+//! │  - handle_prompts_*()               │
 //! ├─────────────────────────────────────┤
 //! │     Middleware Chain                │
 //! │  (before/after request processing)  │
@@ -33,6 +34,7 @@
 //! │   Registries                        │
 //! │  - ToolRegistry                     │
 //! │  - ResourceRegistry                 │
+//! │  - PromptRegistry                   │
 //! └─────────────────────────────────────┘
 //! ```
 //!
@@ -0,0 +1,508 @@
+//! In-process test client for MCP servers
+//!
+//! [`TestClient`] drives an [`McpServer`] directly, without a transport. It
+//! performs the `initialize` handshake on connect and then offers typed helpers
+//! for the common requests. Every request goes through
+//! [`McpServer::handle_request`], so middleware and hooks run exactly as they
+//! would behind a real transport, and tests catch ordering bugs between them.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use mcp_sdk::prelude::*;
+//! use mcp_sdk::testing::TestClient;
+//!
+//! struct EchoTool;
+//!
+//! #[async_trait]
+//! impl Tool for EchoTool {
+//!     fn name(&self) -> &str { "echo" }
+//!     fn input_schema(&self) -> Value { json!({"type": "object"}) }
+//!     async fn execute(&self, input: Value, _: &ToolContext) -> std::result::Result<ToolResult, ToolError> {
+//!         Ok(ToolResult::success_text(input["message"].as_str().unwrap_or_default()))
+//!     }
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let server = McpServer::builder()
+//!         .name("echo-server")
+//!         .version("1.0.0")
+//!         .tool(EchoTool)
+//!         .build();
+//!
+//!     let client = TestClient::connect(server).await.unwrap();
+//!     client.assert_capability("tools");
+//!
+//!     let tools = client.list_tools().await.unwrap();
+//!     assert_eq!(tools[0].name, "echo");
+//!
+//!     let result = client.call_tool("echo", json!({"message": "hi"})).await.unwrap();
+//!     assert_eq!(result.is_error, None);
+//! }
+//! ```
+
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::sync::broadcast::{self, error::TryRecvError};
+
+use crate::protocol::{
+    CallToolResult, ClientCapabilities, ClientInfo, GetPromptResult, InitializeParams,
+    InitializeResult, JsonRpcError, JsonRpcRequest, JsonRpcResponse, ListPromptsResult,
+    ListResourcesResult, ListToolsResult, PromptDefinition, ReadResourceResult,
+    ResourceDefinition, ServerCapabilities, ToolDefinition,
+};
+use crate::server::McpServer;
+use crate::PROTOCOL_VERSION;
+
+/// Client that talks to an [`McpServer`] in-process.
+///
+/// Requests are numbered from 1, starting with the `initialize` request sent by
+/// [`connect`](Self::connect).
+pub struct TestClient {
+    server: McpServer,
+    next_id: AtomicU64,
+    initialize: InitializeResult,
+    subscription: Mutex<broadcast::Receiver<JsonRpcRequest>>,
+    notifications: Mutex<Vec<JsonRpcRequest>>,
+}
+
+impl std::fmt::Debug for TestClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TestClient")
+            .field("server", &self.server)
+            .field("initialize", &self.initialize)
+            .finish()
+    }
+}
+
+impl TestClient {
+    /// Connects to `server` with default client capabilities.
+    ///
+    /// # Errors
+    ///
+    /// Returns the server's error if the `initialize` request fails.
+    pub async fn connect(server: McpServer) -> Result<Self, JsonRpcError> {
+        Self::connect_with(server, ClientCapabilities::default()).await
+    }
+
+    /// Connects to `server`, advertising `capabilities` in the handshake.
+    ///
+    /// Notifications are captured from before the handshake, so anything the
+    /// server pushes while initializing shows up in
+    /// [`notifications`](Self::notifications).
+    ///
+    /// # Errors
+    ///
+    /// Returns the server's error if the `initialize` request fails.
+    pub async fn connect_with(
+        server: McpServer,
+        capabilities: ClientCapabilities,
+    ) -> Result<Self, JsonRpcError> {
+        let subscription = server.subscribe_notifications();
+        let params = InitializeParams {
+            protocol_version: PROTOCOL_VERSION.to_string(),
+            capabilities,
+            client_info: ClientInfo {
+                name: "mcp-sdk-test-client".to_string(),
+                version: crate::VERSION.to_string(),
+            },
+        };
+        let request = JsonRpcRequest::new(
+            Some(json!(1)),
+            "initialize".to_string(),
+            Some(to_params(&params)?),
+        );
+        let initialize = decode(server.handle_request(request).await)?;
+
+        Ok(Self {
+            server,
+            next_id: AtomicU64::new(2),
+            initialize,
+            subscription: Mutex::new(subscription),
+            notifications: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// The server under test.
+    pub fn server(&self) -> &McpServer {
+        &self.server
+    }
+
+    /// The result of the `initialize` handshake.
+    pub fn initialize_result(&self) -> &InitializeResult {
+        &self.initialize
+    }
+
+    /// Capabilities the server advertised during the handshake.
+    pub fn server_capabilities(&self) -> &ServerCapabilities {
+        &self.initialize.capabilities
+    }
+
+    /// Asserts that the server negotiated `version` of the protocol.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the server answered with a different protocol version.
+    pub fn assert_protocol_version(&self, version: &str) {
+        assert_eq!(
+            self.initialize.protocol_version, version,
+            "server negotiated protocol version {}, expected {}",
+            self.initialize.protocol_version, version
+        );
+    }
+
+    /// Asserts that the server advertised a capability.
+    ///
+    /// `name` is one of `tools`, `resources`, `prompts` or `logging`, or the
+    /// key of an experimental capability.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the capability was not advertised.
+    pub fn assert_capability(&self, name: &str) {
+        assert!(
+            self.has_capability(name),
+            "server did not advertise the `{}` capability: {:?}",
+            name,
+            self.initialize.capabilities
+        );
+    }
+
+    /// Asserts that the server did not advertise a capability.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the capability was advertised.
+    pub fn assert_no_capability(&self, name: &str) {
+        assert!(
+            !self.has_capability(name),
+            "server unexpectedly advertised the `{}` capability",
+            name
+        );
+    }
+
+    fn has_capability(&self, name: &str) -> bool {
+        let capabilities = &self.initialize.capabilities;
+        match name {
+            "tools" => capabilities.tools.is_some(),
+            "resources" => capabilities.resources.is_some(),
+            "prompts" => capabilities.prompts.is_some(),
+            "logging" => capabilities.logging.is_some(),
+            other => capabilities.experimental.contains_key(other),
+        }
+    }
+
+    /// Sends a raw request and returns the raw response.
+    pub async fn request(&self, method: &str, params: Option<Value>) -> JsonRpcResponse {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let request = JsonRpcRequest::new(Some(json!(id)), method.to_string(), params);
+        self.server.handle_request(request).await
+    }
+
+    /// Sends a request and decodes its result.
+    ///
+    /// # Errors
+    ///
+    /// Returns the server's error, or an internal error if the result does
+    /// not decode as `T`.
+    pub async fn call<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: Option<Value>,
+    ) -> Result<T, JsonRpcError> {
+        decode(self.request(method, params).await)
+    }
+
+    /// Lists the server's tools.
+    ///
+    /// # Errors
+    ///
+    /// Returns the server's error if the request fails.
+    pub async fn list_tools(&self) -> Result<Vec<ToolDefinition>, JsonRpcError> {
+        let result: ListToolsResult = self.call("tools/list", None).await?;
+        Ok(result.tools)
+    }
+
+    /// Calls a tool with JSON arguments.
+    ///
+    /// A tool that reports failure through `is_error` still returns `Ok`;
+    /// only protocol-level failures are errors.
+    ///
+    /// # Errors
+    ///
+    /// Returns the server's error if the tool is unknown or fails to execute.
+    pub async fn call_tool(
+        &self,
+        name: &str,
+        arguments: Value,
+    ) -> Result<CallToolResult, JsonRpcError> {
+        self.call("tools/call", Some(json!({ "name": name, "arguments": arguments })))
+            .await
+    }
+
+    /// Lists the server's resources.
+    ///
+    /// # Errors
+    ///
+    /// Returns the server's error if the request fails.
+    pub async fn list_resources(&self) -> Result<Vec<ResourceDefinition>, JsonRpcError> {
+        let result: ListResourcesResult = self.call("resources/list", None).await?;
+        Ok(result.resources)
+    }
+
+    /// Reads a resource by URI.
+    ///
+    /// # Errors
+    ///
+    /// Returns the server's error if the resource is unknown or cannot be read.
+    pub async fn read_resource(&self, uri: &str) -> Result<ReadResourceResult, JsonRpcError> {
+        self.call("resources/read", Some(json!({ "uri": uri })))
+            .await
+    }
+
+    /// Lists the server's prompts.
+    ///
+    /// # Errors
+    ///
+    /// Returns the server's error if the request fails.
+    pub async fn list_prompts(&self) -> Result<Vec<PromptDefinition>, JsonRpcError> {
+        let result: ListPromptsResult = self.call("prompts/list", None).await?;
+        Ok(result.prompts)
+    }
+
+    /// Renders a prompt with string arguments.
+    ///
+    /// # Errors
+    ///
+    /// Returns the server's error if the prompt is unknown or a required
+    /// argument is missing.
+    pub async fn get_prompt(
+        &self,
+        name: &str,
+        arguments: Value,
+    ) -> Result<GetPromptResult, JsonRpcError> {
+        self.call("prompts/get", Some(json!({ "name": name, "arguments": arguments })))
+            .await
+    }
+
+    /// Every notification the server has pushed since the client connected,
+    /// oldest first.
+    ///
+    /// Notifications are kept, so repeated calls return a growing list.
+    pub fn notifications(&self) -> Vec<JsonRpcRequest> {
+        let mut subscription = self.subscription.lock();
+        let mut notifications = self.notifications.lock();
+        loop {
+            match subscription.try_recv() {
+                Ok(notification) => notifications.push(notification),
+                Err(TryRecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "Test client missed server notifications");
+                },
+                Err(TryRecvError::Empty | TryRecvError::Closed) => break,
+            }
+        }
+        notifications.clone()
+    }
+}
+
+fn to_params(params: &InitializeParams) -> Result<Value, JsonRpcError> {
+    serde_json::to_value(params)
+        .map_err(|e| JsonRpcError::internal_error(Some(format!("Failed to encode params: {}", e))))
+}
+
+/// Turns a response into its decoded result or its error.
+fn decode<T: DeserializeOwned>(response: JsonRpcResponse) -> Result<T, JsonRpcError> {
+    if let Some(error) = response.error {
+        return Err(error);
+    }
+    let result = response.result.unwrap_or(Value::Null);
+    serde_json::from_value(result)
+        .map_err(|e| JsonRpcError::internal_error(Some(format!("Malformed result: {}", e))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{MiddlewareError, ToolError};
+    use crate::hooks::{Hook, HookEvent};
+    use crate::middleware::{Middleware, RequestContext};
+    use crate::tool::{Tool, ToolContext, ToolResult};
+    use async_trait::async_trait;
+    use std::sync::Arc;
+
+    /// Shared log of everything the middleware, hooks and tool saw, in order
+    type Log = Arc<Mutex<Vec<String>>>;
+
+    struct LoggingTool {
+        log: Log,
+    }
+
+    #[async_trait]
+    impl Tool for LoggingTool {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn input_schema(&self) -> Value {
+            json!({"type": "object"})
+        }
+
+        async fn execute(
+            &self,
+            input: Value,
+            _context: &ToolContext,
+        ) -> Result<ToolResult, ToolError> {
+            self.log.lock().push("tool".to_string());
+            match input["message"].as_str() {
+                Some(message) => Ok(ToolResult::success_text(message)),
+                None => Err(ToolError::ExecutionFailed("message is required".to_string())),
+            }
+        }
+    }
+
+    struct LoggingMiddleware {
+        name: &'static str,
+        log: Log,
+    }
+
+    #[async_trait]
+    impl Middleware for LoggingMiddleware {
+        async fn on_request(
+            &self,
+            request: &JsonRpcRequest,
+            _context: &mut RequestContext,
+        ) -> Result<(), MiddlewareError> {
+            self.log
+                .lock()
+                .push(format!("{}:request:{}", self.name, request.method));
+            if request.method == "forbidden" {
+                return Err(MiddlewareError::Blocked("forbidden".to_string()));
+            }
+            Ok(())
+        }
+
+        async fn on_response(
+            &self,
+            _response: &JsonRpcResponse,
+            context: &RequestContext,
+        ) -> Result<(), MiddlewareError> {
+            self.log
+                .lock()
+                .push(format!("{}:response:{}", self.name, context.method()));
+            Ok(())
+        }
+    }
+
+    struct LoggingHook {
+        log: Log,
+    }
+
+    #[async_trait]
+    impl Hook for LoggingHook {
+        async fn on_event(&self, event: &HookEvent) -> Result<(), MiddlewareError> {
+            self.log.lock().push(format!("hook:{}", event.event_type()));
+            Ok(())
+        }
+    }
+
+    fn server(log: &Log) -> McpServer {
+        McpServer::builder()
+            .name("test-server")
+            .version("1.0.0")
+            .tool(LoggingTool { log: log.clone() })
+            .middleware(LoggingMiddleware {
+                name: "outer",
+                log: log.clone(),
+            })
+            .middleware(LoggingMiddleware {
+                name: "inner",
+                log: log.clone(),
+            })
+            .hook(LoggingHook { log: log.clone() })
+            .build()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_connect_negotiates_capabilities() {
+        let log = Log::default();
+        let client = TestClient::connect(server(&log)).await.unwrap();
+
+        client.assert_protocol_version(PROTOCOL_VERSION);
+        client.assert_capability("tools");
+        client.assert_no_capability("prompts");
+        assert_eq!(client.initialize_result().server_info.name, "test-server");
+        assert!(log.lock().contains(&"hook:client_connected".to_string()));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_call_tool_runs_middleware_and_hooks_in_order() {
+        let log = Log::default();
+        let client = TestClient::connect(server(&log)).await.unwrap();
+        log.lock().clear();
+
+        let result = client
+            .call_tool("echo", json!({"message": "hi"}))
+            .await
+            .unwrap();
+        assert_eq!(result.is_error, None);
+
+        assert_eq!(
+            *log.lock(),
+            vec![
+                "outer:request:tools/call",
+                "inner:request:tools/call",
+                "hook:tool_called",
+                "tool",
+                "hook:tool_completed",
+                "inner:response:tools/call",
+                "outer:response:tools/call",
+            ]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_errors_are_returned_as_protocol_errors() {
+        let log = Log::default();
+        let client = TestClient::connect(server(&log)).await.unwrap();
+
+        let error = client.call_tool("missing", json!({})).await.unwrap_err();
+        assert_eq!(error.code, crate::protocol::mcp_codes::TOOL_NOT_FOUND);
+
+        let error = client.call_tool("echo", json!({})).await.unwrap_err();
+        assert_eq!(error.code, crate::protocol::mcp_codes::TOOL_EXECUTION_FAILED);
+
+        let error = client.read_resource("file:///missing").await.unwrap_err();
+        assert_eq!(error.code, crate::protocol::mcp_codes::RESOURCE_NOT_FOUND);
+
+        // Blocked requests never reach the inner middleware or a handler
+        log.lock().clear();
+        let response = client.request("forbidden", None).await;
+        assert!(response.is_error());
+        assert_eq!(*log.lock(), vec!["outer:request:forbidden", "hook:error"]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_notifications_are_captured() {
+        let log = Log::default();
+        let client = TestClient::connect(server(&log)).await.unwrap();
+        assert!(client.notifications().is_empty());
+
+        client
+            .server()
+            .notify("notifications/tools/list_changed", None);
+        client
+            .server()
+            .notify("notifications/message", Some(json!({"level": "info"})));
+
+        let methods: Vec<String> = client
+            .notifications()
+            .into_iter()
+            .map(|notification| notification.method)
+            .collect();
+        assert_eq!(methods, vec!["notifications/tools/list_changed", "notifications/message"]);
+        assert_eq!(client.notifications().len(), 2);
+    }
+}
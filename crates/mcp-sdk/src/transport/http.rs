@@ -9,6 +9,22 @@
 //! - `POST /mcp` - Send JSON-RPC requests and receive responses
 //! - `GET /mcp/sse` - Establish SSE connection for streaming notifications
 //!
+//! # Sessions and Resumption
+//!
+//! Opening `GET /mcp/sse` without a session id starts a new session. Its id is
+//! returned in the `Mcp-Session-Id` response header and as the first `session`
+//! event. Requests posted with that header have their responses delivered to
+//! the session's stream; requests without it are answered on every stream.
+//!
+//! Every message on a session stream carries an event id and is buffered
+//! (see [`HttpTransportConfig`]). A client that loses its connection
+//! reconnects with `Mcp-Session-Id` (or `?sessionId=`) and `Last-Event-ID`
+//! and receives the events it missed, including responses to requests that
+//! were still running when it dropped. Sessions stay resumable for
+//! [`HttpTransportConfig::session_grace_period`] after their stream closes.
+//! Malformed session ids are rejected with 400, and unknown or expired ones
+//! with 404.
+//!
 //! # Examples
 //!
 //! ```rust,no_run
@@ -31,14 +47,19 @@
 use async_trait::async_trait;
 #[cfg(feature = "http")]
 use axum::{
-    extract::State,
-    http::StatusCode,
-    response::{sse::Event, IntoResponse, Response, Sse},
+    extract::{Query, State},
+    http::{HeaderMap, HeaderValue, StatusCode},
+    response::{
+        sse::{Event, KeepAlive},
+        IntoResponse, Response, Sse,
+    },
     routing::{get, post},
     Json, Router,
 };
 #[cfg(feature = "http")]
-use futures::stream::{self, Stream};
+use futures::stream::{self, StreamExt};
+#[cfg(feature = "http")]
+use serde::Deserialize;
 #[cfg(feature = "http")]
 use std::convert::Infallible;
 #[cfg(feature = "http")]
@@ -46,6 +67,8 @@ use std::net::SocketAddr;
 #[cfg(feature = "http")]
 use std::sync::Arc;
 #[cfg(feature = "http")]
+use std::time::Duration;
+#[cfg(feature = "http")]
 use tokio::sync::{mpsc, RwLock};
 #[cfg(feature = "http")]
 use tower_http::cors::CorsLayer;
@@ -58,6 +81,55 @@ use crate::protocol::{JsonRpcRequest, JsonRpcResponse};
 #[cfg(feature = "http")]
 use super::traits::Transport;
 
+#[cfg(feature = "http")]
+mod session;
+
+#[cfg(feature = "http")]
+use session::{SessionError, SessionStore, SseEvent};
+
+/// Header carrying the session id on SSE connections and posted requests.
+#[cfg(feature = "http")]
+pub const SESSION_HEADER: &str = "mcp-session-id";
+
+/// Limits for SSE session buffering and resumption.
+///
+/// # Examples
+///
+/// ```rust
+/// use mcp_sdk::transport::http::HttpTransportConfig;
+/// use std::time::Duration;
+///
+/// let config = HttpTransportConfig {
+///     max_buffered_events: 100,
+///     session_grace_period: Duration::from_secs(30),
+///     ..HttpTransportConfig::default()
+/// };
+/// ```
+#[cfg(feature = "http")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpTransportConfig {
+    /// Most events kept per session for replay.
+    pub max_buffered_events: usize,
+
+    /// Most bytes of event data kept per session for replay. The newest
+    /// event is kept even if it is larger on its own.
+    pub max_buffered_bytes: usize,
+
+    /// How long a session stays resumable after its SSE stream closes.
+    pub session_grace_period: Duration,
+}
+
+#[cfg(feature = "http")]
+impl Default for HttpTransportConfig {
+    fn default() -> Self {
+        Self {
+            max_buffered_events: 1000,
+            max_buffered_bytes: 4 * 1024 * 1024,
+            session_grace_period: Duration::from_secs(300),
+        }
+    }
+}
+
 /// HTTP transport for JSON-RPC communication.
 ///
 /// This transport provides HTTP endpoints for receiving requests and sending responses.
@@ -68,7 +140,8 @@ use super::traits::Transport;
 /// The HTTP transport uses channels to bridge between HTTP handlers and the transport trait:
 /// - Incoming requests are queued in a channel for `recv()`
 /// - Responses are sent back via a response channel
-/// - SSE connections receive notifications via a broadcast channel
+/// - SSE connections receive responses and notifications through their
+///   session, which buffers them for replay
 ///
 /// # Examples
 ///
@@ -91,6 +164,7 @@ pub struct HttpTransport {
     request_tx: mpsc::UnboundedSender<JsonRpcRequest>,
     response_tx: Arc<RwLock<Option<mpsc::UnboundedSender<JsonRpcResponse>>>>,
     notification_tx: Arc<tokio::sync::broadcast::Sender<JsonRpcResponse>>,
+    sessions: Arc<SessionStore>,
     closed: Arc<std::sync::atomic::AtomicBool>,
 }
 
@@ -98,7 +172,7 @@ pub struct HttpTransport {
 #[derive(Clone)]
 struct AppState {
     request_tx: mpsc::UnboundedSender<JsonRpcRequest>,
-    notification_tx: Arc<tokio::sync::broadcast::Sender<JsonRpcResponse>>,
+    sessions: Arc<SessionStore>,
 }
 
 #[cfg(feature = "http")]
@@ -119,6 +193,21 @@ impl HttpTransport {
     /// let transport = HttpTransport::new(addr);
     /// ```
     pub fn new(addr: SocketAddr) -> Self {
+        Self::with_config(addr, HttpTransportConfig::default())
+    }
+
+    /// Create a new HTTP transport with custom session limits.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mcp_sdk::transport::http::{HttpTransport, HttpTransportConfig};
+    /// use std::net::SocketAddr;
+    ///
+    /// let addr: SocketAddr = "127.0.0.1:3000".parse().unwrap();
+    /// let transport = HttpTransport::with_config(addr, HttpTransportConfig::default());
+    /// ```
+    pub fn with_config(addr: SocketAddr, config: HttpTransportConfig) -> Self {
         let (request_tx, request_rx) = mpsc::unbounded_channel();
         let (notification_tx, _) = tokio::sync::broadcast::channel(100);
 
@@ -128,6 +217,7 @@ impl HttpTransport {
             request_tx,
             response_tx: Arc::new(RwLock::new(None)),
             notification_tx: Arc::new(notification_tx),
+            sessions: Arc::new(SessionStore::new(config)),
             closed: Arc::new(std::sync::atomic::AtomicBool::new(false)),
         }
     }
@@ -173,7 +263,7 @@ impl HttpTransport {
     pub fn router(&self) -> Router {
         let state = AppState {
             request_tx: self.request_tx.clone(),
-            notification_tx: Arc::clone(&self.notification_tx),
+            sessions: Arc::clone(&self.sessions),
         };
 
         Router::new()
@@ -184,11 +274,51 @@ impl HttpTransport {
     }
 }
 
+#[cfg(feature = "http")]
+impl IntoResponse for SessionError {
+    fn into_response(self) -> Response {
+        match self {
+            SessionError::Malformed => (StatusCode::BAD_REQUEST, "Malformed session id"),
+            SessionError::Unknown => (StatusCode::NOT_FOUND, "Unknown or expired session"),
+        }
+        .into_response()
+    }
+}
+
+/// Session id passed in the query string, for clients that cannot set headers.
+#[cfg(feature = "http")]
+#[derive(Debug, Deserialize)]
+struct SessionQuery {
+    #[serde(rename = "sessionId")]
+    session_id: Option<String>,
+}
+
+#[cfg(feature = "http")]
+fn session_header(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(SESSION_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string())
+}
+
+#[cfg(feature = "http")]
+fn sse_event(event: SseEvent) -> Result<Event, Infallible> {
+    Ok(Event::default().id(event.id.to_string()).data(event.data))
+}
+
 #[cfg(feature = "http")]
 async fn handle_mcp_request(
     state: State<AppState>,
-    Json(request): Json<JsonRpcRequest>,
+    headers: HeaderMap,
+    Json(mut request): Json<JsonRpcRequest>,
 ) -> Response {
+    // Route the response to the caller's session stream
+    if let Some(session_id) = session_header(&headers)
+        && let Err(e) = state.sessions.route(&session_id, &mut request)
+    {
+        return e.into_response();
+    }
+
     // Send request to transport
     if state.request_tx.send(request).is_err() {
         return (
@@ -217,21 +347,47 @@ async fn handle_mcp_request(
 #[cfg(feature = "http")]
 async fn handle_sse(
     state: State<AppState>,
-) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
-    let rx = state.notification_tx.subscribe();
-
-    let stream = stream::unfold(rx, |mut rx| async move {
-        match rx.recv().await {
-            Ok(response) => {
-                let json = serde_json::to_string(&response).ok()?;
-                let event = Event::default().data(json);
-                Some((Ok(event), rx))
-            }
-            Err(_) => None,
+    headers: HeaderMap,
+    Query(query): Query<SessionQuery>,
+) -> Response {
+    let last_event_id = match headers.get("last-event-id") {
+        Some(value) => match value.to_str().ok().and_then(|v| v.trim().parse::<u64>().ok()) {
+            Some(id) => Some(id),
+            None => return (StatusCode::BAD_REQUEST, "Malformed Last-Event-ID").into_response(),
+        },
+        None => None,
+    };
+
+    let (session_id, attachment, greeting) = match session_header(&headers).or(query.session_id) {
+        Some(session_id) => match state.sessions.resume(&session_id, last_event_id) {
+            Ok(attachment) => (session_id, attachment, None),
+            Err(e) => return e.into_response(),
+        },
+        None => {
+            let (session_id, attachment) = state.sessions.create();
+            let greeting = Event::default().event("session").data(&session_id);
+            (session_id, attachment, Some(Ok(greeting)))
         }
-    });
-
-    Sse::new(stream)
+    };
+
+    let replay = attachment.replay.into_iter().map(sse_event);
+    // The guard lives in the stream state, so dropping the stream detaches the session
+    let live = stream::unfold(
+        (attachment.receiver, attachment.guard),
+        |(mut receiver, guard)| async move {
+            let event = receiver.recv().await?;
+            Some((sse_event(event), (receiver, guard)))
+        },
+    );
+    let stream = stream::iter(greeting.into_iter().chain(replay)).chain(live);
+
+    let mut response = Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response();
+    if let Ok(value) = HeaderValue::from_str(&session_id) {
+        response.headers_mut().insert(SESSION_HEADER, value);
+    }
+    response
 }
 
 #[cfg(feature = "http")]
@@ -257,13 +413,33 @@ impl Transport for HttpTransport {
             return Err(TransportError::Closed);
         }
 
+        let mut response = response;
+        let route = response
+            .id
+            .as_ref()
+            .and_then(|id| self.sessions.take_route(id));
+        if let Some((_, request_id)) = &route {
+            response.id = Some(request_id.clone());
+        }
+
+        let data = serde_json::to_string(&response)
+            .map_err(|e| TransportError::InvalidMessage(e.to_string()))?;
+        match route {
+            Some((session_id, _)) => {
+                if !self.sessions.publish(&session_id, data) {
+                    tracing::debug!(session = %session_id, "Dropping response for expired session");
+                }
+            }
+            None => self.sessions.publish_all(data),
+        }
+
         // Send to direct response channel if available
         if let Some(tx) = self.response_tx.read().await.as_ref() {
             tx.send(response.clone())
                 .map_err(|_| TransportError::Closed)?;
         }
 
-        // Also broadcast for SSE connections
+        // Also broadcast to in-process subscribers
         let _ = self.notification_tx.send(response);
 
         Ok(())
@@ -412,6 +588,215 @@ mod tests {
 
         assert_eq!(*transport.addr(), addr);
     }
+
+    use crate::error::ToolError;
+    use crate::server::McpServer;
+    use crate::tool::{Tool, ToolContext, ToolResult};
+    use axum::body::{Body, BodyDataStream};
+    use axum::http::Request;
+    use serde_json::Value;
+    use tokio::sync::Notify;
+    use tower::ServiceExt;
+
+    /// Tool that signals when it starts and finishes only once released.
+    struct GatedTool {
+        started: Arc<Notify>,
+        release: Arc<Notify>,
+    }
+
+    #[async_trait]
+    impl Tool for GatedTool {
+        fn name(&self) -> &str {
+            "gated"
+        }
+
+        fn input_schema(&self) -> Value {
+            json!({"type": "object"})
+        }
+
+        async fn execute(&self, _: Value, _: &ToolContext) -> Result<ToolResult, ToolError> {
+            self.started.notify_one();
+            self.release.notified().await;
+            Ok(ToolResult::success_text("done"))
+        }
+    }
+
+    /// A parsed SSE event.
+    #[derive(Debug)]
+    struct ReceivedEvent {
+        event: Option<String>,
+        id: Option<String>,
+        data: String,
+    }
+
+    /// Reads events off an SSE response body.
+    struct SseReader {
+        body: BodyDataStream,
+        buffer: String,
+    }
+
+    impl SseReader {
+        fn new(response: Response) -> Self {
+            Self {
+                body: response.into_body().into_data_stream(),
+                buffer: String::new(),
+            }
+        }
+
+        async fn next(&mut self) -> ReceivedEvent {
+            loop {
+                if let Some(end) = self.buffer.find("\n\n") {
+                    let frame: String = self.buffer.drain(..end + 2).collect();
+                    let mut event = ReceivedEvent {
+                        event: None,
+                        id: None,
+                        data: String::new(),
+                    };
+                    for line in frame.lines() {
+                        if let Some(value) = line.strip_prefix("event:") {
+                            event.event = Some(value.trim().to_string());
+                        } else if let Some(value) = line.strip_prefix("id:") {
+                            event.id = Some(value.trim().to_string());
+                        } else if let Some(value) = line.strip_prefix("data:") {
+                            event.data.push_str(value.trim());
+                        }
+                    }
+                    // Keep-alive comments carry no data
+                    if !event.data.is_empty() {
+                        return event;
+                    }
+                    continue;
+                }
+
+                let chunk = tokio::time::timeout(Duration::from_secs(5), self.body.next())
+                    .await
+                    .expect("timed out waiting for an SSE event")
+                    .expect("SSE stream ended")
+                    .unwrap();
+                self.buffer.push_str(std::str::from_utf8(&chunk).unwrap());
+            }
+        }
+    }
+
+    fn sse_request(session_id: Option<&str>, last_event_id: Option<u64>) -> Request<Body> {
+        let mut builder = Request::builder().method("GET").uri("/mcp/sse");
+        if let Some(session_id) = session_id {
+            builder = builder.header(SESSION_HEADER, session_id);
+        }
+        if let Some(last_event_id) = last_event_id {
+            builder = builder.header("last-event-id", last_event_id.to_string());
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    fn post_request(session_id: &str, body: Value) -> Request<Body> {
+        Request::builder()
+            .method("POST")
+            .uri("/mcp")
+            .header("content-type", "application/json")
+            .header(SESSION_HEADER, session_id)
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_sse_resume_delivers_result_of_interrupted_tool_call() {
+        let started = Arc::new(Notify::new());
+        let release = Arc::new(Notify::new());
+        let server = McpServer::builder()
+            .name("test")
+            .version("1.0.0")
+            .tool(GatedTool {
+                started: Arc::clone(&started),
+                release: Arc::clone(&release),
+            })
+            .build();
+
+        let addr: SocketAddr = "127.0.0.1:3000".parse().unwrap();
+        let transport = HttpTransport::new(addr);
+        let router = transport.router();
+        let sessions = Arc::clone(&transport.sessions);
+        tokio::spawn(async move {
+            let _ = server.serve(transport).await;
+        });
+
+        // Open a session and see one response arrive live
+        let response = router.clone().oneshot(sse_request(None, None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let session_id = response.headers()[SESSION_HEADER].to_str().unwrap().to_string();
+        let mut events = SseReader::new(response);
+
+        let greeting = events.next().await;
+        assert_eq!(greeting.event.as_deref(), Some("session"));
+        assert_eq!(greeting.data, session_id);
+
+        let initialize = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": "2025-03-26",
+                "capabilities": {},
+                "clientInfo": { "name": "test", "version": "1.0.0" }
+            }
+        });
+        router.clone().oneshot(post_request(&session_id, initialize)).await.unwrap();
+        let event = events.next().await;
+        assert_eq!(event.id.as_deref(), Some("1"));
+        let response: JsonRpcResponse = serde_json::from_str(&event.data).unwrap();
+        assert_eq!(response.id, Some(json!(1)));
+
+        // Disconnect while the tool is running
+        let call = json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "tools/call",
+            "params": { "name": "gated", "arguments": {} }
+        });
+        router.clone().oneshot(post_request(&session_id, call)).await.unwrap();
+        started.notified().await;
+        drop(events);
+        assert!(!sessions.is_connected(&session_id));
+
+        release.notify_one();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while sessions.buffered(&session_id).len() < 2 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("tool result was never buffered");
+
+        // Reconnect and pick up where we left off
+        let response = router.clone().oneshot(sse_request(Some(&session_id), Some(1))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let mut events = SseReader::new(response);
+
+        let event = events.next().await;
+        assert_eq!(event.event, None);
+        assert_eq!(event.id.as_deref(), Some("2"));
+        let response: JsonRpcResponse = serde_json::from_str(&event.data).unwrap();
+        assert_eq!(response.id, Some(json!(2)));
+        assert_eq!(response.result.unwrap()["content"][0]["text"], "done");
+    }
+
+    #[tokio::test]
+    async fn test_sse_rejects_malformed_and_unknown_sessions() {
+        let addr: SocketAddr = "127.0.0.1:3000".parse().unwrap();
+        let transport = HttpTransport::new(addr);
+        let router = transport.router();
+        let unknown = "0".repeat(32);
+
+        let response = router.clone().oneshot(sse_request(Some("not-a-session"), None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = router.clone().oneshot(sse_request(Some(&unknown), Some(3))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let ping = json!({"jsonrpc": "2.0", "id": 1, "method": "ping"});
+        let response = router.clone().oneshot(post_request(&unknown, ping)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
 }
 
 // Re-export when feature is disabled with documentation
@@ -0,0 +1,453 @@
+//! SSE session state for the HTTP transport.
+//!
+//! Every message sent to a session gets an increasing event id and is kept in
+//! a bounded replay buffer, so a client that reconnects with `Last-Event-ID`
+//! receives what it missed while it was away. A session outlives its SSE
+//! connection for a grace period and is dropped once that runs out.
+
+use parking_lot::Mutex;
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::mpsc;
+
+use super::HttpTransportConfig;
+use crate::protocol::JsonRpcRequest;
+
+/// Length of a session id in hex characters (128 random bits).
+const SESSION_ID_LEN: usize = 32;
+
+/// A message buffered for, or delivered to, an SSE stream.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct SseEvent {
+    pub id: u64,
+    pub data: String,
+}
+
+/// Why a client-supplied session id was rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum SessionError {
+    /// The id is not a session id this transport could have issued.
+    Malformed,
+    /// The id is well-formed but the session does not exist or has expired.
+    Unknown,
+}
+
+/// Where the response to a rewritten request has to go.
+struct Route {
+    session_id: String,
+    request_id: Value,
+}
+
+struct Session {
+    next_event_id: u64,
+    buffer: VecDeque<SseEvent>,
+    buffered_bytes: usize,
+    connection: u64,
+    live: Option<mpsc::UnboundedSender<SseEvent>>,
+    disconnected_at: Option<Instant>,
+}
+
+impl Session {
+    fn new() -> Self {
+        Self {
+            next_event_id: 1,
+            buffer: VecDeque::new(),
+            buffered_bytes: 0,
+            connection: 0,
+            live: None,
+            disconnected_at: None,
+        }
+    }
+
+    /// Assigns the next event id, buffers the event and delivers it if a
+    /// client is connected.
+    fn push(&mut self, data: String, config: &HttpTransportConfig) {
+        let event = SseEvent {
+            id: self.next_event_id,
+            data,
+        };
+        self.next_event_id += 1;
+
+        if let Some(live) = &self.live
+            && live.send(event.clone()).is_err()
+        {
+            self.detach(Instant::now());
+        }
+
+        self.buffered_bytes += event.data.len();
+        self.buffer.push_back(event);
+
+        // The newest event is always kept, even when it alone exceeds the byte limit
+        while self.buffer.len() > 1
+            && (self.buffer.len() > config.max_buffered_events
+                || self.buffered_bytes > config.max_buffered_bytes)
+        {
+            if let Some(evicted) = self.buffer.pop_front() {
+                self.buffered_bytes -= evicted.data.len();
+            }
+        }
+    }
+
+    fn detach(&mut self, now: Instant) {
+        self.live = None;
+        self.disconnected_at = Some(now);
+    }
+}
+
+/// A client attached to a session's SSE stream.
+pub(super) struct Attachment {
+    /// Buffered events the client has not seen yet, oldest first.
+    pub replay: Vec<SseEvent>,
+    /// Events published after the client attached.
+    pub receiver: mpsc::UnboundedReceiver<SseEvent>,
+    /// Marks the session disconnected when the stream is dropped.
+    pub guard: ConnectionGuard,
+}
+
+/// Detaches a connection from its session when dropped.
+pub(super) struct ConnectionGuard {
+    store: Arc<SessionStore>,
+    session_id: String,
+    connection: u64,
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        let mut inner = self.store.inner.lock();
+        if let Some(session) = inner.sessions.get_mut(&self.session_id)
+            // A newer connection may already have taken over the session
+            && session.connection == self.connection
+        {
+            session.detach(Instant::now());
+        }
+    }
+}
+
+#[derive(Default)]
+struct Inner {
+    sessions: HashMap<String, Session>,
+    routes: HashMap<String, Route>,
+    next_route: u64,
+    next_connection: u64,
+}
+
+impl Inner {
+    /// Drops sessions whose grace period has run out, with their pending routes.
+    fn sweep(&mut self, now: Instant, config: &HttpTransportConfig) {
+        self.sessions
+            .retain(|id, session| match session.disconnected_at {
+                Some(at) if now.duration_since(at) >= config.session_grace_period => {
+                    tracing::debug!(session = %id, "SSE session expired");
+                    false
+                },
+                _ => true,
+            });
+        let sessions = &self.sessions;
+        self.routes
+            .retain(|_, route| sessions.contains_key(&route.session_id));
+    }
+
+    fn attach(
+        &mut self,
+        session_id: &str,
+        last_event_id: Option<u64>,
+    ) -> Option<(Vec<SseEvent>, mpsc::UnboundedReceiver<SseEvent>, u64)> {
+        self.next_connection += 1;
+        let connection = self.next_connection;
+        let session = self.sessions.get_mut(session_id)?;
+
+        let replay: Vec<SseEvent> = match last_event_id {
+            Some(last) => {
+                if let Some(oldest) = session.buffer.front()
+                    && oldest.id > last + 1
+                {
+                    tracing::warn!(
+                        session = %session_id,
+                        missed = oldest.id - last - 1,
+                        "SSE replay buffer no longer holds every missed event"
+                    );
+                }
+                session
+                    .buffer
+                    .iter()
+                    .filter(|event| event.id > last)
+                    .cloned()
+                    .collect()
+            },
+            None => Vec::new(),
+        };
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        session.live = Some(tx);
+        session.connection = connection;
+        session.disconnected_at = None;
+        Some((replay, rx, connection))
+    }
+}
+
+/// Sessions of one HTTP transport.
+pub(super) struct SessionStore {
+    config: HttpTransportConfig,
+    inner: Mutex<Inner>,
+}
+
+impl SessionStore {
+    pub fn new(config: HttpTransportConfig) -> Self {
+        Self {
+            config,
+            inner: Mutex::new(Inner::default()),
+        }
+    }
+
+    /// Opens a new session and attaches the caller to it.
+    pub fn create(self: &Arc<Self>) -> (String, Attachment) {
+        let session_id = new_session_id();
+        let mut inner = self.inner.lock();
+        inner.sweep(Instant::now(), &self.config);
+        inner.sessions.insert(session_id.clone(), Session::new());
+
+        let (replay, receiver, connection) = inner
+            .attach(&session_id, None)
+            .expect("session was just inserted");
+        let attachment = Attachment {
+            replay,
+            receiver,
+            guard: self.guard(&session_id, connection),
+        };
+        (session_id, attachment)
+    }
+
+    /// Reattaches to an existing session, replaying every buffered event
+    /// after `last_event_id`.
+    ///
+    /// The previous connection, if still open, stops receiving events.
+    pub fn resume(
+        self: &Arc<Self>,
+        session_id: &str,
+        last_event_id: Option<u64>,
+    ) -> Result<Attachment, SessionError> {
+        validate_session_id(session_id)?;
+        let mut inner = self.inner.lock();
+        inner.sweep(Instant::now(), &self.config);
+
+        let (replay, receiver, connection) = inner
+            .attach(session_id, last_event_id)
+            .ok_or(SessionError::Unknown)?;
+        Ok(Attachment {
+            replay,
+            receiver,
+            guard: self.guard(session_id, connection),
+        })
+    }
+
+    /// Tags `request` so that its response is delivered to `session_id`.
+    ///
+    /// The request id is replaced with one that is unique across sessions;
+    /// [`take_route`](Self::take_route) restores it.
+    pub fn route(
+        &self,
+        session_id: &str,
+        request: &mut JsonRpcRequest,
+    ) -> Result<(), SessionError> {
+        validate_session_id(session_id)?;
+        let mut inner = self.inner.lock();
+        inner.sweep(Instant::now(), &self.config);
+        if !inner.sessions.contains_key(session_id) {
+            return Err(SessionError::Unknown);
+        }
+
+        // Notifications get no response, so there is nothing to route
+        let Some(request_id) = request.id.take() else {
+            return Ok(());
+        };
+        inner.next_route += 1;
+        let route_id = format!("sse-{}", inner.next_route);
+        inner.routes.insert(
+            route_id.clone(),
+            Route {
+                session_id: session_id.to_string(),
+                request_id,
+            },
+        );
+        request.id = Some(Value::String(route_id));
+        Ok(())
+    }
+
+    /// Looks up the session and original id for a response id set by
+    /// [`route`](Self::route).
+    pub fn take_route(&self, response_id: &Value) -> Option<(String, Value)> {
+        let route_id = response_id.as_str()?;
+        let route = self.inner.lock().routes.remove(route_id)?;
+        Some((route.session_id, route.request_id))
+    }
+
+    /// Sends `data` to one session. Returns `false` if the session is gone.
+    pub fn publish(&self, session_id: &str, data: String) -> bool {
+        let mut inner = self.inner.lock();
+        match inner.sessions.get_mut(session_id) {
+            Some(session) => {
+                session.push(data, &self.config);
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Sends `data` to every session.
+    pub fn publish_all(&self, data: String) {
+        let mut inner = self.inner.lock();
+        inner.sweep(Instant::now(), &self.config);
+        for session in inner.sessions.values_mut() {
+            session.push(data.clone(), &self.config);
+        }
+    }
+
+    fn guard(self: &Arc<Self>, session_id: &str, connection: u64) -> ConnectionGuard {
+        ConnectionGuard {
+            store: Arc::clone(self),
+            session_id: session_id.to_string(),
+            connection,
+        }
+    }
+
+    #[cfg(test)]
+    pub fn buffered(&self, session_id: &str) -> Vec<SseEvent> {
+        self.inner
+            .lock()
+            .sessions
+            .get(session_id)
+            .map(|session| session.buffer.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    #[cfg(test)]
+    pub fn is_connected(&self, session_id: &str) -> bool {
+        self.inner
+            .lock()
+            .sessions
+            .get(session_id)
+            .is_some_and(|session| session.live.is_some())
+    }
+}
+
+/// Generates a session id from 128 bits of OS-seeded CSPRNG output.
+fn new_session_id() -> String {
+    let bytes: [u8; SESSION_ID_LEN / 2] = rand::random();
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn validate_session_id(session_id: &str) -> Result<(), SessionError> {
+    let well_formed = session_id.len() == SESSION_ID_LEN
+        && session_id
+            .bytes()
+            .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b));
+    if well_formed {
+        Ok(())
+    } else {
+        Err(SessionError::Malformed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::time::Duration;
+
+    fn store(config: HttpTransportConfig) -> Arc<SessionStore> {
+        Arc::new(SessionStore::new(config))
+    }
+
+    #[test]
+    fn test_session_ids_are_random_and_validated() {
+        let a = new_session_id();
+        let b = new_session_id();
+        assert_ne!(a, b);
+        assert_eq!(validate_session_id(&a), Ok(()));
+
+        assert_eq!(validate_session_id("short"), Err(SessionError::Malformed));
+        assert_eq!(validate_session_id(&a.to_uppercase()), Err(SessionError::Malformed));
+    }
+
+    #[test]
+    fn test_resume_replays_events_after_last_event_id() {
+        let store = store(HttpTransportConfig::default());
+        let (id, attachment) = store.create();
+        drop(attachment);
+        assert!(!store.is_connected(&id));
+
+        for n in 1..=3 {
+            assert!(store.publish(&id, format!("message {}", n)));
+        }
+
+        let attachment = store.resume(&id, Some(1)).unwrap();
+        let ids: Vec<u64> = attachment.replay.iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec![2, 3]);
+        assert!(store.is_connected(&id));
+    }
+
+    #[test]
+    fn test_replay_buffer_is_bounded() {
+        let store = store(HttpTransportConfig {
+            max_buffered_events: 3,
+            max_buffered_bytes: 10,
+            ..HttpTransportConfig::default()
+        });
+        let (id, _attachment) = store.create();
+
+        for n in 1..=5 {
+            store.publish(&id, format!("{}", n));
+        }
+        let ids: Vec<u64> = store.buffered(&id).iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec![3, 4, 5]);
+
+        store.publish(&id, "0123456789".to_string());
+        let ids: Vec<u64> = store.buffered(&id).iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec![6]);
+    }
+
+    #[test]
+    fn test_old_connection_does_not_detach_new_one() {
+        let store = store(HttpTransportConfig::default());
+        let (id, first) = store.create();
+        let second = store.resume(&id, None).unwrap();
+
+        drop(first);
+        assert!(store.is_connected(&id));
+        drop(second);
+        assert!(!store.is_connected(&id));
+    }
+
+    #[test]
+    fn test_sessions_expire_after_grace_period() {
+        let store = store(HttpTransportConfig {
+            session_grace_period: Duration::ZERO,
+            ..HttpTransportConfig::default()
+        });
+        let (id, attachment) = store.create();
+        assert!(store.resume(&id, None).is_ok());
+
+        drop(attachment);
+        // The resumed connection above was dropped too, so the session is idle
+        assert_eq!(store.resume(&id, None).err(), Some(SessionError::Unknown));
+    }
+
+    #[test]
+    fn test_route_rewrites_and_restores_request_ids() {
+        let store = store(HttpTransportConfig::default());
+        let (id, _attachment) = store.create();
+
+        let mut request = JsonRpcRequest::new(Some(json!(1)), "ping".to_string(), None);
+        store.route(&id, &mut request).unwrap();
+        let route_id = request.id.clone().unwrap();
+        assert_ne!(route_id, json!(1));
+
+        assert_eq!(store.take_route(&route_id), Some((id, json!(1))));
+        assert_eq!(store.take_route(&route_id), None);
+
+        let unknown = "0".repeat(SESSION_ID_LEN);
+        assert_eq!(store.route(&unknown, &mut request), Err(SessionError::Unknown));
+    }
+}
@@ -157,7 +157,7 @@ pub use traits::Transport;
 pub use stdio::StdioTransport;
 
 #[cfg(feature = "http")]
-pub use http::HttpTransport;
+pub use http::{HttpTransport, HttpTransportConfig};
 
 pub use mock::MockTransport;
 
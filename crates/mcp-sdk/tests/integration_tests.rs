@@ -516,9 +516,6 @@ async fn test_resource_read() {
 // Middleware Tests
 // =============================================================================
 
-// Note: Current middleware implementation doesn't integrate with handle_request
-// These tests verify middleware registration and basic functionality
-
 #[tokio::test(flavor = "multi_thread")]
 async fn test_middleware_registration() {
     let server = McpServer::builder()
@@ -531,13 +528,32 @@ async fn test_middleware_registration() {
     assert_eq!(server.middleware().count().await, 1);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_middleware_runs_on_handle_request() {
+    let middleware = TestMiddleware::new();
+    let request_count = middleware.request_count.clone();
+    let response_count = middleware.response_count.clone();
+
+    let server = McpServer::builder()
+        .name("test-server")
+        .version("1.0.0")
+        .tool(EchoTool)
+        .middleware(middleware)
+        .build();
+
+    call_tool_via_request(&server, "echo", json!({"message": "hi"}))
+        .await
+        .unwrap();
+    list_tools_via_request(&server).await.unwrap();
+
+    assert_eq!(request_count.load(Ordering::SeqCst), 2);
+    assert_eq!(response_count.load(Ordering::SeqCst), 2);
+}
+
 // =============================================================================
 // Hook Tests
 // =============================================================================
 
-// Note: Current hook implementation doesn't integrate with handle_request
-// These tests verify hook registration and basic functionality
-
 #[tokio::test(flavor = "multi_thread")]
 async fn test_hook_registration() {
     let server = McpServer::builder()
@@ -550,6 +566,26 @@ async fn test_hook_registration() {
     assert_eq!(server.hooks().count().await, 1);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_hooks_fire_on_tool_call() {
+    let hook = TestHook::new();
+    let event_count = hook.event_count.clone();
+
+    let server = McpServer::builder()
+        .name("test-server")
+        .version("1.0.0")
+        .tool(EchoTool)
+        .hook(hook)
+        .build();
+
+    call_tool_via_request(&server, "echo", json!({"message": "hi"}))
+        .await
+        .unwrap();
+
+    // ToolCalled and ToolCompleted
+    assert_eq!(event_count.load(Ordering::SeqCst), 2);
+}
+
 // =============================================================================
 // Concurrent Execution Tests
 // =============================================================================